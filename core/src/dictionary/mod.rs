@@ -2,12 +2,22 @@
 //!
 //! The standard data dictionary is available in the `dicom-std-dict` crate.
 
+#[cfg(feature = "std")]
+pub mod private;
+#[cfg(feature = "std")]
+pub mod runtime;
 pub mod stub;
+pub mod tag_set;
+pub mod uid;
+
+pub use self::tag_set::TagSet;
 
 use crate::header::{Tag, VR};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use core::fmt::Debug;
+use core::str::FromStr;
 use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
-use std::fmt::Debug;
-use std::str::FromStr;
 
 /// Specification of a range of tags pertaining to an attribute.
 /// Very often, the dictionary of attributes indicates a unique `(group,elem)`
@@ -34,6 +44,35 @@ impl TagRange {
             TagRange::Element100(tag) => tag,
         }
     }
+
+    /// Check whether the given tag is within this range.
+    pub fn contains(self, tag: Tag) -> bool {
+        match self {
+            TagRange::Single(t) => t == tag,
+            TagRange::Group100(Tag(group, elem)) => {
+                tag.group() & 0xFF00 == group & 0xFF00 && tag.element() == elem
+            }
+            TagRange::Element100(Tag(group, elem)) => {
+                tag.group() == group && tag.element() & 0xFF00 == elem & 0xFF00
+            }
+        }
+    }
+}
+
+impl core::fmt::Display for TagRange {
+    /// Write the tag range in the form used by the standard,
+    /// such as `(0010,0010)`, `(60xx,3000)`, or `(0020,31xx)`.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match *self {
+            TagRange::Single(Tag(group, elem)) => write!(f, "({:04X},{:04X})", group, elem),
+            TagRange::Group100(Tag(group, elem)) => {
+                write!(f, "({:02X}xx,{:04X})", group >> 8, elem)
+            }
+            TagRange::Element100(Tag(group, elem)) => {
+                write!(f, "({:04X},{:02X}xx)", group, elem >> 8)
+            }
+        }
+    }
 }
 
 /// An error returned when parsing an invalid tag range.
@@ -59,12 +98,12 @@ pub enum TagRangeParseError {
     #[snafu(display("invalid tag component `group`"))]
     InvalidTagGroup {
         backtrace: Backtrace,
-        source: std::num::ParseIntError,
+        source: core::num::ParseIntError,
     },
     #[snafu(display("invalid tag component `element`"))]
     InvalidTagElement {
         backtrace: Backtrace,
-        source: std::num::ParseIntError,
+        source: core::num::ParseIntError,
     },
 }
 
@@ -113,6 +152,108 @@ impl FromStr for TagRange {
     }
 }
 
+/// The value multiplicity (VM) of an attribute,
+/// as specified in the data dictionary.
+///
+/// A value multiplicity describes the number of values
+/// which an element of the attribute may contain:
+/// at least `min`, at most `max` (if bounded),
+/// and only in increments of `step` values above the minimum.
+/// For instance, `1-n` admits any positive number of values,
+/// whereas `2-2n` only admits an even number of values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ValueMultiplicity {
+    /// The minimum number of values
+    pub min: u32,
+    /// The maximum number of values, or `None` if unbounded
+    pub max: Option<u32>,
+    /// The step between admissible numbers of values
+    pub step: u32,
+}
+
+impl ValueMultiplicity {
+    /// A value multiplicity of exactly one value.
+    pub const ONE: ValueMultiplicity = ValueMultiplicity::exact(1);
+
+    /// Create a value multiplicity of exactly `n` values.
+    pub const fn exact(n: u32) -> Self {
+        ValueMultiplicity {
+            min: n,
+            max: Some(n),
+            step: 1,
+        }
+    }
+
+    /// Create a value multiplicity of `min` up to `max` values.
+    pub const fn range(min: u32, max: u32) -> Self {
+        ValueMultiplicity {
+            min,
+            max: Some(max),
+            step: 1,
+        }
+    }
+
+    /// Create a value multiplicity of at least `min` values,
+    /// in increments of `step` values.
+    pub const fn unbounded(min: u32, step: u32) -> Self {
+        ValueMultiplicity {
+            min,
+            max: None,
+            step,
+        }
+    }
+
+    /// Check whether the given number of values
+    /// conforms to this value multiplicity.
+    pub fn contains(&self, n: u32) -> bool {
+        n >= self.min
+            && self.max.map(|max| n <= max).unwrap_or(true)
+            && (n - self.min) % self.step.max(1) == 0
+    }
+}
+
+impl core::fmt::Display for ValueMultiplicity {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match (self.max, self.step) {
+            (Some(max), _) if max == self.min => write!(f, "{}", self.min),
+            (Some(max), _) => write!(f, "{}-{}", self.min, max),
+            (None, 1) => write!(f, "{}-n", self.min),
+            (None, step) => write!(f, "{}-{}n", self.min, step),
+        }
+    }
+}
+
+/// An error returned when parsing an invalid value multiplicity.
+#[derive(Debug, Snafu)]
+#[snafu(display("invalid value multiplicity"))]
+pub struct ValueMultiplicityParseError {
+    backtrace: Backtrace,
+}
+
+impl FromStr for ValueMultiplicity {
+    type Err = ValueMultiplicityParseError;
+
+    /// Parse a value multiplicity in the form used by the standard,
+    /// such as `1`, `1-3`, `1-n`, or `2-2n`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| n.trim().parse::<u32>().ok();
+        let vm = match s.split_once('-') {
+            None => parse(s).map(ValueMultiplicity::exact),
+            Some((min, max)) => {
+                let min = parse(min).context(ValueMultiplicityParseSnafu)?;
+                match max.trim().strip_suffix('n') {
+                    Some("") => Some(ValueMultiplicity::unbounded(min, 1)),
+                    Some(step) => parse(step).map(|step| ValueMultiplicity::unbounded(min, step)),
+                    None => parse(max)
+                        .filter(|max| *max >= min)
+                        .map(|max| ValueMultiplicity::range(min, max)),
+                }
+            }
+        };
+        vm.context(ValueMultiplicityParseSnafu)
+    }
+}
+
 /** Type trait for a dictionary of DICOM attributes. Attribute dictionaries provide the
  * means to convert a tag to an alias and vice versa, as well as a form of retrieving
  * additional information about the attribute.
@@ -130,6 +271,74 @@ pub trait DataDictionary: Debug {
 
     /// Fetch an entry by its tag.
     fn by_tag(&self, tag: Tag) -> Option<&Self::Entry>;
+
+    /// Fetch an entry by a textual expression,
+    /// which can be either an attribute alias
+    /// or a tag in one of the forms
+    /// `(gggg,eeee)`, `gggg,eeee`, or `ggggeeee`.
+    fn by_expr(&self, expr: &str) -> Option<&Self::Entry> {
+        match parse_tag(expr) {
+            Some(tag) => self.by_tag(tag),
+            None => self.by_name(expr),
+        }
+    }
+
+    /// Resolve a textual expression into an attribute tag,
+    /// which can be either an attribute alias
+    /// or a tag in one of the forms
+    /// `(gggg,eeee)`, `gggg,eeee`, or `ggggeeee`.
+    ///
+    /// Unlike [`by_expr`](DataDictionary::by_expr),
+    /// tags which are not in the dictionary are still resolved.
+    fn parse_tag(&self, expr: &str) -> Option<Tag> {
+        parse_tag(expr).or_else(|| self.by_name(expr).map(|e| e.tag()))
+    }
+
+    /// Obtain an iterator over all entries in the dictionary.
+    ///
+    /// The default implementation yields no entries,
+    /// which is suitable for dictionaries which cannot be enumerated.
+    fn entries(&self) -> Box<dyn Iterator<Item = &Self::Entry> + '_> {
+        Box::new(core::iter::empty())
+    }
+}
+
+/// Parse a tag in one of the forms `(gggg,eeee)`, `gggg,eeee`, or `ggggeeee`.
+fn parse_tag(expr: &str) -> Option<Tag> {
+    let expr = expr.trim();
+    let expr = if expr.starts_with('(') && expr.ends_with(')') {
+        &expr[1..expr.len() - 1]
+    } else {
+        expr
+    };
+    let (group, elem) = match expr.len() {
+        9 if expr.as_bytes()[4] == b',' => (&expr[..4], &expr[5..]),
+        8 => (&expr[..4], &expr[4..]),
+        _ => return None,
+    };
+    if !group
+        .bytes()
+        .chain(elem.bytes())
+        .all(|b| b.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    let group = u16::from_str_radix(group, 16).ok()?;
+    let elem = u16::from_str_radix(elem, 16).ok()?;
+    Some(Tag(group, elem))
+}
+
+/// Parse a value representation in a DCMTK dictionary,
+/// which may also be one of the ambiguous pseudo-VRs.
+#[cfg(feature = "std")]
+pub(crate) fn parse_dcmtk_vr(vr: &str) -> VR {
+    match vr {
+        "ox" | "px" => VR::OB,
+        "xs" => VR::US,
+        "lt" => VR::OW,
+        "up" => VR::UL,
+        vr => vr.parse().unwrap_or(VR::UN),
+    }
 }
 
 /// The dictionary entry data type, representing a DICOM attribute.
@@ -149,6 +358,12 @@ pub trait DictionaryEntry {
     /// The _typical_ value representation of the attribute.
     /// In some edge cases, an element might not have this VR.
     fn vr(&self) -> VR;
+    /// The full name of the attribute, as a human readable text.
+    fn name(&self) -> &str;
+    /// The value multiplicity of the attribute.
+    fn vm(&self) -> ValueMultiplicity;
+    /// Whether the attribute has been retired from the standard.
+    fn is_retired(&self) -> bool;
 }
 
 /// A data type for a dictionary entry with full ownership.
@@ -160,6 +375,12 @@ pub struct DictionaryEntryBuf {
     pub alias: String,
     /// The _typical_  value representation of the attribute
     pub vr: VR,
+    /// The full name of the attribute
+    pub name: String,
+    /// The value multiplicity of the attribute
+    pub vm: ValueMultiplicity,
+    /// Whether the attribute is retired
+    pub retired: bool,
 }
 
 impl DictionaryEntry for DictionaryEntryBuf {
@@ -172,6 +393,15 @@ impl DictionaryEntry for DictionaryEntryBuf {
     fn vr(&self) -> VR {
         self.vr
     }
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+    fn vm(&self) -> ValueMultiplicity {
+        self.vm
+    }
+    fn is_retired(&self) -> bool {
+        self.retired
+    }
 }
 
 /// A data type for a dictionary entry with a string slice for its alias.
@@ -183,6 +413,12 @@ pub struct DictionaryEntryRef<'a> {
     pub alias: &'a str,
     /// The _typical_  value representation of the attribute
     pub vr: VR,
+    /// The full name of the attribute
+    pub name: &'a str,
+    /// The value multiplicity of the attribute
+    pub vm: ValueMultiplicity,
+    /// Whether the attribute is retired
+    pub retired: bool,
 }
 
 impl<'a> DictionaryEntry for DictionaryEntryRef<'a> {
@@ -195,6 +431,15 @@ impl<'a> DictionaryEntry for DictionaryEntryRef<'a> {
     fn vr(&self) -> VR {
         self.vr
     }
+    fn name(&self) -> &str {
+        self.name
+    }
+    fn vm(&self) -> ValueMultiplicity {
+        self.vm
+    }
+    fn is_retired(&self) -> bool {
+        self.retired
+    }
 }
 
 /// Utility data structure that resolves to a DICOM attribute tag
@@ -231,9 +476,19 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::TagRange;
+    use super::{parse_tag, TagRange, ValueMultiplicity};
     use crate::header::Tag;
 
+    #[test]
+    fn test_parse_tag() {
+        assert_eq!(parse_tag("(0010,0010)"), Some(Tag(0x0010, 0x0010)));
+        assert_eq!(parse_tag("7FE0,0010"), Some(Tag(0x7FE0, 0x0010)));
+        assert_eq!(parse_tag("0020000d"), Some(Tag(0x0020, 0x000D)));
+        assert_eq!(parse_tag("PatientName"), None);
+        assert_eq!(parse_tag("PatientID"), None);
+        assert_eq!(parse_tag("(0010,00xx)"), None);
+    }
+
     #[test]
     fn test_parse_tag_range() {
         let tag: TagRange = "(1234,5678)".parse().unwrap();
@@ -248,4 +503,57 @@ mod tests {
         let tag: TagRange = "1234,56xx".parse().unwrap();
         assert_eq!(tag, TagRange::Element100(Tag(0x1234, 0x5600)));
     }
+
+    #[test]
+    fn test_tag_range_contains() {
+        let range: TagRange = "60xx,3000".parse().unwrap();
+        assert!(range.contains(Tag(0x6000, 0x3000)));
+        assert!(range.contains(Tag(0x601E, 0x3000)));
+        assert!(!range.contains(Tag(0x6000, 0x3001)));
+        assert!(!range.contains(Tag(0x6100, 0x3000)));
+        assert_eq!(range.to_string(), "(60xx,3000)");
+
+        let range: TagRange = "0020,31xx".parse().unwrap();
+        assert!(range.contains(Tag(0x0020, 0x3100)));
+        assert!(range.contains(Tag(0x0020, 0x31FF)));
+        assert!(!range.contains(Tag(0x0020, 0x3200)));
+        assert_eq!(range.to_string(), "(0020,31xx)");
+
+        let range = TagRange::Single(Tag(0x0010, 0x0010));
+        assert!(range.contains(Tag(0x0010, 0x0010)));
+        assert!(!range.contains(Tag(0x0010, 0x0020)));
+        assert_eq!(range.to_string(), "(0010,0010)");
+    }
+
+    #[test]
+    fn test_value_multiplicity() {
+        let vm: ValueMultiplicity = "1".parse().unwrap();
+        assert_eq!(vm, ValueMultiplicity::ONE);
+        assert!(vm.contains(1));
+        assert!(!vm.contains(0));
+        assert!(!vm.contains(2));
+
+        let vm: ValueMultiplicity = "1-3".parse().unwrap();
+        assert_eq!(vm, ValueMultiplicity::range(1, 3));
+        assert!(vm.contains(3));
+        assert!(!vm.contains(4));
+
+        let vm: ValueMultiplicity = "1-n".parse().unwrap();
+        assert_eq!(vm, ValueMultiplicity::unbounded(1, 1));
+        assert!(vm.contains(1));
+        assert!(vm.contains(200));
+
+        let vm: ValueMultiplicity = "2-2n".parse().unwrap();
+        assert_eq!(vm, ValueMultiplicity::unbounded(2, 2));
+        assert!(vm.contains(4));
+        assert!(!vm.contains(3));
+
+        for vm in &["1", "16", "1-32", "2-n", "3-3n"] {
+            assert_eq!(&vm.parse::<ValueMultiplicity>().unwrap().to_string(), vm);
+        }
+
+        assert!("".parse::<ValueMultiplicity>().is_err());
+        assert!("n".parse::<ValueMultiplicity>().is_err());
+        assert!("3-1".parse::<ValueMultiplicity>().is_err());
+    }
 }
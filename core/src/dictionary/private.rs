@@ -0,0 +1,335 @@
+//! This module contains the concept of a private data dictionary.
+//!
+//! Private attributes are not identified by their tag alone.
+//! Each private group reserves blocks of elements
+//! through private creator elements `(gggg,0010-00FF)`,
+//! so that element `(gggg,xxee)` belongs to the creator
+//! found in element `(gggg,00xx)` of the same data set.
+//! A [`PrivateDictionary`] maps the creator identifier,
+//! the group, and the low byte of the element number
+//! to the attribute's alias and typical value representation.
+//!
+//! Private dictionaries can be loaded at run-time
+//! from files in the DCMTK `private.dic` format
+//! (see [`PrivateDictionary::from_dcmtk_reader`]),
+//! while [`PrivateCreators`] keeps track of the creators
+//! declared in a data set.
+
+use super::parse_dcmtk_vr;
+use crate::header::{Tag, VR};
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+use std::collections::HashMap;
+use std::io::BufRead;
+
+/// An error returned when loading a private dictionary.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum ParseError {
+    #[snafu(display("Could not read dictionary source"))]
+    ReadSource {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid private dictionary entry at line {}", line))]
+    InvalidEntry { line: usize, backtrace: Backtrace },
+    #[snafu(display("Invalid tag component at line {}", line))]
+    InvalidTagComponent {
+        line: usize,
+        backtrace: Backtrace,
+        source: std::num::ParseIntError,
+    },
+}
+
+/// A private dictionary entry, representing a private DICOM attribute.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PrivateDictionaryEntry {
+    /// The private creator identifier
+    pub creator: String,
+    /// The attribute group
+    pub group: u16,
+    /// The low byte of the element number,
+    /// which is independent of the block reserved by the creator
+    pub element: u8,
+    /// The alias of the attribute, with no spaces, usually InCapitalizedCamelCase
+    pub alias: String,
+    /// The _typical_ value representation of the attribute
+    pub vr: VR,
+}
+
+impl PrivateDictionaryEntry {
+    /// Obtain the tag of this attribute
+    /// when the creator reserves the given block of elements
+    /// (the high byte of the element number).
+    pub fn tag_in_block(&self, block: u8) -> Tag {
+        Tag(
+            self.group,
+            (u16::from(block) << 8) | u16::from(self.element),
+        )
+    }
+}
+
+/// A dictionary of private attributes, indexed by creator.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PrivateDictionary {
+    /// mapping: (creator, group, element low byte) → entry
+    entries: HashMap<(String, u16, u8), PrivateDictionaryEntry>,
+}
+
+impl PrivateDictionary {
+    /// Create an empty private dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert an entry in the dictionary,
+    /// replacing and returning any previous entry
+    /// of the same creator and element.
+    pub fn insert(&mut self, entry: PrivateDictionaryEntry) -> Option<PrivateDictionaryEntry> {
+        let key = (
+            normalize_creator(&entry.creator).to_string(),
+            entry.group,
+            entry.element,
+        );
+        self.entries.insert(key, entry)
+    }
+
+    /// Retrieve the number of entries in the dictionary.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Obtain an iterator over all entries in the dictionary.
+    pub fn entries(&self) -> impl Iterator<Item = &PrivateDictionaryEntry> + '_ {
+        self.entries.values()
+    }
+
+    /// Fetch an entry by its private creator and tag.
+    ///
+    /// Only the group and the low byte of the element number
+    /// are considered from the tag.
+    pub fn by_creator_tag(&self, creator: &str, tag: Tag) -> Option<&PrivateDictionaryEntry> {
+        let key = (
+            normalize_creator(creator).to_string(),
+            tag.group(),
+            (tag.element() & 0xFF) as u8,
+        );
+        self.entries.get(&key)
+    }
+
+    /// Fetch the entry of a private attribute,
+    /// using the private creators declared in the data set.
+    pub fn resolve(&self, creators: &PrivateCreators, tag: Tag) -> Option<&PrivateDictionaryEntry> {
+        let creator = creators.creator_of(tag)?;
+        self.by_creator_tag(creator, tag)
+    }
+
+    /// Add all entries from another private dictionary,
+    /// replacing existing entries of the same creator and element.
+    pub fn extend(&mut self, other: PrivateDictionary) {
+        self.entries.extend(other.entries);
+    }
+
+    /// Load a private dictionary
+    /// from a source in the DCMTK `private.dic` format.
+    ///
+    /// Each line describes an attribute in tab separated fields:
+    /// `(gggg,"creator",ee)`, the value representation, and the alias.
+    /// The remaining fields are ignored,
+    /// as well as empty lines and lines starting with `#`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dicom_core::dictionary::private::PrivateDictionary;
+    /// # use dicom_core::{Tag, VR};
+    /// let source = "(0019,\"SIEMENS MR HEADER\",08)\tCS\tImagingMode\t1\tPrivateTag\n";
+    /// let dict = PrivateDictionary::from_dcmtk_reader(source.as_bytes())?;
+    /// let entry = dict
+    ///     .by_creator_tag("SIEMENS MR HEADER", Tag(0x0019, 0x1008))
+    ///     .unwrap();
+    /// assert_eq!(entry.alias, "ImagingMode");
+    /// assert_eq!(entry.vr, VR::CS);
+    /// # Ok::<(), dicom_core::dictionary::private::ParseError>(())
+    /// ```
+    pub fn from_dcmtk_reader<R: BufRead>(source: R) -> Result<Self, ParseError> {
+        let mut dict = PrivateDictionary::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.context(ReadSourceSnafu)?;
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.split('\t').filter(|f| !f.is_empty());
+            let tag = fields.next().context(InvalidEntrySnafu { line: line_no })?;
+            let vr = fields.next().context(InvalidEntrySnafu { line: line_no })?;
+            let alias = fields.next().context(InvalidEntrySnafu { line: line_no })?;
+
+            // (gggg,"creator",ee)
+            let tag = tag
+                .strip_prefix('(')
+                .and_then(|t| t.strip_suffix(')'))
+                .context(InvalidEntrySnafu { line: line_no })?;
+            let (group, rest) = tag
+                .split_once(',')
+                .context(InvalidEntrySnafu { line: line_no })?;
+            let (creator, element) = rest
+                .rsplit_once(',')
+                .context(InvalidEntrySnafu { line: line_no })?;
+            let creator = creator
+                .strip_prefix('"')
+                .and_then(|c| c.strip_suffix('"'))
+                .context(InvalidEntrySnafu { line: line_no })?;
+            if group.contains('x') || element.contains('x') {
+                // repeating groups are not supported
+                continue;
+            }
+            let group = u16::from_str_radix(group, 16)
+                .context(InvalidTagComponentSnafu { line: line_no })?;
+            let element = u8::from_str_radix(element, 16)
+                .context(InvalidTagComponentSnafu { line: line_no })?;
+
+            dict.insert(PrivateDictionaryEntry {
+                creator: creator.to_string(),
+                group,
+                element,
+                alias: alias.to_string(),
+                vr: parse_dcmtk_vr(vr),
+            });
+        }
+        Ok(dict)
+    }
+}
+
+/// The private creators declared in a data set.
+///
+/// Each private creator element `(gggg,00xx)`
+/// reserves the block of elements `(gggg,xx00-xxFF)`.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PrivateCreators {
+    /// mapping: (group, block) → creator
+    creators: HashMap<(u16, u8), String>,
+}
+
+impl PrivateCreators {
+    /// Create an empty table of private creators.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Check whether the given tag is that of a private creator element.
+    pub fn is_creator_tag(tag: Tag) -> bool {
+        tag.group() % 2 == 1 && (0x0010..=0x00FF).contains(&tag.element())
+    }
+
+    /// Record the value of a private creator element.
+    ///
+    /// Nothing is recorded if the tag is not that of a private creator.
+    pub fn declare(&mut self, tag: Tag, creator: &str) {
+        if Self::is_creator_tag(tag) {
+            self.creators.insert(
+                (tag.group(), tag.element() as u8),
+                normalize_creator(creator).to_string(),
+            );
+        }
+    }
+
+    /// Obtain the private creator reserving the block of the given tag.
+    pub fn creator_of(&self, tag: Tag) -> Option<&str> {
+        if tag.group() % 2 == 0 {
+            return None;
+        }
+        let block = (tag.element() >> 8) as u8;
+        if block < 0x10 {
+            return None;
+        }
+        self.creators.get(&(tag.group(), block)).map(String::as_str)
+    }
+
+    /// Remove all declared private creators.
+    pub fn clear(&mut self) {
+        self.creators.clear();
+    }
+}
+
+/// Trim the padding from a private creator identifier.
+fn normalize_creator(creator: &str) -> &str {
+    creator.trim_matches(|c| c == ' ' || c == '\0')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PrivateCreators, PrivateDictionary};
+    use crate::header::{Tag, VR};
+
+    const SOURCE: &str = "\
+# sample private dictionary
+(0019,\"SIEMENS MR HEADER\",08)\tCS\tImagingMode\t1\tPrivateTag
+(0019,\"SIEMENS MR HEADER\",0c)\tIS\tB_value\t1\tPrivateTag
+
+(2001,\"Philips Imaging DD 001\",03)\tFL\tDiffusionBFactor\t1\tPrivateTag
+(0029,\"SIEMENS CSA HEADER\",10)\tox\tCSAImageHeaderInfo\t1\tPrivateTag
+";
+
+    #[test]
+    fn load_dcmtk_private_dictionary() {
+        let dict = PrivateDictionary::from_dcmtk_reader(SOURCE.as_bytes()).unwrap();
+        assert_eq!(dict.len(), 4);
+
+        let entry = dict
+            .by_creator_tag("SIEMENS MR HEADER", Tag(0x0019, 0x100C))
+            .unwrap();
+        assert_eq!(entry.alias, "B_value");
+        assert_eq!(entry.vr, VR::IS);
+        assert_eq!(entry.tag_in_block(0x10), Tag(0x0019, 0x100C));
+
+        // creator padding is ignored
+        let entry = dict
+            .by_creator_tag("SIEMENS CSA HEADER ", Tag(0x0029, 0x1110))
+            .unwrap();
+        assert_eq!(entry.alias, "CSAImageHeaderInfo");
+        assert_eq!(entry.vr, VR::OB);
+
+        assert!(dict
+            .by_creator_tag("GEMS_IDEN_01", Tag(0x0019, 0x1008))
+            .is_none());
+    }
+
+    #[test]
+    fn load_invalid_private_dictionary() {
+        assert!(
+            PrivateDictionary::from_dcmtk_reader(&b"(0019,08)\tCS\tImagingMode\n"[..]).is_err()
+        );
+        assert!(
+            PrivateDictionary::from_dcmtk_reader(&b"(00G9,\"ACME\",08)\tCS\tThing\n"[..]).is_err()
+        );
+    }
+
+    #[test]
+    fn resolve_with_private_creators() {
+        let dict = PrivateDictionary::from_dcmtk_reader(SOURCE.as_bytes()).unwrap();
+        let mut creators = PrivateCreators::new();
+        creators.declare(Tag(0x0019, 0x0011), "SIEMENS MR HEADER");
+        creators.declare(Tag(0x2001, 0x0010), "Philips Imaging DD 001");
+        // not a private creator
+        creators.declare(Tag(0x0019, 0x1008), "ACME");
+
+        assert_eq!(
+            creators.creator_of(Tag(0x0019, 0x1108)),
+            Some("SIEMENS MR HEADER")
+        );
+        assert_eq!(creators.creator_of(Tag(0x0019, 0x1008)), None);
+
+        let entry = dict.resolve(&creators, Tag(0x0019, 0x1108)).unwrap();
+        assert_eq!(entry.alias, "ImagingMode");
+        let entry = dict.resolve(&creators, Tag(0x2001, 0x1003)).unwrap();
+        assert_eq!(entry.alias, "DiffusionBFactor");
+        assert!(dict.resolve(&creators, Tag(0x0019, 0x1008)).is_none());
+        assert!(dict.resolve(&creators, Tag(0x0010, 0x0010)).is_none());
+    }
+}
@@ -0,0 +1,606 @@
+//! This module contains a data dictionary which can be built at run-time.
+//!
+//! A [`RuntimeDataDictionary`] can be populated from
+//! a few common external dictionary formats,
+//! so that the attribute dictionary can be extended
+//! without recompiling the library:
+//!
+//! - the DCMTK data dictionary format (`dicom.dic`),
+//!   through [`from_dcmtk_reader`](RuntimeDataDictionary::from_dcmtk_reader);
+//! - the GDCM XML dictionary format,
+//!   through [`from_gdcm_xml_reader`](RuntimeDataDictionary::from_gdcm_xml_reader);
+//! - a simple CSV format with the columns _tag_, _VR_, and _keyword_,
+//!   through [`from_csv_reader`](RuntimeDataDictionary::from_csv_reader).
+//!
+//! Dictionaries can also be combined with one another,
+//! including the entries of an existing dictionary
+//! such as the standard data dictionary.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
+//! # use dicom_core::dictionary::runtime::RuntimeDataDictionary;
+//! # use dicom_core::{Tag, VR};
+//! let csv = "tag,vr,keyword\n(0009,1001),LO,AcmeScannerMode\n";
+//! let dict = RuntimeDataDictionary::from_csv_reader(csv.as_bytes())?;
+//! let entry = dict.by_name("AcmeScannerMode").unwrap();
+//! assert_eq!(entry.tag(), Tag(0x0009, 0x1001));
+//! assert_eq!(entry.vr(), VR::LO);
+//! # Ok::<(), dicom_core::dictionary::runtime::LoadError>(())
+//! ```
+
+use super::{
+    parse_dcmtk_vr, DataDictionary, DictionaryEntry, DictionaryEntryBuf, TagRange,
+    ValueMultiplicity,
+};
+use crate::header::{Tag, VR};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+use std::collections::HashMap;
+use std::io::{BufRead, Read};
+
+/// An error returned when loading a data dictionary.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum LoadError {
+    #[snafu(display("Could not read dictionary source"))]
+    ReadSource {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+    #[snafu(display("Invalid dictionary entry at line {}", line))]
+    InvalidEntry { line: usize, backtrace: Backtrace },
+    #[snafu(display("Invalid XML at line {}", line))]
+    ParseXml {
+        line: usize,
+        backtrace: Backtrace,
+        source: quick_xml::Error,
+    },
+    #[snafu(display("Invalid tag `{}` at line {}", tag, line))]
+    InvalidTag {
+        tag: String,
+        line: usize,
+        #[snafu(backtrace)]
+        source: super::TagRangeParseError,
+    },
+}
+
+/// A data dictionary with entries defined at run-time.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct RuntimeDataDictionary {
+    /// mapping: tag → entry
+    by_tag: HashMap<Tag, DictionaryEntryBuf>,
+    /// mapping: name → tag
+    by_name: HashMap<String, Tag>,
+    /// repeating elements of the form (ggxx, eeee). The `xx` portion is zeroed.
+    repeating_ggxx: Vec<Tag>,
+    /// repeating elements of the form (gggg, eexx). The `xx` portion is zeroed.
+    repeating_eexx: Vec<Tag>,
+}
+
+impl RuntimeDataDictionary {
+    /// Create an empty data dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a data dictionary with a copy of all entries
+    /// of the given dictionary.
+    ///
+    /// Only dictionaries which support
+    /// [iterating over their entries](DataDictionary::entries)
+    /// can be copied.
+    pub fn from_dictionary<D>(dict: &D) -> Self
+    where
+        D: DataDictionary,
+    {
+        let mut out = RuntimeDataDictionary::new();
+        for entry in dict.entries() {
+            out.insert(DictionaryEntryBuf {
+                tag: entry.tag_range(),
+                alias: entry.alias().to_string(),
+                vr: entry.vr(),
+                name: entry.name().to_string(),
+                vm: entry.vm(),
+                retired: entry.is_retired(),
+            });
+        }
+        out
+    }
+
+    /// Insert an entry in the dictionary,
+    /// replacing and returning any previous entry with the same tag.
+    pub fn insert(&mut self, entry: DictionaryEntryBuf) -> Option<DictionaryEntryBuf> {
+        let tag = entry.tag.inner();
+        match entry.tag {
+            TagRange::Group100(tag) if !self.repeating_ggxx.contains(&tag) => {
+                self.repeating_ggxx.push(tag);
+            }
+            TagRange::Element100(tag) if !self.repeating_eexx.contains(&tag) => {
+                self.repeating_eexx.push(tag);
+            }
+            _ => {}
+        }
+        if let Some(old) = self.by_tag.get(&tag) {
+            // drop the previous name of this entry
+            if old.alias != entry.alias && self.by_name.get(&old.alias) == Some(&tag) {
+                self.by_name.remove(&old.alias);
+            }
+        }
+        self.by_name.insert(entry.alias.clone(), tag);
+        self.by_tag.insert(tag, entry)
+    }
+
+    /// Add all entries from another dictionary,
+    /// replacing existing entries with the same tag.
+    pub fn extend(&mut self, other: RuntimeDataDictionary) {
+        for entry in other.by_tag.into_values() {
+            self.insert(entry);
+        }
+    }
+
+    /// Retrieve the number of entries in the dictionary.
+    pub fn len(&self) -> usize {
+        self.by_tag.len()
+    }
+
+    /// Check whether the dictionary has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.by_tag.is_empty()
+    }
+
+    /// Load a data dictionary
+    /// from a source in the DCMTK data dictionary format (`dicom.dic`).
+    ///
+    /// Each line describes an attribute in tab separated fields:
+    /// the tag, the value representation, the keyword,
+    /// the value multiplicity, and the attribute's origin.
+    /// Empty lines and lines starting with `#` are ignored,
+    /// as well as private, generic, and illegal entries,
+    /// and tag ranges which cannot be represented by [`TagRange`].
+    /// Since this format does not describe the full name of an attribute,
+    /// each entry is named by its keyword.
+    pub fn from_dcmtk_reader<R: BufRead>(source: R) -> Result<Self, LoadError> {
+        let mut dict = RuntimeDataDictionary::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.context(ReadSourceSnafu)?;
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<_> = line.split('\t').filter(|f| !f.is_empty()).collect();
+            let (tag, vr, alias) = match fields.as_slice() {
+                [tag, vr, alias, ..] => (*tag, *vr, *alias),
+                _ => return InvalidEntrySnafu { line: line_no }.fail(),
+            };
+            let origin = fields.get(4).copied().unwrap_or("DICOM");
+            if matches!(origin, "ILLEGAL" | "PRIVATE" | "GENERIC") {
+                continue;
+            }
+            if vr == "na" {
+                continue;
+            }
+            let retired = origin.contains("retired") || alias.starts_with("RETIRED_");
+            let alias = alias.trim_start_matches("RETIRED_");
+            let vm = match fields.get(3) {
+                Some(vm) => vm
+                    .parse()
+                    .ok()
+                    .context(InvalidEntrySnafu { line: line_no })?,
+                None => ValueMultiplicity::ONE,
+            };
+
+            // (gggg,eeee), (gg00-ggFF,eeee), or (gggg,ee00-eeFF)
+            let tag = match parse_dcmtk_tag(tag) {
+                Some(tag) => tag,
+                None if tag.contains('-') || tag.contains('x') => continue,
+                None => {
+                    return InvalidEntrySnafu { line: line_no }.fail();
+                }
+            };
+
+            dict.insert(DictionaryEntryBuf {
+                tag,
+                alias: alias.to_string(),
+                vr: parse_dcmtk_vr(vr),
+                name: alias.to_string(),
+                vm,
+                retired,
+            });
+        }
+        Ok(dict)
+    }
+
+    /// Load a data dictionary from a source in the GDCM XML format.
+    ///
+    /// Each attribute is described by an `entry` element
+    /// with the attributes `group`, `element`, `vr`,
+    /// and either `keyword` or `name`.
+    /// In the absence of a keyword,
+    /// one is derived from the name by removing
+    /// all characters which are not alphanumeric,
+    /// and in the absence of a name, the keyword is used as the name.
+    /// The optional attributes `vm` and `retired`
+    /// describe the value multiplicity and retired status.
+    /// Other elements and attributes are ignored.
+    pub fn from_gdcm_xml_reader<R: Read>(mut source: R) -> Result<Self, LoadError> {
+        let mut text = String::new();
+        source.read_to_string(&mut text).context(ReadSourceSnafu)?;
+
+        let mut dict = RuntimeDataDictionary::new();
+        let mut reader = Reader::from_str(&text);
+        // line numbers are counted up to the start of each event
+        let mut line = 1;
+        let mut counted = 0;
+        loop {
+            let position = reader.buffer_position();
+            line += text.as_bytes()[counted..position]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count();
+            counted = position;
+
+            let entry = match reader.read_event().context(ParseXmlSnafu { line })? {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"entry" => e,
+                Event::Eof => break,
+                _ => continue,
+            };
+            let attributes = entry
+                .attributes()
+                .map(|attribute| {
+                    let attribute = attribute.map_err(quick_xml::Error::from)?;
+                    let value = attribute.unescape_value()?;
+                    Ok((attribute.key.into_inner(), value))
+                })
+                .collect::<Result<Vec<_>, quick_xml::Error>>()
+                .context(ParseXmlSnafu { line })?;
+
+            let get = |key: &str| {
+                attributes
+                    .iter()
+                    .find(|(k, _)| *k == key.as_bytes())
+                    .map(|(_, v)| v.as_ref())
+            };
+            let group = get("group").context(InvalidEntrySnafu { line })?;
+            let element = get("element").context(InvalidEntrySnafu { line })?;
+            let alias = match get("keyword").filter(|k| !k.is_empty()) {
+                Some(keyword) => keyword.to_string(),
+                None => get("name")
+                    .context(InvalidEntrySnafu { line })?
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .collect(),
+            };
+            if alias.is_empty() {
+                continue;
+            }
+            // ambiguous VRs such as `US_SS` resolve to the first option
+            let vr = get("vr")
+                .and_then(|vr| vr.split(|c| c == '_' || c == ' ').next())
+                .and_then(|vr| vr.parse().ok())
+                .unwrap_or(VR::UN);
+
+            let vm = match get("vm") {
+                Some(vm) => vm.parse().ok().context(InvalidEntrySnafu { line })?,
+                None => ValueMultiplicity::ONE,
+            };
+            let name = match get("name").filter(|n| !n.is_empty()) {
+                Some(name) => name.to_string(),
+                None => alias.clone(),
+            };
+            let retired = get("retired") == Some("true");
+
+            let tag = format!("({},{})", group, element);
+            let tag: TagRange = tag.parse().context(InvalidTagSnafu { tag, line })?;
+            dict.insert(DictionaryEntryBuf {
+                tag,
+                alias,
+                vr,
+                name,
+                vm,
+                retired,
+            });
+        }
+        Ok(dict)
+    }
+
+    /// Load a data dictionary from a source in CSV format.
+    ///
+    /// Each line describes an attribute in comma separated fields:
+    /// the tag, the value representation, and the keyword,
+    /// optionally followed by the value multiplicity (`1` by default),
+    /// the full name (the keyword by default),
+    /// and whether the attribute is retired (`false` by default).
+    /// The tag may be written as `(gggg,eeee)`, `gggg,eeee` in quotes,
+    /// or `ggggeeee`,
+    /// and repeating groups or elements can be written with `xx`.
+    /// Additional fields are ignored.
+    /// The first line is skipped if it is a header
+    /// (its first field starts with `tag`, in any case).
+    pub fn from_csv_reader<R: BufRead>(source: R) -> Result<Self, LoadError> {
+        let mut dict = RuntimeDataDictionary::new();
+        for (i, line) in source.lines().enumerate() {
+            let line = line.context(ReadSourceSnafu)?;
+            let line_no = i + 1;
+            let line = line.trim();
+            if line.is_empty() || (i == 0 && line.to_ascii_lowercase().starts_with("tag")) {
+                continue;
+            }
+
+            // split the tag, which may contain a comma
+            let (tag, rest) = if let Some(line) = line.strip_prefix('"') {
+                line.split_once('"')
+                    .map(|(tag, rest)| (tag, rest.trim_start_matches(',')))
+            } else if line.starts_with('(') {
+                line.split_once(')')
+                    .map(|(tag, rest)| (&line[..=tag.len()], rest.trim_start_matches(',')))
+            } else {
+                line.split_once(',')
+            }
+            .context(InvalidEntrySnafu { line: line_no })?;
+
+            let mut fields = rest.split(',').map(|f| f.trim().trim_matches('"'));
+            let vr = fields.next().context(InvalidEntrySnafu { line: line_no })?;
+            let alias = fields
+                .next()
+                .filter(|a| !a.is_empty())
+                .context(InvalidEntrySnafu { line: line_no })?;
+            let vm = match fields.next().filter(|vm| !vm.is_empty()) {
+                Some(vm) => vm
+                    .parse()
+                    .ok()
+                    .context(InvalidEntrySnafu { line: line_no })?,
+                None => ValueMultiplicity::ONE,
+            };
+            let name = match fields.next().filter(|n| !n.is_empty()) {
+                Some(name) => name.to_string(),
+                None => alias.to_string(),
+            };
+            let retired = fields
+                .next()
+                .map(|r| r.eq_ignore_ascii_case("true") || r.eq_ignore_ascii_case("retired"))
+                .unwrap_or(false);
+
+            let tag = tag.trim();
+            let tag_range = if tag.len() == 8 && !tag.contains(',') {
+                format!("{},{}", &tag[..4], &tag[4..]).parse()
+            } else {
+                tag.parse()
+            }
+            .context(InvalidTagSnafu { tag, line: line_no })?;
+
+            dict.insert(DictionaryEntryBuf {
+                tag: tag_range,
+                alias: alias.to_string(),
+                vr: vr.parse().unwrap_or(VR::UN),
+                name,
+                vm,
+                retired,
+            });
+        }
+        Ok(dict)
+    }
+
+    fn indexed_tag(&self, tag: Tag) -> Option<&DictionaryEntryBuf> {
+        self.by_tag.get(&tag).or_else(|| {
+            let group_trimmed = Tag(tag.0 & 0xFF00, tag.1);
+            if self.repeating_ggxx.contains(&group_trimmed) {
+                return self.by_tag.get(&group_trimmed);
+            }
+            let elem_trimmed = Tag(tag.0, tag.1 & 0xFF00);
+            if self.repeating_eexx.contains(&elem_trimmed) {
+                return self.by_tag.get(&elem_trimmed);
+            }
+            None
+        })
+    }
+}
+
+impl DataDictionary for RuntimeDataDictionary {
+    type Entry = DictionaryEntryBuf;
+
+    fn by_name(&self, name: &str) -> Option<&Self::Entry> {
+        self.by_name.get(name).and_then(|tag| self.by_tag.get(tag))
+    }
+
+    fn by_tag(&self, tag: Tag) -> Option<&Self::Entry> {
+        self.indexed_tag(tag)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = &Self::Entry> + '_> {
+        Box::new(self.by_tag.values())
+    }
+}
+
+impl<'a> DataDictionary for &'a RuntimeDataDictionary {
+    type Entry = DictionaryEntryBuf;
+
+    fn by_name(&self, name: &str) -> Option<&Self::Entry> {
+        (**self).by_name(name)
+    }
+
+    fn by_tag(&self, tag: Tag) -> Option<&Self::Entry> {
+        (**self).by_tag(tag)
+    }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = &Self::Entry> + '_> {
+        (**self).entries()
+    }
+}
+
+/// Parse a tag in the DCMTK dictionary format,
+/// which may describe a range of groups or elements.
+fn parse_dcmtk_tag(tag: &str) -> Option<TagRange> {
+    let tag = tag.strip_prefix('(')?.strip_suffix(')')?;
+    let (group, element) = tag.split_once(',')?;
+
+    // a range of the form `XX00-XXFF`
+    fn range_base(part: &str) -> Option<u16> {
+        let (start, end) = part.split_once('-')?;
+        if start.len() != 4 || end.len() != 4 || start[..2] != end[..2] {
+            return None;
+        }
+        if &start[2..] != "00" || !end[2..].eq_ignore_ascii_case("FF") {
+            return None;
+        }
+        u16::from_str_radix(start, 16).ok()
+    }
+
+    let single = |part: &str| {
+        if part.len() == 4 && part.bytes().all(|b| b.is_ascii_hexdigit()) {
+            u16::from_str_radix(part, 16).ok()
+        } else {
+            None
+        }
+    };
+
+    match (single(group), single(element)) {
+        (Some(g), Some(e)) => Some(TagRange::Single(Tag(g, e))),
+        (None, Some(e)) => range_base(group).map(|g| TagRange::Group100(Tag(g, e))),
+        (Some(g), None) => range_base(element).map(|e| TagRange::Element100(Tag(g, e))),
+        (None, None) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LoadError, RuntimeDataDictionary};
+    use crate::dictionary::{DataDictionary, DictionaryEntry, TagRange, ValueMultiplicity};
+    use crate::header::{Tag, VR};
+
+    #[test]
+    fn load_dcmtk_dictionary() {
+        let source = "\
+#
+# sample dictionary
+#
+(0008,0005)\tCS\tSpecificCharacterSet\t1-n\tDICOM
+(0010,0010)\tPN\tPatientName\t1\tDICOM
+(0028,0040)\tCS\tRETIRED_ImageFormat\t1\tDICOM/retired
+(6000-60FF,3000)\tox\tOverlayData\t1\tDICOM
+(0028,04x0)\tUS\tRETIRED_RowsForNthOrderCoefficients\t1\tDICOM/retired
+(FFFE,E000)\tna\tItem\t1\tDICOM
+(0009,0010)\tLO\tPrivateCreator\t1\tPRIVATE
+";
+        let dict = RuntimeDataDictionary::from_dcmtk_reader(source.as_bytes()).unwrap();
+        assert_eq!(dict.len(), 4);
+
+        let entry = dict.by_name("PatientName").unwrap();
+        assert_eq!(entry.tag(), Tag(0x0010, 0x0010));
+        assert_eq!(entry.vr(), VR::PN);
+
+        assert_eq!(entry.name(), "PatientName");
+        assert!(!entry.is_retired());
+        let entry = dict.by_name("SpecificCharacterSet").unwrap();
+        assert_eq!(entry.vm(), ValueMultiplicity::unbounded(1, 1));
+
+        let entry = dict.by_tag(Tag(0x0028, 0x0040)).unwrap();
+        assert_eq!(entry.alias(), "ImageFormat");
+        assert!(entry.is_retired());
+
+        let entry = dict.by_tag(Tag(0x6002, 0x3000)).unwrap();
+        assert_eq!(entry.tag_range(), TagRange::Group100(Tag(0x6000, 0x3000)));
+        assert_eq!(entry.alias(), "OverlayData");
+        assert_eq!(entry.vr(), VR::OB);
+
+        assert!(dict.by_name("Item").is_none());
+        assert!(dict.by_tag(Tag(0x0009, 0x0010)).is_none());
+
+        assert!(RuntimeDataDictionary::from_dcmtk_reader(&b"(0010,0010)\tPN\n"[..]).is_err());
+    }
+
+    #[test]
+    fn load_gdcm_xml_dictionary() {
+        let source = r#"<?xml version="1.0" encoding="UTF-8"?>
+<dicts>
+  <dict edition="2008">
+    <entry group="0010" element="0010" keyword="PatientName" vr="PN" vm="1" name="Patient&apos;s Name"/>
+    <entry group="0028" element="0106" vr="US_SS" vm="1" name="Smallest Image Pixel Value" retired="true"/>
+    <entry group="60xx" element="3000" keyword="OverlayData" vr="OB_OW" vm="1" name="Overlay Data">
+      <description>Overlay pixel data</description>
+    </entry>
+  </dict>
+</dicts>
+"#;
+        let dict = RuntimeDataDictionary::from_gdcm_xml_reader(source.as_bytes()).unwrap();
+        assert_eq!(dict.len(), 3);
+
+        let entry = dict.by_name("PatientName").unwrap();
+        assert_eq!(entry.tag(), Tag(0x0010, 0x0010));
+        assert_eq!(entry.name(), "Patient's Name");
+
+        // keyword derived from the name
+        let entry = dict.by_name("SmallestImagePixelValue").unwrap();
+        assert_eq!(entry.tag(), Tag(0x0028, 0x0106));
+        assert_eq!(entry.vr(), VR::US);
+        assert!(entry.is_retired());
+
+        let entry = dict.by_tag(Tag(0x6010, 0x3000)).unwrap();
+        assert_eq!(entry.alias(), "OverlayData");
+        assert_eq!(entry.vr(), VR::OB);
+
+        let source = "<dict>\n  <entry group=\"00G0\" element=\"0010\" keyword=\"Thing\" vr=\"LO\"/>\n</dict>";
+        assert!(matches!(
+            RuntimeDataDictionary::from_gdcm_xml_reader(source.as_bytes()),
+            Err(LoadError::InvalidTag { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn load_csv_dictionary() {
+        let source = "\
+Tag,VR,Keyword,VM,Name,Retired
+(0009,1001),LO,AcmeScannerMode,1-n,Acme Scanner Mode (legacy),true
+\"0009,1002\",DS,AcmeTubeAngle
+00091003,UN,AcmeBlob
+(0009,10xx),SH,AcmeRepeating
+";
+        let dict = RuntimeDataDictionary::from_csv_reader(source.as_bytes()).unwrap();
+        assert_eq!(dict.len(), 4);
+
+        let entry = dict.by_name("AcmeScannerMode").unwrap();
+        assert_eq!(entry.vr(), VR::LO);
+        assert_eq!(entry.vm(), ValueMultiplicity::unbounded(1, 1));
+        assert_eq!(entry.name(), "Acme Scanner Mode (legacy)");
+        assert!(entry.is_retired());
+        let entry = dict.by_name("AcmeTubeAngle").unwrap();
+        assert_eq!(entry.vm(), ValueMultiplicity::ONE);
+        assert_eq!(entry.name(), "AcmeTubeAngle");
+        assert!(!entry.is_retired());
+        assert_eq!(
+            dict.by_tag(Tag(0x0009, 0x1002)).unwrap().alias(),
+            "AcmeTubeAngle"
+        );
+        assert_eq!(dict.by_tag(Tag(0x0009, 0x1003)).unwrap().vr(), VR::UN);
+        assert_eq!(
+            dict.by_tag(Tag(0x0009, 0x1004)).unwrap().alias(),
+            "AcmeRepeating"
+        );
+
+        assert!(RuntimeDataDictionary::from_csv_reader(&b"(0009,1001),LO\n"[..]).is_err());
+    }
+
+    #[test]
+    fn extend_dictionary() {
+        let mut dict =
+            RuntimeDataDictionary::from_csv_reader(&b"(0009,1001),LO,AcmeScannerMode\n"[..])
+                .unwrap();
+        let other = RuntimeDataDictionary::from_csv_reader(
+            &b"(0009,1001),CS,AcmeMode\n(0009,1002),DS,AcmeTubeAngle\n"[..],
+        )
+        .unwrap();
+        dict.extend(other);
+
+        assert_eq!(dict.len(), 2);
+        assert!(dict.by_name("AcmeScannerMode").is_none());
+        assert_eq!(dict.by_name("AcmeMode").unwrap().vr(), VR::CS);
+        assert_eq!(dict.entries().count(), 2);
+
+        // copy into another dictionary
+        let copy = RuntimeDataDictionary::from_dictionary(&dict);
+        assert_eq!(copy, dict);
+    }
+}
@@ -2,6 +2,8 @@
 
 use super::{DataDictionary, DictionaryEntryRef};
 use crate::header::Tag;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 
 /// An empty attribute dictionary.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -0,0 +1,261 @@
+//! A set of attribute tags with support for tag masks.
+//!
+//! See [`TagSet`] for more information.
+use super::{
+    InvalidElementLengthSnafu, InvalidGroupLengthSnafu, InvalidTagElementSnafu,
+    InvalidTagGroupSnafu, MissingTagElementSnafu, MissingTagSnafu, TagRange, TagRangeParseError,
+};
+use crate::header::Tag;
+use alloc::collections::{BTreeMap, BTreeSet};
+use core::iter::FromIterator;
+use core::num::ParseIntError;
+use core::str::FromStr;
+use snafu::{ensure, OptionExt, ResultExt};
+
+/// A set of attribute tags,
+/// which may also contain whole ranges of tags.
+///
+/// Besides single tags,
+/// a tag set can hold tag masks,
+/// in which any hexadecimal digit of the group or element
+/// may be left open.
+/// This covers the repeating groups of the standard,
+/// such as `(60xx,3000)` for _Overlay Data_,
+/// as well as wider selections,
+/// such as `(50xx,xxxx)` for all _Curve_ attributes
+/// or `(0009,xxxx)` for a whole private group.
+///
+/// Membership testing takes a logarithmic number of steps
+/// for each distinct mask in the set,
+/// regardless of the number of tags and ranges inserted.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_core::Tag;
+/// # use dicom_core::dictionary::TagSet;
+/// let tags: TagSet = "(0010,0010) (60xx,3000) 0009,xxxx".parse()?;
+/// assert!(tags.contains(Tag(0x0010, 0x0010)));
+/// assert!(tags.contains(Tag(0x6002, 0x3000)));
+/// assert!(tags.contains(Tag(0x0009, 0x1001)));
+/// assert!(!tags.contains(Tag(0x0010, 0x0020)));
+/// # Result::<(), Box<dyn std::error::Error>>::Ok(())
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TagSet {
+    /// the masked tag values in the set, indexed by mask
+    patterns: BTreeMap<u32, BTreeSet<u32>>,
+}
+
+impl TagSet {
+    /// Create a new empty tag set.
+    pub fn new() -> Self {
+        TagSet::default()
+    }
+
+    /// Check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Insert a single tag into the set.
+    pub fn insert(&mut self, tag: Tag) {
+        self.insert_masked(tag, Tag(0xFFFF, 0xFFFF));
+    }
+
+    /// Insert a range of tags from the data dictionary into the set.
+    pub fn insert_range(&mut self, range: TagRange) {
+        match range {
+            TagRange::Single(tag) => self.insert(tag),
+            TagRange::Group100(tag) => self.insert_masked(tag, Tag(0xFF00, 0xFFFF)),
+            TagRange::Element100(tag) => self.insert_masked(tag, Tag(0xFFFF, 0xFF00)),
+        }
+    }
+
+    /// Insert all tags of the given group into the set.
+    pub fn insert_group(&mut self, group: u16) {
+        self.insert_masked(Tag(group, 0), Tag(0xFFFF, 0));
+    }
+
+    /// Insert all tags of private attributes into the set
+    /// (those with an odd group number).
+    pub fn insert_private(&mut self) {
+        self.insert_masked(Tag(0x0001, 0), Tag(0x0001, 0));
+    }
+
+    /// Insert all tags which match the given tag
+    /// in the bits set in `mask`.
+    ///
+    /// For instance, a mask of `(FF00,FFFF)`
+    /// leaves the two rightmost digits of the group open.
+    pub fn insert_masked(&mut self, tag: Tag, mask: Tag) {
+        let mask = tag_bits(mask);
+        self.patterns
+            .entry(mask)
+            .or_default()
+            .insert(tag_bits(tag) & mask);
+    }
+
+    /// Check whether the given tag is in the set.
+    pub fn contains(&self, tag: Tag) -> bool {
+        let tag = tag_bits(tag);
+        self.patterns
+            .iter()
+            .any(|(mask, values)| values.contains(&(tag & mask)))
+    }
+}
+
+fn tag_bits(tag: Tag) -> u32 {
+    u32::from(tag.group()) << 16 | u32::from(tag.element())
+}
+
+impl From<TagRange> for TagSet {
+    fn from(range: TagRange) -> Self {
+        let mut set = TagSet::new();
+        set.insert_range(range);
+        set
+    }
+}
+
+impl Extend<Tag> for TagSet {
+    fn extend<I: IntoIterator<Item = Tag>>(&mut self, iter: I) {
+        for tag in iter {
+            self.insert(tag);
+        }
+    }
+}
+
+impl Extend<TagRange> for TagSet {
+    fn extend<I: IntoIterator<Item = TagRange>>(&mut self, iter: I) {
+        for range in iter {
+            self.insert_range(range);
+        }
+    }
+}
+
+impl FromIterator<Tag> for TagSet {
+    fn from_iter<I: IntoIterator<Item = Tag>>(iter: I) -> Self {
+        let mut set = TagSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl FromIterator<TagRange> for TagSet {
+    fn from_iter<I: IntoIterator<Item = TagRange>>(iter: I) -> Self {
+        let mut set = TagSet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl FromStr for TagSet {
+    type Err = TagRangeParseError;
+
+    /// Parse a list of tags separated by whitespace or semicolons,
+    /// each in the form `(gggg,eeee)` or `gggg,eeee`,
+    /// in which any digit may be replaced with `x`
+    /// to leave it open.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut set = TagSet::new();
+        for part in s
+            .split(|c: char| c == ';' || c.is_whitespace())
+            .filter(|part| !part.is_empty())
+        {
+            let part = part
+                .strip_prefix('(')
+                .and_then(|part| part.strip_suffix(')'))
+                .unwrap_or(part);
+            let mut parts = part.split(',');
+            let group = parts.next().context(MissingTagSnafu)?;
+            let elem = parts.next().context(MissingTagElementSnafu)?;
+            ensure!(
+                group.len() == 4,
+                InvalidGroupLengthSnafu { got: group.len() }
+            );
+            ensure!(
+                elem.len() == 4,
+                InvalidElementLengthSnafu { got: elem.len() }
+            );
+            let (group, group_mask) = parse_masked(group).context(InvalidTagGroupSnafu)?;
+            let (elem, elem_mask) = parse_masked(elem).context(InvalidTagElementSnafu)?;
+            set.insert_masked(Tag(group, elem), Tag(group_mask, elem_mask));
+        }
+        Ok(set)
+    }
+}
+
+/// Parse a tag component in hexadecimal with open digits,
+/// returning the value and the mask of the digits set.
+fn parse_masked(s: &str) -> Result<(u16, u16), ParseIntError> {
+    let mut mask = 0;
+    for c in s.chars() {
+        mask <<= 4;
+        if c != 'x' && c != 'X' {
+            mask |= 0xF;
+        }
+    }
+    let value = u16::from_str_radix(&s.replace(&['x', 'X'][..], "0"), 16)?;
+    Ok((value, mask))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TagSet;
+    use crate::dictionary::TagRange;
+    use crate::header::Tag;
+
+    #[test]
+    fn tag_set_membership() {
+        let mut set = TagSet::new();
+        assert!(set.is_empty());
+        set.insert(Tag(0x0010, 0x0010));
+        set.insert_range(TagRange::Group100(Tag(0x6000, 0x3000)));
+        set.insert_range(TagRange::Element100(Tag(0x0020, 0x3100)));
+        set.insert_group(0x0009);
+        assert!(!set.is_empty());
+
+        assert!(set.contains(Tag(0x0010, 0x0010)));
+        assert!(!set.contains(Tag(0x0010, 0x0020)));
+        assert!(set.contains(Tag(0x6000, 0x3000)));
+        assert!(set.contains(Tag(0x60FE, 0x3000)));
+        assert!(!set.contains(Tag(0x6000, 0x4000)));
+        assert!(set.contains(Tag(0x0020, 0x31AB)));
+        assert!(!set.contains(Tag(0x0020, 0x3200)));
+        assert!(set.contains(Tag(0x0009, 0x0010)));
+        assert!(set.contains(Tag(0x0009, 0x10FF)));
+        assert!(!set.contains(Tag(0x0011, 0x0010)));
+
+        let mut private = TagSet::new();
+        private.insert_private();
+        assert!(private.contains(Tag(0x0011, 0x1001)));
+        assert!(!private.contains(Tag(0x0010, 0x0010)));
+    }
+
+    #[test]
+    fn parse_tag_set() {
+        let set: TagSet = "(0010,0010); 60xx,3000\n50xx,xxxx (0020,31xx)"
+            .parse()
+            .unwrap();
+        assert!(set.contains(Tag(0x0010, 0x0010)));
+        assert!(set.contains(Tag(0x6010, 0x3000)));
+        assert!(set.contains(Tag(0x5000, 0x0005)));
+        assert!(set.contains(Tag(0x50FF, 0x3000)));
+        assert!(set.contains(Tag(0x0020, 0x3101)));
+        assert!(!set.contains(Tag(0x6010, 0x3001)));
+        assert!(!set.contains(Tag(0x0010, 0x0020)));
+
+        let expected: TagSet = vec![
+            TagRange::Single(Tag(0x0010, 0x0010)),
+            TagRange::Group100(Tag(0x6000, 0x3000)),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!("0010,0010 60xx,3000".parse::<TagSet>().unwrap(), expected);
+
+        assert!("".parse::<TagSet>().unwrap().is_empty());
+        assert!("0010".parse::<TagSet>().is_err());
+        assert!("0010,001".parse::<TagSet>().is_err());
+        assert!("00g0,0010".parse::<TagSet>().is_err());
+    }
+}
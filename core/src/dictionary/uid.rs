@@ -0,0 +1,151 @@
+//! This module contains the concept of a DICOM UID dictionary.
+//!
+//! A UID dictionary describes the unique identifiers
+//! registered in the standard (PS3.6 Table A-1),
+//! such as SOP classes, transfer syntaxes,
+//! and well-known frames of reference.
+//! The standard UID dictionary is available in the `dicom-dictionary-std` crate.
+
+use core::fmt;
+
+/// The kind of a registered DICOM unique identifier.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum UidType {
+    /// SOP Class
+    SopClass,
+    /// Meta SOP Class
+    MetaSopClass,
+    /// Transfer Syntax
+    TransferSyntax,
+    /// Service Class
+    ServiceClass,
+    /// Well-known SOP Instance
+    WellKnownSopInstance,
+    /// Well-known frame of reference
+    WellKnownFrameOfReference,
+    /// Synchronization Frame of Reference
+    SynchronizationFrameOfReference,
+    /// Application Context Name
+    ApplicationContextName,
+    /// Application Hosting Model
+    ApplicationHostingModel,
+    /// Coding Scheme
+    CodingScheme,
+    /// Context Group Name
+    ContextGroupName,
+    /// Mapping Resource
+    MappingResource,
+    /// LDAP OID
+    LdapOid,
+    /// Any other kind of unique identifier
+    Other,
+}
+
+impl fmt::Display for UidType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let str = match self {
+            UidType::SopClass => "SOP Class",
+            UidType::MetaSopClass => "Meta SOP Class",
+            UidType::TransferSyntax => "Transfer Syntax",
+            UidType::ServiceClass => "Service Class",
+            UidType::WellKnownSopInstance => "Well-known SOP Instance",
+            UidType::WellKnownFrameOfReference => "Well-known frame of reference",
+            UidType::SynchronizationFrameOfReference => "Synchronization Frame of Reference",
+            UidType::ApplicationContextName => "Application Context Name",
+            UidType::ApplicationHostingModel => "Application Hosting Model",
+            UidType::CodingScheme => "Coding Scheme",
+            UidType::ContextGroupName => "Context Group Name",
+            UidType::MappingResource => "Mapping Resource",
+            UidType::LdapOid => "LDAP OID",
+            UidType::Other => "Other",
+        };
+        f.write_str(str)
+    }
+}
+
+/// Type trait for a dictionary of DICOM unique identifiers.
+///
+/// Lookups by UID ignore the trailing padding
+/// which may be present in UID values read from a data set.
+pub trait UidDictionary: fmt::Debug {
+    /// The type of the dictionary entry.
+    type Entry: UidDictionaryEntry;
+
+    /// Fetch an entry by its unique identifier.
+    fn by_uid(&self, uid: &str) -> Option<&Self::Entry>;
+
+    /// Fetch an entry by its keyword (e.g. "CTImageStorage").
+    fn by_keyword(&self, keyword: &str) -> Option<&Self::Entry>;
+}
+
+/// The UID dictionary entry data type, representing a registered identifier.
+pub trait UidDictionaryEntry {
+    /// The unique identifier.
+    fn uid(&self) -> &str;
+    /// The keyword of the identifier, with no spaces, usually in UpperCamelCase.
+    fn alias(&self) -> &str;
+    /// The full name of the identifier, as a human readable text.
+    fn name(&self) -> &str;
+    /// The kind of identifier.
+    fn uid_type(&self) -> UidType;
+    /// Whether the identifier has been retired from the standard.
+    fn is_retired(&self) -> bool;
+}
+
+/// A data type for a UID dictionary entry with string slices for its text.
+#[derive(Debug, PartialEq, Clone)]
+pub struct UidDictionaryEntryRef<'a> {
+    /// The unique identifier
+    pub uid: &'a str,
+    /// The keyword of the identifier, with no spaces, usually InCapitalizedCamelCase
+    pub alias: &'a str,
+    /// The full name of the identifier
+    pub name: &'a str,
+    /// The kind of identifier
+    pub kind: UidType,
+    /// Whether the identifier is retired
+    pub retired: bool,
+}
+
+impl<'a> UidDictionaryEntry for UidDictionaryEntryRef<'a> {
+    fn uid(&self) -> &str {
+        self.uid
+    }
+    fn alias(&self) -> &str {
+        self.alias
+    }
+    fn name(&self) -> &str {
+        self.name
+    }
+    fn uid_type(&self) -> UidType {
+        self.kind
+    }
+    fn is_retired(&self) -> bool {
+        self.retired
+    }
+}
+
+/// Remove the trailing padding of a UID value,
+/// so that it can be used as a dictionary key.
+pub fn trim_uid(uid: &str) -> &str {
+    uid.trim_end_matches(|c: char| c == '\0' || c.is_whitespace())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{trim_uid, UidType};
+
+    #[test]
+    fn test_trim_uid() {
+        assert_eq!(trim_uid("1.2.840.10008.1.2\0"), "1.2.840.10008.1.2");
+        assert_eq!(trim_uid("1.2.840.10008.1.2.1 "), "1.2.840.10008.1.2.1");
+        assert_eq!(trim_uid("1.2.840.10008.1.1"), "1.2.840.10008.1.1");
+    }
+
+    #[test]
+    fn uid_type_display() {
+        assert_eq!(UidType::SopClass.to_string(), "SOP Class");
+        assert_eq!(UidType::TransferSyntax.to_string(), "Transfer Syntax");
+    }
+}
@@ -2,16 +2,20 @@
 //! It comprises a variety of basic data types, such as the DICOM attribute tag, the
 //! element header, and element composite types.
 
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::value::validate::{check_value, Error as ValidationError, ValidationMode};
 use crate::value::{
-    CastValueError, ConvertValueError, DicomDate, DicomDateTime, DicomTime, PrimitiveValue, Value,
+    CastValueError, ConvertValueError, DicomDate, DicomDateTime, DicomTime, F64Values,
+    PrimitiveValue, StrValues, Value,
 };
+use alloc::borrow::Cow;
 use chrono::FixedOffset;
+use core::cmp::Ordering;
+use core::fmt;
+use core::str::{from_utf8, FromStr};
 use num_traits::NumCast;
 use snafu::{Backtrace, Snafu};
-use std::borrow::Cow;
-use std::cmp::Ordering;
-use std::fmt;
-use std::str::{from_utf8, FromStr};
 
 /// Error type for issues constructing a sequence item header.
 #[derive(Debug, Snafu)]
@@ -30,7 +34,7 @@ pub enum SequenceItemHeaderError {
     UnexpectedDelimiterLength { len: Length, backtrace: Backtrace },
 }
 
-type Result<T, E = SequenceItemHeaderError> = std::result::Result<T, E>;
+type Result<T, E = SequenceItemHeaderError> = core::result::Result<T, E>;
 
 /// Trait for any DICOM entity (element or item) which may have a length.
 pub trait HasLength {
@@ -228,6 +232,18 @@ impl<I, P> DataElement<I, P> {
     pub fn into_value(self) -> Value<I, P> {
         self.value
     }
+
+    /// Check whether the element's value is valid
+    /// for the element's value representation.
+    ///
+    /// Only primitive values are checked.
+    /// Items of a data set sequence should be validated separately.
+    pub fn validate(&self, mode: ValidationMode) -> Result<(), ValidationError> {
+        match &self.value {
+            Value::Primitive(value) => check_value(self.header.vr, value, mode),
+            _ => Ok(()),
+        }
+    }
 }
 
 impl<I, P> DataElement<I, P>
@@ -259,6 +275,43 @@ where
         }
     }
 
+    /// Create a data element from the given parts,
+    /// checking that a primitive value is valid
+    /// for the given value representation.
+    ///
+    /// The length is inferred from the value's byte length,
+    /// as in [`new`](DataElement::new).
+    /// See the [`validate`](crate::value::validate) module
+    /// for the checks performed in each mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dicom_core::{DataElement, PrimitiveValue, Tag, VR};
+    /// use dicom_core::value::validate::ValidationMode;
+    ///
+    /// let elem: Result<DataElement, _> = DataElement::new_checked(
+    ///     Tag(0x0008, 0x0020),
+    ///     VR::DA,
+    ///     PrimitiveValue::from("2023.01.31"),
+    ///     ValidationMode::Strict,
+    /// );
+    /// assert!(elem.is_err());
+    /// ```
+    pub fn new_checked<T>(
+        tag: Tag,
+        vr: VR,
+        value: T,
+        mode: ValidationMode,
+    ) -> Result<Self, ValidationError>
+    where
+        T: Into<Value<I, P>>,
+    {
+        let elem = DataElement::new(tag, vr, value);
+        elem.validate(mode)?;
+        Ok(elem)
+    }
+
     /// Create a primitive data element from the given parts.
     ///
     /// This method will not check
@@ -326,6 +379,20 @@ where
         self.value().to_multi_str()
     }
 
+    /// Obtain an iterator over the individual values
+    /// of the data element as strings.
+    ///
+    /// If the value is a primitive, the values are provided
+    /// as described in [`PrimitiveValue::values_str`],
+    /// with textual values split by the backslash delimiter.
+    ///
+    /// Returns an error if the value is not primitive.
+    ///
+    /// [`PrimitiveValue::values_str`]: ../enum.PrimitiveValue.html#values_str
+    pub fn values_str(&self) -> Result<StrValues<'_>, CastValueError> {
+        self.value().values_str()
+    }
+
     /// Retrieve and convert the value of the data element into an integer.
     ///
     /// If the value is a primitive,
@@ -339,7 +406,7 @@ where
     where
         T: Clone,
         T: NumCast,
-        T: FromStr<Err = std::num::ParseIntError>,
+        T: FromStr<Err = core::num::ParseIntError>,
     {
         self.value().to_int()
     }
@@ -355,7 +422,7 @@ where
     where
         T: Clone,
         T: NumCast,
-        T: FromStr<Err = std::num::ParseIntError>,
+        T: FromStr<Err = core::num::ParseIntError>,
     {
         self.value().to_multi_int()
     }
@@ -412,6 +479,19 @@ where
         self.value().to_multi_float64()
     }
 
+    /// Obtain an iterator over the individual values
+    /// of the data element converted to double-precision floating point numbers.
+    ///
+    /// If the value is a primitive, the values are converted
+    /// as described in [`PrimitiveValue::values_f64`].
+    ///
+    /// Returns an error if the value is not primitive.
+    ///
+    /// [`PrimitiveValue::values_f64`]: ../enum.PrimitiveValue.html#values_f64
+    pub fn values_f64(&self) -> Result<F64Values<'_>, ConvertValueError> {
+        self.value().values_f64()
+    }
+
     /// Retrieve and convert the primitive value into a date.
     ///
     /// If the value is a primitive, it will be converted into
@@ -853,7 +933,7 @@ impl VR {
 impl FromStr for VR {
     type Err = &'static str;
 
-    fn from_str(string: &str) -> std::result::Result<Self, Self::Err> {
+    fn from_str(string: &str) -> core::result::Result<Self, Self::Err> {
         use VR::*;
         match string {
             "AE" => Ok(AE),
@@ -1058,7 +1138,7 @@ impl PartialOrd<Length> for Length {
     }
 }
 
-impl ::std::ops::Add<Length> for Length {
+impl core::ops::Add<Length> for Length {
     type Output = Self;
 
     fn add(self, rhs: Length) -> Self::Output {
@@ -1076,7 +1156,7 @@ impl ::std::ops::Add<Length> for Length {
     }
 }
 
-impl ::std::ops::Add<i32> for Length {
+impl core::ops::Add<i32> for Length {
     type Output = Self;
 
     fn add(self, rhs: i32) -> Self::Output {
@@ -1095,7 +1175,7 @@ impl ::std::ops::Add<i32> for Length {
     }
 }
 
-impl std::ops::Sub<Length> for Length {
+impl core::ops::Sub<Length> for Length {
     type Output = Self;
 
     fn sub(self, rhs: Length) -> Self::Output {
@@ -1105,7 +1185,7 @@ impl std::ops::Sub<Length> for Length {
     }
 }
 
-impl std::ops::SubAssign<Length> for Length {
+impl core::ops::SubAssign<Length> for Length {
     fn sub_assign(&mut self, rhs: Length) {
         match (self.0, rhs.0) {
             (UNDEFINED_LEN, _) | (_, UNDEFINED_LEN) => (), // no-op
@@ -1120,7 +1200,7 @@ impl std::ops::SubAssign<Length> for Length {
     }
 }
 
-impl std::ops::Sub<i32> for Length {
+impl core::ops::Sub<i32> for Length {
     type Output = Self;
 
     fn sub(self, rhs: i32) -> Self::Output {
@@ -1130,7 +1210,7 @@ impl std::ops::Sub<i32> for Length {
     }
 }
 
-impl std::ops::SubAssign<i32> for Length {
+impl core::ops::SubAssign<i32> for Length {
     fn sub_assign(&mut self, rhs: i32) {
         match self.0 {
             UNDEFINED_LEN => (), // no-op
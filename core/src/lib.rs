@@ -1,4 +1,5 @@
 #![crate_type = "lib"]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![deny(trivial_numeric_casts, unsafe_code, unstable_features)]
 #![warn(
     missing_debug_implementations,
@@ -21,11 +22,25 @@
 //!   and the possible presence of sequences.
 //! - [`error`] contains crate-level error and result types.
 //!
+//!
+//! ## Features
+//!
+//! The `std` feature is enabled by default.
+//! Disabling it makes the crate depend only on `core` and `alloc`,
+//! so that headers, values, and dictionary traits
+//! can be used in environments without the standard library.
+//! The items which require I/O,
+//! such as the [run-time dictionary](dictionary::runtime)
+//! and the [private dictionary](dictionary::private),
+//! are only available with `std`.
+//!
 //! [`dictionary`]: ./dictionary/index.html
 //! [`error`]: ./error/index.html
 //! [`header`]: ./header/index.html
 //! [`value`]: ./value/index.html
 
+extern crate alloc;
+
 pub mod dictionary;
 pub mod header;
 pub mod value;
@@ -38,8 +53,21 @@ pub use value::{PrimitiveValue, Value as DicomValue};
 pub use chrono;
 pub use smallvec;
 
+#[cfg(feature = "std")]
 mod util;
 
+/// The items of the standard prelude which are not in `core`,
+/// for building without `std`.
+#[cfg(not(feature = "std"))]
+mod prelude {
+    pub use alloc::borrow::ToOwned;
+    pub use alloc::boxed::Box;
+    pub use alloc::format;
+    pub use alloc::string::{String, ToString};
+    pub use alloc::vec;
+    pub use alloc::vec::Vec;
+}
+
 /// Helper macro for constructing a DICOM primitive value,
 /// of an arbitrary variant and multiplicity.
 ///
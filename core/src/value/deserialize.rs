@@ -4,9 +4,9 @@ use crate::value::partial::{
     Error as PartialValuesError,
 };
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use core::convert::TryFrom;
+use core::ops::{Add, Mul, Sub};
 use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
-use std::convert::TryFrom;
-use std::ops::{Add, Mul, Sub};
 
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -52,7 +52,7 @@ pub enum Error {
     },
 }
 
-type Result<T, E = Error> = std::result::Result<T, E>;
+type Result<T, E = Error> = core::result::Result<T, E>;
 
 /** Decode a single DICOM Date (DA) into a `chrono::NaiveDate` value.
   * As per standard, a full 8 byte representation (YYYYMMDD) is required,
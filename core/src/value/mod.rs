@@ -1,16 +1,19 @@
 //! This module includes a high level abstraction over a DICOM data element's value.
 
 use crate::header::{EmptyObject, HasLength, Length, Tag};
+use alloc::borrow::Cow;
+use core::str::FromStr;
 use num_traits::NumCast;
 use smallvec::SmallVec;
-use std::{borrow::Cow, str::FromStr};
 
 pub mod deserialize;
 pub mod partial;
 pub mod person_name;
 mod primitive;
 pub mod range;
+#[cfg(feature = "std")]
 pub mod serialize;
+pub mod validate;
 
 pub use self::deserialize::Error as DeserializeError;
 pub use self::partial::{DicomDate, DicomDateTime, DicomTime};
@@ -18,13 +21,23 @@ pub use self::person_name::PersonName;
 pub use self::range::{AsRange, DateRange, DateTimeRange, TimeRange};
 
 pub use self::primitive::{
-    CastValueError, ConvertValueError, InvalidValueReadError, PrimitiveValue, ValueType,
+    CastValueError, ConvertValueError, F64Values, InvalidValueReadError, PrimitiveValue, StrValues,
+    ValueType,
 };
 
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 /// re-exported from chrono
 use chrono::FixedOffset;
 
 /// An aggregation of one or more elements in a value.
+///
+/// Most data elements have a value multiplicity of 1 or 2,
+/// so up to two elements are stored inline
+/// without a separate heap allocation.
+/// A larger inline capacity would grow every [`PrimitiveValue`]
+/// (currently 72 bytes on 64-bit targets)
+/// for the sake of a small number of attributes.
 pub type C<T> = SmallVec<[T; 2]>;
 
 /// A trait for a value that maps to a DICOM element data value.
@@ -267,6 +280,23 @@ where
         }
     }
 
+    /// Obtain an iterator over the individual values
+    /// of the primitive value as strings.
+    ///
+    /// If the value is a primitive, the values are provided
+    /// as described in [`PrimitiveValue::values_str`].
+    ///
+    /// Returns an error if the value is not primitive.
+    pub fn values_str(&self) -> Result<StrValues<'_>, CastValueError> {
+        match self {
+            Value::Primitive(prim) => Ok(prim.values_str()),
+            _ => Err(CastValueError {
+                requested: "string",
+                got: self.value_type(),
+            }),
+        }
+    }
+
     /// Convert the full primitive value into raw bytes.
     ///
     /// String values already encoded with the `Str` and `Strs` variants
@@ -293,7 +323,7 @@ where
     where
         T: Clone,
         T: NumCast,
-        T: FromStr<Err = std::num::ParseIntError>,
+        T: FromStr<Err = core::num::ParseIntError>,
     {
         match self {
             Value::Primitive(v) => v.to_int::<T>(),
@@ -315,7 +345,7 @@ where
     where
         T: Clone,
         T: NumCast,
-        T: FromStr<Err = std::num::ParseIntError>,
+        T: FromStr<Err = core::num::ParseIntError>,
     {
         match self {
             Value::Primitive(v) => v.to_multi_int::<T>(),
@@ -399,6 +429,24 @@ where
         }
     }
 
+    /// Obtain an iterator over the individual values
+    /// of the primitive value converted to double-precision floating point numbers.
+    ///
+    /// If the value is a primitive, the values are converted
+    /// as described in [`PrimitiveValue::values_f64`].
+    ///
+    /// Returns an error if the value is not primitive.
+    pub fn values_f64(&self) -> Result<F64Values<'_>, ConvertValueError> {
+        match self {
+            Value::Primitive(v) => Ok(v.values_f64()),
+            _ => Err(ConvertValueError {
+                requested: "float64",
+                original: self.value_type(),
+                cause: None,
+            }),
+        }
+    }
+
     /// Retrieve and convert the primitive value into a `DicomDate`.
     ///
     /// If the value is a primitive, it will be converted into
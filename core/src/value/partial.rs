@@ -1,11 +1,13 @@
 //! Handling of partial precision of Date, Time and DateTime values.
 
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::value::range::AsRange;
 use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveTime, Timelike};
+use core::convert::{TryFrom, TryInto};
+use core::fmt;
+use core::ops::RangeInclusive;
 use snafu::{Backtrace, ResultExt, Snafu};
-use std::convert::{TryFrom, TryInto};
-use std::fmt;
-use std::ops::RangeInclusive;
 
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -46,7 +48,7 @@ pub enum Error {
     Conversion {
         value: String,
         component: DateComponent,
-        source: std::num::TryFromIntError,
+        source: core::num::TryFromIntError,
     },
     #[snafu(display(
         "Cannot convert from an imprecise value. This value represents a date / time range"
@@ -54,7 +56,7 @@ pub enum Error {
     ImpreciseValue { backtrace: Backtrace },
 }
 
-type Result<T, E = Error> = std::result::Result<T, E>;
+type Result<T, E = Error> = core::result::Result<T, E>;
 
 /// Represents components of Date, Time and DateTime values.
 #[derive(Debug, PartialEq, Copy, Clone, Eq, Hash, PartialOrd, Ord)]
@@ -82,7 +84,7 @@ pub enum DateComponent {
 /// # Example
 /// ```
 /// # use std::error::Error;
-/// # use std::convert::TryFrom;
+/// # use core::convert::TryFrom;
 /// use chrono::NaiveDate;
 /// use dicom_core::value::{DicomDate, AsRange};
 /// # fn main() -> Result<(), Box<dyn Error>> {
@@ -117,7 +119,7 @@ pub struct DicomDate(DicomDateImpl);
 /// # Example
 /// ```
 /// # use std::error::Error;
-/// # use std::convert::TryFrom;
+/// # use core::convert::TryFrom;
 /// use chrono::NaiveTime;
 /// use dicom_core::value::{DicomTime, AsRange};
 /// # fn main() -> Result<(), Box<dyn Error>> {
@@ -182,7 +184,7 @@ enum DicomTimeImpl {
 /// # Example
 /// ```
 /// # use std::error::Error;
-/// # use std::convert::TryFrom;
+/// # use core::convert::TryFrom;
 /// use chrono::{DateTime, FixedOffset, TimeZone};
 /// use dicom_core::value::{DicomDate, DicomTime, DicomDateTime, AsRange};
 /// # fn main() -> Result<(), Box<dyn Error>> {
@@ -1,6 +1,8 @@
 //! Handling of DICOM values with the PN (person name) value representation
 //! as per PS3.5 sect 6.2.
-use std::fmt::{Display, Formatter};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use core::fmt::{Display, Formatter};
 
 /// A DICOM _Person Name_ (PN value representation).
 ///
@@ -49,7 +51,7 @@ pub struct PersonNameBuilder<'a> {
 }
 
 impl Display for PersonName<'_> {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         let components: &[Option<&str>] = &[
             self.prefix,
             self.given,
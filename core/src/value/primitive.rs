@@ -4,18 +4,22 @@
 
 use super::DicomValueType;
 use crate::header::{HasLength, Length, Tag};
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::value::partial::{DateComponent, DicomDate, DicomDateTime, DicomTime, Precision};
 use crate::value::person_name::PersonName;
 use crate::value::range::{DateRange, DateTimeRange, TimeRange};
+use alloc::borrow::Cow;
+use alloc::sync::Arc;
+use bytes::Bytes;
 use chrono::FixedOffset;
+use core::fmt::{self, Display};
+use core::str::FromStr;
 use itertools::Itertools;
 use num_traits::NumCast;
 use safe_transmute::to_bytes::transmute_to_bytes;
 use smallvec::SmallVec;
 use snafu::{Backtrace, ResultExt, Snafu};
-use std::borrow::Cow;
-use std::fmt::{self, Display};
-use std::str::FromStr;
 
 /** Triggered when a value reading attempt fails.
  */
@@ -32,13 +36,13 @@ pub enum InvalidValueReadError {
     #[snafu(display("Failed to read text as a floating point number"))]
     ParseFloat {
         backtrace: Backtrace,
-        source: std::num::ParseFloatError,
+        source: core::num::ParseFloatError,
     },
     /// The value cannot be parsed to an integer.
     #[snafu(display("Failed to read text as an integer"))]
     ParseInteger {
         backtrace: Backtrace,
-        source: std::num::ParseIntError,
+        source: core::num::ParseIntError,
     },
     /// An attempt of reading more than the number of bytes in the length attribute was made.
     #[snafu(display("Unexpected end of element"))]
@@ -126,7 +130,7 @@ impl Display for CastValueError {
     }
 }
 
-impl std::error::Error for CastValueError {}
+impl snafu::Error for CastValueError {}
 
 /// An error type for a failed attempt at converting a value
 /// into another representation.
@@ -158,13 +162,13 @@ impl Display for ConvertValueError {
     }
 }
 
-impl std::error::Error for ConvertValueError {
-    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+impl snafu::Error for ConvertValueError {
+    fn source(&self) -> Option<&(dyn snafu::Error + 'static)> {
         self.cause.as_ref().map(|x| x as _)
     }
 }
 
-pub type Result<T, E = InvalidValueReadError> = std::result::Result<T, E>;
+pub type Result<T, E = InvalidValueReadError> = core::result::Result<T, E>;
 
 // Re-exported from chrono
 pub use chrono::{DateTime, NaiveDate, NaiveTime};
@@ -202,7 +206,7 @@ pub type C<T> = SmallVec<[T; 2]>;
 /// [`smallvec`]: ../../smallvec/index.html
 /// [`C`]: ./type.C.html
 /// [`dicom_value!`]: ../macro.dicom_value.html
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub enum PrimitiveValue {
     /// No data. Usually employed for zero-length values.
     Empty,
@@ -217,6 +221,16 @@ pub enum PrimitiveValue {
     /// Used for ST, LT, UT and UR, which are never multi-valued.
     Str(String),
 
+    /// A sequence of strings in a reference counted buffer,
+    /// which may be shared with other values,
+    /// such as values interned while reading many objects.
+    /// Used in place of `Strs`.
+    ///
+    /// It compares equal to a `Strs` value with the same strings,
+    /// and the strings can be obtained in the same way,
+    /// including through [`strings`](PrimitiveValue::strings).
+    SharedStrs(Arc<[String]>),
+
     /// A sequence of attribute tags.
     /// Used specifically for AT.
     Tags(C<Tag>),
@@ -225,6 +239,15 @@ pub enum PrimitiveValue {
     /// Used for OB and UN.
     U8(C<u8>),
 
+    /// A sequence of bytes in a reference counted buffer,
+    /// which may be shared with other values,
+    /// such as a slice of a data set read entirely into memory.
+    /// Used for OB and UN
+    /// when read from a shared buffer without copying.
+    ///
+    /// It compares equal to a `U8` value with the same bytes.
+    SharedBytes(Bytes),
+
     /// The value is a sequence of signed 16-bit integers.
     /// Used for SS.
     I16(C<i16>),
@@ -321,6 +344,12 @@ impl From<&[u8]> for PrimitiveValue {
     }
 }
 
+impl From<Bytes> for PrimitiveValue {
+    fn from(value: Bytes) -> Self {
+        PrimitiveValue::SharedBytes(value)
+    }
+}
+
 impl<'a> From<PersonName<'a>> for PrimitiveValue {
     fn from(p: PersonName) -> Self {
         PrimitiveValue::Str(p.to_dicom_string())
@@ -387,6 +416,16 @@ impl PrimitiveValue {
         PrimitiveValue::I32(C::from_elem(value, 1))
     }
 
+    /// Obtain the strings of a `Strs` or `SharedStrs` value,
+    /// or an empty slice for any other variant.
+    fn text_values(&self) -> &[String] {
+        match self {
+            PrimitiveValue::Strs(values) => values,
+            PrimitiveValue::SharedStrs(values) => values,
+            _ => &[],
+        }
+    }
+
     /// Obtain the number of individual elements. This number may not
     /// match the DICOM value multiplicity in some value representations.
     pub fn multiplicity(&self) -> u32 {
@@ -395,8 +434,10 @@ impl PrimitiveValue {
             Empty => 0,
             Str(_) => 1,
             Strs(c) => c.len() as u32,
+            SharedStrs(c) => c.len() as u32,
             Tags(c) => c.len() as u32,
             U8(c) => c.len() as u32,
+            SharedBytes(c) => c.len() as u32,
             I16(c) => c.len() as u32,
             U16(c) => c.len() as u32,
             I32(c) => c.len() as u32,
@@ -432,6 +473,7 @@ impl PrimitiveValue {
         match self {
             Empty => 0,
             U8(c) => c.len(),
+            SharedBytes(c) => c.len(),
             I16(c) => c.len() * 2,
             U16(c) => c.len() * 2,
             U32(c) => c.len() * 4,
@@ -443,6 +485,7 @@ impl PrimitiveValue {
             Tags(c) => c.len() * 4,
             Str(s) => s.as_bytes().len(),
             Strs(c) => c.iter().map(|s| s.as_bytes().len() + 1).sum::<usize>() & !1,
+            SharedStrs(c) => c.iter().map(|s| s.as_bytes().len() + 1).sum::<usize>() & !1,
             Date(c) => {
                 c.iter()
                     .map(|d| PrimitiveValue::da_byte_len(d) + 1)
@@ -556,7 +599,8 @@ impl PrimitiveValue {
             PrimitiveValue::Str(values) => {
                 Cow::from(values.trim_end_matches(|c| c == ' ' || c == '\u{0}'))
             }
-            PrimitiveValue::Strs(values) => {
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let values = self.text_values();
                 if values.len() == 1 {
                     Cow::from(values[0].trim_end_matches(|c| c == ' ' || c == '\u{0}'))
                 } else {
@@ -623,7 +667,8 @@ impl PrimitiveValue {
         match self {
             PrimitiveValue::Empty => Cow::from(""),
             PrimitiveValue::Str(values) => Cow::from(values.as_str()),
-            PrimitiveValue::Strs(values) => {
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let values = self.text_values();
                 if values.len() == 1 {
                     Cow::from(&values[0])
                 } else {
@@ -634,6 +679,113 @@ impl PrimitiveValue {
         }
     }
 
+    /// Convert the primitive value into a string representation
+    /// of at most `max_len` characters,
+    /// meant for logging and other diagnostic output.
+    ///
+    /// The value is represented in the same way as in [`to_str()`],
+    /// but only the values which fit in `max_len` characters are converted.
+    /// If the representation does not fit,
+    /// it is cut short and followed by an ellipsis (`...`)
+    /// and the total number of values
+    /// (or of bytes for byte values,
+    /// and of characters for single text values).
+    /// This trailing note does not count towards `max_len`.
+    ///
+    /// [`to_str()`]: PrimitiveValue::to_str
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use dicom_core::dicom_value;
+    /// # use dicom_core::value::PrimitiveValue;
+    /// assert_eq!(
+    ///     dicom_value!(Strs, ["ORIGINAL", "PRIMARY"]).to_str_lossy(64),
+    ///     "ORIGINAL\\PRIMARY",
+    /// );
+    /// assert_eq!(
+    ///     PrimitiveValue::from(vec![0_u8; 1_000_000]).to_str_lossy(8),
+    ///     "0\\0\\0\\0\\... (1000000 bytes)",
+    /// );
+    /// assert_eq!(
+    ///     dicom_value!(Str, "Lorem ipsum dolor sit amet").to_str_lossy(11),
+    ///     "Lorem ipsum... (26 characters)",
+    /// );
+    /// ```
+    pub fn to_str_lossy(&self, max_len: usize) -> Cow<str> {
+        if let PrimitiveValue::Empty | PrimitiveValue::Str(_) | PrimitiveValue::Strs(_) = self {
+            let text = self.to_str();
+            if text.chars().count() <= max_len {
+                return text;
+            }
+        }
+
+        let mut out = String::new();
+        let mut len = 0;
+        for (i, item) in self.str_items().enumerate() {
+            if i > 0 {
+                out.push('\\');
+                len += 1;
+            }
+            for c in item.chars() {
+                if len == max_len {
+                    out.push_str("...");
+                    self.write_total(&mut out).unwrap();
+                    return Cow::Owned(out);
+                }
+                out.push(c);
+                len += 1;
+            }
+        }
+        Cow::Owned(out)
+    }
+
+    /// Iterate over the textual representation of each value,
+    /// as in [`to_str()`](PrimitiveValue::to_str).
+    fn str_items(&self) -> Box<dyn Iterator<Item = Cow<str>> + '_> {
+        fn trim(s: &str) -> Cow<str> {
+            Cow::from(s.trim_end_matches(|c| c == ' ' || c == '\u{0}'))
+        }
+        fn display<T: Display>(value: &T) -> Cow<str> {
+            Cow::Owned(value.to_string())
+        }
+
+        match self {
+            PrimitiveValue::Empty => Box::new(core::iter::empty()),
+            PrimitiveValue::Str(value) => Box::new(core::iter::once(trim(value))),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                Box::new(self.text_values().iter().map(|s| trim(s)))
+            }
+            PrimitiveValue::Tags(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::U8(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::SharedBytes(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::I16(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::U16(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::I32(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::U32(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::I64(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::U64(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::F32(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::F64(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::Date(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::DateTime(values) => Box::new(values.iter().map(display)),
+            PrimitiveValue::Time(values) => Box::new(values.iter().map(display)),
+        }
+    }
+
+    /// Write the note on the total size of the value
+    /// which follows a truncated representation,
+    /// such as ` (1024 values)`.
+    fn write_total(&self, f: &mut dyn fmt::Write) -> fmt::Result {
+        match self {
+            PrimitiveValue::Str(value) => write!(f, " ({} characters)", value.chars().count()),
+            PrimitiveValue::U8(_) | PrimitiveValue::SharedBytes(_) => {
+                write!(f, " ({} bytes)", self.multiplicity())
+            }
+            _ => write!(f, " ({} values)", self.multiplicity()),
+        }
+    }
+
     /// Convert the primitive value into a multi-string representation.
     ///
     /// String values already encoded with the `Str` and `Strs` variants
@@ -705,7 +857,7 @@ impl PrimitiveValue {
         match self {
             PrimitiveValue::Empty => Cow::from(&[][..]),
             PrimitiveValue::Str(_) => Cow::Owned(vec![self.to_str().to_string()]),
-            PrimitiveValue::Strs(_) => {
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
                 Cow::Owned(self.to_str().split('\\').map(|s| s.to_string()).collect())
             }
             PrimitiveValue::Date(values) => values
@@ -724,6 +876,7 @@ impl PrimitiveValue {
                 .collect::<Vec<_>>()
                 .into(),
             PrimitiveValue::U8(values) => Cow::Owned(seq_to_str(values)),
+            PrimitiveValue::SharedBytes(values) => Cow::Owned(seq_to_str(values)),
             PrimitiveValue::U16(values) => Cow::Owned(seq_to_str(values)),
             PrimitiveValue::U32(values) => Cow::Owned(seq_to_str(values)),
             PrimitiveValue::I16(values) => Cow::Owned(seq_to_str(values)),
@@ -736,6 +889,52 @@ impl PrimitiveValue {
         }
     }
 
+    /// Obtain an iterator over the individual values
+    /// of this primitive value as strings.
+    ///
+    /// Textual values are split by the backslash (`'\\'`) delimiter,
+    /// even if they were read into a single string,
+    /// and each value is provided without trailing whitespace or null characters,
+    /// without copying.
+    /// All other variants yield one string per value,
+    /// as in [`to_multi_str`](PrimitiveValue::to_multi_str).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dicom_core::dicom_value;
+    /// # use dicom_core::value::PrimitiveValue;
+    /// // Image Type
+    /// let value = PrimitiveValue::from("ORIGINAL\\PRIMARY\\AXIAL ");
+    /// let values: Vec<_> = value.values_str().collect();
+    /// assert_eq!(values, ["ORIGINAL", "PRIMARY", "AXIAL"]);
+    ///
+    /// let value = dicom_value!(U16, [1, 2]);
+    /// assert_eq!(value.values_str().collect::<Vec<_>>(), ["1", "2"]);
+    /// ```
+    pub fn values_str(&self) -> StrValues<'_> {
+        let inner = match self {
+            PrimitiveValue::Empty => StrValuesInner::Text {
+                strings: (&[] as &[String]).iter(),
+                current: None,
+            },
+            PrimitiveValue::Str(s) if trim_value(s).is_empty() => StrValuesInner::Text {
+                strings: (&[] as &[String]).iter(),
+                current: None,
+            },
+            PrimitiveValue::Str(s) => StrValuesInner::Text {
+                strings: core::slice::from_ref(s).iter(),
+                current: None,
+            },
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => StrValuesInner::Text {
+                strings: self.text_values().iter(),
+                current: None,
+            },
+            _ => StrValuesInner::Converted(self.to_multi_str().into_owned().into_iter()),
+        };
+        StrValues { inner }
+    }
+
     /// Convert the primitive value into a clean string representation,
     /// removing unwanted whitespaces.
     ///
@@ -874,6 +1073,7 @@ impl PrimitiveValue {
         match self {
             PrimitiveValue::Empty => Cow::from(&[][..]),
             PrimitiveValue::U8(values) => Cow::from(&values[..]),
+            PrimitiveValue::SharedBytes(values) => Cow::from(&values[..]),
             PrimitiveValue::U16(values) => Cow::Borrowed(transmute_to_bytes(values)),
             PrimitiveValue::I16(values) => Cow::Borrowed(transmute_to_bytes(values)),
             PrimitiveValue::U32(values) => Cow::Borrowed(transmute_to_bytes(values)),
@@ -883,7 +1083,8 @@ impl PrimitiveValue {
             PrimitiveValue::F32(values) => Cow::Borrowed(transmute_to_bytes(values)),
             PrimitiveValue::F64(values) => Cow::Borrowed(transmute_to_bytes(values)),
             PrimitiveValue::Str(values) => Cow::from(values.as_bytes()),
-            PrimitiveValue::Strs(values) => {
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let values = self.text_values();
                 if values.len() == 1 {
                     // no need to copy if it's a single string
                     Cow::from(values[0].as_bytes())
@@ -944,7 +1145,7 @@ impl PrimitiveValue {
     pub fn to_int<T>(&self) -> Result<T, ConvertValueError>
     where
         T: NumCast,
-        T: FromStr<Err = std::num::ParseIntError>,
+        T: FromStr<Err = core::num::ParseIntError>,
     {
         match self {
             PrimitiveValue::Str(s) => {
@@ -957,15 +1158,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) if !s.is_empty() => s[0]
-                .trim_end()
-                .parse()
-                .context(ParseIntegerSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "integer",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_)
+                if !self.text_values().is_empty() =>
+            {
+                let s = self.text_values();
+                s[0].trim_end()
+                    .parse()
+                    .context(ParseIntegerSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "integer",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) if !bytes.is_empty() => {
                 T::from(bytes[0]).ok_or_else(|| ConvertValueError {
                     requested: "integer",
@@ -978,6 +1183,17 @@ impl PrimitiveValue {
                     ),
                 })
             }
+            PrimitiveValue::SharedBytes(bytes) if !bytes.is_empty() => T::from(bytes[0])
+                .ok_or_else(|| ConvertValueError {
+                    requested: "integer",
+                    original: self.value_type(),
+                    cause: Some(
+                        NarrowConvertSnafu {
+                            value: bytes[0].to_string(),
+                        }
+                        .build(),
+                    ),
+                }),
             PrimitiveValue::U16(s) if !s.is_empty() => {
                 T::from(s[0]).ok_or_else(|| ConvertValueError {
                     requested: "integer",
@@ -1106,7 +1322,7 @@ impl PrimitiveValue {
     pub fn to_multi_int<T>(&self) -> Result<Vec<T>, ConvertValueError>
     where
         T: NumCast,
-        T: FromStr<Err = std::num::ParseIntError>,
+        T: FromStr<Err = core::num::ParseIntError>,
     {
         match self {
             PrimitiveValue::Empty => Ok(Vec::new()),
@@ -1122,20 +1338,37 @@ impl PrimitiveValue {
                     })?;
                 Ok(vec![out])
             }
-            PrimitiveValue::Strs(s) => s
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|v| {
+                        v.trim_end()
+                            .parse()
+                            .context(ParseIntegerSnafu)
+                            .map_err(|err| ConvertValueError {
+                                requested: "integer",
+                                original: self.value_type(),
+                                cause: Some(err),
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            }
+            PrimitiveValue::U8(bytes) => bytes
                 .iter()
                 .map(|v| {
-                    v.trim_end()
-                        .parse()
-                        .context(ParseIntegerSnafu)
-                        .map_err(|err| ConvertValueError {
-                            requested: "integer",
-                            original: self.value_type(),
-                            cause: Some(err),
-                        })
+                    T::from(*v).ok_or_else(|| ConvertValueError {
+                        requested: "integer",
+                        original: self.value_type(),
+                        cause: Some(
+                            NarrowConvertSnafu {
+                                value: v.to_string(),
+                            }
+                            .build(),
+                        ),
+                    })
                 })
                 .collect::<Result<Vec<_>, _>>(),
-            PrimitiveValue::U8(bytes) => bytes
+            PrimitiveValue::SharedBytes(bytes) => bytes
                 .iter()
                 .map(|v| {
                     T::from(*v).ok_or_else(|| ConvertValueError {
@@ -1294,15 +1527,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) if !s.is_empty() => s[0]
-                .trim_end()
-                .parse()
-                .context(ParseFloatSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "float32",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_)
+                if !self.text_values().is_empty() =>
+            {
+                let s = self.text_values();
+                s[0].trim_end()
+                    .parse()
+                    .context(ParseFloatSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "float32",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) if !bytes.is_empty() => {
                 NumCast::from(bytes[0]).ok_or_else(|| ConvertValueError {
                     requested: "float32",
@@ -1315,6 +1552,17 @@ impl PrimitiveValue {
                     ),
                 })
             }
+            PrimitiveValue::SharedBytes(bytes) if !bytes.is_empty() => NumCast::from(bytes[0])
+                .ok_or_else(|| ConvertValueError {
+                    requested: "float32",
+                    original: self.value_type(),
+                    cause: Some(
+                        NarrowConvertSnafu {
+                            value: bytes[0].to_string(),
+                        }
+                        .build(),
+                    ),
+                }),
             PrimitiveValue::U16(s) if !s.is_empty() => {
                 NumCast::from(s[0]).ok_or_else(|| ConvertValueError {
                     requested: "float32",
@@ -1458,20 +1706,37 @@ impl PrimitiveValue {
                     })?;
                 Ok(vec![out])
             }
-            PrimitiveValue::Strs(s) => s
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|v| {
+                        v.trim_end()
+                            .parse()
+                            .context(ParseFloatSnafu)
+                            .map_err(|err| ConvertValueError {
+                                requested: "float32",
+                                original: self.value_type(),
+                                cause: Some(err),
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            }
+            PrimitiveValue::U8(bytes) => bytes
                 .iter()
                 .map(|v| {
-                    v.trim_end()
-                        .parse()
-                        .context(ParseFloatSnafu)
-                        .map_err(|err| ConvertValueError {
-                            requested: "float32",
-                            original: self.value_type(),
-                            cause: Some(err),
-                        })
+                    NumCast::from(*v).ok_or_else(|| ConvertValueError {
+                        requested: "float32",
+                        original: self.value_type(),
+                        cause: Some(
+                            NarrowConvertSnafu {
+                                value: v.to_string(),
+                            }
+                            .build(),
+                        ),
+                    })
                 })
                 .collect::<Result<Vec<_>, _>>(),
-            PrimitiveValue::U8(bytes) => bytes
+            PrimitiveValue::SharedBytes(bytes) => bytes
                 .iter()
                 .map(|v| {
                     NumCast::from(*v).ok_or_else(|| ConvertValueError {
@@ -1644,15 +1909,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) if !s.is_empty() => s[0]
-                .trim_end()
-                .parse()
-                .context(ParseFloatSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "float64",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_)
+                if !self.text_values().is_empty() =>
+            {
+                let s = self.text_values();
+                s[0].trim_end()
+                    .parse()
+                    .context(ParseFloatSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "float64",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) if !bytes.is_empty() => {
                 NumCast::from(bytes[0]).ok_or_else(|| ConvertValueError {
                     requested: "float64",
@@ -1665,6 +1934,17 @@ impl PrimitiveValue {
                     ),
                 })
             }
+            PrimitiveValue::SharedBytes(bytes) if !bytes.is_empty() => NumCast::from(bytes[0])
+                .ok_or_else(|| ConvertValueError {
+                    requested: "float64",
+                    original: self.value_type(),
+                    cause: Some(
+                        NarrowConvertSnafu {
+                            value: bytes[0].to_string(),
+                        }
+                        .build(),
+                    ),
+                }),
             PrimitiveValue::U16(s) if !s.is_empty() => {
                 NumCast::from(s[0]).ok_or_else(|| ConvertValueError {
                     requested: "float64",
@@ -1807,20 +2087,37 @@ impl PrimitiveValue {
                     })?;
                 Ok(vec![out])
             }
-            PrimitiveValue::Strs(s) => s
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|v| {
+                        v.trim_end()
+                            .parse()
+                            .context(ParseFloatSnafu)
+                            .map_err(|err| ConvertValueError {
+                                requested: "float64",
+                                original: self.value_type(),
+                                cause: Some(err),
+                            })
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            }
+            PrimitiveValue::U8(bytes) => bytes
                 .iter()
                 .map(|v| {
-                    v.trim_end()
-                        .parse()
-                        .context(ParseFloatSnafu)
-                        .map_err(|err| ConvertValueError {
-                            requested: "float64",
-                            original: self.value_type(),
-                            cause: Some(err),
-                        })
+                    NumCast::from(*v).ok_or_else(|| ConvertValueError {
+                        requested: "float64",
+                        original: self.value_type(),
+                        cause: Some(
+                            NarrowConvertSnafu {
+                                value: v.to_string(),
+                            }
+                            .build(),
+                        ),
+                    })
                 })
                 .collect::<Result<Vec<_>, _>>(),
-            PrimitiveValue::U8(bytes) => bytes
+            PrimitiveValue::SharedBytes(bytes) => bytes
                 .iter()
                 .map(|v| {
                     NumCast::from(*v).ok_or_else(|| ConvertValueError {
@@ -1949,6 +2246,44 @@ impl PrimitiveValue {
         }
     }
 
+    /// Obtain an iterator over the individual values
+    /// of this primitive value converted to 64-bit floating point numbers.
+    ///
+    /// Textual values are split by the backslash (`'\\'`) delimiter,
+    /// and each value is parsed as a number,
+    /// ignoring surrounding whitespace.
+    /// Binary numbers are converted one by one.
+    /// Each value which cannot be converted yields an error,
+    /// and values of other types yield a single error.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dicom_core::dicom_value;
+    /// # use dicom_core::value::PrimitiveValue;
+    /// // Window Center
+    /// let value = PrimitiveValue::from("40\\-600 ");
+    /// let values: Vec<f64> = value.values_f64().collect::<Result<_, _>>()?;
+    /// assert_eq!(values, [40., -600.]);
+    ///
+    /// let value = dicom_value!(F32, [1.5, 2.5]);
+    /// assert_eq!(value.values_f64().nth(1).transpose()?, Some(2.5));
+    /// # Ok::<(), dicom_core::value::ConvertValueError>(())
+    /// ```
+    pub fn values_f64(&self) -> F64Values<'_> {
+        let strings = match self {
+            PrimitiveValue::Str(_) | PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                Some(self.values_str())
+            }
+            _ => None,
+        };
+        F64Values {
+            value: self,
+            strings,
+            index: 0,
+        }
+    }
+
     /// Retrieve a single `chrono::NaiveDate` from this value.
     ///
     /// If the value is already represented as a precise `DicomDate`, it is converted
@@ -2013,7 +2348,8 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => {
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
                 super::deserialize::parse_date(s.first().map(|s| s.as_bytes()).unwrap_or(&[]))
                     .context(ParseDateSnafu)
                     .map_err(|err| ConvertValueError {
@@ -2102,16 +2438,18 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => s
-                .into_iter()
-                .map(|s| super::deserialize::parse_date(s.trim_end().as_bytes()))
-                .collect::<Result<Vec<_>, _>>()
-                .context(ParseDateSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "NaiveDate",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|s| super::deserialize::parse_date(s.trim_end().as_bytes()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ParseDateSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "NaiveDate",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) => trim_last_whitespace(bytes)
                 .split(|c| *c == b'\\')
                 .into_iter()
@@ -2200,16 +2538,19 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => super::deserialize::parse_date_partial(
-                s.first().map(|s| s.as_bytes()).unwrap_or(&[]),
-            )
-            .map(|(date, _)| date)
-            .context(ParseDateSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "DicomDate",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::deserialize::parse_date_partial(
+                    s.first().map(|s| s.as_bytes()).unwrap_or(&[]),
+                )
+                .map(|(date, _)| date)
+                .context(ParseDateSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "DicomDate",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => super::deserialize::parse_date_partial(bytes)
                 .map(|(date, _)| date)
                 .context(ParseDateSnafu)
@@ -2262,19 +2603,21 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => s
-                .into_iter()
-                .map(|s| {
-                    super::deserialize::parse_date_partial(s.trim_end().as_bytes())
-                        .map(|(date, _rest)| date)
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .context(ParseDateSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "DicomDate",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|s| {
+                        super::deserialize::parse_date_partial(s.trim_end().as_bytes())
+                            .map(|(date, _rest)| date)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ParseDateSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "DicomDate",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) => trim_last_whitespace(bytes)
                 .split(|c| *c == b'\\')
                 .into_iter()
@@ -2352,16 +2695,19 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => super::deserialize::parse_time(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-            )
-            .map(|(date, _rest)| date)
-            .context(ParseTimeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "NaiveTime",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::deserialize::parse_time(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                )
+                .map(|(date, _rest)| date)
+                .context(ParseTimeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "NaiveTime",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => {
                 super::deserialize::parse_time(trim_last_whitespace(bytes))
                     .map(|(date, _rest)| date)
@@ -2445,19 +2791,21 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => s
-                .into_iter()
-                .map(|s| {
-                    super::deserialize::parse_time(s.trim_end().as_bytes())
-                        .map(|(date, _rest)| date)
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .context(ParseDateSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "NaiveTime",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|s| {
+                        super::deserialize::parse_time(s.trim_end().as_bytes())
+                            .map(|(date, _rest)| date)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ParseDateSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "NaiveTime",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) => trim_last_whitespace(bytes)
                 .split(|c| *c == b'\\')
                 .into_iter()
@@ -2563,16 +2911,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => super::deserialize::parse_time_partial(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-            )
-            .map(|(date, _rest)| date)
-            .context(ParseTimeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "DicomTime",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::deserialize::parse_time_partial(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                )
+                .map(|(date, _rest)| date)
+                .context(ParseTimeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "DicomTime",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => {
                 super::deserialize::parse_time_partial(trim_last_whitespace(bytes))
                     .map(|(date, _rest)| date)
@@ -2643,19 +2994,21 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => s
-                .into_iter()
-                .map(|s| {
-                    super::deserialize::parse_time_partial(s.trim_end().as_bytes())
-                        .map(|(date, _rest)| date)
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .context(ParseDateSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "DicomTime",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|s| {
+                        super::deserialize::parse_time_partial(s.trim_end().as_bytes())
+                            .map(|(date, _rest)| date)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ParseDateSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "DicomTime",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) => trim_last_whitespace(bytes)
                 .split(|c| *c == b'\\')
                 .into_iter()
@@ -2756,16 +3109,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => super::deserialize::parse_datetime(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-                default_offset,
-            )
-            .context(ParseDateTimeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "DateTime",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::deserialize::parse_datetime(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                    default_offset,
+                )
+                .context(ParseDateTimeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "DateTime",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => {
                 super::deserialize::parse_datetime(trim_last_whitespace(bytes), default_offset)
                     .context(ParseDateTimeSnafu)
@@ -2867,18 +3223,20 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => s
-                .into_iter()
-                .map(|s| {
-                    super::deserialize::parse_datetime(s.trim_end().as_bytes(), default_offset)
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .context(ParseDateSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "DateTime",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|s| {
+                        super::deserialize::parse_datetime(s.trim_end().as_bytes(), default_offset)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ParseDateSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "DateTime",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) => trim_last_whitespace(bytes)
                 .split(|c| *c == b'\\')
                 .into_iter()
@@ -2983,16 +3341,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => super::deserialize::parse_datetime_partial(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-                default_offset,
-            )
-            .context(ParseDateTimeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "DicomDateTime",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::deserialize::parse_datetime_partial(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                    default_offset,
+                )
+                .context(ParseDateTimeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "DicomDateTime",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => super::deserialize::parse_datetime_partial(
                 trim_last_whitespace(bytes),
                 default_offset,
@@ -3029,21 +3390,23 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => s
-                .into_iter()
-                .map(|s| {
-                    super::deserialize::parse_datetime_partial(
-                        s.trim_end().as_bytes(),
-                        default_offset,
-                    )
-                })
-                .collect::<Result<Vec<_>, _>>()
-                .context(ParseDateSnafu)
-                .map_err(|err| ConvertValueError {
-                    requested: "DicomDateTime",
-                    original: self.value_type(),
-                    cause: Some(err),
-                }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.iter()
+                    .map(|s| {
+                        super::deserialize::parse_datetime_partial(
+                            s.trim_end().as_bytes(),
+                            default_offset,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .context(ParseDateSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "DicomDateTime",
+                        original: self.value_type(),
+                        cause: Some(err),
+                    })
+            }
             PrimitiveValue::U8(bytes) => trim_last_whitespace(bytes)
                 .split(|c| *c == b'\\')
                 .into_iter()
@@ -3108,15 +3471,18 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => super::range::parse_date_range(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-            )
-            .context(ParseDateRangeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "DateRange",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::range::parse_date_range(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                )
+                .context(ParseDateRangeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "DateRange",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => {
                 super::range::parse_date_range(trim_last_whitespace(bytes))
                     .context(ParseDateRangeSnafu)
@@ -3183,15 +3549,18 @@ impl PrimitiveValue {
                     original: self.value_type(),
                     cause: Some(err),
                 }),
-            PrimitiveValue::Strs(s) => super::range::parse_time_range(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-            )
-            .context(ParseTimeRangeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "TimeRange",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::range::parse_time_range(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                )
+                .context(ParseTimeRangeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "TimeRange",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => {
                 super::range::parse_time_range(trim_last_whitespace(bytes))
                     .context(ParseTimeRangeSnafu)
@@ -3269,16 +3638,19 @@ impl PrimitiveValue {
                         cause: Some(err),
                     })
             }
-            PrimitiveValue::Strs(s) => super::range::parse_datetime_range(
-                s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
-                offset,
-            )
-            .context(ParseDateTimeRangeSnafu)
-            .map_err(|err| ConvertValueError {
-                requested: "DateTimeRange",
-                original: self.value_type(),
-                cause: Some(err),
-            }),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                super::range::parse_datetime_range(
+                    s.first().map(|s| s.trim_end().as_bytes()).unwrap_or(&[]),
+                    offset,
+                )
+                .context(ParseDateTimeRangeSnafu)
+                .map_err(|err| ConvertValueError {
+                    requested: "DateTimeRange",
+                    original: self.value_type(),
+                    cause: Some(err),
+                })
+            }
             PrimitiveValue::U8(bytes) => {
                 super::range::parse_datetime_range(trim_last_whitespace(bytes), offset)
                     .context(ParseDateTimeRangeSnafu)
@@ -3329,16 +3701,19 @@ impl PrimitiveValue {
     pub fn to_person_name(&self) -> Result<PersonName<'_>, ConvertValueError> {
         match self {
             PrimitiveValue::Str(s) => Ok(PersonName::from_str(s)),
-            PrimitiveValue::Strs(s) => s.first().map_or_else(
-                || {
-                    Err(ConvertValueError {
-                        requested: "PersonName",
-                        original: self.value_type(),
-                        cause: None,
-                    })
-                },
-                |s| Ok(PersonName::from_str(s)),
-            ),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let s = self.text_values();
+                s.first().map_or_else(
+                    || {
+                        Err(ConvertValueError {
+                            requested: "PersonName",
+                            original: self.value_type(),
+                            cause: None,
+                        })
+                    },
+                    |s| Ok(PersonName::from_str(s)),
+                )
+            }
             _ => Err(ConvertValueError {
                 requested: "PersonName",
                 original: self.value_type(),
@@ -3404,11 +3779,15 @@ impl PrimitiveValue {
     pub fn string(&self) -> Result<&str, CastValueError> {
         use self::PrimitiveValue::*;
         match self {
-            Strs(c) if c.is_empty() => Err(CastValueError {
-                requested: "Str",
-                got: ValueType::Empty,
-            }),
-            Strs(c) if !c.is_empty() => Ok(&c[0]),
+            Strs(_) | SharedStrs(_) => {
+                self.text_values()
+                    .first()
+                    .map(String::as_str)
+                    .ok_or(CastValueError {
+                        requested: "Str",
+                        got: ValueType::Empty,
+                    })
+            }
             Str(s) => Ok(s),
             value => Err(CastValueError {
                 requested: "Str",
@@ -3418,7 +3797,7 @@ impl PrimitiveValue {
     }
 
     /// Get the inner sequence of string values
-    /// if the variant is either `Str` or `Strs`.
+    /// if the variant is either `Str`, `Strs`, or `SharedStrs`.
     ///
     /// An error is returned if the variant is not compatible.
     ///
@@ -3429,8 +3808,8 @@ impl PrimitiveValue {
     pub fn strings(&self) -> Result<&[String], CastValueError> {
         use self::PrimitiveValue::*;
         match self {
-            Strs(c) => Ok(c),
-            Str(s) => Ok(std::slice::from_ref(s)),
+            Strs(_) | SharedStrs(_) => Ok(self.text_values()),
+            Str(s) => Ok(core::slice::from_ref(s)),
             value => Err(CastValueError {
                 requested: "strings",
                 got: value.value_type(),
@@ -3453,9 +3832,27 @@ impl PrimitiveValue {
     impl_primitive_getters!(float64, float64_slice, F64, f64);
 }
 
-/// The output of this method is equivalent to calling the method `to_str`
+/// The output of this method is equivalent to calling the method `to_str`.
+///
+/// A precision limits the output to that number of values,
+/// or to that number of characters for a single text value,
+/// followed by an ellipsis and the total number of values
+/// if any were left out.
+///
+/// ```
+/// # use dicom_core::dicom_value;
+/// # use dicom_core::value::PrimitiveValue;
+/// let value = dicom_value!(U16, [1, 2, 3, 4, 5]);
+/// assert_eq!(format!("{}", value), "1\\2\\3\\4\\5");
+/// assert_eq!(format!("{:.3}", value), "1\\2\\3\\... (5 values)");
+/// assert_eq!(format!("{:.8}", value), "1\\2\\3\\4\\5");
+/// ```
 impl Display for PrimitiveValue {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(max) = f.precision() {
+            return self.fmt_truncated(f, max);
+        }
+
         /// Auxillary function for turning a sequence of values
         /// into a backslash-delimited string.
         fn seq_to_str<I>(iter: I) -> String
@@ -3469,7 +3866,7 @@ impl Display for PrimitiveValue {
         match self {
             PrimitiveValue::Empty => Ok(()),
             PrimitiveValue::Str(_) => f.write_str(&self.to_str()),
-            PrimitiveValue::Strs(_) => f.write_str(&self.to_str()),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => f.write_str(&self.to_str()),
             PrimitiveValue::Date(values) => {
                 f.write_str(&values.into_iter().map(|date| date.to_string()).join("\\"))
             }
@@ -3483,6 +3880,7 @@ impl Display for PrimitiveValue {
                     .join("\\"),
             ),
             PrimitiveValue::U8(values) => f.write_str(&seq_to_str(values)),
+            PrimitiveValue::SharedBytes(values) => f.write_str(&seq_to_str(values)),
             PrimitiveValue::U16(values) => f.write_str(&seq_to_str(values)),
             PrimitiveValue::U32(values) => f.write_str(&seq_to_str(values)),
             PrimitiveValue::I16(values) => f.write_str(&seq_to_str(values)),
@@ -3496,6 +3894,113 @@ impl Display for PrimitiveValue {
     }
 }
 
+impl PrimitiveValue {
+    /// Display at most `max` values,
+    /// or `max` characters of a single text value.
+    fn fmt_truncated(&self, f: &mut fmt::Formatter, max: usize) -> fmt::Result {
+        if let PrimitiveValue::Str(value) = self {
+            let value = value.trim_end_matches(|c| c == ' ' || c == '\u{0}');
+            match value.char_indices().nth(max) {
+                Some((end, _)) => {
+                    f.write_str(&value[..end])?;
+                    f.write_str("...")?;
+                    return self.write_total(f);
+                }
+                None => return f.write_str(value),
+            }
+        }
+
+        for (i, item) in self.str_items().enumerate() {
+            if i > 0 {
+                f.write_str("\\")?;
+            }
+            if i == max {
+                f.write_str("...")?;
+                return self.write_total(f);
+            }
+            f.write_str(&item)?;
+        }
+        Ok(())
+    }
+}
+
+/// The maximum number of values shown
+/// when debug formatting a primitive value.
+const DEBUG_MAX_VALUES: usize = 64;
+
+/// The maximum number of characters shown
+/// when debug formatting a single text value.
+const DEBUG_MAX_CHARACTERS: usize = 1024;
+
+/// Values with more than 64 elements
+/// (or text values with more than 1024 characters)
+/// are cut short,
+/// with a note on the number of values left out.
+impl fmt::Debug for PrimitiveValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        /// A list of values which shows up to `DEBUG_MAX_VALUES` elements.
+        struct Values<'a, T>(&'a [T]);
+
+        impl<T: fmt::Debug> fmt::Debug for Values<'_, T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                let mut list = f.debug_list();
+                list.entries(self.0.iter().take(DEBUG_MAX_VALUES));
+                if self.0.len() > DEBUG_MAX_VALUES {
+                    list.entry(&format_args!(
+                        "... ({} more)",
+                        self.0.len() - DEBUG_MAX_VALUES
+                    ));
+                }
+                list.finish()
+            }
+        }
+
+        /// A text value which shows up to `DEBUG_MAX_CHARACTERS` characters.
+        struct Text<'a>(&'a str);
+
+        impl fmt::Debug for Text<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match self.0.char_indices().nth(DEBUG_MAX_CHARACTERS) {
+                    Some((end, _)) => write!(
+                        f,
+                        "{:?}... ({} more characters)",
+                        &self.0[..end],
+                        self.0[end..].chars().count()
+                    ),
+                    None => write!(f, "{:?}", self.0),
+                }
+            }
+        }
+
+        match self {
+            PrimitiveValue::Empty => f.write_str("Empty"),
+            PrimitiveValue::Strs(values) => f.debug_tuple("Strs").field(&Values(values)).finish(),
+            PrimitiveValue::SharedStrs(values) => {
+                f.debug_tuple("SharedStrs").field(&Values(values)).finish()
+            }
+            PrimitiveValue::Str(value) => f.debug_tuple("Str").field(&Text(value)).finish(),
+            PrimitiveValue::Tags(values) => f.debug_tuple("Tags").field(&Values(values)).finish(),
+            PrimitiveValue::U8(values) => f.debug_tuple("U8").field(&Values(values)).finish(),
+            PrimitiveValue::SharedBytes(values) => {
+                f.debug_tuple("SharedBytes").field(&Values(values)).finish()
+            }
+            PrimitiveValue::I16(values) => f.debug_tuple("I16").field(&Values(values)).finish(),
+            PrimitiveValue::U16(values) => f.debug_tuple("U16").field(&Values(values)).finish(),
+            PrimitiveValue::I32(values) => f.debug_tuple("I32").field(&Values(values)).finish(),
+            PrimitiveValue::U32(values) => f.debug_tuple("U32").field(&Values(values)).finish(),
+            PrimitiveValue::I64(values) => f.debug_tuple("I64").field(&Values(values)).finish(),
+            PrimitiveValue::U64(values) => f.debug_tuple("U64").field(&Values(values)).finish(),
+            PrimitiveValue::F32(values) => f.debug_tuple("F32").field(&Values(values)).finish(),
+            PrimitiveValue::F64(values) => f.debug_tuple("F64").field(&Values(values)).finish(),
+            PrimitiveValue::Date(values) => f.debug_tuple("Date").field(&Values(values)).finish(),
+            PrimitiveValue::DateTime(values) => {
+                f.debug_tuple("DateTime").field(&Values(values)).finish()
+            }
+            PrimitiveValue::Time(values) => f.debug_tuple("Time").field(&Values(values)).finish(),
+        }
+    }
+}
+
 impl HasLength for PrimitiveValue {
     fn length(&self) -> Length {
         Length::defined(self.calculate_byte_len() as u32)
@@ -3514,8 +4019,22 @@ impl PartialEq for PrimitiveValue {
             }
             (PrimitiveValue::Strs(_), PrimitiveValue::Strs(_)) => self.to_str() == other.to_str(),
             (PrimitiveValue::Str(_), PrimitiveValue::Str(_)) => self.to_str() == other.to_str(),
+            (PrimitiveValue::SharedStrs(_), PrimitiveValue::SharedStrs(_))
+            | (PrimitiveValue::SharedStrs(_), PrimitiveValue::Strs(_))
+            | (PrimitiveValue::Strs(_), PrimitiveValue::SharedStrs(_)) => {
+                self.to_str() == other.to_str()
+            }
+            (PrimitiveValue::SharedStrs(v1), PrimitiveValue::Str(_)) => {
+                v1.len() == 1 && self.to_str() == other.to_str()
+            }
+            (PrimitiveValue::Str(_), PrimitiveValue::SharedStrs(v2)) => {
+                v2.len() == 1 && self.to_str() == other.to_str()
+            }
             (PrimitiveValue::Tags(v1), PrimitiveValue::Tags(v2)) => v1 == v2,
             (PrimitiveValue::U8(v1), PrimitiveValue::U8(v2)) => v1 == v2,
+            (PrimitiveValue::SharedBytes(v1), PrimitiveValue::SharedBytes(v2)) => v1 == v2,
+            (PrimitiveValue::U8(v1), PrimitiveValue::SharedBytes(v2)) => v1[..] == v2[..],
+            (PrimitiveValue::SharedBytes(v1), PrimitiveValue::U8(v2)) => v1[..] == v2[..],
             (PrimitiveValue::I16(v1), PrimitiveValue::I16(v2)) => v1 == v2,
             (PrimitiveValue::U16(v1), PrimitiveValue::U16(v2)) => v1 == v2,
             (PrimitiveValue::I32(v1), PrimitiveValue::I32(v2)) => v1 == v2,
@@ -3535,7 +4054,10 @@ impl PartialEq for PrimitiveValue {
 impl PartialEq<str> for PrimitiveValue {
     fn eq(&self, other: &str) -> bool {
         match self {
-            PrimitiveValue::Strs(v) => v.len() == 1 && v[0] == other,
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+                let v = self.text_values();
+                v.len() == 1 && v[0] == other
+            }
             PrimitiveValue::Str(v) => v == other,
             _ => false,
         }
@@ -3637,13 +4159,14 @@ impl DicomValueType for PrimitiveValue {
             PrimitiveValue::I32(_) => ValueType::I32,
             PrimitiveValue::I64(_) => ValueType::I64,
             PrimitiveValue::Str(_) => ValueType::Str,
-            PrimitiveValue::Strs(_) => ValueType::Strs,
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => ValueType::Strs,
             PrimitiveValue::Tags(_) => ValueType::Tags,
             PrimitiveValue::Time(_) => ValueType::Time,
             PrimitiveValue::U16(_) => ValueType::U16,
             PrimitiveValue::U32(_) => ValueType::U32,
             PrimitiveValue::U64(_) => ValueType::U64,
             PrimitiveValue::U8(_) => ValueType::U8,
+            PrimitiveValue::SharedBytes(_) => ValueType::U8,
         }
     }
 
@@ -3658,17 +4181,126 @@ impl DicomValueType for PrimitiveValue {
             PrimitiveValue::I16(b) => b.len(),
             PrimitiveValue::I32(b) => b.len(),
             PrimitiveValue::I64(b) => b.len(),
-            PrimitiveValue::Strs(b) => b.len(),
+            PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => self.text_values().len(),
             PrimitiveValue::Tags(b) => b.len(),
             PrimitiveValue::Time(b) => b.len(),
             PrimitiveValue::U16(b) => b.len(),
             PrimitiveValue::U32(b) => b.len(),
             PrimitiveValue::U64(b) => b.len(),
             PrimitiveValue::U8(b) => b.len(),
+            PrimitiveValue::SharedBytes(b) => b.len(),
         }
     }
 }
 
+/// An iterator over the individual values of a primitive value as strings.
+///
+/// See [`PrimitiveValue::values_str`].
+#[derive(Debug, Clone)]
+pub struct StrValues<'a> {
+    inner: StrValuesInner<'a>,
+}
+
+#[derive(Debug, Clone)]
+enum StrValuesInner<'a> {
+    /// values from textual variants, split on demand
+    Text {
+        strings: core::slice::Iter<'a, String>,
+        current: Option<core::str::Split<'a, char>>,
+    },
+    /// values converted from other variants
+    Converted(alloc::vec::IntoIter<String>),
+}
+
+impl<'a> Iterator for StrValues<'a> {
+    type Item = Cow<'a, str>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.inner {
+            StrValuesInner::Text { strings, current } => loop {
+                if let Some(value) = current.as_mut().and_then(|split| split.next()) {
+                    return Some(Cow::Borrowed(trim_value(value)));
+                }
+                *current = Some(strings.next()?.split('\\'));
+            },
+            StrValuesInner::Converted(values) => values.next().map(Cow::Owned),
+        }
+    }
+}
+
+/// An iterator over the individual values of a primitive value
+/// converted to 64-bit floating point numbers.
+///
+/// See [`PrimitiveValue::values_f64`].
+#[derive(Debug, Clone)]
+pub struct F64Values<'a> {
+    value: &'a PrimitiveValue,
+    /// the textual values, if the value is textual
+    strings: Option<StrValues<'a>>,
+    /// the index of the next binary value
+    index: usize,
+}
+
+impl Iterator for F64Values<'_> {
+    type Item = Result<f64, ConvertValueError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let original = self.value.value_type();
+        if let Some(strings) = &mut self.strings {
+            let value = strings.next()?;
+            return Some(
+                value
+                    .trim()
+                    .parse()
+                    .context(ParseFloatSnafu)
+                    .map_err(|err| ConvertValueError {
+                        requested: "float64",
+                        original,
+                        cause: Some(err),
+                    }),
+            );
+        }
+
+        let i = self.index;
+        self.index += 1;
+        let value: Option<f64> = match self.value {
+            PrimitiveValue::Empty
+            | PrimitiveValue::Str(_)
+            | PrimitiveValue::Strs(_)
+            | PrimitiveValue::SharedStrs(_) => None,
+            PrimitiveValue::U8(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::SharedBytes(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::U16(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::I16(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::U32(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::I32(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::U64(values) => values.get(i).map(|v| *v as f64),
+            PrimitiveValue::I64(values) => values.get(i).map(|v| *v as f64),
+            PrimitiveValue::F32(values) => values.get(i).map(|&v| v.into()),
+            PrimitiveValue::F64(values) => values.get(i).copied(),
+            PrimitiveValue::Date(_)
+            | PrimitiveValue::Time(_)
+            | PrimitiveValue::DateTime(_)
+            | PrimitiveValue::Tags(_) => {
+                if i == 0 {
+                    return Some(Err(ConvertValueError {
+                        requested: "float64",
+                        original,
+                        cause: None,
+                    }));
+                }
+                None
+            }
+        };
+        value.map(Ok)
+    }
+}
+
+/// Remove the trailing whitespace and null characters of a textual value.
+fn trim_value(value: &str) -> &str {
+    value.trim_end_matches(|c: char| c == ' ' || c == '\0')
+}
+
 fn trim_last_whitespace(x: &[u8]) -> &[u8] {
     match x.last() {
         Some(b' ') | Some(b'\0') => &x[..x.len() - 1],
@@ -3695,7 +4327,7 @@ mod tests {
         let string = value.to_str();
         assert_eq!(string, "Smith^John",);
         match string {
-            std::borrow::Cow::Borrowed(_) => {} // good
+            alloc::borrow::Cow::Borrowed(_) => {} // good
             _ => panic!("expected string to be borrowed, but was owned"),
         }
 
@@ -3751,6 +4383,123 @@ mod tests {
         assert_eq!(&value.to_raw_str(), " ONE\\TWO\\THREE\\ SIX ");
     }
 
+    #[test]
+    fn primitive_value_to_str_lossy() {
+        // fits entirely
+        let value = dicom_value!(Strs, ["ONE", "TWO "]);
+        assert_eq!(&value.to_str_lossy(7), "ONE\\TWO");
+        assert_eq!(&PrimitiveValue::Empty.to_str_lossy(0), "");
+
+        // cut in the middle of a value
+        assert_eq!(&value.to_str_lossy(5), "ONE\\T... (2 values)");
+
+        let value = dicom_value!(U16, [10, 20, 30]);
+        assert_eq!(&value.to_str_lossy(8), "10\\20\\30");
+        assert_eq!(&value.to_str_lossy(3), "10\\... (3 values)");
+
+        let value = PrimitiveValue::SharedBytes(vec![1, 2, 3].into());
+        assert_eq!(&value.to_str_lossy(2), "1\\... (3 bytes)");
+
+        let value = PrimitiveValue::from("Açaí com granola");
+        assert_eq!(&value.to_str_lossy(4), "Açaí... (16 characters)");
+    }
+
+    #[test]
+    fn primitive_value_display_truncated() {
+        let value = PrimitiveValue::from(vec![0_u8; 1000]);
+        assert_eq!(format!("{:.2}", value), "0\\0\\... (1000 bytes)");
+        assert_eq!(format!("{:.0}", value), "... (1000 bytes)");
+
+        let value = dicom_value!(Strs, ["ONE", "TWO "]);
+        assert_eq!(format!("{:.2}", value), "ONE\\TWO");
+        assert_eq!(format!("{:.1}", value), "ONE\\... (2 values)");
+
+        let value = PrimitiveValue::from("Lorem ipsum ");
+        assert_eq!(format!("{:.5}", value), "Lorem... (12 characters)");
+        assert_eq!(format!("{:.11}", value), "Lorem ipsum");
+    }
+
+    #[test]
+    fn primitive_value_debug_truncated() {
+        let value = dicom_value!(U16, [1, 2]);
+        assert_eq!(format!("{:?}", value), "U16([1, 2])");
+        assert_eq!(format!("{:?}", PrimitiveValue::Empty), "Empty");
+        assert_eq!(
+            format!("{:?}", PrimitiveValue::from("Doe^John")),
+            "Str(\"Doe^John\")"
+        );
+
+        let value = PrimitiveValue::from(vec![0_u8; 100]);
+        let debug = format!("{:?}", value);
+        assert!(debug.starts_with("U8([0, 0, "));
+        assert!(debug.ends_with(", 0, ... (36 more)])"));
+    }
+
+    #[test]
+    fn primitive_value_small_multiplicity_is_inline() {
+        // values with a multiplicity of up to 2 do not allocate
+        match dicom_value!(F64, [0.5, 0.5]) {
+            PrimitiveValue::F64(values) => assert!(!values.spilled()),
+            value => panic!("unexpected value {:?}", value),
+        }
+        match dicom_value!(Strs, ["ORIGINAL", "PRIMARY"]) {
+            PrimitiveValue::Strs(values) => assert!(!values.spilled()),
+            value => panic!("unexpected value {:?}", value),
+        }
+        match PrimitiveValue::from(0x0010_u16) {
+            PrimitiveValue::U16(values) => assert!(!values.spilled()),
+            value => panic!("unexpected value {:?}", value),
+        }
+        // collecting from an iterator also stays inline
+        let values: crate::value::C<u32> = (0..2).collect();
+        assert!(!values.spilled());
+
+        // larger values move to the heap
+        match dicom_value!(F64, [1., 0., 0., 0., 1., 0.]) {
+            PrimitiveValue::F64(values) => assert!(values.spilled()),
+            value => panic!("unexpected value {:?}", value),
+        }
+    }
+
+    #[test]
+    fn primitive_value_shared_strs() {
+        use crate::value::DicomValueType;
+        use alloc::sync::Arc;
+
+        let value = PrimitiveValue::SharedStrs(Arc::from(vec![
+            "DERIVED".to_string(),
+            "PRIMARY".to_string(),
+            "AXIAL ".to_string(),
+        ]));
+        assert_eq!(value.value_type(), ValueType::Strs);
+        assert_eq!(value.multiplicity(), 3);
+        assert_eq!(value.calculate_byte_len(), 22);
+        assert_eq!(value.to_raw_str(), "DERIVED\\PRIMARY\\AXIAL ");
+        assert_eq!(value.to_str(), "DERIVED\\PRIMARY\\AXIAL");
+        assert_eq!(
+            value.values_str().collect::<Vec<_>>(),
+            vec!["DERIVED", "PRIMARY", "AXIAL"],
+        );
+        assert_eq!(value.string().unwrap(), "DERIVED");
+        assert_eq!(value.strings().unwrap(), ["DERIVED", "PRIMARY", "AXIAL "]);
+
+        // compares equal to the same strings in other variants
+        assert_eq!(value, dicom_value!(Strs, ["DERIVED", "PRIMARY", "AXIAL "]));
+        assert_eq!(dicom_value!(Strs, ["DERIVED", "PRIMARY", "AXIAL "]), value);
+        let single = PrimitiveValue::SharedStrs(Arc::from(vec!["PRIMARY".to_string()]));
+        assert_eq!(single, PrimitiveValue::from("PRIMARY"));
+        assert_eq!(single, "PRIMARY");
+        assert_ne!(single, value);
+
+        let numbers =
+            PrimitiveValue::SharedStrs(Arc::from(vec!["40".to_string(), "-600 ".to_string()]));
+        assert_eq!(numbers.to_multi_int::<i32>().unwrap(), vec![40, -600]);
+        assert_eq!(
+            numbers.values_f64().collect::<Result<Vec<_>, _>>().unwrap(),
+            vec![40., -600.],
+        );
+    }
+
     #[test]
     fn primitive_value_to_bytes() {
         assert_eq!(PrimitiveValue::Empty.to_bytes(), &[][..]);
@@ -3772,7 +4521,7 @@ mod tests {
         let bytes = value.to_bytes();
         assert_eq!(bytes, &b"Smith^John"[..],);
         match bytes {
-            std::borrow::Cow::Borrowed(_) => {} // good
+            alloc::borrow::Cow::Borrowed(_) => {} // good
             _ => panic!("expected bytes to be borrowed, but are owned"),
         }
 
@@ -3790,7 +4539,7 @@ mod tests {
         let bytes = value.to_bytes();
         assert_eq!(bytes, &[0x99; 16][..],);
         match bytes {
-            std::borrow::Cow::Borrowed(_) => {} // good
+            alloc::borrow::Cow::Borrowed(_) => {} // good
             _ => panic!("expected bytes to be borrowed, but are owned"),
         }
     }
@@ -3941,6 +4690,65 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn primitive_value_values_iterators() {
+        assert_eq!(PrimitiveValue::Empty.values_str().count(), 0);
+        assert_eq!(dicom_value!(Str, "  ").values_str().count(), 0);
+
+        // backslash splitting, even in a single string
+        let image_type = dicom_value!(Str, "DERIVED\\SECONDARY\\MPR\0");
+        assert_eq!(
+            image_type.values_str().collect::<Vec<_>>(),
+            vec!["DERIVED", "SECONDARY", "MPR"],
+        );
+        assert_eq!(
+            dicom_value!(Strs, ["ORIGINAL", "PRIMARY\\AXIAL "])
+                .values_str()
+                .collect::<Vec<_>>(),
+            vec!["ORIGINAL", "PRIMARY", "AXIAL"],
+        );
+        assert_eq!(
+            dicom_value!(I32, [-1, 2]).values_str().collect::<Vec<_>>(),
+            vec!["-1", "2"],
+        );
+
+        let window_center = dicom_value!(Strs, [" 40", "-600\\350 "]);
+        assert_eq!(
+            window_center
+                .values_f64()
+                .collect::<Result<Vec<_>, _>>()
+                .ok(),
+            Some(vec![40., -600., 350.]),
+        );
+        assert_eq!(
+            dicom_value!(U16, [1, 2, 3])
+                .values_f64()
+                .collect::<Result<Vec<_>, _>>()
+                .ok(),
+            Some(vec![1., 2., 3.]),
+        );
+
+        // errors are reported per value
+        let value = dicom_value!(Strs, ["1.5", "Smith^John", "2"]);
+        let mut values = value.values_f64();
+        assert_eq!(values.next().unwrap().ok(), Some(1.5));
+        assert!(matches!(
+            values.next(),
+            Some(Err(ConvertValueError {
+                cause: Some(InvalidValueReadError::ParseFloat { .. }),
+                ..
+            }))
+        ));
+        assert_eq!(values.next().unwrap().ok(), Some(2.));
+        assert!(values.next().is_none());
+
+        // dates cannot be converted
+        let value = PrimitiveValue::from(DicomDate::from_ymd(2014, 10, 12).unwrap());
+        let mut values = value.values_f64();
+        assert!(matches!(values.next(), Some(Err(_))));
+        assert!(values.next().is_none());
+    }
+
     #[test]
     fn primitive_value_to_naive_date() {
         // to NaiveDate
@@ -4,6 +4,8 @@
 use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
 use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
 
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
 use crate::value::deserialize::{
     parse_date_partial, parse_datetime_partial, parse_time_partial, Error as DeserializeError,
 };
@@ -40,7 +42,7 @@ pub enum Error {
     #[snafu(display("Time is invalid"))]
     InvalidTime { backtrace: Backtrace },
 }
-type Result<T, E = Error> = std::result::Result<T, E>;
+type Result<T, E = Error> = core::result::Result<T, E>;
 
 /// The DICOM protocol accepts date / time values with null components.
 /// Imprecise values are to be handled as date / time ranges.
@@ -0,0 +1,549 @@
+//! Validation of primitive values against their value representation.
+//!
+//! The checks in this module follow the definitions of
+//! PS3.5 section 6.2:
+//! the value type must be compatible with the value representation,
+//! textual values must fit in the maximum length
+//! and only contain the characters allowed,
+//! dates and times must be properly formatted,
+//! and binary values must have a byte length
+//! which is a multiple of the size of each value.
+//!
+//! See [`check_value`] for the entry point,
+//! and [`DataElement::new_checked`](crate::DataElement::new_checked)
+//! to validate an element on construction.
+use crate::header::VR;
+#[cfg(not(feature = "std"))]
+use crate::prelude::*;
+use crate::value::deserialize::{
+    parse_date_partial, parse_datetime_partial, parse_time_partial, Error as DeserializeError,
+};
+use crate::value::partial::{DateComponent, Precision};
+use crate::value::{DicomValueType, PrimitiveValue, ValueType};
+use alloc::borrow::Cow;
+use chrono::FixedOffset;
+use snafu::{Backtrace, ResultExt, Snafu};
+
+/// How strictly values are validated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum ValidationMode {
+    /// All requirements of the standard are enforced.
+    Strict,
+    /// Deviations which are commonly found in existing data
+    /// and which do not prevent the value from being interpreted
+    /// are tolerated:
+    ///
+    /// - maximum lengths are not enforced;
+    /// - leading and trailing spaces are ignored in all textual values;
+    /// - lower case letters are allowed in code strings (CS);
+    /// - dates (DA) may be incomplete,
+    ///   or separated by periods as in ACR-NEMA (`YYYY.MM.DD`);
+    /// - times (TM) may be separated by colons as in ACR-NEMA (`HH:MM:SS`);
+    /// - the byte length of binary values is checked
+    ///   after padding to an even length.
+    Lenient,
+}
+
+impl Default for ValidationMode {
+    fn default() -> Self {
+        ValidationMode::Strict
+    }
+}
+
+/// An error describing why a value is not valid for a value representation.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// The type of the value cannot be encoded in the value representation.
+    #[snafu(display("Value of type {:?} is not compatible with VR {}", value_type, vr))]
+    IncompatibleType {
+        vr: VR,
+        value_type: ValueType,
+        backtrace: Backtrace,
+    },
+    /// The value representation admits a single value.
+    #[snafu(display("VR {} admits a single value, but got {}", vr, count))]
+    MultipleValues {
+        vr: VR,
+        count: usize,
+        backtrace: Backtrace,
+    },
+    /// A value is longer than allowed by the value representation.
+    #[snafu(display(
+        "Value #{} has {} characters, exceeding the maximum of {} for VR {}",
+        index,
+        len,
+        max,
+        vr
+    ))]
+    ValueTooLong {
+        vr: VR,
+        index: usize,
+        len: usize,
+        max: usize,
+        backtrace: Backtrace,
+    },
+    /// A value contains a character not allowed by the value representation.
+    #[snafu(display(
+        "Value #{} contains invalid character {:?} for VR {}",
+        index,
+        character,
+        vr
+    ))]
+    InvalidCharacter {
+        vr: VR,
+        index: usize,
+        character: char,
+        backtrace: Backtrace,
+    },
+    /// A value does not follow the format of the value representation.
+    #[snafu(display("Value #{} `{}` is not a valid {}", index, value, vr))]
+    InvalidFormat {
+        vr: VR,
+        index: usize,
+        value: String,
+        backtrace: Backtrace,
+    },
+    /// A date or time value could not be parsed.
+    #[snafu(display("Value #{} `{}` is not a valid {}", index, value, vr))]
+    InvalidDateTime {
+        vr: VR,
+        index: usize,
+        value: String,
+        #[snafu(backtrace)]
+        source: DeserializeError,
+    },
+    /// The byte length of a binary value is not a multiple of
+    /// the size of each value.
+    #[snafu(display(
+        "Byte length {} is not a multiple of {} as required by VR {}",
+        len,
+        unit,
+        vr
+    ))]
+    InvalidByteLength {
+        vr: VR,
+        len: usize,
+        unit: usize,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = core::result::Result<T, E>;
+
+/// Check whether a primitive value is valid
+/// for the given value representation.
+///
+/// Empty values are always valid.
+/// Values of non-standard value representations are not checked.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_core::{PrimitiveValue, VR};
+/// use dicom_core::value::validate::{check_value, ValidationMode};
+///
+/// let value = PrimitiveValue::from("ORIGINAL\\PRIMARY");
+/// assert!(check_value(VR::CS, &value, ValidationMode::Strict).is_ok());
+///
+/// let value = PrimitiveValue::from("original");
+/// assert!(check_value(VR::CS, &value, ValidationMode::Strict).is_err());
+/// assert!(check_value(VR::CS, &value, ValidationMode::Lenient).is_ok());
+/// ```
+pub fn check_value(vr: VR, value: &PrimitiveValue, mode: ValidationMode) -> Result<()> {
+    use PrimitiveValue::*;
+    match value {
+        Empty => Ok(()),
+        Str(_) | Strs(_) | SharedStrs(_) if is_textual(vr) => check_text(vr, value, mode),
+        U8(bytes) => check_bytes(vr, bytes.len(), mode),
+        SharedBytes(bytes) => check_bytes(vr, bytes.len(), mode),
+        Date(_) if vr == VR::DA => Ok(()),
+        Time(_) if vr == VR::TM => Ok(()),
+        DateTime(_) if vr == VR::DT => Ok(()),
+        _ => {
+            if is_compatible_type(vr, value.value_type()) {
+                Ok(())
+            } else {
+                IncompatibleTypeSnafu {
+                    vr,
+                    value_type: value.value_type(),
+                }
+                .fail()
+            }
+        }
+    }
+}
+
+/// Whether the value representation is encoded as text.
+fn is_textual(vr: VR) -> bool {
+    matches!(
+        vr,
+        VR::AE
+            | VR::AS
+            | VR::CS
+            | VR::DA
+            | VR::DS
+            | VR::DT
+            | VR::IS
+            | VR::LO
+            | VR::LT
+            | VR::PN
+            | VR::SH
+            | VR::ST
+            | VR::TM
+            | VR::UC
+            | VR::UI
+            | VR::UR
+            | VR::UT
+    )
+}
+
+/// Whether a value of the given type can be encoded
+/// in the value representation.
+fn is_compatible_type(vr: VR, value_type: ValueType) -> bool {
+    use ValueType::*;
+    match vr {
+        VR::AT => value_type == Tags,
+        VR::DS => matches!(value_type, F32 | F64),
+        VR::IS => matches!(value_type, I16 | U16 | I32 | U32),
+        VR::FL | VR::OF => value_type == F32,
+        VR::FD | VR::OD => value_type == F64,
+        VR::OB | VR::UN => value_type == U8,
+        VR::OL | VR::UL => value_type == U32,
+        VR::OV | VR::UV => value_type == U64,
+        VR::OW => matches!(value_type, U16 | I16),
+        VR::SL => value_type == I32,
+        VR::SS => value_type == I16,
+        VR::SV => value_type == I64,
+        VR::US => value_type == U16,
+        // types which would be written as text
+        _ if is_textual(vr) => matches!(value_type, Str | Strs),
+        // not checked
+        _ => true,
+    }
+}
+
+/// Check the byte length of a value of raw bytes.
+fn check_bytes(vr: VR, len: usize, mode: ValidationMode) -> Result<()> {
+    let unit = match vr {
+        VR::OB | VR::UN => return Ok(()),
+        VR::OW | VR::US | VR::SS => 2,
+        VR::OF | VR::OL | VR::FL | VR::UL | VR::SL | VR::AT => 4,
+        VR::OD | VR::OV | VR::FD | VR::UV | VR::SV => 8,
+        _ => {
+            return IncompatibleTypeSnafu {
+                vr,
+                value_type: ValueType::U8,
+            }
+            .fail()
+        }
+    };
+    let padded_len = match mode {
+        ValidationMode::Strict => len,
+        ValidationMode::Lenient => (len + 1) & !1,
+    };
+    if padded_len % unit != 0 {
+        return InvalidByteLengthSnafu { vr, len, unit }.fail();
+    }
+    Ok(())
+}
+
+/// Check each value of a textual value.
+fn check_text(vr: VR, value: &PrimitiveValue, mode: ValidationMode) -> Result<()> {
+    let values: Vec<&str> = match value {
+        PrimitiveValue::Str(s) if is_single_valued(vr) => vec![s.as_str()],
+        PrimitiveValue::Str(s) => s.split('\\').collect(),
+        PrimitiveValue::Strs(values) => values.iter().map(|s| s.as_str()).collect(),
+        PrimitiveValue::SharedStrs(values) => values.iter().map(|s| s.as_str()).collect(),
+        _ => unreachable!("value should be textual"),
+    };
+    if is_single_valued(vr) && values.len() > 1 {
+        return MultipleValuesSnafu {
+            vr,
+            count: values.len(),
+        }
+        .fail();
+    }
+
+    for (index, value) in values.into_iter().enumerate() {
+        let value = match mode {
+            ValidationMode::Strict => trim_padding(vr, value),
+            ValidationMode::Lenient => value.trim_matches(|c| c == ' ' || c == '\0'),
+        };
+        check_length(vr, index, value, mode)?;
+        check_characters(vr, index, value, mode)?;
+        check_format(vr, index, value, mode)?;
+    }
+    Ok(())
+}
+
+/// Whether the value representation admits a single value.
+fn is_single_valued(vr: VR) -> bool {
+    matches!(vr, VR::LT | VR::ST | VR::UR | VR::UT)
+}
+
+/// Remove the characters which may be used for padding the value,
+/// or which are not significant in the value representation.
+fn trim_padding(vr: VR, value: &str) -> &str {
+    match vr {
+        VR::UI => value.trim_end_matches('\0'),
+        VR::AE | VR::CS | VR::DS | VR::IS | VR::LO | VR::SH | VR::UR => value.trim_matches(' '),
+        VR::DA | VR::DT | VR::TM | VR::LT | VR::PN | VR::ST | VR::UC | VR::UT => {
+            value.trim_end_matches(' ')
+        }
+        _ => value,
+    }
+}
+
+/// The maximum length of a single value in the value representation,
+/// in characters.
+fn max_length(vr: VR) -> Option<usize> {
+    match vr {
+        VR::AE | VR::CS | VR::DS | VR::SH => Some(16),
+        VR::AS => Some(4),
+        VR::DA => Some(8),
+        VR::DT => Some(26),
+        VR::IS => Some(12),
+        VR::LO | VR::UI => Some(64),
+        VR::LT => Some(10240),
+        VR::ST => Some(1024),
+        VR::TM => Some(14),
+        _ => None,
+    }
+}
+
+fn check_length(vr: VR, index: usize, value: &str, mode: ValidationMode) -> Result<()> {
+    if mode == ValidationMode::Lenient {
+        return Ok(());
+    }
+    let check = |len: usize, max: usize| {
+        if len > max {
+            ValueTooLongSnafu {
+                vr,
+                index,
+                len,
+                max,
+            }
+            .fail()
+        } else {
+            Ok(())
+        }
+    };
+    if vr == VR::PN {
+        // each component group has its own maximum length
+        for group in value.split('=') {
+            check(group.chars().count(), 64)?;
+        }
+        Ok(())
+    } else if let Some(max) = max_length(vr) {
+        check(value.chars().count(), max)
+    } else {
+        Ok(())
+    }
+}
+
+fn check_characters(vr: VR, index: usize, value: &str, mode: ValidationMode) -> Result<()> {
+    let allowed: fn(char) -> bool = match (vr, mode) {
+        (VR::AS, _) => |c| c.is_ascii_digit() || matches!(c, 'D' | 'W' | 'M' | 'Y'),
+        (VR::CS, ValidationMode::Strict) => {
+            |c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == ' ' || c == '_'
+        }
+        (VR::CS, ValidationMode::Lenient) => |c| c.is_ascii_alphanumeric() || c == ' ' || c == '_',
+        (VR::DA, ValidationMode::Strict) => |c| c.is_ascii_digit(),
+        (VR::DA, ValidationMode::Lenient) => |c| c.is_ascii_digit() || c == '.',
+        (VR::DS, _) => |c| c.is_ascii_digit() || matches!(c, '+' | '-' | 'E' | 'e' | '.' | ' '),
+        (VR::DT, _) => |c| c.is_ascii_digit() || matches!(c, '+' | '-' | '.' | ' '),
+        (VR::IS, _) => |c| c.is_ascii_digit() || matches!(c, '+' | '-' | ' '),
+        (VR::TM, ValidationMode::Strict) => |c| c.is_ascii_digit() || c == '.' || c == ' ',
+        (VR::TM, ValidationMode::Lenient) => {
+            |c| c.is_ascii_digit() || c == '.' || c == ' ' || c == ':'
+        }
+        (VR::UI, _) => |c| c.is_ascii_digit() || c == '.',
+        (VR::AE, _) => |c| !c.is_control() && c != '\\',
+        (VR::LT | VR::ST | VR::UT, _) => {
+            |c| !c.is_control() || matches!(c, '\t' | '\n' | '\x0c' | '\r' | '\x1b')
+        }
+        (VR::UR, _) => |c| !c.is_control() && c != ' ',
+        // LO, PN, SH, UC
+        _ => |c| !c.is_control() || c == '\x1b',
+    };
+    if let Some(character) = value.chars().find(|c| !allowed(*c)) {
+        return InvalidCharacterSnafu {
+            vr,
+            index,
+            character,
+        }
+        .fail();
+    }
+    Ok(())
+}
+
+fn check_format(vr: VR, index: usize, value: &str, mode: ValidationMode) -> Result<()> {
+    if value.is_empty() {
+        return Ok(());
+    }
+    let invalid = || {
+        InvalidFormatSnafu {
+            vr,
+            index,
+            value: value.to_string(),
+        }
+        .fail()
+    };
+    match vr {
+        VR::AS => {
+            if value.len() != 4 || !value[..3].bytes().all(|b| b.is_ascii_digit()) {
+                return invalid();
+            }
+        }
+        VR::DS => {
+            if value.trim().parse::<f64>().is_err() {
+                return invalid();
+            }
+        }
+        VR::IS => {
+            if value.trim().parse::<i32>().is_err() {
+                return invalid();
+            }
+        }
+        VR::UI => {
+            let valid = value.split('.').all(|component| {
+                !component.is_empty()
+                    && (mode == ValidationMode::Lenient
+                        || component == "0"
+                        || !component.starts_with('0'))
+            });
+            if !valid {
+                return invalid();
+            }
+        }
+        VR::DA => {
+            let text = match mode {
+                ValidationMode::Strict => Cow::Borrowed(value),
+                ValidationMode::Lenient => Cow::Owned(value.replace('.', "")),
+            };
+            let (date, rest) = parse_date_partial(text.as_bytes())
+                .context(InvalidDateTimeSnafu { vr, index, value })?;
+            let complete = date.precision() == DateComponent::Day;
+            if !rest.is_empty() || (mode == ValidationMode::Strict && !complete) {
+                return invalid();
+            }
+        }
+        VR::TM => {
+            let text = match mode {
+                ValidationMode::Strict => Cow::Borrowed(value),
+                ValidationMode::Lenient => Cow::Owned(value.replace(':', "")),
+            };
+            let (_, rest) = parse_time_partial(text.as_bytes()).context(InvalidDateTimeSnafu {
+                vr,
+                index,
+                value,
+            })?;
+            if !rest.is_empty() {
+                return invalid();
+            }
+        }
+        VR::DT => {
+            parse_datetime_partial(value.as_bytes(), FixedOffset::east(0))
+                .context(InvalidDateTimeSnafu { vr, index, value })?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dicom_value;
+
+    #[test]
+    fn check_strings() {
+        use ValidationMode::*;
+        let ok =
+            |vr, value: &str, mode| check_value(vr, &PrimitiveValue::from(value), mode).is_ok();
+
+        assert!(ok(VR::CS, "ORIGINAL\\PRIMARY\\AXIAL", Strict));
+        assert!(!ok(VR::CS, "Original", Strict));
+        assert!(ok(VR::CS, "Original", Lenient));
+        assert!(!ok(VR::CS, "A_CODE_STRING_TOO_LONG", Strict));
+        assert!(ok(VR::CS, "A_CODE_STRING_TOO_LONG", Lenient));
+
+        assert!(ok(VR::UI, "1.2.840.10008.1.2.1\0", Strict));
+        assert!(!ok(VR::UI, "1.2.840.010008", Strict));
+        assert!(!ok(VR::UI, "1.2..3", Lenient));
+        assert!(!ok(VR::UI, "1.2.3a", Lenient));
+
+        assert!(ok(VR::AE, "STORESCP ", Strict));
+        assert!(!ok(VR::AE, "STORE\u{7}SCP", Lenient));
+
+        assert!(ok(VR::AS, "045Y", Strict));
+        assert!(!ok(VR::AS, "45Y", Strict));
+
+        assert!(ok(VR::DS, "1.5\\-2E3", Strict));
+        assert!(!ok(VR::DS, "1,5", Lenient));
+        assert!(ok(VR::IS, " 42", Strict));
+        assert!(!ok(VR::IS, "42.0", Strict));
+
+        assert!(ok(VR::LT, "first line\r\nsecond line", Strict));
+        assert!(!ok(VR::ST, &"a".repeat(1025), Strict));
+        assert!(ok(VR::PN, "Doe^John=Doe^John", Strict));
+        assert!(!ok(VR::PN, &"a".repeat(65), Strict));
+    }
+
+    #[test]
+    fn check_dates_and_times() {
+        use ValidationMode::*;
+        let ok =
+            |vr, value: &str, mode| check_value(vr, &PrimitiveValue::from(value), mode).is_ok();
+
+        assert!(ok(VR::DA, "20230131", Strict));
+        assert!(!ok(VR::DA, "20230132", Lenient));
+        assert!(!ok(VR::DA, "2023", Strict));
+        assert!(ok(VR::DA, "2023", Lenient));
+        assert!(!ok(VR::DA, "2023.01.31", Strict));
+        assert!(ok(VR::DA, "2023.01.31", Lenient));
+
+        assert!(ok(VR::TM, "101530.123456", Strict));
+        assert!(ok(VR::TM, "10", Strict));
+        assert!(!ok(VR::TM, "10:15:30", Strict));
+        assert!(ok(VR::TM, "10:15:30", Lenient));
+        assert!(!ok(VR::TM, "251530", Lenient));
+
+        assert!(ok(VR::DT, "20230131101530.5+0100", Strict));
+        assert!(!ok(VR::DT, "2023013110x", Strict));
+    }
+
+    #[test]
+    fn check_binary_values() {
+        use ValidationMode::*;
+
+        assert!(check_value(VR::US, &dicom_value!(U16, [1, 2]), Strict).is_ok());
+        assert!(matches!(
+            check_value(VR::US, &dicom_value!(U32, [1, 2]), Strict),
+            Err(Error::IncompatibleType { .. })
+        ));
+        assert!(matches!(
+            check_value(VR::LT, &dicom_value!(Strs, ["one", "two"]), Strict),
+            Err(Error::MultipleValues { count: 2, .. })
+        ));
+        assert!(check_value(VR::DS, &dicom_value!(F64, [1.5]), Strict).is_ok());
+
+        let odd = PrimitiveValue::from(vec![0_u8; 3]);
+        assert!(check_value(VR::OB, &odd, Strict).is_ok());
+        assert!(matches!(
+            check_value(VR::OW, &odd, Strict),
+            Err(Error::InvalidByteLength {
+                len: 3,
+                unit: 2,
+                ..
+            })
+        ));
+        assert!(check_value(VR::OW, &odd, Lenient).is_ok());
+        // lenient mode only tolerates a missing padding byte
+        assert!(check_value(VR::OL, &odd, Lenient).is_ok());
+        let odd = PrimitiveValue::from(vec![0_u8; 5]);
+        assert!(check_value(VR::OL, &odd, Lenient).is_err());
+        assert!(check_value(VR::CS, &odd, Lenient).is_err());
+    }
+}
@@ -0,0 +1,221 @@
+//! DICOM dictionary query library
+//!
+//! This is a helper library
+//! for querying the standard DICOM dictionaries
+//! from scripts and command line tools:
+//! resolving attribute keywords to tags and vice versa,
+//! listing the attributes of a group,
+//! and looking up registered unique identifiers.
+//!
+//! All queries are backed by the dictionaries in
+//! [`dicom_dictionary_std`].
+//!
+//! # Example
+//!
+//! ```
+//! use dicom_core::dictionary::DictionaryEntry;
+//! use dicom_core::Tag;
+//! use dicom_dict::{lookup_attribute, lookup_uid};
+//!
+//! let entry = lookup_attribute("PatientName").unwrap();
+//! assert_eq!(entry.tag(), Tag(0x0010, 0x0010));
+//!
+//! let entry = lookup_attribute("(0010,0010)").unwrap();
+//! assert_eq!(entry.alias(), "PatientName");
+//!
+//! let entry = lookup_uid("1.2.840.10008.1.2.1").unwrap();
+//! assert_eq!(entry.alias, "ExplicitVRLittleEndian");
+//! ```
+use dicom_core::dictionary::uid::{UidDictionary, UidDictionaryEntryRef};
+use dicom_core::dictionary::{DataDictionary, DictionaryEntryRef, TagRange};
+use dicom_dictionary_std::{StandardDataDictionary, StandardUidDictionary};
+
+static DICT: StandardDataDictionary = StandardDataDictionary;
+static UID_DICT: StandardUidDictionary = StandardUidDictionary;
+
+/// Look up an attribute in the standard data dictionary,
+/// by keyword (e.g. `PatientName`)
+/// or by tag in one of the forms
+/// `(gggg,eeee)`, `gggg,eeee`, or `ggggeeee`.
+///
+/// Tags in repeating groups,
+/// such as those of overlay planes,
+/// resolve to their respective attribute.
+pub fn lookup_attribute(expr: &str) -> Option<&'static DictionaryEntryRef<'static>> {
+    DICT.by_expr(expr)
+}
+
+/// Look up a unique identifier in the standard UID dictionary,
+/// by UID (e.g. `1.2.840.10008.1.2.1`)
+/// or by keyword (e.g. `ExplicitVRLittleEndian`).
+pub fn lookup_uid(expr: &str) -> Option<&'static UidDictionaryEntryRef<'static>> {
+    let expr = expr.trim();
+    UID_DICT.by_uid(expr).or_else(|| UID_DICT.by_keyword(expr))
+}
+
+/// Retrieve all attributes of the given group
+/// in the standard data dictionary,
+/// in ascending tag order.
+///
+/// Attributes of repeating groups of the form `(ggxx,eeee)`
+/// are included for all groups which they cover.
+pub fn group_attributes(group: u16) -> Vec<&'static DictionaryEntryRef<'static>> {
+    let mut entries: Vec<_> = DICT
+        .entries()
+        .filter(|entry| match entry.tag {
+            TagRange::Single(tag) | TagRange::Element100(tag) => tag.group() == group,
+            TagRange::Group100(tag) => tag.group() == group & 0xFF00,
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.tag.inner().element());
+    entries
+}
+
+/// Retrieve all attributes in the standard data dictionary
+/// whose keyword or name contain the given text,
+/// ignoring case,
+/// in ascending tag order.
+pub fn search_attributes(text: &str) -> Vec<&'static DictionaryEntryRef<'static>> {
+    let text = text.to_lowercase();
+    let mut entries: Vec<_> = DICT
+        .entries()
+        .filter(|entry| {
+            entry.alias.to_lowercase().contains(&text) || entry.name.to_lowercase().contains(&text)
+        })
+        .collect();
+    entries.sort_by_key(|entry| entry.tag.inner());
+    entries
+}
+
+/// Retrieve all unique identifiers in the standard UID dictionary
+/// whose keyword or name contain the given text,
+/// ignoring case.
+pub fn search_uids(text: &str) -> Vec<&'static UidDictionaryEntryRef<'static>> {
+    let text = text.to_lowercase();
+    UID_DICT
+        .entries()
+        .filter(|entry| {
+            entry.alias.to_lowercase().contains(&text) || entry.name.to_lowercase().contains(&text)
+        })
+        .collect()
+}
+
+/// Format a tag range in the notation of the standard,
+/// with `xx` in place of the open digits
+/// (e.g. `(0010,0010)` or `(60xx,3000)`).
+pub fn format_tag_range(range: TagRange) -> String {
+    match range {
+        TagRange::Single(tag) => format!("({:04X},{:04X})", tag.group(), tag.element()),
+        TagRange::Group100(tag) => format!("({:02X}xx,{:04X})", tag.group() >> 8, tag.element()),
+        TagRange::Element100(tag) => {
+            format!("({:04X},{:02X}xx)", tag.group(), tag.element() >> 8)
+        }
+    }
+}
+
+/// Describe an attribute in a single line of tab separated columns:
+/// tag, keyword, value representation, value multiplicity, and name.
+/// Retired attributes are marked with a trailing `(retired)` column.
+pub fn describe_attribute(entry: &DictionaryEntryRef) -> String {
+    let mut line = format!(
+        "{}\t{}\t{}\t{}\t{}",
+        format_tag_range(entry.tag),
+        entry.alias,
+        entry.vr,
+        entry.vm,
+        entry.name,
+    );
+    if entry.retired {
+        line.push_str("\t(retired)");
+    }
+    line
+}
+
+/// Describe a unique identifier in a single line of tab separated columns:
+/// UID, keyword, kind of identifier, and name.
+/// Retired identifiers are marked with a trailing `(retired)` column.
+pub fn describe_uid(entry: &UidDictionaryEntryRef) -> String {
+    let mut line = format!(
+        "{}\t{}\t{}\t{}",
+        entry.uid, entry.alias, entry.kind, entry.name,
+    );
+    if entry.retired {
+        line.push_str("\t(retired)");
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dictionary::uid::UidType;
+    use dicom_core::{Tag, VR};
+
+    #[test]
+    fn lookup_attribute_by_keyword_and_tag() {
+        let entry = lookup_attribute("PatientName").unwrap();
+        assert_eq!(entry.tag, TagRange::Single(Tag(0x0010, 0x0010)));
+        assert_eq!(entry.vr, VR::PN);
+
+        for expr in &["(0010,0010)", "0010,0010", "00100010"] {
+            assert_eq!(lookup_attribute(expr).unwrap().alias, "PatientName");
+        }
+
+        // repeating group
+        let entry = lookup_attribute("(6002,3000)").unwrap();
+        assert_eq!(entry.alias, "OverlayData");
+        assert_eq!(format_tag_range(entry.tag), "(60xx,3000)");
+
+        assert!(lookup_attribute("NotAnAttribute").is_none());
+    }
+
+    #[test]
+    fn list_group_attributes() {
+        let entries = group_attributes(0x0010);
+        assert!(entries.iter().any(|e| e.alias == "PatientName"));
+        assert!(entries.iter().any(|e| e.alias == "PatientID"));
+        assert!(entries.iter().all(|e| e.tag.inner().group() == 0x0010));
+        assert!(entries
+            .windows(2)
+            .all(|w| w[0].tag.inner().element() <= w[1].tag.inner().element()));
+
+        let entries = group_attributes(0x6004);
+        assert!(entries.iter().any(|e| e.alias == "OverlayData"));
+    }
+
+    #[test]
+    fn search_attributes_ignoring_case() {
+        let entries = search_attributes("patient birth date");
+        assert!(entries.iter().any(|e| e.alias == "PatientBirthDate"));
+        let entries = search_attributes("patientname");
+        assert!(entries.iter().any(|e| e.alias == "PatientName"));
+    }
+
+    #[test]
+    fn lookup_uid_by_uid_and_keyword() {
+        let entry = lookup_uid("1.2.840.10008.1.2.1").unwrap();
+        assert_eq!(entry.alias, "ExplicitVRLittleEndian");
+        assert_eq!(entry.kind, UidType::TransferSyntax);
+        // trailing padding is ignored
+        assert!(lookup_uid("1.2.840.10008.1.2.1\0").is_some());
+
+        let entry = lookup_uid("CTImageStorage").unwrap();
+        assert_eq!(entry.uid, "1.2.840.10008.5.1.4.1.1.2");
+
+        assert!(search_uids("ct image")
+            .iter()
+            .any(|e| e.alias == "CTImageStorage"));
+    }
+
+    #[test]
+    fn describe_entries() {
+        assert_eq!(
+            describe_attribute(lookup_attribute("PatientName").unwrap()),
+            "(0010,0010)\tPatientName\tPN\t1\tPatient Name",
+        );
+        assert_eq!(
+            describe_uid(lookup_uid("1.2.840.10008.1.2.1").unwrap()),
+            "1.2.840.10008.1.2.1\tExplicitVRLittleEndian\tTransfer Syntax\tExplicit VR Little Endian",
+        );
+    }
+}
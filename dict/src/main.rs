@@ -0,0 +1,98 @@
+//! A CLI tool for querying the DICOM standard dictionaries.
+use dicom_dict::{
+    describe_attribute, describe_uid, group_attributes, lookup_attribute, lookup_uid,
+    search_attributes, search_uids,
+};
+use structopt::StructOpt;
+
+/// Query the DICOM standard data element and UID dictionaries
+#[derive(Debug, StructOpt)]
+enum App {
+    /// Look up attributes by keyword or by tag
+    /// (e.g. `PatientName` or `(0010,0010)`)
+    Tag {
+        /// The attribute keywords or tags to look up
+        #[structopt(required = true)]
+        exprs: Vec<String>,
+    },
+    /// List the attributes of a group (e.g. `0010`)
+    Group {
+        /// The group number, in hexadecimal
+        #[structopt(parse(try_from_str = parse_group))]
+        group: u16,
+    },
+    /// Look up unique identifiers by UID or by keyword
+    /// (e.g. `1.2.840.10008.1.2.1` or `ExplicitVRLittleEndian`)
+    Uid {
+        /// The UIDs or UID keywords to look up
+        #[structopt(required = true)]
+        exprs: Vec<String>,
+    },
+    /// Search for attributes or UIDs
+    /// whose keyword or name contain the given text, ignoring case
+    Search {
+        /// The text to search for
+        text: String,
+        /// Search the UID dictionary instead of the attribute dictionary
+        #[structopt(short = "u", long = "uids")]
+        uids: bool,
+    },
+}
+
+fn main() {
+    let found = match App::from_args() {
+        App::Tag { exprs } => exprs
+            .iter()
+            .map(|expr| match lookup_attribute(expr) {
+                Some(entry) => {
+                    println!("{}", describe_attribute(entry));
+                    true
+                }
+                None => {
+                    eprintln!("[ERROR] No such attribute `{}`", expr);
+                    false
+                }
+            })
+            .fold(true, |acc, found| acc && found),
+        App::Group { group } => print_all(group_attributes(group), describe_attribute),
+        App::Uid { exprs } => exprs
+            .iter()
+            .map(|expr| match lookup_uid(expr) {
+                Some(entry) => {
+                    println!("{}", describe_uid(entry));
+                    true
+                }
+                None => {
+                    eprintln!("[ERROR] No such UID `{}`", expr);
+                    false
+                }
+            })
+            .fold(true, |acc, found| acc && found),
+        App::Search { text, uids: false } => {
+            print_all(search_attributes(&text), describe_attribute)
+        }
+        App::Search { text, uids: true } => print_all(search_uids(&text), describe_uid),
+    };
+
+    if !found {
+        std::process::exit(1);
+    }
+}
+
+/// Print one line per entry,
+/// returning whether there were any entries.
+fn print_all<T>(entries: Vec<T>, describe: impl Fn(T) -> String) -> bool {
+    let found = !entries.is_empty();
+    for entry in entries {
+        println!("{}", describe(entry));
+    }
+    found
+}
+
+/// Parse a group number in hexadecimal,
+/// with or without parentheses or a `0x` prefix.
+fn parse_group(s: &str) -> Result<u16, std::num::ParseIntError> {
+    let s = s.trim().trim_start_matches('(').trim_end_matches(')');
+    let s = s.trim_start_matches("0x");
+    u16::from_str_radix(s, 16)
+}
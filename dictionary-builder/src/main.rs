@@ -5,16 +5,24 @@
 //!
 //! Simply run the application. It will automatically retrieve the dictionary
 //! from the DCMTK data dictionary and store the result in "tags.rs".
-//! Future versions will enable different kinds of outputs.
+//! Since the DCMTK data dictionary only lists attribute keywords,
+//! the full attribute names are retrieved
+//! from the data dictionary of the pydicom project.
+//! With the `--uids` flag,
+//! the application will instead retrieve the UID dictionary
+//! from the pydicom project and store the result in "uids.rs".
 //!
 //! Please use the `--help` flag for the full usage information.
 
+mod uids;
+
 use clap::{App, Arg};
 use regex::Regex;
 use serde::Serialize;
 
 use heck::ToShoutySnakeCase;
 use std::borrow::Cow;
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader, Write};
 use std::path::Path;
 use std::{
@@ -26,6 +34,14 @@ use std::{
 const DEFAULT_LOCATION: &str =
     "https://raw.githubusercontent.com/DCMTK/dcmtk/master/dcmdata/data/dicom.dic";
 
+/// url to pydicom data dictionary file, for the attribute names
+const DEFAULT_NAMES_LOCATION: &str =
+    "https://raw.githubusercontent.com/pydicom/pydicom/main/src/pydicom/_dicom_dict.py";
+
+/// url to pydicom UID dictionary file
+const DEFAULT_UID_LOCATION: &str =
+    "https://raw.githubusercontent.com/pydicom/pydicom/main/src/pydicom/_uid_dict.py";
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 enum RetiredOptions {
     /// ignore retired data attributes
@@ -45,6 +61,19 @@ fn main() {
                 .default_value(DEFAULT_LOCATION)
                 .help("Where to fetch the dictionary from"),
         )
+        .arg(
+            Arg::with_name("names")
+                .long("names")
+                .help("Where to fetch the attribute names from")
+                .default_value(DEFAULT_NAMES_LOCATION)
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("uids")
+                .long("uids")
+                .help("Whether to build the UID dictionary instead of the tag dictionary")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("no-retired")
                 .long("no-retired")
@@ -76,11 +105,40 @@ fn main() {
         }
     };
 
+    if matches.is_present("uids") {
+        let src = if matches.occurrences_of("FROM") > 0 {
+            matches.value_of("FROM").unwrap()
+        } else {
+            DEFAULT_UID_LOCATION
+        };
+        let dst = if matches.occurrences_of("OUTPUT") > 0 {
+            Path::new(matches.value_of("OUTPUT").unwrap())
+        } else {
+            Path::new("uids.rs")
+        };
+        uids::run(src, dst, retired);
+        return;
+    }
+
     let src = matches.value_of("FROM").unwrap();
+    let names_src = matches.value_of("names").unwrap();
 
     let dst = Path::new(matches.value_of("OUTPUT").unwrap());
 
-    if src.starts_with("http:") || src.starts_with("https:") {
+    let names = if is_url(names_src) {
+        // read from URL
+        println!("Downloading attribute names ...");
+        let resp = ureq::get(names_src).call().unwrap();
+        let mut data = vec![];
+        std::io::copy(&mut resp.into_reader(), &mut data).unwrap();
+        parse_names(&*data).unwrap()
+    } else {
+        // read from File
+        let file = File::open(names_src).unwrap();
+        parse_names(BufReader::new(file)).unwrap()
+    };
+
+    if is_url(src) {
         // read from URL
         println!("Downloading DICOM dictionary ...");
         let resp = ureq::get(src).call().unwrap();
@@ -92,26 +150,73 @@ fn main() {
             .filter_map(|l| std::str::from_utf8(l).ok())
             .find(|l| l.contains("Copyright"))
             .unwrap_or("");
-        let preamble = format!(
+        let mut preamble = format!(
             "Adapted from the DCMTK project.\nURL: <{}>\nLicense: <{}>\n{}",
             src, "https://github.com/DCMTK/dcmtk/blob/master/COPYRIGHT", preamble,
         );
+        if is_url(names_src) {
+            preamble.push_str(&format!(
+                "\nAttribute names adapted from the pydicom project.\nURL: <{}>\nLicense: <{}>",
+                names_src, "https://github.com/pydicom/pydicom/blob/main/LICENSE",
+            ));
+        }
 
-        let entries = parse_entries(&*data).unwrap();
+        let entries = parse_entries(&*data, &names).unwrap();
         println!("Writing to file ...");
         to_code_file(dst, entries, retired, &preamble).expect("Failed to write file");
     } else {
         // read from File
         let file = File::open(src).unwrap();
-        let entries = parse_entries(BufReader::new(file)).unwrap();
+        let entries = parse_entries(BufReader::new(file), &names).unwrap();
         println!("Writing to file ...");
         to_code_file(dst, entries, retired, "").expect("Failed to write file");
     }
 }
 
+fn is_url(src: &str) -> bool {
+    src.starts_with("http:") || src.starts_with("https:")
+}
+
 type DynResult<T> = Result<T, Box<dyn std::error::Error>>;
 
-fn parse_entries<R: BufRead>(source: R) -> DynResult<Vec<Entry>> {
+/// Collect the full name of each attribute by keyword
+/// from the data dictionary of the pydicom project.
+fn parse_names<R: BufRead>(source: R) -> DynResult<HashMap<String, String>> {
+    // 0x00100010: ('PN', '1', "Patient's Name", '', 'PatientName'),
+    // '002031xx': ('CS', '1-n', 'Source Image IDs', 'Retired', 'SourceImageIDs'),
+    let key = r"(?:0x[0-9A-Fa-f]{8}|'[0-9A-Fa-fx]{8}')";
+    let field = r#"(?:'([^']*)'|"([^"]*)")"#;
+    let regex_entry = Regex::new(&format!(
+        r"^\s*{k}:\s*\(\s*{f},\s*{f},\s*{f},\s*{f},\s*{f}\s*\)",
+        k = key,
+        f = field
+    ))?;
+
+    let mut names = HashMap::new();
+    for line in source.lines() {
+        let line = line?;
+        let cap = match regex_entry.captures(&line) {
+            Some(cap) => cap,
+            None => continue,
+        };
+        // each field is captured by one of two groups, depending on the quotes
+        let get = |i: usize| {
+            cap.get(2 * i + 1)
+                .or_else(|| cap.get(2 * i + 2))
+                .map(|m| m.as_str())
+                .unwrap_or("")
+        };
+
+        let (name, keyword) = (get(2), get(4));
+        if !name.is_empty() && !keyword.is_empty() {
+            names.insert(keyword.to_string(), name.to_string());
+        }
+    }
+
+    Ok(names)
+}
+
+fn parse_entries<R: BufRead>(source: R, names: &HashMap<String, String>) -> DynResult<Vec<Entry>> {
     let mut result = vec![];
 
     for line in source.lines() {
@@ -193,9 +298,13 @@ fn parse_entries<R: BufRead>(source: R) -> DynResult<Vec<Entry>> {
             panic!("invalid tag: {}", alias);
         };
 
+        // attributes missing from the names source are named by keyword
+        let name = names.get(&alias).unwrap_or(&alias).to_string();
+
         result.push(Entry {
             tag,
             vr,
+            name,
             alias,
             vm: parts[3].to_string(),
             obs: parts[4].to_string(),
@@ -223,6 +332,8 @@ struct Entry {
     vr: String,
     /// alias. example: PatientName
     alias: String,
+    /// full name. example: Patient's Name
+    name: String,
     /// VM. example: 1
     vm: String,
     /// observation (usually "DICOM")
@@ -262,6 +373,7 @@ where
 
     f.write_all(
         b"\n\
+    use dicom_core::dictionary::ValueMultiplicity as VM;\n\
     use dicom_core::dictionary::{DictionaryEntryRef, TagRange, TagRange::*};\n\
     use dicom_core::Tag;\n\
     use dicom_core::VR::*;\n\n",
@@ -333,11 +445,70 @@ where
 
         writeln!(
             f,
-            "    E {{ tag: {}, alias: \"{}\", vr: {}{} }}, // {}",
-            tag_set, e.alias, vr1, second_vr, e.obs
+            "    E {{ tag: {}, alias: \"{}\", vr: {}{}, name: {:?}, vm: {}, retired: {} }}, // {}",
+            tag_set,
+            e.alias,
+            vr1,
+            second_vr,
+            e.name,
+            vm_declaration(&e.vm)?,
+            e.is_retired,
+            e.obs
         )?;
     }
     f.write_all(b"];\n")?;
 
     Ok(())
 }
+
+/// Write the value multiplicity of an attribute as Rust code.
+///
+/// Example: `2-2n` becomes `VM::unbounded(2, 2)`.
+fn vm_declaration(vm: &str) -> DynResult<String> {
+    let parse = |n: &str| n.parse::<u32>();
+    let decl = match vm.split_once('-') {
+        None => format!("VM::exact({})", parse(vm)?),
+        Some((min, max)) => match max.strip_suffix('n') {
+            Some("") => format!("VM::unbounded({}, 1)", parse(min)?),
+            Some(step) => format!("VM::unbounded({}, {})", parse(min)?, parse(step)?),
+            None => format!("VM::range({}, {})", parse(min)?, parse(max)?),
+        },
+    };
+    Ok(decl)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_names, vm_declaration};
+
+    #[test]
+    fn parse_attribute_names() {
+        let source = r#"DicomDictionary = {
+    0x00100010: ('PN', '1', "Patient's Name", '', 'PatientName'),
+    0x00209071: ('SQ', '1', "X-Ray 3D Frame Type Sequence", '', 'XRay3DFrameTypeSequence'),
+    0x00080001: ('UL', '1', "Length to End", 'Retired', 'LengthToEnd'),
+    0xFFFEE000: ('NONE', '1', "Item", '', 'Item'),
+}
+RepeatersDictionary = {
+    '002031xx': ('CS', '1-n', 'Source Image IDs', 'Retired', 'SourceImageIDs'),
+}
+"#;
+        let names = parse_names(source.as_bytes()).unwrap();
+        assert_eq!(names["PatientName"], "Patient's Name");
+        assert_eq!(
+            names["XRay3DFrameTypeSequence"],
+            "X-Ray 3D Frame Type Sequence"
+        );
+        assert_eq!(names["LengthToEnd"], "Length to End");
+        assert_eq!(names["SourceImageIDs"], "Source Image IDs");
+    }
+
+    #[test]
+    fn declare_value_multiplicity() {
+        assert_eq!(vm_declaration("1").unwrap(), "VM::exact(1)");
+        assert_eq!(vm_declaration("1-3").unwrap(), "VM::range(1, 3)");
+        assert_eq!(vm_declaration("1-n").unwrap(), "VM::unbounded(1, 1)");
+        assert_eq!(vm_declaration("2-2n").unwrap(), "VM::unbounded(2, 2)");
+        assert!(vm_declaration("n").is_err());
+    }
+}
@@ -0,0 +1,227 @@
+//! Generation of the UID dictionary.
+//!
+//! The UID dictionary is built from the UID dictionary of the pydicom project,
+//! which lists the unique identifiers registered in DICOM PS3.6 Table A-1.
+
+use crate::{DynResult, RetiredOptions};
+use heck::ToShoutySnakeCase;
+use regex::Regex;
+use std::fs::{create_dir_all, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+/// Build the UID dictionary from the given source
+/// and write it to the given destination.
+pub fn run(src: &str, dst: &Path, retired: RetiredOptions) {
+    let (entries, preamble) = if src.starts_with("http:") || src.starts_with("https:") {
+        // read from URL
+        println!("Downloading DICOM UID dictionary ...");
+        let resp = ureq::get(src).call().unwrap();
+        let mut data = vec![];
+        std::io::copy(&mut resp.into_reader(), &mut data).unwrap();
+
+        let preamble = format!(
+            "Adapted from the pydicom project.\nURL: <{}>\nLicense: <{}>",
+            src, "https://github.com/pydicom/pydicom/blob/main/LICENSE",
+        );
+        (parse_uid_entries(&*data).unwrap(), preamble)
+    } else {
+        // read from File
+        let file = File::open(src).unwrap();
+        (
+            parse_uid_entries(BufReader::new(file)).unwrap(),
+            String::new(),
+        )
+    };
+    println!("Writing to file ...");
+    to_uid_code_file(dst, entries, retired, &preamble).expect("Failed to write file");
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct UidEntry {
+    /// UID. example: 1.2.840.10008.1.2
+    uid: String,
+    /// full name. example: Implicit VR Little Endian
+    name: String,
+    /// UID type variant. example: TransferSyntax
+    kind: &'static str,
+    /// keyword. example: ImplicitVRLittleEndian
+    keyword: String,
+    /// Retired UID?
+    is_retired: bool,
+}
+
+/// Map the UID type as written in the standard
+/// to a variant of `UidType`.
+fn uid_type_variant(kind: &str) -> &'static str {
+    match kind {
+        "SOP Class" => "SopClass",
+        "Meta SOP Class" => "MetaSopClass",
+        "Transfer Syntax" => "TransferSyntax",
+        "Service Class" => "ServiceClass",
+        "Well-known SOP Instance" => "WellKnownSopInstance",
+        "Well-known frame of reference" => "WellKnownFrameOfReference",
+        "Synchronization Frame of Reference" => "SynchronizationFrameOfReference",
+        "Application Context Name" => "ApplicationContextName",
+        "Application Hosting Model" => "ApplicationHostingModel",
+        "Coding Scheme" | "DICOM UIDs as a Coding Scheme" => "CodingScheme",
+        "Context Group Name" => "ContextGroupName",
+        "Mapping Resource" => "MappingResource",
+        "LDAP OID" => "LdapOid",
+        _ => "Other",
+    }
+}
+
+fn parse_uid_entries<R: BufRead>(source: R) -> DynResult<Vec<UidEntry>> {
+    // '1.2.840.10008.1.1': ('Verification SOP Class', 'SOP Class', '', '', 'Verification'),
+    let field = r#"(?:'([^']*)'|"([^"]*)")"#;
+    let regex_entry = Regex::new(&format!(
+        r"^\s*{f}:\s*\(\s*{f},\s*{f},\s*{f},\s*{f},\s*{f}\s*\)",
+        f = field
+    ))?;
+
+    let mut result = vec![];
+    for line in source.lines() {
+        let line = line?;
+        let cap = match regex_entry.captures(&line) {
+            Some(cap) => cap,
+            None => continue,
+        };
+        // each field is captured by one of two groups, depending on the quotes
+        let get = |i: usize| {
+            cap.get(2 * i + 1)
+                .or_else(|| cap.get(2 * i + 2))
+                .map(|m| m.as_str())
+                .unwrap_or("")
+        };
+
+        let keyword = get(5);
+        if keyword.is_empty() {
+            // not addressable by keyword
+            continue;
+        }
+
+        result.push(UidEntry {
+            uid: get(0).to_string(),
+            name: get(1).to_string(),
+            kind: uid_type_variant(get(2)),
+            keyword: keyword.to_string(),
+            is_retired: get(4) == "Retired",
+        });
+    }
+
+    Ok(result)
+}
+
+/// Write the UID dictionary as Rust code.
+fn to_uid_code_file<P>(
+    dest_path: P,
+    entries: Vec<UidEntry>,
+    retired_options: RetiredOptions,
+    preamble: &str,
+) -> DynResult<()>
+where
+    P: AsRef<Path>,
+{
+    if let Some(p_dir) = dest_path.as_ref().parent() {
+        create_dir_all(&p_dir)?;
+    }
+    let mut f = BufWriter::new(File::create(&dest_path)?);
+
+    f.write_all(b"//! Automatically generated. Edit at your own risk.\n")?;
+
+    for line in preamble.split('\n') {
+        writeln!(f, "//! {}", line)?;
+    }
+
+    if matches!(retired_options, RetiredOptions::Include { deprecate: true }) {
+        f.write_all(b"#![allow(deprecated)]\n")?;
+    }
+
+    f.write_all(
+        b"\n\
+    use dicom_core::dictionary::uid::{UidDictionaryEntryRef, UidType::*};\n\n",
+    )?;
+
+    let entries: Vec<_> = entries
+        .into_iter()
+        .filter(|e| !(retired_options == RetiredOptions::Ignore && e.is_retired))
+        .collect();
+
+    for e in &entries {
+        writeln!(f, "/// {}: {}", e.name, e.uid)?;
+
+        if e.is_retired
+            && matches!(
+                retired_options,
+                RetiredOptions::Include {
+                    deprecate: true,
+                    ..
+                }
+            )
+        {
+            writeln!(f, "#[deprecated(note = \"Retired DICOM UID\")]")?;
+        }
+
+        writeln!(
+            f,
+            "#[rustfmt::skip]\npub const {}: &str = \"{}\";",
+            e.keyword.to_shouty_snake_case(),
+            e.uid,
+        )?;
+    }
+
+    f.write_all(
+        b"\n\n\
+    type E = UidDictionaryEntryRef<'static>;\n\n\
+    #[rustfmt::skip]\n\
+    pub(crate) const UID_ENTRIES: &[E] = &[\n",
+    )?;
+    for e in &entries {
+        writeln!(
+            f,
+            "    E {{ uid: {}, alias: \"{}\", name: \"{}\", kind: {}, retired: {} }},",
+            e.keyword.to_shouty_snake_case(),
+            e.keyword,
+            e.name,
+            e.kind,
+            e.is_retired,
+        )?;
+    }
+    f.write_all(b"];\n")?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_uid_entries;
+
+    #[test]
+    fn parse_pydicom_uid_dict() {
+        let source = r#"
+"""DICOM UID dictionary auto-generated by generate_uid_dict.py"""
+
+# Each dict entry is UID: (Name, Type, Info, Retired, Keyword)
+UID_dictionary = {
+    '1.2.840.10008.1.1': ('Verification SOP Class', 'SOP Class', '', '', 'Verification'),  # noqa
+    '1.2.840.10008.1.2': ('Implicit VR Little Endian', 'Transfer Syntax', 'Default Transfer Syntax for DICOM', '', 'ImplicitVRLittleEndian'),  # noqa
+    '1.2.840.10008.1.2.2': ('Explicit VR Big Endian', 'Transfer Syntax', '', 'Retired', 'ExplicitVRBigEndian'),  # noqa
+    '1.2.840.10008.1.5.1': ('Hot Iron Color Palette SOP Instance', 'Well-known SOP Instance', '', '', 'HotIronPalette'),  # noqa
+    '1.2.840.10008.15.0.3.1': ('dicomDeviceName', 'LDAP OID', '', '', ''),  # noqa
+}
+"#;
+        let entries = parse_uid_entries(source.as_bytes()).unwrap();
+        assert_eq!(entries.len(), 4);
+
+        assert_eq!(entries[0].uid, "1.2.840.10008.1.1");
+        assert_eq!(entries[0].name, "Verification SOP Class");
+        assert_eq!(entries[0].kind, "SopClass");
+        assert_eq!(entries[0].keyword, "Verification");
+        assert!(!entries[0].is_retired);
+
+        assert_eq!(entries[1].kind, "TransferSyntax");
+        assert!(entries[2].is_retired);
+        assert_eq!(entries[3].kind, "WellKnownSopInstance");
+    }
+}
@@ -5,10 +5,19 @@
 //! and it will be used by default in most other abstractions available.
 //!
 //! When not using private tags, this dictionary should suffice.
+//!
+//! This crate also provides the standard UID dictionary,
+//! with the unique identifiers registered in the standard,
+//! such as SOP classes and transfer syntaxes.
+//! See [`StandardUidDictionary`] and the [`uids`] module.
 
+pub mod private;
 pub mod tags;
+pub mod uids;
 
 use crate::tags::ENTRIES;
+use crate::uids::UID_ENTRIES;
+use dicom_core::dictionary::uid::{trim_uid, UidDictionary, UidDictionaryEntryRef};
 use dicom_core::dictionary::{DataDictionary, DictionaryEntryRef, TagRange::*};
 use dicom_core::header::Tag;
 use lazy_static::lazy_static;
@@ -18,6 +27,7 @@ use std::fmt::{Display, Formatter};
 
 lazy_static! {
     static ref DICT: StandardDictionaryRegistry = init_dictionary();
+    static ref UID_DICT: StandardUidRegistry = init_uid_dictionary();
 }
 
 /// Retrieve a singleton instance of the standard dictionary registry.
@@ -25,6 +35,11 @@ pub fn registry() -> &'static StandardDictionaryRegistry {
     &DICT
 }
 
+/// Retrieve a singleton instance of the standard UID dictionary registry.
+pub fn uid_registry() -> &'static StandardUidRegistry {
+    &UID_DICT
+}
+
 /// The data struct containing the standard dictionary.
 #[derive(Debug)]
 pub struct StandardDictionaryRegistry {
@@ -103,6 +118,10 @@ impl DataDictionary for StandardDataDictionary {
     fn by_tag(&self, tag: Tag) -> Option<&Self::Entry> {
         StandardDataDictionary::indexed_tag(tag)
     }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = &Self::Entry> + '_> {
+        Box::new(ENTRIES.iter())
+    }
 }
 
 impl<'a> DataDictionary for &'a StandardDataDictionary {
@@ -115,6 +134,10 @@ impl<'a> DataDictionary for &'a StandardDataDictionary {
     fn by_tag(&self, tag: Tag) -> Option<&'static DictionaryEntryRef<'static>> {
         StandardDataDictionary::indexed_tag(tag)
     }
+
+    fn entries(&self) -> Box<dyn Iterator<Item = &Self::Entry> + '_> {
+        Box::new(ENTRIES.iter())
+    }
 }
 
 impl Display for StandardDataDictionary {
@@ -131,10 +154,84 @@ fn init_dictionary() -> StandardDictionaryRegistry {
     d
 }
 
+/// The data struct containing the standard UID dictionary.
+#[derive(Debug)]
+pub struct StandardUidRegistry {
+    /// mapping: UID → entry
+    by_uid: HashMap<&'static str, &'static UidDictionaryEntryRef<'static>>,
+    /// mapping: keyword → entry
+    by_keyword: HashMap<&'static str, &'static UidDictionaryEntryRef<'static>>,
+}
+
+impl StandardUidRegistry {
+    fn new() -> StandardUidRegistry {
+        StandardUidRegistry {
+            by_uid: HashMap::with_capacity(UID_ENTRIES.len()),
+            by_keyword: HashMap::with_capacity(UID_ENTRIES.len()),
+        }
+    }
+
+    /// record the given UID dictionary entry reference
+    fn index(&mut self, entry: &'static UidDictionaryEntryRef<'static>) -> &mut Self {
+        self.by_uid.insert(entry.uid, entry);
+        self.by_keyword.insert(entry.alias, entry);
+        self
+    }
+}
+
+/// A UID dictionary which consults the library's global DICOM UID registry.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_core::dictionary::uid::{UidDictionary, UidDictionaryEntry, UidType};
+/// # use dicom_dictionary_std::StandardUidDictionary;
+/// let entry = StandardUidDictionary.by_uid("1.2.840.10008.5.1.4.1.1.2").unwrap();
+/// assert_eq!(entry.name(), "CT Image Storage");
+/// assert_eq!(entry.uid_type(), UidType::SopClass);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StandardUidDictionary;
+
+impl StandardUidDictionary {
+    /// Obtain an iterator over all entries in the UID dictionary.
+    pub fn entries(&self) -> impl Iterator<Item = &'static UidDictionaryEntryRef<'static>> {
+        UID_ENTRIES.iter()
+    }
+}
+
+impl UidDictionary for StandardUidDictionary {
+    type Entry = UidDictionaryEntryRef<'static>;
+
+    fn by_uid(&self, uid: &str) -> Option<&Self::Entry> {
+        uid_registry().by_uid.get(trim_uid(uid)).cloned()
+    }
+
+    fn by_keyword(&self, keyword: &str) -> Option<&Self::Entry> {
+        uid_registry().by_keyword.get(keyword).cloned()
+    }
+}
+
+impl Display for StandardUidDictionary {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), fmt::Error> {
+        f.write_str("Standard DICOM UID Dictionary")
+    }
+}
+
+fn init_uid_dictionary() -> StandardUidRegistry {
+    let mut d = StandardUidRegistry::new();
+    for entry in UID_ENTRIES {
+        d.index(entry);
+    }
+    d
+}
+
 #[cfg(test)]
 mod tests {
-    use super::StandardDataDictionary;
-    use dicom_core::dictionary::{DataDictionary, DictionaryEntryRef, TagRange::*};
+    use super::{StandardDataDictionary, StandardUidDictionary};
+    use dicom_core::dictionary::{
+        DataDictionary, DictionaryEntry, DictionaryEntryRef, TagRange::*, ValueMultiplicity,
+    };
     use dicom_core::header::{Tag, VR};
 
     // tests for just a few attributes to make sure that the entries
@@ -149,6 +246,9 @@ mod tests {
                 tag: Single(Tag(0x0010, 0x0010)),
                 alias: "PatientName",
                 vr: VR::PN,
+                name: "Patient Name",
+                vm: ValueMultiplicity::ONE,
+                retired: false,
             })
         );
 
@@ -158,6 +258,9 @@ mod tests {
                 tag: Single(Tag(0x0008, 0x0060)),
                 alias: "Modality",
                 vr: VR::CS,
+                name: "Modality",
+                vm: ValueMultiplicity::ONE,
+                retired: false,
             })
         );
 
@@ -185,6 +288,92 @@ mod tests {
         assert!(overlay_data.vr == VR::OB || overlay_data.vr == VR::OW);
     }
 
+    #[test]
+    fn lookup_by_expr() {
+        let dict = StandardDataDictionary;
+
+        let entry = dict.by_expr("(0010,0020)").expect("PatientID should exist");
+        assert_eq!(entry.alias, "PatientID");
+        let entry = dict.by_expr("StudyInstanceUID").unwrap();
+        assert_eq!(entry.tag, Single(Tag(0x0020, 0x000D)));
+        assert_eq!(dict.by_expr("NotAnAttribute"), None);
+
+        assert_eq!(dict.parse_tag("PatientName"), Some(Tag(0x0010, 0x0010)));
+        // unknown tags are still parsed
+        assert_eq!(dict.parse_tag("0009,1001"), Some(Tag(0x0009, 0x1001)));
+    }
+
+    #[test]
+    fn iterate_entries() {
+        let dict = StandardDataDictionary;
+
+        assert!(dict.entries().count() > 4000);
+        // every entry can be looked up in both directions
+        for entry in dict.entries() {
+            assert_eq!(dict.by_name(entry.alias).map(|e| e.tag), Some(entry.tag));
+        }
+        assert!(dict
+            .entries()
+            .any(|e| e.alias == "PixelData" && e.tag == Single(Tag(0x7FE0, 0x0010))));
+    }
+
+    #[test]
+    fn entry_metadata() {
+        let dict = StandardDataDictionary;
+
+        let entry = dict.by_name("SOPInstanceUID").unwrap();
+        assert_eq!(entry.name(), "SOP Instance UID");
+        assert_eq!(entry.vm(), ValueMultiplicity::ONE);
+        assert!(!entry.is_retired());
+
+        let entry = dict.by_name("ImageType").unwrap();
+        assert_eq!(entry.vm(), ValueMultiplicity::unbounded(2, 1));
+        assert!(entry.vm().contains(4));
+        assert!(!entry.vm().contains(1));
+
+        let entry = dict.by_tag(Tag(0x0028, 0x0040)).unwrap();
+        assert_eq!(entry.alias(), "ImageFormat");
+        assert!(entry.is_retired());
+
+        assert_eq!(
+            dict.by_name("ImagePositionPatient").unwrap().vm(),
+            ValueMultiplicity::exact(3)
+        );
+    }
+
+    #[test]
+    fn uid_lookup() {
+        use dicom_core::dictionary::uid::{UidDictionary, UidDictionaryEntry, UidType};
+
+        let dict = StandardUidDictionary;
+
+        let entry = dict.by_uid(crate::uids::CT_IMAGE_STORAGE).unwrap();
+        assert_eq!(entry.name(), "CT Image Storage");
+        assert_eq!(entry.alias(), "CTImageStorage");
+        assert_eq!(entry.uid_type(), UidType::SopClass);
+        assert!(!entry.is_retired());
+
+        // trailing padding is ignored
+        let entry = dict.by_uid("1.2.840.10008.1.2.1\0").unwrap();
+        assert_eq!(entry.name(), "Explicit VR Little Endian");
+        assert_eq!(entry.uid_type(), UidType::TransferSyntax);
+
+        let entry = dict.by_keyword("ExplicitVRBigEndian").unwrap();
+        assert_eq!(entry.uid(), "1.2.840.10008.1.2.2");
+        assert!(entry.is_retired());
+
+        let entry = dict.by_uid("1.2.840.10008.1.4.1.1").unwrap();
+        assert_eq!(entry.uid_type(), UidType::WellKnownFrameOfReference);
+
+        assert!(dict.by_uid("1.2.3.4.5").is_none());
+
+        // every entry can be looked up in both directions
+        for entry in dict.entries() {
+            assert_eq!(dict.by_uid(entry.uid), Some(entry));
+            assert_eq!(dict.by_keyword(entry.alias), Some(entry));
+        }
+    }
+
     // tests for just a few attributes to make sure that the tag constants
     // were well installed into the crate
     #[test]
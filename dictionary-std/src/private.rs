@@ -0,0 +1,52 @@
+//! Private attribute dictionaries of common vendors.
+//!
+//! The [vendor dictionary](vendor_dictionary) contains a selection
+//! of well known private attributes from Siemens, GE and Philips.
+//! It can be combined with dictionaries loaded at run-time
+//! through [`PrivateDictionary::extend`].
+
+use dicom_core::dictionary::private::PrivateDictionary;
+use lazy_static::lazy_static;
+
+/// The vendor dictionary source, in the DCMTK `private.dic` format.
+const VENDOR_DIC: &str = include_str!("../data/private.dic");
+
+lazy_static! {
+    static ref VENDOR_DICT: PrivateDictionary =
+        PrivateDictionary::from_dcmtk_reader(VENDOR_DIC.as_bytes())
+            .expect("built-in private dictionary should be valid");
+}
+
+/// Retrieve a singleton instance of the built-in vendor private dictionary.
+pub fn vendor_dictionary() -> &'static PrivateDictionary {
+    &VENDOR_DICT
+}
+
+#[cfg(test)]
+mod tests {
+    use super::vendor_dictionary;
+    use dicom_core::dictionary::private::PrivateCreators;
+    use dicom_core::{Tag, VR};
+
+    #[test]
+    fn vendor_dictionary_entries() {
+        let dict = vendor_dictionary();
+        assert!(!dict.is_empty());
+
+        let mut creators = PrivateCreators::new();
+        creators.declare(Tag(0x0029, 0x0010), "SIEMENS CSA HEADER");
+        creators.declare(Tag(0x0009, 0x0010), "GEMS_IDEN_01");
+        creators.declare(Tag(0x2001, 0x0010), "Philips Imaging DD 001");
+
+        let entry = dict.resolve(&creators, Tag(0x0029, 0x1010)).unwrap();
+        assert_eq!(entry.alias, "CSAImageHeaderInfo");
+        assert_eq!(entry.vr, VR::OB);
+
+        let entry = dict.resolve(&creators, Tag(0x0009, 0x1002)).unwrap();
+        assert_eq!(entry.alias, "SuiteId");
+
+        let entry = dict.resolve(&creators, Tag(0x2001, 0x1003)).unwrap();
+        assert_eq!(entry.alias, "DiffusionBFactor");
+        assert_eq!(entry.vr, VR::FL);
+    }
+}
@@ -0,0 +1,671 @@
+//! Automatically generated. Edit at your own risk.
+//! Adapted from the pydicom project.
+//! URL: <https://raw.githubusercontent.com/pydicom/pydicom/main/src/pydicom/_uid_dict.py>
+//! License: <https://github.com/pydicom/pydicom/blob/main/LICENSE>
+#![allow(deprecated)]
+
+use dicom_core::dictionary::uid::{UidDictionaryEntryRef, UidType::*};
+
+/// Verification SOP Class: 1.2.840.10008.1.1
+#[rustfmt::skip]
+pub const VERIFICATION: &str = "1.2.840.10008.1.1";
+/// Implicit VR Little Endian: 1.2.840.10008.1.2
+#[rustfmt::skip]
+pub const IMPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2";
+/// Explicit VR Little Endian: 1.2.840.10008.1.2.1
+#[rustfmt::skip]
+pub const EXPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2.1";
+/// Encapsulated Uncompressed Explicit VR Little Endian: 1.2.840.10008.1.2.1.98
+#[rustfmt::skip]
+pub const ENCAPSULATED_UNCOMPRESSED_EXPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2.1.98";
+/// Deflated Explicit VR Little Endian: 1.2.840.10008.1.2.1.99
+#[rustfmt::skip]
+pub const DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN: &str = "1.2.840.10008.1.2.1.99";
+/// Explicit VR Big Endian: 1.2.840.10008.1.2.2
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const EXPLICIT_VR_BIG_ENDIAN: &str = "1.2.840.10008.1.2.2";
+/// JPEG Baseline (Process 1): 1.2.840.10008.1.2.4.50
+#[rustfmt::skip]
+pub const JPEG_BASELINE8_BIT: &str = "1.2.840.10008.1.2.4.50";
+/// JPEG Extended (Process 2 & 4): 1.2.840.10008.1.2.4.51
+#[rustfmt::skip]
+pub const JPEG_EXTENDED12_BIT: &str = "1.2.840.10008.1.2.4.51";
+/// JPEG Extended (Process 3 & 5): 1.2.840.10008.1.2.4.52
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const JPEG_EXTENDED35: &str = "1.2.840.10008.1.2.4.52";
+/// JPEG Spectral Selection, Non-Hierarchical (Process 6 & 8): 1.2.840.10008.1.2.4.53
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const JPEG_SPECTRAL_SELECTION_NON_HIERARCHICAL68: &str = "1.2.840.10008.1.2.4.53";
+/// JPEG Full Progression, Non-Hierarchical (Process 10 & 12): 1.2.840.10008.1.2.4.55
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const JPEG_FULL_PROGRESSION_NON_HIERARCHICAL1012: &str = "1.2.840.10008.1.2.4.55";
+/// JPEG Lossless, Non-Hierarchical (Process 14): 1.2.840.10008.1.2.4.57
+#[rustfmt::skip]
+pub const JPEG_LOSSLESS: &str = "1.2.840.10008.1.2.4.57";
+/// JPEG Lossless, Non-Hierarchical (Process 15): 1.2.840.10008.1.2.4.58
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const JPEG_LOSSLESS_NON_HIERARCHICAL15: &str = "1.2.840.10008.1.2.4.58";
+/// JPEG Lossless, Non-Hierarchical, First-Order Prediction (Process 14 [Selection Value 1]): 1.2.840.10008.1.2.4.70
+#[rustfmt::skip]
+pub const JPEG_LOSSLESS_SV1: &str = "1.2.840.10008.1.2.4.70";
+/// JPEG-LS Lossless Image Compression: 1.2.840.10008.1.2.4.80
+#[rustfmt::skip]
+pub const JPEGLS_LOSSLESS: &str = "1.2.840.10008.1.2.4.80";
+/// JPEG-LS Lossy (Near-Lossless) Image Compression: 1.2.840.10008.1.2.4.81
+#[rustfmt::skip]
+pub const JPEGLS_NEAR_LOSSLESS: &str = "1.2.840.10008.1.2.4.81";
+/// JPEG 2000 Image Compression (Lossless Only): 1.2.840.10008.1.2.4.90
+#[rustfmt::skip]
+pub const JPEG2000_LOSSLESS: &str = "1.2.840.10008.1.2.4.90";
+/// JPEG 2000 Image Compression: 1.2.840.10008.1.2.4.91
+#[rustfmt::skip]
+pub const JPEG2000: &str = "1.2.840.10008.1.2.4.91";
+/// JPEG 2000 Part 2 Multi-component Image Compression (Lossless Only): 1.2.840.10008.1.2.4.92
+#[rustfmt::skip]
+pub const JPEG2000MC_LOSSLESS: &str = "1.2.840.10008.1.2.4.92";
+/// JPEG 2000 Part 2 Multi-component Image Compression: 1.2.840.10008.1.2.4.93
+#[rustfmt::skip]
+pub const JPEG2000MC: &str = "1.2.840.10008.1.2.4.93";
+/// JPIP Referenced: 1.2.840.10008.1.2.4.94
+#[rustfmt::skip]
+pub const JPIP_REFERENCED: &str = "1.2.840.10008.1.2.4.94";
+/// JPIP Referenced Deflate: 1.2.840.10008.1.2.4.95
+#[rustfmt::skip]
+pub const JPIP_REFERENCED_DEFLATE: &str = "1.2.840.10008.1.2.4.95";
+/// MPEG2 Main Profile / Main Level: 1.2.840.10008.1.2.4.100
+#[rustfmt::skip]
+pub const MPEG2MPML: &str = "1.2.840.10008.1.2.4.100";
+/// MPEG2 Main Profile / High Level: 1.2.840.10008.1.2.4.101
+#[rustfmt::skip]
+pub const MPEG2MPHL: &str = "1.2.840.10008.1.2.4.101";
+/// MPEG-4 AVC/H.264 High Profile / Level 4.1: 1.2.840.10008.1.2.4.102
+#[rustfmt::skip]
+pub const MPEG4HP41: &str = "1.2.840.10008.1.2.4.102";
+/// MPEG-4 AVC/H.264 BD-compatible High Profile / Level 4.1: 1.2.840.10008.1.2.4.103
+#[rustfmt::skip]
+pub const MPEG4HP41BD: &str = "1.2.840.10008.1.2.4.103";
+/// MPEG-4 AVC/H.264 High Profile / Level 4.2 For 2D Video: 1.2.840.10008.1.2.4.104
+#[rustfmt::skip]
+pub const MPEG4HP422D: &str = "1.2.840.10008.1.2.4.104";
+/// MPEG-4 AVC/H.264 High Profile / Level 4.2 For 3D Video: 1.2.840.10008.1.2.4.105
+#[rustfmt::skip]
+pub const MPEG4HP423D: &str = "1.2.840.10008.1.2.4.105";
+/// MPEG-4 AVC/H.264 Stereo High Profile / Level 4.2: 1.2.840.10008.1.2.4.106
+#[rustfmt::skip]
+pub const MPEG4HP42STEREO: &str = "1.2.840.10008.1.2.4.106";
+/// HEVC/H.265 Main Profile / Level 5.1: 1.2.840.10008.1.2.4.107
+#[rustfmt::skip]
+pub const HEVCMP51: &str = "1.2.840.10008.1.2.4.107";
+/// HEVC/H.265 Main 10 Profile / Level 5.1: 1.2.840.10008.1.2.4.108
+#[rustfmt::skip]
+pub const HEVCM10P51: &str = "1.2.840.10008.1.2.4.108";
+/// High-Throughput JPEG 2000 Image Compression (Lossless Only): 1.2.840.10008.1.2.4.201
+#[rustfmt::skip]
+pub const HTJ2K_LOSSLESS: &str = "1.2.840.10008.1.2.4.201";
+/// High-Throughput JPEG 2000 with RPCL Options Image Compression (Lossless Only): 1.2.840.10008.1.2.4.202
+#[rustfmt::skip]
+pub const HTJ2K_LOSSLESS_RPCL: &str = "1.2.840.10008.1.2.4.202";
+/// High-Throughput JPEG 2000 Image Compression: 1.2.840.10008.1.2.4.203
+#[rustfmt::skip]
+pub const HTJ2K: &str = "1.2.840.10008.1.2.4.203";
+/// RLE Lossless: 1.2.840.10008.1.2.5
+#[rustfmt::skip]
+pub const RLE_LOSSLESS: &str = "1.2.840.10008.1.2.5";
+/// RFC 2557 MIME encapsulation: 1.2.840.10008.1.2.6.1
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const RFC2557MIME_ENCAPSULATION: &str = "1.2.840.10008.1.2.6.1";
+/// XML Encoding: 1.2.840.10008.1.2.6.2
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const XML_ENCODING: &str = "1.2.840.10008.1.2.6.2";
+/// Media Storage Directory Storage: 1.2.840.10008.1.3.10
+#[rustfmt::skip]
+pub const MEDIA_STORAGE_DIRECTORY_STORAGE: &str = "1.2.840.10008.1.3.10";
+/// Talairach Brain Atlas Frame of Reference: 1.2.840.10008.1.4.1.1
+#[rustfmt::skip]
+pub const TALAIRACH_BRAIN_ATLAS: &str = "1.2.840.10008.1.4.1.1";
+/// SPM2 T1 Frame of Reference: 1.2.840.10008.1.4.1.2
+#[rustfmt::skip]
+pub const SPM2T1: &str = "1.2.840.10008.1.4.1.2";
+/// SPM2 T2 Frame of Reference: 1.2.840.10008.1.4.1.3
+#[rustfmt::skip]
+pub const SPM2T2: &str = "1.2.840.10008.1.4.1.3";
+/// SPM2 PD Frame of Reference: 1.2.840.10008.1.4.1.4
+#[rustfmt::skip]
+pub const SPM2PD: &str = "1.2.840.10008.1.4.1.4";
+/// SPM2 EPI Frame of Reference: 1.2.840.10008.1.4.1.5
+#[rustfmt::skip]
+pub const SPM2EPI: &str = "1.2.840.10008.1.4.1.5";
+/// SPM2 FIL T1 Frame of Reference: 1.2.840.10008.1.4.1.6
+#[rustfmt::skip]
+pub const SPM2FILT1: &str = "1.2.840.10008.1.4.1.6";
+/// SPM2 PET Frame of Reference: 1.2.840.10008.1.4.1.7
+#[rustfmt::skip]
+pub const SPM2PET: &str = "1.2.840.10008.1.4.1.7";
+/// SPM2 TRANSM Frame of Reference: 1.2.840.10008.1.4.1.8
+#[rustfmt::skip]
+pub const SPM2TRANSM: &str = "1.2.840.10008.1.4.1.8";
+/// SPM2 SPECT Frame of Reference: 1.2.840.10008.1.4.1.9
+#[rustfmt::skip]
+pub const SPM2SPECT: &str = "1.2.840.10008.1.4.1.9";
+/// SPM2 GRAY Frame of Reference: 1.2.840.10008.1.4.1.10
+#[rustfmt::skip]
+pub const SPM2GRAY: &str = "1.2.840.10008.1.4.1.10";
+/// SPM2 WHITE Frame of Reference: 1.2.840.10008.1.4.1.11
+#[rustfmt::skip]
+pub const SPM2WHITE: &str = "1.2.840.10008.1.4.1.11";
+/// SPM2 CSF Frame of Reference: 1.2.840.10008.1.4.1.12
+#[rustfmt::skip]
+pub const SPM2CSF: &str = "1.2.840.10008.1.4.1.12";
+/// SPM2 BRAINMASK Frame of Reference: 1.2.840.10008.1.4.1.13
+#[rustfmt::skip]
+pub const SPM2BRAINMASK: &str = "1.2.840.10008.1.4.1.13";
+/// SPM2 AVG305T1 Frame of Reference: 1.2.840.10008.1.4.1.14
+#[rustfmt::skip]
+pub const SPM2AVG305T1: &str = "1.2.840.10008.1.4.1.14";
+/// SPM2 AVG152T1 Frame of Reference: 1.2.840.10008.1.4.1.15
+#[rustfmt::skip]
+pub const SPM2AVG152T1: &str = "1.2.840.10008.1.4.1.15";
+/// SPM2 AVG152T2 Frame of Reference: 1.2.840.10008.1.4.1.16
+#[rustfmt::skip]
+pub const SPM2AVG152T2: &str = "1.2.840.10008.1.4.1.16";
+/// SPM2 AVG152PD Frame of Reference: 1.2.840.10008.1.4.1.17
+#[rustfmt::skip]
+pub const SPM2AVG152PD: &str = "1.2.840.10008.1.4.1.17";
+/// SPM2 SINGLESUBJT1 Frame of Reference: 1.2.840.10008.1.4.1.18
+#[rustfmt::skip]
+pub const SPM2SINGLESUBJT1: &str = "1.2.840.10008.1.4.1.18";
+/// ICBM 452 T1 Frame of Reference: 1.2.840.10008.1.4.2.1
+#[rustfmt::skip]
+pub const ICBM452T1: &str = "1.2.840.10008.1.4.2.1";
+/// ICBM Single Subject MRI Frame of Reference: 1.2.840.10008.1.4.2.2
+#[rustfmt::skip]
+pub const ICBM_SINGLE_SUBJECT_MRI: &str = "1.2.840.10008.1.4.2.2";
+/// Storage Commitment Push Model SOP Class: 1.2.840.10008.1.20.1
+#[rustfmt::skip]
+pub const STORAGE_COMMITMENT_PUSH_MODEL: &str = "1.2.840.10008.1.20.1";
+/// Storage Commitment Push Model SOP Instance: 1.2.840.10008.1.20.1.1
+#[rustfmt::skip]
+pub const STORAGE_COMMITMENT_PUSH_MODEL_INSTANCE: &str = "1.2.840.10008.1.20.1.1";
+/// Procedural Event Logging SOP Class: 1.2.840.10008.1.40
+#[rustfmt::skip]
+pub const PROCEDURAL_EVENT_LOGGING: &str = "1.2.840.10008.1.40";
+/// DICOM Controlled Terminology: 1.2.840.10008.2.16.4
+#[rustfmt::skip]
+pub const DCM: &str = "1.2.840.10008.2.16.4";
+/// DICOM Application Context Name: 1.2.840.10008.3.1.1.1
+#[rustfmt::skip]
+pub const DICOM_APPLICATION_CONTEXT: &str = "1.2.840.10008.3.1.1.1";
+/// Modality Performed Procedure Step SOP Class: 1.2.840.10008.3.1.2.3.3
+#[rustfmt::skip]
+pub const MODALITY_PERFORMED_PROCEDURE_STEP: &str = "1.2.840.10008.3.1.2.3.3";
+/// Modality Performed Procedure Step Retrieve SOP Class: 1.2.840.10008.3.1.2.3.4
+#[rustfmt::skip]
+pub const MODALITY_PERFORMED_PROCEDURE_STEP_RETRIEVE: &str = "1.2.840.10008.3.1.2.3.4";
+/// Modality Performed Procedure Step Notification SOP Class: 1.2.840.10008.3.1.2.3.5
+#[rustfmt::skip]
+pub const MODALITY_PERFORMED_PROCEDURE_STEP_NOTIFICATION: &str = "1.2.840.10008.3.1.2.3.5";
+/// Storage Service Class: 1.2.840.10008.4.2
+#[rustfmt::skip]
+pub const STORAGE_SERVICE_CLASS: &str = "1.2.840.10008.4.2";
+/// Basic Grayscale Print Management Meta SOP Class: 1.2.840.10008.5.1.1.9
+#[rustfmt::skip]
+pub const BASIC_GRAYSCALE_PRINT_MANAGEMENT_META: &str = "1.2.840.10008.5.1.1.9";
+/// Basic Color Print Management Meta SOP Class: 1.2.840.10008.5.1.1.18
+#[rustfmt::skip]
+pub const BASIC_COLOR_PRINT_MANAGEMENT_META: &str = "1.2.840.10008.5.1.1.18";
+/// Computed Radiography Image Storage: 1.2.840.10008.5.1.4.1.1.1
+#[rustfmt::skip]
+pub const COMPUTED_RADIOGRAPHY_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.1";
+/// Digital X-Ray Image Storage - For Presentation: 1.2.840.10008.5.1.4.1.1.1.1
+#[rustfmt::skip]
+pub const DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: &str = "1.2.840.10008.5.1.4.1.1.1.1";
+/// Digital X-Ray Image Storage - For Processing: 1.2.840.10008.5.1.4.1.1.1.1.1
+#[rustfmt::skip]
+pub const DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: &str = "1.2.840.10008.5.1.4.1.1.1.1.1";
+/// Digital Mammography X-Ray Image Storage - For Presentation: 1.2.840.10008.5.1.4.1.1.1.2
+#[rustfmt::skip]
+pub const DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: &str = "1.2.840.10008.5.1.4.1.1.1.2";
+/// Digital Mammography X-Ray Image Storage - For Processing: 1.2.840.10008.5.1.4.1.1.1.2.1
+#[rustfmt::skip]
+pub const DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: &str = "1.2.840.10008.5.1.4.1.1.1.2.1";
+/// Digital Intra-Oral X-Ray Image Storage - For Presentation: 1.2.840.10008.5.1.4.1.1.1.3
+#[rustfmt::skip]
+pub const DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION: &str = "1.2.840.10008.5.1.4.1.1.1.3";
+/// Digital Intra-Oral X-Ray Image Storage - For Processing: 1.2.840.10008.5.1.4.1.1.1.3.1
+#[rustfmt::skip]
+pub const DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING: &str = "1.2.840.10008.5.1.4.1.1.1.3.1";
+/// CT Image Storage: 1.2.840.10008.5.1.4.1.1.2
+#[rustfmt::skip]
+pub const CT_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.2";
+/// Enhanced CT Image Storage: 1.2.840.10008.5.1.4.1.1.2.1
+#[rustfmt::skip]
+pub const ENHANCED_CT_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.2.1";
+/// Legacy Converted Enhanced CT Image Storage: 1.2.840.10008.5.1.4.1.1.2.2
+#[rustfmt::skip]
+pub const LEGACY_CONVERTED_ENHANCED_CT_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.2.2";
+/// Ultrasound Multi-frame Image Storage: 1.2.840.10008.5.1.4.1.1.3
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE_RETIRED: &str = "1.2.840.10008.5.1.4.1.1.3";
+/// Ultrasound Multi-frame Image Storage: 1.2.840.10008.5.1.4.1.1.3.1
+#[rustfmt::skip]
+pub const ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.3.1";
+/// MR Image Storage: 1.2.840.10008.5.1.4.1.1.4
+#[rustfmt::skip]
+pub const MR_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.4";
+/// Enhanced MR Image Storage: 1.2.840.10008.5.1.4.1.1.4.1
+#[rustfmt::skip]
+pub const ENHANCED_MR_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.4.1";
+/// MR Spectroscopy Storage: 1.2.840.10008.5.1.4.1.1.4.2
+#[rustfmt::skip]
+pub const MR_SPECTROSCOPY_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.4.2";
+/// Enhanced MR Color Image Storage: 1.2.840.10008.5.1.4.1.1.4.3
+#[rustfmt::skip]
+pub const ENHANCED_MR_COLOR_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.4.3";
+/// Legacy Converted Enhanced MR Image Storage: 1.2.840.10008.5.1.4.1.1.4.4
+#[rustfmt::skip]
+pub const LEGACY_CONVERTED_ENHANCED_MR_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.4.4";
+/// Nuclear Medicine Image Storage: 1.2.840.10008.5.1.4.1.1.5
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const NUCLEAR_MEDICINE_IMAGE_STORAGE_RETIRED: &str = "1.2.840.10008.5.1.4.1.1.5";
+/// Ultrasound Image Storage: 1.2.840.10008.5.1.4.1.1.6
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const ULTRASOUND_IMAGE_STORAGE_RETIRED: &str = "1.2.840.10008.5.1.4.1.1.6";
+/// Ultrasound Image Storage: 1.2.840.10008.5.1.4.1.1.6.1
+#[rustfmt::skip]
+pub const ULTRASOUND_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.6.1";
+/// Enhanced US Volume Storage: 1.2.840.10008.5.1.4.1.1.6.2
+#[rustfmt::skip]
+pub const ENHANCED_US_VOLUME_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.6.2";
+/// Secondary Capture Image Storage: 1.2.840.10008.5.1.4.1.1.7
+#[rustfmt::skip]
+pub const SECONDARY_CAPTURE_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.7";
+/// Multi-frame Single Bit Secondary Capture Image Storage: 1.2.840.10008.5.1.4.1.1.7.1
+#[rustfmt::skip]
+pub const MULTI_FRAME_SINGLE_BIT_SECONDARY_CAPTURE_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.7.1";
+/// Multi-frame Grayscale Byte Secondary Capture Image Storage: 1.2.840.10008.5.1.4.1.1.7.2
+#[rustfmt::skip]
+pub const MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.7.2";
+/// Multi-frame Grayscale Word Secondary Capture Image Storage: 1.2.840.10008.5.1.4.1.1.7.3
+#[rustfmt::skip]
+pub const MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.7.3";
+/// Multi-frame True Color Secondary Capture Image Storage: 1.2.840.10008.5.1.4.1.1.7.4
+#[rustfmt::skip]
+pub const MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.7.4";
+/// 12-lead ECG Waveform Storage: 1.2.840.10008.5.1.4.1.1.9.1.1
+#[rustfmt::skip]
+pub const TWELVE_LEAD_ECG_WAVEFORM_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.9.1.1";
+/// General ECG Waveform Storage: 1.2.840.10008.5.1.4.1.1.9.1.2
+#[rustfmt::skip]
+pub const GENERAL_ECG_WAVEFORM_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.9.1.2";
+/// Ambulatory ECG Waveform Storage: 1.2.840.10008.5.1.4.1.1.9.1.3
+#[rustfmt::skip]
+pub const AMBULATORY_ECG_WAVEFORM_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.9.1.3";
+/// Hemodynamic Waveform Storage: 1.2.840.10008.5.1.4.1.1.9.2.1
+#[rustfmt::skip]
+pub const HEMODYNAMIC_WAVEFORM_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.9.2.1";
+/// Cardiac Electrophysiology Waveform Storage: 1.2.840.10008.5.1.4.1.1.9.3.1
+#[rustfmt::skip]
+pub const CARDIAC_ELECTROPHYSIOLOGY_WAVEFORM_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.9.3.1";
+/// Basic Voice Audio Waveform Storage: 1.2.840.10008.5.1.4.1.1.9.4.1
+#[rustfmt::skip]
+pub const BASIC_VOICE_AUDIO_WAVEFORM_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.9.4.1";
+/// Grayscale Softcopy Presentation State Storage: 1.2.840.10008.5.1.4.1.1.11.1
+#[rustfmt::skip]
+pub const GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.11.1";
+/// Color Softcopy Presentation State Storage: 1.2.840.10008.5.1.4.1.1.11.2
+#[rustfmt::skip]
+pub const COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.11.2";
+/// X-Ray Angiographic Image Storage: 1.2.840.10008.5.1.4.1.1.12.1
+#[rustfmt::skip]
+pub const X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.12.1";
+/// Enhanced XA Image Storage: 1.2.840.10008.5.1.4.1.1.12.1.1
+#[rustfmt::skip]
+pub const ENHANCED_XA_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.12.1.1";
+/// X-Ray Radiofluoroscopic Image Storage: 1.2.840.10008.5.1.4.1.1.12.2
+#[rustfmt::skip]
+pub const X_RAY_RADIOFLUOROSCOPIC_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.12.2";
+/// Enhanced XRF Image Storage: 1.2.840.10008.5.1.4.1.1.12.2.1
+#[rustfmt::skip]
+pub const ENHANCED_XRF_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.12.2.1";
+/// X-Ray 3D Angiographic Image Storage: 1.2.840.10008.5.1.4.1.1.13.1.1
+#[rustfmt::skip]
+pub const X_RAY3_D_ANGIOGRAPHIC_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.13.1.1";
+/// Breast Tomosynthesis Image Storage: 1.2.840.10008.5.1.4.1.1.13.1.3
+#[rustfmt::skip]
+pub const BREAST_TOMOSYNTHESIS_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.13.1.3";
+/// Nuclear Medicine Image Storage: 1.2.840.10008.5.1.4.1.1.20
+#[rustfmt::skip]
+pub const NUCLEAR_MEDICINE_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.20";
+/// Parametric Map Storage: 1.2.840.10008.5.1.4.1.1.30
+#[rustfmt::skip]
+pub const PARAMETRIC_MAP_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.30";
+/// Raw Data Storage: 1.2.840.10008.5.1.4.1.1.66
+#[rustfmt::skip]
+pub const RAW_DATA_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66";
+/// Spatial Registration Storage: 1.2.840.10008.5.1.4.1.1.66.1
+#[rustfmt::skip]
+pub const SPATIAL_REGISTRATION_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66.1";
+/// Spatial Fiducials Storage: 1.2.840.10008.5.1.4.1.1.66.2
+#[rustfmt::skip]
+pub const SPATIAL_FIDUCIALS_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66.2";
+/// Deformable Spatial Registration Storage: 1.2.840.10008.5.1.4.1.1.66.3
+#[rustfmt::skip]
+pub const DEFORMABLE_SPATIAL_REGISTRATION_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66.3";
+/// Segmentation Storage: 1.2.840.10008.5.1.4.1.1.66.4
+#[rustfmt::skip]
+pub const SEGMENTATION_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66.4";
+/// Surface Segmentation Storage: 1.2.840.10008.5.1.4.1.1.66.5
+#[rustfmt::skip]
+pub const SURFACE_SEGMENTATION_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66.5";
+/// Real World Value Mapping Storage: 1.2.840.10008.5.1.4.1.1.67
+#[rustfmt::skip]
+pub const REAL_WORLD_VALUE_MAPPING_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.67";
+/// VL Endoscopic Image Storage: 1.2.840.10008.5.1.4.1.1.77.1.1
+#[rustfmt::skip]
+pub const VL_ENDOSCOPIC_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.77.1.1";
+/// VL Microscopic Image Storage: 1.2.840.10008.5.1.4.1.1.77.1.2
+#[rustfmt::skip]
+pub const VL_MICROSCOPIC_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.77.1.2";
+/// VL Photographic Image Storage: 1.2.840.10008.5.1.4.1.1.77.1.4
+#[rustfmt::skip]
+pub const VL_PHOTOGRAPHIC_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.77.1.4";
+/// Ophthalmic Photography 8 Bit Image Storage: 1.2.840.10008.5.1.4.1.1.77.1.5.1
+#[rustfmt::skip]
+pub const OPHTHALMIC_PHOTOGRAPHY8_BIT_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.77.1.5.1";
+/// VL Whole Slide Microscopy Image Storage: 1.2.840.10008.5.1.4.1.1.77.1.6
+#[rustfmt::skip]
+pub const VL_WHOLE_SLIDE_MICROSCOPY_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.77.1.6";
+/// Basic Text SR Storage: 1.2.840.10008.5.1.4.1.1.88.11
+#[rustfmt::skip]
+pub const BASIC_TEXT_SR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.11";
+/// Enhanced SR Storage: 1.2.840.10008.5.1.4.1.1.88.22
+#[rustfmt::skip]
+pub const ENHANCED_SR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.22";
+/// Comprehensive SR Storage: 1.2.840.10008.5.1.4.1.1.88.33
+#[rustfmt::skip]
+pub const COMPREHENSIVE_SR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.33";
+/// Comprehensive 3D SR Storage: 1.2.840.10008.5.1.4.1.1.88.34
+#[rustfmt::skip]
+pub const COMPREHENSIVE3_DSR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.34";
+/// Mammography CAD SR Storage: 1.2.840.10008.5.1.4.1.1.88.50
+#[rustfmt::skip]
+pub const MAMMOGRAPHY_CADSR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.50";
+/// Key Object Selection Document Storage: 1.2.840.10008.5.1.4.1.1.88.59
+#[rustfmt::skip]
+pub const KEY_OBJECT_SELECTION_DOCUMENT_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.59";
+/// X-Ray Radiation Dose SR Storage: 1.2.840.10008.5.1.4.1.1.88.67
+#[rustfmt::skip]
+pub const X_RAY_RADIATION_DOSE_SR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.67";
+/// Acquisition Context SR Storage: 1.2.840.10008.5.1.4.1.1.88.71
+#[rustfmt::skip]
+pub const ACQUISITION_CONTEXT_SR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.71";
+/// Encapsulated PDF Storage: 1.2.840.10008.5.1.4.1.1.104.1
+#[rustfmt::skip]
+pub const ENCAPSULATED_PDF_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.104.1";
+/// Encapsulated CDA Storage: 1.2.840.10008.5.1.4.1.1.104.2
+#[rustfmt::skip]
+pub const ENCAPSULATED_CDA_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.104.2";
+/// Encapsulated STL Storage: 1.2.840.10008.5.1.4.1.1.104.3
+#[rustfmt::skip]
+pub const ENCAPSULATED_STL_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.104.3";
+/// Positron Emission Tomography Image Storage: 1.2.840.10008.5.1.4.1.1.128
+#[rustfmt::skip]
+pub const POSITRON_EMISSION_TOMOGRAPHY_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.128";
+/// Legacy Converted Enhanced PET Image Storage: 1.2.840.10008.5.1.4.1.1.128.1
+#[rustfmt::skip]
+pub const LEGACY_CONVERTED_ENHANCED_PET_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.128.1";
+/// Enhanced PET Image Storage: 1.2.840.10008.5.1.4.1.1.130
+#[rustfmt::skip]
+pub const ENHANCED_PET_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.130";
+/// RT Image Storage: 1.2.840.10008.5.1.4.1.1.481.1
+#[rustfmt::skip]
+pub const RT_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.1";
+/// RT Dose Storage: 1.2.840.10008.5.1.4.1.1.481.2
+#[rustfmt::skip]
+pub const RT_DOSE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.2";
+/// RT Structure Set Storage: 1.2.840.10008.5.1.4.1.1.481.3
+#[rustfmt::skip]
+pub const RT_STRUCTURE_SET_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.3";
+/// RT Beams Treatment Record Storage: 1.2.840.10008.5.1.4.1.1.481.4
+#[rustfmt::skip]
+pub const RT_BEAMS_TREATMENT_RECORD_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.4";
+/// RT Plan Storage: 1.2.840.10008.5.1.4.1.1.481.5
+#[rustfmt::skip]
+pub const RT_PLAN_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.5";
+/// RT Brachy Treatment Record Storage: 1.2.840.10008.5.1.4.1.1.481.6
+#[rustfmt::skip]
+pub const RT_BRACHY_TREATMENT_RECORD_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.6";
+/// RT Treatment Summary Record Storage: 1.2.840.10008.5.1.4.1.1.481.7
+#[rustfmt::skip]
+pub const RT_TREATMENT_SUMMARY_RECORD_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.7";
+/// RT Ion Plan Storage: 1.2.840.10008.5.1.4.1.1.481.8
+#[rustfmt::skip]
+pub const RT_ION_PLAN_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.8";
+/// RT Ion Beams Treatment Record Storage: 1.2.840.10008.5.1.4.1.1.481.9
+#[rustfmt::skip]
+pub const RT_ION_BEAMS_TREATMENT_RECORD_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.481.9";
+/// Patient Root Query/Retrieve Information Model - FIND: 1.2.840.10008.5.1.4.1.2.1.1
+#[rustfmt::skip]
+pub const PATIENT_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_FIND: &str = "1.2.840.10008.5.1.4.1.2.1.1";
+/// Patient Root Query/Retrieve Information Model - MOVE: 1.2.840.10008.5.1.4.1.2.1.2
+#[rustfmt::skip]
+pub const PATIENT_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE: &str = "1.2.840.10008.5.1.4.1.2.1.2";
+/// Patient Root Query/Retrieve Information Model - GET: 1.2.840.10008.5.1.4.1.2.1.3
+#[rustfmt::skip]
+pub const PATIENT_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET: &str = "1.2.840.10008.5.1.4.1.2.1.3";
+/// Study Root Query/Retrieve Information Model - FIND: 1.2.840.10008.5.1.4.1.2.2.1
+#[rustfmt::skip]
+pub const STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_FIND: &str = "1.2.840.10008.5.1.4.1.2.2.1";
+/// Study Root Query/Retrieve Information Model - MOVE: 1.2.840.10008.5.1.4.1.2.2.2
+#[rustfmt::skip]
+pub const STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE: &str = "1.2.840.10008.5.1.4.1.2.2.2";
+/// Study Root Query/Retrieve Information Model - GET: 1.2.840.10008.5.1.4.1.2.2.3
+#[rustfmt::skip]
+pub const STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET: &str = "1.2.840.10008.5.1.4.1.2.2.3";
+/// Patient/Study Only Query/Retrieve Information Model - FIND: 1.2.840.10008.5.1.4.1.2.3.1
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const PATIENT_STUDY_ONLY_QUERY_RETRIEVE_INFORMATION_MODEL_FIND: &str = "1.2.840.10008.5.1.4.1.2.3.1";
+/// Patient/Study Only Query/Retrieve Information Model - MOVE: 1.2.840.10008.5.1.4.1.2.3.2
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const PATIENT_STUDY_ONLY_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE: &str = "1.2.840.10008.5.1.4.1.2.3.2";
+/// Patient/Study Only Query/Retrieve Information Model - GET: 1.2.840.10008.5.1.4.1.2.3.3
+#[deprecated(note = "Retired DICOM UID")]
+#[rustfmt::skip]
+pub const PATIENT_STUDY_ONLY_QUERY_RETRIEVE_INFORMATION_MODEL_GET: &str = "1.2.840.10008.5.1.4.1.2.3.3";
+/// Composite Instance Root Retrieve - MOVE: 1.2.840.10008.5.1.4.1.2.4.2
+#[rustfmt::skip]
+pub const COMPOSITE_INSTANCE_ROOT_RETRIEVE_MOVE: &str = "1.2.840.10008.5.1.4.1.2.4.2";
+/// Composite Instance Root Retrieve - GET: 1.2.840.10008.5.1.4.1.2.4.3
+#[rustfmt::skip]
+pub const COMPOSITE_INSTANCE_ROOT_RETRIEVE_GET: &str = "1.2.840.10008.5.1.4.1.2.4.3";
+/// Composite Instance Retrieve Without Bulk Data - GET: 1.2.840.10008.5.1.4.1.2.5.3
+#[rustfmt::skip]
+pub const COMPOSITE_INSTANCE_RETRIEVE_WITHOUT_BULK_DATA_GET: &str = "1.2.840.10008.5.1.4.1.2.5.3";
+/// Modality Worklist Information Model - FIND: 1.2.840.10008.5.1.4.31
+#[rustfmt::skip]
+pub const MODALITY_WORKLIST_INFORMATION_MODEL_FIND: &str = "1.2.840.10008.5.1.4.31";
+/// Instance Availability Notification SOP Class: 1.2.840.10008.5.1.4.33
+#[rustfmt::skip]
+pub const INSTANCE_AVAILABILITY_NOTIFICATION: &str = "1.2.840.10008.5.1.4.33";
+/// Universal Coordinated Time: 1.2.840.10008.15.1.1
+#[rustfmt::skip]
+pub const UTC: &str = "1.2.840.10008.15.1.1";
+
+type E = UidDictionaryEntryRef<'static>;
+
+#[rustfmt::skip]
+pub(crate) const UID_ENTRIES: &[E] = &[
+    E { uid: VERIFICATION, alias: "Verification", name: "Verification SOP Class", kind: SopClass, retired: false },
+    E { uid: IMPLICIT_VR_LITTLE_ENDIAN, alias: "ImplicitVRLittleEndian", name: "Implicit VR Little Endian", kind: TransferSyntax, retired: false },
+    E { uid: EXPLICIT_VR_LITTLE_ENDIAN, alias: "ExplicitVRLittleEndian", name: "Explicit VR Little Endian", kind: TransferSyntax, retired: false },
+    E { uid: ENCAPSULATED_UNCOMPRESSED_EXPLICIT_VR_LITTLE_ENDIAN, alias: "EncapsulatedUncompressedExplicitVRLittleEndian", name: "Encapsulated Uncompressed Explicit VR Little Endian", kind: TransferSyntax, retired: false },
+    E { uid: DEFLATED_EXPLICIT_VR_LITTLE_ENDIAN, alias: "DeflatedExplicitVRLittleEndian", name: "Deflated Explicit VR Little Endian", kind: TransferSyntax, retired: false },
+    E { uid: EXPLICIT_VR_BIG_ENDIAN, alias: "ExplicitVRBigEndian", name: "Explicit VR Big Endian", kind: TransferSyntax, retired: true },
+    E { uid: JPEG_BASELINE8_BIT, alias: "JPEGBaseline8Bit", name: "JPEG Baseline (Process 1)", kind: TransferSyntax, retired: false },
+    E { uid: JPEG_EXTENDED12_BIT, alias: "JPEGExtended12Bit", name: "JPEG Extended (Process 2 & 4)", kind: TransferSyntax, retired: false },
+    E { uid: JPEG_EXTENDED35, alias: "JPEGExtended35", name: "JPEG Extended (Process 3 & 5)", kind: TransferSyntax, retired: true },
+    E { uid: JPEG_SPECTRAL_SELECTION_NON_HIERARCHICAL68, alias: "JPEGSpectralSelectionNonHierarchical68", name: "JPEG Spectral Selection, Non-Hierarchical (Process 6 & 8)", kind: TransferSyntax, retired: true },
+    E { uid: JPEG_FULL_PROGRESSION_NON_HIERARCHICAL1012, alias: "JPEGFullProgressionNonHierarchical1012", name: "JPEG Full Progression, Non-Hierarchical (Process 10 & 12)", kind: TransferSyntax, retired: true },
+    E { uid: JPEG_LOSSLESS, alias: "JPEGLossless", name: "JPEG Lossless, Non-Hierarchical (Process 14)", kind: TransferSyntax, retired: false },
+    E { uid: JPEG_LOSSLESS_NON_HIERARCHICAL15, alias: "JPEGLosslessNonHierarchical15", name: "JPEG Lossless, Non-Hierarchical (Process 15)", kind: TransferSyntax, retired: true },
+    E { uid: JPEG_LOSSLESS_SV1, alias: "JPEGLosslessSV1", name: "JPEG Lossless, Non-Hierarchical, First-Order Prediction (Process 14 [Selection Value 1])", kind: TransferSyntax, retired: false },
+    E { uid: JPEGLS_LOSSLESS, alias: "JPEGLSLossless", name: "JPEG-LS Lossless Image Compression", kind: TransferSyntax, retired: false },
+    E { uid: JPEGLS_NEAR_LOSSLESS, alias: "JPEGLSNearLossless", name: "JPEG-LS Lossy (Near-Lossless) Image Compression", kind: TransferSyntax, retired: false },
+    E { uid: JPEG2000_LOSSLESS, alias: "JPEG2000Lossless", name: "JPEG 2000 Image Compression (Lossless Only)", kind: TransferSyntax, retired: false },
+    E { uid: JPEG2000, alias: "JPEG2000", name: "JPEG 2000 Image Compression", kind: TransferSyntax, retired: false },
+    E { uid: JPEG2000MC_LOSSLESS, alias: "JPEG2000MCLossless", name: "JPEG 2000 Part 2 Multi-component Image Compression (Lossless Only)", kind: TransferSyntax, retired: false },
+    E { uid: JPEG2000MC, alias: "JPEG2000MC", name: "JPEG 2000 Part 2 Multi-component Image Compression", kind: TransferSyntax, retired: false },
+    E { uid: JPIP_REFERENCED, alias: "JPIPReferenced", name: "JPIP Referenced", kind: TransferSyntax, retired: false },
+    E { uid: JPIP_REFERENCED_DEFLATE, alias: "JPIPReferencedDeflate", name: "JPIP Referenced Deflate", kind: TransferSyntax, retired: false },
+    E { uid: MPEG2MPML, alias: "MPEG2MPML", name: "MPEG2 Main Profile / Main Level", kind: TransferSyntax, retired: false },
+    E { uid: MPEG2MPHL, alias: "MPEG2MPHL", name: "MPEG2 Main Profile / High Level", kind: TransferSyntax, retired: false },
+    E { uid: MPEG4HP41, alias: "MPEG4HP41", name: "MPEG-4 AVC/H.264 High Profile / Level 4.1", kind: TransferSyntax, retired: false },
+    E { uid: MPEG4HP41BD, alias: "MPEG4HP41BD", name: "MPEG-4 AVC/H.264 BD-compatible High Profile / Level 4.1", kind: TransferSyntax, retired: false },
+    E { uid: MPEG4HP422D, alias: "MPEG4HP422D", name: "MPEG-4 AVC/H.264 High Profile / Level 4.2 For 2D Video", kind: TransferSyntax, retired: false },
+    E { uid: MPEG4HP423D, alias: "MPEG4HP423D", name: "MPEG-4 AVC/H.264 High Profile / Level 4.2 For 3D Video", kind: TransferSyntax, retired: false },
+    E { uid: MPEG4HP42STEREO, alias: "MPEG4HP42STEREO", name: "MPEG-4 AVC/H.264 Stereo High Profile / Level 4.2", kind: TransferSyntax, retired: false },
+    E { uid: HEVCMP51, alias: "HEVCMP51", name: "HEVC/H.265 Main Profile / Level 5.1", kind: TransferSyntax, retired: false },
+    E { uid: HEVCM10P51, alias: "HEVCM10P51", name: "HEVC/H.265 Main 10 Profile / Level 5.1", kind: TransferSyntax, retired: false },
+    E { uid: HTJ2K_LOSSLESS, alias: "HTJ2KLossless", name: "High-Throughput JPEG 2000 Image Compression (Lossless Only)", kind: TransferSyntax, retired: false },
+    E { uid: HTJ2K_LOSSLESS_RPCL, alias: "HTJ2KLosslessRPCL", name: "High-Throughput JPEG 2000 with RPCL Options Image Compression (Lossless Only)", kind: TransferSyntax, retired: false },
+    E { uid: HTJ2K, alias: "HTJ2K", name: "High-Throughput JPEG 2000 Image Compression", kind: TransferSyntax, retired: false },
+    E { uid: RLE_LOSSLESS, alias: "RLELossless", name: "RLE Lossless", kind: TransferSyntax, retired: false },
+    E { uid: RFC2557MIME_ENCAPSULATION, alias: "RFC2557MIMEEncapsulation", name: "RFC 2557 MIME encapsulation", kind: TransferSyntax, retired: true },
+    E { uid: XML_ENCODING, alias: "XMLEncoding", name: "XML Encoding", kind: TransferSyntax, retired: true },
+    E { uid: MEDIA_STORAGE_DIRECTORY_STORAGE, alias: "MediaStorageDirectoryStorage", name: "Media Storage Directory Storage", kind: SopClass, retired: false },
+    E { uid: TALAIRACH_BRAIN_ATLAS, alias: "TalairachBrainAtlas", name: "Talairach Brain Atlas Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2T1, alias: "SPM2T1", name: "SPM2 T1 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2T2, alias: "SPM2T2", name: "SPM2 T2 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2PD, alias: "SPM2PD", name: "SPM2 PD Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2EPI, alias: "SPM2EPI", name: "SPM2 EPI Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2FILT1, alias: "SPM2FILT1", name: "SPM2 FIL T1 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2PET, alias: "SPM2PET", name: "SPM2 PET Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2TRANSM, alias: "SPM2TRANSM", name: "SPM2 TRANSM Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2SPECT, alias: "SPM2SPECT", name: "SPM2 SPECT Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2GRAY, alias: "SPM2GRAY", name: "SPM2 GRAY Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2WHITE, alias: "SPM2WHITE", name: "SPM2 WHITE Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2CSF, alias: "SPM2CSF", name: "SPM2 CSF Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2BRAINMASK, alias: "SPM2BRAINMASK", name: "SPM2 BRAINMASK Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2AVG305T1, alias: "SPM2AVG305T1", name: "SPM2 AVG305T1 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2AVG152T1, alias: "SPM2AVG152T1", name: "SPM2 AVG152T1 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2AVG152T2, alias: "SPM2AVG152T2", name: "SPM2 AVG152T2 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2AVG152PD, alias: "SPM2AVG152PD", name: "SPM2 AVG152PD Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: SPM2SINGLESUBJT1, alias: "SPM2SINGLESUBJT1", name: "SPM2 SINGLESUBJT1 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: ICBM452T1, alias: "ICBM452T1", name: "ICBM 452 T1 Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: ICBM_SINGLE_SUBJECT_MRI, alias: "ICBMSingleSubjectMRI", name: "ICBM Single Subject MRI Frame of Reference", kind: WellKnownFrameOfReference, retired: false },
+    E { uid: STORAGE_COMMITMENT_PUSH_MODEL, alias: "StorageCommitmentPushModel", name: "Storage Commitment Push Model SOP Class", kind: SopClass, retired: false },
+    E { uid: STORAGE_COMMITMENT_PUSH_MODEL_INSTANCE, alias: "StorageCommitmentPushModelInstance", name: "Storage Commitment Push Model SOP Instance", kind: WellKnownSopInstance, retired: false },
+    E { uid: PROCEDURAL_EVENT_LOGGING, alias: "ProceduralEventLogging", name: "Procedural Event Logging SOP Class", kind: SopClass, retired: false },
+    E { uid: DCM, alias: "DCM", name: "DICOM Controlled Terminology", kind: CodingScheme, retired: false },
+    E { uid: DICOM_APPLICATION_CONTEXT, alias: "DICOMApplicationContext", name: "DICOM Application Context Name", kind: ApplicationContextName, retired: false },
+    E { uid: MODALITY_PERFORMED_PROCEDURE_STEP, alias: "ModalityPerformedProcedureStep", name: "Modality Performed Procedure Step SOP Class", kind: SopClass, retired: false },
+    E { uid: MODALITY_PERFORMED_PROCEDURE_STEP_RETRIEVE, alias: "ModalityPerformedProcedureStepRetrieve", name: "Modality Performed Procedure Step Retrieve SOP Class", kind: SopClass, retired: false },
+    E { uid: MODALITY_PERFORMED_PROCEDURE_STEP_NOTIFICATION, alias: "ModalityPerformedProcedureStepNotification", name: "Modality Performed Procedure Step Notification SOP Class", kind: SopClass, retired: false },
+    E { uid: STORAGE_SERVICE_CLASS, alias: "StorageServiceClass", name: "Storage Service Class", kind: ServiceClass, retired: false },
+    E { uid: BASIC_GRAYSCALE_PRINT_MANAGEMENT_META, alias: "BasicGrayscalePrintManagementMeta", name: "Basic Grayscale Print Management Meta SOP Class", kind: MetaSopClass, retired: false },
+    E { uid: BASIC_COLOR_PRINT_MANAGEMENT_META, alias: "BasicColorPrintManagementMeta", name: "Basic Color Print Management Meta SOP Class", kind: MetaSopClass, retired: false },
+    E { uid: COMPUTED_RADIOGRAPHY_IMAGE_STORAGE, alias: "ComputedRadiographyImageStorage", name: "Computed Radiography Image Storage", kind: SopClass, retired: false },
+    E { uid: DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION, alias: "DigitalXRayImageStorageForPresentation", name: "Digital X-Ray Image Storage - For Presentation", kind: SopClass, retired: false },
+    E { uid: DIGITAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING, alias: "DigitalXRayImageStorageForProcessing", name: "Digital X-Ray Image Storage - For Processing", kind: SopClass, retired: false },
+    E { uid: DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION, alias: "DigitalMammographyXRayImageStorageForPresentation", name: "Digital Mammography X-Ray Image Storage - For Presentation", kind: SopClass, retired: false },
+    E { uid: DIGITAL_MAMMOGRAPHY_X_RAY_IMAGE_STORAGE_FOR_PROCESSING, alias: "DigitalMammographyXRayImageStorageForProcessing", name: "Digital Mammography X-Ray Image Storage - For Processing", kind: SopClass, retired: false },
+    E { uid: DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PRESENTATION, alias: "DigitalIntraOralXRayImageStorageForPresentation", name: "Digital Intra-Oral X-Ray Image Storage - For Presentation", kind: SopClass, retired: false },
+    E { uid: DIGITAL_INTRA_ORAL_X_RAY_IMAGE_STORAGE_FOR_PROCESSING, alias: "DigitalIntraOralXRayImageStorageForProcessing", name: "Digital Intra-Oral X-Ray Image Storage - For Processing", kind: SopClass, retired: false },
+    E { uid: CT_IMAGE_STORAGE, alias: "CTImageStorage", name: "CT Image Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_CT_IMAGE_STORAGE, alias: "EnhancedCTImageStorage", name: "Enhanced CT Image Storage", kind: SopClass, retired: false },
+    E { uid: LEGACY_CONVERTED_ENHANCED_CT_IMAGE_STORAGE, alias: "LegacyConvertedEnhancedCTImageStorage", name: "Legacy Converted Enhanced CT Image Storage", kind: SopClass, retired: false },
+    E { uid: ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE_RETIRED, alias: "UltrasoundMultiFrameImageStorageRetired", name: "Ultrasound Multi-frame Image Storage", kind: SopClass, retired: true },
+    E { uid: ULTRASOUND_MULTI_FRAME_IMAGE_STORAGE, alias: "UltrasoundMultiFrameImageStorage", name: "Ultrasound Multi-frame Image Storage", kind: SopClass, retired: false },
+    E { uid: MR_IMAGE_STORAGE, alias: "MRImageStorage", name: "MR Image Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_MR_IMAGE_STORAGE, alias: "EnhancedMRImageStorage", name: "Enhanced MR Image Storage", kind: SopClass, retired: false },
+    E { uid: MR_SPECTROSCOPY_STORAGE, alias: "MRSpectroscopyStorage", name: "MR Spectroscopy Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_MR_COLOR_IMAGE_STORAGE, alias: "EnhancedMRColorImageStorage", name: "Enhanced MR Color Image Storage", kind: SopClass, retired: false },
+    E { uid: LEGACY_CONVERTED_ENHANCED_MR_IMAGE_STORAGE, alias: "LegacyConvertedEnhancedMRImageStorage", name: "Legacy Converted Enhanced MR Image Storage", kind: SopClass, retired: false },
+    E { uid: NUCLEAR_MEDICINE_IMAGE_STORAGE_RETIRED, alias: "NuclearMedicineImageStorageRetired", name: "Nuclear Medicine Image Storage", kind: SopClass, retired: true },
+    E { uid: ULTRASOUND_IMAGE_STORAGE_RETIRED, alias: "UltrasoundImageStorageRetired", name: "Ultrasound Image Storage", kind: SopClass, retired: true },
+    E { uid: ULTRASOUND_IMAGE_STORAGE, alias: "UltrasoundImageStorage", name: "Ultrasound Image Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_US_VOLUME_STORAGE, alias: "EnhancedUSVolumeStorage", name: "Enhanced US Volume Storage", kind: SopClass, retired: false },
+    E { uid: SECONDARY_CAPTURE_IMAGE_STORAGE, alias: "SecondaryCaptureImageStorage", name: "Secondary Capture Image Storage", kind: SopClass, retired: false },
+    E { uid: MULTI_FRAME_SINGLE_BIT_SECONDARY_CAPTURE_IMAGE_STORAGE, alias: "MultiFrameSingleBitSecondaryCaptureImageStorage", name: "Multi-frame Single Bit Secondary Capture Image Storage", kind: SopClass, retired: false },
+    E { uid: MULTI_FRAME_GRAYSCALE_BYTE_SECONDARY_CAPTURE_IMAGE_STORAGE, alias: "MultiFrameGrayscaleByteSecondaryCaptureImageStorage", name: "Multi-frame Grayscale Byte Secondary Capture Image Storage", kind: SopClass, retired: false },
+    E { uid: MULTI_FRAME_GRAYSCALE_WORD_SECONDARY_CAPTURE_IMAGE_STORAGE, alias: "MultiFrameGrayscaleWordSecondaryCaptureImageStorage", name: "Multi-frame Grayscale Word Secondary Capture Image Storage", kind: SopClass, retired: false },
+    E { uid: MULTI_FRAME_TRUE_COLOR_SECONDARY_CAPTURE_IMAGE_STORAGE, alias: "MultiFrameTrueColorSecondaryCaptureImageStorage", name: "Multi-frame True Color Secondary Capture Image Storage", kind: SopClass, retired: false },
+    E { uid: TWELVE_LEAD_ECG_WAVEFORM_STORAGE, alias: "TwelveLeadECGWaveformStorage", name: "12-lead ECG Waveform Storage", kind: SopClass, retired: false },
+    E { uid: GENERAL_ECG_WAVEFORM_STORAGE, alias: "GeneralECGWaveformStorage", name: "General ECG Waveform Storage", kind: SopClass, retired: false },
+    E { uid: AMBULATORY_ECG_WAVEFORM_STORAGE, alias: "AmbulatoryECGWaveformStorage", name: "Ambulatory ECG Waveform Storage", kind: SopClass, retired: false },
+    E { uid: HEMODYNAMIC_WAVEFORM_STORAGE, alias: "HemodynamicWaveformStorage", name: "Hemodynamic Waveform Storage", kind: SopClass, retired: false },
+    E { uid: CARDIAC_ELECTROPHYSIOLOGY_WAVEFORM_STORAGE, alias: "CardiacElectrophysiologyWaveformStorage", name: "Cardiac Electrophysiology Waveform Storage", kind: SopClass, retired: false },
+    E { uid: BASIC_VOICE_AUDIO_WAVEFORM_STORAGE, alias: "BasicVoiceAudioWaveformStorage", name: "Basic Voice Audio Waveform Storage", kind: SopClass, retired: false },
+    E { uid: GRAYSCALE_SOFTCOPY_PRESENTATION_STATE_STORAGE, alias: "GrayscaleSoftcopyPresentationStateStorage", name: "Grayscale Softcopy Presentation State Storage", kind: SopClass, retired: false },
+    E { uid: COLOR_SOFTCOPY_PRESENTATION_STATE_STORAGE, alias: "ColorSoftcopyPresentationStateStorage", name: "Color Softcopy Presentation State Storage", kind: SopClass, retired: false },
+    E { uid: X_RAY_ANGIOGRAPHIC_IMAGE_STORAGE, alias: "XRayAngiographicImageStorage", name: "X-Ray Angiographic Image Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_XA_IMAGE_STORAGE, alias: "EnhancedXAImageStorage", name: "Enhanced XA Image Storage", kind: SopClass, retired: false },
+    E { uid: X_RAY_RADIOFLUOROSCOPIC_IMAGE_STORAGE, alias: "XRayRadiofluoroscopicImageStorage", name: "X-Ray Radiofluoroscopic Image Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_XRF_IMAGE_STORAGE, alias: "EnhancedXRFImageStorage", name: "Enhanced XRF Image Storage", kind: SopClass, retired: false },
+    E { uid: X_RAY3_D_ANGIOGRAPHIC_IMAGE_STORAGE, alias: "XRay3DAngiographicImageStorage", name: "X-Ray 3D Angiographic Image Storage", kind: SopClass, retired: false },
+    E { uid: BREAST_TOMOSYNTHESIS_IMAGE_STORAGE, alias: "BreastTomosynthesisImageStorage", name: "Breast Tomosynthesis Image Storage", kind: SopClass, retired: false },
+    E { uid: NUCLEAR_MEDICINE_IMAGE_STORAGE, alias: "NuclearMedicineImageStorage", name: "Nuclear Medicine Image Storage", kind: SopClass, retired: false },
+    E { uid: PARAMETRIC_MAP_STORAGE, alias: "ParametricMapStorage", name: "Parametric Map Storage", kind: SopClass, retired: false },
+    E { uid: RAW_DATA_STORAGE, alias: "RawDataStorage", name: "Raw Data Storage", kind: SopClass, retired: false },
+    E { uid: SPATIAL_REGISTRATION_STORAGE, alias: "SpatialRegistrationStorage", name: "Spatial Registration Storage", kind: SopClass, retired: false },
+    E { uid: SPATIAL_FIDUCIALS_STORAGE, alias: "SpatialFiducialsStorage", name: "Spatial Fiducials Storage", kind: SopClass, retired: false },
+    E { uid: DEFORMABLE_SPATIAL_REGISTRATION_STORAGE, alias: "DeformableSpatialRegistrationStorage", name: "Deformable Spatial Registration Storage", kind: SopClass, retired: false },
+    E { uid: SEGMENTATION_STORAGE, alias: "SegmentationStorage", name: "Segmentation Storage", kind: SopClass, retired: false },
+    E { uid: SURFACE_SEGMENTATION_STORAGE, alias: "SurfaceSegmentationStorage", name: "Surface Segmentation Storage", kind: SopClass, retired: false },
+    E { uid: REAL_WORLD_VALUE_MAPPING_STORAGE, alias: "RealWorldValueMappingStorage", name: "Real World Value Mapping Storage", kind: SopClass, retired: false },
+    E { uid: VL_ENDOSCOPIC_IMAGE_STORAGE, alias: "VLEndoscopicImageStorage", name: "VL Endoscopic Image Storage", kind: SopClass, retired: false },
+    E { uid: VL_MICROSCOPIC_IMAGE_STORAGE, alias: "VLMicroscopicImageStorage", name: "VL Microscopic Image Storage", kind: SopClass, retired: false },
+    E { uid: VL_PHOTOGRAPHIC_IMAGE_STORAGE, alias: "VLPhotographicImageStorage", name: "VL Photographic Image Storage", kind: SopClass, retired: false },
+    E { uid: OPHTHALMIC_PHOTOGRAPHY8_BIT_IMAGE_STORAGE, alias: "OphthalmicPhotography8BitImageStorage", name: "Ophthalmic Photography 8 Bit Image Storage", kind: SopClass, retired: false },
+    E { uid: VL_WHOLE_SLIDE_MICROSCOPY_IMAGE_STORAGE, alias: "VLWholeSlideMicroscopyImageStorage", name: "VL Whole Slide Microscopy Image Storage", kind: SopClass, retired: false },
+    E { uid: BASIC_TEXT_SR_STORAGE, alias: "BasicTextSRStorage", name: "Basic Text SR Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_SR_STORAGE, alias: "EnhancedSRStorage", name: "Enhanced SR Storage", kind: SopClass, retired: false },
+    E { uid: COMPREHENSIVE_SR_STORAGE, alias: "ComprehensiveSRStorage", name: "Comprehensive SR Storage", kind: SopClass, retired: false },
+    E { uid: COMPREHENSIVE3_DSR_STORAGE, alias: "Comprehensive3DSRStorage", name: "Comprehensive 3D SR Storage", kind: SopClass, retired: false },
+    E { uid: MAMMOGRAPHY_CADSR_STORAGE, alias: "MammographyCADSRStorage", name: "Mammography CAD SR Storage", kind: SopClass, retired: false },
+    E { uid: KEY_OBJECT_SELECTION_DOCUMENT_STORAGE, alias: "KeyObjectSelectionDocumentStorage", name: "Key Object Selection Document Storage", kind: SopClass, retired: false },
+    E { uid: X_RAY_RADIATION_DOSE_SR_STORAGE, alias: "XRayRadiationDoseSRStorage", name: "X-Ray Radiation Dose SR Storage", kind: SopClass, retired: false },
+    E { uid: ACQUISITION_CONTEXT_SR_STORAGE, alias: "AcquisitionContextSRStorage", name: "Acquisition Context SR Storage", kind: SopClass, retired: false },
+    E { uid: ENCAPSULATED_PDF_STORAGE, alias: "EncapsulatedPDFStorage", name: "Encapsulated PDF Storage", kind: SopClass, retired: false },
+    E { uid: ENCAPSULATED_CDA_STORAGE, alias: "EncapsulatedCDAStorage", name: "Encapsulated CDA Storage", kind: SopClass, retired: false },
+    E { uid: ENCAPSULATED_STL_STORAGE, alias: "EncapsulatedSTLStorage", name: "Encapsulated STL Storage", kind: SopClass, retired: false },
+    E { uid: POSITRON_EMISSION_TOMOGRAPHY_IMAGE_STORAGE, alias: "PositronEmissionTomographyImageStorage", name: "Positron Emission Tomography Image Storage", kind: SopClass, retired: false },
+    E { uid: LEGACY_CONVERTED_ENHANCED_PET_IMAGE_STORAGE, alias: "LegacyConvertedEnhancedPETImageStorage", name: "Legacy Converted Enhanced PET Image Storage", kind: SopClass, retired: false },
+    E { uid: ENHANCED_PET_IMAGE_STORAGE, alias: "EnhancedPETImageStorage", name: "Enhanced PET Image Storage", kind: SopClass, retired: false },
+    E { uid: RT_IMAGE_STORAGE, alias: "RTImageStorage", name: "RT Image Storage", kind: SopClass, retired: false },
+    E { uid: RT_DOSE_STORAGE, alias: "RTDoseStorage", name: "RT Dose Storage", kind: SopClass, retired: false },
+    E { uid: RT_STRUCTURE_SET_STORAGE, alias: "RTStructureSetStorage", name: "RT Structure Set Storage", kind: SopClass, retired: false },
+    E { uid: RT_BEAMS_TREATMENT_RECORD_STORAGE, alias: "RTBeamsTreatmentRecordStorage", name: "RT Beams Treatment Record Storage", kind: SopClass, retired: false },
+    E { uid: RT_PLAN_STORAGE, alias: "RTPlanStorage", name: "RT Plan Storage", kind: SopClass, retired: false },
+    E { uid: RT_BRACHY_TREATMENT_RECORD_STORAGE, alias: "RTBrachyTreatmentRecordStorage", name: "RT Brachy Treatment Record Storage", kind: SopClass, retired: false },
+    E { uid: RT_TREATMENT_SUMMARY_RECORD_STORAGE, alias: "RTTreatmentSummaryRecordStorage", name: "RT Treatment Summary Record Storage", kind: SopClass, retired: false },
+    E { uid: RT_ION_PLAN_STORAGE, alias: "RTIonPlanStorage", name: "RT Ion Plan Storage", kind: SopClass, retired: false },
+    E { uid: RT_ION_BEAMS_TREATMENT_RECORD_STORAGE, alias: "RTIonBeamsTreatmentRecordStorage", name: "RT Ion Beams Treatment Record Storage", kind: SopClass, retired: false },
+    E { uid: PATIENT_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_FIND, alias: "PatientRootQueryRetrieveInformationModelFind", name: "Patient Root Query/Retrieve Information Model - FIND", kind: SopClass, retired: false },
+    E { uid: PATIENT_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE, alias: "PatientRootQueryRetrieveInformationModelMove", name: "Patient Root Query/Retrieve Information Model - MOVE", kind: SopClass, retired: false },
+    E { uid: PATIENT_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET, alias: "PatientRootQueryRetrieveInformationModelGet", name: "Patient Root Query/Retrieve Information Model - GET", kind: SopClass, retired: false },
+    E { uid: STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_FIND, alias: "StudyRootQueryRetrieveInformationModelFind", name: "Study Root Query/Retrieve Information Model - FIND", kind: SopClass, retired: false },
+    E { uid: STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE, alias: "StudyRootQueryRetrieveInformationModelMove", name: "Study Root Query/Retrieve Information Model - MOVE", kind: SopClass, retired: false },
+    E { uid: STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET, alias: "StudyRootQueryRetrieveInformationModelGet", name: "Study Root Query/Retrieve Information Model - GET", kind: SopClass, retired: false },
+    E { uid: PATIENT_STUDY_ONLY_QUERY_RETRIEVE_INFORMATION_MODEL_FIND, alias: "PatientStudyOnlyQueryRetrieveInformationModelFind", name: "Patient/Study Only Query/Retrieve Information Model - FIND", kind: SopClass, retired: true },
+    E { uid: PATIENT_STUDY_ONLY_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE, alias: "PatientStudyOnlyQueryRetrieveInformationModelMove", name: "Patient/Study Only Query/Retrieve Information Model - MOVE", kind: SopClass, retired: true },
+    E { uid: PATIENT_STUDY_ONLY_QUERY_RETRIEVE_INFORMATION_MODEL_GET, alias: "PatientStudyOnlyQueryRetrieveInformationModelGet", name: "Patient/Study Only Query/Retrieve Information Model - GET", kind: SopClass, retired: true },
+    E { uid: COMPOSITE_INSTANCE_ROOT_RETRIEVE_MOVE, alias: "CompositeInstanceRootRetrieveMove", name: "Composite Instance Root Retrieve - MOVE", kind: SopClass, retired: false },
+    E { uid: COMPOSITE_INSTANCE_ROOT_RETRIEVE_GET, alias: "CompositeInstanceRootRetrieveGet", name: "Composite Instance Root Retrieve - GET", kind: SopClass, retired: false },
+    E { uid: COMPOSITE_INSTANCE_RETRIEVE_WITHOUT_BULK_DATA_GET, alias: "CompositeInstanceRetrieveWithoutBulkDataGet", name: "Composite Instance Retrieve Without Bulk Data - GET", kind: SopClass, retired: false },
+    E { uid: MODALITY_WORKLIST_INFORMATION_MODEL_FIND, alias: "ModalityWorklistInformationModelFind", name: "Modality Worklist Information Model - FIND", kind: SopClass, retired: false },
+    E { uid: INSTANCE_AVAILABILITY_NOTIFICATION, alias: "InstanceAvailabilityNotification", name: "Instance Availability Notification SOP Class", kind: SopClass, retired: false },
+    E { uid: UTC, alias: "UTC", name: "Universal Coordinated Time", kind: SynchronizationFrameOfReference, retired: false },
+];
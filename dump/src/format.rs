@@ -0,0 +1,425 @@
+//! Formatting of DICOM values for human consumption.
+//!
+//! The [`ValueFormatter`] trait decides how
+//! dates, times, person names, and decimal numbers are presented,
+//! independently of how they are encoded in the data set.
+//! [`ValueFormat`] is a configurable implementation,
+//! which by default shows values
+//! in the same form as the main dump format always has.
+//!
+//! # Example
+//!
+//! ```
+//! use dicom_core::{PrimitiveValue, VR};
+//! use dicom_dump::format::{PersonNameStyle, ValueFormat, ValueFormatter};
+//!
+//! let format = ValueFormat::new()
+//!     .date_format("%d/%m/%Y")
+//!     .person_name_style(PersonNameStyle::FamilyFirst)
+//!     .decimal_precision(2);
+//!
+//! let name = PrimitiveValue::from("Doe^John");
+//! assert_eq!(format.display(&name, VR::PN).to_string(), "Doe, John");
+//! let date = PrimitiveValue::from("20221003");
+//! assert_eq!(format.display(&date, VR::DA).to_string(), "03/10/2022");
+//! let thickness = PrimitiveValue::from("1.25000");
+//! assert_eq!(format.display(&thickness, VR::DS).to_string(), "1.25");
+//! ```
+use dicom_core::chrono::FixedOffset;
+use dicom_core::value::person_name::PersonName;
+use dicom_core::value::{AsRange, DicomDate, DicomDateTime, DicomTime, PrimitiveValue};
+use dicom_core::VR;
+use std::fmt::{self, Display, Formatter, Write as _};
+use std::str::FromStr;
+
+/// A strategy for presenting DICOM values to humans.
+///
+/// All methods have a default implementation
+/// which shows the value in a neutral, locale independent form.
+pub trait ValueFormatter {
+    /// Format a date.
+    fn format_date(&self, date: &DicomDate) -> String {
+        date.to_string()
+    }
+
+    /// Format a time.
+    fn format_time(&self, time: &DicomTime) -> String {
+        time.to_string()
+    }
+
+    /// Format a date-time.
+    fn format_datetime(&self, datetime: &DicomDateTime) -> String {
+        datetime.to_string()
+    }
+
+    /// Format a single person name,
+    /// given in its DICOM encoded form
+    /// (components separated by `^`,
+    /// component groups separated by `=`).
+    fn format_person_name(&self, name: &str) -> String {
+        name.to_string()
+    }
+
+    /// Format a decimal number,
+    /// given in its shortest textual form.
+    fn format_decimal(&self, text: &str) -> String {
+        text.to_string()
+    }
+
+    /// Obtain a displayable representation of a primitive value
+    /// with the given value representation,
+    /// formatted by this formatter.
+    fn display<'a>(&'a self, value: &'a PrimitiveValue, vr: VR) -> DisplayValue<'a, Self>
+    where
+        Self: Sized,
+    {
+        DisplayValue {
+            formatter: self,
+            value,
+            vr,
+        }
+    }
+}
+
+/// How to present person names.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum PersonNameStyle {
+    /// As encoded, e.g. `Doe^John^^Dr.`
+    Dicom,
+    /// Family name first, e.g. `Doe, Dr. John`
+    FamilyFirst,
+    /// Given name first, e.g. `Dr. John Doe`
+    GivenFirst,
+}
+
+impl Default for PersonNameStyle {
+    fn default() -> Self {
+        PersonNameStyle::Dicom
+    }
+}
+
+impl FromStr for PersonNameStyle {
+    type Err = PersonNameStyleError;
+
+    fn from_str(style: &str) -> Result<Self, Self::Err> {
+        match style {
+            "dicom" => Ok(PersonNameStyle::Dicom),
+            "family" => Ok(PersonNameStyle::FamilyFirst),
+            "given" => Ok(PersonNameStyle::GivenFirst),
+            _ => Err(PersonNameStyleError),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct PersonNameStyleError;
+
+impl Display for PersonNameStyleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid person name style (must be \"dicom\", \"family\", or \"given\")")
+    }
+}
+
+impl std::error::Error for PersonNameStyleError {}
+
+/// A configurable value formatter.
+///
+/// Dates, times, and date-times can be given a custom format
+/// in the syntax of [`chrono::format::strftime`](dicom_core::chrono::format::strftime),
+/// which is only applied to values
+/// precise enough to fill in the whole format
+/// (day of the month for dates, seconds for times).
+/// Other values, as well as values which the custom format fails to render,
+/// are shown in the default form.
+///
+/// The default configuration applies no custom formatting.
+#[derive(Debug, Default, Clone, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub struct ValueFormat {
+    /// the custom format of dates
+    pub date_format: Option<String>,
+    /// the custom format of times
+    pub time_format: Option<String>,
+    /// the custom format of date-times
+    pub datetime_format: Option<String>,
+    /// how to present person names
+    pub person_name_style: PersonNameStyle,
+    /// the number of decimal places to show in decimal numbers
+    pub decimal_precision: Option<usize>,
+}
+
+impl ValueFormat {
+    /// Create a value format which applies no custom formatting.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a value format for reading values at a glance:
+    /// dates such as `3 Oct 2022`,
+    /// times such as `10:30:00`,
+    /// and person names with the family name first.
+    pub fn human() -> Self {
+        ValueFormat::new()
+            .date_format("%-d %b %Y")
+            .time_format("%H:%M:%S")
+            .datetime_format("%-d %b %Y %H:%M:%S %:z")
+            .person_name_style(PersonNameStyle::FamilyFirst)
+    }
+
+    /// Set the custom format of dates.
+    pub fn date_format(mut self, format: impl Into<String>) -> Self {
+        self.date_format = Some(format.into());
+        self
+    }
+
+    /// Set the custom format of times.
+    pub fn time_format(mut self, format: impl Into<String>) -> Self {
+        self.time_format = Some(format.into());
+        self
+    }
+
+    /// Set the custom format of date-times.
+    pub fn datetime_format(mut self, format: impl Into<String>) -> Self {
+        self.datetime_format = Some(format.into());
+        self
+    }
+
+    /// Set how to present person names.
+    pub fn person_name_style(mut self, style: PersonNameStyle) -> Self {
+        self.person_name_style = style;
+        self
+    }
+
+    /// Set the number of decimal places to show in decimal numbers.
+    pub fn decimal_precision(mut self, precision: usize) -> Self {
+        self.decimal_precision = Some(precision);
+        self
+    }
+}
+
+/// Render a chrono value with a custom format,
+/// or `None` if the format is invalid for the value.
+fn try_format(value: impl Display) -> Option<String> {
+    let mut out = String::new();
+    write!(out, "{}", value).ok()?;
+    Some(out)
+}
+
+impl ValueFormatter for ValueFormat {
+    fn format_date(&self, date: &DicomDate) -> String {
+        self.date_format
+            .as_ref()
+            .filter(|_| date.day().is_some())
+            .and_then(|format| try_format(date.earliest().ok()?.format(format)))
+            .unwrap_or_else(|| date.to_string())
+    }
+
+    fn format_time(&self, time: &DicomTime) -> String {
+        self.time_format
+            .as_ref()
+            .filter(|_| time.second().is_some())
+            .and_then(|format| try_format(time.earliest().ok()?.format(format)))
+            .unwrap_or_else(|| time.to_string())
+    }
+
+    fn format_datetime(&self, datetime: &DicomDateTime) -> String {
+        self.datetime_format
+            .as_ref()
+            .filter(|_| {
+                datetime.date().day().is_some()
+                    && datetime.time().and_then(DicomTime::second).is_some()
+            })
+            .and_then(|format| try_format(datetime.earliest().ok()?.format(format)))
+            .unwrap_or_else(|| datetime.to_string())
+    }
+
+    fn format_person_name(&self, name: &str) -> String {
+        // only the alphabetic representation is considered
+        let alphabetic = name.split('=').next().unwrap_or_default();
+        let pn = PersonName::from_str(alphabetic);
+        match self.person_name_style {
+            PersonNameStyle::Dicom => name.to_string(),
+            PersonNameStyle::GivenFirst => pn.to_string(),
+            PersonNameStyle::FamilyFirst => {
+                let rest: Vec<&str> = [pn.prefix(), pn.given(), pn.middle()]
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .collect();
+                let mut out = pn.family().unwrap_or_default().to_string();
+                if !rest.is_empty() {
+                    if !out.is_empty() {
+                        out.push_str(", ");
+                    }
+                    out.push_str(&rest.join(" "));
+                }
+                if let Some(suffix) = pn.suffix() {
+                    out.push_str(", ");
+                    out.push_str(suffix);
+                }
+                out
+            }
+        }
+    }
+
+    fn format_decimal(&self, text: &str) -> String {
+        match (self.decimal_precision, text.trim().parse::<f64>()) {
+            (Some(precision), Ok(value)) => format!("{:.*}", precision, value),
+            _ => text.to_string(),
+        }
+    }
+}
+
+/// A primitive value prepared for display by a [`ValueFormatter`].
+///
+/// Multiple values are separated by a comma.
+/// Values which the formatter does not cover
+/// are shown as in the `Display` implementation of [`PrimitiveValue`].
+///
+/// See [`ValueFormatter::display`].
+#[derive(Debug)]
+pub struct DisplayValue<'a, F: ?Sized> {
+    formatter: &'a F,
+    value: &'a PrimitiveValue,
+    vr: VR,
+}
+
+impl<F> Display for DisplayValue<'_, F>
+where
+    F: ?Sized + ValueFormatter,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let pieces = format_values(self.formatter, self.value, self.vr)
+            .unwrap_or_else(|| vec![self.value.to_str().into_owned()]);
+        f.write_str(&pieces.join(", "))
+    }
+}
+
+/// Format each of the values in a primitive value,
+/// or `None` if the value is of a kind not covered by value formatters.
+pub(crate) fn format_values<F>(formatter: &F, value: &PrimitiveValue, vr: VR) -> Option<Vec<String>>
+where
+    F: ?Sized + ValueFormatter,
+{
+    use PrimitiveValue::*;
+
+    let trim = |s: &str| {
+        s.trim_end_matches(|c: char| c.is_whitespace() || c == '\0')
+            .to_string()
+    };
+    let values = match (value, vr) {
+        (Date(values), _) => values.iter().map(|v| formatter.format_date(v)).collect(),
+        (Time(values), _) => values.iter().map(|v| formatter.format_time(v)).collect(),
+        (DateTime(values), _) => values
+            .iter()
+            .map(|v| formatter.format_datetime(v))
+            .collect(),
+        (Str(_), VR::DA) | (Strs(_), VR::DA) | (SharedStrs(_), VR::DA) => value
+            .to_multi_date()
+            .ok()?
+            .iter()
+            .map(|v| formatter.format_date(v))
+            .collect(),
+        (Str(_), VR::TM) | (Strs(_), VR::TM) | (SharedStrs(_), VR::TM) => value
+            .to_multi_time()
+            .ok()?
+            .iter()
+            .map(|v| formatter.format_time(v))
+            .collect(),
+        (Str(_), VR::DT) | (Strs(_), VR::DT) | (SharedStrs(_), VR::DT) => value
+            .to_multi_datetime(FixedOffset::east(0))
+            .ok()?
+            .iter()
+            .map(|v| formatter.format_datetime(v))
+            .collect(),
+        (Str(s), VR::PN) => vec![formatter.format_person_name(&trim(s))],
+        (Strs(values), VR::PN) => values
+            .iter()
+            .map(|s| formatter.format_person_name(&trim(s)))
+            .collect(),
+        (SharedStrs(values), VR::PN) => values
+            .iter()
+            .map(|s| formatter.format_person_name(&trim(s)))
+            .collect(),
+        (Str(s), VR::DS) => vec![formatter.format_decimal(&trim(s))],
+        (Strs(values), VR::DS) => values
+            .iter()
+            .map(|s| formatter.format_decimal(&trim(s)))
+            .collect(),
+        (SharedStrs(values), VR::DS) => values
+            .iter()
+            .map(|s| formatter.format_decimal(&trim(s)))
+            .collect(),
+        (F32(values), _) => values
+            .iter()
+            .map(|v| formatter.format_decimal(&v.to_string()))
+            .collect(),
+        (F64(values), _) => values
+            .iter()
+            .map(|v| formatter.format_decimal(&v.to_string()))
+            .collect(),
+        _ => return None,
+    };
+    Some(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_format_keeps_values() {
+        let format = ValueFormat::new();
+        let date = PrimitiveValue::from("20221003");
+        assert_eq!(format.display(&date, VR::DA).to_string(), "2022-10-03");
+        let name = PrimitiveValue::from("Doe^John ");
+        assert_eq!(format.display(&name, VR::PN).to_string(), "Doe^John");
+        let values = PrimitiveValue::F64(vec![1.5, 2.].into());
+        assert_eq!(format.display(&values, VR::FD).to_string(), "1.5, 2");
+        let id = PrimitiveValue::from("ID1");
+        assert_eq!(format.display(&id, VR::LO).to_string(), "ID1");
+    }
+
+    #[test]
+    fn custom_format() {
+        let format = ValueFormat::human().decimal_precision(3);
+        let date = PrimitiveValue::from("20221003");
+        assert_eq!(format.display(&date, VR::DA).to_string(), "3 Oct 2022");
+        // partial values are shown in full
+        let date = PrimitiveValue::from("202210");
+        assert_eq!(format.display(&date, VR::DA).to_string(), "2022-10");
+        let time = PrimitiveValue::from("103005.25");
+        assert_eq!(format.display(&time, VR::TM).to_string(), "10:30:05");
+        let ds = PrimitiveValue::Strs(
+            vec!["0.5".to_string(), "12 ".to_string(), "n/a".to_string()].into(),
+        );
+        assert_eq!(
+            format.display(&ds, VR::DS).to_string(),
+            "0.500, 12.000, n/a"
+        );
+        let fl = PrimitiveValue::F32(vec![0.1].into());
+        assert_eq!(format.display(&fl, VR::FL).to_string(), "0.100");
+    }
+
+    #[test]
+    fn person_name_styles() {
+        let name = "Geisel^Theodor^Seuss^Dr.^Jr.";
+        let format = ValueFormat::new().person_name_style(PersonNameStyle::FamilyFirst);
+        assert_eq!(
+            format.format_person_name(name),
+            "Geisel, Dr. Theodor Seuss, Jr."
+        );
+        assert_eq!(format.format_person_name("Doe"), "Doe");
+        assert_eq!(format.format_person_name("^John"), "John");
+        assert_eq!(
+            format.format_person_name("Yamada^Tarou=山田^太郎"),
+            "Yamada, Tarou"
+        );
+
+        let format = ValueFormat::new().person_name_style(PersonNameStyle::GivenFirst);
+        assert_eq!(
+            format.format_person_name(name),
+            "Dr. Theodor Seuss Geisel Jr."
+        );
+    }
+}
@@ -31,20 +31,55 @@
 //! options.width(100).dump_file(&obj)?;
 //! # Result::<(), Box<dyn std::error::Error>>::Ok(())
 //! ```
+//!
+//! The data set can also be dumped in the DICOM JSON model.
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_dump::{DumpFormat, DumpOptions};
+//!
+//! let obj = open_file("path/to/file3.dcm")?;
+//! DumpOptions::new().format(DumpFormat::Json).dump_file(&obj)?;
+//! # Result::<(), Box<dyn std::error::Error>>::Ok(())
+//! ```
+//!
+//! Dates, times, person names, and decimal numbers
+//! can be presented in a friendlier form
+//! with a [value format](format::ValueFormat).
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_dump::DumpOptions;
+//! use dicom_dump::format::ValueFormat;
+//!
+//! let obj = open_file("path/to/file4.dcm")?;
+//! DumpOptions::new()
+//!     .value_format(ValueFormat::human().decimal_precision(2))
+//!     .dump_file(&obj)?;
+//! # Result::<(), Box<dyn std::error::Error>>::Ok(())
+//! ```
 use colored::*;
-use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
+use dicom_core::dictionary::uid::{UidDictionary, UidDictionaryEntry};
+use dicom_core::dictionary::{DataDictionary, DictionaryEntry, TagSet};
 use dicom_core::header::Header;
 use dicom_core::value::{PrimitiveValue, Value as DicomValue};
 use dicom_core::VR;
+use dicom_dictionary_std::StandardUidDictionary;
 use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
 use dicom_object::mem::{InMemDicomObject, InMemElement};
 use dicom_object::{FileDicomObject, FileMetaTable, StandardDataDictionary};
+use dicom_parser::dataset::json::{Error as JsonError, JsonDataSetWriter};
+use dicom_parser::dataset::IntoTokens;
 use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
 use std::borrow::Cow;
 use std::fmt::{self, Display, Formatter};
-use std::io::{stdout, Result as IoResult, Write};
+use std::io::{stdout, BufWriter, Error as IoError, ErrorKind, Result as IoResult, Write};
 use std::str::FromStr;
 
+pub mod format;
+
+use crate::format::{format_values, ValueFormat, ValueFormatter};
+
 /// An enum of all supported output formats for dumping DICOM data.
 #[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
 #[non_exhaustive]
@@ -60,6 +95,13 @@ pub enum DumpFormat {
     /// Note that this format is not stabilized,
     /// and may change with subsequent versions of the crate.
     Main,
+    /// The DICOM JSON model (PS3.18 section F.2).
+    ///
+    /// Only the main data set is dumped,
+    /// without the file meta group.
+    /// All values are written in full,
+    /// and neither width, depth, nor color options apply.
+    Json,
 }
 
 /// The [main output format](DumpFormat::Main) is used by default.
@@ -119,6 +161,12 @@ pub struct DumpOptions {
     pub no_text_limit: bool,
     /// never trim out any values (implies `no_text_limit`)
     pub no_limit: bool,
+    /// the maximum sequence nesting level to dump (unlimited by default)
+    pub max_depth: Option<u32>,
+    /// the tags of the elements to leave out, at any nesting level
+    pub exclude_tags: TagSet,
+    /// how to present dates, times, person names, and decimal numbers
+    pub value_format: ValueFormat,
 }
 
 impl DumpOptions {
@@ -170,10 +218,44 @@ impl DumpOptions {
         self
     }
 
+    /// Set the maximum sequence nesting level to dump.
+    ///
+    /// With a depth of 0, only the elements of the root data set are dumped,
+    /// and the items of sequences are omitted.
+    pub fn max_depth(&mut self, max_depth: u32) -> &mut Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    /// Remove the limit on the sequence nesting level to dump.
+    ///
+    /// This is the default behavior.
+    pub fn no_max_depth(&mut self) -> &mut Self {
+        self.max_depth = None;
+        self
+    }
+
+    /// Leave out the elements with a tag in the given set,
+    /// at any nesting level.
+    ///
+    /// This only applies to the main output format.
+    pub fn exclude_tags(&mut self, tags: TagSet) -> &mut Self {
+        self.exclude_tags = tags;
+        self
+    }
+
+    /// Set how to present dates, times, person names, and decimal numbers.
+    ///
+    /// This only applies to the main output format.
+    pub fn value_format(&mut self, value_format: ValueFormat) -> &mut Self {
+        self.value_format = value_format;
+        self
+    }
+
     /// Dump the contents of an open DICOM file to standard output.
     pub fn dump_file<D>(&self, obj: &FileDicomObject<InMemDicomObject<D>>) -> IoResult<()>
     where
-        D: DataDictionary,
+        D: DataDictionary + Clone,
     {
         self.dump_file_to(stdout(), obj)
     }
@@ -185,8 +267,12 @@ impl DumpOptions {
         obj: &FileDicomObject<InMemDicomObject<D>>,
     ) -> IoResult<()>
     where
-        D: DataDictionary,
+        D: DataDictionary + Clone,
     {
+        if self.format == DumpFormat::Json {
+            return dump_json(to, obj);
+        }
+
         match self.color {
             ColorMode::Never => colored::control::set_override(false),
             ColorMode::Always => colored::control::set_override(true),
@@ -201,7 +287,17 @@ impl DumpOptions {
 
         writeln!(to, "{:-<58}", "")?;
 
-        dump(&mut to, obj, width, 0, self.no_text_limit, self.no_limit)?;
+        dump(
+            &mut to,
+            obj,
+            width,
+            0,
+            self.no_text_limit,
+            self.no_limit,
+            self.max_depth,
+            &self.exclude_tags,
+            &self.value_format,
+        )?;
 
         Ok(())
     }
@@ -210,7 +306,7 @@ impl DumpOptions {
     #[inline]
     pub fn dump_object<D>(&self, obj: &InMemDicomObject<D>) -> IoResult<()>
     where
-        D: DataDictionary,
+        D: DataDictionary + Clone,
     {
         self.dump_object_impl(stdout(), obj, true)
     }
@@ -219,7 +315,7 @@ impl DumpOptions {
     #[inline]
     pub fn dump_object_to<D>(&self, to: impl Write, obj: &InMemDicomObject<D>) -> IoResult<()>
     where
-        D: DataDictionary,
+        D: DataDictionary + Clone,
     {
         self.dump_object_impl(to, obj, false)
     }
@@ -231,8 +327,12 @@ impl DumpOptions {
         to_stdout: bool,
     ) -> IoResult<()>
     where
-        D: DataDictionary,
+        D: DataDictionary + Clone,
     {
+        if self.format == DumpFormat::Json {
+            return dump_json(to, obj);
+        }
+
         match (self.color, to_stdout) {
             (ColorMode::Never, _) => colored::control::set_override(false),
             (ColorMode::Always, _) => colored::control::set_override(true),
@@ -246,7 +346,17 @@ impl DumpOptions {
             120
         };
 
-        dump(&mut to, obj, width, 0, self.no_text_limit, self.no_limit)?;
+        dump(
+            &mut to,
+            obj,
+            width,
+            0,
+            self.no_text_limit,
+            self.no_limit,
+            self.max_depth,
+            &self.exclude_tags,
+            &self.value_format,
+        )?;
 
         Ok(())
     }
@@ -297,6 +407,38 @@ impl FromStr for ColorMode {
     }
 }
 
+impl std::fmt::Display for DumpFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DumpFormat::Main => f.write_str("main"),
+            DumpFormat::Json => f.write_str("json"),
+        }
+    }
+}
+
+impl FromStr for DumpFormat {
+    type Err = DumpFormatError;
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "main" => Ok(DumpFormat::Main),
+            "json" => Ok(DumpFormat::Json),
+            _ => Err(DumpFormatError),
+        }
+    }
+}
+
+/// The error raised when providing an invalid output format.
+#[derive(Debug, Default, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct DumpFormatError;
+
+impl Display for DumpFormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("invalid output format")
+    }
+}
+
+impl std::error::Error for DumpFormatError {}
+
 /// The error raised when providing an invalid color mode.
 #[derive(Debug, Default, Copy, Clone, Eq, Hash, PartialEq)]
 pub struct ColorModeError;
@@ -350,7 +492,7 @@ where
 /// Both file meta table and main data set are dumped.
 pub fn dump_file<D>(obj: &FileDicomObject<InMemDicomObject<D>>) -> IoResult<()>
 where
-    D: DataDictionary,
+    D: DataDictionary + Clone,
 {
     DumpOptions::new().dump_file(obj)
 }
@@ -360,11 +502,27 @@ where
 /// Both file meta table and main data set are dumped.
 pub fn dump_file_to<D>(to: impl Write, obj: &FileDicomObject<InMemDicomObject<D>>) -> IoResult<()>
 where
-    D: DataDictionary,
+    D: DataDictionary + Clone,
 {
     DumpOptions::new().dump_file_to(to, obj)
 }
 
+/// Dump the data set of a DICOM object in the DICOM JSON model.
+fn dump_json<D>(to: impl Write, obj: &InMemDicomObject<D>) -> IoResult<()>
+where
+    D: Clone,
+{
+    let mut writer = JsonDataSetWriter::new(BufWriter::new(to));
+    writer
+        .write_sequence(obj.into_tokens())
+        .and_then(|_| writer.finish())
+        .map_err(|e| match e {
+            JsonError::WriteJson { source, .. } => source,
+            e => IoError::new(ErrorKind::Other, e),
+        })?
+        .flush()
+}
+
 #[inline]
 fn whitespace_or_null(c: char) -> bool {
     c.is_whitespace() || c == '\0'
@@ -374,13 +532,25 @@ fn meta_dump<W>(to: &mut W, meta: &FileMetaTable, width: u32) -> IoResult<()>
 where
     W: ?Sized + Write,
 {
-    writeln!(
-        to,
-        "{}: {}",
-        "Media Storage SOP Class UID".bold(),
-        meta.media_storage_sop_class_uid
-            .trim_end_matches(whitespace_or_null),
-    )?;
+    let sop_class_uid = meta
+        .media_storage_sop_class_uid
+        .trim_end_matches(whitespace_or_null);
+    if let Some(entry) = StandardUidDictionary.by_uid(sop_class_uid) {
+        writeln!(
+            to,
+            "{}: {} ({})",
+            "Media Storage SOP Class UID".bold(),
+            sop_class_uid,
+            entry.name(),
+        )?;
+    } else {
+        writeln!(
+            to,
+            "{}: {}",
+            "Media Storage SOP Class UID".bold(),
+            sop_class_uid,
+        )?;
+    }
     writeln!(
         to,
         "{}: {}",
@@ -470,6 +640,7 @@ where
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn dump<W, D>(
     to: &mut W,
     obj: &InMemDicomObject<D>,
@@ -477,13 +648,29 @@ fn dump<W, D>(
     depth: u32,
     no_text_limit: bool,
     no_limit: bool,
+    max_depth: Option<u32>,
+    exclude_tags: &TagSet,
+    formatter: &dyn ValueFormatter,
 ) -> IoResult<()>
 where
     W: ?Sized + Write,
     D: DataDictionary,
 {
     for elem in obj {
-        dump_element(&mut *to, elem, width, depth, no_text_limit, no_limit)?;
+        if exclude_tags.contains(elem.tag()) {
+            continue;
+        }
+        dump_element_impl(
+            &mut *to,
+            elem,
+            width,
+            depth,
+            no_text_limit,
+            no_limit,
+            max_depth,
+            exclude_tags,
+            formatter,
+        )?;
     }
 
     Ok(())
@@ -497,6 +684,38 @@ pub fn dump_element<W, D>(
     no_text_limit: bool,
     no_limit: bool,
 ) -> IoResult<()>
+where
+    W: ?Sized + Write,
+    D: DataDictionary,
+{
+    dump_element_impl(
+        to,
+        elem,
+        width,
+        depth,
+        no_text_limit,
+        no_limit,
+        None,
+        &TagSet::new(),
+        &ValueFormat::new(),
+    )
+}
+
+/// Dump a data element,
+/// including sequence items up to `max_depth` levels of nesting.
+/// Nested elements with a tag in `exclude_tags` are left out.
+#[allow(clippy::too_many_arguments)]
+fn dump_element_impl<W, D>(
+    to: &mut W,
+    elem: &InMemElement<D>,
+    width: u32,
+    depth: u32,
+    no_text_limit: bool,
+    no_limit: bool,
+    max_depth: Option<u32>,
+    exclude_tags: &TagSet,
+    formatter: &dyn ValueFormatter,
+) -> IoResult<()>
 where
     W: ?Sized + Write,
     D: DataDictionary,
@@ -523,8 +742,22 @@ where
                 vm,
                 if vm == 1 { "" } else { "s" },
             )?;
+            if max_depth == Some(0) {
+                // items are beyond the maximum depth
+                return Ok(());
+            }
             for item in items {
-                dump_item(&mut *to, item, width, depth + 2, no_text_limit, no_limit)?;
+                dump_item(
+                    &mut *to,
+                    item,
+                    width,
+                    depth + 2,
+                    no_text_limit,
+                    no_limit,
+                    max_depth.map(|d| d - 1),
+                    exclude_tags,
+                    formatter,
+                )?;
             }
             to.write_all(&indent)?;
             writeln!(
@@ -602,6 +835,7 @@ where
                     width.saturating_sub(63 + depth * 2),
                     no_text_limit,
                     no_limit,
+                    formatter,
                 ),
             )?;
         }
@@ -610,6 +844,7 @@ where
     Ok(())
 }
 
+#[allow(clippy::too_many_arguments)]
 fn dump_item<W, D>(
     to: &mut W,
     item: &InMemDicomObject<D>,
@@ -617,6 +852,9 @@ fn dump_item<W, D>(
     depth: u32,
     no_text_limit: bool,
     no_limit: bool,
+    max_depth: Option<u32>,
+    exclude_tags: &TagSet,
+    formatter: &dyn ValueFormatter,
 ) -> IoResult<()>
 where
     W: ?Sized + Write,
@@ -630,7 +868,17 @@ where
         DumpValue::TagNum("(FFFE,E000)"),
         DumpValue::Alias("Item"),
     )?;
-    dump(to, item, width, depth + 1, no_text_limit, no_limit)?;
+    dump(
+        to,
+        item,
+        width,
+        depth + 1,
+        no_text_limit,
+        no_limit,
+        max_depth,
+        exclude_tags,
+        formatter,
+    )?;
     writeln!(
         to,
         "{}{} {}",
@@ -647,6 +895,7 @@ fn value_summary(
     max_characters: u32,
     no_text_limit: bool,
     no_limit: bool,
+    formatter: &dyn ValueFormatter,
 ) -> DumpValue<String> {
     use PrimitiveValue::*;
 
@@ -672,8 +921,11 @@ fn value_summary(
         (false, _, _) => Some(max_characters),
     };
     match (value, vr) {
-        (F32(values), _) => DumpValue::Num(format_value_list(values, max_characters, false)),
-        (F64(values), _) => DumpValue::Num(format_value_list(values, max_characters, false)),
+        (F32(_) | F64(_), _) => DumpValue::Num(format_value_list(
+            format_values(formatter, value, vr).unwrap_or_default(),
+            max_characters,
+            false,
+        )),
         (I32(values), _) => DumpValue::Num(format_value_list(values, max_characters, false)),
         (I64(values), _) => DumpValue::Num(format_value_list(values, max_characters, false)),
         (U32(values), _) => DumpValue::Num(format_value_list(values, max_characters, false)),
@@ -691,43 +943,31 @@ fn value_summary(
             false,
         )),
         (U8(values), _) => DumpValue::Num(format_value_list(values, max_characters, false)),
+        (SharedBytes(values), _) => DumpValue::Num(format_value_list(
+            values.iter().map(|n| format!("{:02X}", n)),
+            max_characters,
+            false,
+        )),
         (Tags(values), _) => DumpValue::Str(format_value_list(values, max_characters, false)),
-        (Strs(values), VR::DA) => {
-            match value.to_multi_date() {
-                Ok(values) => {
-                    // print as reformatted date
+        (Str(_) | Strs(_) | SharedStrs(_), VR::DA | VR::TM | VR::DT) => {
+            match format_values(formatter, value, vr) {
+                // print as reformatted date/time
+                Some(values) => {
                     DumpValue::DateTime(format_value_list(values, max_characters, false))
                 }
-                Err(_e) => {
-                    // print as text
-                    DumpValue::Invalid(format_value_list(values, max_characters, true))
-                }
-            }
-        }
-        (Strs(values), VR::TM) => {
-            match value.to_multi_time() {
-                Ok(values) => {
-                    // print as reformatted date
-                    DumpValue::DateTime(format_value_list(values, max_characters, false))
-                }
-                Err(_e) => {
-                    // print as text
-                    DumpValue::Invalid(format_value_list(values, max_characters, true))
-                }
-            }
-        }
-        (Strs(values), VR::DT) => {
-            match value.to_multi_datetime(dicom_core::chrono::FixedOffset::east(0)) {
-                Ok(values) => {
-                    // print as reformatted date
-                    DumpValue::DateTime(format_value_list(values, max_characters, false))
-                }
-                Err(_e) => {
-                    // print as text
-                    DumpValue::Invalid(format_value_list(values, max_characters, true))
-                }
+                // print as text
+                None => DumpValue::Invalid(format_value_list(
+                    value.to_multi_str().iter(),
+                    max_characters,
+                    true,
+                )),
             }
         }
+        (Str(_) | Strs(_) | SharedStrs(_), VR::PN | VR::DS) => DumpValue::Str(format_value_list(
+            format_values(formatter, value, vr).unwrap_or_default(),
+            max_characters,
+            true,
+        )),
         (Strs(values), _) => DumpValue::Str(format_value_list(
             values
                 .iter()
@@ -735,11 +975,18 @@ fn value_summary(
             max_characters,
             true,
         )),
-        (Date(values), _) => DumpValue::DateTime(format_value_list(values, max_characters, true)),
-        (Time(values), _) => DumpValue::DateTime(format_value_list(values, max_characters, true)),
-        (DateTime(values), _) => {
-            DumpValue::DateTime(format_value_list(values, max_characters, true))
-        }
+        (SharedStrs(values), _) => DumpValue::Str(format_value_list(
+            values
+                .iter()
+                .map(|s| s.trim_end_matches(whitespace_or_null)),
+            max_characters,
+            true,
+        )),
+        (Date(_) | Time(_) | DateTime(_), _) => DumpValue::DateTime(format_value_list(
+            format_values(formatter, value, vr).unwrap_or_default(),
+            max_characters,
+            true,
+        )),
         (Str(value), _) => {
             let txt = format!(
                 "\"{}\"",
@@ -847,12 +1094,14 @@ fn determine_width(user_width: Option<u32>) -> u32 {
 #[cfg(test)]
 mod tests {
 
-    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_core::value::Value;
+    use dicom_core::{DataElement, Length, PrimitiveValue, VR};
     use dicom_dictionary_std::tags;
     use dicom_object::{FileMetaTableBuilder, InMemDicomObject};
 
     use super::whitespace_or_null;
-    use crate::{ColorMode, DumpOptions};
+    use crate::format::ValueFormat;
+    use crate::{ColorMode, DumpFormat, DumpOptions};
 
     #[test]
     fn trims_all_whitespace() {
@@ -893,7 +1142,7 @@ mod tests {
             .collect();
         assert_eq!(
             lines[0],
-            "Media Storage SOP Class UID: 1.2.840.10008.5.1.4.1.1.1"
+            "Media Storage SOP Class UID: 1.2.840.10008.5.1.4.1.1.1 (Computed Radiography Image Storage)"
         );
         assert_eq!(lines[1], "Media Storage SOP Instance UID: 1.2.888.123");
         assert_eq!(
@@ -935,4 +1184,119 @@ mod tests {
 
         assert_eq!(&parts[..3], &["(0008,0018)", "SOPInstanceUID", "UI"]);
     }
+
+    fn nested_object() -> InMemDicomObject {
+        let item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from("1.2.888.456"),
+        )]);
+        InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.888.123"),
+            ),
+            DataElement::new(
+                tags::REFERENCED_IMAGE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![item].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn dump_object_to_respects_max_depth() {
+        let obj = nested_object();
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .color_mode(ColorMode::Never)
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        let out = String::from_utf8(out).expect("output is not valid UTF-8");
+        assert!(out.contains("ReferencedSOPInstanceUID"));
+        assert!(out.contains("SequenceDelimitationItem"));
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .color_mode(ColorMode::Never)
+            .max_depth(0)
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        let out = String::from_utf8(out).expect("output is not valid UTF-8");
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[1].contains("ReferencedImageSequence"));
+        assert!(lines[1].contains("(1 Item)"));
+    }
+
+    #[test]
+    fn dump_object_to_excludes_tags() {
+        let obj = nested_object();
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .color_mode(ColorMode::Never)
+            .exclude_tags("(0008,1155)".parse().unwrap())
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        let out = String::from_utf8(out).expect("output is not valid UTF-8");
+        assert!(out.contains("SOPInstanceUID"));
+        assert!(out.contains("ReferencedImageSequence"));
+        assert!(!out.contains("ReferencedSOPInstanceUID"));
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .color_mode(ColorMode::Never)
+            .exclude_tags("(0008,xxxx)".parse().unwrap())
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn dump_object_to_with_value_format() {
+        let obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(tags::STUDY_DATE, VR::DA, PrimitiveValue::from("20221003")),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+        ]);
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .color_mode(ColorMode::Never)
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        let out = String::from_utf8(out).expect("output is not valid UTF-8");
+        assert!(out.contains("2022-10-03"));
+        assert!(out.contains("\"Doe^John\""));
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .color_mode(ColorMode::Never)
+            .value_format(ValueFormat::human())
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        let out = String::from_utf8(out).expect("output is not valid UTF-8");
+        assert!(out.contains("3 Oct 2022"));
+        assert!(out.contains("\"Doe, John\""));
+    }
+
+    #[test]
+    fn dump_object_to_json() {
+        let obj = nested_object();
+
+        let mut out = Vec::new();
+        DumpOptions::new()
+            .format(DumpFormat::Json)
+            .dump_object_to(&mut out, &obj)
+            .unwrap();
+        let out = String::from_utf8(out).expect("output is not valid UTF-8");
+        assert!(out.starts_with('{'));
+        assert!(out.contains(r#""00080018""#));
+        assert!(out.contains(r#""1.2.888.456""#));
+    }
 }
@@ -1,6 +1,8 @@
 //! A CLI tool for inspecting the contents of a DICOM file
 //! by printing it in a human readable format.
-use dicom_dump::{ColorMode, DumpOptions};
+use dicom_core::dictionary::TagSet;
+use dicom_dump::format::{PersonNameStyle, ValueFormat};
+use dicom_dump::{ColorMode, DumpFormat, DumpOptions};
 use dicom_object::open_file;
 use snafu::{whatever, ErrorCompat, Whatever};
 use std::io::ErrorKind;
@@ -40,9 +42,37 @@ struct App {
     /// (default is to check automatically)
     #[structopt(short = "w", long = "width")]
     width: Option<u32>,
+    /// The maximum sequence nesting level to print
+    /// (default is to print all levels)
+    #[structopt(short = "d", long = "depth")]
+    depth: Option<u32>,
+    /// The tags of the elements to leave out,
+    /// separated by spaces or semicolons
+    /// (any digit can be `x`, as in "60xx,3000")
+    #[structopt(short = "x", long = "exclude")]
+    exclude: Option<TagSet>,
+    /// Present dates, times, and person names in a friendlier form
+    /// (e.g. "3 Oct 2022", "Doe, John")
+    #[structopt(long = "human")]
+    human: bool,
+    /// The format of dates, in strftime syntax (e.g. "%d/%m/%Y")
+    #[structopt(long = "date-format")]
+    date_format: Option<String>,
+    /// The format of times, in strftime syntax (e.g. "%H:%M")
+    #[structopt(long = "time-format")]
+    time_format: Option<String>,
+    /// How to present person names (dicom, family, or given)
+    #[structopt(long = "pn")]
+    person_name_style: Option<PersonNameStyle>,
+    /// The number of decimal places to show in decimal numbers
+    #[structopt(long = "precision")]
+    precision: Option<usize>,
     /// The color mode
     #[structopt(long = "color", default_value = "auto")]
     color: ColorMode,
+    /// The output format (main or json)
+    #[structopt(short = "f", long = "format", default_value = "main")]
+    format: DumpFormat,
     /// Fail if any errors are encountered
     #[structopt(long = "fail-first")]
     fail_first: bool,
@@ -65,7 +95,15 @@ fn run() -> Result<(), Whatever> {
         no_text_limit,
         no_limit,
         width,
+        depth,
+        exclude,
+        human,
+        date_format,
+        time_format,
+        person_name_style,
+        precision,
         color,
+        format,
         fail_first,
     } = App::from_args();
 
@@ -78,12 +116,40 @@ fn run() -> Result<(), Whatever> {
         .no_text_limit(no_text_limit)
         .no_limit(no_limit)
         .width(width)
-        .color_mode(color);
+        .color_mode(color)
+        .format(format);
+    if let Some(depth) = depth {
+        options.max_depth(depth);
+    }
+    if let Some(exclude) = exclude {
+        options.exclude_tags(exclude);
+    }
+
+    let mut value_format = if human {
+        ValueFormat::human()
+    } else {
+        ValueFormat::new()
+    };
+    if let Some(date_format) = date_format {
+        value_format = value_format.date_format(date_format);
+    }
+    if let Some(time_format) = time_format {
+        value_format = value_format.time_format(time_format);
+    }
+    if let Some(style) = person_name_style {
+        value_format = value_format.person_name_style(style);
+    }
+    if let Some(precision) = precision {
+        value_format = value_format.decimal_precision(precision);
+    }
+    options.value_format(value_format);
     let fail_first = filenames.len() == 1 || fail_first;
     let mut errors: i32 = 0;
 
     for filename in &filenames {
-        println!("{}: ", filename.display());
+        if format == DumpFormat::Main {
+            println!("{}: ", filename.display());
+        }
         match open_file(filename) {
             Err(e) => {
                 report(e);
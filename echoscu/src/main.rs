@@ -15,7 +15,8 @@ use tracing::warn;
 /// DICOM C-ECHO SCU
 #[derive(Debug, StructOpt)]
 struct App {
-    /// socket address to SCP (example: "127.0.0.1:104")
+    /// socket address to SCP,
+    /// optionally with the called AE title (example: "ANY-SCP@127.0.0.1:104")
     addr: String,
     /// verbose mode
     #[structopt(short = "v", long = "verbose")]
@@ -26,7 +27,7 @@ struct App {
     /// the calling AE title
     #[structopt(long = "calling-ae-title", default_value = "ECHOSCU")]
     calling_ae_title: String,
-    /// the called AE title
+    /// the called AE title, if not specified in the address
     #[structopt(long = "called-ae-title", default_value = "ANY-SCP")]
     called_ae_title: String,
 }
@@ -82,7 +83,7 @@ fn run() -> Result<(), Whatever> {
         .with_abstract_syntax("1.2.840.10008.1.1")
         .calling_ae_title(calling_ae_title)
         .called_ae_title(called_ae_title)
-        .establish(&addr)
+        .establish_with_str(&addr)
         .whatever_context("Could not establish association with SCP")?;
 
     let pc = association
@@ -181,7 +182,7 @@ fn run() -> Result<(), Whatever> {
 
             if message_id
                 != msg_id_elem
-                    .to_int()
+                    .to_int::<u16>()
                     .whatever_context("Message ID is not a valid integer")?
             {
                 whatever!("Message ID mismatch");
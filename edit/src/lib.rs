@@ -0,0 +1,440 @@
+//! DICOM file editing library
+//!
+//! This is a helper library
+//! for setting, deleting, or renaming the data elements
+//! of DICOM objects and files.
+//!
+//! Edits are described by the [`Edit`] type
+//! and applied to an object via [`apply_edits`],
+//! or to a file on disk via [`modify_file`].
+//! When editing files,
+//! the file meta group is updated to reflect the edited object,
+//! and the original file is only replaced
+//! once the edited file was written in full.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_dictionary_std::tags;
+//! use dicom_edit::{modify_file, Edit};
+//!
+//! modify_file(
+//!     "path/to/file.dcm",
+//!     &[
+//!         Edit::set(tags::PATIENT_NAME, "Doe^John"),
+//!         Edit::delete(tags::PATIENT_BIRTH_DATE),
+//!     ],
+//! )?;
+//! # Result::<(), Box<dyn std::error::Error>>::Ok(())
+//! ```
+use std::path::{Path, PathBuf};
+
+use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, C};
+use dicom_core::{DataElement, Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_object::mem::InMemElement;
+use dicom_object::{
+    open_file, DefaultDicomObject, FileMetaTableBuilder, InMemDicomObject, StandardDataDictionary,
+};
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("unknown attribute `{}`", expr))]
+    UnknownAttribute { expr: String, backtrace: Backtrace },
+
+    #[snafu(display("unknown value representation for element {}", tag))]
+    UnknownVr { tag: Tag, backtrace: Backtrace },
+
+    #[snafu(display("cannot set the value of {} element {} from text", vr, tag))]
+    UnsupportedVr {
+        tag: Tag,
+        vr: VR,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("invalid value `{}` for {} element {}", value, vr, tag))]
+    InvalidValue {
+        tag: Tag,
+        vr: VR,
+        value: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("cannot edit element {} of the file meta group", tag))]
+    MetaGroupElement { tag: Tag, backtrace: Backtrace },
+
+    #[snafu(display("missing element {} to rename", tag))]
+    MissingElement { tag: Tag, backtrace: Backtrace },
+
+    #[snafu(display("failed to open DICOM file {}", path.display()))]
+    OpenFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    /// failed to update the file meta group
+    UpdateMeta {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("failed to write DICOM file {}", path.display()))]
+    WriteFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("failed to replace file {}", path.display()))]
+    ReplaceFile {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A modification to a DICOM object.
+#[derive(Debug, Clone)]
+pub enum Edit {
+    /// Set the value of an element from its textual form,
+    /// creating the element if it does not exist.
+    ///
+    /// The value representation of an existing element is kept.
+    /// Otherwise, it is looked up in the standard data dictionary.
+    /// Multiple values are separated by a backslash (`\`).
+    Set { tag: Tag, value: String },
+    /// Insert the given element,
+    /// replacing any element with the same tag.
+    Put(InMemElement),
+    /// Remove an element, if it exists.
+    Delete(Tag),
+    /// Move an existing element to another tag,
+    /// replacing any element with the new tag.
+    /// The value representation and value are kept as is.
+    Rename { from: Tag, to: Tag },
+}
+
+impl Edit {
+    /// Create an edit which sets the value of an element
+    /// from its textual form.
+    pub fn set(tag: Tag, value: impl Into<String>) -> Self {
+        Edit::Set {
+            tag,
+            value: value.into(),
+        }
+    }
+
+    /// Create an edit which inserts the given element.
+    pub fn put(element: InMemElement) -> Self {
+        Edit::Put(element)
+    }
+
+    /// Create an edit which removes an element.
+    pub fn delete(tag: Tag) -> Self {
+        Edit::Delete(tag)
+    }
+
+    /// Create an edit which moves an element to another tag.
+    pub fn rename(from: Tag, to: Tag) -> Self {
+        Edit::Rename { from, to }
+    }
+
+    /// Retrieve the tags affected by this edit.
+    fn tags(&self) -> impl Iterator<Item = Tag> {
+        let (first, second) = match self {
+            Edit::Set { tag, .. } | Edit::Delete(tag) => (*tag, None),
+            Edit::Put(elem) => (elem.tag(), None),
+            Edit::Rename { from, to } => (*from, Some(*to)),
+        };
+        std::iter::once(first).chain(second)
+    }
+}
+
+/// Resolve an attribute by keyword (such as `PatientName`)
+/// or by tag in one of the forms `(gggg,eeee)`, `gggg,eeee`, or `ggggeeee`.
+pub fn parse_tag(expr: &str) -> Result<Tag> {
+    StandardDataDictionary
+        .parse_tag(expr)
+        .context(UnknownAttributeSnafu { expr })
+}
+
+/// Apply the given edits to a DICOM object, in order.
+///
+/// Edits to the file meta group (group 0002) are rejected,
+/// in which case the object is left untouched.
+pub fn apply_edits(obj: &mut InMemDicomObject, edits: &[Edit]) -> Result<()> {
+    for tag in edits.iter().flat_map(Edit::tags) {
+        ensure!(tag.group() != 0x0002, MetaGroupElementSnafu { tag });
+    }
+
+    for edit in edits {
+        match edit {
+            Edit::Set { tag, value } => {
+                let vr = match obj.element_opt(*tag).ok().flatten() {
+                    Some(elem) => elem.vr(),
+                    None => dictionary_vr(*tag)?,
+                };
+                let value = parse_value(*tag, vr, value)?;
+                obj.put(DataElement::new(*tag, vr, value));
+            }
+            Edit::Put(elem) => {
+                obj.put(elem.clone());
+            }
+            Edit::Delete(tag) => {
+                obj.remove_element(*tag);
+            }
+            Edit::Rename { from, to } => {
+                let elem = obj
+                    .take_element(*from)
+                    .ok()
+                    .context(MissingElementSnafu { tag: *from })?;
+                let vr = elem.vr();
+                obj.put(DataElement::new(*to, vr, elem.into_value()));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply the given edits to a DICOM file object, in order,
+/// and update its file meta group accordingly.
+///
+/// The media storage SOP class and instance UIDs
+/// are synchronized with the SOP class and instance UIDs of the object,
+/// and the group length is recalculated.
+pub fn edit_file(obj: DefaultDicomObject, edits: &[Edit]) -> Result<DefaultDicomObject> {
+    let meta = obj.meta().clone();
+    let mut obj = obj.into_inner();
+    apply_edits(&mut obj, edits)?;
+
+    let sop_class_uid = obj
+        .get_string(tags::SOP_CLASS_UID)
+        .map(|uid| uid.trim_end_matches(|c| c == '\0' || c == ' ').to_string())
+        .unwrap_or(meta.media_storage_sop_class_uid);
+    let mut builder = FileMetaTableBuilder::new()
+        .information_version(meta.information_version)
+        .media_storage_sop_class_uid(sop_class_uid)
+        .media_storage_sop_instance_uid(meta.media_storage_sop_instance_uid)
+        .transfer_syntax(meta.transfer_syntax)
+        .implementation_class_uid(meta.implementation_class_uid);
+    if let Some(name) = meta.implementation_version_name {
+        builder = builder.implementation_version_name(name);
+    }
+    if let Some(ae_title) = meta.source_application_entity_title {
+        builder = builder.source_application_entity_title(ae_title);
+    }
+    if let Some(ae_title) = meta.sending_application_entity_title {
+        builder = builder.sending_application_entity_title(ae_title);
+    }
+    if let Some(ae_title) = meta.receiving_application_entity_title {
+        builder = builder.receiving_application_entity_title(ae_title);
+    }
+    if let Some(uid) = meta.private_information_creator_uid {
+        builder = builder.private_information_creator_uid(uid);
+    }
+    if let Some(data) = meta.private_information {
+        builder = builder.private_information(data);
+    }
+
+    obj.with_meta(builder).context(UpdateMetaSnafu)
+}
+
+/// Apply the given edits to the DICOM file at the given path, in order.
+///
+/// The edited file is first written to a temporary file
+/// in the same directory,
+/// which then replaces the original file.
+/// The original file is left untouched if any step fails.
+pub fn modify_file(path: impl AsRef<Path>, edits: &[Edit]) -> Result<()> {
+    let path = path.as_ref();
+    let obj = open_file(path).context(OpenFileSnafu { path })?;
+    let obj = edit_file(obj, edits)?;
+
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(".tmp");
+    let tmp_path = path.with_file_name(tmp_name);
+
+    if let Err(e) = obj.write_to_file(&tmp_path) {
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(e).context(WriteFileSnafu { path: tmp_path });
+    }
+    std::fs::rename(&tmp_path, path)
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&tmp_path);
+            e
+        })
+        .context(ReplaceFileSnafu { path })
+}
+
+/// Look up the value representation of an attribute
+/// in the standard data dictionary.
+fn dictionary_vr(tag: Tag) -> Result<VR> {
+    StandardDataDictionary
+        .by_tag(tag)
+        .map(DictionaryEntry::vr)
+        .context(UnknownVrSnafu { tag })
+}
+
+/// Convert the textual form of a value into a primitive value
+/// of the given value representation.
+fn parse_value(tag: Tag, vr: VR, text: &str) -> Result<PrimitiveValue> {
+    fn parse_all<T: std::str::FromStr>(text: &str) -> Option<C<T>> {
+        text.split('\\').map(|v| v.trim().parse().ok()).collect()
+    }
+
+    let invalid = || InvalidValueSnafu {
+        tag,
+        vr,
+        value: text,
+    };
+    Ok(match vr {
+        VR::LT | VR::ST | VR::UT | VR::UR => PrimitiveValue::Str(text.to_string()),
+        VR::AE
+        | VR::AS
+        | VR::CS
+        | VR::DA
+        | VR::DS
+        | VR::DT
+        | VR::IS
+        | VR::LO
+        | VR::PN
+        | VR::SH
+        | VR::TM
+        | VR::UC
+        | VR::UI => {
+            if text.contains('\\') {
+                PrimitiveValue::Strs(text.split('\\').map(str::to_string).collect())
+            } else {
+                PrimitiveValue::Str(text.to_string())
+            }
+        }
+        VR::US => PrimitiveValue::U16(parse_all(text).with_context(invalid)?),
+        VR::SS => PrimitiveValue::I16(parse_all(text).with_context(invalid)?),
+        VR::UL => PrimitiveValue::U32(parse_all(text).with_context(invalid)?),
+        VR::SL => PrimitiveValue::I32(parse_all(text).with_context(invalid)?),
+        VR::UV => PrimitiveValue::U64(parse_all(text).with_context(invalid)?),
+        VR::SV => PrimitiveValue::I64(parse_all(text).with_context(invalid)?),
+        VR::FL => PrimitiveValue::F32(parse_all(text).with_context(invalid)?),
+        VR::FD => PrimitiveValue::F64(parse_all(text).with_context(invalid)?),
+        VR::AT => PrimitiveValue::Tags(
+            text.split('\\')
+                .map(|v| parse_tag(v).ok())
+                .collect::<Option<_>>()
+                .with_context(invalid)?,
+        ),
+        _ => return UnsupportedVrSnafu { tag, vr }.fail(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_object() -> DefaultDicomObject {
+        InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.1"),
+            ),
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.888.123"),
+            ),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(Tag(0x0009, 0x1010), VR::LO, PrimitiveValue::from("private")),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn set_delete_and_rename() {
+        let mut obj = test_object().into_inner();
+        apply_edits(
+            &mut obj,
+            &[
+                Edit::set(tags::PATIENT_NAME, "Doe^Jane"),
+                Edit::set(tags::ROWS, "512"),
+                Edit::set(tags::PIXEL_SPACING, "0.5\\0.5"),
+                Edit::delete(tags::SOP_CLASS_UID),
+                Edit::rename(Tag(0x0009, 0x1010), Tag(0x0011, 0x1010)),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(obj.get_string(tags::PATIENT_NAME).unwrap(), "Doe^Jane");
+        assert_eq!(obj.get_u16(tags::ROWS).unwrap(), 512);
+        assert_eq!(obj.element(tags::PIXEL_SPACING).unwrap().vr(), VR::DS);
+        assert!(obj.element_opt(tags::SOP_CLASS_UID).unwrap().is_none());
+        assert!(obj.element_opt(Tag(0x0009, 0x1010)).unwrap().is_none());
+        assert_eq!(obj.get_string(Tag(0x0011, 0x1010)).unwrap(), "private");
+    }
+
+    #[test]
+    fn reject_invalid_edits() {
+        let mut obj = test_object().into_inner();
+        assert!(matches!(
+            apply_edits(&mut obj, &[Edit::set(tags::ROWS, "many")]),
+            Err(Error::InvalidValue { .. })
+        ));
+        assert!(matches!(
+            apply_edits(
+                &mut obj,
+                &[
+                    Edit::delete(tags::PATIENT_NAME),
+                    Edit::set(tags::TRANSFER_SYNTAX_UID, "1.2.840.10008.1.2"),
+                ]
+            ),
+            Err(Error::MetaGroupElement { .. })
+        ));
+        // nothing was applied
+        assert!(obj.element_opt(tags::PATIENT_NAME).unwrap().is_some());
+        assert!(matches!(
+            apply_edits(&mut obj, &[Edit::rename(tags::ROWS, tags::COLUMNS)]),
+            Err(Error::MissingElement { .. })
+        ));
+        assert!(matches!(
+            parse_tag("NotAnAttribute"),
+            Err(Error::UnknownAttribute { .. })
+        ));
+        assert_eq!(parse_tag("(0010,0010)").unwrap(), tags::PATIENT_NAME);
+    }
+
+    #[test]
+    fn edit_file_updates_meta() {
+        let obj = test_object();
+        let group_length = obj.meta().information_group_length;
+
+        let obj = edit_file(
+            obj,
+            &[
+                Edit::set(tags::SOP_INSTANCE_UID, "1.2.888.123456"),
+                Edit::set(tags::SOP_CLASS_UID, "1.2.840.10008.5.1.4.1.1.2"),
+            ],
+        )
+        .unwrap();
+        let meta = obj.meta();
+        assert_eq!(
+            meta.media_storage_sop_instance_uid.trim_end_matches('\0'),
+            "1.2.888.123456"
+        );
+        assert_eq!(
+            meta.media_storage_sop_class_uid.trim_end_matches('\0'),
+            "1.2.840.10008.5.1.4.1.1.2"
+        );
+        assert_eq!(meta.information_group_length, group_length + 2);
+    }
+}
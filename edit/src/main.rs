@@ -0,0 +1,101 @@
+//! A CLI tool for editing the data elements of DICOM files in place.
+use dicom_edit::{modify_file, parse_tag, Edit};
+use snafu::{whatever, ErrorCompat, OptionExt, ResultExt, Whatever};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Set, delete, or rename data elements in DICOM files
+#[derive(Debug, StructOpt)]
+struct App {
+    /// The DICOM file(s) to edit
+    #[structopt(required = true)]
+    files: Vec<PathBuf>,
+    /// Set the value of an element, creating it if missing
+    /// (e.g. `PatientName=Doe^John`)
+    #[structopt(short = "s", long = "set")]
+    set: Vec<String>,
+    /// Delete an element (e.g. `PatientBirthDate`)
+    #[structopt(short = "d", long = "delete")]
+    delete: Vec<String>,
+    /// Move an element to another tag
+    /// (e.g. `(0009,1010)=(0011,1010)`)
+    #[structopt(short = "r", long = "rename")]
+    rename: Vec<String>,
+}
+
+fn main() {
+    run().unwrap_or_else(|e| {
+        report(e);
+        std::process::exit(-2);
+    });
+}
+
+fn run() -> Result<(), Whatever> {
+    let App {
+        files,
+        set,
+        delete,
+        rename,
+    } = App::from_args();
+
+    let mut edits = Vec::new();
+    for expr in &rename {
+        let (from, to) = split_assignment(expr)?;
+        edits.push(Edit::rename(attribute(from)?, attribute(to)?));
+    }
+    for expr in &set {
+        let (attr, value) = split_assignment(expr)?;
+        edits.push(Edit::set(attribute(attr)?, value));
+    }
+    for expr in &delete {
+        edits.push(Edit::delete(attribute(expr)?));
+    }
+    if edits.is_empty() {
+        whatever!("No edits were given");
+    }
+
+    let mut errors: i32 = 0;
+    for file in &files {
+        if let Err(e) = modify_file(file, &edits) {
+            report(e);
+            errors += 1;
+        }
+    }
+
+    std::process::exit(errors);
+}
+
+/// Split an expression of the form `ATTRIBUTE=VALUE`.
+fn split_assignment(expr: &str) -> Result<(&str, &str), Whatever> {
+    expr.split_once('=')
+        .with_whatever_context(|| format!("Expected `ATTRIBUTE=VALUE`, got `{}`", expr))
+}
+
+fn attribute(expr: &str) -> Result<dicom_core::Tag, Whatever> {
+    parse_tag(expr).whatever_context("Invalid attribute")
+}
+
+fn report<E: 'static>(err: E)
+where
+    E: std::error::Error,
+    E: ErrorCompat,
+{
+    eprintln!("[ERROR] {}", err);
+    if let Some(source) = err.source() {
+        eprintln!();
+        eprintln!("Caused by:");
+        for (i, e) in std::iter::successors(Some(source), |e| e.source()).enumerate() {
+            eprintln!("   {}: {}", i, e);
+        }
+    }
+
+    let env_backtrace = std::env::var("RUST_BACKTRACE").unwrap_or_default();
+    let env_lib_backtrace = std::env::var("RUST_LIB_BACKTRACE").unwrap_or_default();
+    if env_lib_backtrace == "1" || (env_backtrace == "1" && env_lib_backtrace != "0") {
+        if let Some(backtrace) = ErrorCompat::backtrace(&err) {
+            eprintln!();
+            eprintln!("Backtrace:");
+            eprintln!("{}", backtrace);
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Benchmarks for decoding data element headers.
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
+use dicom_encoding::decode::explicit_le::ExplicitVRLittleEndianDecoder;
+use dicom_encoding::decode::implicit_le::ImplicitVRLittleEndianDecoder;
+use dicom_encoding::Decode;
+
+/// Number of headers in each encoded sequence.
+const COUNT: usize = 1024;
+
+/// Header in explicit VR little endian with a 2-byte length:
+/// PatientName, PN, 8 bytes.
+const EXPLICIT_LE_SHORT_HEADER: [u8; 8] = [0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00];
+
+/// Header in explicit VR little endian with a 4-byte length:
+/// PixelData, OB, 512 bytes.
+const EXPLICIT_LE_LONG_HEADER: [u8; 12] = [
+    0xE0, 0x7F, 0x10, 0x00, b'O', b'B', 0x00, 0x00, 0x00, 0x02, 0x00, 0x00,
+];
+
+/// Headers in implicit VR little endian:
+/// PatientName (8 bytes) and PixelData (512 bytes).
+const IMPLICIT_LE_HEADERS: [[u8; 8]; 2] = [
+    [0x10, 0x00, 0x10, 0x00, 0x08, 0x00, 0x00, 0x00],
+    [0xE0, 0x7F, 0x10, 0x00, 0x00, 0x02, 0x00, 0x00],
+];
+
+fn bench_decode_header(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_header");
+    group.throughput(Throughput::Elements(COUNT as u64));
+
+    let explicit_data: Vec<u8> = (0..COUNT / 2)
+        .flat_map(|_| {
+            EXPLICIT_LE_SHORT_HEADER
+                .iter()
+                .chain(&EXPLICIT_LE_LONG_HEADER)
+                .copied()
+        })
+        .collect();
+    let decoder = ExplicitVRLittleEndianDecoder::default();
+    group.bench_function("explicit_vr_le", |b| {
+        b.iter(|| {
+            let mut source = &explicit_data[..];
+            for _ in 0..COUNT {
+                black_box(decoder.decode_header(&mut source).unwrap());
+            }
+        })
+    });
+
+    let implicit_data: Vec<u8> = (0..COUNT / 2)
+        .flat_map(|_| IMPLICIT_LE_HEADERS.iter().flatten().copied())
+        .collect();
+    let decoder = ImplicitVRLittleEndianDecoder::default();
+    group.bench_function("implicit_vr_le", |b| {
+        b.iter(|| {
+            let mut source = &implicit_data[..];
+            for _ in 0..COUNT {
+                black_box(decoder.decode_header(&mut source).unwrap());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode_header);
+criterion_main!(benches);
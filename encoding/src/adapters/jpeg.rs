@@ -1,9 +1,9 @@
 //! Support for JPG image decoding.
 
-use super::MissingAttributeSnafu;
+use super::{frame_size, FrameOutOfRangeSnafu, MissingAttributeSnafu};
 use crate::adapters::{DecodeResult, PixelDataObject, PixelRWAdapter};
 use jpeg_decoder::Decoder;
-use snafu::{whatever, OptionExt, ResultExt};
+use snafu::{ensure, whatever, OptionExt, ResultExt};
 use std::io::Cursor;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -73,4 +73,87 @@ impl PixelRWAdapter for JPEGAdapter {
 
         Ok(())
     }
+
+    /// Decode a single frame of DICOM image data with jpeg encoding.
+    ///
+    /// When the fragments of the frame can be resolved,
+    /// such as through an offset table,
+    /// or each frame is encoded in its own fragment,
+    /// only those fragments are decoded.
+    /// Otherwise, the preceding frames are decoded and skipped.
+    fn decode_frame(
+        &self,
+        src: &dyn PixelDataObject,
+        frame: u32,
+        dst: &mut Vec<u8>,
+    ) -> DecodeResult<()> {
+        let bits_allocated = src.bits_allocated().context(MissingAttributeSnafu {
+            name: "BitsAllocated",
+        })?;
+
+        if bits_allocated != 8 && bits_allocated != 16 {
+            whatever!("BitsAllocated other than 8 or 16 is not supported");
+        }
+
+        let nr_frames = src.number_of_frames().unwrap_or(1) as u32;
+        ensure!(frame < nr_frames, FrameOutOfRangeSnafu { frame });
+        let frame_size = frame_size(src)?;
+
+        let nr_fragments = src
+            .number_of_fragments()
+            .whatever_context("Invalid pixel data, no fragments found")?;
+
+        let (data, skip) = if let Some(fragments) = src.frame_fragments(frame) {
+            // the fragments of the frame are known (e.g. via an offset table)
+            let mut data = Vec::new();
+            for i in fragments {
+                let fragment = src
+                    .fragment(i)
+                    .whatever_context("No pixel data found for frame")?;
+                data.extend_from_slice(&fragment);
+            }
+            (data, 0)
+        } else if nr_fragments == nr_frames {
+            // one fragment per frame
+            let fragment = src
+                .fragment(frame as usize)
+                .whatever_context("No pixel data found for frame")?;
+            (fragment, 0)
+        } else {
+            let fragments: Vec<u8> = src
+                .raw_pixel_data()
+                .whatever_context("Expected to have raw pixel data available")?
+                .fragments
+                .into_iter()
+                .flatten()
+                .collect();
+            (fragments, frame)
+        };
+
+        let data_len = data.len() as u64;
+        let mut cursor = Cursor::new(data);
+        for _ in 0..skip {
+            Decoder::new(&mut cursor)
+                .decode()
+                .map_err(|e| Box::new(e) as Box<_>)
+                .whatever_context("JPEG decoder failure")?;
+            // skip padding, as above
+            if cursor.position() % 2 > 0 {
+                cursor.set_position(cursor.position() + 1);
+            }
+            if cursor.position() >= data_len {
+                return FrameOutOfRangeSnafu { frame }.fail();
+            }
+        }
+
+        let decoded = Decoder::new(&mut cursor)
+            .decode()
+            .map_err(|e| Box::new(e) as Box<_>)
+            .whatever_context("JPEG decoder failure")?;
+        dst.clear();
+        dst.extend_from_slice(&decoded);
+        dst.resize(frame_size, 0);
+
+        Ok(())
+    }
 }
@@ -1,7 +1,8 @@
 //! Module for built-in pixel data adapters.
 
 use dicom_core::value::C;
-use snafu::Snafu;
+use snafu::{OptionExt, Snafu};
+use std::ops::Range;
 
 pub mod jpeg;
 pub mod rle_lossless;
@@ -28,6 +29,10 @@ pub enum DecodeError {
     /// A required attribute is missing from the DICOM
     #[snafu(display("Missing required attribute: {}", name))]
     MissingAttribute { name: &'static str },
+
+    /// The requested frame is out of the range of available frames
+    #[snafu(display("Frame #{} is out of range", frame))]
+    FrameOutOfRange { frame: u32 },
 }
 
 /// Error conditions when encoding pixel data.
@@ -98,6 +103,67 @@ pub trait PixelDataObject {
     /// or byte fragments if encapsulated.
     /// Returns None if no pixel data is found
     fn raw_pixel_data(&self) -> Option<RawPixelData>;
+
+    /// Return the range of fragment indices
+    /// which make up the given frame of encapsulated pixel data,
+    /// usually resolved with the help of
+    /// the Extended Offset Table or the Basic Offset Table,
+    /// so that the frame can be retrieved
+    /// without going through the preceding fragments.
+    ///
+    /// Returns None if the frame cannot be resolved this way,
+    /// or if the pixel data is not encapsulated.
+    /// The default implementation always returns None.
+    #[allow(unused_variables)]
+    fn frame_fragments(&self, frame: u32) -> Option<Range<usize>> {
+        None
+    }
+}
+
+/// Determine the range of fragments of encapsulated pixel data
+/// which make up the given frame,
+/// using an offset table.
+///
+/// `offset_table` contains the byte offset of the first fragment of each frame,
+/// relative to the first fragment,
+/// as found in the Basic Offset Table
+/// or the Extended Offset Table (7FE0,0001).
+/// Offsets refer to the start of each fragment item,
+/// so they account for the 8 bytes of each item header.
+/// `fragment_lengths` are the lengths of all fragments, in order.
+///
+/// Returns None if the frame is out of range
+/// or the offsets do not match the boundaries of any fragment.
+pub fn frame_fragment_range<I>(
+    offset_table: &[u64],
+    fragment_lengths: I,
+    frame: u32,
+) -> Option<Range<usize>>
+where
+    I: IntoIterator<Item = u64>,
+{
+    let start_offset = *offset_table.get(frame as usize)?;
+    let end_offset = offset_table.get(frame as usize + 1).copied();
+
+    let mut start = None;
+    let mut position = 0;
+    let mut count = 0;
+    for (i, len) in fragment_lengths.into_iter().enumerate() {
+        if position == start_offset {
+            start = Some(i);
+        }
+        if Some(position) == end_offset {
+            return Some(start?..i);
+        }
+        // item tag and item length
+        position += 8 + len;
+        count = i + 1;
+    }
+
+    match end_offset {
+        None => Some(start?..count),
+        Some(_) => None,
+    }
 }
 
 /// Custom options when encoding pixel data into an encapsulated form.
@@ -159,6 +225,39 @@ pub trait PixelRWAdapter {
     /// (planar configuration of 0).
     fn decode(&self, src: &dyn PixelDataObject, dst: &mut Vec<u8>) -> DecodeResult<()>;
 
+    /// Decode a single frame of the given DICOM object
+    /// containing encapsulated pixel data
+    /// into native pixel data as a byte stream in little endian,
+    /// resizing the given vector `dst` to contain exactly these bytes.
+    ///
+    /// The frame index starts at 0.
+    /// The same preconditions and output format of [`decode`](Self::decode)
+    /// apply, and a `FrameOutOfRange` error is returned
+    /// if the object does not have the requested frame.
+    ///
+    /// The default implementation decodes all frames
+    /// and retains the bytes of the requested one.
+    /// Adapters which can decode each frame independently
+    /// should override this method.
+    fn decode_frame(
+        &self,
+        src: &dyn PixelDataObject,
+        frame: u32,
+        dst: &mut Vec<u8>,
+    ) -> DecodeResult<()> {
+        let frame_size = frame_size(src)?;
+        let mut data = Vec::new();
+        self.decode(src, &mut data)?;
+
+        let start = frame_size * frame as usize;
+        let frame_data = data
+            .get(start..start + frame_size)
+            .context(FrameOutOfRangeSnafu { frame })?;
+        dst.clear();
+        dst.extend_from_slice(frame_data);
+        Ok(())
+    }
+
     /// Encode a DICOM object's image into the format supported by this adapter,
     /// writing a byte stream of pixel data fragment values
     /// into the given destination.
@@ -183,6 +282,22 @@ pub trait PixelRWAdapter {
     }
 }
 
+/// Calculate the size of a single decoded frame in bytes,
+/// based on the image properties of the given object.
+pub(crate) fn frame_size(src: &dyn PixelDataObject) -> DecodeResult<usize> {
+    let cols = src
+        .cols()
+        .context(MissingAttributeSnafu { name: "Columns" })?;
+    let rows = src.rows().context(MissingAttributeSnafu { name: "Rows" })?;
+    let samples_per_pixel = src.samples_per_pixel().context(MissingAttributeSnafu {
+        name: "SamplesPerPixel",
+    })?;
+    let bits_allocated = src.bits_allocated().context(MissingAttributeSnafu {
+        name: "BitsAllocated",
+    })?;
+    Ok(cols as usize * rows as usize * samples_per_pixel as usize * (bits_allocated as usize / 8))
+}
+
 /// Alias type for a dynamically dispatched data adapter.
 pub type DynPixelRWAdapter = Box<dyn PixelRWAdapter + Send + Sync>;
 
@@ -195,6 +310,15 @@ impl PixelRWAdapter for NeverPixelAdapter {
         unreachable!();
     }
 
+    fn decode_frame(
+        &self,
+        _src: &dyn PixelDataObject,
+        _frame: u32,
+        _dst: &mut Vec<u8>,
+    ) -> DecodeResult<()> {
+        unreachable!();
+    }
+
     fn encode(
         &self,
         _src: &dyn PixelDataObject,
@@ -204,3 +328,35 @@ impl PixelRWAdapter for NeverPixelAdapter {
         unreachable!();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::frame_fragment_range;
+
+    #[test]
+    fn test_frame_fragment_range() {
+        // frame 0: fragments 0 and 1, frame 1: fragment 2, frame 2: fragment 3
+        let fragment_lengths = [100_u64, 20, 50, 30];
+        let offset_table = [0, 136, 194];
+
+        assert_eq!(
+            frame_fragment_range(&offset_table, fragment_lengths, 0),
+            Some(0..2)
+        );
+        assert_eq!(
+            frame_fragment_range(&offset_table, fragment_lengths, 1),
+            Some(2..3)
+        );
+        assert_eq!(
+            frame_fragment_range(&offset_table, fragment_lengths, 2),
+            Some(3..4)
+        );
+        // out of range
+        assert_eq!(
+            frame_fragment_range(&offset_table, fragment_lengths, 3),
+            None
+        );
+        // offset in the middle of a fragment
+        assert_eq!(frame_fragment_range(&[0, 60], fragment_lengths, 0), None);
+    }
+}
@@ -7,12 +7,12 @@
 //!
 //! License: <https://github.com/pydicom/pydicom/blob/master/LICENSE>
 use byteordered::byteorder::{ByteOrder, LittleEndian};
-use snafu::{whatever, OptionExt, ResultExt};
+use snafu::{ensure, whatever, OptionExt, ResultExt};
 
 use crate::adapters::{DecodeResult, PixelDataObject, PixelRWAdapter};
 use std::io::{self, Read, Seek};
 
-use super::MissingAttributeSnafu;
+use super::{FrameOutOfRangeSnafu, MissingAttributeSnafu};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct RLELosslessAdapter;
@@ -42,64 +42,130 @@ impl PixelRWAdapter for RLELosslessAdapter {
         let nr_frames =
             src.number_of_fragments()
                 .whatever_context("Invalid pixel data, no fragments found")? as usize;
-        let bytes_per_sample = bits_allocated / 8;
-        // `stride` it the total number of bytes for each sample plane
-        let stride = bytes_per_sample * cols * rows;
-        dst.resize((samples_per_pixel * stride) as usize * nr_frames, 0);
-
-        // RLE encoded data is ordered like this (for 16-bit, 3 sample):
-        //  Segment: 0     | 1     | 2     | 3     | 4     | 5
-        //           R MSB | R LSB | G MSB | G LSB | B MSB | B LSB
-        //  A segment contains only the MSB or LSB parts of all the sample pixels
-
-        // To minimise the amount of array manipulation later, and to make things
-        // faster we interleave each segment in a manner consistent with a planar
-        // configuration of 1 (and use little endian byte ordering):
-        //    All red samples             | All green samples           | All blue
-        //    Pxl 1   Pxl 2   ... Pxl N   | Pxl 1   Pxl 2   ... Pxl N   | ...
-        //    LSB MSB LSB MSB ... LSB MSB | LSB MSB LSB MSB ... LSB MSB | ...
+        let frame_size = cols as usize
+            * rows as usize
+            * samples_per_pixel as usize
+            * (bits_allocated as usize / 8);
+        dst.resize(frame_size * nr_frames, 0);
 
         for i in 0..nr_frames {
             let fragment = &src
                 .fragment(i)
                 .whatever_context("No pixel data found for frame")?;
-            let mut offsets = read_rle_header(fragment);
-            offsets.push(fragment.len() as u32);
-
-            for sample_number in 0..samples_per_pixel {
-                for byte_offset in (0..bytes_per_sample).rev() {
-                    // ii is 1, 0, 3, 2, 5, 4 for the example above
-                    // This is where the segment order correction occurs
-                    let ii = sample_number * bytes_per_sample + byte_offset;
-                    let segment = &fragment
-                        [offsets[ii as usize] as usize..offsets[(ii + 1) as usize] as usize];
-                    let buff = io::Cursor::new(segment);
-                    let (_, mut decoder) = PackBitsReader::new(buff, segment.len())
-                        .map_err(|e| Box::new(e) as Box<_>)
-                        .whatever_context("Failed to read RLE segments")?;
-                    let mut decoded_segment: Vec<u8> = vec![0; (rows * cols) as usize];
-                    decoder.read_exact(&mut decoded_segment).unwrap();
-
-                    // Interleave pixels as described in the example above
-                    let byte_offset = bytes_per_sample - byte_offset - 1;
-                    let start = (samples_per_pixel as usize * stride as usize * i)
-                        + byte_offset as usize
-                        + (sample_number * stride) as usize;
-                    let end = start + stride as usize;
-                    for (decoded_index, dst_index) in
-                        (start..end).step_by(bytes_per_sample as usize).enumerate()
-                    {
-                        dst[dst_index] = decoded_segment[decoded_index];
-                    }
-                }
-            }
+            decode_fragment(
+                fragment,
+                cols,
+                rows,
+                samples_per_pixel,
+                bits_allocated,
+                &mut dst[frame_size * i..frame_size * (i + 1)],
+            )?;
         }
         Ok(())
     }
 
+    /// Decode a single frame of the DICOM image from RLE Lossless.
+    ///
+    /// Since each frame is encoded in its own fragment,
+    /// only the fragment of the requested frame is decoded.
+    fn decode_frame(
+        &self,
+        src: &dyn PixelDataObject,
+        frame: u32,
+        dst: &mut Vec<u8>,
+    ) -> DecodeResult<()> {
+        let cols = src
+            .cols()
+            .context(MissingAttributeSnafu { name: "Columns" })?;
+        let rows = src.rows().context(MissingAttributeSnafu { name: "Rows" })?;
+        let samples_per_pixel = src.samples_per_pixel().context(MissingAttributeSnafu {
+            name: "SamplesPerPixel",
+        })?;
+        let bits_allocated = src.bits_allocated().context(MissingAttributeSnafu {
+            name: "BitsAllocated",
+        })?;
+
+        if bits_allocated != 8 && bits_allocated != 16 {
+            whatever!("BitsAllocated other than 8 or 16 is not supported");
+        }
+        let nr_frames = src
+            .number_of_fragments()
+            .whatever_context("Invalid pixel data, no fragments found")?;
+        ensure!(frame < nr_frames, FrameOutOfRangeSnafu { frame });
+
+        let frame_size = cols as usize
+            * rows as usize
+            * samples_per_pixel as usize
+            * (bits_allocated as usize / 8);
+        dst.clear();
+        dst.resize(frame_size, 0);
+
+        let fragment = &src
+            .fragment(frame as usize)
+            .whatever_context("No pixel data found for frame")?;
+        decode_fragment(fragment, cols, rows, samples_per_pixel, bits_allocated, dst)
+    }
+
     // TODO(#125) implement `encode`
 }
 
+/// Decode the RLE segments of one fragment into the frame buffer `dst`,
+/// which must have the exact size of a decoded frame.
+fn decode_fragment(
+    fragment: &[u8],
+    cols: u16,
+    rows: u16,
+    samples_per_pixel: u16,
+    bits_allocated: u16,
+    dst: &mut [u8],
+) -> DecodeResult<()> {
+    let bytes_per_sample = bits_allocated as usize / 8;
+    // `stride` it the total number of bytes for each sample plane
+    let stride = bytes_per_sample * cols as usize * rows as usize;
+
+    // RLE encoded data is ordered like this (for 16-bit, 3 sample):
+    //  Segment: 0     | 1     | 2     | 3     | 4     | 5
+    //           R MSB | R LSB | G MSB | G LSB | B MSB | B LSB
+    //  A segment contains only the MSB or LSB parts of all the sample pixels
+
+    // To minimise the amount of array manipulation later, and to make things
+    // faster we interleave each segment in a manner consistent with a planar
+    // configuration of 1 (and use little endian byte ordering):
+    //    All red samples             | All green samples           | All blue
+    //    Pxl 1   Pxl 2   ... Pxl N   | Pxl 1   Pxl 2   ... Pxl N   | ...
+    //    LSB MSB LSB MSB ... LSB MSB | LSB MSB LSB MSB ... LSB MSB | ...
+
+    let mut offsets = read_rle_header(fragment);
+    offsets.push(fragment.len() as u32);
+
+    for sample_number in 0..samples_per_pixel as usize {
+        for byte_offset in (0..bytes_per_sample).rev() {
+            // ii is 1, 0, 3, 2, 5, 4 for the example above
+            // This is where the segment order correction occurs
+            let ii = sample_number * bytes_per_sample + byte_offset;
+            let segment = &fragment[offsets[ii] as usize..offsets[ii + 1] as usize];
+            let buff = io::Cursor::new(segment);
+            let (_, mut decoder) = PackBitsReader::new(buff, segment.len())
+                .map_err(|e| Box::new(e) as Box<_>)
+                .whatever_context("Failed to read RLE segments")?;
+            let mut decoded_segment: Vec<u8> = vec![0; rows as usize * cols as usize];
+            decoder
+                .read_exact(&mut decoded_segment)
+                .map_err(|e| Box::new(e) as Box<_>)
+                .whatever_context("Failed to decode RLE segment")?;
+
+            // Interleave pixels as described in the example above
+            let byte_offset = bytes_per_sample - byte_offset - 1;
+            let start = byte_offset + sample_number * stride;
+            let end = start + stride;
+            for (decoded_index, dst_index) in (start..end).step_by(bytes_per_sample).enumerate() {
+                dst[dst_index] = decoded_segment[decoded_index];
+            }
+        }
+    }
+    Ok(())
+}
+
 // Read the RLE header and return the offsets
 fn read_rle_header(fragment: &[u8]) -> Vec<u32> {
     let nr_segments = LittleEndian::read_u32(&fragment[0..4]);
@@ -189,6 +189,14 @@ pub trait BasicEncode {
                 Ok(s.len())
             })
             .context(WriteStringSnafu),
+            SharedStrs(s) => encode_collection_delimited(&mut to, &s[..], |to, s| {
+                // Note: this will always print in UTF-8. Consumers should
+                // intercept string primitive values and encode them according
+                // to the expected character set.
+                write!(to, "{}", s)?;
+                Ok(s.len())
+            })
+            .context(WriteStringSnafu),
             F32(values) => {
                 for v in values {
                     self.encode_fl(&mut to, *v)
@@ -249,6 +257,10 @@ pub trait BasicEncode {
                 to.write_all(values).context(WriteBytesSnafu)?;
                 Ok(values.len())
             }
+            SharedBytes(values) => {
+                to.write_all(values).context(WriteBytesSnafu)?;
+                Ok(values.len())
+            }
             Tags(tags) => {
                 for tag in tags {
                     self.encode_us(&mut to, tag.0).context(WriteTagGroupSnafu)?;
@@ -18,6 +18,7 @@
 pub mod adapters;
 pub mod decode;
 pub mod encode;
+pub mod source;
 pub mod text;
 pub mod transfer_syntax;
 
@@ -25,6 +26,7 @@ pub use adapters::NeverPixelAdapter;
 pub use byteordered::Endianness;
 pub use decode::Decode;
 pub use encode::Encode;
+pub use source::DataSource;
 pub use transfer_syntax::Codec;
 pub use transfer_syntax::DataRWAdapter;
 pub use transfer_syntax::NeverAdapter;
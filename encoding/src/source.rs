@@ -0,0 +1,282 @@
+//! Abstraction over sources of DICOM data.
+//!
+//! The [`DataSource`] trait extends [`Read`]
+//! with optional capabilities which some sources can provide efficiently,
+//! such as seeking, knowing the total length of the data,
+//! or exposing the remaining bytes without copying.
+//! Readers in the upper layers can take advantage of these capabilities
+//! when available,
+//! while still accepting plain byte streams such as network sockets.
+//!
+//! Files, in-memory buffers, and buffered seekable readers
+//! implement this trait out of the box.
+//! Other readers can be adapted through [`SeekSource`]
+//! (for readers which also implement [`Seek`])
+//! or [`StreamSource`] (for forward-only readers).
+//!
+//! At the moment, only the lazy data set reader in `dicom_parser`
+//! can be created from a data source.
+//! The other readers still work over plain [`Read`]
+//! or [`Read`] + [`Seek`] implementations.
+use std::fs::File;
+use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom};
+
+/// A source of DICOM data.
+///
+/// All capabilities other than reading are optional.
+/// The default implementations of the provided methods
+/// report the capability as unavailable,
+/// or fall back to plain reading where possible.
+pub trait DataSource: Read {
+    /// Skip the next `n` bytes of the source,
+    /// returning the number of bytes effectively skipped.
+    ///
+    /// The default implementation reads and discards the bytes.
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        io::copy(&mut Read::take(self, n), &mut io::sink())
+    }
+
+    /// Reposition the source, if it supports seeking.
+    ///
+    /// Returns `None` if the source cannot seek.
+    fn try_seek(&mut self, _pos: SeekFrom) -> Option<io::Result<u64>> {
+        None
+    }
+
+    /// Retrieve the current position of the source,
+    /// if it can be known.
+    fn try_position(&mut self) -> Option<io::Result<u64>> {
+        self.try_seek(SeekFrom::Current(0))
+    }
+
+    /// Obtain the total length of the source in bytes, if known.
+    fn len_hint(&self) -> Option<u64> {
+        None
+    }
+
+    /// Obtain the bytes between the current position and the end of the source
+    /// without copying them, if the source is already in memory.
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        None
+    }
+}
+
+impl<S: ?Sized> DataSource for &mut S
+where
+    S: DataSource,
+{
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        (**self).skip(n)
+    }
+
+    fn try_seek(&mut self, pos: SeekFrom) -> Option<io::Result<u64>> {
+        (**self).try_seek(pos)
+    }
+
+    fn try_position(&mut self) -> Option<io::Result<u64>> {
+        (**self).try_position()
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        (**self).len_hint()
+    }
+
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        (**self).remaining_slice()
+    }
+}
+
+impl<S: ?Sized> DataSource for Box<S>
+where
+    S: DataSource,
+{
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        (**self).skip(n)
+    }
+
+    fn try_seek(&mut self, pos: SeekFrom) -> Option<io::Result<u64>> {
+        (**self).try_seek(pos)
+    }
+
+    fn try_position(&mut self) -> Option<io::Result<u64>> {
+        (**self).try_position()
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        (**self).len_hint()
+    }
+
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        (**self).remaining_slice()
+    }
+}
+
+impl DataSource for &[u8] {
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        let n = std::cmp::min(n, self.len() as u64);
+        *self = &self[n as usize..];
+        Ok(n)
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        Some(self.len() as u64)
+    }
+
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        Some(self)
+    }
+}
+
+impl<T> DataSource for Cursor<T>
+where
+    T: AsRef<[u8]>,
+{
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        let len = self.get_ref().as_ref().len() as u64;
+        let pos = std::cmp::min(self.position(), len);
+        let n = std::cmp::min(n, len - pos);
+        self.set_position(pos + n);
+        Ok(n)
+    }
+
+    fn try_seek(&mut self, pos: SeekFrom) -> Option<io::Result<u64>> {
+        Some(self.seek(pos))
+    }
+
+    fn try_position(&mut self) -> Option<io::Result<u64>> {
+        Some(Ok(self.position()))
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        Some(self.get_ref().as_ref().len() as u64)
+    }
+
+    fn remaining_slice(&self) -> Option<&[u8]> {
+        let data = self.get_ref().as_ref();
+        let pos = std::cmp::min(self.position(), data.len() as u64);
+        Some(&data[pos as usize..])
+    }
+}
+
+impl DataSource for File {
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        seek_skip(self, n)
+    }
+
+    fn try_seek(&mut self, pos: SeekFrom) -> Option<io::Result<u64>> {
+        Some(self.seek(pos))
+    }
+
+    fn len_hint(&self) -> Option<u64> {
+        self.metadata().ok().map(|m| m.len())
+    }
+}
+
+impl<R> DataSource for BufReader<R>
+where
+    R: Read + Seek,
+{
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        seek_skip(self, n)
+    }
+
+    fn try_seek(&mut self, pos: SeekFrom) -> Option<io::Result<u64>> {
+        Some(self.seek(pos))
+    }
+}
+
+/// An adapter turning any seekable reader into a data source.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SeekSource<R>(pub R);
+
+impl<R> Read for SeekSource<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R> DataSource for SeekSource<R>
+where
+    R: Read + Seek,
+{
+    fn skip(&mut self, n: u64) -> io::Result<u64> {
+        seek_skip(&mut self.0, n)
+    }
+
+    fn try_seek(&mut self, pos: SeekFrom) -> Option<io::Result<u64>> {
+        Some(self.0.seek(pos))
+    }
+}
+
+/// An adapter turning any forward-only reader into a data source,
+/// such as a network stream.
+///
+/// None of the optional capabilities are available through this adapter.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct StreamSource<R>(pub R);
+
+impl<R> Read for StreamSource<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl<R> DataSource for StreamSource<R> where R: Read {}
+
+/// Skip bytes by seeking, without going past the end of the source.
+fn seek_skip<S: ?Sized>(source: &mut S, n: u64) -> io::Result<u64>
+where
+    S: Seek,
+{
+    let curr_pos = source.seek(SeekFrom::Current(0))?;
+    let end_pos = source.seek(SeekFrom::End(0))?;
+    let new_pos = std::cmp::min(end_pos, curr_pos.saturating_add(n));
+    source.seek(SeekFrom::Start(new_pos))?;
+    Ok(new_pos - curr_pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DataSource, StreamSource};
+    use std::io::{Cursor, Read, SeekFrom};
+
+    #[test]
+    fn slice_source() {
+        let mut source: &[u8] = &[1, 2, 3, 4, 5, 6];
+        assert_eq!(source.len_hint(), Some(6));
+        assert_eq!(source.skip(2).unwrap(), 2);
+        assert_eq!(source.remaining_slice(), Some(&[3, 4, 5, 6][..]));
+        assert!(source.try_position().is_none());
+        assert_eq!(source.skip(10).unwrap(), 4);
+        assert_eq!(source.remaining_slice(), Some(&[][..]));
+    }
+
+    #[test]
+    fn cursor_source() {
+        let mut source = Cursor::new(vec![1_u8, 2, 3, 4, 5, 6]);
+        assert_eq!(source.skip(4).unwrap(), 4);
+        assert_eq!(source.try_position().unwrap().unwrap(), 4);
+        assert_eq!(source.remaining_slice(), Some(&[5, 6][..]));
+        assert_eq!(source.try_seek(SeekFrom::Start(1)).unwrap().unwrap(), 1);
+        let mut buf = [0; 2];
+        source.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [2, 3]);
+    }
+
+    #[test]
+    fn stream_source() {
+        let mut source = StreamSource(&[1_u8, 2, 3, 4][..]);
+        assert!(source.try_seek(SeekFrom::Start(0)).is_none());
+        assert!(source.len_hint().is_none());
+        assert_eq!(source.skip(3).unwrap(), 3);
+        let mut buf = Vec::new();
+        source.read_to_end(&mut buf).unwrap();
+        assert_eq!(buf, vec![4]);
+    }
+}
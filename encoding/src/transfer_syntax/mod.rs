@@ -20,12 +20,13 @@ use crate::encode::{
     explicit_be::ExplicitVRBigEndianEncoder, explicit_le::ExplicitVRLittleEndianEncoder,
     implicit_le::ImplicitVRLittleEndianEncoder, EncodeTo, EncoderFor,
 };
+use dicom_core::dictionary::DataDictionary;
 use std::io::{Read, Write};
 
 pub use byteordered::Endianness;
 
 /// A decoder with its type erased.
-pub type DynDecoder<S> = Box<dyn DecodeFrom<S>>;
+pub type DynDecoder<S> = Box<dyn DecodeFrom<S> + Send>;
 
 /// An encoder with its type erased.
 pub type DynEncoder<'w, W> = Box<dyn EncodeTo<W> + 'w>;
@@ -313,6 +314,12 @@ impl<D, P> TransferSyntax<D, P> {
         self.byte_order
     }
 
+    /// Obtain whether this transfer syntax
+    /// mandates an explicit value representation.
+    pub const fn explicit_vr(&self) -> bool {
+        self.explicit_vr
+    }
+
     /// Obtain this transfer syntax' codec specification.
     pub fn codec(&self) -> &Codec<D, P> {
         &self.codec
@@ -381,6 +388,29 @@ impl<D, P> TransferSyntax<D, P> {
         }
     }
 
+    /// Retrieve the appropriate data element decoder for this transfer syntax
+    /// and given reader type,
+    /// resolving value representations in implicit VR transfer syntaxes
+    /// with the given data dictionary.
+    /// Can yield none if decoding is not supported.
+    ///
+    /// The resulting decoder does not consider pixel data encapsulation or
+    /// data set compression rules. This means that the consumer of this method
+    /// needs to adapt the reader before using the decoder.
+    pub fn decoder_for_with_dict<S, Dict>(&self, dict: Dict) -> Option<DynDecoder<S>>
+    where
+        Self: Sized,
+        S: ?Sized + Read,
+        Dict: DataDictionary + Send + 'static,
+    {
+        match (self.byte_order, self.explicit_vr) {
+            (Endianness::Little, false) => {
+                Some(Box::new(ImplicitVRLittleEndianDecoder::with_dict(dict)))
+            }
+            _ => self.decoder_for(),
+        }
+    }
+
     /// Retrieve the appropriate data element encoder for this transfer syntax.
     /// Can yield none if encoding is not supported. The resulting encoder does not
     /// consider pixel data encapsulation or data set compression rules.
@@ -0,0 +1,100 @@
+//! A CLI tool for converting a DICOM file into the DICOM JSON model.
+use dicom_json::{write_json, ToJsonOptions};
+use dicom_object::open_file;
+use snafu::{ErrorCompat, ResultExt, Whatever};
+use std::fs::File;
+use std::io::{stdout, BufWriter};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Convert a DICOM file into DICOM JSON
+#[derive(Debug, StructOpt)]
+struct App {
+    /// The DICOM file to convert
+    file: PathBuf,
+    /// Path to the output JSON file
+    /// (default is to print to standard output)
+    #[structopt(short = "o", long = "out")]
+    output: Option<PathBuf>,
+    /// Include the file meta group in the output
+    #[structopt(long = "with-meta")]
+    with_meta: bool,
+    /// Extract large binary values and encapsulated pixel data
+    /// to files in this directory,
+    /// relative to the output file
+    #[structopt(short = "d", long = "bulk-data-dir")]
+    bulk_data_dir: Option<PathBuf>,
+    /// The size in bytes above which binary values are extracted
+    #[structopt(long = "bulk-data-threshold", default_value = "1024")]
+    bulk_data_threshold: u32,
+}
+
+fn main() {
+    run().unwrap_or_else(|e| {
+        report(e);
+        std::process::exit(-2);
+    });
+}
+
+fn run() -> Result<(), Whatever> {
+    let App {
+        file,
+        output,
+        with_meta,
+        bulk_data_dir,
+        bulk_data_threshold,
+    } = App::from_args();
+
+    let obj = open_file(&file).whatever_context("Could not open DICOM file")?;
+
+    let base_dir = output
+        .as_ref()
+        .and_then(|output| output.parent())
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let mut options = ToJsonOptions::new()
+        .with_meta(with_meta)
+        .base_dir(&base_dir)
+        .bulk_data_threshold(bulk_data_threshold);
+    if let Some(dir) = bulk_data_dir {
+        std::fs::create_dir_all(base_dir.join(&dir))
+            .whatever_context("Could not create bulk data directory")?;
+        options = options.bulk_data_dir(dir);
+    }
+
+    match output {
+        Some(output) => {
+            let out = File::create(&output).whatever_context("Could not create output file")?;
+            write_json(&obj, BufWriter::new(out), &options)
+        }
+        None => write_json(&obj, BufWriter::new(stdout()), &options),
+    }
+    .whatever_context("Could not convert DICOM file")?;
+
+    Ok(())
+}
+
+fn report<E: 'static>(err: E)
+where
+    E: std::error::Error,
+    E: ErrorCompat,
+{
+    eprintln!("[ERROR] {}", err);
+    if let Some(source) = err.source() {
+        eprintln!();
+        eprintln!("Caused by:");
+        for (i, e) in std::iter::successors(Some(source), |e| e.source()).enumerate() {
+            eprintln!("   {}: {}", i, e);
+        }
+    }
+
+    let env_backtrace = std::env::var("RUST_BACKTRACE").unwrap_or_default();
+    let env_lib_backtrace = std::env::var("RUST_LIB_BACKTRACE").unwrap_or_default();
+    if env_lib_backtrace == "1" || (env_backtrace == "1" && env_lib_backtrace != "0") {
+        if let Some(backtrace) = ErrorCompat::backtrace(&err) {
+            eprintln!();
+            eprintln!("Backtrace:");
+            eprintln!("{}", backtrace);
+        }
+    }
+}
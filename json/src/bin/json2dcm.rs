@@ -0,0 +1,81 @@
+//! A CLI tool for converting a DICOM JSON document into a DICOM file.
+use dicom_json::{read_json, FromJsonOptions};
+use snafu::{ErrorCompat, ResultExt, Whatever};
+use std::path::PathBuf;
+use structopt::StructOpt;
+
+/// Convert a DICOM JSON document into a DICOM file
+#[derive(Debug, StructOpt)]
+struct App {
+    /// The DICOM JSON file to convert
+    file: PathBuf,
+    /// Path to the output DICOM file
+    /// (default is to replace input extension with `.dcm`)
+    #[structopt(short = "o", long = "out")]
+    output: Option<PathBuf>,
+    /// The transfer syntax UID of the output file
+    /// (default is the one in the document's file meta group,
+    /// or Explicit VR Little Endian)
+    #[structopt(short = "t", long = "transfer-syntax")]
+    transfer_syntax: Option<String>,
+}
+
+fn main() {
+    run().unwrap_or_else(|e| {
+        report(e);
+        std::process::exit(-2);
+    });
+}
+
+fn run() -> Result<(), Whatever> {
+    let App {
+        file,
+        output,
+        transfer_syntax,
+    } = App::from_args();
+
+    let json = std::fs::read(&file).whatever_context("Could not read JSON file")?;
+
+    // bulk data URIs are relative to the JSON document
+    let base_dir = file
+        .parent()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let mut options = FromJsonOptions::new().base_dir(base_dir);
+    if let Some(uid) = transfer_syntax {
+        options = options.transfer_syntax(uid);
+    }
+
+    let obj = read_json(&json, &options).whatever_context("Could not convert JSON document")?;
+
+    let output = output.unwrap_or_else(|| file.with_extension("dcm"));
+    obj.write_to_file(&output)
+        .whatever_context("Could not write DICOM file")?;
+
+    Ok(())
+}
+
+fn report<E: 'static>(err: E)
+where
+    E: std::error::Error,
+    E: ErrorCompat,
+{
+    eprintln!("[ERROR] {}", err);
+    if let Some(source) = err.source() {
+        eprintln!();
+        eprintln!("Caused by:");
+        for (i, e) in std::iter::successors(Some(source), |e| e.source()).enumerate() {
+            eprintln!("   {}: {}", i, e);
+        }
+    }
+
+    let env_backtrace = std::env::var("RUST_BACKTRACE").unwrap_or_default();
+    let env_lib_backtrace = std::env::var("RUST_LIB_BACKTRACE").unwrap_or_default();
+    if env_lib_backtrace == "1" || (env_backtrace == "1" && env_lib_backtrace != "0") {
+        if let Some(backtrace) = ErrorCompat::backtrace(&err) {
+            eprintln!();
+            eprintln!("Backtrace:");
+            eprintln!("{}", backtrace);
+        }
+    }
+}
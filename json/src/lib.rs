@@ -0,0 +1,533 @@
+//! DICOM JSON conversion library
+//!
+//! This is a helper library
+//! for converting DICOM files into the DICOM JSON model
+//! (PS3.18 section F.2)
+//! and back,
+//! as done by the `dcm2json` and `json2dcm` tools.
+//!
+//! Large binary values can be extracted to sidecar files
+//! when writing JSON (see [`ToJsonOptions::bulk_data_dir`]),
+//! in which case the JSON document refers to them
+//! through a `BulkDataURI` relative to the document.
+//! When reading JSON,
+//! these values are retrieved from the respective files again
+//! (see [`FromJsonOptions::base_dir`]).
+//!
+//! The sidecar file of an element holds its value in little endian.
+//! The sidecar file of encapsulated pixel data
+//! holds the items of the pixel data sequence as they are encoded
+//! in a DICOM file,
+//! starting with the basic offset table.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_json::{read_json, write_json, FromJsonOptions, ToJsonOptions};
+//! use dicom_object::open_file;
+//!
+//! let obj = open_file("0001.dcm")?;
+//! let mut json = Vec::new();
+//! write_json(&obj, &mut json, &ToJsonOptions::new().with_meta(true))?;
+//!
+//! let obj2 = read_json(&json, &FromJsonOptions::new())?;
+//! obj2.write_to_file("0001-copy.dcm")?;
+//! # Result::<(), Box<dyn std::error::Error>>::Ok(())
+//! ```
+use std::cell::Cell;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use dicom_core::header::DataElementHeader;
+use dicom_core::value::{Value, C};
+use dicom_core::{DataElement, Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_encoding::transfer_syntax::{Codec, TransferSyntaxIndex};
+use dicom_object::{DefaultDicomObject, FileDicomObject, FileMetaTableBuilder, InMemDicomObject};
+use dicom_parser::dataset::json::read::read_tokens_from_slice_with_bulk_data;
+use dicom_parser::dataset::json::JsonDataSetWriter;
+use dicom_parser::dataset::{DataToken, IntoTokens};
+use dicom_transfer_syntax_registry::entries::EXPLICIT_VR_LITTLE_ENDIAN;
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+/// The default size in bytes above which binary values
+/// are extracted to sidecar files.
+pub const DEFAULT_BULK_DATA_THRESHOLD: u32 = 1024;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to write the DICOM JSON document
+    WriteJson {
+        #[snafu(backtrace)]
+        source: dicom_parser::dataset::json::Error,
+    },
+
+    #[snafu(display("failed to write bulk data file {}", path.display()))]
+    WriteBulkData {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    /// failed to read the DICOM JSON document
+    ReadJson {
+        #[snafu(backtrace)]
+        source: dicom_parser::dataset::json::read::Error,
+    },
+
+    /// failed to build the DICOM object from the JSON document
+    BuildObject {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("missing attribute {} to build the file meta group", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    #[snafu(display("unsupported transfer syntax `{}`", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// invalid encapsulated pixel data
+    InvalidPixelSequence { backtrace: Backtrace },
+
+    /// failed to build the file meta group
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Options for converting a DICOM file into DICOM JSON.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ToJsonOptions {
+    /// whether to include the file meta group
+    with_meta: bool,
+    /// where to write bulk data files, relative to `base_dir`
+    bulk_data_dir: Option<PathBuf>,
+    /// the directory of the JSON document
+    base_dir: PathBuf,
+    /// the size in bytes above which values are extracted
+    bulk_data_threshold: u32,
+}
+
+impl Default for ToJsonOptions {
+    fn default() -> Self {
+        ToJsonOptions {
+            with_meta: false,
+            bulk_data_dir: None,
+            base_dir: PathBuf::from("."),
+            bulk_data_threshold: DEFAULT_BULK_DATA_THRESHOLD,
+        }
+    }
+}
+
+impl ToJsonOptions {
+    /// Create a new set of options,
+    /// in which only the main data set is written
+    /// and all values are written inline.
+    pub fn new() -> Self {
+        ToJsonOptions::default()
+    }
+
+    /// Set whether to include the file meta group in the JSON document.
+    pub fn with_meta(mut self, with_meta: bool) -> Self {
+        self.with_meta = with_meta;
+        self
+    }
+
+    /// Extract large binary values and encapsulated pixel data
+    /// to files in the given directory,
+    /// which is relative to the base directory
+    /// (see [`base_dir`](ToJsonOptions::base_dir)).
+    ///
+    /// The directory must already exist.
+    pub fn bulk_data_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.bulk_data_dir = Some(dir.into());
+        self
+    }
+
+    /// Set the directory where the JSON document is saved,
+    /// against which bulk data files are placed.
+    ///
+    /// This is the working directory by default.
+    pub fn base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = dir.into();
+        self
+    }
+
+    /// Set the size in bytes above which binary values
+    /// are extracted to bulk data files.
+    ///
+    /// Encapsulated pixel data is always extracted.
+    pub fn bulk_data_threshold(mut self, threshold: u32) -> Self {
+        self.bulk_data_threshold = threshold;
+        self
+    }
+}
+
+/// Write a DICOM file in the DICOM JSON model to the given writer.
+///
+/// Without a bulk data directory,
+/// binary values are written inline
+/// and encapsulated pixel data is written without a value.
+pub fn write_json<D, W>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    to: W,
+    options: &ToJsonOptions,
+) -> Result<()>
+where
+    D: Clone,
+    W: Write,
+{
+    let meta_tokens = if options.with_meta {
+        obj.meta()
+            .to_element_iter()
+            .flat_map(IntoTokens::into_tokens)
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let tokens = meta_tokens.into_iter().chain((&**obj).into_tokens());
+
+    let bulk_data_dir = match &options.bulk_data_dir {
+        Some(dir) => dir,
+        None => {
+            let mut writer = JsonDataSetWriter::new(to);
+            writer.write_sequence(tokens).context(WriteJsonSnafu)?;
+            writer.finish().context(WriteJsonSnafu)?;
+            return Ok(());
+        }
+    };
+
+    // the URI of the next value to extract,
+    // decided here before the token is passed to the writer
+    let next_uri: Rc<Cell<Option<String>>> = Rc::default();
+    let bulk_data = {
+        let next_uri = Rc::clone(&next_uri);
+        move |_: &[Tag], _: &DataElementHeader| next_uri.take()
+    };
+    let mut writer = JsonDataSetWriter::with_bulk_data(to, bulk_data, options.bulk_data_threshold);
+
+    let mut count = 0;
+    let create_file = |count: &mut u32| -> Result<(String, PathBuf, BufWriter<File>)> {
+        *count += 1;
+        let uri_path = bulk_data_dir.join(format!("{:06}.bin", count));
+        let path = options.base_dir.join(&uri_path);
+        let uri = uri_path
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let file = File::create(&path).context(WriteBulkDataSnafu { path: &path })?;
+        Ok((uri, path, BufWriter::new(file)))
+    };
+
+    let mut last_header = None;
+    let mut pixel_sequence: Option<(PathBuf, BufWriter<File>)> = None;
+    for token in tokens {
+        match (&token, &mut pixel_sequence) {
+            (DataToken::ElementHeader(header), _) => last_header = Some(*header),
+            (DataToken::PrimitiveValue(value), _) => {
+                let binary = last_header
+                    .take()
+                    .map(|header| is_binary(header.vr))
+                    .unwrap_or(false);
+                if binary
+                    && value.calculate_byte_len() as u64 > u64::from(options.bulk_data_threshold)
+                {
+                    let (uri, path, mut file) = create_file(&mut count)?;
+                    file.write_all(&value.to_bytes())
+                        .and_then(|_| file.flush())
+                        .context(WriteBulkDataSnafu { path })?;
+                    next_uri.set(Some(uri));
+                }
+            }
+            (DataToken::PixelSequenceStart, None) => {
+                let (uri, path, file) = create_file(&mut count)?;
+                next_uri.set(Some(uri));
+                pixel_sequence = Some((path, file));
+            }
+            (DataToken::ItemStart { len }, Some((path, file))) => {
+                let len = len.get().context(InvalidPixelSequenceSnafu)?;
+                let mut item_header = [0xFE, 0xFF, 0x00, 0xE0, 0, 0, 0, 0];
+                item_header[4..].copy_from_slice(&len.to_le_bytes());
+                file.write_all(&item_header)
+                    .context(WriteBulkDataSnafu { path: &*path })?;
+            }
+            (DataToken::OffsetTable(table), Some((path, file))) => {
+                for offset in table {
+                    file.write_all(&offset.to_le_bytes())
+                        .context(WriteBulkDataSnafu { path: &*path })?;
+                }
+            }
+            (DataToken::ItemValue(data), Some((path, file))) => {
+                file.write_all(data)
+                    .context(WriteBulkDataSnafu { path: &*path })?;
+            }
+            (DataToken::SequenceEnd, Some((path, file))) => {
+                file.flush().context(WriteBulkDataSnafu { path: &*path })?;
+                pixel_sequence = None;
+            }
+            _ => {}
+        }
+        writer.write(token).context(WriteJsonSnafu)?;
+    }
+    writer.finish().context(WriteJsonSnafu)?;
+    Ok(())
+}
+
+/// Options for converting a DICOM JSON document into a DICOM file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FromJsonOptions {
+    /// the directory against which bulk data URIs are resolved
+    base_dir: PathBuf,
+    /// the transfer syntax of the file
+    transfer_syntax: Option<String>,
+}
+
+impl Default for FromJsonOptions {
+    fn default() -> Self {
+        FromJsonOptions {
+            base_dir: PathBuf::from("."),
+            transfer_syntax: None,
+        }
+    }
+}
+
+impl FromJsonOptions {
+    /// Create a new set of options with the default values.
+    pub fn new() -> Self {
+        FromJsonOptions::default()
+    }
+
+    /// Set the directory against which
+    /// relative bulk data URIs are resolved,
+    /// usually the directory of the JSON document.
+    ///
+    /// This is the working directory by default.
+    pub fn base_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.base_dir = dir.into();
+        self
+    }
+
+    /// Set the transfer syntax of the resulting file.
+    ///
+    /// By default,
+    /// the transfer syntax in the document's file meta group is used
+    /// if present,
+    /// and _Explicit VR Little Endian_ otherwise.
+    pub fn transfer_syntax(mut self, uid: impl Into<String>) -> Self {
+        self.transfer_syntax = Some(uid.into());
+        self
+    }
+}
+
+/// Read a DICOM JSON document into a DICOM file object.
+///
+/// Values referred to by a `BulkDataURI`
+/// must be local files,
+/// given either as a path relative to the base directory
+/// or as a `file:` URI.
+/// Elements of the file meta group in the document
+/// are used to build the file meta group of the object.
+pub fn read_json(json: &[u8], options: &FromJsonOptions) -> Result<DefaultDicomObject> {
+    let tokens = read_tokens_from_slice_with_bulk_data(json, |uri| {
+        let path = uri
+            .strip_prefix("file://")
+            .or_else(|| uri.strip_prefix("file:"))
+            .unwrap_or(uri);
+        std::fs::read(options.base_dir.join(path)).ok()
+    })
+    .context(ReadJsonSnafu)?;
+    let mut obj = InMemDicomObject::from_tokens(tokens).context(BuildObjectSnafu)?;
+
+    // take out the file meta group
+    let meta_tags: Vec<_> = obj.tags().filter(|tag| tag.group() == 0x0002).collect();
+    let mut meta_elements = InMemDicomObject::new_empty();
+    for tag in meta_tags {
+        if let Ok(elem) = obj.take_element(tag) {
+            meta_elements.put(elem);
+        }
+    }
+    let get_string = |meta_tag: Tag, tag: Tag| {
+        meta_elements
+            .get_string(meta_tag)
+            .or_else(|_| obj.get_string(tag))
+            .ok()
+            .map(|s| trim_uid(&s).to_string())
+            .context(MissingAttributeSnafu { tag })
+    };
+    let sop_class_uid = get_string(tags::MEDIA_STORAGE_SOP_CLASS_UID, tags::SOP_CLASS_UID)?;
+    let sop_instance_uid =
+        get_string(tags::MEDIA_STORAGE_SOP_INSTANCE_UID, tags::SOP_INSTANCE_UID)?;
+    let ts_uid = match &options.transfer_syntax {
+        Some(uid) => uid.clone(),
+        None => meta_elements
+            .get_string(tags::TRANSFER_SYNTAX_UID)
+            .map(|uid| trim_uid(&uid).to_string())
+            .unwrap_or_else(|_| EXPLICIT_VR_LITTLE_ENDIAN.uid().to_string()),
+    };
+    let ts =
+        TransferSyntaxRegistry
+            .get(&ts_uid)
+            .with_context(|| UnsupportedTransferSyntaxSnafu {
+                uid: ts_uid.clone(),
+            })?;
+
+    // reassemble encapsulated pixel data
+    if matches!(
+        ts.codec(),
+        Codec::EncapsulatedPixelData | Codec::PixelData(_)
+    ) {
+        if let Ok(elem) = obj.take_element(tags::PIXEL_DATA) {
+            let data = elem.value().primitive().map(|value| value.to_bytes());
+            let elem = match data {
+                Some(data) => {
+                    DataElement::new(tags::PIXEL_DATA, VR::OB, decode_pixel_sequence(&data)?)
+                }
+                None => elem,
+            };
+            obj.put(elem);
+        }
+    }
+
+    let meta = FileMetaTableBuilder::new()
+        .media_storage_sop_class_uid(sop_class_uid)
+        .media_storage_sop_instance_uid(sop_instance_uid)
+        .transfer_syntax(ts.uid());
+    obj.with_meta(meta).context(BuildMetaSnafu)
+}
+
+/// Decode the items of encapsulated pixel data from their encoded form.
+fn decode_pixel_sequence(mut data: &[u8]) -> Result<Value<InMemDicomObject, Vec<u8>>> {
+    let mut items = Vec::new();
+    while !data.is_empty() {
+        ensure!(
+            data.len() >= 8 && data[0..4] == [0xFE, 0xFF, 0x00, 0xE0],
+            InvalidPixelSequenceSnafu
+        );
+        let len = u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize;
+        let item = data.get(8..8 + len).context(InvalidPixelSequenceSnafu)?;
+        items.push(item);
+        data = &data[8 + len..];
+    }
+    let (offset_table, fragments) = items.split_first().context(InvalidPixelSequenceSnafu)?;
+    let offset_table: C<u32> = offset_table
+        .chunks_exact(4)
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    let fragments: C<Vec<u8>> = fragments.iter().map(|f| f.to_vec()).collect();
+    Ok(Value::PixelSequence {
+        offset_table,
+        fragments,
+    })
+}
+
+fn trim_uid(uid: &str) -> &str {
+    uid.trim_end_matches(|c: char| c == ' ' || c == '\0')
+}
+
+fn is_binary(vr: VR) -> bool {
+    matches!(
+        vr,
+        VR::OB | VR::OD | VR::OF | VR::OL | VR::OV | VR::OW | VR::UN
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::value::PrimitiveValue;
+
+    fn sample_object() -> DefaultDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.7"),
+            ),
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.1234"),
+            ),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(
+                tags::PIXEL_DATA,
+                VR::OB,
+                Value::PixelSequence {
+                    offset_table: C::new(),
+                    fragments: vec![vec![0xFF, 0xD8, 0xFF, 0xD9], vec![1, 2]].into(),
+                },
+            ),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .transfer_syntax("1.2.840.10008.1.2.4.50"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn round_trip_with_bulk_data() {
+        let dir = std::env::temp_dir().join(format!("dicom-json-test-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("bulk")).unwrap();
+
+        let obj = sample_object();
+        let mut json = Vec::new();
+        write_json(
+            &obj,
+            &mut json,
+            &ToJsonOptions::new()
+                .with_meta(true)
+                .base_dir(&dir)
+                .bulk_data_dir("bulk"),
+        )
+        .unwrap();
+        let text = std::str::from_utf8(&json).unwrap();
+        assert!(text.contains(r#""00020010":{"vr":"UI","Value":["1.2.840.10008.1.2.4.50"]}"#));
+        assert!(text.contains(r#""7FE00010":{"vr":"OB","BulkDataURI":"bulk/000001.bin"}"#));
+
+        let obj2 = read_json(&json, &FromJsonOptions::new().base_dir(&dir)).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            obj2.meta().transfer_syntax.trim_end_matches('\0'),
+            "1.2.840.10008.1.2.4.50"
+        );
+        assert!(obj2
+            .element_opt(tags::TRANSFER_SYNTAX_UID)
+            .unwrap()
+            .is_none());
+        assert_eq!(obj2.get_string(tags::PATIENT_NAME).unwrap(), "Doe^John");
+        match obj2.element(tags::PIXEL_DATA).unwrap().value() {
+            Value::PixelSequence {
+                offset_table,
+                fragments,
+            } => {
+                assert!(offset_table.is_empty());
+                assert_eq!(
+                    &fragments[..],
+                    &[vec![0xFF, 0xD8, 0xFF, 0xD9], vec![1, 2]][..]
+                );
+            }
+            value => panic!("expected a pixel sequence, got {:?}", value),
+        }
+    }
+
+    #[test]
+    fn read_json_requires_sop_class() {
+        let json = br#"{ "00100010": { "vr": "PN", "Value": [{ "Alphabetic": "Doe^John" }] } }"#;
+        assert!(matches!(
+            read_json(json, &FromJsonOptions::new()),
+            Err(Error::MissingAttribute { .. })
+        ));
+    }
+}
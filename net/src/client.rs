@@ -0,0 +1,610 @@
+//! Pooled client module, service class user side.
+//!
+//! [`DicomClient`] keeps a pool of associations
+//! to a single remote application entity,
+//! so that many C-STORE and C-FIND operations
+//! can be performed without negotiating a new association for each one.
+//! The client can be shared between threads,
+//! each operation taking an idle association from the pool
+//! or establishing a new one, up to the maximum pool size.
+//!
+//! When an operation fails because of the association itself,
+//! such as when the other node closed an idle connection,
+//! the association is discarded
+//! and the operation is attempted again in a fresh association,
+//! as dictated by the client's [`RetryPolicy`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_dictionary_std::uids;
+//! # use dicom_net::client::{DicomClient, RetryPolicy};
+//! # use std::time::Duration;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = DicomClient::new("PACS@129.168.0.5:104".parse()?)
+//!     .calling_ae_title("ROUTER")
+//!     .with_abstract_syntax(uids::CT_IMAGE_STORAGE)
+//!     .max_pool_size(4)
+//!     .retry_policy(RetryPolicy::new(5).initial_backoff(Duration::from_millis(500)));
+//!
+//! let object = dicom_object::open_file("path/to/image.dcm")?;
+//! let status = client.store(&object)?;
+//! println!("stored with status {:04X}H", status);
+//! client.close()?;
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+use std::sync::{Condvar, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+
+use crate::{find, store};
+use dicom_object::{DefaultDicomObject, InMemDicomObject};
+use dicom_transfer_syntax_registry::entries;
+use dicom_ul::address::AeAddr;
+use dicom_ul::association::client::{self, ClientAssociation, ClientAssociationOptions};
+use dicom_ul::pdu::AssociationRJResult;
+use snafu::{ResultExt, Snafu};
+use tracing::warn;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to establish association
+    Establish { source: client::Error },
+
+    /// failed to release association
+    Release { source: client::Error },
+
+    /// failed to store object
+    Store {
+        #[snafu(backtrace)]
+        source: store::Error,
+    },
+
+    /// failed to query the other node
+    Find {
+        #[snafu(backtrace)]
+        source: find::Error,
+    },
+}
+
+impl Error {
+    /// Whether the operation may succeed if attempted again
+    /// in a new association.
+    fn is_retryable(&self) -> bool {
+        match self {
+            Error::Establish {
+                source:
+                    client::Error::Rejected {
+                        association_result, ..
+                    },
+            } => matches!(association_result, AssociationRJResult::Transient),
+            Error::Establish { .. } => true,
+            Error::Release { .. } => false,
+            Error::Store { source } => source.is_communication_error(),
+            Error::Find { source } => source.is_communication_error(),
+        }
+    }
+
+    /// Whether the association in which the error occurred
+    /// can still be used for other operations.
+    fn keeps_association(&self) -> bool {
+        match self {
+            Error::Store { source } => source.is_object_specific(),
+            Error::Find { source } => matches!(
+                source,
+                find::Error::EncodeIdentifier { .. } | find::Error::QueryFailed { .. }
+            ),
+            _ => false,
+        }
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A policy on whether and when to retry failed operations.
+///
+/// Operations are retried with an exponential backoff:
+/// the delay starts at the initial backoff
+/// and doubles on each retry, up to the maximum backoff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// the maximum number of retries per operation
+    max_retries: u32,
+    /// the delay before the first retry
+    initial_backoff: Duration,
+    /// the maximum delay between retries
+    max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a retry policy with the given maximum number of retries
+    /// and the default backoff delays.
+    pub fn new(max_retries: u32) -> Self {
+        RetryPolicy {
+            max_retries,
+            ..Default::default()
+        }
+    }
+
+    /// Create a retry policy which never retries.
+    pub fn none() -> Self {
+        Self::new(0)
+    }
+
+    /// Set the delay before the first retry.
+    ///
+    /// The default is 100 milliseconds.
+    pub fn initial_backoff(mut self, delay: Duration) -> Self {
+        self.initial_backoff = delay;
+        self
+    }
+
+    /// Set the maximum delay between retries.
+    ///
+    /// The default is 10 seconds.
+    pub fn max_backoff(mut self, delay: Duration) -> Self {
+        self.max_backoff = delay;
+        self
+    }
+
+    /// Obtain the maximum number of retries per operation.
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Obtain the delay to wait before the given retry,
+    /// counting from 1.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let factor = 1_u32
+            .checked_shl(retry.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.initial_backoff
+            .checked_mul(factor)
+            .map_or(self.max_backoff, |delay| delay.min(self.max_backoff))
+    }
+}
+
+/// An association in the pool,
+/// along with the ID of the next message to send through it.
+#[derive(Debug)]
+struct PooledAssociation {
+    association: ClientAssociation,
+    next_message_id: u16,
+}
+
+impl PooledAssociation {
+    /// Take a new message ID for this association.
+    fn message_id(&mut self) -> u16 {
+        let message_id = self.next_message_id;
+        self.next_message_id = self.next_message_id.checked_add(1).unwrap_or(1);
+        message_id
+    }
+}
+
+/// The state of the association pool.
+#[derive(Debug, Default)]
+struct Pool {
+    /// the associations not in use
+    idle: Vec<PooledAssociation>,
+    /// the number of associations open, including those in use
+    open: usize,
+}
+
+/// A service class user keeping a pool of associations
+/// to a remote application entity.
+///
+/// All associations in the pool propose the same presentation contexts,
+/// which must cover every SOP class and transfer syntax
+/// of the operations to perform.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Debug)]
+pub struct DicomClient {
+    /// the address of the remote application entity
+    address: AeAddr,
+    /// the calling AE title
+    calling_ae_title: String,
+    /// the presentation contexts to propose
+    presentation_contexts: Vec<(String, Vec<String>)>,
+    /// the maximum PDU length that this node admits
+    max_pdu_length: Option<u32>,
+    /// the TCP connection timeout
+    connection_timeout: Option<Duration>,
+    /// the TCP read timeout
+    read_timeout: Option<Duration>,
+    /// the TCP write timeout
+    write_timeout: Option<Duration>,
+    /// the maximum number of associations open at once
+    max_pool_size: usize,
+    /// the retry policy for failed operations
+    retry_policy: RetryPolicy,
+    /// the association pool
+    pool: Mutex<Pool>,
+    /// notified whenever an association is returned or closed
+    available: Condvar,
+}
+
+impl DicomClient {
+    /// Create a new client to the application entity at the given address.
+    ///
+    /// The AE title in the address is used as the called AE title,
+    /// `ANY-SCP` being used if it is not specified.
+    /// No association is established until the first operation.
+    pub fn new(address: AeAddr) -> Self {
+        DicomClient {
+            address,
+            calling_ae_title: "THIS-SCU".to_string(),
+            presentation_contexts: Vec::new(),
+            max_pdu_length: None,
+            connection_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            max_pool_size: 1,
+            retry_policy: RetryPolicy::default(),
+            pool: Mutex::new(Pool::default()),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Define the calling application entity title,
+    /// which refers to this DICOM node.
+    ///
+    /// The default is `THIS-SCU`.
+    pub fn calling_ae_title(mut self, calling_ae_title: impl Into<String>) -> Self {
+        self.calling_ae_title = calling_ae_title.into();
+        self
+    }
+
+    /// Include this presentation context
+    /// in the list of presentation contexts proposed in each association.
+    pub fn with_presentation_context<I, T>(
+        mut self,
+        abstract_syntax_uid: impl Into<String>,
+        transfer_syntax_uids: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.presentation_contexts.push((
+            abstract_syntax_uid.into(),
+            transfer_syntax_uids.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Include this abstract syntax
+    /// with the default transfer syntaxes
+    /// _Explicit VR Little Endian_ and _Implicit VR Little Endian_
+    /// in the list of presentation contexts proposed in each association.
+    pub fn with_abstract_syntax(self, abstract_syntax_uid: impl Into<String>) -> Self {
+        self.with_presentation_context(
+            abstract_syntax_uid,
+            [
+                entries::EXPLICIT_VR_LITTLE_ENDIAN.uid(),
+                entries::IMPLICIT_VR_LITTLE_ENDIAN.uid(),
+            ],
+        )
+    }
+
+    /// Override the maximum PDU length that this node admits.
+    pub fn max_pdu_length(mut self, value: u32) -> Self {
+        self.max_pdu_length = Some(value);
+        self
+    }
+
+    /// Set the maximum time to wait for the TCP connection to be established.
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for a response from the other node.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for outgoing data to be sent.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of associations open at once.
+    /// Operations beyond this number wait for an association to be available.
+    ///
+    /// The default is 1.
+    pub fn max_pool_size(mut self, size: usize) -> Self {
+        self.max_pool_size = size.max(1);
+        self
+    }
+
+    /// Set the policy for retrying failed operations.
+    ///
+    /// The default policy retries up to 3 times.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Obtain the address of the remote application entity.
+    pub fn address(&self) -> &AeAddr {
+        &self.address
+    }
+
+    /// Obtain the number of associations currently open,
+    /// including those in use.
+    pub fn open_associations(&self) -> usize {
+        self.lock_pool().open
+    }
+
+    /// Send a DICOM object with a C-STORE request,
+    /// returning the status code in the response.
+    ///
+    /// See [`store::store`] for how the presentation context is chosen.
+    pub fn store(&self, object: &DefaultDicomObject) -> Result<u16> {
+        self.run(|association, message_id| {
+            store::store(association, object, message_id).context(StoreSnafu)
+        })
+    }
+
+    /// Query the other node with a C-FIND request
+    /// in the given information model,
+    /// returning the identifiers of all matches.
+    pub fn find(
+        &self,
+        sop_class_uid: &str,
+        identifier: &InMemDicomObject,
+    ) -> Result<Vec<InMemDicomObject>> {
+        self.run(|association, message_id| {
+            find::find(association, sop_class_uid, identifier, message_id).context(FindSnafu)
+        })
+    }
+
+    /// Release all idle associations and close the client.
+    pub fn close(self) -> Result<()> {
+        let pool = self
+            .pool
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let mut out = Ok(());
+        for pooled in pool.idle {
+            let result = pooled.association.release().context(ReleaseSnafu);
+            if out.is_ok() {
+                out = result;
+            }
+        }
+        out
+    }
+
+    /// Perform an operation in a pooled association,
+    /// retrying as dictated by the retry policy.
+    fn run<T, F>(&self, mut operation: F) -> Result<T>
+    where
+        F: FnMut(&mut ClientAssociation, u16) -> Result<T>,
+    {
+        let mut retry = 0;
+        loop {
+            let result = self.checkout().and_then(|mut pooled| {
+                let message_id = pooled.message_id();
+                let result = operation(&mut pooled.association, message_id);
+                match &result {
+                    Err(e) if !e.keeps_association() => self.discard(pooled),
+                    _ => self.checkin(pooled),
+                }
+                result
+            });
+            match result {
+                Err(e) if e.is_retryable() && retry < self.retry_policy.max_retries => {
+                    retry += 1;
+                    let delay = self.retry_policy.backoff(retry);
+                    warn!(
+                        "Operation with {} failed, retrying in {:?} ({}/{}): {}",
+                        self.address, delay, retry, self.retry_policy.max_retries, e
+                    );
+                    std::thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Take an idle association from the pool,
+    /// establishing a new one if the pool is not full,
+    /// or waiting for one to be available otherwise.
+    fn checkout(&self) -> Result<PooledAssociation> {
+        let mut pool = self.lock_pool();
+        loop {
+            if let Some(pooled) = pool.idle.pop() {
+                return Ok(pooled);
+            }
+            if pool.open < self.max_pool_size {
+                pool.open += 1;
+                drop(pool);
+                return match self.establish() {
+                    Ok(association) => Ok(PooledAssociation {
+                        association,
+                        next_message_id: 1,
+                    }),
+                    Err(e) => {
+                        self.lock_pool().open -= 1;
+                        self.available.notify_one();
+                        Err(e)
+                    }
+                };
+            }
+            pool = self
+                .available
+                .wait(pool)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+    }
+
+    /// Return an association to the pool.
+    fn checkin(&self, pooled: PooledAssociation) {
+        self.lock_pool().idle.push(pooled);
+        self.available.notify_one();
+    }
+
+    /// Abort an association which can no longer be used.
+    fn discard(&self, pooled: PooledAssociation) {
+        let _ = pooled.association.abort();
+        self.lock_pool().open -= 1;
+        self.available.notify_one();
+    }
+
+    /// Establish a new association with the remote application entity.
+    fn establish(&self) -> Result<ClientAssociation> {
+        let mut options =
+            ClientAssociationOptions::new().calling_ae_title(self.calling_ae_title.as_str());
+        for (abstract_syntax_uid, transfer_syntax_uids) in &self.presentation_contexts {
+            options = options.with_presentation_context(
+                Cow::from(abstract_syntax_uid.as_str()),
+                transfer_syntax_uids
+                    .iter()
+                    .map(|uid| Cow::from(uid.as_str()))
+                    .collect(),
+            );
+        }
+        if let Some(max_pdu_length) = self.max_pdu_length {
+            options = options.max_pdu_length(max_pdu_length);
+        }
+        if let Some(timeout) = self.connection_timeout {
+            options = options.connection_timeout(timeout);
+        }
+        if let Some(timeout) = self.read_timeout {
+            options = options.read_timeout(timeout);
+        }
+        if let Some(timeout) = self.write_timeout {
+            options = options.write_timeout(timeout);
+        }
+        options
+            .establish_with(self.address.clone())
+            .context(EstablishSnafu)
+    }
+
+    fn lock_pool(&self) -> MutexGuard<'_, Pool> {
+        self.pool.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dimse::{receive_event, receive_message, send_message, status, Event};
+    use crate::store_scp::{read_store_request, store_response};
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_dictionary_std::{tags, uids};
+    use dicom_object::{FileDicomObject, FileMetaTableBuilder};
+    use dicom_ul::association::ServerAssociationOptions;
+    use dicom_ul::pdu::Pdu;
+    use std::net::TcpListener;
+
+    fn test_object(sop_instance_uid: &str) -> DefaultDicomObject {
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uids::CT_IMAGE_STORAGE)
+            .media_storage_sop_instance_uid(sop_instance_uid)
+            .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+            .build()
+            .unwrap();
+        let mut object = FileDicomObject::new_empty_with_meta(meta);
+        object.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_instance_uid),
+        ));
+        object
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_maximum() {
+        let policy = RetryPolicy::new(10)
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(4), Duration::from_millis(800));
+        assert_eq!(policy.backoff(5), Duration::from_secs(1));
+        assert_eq!(policy.backoff(64), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn store_retries_in_new_association() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let scp = std::thread::spawn(move || {
+            let options = ServerAssociationOptions::new()
+                .ae_title("POOL-SCP")
+                .with_abstract_syntax(uids::CT_IMAGE_STORAGE);
+
+            // first association is dropped without responding
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = options.establish(stream).unwrap();
+            receive_message(&mut association).unwrap();
+            drop(association);
+
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = options.establish(stream).unwrap();
+            let mut received = Vec::new();
+            loop {
+                match receive_event(&mut association).unwrap() {
+                    Event::Message(message) => {
+                        let request =
+                            read_store_request(association.presentation_contexts(), &message)
+                                .unwrap();
+                        received.push((request.message_id, request.sop_instance_uid.clone()));
+                        let response = store_response(&request, status::SUCCESS);
+                        send_message(
+                            &mut association,
+                            message.presentation_context_id,
+                            &response,
+                            None,
+                        )
+                        .unwrap();
+                    }
+                    Event::ReleaseRequested => {
+                        association.send(&Pdu::ReleaseRP).unwrap();
+                        break;
+                    }
+                    Event::Aborted => panic!("association aborted"),
+                }
+            }
+            received
+        });
+
+        let client = DicomClient::new(format!("POOL-SCP@{}", addr).parse().unwrap())
+            .with_abstract_syntax(uids::CT_IMAGE_STORAGE)
+            .read_timeout(Duration::from_secs(10))
+            .retry_policy(RetryPolicy::new(2).initial_backoff(Duration::from_millis(10)));
+
+        assert_eq!(
+            client.store(&test_object("1.2.888.1")).unwrap(),
+            status::SUCCESS
+        );
+        assert_eq!(
+            client.store(&test_object("1.2.888.2")).unwrap(),
+            status::SUCCESS
+        );
+        assert_eq!(client.open_associations(), 1);
+        client.close().unwrap();
+
+        assert_eq!(
+            scp.join().unwrap(),
+            vec![(1, "1.2.888.1".to_string()), (2, "1.2.888.2".to_string())]
+        );
+    }
+}
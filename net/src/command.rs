@@ -0,0 +1,636 @@
+//! DIMSE command set module.
+//!
+//! This module provides a typed representation of DIMSE command sets,
+//! which can be built for any of the DIMSE-C and DIMSE-N services
+//! and parsed from received messages.
+//! It is independent from the transport:
+//! command sets are converted to and from [`InMemDicomObject`] values,
+//! or encoded and decoded in _Implicit VR Little Endian_
+//! via [`write_command`](crate::dimse::write_command)
+//! and [`read_command`](crate::dimse::read_command).
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_net::command::{CommandSet, Priority};
+//! # use dicom_net::dimse::status;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let request = CommandSet::c_find_rq("1.2.840.10008.5.1.4.1.2.2.1", 1)
+//!     .with_priority(Priority::High);
+//! let data = request.encode()?;
+//!
+//! let request = CommandSet::decode(&data)?;
+//! assert_eq!(request.priority, Some(Priority::High));
+//!
+//! let response = request.response(status::PENDING).with_data_set(true);
+//! assert_eq!(response.message_id_being_responded_to, Some(1));
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::{DataElement, PrimitiveValue, Tag, VR};
+use dicom_dictionary_std::{tags, uids::VERIFICATION};
+use dicom_object::InMemDicomObject;
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::dimse::{
+    self, command_field, command_str, command_u16, read_command, status, write_command, NO_DATA_SET,
+};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to encode command set
+    Encode {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to decode command set
+    Decode {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// missing or invalid command element
+    Element {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    #[snafu(display("invalid priority value {:#06x}", value))]
+    InvalidPriority { value: u16, backtrace: Backtrace },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The priority of a DIMSE request (PS3.7 section 9.3.1.1).
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Priority {
+    Low,
+    Medium,
+    High,
+}
+
+impl Priority {
+    /// Retrieve the value of this priority in a command set.
+    pub fn value(self) -> u16 {
+        match self {
+            Priority::Medium => 0x0000,
+            Priority::High => 0x0001,
+            Priority::Low => 0x0002,
+        }
+    }
+
+    /// Interpret a priority value from a command set.
+    pub fn from_value(value: u16) -> Option<Self> {
+        match value {
+            0x0000 => Some(Priority::Medium),
+            0x0001 => Some(Priority::High),
+            0x0002 => Some(Priority::Low),
+            _ => None,
+        }
+    }
+}
+
+/// A DIMSE command set with typed fields.
+///
+/// Fields which are not present in the command set are `None`.
+/// The Command Group Length is not kept here,
+/// as it is computed on encoding.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommandSet {
+    /// Command Field
+    pub command_field: u16,
+    /// Message ID, in requests
+    pub message_id: Option<u16>,
+    /// Message ID Being Responded To, in responses
+    pub message_id_being_responded_to: Option<u16>,
+    /// Priority, in C-STORE, C-FIND, C-GET and C-MOVE requests
+    pub priority: Option<Priority>,
+    /// Affected SOP Class UID
+    pub affected_sop_class_uid: Option<String>,
+    /// Affected SOP Instance UID
+    pub affected_sop_instance_uid: Option<String>,
+    /// Requested SOP Class UID, in DIMSE-N requests
+    pub requested_sop_class_uid: Option<String>,
+    /// Requested SOP Instance UID, in DIMSE-N requests
+    pub requested_sop_instance_uid: Option<String>,
+    /// Move Destination, in C-MOVE requests
+    pub move_destination: Option<String>,
+    /// Move Originator Application Entity Title, in C-STORE requests
+    pub move_originator_ae_title: Option<String>,
+    /// Move Originator Message ID, in C-STORE requests
+    pub move_originator_message_id: Option<u16>,
+    /// Event Type ID, in N-EVENT-REPORT messages
+    pub event_type_id: Option<u16>,
+    /// Action Type ID, in N-ACTION messages
+    pub action_type_id: Option<u16>,
+    /// Status, in responses
+    pub status: Option<u16>,
+    /// Error Comment, in responses
+    pub error_comment: Option<String>,
+    /// Number of Remaining Sub-operations, in C-GET and C-MOVE responses
+    pub remaining_sub_operations: Option<u16>,
+    /// Number of Completed Sub-operations, in C-GET and C-MOVE responses
+    pub completed_sub_operations: Option<u16>,
+    /// Number of Failed Sub-operations, in C-GET and C-MOVE responses
+    pub failed_sub_operations: Option<u16>,
+    /// Number of Warning Sub-operations, in C-GET and C-MOVE responses
+    pub warning_sub_operations: Option<u16>,
+    /// Whether a data set follows the command set
+    pub has_data_set: bool,
+}
+
+impl CommandSet {
+    /// Create a request command set for the given affected SOP class,
+    /// without a data set.
+    pub fn request(command_field: u16, sop_class_uid: &str, message_id: u16) -> Self {
+        CommandSet {
+            command_field,
+            message_id: Some(message_id),
+            affected_sop_class_uid: Some(sop_class_uid.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a C-ECHO-RQ command set.
+    pub fn c_echo_rq(message_id: u16) -> Self {
+        Self::request(command_field::C_ECHO_RQ, VERIFICATION, message_id)
+    }
+
+    /// Create a C-STORE-RQ command set with medium priority,
+    /// followed by the object to store.
+    pub fn c_store_rq(sop_class_uid: &str, sop_instance_uid: &str, message_id: u16) -> Self {
+        CommandSet {
+            affected_sop_instance_uid: Some(sop_instance_uid.to_string()),
+            ..Self::request(command_field::C_STORE_RQ, sop_class_uid, message_id)
+        }
+        .with_priority(Priority::Medium)
+        .with_data_set(true)
+    }
+
+    /// Create a C-FIND-RQ command set with medium priority,
+    /// followed by the query identifier.
+    pub fn c_find_rq(sop_class_uid: &str, message_id: u16) -> Self {
+        Self::request(command_field::C_FIND_RQ, sop_class_uid, message_id)
+            .with_priority(Priority::Medium)
+            .with_data_set(true)
+    }
+
+    /// Create a C-GET-RQ command set with medium priority,
+    /// followed by the retrieve identifier.
+    pub fn c_get_rq(sop_class_uid: &str, message_id: u16) -> Self {
+        Self::request(command_field::C_GET_RQ, sop_class_uid, message_id)
+            .with_priority(Priority::Medium)
+            .with_data_set(true)
+    }
+
+    /// Create a C-MOVE-RQ command set with medium priority,
+    /// followed by the retrieve identifier.
+    pub fn c_move_rq(sop_class_uid: &str, message_id: u16, destination: &str) -> Self {
+        CommandSet {
+            move_destination: Some(destination.to_string()),
+            ..Self::request(command_field::C_MOVE_RQ, sop_class_uid, message_id)
+        }
+        .with_priority(Priority::Medium)
+        .with_data_set(true)
+    }
+
+    /// Create a C-CANCEL-RQ command set
+    /// for the request with the given message ID.
+    pub fn c_cancel_rq(message_id_being_responded_to: u16) -> Self {
+        CommandSet {
+            command_field: command_field::C_CANCEL_RQ,
+            message_id_being_responded_to: Some(message_id_being_responded_to),
+            ..Default::default()
+        }
+    }
+
+    /// Create an N-CREATE-RQ command set,
+    /// followed by the initial attribute values.
+    ///
+    /// The SOP instance UID may be left out
+    /// for the performing node to assign one.
+    pub fn n_create_rq(
+        sop_class_uid: &str,
+        sop_instance_uid: Option<&str>,
+        message_id: u16,
+    ) -> Self {
+        CommandSet {
+            affected_sop_instance_uid: sop_instance_uid.map(str::to_string),
+            ..Self::request(command_field::N_CREATE_RQ, sop_class_uid, message_id)
+        }
+        .with_data_set(true)
+    }
+
+    /// Create an N-SET-RQ command set,
+    /// followed by the attribute modifications.
+    pub fn n_set_rq(sop_class_uid: &str, sop_instance_uid: &str, message_id: u16) -> Self {
+        Self::n_request(
+            command_field::N_SET_RQ,
+            sop_class_uid,
+            sop_instance_uid,
+            message_id,
+        )
+        .with_data_set(true)
+    }
+
+    /// Create an N-GET-RQ command set.
+    pub fn n_get_rq(sop_class_uid: &str, sop_instance_uid: &str, message_id: u16) -> Self {
+        Self::n_request(
+            command_field::N_GET_RQ,
+            sop_class_uid,
+            sop_instance_uid,
+            message_id,
+        )
+    }
+
+    /// Create an N-ACTION-RQ command set.
+    pub fn n_action_rq(
+        sop_class_uid: &str,
+        sop_instance_uid: &str,
+        action_type_id: u16,
+        message_id: u16,
+    ) -> Self {
+        CommandSet {
+            action_type_id: Some(action_type_id),
+            ..Self::n_request(
+                command_field::N_ACTION_RQ,
+                sop_class_uid,
+                sop_instance_uid,
+                message_id,
+            )
+        }
+    }
+
+    /// Create an N-DELETE-RQ command set.
+    pub fn n_delete_rq(sop_class_uid: &str, sop_instance_uid: &str, message_id: u16) -> Self {
+        Self::n_request(
+            command_field::N_DELETE_RQ,
+            sop_class_uid,
+            sop_instance_uid,
+            message_id,
+        )
+    }
+
+    /// Create an N-EVENT-REPORT-RQ command set.
+    pub fn n_event_report_rq(
+        sop_class_uid: &str,
+        sop_instance_uid: &str,
+        event_type_id: u16,
+        message_id: u16,
+    ) -> Self {
+        CommandSet {
+            affected_sop_instance_uid: Some(sop_instance_uid.to_string()),
+            event_type_id: Some(event_type_id),
+            ..Self::request(command_field::N_EVENT_REPORT_RQ, sop_class_uid, message_id)
+        }
+    }
+
+    fn n_request(
+        command_field: u16,
+        sop_class_uid: &str,
+        sop_instance_uid: &str,
+        message_id: u16,
+    ) -> Self {
+        CommandSet {
+            command_field,
+            message_id: Some(message_id),
+            requested_sop_class_uid: Some(sop_class_uid.to_string()),
+            requested_sop_instance_uid: Some(sop_instance_uid.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Create a response command set to this request,
+    /// with the given status and without a data set.
+    ///
+    /// The SOP class and instance of the request are carried over
+    /// as the affected SOP class and instance,
+    /// as well as its event or action type.
+    pub fn response(&self, status: u16) -> Self {
+        CommandSet {
+            command_field: self.command_field | 0x8000,
+            message_id_being_responded_to: self.message_id,
+            affected_sop_class_uid: self
+                .affected_sop_class_uid
+                .clone()
+                .or_else(|| self.requested_sop_class_uid.clone()),
+            affected_sop_instance_uid: self
+                .affected_sop_instance_uid
+                .clone()
+                .or_else(|| self.requested_sop_instance_uid.clone()),
+            event_type_id: self.event_type_id,
+            action_type_id: self.action_type_id,
+            status: Some(status),
+            ..Default::default()
+        }
+    }
+
+    /// Set the priority of the request.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Set whether a data set follows the command set.
+    pub fn with_data_set(mut self, has_data_set: bool) -> Self {
+        self.has_data_set = has_data_set;
+        self
+    }
+
+    /// Set the error comment of the response.
+    pub fn with_error_comment(mut self, comment: impl Into<String>) -> Self {
+        self.error_comment = Some(comment.into());
+        self
+    }
+
+    /// Whether this command set belongs to a response message.
+    pub fn is_response(&self) -> bool {
+        command_field::is_response(self.command_field)
+    }
+
+    /// Retrieve the category of the response status,
+    /// if the command set has one.
+    pub fn status_type(&self) -> Option<status::StatusType> {
+        self.status.map(status::status_type)
+    }
+
+    /// Convert the command set into a DICOM object,
+    /// without the Command Group Length.
+    pub fn to_object(&self) -> InMemDicomObject {
+        let mut obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::COMMAND_FIELD,
+                VR::US,
+                PrimitiveValue::from(self.command_field),
+            ),
+            DataElement::new(
+                tags::COMMAND_DATA_SET_TYPE,
+                VR::US,
+                PrimitiveValue::from(if self.has_data_set { 0 } else { NO_DATA_SET }),
+            ),
+        ]);
+
+        let u16_fields = [
+            (tags::MESSAGE_ID, self.message_id),
+            (
+                tags::MESSAGE_ID_BEING_RESPONDED_TO,
+                self.message_id_being_responded_to,
+            ),
+            (tags::PRIORITY, self.priority.map(Priority::value)),
+            (
+                tags::MOVE_ORIGINATOR_MESSAGE_ID,
+                self.move_originator_message_id,
+            ),
+            (tags::EVENT_TYPE_ID, self.event_type_id),
+            (tags::ACTION_TYPE_ID, self.action_type_id),
+            (tags::STATUS, self.status),
+            (
+                tags::NUMBER_OF_REMAINING_SUBOPERATIONS,
+                self.remaining_sub_operations,
+            ),
+            (
+                tags::NUMBER_OF_COMPLETED_SUBOPERATIONS,
+                self.completed_sub_operations,
+            ),
+            (
+                tags::NUMBER_OF_FAILED_SUBOPERATIONS,
+                self.failed_sub_operations,
+            ),
+            (
+                tags::NUMBER_OF_WARNING_SUBOPERATIONS,
+                self.warning_sub_operations,
+            ),
+        ];
+        for (tag, value) in u16_fields {
+            if let Some(value) = value {
+                obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+            }
+        }
+
+        let str_fields = [
+            (
+                tags::AFFECTED_SOP_CLASS_UID,
+                VR::UI,
+                &self.affected_sop_class_uid,
+            ),
+            (
+                tags::AFFECTED_SOP_INSTANCE_UID,
+                VR::UI,
+                &self.affected_sop_instance_uid,
+            ),
+            (
+                tags::REQUESTED_SOP_CLASS_UID,
+                VR::UI,
+                &self.requested_sop_class_uid,
+            ),
+            (
+                tags::REQUESTED_SOP_INSTANCE_UID,
+                VR::UI,
+                &self.requested_sop_instance_uid,
+            ),
+            (tags::MOVE_DESTINATION, VR::AE, &self.move_destination),
+            (
+                tags::MOVE_ORIGINATOR_APPLICATION_ENTITY_TITLE,
+                VR::AE,
+                &self.move_originator_ae_title,
+            ),
+            (tags::ERROR_COMMENT, VR::LO, &self.error_comment),
+        ];
+        for (tag, vr, value) in str_fields {
+            if let Some(value) = value {
+                obj.put(DataElement::new(
+                    tag,
+                    vr,
+                    PrimitiveValue::from(value.as_str()),
+                ));
+            }
+        }
+
+        obj
+    }
+
+    /// Interpret a DICOM object as a command set.
+    ///
+    /// Only the Command Field is required.
+    /// Trailing padding is removed from textual fields,
+    /// and unknown command elements are ignored.
+    pub fn from_object(command: &InMemDicomObject) -> Result<Self> {
+        let command_field = command_u16(command, tags::COMMAND_FIELD).context(ElementSnafu)?;
+        let priority = match optional(command, tags::PRIORITY, command_u16)? {
+            Some(value) => {
+                Some(Priority::from_value(value).context(InvalidPrioritySnafu { value })?)
+            }
+            None => None,
+        };
+        let has_data_set = optional(command, tags::COMMAND_DATA_SET_TYPE, command_u16)?
+            .map(|value| value != NO_DATA_SET)
+            .unwrap_or(false);
+
+        Ok(CommandSet {
+            command_field,
+            message_id: optional(command, tags::MESSAGE_ID, command_u16)?,
+            message_id_being_responded_to: optional(
+                command,
+                tags::MESSAGE_ID_BEING_RESPONDED_TO,
+                command_u16,
+            )?,
+            priority,
+            affected_sop_class_uid: optional(command, tags::AFFECTED_SOP_CLASS_UID, command_str)?,
+            affected_sop_instance_uid: optional(
+                command,
+                tags::AFFECTED_SOP_INSTANCE_UID,
+                command_str,
+            )?,
+            requested_sop_class_uid: optional(command, tags::REQUESTED_SOP_CLASS_UID, command_str)?,
+            requested_sop_instance_uid: optional(
+                command,
+                tags::REQUESTED_SOP_INSTANCE_UID,
+                command_str,
+            )?,
+            move_destination: optional(command, tags::MOVE_DESTINATION, command_str)?,
+            move_originator_ae_title: optional(
+                command,
+                tags::MOVE_ORIGINATOR_APPLICATION_ENTITY_TITLE,
+                command_str,
+            )?,
+            move_originator_message_id: optional(
+                command,
+                tags::MOVE_ORIGINATOR_MESSAGE_ID,
+                command_u16,
+            )?,
+            event_type_id: optional(command, tags::EVENT_TYPE_ID, command_u16)?,
+            action_type_id: optional(command, tags::ACTION_TYPE_ID, command_u16)?,
+            status: optional(command, tags::STATUS, command_u16)?,
+            error_comment: optional(command, tags::ERROR_COMMENT, command_str)?,
+            remaining_sub_operations: optional(
+                command,
+                tags::NUMBER_OF_REMAINING_SUBOPERATIONS,
+                command_u16,
+            )?,
+            completed_sub_operations: optional(
+                command,
+                tags::NUMBER_OF_COMPLETED_SUBOPERATIONS,
+                command_u16,
+            )?,
+            failed_sub_operations: optional(
+                command,
+                tags::NUMBER_OF_FAILED_SUBOPERATIONS,
+                command_u16,
+            )?,
+            warning_sub_operations: optional(
+                command,
+                tags::NUMBER_OF_WARNING_SUBOPERATIONS,
+                command_u16,
+            )?,
+            has_data_set,
+        })
+    }
+
+    /// Encode the command set in _Implicit VR Little Endian_,
+    /// including the Command Group Length.
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        write_command(&self.to_object()).context(EncodeSnafu)
+    }
+
+    /// Decode a command set encoded in _Implicit VR Little Endian_.
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let command = read_command(data).context(DecodeSnafu)?;
+        Self::from_object(&command)
+    }
+}
+
+/// Retrieve a command element which may be absent.
+fn optional<T>(
+    command: &InMemDicomObject,
+    tag: Tag,
+    get: fn(&InMemDicomObject, Tag) -> dimse::Result<T>,
+) -> Result<Option<T>> {
+    if command.element_opt(tag).ok().flatten().is_none() {
+        return Ok(None);
+    }
+    get(command, tag).map(Some).context(ElementSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_request_round_trip() {
+        let request = CommandSet {
+            move_originator_ae_title: Some("MOVE-SCU".to_string()),
+            move_originator_message_id: Some(3),
+            ..CommandSet::c_store_rq("1.2.840.10008.5.1.4.1.1.7", "1.2.3.4.5", 9)
+        };
+
+        let data = request.encode().unwrap();
+        let decoded = CommandSet::decode(&data).unwrap();
+        assert_eq!(decoded, request);
+        assert!(decoded.has_data_set);
+        assert_eq!(decoded.priority, Some(Priority::Medium));
+
+        let response = decoded.response(status::SUCCESS);
+        assert_eq!(response.command_field, command_field::C_STORE_RSP);
+        assert!(response.is_response());
+        assert_eq!(response.message_id_being_responded_to, Some(9));
+        assert_eq!(
+            response.affected_sop_instance_uid.as_deref(),
+            Some("1.2.3.4.5")
+        );
+        assert_eq!(response.status_type(), Some(status::StatusType::Success));
+        assert!(!response.has_data_set);
+    }
+
+    #[test]
+    fn n_request_response() {
+        let request =
+            CommandSet::n_action_rq("1.2.840.10008.1.20.1", "1.2.840.10008.1.20.1.1", 1, 5);
+        let object = request.to_object();
+        assert_eq!(
+            command_str(&object, tags::REQUESTED_SOP_CLASS_UID).unwrap(),
+            "1.2.840.10008.1.20.1"
+        );
+        assert_eq!(
+            command_u16(&object, tags::COMMAND_DATA_SET_TYPE).unwrap(),
+            NO_DATA_SET
+        );
+        assert!(object.element(tags::AFFECTED_SOP_CLASS_UID).is_err());
+
+        let response = request
+            .response(0x0110)
+            .with_error_comment("processing failure");
+        assert_eq!(response.command_field, command_field::N_ACTION_RSP);
+        assert_eq!(
+            response.affected_sop_class_uid.as_deref(),
+            Some("1.2.840.10008.1.20.1")
+        );
+        assert_eq!(response.action_type_id, Some(1));
+        assert_eq!(response.status_type(), Some(status::StatusType::Failure));
+        assert_eq!(
+            CommandSet::decode(&response.encode().unwrap()).unwrap(),
+            response
+        );
+    }
+
+    #[test]
+    fn reject_invalid_priority() {
+        let mut object = CommandSet::c_find_rq("1.2.840.10008.5.1.4.1.2.2.1", 1).to_object();
+        object.put(DataElement::new(
+            tags::PRIORITY,
+            VR::US,
+            PrimitiveValue::from(7_u16),
+        ));
+        assert!(matches!(
+            CommandSet::from_object(&object),
+            Err(Error::InvalidPriority { value: 7, .. })
+        ));
+
+        object.remove_element(tags::COMMAND_FIELD);
+        assert!(matches!(
+            CommandSet::from_object(&object),
+            Err(Error::Element { .. })
+        ));
+    }
+}
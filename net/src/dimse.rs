@@ -0,0 +1,814 @@
+//! DIMSE message exchange module.
+//!
+//! A DIMSE message comprises a command set,
+//! always encoded in _Implicit VR Little Endian_,
+//! optionally followed by a data set
+//! encoded in the transfer syntax of the respective presentation context.
+//!
+//! The functions in this module send and receive whole messages
+//! through an established association,
+//! taking care of P-Data value fragmentation and reassembly.
+//! They work with both association requesters and acceptors
+//! through the [`Association`] trait.
+//!
+//! With the `async` feature enabled,
+//! the `_async` variants of these functions do the same
+//! through the `AsyncAssociation` trait,
+//! for associations established on top of tokio.
+#[cfg(feature = "async")]
+use std::future::Future;
+use std::io::Write;
+use std::net::TcpStream;
+#[cfg(feature = "async")]
+use std::pin::Pin;
+
+use dicom_core::{
+    value::{CastValueError, ConvertValueError},
+    DataElement, PrimitiveValue, Tag, VR,
+};
+use dicom_dictionary_std::tags;
+use dicom_object::InMemDicomObject;
+use dicom_transfer_syntax_registry::entries::IMPLICIT_VR_LITTLE_ENDIAN;
+use dicom_ul::association::{client, server, ClientAssociation, PDataWriter, ServerAssociation};
+#[cfg(feature = "async")]
+use dicom_ul::association::{AsyncClientAssociation, AsyncServerAssociation};
+use dicom_ul::pdu::codec::{self, PDataAssembler};
+use dicom_ul::pdu::{PDataValue, PDataValueType, Pdu, PresentationContextResult};
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to communicate through the requester association
+    Client { source: client::Error },
+
+    /// failed to communicate through the acceptor association
+    Server { source: server::Error },
+
+    /// failed to encode command set
+    EncodeCommand {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    /// failed to decode command set
+    DecodeCommand {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    /// failed to send data set fragments
+    SendData {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("missing command element {}", tag))]
+    MissingCommandElement { tag: Tag, backtrace: Backtrace },
+
+    #[snafu(display("invalid value in command element {}", tag))]
+    InvalidCommandElement {
+        tag: Tag,
+        source: ConvertValueError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("command element {} is not a string", tag))]
+    NotAString {
+        tag: Tag,
+        source: CastValueError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("unexpected PDU `{:?}`", pdu))]
+    #[non_exhaustive]
+    UnexpectedPdu {
+        /// the PDU obtained from the other node
+        pdu: Pdu,
+    },
+
+    #[snafu(display(
+        "P-Data value for presentation context {} while receiving a message for {}",
+        got,
+        expected
+    ))]
+    PresentationContextMismatch {
+        expected: u8,
+        got: u8,
+        backtrace: Backtrace,
+    },
+
+    /// failed to reassemble the message from P-Data values
+    AssemblePData { source: codec::Error },
+
+    /// received a data set fragment before the end of the command set
+    UnexpectedData { backtrace: Backtrace },
+
+    /// the association was released by the other node
+    Released { backtrace: Backtrace },
+
+    /// the association was aborted by the other node
+    Aborted { backtrace: Backtrace },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Command field codes (PS3.7 section E.1).
+pub mod command_field {
+    pub const C_STORE_RQ: u16 = 0x0001;
+    pub const C_STORE_RSP: u16 = 0x8001;
+    pub const C_GET_RQ: u16 = 0x0010;
+    pub const C_GET_RSP: u16 = 0x8010;
+    pub const C_FIND_RQ: u16 = 0x0020;
+    pub const C_FIND_RSP: u16 = 0x8020;
+    pub const C_MOVE_RQ: u16 = 0x0021;
+    pub const C_MOVE_RSP: u16 = 0x8021;
+    pub const C_ECHO_RQ: u16 = 0x0030;
+    pub const C_ECHO_RSP: u16 = 0x8030;
+    pub const C_CANCEL_RQ: u16 = 0x0FFF;
+    pub const N_EVENT_REPORT_RQ: u16 = 0x0100;
+    pub const N_EVENT_REPORT_RSP: u16 = 0x8100;
+    pub const N_GET_RQ: u16 = 0x0110;
+    pub const N_GET_RSP: u16 = 0x8110;
+    pub const N_SET_RQ: u16 = 0x0120;
+    pub const N_SET_RSP: u16 = 0x8120;
+    pub const N_ACTION_RQ: u16 = 0x0130;
+    pub const N_ACTION_RSP: u16 = 0x8130;
+    pub const N_CREATE_RQ: u16 = 0x0140;
+    pub const N_CREATE_RSP: u16 = 0x8140;
+    pub const N_DELETE_RQ: u16 = 0x0150;
+    pub const N_DELETE_RSP: u16 = 0x8150;
+
+    /// Whether the command field refers to a response message.
+    pub fn is_response(command_field: u16) -> bool {
+        command_field & 0x8000 != 0
+    }
+}
+
+/// Status codes common to all DIMSE services (PS3.7 annex C).
+pub mod status {
+    /// The operation was successful.
+    pub const SUCCESS: u16 = 0x0000;
+    /// The operation was cancelled.
+    pub const CANCEL: u16 = 0xFE00;
+    /// The operation is pending.
+    pub const PENDING: u16 = 0xFF00;
+    /// The operation is pending, with warnings for optional keys.
+    pub const PENDING_WARNING: u16 = 0xFF01;
+    /// The command field is not recognized by the service provider.
+    pub const UNRECOGNIZED_OPERATION: u16 = 0x0211;
+    /// The SOP class is not supported by the service provider.
+    pub const SOP_CLASS_NOT_SUPPORTED: u16 = 0x0122;
+
+    /// The category of a DIMSE status code.
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+    pub enum StatusType {
+        Success,
+        Warning,
+        Failure,
+        Cancel,
+        Pending,
+    }
+
+    /// Classify a DIMSE status code.
+    pub fn status_type(status: u16) -> StatusType {
+        match status {
+            SUCCESS => StatusType::Success,
+            0x0001 | 0x0107 | 0x0116 | 0xB000..=0xBFFF => StatusType::Warning,
+            CANCEL => StatusType::Cancel,
+            PENDING | PENDING_WARNING => StatusType::Pending,
+            _ => StatusType::Failure,
+        }
+    }
+}
+
+/// The Command Data Set Type value
+/// indicating that no data set follows the command set.
+pub const NO_DATA_SET: u16 = 0x0101;
+
+/// Common interface for established associations,
+/// through which DIMSE messages can be exchanged.
+///
+/// This is implemented for both [`ClientAssociation`]
+/// and [`ServerAssociation`].
+pub trait Association {
+    /// Retrieve the list of accepted presentation contexts.
+    fn presentation_contexts(&self) -> &[PresentationContextResult];
+
+    /// Send a PDU message to the other node.
+    fn send(&mut self, pdu: &Pdu) -> Result<()>;
+
+    /// Read a PDU message from the other node.
+    fn receive(&mut self) -> Result<Pdu>;
+
+    /// Prepare a P-Data writer for sending data set fragments.
+    fn send_pdata(&mut self, presentation_context_id: u8) -> PDataWriter<&mut TcpStream>;
+}
+
+impl Association for ClientAssociation {
+    fn presentation_contexts(&self) -> &[PresentationContextResult] {
+        ClientAssociation::presentation_contexts(self)
+    }
+
+    fn send(&mut self, pdu: &Pdu) -> Result<()> {
+        ClientAssociation::send(self, pdu).context(ClientSnafu)
+    }
+
+    fn receive(&mut self) -> Result<Pdu> {
+        ClientAssociation::receive(self).context(ClientSnafu)
+    }
+
+    fn send_pdata(&mut self, presentation_context_id: u8) -> PDataWriter<&mut TcpStream> {
+        ClientAssociation::send_pdata(self, presentation_context_id)
+    }
+}
+
+impl Association for ServerAssociation {
+    fn presentation_contexts(&self) -> &[PresentationContextResult] {
+        ServerAssociation::presentation_contexts(self)
+    }
+
+    fn send(&mut self, pdu: &Pdu) -> Result<()> {
+        ServerAssociation::send(self, pdu).context(ServerSnafu)
+    }
+
+    fn receive(&mut self) -> Result<Pdu> {
+        ServerAssociation::receive(self).context(ServerSnafu)
+    }
+
+    fn send_pdata(&mut self, presentation_context_id: u8) -> PDataWriter<&mut TcpStream> {
+        ServerAssociation::send_pdata(self, presentation_context_id)
+    }
+}
+
+/// A boxed future, as returned by the methods of [`AsyncAssociation`].
+#[cfg(feature = "async")]
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Common interface for asynchronous established associations,
+/// through which DIMSE messages can be exchanged.
+///
+/// This is the asynchronous counterpart of [`Association`],
+/// implemented for both [`AsyncClientAssociation`]
+/// and [`AsyncServerAssociation`].
+#[cfg(feature = "async")]
+pub trait AsyncAssociation: Send {
+    /// Retrieve the list of accepted presentation contexts.
+    fn presentation_contexts(&self) -> &[PresentationContextResult];
+
+    /// Send a PDU message to the other node.
+    fn send<'a>(&'a mut self, pdu: &'a Pdu) -> BoxFuture<'a, Result<()>>;
+
+    /// Read a PDU message from the other node.
+    fn receive(&mut self) -> BoxFuture<'_, Result<Pdu>>;
+
+    /// Send a data set in one or more P-Data PDUs.
+    fn send_pdata<'a>(
+        &'a mut self,
+        presentation_context_id: u8,
+        data: &'a [u8],
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+#[cfg(feature = "async")]
+impl AsyncAssociation for AsyncClientAssociation {
+    fn presentation_contexts(&self) -> &[PresentationContextResult] {
+        AsyncClientAssociation::presentation_contexts(self)
+    }
+
+    fn send<'a>(&'a mut self, pdu: &'a Pdu) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            AsyncClientAssociation::send(self, pdu)
+                .await
+                .context(ClientSnafu)
+        })
+    }
+
+    fn receive(&mut self) -> BoxFuture<'_, Result<Pdu>> {
+        Box::pin(async move {
+            AsyncClientAssociation::receive(self)
+                .await
+                .context(ClientSnafu)
+        })
+    }
+
+    fn send_pdata<'a>(
+        &'a mut self,
+        presentation_context_id: u8,
+        data: &'a [u8],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            AsyncClientAssociation::send_pdata(self, presentation_context_id, data)
+                .await
+                .context(ClientSnafu)
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl AsyncAssociation for AsyncServerAssociation {
+    fn presentation_contexts(&self) -> &[PresentationContextResult] {
+        AsyncServerAssociation::presentation_contexts(self)
+    }
+
+    fn send<'a>(&'a mut self, pdu: &'a Pdu) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            AsyncServerAssociation::send(self, pdu)
+                .await
+                .context(ServerSnafu)
+        })
+    }
+
+    fn receive(&mut self) -> BoxFuture<'_, Result<Pdu>> {
+        Box::pin(async move {
+            AsyncServerAssociation::receive(self)
+                .await
+                .context(ServerSnafu)
+        })
+    }
+
+    fn send_pdata<'a>(
+        &'a mut self,
+        presentation_context_id: u8,
+        data: &'a [u8],
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            AsyncServerAssociation::send_pdata(self, presentation_context_id, data)
+                .await
+                .context(ServerSnafu)
+        })
+    }
+}
+
+/// A DIMSE message received through an association.
+#[derive(Debug, Clone)]
+pub struct Message {
+    /// The presentation context in which the message was sent
+    pub presentation_context_id: u8,
+    /// The command set
+    pub command: InMemDicomObject,
+    /// The encoded data set, if the command declares one
+    pub data: Option<Vec<u8>>,
+}
+
+impl Message {
+    /// Retrieve the Command Field of the message.
+    pub fn command_field(&self) -> Result<u16> {
+        command_u16(&self.command, tags::COMMAND_FIELD)
+    }
+
+    /// Retrieve the Message ID of a request message.
+    pub fn message_id(&self) -> Result<u16> {
+        command_u16(&self.command, tags::MESSAGE_ID)
+    }
+
+    /// Retrieve the Message ID Being Responded To of a response message.
+    pub fn message_id_being_responded_to(&self) -> Result<u16> {
+        command_u16(&self.command, tags::MESSAGE_ID_BEING_RESPONDED_TO)
+    }
+
+    /// Retrieve the Status of a response message.
+    pub fn status(&self) -> Result<u16> {
+        command_u16(&self.command, tags::STATUS)
+    }
+
+    /// Retrieve the Affected SOP Class UID of the message,
+    /// without trailing padding.
+    pub fn affected_sop_class_uid(&self) -> Result<String> {
+        command_str(&self.command, tags::AFFECTED_SOP_CLASS_UID)
+    }
+}
+
+/// An event observed while waiting for a DIMSE message.
+#[derive(Debug, Clone)]
+pub enum Event {
+    /// A whole DIMSE message was received
+    Message(Message),
+    /// The other node requested the association to be released
+    ReleaseRequested,
+    /// The other node aborted the association
+    Aborted,
+}
+
+/// Encode a command set in _Implicit VR Little Endian_,
+/// replacing its Command Group Length with the correct value.
+pub fn write_command(command: &InMemDicomObject) -> Result<Vec<u8>> {
+    let ts = IMPLICIT_VR_LITTLE_ENDIAN.erased();
+
+    let mut command = command.clone();
+    command.remove_element(tags::COMMAND_GROUP_LENGTH);
+    let mut body = Vec::new();
+    command
+        .write_dataset_with_ts(&mut body, &ts)
+        .context(EncodeCommandSnafu)?;
+
+    let group_length = InMemDicomObject::from_element_iter([DataElement::new(
+        tags::COMMAND_GROUP_LENGTH,
+        VR::UL,
+        PrimitiveValue::from(body.len() as u32),
+    )]);
+    let mut data = Vec::with_capacity(body.len() + 12);
+    group_length
+        .write_dataset_with_ts(&mut data, &ts)
+        .context(EncodeCommandSnafu)?;
+    data.extend(body);
+    Ok(data)
+}
+
+/// Decode a command set encoded in _Implicit VR Little Endian_.
+pub fn read_command(data: &[u8]) -> Result<InMemDicomObject> {
+    let ts = IMPLICIT_VR_LITTLE_ENDIAN.erased();
+    InMemDicomObject::read_dataset_with_ts(data, &ts).context(DecodeCommandSnafu)
+}
+
+/// Send a DIMSE message through the association,
+/// with the given command set and optional encoded data set.
+///
+/// The Command Data Set Type of the command is not checked,
+/// and should be consistent with the presence of `data`.
+pub fn send_message<A>(
+    association: &mut A,
+    presentation_context_id: u8,
+    command: &InMemDicomObject,
+    data: Option<&[u8]>,
+) -> Result<()>
+where
+    A: Association + ?Sized,
+{
+    association.send(&command_pdu(presentation_context_id, command)?)?;
+
+    if let Some(data) = data {
+        let mut writer = association.send_pdata(presentation_context_id);
+        writer.write_all(data).context(SendDataSnafu)?;
+        writer.finish().context(SendDataSnafu)?;
+    }
+    Ok(())
+}
+
+/// Send a DIMSE message through the association asynchronously,
+/// with the given command set and optional encoded data set.
+///
+/// See [`send_message`] for details.
+#[cfg(feature = "async")]
+pub async fn send_message_async<A>(
+    association: &mut A,
+    presentation_context_id: u8,
+    command: &InMemDicomObject,
+    data: Option<&[u8]>,
+) -> Result<()>
+where
+    A: AsyncAssociation + ?Sized,
+{
+    let pdu = command_pdu(presentation_context_id, command)?;
+    association.send(&pdu).await?;
+
+    if let Some(data) = data {
+        association
+            .send_pdata(presentation_context_id, data)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Build the P-Data PDU carrying the given command set.
+fn command_pdu(presentation_context_id: u8, command: &InMemDicomObject) -> Result<Pdu> {
+    Ok(Pdu::PData {
+        data: vec![PDataValue {
+            presentation_context_id,
+            value_type: PDataValueType::Command,
+            is_last: true,
+            data: write_command(command)?,
+        }],
+    })
+}
+
+/// Wait for the next DIMSE message or association event.
+///
+/// P-Data values are collected until the command set is complete
+/// and, if the command declares a data set,
+/// until the data set is complete as well.
+/// A release request or abort from the other node
+/// is reported as an [`Event`] if observed between messages,
+/// and as an error if observed in the middle of a message.
+pub fn receive_event<A>(association: &mut A) -> Result<Event>
+where
+    A: Association + ?Sized,
+{
+    let mut assembler = MessageAssembler::default();
+    loop {
+        if let Some(event) = assembler.push(association.receive()?)? {
+            return Ok(event);
+        }
+    }
+}
+
+/// Wait for the next DIMSE message or association event asynchronously.
+///
+/// See [`receive_event`] for details.
+#[cfg(feature = "async")]
+pub async fn receive_event_async<A>(association: &mut A) -> Result<Event>
+where
+    A: AsyncAssociation + ?Sized,
+{
+    let mut assembler = MessageAssembler::default();
+    loop {
+        if let Some(event) = assembler.push(association.receive().await?)? {
+            return Ok(event);
+        }
+    }
+}
+
+/// The incremental reassembly of a DIMSE message
+/// from the PDUs received through an association.
+///
+/// P-Data values are put back together by a [`PDataAssembler`],
+/// and the command set is kept until its data set is complete.
+#[derive(Debug, Default)]
+pub(crate) struct MessageAssembler {
+    pdata: PDataAssembler,
+    command: Option<(u8, InMemDicomObject)>,
+}
+
+impl MessageAssembler {
+    /// Process the next PDU received,
+    /// returning an event once it is observed.
+    pub(crate) fn push(&mut self, pdu: Pdu) -> Result<Option<Event>> {
+        let between_messages = self.command.is_none() && !self.pdata.has_partial_message();
+        let values = match pdu {
+            Pdu::PData { data } => data,
+            Pdu::ReleaseRQ if between_messages => return Ok(Some(Event::ReleaseRequested)),
+            Pdu::AbortRQ { .. } if between_messages => return Ok(Some(Event::Aborted)),
+            Pdu::ReleaseRQ => return ReleasedSnafu.fail(),
+            Pdu::AbortRQ { .. } => return AbortedSnafu.fail(),
+            pdu => return UnexpectedPduSnafu { pdu }.fail(),
+        };
+
+        for value in values {
+            match &self.command {
+                Some((expected, _)) => ensure!(
+                    value.presentation_context_id == *expected,
+                    PresentationContextMismatchSnafu {
+                        expected: *expected,
+                        got: value.presentation_context_id,
+                    }
+                ),
+                None => ensure!(
+                    value.value_type == PDataValueType::Command,
+                    UnexpectedDataSnafu
+                ),
+            }
+
+            let message = match self.pdata.push(value).context(AssemblePDataSnafu)? {
+                Some(message) => message,
+                None => continue,
+            };
+            match message.value_type {
+                PDataValueType::Command => {
+                    let command = read_command(&message.data)?;
+                    let data_set_type = command_u16(&command, tags::COMMAND_DATA_SET_TYPE)?;
+                    if data_set_type == NO_DATA_SET {
+                        return Ok(Some(Event::Message(Message {
+                            presentation_context_id: message.presentation_context_id,
+                            command,
+                            data: None,
+                        })));
+                    }
+                    self.command = Some((message.presentation_context_id, command));
+                }
+                PDataValueType::Data => {
+                    let (presentation_context_id, command) =
+                        self.command.take().context(UnexpectedDataSnafu)?;
+                    return Ok(Some(Event::Message(Message {
+                        presentation_context_id,
+                        command,
+                        data: Some(message.data),
+                    })));
+                }
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Wait for the next DIMSE message.
+///
+/// Unlike [`receive_event`],
+/// a release request or abort from the other node is reported as an error.
+pub fn receive_message<A>(association: &mut A) -> Result<Message>
+where
+    A: Association + ?Sized,
+{
+    match receive_event(association)? {
+        Event::Message(message) => Ok(message),
+        Event::ReleaseRequested => ReleasedSnafu.fail(),
+        Event::Aborted => AbortedSnafu.fail(),
+    }
+}
+
+/// Wait for the next DIMSE message asynchronously.
+///
+/// Unlike [`receive_event_async`],
+/// a release request or abort from the other node is reported as an error.
+#[cfg(feature = "async")]
+pub async fn receive_message_async<A>(association: &mut A) -> Result<Message>
+where
+    A: AsyncAssociation + ?Sized,
+{
+    match receive_event_async(association).await? {
+        Event::Message(message) => Ok(message),
+        Event::ReleaseRequested => ReleasedSnafu.fail(),
+        Event::Aborted => AbortedSnafu.fail(),
+    }
+}
+
+/// Create a response command set to a request
+/// for an operation which is not supported by this node,
+/// with the status [`UNRECOGNIZED_OPERATION`](status::UNRECOGNIZED_OPERATION).
+pub fn unrecognized_operation_response(request: &Message) -> Result<InMemDicomObject> {
+    let command_field = request.command_field()?;
+    let message_id = request.message_id()?;
+    let mut response = InMemDicomObject::from_element_iter([
+        DataElement::new(
+            tags::COMMAND_FIELD,
+            VR::US,
+            PrimitiveValue::from(command_field | 0x8000),
+        ),
+        DataElement::new(
+            tags::MESSAGE_ID_BEING_RESPONDED_TO,
+            VR::US,
+            PrimitiveValue::from(message_id),
+        ),
+        DataElement::new(
+            tags::COMMAND_DATA_SET_TYPE,
+            VR::US,
+            PrimitiveValue::from(NO_DATA_SET),
+        ),
+        DataElement::new(
+            tags::STATUS,
+            VR::US,
+            PrimitiveValue::from(status::UNRECOGNIZED_OPERATION),
+        ),
+    ]);
+    if let Ok(uid) = request.affected_sop_class_uid() {
+        response.put(DataElement::new(
+            tags::AFFECTED_SOP_CLASS_UID,
+            VR::UI,
+            PrimitiveValue::from(uid),
+        ));
+    }
+    Ok(response)
+}
+
+/// Retrieve an unsigned 16-bit integer from a command set.
+pub(crate) fn command_u16(command: &InMemDicomObject, tag: Tag) -> Result<u16> {
+    command
+        .element_opt(tag)
+        .ok()
+        .flatten()
+        .context(MissingCommandElementSnafu { tag })?
+        .to_int::<u16>()
+        .context(InvalidCommandElementSnafu { tag })
+}
+
+/// Retrieve a string from a command set, without trailing padding.
+pub(crate) fn command_str(command: &InMemDicomObject, tag: Tag) -> Result<String> {
+    let value = command
+        .element_opt(tag)
+        .ok()
+        .flatten()
+        .context(MissingCommandElementSnafu { tag })?
+        .to_str()
+        .context(NotAStringSnafu { tag })?;
+    Ok(value
+        .trim_end_matches(|c: char| c == '\0' || c == ' ')
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_round_trip() {
+        let command = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::AFFECTED_SOP_CLASS_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.840.10008.1.1\0"),
+            ),
+            DataElement::new(
+                tags::COMMAND_FIELD,
+                VR::US,
+                PrimitiveValue::from(command_field::C_ECHO_RQ),
+            ),
+            DataElement::new(tags::MESSAGE_ID, VR::US, PrimitiveValue::from(7_u16)),
+            DataElement::new(
+                tags::COMMAND_DATA_SET_TYPE,
+                VR::US,
+                PrimitiveValue::from(NO_DATA_SET),
+            ),
+        ]);
+
+        let data = write_command(&command).unwrap();
+        // group length element: tag + length + 4 byte value
+        assert_eq!(
+            u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize,
+            data.len() - 12
+        );
+
+        let command = read_command(&data).unwrap();
+        assert_eq!(
+            command_u16(&command, tags::COMMAND_FIELD).unwrap(),
+            command_field::C_ECHO_RQ
+        );
+        assert_eq!(command_u16(&command, tags::MESSAGE_ID).unwrap(), 7);
+        assert_eq!(
+            command_str(&command, tags::AFFECTED_SOP_CLASS_UID).unwrap(),
+            "1.2.840.10008.1.1"
+        );
+        assert!(matches!(
+            command_u16(&command, tags::STATUS),
+            Err(Error::MissingCommandElement { .. })
+        ));
+    }
+
+    #[test]
+    fn assemble_fragmented_message() {
+        let command = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::COMMAND_FIELD,
+                VR::US,
+                PrimitiveValue::from(command_field::C_STORE_RQ),
+            ),
+            DataElement::new(
+                tags::COMMAND_DATA_SET_TYPE,
+                VR::US,
+                PrimitiveValue::from(0_u16),
+            ),
+        ]);
+        let pdv = |value_type, is_last, data| Pdu::PData {
+            data: vec![PDataValue {
+                presentation_context_id: 3,
+                value_type,
+                is_last,
+                data,
+            }],
+        };
+
+        let mut assembler = MessageAssembler::default();
+        let command_data = write_command(&command).unwrap();
+        let (first, second) = command_data.split_at(10);
+        assert!(assembler
+            .push(pdv(PDataValueType::Command, false, first.to_vec()))
+            .unwrap()
+            .is_none());
+        assert!(assembler
+            .push(pdv(PDataValueType::Command, true, second.to_vec()))
+            .unwrap()
+            .is_none());
+        assert!(assembler
+            .push(pdv(PDataValueType::Data, false, vec![1, 2]))
+            .unwrap()
+            .is_none());
+        match assembler.push(pdv(PDataValueType::Data, true, vec![3, 4])) {
+            Ok(Some(Event::Message(message))) => {
+                assert_eq!(message.presentation_context_id, 3);
+                assert_eq!(message.command_field().unwrap(), command_field::C_STORE_RQ);
+                assert_eq!(message.data, Some(vec![1, 2, 3, 4]));
+            }
+            other => panic!("unexpected outcome {:?}", other),
+        }
+
+        // release requests are only accepted between messages
+        let mut assembler = MessageAssembler::default();
+        assert!(matches!(
+            assembler.push(Pdu::ReleaseRQ),
+            Ok(Some(Event::ReleaseRequested))
+        ));
+        let mut assembler = MessageAssembler::default();
+        assembler
+            .push(pdv(PDataValueType::Command, false, first.to_vec()))
+            .unwrap();
+        assert!(matches!(
+            assembler.push(Pdu::ReleaseRQ),
+            Err(Error::Released { .. })
+        ));
+
+        // data set values must follow a command set
+        let mut assembler = MessageAssembler::default();
+        assert!(matches!(
+            assembler.push(pdv(PDataValueType::Data, true, vec![1, 2])),
+            Err(Error::UnexpectedData { .. })
+        ));
+    }
+
+    #[test]
+    fn classify_status() {
+        use status::{status_type, StatusType};
+        assert_eq!(status_type(0), StatusType::Success);
+        assert_eq!(status_type(0xB007), StatusType::Warning);
+        assert_eq!(status_type(0xFF00), StatusType::Pending);
+        assert_eq!(status_type(0xFE00), StatusType::Cancel);
+        assert_eq!(status_type(0xA700), StatusType::Failure);
+    }
+}
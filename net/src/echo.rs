@@ -0,0 +1,313 @@
+//! Verification service module (C-ECHO).
+//!
+//! The verification service is used to check
+//! whether two application entities can communicate with each other.
+//! This module provides both sides of the service:
+//!
+//! - [`echo`] sends a C-ECHO request as a service class user (SCU)
+//!   and awaits the response;
+//! - [`serve_echo`] responds to C-ECHO requests as a service class provider (SCP)
+//!   until the association is released.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_ul::association::client::ClientAssociationOptions;
+//! # use dicom_dictionary_std::uids::VERIFICATION;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut association = ClientAssociationOptions::new()
+//!     .with_abstract_syntax(VERIFICATION)
+//!     .establish("129.168.0.5:104")?;
+//! let status = dicom_net::echo::echo(&mut association, 1)?;
+//! assert_eq!(status, dicom_net::dimse::status::SUCCESS);
+//! association.release()?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::command::CommandSet;
+use crate::dimse::{
+    self, command_field, receive_event, receive_message, send_message, status,
+    unrecognized_operation_response, Association, Event, Message,
+};
+#[cfg(feature = "async")]
+use crate::dimse::{
+    receive_event_async, receive_message_async, send_message_async, AsyncAssociation,
+};
+use dicom_object::InMemDicomObject;
+use dicom_ul::pdu::{Pdu, PresentationContextResult, PresentationContextResultReason};
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use tracing::warn;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// no presentation context was accepted for the verification service
+    NoPresentationContext { backtrace: Backtrace },
+
+    /// failed to send C-ECHO message
+    SendMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to receive C-ECHO message
+    ReceiveMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to release the association
+    Release {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    #[snafu(display("unexpected command field {:04X}H in response", command_field))]
+    UnexpectedCommand {
+        command_field: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "response is for message ID {}, but request had message ID {}",
+        got,
+        expected
+    ))]
+    MessageIdMismatch {
+        expected: u16,
+        got: u16,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Create a C-ECHO-RQ command set.
+pub fn echo_request(message_id: u16) -> InMemDicomObject {
+    CommandSet::c_echo_rq(message_id).to_object()
+}
+
+/// Create a C-ECHO-RSP command set.
+pub fn echo_response(message_id: u16, status: u16) -> InMemDicomObject {
+    CommandSet::c_echo_rq(message_id)
+        .response(status)
+        .to_object()
+}
+
+/// Send a C-ECHO request through the association and wait for its response.
+///
+/// The request is sent in the first accepted presentation context,
+/// which should be the one negotiated for the verification SOP class.
+/// Returns the status code in the response,
+/// which is [`SUCCESS`](crate::dimse::status::SUCCESS)
+/// if the other node is operational.
+pub fn echo<A>(association: &mut A, message_id: u16) -> Result<u16>
+where
+    A: Association + ?Sized,
+{
+    let pc_id = first_accepted(association.presentation_contexts())?;
+    send_message(association, pc_id, &echo_request(message_id), None).context(SendMessageSnafu)?;
+    let response = receive_message(association).context(ReceiveMessageSnafu)?;
+    check_response(&response, message_id)
+}
+
+/// Send a C-ECHO request through the association asynchronously
+/// and wait for its response.
+///
+/// See [`echo`] for details.
+#[cfg(feature = "async")]
+pub async fn echo_async<A>(association: &mut A, message_id: u16) -> Result<u16>
+where
+    A: AsyncAssociation + ?Sized,
+{
+    let pc_id = first_accepted(association.presentation_contexts())?;
+    send_message_async(association, pc_id, &echo_request(message_id), None)
+        .await
+        .context(SendMessageSnafu)?;
+    let response = receive_message_async(association)
+        .await
+        .context(ReceiveMessageSnafu)?;
+    check_response(&response, message_id)
+}
+
+/// Serve C-ECHO requests through the association
+/// until the other node releases or aborts it.
+///
+/// Every C-ECHO request is responded with a success status.
+/// Any other request is responded with the status
+/// [`UNRECOGNIZED_OPERATION`](crate::dimse::status::UNRECOGNIZED_OPERATION).
+pub fn serve_echo<A>(association: &mut A) -> Result<()>
+where
+    A: Association + ?Sized,
+{
+    loop {
+        match receive_event(association).context(ReceiveMessageSnafu)? {
+            Event::Message(message) => {
+                if let Some(response) = respond(&message)? {
+                    send_message(
+                        association,
+                        message.presentation_context_id,
+                        &response,
+                        None,
+                    )
+                    .context(SendMessageSnafu)?;
+                }
+            }
+            Event::ReleaseRequested => {
+                return association.send(&Pdu::ReleaseRP).context(ReleaseSnafu);
+            }
+            Event::Aborted => return Ok(()),
+        }
+    }
+}
+
+/// Serve C-ECHO requests through the association asynchronously
+/// until the other node releases or aborts it.
+///
+/// See [`serve_echo`] for details.
+#[cfg(feature = "async")]
+pub async fn serve_echo_async<A>(association: &mut A) -> Result<()>
+where
+    A: AsyncAssociation + ?Sized,
+{
+    loop {
+        match receive_event_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?
+        {
+            Event::Message(message) => {
+                if let Some(response) = respond(&message)? {
+                    send_message_async(
+                        association,
+                        message.presentation_context_id,
+                        &response,
+                        None,
+                    )
+                    .await
+                    .context(SendMessageSnafu)?;
+                }
+            }
+            Event::ReleaseRequested => {
+                return association
+                    .send(&Pdu::ReleaseRP)
+                    .await
+                    .context(ReleaseSnafu);
+            }
+            Event::Aborted => return Ok(()),
+        }
+    }
+}
+
+/// Obtain the ID of the first accepted presentation context.
+fn first_accepted(presentation_contexts: &[PresentationContextResult]) -> Result<u8> {
+    presentation_contexts
+        .iter()
+        .find(|pc| pc.reason == PresentationContextResultReason::Acceptance)
+        .map(|pc| pc.id)
+        .context(NoPresentationContextSnafu)
+}
+
+/// Check a C-ECHO response, returning its status code.
+fn check_response(response: &Message, message_id: u16) -> Result<u16> {
+    let command_field = response.command_field().context(ReceiveMessageSnafu)?;
+    ensure!(
+        command_field == command_field::C_ECHO_RSP,
+        UnexpectedCommandSnafu { command_field }
+    );
+    let got = response
+        .message_id_being_responded_to()
+        .context(ReceiveMessageSnafu)?;
+    ensure!(
+        got == message_id,
+        MessageIdMismatchSnafu {
+            expected: message_id,
+            got
+        }
+    );
+    response.status().context(ReceiveMessageSnafu)
+}
+
+/// Obtain the response to a single request message as a verification SCP,
+/// or `None` if the message should be ignored.
+fn respond(message: &Message) -> Result<Option<InMemDicomObject>> {
+    let command_field = message.command_field().context(ReceiveMessageSnafu)?;
+    if command_field::is_response(command_field) {
+        warn!(
+            "Ignoring unexpected response message ({:04X}H)",
+            command_field
+        );
+        return Ok(None);
+    }
+
+    let response = if command_field == command_field::C_ECHO_RQ {
+        let message_id = message.message_id().context(ReceiveMessageSnafu)?;
+        echo_response(message_id, status::SUCCESS)
+    } else {
+        warn!("Unsupported operation {:04X}H", command_field);
+        unrecognized_operation_response(message).context(ReceiveMessageSnafu)?
+    };
+    Ok(Some(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_dictionary_std::uids::VERIFICATION;
+    use dicom_ul::association::{ClientAssociationOptions, ServerAssociationOptions};
+    use std::net::TcpListener;
+
+    #[test]
+    fn echo_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(VERIFICATION)
+                .establish(stream)
+                .unwrap();
+            serve_echo(&mut association).unwrap();
+        });
+
+        let mut association = ClientAssociationOptions::new()
+            .with_abstract_syntax(VERIFICATION)
+            .establish(addr)
+            .unwrap();
+        assert_eq!(echo(&mut association, 1).unwrap(), status::SUCCESS);
+        assert_eq!(echo(&mut association, 2).unwrap(), status::SUCCESS);
+        association.release().unwrap();
+
+        scp.join().unwrap();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn echo_loopback_async() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let scp = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(VERIFICATION)
+                .establish_async(stream)
+                .await
+                .unwrap();
+            serve_echo_async(&mut association).await.unwrap();
+        });
+
+        let mut association = ClientAssociationOptions::new()
+            .with_abstract_syntax(VERIFICATION)
+            .establish_async(addr)
+            .await
+            .unwrap();
+        assert_eq!(
+            echo_async(&mut association, 1).await.unwrap(),
+            status::SUCCESS
+        );
+        association.release().await.unwrap();
+
+        scp.await.unwrap();
+    }
+}
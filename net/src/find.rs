@@ -0,0 +1,292 @@
+//! Query service module, service class user side (C-FIND).
+//!
+//! [`find`] sends a query identifier through an association
+//! in the presentation context of the given information model,
+//! and collects the identifiers of all matches.
+//! Dedicated queries such as the modality worklist
+//! are built on top of it.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_core::{DataElement, PrimitiveValue, VR};
+//! # use dicom_dictionary_std::{tags, uids};
+//! # use dicom_net::find::find;
+//! # use dicom_object::InMemDicomObject;
+//! # use dicom_ul::association::client::ClientAssociationOptions;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let sop_class_uid = uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_FIND;
+//! let mut association = ClientAssociationOptions::new()
+//!     .with_abstract_syntax(sop_class_uid)
+//!     .establish("129.168.0.5:104")?;
+//! let identifier = InMemDicomObject::from_element_iter([
+//!     DataElement::new(tags::QUERY_RETRIEVE_LEVEL, VR::CS, PrimitiveValue::from("STUDY")),
+//!     DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from("P1")),
+//!     DataElement::new(tags::STUDY_INSTANCE_UID, VR::UI, PrimitiveValue::Empty),
+//! ]);
+//! for study in find(&mut association, sop_class_uid, &identifier, 1)? {
+//!     println!("{:?}", study.element(tags::STUDY_INSTANCE_UID)?.to_str()?);
+//! }
+//! association.release()?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::command::CommandSet;
+use crate::dimse::{self, command_field, receive_message, send_message, status, Message};
+#[cfg(feature = "async")]
+use crate::dimse::{receive_message_async, send_message_async};
+use crate::retrieve::encode_identifier;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_object::InMemDicomObject;
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use dicom_ul::association::client::ClientAssociation;
+#[cfg(feature = "async")]
+use dicom_ul::association::AsyncClientAssociation;
+use dicom_ul::pdu::PresentationContextResult;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to prepare the query identifier
+    EncodeIdentifier {
+        #[snafu(backtrace)]
+        source: crate::retrieve::Error,
+    },
+
+    /// failed to send message
+    SendMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to receive message
+    ReceiveMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    #[snafu(display("unsupported transfer syntax {}", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// failed to read the identifier of a response
+    ReadIdentifier {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("unexpected command field {:04X}H in response", command_field))]
+    UnexpectedCommand {
+        command_field: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "response is for message ID {}, but request had message ID {}",
+        got,
+        expected
+    ))]
+    MessageIdMismatch {
+        expected: u16,
+        got: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("query failed with status {:04X}H", status))]
+    QueryFailed { status: u16, backtrace: Backtrace },
+}
+
+impl Error {
+    /// Whether the error was caused by the association itself,
+    /// rather than by the query or the responses to it.
+    pub(crate) fn is_communication_error(&self) -> bool {
+        matches!(
+            self,
+            Error::SendMessage { .. } | Error::ReceiveMessage { .. }
+        )
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Query the other node with a C-FIND request
+/// in the given information model,
+/// and wait until all matches are received.
+///
+/// The SOP class of the information model
+/// must have been accepted in the association.
+/// Returns the identifiers of all pending responses.
+pub fn find(
+    association: &mut ClientAssociation,
+    sop_class_uid: &str,
+    identifier: &InMemDicomObject,
+    message_id: u16,
+) -> Result<Vec<InMemDicomObject>> {
+    let command = CommandSet::c_find_rq(sop_class_uid, message_id).to_object();
+    let (pc_id, data) = encode_identifier(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        sop_class_uid,
+        identifier,
+    )
+    .context(EncodeIdentifierSnafu)?;
+    send_message(association, pc_id, &command, Some(&data)).context(SendMessageSnafu)?;
+
+    let mut matches = Vec::new();
+    loop {
+        let message = receive_message(association).context(ReceiveMessageSnafu)?;
+        match read_response(association.presentation_contexts(), &message, message_id)? {
+            Some(identifier) => matches.push(identifier),
+            None => return Ok(matches),
+        }
+    }
+}
+
+/// Query the other node asynchronously with a C-FIND request
+/// in the given information model,
+/// and wait until all matches are received.
+///
+/// See [`find`] for details.
+#[cfg(feature = "async")]
+pub async fn find_async(
+    association: &mut AsyncClientAssociation,
+    sop_class_uid: &str,
+    identifier: &InMemDicomObject,
+    message_id: u16,
+) -> Result<Vec<InMemDicomObject>> {
+    let command = CommandSet::c_find_rq(sop_class_uid, message_id).to_object();
+    let (pc_id, data) = encode_identifier(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        sop_class_uid,
+        identifier,
+    )
+    .context(EncodeIdentifierSnafu)?;
+    send_message_async(association, pc_id, &command, Some(&data))
+        .await
+        .context(SendMessageSnafu)?;
+
+    let mut matches = Vec::new();
+    loop {
+        let message = receive_message_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?;
+        match read_response(association.presentation_contexts(), &message, message_id)? {
+            Some(identifier) => matches.push(identifier),
+            None => return Ok(matches),
+        }
+    }
+}
+
+/// Check a C-FIND response,
+/// returning the identifier of a pending response
+/// or `None` once the query is complete.
+fn read_response(
+    presentation_contexts: &[PresentationContextResult],
+    message: &Message,
+    message_id: u16,
+) -> Result<Option<InMemDicomObject>> {
+    let command_field = message.command_field().context(ReceiveMessageSnafu)?;
+    ensure!(
+        command_field == command_field::C_FIND_RSP,
+        UnexpectedCommandSnafu { command_field }
+    );
+    let got = message
+        .message_id_being_responded_to()
+        .context(ReceiveMessageSnafu)?;
+    ensure!(
+        got == message_id,
+        MessageIdMismatchSnafu {
+            expected: message_id,
+            got
+        }
+    );
+
+    let status = message.status().context(ReceiveMessageSnafu)?;
+    match status {
+        status::SUCCESS => Ok(None),
+        status::PENDING | status::PENDING_WARNING => {
+            let data = match &message.data {
+                Some(data) => data,
+                // a pending response without an identifier matches nothing
+                None => return Ok(Some(InMemDicomObject::new_empty())),
+            };
+            let ts_uid = presentation_contexts
+                .iter()
+                .find(|pc| pc.id == message.presentation_context_id)
+                .map(|pc| pc.transfer_syntax.trim_end_matches('\0'))
+                .unwrap_or_default();
+            let ts = TransferSyntaxRegistry
+                .get(ts_uid)
+                .context(UnsupportedTransferSyntaxSnafu { uid: ts_uid })?;
+            InMemDicomObject::read_dataset_with_ts(data.as_slice(), ts)
+                .map(Some)
+                .context(ReadIdentifierSnafu)
+        }
+        _ => QueryFailedSnafu { status }.fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dimse::{receive_event, Event};
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_dictionary_std::{tags, uids};
+    use dicom_ul::association::client::ClientAssociationOptions;
+    use dicom_ul::association::ServerAssociationOptions;
+    use dicom_ul::pdu::Pdu;
+    use std::net::TcpListener;
+
+    #[test]
+    fn find_reports_failed_status() {
+        let sop_class_uid = uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_FIND;
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(sop_class_uid)
+                .establish(stream)
+                .unwrap();
+            let request = receive_message(&mut association).unwrap();
+            assert_eq!(request.command_field().unwrap(), command_field::C_FIND_RQ);
+            let response = CommandSet::from_object(&request.command)
+                .unwrap()
+                // identifier does not match SOP class
+                .response(0xA900)
+                .to_object();
+            send_message(
+                &mut association,
+                request.presentation_context_id,
+                &response,
+                None,
+            )
+            .unwrap();
+
+            assert!(matches!(
+                receive_event(&mut association).unwrap(),
+                Event::ReleaseRequested
+            ));
+            association.send(&Pdu::ReleaseRP).unwrap();
+        });
+
+        let mut association = ClientAssociationOptions::new()
+            .with_abstract_syntax(sop_class_uid)
+            .establish(addr)
+            .unwrap();
+        let identifier = InMemDicomObject::from_element_iter([DataElement::new(
+            tags::QUERY_RETRIEVE_LEVEL,
+            VR::CS,
+            PrimitiveValue::from("STUDY"),
+        )]);
+        let result = find(&mut association, sop_class_uid, &identifier, 3);
+        association.release().unwrap();
+        scp.join().unwrap();
+
+        assert!(matches!(
+            result,
+            Err(Error::QueryFailed { status: 0xA900, .. })
+        ));
+    }
+}
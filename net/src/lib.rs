@@ -0,0 +1,79 @@
+//! This crate implements DICOM network services
+//! on top of the upper layer protocol provided by [`dicom_ul`].
+//!
+//! While `dicom_ul` takes care of association negotiation
+//! and of the encoding and decoding of protocol data units (PDUs),
+//! this crate deals with the exchange of
+//! DICOM message service element (DIMSE) messages
+//! and the service classes built upon them.
+//!
+//! - The [`client`](crate::client) module
+//! provides [`DicomClient`](crate::client::DicomClient),
+//! which keeps a pool of associations to a remote node
+//! and retries failed C-STORE and C-FIND operations in new associations.
+//! - The [`command`](crate::command) module
+//! builds and parses DIMSE command sets with typed fields,
+//! independently of the transport.
+//! - The [`dimse`](crate::dimse) module
+//! sends and receives whole DIMSE messages through an established association,
+//! either as a requester or as an acceptor.
+//! - The [`echo`](crate::echo) module
+//! implements the verification service (C-ECHO),
+//! as both a service class user (SCU) and a service class provider (SCP).
+//! - The [`find`](crate::find) module
+//! implements the query service (C-FIND) as a service class user,
+//! collecting the identifiers of all matches.
+//! - The [`mpps`](crate::mpps) module
+//! implements the modality performed procedure step service
+//! (N-CREATE and N-SET) as a service class user,
+//! reporting the progress of the procedure steps performed by a modality.
+//! - The [`retrieve`](crate::retrieve) module
+//! implements the retrieve side of the query/retrieve service (C-MOVE and C-GET)
+//! as a service class user, reporting the progress of the sub-operations.
+//! - The [`router`](crate::router) module
+//! provides a storage handler which transforms received objects
+//! and forwards them to other nodes through a durable on-disk queue.
+//! - The [`store`](crate::store) module
+//! implements the storage service (C-STORE) as a service class user,
+//! sending DICOM objects to another node.
+//! - The [`store_scp`](crate::store_scp) module
+//! implements the storage service (C-STORE) as a service class provider,
+//! passing received objects to a [`StorageHandler`](crate::store_scp::StorageHandler).
+//! - The [`worklist`](crate::worklist) module
+//! implements the modality worklist query (C-FIND) as a service class user,
+//! collecting the scheduled procedure steps of a worklist.
+//!
+//! With the `async` feature enabled,
+//! each of these modules also provides `_async` variants of its functions,
+//! which work with associations established on top of tokio.
+//! This enables a single process to serve many simultaneous associations
+//! without dedicating a thread to each connection.
+
+pub mod client;
+pub mod command;
+pub mod dimse;
+pub mod echo;
+pub mod find;
+pub mod mpps;
+pub mod retrieve;
+pub mod router;
+pub mod store;
+pub mod store_scp;
+pub mod worklist;
+
+// re-exports
+
+pub use client::{DicomClient, RetryPolicy};
+pub use command::CommandSet;
+pub use dicom_ul;
+#[cfg(feature = "async")]
+pub use dimse::AsyncAssociation;
+pub use dimse::{Association, Message};
+pub use echo::{echo, serve_echo};
+pub use find::find;
+pub use mpps::{Mpps, PerformedProcedureStep, PerformedSeries};
+pub use retrieve::{c_get, c_move};
+pub use router::Router;
+pub use store::{store, StoreScu};
+pub use store_scp::{StorageHandler, StoreScp};
+pub use worklist::{find_worklist, WorklistItem, WorklistQuery};
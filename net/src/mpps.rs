@@ -0,0 +1,910 @@
+//! Modality performed procedure step service module,
+//! service class user side (N-CREATE and N-SET
+//! with the _Modality Performed Procedure Step_ SOP class).
+//!
+//! A modality reports the procedure steps it performs
+//! to another node, typically a RIS:
+//! it creates the performed procedure step as `IN PROGRESS`
+//! when the acquisition starts,
+//! and later sets it to `COMPLETED` or `DISCONTINUED`,
+//! along with the series acquired.
+//!
+//! [`PerformedProcedureStep`] describes the step being created,
+//! possibly from a [`WorklistItem`],
+//! and [`Mpps`] keeps track of the step's status
+//! so that only valid transitions are requested.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_dictionary_std::uids;
+//! # use dicom_net::mpps::{Mpps, PerformedProcedureStep, PerformedSeries};
+//! # use dicom_ul::association::client::ClientAssociationOptions;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut association = ClientAssociationOptions::new()
+//!     .with_abstract_syntax(uids::MODALITY_PERFORMED_PROCEDURE_STEP)
+//!     .calling_ae_title("CT01")
+//!     .called_ae_title("RIS")
+//!     .establish("129.168.0.5:104")?;
+//! let step = PerformedProcedureStep::new("PPS-1", "CT01", "CT", "20220314", "093000");
+//! let mut mpps = Mpps::create(&mut association, &step, "1.2.3.4.5.6", 1)?;
+//! // ... acquire images ...
+//! let series = PerformedSeries::new("1.2.3.4.5.6.1", "Head");
+//! mpps.complete(&mut association, "20220314", "094500", &[series], 2)?;
+//! association.release()?;
+//! # Ok(())
+//! # }
+//! ```
+use std::fmt;
+
+use crate::command::CommandSet;
+use crate::dimse::{self, command_field, receive_message, send_message, status, Message};
+#[cfg(feature = "async")]
+use crate::dimse::{receive_message_async, send_message_async};
+use crate::retrieve::encode_identifier;
+use crate::worklist::WorklistItem;
+use dicom_core::value::Value;
+use dicom_core::{DataElement, Length, PrimitiveValue, Tag, VR};
+use dicom_dictionary_std::{tags, uids};
+use dicom_object::mem::InMemElement;
+use dicom_object::InMemDicomObject;
+use dicom_ul::association::client::ClientAssociation;
+#[cfg(feature = "async")]
+use dicom_ul::association::AsyncClientAssociation;
+use dicom_ul::pdu::PresentationContextResult;
+use snafu::{ensure, Backtrace, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to encode the data set
+    EncodeDataSet {
+        #[snafu(backtrace)]
+        source: crate::retrieve::Error,
+    },
+
+    /// failed to send message
+    SendMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to receive message
+    ReceiveMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    #[snafu(display("unexpected command field {:04X}H in response", command_field))]
+    UnexpectedCommand {
+        command_field: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "response is for message ID {}, but request had message ID {}",
+        got,
+        expected
+    ))]
+    MessageIdMismatch {
+        expected: u16,
+        got: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("request failed with status {:04X}H", status))]
+    RequestFailed { status: u16, backtrace: Backtrace },
+
+    #[snafu(display("performed procedure step is already {}", status))]
+    AlreadyFinished {
+        status: StepStatus,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The status of a performed procedure step.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum StepStatus {
+    /// The step has started and is not finished yet
+    InProgress,
+    /// The step was finished successfully
+    Completed,
+    /// The step was cancelled or stopped before completion
+    Discontinued,
+}
+
+impl StepStatus {
+    /// The value of _Performed Procedure Step Status_ for this status.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StepStatus::InProgress => "IN PROGRESS",
+            StepStatus::Completed => "COMPLETED",
+            StepStatus::Discontinued => "DISCONTINUED",
+        }
+    }
+
+    /// Interpret a value of _Performed Procedure Step Status_.
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value.trim_end() {
+            "IN PROGRESS" => Some(StepStatus::InProgress),
+            "COMPLETED" => Some(StepStatus::Completed),
+            "DISCONTINUED" => Some(StepStatus::Discontinued),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for StepStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The attributes of a performed procedure step at its creation.
+///
+/// Attributes left as `None` are sent empty.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PerformedProcedureStep {
+    /// Performed Procedure Step ID
+    pub id: String,
+    /// Performed Station AE Title
+    pub station_ae_title: String,
+    /// Modality
+    pub modality: String,
+    /// Performed Procedure Step Start Date
+    pub start_date: String,
+    /// Performed Procedure Step Start Time
+    pub start_time: String,
+    /// Performed Station Name
+    pub station_name: Option<String>,
+    /// Performed Location
+    pub location: Option<String>,
+    /// Performed Procedure Step Description
+    pub description: Option<String>,
+    /// Study ID
+    pub study_id: Option<String>,
+    /// Patient's Name
+    pub patient_name: Option<String>,
+    /// Patient ID
+    pub patient_id: Option<String>,
+    /// Patient's Birth Date
+    pub patient_birth_date: Option<String>,
+    /// Patient's Sex
+    pub patient_sex: Option<String>,
+    /// Study Instance UID of the scheduled step
+    pub study_instance_uid: Option<String>,
+    /// Accession Number of the scheduled step
+    pub accession_number: Option<String>,
+    /// Requested Procedure ID of the scheduled step
+    pub requested_procedure_id: Option<String>,
+    /// Requested Procedure Description of the scheduled step
+    pub requested_procedure_description: Option<String>,
+    /// Scheduled Procedure Step ID of the scheduled step
+    pub scheduled_procedure_step_id: Option<String>,
+    /// Scheduled Procedure Step Description of the scheduled step
+    pub scheduled_procedure_step_description: Option<String>,
+}
+
+impl PerformedProcedureStep {
+    /// Describe an unscheduled performed procedure step.
+    pub fn new(
+        id: impl Into<String>,
+        station_ae_title: impl Into<String>,
+        modality: impl Into<String>,
+        start_date: impl Into<String>,
+        start_time: impl Into<String>,
+    ) -> Self {
+        PerformedProcedureStep {
+            id: id.into(),
+            station_ae_title: station_ae_title.into(),
+            modality: modality.into(),
+            start_date: start_date.into(),
+            start_time: start_time.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Describe a performed procedure step
+    /// which fulfills the first scheduled procedure step of a worklist item,
+    /// copying the patient and scheduled step attributes.
+    pub fn from_worklist_item(
+        item: &WorklistItem,
+        id: impl Into<String>,
+        station_ae_title: impl Into<String>,
+        start_date: impl Into<String>,
+        start_time: impl Into<String>,
+    ) -> Self {
+        let scheduled = item.scheduled_procedure_steps.first();
+        let modality = scheduled
+            .and_then(|s| s.modality.clone())
+            .unwrap_or_default();
+        PerformedProcedureStep {
+            patient_name: item.patient_name.clone(),
+            patient_id: item.patient_id.clone(),
+            patient_birth_date: item.patient_birth_date.clone(),
+            patient_sex: item.patient_sex.clone(),
+            study_instance_uid: item.study_instance_uid.clone(),
+            accession_number: item.accession_number.clone(),
+            requested_procedure_id: item.requested_procedure_id.clone(),
+            requested_procedure_description: item.requested_procedure_description.clone(),
+            scheduled_procedure_step_id: scheduled.and_then(|s| s.id.clone()),
+            scheduled_procedure_step_description: scheduled.and_then(|s| s.description.clone()),
+            ..Self::new(id, station_ae_title, modality, start_date, start_time)
+        }
+    }
+
+    /// Build the data set of the N-CREATE request,
+    /// with the status `IN PROGRESS`.
+    pub fn to_create_data_set(&self) -> InMemDicomObject {
+        let scheduled = InMemDicomObject::from_element_iter([
+            string(tags::ACCESSION_NUMBER, VR::SH, &self.accession_number),
+            sequence(tags::REFERENCED_STUDY_SEQUENCE, Vec::new()),
+            string(tags::STUDY_INSTANCE_UID, VR::UI, &self.study_instance_uid),
+            string(
+                tags::REQUESTED_PROCEDURE_DESCRIPTION,
+                VR::LO,
+                &self.requested_procedure_description,
+            ),
+            string(
+                tags::SCHEDULED_PROCEDURE_STEP_DESCRIPTION,
+                VR::LO,
+                &self.scheduled_procedure_step_description,
+            ),
+            sequence(tags::SCHEDULED_PROTOCOL_CODE_SEQUENCE, Vec::new()),
+            string(
+                tags::SCHEDULED_PROCEDURE_STEP_ID,
+                VR::SH,
+                &self.scheduled_procedure_step_id,
+            ),
+            string(
+                tags::REQUESTED_PROCEDURE_ID,
+                VR::SH,
+                &self.requested_procedure_id,
+            ),
+        ]);
+
+        InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::MODALITY,
+                VR::CS,
+                PrimitiveValue::from(&*self.modality),
+            ),
+            sequence(tags::PROCEDURE_CODE_SEQUENCE, Vec::new()),
+            sequence(tags::REFERENCED_PATIENT_SEQUENCE, Vec::new()),
+            string(tags::PATIENT_NAME, VR::PN, &self.patient_name),
+            string(tags::PATIENT_ID, VR::LO, &self.patient_id),
+            string(tags::PATIENT_BIRTH_DATE, VR::DA, &self.patient_birth_date),
+            string(tags::PATIENT_SEX, VR::CS, &self.patient_sex),
+            string(tags::STUDY_ID, VR::SH, &self.study_id),
+            DataElement::new(
+                tags::PERFORMED_STATION_AE_TITLE,
+                VR::AE,
+                PrimitiveValue::from(&*self.station_ae_title),
+            ),
+            string(tags::PERFORMED_STATION_NAME, VR::SH, &self.station_name),
+            string(tags::PERFORMED_LOCATION, VR::SH, &self.location),
+            DataElement::new(
+                tags::PERFORMED_PROCEDURE_STEP_START_DATE,
+                VR::DA,
+                PrimitiveValue::from(&*self.start_date),
+            ),
+            DataElement::new(
+                tags::PERFORMED_PROCEDURE_STEP_START_TIME,
+                VR::TM,
+                PrimitiveValue::from(&*self.start_time),
+            ),
+            DataElement::new(
+                tags::PERFORMED_PROCEDURE_STEP_END_DATE,
+                VR::DA,
+                PrimitiveValue::Empty,
+            ),
+            DataElement::new(
+                tags::PERFORMED_PROCEDURE_STEP_END_TIME,
+                VR::TM,
+                PrimitiveValue::Empty,
+            ),
+            status_element(StepStatus::InProgress),
+            DataElement::new(
+                tags::PERFORMED_PROCEDURE_STEP_ID,
+                VR::SH,
+                PrimitiveValue::from(&*self.id),
+            ),
+            string(
+                tags::PERFORMED_PROCEDURE_STEP_DESCRIPTION,
+                VR::LO,
+                &self.description,
+            ),
+            DataElement::new(
+                tags::PERFORMED_PROCEDURE_TYPE_DESCRIPTION,
+                VR::LO,
+                PrimitiveValue::Empty,
+            ),
+            sequence(tags::PERFORMED_PROTOCOL_CODE_SEQUENCE, Vec::new()),
+            sequence(tags::SCHEDULED_STEP_ATTRIBUTES_SEQUENCE, vec![scheduled]),
+            sequence(tags::PERFORMED_SERIES_SEQUENCE, Vec::new()),
+        ])
+    }
+}
+
+/// A series acquired in a performed procedure step.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct PerformedSeries {
+    /// Series Instance UID
+    pub series_instance_uid: String,
+    /// Protocol Name
+    pub protocol_name: String,
+    /// Series Description
+    pub description: Option<String>,
+    /// Performing Physician's Name
+    pub performing_physician_name: Option<String>,
+    /// Operators' Name
+    pub operators_name: Option<String>,
+    /// Retrieve AE Title
+    pub retrieve_ae_title: Option<String>,
+    /// The SOP class UID and SOP instance UID
+    /// of each image in the series
+    pub images: Vec<(String, String)>,
+}
+
+impl PerformedSeries {
+    /// Describe a series without images.
+    pub fn new(series_instance_uid: impl Into<String>, protocol_name: impl Into<String>) -> Self {
+        PerformedSeries {
+            series_instance_uid: series_instance_uid.into(),
+            protocol_name: protocol_name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Add an image to the series.
+    pub fn with_image(
+        mut self,
+        sop_class_uid: impl Into<String>,
+        sop_instance_uid: impl Into<String>,
+    ) -> Self {
+        self.images
+            .push((sop_class_uid.into(), sop_instance_uid.into()));
+        self
+    }
+
+    fn to_item(&self) -> InMemDicomObject {
+        let images = self
+            .images
+            .iter()
+            .map(|(class_uid, instance_uid)| {
+                InMemDicomObject::from_element_iter([
+                    DataElement::new(
+                        tags::REFERENCED_SOP_CLASS_UID,
+                        VR::UI,
+                        PrimitiveValue::from(class_uid.as_str()),
+                    ),
+                    DataElement::new(
+                        tags::REFERENCED_SOP_INSTANCE_UID,
+                        VR::UI,
+                        PrimitiveValue::from(instance_uid.as_str()),
+                    ),
+                ])
+            })
+            .collect();
+        InMemDicomObject::from_element_iter([
+            string(tags::RETRIEVE_AE_TITLE, VR::AE, &self.retrieve_ae_title),
+            string(tags::SERIES_DESCRIPTION, VR::LO, &self.description),
+            string(
+                tags::PERFORMING_PHYSICIAN_NAME,
+                VR::PN,
+                &self.performing_physician_name,
+            ),
+            string(tags::OPERATORS_NAME, VR::PN, &self.operators_name),
+            sequence(tags::REFERENCED_IMAGE_SEQUENCE, images),
+            DataElement::new(
+                tags::PROTOCOL_NAME,
+                VR::LO,
+                PrimitiveValue::from(self.protocol_name.as_str()),
+            ),
+            DataElement::new(
+                tags::SERIES_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(self.series_instance_uid.as_str()),
+            ),
+            sequence(
+                tags::REFERENCED_NON_IMAGE_COMPOSITE_SOP_INSTANCE_SEQUENCE,
+                Vec::new(),
+            ),
+        ])
+    }
+}
+
+/// Build the data set of the N-SET request
+/// which finishes a performed procedure step with the given status.
+pub fn finish_data_set(
+    status: StepStatus,
+    end_date: &str,
+    end_time: &str,
+    series: &[PerformedSeries],
+) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter([
+        DataElement::new(
+            tags::PERFORMED_PROCEDURE_STEP_END_DATE,
+            VR::DA,
+            PrimitiveValue::from(end_date),
+        ),
+        DataElement::new(
+            tags::PERFORMED_PROCEDURE_STEP_END_TIME,
+            VR::TM,
+            PrimitiveValue::from(end_time),
+        ),
+        status_element(status),
+        sequence(
+            tags::PERFORMED_SERIES_SEQUENCE,
+            series.iter().map(PerformedSeries::to_item).collect(),
+        ),
+    ])
+}
+
+/// A performed procedure step created in another node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mpps {
+    sop_instance_uid: String,
+    status: StepStatus,
+}
+
+impl Mpps {
+    /// Create the performed procedure step in the other node
+    /// with an N-CREATE request.
+    ///
+    /// The _Modality Performed Procedure Step_ SOP class
+    /// must have been accepted in the association.
+    pub fn create(
+        association: &mut ClientAssociation,
+        step: &PerformedProcedureStep,
+        sop_instance_uid: &str,
+        message_id: u16,
+    ) -> Result<Self> {
+        let (pc_id, command, data) = create_request(
+            association.presentation_contexts(),
+            |pc_id| association.abstract_syntax(pc_id),
+            step,
+            sop_instance_uid,
+            message_id,
+        )?;
+        send_message(association, pc_id, &command, Some(&data)).context(SendMessageSnafu)?;
+        let message = receive_message(association).context(ReceiveMessageSnafu)?;
+        check_response(&message, command_field::N_CREATE_RSP, message_id)?;
+        Ok(Self::in_progress(sop_instance_uid))
+    }
+
+    /// Create the performed procedure step in the other node
+    /// asynchronously with an N-CREATE request.
+    ///
+    /// See [`create`](Self::create) for details.
+    #[cfg(feature = "async")]
+    pub async fn create_async(
+        association: &mut AsyncClientAssociation,
+        step: &PerformedProcedureStep,
+        sop_instance_uid: &str,
+        message_id: u16,
+    ) -> Result<Self> {
+        let (pc_id, command, data) = create_request(
+            association.presentation_contexts(),
+            |pc_id| association.abstract_syntax(pc_id),
+            step,
+            sop_instance_uid,
+            message_id,
+        )?;
+        send_message_async(association, pc_id, &command, Some(&data))
+            .await
+            .context(SendMessageSnafu)?;
+        let message = receive_message_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?;
+        check_response(&message, command_field::N_CREATE_RSP, message_id)?;
+        Ok(Self::in_progress(sop_instance_uid))
+    }
+
+    /// Refer to a performed procedure step which is in progress,
+    /// such as one created through another association.
+    pub fn in_progress(sop_instance_uid: impl Into<String>) -> Self {
+        Mpps {
+            sop_instance_uid: sop_instance_uid.into(),
+            status: StepStatus::InProgress,
+        }
+    }
+
+    /// The SOP instance UID of the performed procedure step.
+    pub fn sop_instance_uid(&self) -> &str {
+        &self.sop_instance_uid
+    }
+
+    /// The current status of the performed procedure step.
+    pub fn status(&self) -> StepStatus {
+        self.status
+    }
+
+    /// Set the performed procedure step as completed
+    /// with an N-SET request,
+    /// reporting the series acquired.
+    pub fn complete(
+        &mut self,
+        association: &mut ClientAssociation,
+        end_date: &str,
+        end_time: &str,
+        series: &[PerformedSeries],
+        message_id: u16,
+    ) -> Result<()> {
+        let data = finish_data_set(StepStatus::Completed, end_date, end_time, series);
+        self.finish(association, StepStatus::Completed, &data, message_id)
+    }
+
+    /// Set the performed procedure step as discontinued
+    /// with an N-SET request,
+    /// reporting the series acquired until then.
+    pub fn discontinue(
+        &mut self,
+        association: &mut ClientAssociation,
+        end_date: &str,
+        end_time: &str,
+        series: &[PerformedSeries],
+        message_id: u16,
+    ) -> Result<()> {
+        let data = finish_data_set(StepStatus::Discontinued, end_date, end_time, series);
+        self.finish(association, StepStatus::Discontinued, &data, message_id)
+    }
+
+    /// Finish the performed procedure step with an N-SET request
+    /// with the given data set,
+    /// which must set the _Performed Procedure Step Status_ to `status`.
+    ///
+    /// Fails without sending anything
+    /// if the step is no longer in progress.
+    pub fn finish(
+        &mut self,
+        association: &mut ClientAssociation,
+        status: StepStatus,
+        data: &InMemDicomObject,
+        message_id: u16,
+    ) -> Result<()> {
+        let (pc_id, command, data) = self.set_request(
+            association.presentation_contexts(),
+            |pc_id| association.abstract_syntax(pc_id),
+            data,
+            message_id,
+        )?;
+        send_message(association, pc_id, &command, Some(&data)).context(SendMessageSnafu)?;
+        let message = receive_message(association).context(ReceiveMessageSnafu)?;
+        check_response(&message, command_field::N_SET_RSP, message_id)?;
+        self.status = status;
+        Ok(())
+    }
+
+    /// Set the performed procedure step as completed
+    /// asynchronously with an N-SET request.
+    ///
+    /// See [`complete`](Self::complete) for details.
+    #[cfg(feature = "async")]
+    pub async fn complete_async(
+        &mut self,
+        association: &mut AsyncClientAssociation,
+        end_date: &str,
+        end_time: &str,
+        series: &[PerformedSeries],
+        message_id: u16,
+    ) -> Result<()> {
+        let data = finish_data_set(StepStatus::Completed, end_date, end_time, series);
+        self.finish_async(association, StepStatus::Completed, &data, message_id)
+            .await
+    }
+
+    /// Set the performed procedure step as discontinued
+    /// asynchronously with an N-SET request.
+    ///
+    /// See [`discontinue`](Self::discontinue) for details.
+    #[cfg(feature = "async")]
+    pub async fn discontinue_async(
+        &mut self,
+        association: &mut AsyncClientAssociation,
+        end_date: &str,
+        end_time: &str,
+        series: &[PerformedSeries],
+        message_id: u16,
+    ) -> Result<()> {
+        let data = finish_data_set(StepStatus::Discontinued, end_date, end_time, series);
+        self.finish_async(association, StepStatus::Discontinued, &data, message_id)
+            .await
+    }
+
+    /// Finish the performed procedure step asynchronously
+    /// with an N-SET request with the given data set.
+    ///
+    /// See [`finish`](Self::finish) for details.
+    #[cfg(feature = "async")]
+    pub async fn finish_async(
+        &mut self,
+        association: &mut AsyncClientAssociation,
+        status: StepStatus,
+        data: &InMemDicomObject,
+        message_id: u16,
+    ) -> Result<()> {
+        let (pc_id, command, data) = self.set_request(
+            association.presentation_contexts(),
+            |pc_id| association.abstract_syntax(pc_id),
+            data,
+            message_id,
+        )?;
+        send_message_async(association, pc_id, &command, Some(&data))
+            .await
+            .context(SendMessageSnafu)?;
+        let message = receive_message_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?;
+        check_response(&message, command_field::N_SET_RSP, message_id)?;
+        self.status = status;
+        Ok(())
+    }
+
+    /// Prepare an N-SET request,
+    /// returning the presentation context ID,
+    /// the command set and the encoded data set.
+    fn set_request<'a>(
+        &self,
+        presentation_contexts: &[PresentationContextResult],
+        abstract_syntax: impl Fn(u8) -> Option<&'a str>,
+        data: &InMemDicomObject,
+        message_id: u16,
+    ) -> Result<(u8, InMemDicomObject, Vec<u8>)> {
+        ensure!(
+            self.status == StepStatus::InProgress,
+            AlreadyFinishedSnafu {
+                status: self.status
+            }
+        );
+        let sop_class_uid = uids::MODALITY_PERFORMED_PROCEDURE_STEP;
+        let command =
+            CommandSet::n_set_rq(sop_class_uid, &self.sop_instance_uid, message_id).to_object();
+        let (pc_id, data) =
+            encode_identifier(presentation_contexts, abstract_syntax, sop_class_uid, data)
+                .context(EncodeDataSetSnafu)?;
+        Ok((pc_id, command, data))
+    }
+}
+
+/// Prepare an N-CREATE request,
+/// returning the presentation context ID,
+/// the command set and the encoded data set.
+fn create_request<'a>(
+    presentation_contexts: &[PresentationContextResult],
+    abstract_syntax: impl Fn(u8) -> Option<&'a str>,
+    step: &PerformedProcedureStep,
+    sop_instance_uid: &str,
+    message_id: u16,
+) -> Result<(u8, InMemDicomObject, Vec<u8>)> {
+    let sop_class_uid = uids::MODALITY_PERFORMED_PROCEDURE_STEP;
+    let command =
+        CommandSet::n_create_rq(sop_class_uid, Some(sop_instance_uid), message_id).to_object();
+    let (pc_id, data) = encode_identifier(
+        presentation_contexts,
+        abstract_syntax,
+        sop_class_uid,
+        &step.to_create_data_set(),
+    )
+    .context(EncodeDataSetSnafu)?;
+    Ok((pc_id, command, data))
+}
+
+/// Check an N-CREATE or N-SET response,
+/// accepting success and warning statuses.
+fn check_response(message: &Message, expected: u16, message_id: u16) -> Result<()> {
+    let command_field = message.command_field().context(ReceiveMessageSnafu)?;
+    ensure!(
+        command_field == expected,
+        UnexpectedCommandSnafu { command_field }
+    );
+    let got = message
+        .message_id_being_responded_to()
+        .context(ReceiveMessageSnafu)?;
+    ensure!(
+        got == message_id,
+        MessageIdMismatchSnafu {
+            expected: message_id,
+            got
+        }
+    );
+    let status = message.status().context(ReceiveMessageSnafu)?;
+    ensure!(
+        matches!(
+            status::status_type(status),
+            status::StatusType::Success | status::StatusType::Warning
+        ),
+        RequestFailedSnafu { status }
+    );
+    Ok(())
+}
+
+fn status_element(status: StepStatus) -> InMemElement {
+    DataElement::new(
+        tags::PERFORMED_PROCEDURE_STEP_STATUS,
+        VR::CS,
+        PrimitiveValue::from(status.as_str()),
+    )
+}
+
+/// Create a type 2 string attribute, empty if no value is given.
+fn string(tag: Tag, vr: VR, value: &Option<String>) -> InMemElement {
+    let value = match value {
+        Some(value) => PrimitiveValue::from(value.as_str()),
+        None => PrimitiveValue::Empty,
+    };
+    DataElement::new(tag, vr, value)
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> InMemElement {
+    DataElement::new(
+        tag,
+        VR::SQ,
+        Value::Sequence {
+            items: items.into(),
+            size: Length::UNDEFINED,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dimse::{receive_event, Event};
+    use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+    use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+    use dicom_ul::association::client::ClientAssociationOptions;
+    use dicom_ul::association::ServerAssociationOptions;
+    use dicom_ul::pdu::Pdu;
+    use std::net::TcpListener;
+
+    fn step_status(obj: &InMemDicomObject) -> Option<StepStatus> {
+        let value = obj
+            .element(tags::PERFORMED_PROCEDURE_STEP_STATUS)
+            .ok()?
+            .to_str()
+            .ok()?;
+        StepStatus::from_value(&value)
+    }
+
+    #[test]
+    fn create_and_complete() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(uids::MODALITY_PERFORMED_PROCEDURE_STEP)
+                .establish(stream)
+                .unwrap();
+            let ts = TransferSyntaxRegistry
+                .get(
+                    association.presentation_contexts()[0]
+                        .transfer_syntax
+                        .trim_end_matches('\0'),
+                )
+                .unwrap();
+
+            let mut statuses = Vec::new();
+            for expected in [command_field::N_CREATE_RQ, command_field::N_SET_RQ] {
+                let request = receive_message(&mut association).unwrap();
+                assert_eq!(request.command_field().unwrap(), expected);
+                let command = CommandSet::from_object(&request.command).unwrap();
+                let data =
+                    InMemDicomObject::read_dataset_with_ts(request.data.as_deref().unwrap(), ts)
+                        .unwrap();
+                statuses.push(step_status(&data));
+                if expected == command_field::N_SET_RQ {
+                    assert_eq!(
+                        command.requested_sop_instance_uid.as_deref(),
+                        Some("1.2.999")
+                    );
+                    let series = data
+                        .element(tags::PERFORMED_SERIES_SEQUENCE)
+                        .unwrap()
+                        .items()
+                        .unwrap();
+                    assert_eq!(series.len(), 1);
+                } else {
+                    assert_eq!(
+                        command.affected_sop_instance_uid.as_deref(),
+                        Some("1.2.999")
+                    );
+                }
+                let response = command.response(status::SUCCESS).to_object();
+                send_message(
+                    &mut association,
+                    request.presentation_context_id,
+                    &response,
+                    None,
+                )
+                .unwrap();
+            }
+
+            assert!(matches!(
+                receive_event(&mut association).unwrap(),
+                Event::ReleaseRequested
+            ));
+            association.send(&Pdu::ReleaseRP).unwrap();
+            statuses
+        });
+
+        let mut association = ClientAssociationOptions::new()
+            .with_abstract_syntax(uids::MODALITY_PERFORMED_PROCEDURE_STEP)
+            .establish(addr)
+            .unwrap();
+        let step = PerformedProcedureStep::new("PPS1", "CT01", "CT", "20220314", "093000");
+        let mut mpps = Mpps::create(&mut association, &step, "1.2.999", 1).unwrap();
+        assert_eq!(mpps.status(), StepStatus::InProgress);
+        let series = PerformedSeries::new("1.2.999.1", "Head")
+            .with_image(uids::CT_IMAGE_STORAGE, "1.2.999.1.1");
+        mpps.complete(&mut association, "20220314", "094500", &[series], 2)
+            .unwrap();
+        assert_eq!(mpps.status(), StepStatus::Completed);
+
+        // no further transitions are allowed
+        assert!(matches!(
+            mpps.discontinue(&mut association, "20220314", "094600", &[], 3),
+            Err(Error::AlreadyFinished { .. })
+        ));
+        association.release().unwrap();
+
+        assert_eq!(
+            scp.join().unwrap(),
+            vec![Some(StepStatus::InProgress), Some(StepStatus::Completed)]
+        );
+    }
+
+    #[test]
+    fn create_data_set_from_worklist_item() {
+        let identifier = InMemDicomObject::from_element_iter([
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(tags::ACCESSION_NUMBER, VR::SH, PrimitiveValue::from("A1")),
+            sequence(
+                tags::SCHEDULED_PROCEDURE_STEP_SEQUENCE,
+                vec![InMemDicomObject::from_element_iter([
+                    DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from("MR")),
+                    DataElement::new(
+                        tags::SCHEDULED_PROCEDURE_STEP_ID,
+                        VR::SH,
+                        PrimitiveValue::from("SPS1"),
+                    ),
+                ])],
+            ),
+        ]);
+        let item = WorklistItem::from_identifier(identifier);
+        let step =
+            PerformedProcedureStep::from_worklist_item(&item, "PPS1", "MR01", "20220314", "1000");
+        assert_eq!(step.modality, "MR");
+
+        let data = step.to_create_data_set();
+        assert_eq!(step_status(&data), Some(StepStatus::InProgress));
+        assert_eq!(
+            data.element(tags::PATIENT_NAME).unwrap().to_str().unwrap(),
+            "Doe^John"
+        );
+        let scheduled = data
+            .element(tags::SCHEDULED_STEP_ATTRIBUTES_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(
+            scheduled[0]
+                .element(tags::SCHEDULED_PROCEDURE_STEP_ID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "SPS1"
+        );
+        assert_eq!(
+            scheduled[0]
+                .element(tags::ACCESSION_NUMBER)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "A1"
+        );
+    }
+}
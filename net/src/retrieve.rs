@@ -0,0 +1,731 @@
+//! Query/retrieve service module, retrieve side (C-MOVE and C-GET SCU).
+//!
+//! Both services ask another node for the objects matching an identifier,
+//! such as all instances of a study,
+//! and differ in how the objects are delivered:
+//!
+//! - [`c_move`] asks the other node to send the objects
+//!   to a destination AE, possibly this node itself,
+//!   through a separate association.
+//!   A [`StoreScp`](crate::store_scp::StoreScp)
+//!   running in the background with [`spawn`](crate::store_scp::StoreScp::spawn)
+//!   can be used to receive them.
+//! - [`c_get`] receives the objects through the same association,
+//!   for which this node must also take the SCP role
+//!   of the respective storage SOP classes
+//!   (see [`get_association_options`]).
+//!
+//! The other node reports the progress of the sub-operations
+//! in pending responses, which are passed to a callback.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_dictionary_std::uids;
+//! # use dicom_net::retrieve::{c_get, get_association_options, study_identifier};
+//! # use dicom_net::store_scp::DirectoryStorage;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let model = uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET;
+//! let mut association = get_association_options(model, [uids::CT_IMAGE_STORAGE])
+//!     .called_ae_title("PACS")
+//!     .establish("129.168.0.5:104")?;
+//! let mut storage = DirectoryStorage::new("./retrieved");
+//! let response = c_get(
+//!     &mut association,
+//!     model,
+//!     &study_identifier("1.2.3.4.5"),
+//!     1,
+//!     &mut storage,
+//!     |progress| println!("{:?}", progress.sub_operations),
+//! )?;
+//! println!("{} objects retrieved", response.sub_operations.completed.unwrap_or(0));
+//! association.release()?;
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+
+use crate::command::CommandSet;
+use crate::dimse::{
+    self, command_field, receive_message, send_message, status, unrecognized_operation_response,
+    Message,
+};
+#[cfg(feature = "async")]
+use crate::dimse::{receive_message_async, send_message_async};
+use crate::store_scp::{self, read_store_request, store_response, StorageHandler};
+use dicom_core::{DataElement, PrimitiveValue, Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_object::InMemDicomObject;
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use dicom_ul::association::client::{ClientAssociation, ClientAssociationOptions};
+#[cfg(feature = "async")]
+use dicom_ul::association::AsyncClientAssociation;
+use dicom_ul::pdu::PresentationContextResult;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use tracing::warn;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("no presentation context accepted for SOP class {}", sop_class_uid))]
+    NoPresentationContext {
+        sop_class_uid: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("unsupported transfer syntax {}", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// failed to encode the identifier
+    WriteIdentifier {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    /// failed to send message
+    SendMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to receive message
+    ReceiveMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// invalid C-STORE request in C-GET sub-operation
+    ReadStoreRequest { source: store_scp::Error },
+
+    #[snafu(display("unexpected command field {:04X}H in response", command_field))]
+    UnexpectedCommand {
+        command_field: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "response is for message ID {}, but request had message ID {}",
+        got,
+        expected
+    ))]
+    MessageIdMismatch {
+        expected: u16,
+        got: u16,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The number of sub-operations reported in a C-MOVE or C-GET response.
+///
+/// Each count is only present if the other node included it in the response.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct SubOperations {
+    /// Number of remaining sub-operations
+    pub remaining: Option<u16>,
+    /// Number of completed sub-operations
+    pub completed: Option<u16>,
+    /// Number of failed sub-operations
+    pub failed: Option<u16>,
+    /// Number of sub-operations which completed with a warning
+    pub warning: Option<u16>,
+}
+
+/// A response to a C-MOVE or C-GET request.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct RetrieveResponse {
+    /// The status code of the response
+    pub status: u16,
+    /// The sub-operation counts in the response
+    pub sub_operations: SubOperations,
+}
+
+impl RetrieveResponse {
+    /// Whether more responses to the same request are to follow.
+    pub fn is_pending(&self) -> bool {
+        self.status == status::PENDING || self.status == status::PENDING_WARNING
+    }
+}
+
+/// Create an identifier for retrieving all instances of a study.
+pub fn study_identifier(study_instance_uid: &str) -> InMemDicomObject {
+    InMemDicomObject::from_element_iter([
+        DataElement::new(
+            tags::QUERY_RETRIEVE_LEVEL,
+            VR::CS,
+            PrimitiveValue::from("STUDY"),
+        ),
+        DataElement::new(
+            tags::STUDY_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(study_instance_uid),
+        ),
+    ])
+}
+
+/// Create a C-MOVE-RQ command set.
+pub fn move_request(sop_class_uid: &str, message_id: u16, destination: &str) -> InMemDicomObject {
+    CommandSet::c_move_rq(sop_class_uid, message_id, destination).to_object()
+}
+
+/// Create a C-GET-RQ command set.
+pub fn get_request(sop_class_uid: &str, message_id: u16) -> InMemDicomObject {
+    CommandSet::c_get_rq(sop_class_uid, message_id).to_object()
+}
+
+/// Create association options suitable for C-GET,
+/// proposing the given query/retrieve information model
+/// and the storage SOP classes of the objects to retrieve,
+/// for which this node proposes to take the SCP role.
+pub fn get_association_options<'a, I, T>(
+    information_model: &'a str,
+    storage_sop_classes: I,
+) -> ClientAssociationOptions<'a>
+where
+    I: IntoIterator<Item = T>,
+    T: Into<Cow<'a, str>>,
+{
+    storage_sop_classes.into_iter().map(Into::into).fold(
+        ClientAssociationOptions::new().with_abstract_syntax(information_model),
+        |options, sop_class_uid: Cow<'a, str>| {
+            options
+                .with_abstract_syntax(sop_class_uid.clone())
+                .with_role_selection(sop_class_uid, false, true)
+        },
+    )
+}
+
+/// Ask the other node to send the objects matching the identifier
+/// to the destination AE with a C-MOVE request,
+/// and wait until the operation is complete.
+///
+/// `sop_class_uid` is the query/retrieve information model,
+/// such as _Study Root Query/Retrieve Information Model - MOVE_,
+/// which must have been accepted in the association.
+/// `on_progress` is called for each pending response.
+/// Returns the final response.
+pub fn c_move<F>(
+    association: &mut ClientAssociation,
+    sop_class_uid: &str,
+    destination: &str,
+    identifier: &InMemDicomObject,
+    message_id: u16,
+    mut on_progress: F,
+) -> Result<RetrieveResponse>
+where
+    F: FnMut(&RetrieveResponse),
+{
+    let command = move_request(sop_class_uid, message_id, destination);
+    let (pc_id, data) = encode_identifier(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        sop_class_uid,
+        identifier,
+    )?;
+    send_message(association, pc_id, &command, Some(&data)).context(SendMessageSnafu)?;
+
+    loop {
+        let message = receive_message(association).context(ReceiveMessageSnafu)?;
+        let response = read_response(&message, command_field::C_MOVE_RSP, message_id)?;
+        if !response.is_pending() {
+            return Ok(response);
+        }
+        on_progress(&response);
+    }
+}
+
+/// Ask the other node asynchronously to send the objects
+/// matching the identifier to the destination AE with a C-MOVE request,
+/// and wait until the operation is complete.
+///
+/// See [`c_move`] for details.
+#[cfg(feature = "async")]
+pub async fn c_move_async<F>(
+    association: &mut AsyncClientAssociation,
+    sop_class_uid: &str,
+    destination: &str,
+    identifier: &InMemDicomObject,
+    message_id: u16,
+    mut on_progress: F,
+) -> Result<RetrieveResponse>
+where
+    F: FnMut(&RetrieveResponse),
+{
+    let command = move_request(sop_class_uid, message_id, destination);
+    let (pc_id, data) = encode_identifier(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        sop_class_uid,
+        identifier,
+    )?;
+    send_message_async(association, pc_id, &command, Some(&data))
+        .await
+        .context(SendMessageSnafu)?;
+
+    loop {
+        let message = receive_message_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?;
+        let response = read_response(&message, command_field::C_MOVE_RSP, message_id)?;
+        if !response.is_pending() {
+            return Ok(response);
+        }
+        on_progress(&response);
+    }
+}
+
+/// Retrieve the objects matching the identifier
+/// through the same association with a C-GET request,
+/// passing each received object to the given handler,
+/// and wait until the operation is complete.
+///
+/// `sop_class_uid` is the query/retrieve information model,
+/// such as _Study Root Query/Retrieve Information Model - GET_,
+/// which must have been accepted in the association.
+/// `on_progress` is called for each pending response.
+/// Returns the final response.
+pub fn c_get<H, F>(
+    association: &mut ClientAssociation,
+    sop_class_uid: &str,
+    identifier: &InMemDicomObject,
+    message_id: u16,
+    handler: &mut H,
+    mut on_progress: F,
+) -> Result<RetrieveResponse>
+where
+    H: StorageHandler + ?Sized,
+    F: FnMut(&RetrieveResponse),
+{
+    let command = get_request(sop_class_uid, message_id);
+    let (pc_id, data) = encode_identifier(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        sop_class_uid,
+        identifier,
+    )?;
+    send_message(association, pc_id, &command, Some(&data)).context(SendMessageSnafu)?;
+
+    loop {
+        let message = receive_message(association).context(ReceiveMessageSnafu)?;
+        match get_step(
+            association.presentation_contexts(),
+            &message,
+            message_id,
+            handler,
+        )? {
+            GetStep::Reply(reply) => {
+                send_message(association, message.presentation_context_id, &reply, None)
+                    .context(SendMessageSnafu)?;
+            }
+            GetStep::Response(response) if response.is_pending() => on_progress(&response),
+            GetStep::Response(response) => return Ok(response),
+        }
+    }
+}
+
+/// Retrieve the objects matching the identifier asynchronously
+/// through the same association with a C-GET request,
+/// passing each received object to the given handler,
+/// and wait until the operation is complete.
+///
+/// See [`c_get`] for details.
+#[cfg(feature = "async")]
+pub async fn c_get_async<H, F>(
+    association: &mut AsyncClientAssociation,
+    sop_class_uid: &str,
+    identifier: &InMemDicomObject,
+    message_id: u16,
+    handler: &mut H,
+    mut on_progress: F,
+) -> Result<RetrieveResponse>
+where
+    H: StorageHandler + ?Sized,
+    F: FnMut(&RetrieveResponse),
+{
+    let command = get_request(sop_class_uid, message_id);
+    let (pc_id, data) = encode_identifier(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        sop_class_uid,
+        identifier,
+    )?;
+    send_message_async(association, pc_id, &command, Some(&data))
+        .await
+        .context(SendMessageSnafu)?;
+
+    loop {
+        let message = receive_message_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?;
+        match get_step(
+            association.presentation_contexts(),
+            &message,
+            message_id,
+            handler,
+        )? {
+            GetStep::Reply(reply) => {
+                send_message_async(association, message.presentation_context_id, &reply, None)
+                    .await
+                    .context(SendMessageSnafu)?;
+            }
+            GetStep::Response(response) if response.is_pending() => on_progress(&response),
+            GetStep::Response(response) => return Ok(response),
+        }
+    }
+}
+
+/// The outcome of processing a message received during a C-GET operation.
+enum GetStep {
+    /// A reply to send back to the other node
+    Reply(InMemDicomObject),
+    /// A response to the C-GET request
+    Response(RetrieveResponse),
+}
+
+/// Process a message received during a C-GET operation,
+/// passing C-STORE sub-operations to the handler.
+fn get_step<H>(
+    presentation_contexts: &[PresentationContextResult],
+    message: &Message,
+    message_id: u16,
+    handler: &mut H,
+) -> Result<GetStep>
+where
+    H: StorageHandler + ?Sized,
+{
+    let command_field = message.command_field().context(ReceiveMessageSnafu)?;
+    match command_field {
+        command_field::C_STORE_RQ => {
+            let request = read_store_request(presentation_contexts, message)
+                .context(ReadStoreRequestSnafu)?;
+            let mut data = message.data.as_deref().unwrap_or_default();
+            let status = handler.store_stream(&request, &mut data);
+            Ok(GetStep::Reply(store_response(&request, status)))
+        }
+        _ if command_field::is_response(command_field) => {
+            read_response(message, command_field::C_GET_RSP, message_id).map(GetStep::Response)
+        }
+        _ => {
+            warn!("Unsupported operation {:04X}H", command_field);
+            unrecognized_operation_response(message)
+                .map(GetStep::Reply)
+                .context(ReceiveMessageSnafu)
+        }
+    }
+}
+
+/// Encode the identifier of a retrieve request
+/// in the presentation context of the given SOP class,
+/// returning the presentation context ID and the encoded data set.
+pub(crate) fn encode_identifier<'a>(
+    presentation_contexts: &[PresentationContextResult],
+    abstract_syntax: impl Fn(u8) -> Option<&'a str>,
+    sop_class_uid: &str,
+    identifier: &InMemDicomObject,
+) -> Result<(u8, Vec<u8>)> {
+    let pc = presentation_contexts
+        .iter()
+        .find(|pc| abstract_syntax(pc.id) == Some(sop_class_uid))
+        .context(NoPresentationContextSnafu { sop_class_uid })?;
+    let ts_uid = pc.transfer_syntax.trim_end_matches('\0');
+    let ts = TransferSyntaxRegistry
+        .get(ts_uid)
+        .context(UnsupportedTransferSyntaxSnafu { uid: ts_uid })?;
+
+    let mut data = Vec::new();
+    identifier
+        .write_dataset_with_ts(&mut data, ts)
+        .context(WriteIdentifierSnafu)?;
+    Ok((pc.id, data))
+}
+
+/// Check and collect the properties of a C-MOVE or C-GET response.
+fn read_response(message: &Message, expected: u16, message_id: u16) -> Result<RetrieveResponse> {
+    let command_field = message.command_field().context(ReceiveMessageSnafu)?;
+    ensure!(
+        command_field == expected,
+        UnexpectedCommandSnafu { command_field }
+    );
+    let got = message
+        .message_id_being_responded_to()
+        .context(ReceiveMessageSnafu)?;
+    ensure!(
+        got == message_id,
+        MessageIdMismatchSnafu {
+            expected: message_id,
+            got
+        }
+    );
+
+    let count = |tag: Tag| dimse::command_u16(&message.command, tag).ok();
+    Ok(RetrieveResponse {
+        status: message.status().context(ReceiveMessageSnafu)?,
+        sub_operations: SubOperations {
+            remaining: count(tags::NUMBER_OF_REMAINING_SUBOPERATIONS),
+            completed: count(tags::NUMBER_OF_COMPLETED_SUBOPERATIONS),
+            failed: count(tags::NUMBER_OF_FAILED_SUBOPERATIONS),
+            warning: count(tags::NUMBER_OF_WARNING_SUBOPERATIONS),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dimse::{receive_event, Event};
+    use crate::store::{store_request, StoreScu};
+    use crate::store_scp::{StoreRequest, StoreScp};
+    use dicom_dictionary_std::uids;
+    use dicom_object::{DefaultDicomObject, FileDicomObject, FileMetaTableBuilder};
+    use dicom_ul::association::ServerAssociationOptions;
+    use dicom_ul::pdu::Pdu;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    const SOP_INSTANCE_UIDS: [&str; 2] = ["1.2.888.1", "1.2.888.2"];
+
+    fn test_object(sop_instance_uid: &str) -> DefaultDicomObject {
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uids::CT_IMAGE_STORAGE)
+            .media_storage_sop_instance_uid(sop_instance_uid)
+            .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+            .build()
+            .unwrap();
+        let mut object = FileDicomObject::new_empty_with_meta(meta);
+        object.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_instance_uid),
+        ));
+        object
+    }
+
+    fn retrieve_response(
+        command_field: u16,
+        message_id: u16,
+        status: u16,
+        remaining: u16,
+        completed: u16,
+    ) -> InMemDicomObject {
+        CommandSet {
+            command_field,
+            message_id_being_responded_to: Some(message_id),
+            status: Some(status),
+            remaining_sub_operations: Some(remaining),
+            completed_sub_operations: Some(completed),
+            failed_sub_operations: Some(0),
+            warning_sub_operations: Some(0),
+            ..Default::default()
+        }
+        .to_object()
+    }
+
+    fn progress(response: &RetrieveResponse) -> (u16, Option<u16>, Option<u16>) {
+        (
+            response.status,
+            response.sub_operations.remaining,
+            response.sub_operations.completed,
+        )
+    }
+
+    #[test]
+    fn c_move_to_companion_store_scp() {
+        // the node receiving the objects
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let handler_received = Arc::clone(&received);
+        let store_scp = StoreScp::new(move |request: &StoreRequest, _: DefaultDicomObject| {
+            handler_received
+                .lock()
+                .unwrap()
+                .push(request.sop_instance_uid.clone());
+            dimse::status::SUCCESS
+        })
+        .ae_title("MOVE-DEST")
+        .spawn(TcpListener::bind("127.0.0.1:0").unwrap())
+        .unwrap();
+        let store_addr = store_scp.local_addr();
+
+        // the node performing the C-MOVE
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let move_scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE)
+                .establish(stream)
+                .unwrap();
+            let request = receive_message(&mut association).unwrap();
+            let command = CommandSet::from_object(&request.command).unwrap();
+            let message_id = command.message_id.unwrap();
+            let destination = command.move_destination.unwrap();
+            assert_eq!(destination, "MOVE-DEST");
+            assert!(request.data.is_some());
+
+            let mut scu = StoreScu::new().called_ae_title(destination);
+            for uid in SOP_INSTANCE_UIDS {
+                scu = scu.with_object(test_object(uid));
+            }
+            let pc_id = request.presentation_context_id;
+            let total = SOP_INSTANCE_UIDS.len() as u16;
+            scu.on_progress(|done, _, _| {
+                let response = retrieve_response(
+                    command_field::C_MOVE_RSP,
+                    message_id,
+                    status::PENDING,
+                    total - done as u16,
+                    done as u16,
+                );
+                send_message(&mut association, pc_id, &response, None).unwrap();
+            })
+            .send(store_addr)
+            .unwrap();
+
+            let response = retrieve_response(
+                command_field::C_MOVE_RSP,
+                message_id,
+                status::SUCCESS,
+                0,
+                total,
+            );
+            send_message(&mut association, pc_id, &response, None).unwrap();
+            assert!(matches!(
+                receive_event(&mut association).unwrap(),
+                Event::ReleaseRequested
+            ));
+            association.send(&Pdu::ReleaseRP).unwrap();
+        });
+
+        let model = uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_MOVE;
+        let mut association = ClientAssociationOptions::new()
+            .with_abstract_syntax(model)
+            .establish(addr)
+            .unwrap();
+        let mut pending = Vec::new();
+        let response = c_move(
+            &mut association,
+            model,
+            "MOVE-DEST",
+            &study_identifier("1.2.888"),
+            7,
+            |response| pending.push(progress(response)),
+        )
+        .unwrap();
+        association.release().unwrap();
+        move_scp.join().unwrap();
+        store_scp.stop().unwrap();
+
+        assert_eq!(
+            pending,
+            vec![
+                (status::PENDING, Some(1), Some(1)),
+                (status::PENDING, Some(0), Some(2)),
+            ]
+        );
+        assert_eq!(progress(&response), (status::SUCCESS, Some(0), Some(2)));
+        assert_eq!(*received.lock().unwrap(), SOP_INSTANCE_UIDS);
+    }
+
+    #[test]
+    fn c_get_through_same_association() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let get_scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET)
+                .with_abstract_syntax(uids::CT_IMAGE_STORAGE)
+                .establish(stream)
+                .unwrap();
+            let request = receive_message(&mut association).unwrap();
+            assert_eq!(request.command_field().unwrap(), command_field::C_GET_RQ);
+            let message_id = request.message_id().unwrap();
+
+            // storage presentation context, second in the proposal
+            let store_pc = association.presentation_contexts()[1].clone();
+            let ts = TransferSyntaxRegistry
+                .get(store_pc.transfer_syntax.trim_end_matches('\0'))
+                .unwrap();
+            let total = SOP_INSTANCE_UIDS.len() as u16;
+            for (i, uid) in SOP_INSTANCE_UIDS.iter().enumerate() {
+                let mut data = Vec::new();
+                test_object(uid)
+                    .write_dataset_with_ts(&mut data, ts)
+                    .unwrap();
+                let command = store_request(uids::CT_IMAGE_STORAGE, uid, 100 + i as u16);
+                send_message(&mut association, store_pc.id, &command, Some(&data)).unwrap();
+                let store_response = receive_message(&mut association).unwrap();
+                assert_eq!(store_response.status().unwrap(), dimse::status::SUCCESS);
+
+                let done = i as u16 + 1;
+                let response = retrieve_response(
+                    command_field::C_GET_RSP,
+                    message_id,
+                    status::PENDING,
+                    total - done,
+                    done,
+                );
+                send_message(
+                    &mut association,
+                    request.presentation_context_id,
+                    &response,
+                    None,
+                )
+                .unwrap();
+            }
+
+            let response = retrieve_response(
+                command_field::C_GET_RSP,
+                message_id,
+                status::SUCCESS,
+                0,
+                total,
+            );
+            send_message(
+                &mut association,
+                request.presentation_context_id,
+                &response,
+                None,
+            )
+            .unwrap();
+            assert!(matches!(
+                receive_event(&mut association).unwrap(),
+                Event::ReleaseRequested
+            ));
+            association.send(&Pdu::ReleaseRP).unwrap();
+        });
+
+        let model = uids::STUDY_ROOT_QUERY_RETRIEVE_INFORMATION_MODEL_GET;
+        let mut association = get_association_options(model, [uids::CT_IMAGE_STORAGE])
+            .establish(addr)
+            .unwrap();
+        let mut received = Vec::new();
+        let mut handler = |request: &StoreRequest, _: DefaultDicomObject| {
+            received.push(request.sop_instance_uid.clone());
+            dimse::status::SUCCESS
+        };
+        let mut pending = Vec::new();
+        let response = c_get(
+            &mut association,
+            model,
+            &study_identifier("1.2.888"),
+            3,
+            &mut handler,
+            |response| pending.push(progress(response)),
+        )
+        .unwrap();
+        association.release().unwrap();
+        get_scp.join().unwrap();
+
+        assert_eq!(
+            pending,
+            vec![
+                (status::PENDING, Some(1), Some(1)),
+                (status::PENDING, Some(0), Some(2)),
+            ]
+        );
+        assert_eq!(progress(&response), (status::SUCCESS, Some(0), Some(2)));
+        assert_eq!(received, SOP_INSTANCE_UIDS);
+    }
+}
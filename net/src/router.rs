@@ -0,0 +1,645 @@
+//! Routing module, forwarding received objects to other nodes.
+//!
+//! [`Router`] is a [`StorageHandler`] for the storage SCP
+//! which passes each received object through an optional transformation hook
+//! (such as anonymization or tag morphing)
+//! and places it in a durable on-disk queue,
+//! one for each destination.
+//! An object is only acknowledged to the sender
+//! once it has been written to all queues.
+//!
+//! The forwarders started with [`Router::spawn_forwarders`]
+//! run in background threads, one per destination,
+//! sending the queued objects via C-STORE
+//! and removing them from the queue once accepted by the destination.
+//! Objects rejected by the destination
+//! are moved to a `failed` directory within the queue of that destination.
+//! Since the queue lives on disk,
+//! objects not forwarded yet are picked up again after a restart.
+//! Delivery is _at least once_:
+//! an object may be sent again
+//! if the association fails before its removal from the queue.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_core::{DataElement, PrimitiveValue, VR};
+//! # use dicom_dictionary_std::tags;
+//! # use dicom_net::router::Router;
+//! # use dicom_net::store_scp::StoreScp;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let router = Router::new("/var/spool/dicom-router")
+//!     .calling_ae_title("ROUTER")
+//!     .with_destination("PACS@10.0.0.5:104".parse()?)
+//!     .with_destination("ARCHIVE@10.0.0.6:11112".parse()?)
+//!     .transform(|_request, mut object| {
+//!         object.put(DataElement::new(
+//!             tags::INSTITUTION_NAME,
+//!             VR::LO,
+//!             PrimitiveValue::from("ACME Hospital"),
+//!         ));
+//!         Some(object)
+//!     });
+//! let forwarders = router.spawn_forwarders()?;
+//!
+//! let mut scp = StoreScp::new(router).ae_title("ROUTER");
+//! scp.listen("0.0.0.0:11112")?;
+//! forwarders.stop();
+//! # Ok(())
+//! # }
+//! ```
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::dimse::status::{self, StatusType};
+use crate::store::{self, StoreScu};
+use crate::store_scp::{self, StorageHandler, StoreRequest};
+use dicom_object::DefaultDicomObject;
+use dicom_ul::address::AeAddr;
+use snafu::{ensure, Backtrace, ResultExt, Snafu};
+use tracing::warn;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("could not create queue directory {}", path.display()))]
+    CreateQueue {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("could not read queue directory {}", path.display()))]
+    ReadQueue {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("invalid SOP instance UID {:?}", uid))]
+    InvalidSopInstanceUid { uid: String, backtrace: Backtrace },
+
+    #[snafu(display("could not write queued object to {}", path.display()))]
+    WriteQueueFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("could not move queued object {}", path.display()))]
+    MoveQueueFile {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("could not remove queued object {}", path.display()))]
+    RemoveQueueFile {
+        path: PathBuf,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("could not forward objects to {}", destination))]
+    Forward {
+        destination: String,
+        #[snafu(backtrace)]
+        source: store::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The transformation hook type.
+type TransformHook =
+    Box<dyn FnMut(&StoreRequest, DefaultDicomObject) -> Option<DefaultDicomObject> + Send>;
+
+/// A storage handler forwarding received objects
+/// to one or more destinations through a durable on-disk queue.
+///
+/// See the [module-level documentation](self) for an example.
+pub struct Router {
+    /// the root directory of the queues
+    queue_dir: PathBuf,
+    /// the destinations to forward objects to
+    destinations: Vec<AeAddr>,
+    /// the calling AE title when forwarding
+    calling_ae_title: String,
+    /// the TCP read timeout when forwarding
+    read_timeout: Option<Duration>,
+    /// the maximum number of objects sent per association
+    batch_size: usize,
+    /// the time to wait before checking an empty queue again
+    poll_interval: Duration,
+    /// the time to wait before trying again after a failed association
+    retry_interval: Duration,
+    /// the transformation applied to each received object
+    transform: Option<TransformHook>,
+}
+
+impl fmt::Debug for Router {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Router")
+            .field("queue_dir", &self.queue_dir)
+            .field("destinations", &self.destinations)
+            .field("calling_ae_title", &self.calling_ae_title)
+            .field("read_timeout", &self.read_timeout)
+            .field("batch_size", &self.batch_size)
+            .field("poll_interval", &self.poll_interval)
+            .field("retry_interval", &self.retry_interval)
+            .field("transform", &self.transform.is_some())
+            .finish()
+    }
+}
+
+impl Router {
+    /// Create a new router with its queues in the given directory
+    /// and no destinations.
+    pub fn new<P>(queue_dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Router {
+            queue_dir: queue_dir.as_ref().to_path_buf(),
+            destinations: Vec::new(),
+            calling_ae_title: "ROUTER".to_string(),
+            read_timeout: None,
+            batch_size: 64,
+            poll_interval: Duration::from_secs(1),
+            retry_interval: Duration::from_secs(30),
+            transform: None,
+        }
+    }
+
+    /// Include a destination to forward all received objects to.
+    ///
+    /// The AE title in the address is used as the called AE title,
+    /// `ANY-SCP` being used if it is not specified.
+    pub fn with_destination(mut self, destination: AeAddr) -> Self {
+        self.destinations.push(destination);
+        self
+    }
+
+    /// Define the calling application entity title when forwarding objects.
+    ///
+    /// The default is `ROUTER`.
+    pub fn calling_ae_title(mut self, calling_ae_title: impl Into<String>) -> Self {
+        self.calling_ae_title = calling_ae_title.into();
+        self
+    }
+
+    /// Set the maximum time to wait for a response from a destination.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum number of objects sent in a single association.
+    ///
+    /// The default is 64.
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set the time to wait before checking an empty queue again.
+    ///
+    /// The default is 1 second.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Set the time to wait before trying to forward objects again
+    /// after failing to communicate with a destination.
+    ///
+    /// The default is 30 seconds.
+    pub fn retry_interval(mut self, interval: Duration) -> Self {
+        self.retry_interval = interval;
+        self
+    }
+
+    /// Set a transformation hook for the received objects,
+    /// applied before they are queued.
+    ///
+    /// The hook returns the object to forward,
+    /// or `None` to accept the object without forwarding it.
+    pub fn transform<F>(mut self, transform: F) -> Self
+    where
+        F: FnMut(&StoreRequest, DefaultDicomObject) -> Option<DefaultDicomObject> + Send + 'static,
+    {
+        self.transform = Some(Box::new(transform));
+        self
+    }
+
+    /// Retrieve the destinations of this router.
+    pub fn destinations(&self) -> &[AeAddr] {
+        &self.destinations
+    }
+
+    /// Obtain the queue directory of the given destination.
+    pub fn queue_path(&self, destination: &AeAddr) -> PathBuf {
+        self.queue_dir.join(queue_name(destination))
+    }
+
+    /// Place an object in the queue of every destination,
+    /// without applying the transformation hook.
+    ///
+    /// Each file is written under a temporary name first,
+    /// so that forwarders never see partially written objects.
+    /// Objects whose SOP instance UID is not a valid UID are refused,
+    /// since the UID is used as the file name.
+    pub fn enqueue(&self, object: &DefaultDicomObject) -> Result<()> {
+        let sop_instance_uid = object
+            .meta()
+            .media_storage_sop_instance_uid
+            .trim_end_matches(|c: char| c == '\0' || c == ' ');
+        ensure!(
+            store_scp::is_valid_uid(sop_instance_uid),
+            InvalidSopInstanceUidSnafu {
+                uid: sop_instance_uid
+            }
+        );
+        for destination in &self.destinations {
+            let dir = self.queue_path(destination);
+            fs::create_dir_all(&dir).context(CreateQueueSnafu { path: &dir })?;
+            let tmp_path = dir.join(format!(".{}.tmp", sop_instance_uid));
+            object
+                .write_to_file(&tmp_path)
+                .context(WriteQueueFileSnafu { path: &tmp_path })?;
+            let path = dir.join(format!("{}.dcm", sop_instance_uid));
+            fs::rename(&tmp_path, &path).context(MoveQueueFileSnafu { path: &tmp_path })?;
+        }
+        Ok(())
+    }
+
+    /// List the objects waiting in the queue of the given destination,
+    /// oldest first.
+    pub fn pending(&self, destination: &AeAddr) -> Result<Vec<PathBuf>> {
+        pending_files(&self.queue_path(destination))
+    }
+
+    /// Start forwarding the queued objects to each destination,
+    /// in one background thread per destination.
+    ///
+    /// The forwarders run until [`ForwarderHandle::stop`] is called.
+    pub fn spawn_forwarders(&self) -> Result<ForwarderHandle> {
+        let stop = Arc::new(AtomicBool::new(false));
+        let mut threads = Vec::with_capacity(self.destinations.len());
+        for destination in &self.destinations {
+            let dir = self.queue_path(destination);
+            fs::create_dir_all(&dir).context(CreateQueueSnafu { path: &dir })?;
+            let forwarder = Forwarder {
+                destination: destination.clone(),
+                dir,
+                calling_ae_title: self.calling_ae_title.clone(),
+                read_timeout: self.read_timeout,
+                batch_size: self.batch_size,
+                poll_interval: self.poll_interval,
+                retry_interval: self.retry_interval,
+            };
+            let stop = Arc::clone(&stop);
+            threads.push(std::thread::spawn(move || forwarder.run(&stop)));
+        }
+        Ok(ForwarderHandle { stop, threads })
+    }
+}
+
+impl StorageHandler for Router {
+    fn store_object(&mut self, request: &StoreRequest, object: DefaultDicomObject) -> u16 {
+        let object = match self.transform.as_mut() {
+            Some(transform) => match transform(request, object) {
+                Some(object) => object,
+                None => return status::SUCCESS,
+            },
+            None => object,
+        };
+        match self.enqueue(&object) {
+            Ok(()) => status::SUCCESS,
+            Err(e @ Error::InvalidSopInstanceUid { .. }) => {
+                warn!("Refusing instance {}: {}", request.sop_instance_uid, e);
+                store_scp::status::CANNOT_UNDERSTAND
+            }
+            Err(e) => {
+                warn!(
+                    "Could not queue instance {}: {}",
+                    request.sop_instance_uid, e
+                );
+                store_scp::status::OUT_OF_RESOURCES
+            }
+        }
+    }
+}
+
+/// A handle to the forwarders of a router,
+/// created with [`Router::spawn_forwarders`].
+#[derive(Debug)]
+pub struct ForwarderHandle {
+    stop: Arc<AtomicBool>,
+    threads: Vec<JoinHandle<()>>,
+}
+
+impl ForwarderHandle {
+    /// Stop forwarding objects and wait for the background threads.
+    ///
+    /// A batch of objects being sent is completed first.
+    pub fn stop(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        for thread in self.threads {
+            if let Err(e) = thread.join() {
+                std::panic::resume_unwind(e);
+            }
+        }
+    }
+}
+
+/// The forwarding service of a single destination.
+#[derive(Debug)]
+struct Forwarder {
+    destination: AeAddr,
+    dir: PathBuf,
+    calling_ae_title: String,
+    read_timeout: Option<Duration>,
+    batch_size: usize,
+    poll_interval: Duration,
+    retry_interval: Duration,
+}
+
+impl Forwarder {
+    /// Forward queued objects until requested to stop.
+    fn run(&self, stop: &AtomicBool) {
+        while !stop.load(Ordering::SeqCst) {
+            let delay = match self.forward_batch() {
+                Ok(0) => self.poll_interval,
+                Ok(_) => continue,
+                Err(e) => {
+                    warn!("{}", e);
+                    self.retry_interval
+                }
+            };
+            sleep_unless_stopped(delay, stop);
+        }
+    }
+
+    /// Send the next batch of queued objects in a single association,
+    /// returning the number of objects taken from the queue.
+    fn forward_batch(&self) -> Result<usize> {
+        let mut files = pending_files(&self.dir)?;
+        files.truncate(self.batch_size);
+        if files.is_empty() {
+            return Ok(0);
+        }
+
+        let mut scu = StoreScu::new().calling_ae_title(self.calling_ae_title.as_str());
+        if let Some(timeout) = self.read_timeout {
+            scu = scu.read_timeout(timeout);
+        }
+        for file in &files {
+            scu = scu.with_file(file);
+        }
+
+        let outcomes = match scu.send_to(self.destination.clone()) {
+            Ok(outcomes) => outcomes,
+            Err(store::Error::OpenFile { path, source }) => {
+                // an unreadable file would block the queue
+                warn!(
+                    "Could not open queued object {}: {}",
+                    path.display(),
+                    source
+                );
+                self.fail(&path)?;
+                return Ok(1);
+            }
+            Err(source) => {
+                return Err(source).context(ForwardSnafu {
+                    destination: self.destination.to_string(),
+                })
+            }
+        };
+
+        for (file, outcome) in files.iter().zip(outcomes) {
+            match outcome.status.map(status::status_type) {
+                Some(StatusType::Success) | Some(StatusType::Warning) => {
+                    fs::remove_file(file).context(RemoveQueueFileSnafu { path: file })?;
+                }
+                _ => {
+                    warn!(
+                        "Instance {} not accepted by {} (status {:04X?})",
+                        outcome.sop_instance_uid, self.destination, outcome.status
+                    );
+                    self.fail(file)?;
+                }
+            }
+        }
+        Ok(files.len())
+    }
+
+    /// Move a queued object to the `failed` directory of the queue.
+    fn fail(&self, path: &Path) -> Result<()> {
+        let failed_dir = self.dir.join("failed");
+        fs::create_dir_all(&failed_dir).context(CreateQueueSnafu { path: &failed_dir })?;
+        let file_name = path.file_name().unwrap_or_default();
+        fs::rename(path, failed_dir.join(file_name)).context(MoveQueueFileSnafu { path })
+    }
+}
+
+/// List the queued objects in the given directory, oldest first.
+fn pending_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(source) => return Err(source).context(ReadQueueSnafu { path: dir }),
+    };
+    let mut files = Vec::new();
+    for entry in entries {
+        let entry = entry.context(ReadQueueSnafu { path: dir })?;
+        let path = entry.path();
+        if path.extension().map_or(true, |ext| ext != "dcm") || !path.is_file() {
+            continue;
+        }
+        let modified = entry.metadata().and_then(|m| m.modified()).ok();
+        files.push((modified, path));
+    }
+    files.sort();
+    Ok(files.into_iter().map(|(_, path)| path).collect())
+}
+
+/// Derive the name of a destination's queue directory from its address.
+fn queue_name(destination: &AeAddr) -> String {
+    destination
+        .to_string()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Sleep for the given duration,
+/// waking up early if requested to stop.
+fn sleep_unless_stopped(duration: Duration, stop: &AtomicBool) {
+    let step = Duration::from_millis(50);
+    let mut remaining = duration;
+    while !stop.load(Ordering::SeqCst) && remaining > Duration::from_secs(0) {
+        let delay = remaining.min(step);
+        std::thread::sleep(delay);
+        remaining -= delay;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store_scp::StoreScp;
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_dictionary_std::{tags, uids};
+    use dicom_object::{FileDicomObject, FileMetaTableBuilder};
+    use std::net::TcpListener;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    fn test_object(sop_instance_uid: &str) -> DefaultDicomObject {
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uids::CT_IMAGE_STORAGE)
+            .media_storage_sop_instance_uid(sop_instance_uid)
+            .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+            .build()
+            .unwrap();
+        let mut object = FileDicomObject::new_empty_with_meta(meta);
+        object.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_instance_uid),
+        ));
+        object.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        object
+    }
+
+    fn request(sop_instance_uid: &str) -> StoreRequest {
+        StoreRequest {
+            presentation_context_id: 1,
+            message_id: 1,
+            sop_class_uid: uids::CT_IMAGE_STORAGE.to_string(),
+            sop_instance_uid: sop_instance_uid.to_string(),
+            transfer_syntax: uids::EXPLICIT_VR_LITTLE_ENDIAN.to_string(),
+            move_originator_ae_title: None,
+            move_originator_message_id: None,
+        }
+    }
+
+    #[test]
+    fn queue_names_are_file_names() {
+        let addr: AeAddr = "PACS@10.0.0.5:104".parse().unwrap();
+        assert_eq!(queue_name(&addr), "PACS_10.0.0.5_104");
+        let addr: AeAddr = "[::1]:11112".parse().unwrap();
+        assert_eq!(queue_name(&addr), "___1__11112");
+    }
+
+    #[test]
+    fn router_refuses_hostile_uid() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("queue");
+        let mut router = Router::new(&dir).with_destination("PACS@127.0.0.1:104".parse().unwrap());
+
+        let object = test_object("../../x");
+        assert!(matches!(
+            router.enqueue(&object),
+            Err(Error::InvalidSopInstanceUid { .. })
+        ));
+        assert_eq!(
+            router.store_object(&request("../../x"), object),
+            store_scp::status::CANNOT_UNDERSTAND
+        );
+        assert!(!root.path().join("x.dcm").exists());
+        assert!(!root.path().join(".x.tmp").exists());
+        let destination: AeAddr = "PACS@127.0.0.1:104".parse().unwrap();
+        assert!(router.pending(&destination).unwrap().is_empty());
+    }
+
+    #[test]
+    fn router_forwards_transformed_objects() {
+        let dir = tempfile::tempdir().unwrap();
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let destination = {
+            let received = Arc::clone(&received);
+            StoreScp::new(move |request: &StoreRequest, object: DefaultDicomObject| {
+                let name = object
+                    .element(tags::PATIENT_NAME)
+                    .unwrap()
+                    .to_str()
+                    .unwrap()
+                    .to_string();
+                received
+                    .lock()
+                    .unwrap()
+                    .push((request.sop_instance_uid.clone(), name));
+                status::SUCCESS
+            })
+            .ae_title("DEST")
+            .spawn(TcpListener::bind("127.0.0.1:0").unwrap())
+            .unwrap()
+        };
+        let destination_addr: AeAddr = format!("DEST@{}", destination.local_addr())
+            .parse()
+            .unwrap();
+
+        let mut router = Router::new(dir.path())
+            .with_destination(destination_addr.clone())
+            .poll_interval(Duration::from_millis(20))
+            .read_timeout(Duration::from_secs(10))
+            .transform(|request, mut object| {
+                if request.sop_instance_uid == "1.2.888.2" {
+                    return None;
+                }
+                object.put(DataElement::new(
+                    tags::PATIENT_NAME,
+                    VR::PN,
+                    PrimitiveValue::from("Anonymous"),
+                ));
+                Some(object)
+            });
+
+        for uid in ["1.2.888.1", "1.2.888.2", "1.2.888.3"] {
+            assert_eq!(
+                router.store_object(&request(uid), test_object(uid)),
+                status::SUCCESS
+            );
+        }
+        assert_eq!(router.pending(&destination_addr).unwrap().len(), 2);
+
+        let forwarders = router.spawn_forwarders().unwrap();
+        let start = Instant::now();
+        while !router.pending(&destination_addr).unwrap().is_empty() {
+            assert!(
+                start.elapsed() < Duration::from_secs(10),
+                "queue not emptied"
+            );
+            std::thread::sleep(Duration::from_millis(20));
+        }
+        forwarders.stop();
+        destination.stop().unwrap();
+
+        let mut received = received.lock().unwrap().clone();
+        received.sort();
+        assert_eq!(
+            received,
+            vec![
+                ("1.2.888.1".to_string(), "Anonymous".to_string()),
+                ("1.2.888.3".to_string(), "Anonymous".to_string()),
+            ]
+        );
+    }
+}
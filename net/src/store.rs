@@ -0,0 +1,794 @@
+//! Storage service module, service class user side (C-STORE SCU).
+//!
+//! [`StoreScu`] is the simplest way to send one or more DICOM objects
+//! to another node:
+//! it negotiates a presentation context
+//! for each distinct SOP class and transfer syntax among the objects,
+//! transmits them one by one,
+//! and reports the outcome of each operation.
+//!
+//! To send objects through an association established by other means,
+//! use the [`store`] function.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_net::store::StoreScu;
+//! # use std::time::Duration;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let outcomes = StoreScu::new()
+//!     .called_ae_title("PACS")
+//!     .read_timeout(Duration::from_secs(30))
+//!     .with_file("path/to/image.dcm")
+//!     .on_progress(|done, total, outcome| {
+//!         println!("[{}/{}] {}: {:?}", done, total, outcome.sop_instance_uid, outcome.status);
+//!     })
+//!     .send("129.168.0.5:104")?;
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+use std::fmt;
+use std::net::ToSocketAddrs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::command::CommandSet;
+use crate::dimse::{self, command_field, receive_message, send_message, Message};
+#[cfg(feature = "async")]
+use crate::dimse::{receive_message_async, send_message_async};
+use dicom_core::Tag;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_object::{DefaultDicomObject, InMemDicomObject, OpenFileOptions};
+use dicom_transfer_syntax_registry::{entries, TransferSyntaxRegistry};
+use dicom_ul::address::AeAddr;
+use dicom_ul::association::client::{self, ClientAssociation, ClientAssociationOptions};
+#[cfg(feature = "async")]
+use dicom_ul::association::AsyncClientAssociation;
+use dicom_ul::pdu::PresentationContextResult;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use tracing::warn;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("could not open DICOM file {}", path.display()))]
+    OpenFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("unsupported transfer syntax {}", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    #[snafu(display(
+        "no presentation context accepted for SOP class {} in a compatible transfer syntax",
+        sop_class_uid
+    ))]
+    NoPresentationContext {
+        sop_class_uid: String,
+        backtrace: Backtrace,
+    },
+
+    /// failed to encode the data set
+    WriteDataSet {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    /// failed to establish association
+    Establish { source: client::Error },
+
+    /// failed to release association
+    Release { source: client::Error },
+
+    /// failed to send C-STORE request
+    SendMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to receive C-STORE response
+    ReceiveMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    #[snafu(display("unexpected command field {:04X}H in response", command_field))]
+    UnexpectedCommand {
+        command_field: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "response is for message ID {}, but request had message ID {}",
+        got,
+        expected
+    ))]
+    MessageIdMismatch {
+        expected: u16,
+        got: u16,
+        backtrace: Backtrace,
+    },
+}
+
+impl Error {
+    /// Whether the error only concerns the object being stored,
+    /// so that the association remains usable for other objects.
+    pub(crate) fn is_object_specific(&self) -> bool {
+        matches!(
+            self,
+            Error::OpenFile { .. }
+                | Error::UnsupportedTransferSyntax { .. }
+                | Error::NoPresentationContext { .. }
+                | Error::WriteDataSet { .. }
+        )
+    }
+
+    /// Whether the error was caused by the association itself,
+    /// rather than by the object or the response to it.
+    pub(crate) fn is_communication_error(&self) -> bool {
+        matches!(
+            self,
+            Error::SendMessage { .. } | Error::ReceiveMessage { .. }
+        )
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Create a C-STORE-RQ command set.
+///
+/// See [`CommandSet::c_store_rq`] for a typed command set.
+pub fn store_request(
+    sop_class_uid: &str,
+    sop_instance_uid: &str,
+    message_id: u16,
+) -> InMemDicomObject {
+    CommandSet::c_store_rq(sop_class_uid, sop_instance_uid, message_id).to_object()
+}
+
+/// Send a DICOM object through the association
+/// with a C-STORE request, and wait for its response.
+///
+/// The presentation context is chosen among those accepted
+/// for the object's SOP class,
+/// preferring the one in the object's own transfer syntax.
+/// Objects in a transfer syntax requiring a pixel data codec
+/// can only be sent in that same transfer syntax.
+///
+/// Returns the status code in the response.
+pub fn store(
+    association: &mut ClientAssociation,
+    object: &DefaultDicomObject,
+    message_id: u16,
+) -> Result<u16> {
+    let (pc_id, command, data) = prepare_store(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        object,
+        message_id,
+    )?;
+    send_message(association, pc_id, &command, Some(&data)).context(SendMessageSnafu)?;
+    let response = receive_message(association).context(ReceiveMessageSnafu)?;
+    check_response(&response, message_id)
+}
+
+/// Send a DICOM object through the association asynchronously
+/// with a C-STORE request, and wait for its response.
+///
+/// See [`store`] for details.
+#[cfg(feature = "async")]
+pub async fn store_async(
+    association: &mut AsyncClientAssociation,
+    object: &DefaultDicomObject,
+    message_id: u16,
+) -> Result<u16> {
+    let (pc_id, command, data) = prepare_store(
+        association.presentation_contexts(),
+        |pc_id| association.abstract_syntax(pc_id),
+        object,
+        message_id,
+    )?;
+    send_message_async(association, pc_id, &command, Some(&data))
+        .await
+        .context(SendMessageSnafu)?;
+    let response = receive_message_async(association)
+        .await
+        .context(ReceiveMessageSnafu)?;
+    check_response(&response, message_id)
+}
+
+/// Select the presentation context for sending the object,
+/// and prepare the C-STORE request command set and encoded data set.
+fn prepare_store<'a>(
+    presentation_contexts: &[PresentationContextResult],
+    abstract_syntax: impl Fn(u8) -> Option<&'a str>,
+    object: &DefaultDicomObject,
+    message_id: u16,
+) -> Result<(u8, InMemDicomObject, Vec<u8>)> {
+    let meta = object.meta();
+    let sop_class_uid = trim_uid(&meta.media_storage_sop_class_uid);
+    let sop_instance_uid = trim_uid(&meta.media_storage_sop_instance_uid);
+    let ts_uid = trim_uid(&meta.transfer_syntax);
+    let ts = TransferSyntaxRegistry
+        .get(ts_uid)
+        .context(UnsupportedTransferSyntaxSnafu { uid: ts_uid })?;
+
+    // select presentation context and the transfer syntax to send in
+    let candidates: Vec<_> = presentation_contexts
+        .iter()
+        .filter(|pc| abstract_syntax(pc.id) == Some(sop_class_uid))
+        .collect();
+    let (pc_id, pc_ts) = candidates
+        .iter()
+        .find(|pc| trim_uid(&pc.transfer_syntax) == ts_uid)
+        .map(|pc| (pc.id, ts))
+        .or_else(|| {
+            if !ts.is_codec_free() {
+                return None;
+            }
+            candidates.iter().find_map(|pc| {
+                TransferSyntaxRegistry
+                    .get(trim_uid(&pc.transfer_syntax))
+                    .filter(|pc_ts| pc_ts.is_codec_free())
+                    .map(|pc_ts| (pc.id, pc_ts))
+            })
+        })
+        .context(NoPresentationContextSnafu { sop_class_uid })?;
+
+    let mut data = Vec::new();
+    object
+        .write_dataset_with_ts(&mut data, pc_ts)
+        .context(WriteDataSetSnafu)?;
+
+    let command = store_request(sop_class_uid, sop_instance_uid, message_id);
+    Ok((pc_id, command, data))
+}
+
+/// Check a C-STORE response, returning its status code.
+fn check_response(response: &Message, message_id: u16) -> Result<u16> {
+    let command_field = response.command_field().context(ReceiveMessageSnafu)?;
+    ensure!(
+        command_field == command_field::C_STORE_RSP,
+        UnexpectedCommandSnafu { command_field }
+    );
+    let got = response
+        .message_id_being_responded_to()
+        .context(ReceiveMessageSnafu)?;
+    ensure!(
+        got == message_id,
+        MessageIdMismatchSnafu {
+            expected: message_id,
+            got
+        }
+    );
+    response.status().context(ReceiveMessageSnafu)
+}
+
+/// The outcome of a storage operation performed by [`StoreScu`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreOutcome {
+    /// The SOP instance UID of the object
+    pub sop_instance_uid: String,
+    /// The status code in the C-STORE response,
+    /// or `None` if the object could not be sent
+    pub status: Option<u16>,
+}
+
+/// An object to be sent by the storage SCU.
+enum Source {
+    File(PathBuf),
+    Object(Box<DefaultDicomObject>),
+}
+
+/// The callback type for reporting progress.
+type ProgressCallback<'a> = Box<dyn FnMut(usize, usize, &StoreOutcome) + 'a>;
+
+/// A storage service class user,
+/// sending a batch of DICOM objects to another node
+/// in a single association.
+///
+/// See the [module-level documentation](self) for an example.
+pub struct StoreScu<'a> {
+    /// the calling AE title
+    calling_ae_title: Cow<'a, str>,
+    /// the called AE title
+    called_ae_title: Cow<'a, str>,
+    /// the maximum PDU length that this node admits
+    max_pdu_length: Option<u32>,
+    /// the TCP connection timeout
+    connection_timeout: Option<Duration>,
+    /// the TCP read timeout
+    read_timeout: Option<Duration>,
+    /// the TCP write timeout
+    write_timeout: Option<Duration>,
+    /// the objects to send
+    sources: Vec<Source>,
+    /// the progress callback
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl fmt::Debug for StoreScu<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StoreScu")
+            .field("calling_ae_title", &self.calling_ae_title)
+            .field("called_ae_title", &self.called_ae_title)
+            .field("max_pdu_length", &self.max_pdu_length)
+            .field("connection_timeout", &self.connection_timeout)
+            .field("read_timeout", &self.read_timeout)
+            .field("write_timeout", &self.write_timeout)
+            .field("objects", &self.sources.len())
+            .finish()
+    }
+}
+
+impl Default for StoreScu<'_> {
+    fn default() -> Self {
+        StoreScu {
+            calling_ae_title: "STORE-SCU".into(),
+            called_ae_title: "ANY-SCP".into(),
+            max_pdu_length: None,
+            connection_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
+            sources: Vec::new(),
+            on_progress: None,
+        }
+    }
+}
+
+impl<'a> StoreScu<'a> {
+    /// Create a new storage SCU with no objects to send.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define the calling application entity title,
+    /// which refers to this DICOM node.
+    ///
+    /// The default is `STORE-SCU`.
+    pub fn calling_ae_title<T>(mut self, calling_ae_title: T) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.calling_ae_title = calling_ae_title.into();
+        self
+    }
+
+    /// Define the called application entity title,
+    /// which refers to the target DICOM node.
+    ///
+    /// The default is `ANY-SCP`.
+    pub fn called_ae_title<T>(mut self, called_ae_title: T) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.called_ae_title = called_ae_title.into();
+        self
+    }
+
+    /// Override the maximum PDU length that this node admits.
+    pub fn max_pdu_length(mut self, value: u32) -> Self {
+        self.max_pdu_length = Some(value);
+        self
+    }
+
+    /// Set the maximum time to wait for the TCP connection to be established.
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for a response from the other node.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for outgoing data to be sent.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Include a DICOM file to be sent.
+    ///
+    /// Only the file meta group is read before the association is established.
+    /// The full file is only read once it is about to be sent.
+    pub fn with_file<P>(mut self, path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        self.sources.push(Source::File(path.as_ref().to_path_buf()));
+        self
+    }
+
+    /// Include an in-memory DICOM object to be sent.
+    pub fn with_object(mut self, object: DefaultDicomObject) -> Self {
+        self.sources.push(Source::Object(Box::new(object)));
+        self
+    }
+
+    /// Set a callback to be called after each storage operation,
+    /// with the number of processed objects,
+    /// the total number of objects,
+    /// and the outcome of the operation.
+    pub fn on_progress<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(usize, usize, &StoreOutcome) + 'a,
+    {
+        self.on_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Establish an association with the node at the given address,
+    /// send all objects, and release the association.
+    ///
+    /// Objects which cannot be sent
+    /// because of their SOP class or transfer syntax
+    /// are skipped, reported with an empty status.
+    /// Failures in communication interrupt the whole operation.
+    pub fn send<A>(self, address: A) -> Result<Vec<StoreOutcome>>
+    where
+        A: ToSocketAddrs,
+    {
+        self.send_over(|options| options.establish(address))
+    }
+
+    /// Establish an association with the given application entity,
+    /// send all objects, and release the association.
+    ///
+    /// If the address contains an AE title,
+    /// it is used as the called AE title,
+    /// overriding the one previously defined.
+    /// See [`send`](Self::send) for details.
+    pub fn send_to(self, address: AeAddr) -> Result<Vec<StoreOutcome>> {
+        self.send_over(|options| options.establish_with(address))
+    }
+
+    fn send_over<F>(self, establish: F) -> Result<Vec<StoreOutcome>>
+    where
+        F: FnOnce(ClientAssociationOptions<'a>) -> client::Result<ClientAssociation>,
+    {
+        let (options, mut batch) = match self.prepare()? {
+            Some(prepared) => prepared,
+            None => return Ok(Vec::new()),
+        };
+        let mut association = establish(options).context(EstablishSnafu)?;
+
+        while let Some((message_id, source)) = batch.next_source() {
+            let result = source
+                .load()
+                .and_then(|object| store(&mut association, &object, message_id));
+            if let Err(e) = batch.record(result) {
+                let _ = association.abort();
+                return Err(e);
+            }
+        }
+
+        association.release().context(ReleaseSnafu)?;
+        Ok(batch.outcomes)
+    }
+
+    /// Establish an association with the node at the given address
+    /// asynchronously,
+    /// send all objects, and release the association.
+    ///
+    /// See [`send`](Self::send) for details.
+    /// Note that DICOM files are still read synchronously.
+    #[cfg(feature = "async")]
+    pub async fn send_async<A>(self, address: A) -> Result<Vec<StoreOutcome>>
+    where
+        A: tokio::net::ToSocketAddrs,
+    {
+        let (options, mut batch) = match self.prepare()? {
+            Some(prepared) => prepared,
+            None => return Ok(Vec::new()),
+        };
+        let mut association = options
+            .establish_async(address)
+            .await
+            .context(EstablishSnafu)?;
+
+        while let Some((message_id, source)) = batch.next_source() {
+            let result = match source.load() {
+                Ok(object) => store_async(&mut association, &object, message_id).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = batch.record(result) {
+                let _ = association.abort().await;
+                return Err(e);
+            }
+        }
+
+        association.release().await.context(ReleaseSnafu)?;
+        Ok(batch.outcomes)
+    }
+
+    /// Gather the presentation contexts to propose for the objects to send,
+    /// returning the association options and the batch of objects,
+    /// or `None` if there are no objects to send.
+    fn prepare(self) -> Result<Option<(ClientAssociationOptions<'a>, Batch<'a>)>> {
+        let StoreScu {
+            calling_ae_title,
+            called_ae_title,
+            max_pdu_length,
+            connection_timeout,
+            read_timeout,
+            write_timeout,
+            sources,
+            on_progress,
+        } = self;
+
+        if sources.is_empty() {
+            return Ok(None);
+        }
+
+        // gather presentation contexts
+        let mut presentation_contexts: Vec<(String, Vec<String>)> = Vec::new();
+        let mut sop_instance_uids = Vec::with_capacity(sources.len());
+        for source in &sources {
+            let meta = match source {
+                Source::File(path) => OpenFileOptions::new()
+                    .read_until(Tag(0x0001, 0x0000))
+                    .open_file(path)
+                    .context(OpenFileSnafu { path })?
+                    .meta()
+                    .clone(),
+                Source::Object(object) => object.meta().clone(),
+            };
+            let sop_class_uid = trim_uid(&meta.media_storage_sop_class_uid).to_string();
+            let ts_uid = trim_uid(&meta.transfer_syntax).to_string();
+            for pc in proposed_transfer_syntaxes(&ts_uid) {
+                let entry = (sop_class_uid.clone(), vec![pc]);
+                if !presentation_contexts.contains(&entry) {
+                    presentation_contexts.push(entry);
+                }
+            }
+            sop_instance_uids.push(trim_uid(&meta.media_storage_sop_instance_uid).to_string());
+        }
+
+        let mut options = ClientAssociationOptions::new()
+            .calling_ae_title(calling_ae_title)
+            .called_ae_title(called_ae_title);
+        for (sop_class_uid, ts_uids) in presentation_contexts {
+            options = options.with_presentation_context(
+                Cow::from(sop_class_uid),
+                ts_uids.into_iter().map(Cow::from).collect(),
+            );
+        }
+        if let Some(max_pdu_length) = max_pdu_length {
+            options = options.max_pdu_length(max_pdu_length);
+        }
+        if let Some(timeout) = connection_timeout {
+            options = options.connection_timeout(timeout);
+        }
+        if let Some(timeout) = read_timeout {
+            options = options.read_timeout(timeout);
+        }
+        if let Some(timeout) = write_timeout {
+            options = options.write_timeout(timeout);
+        }
+
+        let total = sources.len();
+        let batch = Batch {
+            sources: sources
+                .into_iter()
+                .zip(sop_instance_uids)
+                .collect::<Vec<_>>()
+                .into_iter(),
+            total,
+            pending_uid: None,
+            outcomes: Vec::with_capacity(total),
+            on_progress,
+        };
+        Ok(Some((options, batch)))
+    }
+}
+
+impl Source {
+    /// Obtain the DICOM object to send.
+    fn load(self) -> Result<Box<DefaultDicomObject>> {
+        match self {
+            Source::File(path) => dicom_object::open_file(&path)
+                .map(Box::new)
+                .context(OpenFileSnafu { path }),
+            Source::Object(object) => Ok(object),
+        }
+    }
+}
+
+/// The state of a batch of storage operations.
+struct Batch<'a> {
+    /// the objects yet to send, with their SOP instance UIDs
+    sources: std::vec::IntoIter<(Source, String)>,
+    /// the total number of objects
+    total: usize,
+    /// the SOP instance UID of the object being sent
+    pending_uid: Option<String>,
+    /// the outcomes of the operations so far
+    outcomes: Vec<StoreOutcome>,
+    /// the progress callback
+    on_progress: Option<ProgressCallback<'a>>,
+}
+
+impl Batch<'_> {
+    /// Take the next object to send, along with its message ID.
+    fn next_source(&mut self) -> Option<(u16, Source)> {
+        let (source, sop_instance_uid) = self.sources.next()?;
+        self.pending_uid = Some(sop_instance_uid);
+        let message_id = (self.outcomes.len() % usize::from(u16::MAX)) as u16 + 1;
+        Some((message_id, source))
+    }
+
+    /// Record the result of sending the last object taken,
+    /// failing if the whole operation should be interrupted.
+    fn record(&mut self, result: Result<u16>) -> Result<()> {
+        let sop_instance_uid = self.pending_uid.take().unwrap_or_default();
+        let status = match result {
+            Ok(status) => Some(status),
+            Err(e) if e.is_object_specific() => {
+                warn!("Could not send instance {}: {}", sop_instance_uid, e);
+                None
+            }
+            Err(e) => return Err(e),
+        };
+
+        let outcome = StoreOutcome {
+            sop_instance_uid,
+            status,
+        };
+        if let Some(callback) = self.on_progress.as_mut() {
+            callback(self.outcomes.len() + 1, self.total, &outcome);
+        }
+        self.outcomes.push(outcome);
+        Ok(())
+    }
+}
+
+/// Determine the transfer syntaxes to propose
+/// for sending an object in the given transfer syntax.
+///
+/// Objects without encapsulated pixel data
+/// can also be sent in the uncompressed little endian transfer syntaxes.
+fn proposed_transfer_syntaxes(ts_uid: &str) -> Vec<String> {
+    let mut out = vec![ts_uid.to_string()];
+    let codec_free = TransferSyntaxRegistry
+        .get(ts_uid)
+        .map(|ts| ts.is_codec_free())
+        .unwrap_or(false);
+    if codec_free {
+        for uid in [
+            entries::EXPLICIT_VR_LITTLE_ENDIAN.uid(),
+            entries::IMPLICIT_VR_LITTLE_ENDIAN.uid(),
+        ] {
+            if uid != ts_uid {
+                out.push(uid.to_string());
+            }
+        }
+    }
+    out
+}
+
+fn trim_uid(uid: &str) -> &str {
+    uid.trim_end_matches(|c: char| c == '\0' || c == ' ')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dimse::{receive_event, status, Event};
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_dictionary_std::{tags, uids};
+    use dicom_object::{FileDicomObject, FileMetaTableBuilder};
+    use dicom_ul::association::ServerAssociationOptions;
+    use std::net::TcpListener;
+
+    fn test_object(sop_instance_uid: &str) -> DefaultDicomObject {
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uids::CT_IMAGE_STORAGE)
+            .media_storage_sop_instance_uid(sop_instance_uid)
+            .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+            .build()
+            .unwrap();
+        let mut object = FileDicomObject::new_empty_with_meta(meta);
+        object.put(DataElement::new(
+            tags::SOP_CLASS_UID,
+            VR::UI,
+            PrimitiveValue::from(uids::CT_IMAGE_STORAGE),
+        ));
+        object.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_instance_uid),
+        ));
+        object.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        object
+    }
+
+    #[test]
+    fn store_scu_loopback() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(uids::CT_IMAGE_STORAGE)
+                .establish(stream)
+                .unwrap();
+            let mut received = Vec::new();
+            loop {
+                match receive_event(&mut association).unwrap() {
+                    Event::Message(message) => {
+                        let request = CommandSet::from_object(&message.command).unwrap();
+                        assert_eq!(request.command_field, command_field::C_STORE_RQ);
+                        let data = message.data.as_ref().unwrap();
+                        assert!(!data.is_empty());
+                        received.push(request.affected_sop_instance_uid.clone().unwrap());
+                        let response = request.response(status::SUCCESS).to_object();
+                        send_message(
+                            &mut association,
+                            message.presentation_context_id,
+                            &response,
+                            None,
+                        )
+                        .unwrap();
+                    }
+                    Event::ReleaseRequested => {
+                        association.send(&dicom_ul::pdu::Pdu::ReleaseRP).unwrap();
+                        break;
+                    }
+                    Event::Aborted => panic!("association aborted"),
+                }
+            }
+            received
+        });
+
+        let mut progress = Vec::new();
+        let outcomes = StoreScu::new()
+            .read_timeout(Duration::from_secs(10))
+            .with_object(test_object("1.2.888.1"))
+            .with_object(test_object("1.2.888.2"))
+            .on_progress(|done, total, _| progress.push((done, total)))
+            .send(addr)
+            .unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                StoreOutcome {
+                    sop_instance_uid: "1.2.888.1".to_string(),
+                    status: Some(status::SUCCESS),
+                },
+                StoreOutcome {
+                    sop_instance_uid: "1.2.888.2".to_string(),
+                    status: Some(status::SUCCESS),
+                },
+            ]
+        );
+        assert_eq!(progress, vec![(1, 2), (2, 2)]);
+        assert_eq!(scp.join().unwrap(), vec!["1.2.888.1", "1.2.888.2"]);
+    }
+
+    #[test]
+    fn propose_uncompressed_alternatives() {
+        assert_eq!(
+            proposed_transfer_syntaxes(uids::EXPLICIT_VR_LITTLE_ENDIAN),
+            vec![
+                uids::EXPLICIT_VR_LITTLE_ENDIAN.to_string(),
+                uids::IMPLICIT_VR_LITTLE_ENDIAN.to_string(),
+            ]
+        );
+        assert_eq!(
+            proposed_transfer_syntaxes(uids::RLE_LOSSLESS),
+            vec![uids::RLE_LOSSLESS.to_string()]
+        );
+    }
+}
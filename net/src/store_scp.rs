@@ -0,0 +1,895 @@
+//! Storage service module, service class provider side (C-STORE SCP).
+//!
+//! [`StoreScp`] accepts incoming associations,
+//! receives C-STORE requests,
+//! and passes each incoming data set to a [`StorageHandler`],
+//! which decides the status code in the response.
+//! Verification requests (C-ECHO) are also served.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_net::store_scp::{DirectoryStorage, StoreScp};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut scp = StoreScp::new(DirectoryStorage::new("./incoming"))
+//!     .ae_title("MINI-PACS");
+//! scp.listen("0.0.0.0:11112")?;
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+use std::io::Read;
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::command::CommandSet;
+use crate::dimse::{
+    self, command_field, receive_event, send_message, unrecognized_operation_response, Association,
+    Event, Message,
+};
+#[cfg(feature = "async")]
+use crate::dimse::{receive_event_async, send_message_async, AsyncAssociation};
+use crate::echo::echo_response;
+use dicom_core::dictionary::uid::{UidDictionaryEntry, UidType};
+use dicom_dictionary_std::{tags, uids, StandardUidDictionary};
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_object::{DefaultDicomObject, FileMetaTableBuilder, InMemDicomObject};
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use dicom_ul::association::server::{self, AcceptAny, ServerAssociationOptions};
+use dicom_ul::pdu::{Pdu, PresentationContextResult};
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use tracing::warn;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to listen for incoming connections
+    Listen {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    /// failed to wake the background listener
+    WakeListener {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    /// failed to configure the incoming connection
+    SetTimeout {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    /// failed to establish association
+    Establish { source: server::Error },
+
+    /// failed to receive message
+    ReceiveMessage {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    /// failed to send response
+    SendResponse {
+        #[snafu(backtrace)]
+        source: dimse::Error,
+    },
+
+    #[snafu(display("unsupported transfer syntax {}", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// could not build the file meta group of the received object
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: dicom_object::meta::Error,
+    },
+
+    /// could not read the received data set
+    ReadDataSet {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("invalid SOP instance UID {:?}", uid))]
+    InvalidSopInstanceUid { uid: String, backtrace: Backtrace },
+
+    #[snafu(display("could not write received object to {}", path.display()))]
+    WriteFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Status codes specific to the storage service (PS3.4 section B.2.3).
+pub mod status {
+    /// Refused: out of resources.
+    pub const OUT_OF_RESOURCES: u16 = 0xA700;
+    /// Error: data set does not match SOP class.
+    pub const DATA_SET_MISMATCH: u16 = 0xA900;
+    /// Error: cannot understand.
+    pub const CANNOT_UNDERSTAND: u16 = 0xC000;
+    /// Warning: coercion of data elements.
+    pub const COERCION_OF_DATA_ELEMENTS: u16 = 0xB000;
+    /// Warning: elements discarded.
+    pub const ELEMENTS_DISCARDED: u16 = 0xB006;
+    /// Warning: data set does not match SOP class.
+    pub const DATA_SET_MISMATCH_WARNING: u16 = 0xB007;
+}
+
+/// The properties of an incoming C-STORE request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreRequest {
+    /// The presentation context in which the object was sent
+    pub presentation_context_id: u8,
+    /// The message ID of the request
+    pub message_id: u16,
+    /// The SOP class UID of the object
+    pub sop_class_uid: String,
+    /// The SOP instance UID of the object
+    pub sop_instance_uid: String,
+    /// The transfer syntax in which the data set is encoded
+    pub transfer_syntax: String,
+    /// The AE title of the node which requested a C-MOVE
+    /// resulting in this request, if any
+    pub move_originator_ae_title: Option<String>,
+    /// The message ID of the C-MOVE request
+    /// resulting in this request, if any
+    pub move_originator_message_id: Option<u16>,
+}
+
+impl StoreRequest {
+    /// Build a file meta group for the object in this request.
+    pub fn meta(&self) -> FileMetaTableBuilder {
+        FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(self.sop_class_uid.as_str())
+            .media_storage_sop_instance_uid(self.sop_instance_uid.as_str())
+            .transfer_syntax(self.transfer_syntax.as_str())
+    }
+
+    /// Decode the received data set into a DICOM object,
+    /// including a file meta group describing it.
+    pub fn read_object(&self, data: &mut dyn Read) -> Result<DefaultDicomObject> {
+        let ts = TransferSyntaxRegistry.get(&self.transfer_syntax).context(
+            UnsupportedTransferSyntaxSnafu {
+                uid: &self.transfer_syntax,
+            },
+        )?;
+        let meta = self.meta().build().context(BuildMetaSnafu)?;
+        let object = InMemDicomObject::read_dataset_with_ts(data, ts).context(ReadDataSetSnafu)?;
+        Ok(object.with_exact_meta(meta))
+    }
+}
+
+/// Common interface for handling objects received by a storage SCP.
+///
+/// Implementors decide what to do with each incoming object
+/// and which status code to respond with,
+/// either from [`dimse::status`](crate::dimse::status)
+/// or from this module's [`status`](self::status) codes.
+///
+/// Only [`store_object`](Self::store_object) needs to be implemented.
+/// Implementors which do not need the data set decoded,
+/// such as those forwarding or saving the data as is,
+/// may override [`store_stream`](Self::store_stream) instead.
+pub trait StorageHandler {
+    /// Handle a received DICOM object.
+    ///
+    /// Returns the status code of the C-STORE response.
+    fn store_object(&mut self, request: &StoreRequest, object: DefaultDicomObject) -> u16;
+
+    /// Handle a received data set as a stream of bytes,
+    /// encoded in the transfer syntax of the request.
+    ///
+    /// The default implementation decodes the data set
+    /// and passes it to [`store_object`](Self::store_object),
+    /// responding with [`CANNOT_UNDERSTAND`](self::status::CANNOT_UNDERSTAND)
+    /// if the data set cannot be decoded.
+    ///
+    /// Returns the status code of the C-STORE response.
+    fn store_stream(&mut self, request: &StoreRequest, data: &mut dyn Read) -> u16 {
+        match request.read_object(data) {
+            Ok(object) => self.store_object(request, object),
+            Err(e) => {
+                warn!(
+                    "Could not read instance {}: {}",
+                    request.sop_instance_uid, e
+                );
+                status::CANNOT_UNDERSTAND
+            }
+        }
+    }
+}
+
+impl<F> StorageHandler for F
+where
+    F: FnMut(&StoreRequest, DefaultDicomObject) -> u16,
+{
+    fn store_object(&mut self, request: &StoreRequest, object: DefaultDicomObject) -> u16 {
+        self(request, object)
+    }
+}
+
+/// A storage handler which saves each received object as a DICOM file
+/// in a directory, named after its SOP instance UID.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryStorage {
+    path: PathBuf,
+}
+
+impl DirectoryStorage {
+    /// Create a storage handler saving files to the given directory.
+    pub fn new<P>(path: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        DirectoryStorage {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Retrieve the directory where files are saved.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn save(&self, object: &DefaultDicomObject, sop_instance_uid: &str) -> Result<()> {
+        ensure!(
+            is_valid_uid(sop_instance_uid),
+            InvalidSopInstanceUidSnafu {
+                uid: sop_instance_uid
+            }
+        );
+        let path = self.path.join(format!("{}.dcm", sop_instance_uid));
+        object.write_to_file(&path).context(WriteFileSnafu { path })
+    }
+}
+
+impl StorageHandler for DirectoryStorage {
+    fn store_object(&mut self, request: &StoreRequest, object: DefaultDicomObject) -> u16 {
+        match self.save(&object, &request.sop_instance_uid) {
+            Ok(()) => dimse::status::SUCCESS,
+            Err(e) => {
+                warn!("{}", e);
+                status::OUT_OF_RESOURCES
+            }
+        }
+    }
+}
+
+/// Check whether a UID is safe to be used as a file name,
+/// consisting of 1 to 64 digits and periods.
+///
+/// UIDs received from a peer are untrusted,
+/// and must be checked before being used to build a path.
+pub(crate) fn is_valid_uid(uid: &str) -> bool {
+    !uid.is_empty() && uid.len() <= 64 && uid.bytes().all(|b| b.is_ascii_digit() || b == b'.')
+}
+
+/// Retrieve the UIDs of all storage SOP classes
+/// in the standard UID dictionary.
+pub fn storage_sop_classes() -> impl Iterator<Item = &'static str> {
+    StandardUidDictionary
+        .entries()
+        .filter(|e| {
+            e.uid_type() == UidType::SopClass
+                && !e.is_retired()
+                && e.uid().starts_with("1.2.840.10008.5.1.4.1.1.")
+                && e.name().ends_with("Storage")
+        })
+        .map(|e| e.uid)
+}
+
+/// Create a C-STORE-RSP command set.
+pub fn store_response(request: &StoreRequest, status: u16) -> InMemDicomObject {
+    CommandSet::c_store_rq(
+        &request.sop_class_uid,
+        &request.sop_instance_uid,
+        request.message_id,
+    )
+    .response(status)
+    .to_object()
+}
+
+/// Serve C-STORE and C-ECHO requests through the association
+/// until the other node releases or aborts it,
+/// passing each received object to the given handler.
+///
+/// Any other request is responded with the status
+/// [`UNRECOGNIZED_OPERATION`](crate::dimse::status::UNRECOGNIZED_OPERATION).
+pub fn serve_store<A, H>(association: &mut A, handler: &mut H) -> Result<()>
+where
+    A: Association + ?Sized,
+    H: StorageHandler + ?Sized,
+{
+    loop {
+        let message = match receive_event(association).context(ReceiveMessageSnafu)? {
+            Event::Message(message) => message,
+            Event::ReleaseRequested => {
+                return association.send(&Pdu::ReleaseRP).context(SendResponseSnafu);
+            }
+            Event::Aborted => return Ok(()),
+        };
+
+        if let Some(response) = respond(association.presentation_contexts(), &message, handler)? {
+            send_message(
+                association,
+                message.presentation_context_id,
+                &response,
+                None,
+            )
+            .context(SendResponseSnafu)?;
+        }
+    }
+}
+
+/// Serve C-STORE and C-ECHO requests through the association asynchronously
+/// until the other node releases or aborts it,
+/// passing each received object to the given handler.
+///
+/// See [`serve_store`] for details.
+#[cfg(feature = "async")]
+pub async fn serve_store_async<A, H>(association: &mut A, handler: &mut H) -> Result<()>
+where
+    A: AsyncAssociation + ?Sized,
+    H: StorageHandler + ?Sized,
+{
+    loop {
+        let message = match receive_event_async(association)
+            .await
+            .context(ReceiveMessageSnafu)?
+        {
+            Event::Message(message) => message,
+            Event::ReleaseRequested => {
+                return association
+                    .send(&Pdu::ReleaseRP)
+                    .await
+                    .context(SendResponseSnafu);
+            }
+            Event::Aborted => return Ok(()),
+        };
+
+        if let Some(response) = respond(association.presentation_contexts(), &message, handler)? {
+            send_message_async(
+                association,
+                message.presentation_context_id,
+                &response,
+                None,
+            )
+            .await
+            .context(SendResponseSnafu)?;
+        }
+    }
+}
+
+/// Obtain the response to a single message as a storage SCP,
+/// or `None` if the message should be ignored.
+fn respond<H>(
+    presentation_contexts: &[PresentationContextResult],
+    message: &Message,
+    handler: &mut H,
+) -> Result<Option<InMemDicomObject>>
+where
+    H: StorageHandler + ?Sized,
+{
+    let command_field = message.command_field().context(ReceiveMessageSnafu)?;
+    let response = match command_field {
+        command_field::C_STORE_RQ => {
+            let request = read_store_request(presentation_contexts, message)?;
+            let status = if is_valid_uid(&request.sop_instance_uid) {
+                let mut data = message.data.as_deref().unwrap_or_default();
+                handler.store_stream(&request, &mut data)
+            } else {
+                warn!(
+                    "Rejecting instance with invalid UID {:?}",
+                    request.sop_instance_uid
+                );
+                status::CANNOT_UNDERSTAND
+            };
+            store_response(&request, status)
+        }
+        command_field::C_ECHO_RQ => {
+            let message_id = message.message_id().context(ReceiveMessageSnafu)?;
+            echo_response(message_id, dimse::status::SUCCESS)
+        }
+        _ if command_field::is_response(command_field) => {
+            warn!(
+                "Ignoring unexpected response message ({:04X}H)",
+                command_field
+            );
+            return Ok(None);
+        }
+        _ => {
+            warn!("Unsupported operation {:04X}H", command_field);
+            unrecognized_operation_response(message).context(ReceiveMessageSnafu)?
+        }
+    };
+    Ok(Some(response))
+}
+
+/// Collect the properties of a C-STORE request message.
+pub(crate) fn read_store_request(
+    presentation_contexts: &[PresentationContextResult],
+    message: &Message,
+) -> Result<StoreRequest> {
+    let transfer_syntax = presentation_contexts
+        .iter()
+        .find(|pc| pc.id == message.presentation_context_id)
+        .map(|pc| pc.transfer_syntax.trim_end_matches('\0').to_string())
+        .unwrap_or_default();
+
+    Ok(StoreRequest {
+        presentation_context_id: message.presentation_context_id,
+        message_id: message.message_id().context(ReceiveMessageSnafu)?,
+        sop_class_uid: message
+            .affected_sop_class_uid()
+            .context(ReceiveMessageSnafu)?,
+        sop_instance_uid: dimse::command_str(&message.command, tags::AFFECTED_SOP_INSTANCE_UID)
+            .context(ReceiveMessageSnafu)?,
+        transfer_syntax,
+        move_originator_ae_title: dimse::command_str(
+            &message.command,
+            tags::MOVE_ORIGINATOR_APPLICATION_ENTITY_TITLE,
+        )
+        .ok()
+        .map(|ae| ae.trim().to_string()),
+        move_originator_message_id: dimse::command_u16(
+            &message.command,
+            tags::MOVE_ORIGINATOR_MESSAGE_ID,
+        )
+        .ok(),
+    })
+}
+
+/// A storage service class provider.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Debug)]
+pub struct StoreScp<'a, H> {
+    /// the storage handler
+    handler: H,
+    /// the AE title of this node
+    ae_title: Cow<'a, str>,
+    /// whether to only accept associations called by this node's AE title
+    strict_ae_title: bool,
+    /// the abstract syntaxes to accept
+    abstract_syntaxes: Vec<Cow<'a, str>>,
+    /// the maximum PDU length that this node admits
+    max_pdu_length: Option<u32>,
+    /// the TCP read timeout
+    read_timeout: Option<Duration>,
+}
+
+impl<'a, H> StoreScp<'a, H>
+where
+    H: StorageHandler,
+{
+    /// Create a new storage SCP with the given handler.
+    ///
+    /// By default, all storage SOP classes in the standard UID dictionary
+    /// and the verification SOP class are accepted.
+    pub fn new(handler: H) -> Self {
+        let abstract_syntaxes = std::iter::once(uids::VERIFICATION)
+            .chain(storage_sop_classes())
+            .map(Cow::from)
+            .collect();
+        StoreScp {
+            handler,
+            ae_title: "STORE-SCP".into(),
+            strict_ae_title: false,
+            abstract_syntaxes,
+            max_pdu_length: None,
+            read_timeout: None,
+        }
+    }
+
+    /// Define the application entity title of this node.
+    ///
+    /// The default is `STORE-SCP`.
+    pub fn ae_title<T>(mut self, ae_title: T) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.ae_title = ae_title.into();
+        self
+    }
+
+    /// Only accept associations in which
+    /// the called AE title matches this node's AE title.
+    ///
+    /// The default is to accept any association.
+    pub fn accept_called_ae_title(mut self) -> Self {
+        self.strict_ae_title = true;
+        self
+    }
+
+    /// Replace the list of accepted abstract syntaxes.
+    pub fn with_abstract_syntaxes<I, T>(mut self, abstract_syntaxes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<Cow<'a, str>>,
+    {
+        self.abstract_syntaxes = abstract_syntaxes.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Override the maximum PDU length that this node admits.
+    pub fn max_pdu_length(mut self, value: u32) -> Self {
+        self.max_pdu_length = Some(value);
+        self
+    }
+
+    /// Set the maximum time to wait for incoming data
+    /// before the association is dropped.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Retrieve the storage handler.
+    pub fn handler(&self) -> &H {
+        &self.handler
+    }
+
+    /// Retrieve the storage handler for modification.
+    pub fn handler_mut(&mut self) -> &mut H {
+        &mut self.handler
+    }
+
+    /// Take the storage handler out of the SCP.
+    pub fn into_handler(self) -> H {
+        self.handler
+    }
+
+    /// Establish an association with an incoming connection
+    /// and serve it until it is released.
+    pub fn handle(&mut self, stream: TcpStream) -> Result<()> {
+        stream
+            .set_read_timeout(self.read_timeout)
+            .context(SetTimeoutSnafu)?;
+
+        let options = self.association_options();
+        let mut association = if self.strict_ae_title {
+            options.accept_called_ae_title().establish(stream)
+        } else {
+            options.establish(stream)
+        }
+        .context(EstablishSnafu)?;
+
+        serve_store(&mut association, &mut self.handler)
+    }
+
+    /// Establish an association with an incoming connection asynchronously
+    /// and serve it until it is released.
+    ///
+    /// The read timeout is not applied to asynchronous associations.
+    #[cfg(feature = "async")]
+    pub async fn handle_async(&mut self, stream: tokio::net::TcpStream) -> Result<()> {
+        handle_connection_async(
+            self.association_options(),
+            self.strict_ae_title,
+            stream,
+            &mut self.handler,
+        )
+        .await
+    }
+
+    /// Prepare the options for accepting an association.
+    fn association_options(&self) -> ServerAssociationOptions<'a, AcceptAny> {
+        let mut options = ServerAssociationOptions::new().ae_title(self.ae_title.clone());
+        for abstract_syntax in &self.abstract_syntaxes {
+            options = options.with_abstract_syntax(abstract_syntax.clone());
+        }
+        if let Some(max_pdu_length) = self.max_pdu_length {
+            options = options.max_pdu_length(max_pdu_length);
+        }
+        options
+    }
+
+    /// Listen for incoming connections on the given address,
+    /// serving each association in turn.
+    ///
+    /// Failures within an association are logged
+    /// and do not interrupt the service.
+    /// This function only returns if the listener fails.
+    pub fn listen<A>(&mut self, address: A) -> Result<()>
+    where
+        A: ToSocketAddrs,
+    {
+        let listener = TcpListener::bind(address).context(ListenSnafu)?;
+        for stream in listener.incoming() {
+            let stream = stream.context(ListenSnafu)?;
+            if let Err(e) = self.handle(stream) {
+                warn!("Association failed: {}", e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<H> StoreScp<'static, H>
+where
+    H: StorageHandler + Send + 'static,
+{
+    /// Serve incoming connections from the given listener
+    /// in a background thread, one association at a time.
+    ///
+    /// This is useful for receiving the objects
+    /// sent by another node in response to a C-MOVE request.
+    /// The service runs until [`StoreScpHandle::stop`] is called.
+    pub fn spawn(mut self, listener: TcpListener) -> Result<StoreScpHandle<H>> {
+        let local_addr = listener.local_addr().context(ListenSnafu)?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let thread = std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                match stream {
+                    Ok(stream) => {
+                        if let Err(e) = self.handle(stream) {
+                            warn!("Association failed: {}", e);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Failed to accept connection: {}", e);
+                        break;
+                    }
+                }
+            }
+            self
+        });
+
+        Ok(StoreScpHandle {
+            local_addr,
+            stop,
+            thread,
+        })
+    }
+}
+
+#[cfg(feature = "async")]
+impl<H> StoreScp<'static, H>
+where
+    H: StorageHandler + Clone + Send + 'static,
+{
+    /// Listen for incoming connections on the given address asynchronously,
+    /// serving each association concurrently in a separate task
+    /// with its own clone of the storage handler.
+    ///
+    /// Failures within an association are logged
+    /// and do not interrupt the service.
+    /// This function only returns if the listener fails.
+    /// The read timeout is not applied to asynchronous associations.
+    pub async fn listen_async<A>(&self, address: A) -> Result<()>
+    where
+        A: tokio::net::ToSocketAddrs,
+    {
+        let listener = tokio::net::TcpListener::bind(address)
+            .await
+            .context(ListenSnafu)?;
+        loop {
+            let (stream, _) = listener.accept().await.context(ListenSnafu)?;
+            let options = self.association_options();
+            let strict_ae_title = self.strict_ae_title;
+            let mut handler = self.handler.clone();
+            tokio::spawn(async move {
+                if let Err(e) =
+                    handle_connection_async(options, strict_ae_title, stream, &mut handler).await
+                {
+                    warn!("Association failed: {}", e);
+                }
+            });
+        }
+    }
+}
+
+/// Establish an association with an incoming connection asynchronously
+/// and serve it until it is released.
+#[cfg(feature = "async")]
+async fn handle_connection_async<H>(
+    options: ServerAssociationOptions<'_, AcceptAny>,
+    strict_ae_title: bool,
+    stream: tokio::net::TcpStream,
+    handler: &mut H,
+) -> Result<()>
+where
+    H: StorageHandler + ?Sized,
+{
+    let mut association = if strict_ae_title {
+        options
+            .accept_called_ae_title()
+            .establish_async(stream)
+            .await
+    } else {
+        options.establish_async(stream).await
+    }
+    .context(EstablishSnafu)?;
+
+    serve_store_async(&mut association, handler).await
+}
+
+/// A handle to a storage SCP running in a background thread,
+/// created with [`StoreScp::spawn`].
+#[derive(Debug)]
+pub struct StoreScpHandle<H> {
+    local_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<StoreScp<'static, H>>,
+}
+
+impl<H> StoreScpHandle<H> {
+    /// The socket address on which the storage SCP is listening.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop accepting connections and wait for the background thread,
+    /// returning the storage SCP with its handler.
+    ///
+    /// An association in progress is served to completion first.
+    pub fn stop(self) -> Result<StoreScp<'static, H>> {
+        self.stop.store(true, Ordering::SeqCst);
+
+        // connect to the listener so that it stops waiting for connections
+        let mut addr = self.local_addr;
+        if addr.ip().is_unspecified() {
+            match addr {
+                SocketAddr::V4(_) => addr.set_ip(Ipv4Addr::LOCALHOST.into()),
+                SocketAddr::V6(_) => addr.set_ip(Ipv6Addr::LOCALHOST.into()),
+            }
+        }
+        TcpStream::connect(addr).context(WakeListenerSnafu)?;
+
+        match self.thread.join() {
+            Ok(scp) => Ok(scp),
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{StoreOutcome, StoreScu};
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_object::FileDicomObject;
+
+    fn test_object(sop_instance_uid: &str) -> DefaultDicomObject {
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(uids::CT_IMAGE_STORAGE)
+            .media_storage_sop_instance_uid(sop_instance_uid)
+            .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+            .build()
+            .unwrap();
+        let mut object = FileDicomObject::new_empty_with_meta(meta);
+        object.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_instance_uid),
+        ));
+        object.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        object
+    }
+
+    #[test]
+    fn store_scp_with_handler() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let scp = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            let mut scp = StoreScp::new(|request: &StoreRequest, object: DefaultDicomObject| {
+                let name = object
+                    .element(tags::PATIENT_NAME)
+                    .unwrap()
+                    .to_str()
+                    .unwrap();
+                received.push((request.sop_instance_uid.clone(), name.to_string()));
+                // reject the second object
+                if received.len() == 2 {
+                    status::OUT_OF_RESOURCES
+                } else {
+                    dimse::status::SUCCESS
+                }
+            });
+            let (stream, _) = listener.accept().unwrap();
+            scp.handle(stream).unwrap();
+            received
+        });
+
+        let outcomes = StoreScu::new()
+            .with_object(test_object("1.2.888.1"))
+            .with_object(test_object("1.2.888.2"))
+            .send(addr)
+            .unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![
+                StoreOutcome {
+                    sop_instance_uid: "1.2.888.1".to_string(),
+                    status: Some(dimse::status::SUCCESS),
+                },
+                StoreOutcome {
+                    sop_instance_uid: "1.2.888.2".to_string(),
+                    status: Some(status::OUT_OF_RESOURCES),
+                },
+            ]
+        );
+        assert_eq!(
+            scp.join().unwrap(),
+            vec![
+                ("1.2.888.1".to_string(), "Doe^John".to_string()),
+                ("1.2.888.2".to_string(), "Doe^John".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_uids_are_rejected() {
+        assert!(is_valid_uid("1.2.888.1"));
+        assert!(!is_valid_uid(""));
+        assert!(!is_valid_uid("../../x"));
+        assert!(!is_valid_uid("1.2/3"));
+        assert!(!is_valid_uid(&"1.".repeat(33)));
+    }
+
+    #[test]
+    fn store_scp_rejects_hostile_uid() {
+        let root = tempfile::tempdir().unwrap();
+        let dir = root.path().join("store");
+        std::fs::create_dir(&dir).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let storage = dir.clone();
+        let scp = std::thread::spawn(move || {
+            let mut scp = StoreScp::new(DirectoryStorage::new(storage));
+            let (stream, _) = listener.accept().unwrap();
+            scp.handle(stream).unwrap();
+        });
+
+        let outcomes = StoreScu::new()
+            .with_object(test_object("../../x"))
+            .send(addr)
+            .unwrap();
+        scp.join().unwrap();
+
+        assert_eq!(
+            outcomes,
+            vec![StoreOutcome {
+                sop_instance_uid: "../../x".to_string(),
+                status: Some(status::CANNOT_UNDERSTAND),
+            }]
+        );
+        assert!(!root.path().join("x.dcm").exists());
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 0);
+
+        // the handler refuses to save it as well
+        let object = test_object("../../x");
+        assert!(matches!(
+            DirectoryStorage::new(&dir).save(&object, "../../x"),
+            Err(Error::InvalidSopInstanceUid { .. })
+        ));
+    }
+
+    #[test]
+    fn storage_sop_classes_include_common_ones() {
+        let classes: Vec<_> = storage_sop_classes().collect();
+        assert!(classes.contains(&uids::CT_IMAGE_STORAGE));
+        assert!(!classes.contains(&uids::VERIFICATION));
+    }
+}
@@ -0,0 +1,532 @@
+//! Modality worklist service module, service class user side
+//! (C-FIND with the _Modality Worklist Information Model - FIND_).
+//!
+//! A modality queries the worklist of another node,
+//! typically a RIS, for the procedure steps scheduled for it.
+//! [`WorklistQuery`] builds the query identifier
+//! with the usual matching keys and return keys,
+//! and [`find_worklist`] sends it through an association,
+//! collecting the matches as [`WorklistItem`]s.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_dictionary_std::uids;
+//! # use dicom_net::worklist::{find_worklist, WorklistQuery};
+//! # use dicom_ul::association::client::ClientAssociationOptions;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut association = ClientAssociationOptions::new()
+//!     .with_abstract_syntax(uids::MODALITY_WORKLIST_INFORMATION_MODEL_FIND)
+//!     .calling_ae_title("CT01")
+//!     .called_ae_title("RIS")
+//!     .establish("129.168.0.5:104")?;
+//! let query = WorklistQuery::new()
+//!     .scheduled_station_ae_title("CT01")
+//!     .modality("CT")
+//!     .scheduled_date("20220314");
+//! for item in find_worklist(&mut association, &query, 1)? {
+//!     println!("{:?} {:?}", item.patient_name, item.accession_number);
+//! }
+//! association.release()?;
+//! # Ok(())
+//! # }
+//! ```
+pub use crate::find::{Error, Result};
+
+use crate::find::find;
+#[cfg(feature = "async")]
+use crate::find::find_async;
+use dicom_core::value::Value;
+use dicom_core::{DataElement, Length, PrimitiveValue, Tag, VR};
+use dicom_dictionary_std::{tags, uids};
+use dicom_object::mem::InMemElement;
+use dicom_object::InMemDicomObject;
+use dicom_ul::association::client::ClientAssociation;
+#[cfg(feature = "async")]
+use dicom_ul::association::AsyncClientAssociation;
+
+/// A builder for a modality worklist query identifier.
+///
+/// Each matching key left undefined is a universal match.
+/// Values follow the matching rules of the query service,
+/// so that person names and textual attributes may contain
+/// the wildcards `*` and `?`,
+/// and dates and times may be ranges such as `20220301-20220331`.
+///
+/// The identifier always asks for the attributes
+/// collected in [`WorklistItem`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WorklistQuery {
+    patient_name: Option<String>,
+    patient_id: Option<String>,
+    accession_number: Option<String>,
+    requested_procedure_id: Option<String>,
+    scheduled_station_ae_title: Option<String>,
+    scheduled_station_name: Option<String>,
+    scheduled_date: Option<String>,
+    scheduled_time: Option<String>,
+    modality: Option<String>,
+    scheduled_performing_physician_name: Option<String>,
+}
+
+impl WorklistQuery {
+    /// Create a query matching all scheduled procedure steps.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match the patient's name.
+    pub fn patient_name(mut self, value: impl Into<String>) -> Self {
+        self.patient_name = Some(value.into());
+        self
+    }
+
+    /// Match the patient ID.
+    pub fn patient_id(mut self, value: impl Into<String>) -> Self {
+        self.patient_id = Some(value.into());
+        self
+    }
+
+    /// Match the accession number.
+    pub fn accession_number(mut self, value: impl Into<String>) -> Self {
+        self.accession_number = Some(value.into());
+        self
+    }
+
+    /// Match the requested procedure ID.
+    pub fn requested_procedure_id(mut self, value: impl Into<String>) -> Self {
+        self.requested_procedure_id = Some(value.into());
+        self
+    }
+
+    /// Match the AE title of the station
+    /// where the procedure step is scheduled,
+    /// usually the AE title of this node.
+    pub fn scheduled_station_ae_title(mut self, value: impl Into<String>) -> Self {
+        self.scheduled_station_ae_title = Some(value.into());
+        self
+    }
+
+    /// Match the name of the station
+    /// where the procedure step is scheduled.
+    pub fn scheduled_station_name(mut self, value: impl Into<String>) -> Self {
+        self.scheduled_station_name = Some(value.into());
+        self
+    }
+
+    /// Match the start date of the procedure step,
+    /// either a single date (`YYYYMMDD`) or a range.
+    pub fn scheduled_date(mut self, value: impl Into<String>) -> Self {
+        self.scheduled_date = Some(value.into());
+        self
+    }
+
+    /// Match the start dates of the procedure step
+    /// between the two given dates (`YYYYMMDD`), inclusive.
+    pub fn scheduled_date_range(self, from: &str, to: &str) -> Self {
+        self.scheduled_date(format!("{}-{}", from, to))
+    }
+
+    /// Match the start time of the procedure step,
+    /// either a single time (`HHMMSS`) or a range.
+    pub fn scheduled_time(mut self, value: impl Into<String>) -> Self {
+        self.scheduled_time = Some(value.into());
+        self
+    }
+
+    /// Match the modality of the procedure step.
+    pub fn modality(mut self, value: impl Into<String>) -> Self {
+        self.modality = Some(value.into());
+        self
+    }
+
+    /// Match the name of the physician scheduled to perform the procedure step.
+    pub fn scheduled_performing_physician_name(mut self, value: impl Into<String>) -> Self {
+        self.scheduled_performing_physician_name = Some(value.into());
+        self
+    }
+
+    /// Build the query identifier.
+    pub fn to_identifier(&self) -> InMemDicomObject {
+        let step = InMemDicomObject::from_element_iter([
+            key(
+                tags::SCHEDULED_STATION_AE_TITLE,
+                VR::AE,
+                &self.scheduled_station_ae_title,
+            ),
+            key(
+                tags::SCHEDULED_PROCEDURE_STEP_START_DATE,
+                VR::DA,
+                &self.scheduled_date,
+            ),
+            key(
+                tags::SCHEDULED_PROCEDURE_STEP_START_TIME,
+                VR::TM,
+                &self.scheduled_time,
+            ),
+            key(tags::MODALITY, VR::CS, &self.modality),
+            key(
+                tags::SCHEDULED_PERFORMING_PHYSICIAN_NAME,
+                VR::PN,
+                &self.scheduled_performing_physician_name,
+            ),
+            key(tags::SCHEDULED_PROCEDURE_STEP_DESCRIPTION, VR::LO, &None),
+            key(
+                tags::SCHEDULED_STATION_NAME,
+                VR::SH,
+                &self.scheduled_station_name,
+            ),
+            key(tags::SCHEDULED_PROCEDURE_STEP_ID, VR::SH, &None),
+        ]);
+
+        InMemDicomObject::from_element_iter([
+            key(tags::ACCESSION_NUMBER, VR::SH, &self.accession_number),
+            key(tags::REFERRING_PHYSICIAN_NAME, VR::PN, &None),
+            key(tags::PATIENT_NAME, VR::PN, &self.patient_name),
+            key(tags::PATIENT_ID, VR::LO, &self.patient_id),
+            key(tags::PATIENT_BIRTH_DATE, VR::DA, &None),
+            key(tags::PATIENT_SEX, VR::CS, &None),
+            key(tags::STUDY_INSTANCE_UID, VR::UI, &None),
+            key(tags::REQUESTED_PROCEDURE_DESCRIPTION, VR::LO, &None),
+            DataElement::new(
+                tags::SCHEDULED_PROCEDURE_STEP_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![step].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+            key(
+                tags::REQUESTED_PROCEDURE_ID,
+                VR::SH,
+                &self.requested_procedure_id,
+            ),
+        ])
+    }
+}
+
+/// Create a matching key, empty if no value is given.
+fn key(tag: Tag, vr: VR, value: &Option<String>) -> InMemElement {
+    let value = match value {
+        Some(value) => PrimitiveValue::from(value.as_str()),
+        None => PrimitiveValue::Empty,
+    };
+    DataElement::new(tag, vr, value)
+}
+
+/// A procedure step scheduled in a worklist item.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ScheduledProcedureStep {
+    /// Scheduled Procedure Step ID
+    pub id: Option<String>,
+    /// Scheduled Procedure Step Description
+    pub description: Option<String>,
+    /// Scheduled Station AE Title
+    pub station_ae_title: Option<String>,
+    /// Scheduled Station Name
+    pub station_name: Option<String>,
+    /// Scheduled Procedure Step Start Date
+    pub start_date: Option<String>,
+    /// Scheduled Procedure Step Start Time
+    pub start_time: Option<String>,
+    /// Modality
+    pub modality: Option<String>,
+    /// Scheduled Performing Physician's Name
+    pub performing_physician_name: Option<String>,
+}
+
+impl ScheduledProcedureStep {
+    /// Collect the scheduled procedure step attributes of an item
+    /// of the _Scheduled Procedure Step Sequence_.
+    pub fn from_item(item: &InMemDicomObject) -> Self {
+        ScheduledProcedureStep {
+            id: string(item, tags::SCHEDULED_PROCEDURE_STEP_ID),
+            description: string(item, tags::SCHEDULED_PROCEDURE_STEP_DESCRIPTION),
+            station_ae_title: string(item, tags::SCHEDULED_STATION_AE_TITLE),
+            station_name: string(item, tags::SCHEDULED_STATION_NAME),
+            start_date: string(item, tags::SCHEDULED_PROCEDURE_STEP_START_DATE),
+            start_time: string(item, tags::SCHEDULED_PROCEDURE_STEP_START_TIME),
+            modality: string(item, tags::MODALITY),
+            performing_physician_name: string(item, tags::SCHEDULED_PERFORMING_PHYSICIAN_NAME),
+        }
+    }
+}
+
+/// A worklist item matching a query.
+///
+/// Attributes which are missing or empty in the response are `None`.
+/// The full response identifier is kept in `identifier`
+/// for attributes not collected here.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorklistItem {
+    /// Patient's Name
+    pub patient_name: Option<String>,
+    /// Patient ID
+    pub patient_id: Option<String>,
+    /// Patient's Birth Date
+    pub patient_birth_date: Option<String>,
+    /// Patient's Sex
+    pub patient_sex: Option<String>,
+    /// Accession Number
+    pub accession_number: Option<String>,
+    /// Referring Physician's Name
+    pub referring_physician_name: Option<String>,
+    /// Study Instance UID
+    pub study_instance_uid: Option<String>,
+    /// Requested Procedure ID
+    pub requested_procedure_id: Option<String>,
+    /// Requested Procedure Description
+    pub requested_procedure_description: Option<String>,
+    /// The items of the Scheduled Procedure Step Sequence
+    pub scheduled_procedure_steps: Vec<ScheduledProcedureStep>,
+    /// The response identifier
+    pub identifier: InMemDicomObject,
+}
+
+impl WorklistItem {
+    /// Collect the worklist attributes of a response identifier.
+    pub fn from_identifier(identifier: InMemDicomObject) -> Self {
+        let scheduled_procedure_steps = identifier
+            .element_opt(tags::SCHEDULED_PROCEDURE_STEP_SEQUENCE)
+            .ok()
+            .flatten()
+            .and_then(|e| e.items())
+            .map(|items| {
+                items
+                    .iter()
+                    .map(ScheduledProcedureStep::from_item)
+                    .collect()
+            })
+            .unwrap_or_default();
+        WorklistItem {
+            patient_name: string(&identifier, tags::PATIENT_NAME),
+            patient_id: string(&identifier, tags::PATIENT_ID),
+            patient_birth_date: string(&identifier, tags::PATIENT_BIRTH_DATE),
+            patient_sex: string(&identifier, tags::PATIENT_SEX),
+            accession_number: string(&identifier, tags::ACCESSION_NUMBER),
+            referring_physician_name: string(&identifier, tags::REFERRING_PHYSICIAN_NAME),
+            study_instance_uid: string(&identifier, tags::STUDY_INSTANCE_UID),
+            requested_procedure_id: string(&identifier, tags::REQUESTED_PROCEDURE_ID),
+            requested_procedure_description: string(
+                &identifier,
+                tags::REQUESTED_PROCEDURE_DESCRIPTION,
+            ),
+            scheduled_procedure_steps,
+            identifier,
+        }
+    }
+}
+
+/// Retrieve a string attribute without padding,
+/// or `None` if it is missing or empty.
+fn string(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+    let value = obj.element_opt(tag).ok().flatten()?.to_str().ok()?;
+    let value = value.trim_end_matches(|c: char| c == ' ' || c == '\0');
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+/// Query the worklist of the other node with a C-FIND request,
+/// and wait until all matches are received.
+///
+/// The _Modality Worklist Information Model - FIND_
+/// must have been accepted in the association.
+pub fn find_worklist(
+    association: &mut ClientAssociation,
+    query: &WorklistQuery,
+    message_id: u16,
+) -> Result<Vec<WorklistItem>> {
+    let identifiers = find(
+        association,
+        uids::MODALITY_WORKLIST_INFORMATION_MODEL_FIND,
+        &query.to_identifier(),
+        message_id,
+    )?;
+    Ok(identifiers
+        .into_iter()
+        .map(WorklistItem::from_identifier)
+        .collect())
+}
+
+/// Query the worklist of the other node asynchronously
+/// with a C-FIND request,
+/// and wait until all matches are received.
+///
+/// See [`find_worklist`] for details.
+#[cfg(feature = "async")]
+pub async fn find_worklist_async(
+    association: &mut AsyncClientAssociation,
+    query: &WorklistQuery,
+    message_id: u16,
+) -> Result<Vec<WorklistItem>> {
+    let identifiers = find_async(
+        association,
+        uids::MODALITY_WORKLIST_INFORMATION_MODEL_FIND,
+        &query.to_identifier(),
+        message_id,
+    )
+    .await?;
+    Ok(identifiers
+        .into_iter()
+        .map(WorklistItem::from_identifier)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::CommandSet;
+    use crate::dimse::{
+        command_field, receive_event, receive_message, send_message, status, Event,
+    };
+    use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+    use dicom_object::matching;
+    use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+    use dicom_ul::association::client::ClientAssociationOptions;
+    use dicom_ul::association::ServerAssociationOptions;
+    use dicom_ul::pdu::Pdu;
+    use std::net::TcpListener;
+
+    fn entry(patient_name: &str, accession_number: &str, modality: &str) -> InMemDicomObject {
+        let step = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SCHEDULED_STATION_AE_TITLE,
+                VR::AE,
+                PrimitiveValue::from("CT01"),
+            ),
+            DataElement::new(
+                tags::SCHEDULED_PROCEDURE_STEP_START_DATE,
+                VR::DA,
+                PrimitiveValue::from("20220314"),
+            ),
+            DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from(modality)),
+            DataElement::new(
+                tags::SCHEDULED_PROCEDURE_STEP_ID,
+                VR::SH,
+                PrimitiveValue::from("SPS1"),
+            ),
+        ]);
+        InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::ACCESSION_NUMBER,
+                VR::SH,
+                PrimitiveValue::from(accession_number),
+            ),
+            DataElement::new(
+                tags::PATIENT_NAME,
+                VR::PN,
+                PrimitiveValue::from(patient_name),
+            ),
+            DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from("P1")),
+            DataElement::new(
+                tags::SCHEDULED_PROCEDURE_STEP_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![step].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn query_identifier_has_return_keys() {
+        let identifier = WorklistQuery::new()
+            .patient_name("Doe^*")
+            .scheduled_date_range("20220301", "20220331")
+            .to_identifier();
+        assert_eq!(
+            identifier
+                .element(tags::PATIENT_NAME)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "Doe^*"
+        );
+        assert!(identifier.element(tags::STUDY_INSTANCE_UID).is_ok());
+        let items = identifier
+            .element(tags::SCHEDULED_PROCEDURE_STEP_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(
+            string(&items[0], tags::SCHEDULED_PROCEDURE_STEP_START_DATE).as_deref(),
+            Some("20220301-20220331")
+        );
+        assert_eq!(string(&items[0], tags::MODALITY), None);
+    }
+
+    #[test]
+    fn find_worklist_items() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let scp = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut association = ServerAssociationOptions::new()
+                .with_abstract_syntax(uids::MODALITY_WORKLIST_INFORMATION_MODEL_FIND)
+                .establish(stream)
+                .unwrap();
+            let request = receive_message(&mut association).unwrap();
+            assert_eq!(request.command_field().unwrap(), command_field::C_FIND_RQ);
+            let pc = association.presentation_contexts()[0].clone();
+            let ts = TransferSyntaxRegistry
+                .get(pc.transfer_syntax.trim_end_matches('\0'))
+                .unwrap();
+            let identifier =
+                InMemDicomObject::read_dataset_with_ts(request.data.as_deref().unwrap(), ts)
+                    .unwrap();
+
+            let request_command = CommandSet::from_object(&request.command).unwrap();
+            let entries = [
+                entry("Doe^John", "A1", "CT"),
+                entry("Doe^Jane", "A2", "MR"),
+                entry("Roe^Richard", "A3", "CT"),
+            ];
+            for entry in entries.iter().filter(|e| matching::matches(&identifier, e)) {
+                let mut data = Vec::new();
+                matching::response(&identifier, entry)
+                    .write_dataset_with_ts(&mut data, ts)
+                    .unwrap();
+                let response = request_command
+                    .response(status::PENDING)
+                    .with_data_set(true)
+                    .to_object();
+                send_message(&mut association, pc.id, &response, Some(&data)).unwrap();
+            }
+            let response = request_command.response(status::SUCCESS).to_object();
+            send_message(&mut association, pc.id, &response, None).unwrap();
+
+            assert!(matches!(
+                receive_event(&mut association).unwrap(),
+                Event::ReleaseRequested
+            ));
+            association.send(&Pdu::ReleaseRP).unwrap();
+        });
+
+        let mut association = ClientAssociationOptions::new()
+            .with_abstract_syntax(uids::MODALITY_WORKLIST_INFORMATION_MODEL_FIND)
+            .establish(addr)
+            .unwrap();
+        let query = WorklistQuery::new()
+            .patient_name("Doe^*")
+            .scheduled_station_ae_title("CT01")
+            .modality("CT");
+        let items = find_worklist(&mut association, &query, 5).unwrap();
+        association.release().unwrap();
+        scp.join().unwrap();
+
+        assert_eq!(items.len(), 1);
+        let item = &items[0];
+        assert_eq!(item.patient_name.as_deref(), Some("Doe^John"));
+        assert_eq!(item.accession_number.as_deref(), Some("A1"));
+        assert_eq!(item.study_instance_uid, None);
+        assert_eq!(item.scheduled_procedure_steps.len(), 1);
+        let step = &item.scheduled_procedure_steps[0];
+        assert_eq!(step.id.as_deref(), Some("SPS1"));
+        assert_eq!(step.modality.as_deref(), Some("CT"));
+        assert_eq!(step.start_date.as_deref(), Some("20220314"));
+    }
+}
@@ -0,0 +1,102 @@
+//! Benchmark for reading a large data set
+//! into an in-memory object versus an arena.
+//!
+//! The data set is generated on the fly,
+//! and resembles a structured report
+//! with thousands of small content items.
+use bytes::Bytes;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dicom_core::value::{Value, C};
+use dicom_core::{DataElement, Length, PrimitiveValue, Tag, VR};
+use dicom_object::{FileMetaTableBuilder, InMemDicomObject, OpenFileOptions};
+
+/// Create the encoded file, without the preamble,
+/// with the given number of content items.
+fn test_file(count: usize) -> Bytes {
+    let sop_uid = "2.25.92840173502738617024";
+    let code = |value: String| {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(Tag(0x0008, 0x0100), VR::SH, PrimitiveValue::from(value)),
+            DataElement::new(Tag(0x0008, 0x0102), VR::SH, PrimitiveValue::from("99BENCH")),
+            DataElement::new(Tag(0x0008, 0x0104), VR::LO, PrimitiveValue::from("Concept")),
+        ])
+    };
+    let items: C<_> = (0..count)
+        .map(|i| {
+            InMemDicomObject::from_element_iter([
+                DataElement::new(
+                    Tag(0x0040, 0xA010),
+                    VR::CS,
+                    PrimitiveValue::from("CONTAINS"),
+                ),
+                DataElement::new(Tag(0x0040, 0xA040), VR::CS, PrimitiveValue::from("TEXT")),
+                DataElement::new(
+                    Tag(0x0040, 0xA043),
+                    VR::SQ,
+                    Value::Sequence {
+                        items: vec![code(format!("C{}", i))].into(),
+                        size: Length::UNDEFINED,
+                    },
+                ),
+                DataElement::new(
+                    Tag(0x0040, 0xA160),
+                    VR::UT,
+                    PrimitiveValue::from(format!("Finding #{}", i)),
+                ),
+            ])
+        })
+        .collect();
+
+    let obj = InMemDicomObject::from_element_iter([
+        DataElement::new(Tag(0x0008, 0x0018), VR::UI, PrimitiveValue::from(sop_uid)),
+        DataElement::new(Tag(0x0008, 0x0060), VR::CS, PrimitiveValue::from("SR")),
+        DataElement::new(
+            Tag(0x0040, 0xA730),
+            VR::SQ,
+            Value::Sequence {
+                items,
+                size: Length::UNDEFINED,
+            },
+        ),
+    ]);
+
+    let mut data = Vec::new();
+    obj.with_exact_meta(
+        FileMetaTableBuilder::default()
+            // Explicit VR Little Endian
+            .transfer_syntax("1.2.840.10008.1.2.1")
+            // Comprehensive SR Storage
+            .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.88.33")
+            .media_storage_sop_instance_uid(sop_uid)
+            .build()
+            .unwrap(),
+    )
+    .write_all(&mut data)
+    .unwrap();
+    // skip the preamble
+    Bytes::from(data).slice(128..)
+}
+
+fn bench_read(c: &mut Criterion) {
+    let mut group = c.benchmark_group("read_report");
+    for count in [1_000, 10_000] {
+        let data = test_file(count);
+        group.throughput(Throughput::Bytes(data.len() as u64));
+        group.bench_with_input(BenchmarkId::new("in_mem", count), &data, |b, data| {
+            b.iter(|| black_box(OpenFileOptions::new().from_bytes(data.clone()).unwrap()))
+        });
+        group.bench_with_input(BenchmarkId::new("arena", count), &data, |b, data| {
+            b.iter(|| {
+                black_box(
+                    OpenFileOptions::new()
+                        .from_bytes_arena(data.clone())
+                        .unwrap(),
+                )
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_read);
+criterion_main!(benches);
@@ -0,0 +1,77 @@
+//! Benchmark for reading DICOM files with different read buffer capacities.
+//!
+//! The file used is generated on the fly
+//! and contains many small data elements,
+//! so that the cost of reading element headers dominates.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dicom_core::{DataElement, PrimitiveValue, Tag, VR};
+use dicom_object::{FileMetaTableBuilder, InMemDicomObject, OpenFileOptions};
+use std::path::Path;
+
+/// Write a file with many short private attributes to the given path.
+fn write_test_file(path: &Path) {
+    let sop_uid = "2.25.92840173502738617024";
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        Tag(0x0008, 0x0018),
+        VR::UI,
+        PrimitiveValue::from(sop_uid),
+    ));
+    obj.put(DataElement::new(
+        Tag(0x0009, 0x0010),
+        VR::LO,
+        PrimitiveValue::from("BENCH"),
+    ));
+    for i in 0..0xF0_u16 {
+        obj.put(DataElement::new(
+            Tag(0x0009, 0x1000 + i),
+            VR::SH,
+            PrimitiveValue::from(format!("V{:04}", i)),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0009, 0x1100 + i),
+            VR::US,
+            PrimitiveValue::from(i),
+        ));
+    }
+
+    obj.with_exact_meta(
+        FileMetaTableBuilder::default()
+            // Explicit VR Little Endian
+            .transfer_syntax("1.2.840.10008.1.2.1")
+            // Secondary Capture Image Storage
+            .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+            .media_storage_sop_instance_uid(sop_uid)
+            .build()
+            .unwrap(),
+    )
+    .write_to_file(path)
+    .unwrap();
+}
+
+fn bench_read_buffer(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bench.dcm");
+    write_test_file(&path);
+
+    let mut group = c.benchmark_group("open_file");
+    for capacity in [0, 512, 8 * 1024, 64 * 1024] {
+        group.bench_with_input(
+            BenchmarkId::new("read_buffer_capacity", capacity),
+            &capacity,
+            |b, &capacity| {
+                b.iter(|| {
+                    let obj = OpenFileOptions::new()
+                        .read_buffer_capacity(capacity)
+                        .open_file(&path)
+                        .unwrap();
+                    black_box(obj)
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_read_buffer);
+criterion_main!(benches);
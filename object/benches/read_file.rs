@@ -0,0 +1,48 @@
+//! Benchmarks for reading whole DICOM files
+//! and converting them to the DICOM JSON model.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use dicom_object::open_file;
+use dicom_parser::dataset::json::JsonDataSetWriter;
+use dicom_parser::dataset::IntoTokens;
+
+/// Representative test files, by description.
+const FILES: [(&str, &str); 3] = [
+    // a small single-frame CT slice
+    ("ct_small", "pydicom/CT_small.dcm"),
+    // an enhanced multi-frame MR image with functional groups
+    ("enhanced_mr", "pydicom/emri_small.dcm"),
+    // a structured report with deeply nested sequences
+    ("sr", "pydicom/test-SR.dcm"),
+];
+
+fn bench_open_file(c: &mut Criterion) {
+    let mut group = c.benchmark_group("open_file");
+    for (name, file) in FILES {
+        let path = dicom_test_files::path(file).unwrap();
+        let len = std::fs::metadata(&path).unwrap().len();
+        group.throughput(Throughput::Bytes(len));
+        group.bench_with_input(BenchmarkId::from_parameter(name), &path, |b, path| {
+            b.iter(|| black_box(open_file(path).unwrap()))
+        });
+    }
+    group.finish();
+}
+
+fn bench_to_json(c: &mut Criterion) {
+    let mut group = c.benchmark_group("to_json");
+    for (name, file) in FILES {
+        let path = dicom_test_files::path(file).unwrap();
+        let obj = open_file(path).unwrap().into_inner();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &obj, |b, obj| {
+            b.iter(|| {
+                let mut writer = JsonDataSetWriter::new(std::io::sink());
+                writer.write_sequence(obj.into_tokens()).unwrap();
+                black_box(writer.finish().unwrap())
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_open_file, bench_to_json);
+criterion_main!(benches);
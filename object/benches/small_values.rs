@@ -0,0 +1,101 @@
+//! Benchmark for building and reading objects
+//! made of data elements with a small value multiplicity.
+//!
+//! Multi-valued primitive values keep up to two elements inline.
+//! The `heap` variants force the same values onto the heap,
+//! serving as a baseline for the cost of one allocation per element.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dicom_core::value::C;
+use dicom_core::{DataElement, PrimitiveValue, Tag, VR};
+use dicom_object::{FileMetaTableBuilder, InMemDicomObject, OpenFileOptions};
+use std::path::Path;
+
+/// The number of attributes of each kind in the generated object.
+const COUNT: u16 = 0xF0;
+
+/// Create a primitive value with the given multiplicity,
+/// either inline or in a separate heap allocation.
+fn f64_value(vm: usize, inline: bool) -> PrimitiveValue {
+    let values = (0..vm).map(|i| i as f64 * 0.5);
+    if inline {
+        PrimitiveValue::F64(values.collect())
+    } else {
+        PrimitiveValue::F64(C::from_vec(values.collect::<Vec<_>>()))
+    }
+}
+
+/// Create an object with many private attributes
+/// with a value multiplicity of 1 to 3.
+fn build_object(inline: bool) -> InMemDicomObject {
+    let mut obj = InMemDicomObject::new_empty();
+    obj.put(DataElement::new(
+        Tag(0x0009, 0x0010),
+        VR::LO,
+        PrimitiveValue::from("BENCH"),
+    ));
+    for i in 0..COUNT {
+        let vm = 1 + usize::from(i % 3);
+        obj.put(DataElement::new(
+            Tag(0x0009, 0x1000 + i),
+            VR::FD,
+            f64_value(vm, inline),
+        ));
+        let values = (0..vm as u16).map(|v| v + i);
+        let value = if inline {
+            PrimitiveValue::U16(values.collect())
+        } else {
+            PrimitiveValue::U16(C::from_vec(values.collect::<Vec<_>>()))
+        };
+        obj.put(DataElement::new(Tag(0x0009, 0x1100 + i), VR::US, value));
+    }
+    obj
+}
+
+/// Write the object built by `build_object` to the given path.
+fn write_test_file(path: &Path) {
+    let sop_uid = "2.25.92840173502738617024";
+    let mut obj = build_object(true);
+    obj.put(DataElement::new(
+        Tag(0x0008, 0x0018),
+        VR::UI,
+        PrimitiveValue::from(sop_uid),
+    ));
+
+    obj.with_exact_meta(
+        FileMetaTableBuilder::default()
+            // Explicit VR Little Endian
+            .transfer_syntax("1.2.840.10008.1.2.1")
+            // Secondary Capture Image Storage
+            .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+            .media_storage_sop_instance_uid(sop_uid)
+            .build()
+            .unwrap(),
+    )
+    .write_to_file(path)
+    .unwrap();
+}
+
+fn bench_build_object(c: &mut Criterion) {
+    let mut group = c.benchmark_group("small_values");
+    for (name, inline) in [("inline", true), ("heap", false)] {
+        group.bench_with_input(
+            BenchmarkId::new("build_object", name),
+            &inline,
+            |b, &inline| b.iter(|| black_box(build_object(inline))),
+        );
+    }
+    group.finish();
+}
+
+fn bench_read_object(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("bench.dcm");
+    write_test_file(&path);
+
+    c.bench_function("small_values/open_file", |b| {
+        b.iter(|| black_box(OpenFileOptions::new().open_file(&path).unwrap()))
+    });
+}
+
+criterion_group!(benches, bench_build_object, bench_read_object);
+criterion_main!(benches);
@@ -1,21 +1,27 @@
 #![no_main]
+use dicom_object::OpenFileOptions;
 use libfuzzer_sys::fuzz_target;
 use std::error::Error;
 
+/// Keep allocations below the fuzzer's allocation limit,
+/// regardless of the lengths declared in the input.
+const MAX_VALUE_LENGTH: u32 = 16 * 1024 * 1024;
+
 fuzz_target!(|data: &[u8]| {
     let _ = fuzz(data);
 });
 
 fn fuzz(data: &[u8]) -> Result<(), Box<dyn Error>> {
     // deserialize random bytes
-    let obj = dicom_object::from_reader(data)?;
+    let obj = options().from_reader(data)?;
 
     // serialize object back to bytes
     let mut bytes = Vec::new();
     obj.write_all(&mut bytes)?;
 
     // deserialize back to object
-    let obj2 = dicom_object::from_reader(bytes.as_slice())
+    let obj2 = options()
+        .from_reader(bytes.as_slice())
         .expect("serialized object should always deserialize");
 
     // assert equivalence
@@ -23,3 +29,9 @@ fn fuzz(data: &[u8]) -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
+
+fn options() -> OpenFileOptions {
+    OpenFileOptions::new()
+        .max_value_length(MAX_VALUE_LENGTH)
+        .allocation_budget(2 * u64::from(MAX_VALUE_LENGTH))
+}
@@ -0,0 +1,1244 @@
+//! De-identification of DICOM objects.
+//!
+//! This module implements the
+//! _Basic Application Level Confidentiality Profile_
+//! described in the standard, part 15, annex E.
+//! An [`Anonymizer`] holds the action to take on each attribute
+//! (see [`Action`]),
+//! along with a table of UID replacements,
+//! so that several objects of the same study or series
+//! can be de-identified consistently.
+//!
+//! The retention options of the profile
+//! regarding dates, descriptors and UIDs
+//! can be enabled on the anonymizer,
+//! and the action for any attribute can be overridden,
+//! either one tag at a time or for a whole [`TagSet`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::anonymize::{Action, Anonymizer};
+//! use dicom_object::open_file;
+//! use dicom_dictionary_std::tags;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut anonymizer = Anonymizer::new()
+//!     .retain_dates(true)
+//!     .with_action(tags::PATIENT_ID, Action::Hash);
+//!
+//! for path in ["ct_0001.dcm", "ct_0002.dcm"] {
+//!     let mut obj = open_file(path)?;
+//!     anonymizer.anonymize_file(&mut obj);
+//!     obj.write_to_file(format!("anon_{}", path))?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::{BTreeMap, HashMap};
+
+use dicom_core::dictionary::{DataDictionary, TagSet};
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+use crate::mem::InMemElement;
+use crate::multiframe::new_uid;
+use crate::{FileDicomObject, InMemDicomObject};
+
+/// The value of _De-identification Method_
+/// recorded in de-identified objects.
+pub const DEIDENTIFICATION_METHOD: &str = "DICOM-rs Basic Profile";
+
+/// An action to apply to a data element during de-identification.
+///
+/// Each variant is named after the respective action code
+/// in the standard, part 15, table E.1-1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Keep the element as is (K).
+    ///
+    /// The items of sequences are still de-identified.
+    Keep,
+    /// Remove the element (X).
+    Remove,
+    /// Replace the value with an empty value (Z).
+    Empty,
+    /// Replace the value with a dummy value
+    /// consistent with the value representation (D).
+    Dummy,
+    /// Replace each UID with a new one,
+    /// consistently across all objects
+    /// processed by the same anonymizer (U).
+    RemapUid,
+    /// Replace the value with a hash of the original value.
+    ///
+    /// This is not an action of the basic profile,
+    /// but is useful to keep identifiers distinguishable
+    /// without disclosing their original value.
+    /// Values which are not textual are replaced with a dummy value.
+    Hash,
+}
+
+/// The role of an attribute in the basic profile,
+/// used for selecting the retention options which apply to it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Category {
+    /// Identifying information with no retention option
+    Identifying,
+    /// Dates and times (Retain Longitudinal Temporal Information option)
+    Temporal,
+    /// Free text descriptions (Retain Descriptors option)
+    Descriptor,
+    /// Unique identifiers (Retain UIDs option)
+    Uid,
+}
+
+/// The attributes of the basic profile,
+/// as in the standard, part 15, table E.1-1,
+/// sorted by tag.
+///
+/// Where the table gives a compound action code
+/// depending on the type of the attribute in the IOD,
+/// the action which keeps the object valid is taken:
+/// dummy values over empty values over removal (`X/Z/D`, `Z/D`, `X/D`),
+/// and empty values over removal (`X/Z`).
+/// Sequences whose UIDs are to be replaced (`X/Z/U*`)
+/// are kept, so that the UIDs in their items are replaced.
+#[rustfmt::skip]
+#[allow(deprecated)]
+const BASIC_PROFILE: &[(Tag, Action, Category)] = &[
+    (tags::AFFECTED_SOP_INSTANCE_UID, Action::Remove, Category::Identifying),
+    (tags::REQUESTED_SOP_INSTANCE_UID, Action::RemapUid, Category::Uid),
+    (tags::REFERENCED_SOP_INSTANCE_UID_IN_FILE, Action::RemapUid, Category::Uid),
+    (tags::INSTANCE_CREATION_DATE, Action::Dummy, Category::Temporal),
+    (tags::INSTANCE_CREATION_TIME, Action::Dummy, Category::Temporal),
+    (tags::INSTANCE_CREATOR_UID, Action::RemapUid, Category::Uid),
+    (tags::INSTANCE_COERCION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::SOP_INSTANCE_UID, Action::RemapUid, Category::Uid),
+    (tags::STUDY_DATE, Action::Empty, Category::Temporal),
+    (tags::SERIES_DATE, Action::Dummy, Category::Temporal),
+    (tags::ACQUISITION_DATE, Action::Empty, Category::Temporal),
+    (tags::CONTENT_DATE, Action::Dummy, Category::Temporal),
+    (tags::OVERLAY_DATE, Action::Remove, Category::Temporal),
+    (tags::CURVE_DATE, Action::Remove, Category::Temporal),
+    (tags::ACQUISITION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::STUDY_TIME, Action::Empty, Category::Temporal),
+    (tags::SERIES_TIME, Action::Dummy, Category::Temporal),
+    (tags::ACQUISITION_TIME, Action::Empty, Category::Temporal),
+    (tags::CONTENT_TIME, Action::Dummy, Category::Temporal),
+    (tags::OVERLAY_TIME, Action::Remove, Category::Temporal),
+    (tags::CURVE_TIME, Action::Remove, Category::Temporal),
+    (tags::ACCESSION_NUMBER, Action::Empty, Category::Identifying),
+    (tags::FAILED_SOP_INSTANCE_UID_LIST, Action::RemapUid, Category::Uid),
+    (tags::INSTITUTION_NAME, Action::Dummy, Category::Identifying),
+    (tags::INSTITUTION_ADDRESS, Action::Remove, Category::Identifying),
+    (tags::INSTITUTION_CODE_SEQUENCE, Action::Dummy, Category::Identifying),
+    (tags::REFERRING_PHYSICIAN_NAME, Action::Empty, Category::Identifying),
+    (tags::REFERRING_PHYSICIAN_ADDRESS, Action::Remove, Category::Identifying),
+    (tags::REFERRING_PHYSICIAN_TELEPHONE_NUMBERS, Action::Remove, Category::Identifying),
+    (tags::REFERRING_PHYSICIAN_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::CONSULTING_PHYSICIAN_NAME, Action::Empty, Category::Identifying),
+    (tags::CONSULTING_PHYSICIAN_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::CONTEXT_GROUP_EXTENSION_CREATOR_UID, Action::RemapUid, Category::Uid),
+    (tags::TIMEZONE_OFFSET_FROM_UTC, Action::Remove, Category::Identifying),
+    (tags::PRIVATE_DATA_ELEMENT_CHARACTERISTICS_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::STATION_NAME, Action::Dummy, Category::Identifying),
+    (tags::STUDY_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SERIES_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::INSTITUTIONAL_DEPARTMENT_NAME, Action::Remove, Category::Identifying),
+    (tags::INSTITUTIONAL_DEPARTMENT_TYPE_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PHYSICIANS_OF_RECORD, Action::Remove, Category::Identifying),
+    (tags::PHYSICIANS_OF_RECORD_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PERFORMING_PHYSICIAN_NAME, Action::Remove, Category::Identifying),
+    (tags::PERFORMING_PHYSICIAN_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::NAME_OF_PHYSICIANS_READING_STUDY, Action::Remove, Category::Identifying),
+    (tags::PHYSICIANS_READING_STUDY_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::OPERATORS_NAME, Action::Dummy, Category::Identifying),
+    (tags::OPERATOR_IDENTIFICATION_SEQUENCE, Action::Dummy, Category::Identifying),
+    (tags::ADMITTING_DIAGNOSES_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::ADMITTING_DIAGNOSES_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::REFERENCED_STUDY_SEQUENCE, Action::Empty, Category::Identifying),
+    (tags::REFERENCED_PERFORMED_PROCEDURE_STEP_SEQUENCE, Action::Dummy, Category::Identifying),
+    (tags::REFERENCED_PATIENT_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::REFERENCED_IMAGE_SEQUENCE, Action::Keep, Category::Identifying),
+    (tags::REFERENCED_SOP_INSTANCE_UID, Action::RemapUid, Category::Uid),
+    (tags::TRANSACTION_UID, Action::RemapUid, Category::Uid),
+    (tags::DERIVATION_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SOURCE_IMAGE_SEQUENCE, Action::Keep, Category::Identifying),
+    (tags::IRRADIATION_EVENT_UID, Action::RemapUid, Category::Uid),
+    (tags::IDENTIFYING_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::CREATOR_VERSION_UID, Action::RemapUid, Category::Uid),
+    (tags::PATIENT_NAME, Action::Empty, Category::Identifying),
+    (tags::PATIENT_ID, Action::Empty, Category::Identifying),
+    (tags::ISSUER_OF_PATIENT_ID, Action::Remove, Category::Identifying),
+    (tags::TYPE_OF_PATIENT_ID, Action::Remove, Category::Identifying),
+    (tags::ISSUER_OF_PATIENT_ID_QUALIFIERS_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::SOURCE_PATIENT_GROUP_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::GROUP_OF_PATIENTS_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_BIRTH_DATE, Action::Empty, Category::Temporal),
+    (tags::PATIENT_BIRTH_TIME, Action::Remove, Category::Temporal),
+    (tags::PATIENT_BIRTH_DATE_IN_ALTERNATIVE_CALENDAR, Action::Remove, Category::Identifying),
+    (tags::PATIENT_DEATH_DATE_IN_ALTERNATIVE_CALENDAR, Action::Remove, Category::Identifying),
+    (tags::PATIENT_ALTERNATIVE_CALENDAR, Action::Remove, Category::Identifying),
+    (tags::PATIENT_SEX, Action::Empty, Category::Identifying),
+    (tags::PATIENT_INSURANCE_PLAN_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_PRIMARY_LANGUAGE_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_PRIMARY_LANGUAGE_MODIFIER_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::OTHER_PATIENT_I_DS, Action::Remove, Category::Identifying),
+    (tags::OTHER_PATIENT_NAMES, Action::Remove, Category::Identifying),
+    (tags::OTHER_PATIENT_I_DS_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_BIRTH_NAME, Action::Remove, Category::Identifying),
+    (tags::PATIENT_AGE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_SIZE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_SIZE_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_BODY_MASS_INDEX, Action::Remove, Category::Identifying),
+    (tags::MEASURED_AP_DIMENSION, Action::Remove, Category::Identifying),
+    (tags::MEASURED_LATERAL_DIMENSION, Action::Remove, Category::Identifying),
+    (tags::PATIENT_WEIGHT, Action::Remove, Category::Identifying),
+    (tags::PATIENT_ADDRESS, Action::Remove, Category::Identifying),
+    (tags::INSURANCE_PLAN_IDENTIFICATION, Action::Remove, Category::Identifying),
+    (tags::PATIENT_MOTHER_BIRTH_NAME, Action::Remove, Category::Identifying),
+    (tags::MILITARY_RANK, Action::Remove, Category::Identifying),
+    (tags::BRANCH_OF_SERVICE, Action::Remove, Category::Identifying),
+    (tags::MEDICAL_RECORD_LOCATOR, Action::Remove, Category::Identifying),
+    (tags::REFERENCED_PATIENT_PHOTO_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::MEDICAL_ALERTS, Action::Remove, Category::Identifying),
+    (tags::ALLERGIES, Action::Remove, Category::Identifying),
+    (tags::COUNTRY_OF_RESIDENCE, Action::Remove, Category::Identifying),
+    (tags::REGION_OF_RESIDENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_TELEPHONE_NUMBERS, Action::Remove, Category::Identifying),
+    (tags::PATIENT_TELECOM_INFORMATION, Action::Remove, Category::Identifying),
+    (tags::ETHNIC_GROUP, Action::Remove, Category::Identifying),
+    (tags::OCCUPATION, Action::Remove, Category::Identifying),
+    (tags::SMOKING_STATUS, Action::Remove, Category::Identifying),
+    (tags::ADDITIONAL_PATIENT_HISTORY, Action::Remove, Category::Descriptor),
+    (tags::PREGNANCY_STATUS, Action::Remove, Category::Identifying),
+    (tags::LAST_MENSTRUAL_DATE, Action::Remove, Category::Temporal),
+    (tags::PATIENT_RELIGIOUS_PREFERENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_SEX_NEUTERED, Action::Empty, Category::Identifying),
+    (tags::RESPONSIBLE_PERSON, Action::Remove, Category::Identifying),
+    (tags::RESPONSIBLE_ORGANIZATION, Action::Remove, Category::Identifying),
+    (tags::PATIENT_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::CALIBRATION_TIME, Action::Remove, Category::Temporal),
+    (tags::CALIBRATION_DATE, Action::Remove, Category::Temporal),
+    (tags::CONTRAST_BOLUS_AGENT, Action::Dummy, Category::Identifying),
+    (tags::DEVICE_SERIAL_NUMBER, Action::Dummy, Category::Identifying),
+    (tags::DEVICE_UID, Action::RemapUid, Category::Uid),
+    (tags::PLATE_ID, Action::Remove, Category::Identifying),
+    (tags::GENERATOR_ID, Action::Remove, Category::Identifying),
+    (tags::CASSETTE_ID, Action::Remove, Category::Identifying),
+    (tags::GANTRY_ID, Action::Remove, Category::Identifying),
+    (tags::UNIQUE_DEVICE_IDENTIFIER, Action::Remove, Category::Identifying),
+    (tags::UDI_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::MANUFACTURER_DEVICE_CLASS_UID, Action::RemapUid, Category::Uid),
+    (tags::DATE_OF_SECONDARY_CAPTURE, Action::Remove, Category::Temporal),
+    (tags::TIME_OF_SECONDARY_CAPTURE, Action::Remove, Category::Temporal),
+    (tags::PROTOCOL_NAME, Action::Dummy, Category::Descriptor),
+    (tags::RADIOPHARMACEUTICAL_START_TIME, Action::Remove, Category::Temporal),
+    (tags::RADIOPHARMACEUTICAL_STOP_TIME, Action::Remove, Category::Temporal),
+    (tags::RADIOPHARMACEUTICAL_START_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::RADIOPHARMACEUTICAL_STOP_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::ACQUISITION_FIELD_OF_VIEW_LABEL, Action::Dummy, Category::Identifying),
+    (tags::DATE_OF_LAST_CALIBRATION, Action::Remove, Category::Temporal),
+    (tags::TIME_OF_LAST_CALIBRATION, Action::Remove, Category::Temporal),
+    (tags::DATE_TIME_OF_LAST_CALIBRATION, Action::Remove, Category::Temporal),
+    (tags::ACQUISITION_DEVICE_PROCESSING_DESCRIPTION, Action::Dummy, Category::Descriptor),
+    (tags::TARGET_UID, Action::RemapUid, Category::Uid),
+    (tags::ACQUISITION_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::DETECTOR_ID, Action::Dummy, Category::Identifying),
+    (tags::DATE_OF_LAST_DETECTOR_CALIBRATION, Action::Dummy, Category::Temporal),
+    (tags::TIME_OF_LAST_DETECTOR_CALIBRATION, Action::Dummy, Category::Temporal),
+    (tags::FRAME_ACQUISITION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::FRAME_REFERENCE_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::RESPIRATORY_MOTION_COMPENSATION_TECHNIQUE_DESCRIPTION, Action::Dummy, Category::Descriptor),
+    (tags::X_RAY_SOURCE_ID, Action::Dummy, Category::Identifying),
+    (tags::SOURCE_START_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::SOURCE_END_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::X_RAY_DETECTOR_ID, Action::Dummy, Category::Identifying),
+    (tags::X_RAY_DETECTOR_LABEL, Action::Remove, Category::Identifying),
+    (tags::MULTIENERGY_ACQUISITION_DESCRIPTION, Action::Dummy, Category::Descriptor),
+    (tags::DECOMPOSITION_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::ACQUISITION_PROTOCOL_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::START_ACQUISITION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::END_ACQUISITION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::FUNCTIONAL_SYNC_PULSE, Action::Dummy, Category::Temporal),
+    (tags::DECAY_CORRECTION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::EXCLUSION_START_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::INSTRUCTION_PERFORMED_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::REQUESTED_SERIES_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::CONTRIBUTION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::CONTRIBUTION_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::STUDY_INSTANCE_UID, Action::RemapUid, Category::Uid),
+    (tags::SERIES_INSTANCE_UID, Action::RemapUid, Category::Uid),
+    (tags::STUDY_ID, Action::Empty, Category::Identifying),
+    (tags::FRAME_OF_REFERENCE_UID, Action::RemapUid, Category::Uid),
+    (tags::SYNCHRONIZATION_FRAME_OF_REFERENCE_UID, Action::RemapUid, Category::Uid),
+    (tags::MODIFYING_DEVICE_ID, Action::Remove, Category::Identifying),
+    (tags::MODIFIED_IMAGE_DATE, Action::Remove, Category::Temporal),
+    (tags::MODIFIED_IMAGE_TIME, Action::Remove, Category::Temporal),
+    (tags::MODIFIED_IMAGE_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::IMAGE_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::FRAME_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::CONCATENATION_UID, Action::RemapUid, Category::Uid),
+    (tags::DIMENSION_ORGANIZATION_UID, Action::RemapUid, Category::Uid),
+    (tags::PALETTE_COLOR_LOOKUP_TABLE_UID, Action::RemapUid, Category::Uid),
+    (tags::LARGE_PALETTE_COLOR_LOOKUP_TABLE_UID, Action::RemapUid, Category::Uid),
+    (tags::IMAGE_PRESENTATION_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::PIXEL_DATA_PROVIDER_URL, Action::Remove, Category::Identifying),
+    (tags::STUDY_ID_ISSUER, Action::Remove, Category::Identifying),
+    (tags::STUDY_VERIFIED_DATE, Action::Remove, Category::Temporal),
+    (tags::STUDY_VERIFIED_TIME, Action::Remove, Category::Temporal),
+    (tags::STUDY_READ_DATE, Action::Remove, Category::Temporal),
+    (tags::STUDY_READ_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_STUDY_START_DATE, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_STUDY_START_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_STUDY_STOP_DATE, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_STUDY_STOP_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_STUDY_LOCATION, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_STUDY_LOCATION_AE_TITLE, Action::Remove, Category::Identifying),
+    (tags::REASON_FOR_STUDY, Action::Remove, Category::Descriptor),
+    (tags::REQUESTING_PHYSICIAN, Action::Remove, Category::Identifying),
+    (tags::REQUESTING_SERVICE, Action::Remove, Category::Identifying),
+    (tags::REQUESTING_SERVICE_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::STUDY_ARRIVAL_DATE, Action::Remove, Category::Temporal),
+    (tags::STUDY_ARRIVAL_TIME, Action::Remove, Category::Temporal),
+    (tags::STUDY_COMPLETION_DATE, Action::Remove, Category::Temporal),
+    (tags::STUDY_COMPLETION_TIME, Action::Remove, Category::Temporal),
+    (tags::REQUESTED_PROCEDURE_DESCRIPTION, Action::Empty, Category::Descriptor),
+    (tags::REASON_FOR_VISIT, Action::Remove, Category::Descriptor),
+    (tags::REASON_FOR_VISIT_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::REQUESTED_CONTRAST_AGENT, Action::Remove, Category::Identifying),
+    (tags::STUDY_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::REFERENCED_PATIENT_ALIAS_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::ADMISSION_ID, Action::Remove, Category::Identifying),
+    (tags::ISSUER_OF_ADMISSION_ID, Action::Remove, Category::Identifying),
+    (tags::ISSUER_OF_ADMISSION_ID_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_ADMISSION_DATE, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_ADMISSION_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_DISCHARGE_DATE, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_DISCHARGE_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PATIENT_INSTITUTION_RESIDENCE, Action::Remove, Category::Identifying),
+    (tags::ADMITTING_DATE, Action::Remove, Category::Temporal),
+    (tags::ADMITTING_TIME, Action::Remove, Category::Temporal),
+    (tags::DISCHARGE_DATE, Action::Remove, Category::Temporal),
+    (tags::DISCHARGE_TIME, Action::Remove, Category::Temporal),
+    (tags::DISCHARGE_DIAGNOSIS_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SPECIAL_NEEDS, Action::Remove, Category::Identifying),
+    (tags::SERVICE_EPISODE_ID, Action::Remove, Category::Identifying),
+    (tags::ISSUER_OF_SERVICE_EPISODE_ID, Action::Remove, Category::Identifying),
+    (tags::SERVICE_EPISODE_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::ISSUER_OF_SERVICE_EPISODE_ID_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::CURRENT_PATIENT_LOCATION, Action::Remove, Category::Identifying),
+    (tags::PATIENT_INSTITUTION_RESIDENCE, Action::Remove, Category::Identifying),
+    (tags::PATIENT_STATE, Action::Remove, Category::Identifying),
+    (tags::VISIT_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::MULTIPLEX_GROUP_UID, Action::RemapUid, Category::Uid),
+    (tags::SCHEDULED_STATION_AE_TITLE, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_PROCEDURE_STEP_START_DATE, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PROCEDURE_STEP_START_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PROCEDURE_STEP_END_DATE, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PROCEDURE_STEP_END_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PERFORMING_PHYSICIAN_NAME, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_PROCEDURE_STEP_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SCHEDULED_PERFORMING_PHYSICIAN_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_STATION_NAME, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_PROCEDURE_STEP_LOCATION, Action::Remove, Category::Identifying),
+    (tags::PRE_MEDICATION, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_STATION_AE_TITLE, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_STATION_NAME, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_LOCATION, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_PROCEDURE_STEP_START_DATE, Action::Remove, Category::Temporal),
+    (tags::PERFORMED_PROCEDURE_STEP_START_TIME, Action::Remove, Category::Temporal),
+    (tags::PERFORMED_PROCEDURE_STEP_END_DATE, Action::Remove, Category::Temporal),
+    (tags::PERFORMED_PROCEDURE_STEP_END_TIME, Action::Remove, Category::Temporal),
+    (tags::PERFORMED_PROCEDURE_STEP_ID, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_PROCEDURE_STEP_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::REQUEST_ATTRIBUTES_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::COMMENTS_ON_THE_PERFORMED_PROCEDURE_STEP, Action::Remove, Category::Descriptor),
+    (tags::SPECIMEN_UID, Action::RemapUid, Category::Uid),
+    (tags::ACQUISITION_CONTEXT_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::SPECIMEN_SHORT_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SPECIMEN_DETAILED_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SLIDE_IDENTIFIER, Action::Remove, Category::Identifying),
+    (tags::REQUESTED_PROCEDURE_ID, Action::Remove, Category::Identifying),
+    (tags::REASON_FOR_THE_REQUESTED_PROCEDURE, Action::Remove, Category::Descriptor),
+    (tags::PATIENT_TRANSPORT_ARRANGEMENTS, Action::Remove, Category::Identifying),
+    (tags::REQUESTED_PROCEDURE_LOCATION, Action::Remove, Category::Identifying),
+    (tags::REASON_FOR_REQUESTED_PROCEDURE_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::NAMES_OF_INTENDED_RECIPIENTS_OF_RESULTS, Action::Remove, Category::Identifying),
+    (tags::INTENDED_RECIPIENTS_OF_RESULTS_IDENTIFICATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PERSON_IDENTIFICATION_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PERSON_ADDRESS, Action::Remove, Category::Identifying),
+    (tags::PERSON_TELEPHONE_NUMBERS, Action::Remove, Category::Identifying),
+    (tags::PERSON_TELECOM_INFORMATION, Action::Remove, Category::Identifying),
+    (tags::REQUESTED_PROCEDURE_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::REASON_FOR_THE_IMAGING_SERVICE_REQUEST, Action::Remove, Category::Descriptor),
+    (tags::ISSUE_DATE_OF_IMAGING_SERVICE_REQUEST, Action::Remove, Category::Temporal),
+    (tags::ISSUE_TIME_OF_IMAGING_SERVICE_REQUEST, Action::Remove, Category::Temporal),
+    (tags::ORDER_ENTERED_BY, Action::Remove, Category::Identifying),
+    (tags::ORDER_ENTERER_LOCATION, Action::Remove, Category::Identifying),
+    (tags::ORDER_CALLBACK_PHONE_NUMBER, Action::Remove, Category::Identifying),
+    (tags::ORDER_CALLBACK_TELECOM_INFORMATION, Action::Remove, Category::Identifying),
+    (tags::PLACER_ORDER_NUMBER_IMAGING_SERVICE_REQUEST, Action::Empty, Category::Identifying),
+    (tags::FILLER_ORDER_NUMBER_IMAGING_SERVICE_REQUEST, Action::Empty, Category::Identifying),
+    (tags::IMAGING_SERVICE_REQUEST_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::CONFIDENTIALITY_CONSTRAINT_ON_PATIENT_DATA_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::SCHEDULED_PROCEDURE_STEP_START_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PROCEDURE_STEP_EXPIRATION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::SCHEDULED_PROCEDURE_STEP_MODIFICATION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::EXPECTED_COMPLETION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::REFERENCED_GENERAL_PURPOSE_SCHEDULED_PROCEDURE_STEP_TRANSACTION_UID, Action::RemapUid, Category::Uid),
+    (tags::SCHEDULED_STATION_NAME_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_STATION_GEOGRAPHIC_LOCATION_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_STATION_NAME_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_STATION_GEOGRAPHIC_LOCATION_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::SCHEDULED_HUMAN_PERFORMERS_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::ACTUAL_HUMAN_PERFORMERS_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::HUMAN_PERFORMER_ORGANIZATION, Action::Remove, Category::Identifying),
+    (tags::HUMAN_PERFORMER_NAME, Action::Remove, Category::Identifying),
+    (tags::PERFORMED_PROCEDURE_STEP_START_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::PERFORMED_PROCEDURE_STEP_END_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::PROCEDURE_STEP_CANCELLATION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::VERIFYING_ORGANIZATION, Action::Remove, Category::Identifying),
+    (tags::VERIFICATION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::OBSERVATION_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::VERIFYING_OBSERVER_SEQUENCE, Action::Dummy, Category::Identifying),
+    (tags::VERIFYING_OBSERVER_NAME, Action::Dummy, Category::Identifying),
+    (tags::AUTHOR_OBSERVER_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PARTICIPANT_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::CUSTODIAL_ORGANIZATION_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::PARTICIPATION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::VERIFYING_OBSERVER_IDENTIFICATION_CODE_SEQUENCE, Action::Empty, Category::Identifying),
+    (tags::DATE_OF_DOCUMENT_OR_VERBAL_TRANSACTION_TRIAL, Action::Remove, Category::Temporal),
+    (tags::TIME_OF_DOCUMENT_CREATION_OR_VERBAL_TRANSACTION_TRIAL, Action::Remove, Category::Temporal),
+    (tags::DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::DATE, Action::Dummy, Category::Temporal),
+    (tags::TIME, Action::Dummy, Category::Temporal),
+    (tags::PERSON_NAME, Action::Dummy, Category::Identifying),
+    (tags::UID, Action::RemapUid, Category::Uid),
+    (tags::REFERENCED_DATE_TIME, Action::Dummy, Category::Temporal),
+    (tags::TEXT_VALUE, Action::Remove, Category::Identifying),
+    (tags::OBSERVATION_UID, Action::RemapUid, Category::Uid),
+    (tags::REFERENCED_OBSERVATION_UID_TRIAL, Action::RemapUid, Category::Uid),
+    (tags::OBSERVATION_DATE_TRIAL, Action::Remove, Category::Temporal),
+    (tags::OBSERVATION_TIME_TRIAL, Action::Remove, Category::Temporal),
+    (tags::CURRENT_OBSERVER_TRIAL, Action::Remove, Category::Identifying),
+    (tags::VERBAL_SOURCE_TRIAL, Action::Remove, Category::Identifying),
+    (tags::ADDRESS_TRIAL, Action::Remove, Category::Identifying),
+    (tags::TELEPHONE_NUMBER_TRIAL, Action::Remove, Category::Identifying),
+    (tags::VERBAL_SOURCE_IDENTIFIER_CODE_SEQUENCE_TRIAL, Action::Remove, Category::Identifying),
+    (tags::OBSERVATION_SUBJECT_UID_TRIAL, Action::RemapUid, Category::Uid),
+    (tags::CONTENT_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::TEMPLATE_EXTENSION_ORGANIZATION_UID, Action::RemapUid, Category::Uid),
+    (tags::TEMPLATE_EXTENSION_CREATOR_UID, Action::RemapUid, Category::Uid),
+    (tags::CONTAINER_COMPONENT_ID, Action::Remove, Category::Identifying),
+    (tags::DEVICE_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::TRACKING_UID, Action::RemapUid, Category::Uid),
+    (tags::SOURCE_FRAME_OF_REFERENCE_UID, Action::RemapUid, Category::Uid),
+    (tags::GRAPHIC_ANNOTATION_SEQUENCE, Action::Dummy, Category::Identifying),
+    (tags::CONTENT_CREATOR_NAME, Action::Empty, Category::Identifying),
+    (tags::CONTENT_CREATOR_IDENTIFICATION_CODE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::FIDUCIAL_UID, Action::RemapUid, Category::Uid),
+    (tags::PRESENTATION_DISPLAY_COLLECTION_UID, Action::RemapUid, Category::Uid),
+    (tags::PRESENTATION_SEQUENCE_COLLECTION_UID, Action::RemapUid, Category::Uid),
+    (tags::STORAGE_MEDIA_FILE_SET_UID, Action::RemapUid, Category::Uid),
+    (tags::ICON_IMAGE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::TOPIC_TITLE, Action::Remove, Category::Identifying),
+    (tags::TOPIC_SUBJECT, Action::Remove, Category::Identifying),
+    (tags::TOPIC_AUTHOR, Action::Remove, Category::Identifying),
+    (tags::TOPIC_KEYWORDS, Action::Remove, Category::Identifying),
+    (tags::SOP_AUTHORIZATION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::SOP_AUTHORIZATION_COMMENT, Action::Remove, Category::Descriptor),
+    (tags::AUTHORIZATION_EQUIPMENT_CERTIFICATION_NUMBER, Action::Remove, Category::Identifying),
+    (tags::DIGITAL_SIGNATURE_UID, Action::Remove, Category::Identifying),
+    (tags::DIGITAL_SIGNATURE_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::CERTIFICATE_OF_SIGNER, Action::Remove, Category::Identifying),
+    (tags::CERTIFIED_TIMESTAMP, Action::Remove, Category::Identifying),
+    (tags::REFERENCED_DIGITAL_SIGNATURE_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::REFERENCED_SOP_INSTANCE_MAC_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::MAC, Action::Remove, Category::Identifying),
+    (tags::MODIFIED_ATTRIBUTES_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::ORIGINAL_ATTRIBUTES_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::ATTRIBUTE_MODIFICATION_DATE_TIME, Action::Remove, Category::Temporal),
+    (tags::MODIFYING_SYSTEM, Action::Remove, Category::Identifying),
+    (tags::SOURCE_OF_PREVIOUS_VALUES, Action::Remove, Category::Identifying),
+    (tags::REASON_FOR_THE_ATTRIBUTE_MODIFICATION, Action::Remove, Category::Descriptor),
+    (tags::INSTANCE_ORIGIN_STATUS, Action::Remove, Category::Identifying),
+    (tags::TEXT_STRING, Action::Remove, Category::Identifying),
+    (tags::STRUCTURE_SET_LABEL, Action::Dummy, Category::Identifying),
+    (tags::STRUCTURE_SET_NAME, Action::Remove, Category::Identifying),
+    (tags::STRUCTURE_SET_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::STRUCTURE_SET_DATE, Action::Empty, Category::Temporal),
+    (tags::STRUCTURE_SET_TIME, Action::Empty, Category::Temporal),
+    (tags::REFERENCED_FRAME_OF_REFERENCE_UID, Action::RemapUid, Category::Uid),
+    (tags::ROI_NAME, Action::Empty, Category::Identifying),
+    (tags::ROI_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::ROI_OBSERVATION_LABEL, Action::Remove, Category::Identifying),
+    (tags::ROI_OBSERVATION_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::ROI_INTERPRETER, Action::Empty, Category::Identifying),
+    (tags::RELATED_FRAME_OF_REFERENCE_UID, Action::RemapUid, Category::Uid),
+    (tags::SOURCE_SERIAL_NUMBER, Action::Remove, Category::Identifying),
+    (tags::TREATMENT_DATE, Action::Remove, Category::Temporal),
+    (tags::TREATMENT_TIME, Action::Remove, Category::Temporal),
+    (tags::RT_PLAN_LABEL, Action::Dummy, Category::Identifying),
+    (tags::RT_PLAN_NAME, Action::Remove, Category::Identifying),
+    (tags::RT_PLAN_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::RT_PLAN_DATE, Action::Remove, Category::Temporal),
+    (tags::RT_PLAN_TIME, Action::Remove, Category::Temporal),
+    (tags::PRESCRIPTION_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::DOSE_REFERENCE_UID, Action::RemapUid, Category::Uid),
+    (tags::DOSE_REFERENCE_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::FRACTION_GROUP_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::TREATMENT_MACHINE_NAME, Action::Empty, Category::Identifying),
+    (tags::PATIENT_SETUP_UID, Action::RemapUid, Category::Uid),
+    (tags::TREATMENT_SESSION_UID, Action::RemapUid, Category::Uid),
+    (tags::REFERENCED_TREATMENT_POSITION_GROUP_UID, Action::RemapUid, Category::Uid),
+    (tags::REVIEW_DATE, Action::Remove, Category::Temporal),
+    (tags::REVIEW_TIME, Action::Remove, Category::Temporal),
+    (tags::REVIEWER_NAME, Action::Remove, Category::Identifying),
+    (tags::ARBITRARY, Action::Remove, Category::Identifying),
+    (tags::TEXT_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::RESULTS_ID, Action::Remove, Category::Identifying),
+    (tags::RESULTS_ID_ISSUER, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_RECORDED_DATE, Action::Remove, Category::Temporal),
+    (tags::INTERPRETATION_RECORDED_TIME, Action::Remove, Category::Temporal),
+    (tags::INTERPRETATION_RECORDER, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_TRANSCRIPTION_DATE, Action::Remove, Category::Temporal),
+    (tags::INTERPRETATION_TRANSCRIPTION_TIME, Action::Remove, Category::Temporal),
+    (tags::INTERPRETATION_TRANSCRIBER, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_TEXT, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_AUTHOR, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_APPROVER_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_APPROVAL_DATE, Action::Remove, Category::Temporal),
+    (tags::INTERPRETATION_APPROVAL_TIME, Action::Remove, Category::Temporal),
+    (tags::PHYSICIAN_APPROVING_INTERPRETATION, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_DIAGNOSIS_DESCRIPTION, Action::Remove, Category::Descriptor),
+    (tags::RESULTS_DISTRIBUTION_LIST_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::DISTRIBUTION_NAME, Action::Remove, Category::Identifying),
+    (tags::DISTRIBUTION_ADDRESS, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_ID, Action::Remove, Category::Identifying),
+    (tags::INTERPRETATION_ID_ISSUER, Action::Remove, Category::Identifying),
+    (tags::IMPRESSIONS, Action::Remove, Category::Descriptor),
+    (tags::RESULTS_COMMENTS, Action::Remove, Category::Descriptor),
+    (tags::DIGITAL_SIGNATURES_SEQUENCE, Action::Remove, Category::Identifying),
+    (tags::DATA_SET_TRAILING_PADDING, Action::Remove, Category::Identifying),
+];
+
+/// Determine the action of the basic profile
+/// for the attributes of repeating groups,
+/// as in the standard, part 15, table E.1-1.
+fn repeating_group_action(tag: Tag) -> Option<(Action, Category)> {
+    let Tag(group, element) = tag;
+    if group % 2 == 1 {
+        // private attributes are handled separately
+        return None;
+    }
+    match (group & 0xFF00, element) {
+        // Curve Data (50xx,xxxx)
+        (0x5000, _) => Some((Action::Remove, Category::Identifying)),
+        // Overlay Data (60xx,3000), Overlay Comments (60xx,4000)
+        (0x6000, 0x3000) | (0x6000, 0x4000) => Some((Action::Remove, Category::Identifying)),
+        _ => None,
+    }
+}
+
+/// A table of UID replacements.
+///
+/// Each original UID is consistently mapped
+/// to the same newly generated UID.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct UidMap {
+    map: HashMap<String, String>,
+}
+
+impl UidMap {
+    /// Create an empty UID map.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Obtain the replacement of the given UID,
+    /// generating a new one if it was not mapped yet.
+    pub fn get_or_insert(&mut self, uid: &str) -> &str {
+        let uid = uid.trim_end_matches(|c| c == '\0' || c == ' ');
+        self.map.entry(uid.to_string()).or_insert_with(new_uid)
+    }
+
+    /// Obtain the replacement of the given UID, if it was mapped.
+    pub fn get(&self, uid: &str) -> Option<&str> {
+        let uid = uid.trim_end_matches(|c| c == '\0' || c == ' ');
+        self.map.get(uid).map(String::as_str)
+    }
+
+    /// Iterate over all pairs of original and replacement UIDs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.map.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// Retrieve the number of mapped UIDs.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Check whether no UIDs were mapped.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+/// A configurable de-identifier of DICOM objects.
+///
+/// By default, the actions of the basic profile are applied,
+/// private attributes are removed,
+/// and all other attributes are kept.
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    /// actions overriding those of the profile
+    actions: BTreeMap<Tag, Action>,
+    /// actions overriding those of the profile for whole sets of tags
+    set_actions: Vec<(TagSet, Action)>,
+    /// whether to keep dates and times
+    retain_dates: bool,
+    /// whether to keep free text descriptions
+    retain_descriptors: bool,
+    /// whether to keep the original UIDs
+    retain_uids: bool,
+    /// whether to keep private attributes
+    retain_private: bool,
+    /// secret key of the HMAC over values replaced by hashing
+    hash_key: Vec<u8>,
+    /// the UID replacement table
+    uid_map: UidMap,
+}
+
+impl Default for Anonymizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Anonymizer {
+    /// Create a new anonymizer applying the basic profile
+    /// with no retention options.
+    ///
+    /// Values replaced through [`Action::Hash`]
+    /// are hashed with a randomly generated key,
+    /// see [`with_hash_key`](Anonymizer::with_hash_key).
+    pub fn new() -> Self {
+        Anonymizer {
+            actions: BTreeMap::new(),
+            set_actions: Vec::new(),
+            retain_dates: false,
+            retain_descriptors: false,
+            retain_uids: false,
+            retain_private: false,
+            hash_key: random_key(),
+            uid_map: UidMap::new(),
+        }
+    }
+
+    /// Override the action to apply to the given attribute.
+    pub fn with_action(mut self, tag: Tag, action: Action) -> Self {
+        self.actions.insert(tag, action);
+        self
+    }
+
+    /// Override the action to apply to all attributes in the given set,
+    /// such as a repeating group or a whole private group.
+    ///
+    /// Actions given for a single attribute through [`with_action`]
+    /// take precedence over actions given for a set of attributes.
+    /// If a tag is in more than one set,
+    /// the action of the set given last applies.
+    ///
+    /// [`with_action`]: Anonymizer::with_action
+    pub fn with_action_for_tags(mut self, tags: TagSet, action: Action) -> Self {
+        self.set_actions.push((tags, action));
+        self
+    }
+
+    /// Set whether dates and times are kept
+    /// (Retain Longitudinal Temporal Information with Full Dates Option).
+    pub fn retain_dates(mut self, retain: bool) -> Self {
+        self.retain_dates = retain;
+        self
+    }
+
+    /// Set whether free text descriptions are kept
+    /// (Retain Descriptors Option).
+    pub fn retain_descriptors(mut self, retain: bool) -> Self {
+        self.retain_descriptors = retain;
+        self
+    }
+
+    /// Set whether UIDs are kept (Retain UIDs Option).
+    pub fn retain_uids(mut self, retain: bool) -> Self {
+        self.retain_uids = retain;
+        self
+    }
+
+    /// Set whether private attributes are kept.
+    pub fn retain_private_tags(mut self, retain: bool) -> Self {
+        self.retain_private = retain;
+        self
+    }
+
+    /// Set the secret key of the HMAC-SHA256
+    /// over values replaced through [`Action::Hash`].
+    ///
+    /// By default, each anonymizer uses a new random key.
+    /// Set the same key in different de-identification sessions
+    /// for values to be replaced consistently across them.
+    /// Anyone holding the key can check guesses of the original values,
+    /// so it should be kept secret.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is empty.
+    pub fn with_hash_key(mut self, key: impl Into<Vec<u8>>) -> Self {
+        let key = key.into();
+        assert!(!key.is_empty(), "hash key must not be empty");
+        self.hash_key = key;
+        self
+    }
+
+    /// Use an existing table of UID replacements,
+    /// such as one from a previous de-identification session.
+    pub fn with_uid_map(mut self, uid_map: UidMap) -> Self {
+        self.uid_map = uid_map;
+        self
+    }
+
+    /// Retrieve the table of UID replacements made so far.
+    pub fn uid_map(&self) -> &UidMap {
+        &self.uid_map
+    }
+
+    /// Retrieve the table of UID replacements made so far,
+    /// discarding the anonymizer.
+    pub fn into_uid_map(self) -> UidMap {
+        self.uid_map
+    }
+
+    /// Determine the action to apply to the given attribute.
+    pub fn action_for(&self, tag: Tag) -> Action {
+        if let Some(action) = self.actions.get(&tag) {
+            return *action;
+        }
+        if let Some((_, action)) = self
+            .set_actions
+            .iter()
+            .rev()
+            .find(|(tags, _)| tags.contains(tag))
+        {
+            return *action;
+        }
+        let profile_entry = BASIC_PROFILE
+            .binary_search_by_key(&tag, |(t, ..)| *t)
+            .ok()
+            .map(|i| (BASIC_PROFILE[i].1, BASIC_PROFILE[i].2))
+            .or_else(|| repeating_group_action(tag));
+        if let Some((action, category)) = profile_entry {
+            let retain = match category {
+                Category::Identifying => false,
+                Category::Temporal => self.retain_dates,
+                Category::Descriptor => self.retain_descriptors,
+                Category::Uid => self.retain_uids,
+            };
+            return if retain { Action::Keep } else { action };
+        }
+        if tag.group() % 2 == 1 && !self.retain_private {
+            return Action::Remove;
+        }
+        Action::Keep
+    }
+
+    /// De-identify the given data set in place,
+    /// including the items of all sequences.
+    ///
+    /// _Patient Identity Removed_ and _De-identification Method_
+    /// are recorded at the root of the data set.
+    pub fn anonymize<D>(&mut self, obj: &mut InMemDicomObject<D>)
+    where
+        D: DataDictionary + Clone,
+    {
+        self.anonymize_dataset(obj);
+        obj.put(DataElement::new(
+            tags::PATIENT_IDENTITY_REMOVED,
+            VR::CS,
+            PrimitiveValue::from("YES"),
+        ));
+        obj.put(DataElement::new(
+            tags::DEIDENTIFICATION_METHOD,
+            VR::LO,
+            PrimitiveValue::from(DEIDENTIFICATION_METHOD),
+        ));
+    }
+
+    /// De-identify the given DICOM file object in place.
+    ///
+    /// Besides the data set,
+    /// the media storage SOP instance UID in the file meta group
+    /// is updated to match the new SOP instance UID.
+    pub fn anonymize_file<D>(&mut self, obj: &mut FileDicomObject<InMemDicomObject<D>>)
+    where
+        D: DataDictionary + Clone,
+    {
+        self.anonymize(&mut obj.obj);
+
+        let new_uid = match obj.obj.element_opt(tags::SOP_INSTANCE_UID) {
+            Ok(Some(e)) => e.to_str().ok().map(|s| s.into_owned()),
+            _ => None,
+        };
+        let new_uid = new_uid.or_else(|| {
+            if self.retain_uids || is_empty_uid(&obj.meta.media_storage_sop_instance_uid) {
+                None
+            } else {
+                Some(
+                    self.uid_map
+                        .get_or_insert(&obj.meta.media_storage_sop_instance_uid)
+                        .to_string(),
+                )
+            }
+        });
+        if let Some(new_uid) = new_uid {
+            let meta = &mut obj.meta;
+            let old_len = even_len(&meta.media_storage_sop_instance_uid);
+            meta.information_group_length =
+                meta.information_group_length - old_len + even_len(&new_uid);
+            meta.media_storage_sop_instance_uid = new_uid;
+        }
+    }
+
+    fn anonymize_dataset<D>(&mut self, obj: &mut InMemDicomObject<D>)
+    where
+        D: DataDictionary + Clone,
+    {
+        let tags: Vec<Tag> = obj.tags().collect();
+        for tag in tags {
+            if let Ok(elem) = obj.take_element(tag) {
+                if let Some(elem) = self.anonymize_element(elem) {
+                    obj.put(elem);
+                }
+            }
+        }
+    }
+
+    fn anonymize_element<D>(&mut self, elem: InMemElement<D>) -> Option<InMemElement<D>>
+    where
+        D: DataDictionary + Clone,
+    {
+        let tag = elem.tag();
+        let vr = elem.vr();
+        let action = self.action_for(tag);
+
+        match (action, elem.into_value()) {
+            (Action::Remove, _) => None,
+            (Action::Empty, Value::Sequence { .. }) | (Action::Dummy, Value::Sequence { .. }) => {
+                Some(DataElement::new(
+                    tag,
+                    vr,
+                    Value::Sequence {
+                        items: C::new(),
+                        size: Length::UNDEFINED,
+                    },
+                ))
+            }
+            (_, Value::Sequence { items, .. }) => {
+                let items: C<_> = items
+                    .into_iter()
+                    .map(|mut item| {
+                        self.anonymize_dataset(&mut item);
+                        item
+                    })
+                    .collect();
+                Some(DataElement::new(
+                    tag,
+                    vr,
+                    Value::Sequence {
+                        items,
+                        size: Length::UNDEFINED,
+                    },
+                ))
+            }
+            (Action::Keep, value) => Some(DataElement::new(tag, vr, value)),
+            (Action::Empty, _) => Some(DataElement::new(tag, vr, PrimitiveValue::Empty)),
+            (Action::Dummy, _) => Some(DataElement::new(tag, vr, dummy_value(vr))),
+            (Action::RemapUid, Value::Primitive(value)) => {
+                let uids: C<String> = value
+                    .to_multi_str()
+                    .iter()
+                    .map(|uid| {
+                        if is_empty_uid(uid) {
+                            String::new()
+                        } else {
+                            self.uid_map.get_or_insert(uid).to_string()
+                        }
+                    })
+                    .collect();
+                if uids.iter().all(String::is_empty) {
+                    return Some(DataElement::new(tag, vr, PrimitiveValue::Empty));
+                }
+                Some(DataElement::new(tag, vr, PrimitiveValue::Strs(uids)))
+            }
+            (Action::Hash, Value::Primitive(value)) => {
+                let value = match value {
+                    PrimitiveValue::Str(_)
+                    | PrimitiveValue::Strs(_)
+                    | PrimitiveValue::SharedStrs(_) => {
+                        hash_value(&self.hash_key, &value.to_str(), vr)
+                    }
+                    _ => dummy_value(vr),
+                };
+                Some(DataElement::new(tag, vr, value))
+            }
+            (_, value @ Value::PixelSequence { .. }) => Some(DataElement::new(tag, vr, value)),
+        }
+    }
+}
+
+/// Create a dummy value for the given value representation.
+fn dummy_value(vr: VR) -> PrimitiveValue {
+    match vr {
+        VR::AE | VR::CS | VR::LO | VR::LT | VR::SH | VR::ST | VR::UC | VR::UT | VR::PN => {
+            PrimitiveValue::from("ANONYMOUS")
+        }
+        VR::DA => PrimitiveValue::from("19000101"),
+        VR::TM => PrimitiveValue::from("000000.00"),
+        VR::DT => PrimitiveValue::from("19000101000000.00"),
+        VR::AS => PrimitiveValue::from("000Y"),
+        VR::DS | VR::IS => PrimitiveValue::from("0"),
+        VR::UI => PrimitiveValue::from(new_uid()),
+        VR::UR => PrimitiveValue::from("urn:anonymous"),
+        VR::US => PrimitiveValue::from(0_u16),
+        VR::SS => PrimitiveValue::from(0_i16),
+        VR::UL => PrimitiveValue::from(0_u32),
+        VR::SL => PrimitiveValue::from(0_i32),
+        VR::FL => PrimitiveValue::from(0_f32),
+        VR::FD => PrimitiveValue::from(0_f64),
+        _ => PrimitiveValue::Empty,
+    }
+}
+
+/// Replace a textual value with a keyed hash of the original value,
+/// fitting the maximum length of the value representation.
+fn hash_value(key: &[u8], value: &str, vr: VR) -> PrimitiveValue {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(value.as_bytes());
+    let digest = mac.finalize().into_bytes();
+    let mut bytes = [0; 16];
+    bytes.copy_from_slice(&digest[..16]);
+    let hash = u128::from_be_bytes(bytes);
+    match vr {
+        VR::UI => PrimitiveValue::from(format!("2.25.{}", hash)),
+        VR::DS | VR::IS => PrimitiveValue::from((hash % 1_000_000_000).to_string()),
+        VR::DA | VR::TM | VR::DT | VR::AS => dummy_value(vr),
+        _ => PrimitiveValue::from(format!("{:016X}", hash >> 64)),
+    }
+}
+
+/// Generate a random 256-bit key for hashing values.
+fn random_key() -> Vec<u8> {
+    let mut key = uuid::Uuid::new_v4().as_bytes().to_vec();
+    key.extend_from_slice(uuid::Uuid::new_v4().as_bytes());
+    key
+}
+
+/// Check whether a UID value is empty, ignoring its padding.
+fn is_empty_uid(uid: &str) -> bool {
+    uid.trim_end_matches(|c| c == '\0' || c == ' ').is_empty()
+}
+
+/// The length of a string value after padding to an even length.
+fn even_len(value: &str) -> u32 {
+    (value.len() as u32 + 1) & !1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, Anonymizer, BASIC_PROFILE};
+    use crate::{FileMetaTableBuilder, InMemDicomObject};
+    use dicom_core::value::PrimitiveValue;
+    use dicom_core::{Tag, VR};
+    use dicom_dictionary_std::tags;
+
+    fn sample_object() -> InMemDicomObject {
+        let item = InMemDicomObject::builder()
+            .add_value(tags::REFERENCED_SOP_INSTANCE_UID, "1.2.3.4.10")
+            .add_value(tags::PATIENT_NAME, "Doe^John")
+            .build()
+            .unwrap();
+        InMemDicomObject::builder()
+            .add_value(tags::SOP_CLASS_UID, "1.2.840.10008.5.1.4.1.1.2")
+            .add_value(tags::SOP_INSTANCE_UID, "1.2.3.4.10")
+            .add_value(tags::STUDY_INSTANCE_UID, "1.2.3.4")
+            .add_value(tags::STUDY_DATE, "20230101")
+            .add_value(tags::STUDY_DESCRIPTION, "Head CT")
+            .add_value(tags::PATIENT_NAME, "Doe^John")
+            .add_value(tags::PATIENT_ID, "12345")
+            .add_value(tags::PATIENT_AGE, "042Y")
+            .add_value(tags::MODALITY, "CT")
+            .add(Tag(0x0009, 0x0010), VR::LO, "ACME")
+            .add_seq(tags::REFERENCED_IMAGE_SEQUENCE, vec![item])
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn anonymize_basic_profile() {
+        let mut obj = sample_object();
+        let mut anonymizer = Anonymizer::new();
+        anonymizer.anonymize(&mut obj);
+
+        // emptied
+        assert_eq!(
+            obj.element(tags::PATIENT_NAME).unwrap().value().primitive(),
+            Some(&PrimitiveValue::Empty)
+        );
+        assert_eq!(
+            obj.element(tags::STUDY_DATE).unwrap().value().primitive(),
+            Some(&PrimitiveValue::Empty)
+        );
+        // removed
+        assert!(obj.element_opt(tags::PATIENT_AGE).unwrap().is_none());
+        assert!(obj.element_opt(tags::STUDY_DESCRIPTION).unwrap().is_none());
+        assert!(obj.element_opt(Tag(0x0009, 0x0010)).unwrap().is_none());
+        // kept
+        assert_eq!(obj.element(tags::MODALITY).unwrap().to_str().unwrap(), "CT");
+        assert_eq!(
+            obj.element(tags::PATIENT_IDENTITY_REMOVED)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "YES"
+        );
+
+        // UIDs remapped consistently, also within sequences
+        let sop_instance_uid = obj
+            .element(tags::SOP_INSTANCE_UID)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_ne!(sop_instance_uid, "1.2.3.4.10");
+        assert_eq!(
+            anonymizer.uid_map().get("1.2.3.4.10"),
+            Some(&*sop_instance_uid)
+        );
+        let items = obj.get_sequence(tags::REFERENCED_IMAGE_SEQUENCE).unwrap();
+        assert_eq!(
+            items[0]
+                .element(tags::REFERENCED_SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            sop_instance_uid
+        );
+        assert_eq!(
+            items[0]
+                .element(tags::PATIENT_NAME)
+                .unwrap()
+                .value()
+                .primitive(),
+            Some(&PrimitiveValue::Empty)
+        );
+        assert_eq!(anonymizer.uid_map().len(), 2);
+    }
+
+    #[test]
+    fn anonymize_with_options() {
+        let mut obj = sample_object();
+        let mut anonymizer = Anonymizer::new()
+            .retain_dates(true)
+            .retain_descriptors(true)
+            .retain_uids(true)
+            .retain_private_tags(true)
+            .with_action(tags::PATIENT_ID, Action::Hash)
+            .with_action(tags::PATIENT_NAME, Action::Dummy);
+        anonymizer.anonymize(&mut obj);
+
+        assert_eq!(
+            obj.element(tags::STUDY_DATE).unwrap().to_str().unwrap(),
+            "20230101"
+        );
+        assert_eq!(
+            obj.element(tags::STUDY_DESCRIPTION)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "Head CT"
+        );
+        assert_eq!(
+            obj.element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "1.2.3.4.10"
+        );
+        assert!(obj.element_opt(Tag(0x0009, 0x0010)).unwrap().is_some());
+        assert_eq!(
+            obj.element(tags::PATIENT_NAME).unwrap().to_str().unwrap(),
+            "ANONYMOUS"
+        );
+
+        let patient_id = obj.element(tags::PATIENT_ID).unwrap().to_str().unwrap();
+        assert_ne!(patient_id, "12345");
+        assert_eq!(patient_id.len(), 16);
+
+        assert!(anonymizer.uid_map().is_empty());
+    }
+
+    #[test]
+    fn anonymize_hash_with_key() {
+        let hash_patient_id = |anonymizer: Anonymizer| {
+            let mut obj = sample_object();
+            anonymizer
+                .with_action(tags::PATIENT_ID, Action::Hash)
+                .anonymize(&mut obj);
+            obj.element(tags::PATIENT_ID)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .into_owned()
+        };
+
+        // hashing is deterministic for the same key
+        let patient_id = hash_patient_id(Anonymizer::new().with_hash_key("secret"));
+        assert_eq!(
+            patient_id,
+            hash_patient_id(Anonymizer::new().with_hash_key("secret"))
+        );
+        assert_ne!(
+            patient_id,
+            hash_patient_id(Anonymizer::new().with_hash_key("other secret"))
+        );
+        // keys are random by default
+        assert_ne!(
+            hash_patient_id(Anonymizer::new()),
+            hash_patient_id(Anonymizer::new())
+        );
+        // HMAC-SHA256("secret", "12345"), truncated to 64 bits
+        assert_eq!(patient_id, "F88C7EBE4740DB59");
+    }
+
+    #[test]
+    #[should_panic(expected = "hash key must not be empty")]
+    fn anonymize_hash_with_empty_key() {
+        let _ = Anonymizer::new().with_hash_key("");
+    }
+
+    #[test]
+    fn anonymize_empty_uids() {
+        let mut obj = InMemDicomObject::builder()
+            .add_value(tags::SOP_INSTANCE_UID, "")
+            .add(tags::FRAME_OF_REFERENCE_UID, VR::UI, PrimitiveValue::Empty)
+            .add_value(tags::STUDY_INSTANCE_UID, "1.2.3.4")
+            .build()
+            .unwrap();
+        let mut anonymizer = Anonymizer::new();
+        anonymizer.anonymize(&mut obj);
+
+        for tag in [tags::SOP_INSTANCE_UID, tags::FRAME_OF_REFERENCE_UID] {
+            assert_eq!(
+                obj.element(tag).unwrap().value().primitive(),
+                Some(&PrimitiveValue::Empty)
+            );
+        }
+        assert_eq!(anonymizer.uid_map().len(), 1);
+        assert!(anonymizer.uid_map().get("").is_none());
+    }
+
+    #[test]
+    fn anonymize_tag_sets() {
+        let mut obj = sample_object();
+        let mut anonymizer = Anonymizer::new()
+            .with_action_for_tags("(0010,xxxx)".parse().unwrap(), Action::Remove)
+            .with_action(tags::PATIENT_ID, Action::Keep);
+        anonymizer.anonymize(&mut obj);
+
+        assert!(obj.element_opt(tags::PATIENT_NAME).unwrap().is_none());
+        assert!(obj.element_opt(tags::PATIENT_AGE).unwrap().is_none());
+        assert_eq!(
+            obj.element(tags::PATIENT_ID).unwrap().to_str().unwrap(),
+            "12345"
+        );
+
+        // repeating groups of the profile
+        assert_eq!(anonymizer.action_for(Tag(0x6000, 0x3000)), Action::Remove);
+        assert_eq!(anonymizer.action_for(Tag(0x6002, 0x4000)), Action::Remove);
+        assert_eq!(anonymizer.action_for(Tag(0x6000, 0x0010)), Action::Keep);
+    }
+
+    #[test]
+    fn basic_profile_table() {
+        // sorted for binary search
+        assert!(BASIC_PROFILE.windows(2).all(|w| w[0].0 < w[1].0));
+
+        let anonymizer = Anonymizer::new();
+        // Other Patient IDs (retired)
+        assert_eq!(anonymizer.action_for(Tag(0x0010, 0x1000)), Action::Remove);
+        assert_eq!(
+            anonymizer.action_for(tags::OTHER_PATIENT_I_DS_SEQUENCE),
+            Action::Remove
+        );
+        assert_eq!(anonymizer.action_for(tags::PERSON_NAME), Action::Dummy);
+        assert_eq!(
+            anonymizer.action_for(tags::CONTENT_SEQUENCE),
+            Action::Remove
+        );
+        // compound action codes
+        assert_eq!(anonymizer.action_for(tags::SERIES_DATE), Action::Dummy);
+        assert_eq!(anonymizer.action_for(tags::ACQUISITION_DATE), Action::Empty);
+        assert_eq!(
+            anonymizer.action_for(tags::SOURCE_IMAGE_SEQUENCE),
+            Action::Keep
+        );
+        // curve data, but not private groups in the same range
+        assert_eq!(anonymizer.action_for(Tag(0x5000, 0x0005)), Action::Remove);
+        assert_eq!(anonymizer.action_for(Tag(0x501E, 0x3000)), Action::Remove);
+        let anonymizer = anonymizer.retain_private_tags(true);
+        assert_eq!(anonymizer.action_for(Tag(0x5001, 0x0010)), Action::Keep);
+        assert_eq!(anonymizer.action_for(Tag(0x6001, 0x3000)), Action::Keep);
+
+        let mut obj = InMemDicomObject::builder()
+            .add(Tag(0x0010, 0x1000), VR::LO, "OTHER-ID")
+            .add_value(tags::PERSON_NAME, "Doe^Jane")
+            .build()
+            .unwrap();
+        Anonymizer::new().anonymize(&mut obj);
+        assert!(obj.element_opt(Tag(0x0010, 0x1000)).unwrap().is_none());
+        assert_eq!(
+            obj.element(tags::PERSON_NAME).unwrap().to_str().unwrap(),
+            "ANONYMOUS"
+        );
+    }
+
+    #[test]
+    fn anonymize_file_updates_meta() {
+        let mut obj = sample_object()
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.2")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+        let group_length = obj.meta().information_group_length;
+
+        let mut anonymizer = Anonymizer::new();
+        anonymizer.anonymize_file(&mut obj);
+
+        let sop_instance_uid = obj
+            .element(tags::SOP_INSTANCE_UID)
+            .unwrap()
+            .to_str()
+            .unwrap();
+        assert_eq!(obj.meta().media_storage_sop_instance_uid, sop_instance_uid);
+        let uid_len = |s: &str| (s.len() as u32 + 1) & !1;
+        assert_eq!(
+            obj.meta().information_group_length,
+            group_length - uid_len("1.2.3.4.10") + uid_len(&sop_instance_uid)
+        );
+    }
+}
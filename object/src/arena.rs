@@ -0,0 +1,813 @@
+//! Arena-backed DICOM objects for fast, read-only access.
+//!
+//! Reading a data set into an [`InMemDicomObject`]
+//! allocates a map entry and a value for every element,
+//! which becomes noticeable in objects with tens of thousands of elements,
+//! such as structured reports and segmentations.
+//! An [`ArenaDicomObject`] instead keeps the encoded data set in one buffer
+//! and records the headers of all elements, nested items included,
+//! in a single contiguous list.
+//! Element values are only decoded when requested,
+//! and the whole object is released at once when dropped.
+//!
+//! Arena objects cannot be modified.
+//! Use [`to_in_mem`](ArenaDicomObject::to_in_mem)
+//! to obtain an equivalent [`InMemDicomObject`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_dictionary_std::tags;
+//! use dicom_object::OpenFileOptions;
+//! # fn run() -> Result<(), dicom_object::Error> {
+//! let obj = OpenFileOptions::new().open_file_arena("report.dcm")?;
+//! let modality = obj.element(tags::MODALITY)?.value()?;
+//! println!("Modality: {}", modality.to_str());
+//!
+//! // walk through the items of a sequence without decoding them
+//! let content = obj.element(tags::CONTENT_SEQUENCE)?;
+//! for item in content.items().into_iter().flatten() {
+//!     println!("item with {} elements", item.len());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use bytes::Bytes;
+use dicom_core::header::{HasLength, Header};
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, DataElementHeader, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_parser::dataset::read::ValueReadStrategy;
+use dicom_parser::dataset::{DataSetReader, DataToken, PositionedToken};
+use dicom_parser::{DynStatefulDecoder, StatefulDecode};
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use snafu::{ensure, OptionExt, ResultExt};
+use std::fmt;
+use std::io::{Cursor, Read};
+use std::ops::Range;
+
+use crate::file::{OpenFileOptions, ReadPreamble};
+use crate::mem::{InMemElement, InMemFragment};
+use crate::{
+    CreateParserSnafu, DecodeValueSnafu, DefaultDicomObject, FileDicomObject, FileMetaTable,
+    InMemDicomObject, MissingElementValueSnafu, NoSuchDataElementTagSnafu, ParseMetaDataSetSnafu,
+    PrematureEndSnafu, ReadPreambleBytesSnafu, ReadTokenSnafu, Result, UnexpectedTokenSnafu,
+    UnsupportedTransferSyntaxSnafu,
+};
+
+/// A range of indices into one of the lists of the arena.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Span {
+    start: u32,
+    end: u32,
+}
+
+impl Span {
+    fn new(start: usize, end: usize) -> Self {
+        Span {
+            start: start as u32,
+            end: end as u32,
+        }
+    }
+
+    fn range(self) -> Range<usize> {
+        self.start as usize..self.end as usize
+    }
+}
+
+/// A range of bytes in the encoded data set.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct ByteSpan {
+    offset: u64,
+    len: u32,
+}
+
+impl ByteSpan {
+    fn range(self) -> Range<usize> {
+        self.offset as usize..self.offset as usize + self.len as usize
+    }
+}
+
+/// Where the contents of an element are found in the arena.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Slot {
+    /// the encoded bytes of a primitive value
+    Primitive(ByteSpan),
+    /// the items of a sequence
+    Sequence(Span),
+    /// the offset table and fragments of encapsulated pixel data
+    PixelSequence {
+        offset_table: Option<ByteSpan>,
+        fragments: Span,
+    },
+}
+
+/// An element header along with the location of its contents.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct Entry {
+    header: DataElementHeader,
+    slot: Slot,
+}
+
+/// A DICOM object stored in a single arena.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Clone)]
+pub struct ArenaDicomObject {
+    /// the file meta group
+    meta: FileMetaTable,
+    /// the character set declared in the root data set
+    charset: SpecificCharacterSet,
+    /// the encoded data set, after the file meta group
+    data: Bytes,
+    /// the elements of all data sets,
+    /// each data set in a contiguous block sorted by tag
+    entries: Vec<Entry>,
+    /// the data set blocks of all sequence items
+    items: Vec<Span>,
+    /// the fragments of all encapsulated pixel data
+    fragments: Vec<ByteSpan>,
+    /// the block of the root data set
+    root: Span,
+}
+
+impl fmt::Debug for ArenaDicomObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaDicomObject")
+            .field("meta", &self.meta)
+            .field("root", &self.root())
+            .finish()
+    }
+}
+
+impl ArenaDicomObject {
+    /// Open the DICOM file at the given path into an arena.
+    ///
+    /// This function assumes the standard file encoding structure: 128-byte
+    /// preamble, file meta group, and the rest of the data set.
+    /// See [`OpenFileOptions::open_file_arena`](crate::OpenFileOptions::open_file_arena)
+    /// for more options.
+    #[cfg(feature = "fs")]
+    pub fn open_file<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<std::path::Path>,
+    {
+        crate::OpenFileOptions::new().open_file_arena(path)
+    }
+
+    /// Read a DICOM object from an in-memory buffer into an arena.
+    ///
+    /// This method assumes
+    /// the standard file encoding structure without the preamble:
+    /// file meta group, followed by the rest of the data set.
+    pub fn from_bytes(data: Bytes) -> Result<Self> {
+        crate::OpenFileOptions::new().from_bytes_arena(data)
+    }
+
+    pub(crate) fn from_bytes_with_all_options<D>(
+        data: Bytes,
+        options: OpenFileOptions<D, TransferSyntaxRegistry>,
+    ) -> Result<Self> {
+        let mut cursor = Cursor::new(&data[..]);
+
+        if options.read_preamble == ReadPreamble::Always {
+            let mut buf = [0u8; 128];
+            // skip the preamble
+            cursor
+                .read_exact(&mut buf)
+                .context(ReadPreambleBytesSnafu)?;
+        }
+
+        // read metadata header
+        let meta = FileMetaTable::from_reader(&mut cursor).context(ParseMetaDataSetSnafu)?;
+        let data = data.slice(cursor.position() as usize..);
+
+        let ts = TransferSyntaxRegistry
+            .get(&meta.transfer_syntax)
+            .with_context(|| UnsupportedTransferSyntaxSnafu {
+                uid: meta.transfer_syntax.clone(),
+            })?;
+
+        // values are only located, not decoded,
+        // and remain slices of the shared buffer
+        let reader_options = options.reader_options().value_read(ValueReadStrategy::Raw);
+        let mut dataset = DataSetReader::new_shared_with_ts_cs_options(
+            data.clone(),
+            ts,
+            SpecificCharacterSet::Default,
+            reader_options,
+        )
+        .context(CreateParserSnafu)?;
+
+        let mut builder = ArenaBuilder {
+            strip_group_lengths: options.strip_group_lengths,
+            ..Default::default()
+        };
+        for token in dataset.positioned() {
+            builder.push(token.context(ReadTokenSnafu)?)?;
+        }
+        let (entries, items, fragments, root) = builder.finish()?;
+
+        let mut obj = ArenaDicomObject {
+            meta,
+            charset: SpecificCharacterSet::Default,
+            data,
+            entries,
+            items,
+            fragments,
+            root,
+        };
+        obj.charset = obj.root().declared_charset().unwrap_or_default();
+        Ok(obj)
+    }
+
+    /// Retrieve the processed meta header table.
+    pub fn meta(&self) -> &FileMetaTable {
+        &self.meta
+    }
+
+    /// Retrieve the root data set of the object.
+    pub fn root(&self) -> ArenaItem<'_> {
+        ArenaItem {
+            obj: self,
+            entries: &self.entries[self.root.range()],
+        }
+    }
+
+    /// Retrieve a particular DICOM element of the root data set by its tag.
+    ///
+    /// An error is returned if the element does not exist.
+    pub fn element(&self, tag: Tag) -> Result<ArenaElement<'_>> {
+        self.root().element(tag)
+    }
+
+    /// Retrieve a particular DICOM element of the root data set
+    /// that might not exist by its tag.
+    pub fn element_opt(&self, tag: Tag) -> Option<ArenaElement<'_>> {
+        self.root().element_opt(tag)
+    }
+
+    /// Obtain an iterator over the elements of the root data set,
+    /// in ascending tag order.
+    pub fn iter(&self) -> impl Iterator<Item = ArenaElement<'_>> + '_ {
+        self.root().iter()
+    }
+
+    /// Retrieve the total number of elements in the object,
+    /// including the elements in nested data sets.
+    pub fn element_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Decode all elements of the object
+    /// into a new file DICOM object fully contained in memory.
+    pub fn to_in_mem(&self) -> Result<DefaultDicomObject> {
+        Ok(FileDicomObject {
+            meta: self.meta.clone(),
+            obj: self.root().to_in_mem()?,
+        })
+    }
+
+    /// Decode the primitive value of the given element.
+    fn decode_value(&self, header: &DataElementHeader, span: ByteSpan) -> Result<PrimitiveValue> {
+        let ts = TransferSyntaxRegistry
+            .get(&self.meta.transfer_syntax)
+            .with_context(|| UnsupportedTransferSyntaxSnafu {
+                uid: self.meta.transfer_syntax.clone(),
+            })?;
+        let header = DataElementHeader::new(header.tag, header.vr, Length(span.len));
+        let data = self.data.slice(span.range());
+        let mut decoder =
+            DynStatefulDecoder::<Cursor<Bytes>>::new_shared_with(data, ts, self.charset, 0)
+                .context(DecodeValueSnafu { tag: header.tag })?;
+        decoder
+            .read_value_preserved(&header)
+            .context(DecodeValueSnafu { tag: header.tag })
+    }
+}
+
+/// A data set in an [`ArenaDicomObject`],
+/// either the root data set or an item of a sequence.
+#[derive(Copy, Clone)]
+pub struct ArenaItem<'a> {
+    obj: &'a ArenaDicomObject,
+    entries: &'a [Entry],
+}
+
+impl fmt::Debug for ArenaItem<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a> ArenaItem<'a> {
+    /// Retrieve a particular DICOM element by its tag.
+    ///
+    /// An error is returned if the element does not exist.
+    pub fn element(&self, tag: Tag) -> Result<ArenaElement<'a>> {
+        self.element_opt(tag)
+            .context(NoSuchDataElementTagSnafu { tag })
+    }
+
+    /// Retrieve a particular DICOM element that might not exist by its tag.
+    pub fn element_opt(&self, tag: Tag) -> Option<ArenaElement<'a>> {
+        self.entries
+            .binary_search_by_key(&tag, |entry| entry.header.tag)
+            .ok()
+            .map(|i| ArenaElement {
+                obj: self.obj,
+                entry: &self.entries[i],
+            })
+    }
+
+    /// Obtain an iterator over the elements of the data set,
+    /// in ascending tag order.
+    pub fn iter(&self) -> impl Iterator<Item = ArenaElement<'a>> + 'a {
+        let obj = self.obj;
+        self.entries
+            .iter()
+            .map(move |entry| ArenaElement { obj, entry })
+    }
+
+    /// Retrieve the number of elements in the data set,
+    /// not including the elements in nested data sets.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check whether the data set has no elements.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Decode all elements of the data set
+    /// into a new DICOM object fully contained in memory.
+    pub fn to_in_mem(&self) -> Result<InMemDicomObject> {
+        let elements = self
+            .iter()
+            .map(|elem| elem.to_in_mem())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(InMemDicomObject::from_element_iter(elements))
+    }
+
+    /// Retrieve the character set declared by
+    /// the _Specific Character Set_ attribute of this data set, if any.
+    fn declared_charset(&self) -> Option<SpecificCharacterSet> {
+        let elem = self.element_opt(tags::SPECIFIC_CHARACTER_SET)?;
+        let code = std::str::from_utf8(elem.raw_value()?).ok()?;
+        let code = code.split('\\').next()?;
+        SpecificCharacterSet::from_code(code.trim_matches(|c: char| c == ' ' || c == '\0'))
+    }
+}
+
+/// A data element in an [`ArenaDicomObject`].
+///
+/// The value of the element is decoded on demand.
+#[derive(Copy, Clone)]
+pub struct ArenaElement<'a> {
+    obj: &'a ArenaDicomObject,
+    entry: &'a Entry,
+}
+
+impl fmt::Debug for ArenaElement<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArenaElement")
+            .field("header", &self.entry.header)
+            .finish()
+    }
+}
+
+impl HasLength for ArenaElement<'_> {
+    fn length(&self) -> Length {
+        self.entry.header.len
+    }
+}
+
+impl Header for ArenaElement<'_> {
+    fn tag(&self) -> Tag {
+        self.entry.header.tag
+    }
+}
+
+impl<'a> ArenaElement<'a> {
+    /// Retrieve the element's header.
+    pub fn header(&self) -> &'a DataElementHeader {
+        &self.entry.header
+    }
+
+    /// Retrieve the element's value representation.
+    pub fn vr(&self) -> VR {
+        self.entry.header.vr
+    }
+
+    /// Retrieve the encoded bytes of the element's primitive value,
+    /// exactly as found in the source.
+    ///
+    /// Returns `None` if the element is a sequence
+    /// or encapsulated pixel data.
+    pub fn raw_value(&self) -> Option<&'a [u8]> {
+        match self.entry.slot {
+            Slot::Primitive(span) => Some(&self.obj.data[span.range()]),
+            _ => None,
+        }
+    }
+
+    /// Decode the element's primitive value.
+    ///
+    /// Text is decoded according to
+    /// the character set declared in the root data set,
+    /// while dates, times, and numbers in text form
+    /// are kept in their original representation.
+    /// Sequences and encapsulated pixel data have no primitive value,
+    /// so an empty value is returned for them.
+    pub fn value(&self) -> Result<PrimitiveValue> {
+        match self.entry.slot {
+            Slot::Primitive(span) => self.obj.decode_value(&self.entry.header, span),
+            _ => Ok(PrimitiveValue::Empty),
+        }
+    }
+
+    /// Obtain an iterator over the items of the element,
+    /// if it is a sequence.
+    pub fn items(&self) -> Option<impl Iterator<Item = ArenaItem<'a>> + 'a> {
+        let obj = self.obj;
+        match self.entry.slot {
+            Slot::Sequence(items) => {
+                Some(obj.items[items.range()].iter().map(move |span| ArenaItem {
+                    obj,
+                    entries: &obj.entries[span.range()],
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    /// Obtain an iterator over the compressed fragments of the element,
+    /// if it is encapsulated pixel data.
+    pub fn fragments(&self) -> Option<impl Iterator<Item = &'a [u8]> + 'a> {
+        let obj = self.obj;
+        match self.entry.slot {
+            Slot::PixelSequence { fragments, .. } => Some(
+                obj.fragments[fragments.range()]
+                    .iter()
+                    .map(move |span| &obj.data[span.range()]),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the basic offset table of the element,
+    /// if it is encapsulated pixel data.
+    pub fn offset_table(&self) -> Option<C<u32>> {
+        match self.entry.slot {
+            Slot::PixelSequence { offset_table, .. } => Some(
+                offset_table
+                    .map(|span| {
+                        // the offset table is always in little endian
+                        self.obj.data[span.range()]
+                            .chunks_exact(4)
+                            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Decode the element and its nested data sets
+    /// into a new data element fully contained in memory.
+    pub fn to_in_mem(&self) -> Result<InMemElement> {
+        let header = self.entry.header;
+        match self.entry.slot {
+            Slot::Primitive(span) => Ok(DataElement::new_with_len(
+                header.tag,
+                header.vr,
+                header.len,
+                Value::Primitive(self.obj.decode_value(&header, span)?),
+            )),
+            Slot::Sequence(_) => {
+                let items = self
+                    .items()
+                    .into_iter()
+                    .flatten()
+                    .map(|item| item.to_in_mem())
+                    .collect::<Result<C<_>>>()?;
+                Ok(DataElement::new_with_len(
+                    header.tag,
+                    VR::SQ,
+                    header.len,
+                    Value::Sequence {
+                        items,
+                        size: header.len,
+                    },
+                ))
+            }
+            Slot::PixelSequence { .. } => {
+                let fragments: C<InMemFragment> = self
+                    .fragments()
+                    .into_iter()
+                    .flatten()
+                    .map(|fragment| fragment.to_vec())
+                    .collect();
+                Ok(DataElement::new(
+                    header.tag,
+                    header.vr,
+                    Value::PixelSequence {
+                        offset_table: self.offset_table().unwrap_or_default(),
+                        fragments,
+                    },
+                ))
+            }
+        }
+    }
+}
+
+/// A nesting level which is still being read.
+#[derive(Debug, Copy, Clone)]
+enum Frame {
+    /// an item, whose elements start at `mark` in the pending entries
+    Item { mark: usize },
+    /// a sequence, whose items start at `mark` in the pending items
+    Sequence { tag: Tag, len: Length, mark: usize },
+    /// encapsulated pixel data,
+    /// whose fragments start at `mark` in the pending fragments
+    PixelSequence {
+        mark: usize,
+        offset_table: Option<ByteSpan>,
+    },
+}
+
+/// The state for building an arena from a sequence of data set tokens.
+///
+/// The elements of the data sets still being read are kept in a stack,
+/// and moved to the arena as a single block once their data set ends,
+/// so that every data set ends up contiguous.
+#[derive(Debug, Default)]
+struct ArenaBuilder {
+    strip_group_lengths: bool,
+    entries: Vec<Entry>,
+    items: Vec<Span>,
+    fragments: Vec<ByteSpan>,
+    pending_entries: Vec<Entry>,
+    pending_items: Vec<Span>,
+    pending_fragments: Vec<ByteSpan>,
+    frames: Vec<Frame>,
+    header: Option<DataElementHeader>,
+}
+
+impl ArenaBuilder {
+    fn push(&mut self, token: PositionedToken) -> Result<()> {
+        let span = ByteSpan {
+            offset: token.offset,
+            len: token.len as u32,
+        };
+        match (token.token, self.frames.last().copied()) {
+            (DataToken::ElementHeader(header), _) if self.header.is_none() => {
+                self.header = Some(header);
+            }
+            (DataToken::PrimitiveValue(_), _) if self.header.is_some() => {
+                let header = self.header.take().context(MissingElementValueSnafu)?;
+                let is_group_length = header.tag.element() == 0 && header.tag.group() != 0x0002;
+                if !(self.strip_group_lengths && is_group_length) {
+                    self.pending_entries.push(Entry {
+                        header,
+                        slot: Slot::Primitive(span),
+                    });
+                }
+            }
+            (DataToken::SequenceStart { tag, len }, _) => {
+                self.frames.push(Frame::Sequence {
+                    tag,
+                    len,
+                    mark: self.pending_items.len(),
+                });
+            }
+            (DataToken::PixelSequenceStart, _) => {
+                self.frames.push(Frame::PixelSequence {
+                    mark: self.pending_fragments.len(),
+                    offset_table: None,
+                });
+            }
+            (DataToken::ItemStart { .. }, Some(Frame::Sequence { .. })) => {
+                self.frames.push(Frame::Item {
+                    mark: self.pending_entries.len(),
+                });
+            }
+            (DataToken::ItemStart { .. }, Some(Frame::PixelSequence { .. }))
+            | (DataToken::ItemEnd, Some(Frame::PixelSequence { .. })) => { /* no-op */ }
+            (DataToken::ItemEnd, Some(Frame::Item { mark })) => {
+                self.frames.pop();
+                let item = self.close_data_set(mark);
+                self.pending_items.push(item);
+            }
+            (DataToken::OffsetTable(_), Some(Frame::PixelSequence { .. })) => {
+                if let Some(Frame::PixelSequence { offset_table, .. }) = self.frames.last_mut() {
+                    *offset_table = Some(span);
+                }
+            }
+            (DataToken::ItemValue(_), Some(Frame::PixelSequence { .. })) => {
+                self.pending_fragments.push(span);
+            }
+            (DataToken::SequenceEnd, Some(Frame::Sequence { tag, len, mark })) => {
+                self.frames.pop();
+                let start = self.items.len();
+                self.items.extend(self.pending_items.drain(mark..));
+                self.pending_entries.push(Entry {
+                    header: DataElementHeader::new(tag, VR::SQ, len),
+                    slot: Slot::Sequence(Span::new(start, self.items.len())),
+                });
+            }
+            (DataToken::SequenceEnd, Some(Frame::PixelSequence { mark, offset_table })) => {
+                self.frames.pop();
+                let start = self.fragments.len();
+                self.fragments.extend(self.pending_fragments.drain(mark..));
+                self.pending_entries.push(Entry {
+                    header: DataElementHeader::new(tags::PIXEL_DATA, VR::OB, Length::UNDEFINED),
+                    slot: Slot::PixelSequence {
+                        offset_table,
+                        fragments: Span::new(start, self.fragments.len()),
+                    },
+                });
+            }
+            (token, _) => return UnexpectedTokenSnafu { token }.fail(),
+        }
+        Ok(())
+    }
+
+    /// Move the pending elements starting at `mark` to the arena,
+    /// sorted by tag.
+    fn close_data_set(&mut self, mark: usize) -> Span {
+        let pending = &mut self.pending_entries[mark..];
+        if !pending
+            .windows(2)
+            .all(|w| w[0].header.tag <= w[1].header.tag)
+        {
+            pending.sort_by_key(|entry| entry.header.tag);
+        }
+        let start = self.entries.len();
+        self.entries.extend(self.pending_entries.drain(mark..));
+        Span::new(start, self.entries.len())
+    }
+
+    fn finish(mut self) -> Result<(Vec<Entry>, Vec<Span>, Vec<ByteSpan>, Span)> {
+        ensure!(self.frames.is_empty(), PrematureEndSnafu);
+        ensure!(self.header.is_none(), MissingElementValueSnafu);
+        let root = self.close_data_set(0);
+        Ok((self.entries, self.items, self.fragments, root))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileMetaTableBuilder, OpenFileOptions};
+    use smallvec::smallvec;
+
+    fn sample_file(ts: &str) -> Bytes {
+        let item = |value: &str| {
+            InMemDicomObject::from_element_iter([
+                DataElement::new(tags::CODE_VALUE, VR::SH, PrimitiveValue::from(value)),
+                DataElement::new(
+                    tags::CODING_SCHEME_DESIGNATOR,
+                    VR::SH,
+                    PrimitiveValue::from("DCM"),
+                ),
+            ])
+        };
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SPECIFIC_CHARACTER_SET,
+                VR::CS,
+                PrimitiveValue::from("ISO_IR 100"),
+            ),
+            DataElement::new(
+                tags::PATIENT_NAME,
+                VR::PN,
+                PrimitiveValue::from("Müller^José"),
+            ),
+            DataElement::new(tags::STUDY_DATE, VR::DA, PrimitiveValue::from("20221003")),
+            DataElement::new(
+                tags::CONCEPT_NAME_CODE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: smallvec![item("121071"), item("121072")],
+                    size: Length::UNDEFINED,
+                },
+            ),
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(64_u16)),
+            DataElement::new(
+                tags::PIXEL_DATA,
+                VR::OB,
+                Value::PixelSequence {
+                    offset_table: smallvec![0],
+                    fragments: smallvec![vec![1, 2, 3, 4]],
+                },
+            ),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .transfer_syntax(ts)
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .media_storage_sop_instance_uid("2.25.1.2.3"),
+        )
+        .unwrap();
+
+        let mut data = Vec::new();
+        obj.write_all(&mut data).unwrap();
+        // skip the preamble
+        Bytes::from(data).slice(128..)
+    }
+
+    #[test]
+    fn read_arena_object() {
+        // Explicit VR Little Endian
+        let obj = ArenaDicomObject::from_bytes(sample_file("1.2.840.10008.1.2.1")).unwrap();
+
+        assert_eq!(
+            obj.meta()
+                .media_storage_sop_instance_uid
+                .trim_end_matches('\0'),
+            "2.25.1.2.3"
+        );
+        assert_eq!(obj.root().len(), 6);
+        // 6 root elements and 2 elements in each of the 2 items
+        assert_eq!(obj.element_count(), 10);
+
+        let name = obj.element(tags::PATIENT_NAME).unwrap();
+        assert_eq!(name.vr(), VR::PN);
+        assert_eq!(name.value().unwrap().to_str(), "Müller^José");
+        assert_eq!(
+            obj.element(tags::STUDY_DATE).unwrap().raw_value(),
+            Some(&b"20221003"[..])
+        );
+        assert_eq!(
+            obj.element(tags::ROWS).unwrap().value().unwrap(),
+            PrimitiveValue::from(64_u16)
+        );
+        assert!(obj.element_opt(tags::MODALITY).is_none());
+        assert!(obj.element(tags::MODALITY).is_err());
+
+        let codes: Vec<_> = obj
+            .element(tags::CONCEPT_NAME_CODE_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap()
+            .map(|item| {
+                let code = item.element(tags::CODE_VALUE).unwrap().value().unwrap();
+                code.to_str().into_owned()
+            })
+            .collect();
+        assert_eq!(codes, vec!["121071", "121072"]);
+
+        let pixel_data = obj.element(tags::PIXEL_DATA).unwrap();
+        assert_eq!(pixel_data.offset_table().unwrap().as_slice(), &[0]);
+        let fragments: Vec<_> = pixel_data.fragments().unwrap().collect();
+        assert_eq!(fragments, vec![&[1_u8, 2, 3, 4][..]]);
+    }
+
+    #[test]
+    fn arena_object_to_in_mem() {
+        // Explicit VR Little Endian and Implicit VR Little Endian
+        for ts in ["1.2.840.10008.1.2.1", "1.2.840.10008.1.2"] {
+            let data = sample_file(ts);
+            let arena = ArenaDicomObject::from_bytes(data.clone()).unwrap();
+            let obj = OpenFileOptions::new().from_bytes(data).unwrap();
+
+            let converted = arena.to_in_mem().unwrap();
+            assert_eq!(converted.meta(), obj.meta());
+            assert_data_set_eq(&converted, &obj);
+        }
+    }
+
+    /// Compare two data sets element by element,
+    /// since sequences of undefined length never compare equal.
+    fn assert_data_set_eq(obj1: &InMemDicomObject, obj2: &InMemDicomObject) {
+        assert_eq!(obj1.length().get(), obj2.length().get());
+        assert_eq!(
+            obj1.tags().collect::<Vec<_>>(),
+            obj2.tags().collect::<Vec<_>>()
+        );
+        for (elem1, elem2) in obj1.iter().zip(obj2.iter()) {
+            let tag = elem1.tag();
+            assert_eq!(elem1.vr(), elem2.vr(), "VR of {}", tag);
+            assert_eq!(
+                elem1.length().get(),
+                elem2.length().get(),
+                "length of {}",
+                tag
+            );
+            match (elem1.value(), elem2.value()) {
+                (Value::Sequence { items: items1, .. }, Value::Sequence { items: items2, .. }) => {
+                    assert_eq!(items1.len(), items2.len(), "items of {}", tag);
+                    for (item1, item2) in items1.iter().zip(items2.iter()) {
+                        assert_data_set_eq(item1, item2);
+                    }
+                }
+                (value1, value2) => assert_eq!(value1, value2, "value of {}", tag),
+            }
+        }
+    }
+}
@@ -0,0 +1,309 @@
+//! Coded concepts and code sequences.
+//!
+//! Many DICOM attributes identify a concept by a code
+//! from a coding scheme, encoded as a sequence item
+//! following the _Code Sequence Macro_ (standard, part 3, section 8.8).
+//! [`CodedConcept`] holds such a code,
+//! and can be read from and written to code sequence items,
+//! as found in structured reports, segmentations,
+//! and protocol related attributes.
+//!
+//! # Example
+//!
+//! ```
+//! use dicom_dictionary_std::tags;
+//! use dicom_object::code::CodedConcept;
+//! use dicom_object::InMemDicomObject;
+//!
+//! let lesion = CodedConcept::new("52988006", "SCT", "Lesion");
+//! let mut obj = InMemDicomObject::new_empty();
+//! obj.put(lesion.to_sequence_element(tags::CONCEPT_CODE_SEQUENCE));
+//!
+//! let code = CodedConcept::from_sequence(&obj, tags::CONCEPT_CODE_SEQUENCE).unwrap();
+//! assert!(code.matches("52988006", "SCT"));
+//! assert_eq!(code.meaning, "Lesion");
+//! ```
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use std::fmt;
+
+use crate::mem::InMemElement;
+use crate::InMemDicomObject;
+
+/// The maximum length of a value in _Code Value_ (SH).
+const MAX_CODE_VALUE_LENGTH: usize = 16;
+
+/// A coded concept,
+/// as described by the _Code Sequence Macro_
+/// (standard, part 3, section 8.8).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CodedConcept {
+    /// The code value,
+    /// taken from _Code Value_ (0008,0100),
+    /// _Long Code Value_ (0008,0119),
+    /// or _URN Code Value_ (0008,0120).
+    pub value: String,
+    /// The coding scheme designator (0008,0102), such as `DCM` or `SCT`.
+    /// Empty for codes given by URN.
+    pub scheme_designator: String,
+    /// The human readable meaning of the code (0008,0104).
+    pub meaning: String,
+    /// The coding scheme version (0008,0103), if present.
+    pub version: Option<String>,
+}
+
+impl CodedConcept {
+    /// Create a coded concept from its code value,
+    /// coding scheme designator, and code meaning.
+    pub fn new(
+        value: impl Into<String>,
+        scheme_designator: impl Into<String>,
+        meaning: impl Into<String>,
+    ) -> Self {
+        CodedConcept {
+            value: value.into(),
+            scheme_designator: scheme_designator.into(),
+            meaning: meaning.into(),
+            version: None,
+        }
+    }
+
+    /// Set the coding scheme version of this concept.
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    /// Check whether this concept has the given code value
+    /// and coding scheme designator.
+    pub fn matches(&self, value: &str, scheme_designator: &str) -> bool {
+        self.value == value && self.scheme_designator == scheme_designator
+    }
+
+    /// Read a coded concept from a code sequence item.
+    ///
+    /// The code value is taken from _Code Value_,
+    /// _Long Code Value_, or _URN Code Value_, in this order.
+    /// Returns `None` if the item has no code value,
+    /// or if it has no coding scheme designator
+    /// while the code is not given by URN.
+    pub fn from_item<D>(item: &InMemDicomObject<D>) -> Option<Self>
+    where
+        D: DataDictionary + Clone,
+    {
+        let (value, scheme_designator) = match opt_string(item, tags::CODE_VALUE)
+            .or_else(|| opt_string(item, tags::LONG_CODE_VALUE))
+        {
+            Some(value) => (value, opt_string(item, tags::CODING_SCHEME_DESIGNATOR)?),
+            None => (opt_string(item, tags::URN_CODE_VALUE)?, String::new()),
+        };
+        Some(CodedConcept {
+            value,
+            scheme_designator,
+            meaning: opt_string(item, tags::CODE_MEANING).unwrap_or_default(),
+            version: opt_string(item, tags::CODING_SCHEME_VERSION),
+        })
+    }
+
+    /// Read the coded concept in the first item
+    /// of the code sequence with the given tag.
+    ///
+    /// Returns `None` if the sequence is missing or empty,
+    /// or if its first item is not a valid code.
+    pub fn from_sequence<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<Self>
+    where
+        D: DataDictionary + Clone,
+    {
+        obj.element_opt(tag)
+            .ok()
+            .flatten()
+            .and_then(|e| e.items())
+            .and_then(|items| items.first())
+            .and_then(CodedConcept::from_item)
+    }
+
+    /// Read all coded concepts
+    /// in the code sequence with the given tag.
+    ///
+    /// Items which are not valid codes are skipped.
+    pub fn all_from_sequence<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Vec<Self>
+    where
+        D: DataDictionary + Clone,
+    {
+        obj.element_opt(tag)
+            .ok()
+            .flatten()
+            .and_then(|e| e.items())
+            .map(|items| items.iter().filter_map(CodedConcept::from_item).collect())
+            .unwrap_or_default()
+    }
+
+    /// Create a code sequence item with this concept.
+    ///
+    /// Code values longer than 16 characters
+    /// are written to _Long Code Value_,
+    /// and concepts without a coding scheme designator
+    /// are written to _URN Code Value_.
+    pub fn to_item(&self) -> InMemDicomObject {
+        let mut item = InMemDicomObject::new_empty();
+        if self.scheme_designator.is_empty() {
+            item.put(DataElement::new(
+                tags::URN_CODE_VALUE,
+                VR::UR,
+                PrimitiveValue::from(self.value.as_str()),
+            ));
+        } else {
+            if self.value.len() > MAX_CODE_VALUE_LENGTH {
+                item.put(DataElement::new(
+                    tags::LONG_CODE_VALUE,
+                    VR::UC,
+                    PrimitiveValue::from(self.value.as_str()),
+                ));
+            } else {
+                item.put(DataElement::new(
+                    tags::CODE_VALUE,
+                    VR::SH,
+                    PrimitiveValue::from(self.value.as_str()),
+                ));
+            }
+            item.put(DataElement::new(
+                tags::CODING_SCHEME_DESIGNATOR,
+                VR::SH,
+                PrimitiveValue::from(self.scheme_designator.as_str()),
+            ));
+        }
+        if let Some(version) = &self.version {
+            item.put(DataElement::new(
+                tags::CODING_SCHEME_VERSION,
+                VR::SH,
+                PrimitiveValue::from(version.as_str()),
+            ));
+        }
+        item.put(DataElement::new(
+            tags::CODE_MEANING,
+            VR::LO,
+            PrimitiveValue::from(self.meaning.as_str()),
+        ));
+        item
+    }
+
+    /// Create a code sequence element with the given tag
+    /// holding a single item with this concept.
+    pub fn to_sequence_element(&self, tag: Tag) -> InMemElement {
+        code_sequence_element(tag, std::slice::from_ref(self))
+    }
+}
+
+impl fmt::Display for CodedConcept {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "({}, {}, \"{}\")",
+            self.value, self.scheme_designator, self.meaning
+        )
+    }
+}
+
+/// Create a code sequence element with the given tag
+/// holding one item per coded concept.
+pub fn code_sequence_element(tag: Tag, concepts: &[CodedConcept]) -> InMemElement {
+    DataElement::new(
+        tag,
+        VR::SQ,
+        Value::Sequence {
+            items: concepts.iter().map(CodedConcept::to_item).collect::<C<_>>(),
+            size: Length::UNDEFINED,
+        },
+    )
+}
+
+fn opt_string<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<String>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag)
+        .ok()
+        .flatten()
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn code_item_roundtrip() {
+        let code = CodedConcept::new("121071", "DCM", "Finding").with_version("01");
+        let item = code.to_item();
+        assert_eq!(
+            item.element(tags::CODE_VALUE).unwrap().to_str().unwrap(),
+            "121071"
+        );
+        assert_eq!(
+            item.element(tags::CODING_SCHEME_VERSION)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "01"
+        );
+        assert_eq!(CodedConcept::from_item(&item), Some(code));
+    }
+
+    #[test]
+    fn long_and_urn_code_values() {
+        let long = CodedConcept::new("a-code-value-over-16-chars", "99TEST", "Long");
+        let item = long.to_item();
+        assert!(item.element_opt(tags::CODE_VALUE).unwrap().is_none());
+        assert!(item.element_opt(tags::LONG_CODE_VALUE).unwrap().is_some());
+        assert_eq!(CodedConcept::from_item(&item), Some(long));
+
+        let urn = CodedConcept::new("urn:example:concept", "", "By URN");
+        let item = urn.to_item();
+        assert!(item
+            .element_opt(tags::CODING_SCHEME_DESIGNATOR)
+            .unwrap()
+            .is_none());
+        assert_eq!(CodedConcept::from_item(&item), Some(urn));
+    }
+
+    #[test]
+    fn code_without_scheme_is_rejected() {
+        let mut item = InMemDicomObject::new_empty();
+        item.put(DataElement::new(
+            tags::CODE_VALUE,
+            VR::SH,
+            PrimitiveValue::from("T-D0050"),
+        ));
+        assert_eq!(CodedConcept::from_item(&item), None);
+    }
+
+    #[test]
+    fn code_sequences() {
+        let codes = vec![
+            CodedConcept::new("T-D0050", "SRT", "Tissue"),
+            CodedConcept::new("85756007", "SCT", "Tissue"),
+        ];
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(code_sequence_element(
+            tags::SEGMENTED_PROPERTY_CATEGORY_CODE_SEQUENCE,
+            &codes,
+        ));
+
+        assert_eq!(
+            CodedConcept::from_sequence(&obj, tags::SEGMENTED_PROPERTY_CATEGORY_CODE_SEQUENCE),
+            Some(codes[0].clone())
+        );
+        assert_eq!(
+            CodedConcept::all_from_sequence(&obj, tags::SEGMENTED_PROPERTY_CATEGORY_CODE_SEQUENCE),
+            codes
+        );
+        assert_eq!(
+            CodedConcept::from_sequence(&obj, tags::CONCEPT_CODE_SEQUENCE),
+            None
+        );
+        assert_eq!(codes[1].to_string(), "(85756007, SCT, \"Tissue\")");
+    }
+}
@@ -0,0 +1,448 @@
+//! Streaming copy of DICOM files.
+//!
+//! [`copy_object`] reads a DICOM file from a byte source
+//! and writes it into a byte sink token by token,
+//! without ever building an in-memory DICOM object.
+//! Only one element value is held in memory at a time,
+//! so that arbitrarily large files can be rewritten
+//! with a constant memory footprint.
+//! A [token filter](dicom_parser::dataset::filter) can be set in [`CopyOptions`]
+//! to change or drop parts of the data set along the way.
+//!
+//! # Example
+//!
+//! Replace the patient name of a file:
+//!
+//! ```no_run
+//! # use dicom_core::dicom_value;
+//! # use dicom_dictionary_std::tags;
+//! # use dicom_object::copy::{copy_file, CopyOptions};
+//! # use dicom_parser::dataset::filter::MapValues;
+//! let options = CopyOptions::new().filter(MapValues::new(|header, value| {
+//!     if header.tag == tags::PATIENT_NAME {
+//!         dicom_value!(Str, "Anonymous")
+//!     } else {
+//!         value
+//!     }
+//! }));
+//! copy_file("big.dcm", "big_anonymous.dcm", options)?;
+//! # Result::<(), dicom_object::Error>::Ok(())
+//! ```
+use crate::file::{ReadPreamble, DEFAULT_READ_BUFFER_CAPACITY};
+use crate::meta::FileMetaTable;
+use crate::{
+    CreateParserSnafu, CreatePrinterSnafu, ParseMetaDataSetSnafu, PrintDataSetSnafu,
+    PrintMetaDataSetSnafu, ReadPreambleBytesSnafu, ReadTokenSnafu, Result, TranscodeRequiredSnafu,
+    UnsupportedTransferSyntaxSnafu, WriteMagicCodeSnafu, WritePreambleSnafu,
+};
+#[cfg(feature = "fs")]
+use crate::{OpenFileSnafu, WriteFileSnafu};
+use dicom_encoding::{text::SpecificCharacterSet, transfer_syntax::TransferSyntaxIndex};
+use dicom_parser::dataset::filter::{FilterMap, TokenFilter, UndefinedLengths};
+pub use dicom_parser::dataset::read::{ParseMode, TrailingPaddingStrategy};
+use dicom_parser::dataset::{read::DataSetReaderOptions, DataSetReader, DataSetWriter, DataToken};
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use snafu::{ensure, OptionExt, ResultExt};
+use std::fmt;
+#[cfg(feature = "fs")]
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+/// A builder type for the options of a streaming copy.
+///
+/// # Example
+///
+/// ```no_run
+/// # use dicom_object::copy::{copy_object, CopyOptions};
+/// # use dicom_object::file::ReadPreamble;
+/// # fn run(source: &[u8], sink: &mut Vec<u8>) -> Result<(), dicom_object::Error> {
+/// let options = CopyOptions::new()
+///     .read_preamble(ReadPreamble::Always)
+///     // Implicit VR Little Endian
+///     .transfer_syntax("1.2.840.10008.1.2");
+/// copy_object(source, sink, options)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct CopyOptions<'a> {
+    read_preamble: ReadPreamble,
+    write_preamble: bool,
+    trailing_padding: TrailingPaddingStrategy,
+    parse_mode: ParseMode,
+    transfer_syntax: Option<String>,
+    read_buffer_capacity: Option<usize>,
+    filter: Option<Box<dyn TokenFilter + 'a>>,
+}
+
+impl fmt::Debug for CopyOptions<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CopyOptions")
+            .field("read_preamble", &self.read_preamble)
+            .field("write_preamble", &self.write_preamble)
+            .field("trailing_padding", &self.trailing_padding)
+            .field("parse_mode", &self.parse_mode)
+            .field("transfer_syntax", &self.transfer_syntax)
+            .field("read_buffer_capacity", &self.read_buffer_capacity)
+            .field("filter", &self.filter.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl<'a> CopyOptions<'a> {
+    pub fn new() -> Self {
+        CopyOptions {
+            write_preamble: true,
+            ..Default::default()
+        }
+    }
+
+    /// Set whether to read the 128-byte DICOM file preamble
+    /// from the source.
+    ///
+    /// Like when [reading from a byte source](crate::OpenFileOptions::from_reader),
+    /// [`ReadPreamble::Auto`] does not read the preamble,
+    /// unless the copy is made through [`copy_file`].
+    pub fn read_preamble(mut self, option: ReadPreamble) -> Self {
+        self.read_preamble = option;
+        self
+    }
+
+    /// Set whether to write a 128-byte DICOM file preamble
+    /// before the magic code.
+    ///
+    /// The preamble written is always filled with zeros.
+    /// By default, the preamble is written.
+    pub fn write_preamble(mut self, write: bool) -> Self {
+        self.write_preamble = write;
+        self
+    }
+
+    /// Set how to handle the data set trailing padding element (FFFC,FFFC).
+    ///
+    /// By default, the element is kept in the output.
+    pub fn trailing_padding(mut self, option: TrailingPaddingStrategy) -> Self {
+        self.trailing_padding = option;
+        self
+    }
+
+    /// Set the parsing mode, which determines how violations of the standard
+    /// in the source data set are handled.
+    ///
+    /// By default, the data set is parsed in [strict](ParseMode::Strict) mode.
+    pub fn parse_mode(mut self, option: ParseMode) -> Self {
+        self.parse_mode = option;
+        self
+    }
+
+    /// Set the transfer syntax of the output.
+    ///
+    /// Since no pixel data is transcoded in a streaming copy,
+    /// the transfer syntax can only be changed
+    /// if both the source and the target transfer syntaxes
+    /// are native (not encapsulated).
+    ///
+    /// By default, the output has the same transfer syntax as the source.
+    pub fn transfer_syntax<T>(mut self, uid: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.transfer_syntax = Some(uid.into());
+        self
+    }
+
+    /// Set the capacity of the buffers
+    /// in between the byte source and the parser,
+    /// and in between the printer and the byte sink,
+    /// in bytes.
+    ///
+    /// The default is 8 KiB.
+    pub fn read_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.read_buffer_capacity = Some(capacity);
+        self
+    }
+
+    /// Set a token filter to apply to the data set
+    /// before it is written.
+    ///
+    /// Element header lengths are recomputed from the values written,
+    /// and sequences and items are written with undefined length,
+    /// so that values can be freely replaced.
+    /// Pixel data fragments keep their length, however,
+    /// so replacing a fragment must not change its length.
+    ///
+    /// Setting a filter replaces any filter previously set.
+    /// Use [`then`](TokenFilter::then) to combine several filters.
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: TokenFilter + 'a,
+    {
+        self.filter = Some(Box::new(filter));
+        self
+    }
+
+    /// Set a function to apply to every data set token
+    /// before it is written.
+    ///
+    /// The function may return the token as is,
+    /// return a different token to replace it,
+    /// or return `None` to drop it.
+    /// It is up to the function to keep the token stream well formed,
+    /// such as by dropping whole elements or sequences
+    /// rather than part of them.
+    /// This is equivalent to setting a [`FilterMap`] filter.
+    pub fn map_tokens<F>(self, map: F) -> Self
+    where
+        F: FnMut(DataToken) -> Option<DataToken> + 'a,
+    {
+        self.filter(FilterMap::new(map))
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        self.read_buffer_capacity
+            .unwrap_or(DEFAULT_READ_BUFFER_CAPACITY)
+    }
+
+    fn reader_options(&self) -> DataSetReaderOptions {
+        DataSetReaderOptions::default()
+            .trailing_padding(self.trailing_padding)
+            .parse_mode(self.parse_mode)
+    }
+}
+
+/// Copy a DICOM file from a byte source into a byte sink,
+/// streaming its data set tokens without building a DICOM object.
+///
+/// The file meta group is read from `reader` as a whole,
+/// and written back to `writer`
+/// with the transfer syntax possibly replaced.
+/// The rest of the data set is then passed through one token at a time,
+/// going through the token function set in the options, if any.
+pub fn copy_object<R, W>(reader: R, writer: W, mut options: CopyOptions<'_>) -> Result<()>
+where
+    R: Read,
+    W: Write,
+{
+    let mut reader = BufReader::with_capacity(options.buffer_capacity(), reader);
+    let mut writer = BufWriter::with_capacity(options.buffer_capacity(), writer);
+
+    if options.read_preamble == ReadPreamble::Always {
+        let mut buf = [0u8; 128];
+        // skip the preamble
+        reader
+            .read_exact(&mut buf)
+            .context(ReadPreambleBytesSnafu)?;
+    }
+
+    // read and adjust the file meta group
+    let mut meta = FileMetaTable::from_reader(&mut reader).context(ParseMetaDataSetSnafu)?;
+    let registry = TransferSyntaxRegistry::default();
+    let source_ts =
+        registry
+            .get(&meta.transfer_syntax)
+            .with_context(|| UnsupportedTransferSyntaxSnafu {
+                uid: meta.transfer_syntax.clone(),
+            })?;
+    let target_ts = match &options.transfer_syntax {
+        Some(uid) => {
+            let target_ts = registry
+                .get(uid)
+                .with_context(|| UnsupportedTransferSyntaxSnafu { uid: uid.clone() })?;
+            if target_ts.uid() != source_ts.uid() {
+                ensure!(
+                    source_ts.is_codec_free() && target_ts.is_codec_free(),
+                    TranscodeRequiredSnafu {
+                        from: source_ts.uid(),
+                        to: target_ts.uid(),
+                    }
+                );
+                meta.transfer_syntax = crate::meta::ui_padded(target_ts.uid());
+                meta.update_information_group_length()
+                    .context(PrintMetaDataSetSnafu)?;
+            }
+            target_ts
+        }
+        None => source_ts,
+    };
+
+    // write preamble, magic code, and file meta group
+    if options.write_preamble {
+        writer
+            .write_all(&[0_u8; 128][..])
+            .context(WritePreambleSnafu)?;
+    }
+    writer.write_all(b"DICM").context(WriteMagicCodeSnafu)?;
+    meta.write(&mut writer).context(PrintMetaDataSetSnafu)?;
+
+    // stream the data set
+    let cs = SpecificCharacterSet::Default;
+    let dataset =
+        DataSetReader::new_with_ts_cs_options(reader, source_ts, cs, options.reader_options())
+            .context(CreateParserSnafu)?;
+    let mut dset_writer =
+        DataSetWriter::with_ts_cs(writer, target_ts, cs).context(CreatePrinterSnafu)?;
+
+    match options.filter.take() {
+        Some(filter) => {
+            let filter = filter.then(UndefinedLengths::default());
+            for token in filter.try_apply(dataset) {
+                let token = token.context(ReadTokenSnafu)?;
+                dset_writer.write(token).context(PrintDataSetSnafu)?;
+            }
+        }
+        None => {
+            for token in dataset {
+                let token = token.context(ReadTokenSnafu)?;
+                dset_writer.write(token).context(PrintDataSetSnafu)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Copy a DICOM file into a new file,
+/// streaming its data set tokens without building a DICOM object.
+///
+/// Unless specified otherwise in the options,
+/// the source file is expected to have a preamble.
+/// See [`copy_object`] for more details.
+#[cfg(feature = "fs")]
+pub fn copy_file<P, Q>(from: P, to: Q, options: CopyOptions<'_>) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let from = from.as_ref();
+    let to = to.as_ref();
+    let options = if options.read_preamble == ReadPreamble::Auto {
+        options.read_preamble(ReadPreamble::Always)
+    } else {
+        options
+    };
+    let source = File::open(from).context(OpenFileSnafu { filename: from })?;
+    let sink = File::create(to).context(WriteFileSnafu { filename: to })?;
+    copy_object(source, sink, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileMetaTableBuilder, InMemDicomObject, OpenFileOptions};
+    use dicom_core::value::Value;
+    use dicom_core::{smallvec::smallvec, DataElement, Length, PrimitiveValue, VR};
+    use dicom_dictionary_std::tags;
+    use dicom_transfer_syntax_registry::entries::{
+        EXPLICIT_VR_LITTLE_ENDIAN, IMPLICIT_VR_LITTLE_ENDIAN,
+    };
+
+    fn sample_file(ts: &str) -> Vec<u8> {
+        let item = InMemDicomObject::from_element_iter([DataElement::new(
+            tags::CODE_VALUE,
+            VR::SH,
+            PrimitiveValue::from("121327"),
+        )]);
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from("P1")),
+            DataElement::new(
+                tags::CONCEPT_NAME_CODE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: smallvec![item],
+                    size: Length::UNDEFINED,
+                },
+            ),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .transfer_syntax(ts)
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .media_storage_sop_instance_uid("2.25.1234"),
+        )
+        .unwrap();
+        let mut data = Vec::new();
+        obj.write_all(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn copy_without_changes() {
+        let source = sample_file(EXPLICIT_VR_LITTLE_ENDIAN.uid());
+        let mut sink = Vec::new();
+        copy_object(
+            &source[..],
+            &mut sink,
+            CopyOptions::new().read_preamble(ReadPreamble::Always),
+        )
+        .unwrap();
+        assert_eq!(sink, source);
+    }
+
+    #[test]
+    fn copy_with_replaced_value() {
+        let source = sample_file(EXPLICIT_VR_LITTLE_ENDIAN.uid());
+        let mut in_patient_name = false;
+        let options = CopyOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .transfer_syntax(IMPLICIT_VR_LITTLE_ENDIAN.uid())
+            .map_tokens(|token| match token {
+                DataToken::ElementHeader(header) => {
+                    in_patient_name = header.tag == tags::PATIENT_NAME;
+                    Some(DataToken::ElementHeader(header))
+                }
+                DataToken::PrimitiveValue(_) if in_patient_name => {
+                    Some(DataToken::PrimitiveValue(PrimitiveValue::from("Anonymous")))
+                }
+                token => Some(token),
+            });
+        let mut sink = Vec::new();
+        copy_object(&source[..], &mut sink, options).unwrap();
+
+        let obj = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader(&sink[..])
+            .unwrap();
+        assert_eq!(
+            obj.meta().transfer_syntax.trim_end_matches('\0'),
+            IMPLICIT_VR_LITTLE_ENDIAN.uid()
+        );
+        assert_eq!(
+            obj.element(tags::PATIENT_NAME).unwrap().to_str().unwrap(),
+            "Anonymous"
+        );
+        assert_eq!(
+            obj.element(tags::PATIENT_ID).unwrap().to_str().unwrap(),
+            "P1"
+        );
+        let items = obj
+            .element(tags::CONCEPT_NAME_CODE_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(
+            items[0]
+                .element(tags::CODE_VALUE)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "121327"
+        );
+    }
+
+    #[test]
+    fn refuse_to_transcode_pixel_data() {
+        let source = sample_file(EXPLICIT_VR_LITTLE_ENDIAN.uid());
+        let options = CopyOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            // JPEG Baseline
+            .transfer_syntax("1.2.840.10008.1.2.4.50");
+        let result = copy_object(&source[..], Vec::new(), options);
+        assert!(matches!(
+            result,
+            Err(crate::Error::TranscodeRequired { .. })
+        ));
+    }
+}
@@ -0,0 +1,1505 @@
+//! Support for DICOMDIR files (Media Storage Directory).
+//!
+//! A DICOMDIR file describes the contents of a file-set,
+//! such as those found in CDs and USB drives,
+//! through a hierarchy of directory records
+//! (_Patient_ → _Study_ → _Series_ → _Image_, among other record types).
+//! The records are stored in a flat sequence,
+//! and linked to each other through byte offsets in the file.
+//! [`DicomDir`] reads the directory record sequence
+//! into a tree of [`DirectoryRecord`]s,
+//! and resolves the files referenced by each record
+//! to paths on the media.
+//! [`DicomDirBuilder`] does the opposite,
+//! creating a DICOMDIR for a set of DICOM files.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use dicom_object::dicomdir::{DicomDir, RecordType};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let dir = DicomDir::open_file("/media/cdrom/DICOMDIR")?;
+//! for patient in dir.root_records() {
+//!     for study in patient.children() {
+//!         println!("{} / {}", patient.record_type(), study.record_type());
+//!     }
+//! }
+//! for image in dir.iter().filter(|r| r.record_type() == &RecordType::Image) {
+//!     if let Some(path) = dir.resolve_file(image) {
+//!         println!("{}", path.display());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Create a DICOMDIR for all DICOM files in a directory:
+//!
+//! ```no_run
+//! use dicom_object::dicomdir::DicomDirBuilder;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let mut builder = DicomDirBuilder::new().file_set_id("MY_MEDIA");
+//! for error in builder.add_dir("/media/usb")? {
+//!     eprintln!("Skipped file: {}", error);
+//! }
+//! builder.build()?.write_to_file("/media/usb/DICOMDIR")?;
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataDictionary, DataElement, Length, Tag, VR};
+use dicom_dictionary_std::{tags, uids, StandardDataDictionary};
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_parser::dataset::read::Result as ReaderResult;
+use dicom_parser::dataset::{DataSetReader, DataToken};
+use dicom_parser::DynStatefulDecoder;
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::multiframe::new_uid;
+use crate::scan::walk_files;
+use crate::{
+    FileDicomObject, FileMetaTable, FileMetaTableBuilder, InMemDicomObject, OpenFileOptions,
+};
+
+/// The SOP class UID of a Media Storage Directory.
+pub const MEDIA_STORAGE_DIRECTORY_STORAGE: &str = "1.2.840.10008.1.3.10";
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// The file could not be read.
+    #[snafu(display("Could not read DICOMDIR file '{}'", filename.display()))]
+    ReadFile {
+        filename: PathBuf,
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+
+    /// The byte source could not be read.
+    #[snafu(display("Could not read DICOMDIR data"))]
+    ReadSource {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+
+    /// The data is too short to contain the file preamble.
+    #[snafu(display("Missing file preamble"))]
+    MissingPreamble { backtrace: Backtrace },
+
+    /// The file meta group could not be read.
+    #[snafu(display("Could not parse file meta group"))]
+    ParseMeta {
+        #[snafu(backtrace)]
+        source: crate::meta::Error,
+    },
+
+    /// The file is not a Media Storage Directory.
+    #[snafu(display("Not a DICOMDIR file (SOP class `{}`)", uid))]
+    NotADicomDir { uid: String, backtrace: Backtrace },
+
+    /// The transfer syntax of the file is not supported.
+    #[snafu(display("Unsupported transfer syntax `{}`", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// The data set parser could not be created.
+    #[snafu(display("Could not create data set parser"))]
+    CreateParser {
+        #[snafu(backtrace)]
+        source: dicom_parser::stateful::decode::Error,
+    },
+
+    /// The DICOMDIR data set could not be read.
+    #[snafu(display("Could not read DICOMDIR data set"))]
+    ReadDataSet {
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+
+    /// A required attribute is missing.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// An offset does not point to a directory record,
+    /// or points to a record which was already visited.
+    #[snafu(display("Invalid directory record offset {}", offset))]
+    InvalidRecordOffset { offset: u32, backtrace: Backtrace },
+
+    /// A directory could not be scanned for files.
+    #[snafu(display("Could not read directory '{}'", path.display()))]
+    ReadDirectory {
+        path: PathBuf,
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+
+    /// A file ID is not valid for use in a file-set.
+    #[snafu(display("Invalid file ID `{}`", file_id))]
+    InvalidFileId {
+        file_id: String,
+        backtrace: Backtrace,
+    },
+
+    /// A DICOM file to reference in the DICOMDIR could not be read.
+    #[snafu(display("Could not read DICOM file '{}'", path.display()))]
+    ReadDicomFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+
+    /// The file meta group of the DICOMDIR could not be built.
+    #[snafu(display("Could not build file meta group"))]
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: crate::meta::Error,
+    },
+
+    /// The DICOMDIR data set could not be encoded.
+    #[snafu(display("Could not encode DICOMDIR data set"))]
+    WriteDataSet {
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The type of a directory record,
+/// as defined by _Directory Record Type_ (0004,1430).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RecordType {
+    Patient,
+    Study,
+    Series,
+    Image,
+    RtDose,
+    RtStructureSet,
+    RtPlan,
+    RtTreatRecord,
+    Presentation,
+    Waveform,
+    SrDocument,
+    KeyObjectDoc,
+    Spectroscopy,
+    RawData,
+    Registration,
+    Fiducial,
+    HangingProtocol,
+    EncapDoc,
+    Measurement,
+    Surface,
+    Plan,
+    Private,
+    /// Any other record type, with its code string.
+    Other(String),
+}
+
+impl RecordType {
+    /// Obtain the code string of this record type.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RecordType::Patient => "PATIENT",
+            RecordType::Study => "STUDY",
+            RecordType::Series => "SERIES",
+            RecordType::Image => "IMAGE",
+            RecordType::RtDose => "RT DOSE",
+            RecordType::RtStructureSet => "RT STRUCTURE SET",
+            RecordType::RtPlan => "RT PLAN",
+            RecordType::RtTreatRecord => "RT TREAT RECORD",
+            RecordType::Presentation => "PRESENTATION",
+            RecordType::Waveform => "WAVEFORM",
+            RecordType::SrDocument => "SR DOCUMENT",
+            RecordType::KeyObjectDoc => "KEY OBJECT DOC",
+            RecordType::Spectroscopy => "SPECTROSCOPY",
+            RecordType::RawData => "RAW DATA",
+            RecordType::Registration => "REGISTRATION",
+            RecordType::Fiducial => "FIDUCIAL",
+            RecordType::HangingProtocol => "HANGING PROTOCOL",
+            RecordType::EncapDoc => "ENCAP DOC",
+            RecordType::Measurement => "MEASUREMENT",
+            RecordType::Surface => "SURFACE",
+            RecordType::Plan => "PLAN",
+            RecordType::Private => "PRIVATE",
+            RecordType::Other(code) => code.as_str(),
+        }
+    }
+}
+
+impl From<&str> for RecordType {
+    fn from(code: &str) -> Self {
+        match code.trim_end_matches(|c| c == '\0' || c == ' ') {
+            "PATIENT" => RecordType::Patient,
+            "STUDY" => RecordType::Study,
+            "SERIES" => RecordType::Series,
+            "IMAGE" => RecordType::Image,
+            "RT DOSE" => RecordType::RtDose,
+            "RT STRUCTURE SET" => RecordType::RtStructureSet,
+            "RT PLAN" => RecordType::RtPlan,
+            "RT TREAT RECORD" => RecordType::RtTreatRecord,
+            "PRESENTATION" => RecordType::Presentation,
+            "WAVEFORM" => RecordType::Waveform,
+            "SR DOCUMENT" => RecordType::SrDocument,
+            "KEY OBJECT DOC" => RecordType::KeyObjectDoc,
+            "SPECTROSCOPY" => RecordType::Spectroscopy,
+            "RAW DATA" => RecordType::RawData,
+            "REGISTRATION" => RecordType::Registration,
+            "FIDUCIAL" => RecordType::Fiducial,
+            "HANGING PROTOCOL" => RecordType::HangingProtocol,
+            "ENCAP DOC" => RecordType::EncapDoc,
+            "MEASUREMENT" => RecordType::Measurement,
+            "SURFACE" => RecordType::Surface,
+            "PLAN" => RecordType::Plan,
+            "PRIVATE" => RecordType::Private,
+            other => RecordType::Other(other.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A directory record of a DICOMDIR,
+/// along with the records of its lower level directory entity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DirectoryRecord {
+    offset: u32,
+    record_type: RecordType,
+    item: InMemDicomObject,
+    children: Vec<DirectoryRecord>,
+}
+
+impl DirectoryRecord {
+    /// Obtain the byte offset of this record in the DICOMDIR file.
+    pub fn offset(&self) -> u32 {
+        self.offset
+    }
+
+    /// Obtain the type of this record.
+    pub fn record_type(&self) -> &RecordType {
+        &self.record_type
+    }
+
+    /// Obtain the full data set of this record's item.
+    pub fn item(&self) -> &InMemDicomObject {
+        &self.item
+    }
+
+    /// Obtain the records of the lower level directory entity
+    /// referenced by this record
+    /// (e.g. the studies of a patient record).
+    pub fn children(&self) -> &[DirectoryRecord] {
+        &self.children
+    }
+
+    /// Check whether this record is in use.
+    ///
+    /// Records with a _Record In-use Flag_ of zero are inactive,
+    /// and should be ignored.
+    pub fn is_in_use(&self) -> bool {
+        self.item
+            .element(tags::RECORD_IN_USE_FLAG)
+            .ok()
+            .and_then(|e| e.to_int::<u16>().ok())
+            .map(|flag| flag != 0)
+            .unwrap_or(true)
+    }
+
+    /// Obtain the components of the _Referenced File ID_,
+    /// which identifies the file referenced by this record, if any.
+    pub fn referenced_file_id(&self) -> Option<Vec<String>> {
+        let file_id = self
+            .item
+            .element(tags::REFERENCED_FILE_ID)
+            .ok()?
+            .to_multi_str()
+            .ok()?
+            .iter()
+            .map(|c| c.trim_end_matches(|c| c == '\0' || c == ' ').to_string())
+            .collect();
+        Some(file_id)
+    }
+
+    /// Obtain the SOP class UID of the file referenced by this record, if any.
+    pub fn referenced_sop_class_uid(&self) -> Option<String> {
+        self.string_of(tags::REFERENCED_SOP_CLASS_UID_IN_FILE)
+    }
+
+    /// Obtain the SOP instance UID of the file referenced by this record, if any.
+    pub fn referenced_sop_instance_uid(&self) -> Option<String> {
+        self.string_of(tags::REFERENCED_SOP_INSTANCE_UID_IN_FILE)
+    }
+
+    /// Obtain the transfer syntax UID of the file referenced by this record, if any.
+    pub fn referenced_transfer_syntax_uid(&self) -> Option<String> {
+        self.string_of(tags::REFERENCED_TRANSFER_SYNTAX_UID_IN_FILE)
+    }
+
+    /// Obtain an iterator over this record and all of its descendants,
+    /// in depth-first order.
+    pub fn iter(&self) -> impl Iterator<Item = &DirectoryRecord> {
+        let mut stack = vec![self];
+        std::iter::from_fn(move || {
+            let record = stack.pop()?;
+            stack.extend(record.children.iter().rev());
+            Some(record)
+        })
+    }
+
+    fn string_of(&self, tag: Tag) -> Option<String> {
+        let value = self.item.element(tag).ok()?.to_str().ok()?;
+        Some(
+            value
+                .trim_end_matches(|c| c == '\0' || c == ' ')
+                .to_string(),
+        )
+    }
+}
+
+/// A Media Storage Directory (DICOMDIR),
+/// with its directory records arranged in a tree.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DicomDir {
+    meta: FileMetaTable,
+    dataset: InMemDicomObject,
+    records: Vec<DirectoryRecord>,
+    base_dir: Option<PathBuf>,
+}
+
+impl DicomDir {
+    /// Read a DICOMDIR file.
+    ///
+    /// The files referenced by its records
+    /// are resolved relative to the directory containing the file.
+    pub fn open_file<P>(path: P) -> Result<Self>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let data = std::fs::read(path).context(ReadFileSnafu { filename: path })?;
+        let mut dicomdir = DicomDir::from_bytes(&data)?;
+        dicomdir.base_dir = path.parent().map(Path::to_path_buf);
+        Ok(dicomdir)
+    }
+
+    /// Read a DICOMDIR from a byte source.
+    ///
+    /// The source must contain the whole file,
+    /// starting with the 128-byte preamble,
+    /// since the offsets of the records are relative to the start of the file.
+    /// The files referenced by its records
+    /// are resolved relative to the current directory,
+    /// unless a base directory is set with [`set_base_dir`](Self::set_base_dir).
+    pub fn from_reader<R>(mut from: R) -> Result<Self>
+    where
+        R: Read,
+    {
+        let mut data = Vec::new();
+        from.read_to_end(&mut data).context(ReadSourceSnafu)?;
+        DicomDir::from_bytes(&data)
+    }
+
+    fn from_bytes(data: &[u8]) -> Result<Self> {
+        let (meta, mut dataset, offsets) = read_dicomdir(data)?;
+
+        let items = match dataset.take_element(tags::DIRECTORY_RECORD_SEQUENCE) {
+            Ok(elem) => match elem.into_value() {
+                Value::Sequence { items, .. } => items,
+                _ => C::new(),
+            },
+            Err(_) => C::new(),
+        };
+        let mut items: HashMap<u32, InMemDicomObject> = offsets.into_iter().zip(items).collect();
+
+        let root = read_offset(
+            &dataset,
+            tags::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+        )?;
+        // an empty file-set has no root record
+        let records = match root {
+            Some(root) => build_records(&mut items, root)?,
+            None => Vec::new(),
+        };
+
+        Ok(DicomDir {
+            meta,
+            dataset,
+            records,
+            base_dir: None,
+        })
+    }
+
+    /// Obtain the file meta group of the DICOMDIR file.
+    pub fn meta(&self) -> &FileMetaTable {
+        &self.meta
+    }
+
+    /// Obtain the DICOMDIR data set,
+    /// without the directory record sequence.
+    pub fn dataset(&self) -> &InMemDicomObject {
+        &self.dataset
+    }
+
+    /// Obtain the _File-set ID_ of the file-set, if any.
+    pub fn file_set_id(&self) -> Option<String> {
+        let value = self
+            .dataset
+            .element(tags::FILE_SET_ID)
+            .ok()?
+            .to_str()
+            .ok()?;
+        let value = value.trim_end_matches(|c| c == '\0' || c == ' ');
+        if value.is_empty() {
+            None
+        } else {
+            Some(value.to_string())
+        }
+    }
+
+    /// Obtain the records of the root directory entity
+    /// (usually the patient records).
+    pub fn root_records(&self) -> &[DirectoryRecord] {
+        &self.records
+    }
+
+    /// Obtain an iterator over all records in the directory,
+    /// in depth-first order.
+    pub fn iter(&self) -> impl Iterator<Item = &DirectoryRecord> {
+        self.records.iter().flat_map(|r| r.iter())
+    }
+
+    /// Retrieve the record at the given byte offset in the file.
+    pub fn record_at(&self, offset: u32) -> Option<&DirectoryRecord> {
+        self.iter().find(|r| r.offset == offset)
+    }
+
+    /// Set the directory against which referenced files are resolved.
+    pub fn set_base_dir<P>(&mut self, base_dir: P)
+    where
+        P: Into<PathBuf>,
+    {
+        self.base_dir = Some(base_dir.into());
+    }
+
+    /// Resolve the file referenced by the given record to a path on the media,
+    /// if the record references a file.
+    pub fn resolve_file(&self, record: &DirectoryRecord) -> Option<PathBuf> {
+        let file_id = record.referenced_file_id()?;
+        Some(self.resolve_file_id(&file_id))
+    }
+
+    /// Resolve the given _Referenced File ID_ components
+    /// to a path on the media.
+    pub fn resolve_file_id<S>(&self, file_id: &[S]) -> PathBuf
+    where
+        S: AsRef<str>,
+    {
+        let mut path = self.base_dir.clone().unwrap_or_default();
+        path.extend(file_id.iter().map(|c| c.as_ref()));
+        path
+    }
+}
+
+/// The transfer syntax of DICOMDIR files (Explicit VR Little Endian).
+const DICOMDIR_TRANSFER_SYNTAX: &str = "1.2.840.10008.1.2.1";
+
+/// How a key attribute is copied from a file into a directory record.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum KeyType {
+    /// The attribute must be present in the file (type 1).
+    Required,
+    /// The attribute is left empty if absent from the file (type 2).
+    Empty,
+    /// The attribute is only copied if present in the file.
+    Optional,
+}
+
+type Key = (Tag, VR, KeyType);
+
+const PATIENT_KEYS: &[Key] = &[
+    (tags::SPECIFIC_CHARACTER_SET, VR::CS, KeyType::Optional),
+    (tags::PATIENT_NAME, VR::PN, KeyType::Empty),
+    (tags::PATIENT_ID, VR::LO, KeyType::Required),
+];
+
+const STUDY_KEYS: &[Key] = &[
+    (tags::SPECIFIC_CHARACTER_SET, VR::CS, KeyType::Optional),
+    (tags::STUDY_DATE, VR::DA, KeyType::Required),
+    (tags::STUDY_TIME, VR::TM, KeyType::Required),
+    (tags::STUDY_DESCRIPTION, VR::LO, KeyType::Empty),
+    (tags::STUDY_INSTANCE_UID, VR::UI, KeyType::Required),
+    (tags::STUDY_ID, VR::SH, KeyType::Required),
+    (tags::ACCESSION_NUMBER, VR::SH, KeyType::Empty),
+];
+
+const SERIES_KEYS: &[Key] = &[
+    (tags::SPECIFIC_CHARACTER_SET, VR::CS, KeyType::Optional),
+    (tags::MODALITY, VR::CS, KeyType::Required),
+    (tags::SERIES_INSTANCE_UID, VR::UI, KeyType::Required),
+    (tags::SERIES_NUMBER, VR::IS, KeyType::Required),
+];
+
+const INSTANCE_KEYS: &[Key] = &[
+    (tags::SPECIFIC_CHARACTER_SET, VR::CS, KeyType::Optional),
+    (tags::INSTANCE_NUMBER, VR::IS, KeyType::Required),
+    (tags::CONTENT_DATE, VR::DA, KeyType::Optional),
+    (tags::CONTENT_TIME, VR::TM, KeyType::Optional),
+];
+
+/// The directory records describing one file of the file-set.
+#[derive(Debug, Clone)]
+struct FileRecords {
+    patient_id: String,
+    patient: InMemDicomObject,
+    study_uid: String,
+    study: InMemDicomObject,
+    series_uid: String,
+    series: InMemDicomObject,
+    instance: InMemDicomObject,
+}
+
+/// A directory record with the records of its lower level directory entity.
+struct Node {
+    key: String,
+    record: InMemDicomObject,
+    children: Vec<Node>,
+}
+
+/// A builder for a DICOMDIR describing a set of DICOM files.
+///
+/// Each file added produces an instance level record
+/// (_Image_ for most objects),
+/// grouped under _Patient_, _Study_, and _Series_ records
+/// according to the respective identifiers.
+/// The required keys of each record are copied from the files.
+#[derive(Debug, Default, Clone)]
+pub struct DicomDirBuilder {
+    file_set_id: Option<String>,
+    files: Vec<FileRecords>,
+}
+
+impl DicomDirBuilder {
+    /// Create a new DICOMDIR builder for an empty file-set.
+    pub fn new() -> Self {
+        DicomDirBuilder::default()
+    }
+
+    /// Define the _File-set ID_ of the file-set.
+    pub fn file_set_id<S>(mut self, file_set_id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.file_set_id = Some(file_set_id.into());
+        self
+    }
+
+    /// Add a DICOM file to the file-set,
+    /// identified by the given _Referenced File ID_ components
+    /// (the path to the file relative to the DICOMDIR).
+    ///
+    /// Each component must have up to 8 characters,
+    /// and only contain uppercase letters, digits, and underscores.
+    pub fn add_file<I, S, D>(
+        &mut self,
+        file_id: I,
+        obj: &FileDicomObject<InMemDicomObject<D>>,
+    ) -> Result<()>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+        D: DataDictionary + Clone,
+    {
+        let file_id: Vec<String> = file_id.into_iter().map(Into::into).collect();
+        ensure!(
+            is_valid_file_id(&file_id),
+            InvalidFileIdSnafu {
+                file_id: file_id.join("\\"),
+            }
+        );
+
+        let mut patient = new_record(&RecordType::Patient);
+        copy_keys(obj, &mut patient, PATIENT_KEYS)?;
+        let mut study = new_record(&RecordType::Study);
+        copy_keys(obj, &mut study, STUDY_KEYS)?;
+        let mut series = new_record(&RecordType::Series);
+        copy_keys(obj, &mut series, SERIES_KEYS)?;
+
+        let meta = obj.meta();
+        let mut instance = new_record(&instance_record_type(&meta.media_storage_sop_class_uid));
+        copy_keys(obj, &mut instance, INSTANCE_KEYS)?;
+        instance.put(DataElement::new(
+            tags::REFERENCED_FILE_ID,
+            VR::CS,
+            PrimitiveValue::Strs(file_id.into_iter().collect()),
+        ));
+        for (tag, uid) in [
+            (
+                tags::REFERENCED_SOP_CLASS_UID_IN_FILE,
+                &meta.media_storage_sop_class_uid,
+            ),
+            (
+                tags::REFERENCED_SOP_INSTANCE_UID_IN_FILE,
+                &meta.media_storage_sop_instance_uid,
+            ),
+            (
+                tags::REFERENCED_TRANSFER_SYNTAX_UID_IN_FILE,
+                &meta.transfer_syntax,
+            ),
+        ] {
+            let uid = uid.trim_end_matches(|c| c == '\0' || c == ' ');
+            instance.put(DataElement::new(tag, VR::UI, PrimitiveValue::from(uid)));
+        }
+
+        self.files.push(FileRecords {
+            patient_id: string_of(&patient, tags::PATIENT_ID).unwrap_or_default(),
+            patient,
+            study_uid: string_of(&study, tags::STUDY_INSTANCE_UID).unwrap_or_default(),
+            study,
+            series_uid: string_of(&series, tags::SERIES_INSTANCE_UID).unwrap_or_default(),
+            series,
+            instance,
+        });
+        Ok(())
+    }
+
+    /// Add all DICOM files in the given directory and its subdirectories
+    /// to the file-set,
+    /// with file IDs relative to that directory.
+    ///
+    /// Files which cannot be read as DICOM files are ignored,
+    /// as well as an existing DICOMDIR file at the root of the directory.
+    /// DICOM files which cannot be added to the file-set,
+    /// such as files with a name which is not a valid file ID
+    /// or files without the required key attributes,
+    /// are skipped without interrupting the process.
+    /// The errors of the skipped files are returned.
+    pub fn add_dir<P>(&mut self, root: P) -> Result<Vec<Error>>
+    where
+        P: AsRef<Path>,
+    {
+        let root = root.as_ref();
+        let mut files = Vec::new();
+        collect_files(root, &mut files)?;
+        files.sort();
+
+        let mut skipped = Vec::new();
+        for path in files {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            if relative == Path::new("DICOMDIR") {
+                continue;
+            }
+            if let Err(e) = self.add_dir_file(&path, relative) {
+                tracing::warn!("Skipping file {}: {}", path.display(), e);
+                skipped.push(e);
+            }
+        }
+        Ok(skipped)
+    }
+
+    /// Add a file found by [`add_dir`](Self::add_dir),
+    /// ignoring it if it cannot be read as a DICOM file.
+    fn add_dir_file(&mut self, path: &Path, relative: &Path) -> Result<()> {
+        let file_id: Option<Vec<String>> = relative
+            .components()
+            .map(|c| c.as_os_str().to_str().map(String::from))
+            .collect();
+        let file_id = file_id.context(InvalidFileIdSnafu {
+            file_id: relative.display().to_string(),
+        })?;
+
+        let obj = match OpenFileOptions::new()
+            .read_until(tags::PIXEL_DATA)
+            .open_file(path)
+        {
+            Ok(obj) => obj,
+            Err(crate::Error::ParseMetaDataSet { .. }) | Err(crate::Error::ReadFile { .. }) => {
+                tracing::warn!("Ignoring non-DICOM file {}", path.display());
+                return Ok(());
+            }
+            Err(e) => return Err(e).context(ReadDicomFileSnafu { path }),
+        };
+        self.add_file(file_id, &obj)
+    }
+
+    /// Build the DICOMDIR object,
+    /// ready to be written to a file named `DICOMDIR`
+    /// at the root of the file-set.
+    ///
+    /// The records are linked through the byte offsets
+    /// which they will have once the object is written
+    /// with [`write_to_file`](FileDicomObject::write_to_file)
+    /// or [`write_all`](FileDicomObject::write_all),
+    /// so the object should not be modified afterwards.
+    pub fn build(&self) -> Result<FileDicomObject<InMemDicomObject>> {
+        // group the records by patient, study, and series
+        let mut patients: Vec<Node> = Vec::new();
+        for file in &self.files {
+            let patient = child_node(&mut patients, &file.patient_id, &file.patient);
+            let study = child_node(&mut patient.children, &file.study_uid, &file.study);
+            let series = child_node(&mut study.children, &file.series_uid, &file.series);
+            series.children.push(Node {
+                key: String::new(),
+                record: file.instance.clone(),
+                children: Vec::new(),
+            });
+        }
+
+        let mut items = Vec::new();
+        let mut links = Vec::new();
+        let roots = flatten_nodes(patients, &mut items, &mut links);
+
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid(MEDIA_STORAGE_DIRECTORY_STORAGE)
+            .media_storage_sop_instance_uid(new_uid())
+            .transfer_syntax(DICOMDIR_TRANSFER_SYNTAX)
+            .build()
+            .context(BuildMetaSnafu)?;
+
+        // first pass, to find the offset of each record in the file
+        // (offsets have a fixed size, so they do not change in the second pass)
+        let mut data = Vec::new();
+        self.dicomdir_object(meta.clone(), items.clone(), 0, 0)
+            .write_all(&mut data)
+            .context(WriteDataSetSnafu)?;
+        let (_, _, offsets) = read_dicomdir(&data)?;
+
+        // second pass, linking the records
+        for (item, (next, lower)) in items.iter_mut().zip(links) {
+            for (tag, index) in [
+                (tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD, next),
+                (
+                    tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+                    lower,
+                ),
+            ] {
+                let offset = index.map(|i| offsets[i]).unwrap_or(0);
+                item.put(DataElement::new(tag, VR::UL, PrimitiveValue::from(offset)));
+            }
+        }
+        let first = roots.first().map(|i| offsets[*i]).unwrap_or(0);
+        let last = roots.last().map(|i| offsets[*i]).unwrap_or(0);
+        Ok(self.dicomdir_object(meta, items, first, last))
+    }
+
+    fn dicomdir_object(
+        &self,
+        meta: FileMetaTable,
+        items: Vec<InMemDicomObject>,
+        first: u32,
+        last: u32,
+    ) -> FileDicomObject<InMemDicomObject> {
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::FILE_SET_ID,
+            VR::CS,
+            self.file_set_id
+                .as_deref()
+                .map(PrimitiveValue::from)
+                .unwrap_or(PrimitiveValue::Empty),
+        ));
+        obj.put(DataElement::new(
+            tags::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+            VR::UL,
+            PrimitiveValue::from(first),
+        ));
+        obj.put(DataElement::new(
+            tags::OFFSET_OF_THE_LAST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+            VR::UL,
+            PrimitiveValue::from(last),
+        ));
+        obj.put(DataElement::new(
+            tags::FILE_SET_CONSISTENCY_FLAG,
+            VR::US,
+            PrimitiveValue::from(0_u16),
+        ));
+        obj.put(DataElement::new(
+            tags::DIRECTORY_RECORD_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: items.into(),
+                size: Length::UNDEFINED,
+            },
+        ));
+        obj.with_exact_meta(meta)
+    }
+}
+
+/// Check whether the given file ID components are valid in a file-set.
+fn is_valid_file_id(file_id: &[String]) -> bool {
+    !file_id.is_empty()
+        && file_id.len() <= 8
+        && file_id.iter().all(|c| {
+            !c.is_empty()
+                && c.len() <= 8
+                && c.bytes()
+                    .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit() || b == b'_')
+        })
+}
+
+/// Recursively collect the paths of all files in a directory,
+/// failing on the first directory which could not be read.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut error = None;
+    walk_files(dir, files, &mut |path, source| {
+        if error.is_none() {
+            error = Some((path.to_path_buf(), source));
+        }
+    });
+    match error {
+        Some((path, source)) => Err(source).context(ReadDirectorySnafu { path }),
+        None => Ok(()),
+    }
+}
+
+/// Create a directory record of the given type,
+/// not linked to any other record.
+fn new_record(record_type: &RecordType) -> InMemDicomObject {
+    let mut record = InMemDicomObject::new_empty();
+    record.put(DataElement::new(
+        tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD,
+        VR::UL,
+        PrimitiveValue::from(0_u32),
+    ));
+    record.put(DataElement::new(
+        tags::RECORD_IN_USE_FLAG,
+        VR::US,
+        PrimitiveValue::from(0xFFFF_u16),
+    ));
+    record.put(DataElement::new(
+        tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+        VR::UL,
+        PrimitiveValue::from(0_u32),
+    ));
+    record.put(DataElement::new(
+        tags::DIRECTORY_RECORD_TYPE,
+        VR::CS,
+        PrimitiveValue::from(record_type.as_str()),
+    ));
+    record
+}
+
+/// Determine the type of the instance level record
+/// for an object of the given SOP class.
+fn instance_record_type(sop_class_uid: &str) -> RecordType {
+    let uid = sop_class_uid.trim_end_matches(|c| c == '\0' || c == ' ');
+    // whether the UID is the given one or one of its descendants
+    let under = |root: &str| match uid.strip_prefix(root) {
+        Some(rest) => rest.is_empty() || rest.starts_with('.'),
+        None => false,
+    };
+    match uid {
+        uids::RT_DOSE_STORAGE => RecordType::RtDose,
+        uids::RT_STRUCTURE_SET_STORAGE => RecordType::RtStructureSet,
+        uids::RT_PLAN_STORAGE | uids::RT_ION_PLAN_STORAGE => RecordType::RtPlan,
+        uids::RT_BEAMS_TREATMENT_RECORD_STORAGE
+        | uids::RT_BRACHY_TREATMENT_RECORD_STORAGE
+        | uids::RT_TREATMENT_SUMMARY_RECORD_STORAGE
+        | uids::RT_ION_BEAMS_TREATMENT_RECORD_STORAGE => RecordType::RtTreatRecord,
+        uids::KEY_OBJECT_SELECTION_DOCUMENT_STORAGE => RecordType::KeyObjectDoc,
+        uids::MR_SPECTROSCOPY_STORAGE => RecordType::Spectroscopy,
+        uids::RAW_DATA_STORAGE => RecordType::RawData,
+        uids::SPATIAL_REGISTRATION_STORAGE | uids::DEFORMABLE_SPATIAL_REGISTRATION_STORAGE => {
+            RecordType::Registration
+        }
+        uids::SPATIAL_FIDUCIALS_STORAGE => RecordType::Fiducial,
+        uids::SURFACE_SEGMENTATION_STORAGE => RecordType::Surface,
+        // Hanging Protocol Storage
+        "1.2.840.10008.5.1.4.38.1" => RecordType::HangingProtocol,
+        _ if under("1.2.840.10008.5.1.4.1.1.11") => RecordType::Presentation,
+        _ if under("1.2.840.10008.5.1.4.1.1.9") => RecordType::Waveform,
+        _ if under("1.2.840.10008.5.1.4.1.1.88") => RecordType::SrDocument,
+        _ if under("1.2.840.10008.5.1.4.1.1.104") => RecordType::EncapDoc,
+        _ if under("1.2.840.10008.5.1.4.1.1.78") => RecordType::Measurement,
+        _ if !under("1.2.840.10008") => RecordType::Private,
+        _ => RecordType::Image,
+    }
+}
+
+/// Copy the given key attributes of a file into a directory record.
+fn copy_keys<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    record: &mut InMemDicomObject,
+    keys: &[Key],
+) -> Result<()>
+where
+    D: DataDictionary + Clone,
+{
+    for &(tag, vr, key_type) in keys {
+        let elem = obj.element(tag).ok();
+        match elem.and_then(|e| e.value().primitive().map(|v| (e.vr(), v))) {
+            Some((vr, value)) => {
+                record.put(DataElement::new(tag, vr, value.clone()));
+            }
+            None => match key_type {
+                KeyType::Required => return MissingAttributeSnafu { tag }.fail(),
+                KeyType::Empty => {
+                    record.put(DataElement::new(tag, vr, PrimitiveValue::Empty));
+                }
+                KeyType::Optional => {}
+            },
+        }
+    }
+    Ok(())
+}
+
+/// Obtain the value of an attribute as a trimmed string.
+fn string_of(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+    let value = obj.element(tag).ok()?.to_str().ok()?;
+    Some(
+        value
+            .trim_end_matches(|c| c == '\0' || c == ' ')
+            .to_string(),
+    )
+}
+
+/// Retrieve the node with the given key,
+/// inserting a new one with the given record if it does not exist.
+fn child_node<'a>(nodes: &'a mut Vec<Node>, key: &str, record: &InMemDicomObject) -> &'a mut Node {
+    let index = match nodes.iter().position(|n| n.key == key) {
+        Some(index) => index,
+        None => {
+            nodes.push(Node {
+                key: key.to_string(),
+                record: record.clone(),
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        }
+    };
+    &mut nodes[index]
+}
+
+/// Lay out the records of the given nodes and their descendants
+/// in depth-first order,
+/// recording the indices of the next record
+/// and of the first lower level record of each one.
+///
+/// Returns the indices of the given nodes' records.
+fn flatten_nodes(
+    nodes: Vec<Node>,
+    items: &mut Vec<InMemDicomObject>,
+    links: &mut Vec<(Option<usize>, Option<usize>)>,
+) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(nodes.len());
+    for node in nodes {
+        let index = items.len();
+        items.push(node.record);
+        links.push((None, None));
+        let children = flatten_nodes(node.children, items, links);
+        links[index].1 = children.first().copied();
+        indices.push(index);
+    }
+    for pair in indices.windows(2) {
+        links[pair[0]].0 = Some(pair[1]);
+    }
+    indices
+}
+
+/// Read the file meta group and data set of a DICOMDIR file,
+/// along with the byte offsets of each item in the directory record sequence.
+fn read_dicomdir(data: &[u8]) -> Result<(FileMetaTable, InMemDicomObject, Vec<u32>)> {
+    let mut source = data.get(128..).context(MissingPreambleSnafu)?;
+    let meta = FileMetaTable::from_reader(&mut source).context(ParseMetaSnafu)?;
+
+    let sop_class_uid = meta
+        .media_storage_sop_class_uid
+        .trim_end_matches(|c| c == '\0' || c == ' ');
+    ensure!(
+        sop_class_uid == MEDIA_STORAGE_DIRECTORY_STORAGE,
+        NotADicomDirSnafu { uid: sop_class_uid }
+    );
+
+    let ts = TransferSyntaxRegistry.get(&meta.transfer_syntax).context(
+        UnsupportedTransferSyntaxSnafu {
+            uid: &meta.transfer_syntax,
+        },
+    )?;
+
+    // the offsets are relative to the start of the file
+    let position = (data.len() - source.len()) as u64;
+    let parser = DynStatefulDecoder::new_with(source, ts, SpecificCharacterSet::Default, position)
+        .context(CreateParserSnafu)?;
+    let mut reader = DataSetReader::new(parser, Default::default());
+
+    let mut offsets = Vec::new();
+    let dataset = {
+        let mut depth = 0;
+        let mut in_records = false;
+        let mut tokens = reader.positioned().map(|token| -> ReaderResult<DataToken> {
+            let token = token?;
+            match &token.token {
+                DataToken::SequenceStart { tag, .. } => {
+                    if depth == 0 && *tag == tags::DIRECTORY_RECORD_SEQUENCE {
+                        in_records = true;
+                    }
+                    depth += 1;
+                }
+                DataToken::ItemStart { .. } => {
+                    if in_records && depth == 1 {
+                        offsets.push(token.offset as u32);
+                    }
+                    depth += 1;
+                }
+                DataToken::PixelSequenceStart => depth += 1,
+                DataToken::SequenceEnd | DataToken::ItemEnd => {
+                    depth -= 1;
+                    if depth == 0 {
+                        in_records = false;
+                    }
+                }
+                _ => {}
+            }
+            Ok(token.token)
+        });
+        InMemDicomObject::build_object(
+            &mut tokens,
+            StandardDataDictionary,
+            false,
+            Length::UNDEFINED,
+            None,
+        )
+        .context(ReadDataSetSnafu)?
+    };
+
+    Ok((meta, dataset, offsets))
+}
+
+/// Read an offset attribute, where zero means no record.
+fn read_offset(obj: &InMemDicomObject, tag: Tag) -> Result<Option<u32>> {
+    match obj.element(tag) {
+        Ok(elem) => {
+            let offset = elem
+                .to_int::<u32>()
+                .context(InvalidAttributeSnafu { tag })?;
+            Ok(Some(offset).filter(|o| *o != 0))
+        }
+        Err(_) => Ok(None),
+    }
+}
+
+/// Build the records of a directory entity,
+/// starting with the record at the given offset.
+///
+/// Items are taken from the map as they are visited,
+/// so that offsets pointing to a record twice are detected.
+fn build_records(
+    items: &mut HashMap<u32, InMemDicomObject>,
+    first: u32,
+) -> Result<Vec<DirectoryRecord>> {
+    let mut records = Vec::new();
+    let mut next = Some(first);
+    while let Some(offset) = next {
+        let item = items
+            .remove(&offset)
+            .context(InvalidRecordOffsetSnafu { offset })?;
+        next = read_offset(&item, tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD)?;
+        let lower = read_offset(
+            &item,
+            tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+        )?;
+        let record_type = item
+            .element(tags::DIRECTORY_RECORD_TYPE)
+            .ok()
+            .and_then(|e| e.to_str().ok().map(|s| RecordType::from(&*s)))
+            .context(MissingAttributeSnafu {
+                tag: tags::DIRECTORY_RECORD_TYPE,
+            })?;
+        let children = match lower {
+            Some(lower) => build_records(items, lower)?,
+            None => Vec::new(),
+        };
+        records.push(DirectoryRecord {
+            offset,
+            record_type,
+            item,
+            children,
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dicom_value;
+
+    fn record(record_type: &str, file_id: Option<&[&str]>) -> InMemDicomObject {
+        let mut item = InMemDicomObject::new_empty();
+        for tag in [
+            tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD,
+            tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+        ] {
+            item.put(DataElement::new(tag, VR::UL, dicom_value!(U32, [0])));
+        }
+        item.put(DataElement::new(
+            tags::RECORD_IN_USE_FLAG,
+            VR::US,
+            dicom_value!(U16, [0xFFFF]),
+        ));
+        item.put(DataElement::new(
+            tags::DIRECTORY_RECORD_TYPE,
+            VR::CS,
+            PrimitiveValue::from(record_type),
+        ));
+        if let Some(file_id) = file_id {
+            item.put(DataElement::new(
+                tags::REFERENCED_FILE_ID,
+                VR::CS,
+                PrimitiveValue::Strs(file_id.iter().map(|c| c.to_string()).collect()),
+            ));
+        }
+        item
+    }
+
+    /// Encode a DICOMDIR with a patient, a study, a series and two images,
+    /// linking the records in a second pass.
+    fn sample_dicomdir() -> Vec<u8> {
+        let items = vec![
+            record("PATIENT", None),
+            record("STUDY", None),
+            record("SERIES", None),
+            record("IMAGE", Some(&["IMAGES", "IM0001"][..])),
+            record("IMAGE", Some(&["IMAGES", "IM0002"][..])),
+        ];
+        // (record index, next record index, lower level record index)
+        let links = [
+            (0, None, Some(1)),
+            (1, None, Some(2)),
+            (2, None, Some(3)),
+            (3, Some(4), None),
+        ];
+
+        let encode = |items: Vec<InMemDicomObject>, root: u32| {
+            let mut obj = InMemDicomObject::new_empty();
+            obj.put(DataElement::new(
+                tags::FILE_SET_ID,
+                VR::CS,
+                PrimitiveValue::from("SAMPLE"),
+            ));
+            obj.put(DataElement::new(
+                tags::OFFSET_OF_THE_FIRST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY,
+                VR::UL,
+                dicom_value!(U32, [root]),
+            ));
+            obj.put(DataElement::new(
+                tags::DIRECTORY_RECORD_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: items.into(),
+                    size: Length::UNDEFINED,
+                },
+            ));
+            let obj = obj
+                .with_meta(
+                    FileMetaTableBuilder::new()
+                        .media_storage_sop_class_uid(MEDIA_STORAGE_DIRECTORY_STORAGE)
+                        .media_storage_sop_instance_uid("2.25.1")
+                        .transfer_syntax("1.2.840.10008.1.2.1"),
+                )
+                .unwrap();
+            let mut out = Vec::new();
+            obj.write_all(&mut out).unwrap();
+            out
+        };
+
+        // first pass, to find the offset of each record
+        let data = encode(items.clone(), 0);
+        let (_, _, offsets) = read_dicomdir(&data).unwrap();
+        assert_eq!(offsets.len(), items.len());
+
+        // second pass, with the offsets in place
+        let mut items = items;
+        for (i, next, lower) in links {
+            if let Some(next) = next {
+                items[i].put(DataElement::new(
+                    tags::OFFSET_OF_THE_NEXT_DIRECTORY_RECORD,
+                    VR::UL,
+                    dicom_value!(U32, [offsets[next]]),
+                ));
+            }
+            if let Some(lower) = lower {
+                items[i].put(DataElement::new(
+                    tags::OFFSET_OF_REFERENCED_LOWER_LEVEL_DIRECTORY_ENTITY,
+                    VR::UL,
+                    dicom_value!(U32, [offsets[lower]]),
+                ));
+            }
+        }
+        encode(items, offsets[0])
+    }
+
+    #[test]
+    fn read_record_tree() {
+        let data = sample_dicomdir();
+        let mut dir = DicomDir::from_reader(&data[..]).unwrap();
+        dir.set_base_dir("/media/cdrom");
+
+        assert_eq!(dir.file_set_id().as_deref(), Some("SAMPLE"));
+        assert_eq!(dir.root_records().len(), 1);
+
+        let patient = &dir.root_records()[0];
+        assert_eq!(patient.record_type(), &RecordType::Patient);
+        let study = &patient.children()[0];
+        assert_eq!(study.record_type(), &RecordType::Study);
+        let series = &study.children()[0];
+        assert_eq!(series.record_type(), &RecordType::Series);
+        let images = series.children();
+        assert_eq!(images.len(), 2);
+        assert!(images.iter().all(|r| r.record_type() == &RecordType::Image));
+        assert!(images.iter().all(|r| r.is_in_use()));
+
+        let types: Vec<_> = dir.iter().map(|r| r.record_type().as_str()).collect();
+        assert_eq!(types, ["PATIENT", "STUDY", "SERIES", "IMAGE", "IMAGE"]);
+
+        // navigation by offset
+        let offset = images[1].offset();
+        assert_eq!(dir.record_at(offset), Some(&images[1]));
+
+        // file resolution
+        assert_eq!(
+            images[0].referenced_file_id(),
+            Some(vec!["IMAGES".to_string(), "IM0001".to_string()])
+        );
+        assert_eq!(
+            dir.resolve_file(&images[1]),
+            Some(Path::new("/media/cdrom").join("IMAGES").join("IM0002"))
+        );
+        assert_eq!(dir.resolve_file(patient), None);
+    }
+
+    #[test]
+    fn record_type_codes() {
+        assert_eq!(RecordType::from("RT DOSE "), RecordType::RtDose);
+        assert_eq!(RecordType::from("IMAGE"), RecordType::Image);
+        assert_eq!(
+            RecordType::from("CUSTOM"),
+            RecordType::Other("CUSTOM".to_string())
+        );
+        assert_eq!(RecordType::SrDocument.to_string(), "SR DOCUMENT");
+    }
+
+    fn instance(
+        patient_id: &str,
+        study_uid: &str,
+        series_uid: &str,
+        sop_instance_uid: &str,
+    ) -> FileDicomObject<InMemDicomObject> {
+        let mut obj = InMemDicomObject::new_empty();
+        for (tag, vr, value) in [
+            (tags::PATIENT_NAME, VR::PN, "Doe^John"),
+            (tags::PATIENT_ID, VR::LO, patient_id),
+            (tags::STUDY_DATE, VR::DA, "20240101"),
+            (tags::STUDY_TIME, VR::TM, "120000"),
+            (tags::STUDY_INSTANCE_UID, VR::UI, study_uid),
+            (tags::STUDY_ID, VR::SH, "1"),
+            (tags::MODALITY, VR::CS, "CT"),
+            (tags::SERIES_INSTANCE_UID, VR::UI, series_uid),
+            (tags::SERIES_NUMBER, VR::IS, "1"),
+            (tags::INSTANCE_NUMBER, VR::IS, "1"),
+            (tags::SOP_INSTANCE_UID, VR::UI, sop_instance_uid),
+        ] {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        }
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.2")
+                .media_storage_sop_instance_uid(sop_instance_uid)
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn build_and_read_dicomdir() {
+        let mut builder = DicomDirBuilder::new().file_set_id("TEST");
+        builder
+            .add_file(
+                ["IMAGES", "IM0001"],
+                &instance("P1", "2.25.10", "2.25.100", "2.25.1001"),
+            )
+            .unwrap();
+        builder
+            .add_file(
+                ["IMAGES", "IM0002"],
+                &instance("P1", "2.25.10", "2.25.100", "2.25.1002"),
+            )
+            .unwrap();
+        builder
+            .add_file(
+                ["IMAGES", "IM0003"],
+                &instance("P1", "2.25.20", "2.25.200", "2.25.2001"),
+            )
+            .unwrap();
+        builder
+            .add_file(
+                ["IMAGES", "IM0004"],
+                &instance("P2", "2.25.30", "2.25.300", "2.25.3001"),
+            )
+            .unwrap();
+
+        let obj = builder.build().unwrap();
+        let mut data = Vec::new();
+        obj.write_all(&mut data).unwrap();
+
+        let dir = DicomDir::from_reader(&data[..]).unwrap();
+        assert_eq!(dir.file_set_id().as_deref(), Some("TEST"));
+
+        let patients = dir.root_records();
+        assert_eq!(patients.len(), 2);
+        assert_eq!(patients[0].children().len(), 2);
+        assert_eq!(patients[0].children()[0].children()[0].children().len(), 2);
+        assert_eq!(patients[1].children().len(), 1);
+
+        let types: Vec<_> = dir.iter().map(|r| r.record_type().as_str()).collect();
+        assert_eq!(
+            types,
+            [
+                "PATIENT", "STUDY", "SERIES", "IMAGE", "IMAGE", "STUDY", "SERIES", "IMAGE",
+                "PATIENT", "STUDY", "SERIES", "IMAGE",
+            ]
+        );
+
+        let image = &patients[1].children()[0].children()[0].children()[0];
+        assert_eq!(
+            image.referenced_file_id(),
+            Some(vec!["IMAGES".to_string(), "IM0004".to_string()])
+        );
+        assert_eq!(
+            image.referenced_sop_instance_uid().as_deref(),
+            Some("2.25.3001")
+        );
+        assert_eq!(
+            image.referenced_sop_class_uid().as_deref(),
+            Some("1.2.840.10008.5.1.4.1.1.2")
+        );
+
+        // root record offsets
+        let last = dir
+            .dataset()
+            .element(tags::OFFSET_OF_THE_LAST_DIRECTORY_RECORD_OF_THE_ROOT_DIRECTORY_ENTITY)
+            .unwrap()
+            .to_int::<u32>()
+            .unwrap();
+        assert_eq!(last, patients[1].offset());
+    }
+
+    #[test]
+    fn build_rejects_invalid_input() {
+        let mut builder = DicomDirBuilder::new();
+        let obj = instance("P1", "2.25.10", "2.25.100", "2.25.1001");
+        assert!(matches!(
+            builder.add_file(["images", "im0001.dcm"], &obj),
+            Err(Error::InvalidFileId { .. })
+        ));
+
+        let mut obj = obj;
+        obj.remove_element(tags::SERIES_INSTANCE_UID);
+        assert!(matches!(
+            builder.add_file(["IM0001"], &obj),
+            Err(Error::MissingAttribute { tag, .. }) if tag == tags::SERIES_INSTANCE_UID
+        ));
+    }
+
+    #[test]
+    fn build_dicomdir_from_directory() {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::create_dir(root.path().join("IMAGES")).unwrap();
+        instance("P1", "2.25.10", "2.25.100", "2.25.1001")
+            .write_to_file(root.path().join("IMAGES").join("IM0001"))
+            .unwrap();
+        instance("P1", "2.25.10", "2.25.100", "2.25.1002")
+            .write_to_file(root.path().join("IMAGES").join("IM0002"))
+            .unwrap();
+        std::fs::write(root.path().join("README"), b"not a DICOM file").unwrap();
+        // not a valid file ID
+        instance("P1", "2.25.10", "2.25.100", "2.25.1003")
+            .write_to_file(root.path().join("IMAGES").join("im0003.dcm"))
+            .unwrap();
+        // without the required series key
+        let mut obj = instance("P1", "2.25.10", "2.25.100", "2.25.1004");
+        obj.remove_element(tags::SERIES_INSTANCE_UID);
+        obj.write_to_file(root.path().join("IMAGES").join("IM0004"))
+            .unwrap();
+
+        let mut builder = DicomDirBuilder::new();
+        let skipped = builder.add_dir(root.path()).unwrap();
+        assert_eq!(skipped.len(), 2);
+        assert!(matches!(skipped[0], Error::MissingAttribute { .. }));
+        assert!(matches!(skipped[1], Error::InvalidFileId { .. }));
+        let dicomdir_path = root.path().join("DICOMDIR");
+        builder
+            .build()
+            .unwrap()
+            .write_to_file(&dicomdir_path)
+            .unwrap();
+
+        let dir = DicomDir::open_file(&dicomdir_path).unwrap();
+        let images: Vec<_> = dir
+            .iter()
+            .filter(|r| r.record_type() == &RecordType::Image)
+            .collect();
+        assert_eq!(images.len(), 2);
+        for image in images {
+            let path = dir.resolve_file(image).unwrap();
+            assert!(path.is_file());
+        }
+
+        // scanning again ignores the existing DICOMDIR
+        let mut builder = DicomDirBuilder::new();
+        builder.add_dir(root.path()).unwrap();
+        let obj = builder.build().unwrap();
+        let records = obj.element(tags::DIRECTORY_RECORD_SEQUENCE).unwrap();
+        assert_eq!(records.items().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn instance_record_types() {
+        assert_eq!(
+            instance_record_type("1.2.840.10008.5.1.4.1.1.2"),
+            RecordType::Image
+        );
+        assert_eq!(
+            instance_record_type("1.2.840.10008.5.1.4.1.1.88.22\0"),
+            RecordType::SrDocument
+        );
+        assert_eq!(
+            instance_record_type(uids::KEY_OBJECT_SELECTION_DOCUMENT_STORAGE),
+            RecordType::KeyObjectDoc
+        );
+        assert_eq!(
+            instance_record_type("1.2.840.10008.5.1.4.1.1.11.1"),
+            RecordType::Presentation
+        );
+        assert_eq!(
+            instance_record_type("1.2.840.10008.5.1.4.1.1.481.2"),
+            RecordType::RtDose
+        );
+        assert_eq!(
+            instance_record_type("1.2.840.10008.5.1.4.1.1.104.1"),
+            RecordType::EncapDoc
+        );
+        // not to be confused with waveforms (1.2.840.10008.5.1.4.1.1.9)
+        assert_eq!(
+            instance_record_type("1.2.840.10008.5.1.4.1.1.90.1"),
+            RecordType::Image
+        );
+        assert_eq!(instance_record_type("2.25.1234"), RecordType::Private);
+    }
+}
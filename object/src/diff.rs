@@ -0,0 +1,503 @@
+//! Structured comparison of DICOM objects.
+//!
+//! [`diff`] lists every [`Difference`] between two DICOM files,
+//! including those found inside sequence items,
+//! each located by a [`DicomPath`] in which attributes are given by tag.
+//! This is useful for regression testing
+//! of transcoding and anonymization pipelines.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::diff::{diff_with_options, DiffOptions};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let original = open_file("original.dcm")?;
+//! let transcoded = open_file("transcoded.dcm")?;
+//! let options = DiffOptions::new().ignore_meta_group();
+//! for difference in diff_with_options(&original, &transcoded, &options) {
+//!     println!("{}", difference);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::fmt;
+
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::header::Header;
+use dicom_core::value::Value;
+use dicom_core::{DataElement, Tag};
+use dicom_dictionary_std::StandardDataDictionary;
+
+use crate::mem::{InMemDicomObject, InMemElement};
+use crate::meta::FileMetaTable;
+use crate::path::{DicomPath, SequenceStep};
+use crate::FileDicomObject;
+
+/// A set of options for comparing DICOM objects.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct DiffOptions {
+    /// the tags of the attributes to ignore, at any nesting level
+    ignore_tags: BTreeSet<Tag>,
+    /// whether to ignore the file meta group
+    ignore_meta_group: bool,
+}
+
+impl DiffOptions {
+    /// Create a new set of options,
+    /// in which all attributes are compared.
+    pub fn new() -> Self {
+        DiffOptions::default()
+    }
+
+    /// Ignore the attribute with the given tag,
+    /// wherever it appears in the data set.
+    pub fn ignore_tag(mut self, tag: Tag) -> Self {
+        self.ignore_tags.insert(tag);
+        self
+    }
+
+    /// Ignore the attributes with the given tags,
+    /// wherever they appear in the data set.
+    pub fn ignore_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        self.ignore_tags.extend(tags);
+        self
+    }
+
+    /// Ignore the file meta group (group 0002),
+    /// which usually changes when a file is transcoded.
+    pub fn ignore_meta_group(mut self) -> Self {
+        self.ignore_meta_group = true;
+        self
+    }
+
+    fn is_ignored(&self, tag: Tag) -> bool {
+        self.ignore_tags.contains(&tag)
+    }
+}
+
+/// A single difference between two DICOM objects.
+///
+/// Elements are considered different
+/// if they differ in value representation or in value.
+/// Sequences of items are compared item by item.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Difference<D = StandardDataDictionary> {
+    /// The element is only present in the second object.
+    Added {
+        /// the location of the element
+        path: DicomPath,
+        /// the element in the second object
+        element: InMemElement<D>,
+    },
+    /// The element is only present in the first object.
+    Removed {
+        /// the location of the element
+        path: DicomPath,
+        /// the element in the first object
+        element: InMemElement<D>,
+    },
+    /// The element is present in both objects with different contents.
+    Changed {
+        /// the location of the element
+        path: DicomPath,
+        /// the element in the first object
+        old: InMemElement<D>,
+        /// the element in the second object
+        new: InMemElement<D>,
+    },
+    /// The sequence has an item only present in the second object.
+    ItemAdded {
+        /// the location of the sequence
+        path: DicomPath,
+        /// the zero-based index of the item
+        index: u32,
+        /// the item in the second object
+        item: InMemDicomObject<D>,
+    },
+    /// The sequence has an item only present in the first object.
+    ItemRemoved {
+        /// the location of the sequence
+        path: DicomPath,
+        /// the zero-based index of the item
+        index: u32,
+        /// the item in the first object
+        item: InMemDicomObject<D>,
+    },
+}
+
+impl<D> Difference<D> {
+    /// Obtain the location of the element with the difference,
+    /// or of the sequence for differences in its items.
+    pub fn path(&self) -> &DicomPath {
+        match self {
+            Difference::Added { path, .. }
+            | Difference::Removed { path, .. }
+            | Difference::Changed { path, .. }
+            | Difference::ItemAdded { path, .. }
+            | Difference::ItemRemoved { path, .. } => path,
+        }
+    }
+}
+
+impl<D> fmt::Display for Difference<D>
+where
+    D: DataDictionary,
+    D: Clone,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Difference::Added { path, element } => {
+                write!(f, "+ {} {}: {}", path, element.vr(), ValueSummary(element))
+            }
+            Difference::Removed { path, element } => {
+                write!(f, "- {} {}: {}", path, element.vr(), ValueSummary(element))
+            }
+            Difference::Changed { path, old, new } => write!(
+                f,
+                "~ {} {}: {} -> {}: {}",
+                path,
+                old.vr(),
+                ValueSummary(old),
+                new.vr(),
+                ValueSummary(new)
+            ),
+            Difference::ItemAdded { path, index, item } => write!(
+                f,
+                "+ {}[{}]: item with {} elements",
+                path,
+                index,
+                item.iter().count()
+            ),
+            Difference::ItemRemoved { path, index, item } => write!(
+                f,
+                "- {}[{}]: item with {} elements",
+                path,
+                index,
+                item.iter().count()
+            ),
+        }
+    }
+}
+
+/// A short textual description of an element's value.
+struct ValueSummary<'a, D>(&'a InMemElement<D>);
+
+impl<D> fmt::Display for ValueSummary<'_, D> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0.value() {
+            Value::Primitive(value) => write!(f, "[{}]", value.to_str()),
+            Value::Sequence { items, .. } => write!(f, "sequence with {} items", items.len()),
+            Value::PixelSequence { fragments, .. } => {
+                write!(f, "pixel sequence with {} fragments", fragments.len())
+            }
+        }
+    }
+}
+
+/// List the differences between two DICOM files,
+/// including their file meta groups.
+pub fn diff<D>(
+    a: &FileDicomObject<InMemDicomObject<D>>,
+    b: &FileDicomObject<InMemDicomObject<D>>,
+) -> Vec<Difference<D>>
+where
+    D: DataDictionary,
+    D: Clone,
+{
+    diff_with_options(a, b, &DiffOptions::default())
+}
+
+/// List the differences between two DICOM files
+/// according to the given options.
+pub fn diff_with_options<D>(
+    a: &FileDicomObject<InMemDicomObject<D>>,
+    b: &FileDicomObject<InMemDicomObject<D>>,
+    options: &DiffOptions,
+) -> Vec<Difference<D>>
+where
+    D: DataDictionary,
+    D: Clone,
+{
+    let mut out = Vec::new();
+    if !options.ignore_meta_group {
+        let meta_a = meta_object(a.meta(), a);
+        let meta_b = meta_object(b.meta(), b);
+        diff_into(&meta_a, &meta_b, &[], options, &mut out);
+    }
+    diff_into(a, b, &[], options, &mut out);
+    out
+}
+
+/// List the differences between two data sets
+/// according to the given options.
+///
+/// Unlike [`diff_with_options`],
+/// this does not involve file meta groups.
+pub fn diff_data_sets<D>(
+    a: &InMemDicomObject<D>,
+    b: &InMemDicomObject<D>,
+    options: &DiffOptions,
+) -> Vec<Difference<D>>
+where
+    D: DataDictionary,
+    D: Clone,
+{
+    let mut out = Vec::new();
+    diff_into(a, b, &[], options, &mut out);
+    out
+}
+
+/// Build an object with the elements of a file meta group,
+/// so that it can be compared like any other data set.
+fn meta_object<D>(meta: &FileMetaTable, obj: &InMemDicomObject<D>) -> InMemDicomObject<D>
+where
+    D: DataDictionary,
+    D: Clone,
+{
+    let mut out = InMemDicomObject::new_empty_with_dict(obj.dict().clone());
+    for element in meta.to_element_iter() {
+        if let Value::Primitive(value) = element.value() {
+            out.put(DataElement::new(element.tag(), element.vr(), value.clone()));
+        }
+    }
+    out
+}
+
+fn diff_into<D>(
+    a: &InMemDicomObject<D>,
+    b: &InMemDicomObject<D>,
+    steps: &[SequenceStep],
+    options: &DiffOptions,
+    out: &mut Vec<Difference<D>>,
+) where
+    D: DataDictionary,
+    D: Clone,
+{
+    let path = |tag: Tag| DicomPath::new(steps.to_vec(), tag.to_string());
+    let filter = |e: &&InMemElement<D>| !options.is_ignored(e.tag());
+    let mut elements_a = a.iter().filter(filter).peekable();
+    let mut elements_b = b.iter().filter(filter).peekable();
+
+    loop {
+        let ordering = match (elements_a.peek(), elements_b.peek()) {
+            (None, None) => break,
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some(ea), Some(eb)) => ea.tag().cmp(&eb.tag()),
+        };
+        match ordering {
+            Ordering::Less => {
+                let ea = elements_a.next().unwrap();
+                out.push(Difference::Removed {
+                    path: path(ea.tag()),
+                    element: ea.clone(),
+                });
+            }
+            Ordering::Greater => {
+                let eb = elements_b.next().unwrap();
+                out.push(Difference::Added {
+                    path: path(eb.tag()),
+                    element: eb.clone(),
+                });
+            }
+            Ordering::Equal => {
+                let ea = elements_a.next().unwrap();
+                let eb = elements_b.next().unwrap();
+                diff_elements(ea, eb, steps, options, out);
+            }
+        }
+    }
+}
+
+fn diff_elements<D>(
+    a: &InMemElement<D>,
+    b: &InMemElement<D>,
+    steps: &[SequenceStep],
+    options: &DiffOptions,
+    out: &mut Vec<Difference<D>>,
+) where
+    D: DataDictionary,
+    D: Clone,
+{
+    let attribute = a.tag().to_string();
+    match (a.value(), b.value()) {
+        (Value::Sequence { items: items_a, .. }, Value::Sequence { items: items_b, .. }) => {
+            for (index, (item_a, item_b)) in items_a.iter().zip(items_b.iter()).enumerate() {
+                let mut item_steps = steps.to_vec();
+                item_steps.push(SequenceStep {
+                    attribute: attribute.clone(),
+                    item: index as u32,
+                });
+                diff_into(item_a, item_b, &item_steps, options, out);
+            }
+            for (index, item) in items_a.iter().enumerate().skip(items_b.len()) {
+                out.push(Difference::ItemRemoved {
+                    path: DicomPath::new(steps.to_vec(), attribute.clone()),
+                    index: index as u32,
+                    item: item.clone(),
+                });
+            }
+            for (index, item) in items_b.iter().enumerate().skip(items_a.len()) {
+                out.push(Difference::ItemAdded {
+                    path: DicomPath::new(steps.to_vec(), attribute.clone()),
+                    index: index as u32,
+                    item: item.clone(),
+                });
+            }
+        }
+        (value_a, value_b) if a.vr() == b.vr() && value_a == value_b => {}
+        _ => out.push(Difference::Changed {
+            path: DicomPath::new(steps.to_vec(), attribute),
+            old: a.clone(),
+            new: b.clone(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{diff, diff_data_sets, diff_with_options, DiffOptions, Difference};
+    use crate::mem::InMemDicomObject;
+    use crate::meta::FileMetaTableBuilder;
+    use dicom_core::header::Header;
+    use dicom_core::value::Value;
+    use dicom_core::{DataElement, Length, PrimitiveValue, VR};
+    use dicom_dictionary_std::tags;
+
+    fn item(code_value: &str) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([DataElement::new(
+            tags::CODE_VALUE,
+            VR::SH,
+            PrimitiveValue::from(code_value),
+        )])
+    }
+
+    fn base_object() -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.1"),
+            ),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(
+                tags::ANATOMIC_REGION_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![item("T-D1213")].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn diff_identical_objects() {
+        let obj = base_object();
+        assert!(diff_data_sets(&obj, &obj.clone(), &DiffOptions::new()).is_empty());
+    }
+
+    #[test]
+    fn diff_added_removed_changed() {
+        let a = base_object();
+        let mut b = base_object();
+        b.remove_element(tags::SOP_INSTANCE_UID);
+        b.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^Jane"),
+        ));
+        b.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("12345"),
+        ));
+
+        let differences = diff_data_sets(&a, &b, &DiffOptions::new());
+        assert_eq!(differences.len(), 3);
+        assert!(matches!(
+            &differences[0],
+            Difference::Removed { element, .. } if element.tag() == tags::SOP_INSTANCE_UID
+        ));
+        assert!(matches!(
+            &differences[1],
+            Difference::Changed { old, new, .. }
+                if old.tag() == tags::PATIENT_NAME && new.tag() == tags::PATIENT_NAME
+        ));
+        assert!(matches!(
+            &differences[2],
+            Difference::Added { element, .. } if element.tag() == tags::PATIENT_ID
+        ));
+        assert_eq!(differences[1].path().to_string(), "(0010,0010)");
+        assert_eq!(
+            differences[1].to_string(),
+            "~ (0010,0010) PN: [Doe^John] -> PN: [Doe^Jane]"
+        );
+
+        // ignored tags are not compared
+        let options = DiffOptions::new().ignore_tags([tags::SOP_INSTANCE_UID, tags::PATIENT_ID]);
+        let differences = diff_data_sets(&a, &b, &options);
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].path().attribute(), "(0010,0010)");
+    }
+
+    #[test]
+    fn diff_inside_sequences() {
+        let a = base_object();
+        let mut b = base_object();
+        b.put(DataElement::new(
+            tags::ANATOMIC_REGION_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: vec![item("T-D1100"), item("T-D1213")].into(),
+                size: Length::UNDEFINED,
+            },
+        ));
+
+        let differences = diff_data_sets(&a, &b, &DiffOptions::new());
+        assert_eq!(differences.len(), 2);
+        assert_eq!(
+            differences[0].path().to_string(),
+            "(0008,2218)[0].(0008,0100)"
+        );
+        assert!(matches!(&differences[0], Difference::Changed { .. }));
+        assert!(matches!(
+            &differences[1],
+            Difference::ItemAdded { index: 1, .. }
+        ));
+        assert_eq!(differences[1].path().to_string(), "(0008,2218)");
+
+        // the tag is ignored inside sequences as well
+        let options = DiffOptions::new().ignore_tag(tags::CODE_VALUE);
+        let differences = diff_data_sets(&a, &b, &options);
+        assert_eq!(differences.len(), 1);
+    }
+
+    #[test]
+    fn diff_meta_group() {
+        let meta = |ts: &str| {
+            FileMetaTableBuilder::new()
+                .transfer_syntax(ts)
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .media_storage_sop_instance_uid("2.25.1")
+                .build()
+                .unwrap()
+        };
+        let a = base_object().with_exact_meta(meta("1.2.840.10008.1.2.1"));
+        let b = base_object().with_exact_meta(meta("1.2.840.10008.1.2"));
+
+        let differences = diff(&a, &b);
+        assert!(differences
+            .iter()
+            .any(|d| d.path().attribute() == "(0002,0010)"));
+        assert!(diff_with_options(&a, &b, &DiffOptions::new().ignore_meta_group()).is_empty());
+    }
+}
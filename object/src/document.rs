@@ -0,0 +1,450 @@
+//! Encapsulated documents, such as PDF reports and HL7 CDA documents.
+//!
+//! [`extract_document`] takes the document stream
+//! out of an _Encapsulated PDF_ or _Encapsulated CDA_ object,
+//! along with its MIME type and title,
+//! and [`EncapsulatedPdfBuilder`] wraps a PDF file
+//! into a new _Encapsulated PDF_ object.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::document::{extract_document, EncapsulatedPdfBuilder};
+//! use dicom_object::open_file;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! // save the report of an encapsulated PDF object
+//! let obj = open_file("report.dcm")?;
+//! let document = extract_document(&obj)?;
+//! if document.is_pdf() {
+//!     std::fs::write("report.pdf", &document.data)?;
+//! }
+//!
+//! // and wrap it back into the same study as an image
+//! let image = open_file("ct_0001.dcm")?;
+//! let obj = EncapsulatedPdfBuilder::new(std::fs::read("report.pdf")?)
+//!     .source(&image)
+//!     .document_title("Radiology Report")
+//!     .build()?;
+//! obj.write_to_file("report_copy.dcm")?;
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::multiframe::new_uid;
+use crate::sr::{current_date_time, PATIENT_STUDY_ATTRIBUTES};
+use crate::{FileDicomObject, FileMetaTableBuilder, InMemDicomObject};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from the object.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// The data to encapsulate is not a PDF document.
+    #[snafu(display("Data is not a PDF document"))]
+    NotPdf { backtrace: Backtrace },
+
+    /// The file meta group of the new object could not be built.
+    #[snafu(display("Could not build file meta group"))]
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The SOP class UID of _Encapsulated PDF Storage_.
+pub const ENCAPSULATED_PDF_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.104.1";
+
+/// The SOP class UID of _Encapsulated CDA Storage_.
+pub const ENCAPSULATED_CDA_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.104.2";
+
+/// The MIME type of PDF documents.
+const PDF_MIME_TYPE: &str = "application/pdf";
+
+/// The MIME type of HL7 CDA documents.
+const CDA_MIME_TYPE: &str = "text/XML";
+
+/// A document extracted from an encapsulated document object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EncapsulatedDocument {
+    /// The MIME type of the document,
+    /// such as `application/pdf` or `text/XML`.
+    pub mime_type: String,
+    /// The document title, if present.
+    pub title: Option<String>,
+    /// The document stream, without padding.
+    pub data: Vec<u8>,
+}
+
+impl EncapsulatedDocument {
+    /// Check whether the document is a PDF document.
+    pub fn is_pdf(&self) -> bool {
+        self.mime_type.eq_ignore_ascii_case(PDF_MIME_TYPE)
+    }
+
+    /// Check whether the document is an HL7 CDA document.
+    pub fn is_cda(&self) -> bool {
+        self.mime_type.eq_ignore_ascii_case(CDA_MIME_TYPE)
+    }
+}
+
+/// Extract the encapsulated document of an object.
+///
+/// The padding of _Encapsulated Document_ is removed
+/// according to _Encapsulated Document Length_.
+/// For older objects without this attribute,
+/// a single trailing null byte is taken as padding.
+pub fn extract_document<D>(obj: &InMemDicomObject<D>) -> Result<EncapsulatedDocument>
+where
+    D: DataDictionary + Clone,
+{
+    let elem = obj
+        .element_opt(tags::ENCAPSULATED_DOCUMENT)
+        .ok()
+        .flatten()
+        .context(MissingAttributeSnafu {
+            tag: tags::ENCAPSULATED_DOCUMENT,
+        })?;
+    let mut data = match elem.value() {
+        Value::Primitive(value) => value.to_bytes().into_owned(),
+        _ => {
+            return MissingAttributeSnafu {
+                tag: tags::ENCAPSULATED_DOCUMENT,
+            }
+            .fail()
+        }
+    };
+
+    match obj
+        .element_opt(tags::ENCAPSULATED_DOCUMENT_LENGTH)
+        .ok()
+        .flatten()
+    {
+        Some(e) => {
+            let len: u32 = e.to_int().context(InvalidAttributeSnafu {
+                tag: tags::ENCAPSULATED_DOCUMENT_LENGTH,
+            })?;
+            data.truncate(len as usize);
+        }
+        None => {
+            if data.len() % 2 == 0 && data.last() == Some(&0) {
+                data.pop();
+            }
+        }
+    }
+
+    Ok(EncapsulatedDocument {
+        mime_type: opt_string(obj, tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT).context(
+            MissingAttributeSnafu {
+                tag: tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT,
+            },
+        )?,
+        title: opt_string(obj, tags::DOCUMENT_TITLE).filter(|title| !title.is_empty()),
+        data,
+    })
+}
+
+/// A builder for _Encapsulated PDF_ objects.
+///
+/// The object is created with the attributes required
+/// by the _Encapsulated PDF_ IOD:
+/// patient and study attributes copied from a source object,
+/// a new series of modality `DOC`,
+/// workstation conversion equipment,
+/// and the document with its MIME type, title, and length.
+#[derive(Debug, Clone)]
+pub struct EncapsulatedPdfBuilder {
+    data: Vec<u8>,
+    source: Option<InMemDicomObject>,
+    title: Option<String>,
+    burned_in_annotation: bool,
+    content_date_time: Option<(String, String)>,
+    series_number: i32,
+    instance_number: i32,
+}
+
+impl EncapsulatedPdfBuilder {
+    /// Create a builder for an object encapsulating the given PDF document.
+    pub fn new(data: impl Into<Vec<u8>>) -> Self {
+        EncapsulatedPdfBuilder {
+            data: data.into(),
+            source: None,
+            title: None,
+            burned_in_annotation: true,
+            content_date_time: None,
+            series_number: 1,
+            instance_number: 1,
+        }
+    }
+
+    /// Copy the patient and study attributes of the object
+    /// from the given object.
+    ///
+    /// Without a source object,
+    /// the patient attributes are left empty
+    /// and a new study instance UID is generated.
+    pub fn source<D>(mut self, obj: &InMemDicomObject<D>) -> Self
+    where
+        D: DataDictionary + Clone,
+    {
+        let mut source = InMemDicomObject::new_empty();
+        for &(tag, vr) in PATIENT_STUDY_ATTRIBUTES {
+            if let Ok(Some(e)) = obj.element_opt(tag) {
+                if let Value::Primitive(value) = e.value() {
+                    source.put(DataElement::new(tag, vr, value.clone()));
+                }
+            }
+        }
+        self.source = Some(source);
+        self
+    }
+
+    /// Set the document title.
+    pub fn document_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Declare whether the document contains
+    /// sufficient burned in annotation to identify the patient.
+    /// The default is `true`.
+    pub fn burned_in_annotation(mut self, burned_in: bool) -> Self {
+        self.burned_in_annotation = burned_in;
+        self
+    }
+
+    /// Set the content date (DA) and time (TM) of the object.
+    ///
+    /// If not set, the current time in UTC is used.
+    pub fn content_date_time(mut self, date: impl Into<String>, time: impl Into<String>) -> Self {
+        self.content_date_time = Some((date.into(), time.into()));
+        self
+    }
+
+    /// Set the series number of the object. The default is 1.
+    pub fn series_number(mut self, number: i32) -> Self {
+        self.series_number = number;
+        self
+    }
+
+    /// Set the instance number of the object. The default is 1.
+    pub fn instance_number(mut self, number: i32) -> Self {
+        self.instance_number = number;
+        self
+    }
+
+    /// Build the encapsulated PDF as a new DICOM file object.
+    ///
+    /// Fails if the data does not start with a PDF header.
+    pub fn build(self) -> Result<FileDicomObject<InMemDicomObject>> {
+        ensure!(self.data.starts_with(b"%PDF-"), NotPdfSnafu);
+
+        let mut obj = InMemDicomObject::new_empty();
+        let mut source = self.source.unwrap_or_else(InMemDicomObject::new_empty);
+        if source
+            .element_opt(tags::STUDY_INSTANCE_UID)
+            .ok()
+            .flatten()
+            .is_none()
+        {
+            source.put(DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(new_uid()),
+            ));
+        }
+        for &(tag, vr) in PATIENT_STUDY_ATTRIBUTES {
+            let elem = source
+                .take_element(tag)
+                .unwrap_or_else(|_| DataElement::new(tag, vr, PrimitiveValue::Empty));
+            obj.put(elem);
+        }
+
+        let (date, time) = match self.content_date_time {
+            Some(date_time) => date_time,
+            None => current_date_time(),
+        };
+        for (tag, vr, value) in [
+            (
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                ENCAPSULATED_PDF_STORAGE.to_string(),
+            ),
+            (tags::SOP_INSTANCE_UID, VR::UI, new_uid()),
+            (tags::MODALITY, VR::CS, "DOC".to_string()),
+            (tags::CONVERSION_TYPE, VR::CS, "WSD".to_string()),
+            (tags::SERIES_INSTANCE_UID, VR::UI, new_uid()),
+            (tags::SERIES_NUMBER, VR::IS, self.series_number.to_string()),
+            (
+                tags::INSTANCE_NUMBER,
+                VR::IS,
+                self.instance_number.to_string(),
+            ),
+            (tags::CONTENT_DATE, VR::DA, date),
+            (tags::CONTENT_TIME, VR::TM, time),
+            (
+                tags::BURNED_IN_ANNOTATION,
+                VR::CS,
+                if self.burned_in_annotation {
+                    "YES"
+                } else {
+                    "NO"
+                }
+                .to_string(),
+            ),
+            (tags::DOCUMENT_TITLE, VR::ST, self.title.unwrap_or_default()),
+            (
+                tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT,
+                VR::LO,
+                PDF_MIME_TYPE.to_string(),
+            ),
+        ] {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        }
+        for (tag, vr) in [
+            (tags::MANUFACTURER, VR::LO),
+            (tags::ACQUISITION_DATE_TIME, VR::DT),
+        ] {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::Empty));
+        }
+        obj.put(DataElement::new(
+            tags::CONCEPT_NAME_CODE_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: C::new(),
+                size: Length::UNDEFINED,
+            },
+        ));
+
+        let mut data = self.data;
+        obj.put(DataElement::new(
+            tags::ENCAPSULATED_DOCUMENT_LENGTH,
+            VR::UL,
+            PrimitiveValue::from(data.len() as u32),
+        ));
+        if data.len() % 2 == 1 {
+            data.push(0);
+        }
+        obj.put(DataElement::new(
+            tags::ENCAPSULATED_DOCUMENT,
+            VR::OB,
+            PrimitiveValue::from(data),
+        ));
+
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid(ENCAPSULATED_PDF_STORAGE)
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .context(BuildMetaSnafu)
+    }
+}
+
+fn opt_string<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<String>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag)
+        .ok()
+        .flatten()
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PDF: &[u8] = b"%PDF-1.4\n%%EOF\n";
+
+    #[test]
+    fn wraps_and_extracts_pdf() {
+        let image = InMemDicomObject::from_element_iter([
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.3"),
+            ),
+        ]);
+        let obj = EncapsulatedPdfBuilder::new(PDF)
+            .source(&image)
+            .document_title("Report")
+            .content_date_time("20240101", "120000")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            obj.meta()
+                .media_storage_sop_class_uid
+                .trim_end_matches('\0'),
+            ENCAPSULATED_PDF_STORAGE
+        );
+        assert_eq!(
+            obj.element(tags::STUDY_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "1.2.3"
+        );
+        // odd length documents are padded
+        assert_eq!(
+            obj.element(tags::ENCAPSULATED_DOCUMENT)
+                .unwrap()
+                .to_bytes()
+                .unwrap()
+                .len(),
+            PDF.len() + 1
+        );
+
+        let document = extract_document(&obj).unwrap();
+        assert!(document.is_pdf());
+        assert!(!document.is_cda());
+        assert_eq!(document.title.as_deref(), Some("Report"));
+        assert_eq!(document.data, PDF);
+    }
+
+    #[test]
+    fn extracts_document_without_length() {
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::MIME_TYPE_OF_ENCAPSULATED_DOCUMENT,
+                VR::LO,
+                PrimitiveValue::from("text/XML"),
+            ),
+            DataElement::new(
+                tags::ENCAPSULATED_DOCUMENT,
+                VR::OB,
+                PrimitiveValue::from(&b"<ClinicalDocument/>\0"[..]),
+            ),
+        ]);
+        let document = extract_document(&obj).unwrap();
+        assert!(document.is_cda());
+        assert_eq!(document.title, None);
+        assert_eq!(document.data, b"<ClinicalDocument/>");
+    }
+
+    #[test]
+    fn rejects_data_which_is_not_pdf() {
+        assert!(matches!(
+            EncapsulatedPdfBuilder::new(&b"<html></html>"[..]).build(),
+            Err(Error::NotPdf { .. })
+        ));
+    }
+}
@@ -1,10 +1,20 @@
+use bytes::Bytes;
 use dicom_core::{DataDictionary, Tag};
 use dicom_dictionary_std::StandardDataDictionary;
 use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_parser::dataset::read::DataSetReaderOptions;
+pub use dicom_parser::dataset::read::{ParseIssue, ParseMode, TrailingPaddingStrategy};
 use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
 
+use crate::arena::ArenaDicomObject;
+use crate::intern::StringInterner;
 use crate::{DefaultDicomObject, Result};
+#[cfg(feature = "fs")]
+use crate::{OpenFileSnafu, ReadFileSnafu};
+#[cfg(feature = "fs")]
+use snafu::ResultExt;
 use std::io::Read;
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 /// Create a DICOM object by reading from a byte source.
@@ -22,6 +32,7 @@ where
 ///
 /// This function assumes the standard file encoding structure: 128-byte
 /// preamble, file meta group, and the rest of the data set.
+#[cfg(feature = "fs")]
 pub fn open_file<P>(path: P) -> Result<DefaultDicomObject>
 where
     P: AsRef<Path>,
@@ -29,6 +40,40 @@ where
     OpenFileOptions::new().open_file(path)
 }
 
+/// The default capacity of the buffer used when reading a DICOM file,
+/// in bytes.
+pub(crate) const DEFAULT_READ_BUFFER_CAPACITY: usize = 8 * 1024;
+
+/// Create a DICOM object by memory mapping a file.
+///
+/// This function assumes the standard file encoding structure: 128-byte
+/// preamble, file meta group, and the rest of the data set.
+/// See [`OpenFileOptions::open_file_mmap`] for more details.
+///
+/// # Safety
+///
+/// The same requirements as in [`OpenFileOptions::open_file_mmap`] apply.
+#[cfg(feature = "mmap")]
+pub unsafe fn open_file_mmap<P>(path: P) -> Result<DefaultDicomObject>
+where
+    P: AsRef<Path>,
+{
+    OpenFileOptions::new().open_file_mmap(path)
+}
+
+/// Create a DICOM object by asynchronously reading from a file.
+///
+/// This function assumes the standard file encoding structure: 128-byte
+/// preamble, file meta group, and the rest of the data set.
+/// See [`OpenFileOptions::open_file_async`] for more details.
+#[cfg(feature = "async")]
+pub async fn open_file_async<P>(path: P) -> Result<DefaultDicomObject>
+where
+    P: AsRef<Path>,
+{
+    OpenFileOptions::new().open_file_async(path).await
+}
+
 /// A builder type for opening a DICOM file with additional options.
 ///
 /// This builder exposes additional properties
@@ -50,10 +95,19 @@ where
 #[derive(Debug, Default, Clone)]
 #[non_exhaustive]
 pub struct OpenFileOptions<D = StandardDataDictionary, T = TransferSyntaxRegistry> {
-    data_dictionary: D,
-    ts_index: T,
-    read_until: Option<Tag>,
-    read_preamble: ReadPreamble,
+    pub(crate) data_dictionary: D,
+    pub(crate) ts_index: T,
+    pub(crate) read_until: Option<Tag>,
+    pub(crate) read_preamble: ReadPreamble,
+    pub(crate) trailing_padding: TrailingPaddingStrategy,
+    pub(crate) parse_mode: ParseMode,
+    pub(crate) read_buffer_capacity: Option<usize>,
+    pub(crate) strip_group_lengths: bool,
+    pub(crate) preserve_raw_elements: bool,
+    pub(crate) max_value_length: Option<u32>,
+    pub(crate) allocation_budget: Option<u64>,
+    pub(crate) trim_padding: bool,
+    pub(crate) string_interner: Option<StringInterner>,
 }
 
 impl OpenFileOptions {
@@ -88,6 +142,131 @@ impl<D, T> OpenFileOptions<D, T> {
         self
     }
 
+    /// Set how to handle the data set trailing padding element (FFFC,FFFC).
+    ///
+    /// By default, the element is kept in the resulting object.
+    pub fn trailing_padding(mut self, option: TrailingPaddingStrategy) -> Self {
+        self.trailing_padding = option;
+        self
+    }
+
+    /// Set the parsing mode, which determines how violations of the standard
+    /// in the data set are handled.
+    ///
+    /// By default, the data set is parsed in [strict](ParseMode::Strict) mode.
+    pub fn parse_mode(mut self, option: ParseMode) -> Self {
+        self.parse_mode = option;
+        self
+    }
+
+    /// Set the capacity of the [`BufReader`](std::io::BufReader)
+    /// which wraps the file or reader given to
+    /// [`open_file`](Self::open_file) or [`from_reader`](Self::from_reader),
+    /// in bytes.
+    ///
+    /// The parser reads element headers and values
+    /// with many small reads,
+    /// which are served from this buffer
+    /// instead of reaching the underlying source every time.
+    /// A larger buffer results in fewer reads from the source
+    /// at the expense of more memory.
+    /// A capacity of zero disables buffering.
+    ///
+    /// The buffering is done here, in the object layer only:
+    /// the decoders and data set readers of `dicom_parser`
+    /// take any [`Read`] implementation and do no buffering of their own.
+    /// The capacity has no effect when reading raw elements,
+    /// memory mapping, or reading asynchronously.
+    ///
+    /// The default is 8 KiB.
+    pub fn read_buffer_capacity(mut self, capacity: usize) -> Self {
+        self.read_buffer_capacity = Some(capacity);
+        self
+    }
+
+    /// Set whether to remove the group length elements (gggg,0000)
+    /// from the data set after reading it.
+    ///
+    /// Group length elements outside of the file meta group are retired,
+    /// and become stale as soon as the object is modified.
+    /// The file meta group length is always kept.
+    ///
+    /// By default, group length elements are kept in the resulting object.
+    pub fn strip_group_lengths(mut self, strip: bool) -> Self {
+        self.strip_group_lengths = strip;
+        self
+    }
+
+    /// Set whether to retain the original bytes
+    /// of each element in the root data set,
+    /// so that the elements not modified afterwards
+    /// are written back exactly as they were read,
+    /// in their original order.
+    ///
+    /// The whole source is read into memory before parsing it.
+    /// Only the raw bytes of elements are retained:
+    /// when writing the object back with
+    /// [`write_all_preserving_raw`](crate::FileDicomObject::write_all_preserving_raw),
+    /// the preamble and file meta group are encoded anew.
+    /// This option is not honored when reading asynchronously.
+    ///
+    /// By default, the original bytes are not retained.
+    pub fn preserve_raw_elements(mut self, preserve: bool) -> Self {
+        self.preserve_raw_elements = preserve;
+        self
+    }
+
+    /// Set the maximum length of a single element value
+    /// or pixel data fragment to read.
+    ///
+    /// Values declaring a longer length fail with an error
+    /// of the kind [`LimitExceeded`](crate::ErrorKind::LimitExceeded)
+    /// before any memory is allocated for them.
+    /// This is recommended when reading untrusted data.
+    ///
+    /// By default, there is no maximum length.
+    pub fn max_value_length(mut self, max: u32) -> Self {
+        self.max_value_length = Some(max);
+        self
+    }
+
+    /// Set the maximum number of bytes to read
+    /// into element values and pixel data fragments
+    /// over the whole data set.
+    ///
+    /// By default, there is no budget.
+    pub fn allocation_budget(mut self, budget: u64) -> Self {
+        self.allocation_budget = Some(budget);
+        self
+    }
+
+    /// Set whether to remove the trailing spaces and null characters
+    /// of textual values as they are read.
+    ///
+    /// The padding is added back when the object is written.
+    /// This option is not honored when reading into an [arena](crate::arena).
+    ///
+    /// By default, textual values are kept as they were encoded.
+    pub fn trim_padding(mut self, trim: bool) -> Self {
+        self.trim_padding = trim;
+        self
+    }
+
+    /// Set an interner for the textual values read,
+    /// so that equal values are shared
+    /// with all other objects read with the same interner.
+    ///
+    /// This saves memory when keeping many objects of the same series or study,
+    /// at the expense of a lookup for each textual value read.
+    /// This option is not honored when reading into an [arena](crate::arena).
+    /// See the [`intern`](crate::intern) module for more details.
+    ///
+    /// By default, strings are not interned.
+    pub fn intern_strings(mut self, interner: StringInterner) -> Self {
+        self.string_interner = Some(interner);
+        self
+    }
+
     /// Set the transfer syntax index to use when reading the file.
     pub fn tranfer_syntax_index<Tr>(self, ts_index: Tr) -> OpenFileOptions<D, Tr>
     where
@@ -97,6 +276,15 @@ impl<D, T> OpenFileOptions<D, T> {
             data_dictionary: self.data_dictionary,
             read_until: self.read_until,
             read_preamble: self.read_preamble,
+            trailing_padding: self.trailing_padding,
+            parse_mode: self.parse_mode,
+            read_buffer_capacity: self.read_buffer_capacity,
+            strip_group_lengths: self.strip_group_lengths,
+            preserve_raw_elements: self.preserve_raw_elements,
+            max_value_length: self.max_value_length,
+            allocation_budget: self.allocation_budget,
+            trim_padding: self.trim_padding,
+            string_interner: self.string_interner,
             ts_index,
         }
     }
@@ -111,11 +299,21 @@ impl<D, T> OpenFileOptions<D, T> {
             data_dictionary: dict,
             read_until: self.read_until,
             read_preamble: self.read_preamble,
+            trailing_padding: self.trailing_padding,
+            parse_mode: self.parse_mode,
+            read_buffer_capacity: self.read_buffer_capacity,
+            strip_group_lengths: self.strip_group_lengths,
+            preserve_raw_elements: self.preserve_raw_elements,
+            max_value_length: self.max_value_length,
+            allocation_budget: self.allocation_budget,
+            trim_padding: self.trim_padding,
+            string_interner: self.string_interner,
             ts_index: self.ts_index,
         }
     }
 
     /// Open the file at the given path.
+    #[cfg(feature = "fs")]
     pub fn open_file<P>(self, path: P) -> Result<DefaultDicomObject<D>>
     where
         P: AsRef<Path>,
@@ -123,13 +321,79 @@ impl<D, T> OpenFileOptions<D, T> {
         D: Clone,
         T: TransferSyntaxIndex,
     {
-        DefaultDicomObject::open_file_with_all_options(
-            path,
-            self.data_dictionary,
-            self.ts_index,
-            self.read_until,
-            self.read_preamble,
-        )
+        DefaultDicomObject::open_file_with_all_options(path, self, None)
+    }
+
+    /// Open the file at the given path by memory mapping it.
+    ///
+    /// The data set is parsed directly from the mapping,
+    /// and binary values in OB and UN
+    /// [refer to the mapped memory](dicom_core::PrimitiveValue::SharedBytes)
+    /// instead of being copied,
+    /// which keeps peak memory usage low for large files.
+    /// The mapping is released once the object and all of its values
+    /// are dropped.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated,
+    /// by this or any other process,
+    /// for as long as the object or any value taken from it is alive.
+    /// Values referring to the mapping are read without synchronization,
+    /// so a modification of the file is undefined behavior,
+    /// and reading mapped memory past the end of a truncated file
+    /// makes the process receive `SIGBUS` on Unix-like systems.
+    /// Only memory map files which are not expected to change,
+    /// such as those exclusively owned by the application.
+    #[cfg(feature = "mmap")]
+    pub unsafe fn open_file_mmap<P>(self, path: P) -> Result<DefaultDicomObject<D>>
+    where
+        P: AsRef<Path>,
+        D: DataDictionary,
+        D: Clone,
+        T: TransferSyntaxIndex,
+    {
+        DefaultDicomObject::open_file_mmap_with_all_options(path, self, None)
+    }
+
+    /// Open the file at the given path asynchronously,
+    /// so that reading it does not block the current thread.
+    ///
+    /// The file is read in chunks through a [`tokio`] file handle
+    /// and decoded with the same state machine as in [`open_file`](Self::open_file),
+    /// so the read buffer capacity does not apply.
+    #[cfg(feature = "async")]
+    pub async fn open_file_async<P>(self, path: P) -> Result<DefaultDicomObject<D>>
+    where
+        P: AsRef<Path>,
+        D: DataDictionary,
+        D: Clone,
+        T: TransferSyntaxIndex,
+    {
+        DefaultDicomObject::open_file_async_with_all_options(path, self, None).await
+    }
+
+    /// Open the file at the given path,
+    /// also retrieving the [issues](ParseIssue) found
+    /// while reading the data set.
+    ///
+    /// The issues are non-fatal anomalies in the data set,
+    /// such as odd value lengths or values with invalid padding,
+    /// each with the byte offset where it was found.
+    #[cfg(feature = "fs")]
+    pub fn open_file_with_issues<P>(
+        self,
+        path: P,
+    ) -> Result<(DefaultDicomObject<D>, Vec<ParseIssue>)>
+    where
+        P: AsRef<Path>,
+        D: DataDictionary,
+        D: Clone,
+        T: TransferSyntaxIndex,
+    {
+        let mut issues = Vec::new();
+        let obj = DefaultDicomObject::open_file_with_all_options(path, self, Some(&mut issues))?;
+        Ok((obj, issues))
     }
 
     /// Obtain a DICOM object by reading from a byte source.
@@ -144,13 +408,123 @@ impl<D, T> OpenFileOptions<D, T> {
         D: Clone,
         T: TransferSyntaxIndex,
     {
-        DefaultDicomObject::from_reader_with_all_options(
-            from,
-            self.data_dictionary,
-            self.ts_index,
-            self.read_until,
-            self.read_preamble,
-        )
+        DefaultDicomObject::from_reader_with_all_options(from, self, None)
+    }
+
+    /// Obtain a DICOM object by asynchronously reading from a byte source.
+    ///
+    /// This method assumes
+    /// the standard file encoding structure without the preamble:
+    /// file meta group, followed by the rest of the data set.
+    #[cfg(feature = "async")]
+    pub async fn from_reader_async<R>(self, from: R) -> Result<DefaultDicomObject<D>>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+        D: DataDictionary,
+        D: Clone,
+        T: TransferSyntaxIndex,
+    {
+        DefaultDicomObject::from_async_reader_with_all_options(from, self, None).await
+    }
+
+    /// Obtain a DICOM object by reading from an in-memory buffer,
+    /// such as the contents of a memory mapped file.
+    ///
+    /// Binary values in OB and UN
+    /// are kept as [slices of `data`](dicom_core::PrimitiveValue::SharedBytes)
+    /// instead of being copied,
+    /// so that the buffer is shared by the object and its values.
+    ///
+    /// This method assumes
+    /// the standard file encoding structure without the preamble:
+    /// file meta group, followed by the rest of the data set.
+    pub fn from_bytes(self, data: Bytes) -> Result<DefaultDicomObject<D>>
+    where
+        D: DataDictionary,
+        D: Clone,
+        T: TransferSyntaxIndex,
+    {
+        DefaultDicomObject::from_bytes_with_all_options(data, self, None)
+    }
+
+    /// Obtain a DICOM object by reading from a byte source,
+    /// also retrieving the [issues](ParseIssue) found
+    /// while reading the data set.
+    ///
+    /// This method assumes
+    /// the standard file encoding structure without the preamble:
+    /// file meta group, followed by the rest of the data set.
+    pub fn from_reader_with_issues<R>(
+        self,
+        from: R,
+    ) -> Result<(DefaultDicomObject<D>, Vec<ParseIssue>)>
+    where
+        R: Read,
+        D: DataDictionary,
+        D: Clone,
+        T: TransferSyntaxIndex,
+    {
+        let mut issues = Vec::new();
+        let obj = DefaultDicomObject::from_reader_with_all_options(from, self, Some(&mut issues))?;
+        Ok((obj, issues))
+    }
+
+    pub(crate) fn buffer_capacity(&self) -> usize {
+        self.read_buffer_capacity
+            .unwrap_or(DEFAULT_READ_BUFFER_CAPACITY)
+    }
+
+    pub(crate) fn reader_options(&self) -> DataSetReaderOptions {
+        let mut options = DataSetReaderOptions::default()
+            .trailing_padding(self.trailing_padding)
+            .parse_mode(self.parse_mode);
+        options.max_value_length = self.max_value_length;
+        options.allocation_budget = self.allocation_budget;
+        options.trim_padding = self.trim_padding;
+        match self.read_until {
+            Some(tag) => options.read_until(tag),
+            None => options,
+        }
+    }
+}
+
+impl<D> OpenFileOptions<D, TransferSyntaxRegistry> {
+    /// Open the file at the given path into an [arena](crate::arena).
+    ///
+    /// The whole file is read into a single buffer,
+    /// and element values are only decoded when requested.
+    /// The reading options apply as in [`open_file`](OpenFileOptions::open_file),
+    /// except for the read buffer capacity,
+    /// since the file is read at once.
+    #[cfg(feature = "fs")]
+    pub fn open_file_arena<P>(mut self, path: P) -> Result<ArenaDicomObject>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let mut data = Vec::new();
+        std::fs::File::open(path)
+            .with_context(|_| OpenFileSnafu { filename: path })?
+            .read_to_end(&mut data)
+            .with_context(|_| ReadFileSnafu { filename: path })?;
+
+        // like with regular files, the preamble is read by default
+        if self.read_preamble == ReadPreamble::Auto {
+            self.read_preamble = ReadPreamble::Always;
+        }
+        ArenaDicomObject::from_bytes_with_all_options(Bytes::from(data), self)
+    }
+
+    /// Obtain a DICOM object in an [arena](crate::arena)
+    /// by reading from an in-memory buffer.
+    ///
+    /// The arena shares the buffer instead of copying it.
+    ///
+    /// This method assumes
+    /// the standard file encoding structure without the preamble:
+    /// file meta group, followed by the rest of the data set.
+    pub fn from_bytes_arena(self, data: Bytes) -> Result<ArenaDicomObject> {
+        ArenaDicomObject::from_bytes_with_all_options(data, self)
     }
 }
 
@@ -0,0 +1,461 @@
+//! Patient-space geometry of image planes.
+//!
+//! An [`ImagePlane`] gathers the attributes of the _Image Plane_ module
+//! (_Image Position (Patient)_, _Image Orientation (Patient)_,
+//! _Pixel Spacing_ and _Slice Thickness_)
+//! and maps pixel indices to patient coordinates in millimeters,
+//! as described in PS3.3 C.7.6.2.1.1.
+//! [`slice_spacing`] and [`check_consistency`] work on the planes of a series,
+//! inferring the distance between slices
+//! and ensuring that they can be stacked into a volume.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::geometry::ImagePlane;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("ct_slice.dcm")?;
+//! let plane = ImagePlane::from_object(&obj)?;
+//! // patient coordinates of the center of the pixel at row 10, column 20
+//! let point = plane.pixel_to_patient(10., 20.);
+//! println!("{:?} along normal {:?}", point, plane.normal());
+//! # Ok(())
+//! # }
+//! ```
+
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::header::Header;
+use dicom_core::Tag;
+use dicom_dictionary_std::tags;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use std::convert::TryFrom;
+
+use crate::mem::InMemElement;
+use crate::multiframe::FrameInfo;
+use crate::InMemDicomObject;
+
+/// The default tolerance used when comparing directions and distances.
+pub const DEFAULT_TOLERANCE: f64 = 1e-3;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from the object.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// An attribute does not have the expected number of values.
+    #[snafu(display("Expected {} values in attribute {}, got {}", expected, tag, got))]
+    UnexpectedMultiplicity {
+        tag: Tag,
+        expected: usize,
+        got: usize,
+        backtrace: Backtrace,
+    },
+
+    /// The direction cosines are not two orthogonal unit vectors.
+    #[snafu(display("Image orientation is not made of orthogonal unit vectors"))]
+    InvalidOrientation { backtrace: Backtrace },
+
+    /// Not enough planes were given to infer the slice spacing.
+    #[snafu(display("At least two slices are required, got {}", count))]
+    NotEnoughSlices { count: usize, backtrace: Backtrace },
+
+    /// A plane has a different orientation from the first plane.
+    #[snafu(display("Slice #{} has a different orientation", index))]
+    OrientationMismatch { index: usize, backtrace: Backtrace },
+
+    /// A plane has a different pixel spacing from the first plane.
+    #[snafu(display("Slice #{} has a different pixel spacing", index))]
+    PixelSpacingMismatch { index: usize, backtrace: Backtrace },
+
+    /// A plane is not positioned along the normal of the first plane.
+    #[snafu(display("Slice #{} is not aligned with the slice normal", index))]
+    NotAligned { index: usize, backtrace: Backtrace },
+
+    /// Two planes are at the same position.
+    #[snafu(display("Slice #{} is at the same position as another slice", index))]
+    DuplicatePosition { index: usize, backtrace: Backtrace },
+
+    /// The distance between consecutive planes is not uniform.
+    #[snafu(display(
+        "Slice #{} is {} mm away from the previous slice, expected {} mm",
+        index,
+        got,
+        expected
+    ))]
+    NonUniformSpacing {
+        index: usize,
+        expected: f64,
+        got: f64,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The position and orientation of an image plane in patient space.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ImagePlane {
+    /// The patient coordinates of the center of the first pixel,
+    /// from _Image Position (Patient)_.
+    pub position: [f64; 3],
+    /// The direction of increasing column index along a row,
+    /// from the first three values of _Image Orientation (Patient)_.
+    pub row_direction: [f64; 3],
+    /// The direction of increasing row index along a column,
+    /// from the last three values of _Image Orientation (Patient)_.
+    pub column_direction: [f64; 3],
+    /// The distance between the centers of adjacent pixels
+    /// as (row spacing, column spacing), from _Pixel Spacing_.
+    pub pixel_spacing: [f64; 2],
+    /// The nominal slice thickness, from _Slice Thickness_.
+    pub slice_thickness: Option<f64>,
+}
+
+impl ImagePlane {
+    /// Create an image plane from the values of
+    /// _Image Position (Patient)_, _Image Orientation (Patient)_
+    /// and _Pixel Spacing_.
+    pub fn new(position: [f64; 3], orientation: [f64; 6], pixel_spacing: [f64; 2]) -> Self {
+        ImagePlane {
+            position,
+            row_direction: [orientation[0], orientation[1], orientation[2]],
+            column_direction: [orientation[3], orientation[4], orientation[5]],
+            pixel_spacing,
+            slice_thickness: None,
+        }
+    }
+
+    /// Set the nominal slice thickness.
+    pub fn with_slice_thickness(mut self, slice_thickness: f64) -> Self {
+        self.slice_thickness = Some(slice_thickness);
+        self
+    }
+
+    /// Read the image plane from the top level attributes of an object.
+    pub fn from_object<D>(obj: &InMemDicomObject<D>) -> Result<Self>
+    where
+        D: DataDictionary + Clone,
+    {
+        let position = floats(required(obj, tags::IMAGE_POSITION_PATIENT)?)?;
+        let orientation = floats(required(obj, tags::IMAGE_ORIENTATION_PATIENT)?)?;
+        let pixel_spacing = floats(required(obj, tags::PIXEL_SPACING)?)?;
+        let slice_thickness = match obj.element_opt(tags::SLICE_THICKNESS).ok().flatten() {
+            Some(e) => Some(
+                e.to_float64()
+                    .context(InvalidAttributeSnafu { tag: e.tag() })?,
+            ),
+            None => None,
+        };
+        Ok(ImagePlane {
+            slice_thickness,
+            ..ImagePlane::new(position, orientation, pixel_spacing)
+        })
+    }
+
+    /// Obtain the image plane of a frame of an enhanced multi-frame object,
+    /// if all of its attributes are available.
+    pub fn from_frame_info(info: &FrameInfo) -> Option<Self> {
+        Some(ImagePlane {
+            slice_thickness: info.slice_thickness,
+            ..ImagePlane::new(
+                info.image_position?,
+                info.image_orientation?,
+                info.pixel_spacing?,
+            )
+        })
+    }
+
+    /// Check that the direction cosines are unit vectors
+    /// orthogonal to each other, within the given tolerance.
+    pub fn check_orientation(&self, tolerance: f64) -> Result<()> {
+        ensure!(
+            (norm(self.row_direction) - 1.).abs() <= tolerance
+                && (norm(self.column_direction) - 1.).abs() <= tolerance
+                && dot(self.row_direction, self.column_direction).abs() <= tolerance,
+            InvalidOrientationSnafu
+        );
+        Ok(())
+    }
+
+    /// The unit vector normal to the plane,
+    /// as the cross product of the row and column directions.
+    pub fn normal(&self) -> [f64; 3] {
+        normalize(cross(self.row_direction, self.column_direction))
+    }
+
+    /// The distance of the plane from the origin along its normal.
+    ///
+    /// Sorting the slices of a series by this value
+    /// sorts them in space.
+    pub fn slice_location(&self) -> f64 {
+        dot(self.position, self.normal())
+    }
+
+    /// Obtain the patient coordinates of the given (fractional) pixel position,
+    /// where (0, 0) is the center of the first pixel.
+    pub fn pixel_to_patient(&self, row: f64, column: f64) -> [f64; 3] {
+        let [row_spacing, column_spacing] = self.pixel_spacing;
+        let mut out = self.position;
+        for (i, p) in out.iter_mut().enumerate() {
+            *p += self.row_direction[i] * column_spacing * column
+                + self.column_direction[i] * row_spacing * row;
+        }
+        out
+    }
+
+    /// Obtain the affine transformation from voxel indices
+    /// (column, row, slice, 1) to patient coordinates,
+    /// for a volume starting at this plane
+    /// and with slices stacked along the normal
+    /// at the given spacing.
+    ///
+    /// The matrix is in row-major order.
+    pub fn voxel_to_patient(&self, slice_spacing: f64) -> [[f64; 4]; 4] {
+        let [row_spacing, column_spacing] = self.pixel_spacing;
+        let normal = self.normal();
+        let mut matrix = [[0.; 4]; 4];
+        for (i, row) in matrix.iter_mut().take(3).enumerate() {
+            *row = [
+                self.row_direction[i] * column_spacing,
+                self.column_direction[i] * row_spacing,
+                normal[i] * slice_spacing,
+                self.position[i],
+            ];
+        }
+        matrix[3] = [0., 0., 0., 1.];
+        matrix
+    }
+}
+
+/// Check that the given planes can be stacked into a volume:
+/// all planes must have the same orientation and pixel spacing,
+/// and be positioned along the normal of the first plane,
+/// within the given tolerance.
+///
+/// The planes do not need to be sorted.
+pub fn check_consistency(planes: &[ImagePlane], tolerance: f64) -> Result<()> {
+    let first = match planes.first() {
+        Some(first) => first,
+        None => return Ok(()),
+    };
+    first.check_orientation(tolerance)?;
+    let normal = first.normal();
+    for (index, plane) in planes.iter().enumerate().skip(1) {
+        ensure!(
+            distance(plane.row_direction, first.row_direction) <= tolerance
+                && distance(plane.column_direction, first.column_direction) <= tolerance,
+            OrientationMismatchSnafu { index }
+        );
+        ensure!(
+            (plane.pixel_spacing[0] - first.pixel_spacing[0]).abs() <= tolerance
+                && (plane.pixel_spacing[1] - first.pixel_spacing[1]).abs() <= tolerance,
+            PixelSpacingMismatchSnafu { index }
+        );
+        // the offset between positions must be parallel to the normal
+        let offset = sub(plane.position, first.position);
+        let along = dot(offset, normal);
+        let across = sub(offset, scale(normal, along));
+        ensure!(norm(across) <= tolerance, NotAlignedSnafu { index });
+    }
+    Ok(())
+}
+
+/// Infer the distance between consecutive slices along the normal,
+/// ensuring that the spacing is uniform within the given tolerance.
+///
+/// The planes do not need to be sorted,
+/// but indices in errors refer to the planes sorted by slice location.
+pub fn slice_spacing(planes: &[ImagePlane], tolerance: f64) -> Result<f64> {
+    ensure!(
+        planes.len() >= 2,
+        NotEnoughSlicesSnafu {
+            count: planes.len()
+        }
+    );
+    let normal = planes[0].normal();
+    let mut locations: Vec<f64> = planes.iter().map(|p| dot(p.position, normal)).collect();
+    locations.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+
+    // the first gap is the reference for all others
+    let expected = locations[1] - locations[0];
+    for (i, pair) in locations.windows(2).enumerate() {
+        let got = pair[1] - pair[0];
+        ensure!(got > tolerance, DuplicatePositionSnafu { index: i + 1 });
+        ensure!(
+            (got - expected).abs() <= tolerance,
+            NonUniformSpacingSnafu {
+                index: i + 1,
+                expected,
+                got,
+            }
+        );
+    }
+    Ok((locations[locations.len() - 1] - locations[0]) / (locations.len() - 1) as f64)
+}
+
+fn required<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Result<&InMemElement<D>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag)
+        .ok()
+        .flatten()
+        .context(MissingAttributeSnafu { tag })
+}
+
+/// Obtain a fixed number of values of the given attribute as floats.
+fn floats<D, const N: usize>(elem: &InMemElement<D>) -> Result<[f64; N]> {
+    let values = elem
+        .to_multi_float64()
+        .context(InvalidAttributeSnafu { tag: elem.tag() })?;
+    let got = values.len();
+    <[f64; N]>::try_from(values).map_err(|_| {
+        UnexpectedMultiplicitySnafu {
+            tag: elem.tag(),
+            expected: N,
+            got,
+        }
+        .build()
+    })
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+fn distance(a: [f64; 3], b: [f64; 3]) -> f64 {
+    norm(sub(a, b))
+}
+
+fn normalize(a: [f64; 3]) -> [f64; 3] {
+    let n = norm(a);
+    if n == 0. {
+        a
+    } else {
+        scale(a, 1. / n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::value::PrimitiveValue;
+    use dicom_core::{dicom_value, DataElement, VR};
+
+    const AXIAL: [f64; 6] = [1., 0., 0., 0., 1., 0.];
+
+    fn axial_at(z: f64) -> ImagePlane {
+        ImagePlane::new([-100., -120., z], AXIAL, [0.5, 0.75])
+    }
+
+    #[test]
+    fn reads_plane_and_maps_pixels() {
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::IMAGE_POSITION_PATIENT,
+                VR::DS,
+                dicom_value!(Strs, ["-100", "-120", "30"]),
+            ),
+            DataElement::new(
+                tags::IMAGE_ORIENTATION_PATIENT,
+                VR::DS,
+                dicom_value!(Strs, ["1", "0", "0", "0", "1", "0"]),
+            ),
+            DataElement::new(
+                tags::PIXEL_SPACING,
+                VR::DS,
+                dicom_value!(Strs, ["0.5", "0.75"]),
+            ),
+            DataElement::new(tags::SLICE_THICKNESS, VR::DS, PrimitiveValue::from("2.5")),
+        ]);
+        let plane = ImagePlane::from_object(&obj).unwrap();
+        assert_eq!(plane, axial_at(30.).with_slice_thickness(2.5));
+        plane.check_orientation(DEFAULT_TOLERANCE).unwrap();
+
+        assert_eq!(plane.normal(), [0., 0., 1.]);
+        assert_eq!(plane.slice_location(), 30.);
+        // rows go along y by 0.5 mm, columns along x by 0.75 mm
+        assert_eq!(plane.pixel_to_patient(2., 4.), [-97., -119., 30.]);
+
+        let matrix = plane.voxel_to_patient(2.);
+        assert_eq!(matrix[0], [0.75, 0., 0., -100.]);
+        assert_eq!(matrix[1], [0., 0.5, 0., -120.]);
+        assert_eq!(matrix[2], [0., 0., 2., 30.]);
+        assert_eq!(matrix[3], [0., 0., 0., 1.]);
+
+        let missing = InMemDicomObject::new_empty();
+        assert!(matches!(
+            ImagePlane::from_object(&missing),
+            Err(Error::MissingAttribute { .. })
+        ));
+    }
+
+    #[test]
+    fn infers_slice_spacing() {
+        let planes = [axial_at(4.), axial_at(0.), axial_at(2.), axial_at(6.)];
+        check_consistency(&planes, DEFAULT_TOLERANCE).unwrap();
+        assert_eq!(slice_spacing(&planes, DEFAULT_TOLERANCE).unwrap(), 2.);
+
+        let gap = [axial_at(0.), axial_at(2.), axial_at(6.)];
+        assert!(matches!(
+            slice_spacing(&gap, DEFAULT_TOLERANCE),
+            Err(Error::NonUniformSpacing { index: 2, .. })
+        ));
+
+        let duplicate = [axial_at(0.), axial_at(0.)];
+        assert!(matches!(
+            slice_spacing(&duplicate, DEFAULT_TOLERANCE),
+            Err(Error::DuplicatePosition { .. })
+        ));
+    }
+
+    #[test]
+    fn detects_inconsistent_planes() {
+        let mut shifted = axial_at(2.);
+        shifted.position[0] += 1.;
+        assert!(matches!(
+            check_consistency(&[axial_at(0.), shifted], DEFAULT_TOLERANCE),
+            Err(Error::NotAligned { index: 1, .. })
+        ));
+
+        let tilted = ImagePlane::new([0., 0., 0.], [1., 0., 0., 0., 0., 1.], [0.5, 0.75]);
+        assert!(matches!(
+            check_consistency(&[axial_at(0.), tilted], DEFAULT_TOLERANCE),
+            Err(Error::OrientationMismatch { index: 1, .. })
+        ));
+
+        let skewed = ImagePlane::new([0., 0., 0.], [1., 0., 0., 1., 1., 0.], [0.5, 0.5]);
+        assert!(skewed.check_orientation(DEFAULT_TOLERANCE).is_err());
+    }
+}
@@ -0,0 +1,279 @@
+//! Canonical hashing of DICOM data sets.
+//!
+//! [`canonical_hash`] computes a SHA-256 digest
+//! of a normalized form of a data set,
+//! so that the same data set stored by different systems
+//! yields the same hash regardless of how it was encoded.
+//! This is useful for detecting duplicates across archives.
+//!
+//! The canonical form of a data set is obtained as follows:
+//!
+//! - the file meta group (group 0002), group length elements (gggg,0000),
+//!   _Specific Character Set_ (0008,0005),
+//!   the data set trailing padding (FFFC,FFFC),
+//!   and any attributes configured to be ignored
+//!   are left out, at any nesting level;
+//! - elements are written in ascending tag order;
+//! - trailing spaces and null characters of text values are removed;
+//! - the data set is encoded in Implicit VR Little Endian,
+//!   with all text in UTF-8
+//!   and all sequences and items of undefined length.
+//!
+//! Since no value representations are written,
+//! elements read as UN from an implicit VR data set
+//! hash the same as when read with their actual value representation.
+//! Pixel data is hashed as it is stored, however,
+//! so the same image in different encapsulated transfer syntaxes
+//! yields different hashes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_dictionary_std::tags;
+//! use dicom_object::open_file;
+//! use dicom_object::hash::{canonical_hash_with_options, HashOptions};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let a = open_file("archive1/0001.dcm")?;
+//! let b = open_file("archive2/0001.dcm")?;
+//! let options = HashOptions::new()
+//!     .ignore_tags([tags::INSTANCE_CREATION_DATE, tags::INSTANCE_CREATION_TIME]);
+//! if canonical_hash_with_options(&a, &options)? == canonical_hash_with_options(&b, &options)? {
+//!     println!("duplicate found: {}", canonical_hash_with_options(&a, &options)?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::BTreeSet;
+use std::fmt;
+use std::io::Write;
+
+use dicom_core::header::DataElementHeader;
+use dicom_core::value::PrimitiveValue;
+use dicom_core::Tag;
+use dicom_dictionary_std::tags;
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_parser::dataset::filter::{DropElements, MapValues, TokenFilter, UndefinedLengths};
+use dicom_parser::dataset::{DataSetWriter, IntoTokens};
+use dicom_transfer_syntax_registry::entries::IMPLICIT_VR_LITTLE_ENDIAN;
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+
+use crate::mem::InMemDicomObject;
+use crate::{CreatePrinterSnafu, PrintDataSetSnafu, Result};
+
+/// A set of options for computing the canonical hash of a data set.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct HashOptions {
+    /// the tags of the attributes to ignore, at any nesting level
+    ignore_tags: BTreeSet<Tag>,
+}
+
+impl HashOptions {
+    /// Create a new set of options,
+    /// in which all attributes except for those always left out
+    /// are hashed.
+    pub fn new() -> Self {
+        HashOptions::default()
+    }
+
+    /// Ignore the attribute with the given tag,
+    /// wherever it appears in the data set.
+    pub fn ignore_tag(mut self, tag: Tag) -> Self {
+        self.ignore_tags.insert(tag);
+        self
+    }
+
+    /// Ignore the attributes with the given tags,
+    /// wherever they appear in the data set.
+    ///
+    /// This is meant for volatile attributes,
+    /// which may change whenever an object is stored,
+    /// such as the instance creation date and time.
+    pub fn ignore_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        self.ignore_tags.extend(tags);
+        self
+    }
+
+    fn is_ignored(&self, tag: Tag) -> bool {
+        tag.group() == 0x0002
+            || tag.element() == 0x0000
+            || tag == tags::SPECIFIC_CHARACTER_SET
+            || tag == tags::DATA_SET_TRAILING_PADDING
+            || self.ignore_tags.contains(&tag)
+    }
+}
+
+/// The canonical hash of a DICOM data set, a SHA-256 digest.
+///
+/// It is displayed as a lowercase hexadecimal string.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct DataSetHash([u8; 32]);
+
+impl DataSetHash {
+    /// Obtain the bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for DataSetHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compute the canonical hash of a data set
+/// with the default options.
+///
+/// If the object is a file DICOM object,
+/// its file meta group is not taken into account.
+pub fn canonical_hash<D>(obj: &InMemDicomObject<D>) -> Result<DataSetHash>
+where
+    D: Clone,
+{
+    canonical_hash_with_options(obj, &HashOptions::default())
+}
+
+/// Compute the canonical hash of a data set
+/// with the given options.
+pub fn canonical_hash_with_options<D>(
+    obj: &InMemDicomObject<D>,
+    options: &HashOptions,
+) -> Result<DataSetHash>
+where
+    D: Clone,
+{
+    let mut hasher = Sha256::new();
+    write_canonical(obj, options, &mut hasher)?;
+    Ok(DataSetHash(hasher.finalize().into()))
+}
+
+/// Write the canonical form of a data set into the given writer,
+/// as it would be hashed with the given options.
+///
+/// This can be used to hash the data set with a different algorithm.
+pub fn write_canonical<D, W>(obj: &InMemDicomObject<D>, options: &HashOptions, to: W) -> Result<()>
+where
+    D: Clone,
+    W: Write,
+{
+    let filter = DropElements::new(|tag| options.is_ignored(tag))
+        .then(MapValues::new(trim_text))
+        .then(UndefinedLengths::new());
+    let ts = IMPLICIT_VR_LITTLE_ENDIAN.erased();
+    let mut dset_writer = DataSetWriter::with_ts_cs(to, &ts, SpecificCharacterSet::IsoIr192)
+        .context(CreatePrinterSnafu)?;
+    dset_writer
+        .write_sequence(filter.apply(obj.into_tokens()))
+        .context(PrintDataSetSnafu)
+}
+
+/// Remove the trailing padding of text values.
+pub(crate) fn trim_text(_header: &DataElementHeader, value: PrimitiveValue) -> PrimitiveValue {
+    fn trim(s: &str) -> &str {
+        s.trim_end_matches(|c: char| c == ' ' || c == '\0')
+    }
+
+    match value {
+        PrimitiveValue::Str(s) => PrimitiveValue::Str(trim(&s).to_string()),
+        PrimitiveValue::Strs(values) => {
+            PrimitiveValue::Strs(values.iter().map(|s| trim(s).to_string()).collect())
+        }
+        PrimitiveValue::SharedStrs(values) => {
+            PrimitiveValue::Strs(values.iter().map(|s| trim(s).to_string()).collect())
+        }
+        value => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileMetaTableBuilder, OpenFileOptions};
+    use dicom_core::value::Value;
+    use dicom_core::{smallvec::smallvec, DataElement, Length, VR};
+    use dicom_transfer_syntax_registry::entries::EXPLICIT_VR_LITTLE_ENDIAN;
+
+    fn sample_object() -> InMemDicomObject {
+        let item = InMemDicomObject::from_element_iter([DataElement::new(
+            tags::CODE_VALUE,
+            VR::SH,
+            PrimitiveValue::from("121327"),
+        )]);
+        InMemDicomObject::from_element_iter([
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from("P1 ")),
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(512_u16)),
+            DataElement::new(
+                tags::CONCEPT_NAME_CODE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: smallvec![item],
+                    size: Length::UNDEFINED,
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn hash_is_independent_of_encoding() {
+        let obj = sample_object();
+        let hash = canonical_hash(&obj).unwrap();
+
+        // round trip through another transfer syntax and file meta group
+        let file = obj
+            .clone()
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .transfer_syntax(EXPLICIT_VR_LITTLE_ENDIAN.uid())
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("2.25.1234"),
+            )
+            .unwrap();
+        let mut data = Vec::new();
+        file.write_all(&mut data).unwrap();
+        let file = OpenFileOptions::new()
+            .read_preamble(crate::file::ReadPreamble::Always)
+            .from_reader(&data[..])
+            .unwrap();
+        assert_eq!(canonical_hash(&file).unwrap(), hash);
+
+        // trailing padding does not count
+        let mut padded = obj.clone();
+        padded.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("P1"),
+        ));
+        assert_eq!(canonical_hash(&padded).unwrap(), hash);
+        assert_eq!(hash.to_string().len(), 64);
+    }
+
+    #[test]
+    fn hash_reflects_changes() {
+        let obj = sample_object();
+        let hash = canonical_hash(&obj).unwrap();
+
+        let mut other = obj.clone();
+        other.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("P2"),
+        ));
+        assert_ne!(canonical_hash(&other).unwrap(), hash);
+
+        // unless the attribute is ignored
+        let options = HashOptions::new().ignore_tag(tags::PATIENT_ID);
+        assert_eq!(
+            canonical_hash_with_options(&other, &options).unwrap(),
+            canonical_hash_with_options(&obj, &options).unwrap()
+        );
+    }
+}
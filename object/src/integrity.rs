@@ -0,0 +1,399 @@
+//! Streaming digests over selected data elements.
+//!
+//! The integrity of an archived object can be checked
+//! with a digest computed over the encoded bytes of some of its elements,
+//! in the same way as the _MAC Parameters_ of the Digital Signatures macro
+//! (DICOM PS3.15, Section C.1):
+//! each selected element of the root data set is encoded in full,
+//! including its header and any nested sequences,
+//! and the resulting bytes are hashed in ascending tag order.
+//!
+//! [`ElementDigester`] computes either a plain SHA-256 digest
+//! or an HMAC-SHA256 message authentication code.
+//! It is a [token filter](dicom_parser::dataset::filter)
+//! which passes all tokens through unchanged,
+//! so that it can be placed in any reading or writing pipeline,
+//! such as a [streaming copy](crate::copy),
+//! in which case even large pixel data is hashed
+//! one value or fragment at a time.
+//! The digest of an in-memory object is computed
+//! with [`digest_object`](ElementDigester::digest_object).
+//!
+//! The standard computes MACs over elements encoded
+//! in Explicit VR Little Endian,
+//! but any transfer syntax may be used,
+//! as long as it is the same when computing and verifying the digest.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_object::copy::{copy_file, CopyOptions};
+//! # use dicom_object::integrity::ElementDigester;
+//! use dicom_core::dictionary::TagSet;
+//! use dicom_dictionary_std::tags;
+//! use dicom_transfer_syntax_registry::entries::EXPLICIT_VR_LITTLE_ENDIAN;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let tags: TagSet = vec![tags::SOP_INSTANCE_UID, tags::PIXEL_DATA].into_iter().collect();
+//! let mut digester = ElementDigester::new(&EXPLICIT_VR_LITTLE_ENDIAN.erased(), tags)?;
+//!
+//! // archive the file while computing the digest
+//! copy_file("0001.dcm", "archive/0001.dcm", CopyOptions::new().filter(&mut digester))?;
+//! println!("0001.dcm {}", digester.finalize()?);
+//! # Ok(())
+//! # }
+//! ```
+use std::fmt;
+
+use dicom_core::dictionary::TagSet;
+use dicom_dictionary_std::tags;
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::DynEncoder;
+use dicom_encoding::TransferSyntax;
+use dicom_parser::dataset::filter::TokenFilter;
+use dicom_parser::dataset::{write, DataSetWriter, DataToken, IntoTokens};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+use snafu::ResultExt;
+
+use crate::mem::InMemDicomObject;
+use crate::{CreatePrinterSnafu, PrintDataSetSnafu, Result};
+
+/// A digest or message authentication code
+/// computed over selected data elements.
+///
+/// It is displayed as a lowercase hexadecimal string.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ElementsDigest([u8; 32]);
+
+impl ElementsDigest {
+    /// Obtain the bytes of the digest.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Check whether this digest matches the given bytes,
+    /// such as those recorded in an integrity manifest.
+    ///
+    /// The comparison takes the same time wherever the bytes differ,
+    /// so that it is safe for verifying message authentication codes.
+    pub fn verify(&self, expected: &[u8]) -> bool {
+        expected.len() == self.0.len()
+            && self
+                .0
+                .iter()
+                .zip(expected)
+                .fold(0, |acc, (a, b)| acc | (a ^ b))
+                == 0
+    }
+}
+
+impl fmt::Display for ElementsDigest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// The hash function state of a digester.
+#[derive(Clone)]
+enum Hasher {
+    Digest(Sha256),
+    Mac(Hmac<Sha256>),
+}
+
+impl Hasher {
+    /// Start an HMAC-SHA256 computation with the given key (RFC 2104).
+    fn mac(key: &[u8]) -> Self {
+        Hasher::Mac(Hmac::new_from_slice(key).expect("HMAC accepts keys of any length"))
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Digest(hasher) => hasher.update(data),
+            Hasher::Mac(mac) => mac.update(data),
+        }
+    }
+
+    fn finalize(self) -> [u8; 32] {
+        match self {
+            Hasher::Digest(hasher) => hasher.finalize().into(),
+            Hasher::Mac(mac) => mac.finalize().into_bytes().into(),
+        }
+    }
+}
+
+/// A token filter computing a digest
+/// over the encoded bytes of selected data elements.
+///
+/// Only elements of the root data set are selected,
+/// along with their full contents.
+/// The tokens are passed through unchanged.
+/// See the [module-level documentation](self) for more details.
+pub struct ElementDigester {
+    /// the tags of the elements to digest
+    tags: TagSet,
+    /// the writer for encoding the elements,
+    /// whose output is hashed and discarded after each token
+    writer: DataSetWriter<Vec<u8>, DynEncoder<'static, Vec<u8>>>,
+    hasher: Hasher,
+    /// the number of sequences currently open
+    depth: usize,
+    /// whether the current root element is digested
+    selected: bool,
+    /// whether the current root element is encoded,
+    /// which is also the case for the specific character set
+    /// so that text is encoded accordingly
+    encoding: bool,
+    /// the first error found while encoding
+    error: Option<write::Error>,
+}
+
+impl fmt::Debug for ElementDigester {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ElementDigester")
+            .field("tags", &self.tags)
+            .field("mac", &matches!(self.hasher, Hasher::Mac(_)))
+            .field("depth", &self.depth)
+            .field("selected", &self.selected)
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl ElementDigester {
+    /// Create a new SHA-256 digester
+    /// of the elements with the given tags,
+    /// as encoded in the given transfer syntax.
+    pub fn new(ts: &TransferSyntax, tags: TagSet) -> Result<Self> {
+        let writer = DataSetWriter::with_ts_cs(Vec::new(), ts, SpecificCharacterSet::Default)
+            .context(CreatePrinterSnafu)?;
+        Ok(ElementDigester {
+            tags,
+            writer,
+            hasher: Hasher::Digest(Sha256::new()),
+            depth: 0,
+            selected: false,
+            encoding: false,
+            error: None,
+        })
+    }
+
+    /// Compute an HMAC-SHA256 message authentication code
+    /// with the given secret key,
+    /// instead of a plain SHA-256 digest.
+    pub fn with_key(mut self, key: &[u8]) -> Self {
+        self.hasher = Hasher::mac(key);
+        self
+    }
+
+    /// Change the tags of the elements to digest
+    /// from the next root element onwards.
+    ///
+    /// This allows a single digest to cover elements of several data sets,
+    /// by feeding each data set in turn.
+    pub fn set_tags(&mut self, tags: TagSet) {
+        self.tags = tags;
+        self.selected = false;
+        self.encoding = false;
+    }
+
+    /// Feed all elements of the given object to the digester,
+    /// and obtain the resulting digest.
+    pub fn digest_object<D>(mut self, obj: &InMemDicomObject<D>) -> Result<ElementsDigest>
+    where
+        D: Clone,
+    {
+        (&mut self).apply(obj.into_tokens()).for_each(drop);
+        self.finalize()
+    }
+
+    /// Obtain the digest of the elements fed so far.
+    ///
+    /// Fails if any of the elements could not be encoded.
+    pub fn finalize(self) -> Result<ElementsDigest> {
+        if let Some(e) = self.error {
+            return Err(e).context(PrintDataSetSnafu);
+        }
+        Ok(ElementsDigest(self.hasher.finalize()))
+    }
+
+    fn digest(&mut self, token: DataToken) {
+        if self.error.is_some() {
+            return;
+        }
+        if let Err(e) = self.writer.write(token) {
+            self.error = Some(e);
+            return;
+        }
+        let bytes = self.writer.get_mut();
+        if self.selected {
+            self.hasher.update(bytes);
+        }
+        bytes.clear();
+    }
+}
+
+impl TokenFilter for ElementDigester {
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        if self.depth == 0 {
+            let tag = match &token {
+                DataToken::ElementHeader(header) => Some(header.tag),
+                DataToken::SequenceStart { tag, .. } => Some(*tag),
+                DataToken::PixelSequenceStart => Some(tags::PIXEL_DATA),
+                _ => None,
+            };
+            if let Some(tag) = tag {
+                self.selected = self.tags.contains(tag);
+                self.encoding = self.selected || tag == tags::SPECIFIC_CHARACTER_SET;
+            }
+        }
+        match &token {
+            DataToken::SequenceStart { .. } | DataToken::PixelSequenceStart => self.depth += 1,
+            DataToken::SequenceEnd => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        if self.encoding {
+            self.digest(token.clone());
+        }
+        out.push(token);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::copy::{copy_object, CopyOptions};
+    use crate::file::ReadPreamble;
+    use crate::FileMetaTableBuilder;
+    use dicom_core::value::{PrimitiveValue, Value};
+    use dicom_core::{DataElement, Length, Tag, VR};
+    use dicom_transfer_syntax_registry::entries::EXPLICIT_VR_LITTLE_ENDIAN;
+    use smallvec::smallvec;
+
+    fn sample_object_with(referenced_uid: &str) -> InMemDicomObject {
+        let item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(referenced_uid),
+        )]);
+        InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.1"),
+            ),
+            DataElement::new(
+                tags::REFERENCED_IMAGE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: smallvec![item],
+                    size: Length::UNDEFINED,
+                },
+            ),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+        ])
+    }
+
+    fn sample_object() -> InMemDicomObject {
+        sample_object_with("1.2.3.4")
+    }
+
+    fn digester(tags: &[Tag]) -> ElementDigester {
+        let ts = EXPLICIT_VR_LITTLE_ENDIAN.erased();
+        ElementDigester::new(&ts, tags.iter().copied().collect()).unwrap()
+    }
+
+    #[test]
+    fn digest_selected_elements() {
+        let obj = sample_object();
+        let selected = [tags::SOP_INSTANCE_UID, tags::REFERENCED_IMAGE_SEQUENCE];
+        let digest = digester(&selected).digest_object(&obj).unwrap();
+
+        // same as hashing the encoded selected elements
+        let subset = InMemDicomObject::from_element_iter(
+            selected
+                .iter()
+                .map(|tag| obj.element(*tag).unwrap().clone()),
+        );
+        let mut encoded = Vec::new();
+        subset
+            .write_dataset_with_ts(&mut encoded, &EXPLICIT_VR_LITTLE_ENDIAN.erased())
+            .unwrap();
+        assert_eq!(digest.as_bytes()[..], Sha256::digest(&encoded)[..]);
+        assert!(digest.verify(&Sha256::digest(&encoded)));
+        assert_eq!(digest.to_string().len(), 64);
+
+        // other elements do not count
+        let mut other = obj.clone();
+        other.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^Jane"),
+        ));
+        assert_eq!(digester(&selected).digest_object(&other).unwrap(), digest);
+
+        // but nested changes in selected elements do
+        let other = sample_object_with("1.2.3.5");
+        let other_digest = digester(&selected).digest_object(&other).unwrap();
+        assert_ne!(other_digest, digest);
+        assert!(!other_digest.verify(digest.as_bytes()));
+    }
+
+    #[test]
+    fn mac_with_key() {
+        // RFC 4231, test case 1
+        let mut hasher = Hasher::mac(&[0x0b; 20]);
+        hasher.update(b"Hi There");
+        assert_eq!(
+            ElementsDigest(hasher.finalize()).to_string(),
+            "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7"
+        );
+
+        let obj = sample_object();
+        let selected = [tags::SOP_INSTANCE_UID];
+        let digest = digester(&selected).digest_object(&obj).unwrap();
+        let mac = digester(&selected)
+            .with_key(b"secret")
+            .digest_object(&obj)
+            .unwrap();
+        let other_mac = digester(&selected)
+            .with_key(b"other secret")
+            .digest_object(&obj)
+            .unwrap();
+        assert_ne!(mac, digest);
+        assert_ne!(mac, other_mac);
+    }
+
+    #[test]
+    fn digest_while_copying() {
+        let obj = sample_object();
+        let selected = [tags::SOP_INSTANCE_UID, tags::REFERENCED_IMAGE_SEQUENCE];
+        let digest = digester(&selected).digest_object(&obj).unwrap();
+
+        let file = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .transfer_syntax(EXPLICIT_VR_LITTLE_ENDIAN.uid())
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("2.25.1"),
+            )
+            .unwrap();
+        let mut data = Vec::new();
+        file.write_all(&mut data).unwrap();
+
+        let mut streaming = digester(&selected);
+        let mut copy = Vec::new();
+        copy_object(
+            &data[..],
+            &mut copy,
+            CopyOptions::new()
+                .read_preamble(ReadPreamble::Always)
+                .filter(&mut streaming),
+        )
+        .unwrap();
+        assert_eq!(copy, data);
+        assert_eq!(streaming.finalize().unwrap(), digest);
+    }
+}
@@ -0,0 +1,192 @@
+//! Interning of repeated textual values.
+//!
+//! Many textual values repeat across the objects of a series or study,
+//! such as image types (`DERIVED\PRIMARY`), study and series UIDs,
+//! and the code meanings of structured content.
+//! A [`StringInterner`] keeps a single shared copy of each distinct value,
+//! so that the objects read with it refer to the same buffer
+//! instead of keeping a copy of their own.
+//!
+//! Interned values are kept as [`PrimitiveValue::SharedStrs`],
+//! which compare equal to the original values,
+//! are encoded in the same way,
+//! and can be read back with [`PrimitiveValue::strings`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_object::OpenFileOptions;
+//! use dicom_object::intern::StringInterner;
+//!
+//! let interner = StringInterner::new();
+//! let objects = ["1.dcm", "2.dcm", "3.dcm"]
+//!     .iter()
+//!     .map(|path| {
+//!         OpenFileOptions::new()
+//!             .intern_strings(interner.clone())
+//!             .open_file(path)
+//!     })
+//!     .collect::<Result<Vec<_>, _>>()?;
+//! println!("{} distinct values", interner.len());
+//! # Result::<(), Box<dyn std::error::Error>>::Ok(())
+//! ```
+use dicom_core::value::PrimitiveValue;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A shared set of interned textual values.
+///
+/// Cloning an interner is cheap,
+/// and the clones refer to the same set of values,
+/// so that a single interner can be used
+/// for all objects read by any number of threads.
+///
+/// Values are kept in the interner for as long as it lives,
+/// even after the objects referring to them are dropped.
+#[derive(Debug, Default, Clone)]
+pub struct StringInterner {
+    values: Arc<Mutex<HashSet<Arc<[String]>>>>,
+}
+
+impl StringInterner {
+    /// Create a new interner with no values.
+    pub fn new() -> Self {
+        StringInterner::default()
+    }
+
+    /// Obtain the shared copy of the given list of strings,
+    /// inserting it if it was not interned yet.
+    pub fn intern(&self, strings: &[String]) -> Arc<[String]> {
+        let mut values = self.lock();
+        if let Some(shared) = values.get(strings) {
+            return Arc::clone(shared);
+        }
+        let shared: Arc<[String]> = Arc::from(strings);
+        values.insert(Arc::clone(&shared));
+        shared
+    }
+
+    /// Obtain the given value with its strings interned.
+    ///
+    /// Only textual values in the `Strs` variant are interned,
+    /// since long texts in `Str` are seldom repeated.
+    /// Other values are returned as is.
+    pub fn intern_value(&self, value: PrimitiveValue) -> PrimitiveValue {
+        match value {
+            PrimitiveValue::Strs(strings) => PrimitiveValue::SharedStrs(self.intern(&strings)),
+            value => value,
+        }
+    }
+
+    /// Get the number of distinct values interned.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Check whether no values were interned yet.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Remove all values from the interner.
+    ///
+    /// Values already interned keep their strings,
+    /// but will no longer share them with values interned afterwards.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashSet<Arc<[String]>>> {
+        // the set is always left in a consistent state,
+        // so it remains usable even if another thread panicked
+        self.values
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StringInterner;
+    use crate::InMemDicomObject;
+    use dicom_core::value::Value;
+    use dicom_core::{dicom_value, DataElement, Length, PrimitiveValue, Tag, VR};
+    use std::sync::Arc;
+
+    #[test]
+    fn intern_values() {
+        let interner = StringInterner::new();
+        assert!(interner.is_empty());
+
+        let a = interner.intern_value(dicom_value!(Strs, ["DERIVED", "PRIMARY"]));
+        let b = interner.intern_value(dicom_value!(Strs, ["DERIVED", "PRIMARY"]));
+        let c = interner.intern_value(dicom_value!(Strs, ["ORIGINAL", "PRIMARY"]));
+        assert_eq!(interner.len(), 2);
+        assert_eq!(a, dicom_value!(Strs, ["DERIVED", "PRIMARY"]));
+        assert_eq!(a.to_str(), "DERIVED\\PRIMARY");
+        assert_eq!(a.strings().unwrap(), ["DERIVED", "PRIMARY"]);
+        assert_ne!(a, c);
+
+        match (&a, &b) {
+            (PrimitiveValue::SharedStrs(a), PrimitiveValue::SharedStrs(b)) => {
+                assert!(Arc::ptr_eq(a, b));
+            }
+            _ => panic!("expected shared strings, got {:?} and {:?}", a, b),
+        }
+
+        // long texts and other values are kept as is
+        let text = interner.intern_value(PrimitiveValue::from("Some finding"));
+        assert_eq!(text, PrimitiveValue::Str("Some finding".to_string()));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn intern_object_strings() {
+        let code = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            Tag(0x0008, 0x0104),
+            VR::LO,
+            dicom_value!(Strs, ["Finding"]),
+        )]);
+        let mut obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(Tag(0x0008, 0x0104), VR::LO, dicom_value!(Strs, ["Finding"])),
+            DataElement::new(
+                Tag(0x0040, 0xA043),
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![code].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+        ]);
+        let original = obj.clone();
+
+        let interner = StringInterner::new();
+        obj.intern_strings(&interner);
+        assert_eq!(interner.len(), 1);
+
+        let item = &obj.element(Tag(0x0040, 0xA043)).unwrap().items().unwrap()[0];
+        let original_item = &original
+            .element(Tag(0x0040, 0xA043))
+            .unwrap()
+            .items()
+            .unwrap()[0];
+        assert_eq!(
+            obj.element(Tag(0x0008, 0x0104)).unwrap().value(),
+            original.element(Tag(0x0008, 0x0104)).unwrap().value(),
+        );
+        assert_eq!(
+            item.element(Tag(0x0008, 0x0104)).unwrap().value(),
+            original_item.element(Tag(0x0008, 0x0104)).unwrap().value(),
+        );
+        match (
+            obj.element(Tag(0x0008, 0x0104)).unwrap().value(),
+            item.element(Tag(0x0008, 0x0104)).unwrap().value(),
+        ) {
+            (
+                Value::Primitive(PrimitiveValue::SharedStrs(a)),
+                Value::Primitive(PrimitiveValue::SharedStrs(b)),
+            ) => assert!(Arc::ptr_eq(a, b)),
+            (a, b) => panic!("expected shared strings, got {:?} and {:?}", a, b),
+        }
+    }
+}
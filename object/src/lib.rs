@@ -82,6 +82,12 @@
 //! # }
 //! ```
 //!
+//! Files too large to be held in memory can be rewritten
+//! with [`copy_object`](crate::copy::copy_object),
+//! which streams the data set from the source to the destination
+//! without building a DICOM object,
+//! optionally changing its tokens along the way.
+//!
 //! In order to write a plain DICOM data set,
 //! use one of the various `write_dataset` methods.
 //!
@@ -107,37 +113,82 @@
 //! # }
 //! # run().unwrap();
 //! ```
+pub mod anonymize;
+pub mod arena;
+pub mod code;
+pub mod copy;
+#[cfg(feature = "fs")]
+pub mod dicomdir;
+pub mod diff;
+pub mod document;
 pub mod file;
+pub mod geometry;
+pub mod hash;
+pub mod integrity;
+pub mod intern;
+pub mod matching;
 pub mod mem;
 pub mod meta;
+pub mod multiframe;
+pub mod path;
 #[deprecated(
     since = "0.5.0",
     note = "This is a stub, use the `dicom-pixeldata` crate instead"
 )]
 pub mod pixeldata;
+pub mod reinterpret;
+pub mod rtstruct;
+#[cfg(feature = "fs")]
+pub mod scan;
+pub mod seg;
+pub mod signature;
+pub mod sr;
+pub mod summary;
 pub mod tokens;
+pub mod validate;
+pub mod waveform;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 mod util;
 
-pub use crate::file::{from_reader, open_file, OpenFileOptions};
+pub use crate::arena::ArenaDicomObject;
+pub use crate::copy::copy_object;
+pub use crate::diff::diff;
+pub use crate::hash::canonical_hash;
+#[cfg(feature = "fs")]
+pub use crate::file::open_file;
+#[cfg(feature = "async")]
+pub use crate::file::open_file_async;
+#[cfg(feature = "mmap")]
+pub use crate::file::open_file_mmap;
+pub use crate::file::{from_reader, OpenFileOptions};
 pub use crate::mem::InMemDicomObject;
 pub use crate::meta::{FileMetaTable, FileMetaTableBuilder};
+pub use crate::path::DicomPath;
 use dicom_core::DataDictionary;
 pub use dicom_core::Tag;
 pub use dicom_dictionary_std::StandardDataDictionary;
+pub use dicom_parser::ErrorKind;
 
 /// The default implementation of a root DICOM object.
 pub type DefaultDicomObject<D = StandardDataDictionary> = FileDicomObject<mem::InMemDicomObject<D>>;
 
 use dicom_core::header::Header;
-use dicom_encoding::adapters::{PixelDataObject, RawPixelData};
+use dicom_encoding::adapters::{frame_fragment_range, PixelDataObject, RawPixelData};
 use dicom_encoding::{text::SpecificCharacterSet, transfer_syntax::TransferSyntaxIndex};
+use dicom_parser::dataset::filter::{MapValues, TokenFilter, UndefinedLengths};
+use dicom_parser::dataset::length::SizedDataSet;
 use dicom_parser::dataset::{DataSetWriter, IntoTokens};
+use dicom_parser::stateful::encode::PaddingPolicy;
 use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
 use smallvec::SmallVec;
-use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::File;
 use std::io::{BufWriter, Write};
+#[cfg(feature = "fs")]
 use std::path::Path;
 
 /// The current implementation class UID generically referring to DICOM-rs.
@@ -197,6 +248,11 @@ pub enum Error {
         backtrace: Backtrace,
         source: std::io::Error,
     },
+    #[snafu(display("Could not read from source"))]
+    ReadSource {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
     #[snafu(display("Could not parse meta group data set"))]
     ParseMetaDataSet {
         #[snafu(backtrace)]
@@ -212,6 +268,24 @@ pub enum Error {
         #[snafu(backtrace)]
         source: dicom_parser::dataset::read::Error,
     },
+    #[snafu(display("Could not decode value of element {}", tag))]
+    DecodeValue {
+        tag: Tag,
+        #[snafu(backtrace)]
+        source: dicom_parser::stateful::decode::Error,
+    },
+    #[cfg(feature = "async")]
+    #[snafu(display("Could not read file meta group"))]
+    ReadMetaGroup {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+    #[cfg(feature = "async")]
+    #[snafu(display("Could not read data set"))]
+    ReadDataSetAsync {
+        #[snafu(backtrace)]
+        source: dicom_parser::dataset::async_read::Error,
+    },
     #[snafu(display("Could not write to file '{}'", filename.display()))]
     WriteFile {
         filename: std::path::PathBuf,
@@ -228,6 +302,11 @@ pub enum Error {
         backtrace: Backtrace,
         source: std::io::Error,
     },
+    #[snafu(display("Could not write file meta group"))]
+    WriteMetaGroup {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
     #[snafu(display("Could not create data set printer"))]
     CreatePrinter {
         #[snafu(backtrace)]
@@ -245,6 +324,16 @@ pub enum Error {
     },
     #[snafu(display("Unsupported transfer syntax `{}`", uid))]
     UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+    #[snafu(display(
+        "Cannot change transfer syntax from `{}` to `{}` without transcoding",
+        from,
+        to
+    ))]
+    TranscodeRequired {
+        from: String,
+        to: String,
+        backtrace: Backtrace,
+    },
     #[snafu(display("No such data element with tag {}", tag))]
     NoSuchDataElementTag { tag: Tag, backtrace: Backtrace },
     #[snafu(display("No such data element {} (with tag {})", alias, tag))]
@@ -255,6 +344,46 @@ pub enum Error {
     },
     #[snafu(display("Unknown data attribute named `{}`", name))]
     NoSuchAttributeName { name: String, backtrace: Backtrace },
+    #[snafu(display("Invalid DICOM path `{}`", path))]
+    ParsePath {
+        path: String,
+        #[snafu(backtrace)]
+        source: crate::path::ParseError,
+    },
+    #[snafu(display("No item #{} in sequence {}", index, tag))]
+    NoSuchSequenceItem {
+        tag: Tag,
+        index: u32,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Could not read value of element {} as {}", tag, requested))]
+    CastValue {
+        tag: Tag,
+        requested: &'static str,
+        source: dicom_core::value::CastValueError,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Could not convert value of element {} to {}", tag, requested))]
+    ConvertValue {
+        tag: Tag,
+        requested: &'static str,
+        source: dicom_core::value::ConvertValueError,
+        backtrace: Backtrace,
+    },
+    #[snafu(display(
+        "Invalid multiplicity {} for element {} with VR {:?}",
+        multiplicity,
+        tag,
+        vr
+    ))]
+    InvalidMultiplicity {
+        tag: Tag,
+        vr: dicom_core::VR,
+        multiplicity: u32,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Data element {} is not a sequence", tag))]
+    NotASequence { tag: Tag, backtrace: Backtrace },
     #[snafu(display("Missing element value"))]
     MissingElementValue { backtrace: Backtrace },
     #[snafu(display("Unexpected token {:?}", token))]
@@ -274,6 +403,92 @@ pub enum Error {
         source: dicom_core::value::CastValueError,
         backtrace: Backtrace,
     },
+    #[snafu(display("Pixel data is not a primitive value or pixel sequence"))]
+    InvalidPixelData { backtrace: Backtrace },
+    #[snafu(display("Frame #{} is out of range", frame))]
+    FrameOutOfRange { frame: u32, backtrace: Backtrace },
+    #[snafu(display("Could not resolve the fragments of frame #{}", frame))]
+    ResolveFrameFragments { frame: u32, backtrace: Backtrace },
+}
+
+impl Error {
+    /// Classify this error,
+    /// following its chain of sources down to the original failure.
+    ///
+    /// This allows applications to tell apart
+    /// truncated data from invalid data or a failing data source
+    /// without matching on every error variant.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use dicom_object::{open_file, ErrorKind};
+    /// match open_file("path/to/file.dcm") {
+    ///     Ok(_obj) => { /* ... */ }
+    ///     Err(e) if e.kind() == ErrorKind::Truncated => {
+    ///         eprintln!("File is incomplete (at position {:?})", e.position());
+    ///     }
+    ///     Err(e) => eprintln!("{}", e),
+    /// }
+    /// ```
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::OpenFile { source, .. }
+            | Error::ReadFile { source, .. }
+            | Error::ReadPreambleBytes { source, .. }
+            | Error::ReadSource { source, .. }
+            | Error::WriteFile { source, .. }
+            | Error::WritePreamble { source, .. }
+            | Error::WriteMagicCode { source, .. }
+            | Error::WriteMetaGroup { source, .. } => ErrorKind::from(source),
+            #[cfg(feature = "async")]
+            Error::ReadMetaGroup { source, .. } => ErrorKind::from(source),
+            Error::ParseMetaDataSet { source } => source.kind(),
+            Error::CreateParser { source } | Error::ReadToken { source } => source.kind(),
+            Error::DecodeValue { source, .. } => source.kind(),
+            #[cfg(feature = "async")]
+            Error::ReadDataSetAsync { source } => source.kind(),
+            Error::UnsupportedTransferSyntax { .. } | Error::TranscodeRequired { .. } => {
+                ErrorKind::Unsupported
+            }
+            Error::UnexpectedToken { .. } => ErrorKind::InvalidStructure,
+            Error::PrematureEnd { .. } => ErrorKind::Truncated,
+            Error::InvalidPixelData { .. } => ErrorKind::InvalidValue,
+            _ => ErrorKind::Other,
+        }
+    }
+
+    /// Retrieve the position in the data set source
+    /// at which the error occurred,
+    /// if known.
+    pub fn position(&self) -> Option<u64> {
+        match self {
+            Error::CreateParser { source } | Error::ReadToken { source } => source.position(),
+            #[cfg(feature = "async")]
+            Error::ReadDataSetAsync { source } => source.position(),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the tag of the data element concerned by the error,
+    /// if known.
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            Error::ParseMetaDataSet { source } => source.tag(),
+            Error::CreateParser { source } | Error::ReadToken { source } => source.tag(),
+            #[cfg(feature = "async")]
+            Error::ReadDataSetAsync { source } => source.tag(),
+            Error::NoSuchDataElementTag { tag, .. }
+            | Error::NoSuchDataElementAlias { tag, .. }
+            | Error::NoSuchSequenceItem { tag, .. }
+            | Error::CastValue { tag, .. }
+            | Error::ConvertValue { tag, .. }
+            | Error::InvalidMultiplicity { tag, .. }
+            | Error::NotASequence { tag, .. }
+            | Error::DecodeValue { tag, .. } => Some(*tag),
+            _ => None,
+        }
+    }
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -283,6 +498,21 @@ pub type Result<T, E = Error> = std::result::Result<T, E>;
 #[deprecated(since = "0.4.0", note = "use `FileDicomObject` instead")]
 pub type RootDicomObject<O> = FileDicomObject<O>;
 
+/// How sequences and items are delimited
+/// when writing an object deterministically.
+///
+/// See [`FileDicomObject::write_all_deterministic`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SequenceLengths {
+    /// All sequences and items are of undefined length,
+    /// closed by delimiters.
+    Undefined,
+    /// All sequences and items are of defined length,
+    /// computed in a sizing pass.
+    /// Encapsulated pixel data is still of undefined length.
+    Defined,
+}
+
 /// A root DICOM object retrieved from a standard DICOM file,
 /// containing additional information from the file meta group
 /// in a separate table value.
@@ -312,6 +542,7 @@ where
     /// into the given file path.
     /// Preamble, magic code, and file meta group will be included
     /// before the inner object.
+    #[cfg(feature = "fs")]
     pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> Result<()> {
         let path = path.as_ref();
         let file = File::create(path).context(WriteFileSnafu { filename: path })?;
@@ -351,8 +582,47 @@ where
     /// Preamble, magic code, and file meta group will be included
     /// before the inner object.
     pub fn write_all<W: Write>(&self, to: W) -> Result<()> {
+        self.write_all_with(to, PaddingPolicy::default(), 0)
+    }
+
+    /// Write the entire object as a DICOM file
+    /// into the given writer,
+    /// padding textual values of odd length
+    /// as specified by the given policy.
+    ///
+    /// Preamble, magic code, and file meta group will be included
+    /// before the inner object.
+    /// The file meta group is always padded as prescribed by the standard.
+    pub fn write_all_with_padding<W: Write>(&self, to: W, padding: PaddingPolicy) -> Result<()> {
+        self.write_all_with(to, padding, 0)
+    }
+
+    /// Write the entire object as a DICOM file
+    /// into the given writer,
+    /// with all sequences and items in defined length.
+    ///
+    /// The lengths are computed in a [sizing pass](dicom_parser::dataset::length)
+    /// before anything is written,
+    /// which keeps the whole encoded data set in memory.
+    /// Encapsulated pixel data is still written with undefined length.
+    /// Preamble, magic code, and file meta group will be included
+    /// before the inner object.
+    pub fn write_all_explicit_lengths<W: Write>(&self, to: W) -> Result<()> {
         let mut to = BufWriter::new(to);
 
+        // prepare encoder
+        let registry = TransferSyntaxRegistry::default();
+        let ts = registry.get(&self.meta.transfer_syntax).with_context(|| {
+            UnsupportedTransferSyntaxSnafu {
+                uid: self.meta.transfer_syntax.clone(),
+            }
+        })?;
+        let cs = SpecificCharacterSet::Default;
+
+        // compute the lengths before writing anything
+        let sized =
+            SizedDataSet::new((&self.obj).into_tokens(), ts, cs).context(PrintDataSetSnafu)?;
+
         // write preamble
         to.write_all(&[0_u8; 128][..]).context(WritePreambleSnafu)?;
 
@@ -362,6 +632,37 @@ where
         // write meta group
         self.meta.write(&mut to).context(PrintMetaDataSetSnafu)?;
 
+        // write object
+        let mut dset_writer = DataSetWriter::with_ts_cs(to, ts, cs).context(CreatePrinterSnafu)?;
+        sized
+            .write_to(&mut dset_writer)
+            .context(PrintDataSetSnafu)?;
+
+        Ok(())
+    }
+
+    /// Write the entire object as a DICOM file
+    /// into the given writer,
+    /// so that the same object always results in the same bytes.
+    ///
+    /// This is meant for comparing outputs byte for byte,
+    /// such as in tests and reproducible builds.
+    /// Regardless of how the object was read or built:
+    ///
+    /// - elements are written in ascending tag order,
+    ///   and the original bytes of any raw elements are not used;
+    /// - trailing spaces and null characters of text values are removed,
+    ///   so that each value is padded with the standard padding character
+    ///   of its value representation;
+    /// - all sequences and items follow the given length policy;
+    /// - nothing is added to the object or to the file meta group,
+    ///   so no timestamps nor generated UIDs are ever injected.
+    ///
+    /// Preamble, magic code, and file meta group will be included
+    /// before the inner object.
+    pub fn write_all_deterministic<W: Write>(&self, to: W, lengths: SequenceLengths) -> Result<()> {
+        let mut to = BufWriter::new(to);
+
         // prepare encoder
         let registry = TransferSyntaxRegistry::default();
         let ts = registry.get(&self.meta.transfer_syntax).with_context(|| {
@@ -370,13 +671,111 @@ where
             }
         })?;
         let cs = SpecificCharacterSet::Default;
+        let tokens = || {
+            MapValues::new(hash::trim_text)
+                .then(UndefinedLengths::new())
+                .apply((&self.obj).into_tokens())
+        };
+
+        // compute the lengths before writing anything
+        let sized = match lengths {
+            SequenceLengths::Undefined => None,
+            SequenceLengths::Defined => {
+                Some(SizedDataSet::new(tokens(), ts, cs).context(PrintDataSetSnafu)?)
+            }
+        };
+
+        // write preamble
+        to.write_all(&[0_u8; 128][..]).context(WritePreambleSnafu)?;
+
+        // write magic sequence
+        to.write_all(b"DICM").context(WriteMagicCodeSnafu)?;
+
+        // write meta group
+        self.meta.write(&mut to).context(PrintMetaDataSetSnafu)?;
+
+        // write object
         let mut dset_writer = DataSetWriter::with_ts_cs(to, ts, cs).context(CreatePrinterSnafu)?;
+        match sized {
+            Some(sized) => sized.write_to(&mut dset_writer),
+            None => dset_writer.write_sequence(tokens()),
+        }
+        .context(PrintDataSetSnafu)?;
+
+        Ok(())
+    }
+
+    /// Write the entire object as a DICOM file
+    /// into the given writer,
+    /// followed by a data set trailing padding element (FFFC,FFFC)
+    /// so that the total file size is a multiple of `block_size` bytes.
+    ///
+    /// Preamble, magic code, and file meta group will be included
+    /// before the inner object.
+    /// No padding element is written
+    /// if the file is already aligned to the given block size
+    /// or if `block_size` is zero.
+    /// Since all DICOM elements have an even length,
+    /// `block_size` should be an even number.
+    pub fn write_all_padded<W: Write>(&self, to: W, block_size: u32) -> Result<()> {
+        self.write_all_with(to, PaddingPolicy::default(), block_size)
+    }
+
+    /// Write the entire object as a DICOM file into the given writer,
+    /// padding textual values as specified by the given policy,
+    /// and padding the file to a multiple of `block_size` bytes
+    /// unless `block_size` is zero.
+    fn write_all_with<W: Write>(
+        &self,
+        to: W,
+        padding: PaddingPolicy,
+        block_size: u32,
+    ) -> Result<()> {
+        let mut to = BufWriter::new(to);
+
+        // write preamble
+        to.write_all(&[0_u8; 128][..]).context(WritePreambleSnafu)?;
+
+        // write magic sequence
+        to.write_all(b"DICM").context(WriteMagicCodeSnafu)?;
+
+        // write meta group, keeping track of its size
+        let mut meta_bytes = Vec::new();
+        self.meta
+            .write(&mut meta_bytes)
+            .context(PrintMetaDataSetSnafu)?;
+        to.write_all(&meta_bytes).context(WriteMetaGroupSnafu)?;
+
+        // prepare encoder
+        let registry = TransferSyntaxRegistry::default();
+        let ts = registry.get(&self.meta.transfer_syntax).with_context(|| {
+            UnsupportedTransferSyntaxSnafu {
+                uid: self.meta.transfer_syntax.clone(),
+            }
+        })?;
+        let cs = SpecificCharacterSet::Default;
+        let mut dset_writer = DataSetWriter::with_ts_cs(to, ts, cs)
+            .context(CreatePrinterSnafu)?
+            .with_padding(padding);
 
         // write object
         dset_writer
             .write_sequence((&self.obj).into_tokens())
             .context(PrintDataSetSnafu)?;
 
+        // write trailing padding
+        let block_size = u64::from(block_size);
+        if block_size > 0 {
+            let written = 132 + meta_bytes.len() as u64 + dset_writer.bytes_written();
+            if written % block_size != 0 {
+                let header_len = if ts.explicit_vr() { 12 } else { 8 };
+                let len = (block_size - (written + header_len) % block_size) % block_size;
+                dset_writer
+                    .write_trailing_padding(len as u32)
+                    .context(PrintDataSetSnafu)?;
+            }
+        }
+
         Ok(())
     }
 
@@ -498,6 +897,110 @@ where
     }
 }
 
+impl<D> FileDicomObject<InMemDicomObject<D>>
+where
+    D: DataDictionary + Clone,
+{
+    /// Retrieve the bytes of a single frame of the pixel data
+    /// as they are in the object, without decoding them.
+    /// The first frame has index 0.
+    ///
+    /// For encapsulated pixel data,
+    /// this is the concatenation of the fragments of the frame,
+    /// resolved as in [`frame_fragments`](PixelDataObject::frame_fragments).
+    /// For native pixel data,
+    /// this is the portion of the pixel data value
+    /// which belongs to the frame.
+    /// When frames do not end at a byte boundary,
+    /// as may happen with a _Bits Allocated_ of 1,
+    /// the bits of the frame are shifted to the start of the first byte,
+    /// and the unused bits of the last byte are zero.
+    ///
+    /// The bytes are borrowed from the object whenever possible,
+    /// and only copied if the frame spans multiple fragments
+    /// or does not start at a byte boundary.
+    /// Call [`into_owned`](Cow::into_owned) on the output
+    /// to obtain an owned byte vector.
+    pub fn raw_frame(&self, frame: u32) -> Result<Cow<[u8]>> {
+        let number_of_frames = u32::from(self.number_of_frames().unwrap_or(1));
+        ensure!(frame < number_of_frames, FrameOutOfRangeSnafu { frame });
+
+        let pixel_data = self.element(dicom_dictionary_std::tags::PIXEL_DATA)?;
+        match pixel_data.value() {
+            dicom_core::DicomValue::PixelSequence { fragments, .. } => {
+                let range = self
+                    .frame_fragments(frame)
+                    .context(ResolveFrameFragmentsSnafu { frame })?;
+                match fragments.get(range) {
+                    Some([fragment]) => Ok(Cow::Borrowed(&fragment[..])),
+                    Some(fragments) => Ok(Cow::Owned(fragments.concat())),
+                    None => ResolveFrameFragmentsSnafu { frame }.fail(),
+                }
+            }
+            dicom_core::DicomValue::Primitive(value) => {
+                let rows = self.get_u16(dicom_dictionary_std::tags::ROWS)?;
+                let cols = self.get_u16(dicom_dictionary_std::tags::COLUMNS)?;
+                let samples_per_pixel =
+                    self.get_u16(dicom_dictionary_std::tags::SAMPLES_PER_PIXEL)?;
+                let bits_allocated = self.get_u16(dicom_dictionary_std::tags::BITS_ALLOCATED)?;
+                let frame_bits = rows as usize
+                    * cols as usize
+                    * samples_per_pixel as usize
+                    * bits_allocated as usize;
+                let start_bit = frame_bits * frame as usize;
+                let start = start_bit / 8;
+                let end = (start_bit + frame_bits + 7) / 8;
+
+                if start_bit % 8 != 0 || frame_bits % 8 != 0 {
+                    // bit-packed frames, as with 1 bit per sample
+                    let bytes = value.to_bytes();
+                    let bytes = bytes
+                        .get(start..end)
+                        .context(FrameOutOfRangeSnafu { frame })?;
+                    return Ok(Cow::Owned(extract_bits(bytes, start_bit % 8, frame_bits)));
+                }
+
+                match value.to_bytes() {
+                    Cow::Borrowed(bytes) => bytes
+                        .get(start..end)
+                        .map(Cow::Borrowed)
+                        .context(FrameOutOfRangeSnafu { frame }),
+                    Cow::Owned(bytes) => bytes
+                        .get(start..end)
+                        .map(|bytes| Cow::Owned(bytes.to_vec()))
+                        .context(FrameOutOfRangeSnafu { frame }),
+                }
+            }
+            dicom_core::DicomValue::Sequence { .. } => InvalidPixelDataSnafu.fail(),
+        }
+    }
+}
+
+/// Copy `len` bits from the given bytes,
+/// starting at bit `offset` (0 to 7) of the first byte,
+/// into the start of a new byte vector.
+/// Bits are packed from the least significant bit of each byte,
+/// as in native pixel data,
+/// and the unused bits of the last byte are zero.
+fn extract_bits(bytes: &[u8], offset: usize, len: usize) -> Vec<u8> {
+    let mut out: Vec<u8> = (0..(len + 7) / 8)
+        .map(|i| {
+            let low = bytes[i] >> offset;
+            let high = match (offset, bytes.get(i + 1)) {
+                (0, _) | (_, None) => 0,
+                (_, Some(next)) => next << (8 - offset),
+            };
+            low | high
+        })
+        .collect();
+    if len % 8 != 0 {
+        if let Some(last) = out.last_mut() {
+            *last &= (1 << (len % 8)) - 1;
+        }
+    }
+    out
+}
+
 /// Implement basic pixeldata encoder/decoder functionality
 impl<D> PixelDataObject for FileDicomObject<InMemDicomObject<D>>
 where
@@ -595,6 +1098,49 @@ where
             dicom_core::DicomValue::Sequence { items: _, size: _ } => None,
         }
     }
+
+    /// Return the range of fragments which make up the given frame,
+    /// based on the Extended Offset Table if present,
+    /// or on the Basic Offset Table otherwise.
+    ///
+    /// Without an offset table,
+    /// the frame is only resolved if there is exactly one fragment per frame
+    /// or if there is only one frame.
+    fn frame_fragments(&self, frame: u32) -> Option<std::ops::Range<usize>> {
+        let pixel_data = self.element(dicom_dictionary_std::tags::PIXEL_DATA).ok()?;
+        let (offset_table, fragments) = match pixel_data.value() {
+            dicom_core::DicomValue::PixelSequence {
+                offset_table,
+                fragments,
+            } => (offset_table, fragments),
+            _ => return None,
+        };
+        let fragment_lengths = fragments.iter().map(|f| f.len() as u64);
+
+        let extended_offset_table = self
+            .element(dicom_dictionary_std::tags::EXTENDED_OFFSET_TABLE)
+            .ok()
+            .and_then(|e| e.to_multi_int::<u64>().ok())
+            .filter(|table| !table.is_empty());
+        if let Some(table) = extended_offset_table {
+            return frame_fragment_range(&table, fragment_lengths, frame);
+        }
+        if !offset_table.is_empty() {
+            let table: Vec<u64> = offset_table.iter().map(|o| u64::from(*o)).collect();
+            return frame_fragment_range(&table, fragment_lengths, frame);
+        }
+
+        let number_of_frames = u32::from(self.number_of_frames().unwrap_or(1));
+        if frame >= number_of_frames {
+            None
+        } else if fragments.len() == number_of_frames as usize {
+            Some(frame as usize..frame as usize + 1)
+        } else if number_of_frames == 1 {
+            Some(0..fragments.len())
+        } else {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
@@ -705,4 +1251,453 @@ mod tests {
         assert_eq!(iter.next().unwrap().header().tag, dicom_dictionary_std::tags::PATIENT_NAME);
         assert_eq!(iter.next(), None);
     }
+
+    #[test]
+    fn file_dicom_object_write_all_padded() {
+        use crate::file::{ReadPreamble, TrailingPaddingStrategy};
+        use crate::OpenFileOptions;
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            dicom_dictionary_std::tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("John Doe"),
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        obj.write_all_padded(&mut out, 512).unwrap();
+        assert_eq!(out.len() % 512, 0);
+
+        // padding is kept by default
+        let obj2 = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader(&out[..])
+            .unwrap();
+        assert!(obj2.element(dicom_core::Tag(0xFFFC, 0xFFFC)).is_ok());
+
+        // padding can be skipped
+        let obj3 = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .trailing_padding(TrailingPaddingStrategy::Skip)
+            .from_reader(&out[..])
+            .unwrap();
+        assert_eq!(obj, obj3);
+    }
+
+    #[test]
+    fn file_dicom_object_read_with_issues() {
+        use crate::file::{ParseIssue, ReadPreamble};
+        use crate::OpenFileOptions;
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            dicom_dictionary_std::tags::MODALITY,
+            VR::CS,
+            PrimitiveValue::from("ABCDEFGHIJKLMNOPQR"),
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        obj.write_all(&mut out).unwrap();
+
+        let (obj2, issues) = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader_with_issues(&out[..])
+            .unwrap();
+        assert_eq!(obj, obj2);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0],
+            ParseIssue::ValueTooLong { len: 18, max: 16, .. }
+        ));
+        assert_eq!(issues[0].tag(), Some(dicom_dictionary_std::tags::MODALITY));
+    }
+
+    #[test]
+    fn file_dicom_object_write_explicit_lengths() {
+        use crate::file::ReadPreamble;
+        use crate::OpenFileOptions;
+        use dicom_core::value::Value;
+        use dicom_core::Length;
+        use dicom_dictionary_std::tags;
+
+        let item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from("1.2.3.4"),
+        )]);
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::REFERENCED_IMAGE_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: vec![item].into(),
+                size: Length::UNDEFINED,
+            },
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        obj.write_all_explicit_lengths(&mut out).unwrap();
+
+        // no item delimiter nor sequence delimiter
+        let mut out_undefined = Vec::new();
+        obj.write_all(&mut out_undefined).unwrap();
+        assert_eq!(out.len() + 16, out_undefined.len());
+
+        let obj2 = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader(&out[..])
+            .unwrap();
+        let seq = obj2.element(tags::REFERENCED_IMAGE_SEQUENCE).unwrap();
+        // item header + UI element
+        assert_eq!(seq.header().len, Length(24));
+        let items = seq.items().unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0]
+                .element(tags::REFERENCED_SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "1.2.3.4"
+        );
+    }
+
+    #[test]
+    fn file_dicom_object_write_with_padding() {
+        use crate::file::ReadPreamble;
+        use crate::OpenFileOptions;
+        use dicom_dictionary_std::tags;
+        use dicom_parser::stateful::encode::PaddingPolicy;
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from("1.2.345"),
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        obj.write_all_with_padding(&mut out, PaddingPolicy::new().ui_null_padded(false))
+            .unwrap();
+        assert!(out.ends_with(b"1.2.345 "));
+
+        // the padding is kept unless trimmed on read
+        let obj2 = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader(&out[..])
+            .unwrap();
+        assert_eq!(
+            obj2.element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .value()
+                .strings()
+                .unwrap(),
+            ["1.2.345 "]
+        );
+        let obj3 = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .trim_padding(true)
+            .from_reader(&out[..])
+            .unwrap();
+        assert_eq!(
+            obj3.element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .value()
+                .strings()
+                .unwrap(),
+            ["1.2.345"]
+        );
+    }
+
+    #[test]
+    fn file_dicom_object_write_deterministic() {
+        use crate::file::ReadPreamble;
+        use crate::{OpenFileOptions, SequenceLengths};
+        use dicom_core::value::Value;
+        use dicom_core::Length;
+        use dicom_dictionary_std::tags;
+
+        let meta = FileMetaTableBuilder::new()
+            .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+            .media_storage_sop_instance_uid("1.2.23456789")
+            .transfer_syntax("1.2.840.10008.1.2.1")
+            .build()
+            .unwrap();
+        let build = |uid: &str, patient_id: &str, seq_len: Length, reversed: bool| {
+            let item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+                tags::REFERENCED_SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(uid),
+            )]);
+            let mut elements = vec![
+                DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from(patient_id)),
+                DataElement::new(
+                    tags::REFERENCED_IMAGE_SEQUENCE,
+                    VR::SQ,
+                    Value::Sequence {
+                        items: vec![item].into(),
+                        size: seq_len,
+                    },
+                ),
+            ];
+            if reversed {
+                elements.reverse();
+            }
+            let mut obj = InMemDicomObject::new_empty();
+            for elem in elements {
+                obj.put(elem);
+            }
+            obj.with_exact_meta(meta.clone())
+        };
+
+        // same content, different padding, insertion order and lengths
+        let a = build("1.2.3.4", "P1", Length::UNDEFINED, false);
+        let b = build("1.2.3.4 ", "P1 ", Length(24), true);
+
+        for lengths in [SequenceLengths::Undefined, SequenceLengths::Defined] {
+            let mut out_a = Vec::new();
+            a.write_all_deterministic(&mut out_a, lengths).unwrap();
+            let mut out_a2 = Vec::new();
+            a.write_all_deterministic(&mut out_a2, lengths).unwrap();
+            let mut out_b = Vec::new();
+            b.write_all_deterministic(&mut out_b, lengths).unwrap();
+            assert_eq!(out_a, out_a2);
+            assert_eq!(out_a, out_b);
+
+            let obj = OpenFileOptions::new()
+                .read_preamble(ReadPreamble::Always)
+                .from_reader(&out_a[..])
+                .unwrap();
+            let seq = obj.element(tags::REFERENCED_IMAGE_SEQUENCE).unwrap();
+            let expected_len = match lengths {
+                SequenceLengths::Undefined => None,
+                SequenceLengths::Defined => Some(24),
+            };
+            assert_eq!(seq.header().len.get(), expected_len);
+            assert_eq!(
+                obj.element(tags::PATIENT_ID).unwrap().to_str().unwrap(),
+                "P1"
+            );
+        }
+    }
+
+    #[test]
+    fn file_dicom_object_error_kind() {
+        use crate::file::ReadPreamble;
+        use crate::{ErrorKind, OpenFileOptions};
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            dicom_dictionary_std::tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        let mut out = Vec::new();
+        obj.write_all(&mut out).unwrap();
+
+        // cut the patient name short
+        let e = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader(&out[..out.len() - 4])
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::Truncated);
+        assert_eq!(e.tag(), Some(dicom_dictionary_std::tags::PATIENT_NAME));
+        assert_eq!(e.position(), Some(8));
+
+        // not DICOM at all
+        let e = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Never)
+            .from_reader(&b"NOT A DICOM FILE"[..])
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::InvalidHeader);
+    }
+
+    #[test]
+    fn file_dicom_object_frame_fragments() {
+        use dicom_core::smallvec::smallvec;
+        use dicom_core::value::Value;
+        use dicom_encoding::adapters::PixelDataObject;
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            dicom_dictionary_std::tags::NUMBER_OF_FRAMES,
+            VR::IS,
+            PrimitiveValue::from("2"),
+        ));
+        // frame 0: fragments 0 and 1, frame 1: fragment 2
+        obj.put(DataElement::new(
+            dicom_dictionary_std::tags::PIXEL_DATA,
+            VR::OB,
+            Value::PixelSequence {
+                offset_table: smallvec![0, 44],
+                fragments: smallvec![vec![0_u8; 20], vec![0_u8; 8], vec![0_u8; 16]],
+            },
+        ));
+        let mut obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    // JPEG Baseline
+                    .transfer_syntax("1.2.840.10008.1.2.4.50"),
+            )
+            .unwrap();
+
+        // using the basic offset table
+        assert_eq!(obj.frame_fragments(0), Some(0..2));
+        assert_eq!(obj.frame_fragments(1), Some(2..3));
+        assert_eq!(obj.frame_fragments(2), None);
+
+        // the extended offset table takes precedence
+        obj.put(DataElement::new(
+            dicom_dictionary_std::tags::EXTENDED_OFFSET_TABLE,
+            VR::OV,
+            PrimitiveValue::U64(smallvec![0, 28]),
+        ));
+        assert_eq!(obj.frame_fragments(0), Some(0..1));
+        assert_eq!(obj.frame_fragments(1), Some(1..3));
+
+        // raw frames
+        let frame = obj.raw_frame(0).unwrap();
+        assert!(matches!(frame, std::borrow::Cow::Borrowed(_)));
+        assert_eq!(frame.len(), 20);
+        let frame = obj.raw_frame(1).unwrap();
+        assert!(matches!(frame, std::borrow::Cow::Owned(_)));
+        assert_eq!(frame.len(), 24);
+        assert!(matches!(
+            obj.raw_frame(2),
+            Err(Error::FrameOutOfRange { frame: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn file_dicom_object_raw_frame_native() {
+        use dicom_dictionary_std::tags;
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::ROWS,
+            VR::US,
+            PrimitiveValue::from(2_u16),
+        ));
+        obj.put(DataElement::new(
+            tags::COLUMNS,
+            VR::US,
+            PrimitiveValue::from(2_u16),
+        ));
+        obj.put(DataElement::new(
+            tags::SAMPLES_PER_PIXEL,
+            VR::US,
+            PrimitiveValue::from(1_u16),
+        ));
+        obj.put(DataElement::new(
+            tags::BITS_ALLOCATED,
+            VR::US,
+            PrimitiveValue::from(8_u16),
+        ));
+        obj.put(DataElement::new(
+            tags::NUMBER_OF_FRAMES,
+            VR::IS,
+            PrimitiveValue::from("2"),
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            PrimitiveValue::from(vec![1_u8, 2, 3, 4, 5, 6, 7, 8]),
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        assert_eq!(&*obj.raw_frame(0).unwrap(), &[1, 2, 3, 4]);
+        assert_eq!(&*obj.raw_frame(1).unwrap(), &[5, 6, 7, 8]);
+        assert!(obj.raw_frame(2).is_err());
+    }
+
+    #[test]
+    fn file_dicom_object_raw_frame_bit_packed() {
+        use dicom_dictionary_std::tags;
+
+        // 3 frames of 3x3 pixels with 1 bit each,
+        // so that frames do not start at a byte boundary
+        let mut obj = InMemDicomObject::new_empty();
+        for (tag, value) in [
+            (tags::ROWS, 3_u16),
+            (tags::COLUMNS, 3),
+            (tags::SAMPLES_PER_PIXEL, 1),
+            (tags::BITS_ALLOCATED, 1),
+        ] {
+            obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+        }
+        obj.put(DataElement::new(
+            tags::NUMBER_OF_FRAMES,
+            VR::IS,
+            PrimitiveValue::from("3"),
+        ));
+        // frame 0: 1_0110_1101, frame 1: 0_0000_0001, frame 2: 1_1111_1110
+        // (bits 0-8, 9-17, and 18-26, from the least significant bit of each byte)
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            PrimitiveValue::from(vec![0b0110_1101, 0b0000_0011, 0b1111_1000, 0b0000_0111]),
+        ));
+        let obj = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("1.2.23456789")
+                    .transfer_syntax("1.2.840.10008.1.2.1"),
+            )
+            .unwrap();
+
+        assert_eq!(&*obj.raw_frame(0).unwrap(), &[0b0110_1101, 0b1]);
+        assert_eq!(&*obj.raw_frame(1).unwrap(), &[0b0000_0001, 0b0]);
+        assert_eq!(&*obj.raw_frame(2).unwrap(), &[0b1111_1110, 0b1]);
+        assert!(obj.raw_frame(3).is_err());
+    }
 }
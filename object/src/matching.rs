@@ -0,0 +1,620 @@
+//! Attribute matching of query keys against data sets,
+//! as used by the query services (C-FIND).
+//!
+//! Each attribute in a query identifier is a matching key,
+//! whose kind of matching is determined
+//! by its value and value representation
+//! (see PS3.4 C.2.2.2):
+//!
+//! - an empty value is a universal match;
+//! - a value with `*` or `?` in a textual attribute is a wildcard match;
+//! - a value with `-` in a date, time or date time attribute
+//!   is a range match;
+//! - multiple values in a unique identifier attribute
+//!   is a list of UID match;
+//! - a sequence with an item is a sequence match,
+//!   where any item of the sequence in the data set must match
+//!   all keys in the item of the identifier;
+//! - anything else is a single value match.
+//!
+//! [`matches`] evaluates a whole identifier against a data set,
+//! which serves both query SCP implementations and local filtering,
+//! and [`response`] builds the corresponding response identifier.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_core::{DataElement, PrimitiveValue, VR};
+//! # use dicom_dictionary_std::tags;
+//! # use dicom_object::InMemDicomObject;
+//! use dicom_object::matching::matches;
+//!
+//! let identifier = InMemDicomObject::from_element_iter([
+//!     DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^*")),
+//!     DataElement::new(tags::STUDY_DATE, VR::DA, PrimitiveValue::from("20220101-")),
+//!     DataElement::new(tags::STUDY_INSTANCE_UID, VR::UI, PrimitiveValue::Empty),
+//! ]);
+//! let study = InMemDicomObject::from_element_iter([
+//!     DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+//!     DataElement::new(tags::STUDY_DATE, VR::DA, PrimitiveValue::from("20220314")),
+//!     DataElement::new(tags::STUDY_INSTANCE_UID, VR::UI, PrimitiveValue::from("1.2.3")),
+//! ]);
+//! assert!(matches(&identifier, &study));
+//! ```
+
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+
+use crate::mem::InMemElement;
+use crate::InMemDicomObject;
+
+/// The kind of matching performed for a matching key.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MatchKind {
+    /// The key is empty and matches any value.
+    Universal,
+    /// The key value must be equal to a value of the attribute.
+    SingleValue,
+    /// The key value is a pattern with `*` and `?` wildcards.
+    Wildcard,
+    /// The key value is a range of dates or times.
+    Range,
+    /// The key value is a list of unique identifiers.
+    UidList,
+    /// The key is a sequence with keys to match in its items.
+    Sequence,
+}
+
+/// Attributes of an identifier which are not matching keys.
+const NON_KEY_ATTRIBUTES: [Tag; 3] = [
+    tags::QUERY_RETRIEVE_LEVEL,
+    tags::SPECIFIC_CHARACTER_SET,
+    tags::TIMEZONE_OFFSET_FROM_UTC,
+];
+
+/// Determine the kind of matching of the given key.
+pub fn match_kind<D>(key: &InMemElement<D>) -> MatchKind
+where
+    D: DataDictionary + Clone,
+{
+    if key.vr() == VR::SQ {
+        return match key.items() {
+            Some([item]) if item.iter().next().is_some() => MatchKind::Sequence,
+            _ => MatchKind::Universal,
+        };
+    }
+    let values = key_values(key);
+    match values.as_slice() {
+        [] => MatchKind::Universal,
+        [_, _, ..] if key.vr() == VR::UI => MatchKind::UidList,
+        [value] if is_range_vr(key.vr()) && split_range(key.vr(), value).is_some() => {
+            MatchKind::Range
+        }
+        [value]
+            if supports_wildcards(key.vr()) && value.contains(|c: char| c == '*' || c == '?') =>
+        {
+            if value.chars().all(|c| c == '*') {
+                MatchKind::Universal
+            } else {
+                MatchKind::Wildcard
+            }
+        }
+        _ => MatchKind::SingleValue,
+    }
+}
+
+/// Check whether a data set matches all keys of a query identifier.
+///
+/// _Query/Retrieve Level_, _Specific Character Set_
+/// and _Timezone Offset From UTC_ are not treated as matching keys.
+pub fn matches<D>(identifier: &InMemDicomObject<D>, obj: &InMemDicomObject<D>) -> bool
+where
+    D: DataDictionary + Clone,
+{
+    identifier
+        .iter()
+        .filter(|key| !NON_KEY_ATTRIBUTES.contains(&key.tag()))
+        .all(|key| match_element(key, obj))
+}
+
+/// Check whether a data set matches a single key.
+///
+/// Only universal matching succeeds
+/// if the attribute is missing from the data set.
+/// Multi-valued attributes match if any of their values matches.
+pub fn match_element<D>(key: &InMemElement<D>, obj: &InMemDicomObject<D>) -> bool
+where
+    D: DataDictionary + Clone,
+{
+    let kind = match_kind(key);
+    if kind == MatchKind::Universal {
+        return true;
+    }
+    let elem = match obj.element_opt(key.tag()).ok().flatten() {
+        Some(elem) => elem,
+        None => return false,
+    };
+
+    if kind == MatchKind::Sequence {
+        let key_item = &key.items().unwrap()[0];
+        return elem
+            .items()
+            .map(|items| items.iter().any(|item| matches(key_item, item)))
+            .unwrap_or(false);
+    }
+
+    let keys = key_values(key);
+    let values = key_values(elem);
+    match kind {
+        MatchKind::UidList => values.iter().any(|v| keys.contains(v)),
+        MatchKind::Range => {
+            let (lower, upper) = split_range(key.vr(), &keys[0]).unwrap();
+            values.iter().any(|v| in_range(key.vr(), v, lower, upper))
+        }
+        MatchKind::Wildcard => values
+            .iter()
+            .any(|v| wildcard_match(&fold(key.vr(), &keys[0]), &fold(key.vr(), v))),
+        _ => values
+            .iter()
+            .any(|v| single_value_match(key.vr(), &keys[0], v)),
+    }
+}
+
+/// Build a response identifier for a data set matching the given identifier,
+/// with the values of the data set for each key of the identifier.
+///
+/// Keys which are missing from the data set are returned empty,
+/// and the items of sequence keys are reduced to the keys in the identifier.
+/// _Query/Retrieve Level_ is copied from the identifier.
+pub fn response<D>(
+    identifier: &InMemDicomObject<D>,
+    obj: &InMemDicomObject<D>,
+) -> InMemDicomObject<D>
+where
+    D: DataDictionary + Clone,
+{
+    let mut out = identifier.clone();
+    for key in identifier.iter() {
+        if key.tag() == tags::QUERY_RETRIEVE_LEVEL {
+            continue;
+        }
+        let elem = match obj.element_opt(key.tag()).ok().flatten() {
+            Some(elem) => elem,
+            None => {
+                out.put(DataElement::new(key.tag(), key.vr(), PrimitiveValue::Empty));
+                continue;
+            }
+        };
+        match (key.items(), elem.items()) {
+            (Some([key_item]), Some(items)) if key_item.iter().next().is_some() => {
+                let items: Vec<_> = items.iter().map(|item| response(key_item, item)).collect();
+                out.put(DataElement::new(
+                    key.tag(),
+                    VR::SQ,
+                    Value::Sequence {
+                        items: items.into(),
+                        size: Length::UNDEFINED,
+                    },
+                ));
+            }
+            _ => {
+                out.put(elem.clone());
+            }
+        }
+    }
+    out
+}
+
+/// Obtain the values of an element as trimmed strings,
+/// splitting multiple values.
+fn key_values<D>(elem: &InMemElement<D>) -> Vec<String> {
+    match elem.value() {
+        Value::Primitive(PrimitiveValue::Empty) => Vec::new(),
+        Value::Primitive(_) => elem
+            .to_multi_str()
+            .map(|values| {
+                values
+                    .iter()
+                    .flat_map(|v| v.split('\\'))
+                    .map(|v| v.trim_matches(|c: char| c == ' ' || c == '\0').to_string())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|v| !v.is_empty())
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn is_range_vr(vr: VR) -> bool {
+    matches!(vr, VR::DA | VR::TM | VR::DT)
+}
+
+/// Split a range matching key value into its lower and upper bounds,
+/// or return `None` if the value is not a range.
+///
+/// A `-` in a date time value which is followed by
+/// a valid UTC offset (`-HHMM`) and then by the end of the value
+/// or by another `-` is the offset of the bound before it,
+/// not the range separator.
+fn split_range(vr: VR, value: &str) -> Option<(&str, &str)> {
+    let bytes = value.as_bytes();
+    let is_offset = |i: usize| {
+        if vr != VR::DT || i == 0 {
+            return false;
+        }
+        let offset = &bytes[i + 1..];
+        let digits = match offset.get(..4) {
+            Some(digits) if digits.iter().all(u8::is_ascii_digit) => digits,
+            _ => return false,
+        };
+        let hours = (digits[0] - b'0') * 10 + (digits[1] - b'0');
+        let minutes = (digits[2] - b'0') * 10 + (digits[3] - b'0');
+        hours <= 14 && minutes < 60 && matches!(offset.get(4), None | Some(b'-'))
+    };
+    let i = value
+        .char_indices()
+        .find(|&(i, c)| c == '-' && !is_offset(i))?
+        .0;
+    Some((value[..i].trim(), value[i + 1..].trim()))
+}
+
+fn supports_wildcards(vr: VR) -> bool {
+    matches!(
+        vr,
+        VR::AE | VR::CS | VR::LO | VR::LT | VR::PN | VR::SH | VR::ST | VR::UC | VR::UR | VR::UT
+    )
+}
+
+/// Person names are matched regardless of case.
+fn fold(vr: VR, value: &str) -> String {
+    if vr == VR::PN {
+        value.to_uppercase()
+    } else {
+        value.to_string()
+    }
+}
+
+fn single_value_match(vr: VR, key: &str, value: &str) -> bool {
+    match vr {
+        VR::DS | VR::IS | VR::FL | VR::FD | VR::SL | VR::SS | VR::UL | VR::US | VR::SV | VR::UV => {
+            match (key.parse::<f64>(), value.parse::<f64>()) {
+                (Ok(key), Ok(value)) => key == value,
+                _ => key == value,
+            }
+        }
+        VR::PN => key.to_uppercase() == value.to_uppercase(),
+        VR::DA | VR::TM | VR::DT => {
+            normalize_date_time(vr, key, '0') == normalize_date_time(vr, value, '0')
+        }
+        _ => key == value,
+    }
+}
+
+/// Check whether a date, time or date time value
+/// lies within the given bounds, any of which may be empty.
+///
+/// Bounds with less precision than the value
+/// include the whole period they denote,
+/// so that `-1000` includes 10:00:30.
+fn in_range(vr: VR, value: &str, lower: &str, upper: &str) -> bool {
+    let value = normalize_date_time(vr, value, '0');
+    (lower.is_empty() || normalize_date_time(vr, lower, '0') <= value)
+        && (upper.is_empty() || value <= normalize_date_time(vr, upper, '9'))
+}
+
+/// Bring a date, time or date time value to full precision,
+/// filling the missing digits with the given character,
+/// so that values can be compared as strings.
+///
+/// Time zone offsets of date time values are ignored.
+fn normalize_date_time(vr: VR, value: &str, fill: char) -> String {
+    // drop the separators of the legacy formats
+    let value: String = match vr {
+        VR::DA => value.chars().filter(|c| *c != '.' && *c != '-').collect(),
+        _ => value.chars().filter(|c| *c != ':').collect(),
+    };
+    let (whole_digits, value) = match vr {
+        VR::DA => (8, value),
+        VR::TM => (6, value),
+        _ => {
+            // ignore the UTC offset
+            let end = value
+                .char_indices()
+                .skip(1)
+                .find(|(_, c)| *c == '+' || *c == '-')
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| value.len());
+            (14, value[..end].to_string())
+        }
+    };
+    let (whole, fraction) = match value.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (value.as_str(), ""),
+    };
+    let mut out = whole.to_string();
+    while out.len() < whole_digits {
+        out.push(fill);
+    }
+    if vr != VR::DA {
+        out.push('.');
+        out.push_str(fraction);
+        while out.len() < whole_digits + 7 {
+            out.push(fill);
+        }
+    }
+    out
+}
+
+/// Match a value against a pattern,
+/// where `*` matches any sequence of characters
+/// and `?` matches any single character.
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+    // positions to backtrack to after the last `*`
+    let (mut p, mut v) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while v < value.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == value[v]) {
+            p += 1;
+            v += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, v));
+            p += 1;
+        } else if let Some((star_p, star_v)) = star {
+            p = star_p + 1;
+            v = star_v + 1;
+            star = Some((star_p, star_v + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dicom_value;
+    use dicom_core::header::HasLength;
+
+    fn element(tag: Tag, vr: VR, value: &str) -> InMemElement {
+        DataElement::new(tag, vr, PrimitiveValue::from(value))
+    }
+
+    fn study() -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            element(tags::PATIENT_NAME, VR::PN, "Doe^John"),
+            element(tags::PATIENT_ID, VR::LO, "P-0001"),
+            element(tags::STUDY_DATE, VR::DA, "20220314"),
+            element(tags::STUDY_TIME, VR::TM, "093015.25"),
+            element(tags::STUDY_INSTANCE_UID, VR::UI, "1.2.3.4"),
+            DataElement::new(
+                tags::MODALITIES_IN_STUDY,
+                VR::CS,
+                dicom_value!(Strs, ["CT", "SR"]),
+            ),
+            element(tags::NUMBER_OF_STUDY_RELATED_INSTANCES, VR::IS, "12"),
+        ])
+    }
+
+    fn key(tag: Tag, vr: VR, value: &str) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([element(tag, vr, value)])
+    }
+
+    #[test]
+    fn match_kinds() {
+        assert_eq!(
+            match_kind(&element(tags::PATIENT_NAME, VR::PN, "")),
+            MatchKind::Universal
+        );
+        assert_eq!(
+            match_kind(&element(tags::PATIENT_NAME, VR::PN, "*")),
+            MatchKind::Universal
+        );
+        assert_eq!(
+            match_kind(&element(tags::PATIENT_NAME, VR::PN, "Doe*")),
+            MatchKind::Wildcard
+        );
+        assert_eq!(
+            match_kind(&element(tags::STUDY_DATE, VR::DA, "2022-")),
+            MatchKind::Range
+        );
+        assert_eq!(
+            match_kind(&element(tags::STUDY_INSTANCE_UID, VR::UI, "1.2\\1.3")),
+            MatchKind::UidList
+        );
+        assert_eq!(
+            match_kind(&element(tags::STUDY_INSTANCE_UID, VR::UI, "1.2*")),
+            MatchKind::SingleValue
+        );
+        assert_eq!(
+            match_kind(&element(
+                tags::ACQUISITION_DATE_TIME,
+                VR::DT,
+                "20220101120000-0500"
+            )),
+            MatchKind::SingleValue
+        );
+        assert_eq!(
+            match_kind(&element(
+                tags::ACQUISITION_DATE_TIME,
+                VR::DT,
+                "20220101120000-0500-"
+            )),
+            MatchKind::Range
+        );
+    }
+
+    #[test]
+    fn date_time_ranges_with_utc_offsets() {
+        assert_eq!(
+            split_range(VR::DT, "20220101000000-0500-20220102000000-0500"),
+            Some(("20220101000000-0500", "20220102000000-0500"))
+        );
+        assert_eq!(
+            split_range(VR::DT, "20220101000000+0100-20220102000000-0500"),
+            Some(("20220101000000+0100", "20220102000000-0500"))
+        );
+        assert_eq!(
+            split_range(VR::DT, "-20220102000000-0500"),
+            Some(("", "20220102000000-0500"))
+        );
+        assert_eq!(split_range(VR::DT, "20220101120000-0500"), None);
+        assert_eq!(split_range(VR::DT, "2022-2023"), Some(("2022", "2023")));
+
+        let obj = InMemDicomObject::from_element_iter([element(
+            tags::ACQUISITION_DATE_TIME,
+            VR::DT,
+            "20220101120000-0500",
+        )]);
+        assert!(matches(
+            &key(tags::ACQUISITION_DATE_TIME, VR::DT, "20220101120000-0500"),
+            &obj
+        ));
+        assert!(!matches(
+            &key(tags::ACQUISITION_DATE_TIME, VR::DT, "20220101120001-0500"),
+            &obj
+        ));
+        assert!(matches(
+            &key(
+                tags::ACQUISITION_DATE_TIME,
+                VR::DT,
+                "20220101000000-0500-20220102000000-0500"
+            ),
+            &obj
+        ));
+        assert!(!matches(
+            &key(
+                tags::ACQUISITION_DATE_TIME,
+                VR::DT,
+                "20220102000000-0500-20220103000000-0500"
+            ),
+            &obj
+        ));
+    }
+
+    #[test]
+    fn single_value_and_wildcard_matching() {
+        let study = study();
+        assert!(matches(&key(tags::PATIENT_ID, VR::LO, "P-0001"), &study));
+        assert!(!matches(&key(tags::PATIENT_ID, VR::LO, "P-0002"), &study));
+        assert!(matches(
+            &key(tags::PATIENT_NAME, VR::PN, "DOE^JOHN"),
+            &study
+        ));
+        assert!(matches(&key(tags::PATIENT_NAME, VR::PN, "doe^*"), &study));
+        assert!(matches(&key(tags::PATIENT_NAME, VR::PN, "D?e^J*n"), &study));
+        assert!(!matches(&key(tags::PATIENT_NAME, VR::PN, "Smith*"), &study));
+        assert!(matches(
+            &key(tags::MODALITIES_IN_STUDY, VR::CS, "SR"),
+            &study
+        ));
+        assert!(matches(
+            &key(tags::NUMBER_OF_STUDY_RELATED_INSTANCES, VR::IS, "12.0"),
+            &study
+        ));
+        // missing attributes only match universally
+        assert!(!matches(&key(tags::ACCESSION_NUMBER, VR::SH, "A1"), &study));
+        assert!(matches(&key(tags::ACCESSION_NUMBER, VR::SH, ""), &study));
+    }
+
+    #[test]
+    fn range_and_uid_list_matching() {
+        let study = study();
+        assert!(matches(
+            &key(tags::STUDY_DATE, VR::DA, "20220101-20221231"),
+            &study
+        ));
+        assert!(matches(&key(tags::STUDY_DATE, VR::DA, "-20220314"), &study));
+        assert!(!matches(
+            &key(tags::STUDY_DATE, VR::DA, "20220315-"),
+            &study
+        ));
+        assert!(matches(&key(tags::STUDY_TIME, VR::TM, "0930-0930"), &study));
+        assert!(!matches(&key(tags::STUDY_TIME, VR::TM, "0931-"), &study));
+        assert!(matches(&key(tags::STUDY_DATE, VR::DA, "20220314"), &study));
+
+        assert!(matches(
+            &key(tags::STUDY_INSTANCE_UID, VR::UI, "1.2.3.5\\1.2.3.4"),
+            &study
+        ));
+        assert!(!matches(
+            &key(tags::STUDY_INSTANCE_UID, VR::UI, "1.2.3.5\\1.2.3.6"),
+            &study
+        ));
+    }
+
+    #[test]
+    fn sequence_matching_and_response() {
+        let item = |code: &str| {
+            InMemDicomObject::from_element_iter([
+                element(tags::CODE_VALUE, VR::SH, code),
+                element(tags::CODING_SCHEME_DESIGNATOR, VR::SH, "DCM"),
+            ])
+        };
+        let sequence = |items: Vec<InMemDicomObject>| {
+            DataElement::new(
+                tags::PROCEDURE_CODE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: items.into(),
+                    size: Length::UNDEFINED,
+                },
+            )
+        };
+        let mut obj = study();
+        obj.put(sequence(vec![item("A"), item("B")]));
+
+        let identifier = InMemDicomObject::from_element_iter([
+            element(tags::QUERY_RETRIEVE_LEVEL, VR::CS, "STUDY"),
+            element(tags::PATIENT_NAME, VR::PN, ""),
+            element(tags::ACCESSION_NUMBER, VR::SH, ""),
+            sequence(vec![InMemDicomObject::from_element_iter([element(
+                tags::CODE_VALUE,
+                VR::SH,
+                "B",
+            )])]),
+        ]);
+        assert!(matches(&identifier, &obj));
+
+        let mut other = identifier.clone();
+        other.put(sequence(vec![InMemDicomObject::from_element_iter([
+            element(tags::CODE_VALUE, VR::SH, "C"),
+        ])]));
+        assert!(!matches(&other, &obj));
+
+        let response = response(&identifier, &obj);
+        assert_eq!(
+            response
+                .element(tags::PATIENT_NAME)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "Doe^John"
+        );
+        assert!(response.element(tags::ACCESSION_NUMBER).unwrap().is_empty());
+        assert!(response.element(tags::PATIENT_ID).is_err());
+        let items = response
+            .element(tags::PROCEDURE_CODE_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(items.len(), 2);
+        assert!(items[0].element(tags::CODING_SCHEME_DESIGNATOR).is_err());
+    }
+
+    #[test]
+    fn wildcards() {
+        assert!(wildcard_match("a*c", "abbbc"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("a?c*", "abcdef"));
+        assert!(!wildcard_match("a?c", "ac"));
+        assert!(!wildcard_match("*x", "abc"));
+    }
+}
@@ -1,29 +1,43 @@
 //! This module contains the implementation for an in-memory DICOM object.
 
-use itertools::Itertools;
+use bytes::Bytes;
+use itertools::{Either, Itertools};
 use smallvec::SmallVec;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
+use std::borrow::Cow;
+#[cfg(feature = "fs")]
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, BufWriter, Cursor, Read};
+#[cfg(feature = "fs")]
 use std::path::Path;
 use std::{collections::BTreeMap, io::Write};
 
-use crate::file::ReadPreamble;
+use crate::file::{OpenFileOptions, ReadPreamble};
+use crate::intern::StringInterner;
+use crate::path::DicomPath;
 use crate::{meta::FileMetaTable, FileMetaTableBuilder};
 use crate::{
-    BuildMetaTableSnafu, CreateParserSnafu, CreatePrinterSnafu, DicomObject, FileDicomObject,
-    MissingElementValueSnafu, NoSuchAttributeNameSnafu, NoSuchDataElementAliasSnafu,
-    NoSuchDataElementTagSnafu, OpenFileSnafu, ParseMetaDataSetSnafu, PrematureEndSnafu,
-    PrepareMetaTableSnafu, PrintDataSetSnafu, ReadFileSnafu, ReadPreambleBytesSnafu,
-    ReadTokenSnafu, Result, UnexpectedTokenSnafu, UnsupportedTransferSyntaxSnafu,
+    BuildMetaTableSnafu, CastValueSnafu, ConvertValueSnafu, CreateParserSnafu, CreatePrinterSnafu,
+    DicomObject, Error, FileDicomObject, InvalidMultiplicitySnafu, MissingElementValueSnafu,
+    NoSuchAttributeNameSnafu, NoSuchDataElementAliasSnafu, NoSuchDataElementTagSnafu,
+    NoSuchSequenceItemSnafu, NotASequenceSnafu, ParseMetaDataSetSnafu, ParsePathSnafu,
+    PrematureEndSnafu, PrepareMetaTableSnafu, PrintDataSetSnafu, PrintMetaDataSetSnafu,
+    ReadPreambleBytesSnafu, ReadSourceSnafu, ReadTokenSnafu, Result, UnexpectedTokenSnafu,
+    UnsupportedTransferSyntaxSnafu, WriteMagicCodeSnafu, WritePreambleSnafu,
 };
+#[cfg(feature = "fs")]
+use crate::{OpenFileSnafu, ReadFileSnafu};
+#[cfg(feature = "async")]
+use crate::{ReadDataSetAsyncSnafu, ReadMetaGroupSnafu};
+use dicom_core::chrono::FixedOffset;
 use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
 use dicom_core::header::{HasLength, Header};
-use dicom_core::value::{Value, C};
+use dicom_core::value::{DicomDate, DicomDateTime, DicomTime, PrimitiveValue, Value, C};
 use dicom_core::{DataElement, Length, Tag, VR};
 use dicom_dictionary_std::{tags, StandardDataDictionary};
 use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
 use dicom_encoding::{encode::EncodeTo, text::SpecificCharacterSet, TransferSyntax};
+use dicom_parser::dataset::read::ParseIssue;
 use dicom_parser::dataset::{DataSetReader, DataToken};
 use dicom_parser::{
     dataset::{read::Error as ParserError, DataSetWriter, IntoTokens},
@@ -39,12 +53,92 @@ pub type InMemFragment = Vec<u8>;
 
 type ParserResult<T> = std::result::Result<T, ParserError>;
 
+/// Implement a pair of typed getters (single and multiple values)
+/// by converting the element's value with the given methods.
+///
+/// Should be placed inside `InMemDicomObject`'s impl block.
+macro_rules! impl_typed_getters {
+    ($name_single: ident, $name_multi: ident, $conv_single: ident, $conv_multi: ident, $ret: ty, $requested: literal) => {
+        #[doc = concat!("Retrieve the value of a particular DICOM element as a single `", stringify!($ret), "`.")]
+        ///
+        /// The value is converted according to its representation,
+        /// so that textual numbers are also accepted.
+        /// If it contains multiple values,
+        /// only the first one is returned.
+        /// An error is returned if the element does not exist
+        /// or its value cannot be converted.
+        pub fn $name_single(&self, tag: Tag) -> Result<$ret> {
+            self.element(tag)?
+                .$conv_single()
+                .context(ConvertValueSnafu {
+                    tag,
+                    requested: $requested,
+                })
+        }
+
+        #[doc = concat!("Retrieve all values of a particular DICOM element as `", stringify!($ret), "`.")]
+        ///
+        /// The values are converted according to their representation,
+        /// so that textual numbers are also accepted.
+        /// An error is returned if the element does not exist
+        /// or its value cannot be converted.
+        pub fn $name_multi(&self, tag: Tag) -> Result<Vec<$ret>> {
+            self.element(tag)?
+                .$conv_multi()
+                .context(ConvertValueSnafu {
+                    tag,
+                    requested: $requested,
+                })
+        }
+    };
+}
+
+/// The strategy for combining sequence elements
+/// when merging one DICOM object into another.
+///
+/// See [`InMemDicomObject::merge_from`].
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum SequenceMerge {
+    /// The incoming sequence replaces the existing one entirely.
+    Replace,
+    /// The items of the incoming sequence are appended
+    /// after the items of the existing sequence.
+    Append,
+    /// Items at the same position are merged recursively,
+    /// and any extra incoming items are appended.
+    Merge,
+}
+
+impl Default for SequenceMerge {
+    fn default() -> Self {
+        SequenceMerge::Replace
+    }
+}
+
+/// The original encoded bytes of the elements in a data set,
+/// retained for writing them back exactly as they were read.
+#[derive(Debug, Clone)]
+struct RawElements {
+    /// the UID of the transfer syntax in which the elements are encoded
+    ts: String,
+    /// the bytes of each element in the root data set,
+    /// header included,
+    /// for as long as the element is not modified
+    elements: BTreeMap<Tag, Bytes>,
+}
+
 /** A DICOM object that is fully contained in memory.
  */
 #[derive(Debug, Clone)]
 pub struct InMemDicomObject<D = StandardDataDictionary> {
     /// the element map
     entries: BTreeMap<Tag, InMemElement<D>>,
+    /// the order in which the elements were inserted,
+    /// only kept if it differs from ascending tag order
+    order: Option<Vec<Tag>>,
+    /// the original bytes of the elements,
+    /// if they were retained when reading the object
+    raw: Option<RawElements>,
     /// the data dictionary
     dict: D,
     /// The length of the DICOM object in bytes.
@@ -54,7 +148,9 @@ pub struct InMemDicomObject<D = StandardDataDictionary> {
 }
 
 impl<D> PartialEq for InMemDicomObject<D> {
-    // This implementation ignores the data dictionary.
+    // This implementation ignores the data dictionary,
+    // the order in which the elements were inserted,
+    // and whether their original bytes were retained.
     fn eq(&self, other: &Self) -> bool {
         self.entries == other.entries
     }
@@ -90,6 +186,7 @@ impl FileDicomObject<InMemDicomObject<StandardDataDictionary>> {
     ///
     /// This function assumes the standard file encoding structure: 128-byte
     /// preamble, file meta group, and the rest of the data set.
+    #[cfg(feature = "fs")]
     pub fn open_file<P: AsRef<Path>>(path: P) -> Result<Self> {
         Self::open_file_with_dict(path, StandardDataDictionary)
     }
@@ -117,11 +214,44 @@ impl InMemDicomObject<StandardDataDictionary> {
     pub fn new_empty() -> Self {
         InMemDicomObject {
             entries: BTreeMap::new(),
+            order: None,
+            raw: None,
             dict: StandardDataDictionary,
             len: Length::UNDEFINED,
         }
     }
 
+    /// Start building a new DICOM object from scratch.
+    ///
+    /// See [`InMemDicomObjectBuilder`] for the available methods.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dicom_core::{dicom_value, VR};
+    /// # use dicom_dictionary_std::tags;
+    /// # use dicom_object::InMemDicomObject;
+    /// # fn run() -> Result<(), dicom_object::Error> {
+    /// let obj = InMemDicomObject::builder()
+    ///     .add(tags::PATIENT_NAME, VR::PN, "Doe^John")
+    ///     .add_value(tags::PATIENT_ID, "12345")
+    ///     .add_by_name("StudyDescription", "Head CT")
+    ///     .add_seq(
+    ///         tags::REFERENCED_STUDY_SEQUENCE,
+    ///         vec![InMemDicomObject::builder()
+    ///             .add_value(tags::REFERENCED_SOP_INSTANCE_UID, "1.2.3.4")
+    ///             .build()?],
+    ///     )
+    ///     .build()?;
+    /// assert_eq!(obj.element(tags::PATIENT_ID)?.vr(), VR::LO);
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn builder() -> InMemDicomObjectBuilder<StandardDataDictionary> {
+        InMemDicomObjectBuilder::new_with_dict(StandardDataDictionary)
+    }
+
     /// Construct a DICOM object from a fallible source of structured elements.
     #[inline]
     pub fn from_element_source<I>(iter: I) -> Result<Self>
@@ -140,6 +270,17 @@ impl InMemDicomObject<StandardDataDictionary> {
         Self::from_iter_with_dict(iter, StandardDataDictionary)
     }
 
+    /// Construct a DICOM object from a sequence of data set tokens,
+    /// such as the ones produced by the DICOM JSON reader
+    /// in [`dicom_parser::dataset::json`].
+    #[inline]
+    pub fn from_tokens<I>(tokens: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = DataToken>,
+    {
+        Self::from_tokens_with_dict(tokens, StandardDataDictionary)
+    }
+
     /// Read an object from a source using the given decoder.
     ///
     /// Note: [`read_dataset_with_ts`] and [`read_dataset_with_ts_cs`]
@@ -204,6 +345,8 @@ where
             meta,
             obj: InMemDicomObject {
                 entries: BTreeMap::new(),
+                order: None,
+                raw: None,
                 dict,
                 len: Length::UNDEFINED,
             },
@@ -214,6 +357,7 @@ where
     ///
     /// This function assumes the standard file encoding structure: 128-byte
     /// preamble, file meta group, and the rest of the data set.
+    #[cfg(feature = "fs")]
     pub fn open_file_with_dict<P: AsRef<Path>>(path: P, dict: D) -> Result<Self> {
         Self::open_file_with(path, dict, TransferSyntaxRegistry)
     }
@@ -228,30 +372,44 @@ where
     /// is insufficient. Otherwise, please use [`open_file_with_dict`] instead.
     ///
     /// [`open_file_with_dict`]: #method.open_file_with_dict
+    #[cfg(feature = "fs")]
     pub fn open_file_with<P: AsRef<Path>, R>(path: P, dict: D, ts_index: R) -> Result<Self>
     where
         P: AsRef<Path>,
         R: TransferSyntaxIndex,
     {
-        Self::open_file_with_all_options(path, dict, ts_index, None, ReadPreamble::Auto)
+        Self::open_file_with_all_options(
+            path,
+            OpenFileOptions::new()
+                .dictionary(dict)
+                .tranfer_syntax_index(ts_index),
+            None,
+        )
     }
 
+    #[cfg(feature = "fs")]
     pub(crate) fn open_file_with_all_options<P: AsRef<Path>, R>(
         path: P,
-        dict: D,
-        ts_index: R,
-        read_until: Option<Tag>,
-        read_preamble: ReadPreamble,
+        options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
     ) -> Result<Self>
     where
         P: AsRef<Path>,
         R: TransferSyntaxIndex,
     {
+        if options.preserve_raw_elements {
+            return Self::open_file_raw_with_all_options(path, options, issues);
+        }
+
         let path = path.as_ref();
-        let mut file =
-            BufReader::new(File::open(path).with_context(|_| OpenFileSnafu { filename: path })?);
+        let mut file = BufReader::with_capacity(
+            options.buffer_capacity(),
+            File::open(path).with_context(|_| OpenFileSnafu { filename: path })?,
+        );
 
-        if read_preamble == ReadPreamble::Auto || read_preamble == ReadPreamble::Always {
+        if options.read_preamble == ReadPreamble::Auto
+            || options.read_preamble == ReadPreamble::Always
+        {
             let mut buf = [0u8; 128];
             // skip the preamble
             file.read_exact(&mut buf)
@@ -262,21 +420,28 @@ where
         let meta = FileMetaTable::from_reader(&mut file).context(ParseMetaDataSetSnafu)?;
 
         // read rest of data according to metadata, feed it to object
-        if let Some(ts) = ts_index.get(&meta.transfer_syntax) {
+        if let Some(ts) = options.ts_index.get(&meta.transfer_syntax) {
             let cs = SpecificCharacterSet::Default;
             let mut dataset =
-                DataSetReader::new_with_ts_cs(file, ts, cs).context(CreateParserSnafu)?;
+                DataSetReader::new_with_ts_cs_options(file, ts, cs, options.reader_options())
+                    .context(CreateParserSnafu)?;
+            if issues.is_some() {
+                dataset = dataset.collect_issues();
+            }
 
-            Ok(FileDicomObject {
-                meta,
-                obj: InMemDicomObject::build_object(
-                    &mut dataset,
-                    dict,
-                    false,
-                    Length::UNDEFINED,
-                    read_until,
-                )?,
-            })
+            let obj = InMemDicomObject::build_object(
+                &mut dataset,
+                options.data_dictionary,
+                false,
+                Length::UNDEFINED,
+                options.read_until,
+            )?;
+            if let Some(issues) = issues {
+                issues.extend(dataset.take_issues());
+            }
+            let mut obj = FileDicomObject { meta, obj };
+            obj.finish_reading(options.strip_group_lengths, options.string_interner);
+            Ok(obj)
         } else {
             UnsupportedTransferSyntaxSnafu {
                 uid: meta.transfer_syntax,
@@ -311,23 +476,31 @@ where
         S: Read,
         R: TransferSyntaxIndex,
     {
-        Self::from_reader_with_all_options(src, dict, ts_index, None, ReadPreamble::Auto)
+        Self::from_reader_with_all_options(
+            src,
+            OpenFileOptions::new()
+                .dictionary(dict)
+                .tranfer_syntax_index(ts_index),
+            None,
+        )
     }
 
     pub(crate) fn from_reader_with_all_options<'s, S: 's, R>(
         src: S,
-        dict: D,
-        ts_index: R,
-        read_until: Option<Tag>,
-        read_preamble: ReadPreamble,
+        options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
     ) -> Result<Self>
     where
         S: Read,
         R: TransferSyntaxIndex,
     {
-        let mut file = BufReader::new(src);
+        if options.preserve_raw_elements {
+            return Self::from_reader_raw_with_all_options(src, options, issues);
+        }
 
-        if read_preamble == ReadPreamble::Always {
+        let mut file = BufReader::with_capacity(options.buffer_capacity(), src);
+
+        if options.read_preamble == ReadPreamble::Always {
             // skip preamble
             let mut buf = [0u8; 128];
             // skip the preamble
@@ -338,18 +511,277 @@ where
         let meta = FileMetaTable::from_reader(&mut file).context(ParseMetaDataSetSnafu)?;
 
         // read rest of data according to metadata, feed it to object
-        if let Some(ts) = ts_index.get(&meta.transfer_syntax) {
+        if let Some(ts) = options.ts_index.get(&meta.transfer_syntax) {
             let cs = SpecificCharacterSet::Default;
             let mut dataset =
-                DataSetReader::new_with_ts_cs(file, ts, cs).context(CreateParserSnafu)?;
+                DataSetReader::new_with_ts_cs_options(file, ts, cs, options.reader_options())
+                    .context(CreateParserSnafu)?;
+            if issues.is_some() {
+                dataset = dataset.collect_issues();
+            }
             let obj = InMemDicomObject::build_object(
                 &mut dataset,
-                dict,
+                options.data_dictionary,
+                false,
+                Length::UNDEFINED,
+                options.read_until,
+            )?;
+            if let Some(issues) = issues {
+                issues.extend(dataset.take_issues());
+            }
+            let mut obj = FileDicomObject { meta, obj };
+            obj.finish_reading(options.strip_group_lengths, options.string_interner);
+            Ok(obj)
+        } else {
+            UnsupportedTransferSyntaxSnafu {
+                uid: meta.transfer_syntax,
+            }
+            .fail()
+        }
+    }
+
+    /// Create a DICOM object by reading from an in-memory buffer,
+    /// such as a memory mapped file,
+    /// which is shared with the binary values of the object.
+    pub(crate) fn from_bytes_with_all_options<R>(
+        data: Bytes,
+        options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Self>
+    where
+        R: TransferSyntaxIndex,
+    {
+        let mut cursor = Cursor::new(&data[..]);
+
+        if options.read_preamble == ReadPreamble::Always {
+            let mut buf = [0u8; 128];
+            // skip the preamble
+            cursor
+                .read_exact(&mut buf)
+                .context(ReadPreambleBytesSnafu)?;
+        }
+
+        // read metadata header
+        let meta = FileMetaTable::from_reader(&mut cursor).context(ParseMetaDataSetSnafu)?;
+        let data = data.slice(cursor.position() as usize..);
+
+        // read rest of data according to metadata, feed it to object
+        if let Some(ts) = options.ts_index.get(&meta.transfer_syntax) {
+            let cs = SpecificCharacterSet::Default;
+            let mut dataset = DataSetReader::new_shared_with_ts_cs_options(
+                data.clone(),
+                ts,
+                cs,
+                options.reader_options(),
+            )
+            .context(CreateParserSnafu)?;
+            if issues.is_some() {
+                dataset = dataset.collect_issues();
+            }
+            let obj = if options.preserve_raw_elements {
+                InMemDicomObject::build_object_with_raw(
+                    &mut dataset,
+                    &data,
+                    ts,
+                    options.data_dictionary,
+                    options.read_until,
+                )?
+            } else {
+                InMemDicomObject::build_object(
+                    &mut dataset,
+                    options.data_dictionary,
+                    false,
+                    Length::UNDEFINED,
+                    options.read_until,
+                )?
+            };
+            if let Some(issues) = issues {
+                issues.extend(dataset.take_issues());
+            }
+            let mut obj = FileDicomObject { meta, obj };
+            obj.finish_reading(options.strip_group_lengths, options.string_interner);
+            Ok(obj)
+        } else {
+            UnsupportedTransferSyntaxSnafu {
+                uid: meta.transfer_syntax,
+            }
+            .fail()
+        }
+    }
+
+    /// Create a DICOM object by memory mapping the file at the given path.
+    ///
+    /// # Safety
+    ///
+    /// See [`OpenFileOptions::open_file_mmap`].
+    #[cfg(feature = "mmap")]
+    pub(crate) unsafe fn open_file_mmap_with_all_options<P, R>(
+        path: P,
+        mut options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        R: TransferSyntaxIndex,
+    {
+        let path = path.as_ref();
+        let file = File::open(path).with_context(|_| OpenFileSnafu { filename: path })?;
+        // Safety: the mapping is only ever read from,
+        // and the caller guarantees that the file is not modified
+        // nor truncated while it is mapped
+        let mmap = unsafe { memmap2::Mmap::map(&file) }
+            .with_context(|_| ReadFileSnafu { filename: path })?;
+
+        // like with regular files, the preamble is read by default
+        if options.read_preamble == ReadPreamble::Auto {
+            options.read_preamble = ReadPreamble::Always;
+        }
+
+        Self::from_bytes_with_all_options(Bytes::from_owner(mmap), options, issues)
+    }
+
+    /// Create a DICOM object by reading the whole file at the given path
+    /// into memory,
+    /// retaining the original bytes of the data set elements.
+    #[cfg(feature = "fs")]
+    pub(crate) fn open_file_raw_with_all_options<P, R>(
+        path: P,
+        mut options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        R: TransferSyntaxIndex,
+    {
+        let path = path.as_ref();
+        let data = std::fs::read(path).with_context(|_| ReadFileSnafu { filename: path })?;
+
+        // like with regular files, the preamble is read by default
+        if options.read_preamble == ReadPreamble::Auto {
+            options.read_preamble = ReadPreamble::Always;
+        }
+        options.preserve_raw_elements = true;
+
+        Self::from_bytes_with_all_options(Bytes::from(data), options, issues)
+    }
+
+    /// Create a DICOM object by reading the whole byte source into memory,
+    /// retaining the original bytes of the data set elements.
+    pub(crate) fn from_reader_raw_with_all_options<S, R>(
+        mut src: S,
+        mut options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Self>
+    where
+        S: Read,
+        R: TransferSyntaxIndex,
+    {
+        let mut data = Vec::new();
+        src.read_to_end(&mut data).context(ReadSourceSnafu)?;
+        options.preserve_raw_elements = true;
+        Self::from_bytes_with_all_options(Bytes::from(data), options, issues)
+    }
+
+    /// Create a DICOM object by asynchronously reading the file at the given path.
+    #[cfg(feature = "async")]
+    pub(crate) async fn open_file_async_with_all_options<P, R>(
+        path: P,
+        mut options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Self>
+    where
+        P: AsRef<Path>,
+        R: TransferSyntaxIndex,
+    {
+        use tokio::io::AsyncReadExt;
+
+        let path = path.as_ref();
+        let mut file = tokio::fs::File::open(path)
+            .await
+            .with_context(|_| OpenFileSnafu { filename: path })?;
+
+        if options.read_preamble == ReadPreamble::Auto
+            || options.read_preamble == ReadPreamble::Always
+        {
+            let mut buf = [0u8; 128];
+            // skip the preamble
+            file.read_exact(&mut buf)
+                .await
+                .with_context(|_| ReadFileSnafu { filename: path })?;
+        }
+        options.read_preamble = ReadPreamble::Never;
+
+        Self::from_async_reader_with_all_options(file, options, issues).await
+    }
+
+    /// Create a DICOM object by asynchronously reading from a byte source.
+    ///
+    /// The source is read in chunks,
+    /// so it does not need to be buffered.
+    #[cfg(feature = "async")]
+    pub(crate) async fn from_async_reader_with_all_options<S, R>(
+        mut src: S,
+        options: OpenFileOptions<D, R>,
+        issues: Option<&mut Vec<ParseIssue>>,
+    ) -> Result<Self>
+    where
+        S: tokio::io::AsyncRead + Unpin,
+        R: TransferSyntaxIndex,
+    {
+        use dicom_parser::dataset::async_read::AsyncDataSetReader;
+        use tokio::io::AsyncReadExt;
+
+        if options.read_preamble == ReadPreamble::Always {
+            let mut buf = [0u8; 128];
+            // skip the preamble
+            src.read_exact(&mut buf)
+                .await
+                .context(ReadPreambleBytesSnafu)?;
+        }
+
+        // retrieve the whole meta group before parsing it:
+        // magic code and group length element first, then the rest of the group
+        let mut meta_data = Vec::with_capacity(256);
+        (&mut src)
+            .take(16)
+            .read_to_end(&mut meta_data)
+            .await
+            .context(ReadMetaGroupSnafu)?;
+        if meta_data.len() == 16 && meta_data[4..8] == [0x02, 0x00, 0x00, 0x00] {
+            let group_length =
+                u32::from_le_bytes([meta_data[12], meta_data[13], meta_data[14], meta_data[15]]);
+            (&mut src)
+                .take(u64::from(group_length))
+                .read_to_end(&mut meta_data)
+                .await
+                .context(ReadMetaGroupSnafu)?;
+        }
+        let meta = FileMetaTable::from_reader(&meta_data[..]).context(ParseMetaDataSetSnafu)?;
+
+        // read rest of data according to metadata, feed it to object
+        if let Some(ts) = options.ts_index.get(&meta.transfer_syntax) {
+            let cs = SpecificCharacterSet::Default;
+            let mut dataset =
+                AsyncDataSetReader::new_with_ts_cs_options(src, ts, cs, options.reader_options())
+                    .context(ReadDataSetAsyncSnafu)?;
+            if issues.is_some() {
+                dataset = dataset.collect_issues();
+            }
+            let tokens = dataset.read_to_end().await.context(ReadDataSetAsyncSnafu)?;
+
+            let obj = InMemDicomObject::build_object(
+                &mut tokens.into_iter().map(Ok::<_, ParserError>),
+                options.data_dictionary,
                 false,
                 Length::UNDEFINED,
-                read_until,
+                options.read_until,
             )?;
-            Ok(FileDicomObject { meta, obj })
+            if let Some(issues) = issues {
+                issues.extend(dataset.take_issues());
+            }
+            let mut obj = FileDicomObject { meta, obj };
+            obj.finish_reading(options.strip_group_lengths, options.string_interner);
+            Ok(obj)
         } else {
             UnsupportedTransferSyntaxSnafu {
                 uid: meta.transfer_syntax,
@@ -357,6 +789,53 @@ where
             .fail()
         }
     }
+
+    /// Apply the reading options
+    /// which take place once the data set is read.
+    fn finish_reading(&mut self, strip_group_lengths: bool, interner: Option<StringInterner>) {
+        if strip_group_lengths {
+            self.remove_group_lengths();
+        }
+        if let Some(interner) = &interner {
+            self.intern_strings(interner);
+        }
+    }
+
+    /// Write the entire object as a DICOM file
+    /// into the given writer,
+    /// writing the elements of the data set
+    /// which were not modified since reading the object
+    /// exactly as they were read
+    /// (see [`OpenFileOptions::preserve_raw_elements`]).
+    ///
+    /// Preamble, magic code, and file meta group will be included
+    /// before the inner object.
+    /// The preamble is always filled with zeros,
+    /// and the file meta group is encoded anew.
+    ///
+    /// [`OpenFileOptions::preserve_raw_elements`]: crate::OpenFileOptions::preserve_raw_elements
+    pub fn write_all_preserving_raw<W: Write>(&self, to: W) -> Result<()> {
+        let mut to = BufWriter::new(to);
+
+        // write preamble
+        to.write_all(&[0_u8; 128][..]).context(WritePreambleSnafu)?;
+
+        // write magic sequence
+        to.write_all(b"DICM").context(WriteMagicCodeSnafu)?;
+
+        // write meta group
+        self.meta.write(&mut to).context(PrintMetaDataSetSnafu)?;
+
+        let registry = TransferSyntaxRegistry::default();
+        let ts = registry.get(&self.meta.transfer_syntax).with_context(|| {
+            UnsupportedTransferSyntaxSnafu {
+                uid: self.meta.transfer_syntax.clone(),
+            }
+        })?;
+
+        // write object
+        self.obj.write_dataset_with_ts(to, ts)
+    }
 }
 
 impl FileDicomObject<InMemDicomObject<StandardDataDictionary>> {
@@ -366,6 +845,8 @@ impl FileDicomObject<InMemDicomObject<StandardDataDictionary>> {
             meta,
             obj: InMemDicomObject {
                 entries: BTreeMap::new(),
+                order: None,
+                raw: None,
                 dict: StandardDataDictionary,
                 len: Length::UNDEFINED,
             },
@@ -382,22 +863,29 @@ where
     pub fn new_empty_with_dict(dict: D) -> Self {
         InMemDicomObject {
             entries: BTreeMap::new(),
+            order: None,
+            raw: None,
             dict,
             len: Length::UNDEFINED,
         }
     }
 
+    /// Start building a new DICOM object from scratch,
+    /// using the given dictionary for name and VR lookup.
+    pub fn builder_with_dict(dict: D) -> InMemDicomObjectBuilder<D> {
+        InMemDicomObjectBuilder::new_with_dict(dict)
+    }
+
     /// Construct a DICOM object from an iterator of structured elements.
     pub fn from_element_source_with_dict<I>(iter: I, dict: D) -> Result<Self>
     where
         I: IntoIterator<Item = Result<InMemElement<D>>>,
     {
-        let entries: Result<_> = iter.into_iter().map_ok(|e| (e.tag(), e)).collect();
-        Ok(InMemDicomObject {
-            entries: entries?,
-            dict,
-            len: Length::UNDEFINED,
-        })
+        let mut obj = InMemDicomObject::new_empty_with_dict(dict);
+        for elem in iter {
+            obj.insert_entry(elem?);
+        }
+        Ok(obj)
     }
 
     /// Construct a DICOM object from a non-fallible iterator of structured elements.
@@ -405,12 +893,19 @@ where
     where
         I: IntoIterator<Item = InMemElement<D>>,
     {
-        let entries = iter.into_iter().map(|e| (e.tag(), e)).collect();
-        InMemDicomObject {
-            entries,
-            dict,
-            len: Length::UNDEFINED,
-        }
+        let mut obj = InMemDicomObject::new_empty_with_dict(dict);
+        obj.extend(iter);
+        obj
+    }
+
+    /// Construct a DICOM object from a sequence of data set tokens,
+    /// using the given dictionary for name lookup.
+    pub fn from_tokens_with_dict<I>(tokens: I, dict: D) -> Result<Self>
+    where
+        I: IntoIterator<Item = DataToken>,
+    {
+        let mut tokens = tokens.into_iter().map(Ok);
+        InMemDicomObject::build_object(&mut tokens, dict, false, Length::UNDEFINED, None)
     }
 
     /// Read an object from a source,
@@ -532,6 +1027,127 @@ where
         }
     }
 
+    /// Retrieve the value of a particular DICOM element
+    /// as a single string, with trailing whitespace removed.
+    ///
+    /// Multiple string values are joined with the standard
+    /// backslash (`\\`) delimiter.
+    /// An error is returned if the element does not exist
+    /// or its value is not primitive.
+    pub fn get_string(&self, tag: Tag) -> Result<Cow<str>> {
+        self.element(tag)?.to_str().context(CastValueSnafu {
+            tag,
+            requested: "string",
+        })
+    }
+
+    /// Retrieve the value of a particular DICOM element
+    /// as a sequence of strings.
+    ///
+    /// An error is returned if the element does not exist
+    /// or its value is not primitive.
+    pub fn get_strings(&self, tag: Tag) -> Result<Cow<[String]>> {
+        self.element(tag)?.to_multi_str().context(CastValueSnafu {
+            tag,
+            requested: "strings",
+        })
+    }
+
+    impl_typed_getters!(get_u16, get_u16s, to_int, to_multi_int, u16, "u16");
+    impl_typed_getters!(get_i16, get_i16s, to_int, to_multi_int, i16, "i16");
+    impl_typed_getters!(get_u32, get_u32s, to_int, to_multi_int, u32, "u32");
+    impl_typed_getters!(get_i32, get_i32s, to_int, to_multi_int, i32, "i32");
+    impl_typed_getters!(get_u64, get_u64s, to_int, to_multi_int, u64, "u64");
+    impl_typed_getters!(get_i64, get_i64s, to_int, to_multi_int, i64, "i64");
+    impl_typed_getters!(get_f32, get_f32s, to_float32, to_multi_float32, f32, "f32");
+    impl_typed_getters!(get_f64, get_f64s, to_float64, to_multi_float64, f64, "f64");
+    impl_typed_getters!(
+        get_date,
+        get_dates,
+        to_date,
+        to_multi_date,
+        DicomDate,
+        "date"
+    );
+    impl_typed_getters!(
+        get_time,
+        get_times,
+        to_time,
+        to_multi_time,
+        DicomTime,
+        "time"
+    );
+
+    /// Retrieve the value of a particular DICOM element
+    /// as a date-time,
+    /// assuming the given time zone offset
+    /// if the value does not specify one.
+    ///
+    /// An error is returned if the element does not exist
+    /// or its value cannot be converted.
+    pub fn get_datetime(&self, tag: Tag, default_offset: FixedOffset) -> Result<DicomDateTime> {
+        self.element(tag)?
+            .to_datetime(default_offset)
+            .context(ConvertValueSnafu {
+                tag,
+                requested: "date-time",
+            })
+    }
+
+    /// Retrieve the items of a particular DICOM sequence element.
+    ///
+    /// An error is returned if the element does not exist
+    /// or is not a sequence.
+    pub fn get_sequence(&self, tag: Tag) -> Result<&[InMemDicomObject<D>]> {
+        self.element(tag)?
+            .items()
+            .context(NotASequenceSnafu { tag })
+    }
+
+    /// Retrieve a DICOM element nested in sequences,
+    /// as designated by the given path.
+    ///
+    /// An error is returned if any of the attributes in the path
+    /// does not exist,
+    /// if an intermediate attribute is not a sequence,
+    /// or if a sequence does not have the requested item.
+    pub fn element_at(&self, path: &DicomPath) -> Result<&InMemElement<D>> {
+        let mut obj = self;
+        for step in path.steps() {
+            let elt = obj.element_by_attribute(&step.attribute)?;
+            let tag = elt.tag();
+            obj = elt
+                .items()
+                .context(NotASequenceSnafu { tag })?
+                .get(step.item as usize)
+                .context(NoSuchSequenceItemSnafu {
+                    tag,
+                    index: step.item,
+                })?;
+        }
+        obj.element_by_attribute(path.attribute())
+    }
+
+    /// Retrieve the value of a DICOM element nested in sequences,
+    /// as designated by the given path in textual form.
+    ///
+    /// See the [`path`](crate::path) module for the path syntax.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use dicom_object::open_file;
+    /// let obj = open_file("enhanced_mr.dcm")?;
+    /// let pixel_spacing = obj
+    ///     .value_at("SharedFunctionalGroupsSequence[0].PixelMeasuresSequence[0].PixelSpacing")?
+    ///     .to_multi_float64()?;
+    /// # Result::<(), Box<dyn std::error::Error>>::Ok(())
+    /// ```
+    pub fn value_at(&self, path: &str) -> Result<&Value<InMemDicomObject<D>, InMemFragment>> {
+        let path = DicomPath::parse(path).context(ParsePathSnafu { path })?;
+        self.element_at(&path).map(|e| e.value())
+    }
+
     /// Insert a data element to the object, replacing (and returning) any
     /// previous element of the same attribute.
     pub fn put(&mut self, elt: InMemElement<D>) -> Option<InMemElement<D>> {
@@ -541,34 +1157,81 @@ where
     /// Insert a data element to the object, replacing (and returning) any
     /// previous element of the same attribute.
     pub fn put_element(&mut self, elt: InMemElement<D>) -> Option<InMemElement<D>> {
-        self.entries.insert(elt.tag(), elt)
+        self.insert_entry(elt)
+    }
+
+    /// Insert a data element to the object by its attribute name,
+    /// replacing (and returning) any previous element of the same attribute.
+    ///
+    /// The attribute name is resolved through the object's data dictionary,
+    /// which also provides the value representation of the new element.
+    /// A tag in the form `(gggg,eeee)`, `gggg,eeee`, or `ggggeeee`
+    /// is also accepted in place of the name.
+    /// The value is converted to the type expected by the value representation,
+    /// such as text parsed into numbers for a VR of `US`,
+    /// or numbers formatted into text for a VR of `IS`.
+    /// An error is returned if the name is not known by the dictionary,
+    /// or if the value cannot be converted.
+    pub fn put_by_name<T>(&mut self, name: &str, value: T) -> Result<Option<InMemElement<D>>>
+    where
+        T: Into<PrimitiveValue>,
+    {
+        let (tag, vr) = self.lookup_name_vr(name)?;
+        let value = convert_value_to_vr(tag, vr, value.into())?;
+        Ok(self.put_element(DataElement::new(tag, vr, value)))
+    }
+
+    /// Insert a textual data element to the object by its attribute name,
+    /// replacing (and returning) any previous element of the same attribute.
+    ///
+    /// The attribute name is resolved as in
+    /// [`put_by_name`](InMemDicomObject::put_by_name),
+    /// and the text is converted to the type expected
+    /// by the value representation of the attribute.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use dicom_object::InMemDicomObject;
+    /// # fn run() -> Result<(), dicom_object::Error> {
+    /// let mut obj = InMemDicomObject::new_empty();
+    /// obj.put_str("StudyDate", "20230101")?;
+    /// assert_eq!(obj.element_by_name("StudyDate")?.to_str().unwrap(), "20230101");
+    /// # Ok(())
+    /// # }
+    /// # run().unwrap();
+    /// ```
+    pub fn put_str(
+        &mut self,
+        name: &str,
+        value: impl Into<String>,
+    ) -> Result<Option<InMemElement<D>>> {
+        self.put_by_name(name, PrimitiveValue::Str(value.into()))
     }
 
     /// Remove a DICOM element by its tag,
     /// reporting whether it was present.
     pub fn remove_element(&mut self, tag: Tag) -> bool {
-        self.entries.remove(&tag).is_some()
+        self.remove_entry(tag).is_some()
     }
 
     /// Remove a DICOM element by its keyword,
     /// reporting whether it was present.
     pub fn remove_element_by_name(&mut self, name: &str) -> Result<bool> {
         let tag = self.lookup_name(name)?;
-        Ok(self.entries.remove(&tag).is_some())
+        Ok(self.remove_entry(tag).is_some())
     }
 
     /// Remove and return a particular DICOM element by its tag.
     pub fn take_element(&mut self, tag: Tag) -> Result<InMemElement<D>> {
-        self.entries
-            .remove(&tag)
+        self.remove_entry(tag)
             .context(NoSuchDataElementTagSnafu { tag })
     }
 
     /// Remove and return a particular DICOM element by its name.
     pub fn take_element_by_name(&mut self, name: &str) -> Result<InMemElement<D>> {
         let tag = self.lookup_name(name)?;
-        self.entries
-            .remove(&tag)
+        self.remove_entry(tag)
             .with_context(|| NoSuchDataElementAliasSnafu {
                 tag,
                 alias: name.to_string(),
@@ -582,43 +1245,224 @@ where
     /// and those for which `f(&element)` returns `false` are removed.
     pub fn retain(&mut self, mut f: impl FnMut(&InMemElement<D>) -> bool) {
         self.entries.retain(|_, elem| f(elem));
+        self.sync_order();
     }
 
-    /// Write this object's data set into the given writer,
-    /// with the given encoder specifications,
-    /// without preamble, magic code, nor file meta group.
-    ///
-    /// The text encoding to use will be the default character set
-    /// until _Specific Character Set_ is found in the data set,
-    /// in which then that character set will be used.
-    ///
-    /// Note: [`write_dataset_with_ts`] and [`write_dataset_with_ts_cs`]
-    /// may be easier to use.
+    /// Update this object with the data elements of another object,
+    /// replacing any existing elements with the same tag.
     ///
-    /// [`write_dataset_with_ts`]: #method.write_dataset_with_ts
-    /// [`write_dataset_with_ts_cs`]: #method.write_dataset_with_ts_cs
-    pub fn write_dataset<W, E>(&self, to: W, encoder: E) -> Result<()>
-    where
-        W: Write,
-        E: EncodeTo<W>,
-    {
-        // prepare data set writer
-        let mut dset_writer = DataSetWriter::new(to, encoder);
-
-        // write object
-        dset_writer
-            .write_sequence(self.into_tokens())
-            .context(PrintDataSetSnafu)?;
-
-        Ok(())
+    /// This is useful for stamping a fixed set of attributes
+    /// (such as those describing the equipment)
+    /// onto generated objects.
+    /// Sequences are replaced as a whole;
+    /// see [`merge_from`](Self::merge_from)
+    /// for other ways to combine sequences.
+    pub fn update_from(&mut self, other: &InMemDicomObject<D>) {
+        self.merge_from(other, SequenceMerge::Replace)
     }
 
-    /// Write this object's data set into the given printer,
-    /// with the specified transfer syntax and character set,
+    /// Merge the data elements of another object into this object.
+    ///
+    /// Elements only present in `other` are added,
+    /// and non-sequence elements present in both objects
+    /// are replaced by those of `other`.
+    /// When both objects have a sequence with the same tag,
+    /// the two are combined according to the given strategy.
+    pub fn merge_from(&mut self, other: &InMemDicomObject<D>, sequences: SequenceMerge) {
+        for elem in other.iter() {
+            let tag = elem.tag();
+            self.record_order(tag);
+            let existing = match sequences {
+                SequenceMerge::Replace => None,
+                SequenceMerge::Append | SequenceMerge::Merge => self.entries.remove(&tag),
+            };
+            let merged = match (existing.map(|e| (e.vr(), e.into_value())), elem.value()) {
+                (
+                    Some((vr, Value::Sequence { mut items, .. })),
+                    Value::Sequence {
+                        items: other_items, ..
+                    },
+                ) => {
+                    if sequences == SequenceMerge::Append {
+                        items.extend(other_items.iter().cloned());
+                    } else {
+                        for (i, other_item) in other_items.iter().enumerate() {
+                            match items.get_mut(i) {
+                                Some(item) => item.merge_from(other_item, sequences),
+                                None => items.push(other_item.clone()),
+                            }
+                        }
+                    }
+                    DataElement::new(
+                        tag,
+                        vr,
+                        Value::Sequence {
+                            items,
+                            size: Length::UNDEFINED,
+                        },
+                    )
+                }
+                _ => elem.clone(),
+            };
+            self.entries.insert(tag, merged);
+        }
+    }
+
+    /// Remove all group length elements (gggg,0000) from the object,
+    /// including those in the items of its sequences.
+    ///
+    /// Group length elements are retired outside of
+    /// the command and file meta groups,
+    /// and their values become stale as soon as a group is modified.
+    pub fn remove_group_lengths(&mut self) {
+        self.entries.retain(|tag, _| tag.element() != 0x0000);
+        self.sync_order();
+
+        let sequences: Vec<Tag> = self
+            .entries
+            .values()
+            .filter(|elem| elem.items().is_some())
+            .map(|elem| elem.tag())
+            .collect();
+        for tag in sequences {
+            self.record_order(tag);
+            if let Some(elem) = self.entries.remove(&tag) {
+                let vr = elem.vr();
+                let elem = match elem.into_value() {
+                    Value::Sequence { mut items, .. } => {
+                        for item in items.iter_mut() {
+                            item.remove_group_lengths();
+                        }
+                        DataElement::new(
+                            tag,
+                            vr,
+                            Value::Sequence {
+                                items,
+                                size: Length::UNDEFINED,
+                            },
+                        )
+                    }
+                    value => DataElement::new(tag, vr, value),
+                };
+                self.entries.insert(tag, elem);
+            }
+        }
+    }
+
+    /// Replace the textual values of the object,
+    /// including those in the items of its sequences,
+    /// with values shared through the given interner.
+    ///
+    /// The values compare equal to the original ones
+    /// and are encoded in the same way,
+    /// so the original bytes of the elements are kept.
+    /// See the [`intern`](crate::intern) module for more details.
+    pub fn intern_strings(&mut self, interner: &StringInterner) {
+        let entries = std::mem::take(&mut self.entries);
+        self.entries = entries
+            .into_iter()
+            .map(|(tag, elem)| {
+                let header = *elem.header();
+                let value = match elem.into_value() {
+                    Value::Primitive(value) => Value::Primitive(interner.intern_value(value)),
+                    Value::Sequence { mut items, size } => {
+                        for item in items.iter_mut() {
+                            item.intern_strings(interner);
+                        }
+                        Value::Sequence { items, size }
+                    }
+                    value => value,
+                };
+                (
+                    tag,
+                    DataElement::new_with_len(tag, header.vr, header.len, value),
+                )
+            })
+            .collect();
+    }
+
+    /// Insert or replace the group length element (gggg,0000)
+    /// of each group in the object's root data set,
+    /// so that it contains the number of bytes taken by
+    /// the other elements of the group
+    /// when encoded in the given transfer syntax.
+    ///
+    /// This is only needed for compatibility with legacy systems
+    /// which expect these retired elements to be present.
+    /// The values are computed for the object as it is now,
+    /// so this should be called right before writing it.
+    pub fn update_group_lengths_with_ts(&mut self, ts: &TransferSyntax) -> Result<()> {
+        let cs = self.charset().unwrap_or_default();
+
+        let groups: Vec<u16> = self
+            .entries
+            .keys()
+            .map(|tag| tag.group())
+            .filter(|&group| group != 0xFFFC)
+            .dedup()
+            .collect();
+        for group in groups {
+            let mut group_obj = InMemDicomObject::new_empty_with_dict(self.dict.clone());
+            group_obj.entries = self
+                .entries
+                .range(Tag(group, 0x0001)..=Tag(group, 0xFFFF))
+                .map(|(tag, elem)| (*tag, elem.clone()))
+                .collect();
+            let mut data = Vec::new();
+            group_obj.write_dataset_with_ts_cs(&mut data, ts, cs)?;
+            self.put(DataElement::new(
+                Tag(group, 0x0000),
+                VR::UL,
+                PrimitiveValue::from(data.len() as u32),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Write this object's data set into the given writer,
+    /// with the given encoder specifications,
+    /// without preamble, magic code, nor file meta group.
+    ///
+    /// The text encoding to use will be the default character set
+    /// until _Specific Character Set_ is found in the data set,
+    /// in which then that character set will be used.
+    ///
+    /// Note: [`write_dataset_with_ts`] and [`write_dataset_with_ts_cs`]
+    /// may be easier to use.
+    ///
+    /// [`write_dataset_with_ts`]: #method.write_dataset_with_ts
+    /// [`write_dataset_with_ts_cs`]: #method.write_dataset_with_ts_cs
+    pub fn write_dataset<W, E>(&self, to: W, encoder: E) -> Result<()>
+    where
+        W: Write,
+        E: EncodeTo<W>,
+    {
+        // prepare data set writer
+        let mut dset_writer = DataSetWriter::new(to, encoder);
+
+        // write object
+        dset_writer
+            .write_sequence(self.into_tokens())
+            .context(PrintDataSetSnafu)?;
+
+        Ok(())
+    }
+
+    /// Write this object's data set into the given printer,
+    /// with the specified transfer syntax and character set,
     /// without preamble, magic code, nor file meta group.
     ///
     /// If the attribute _Specific Character Set_ is found in the data set,
     /// the last parameter is overridden accordingly.
+    ///
+    /// If the original bytes of the elements were retained
+    /// when reading the object
+    /// (see [`OpenFileOptions::preserve_raw_elements`]),
+    /// and the given transfer syntax is the one in which they were read,
+    /// the elements not modified since are written exactly as they were read,
+    /// in their original order.
+    ///
+    /// [`OpenFileOptions::preserve_raw_elements`]: crate::OpenFileOptions::preserve_raw_elements
     pub fn write_dataset_with_ts_cs<W>(
         &self,
         to: W,
@@ -628,6 +1472,25 @@ where
     where
         W: Write,
     {
+        if let Some(raw) = self.raw.as_ref().filter(|raw| raw.ts == ts.uid()) {
+            // the character set element may be among the raw elements,
+            // so the writer would not see it
+            let cs = self.charset().unwrap_or(cs);
+            let mut dset_writer =
+                DataSetWriter::with_ts_cs(to, ts, cs).context(CreatePrinterSnafu)?;
+            for elem in self.iter_in_order() {
+                match raw.elements.get(&elem.tag()) {
+                    Some(bytes) => dset_writer
+                        .write_raw_elements(bytes)
+                        .context(PrintDataSetSnafu)?,
+                    None => dset_writer
+                        .write_sequence(elem.clone().into_tokens())
+                        .context(PrintDataSetSnafu)?,
+                }
+            }
+            return Ok(());
+        }
+
         // prepare data set writer
         let mut dset_writer = DataSetWriter::with_ts_cs(to, ts, cs).context(CreatePrinterSnafu)?;
 
@@ -639,6 +1502,24 @@ where
         Ok(())
     }
 
+    /// Check whether the original bytes of the elements were retained
+    /// when reading the object,
+    /// so that unmodified elements are written exactly as they were read.
+    ///
+    /// See [`OpenFileOptions::preserve_raw_elements`].
+    ///
+    /// [`OpenFileOptions::preserve_raw_elements`]: crate::OpenFileOptions::preserve_raw_elements
+    pub fn has_raw_elements(&self) -> bool {
+        self.raw.is_some()
+    }
+
+    /// Discard the original bytes of the elements
+    /// retained when reading the object,
+    /// so that all elements are encoded anew when writing it.
+    pub fn discard_raw_elements(&mut self) {
+        self.raw = None;
+    }
+
     /// Write this object's data set into the given writer,
     /// with the specified transfer syntax,
     /// without preamble, magic code, nor file meta group.
@@ -692,10 +1573,96 @@ where
         self.entries.keys().copied()
     }
 
+    /// Obtain an iterator over the elements of this object
+    /// in the order in which they were inserted,
+    /// or in which they were found when reading the data set.
+    ///
+    /// Some devices emit elements out of ascending tag order.
+    /// While [`iter`](Self::iter) and the data set writers
+    /// always produce the elements in ascending tag order,
+    /// as mandated by the standard,
+    /// this method can be used to inspect the original order.
+    /// Replacing an existing element keeps its position,
+    /// whereas new elements are placed at the end.
+    pub fn iter_in_order(&self) -> impl Iterator<Item = &InMemElement<D>> + '_ {
+        match &self.order {
+            Some(order) => Either::Left(order.iter().map(move |tag| &self.entries[tag])),
+            None => Either::Right(self.entries.values()),
+        }
+    }
+
+    /// Obtain an iterator over the tags of the object's elements
+    /// in the order in which they were inserted,
+    /// or in which they were found when reading the data set.
+    ///
+    /// See [`iter_in_order`](Self::iter_in_order) for more details.
+    pub fn tags_in_order(&self) -> impl Iterator<Item = Tag> + '_ {
+        self.iter_in_order().map(|elem| elem.tag())
+    }
+
+    /// Check whether the elements of this object
+    /// were inserted in ascending tag order.
+    ///
+    /// This does not check the items of the object's sequences.
+    pub fn is_sorted(&self) -> bool {
+        self.order.is_none()
+    }
+
+    /// Discard the order in which the elements of this object
+    /// and of its sequence items were inserted,
+    /// so that they are only known in ascending tag order.
+    ///
+    /// Any original element bytes retained when reading the object
+    /// are discarded as well,
+    /// so that the object is written in ascending tag order.
+    pub fn into_sorted(mut self) -> Self {
+        self.order = None;
+        self.raw = None;
+        let sequences: Vec<Tag> = self
+            .entries
+            .values()
+            .filter(|elem| elem.items().map_or(false, |items| !items.is_empty()))
+            .map(|elem| elem.tag())
+            .collect();
+        for tag in sequences {
+            if let Some(elem) = self.entries.remove(&tag) {
+                let vr = elem.vr();
+                let len = elem.length();
+                let elem = match elem.into_value() {
+                    Value::Sequence { items, size } => DataElement::new_with_len(
+                        tag,
+                        vr,
+                        len,
+                        Value::Sequence {
+                            items: items.into_iter().map(|item| item.into_sorted()).collect(),
+                            size,
+                        },
+                    ),
+                    value => DataElement::new_with_len(tag, vr, len, value),
+                };
+                self.entries.insert(tag, elem);
+            }
+        }
+        self
+    }
+
+    /// Retrieve the data dictionary used by this object.
+    pub(crate) fn dict(&self) -> &D {
+        &self.dict
+    }
+
+    /// Retrieve the character set declared by
+    /// the object's _Specific Character Set_ attribute, if any.
+    fn charset(&self) -> Option<SpecificCharacterSet> {
+        self.get_string(tags::SPECIFIC_CHARACTER_SET)
+            .ok()
+            .and_then(|code| SpecificCharacterSet::from_code(code.trim()))
+    }
+
     // private methods
 
     /// Build an object by consuming a data set parser.
-    fn build_object<I: ?Sized>(
+    pub(crate) fn build_object<I: ?Sized>(
         dataset: &mut I,
         dict: D,
         in_item: bool,
@@ -705,7 +1672,13 @@ where
     where
         I: Iterator<Item = ParserResult<DataToken>>,
     {
-        let mut entries: BTreeMap<Tag, InMemElement<D>> = BTreeMap::new();
+        let mut obj = InMemDicomObject {
+            entries: BTreeMap::new(),
+            order: None,
+            raw: None,
+            dict,
+            len,
+        };
         // perform a structured parsing of incoming tokens
         while let Some(token) = dataset.next() {
             let elem = match token.context(ReadTokenSnafu)? {
@@ -747,7 +1720,7 @@ where
                     }
 
                     // delegate sequence building to another function
-                    let items = Self::build_sequence(tag, len, &mut *dataset, &dict)?;
+                    let items = Self::build_sequence(tag, len, &mut *dataset, &obj.dict)?;
                     DataElement::new_with_len(
                         tag,
                         VR::SQ,
@@ -757,14 +1730,82 @@ where
                 }
                 DataToken::ItemEnd if in_item => {
                     // end of item, leave now
-                    return Ok(InMemDicomObject { entries, dict, len });
+                    return Ok(obj);
                 }
                 token => return UnexpectedTokenSnafu { token }.fail(),
             };
-            entries.insert(elem.tag(), elem);
+            obj.insert_entry(elem);
         }
 
-        Ok(InMemDicomObject { entries, dict, len })
+        Ok(obj)
+    }
+
+    /// Build an object by consuming a data set parser
+    /// which reads from the start of the given in-memory data,
+    /// retaining the encoded bytes of each element in the root data set.
+    fn build_object_with_raw<S>(
+        dataset: &mut DataSetReader<S>,
+        data: &Bytes,
+        ts: &TransferSyntax,
+        dict: D,
+        read_until: Option<Tag>,
+    ) -> Result<Self>
+    where
+        S: StatefulDecode,
+    {
+        let mut spans = Vec::new();
+        let mut obj = {
+            let mut depth = 0;
+            let mut start = None;
+            let mut tokens = dataset
+                .positioned()
+                .map(|token| -> ParserResult<DataToken> {
+                    let token = token?;
+                    match &token.token {
+                        DataToken::ElementHeader(header) if depth == 0 => {
+                            start = Some((header.tag, token.offset));
+                        }
+                        DataToken::PrimitiveValue(_) if depth == 0 => {
+                            if let Some((tag, offset)) = start.take() {
+                                spans.push((tag, offset, token.end()));
+                            }
+                        }
+                        DataToken::SequenceStart { tag, .. } => {
+                            if depth == 0 {
+                                start = Some((*tag, token.offset));
+                            }
+                            depth += 1;
+                        }
+                        DataToken::PixelSequenceStart => {
+                            if depth == 0 {
+                                start = Some((Tag(0x7fe0, 0x0010), token.offset));
+                            }
+                            depth += 1;
+                        }
+                        DataToken::SequenceEnd => {
+                            depth -= 1;
+                            if depth == 0 {
+                                if let Some((tag, offset)) = start.take() {
+                                    spans.push((tag, offset, token.end()));
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                    Ok(token.token)
+                });
+            InMemDicomObject::build_object(&mut tokens, dict, false, Length::UNDEFINED, read_until)?
+        };
+
+        let elements = spans
+            .into_iter()
+            .map(|(tag, start, end)| (tag, data.slice(start as usize..end as usize)))
+            .collect();
+        obj.raw = Some(RawElements {
+            ts: ts.uid().to_string(),
+            elements,
+        });
+        Ok(obj)
     }
 
     /// Build an encapsulated pixel data by collecting all fragments into an
@@ -861,6 +1902,329 @@ where
             .context(NoSuchAttributeNameSnafu { name })
             .map(|e| e.tag())
     }
+
+    /// Resolve an attribute name or tag expression into its tag,
+    /// as in [`DataDictionary::parse_tag`].
+    fn lookup_attribute(&self, name: &str) -> Result<Tag> {
+        self.dict
+            .parse_tag(name)
+            .context(NoSuchAttributeNameSnafu { name })
+    }
+
+    /// Retrieve a DICOM element by attribute name or tag expression.
+    fn element_by_attribute(&self, name: &str) -> Result<&InMemElement<D>> {
+        let tag = self.lookup_attribute(name)?;
+        self.entries
+            .get(&tag)
+            .with_context(|| NoSuchDataElementAliasSnafu {
+                tag,
+                alias: name.to_string(),
+            })
+    }
+
+    /// Resolve an attribute name or tag expression
+    /// into its tag and typical VR,
+    /// as in [`DataDictionary::parse_tag`].
+    fn lookup_name_vr(&self, name: &str) -> Result<(Tag, VR)> {
+        let tag = self.lookup_attribute(name)?;
+        let vr = self.dict.by_tag(tag).map(|e| e.vr()).unwrap_or(VR::UN);
+        Ok((tag, vr))
+    }
+}
+
+/// Convert a value to a type which can be encoded
+/// in the given value representation.
+///
+/// Values which are already compatible are kept as they are.
+/// Text is parsed into numbers for binary numeric value representations,
+/// and other values are formatted into text
+/// for textual value representations.
+fn convert_value_to_vr(tag: Tag, vr: VR, value: PrimitiveValue) -> Result<PrimitiveValue> {
+    use dicom_core::value::validate::{check_value, ValidationMode};
+    use dicom_core::value::{ConvertValueError, DicomValueType};
+
+    let textual = matches!(
+        vr,
+        VR::AE
+            | VR::AS
+            | VR::CS
+            | VR::DA
+            | VR::DS
+            | VR::DT
+            | VR::IS
+            | VR::LO
+            | VR::LT
+            | VR::PN
+            | VR::SH
+            | VR::ST
+            | VR::TM
+            | VR::UC
+            | VR::UI
+            | VR::UR
+            | VR::UT
+    );
+    let is_text = matches!(
+        value,
+        PrimitiveValue::Str(_) | PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_)
+    );
+    if matches!(value, PrimitiveValue::Empty)
+        || (textual && is_text)
+        || check_value(vr, &value, ValidationMode::Lenient).is_ok()
+    {
+        return Ok(value);
+    }
+
+    let requested = vr.to_string();
+    let converted = match vr {
+        _ if textual => Ok(PrimitiveValue::Strs(
+            value.to_multi_str().iter().cloned().collect(),
+        )),
+        VR::US | VR::OW => value.to_multi_int().map(|v| PrimitiveValue::U16(v.into())),
+        VR::SS => value.to_multi_int().map(|v| PrimitiveValue::I16(v.into())),
+        VR::UL | VR::OL => value.to_multi_int().map(|v| PrimitiveValue::U32(v.into())),
+        VR::SL => value.to_multi_int().map(|v| PrimitiveValue::I32(v.into())),
+        VR::UV | VR::OV => value.to_multi_int().map(|v| PrimitiveValue::U64(v.into())),
+        VR::SV => value.to_multi_int().map(|v| PrimitiveValue::I64(v.into())),
+        VR::FL | VR::OF => value
+            .to_multi_float32()
+            .map(|v| PrimitiveValue::F32(v.into())),
+        VR::FD | VR::OD => value
+            .to_multi_float64()
+            .map(|v| PrimitiveValue::F64(v.into())),
+        _ => Err(ConvertValueError {
+            requested,
+            original: value.value_type(),
+            cause: None,
+        }),
+    };
+    converted.context(ConvertValueSnafu { tag, requested })
+}
+
+/// A builder for DICOM objects constructed from scratch.
+///
+/// Elements are added through chained method calls.
+/// When the value representation is omitted,
+/// it is looked up in the data dictionary,
+/// falling back to `UN` for unknown attributes.
+/// The first error found while adding elements
+/// is reported by [`build`](InMemDicomObjectBuilder::build).
+#[derive(Debug)]
+pub struct InMemDicomObjectBuilder<D = StandardDataDictionary> {
+    /// the object being built
+    obj: InMemDicomObject<D>,
+    /// the first error found while adding elements
+    error: Option<Error>,
+}
+
+impl<D> InMemDicomObjectBuilder<D>
+where
+    D: DataDictionary,
+    D: Clone,
+{
+    /// Create a new builder for an empty object,
+    /// using the given dictionary for name and VR lookup.
+    pub fn new_with_dict(dict: D) -> Self {
+        InMemDicomObjectBuilder {
+            obj: InMemDicomObject::new_empty_with_dict(dict),
+            error: None,
+        }
+    }
+
+    /// Add a primitive data element with the given value representation.
+    ///
+    /// Any previous element with the same tag is replaced.
+    pub fn add<T>(mut self, tag: Tag, vr: VR, value: T) -> Self
+    where
+        T: Into<PrimitiveValue>,
+    {
+        let value = value.into();
+        if self.error.is_none() {
+            match check_multiplicity(tag, vr, &value) {
+                Ok(()) => {
+                    self.obj.put(DataElement::new(tag, vr, value));
+                }
+                Err(e) => self.error = Some(e),
+            }
+        }
+        self
+    }
+
+    /// Add a primitive data element,
+    /// with the value representation given by the data dictionary.
+    ///
+    /// Any previous element with the same tag is replaced.
+    pub fn add_value<T>(self, tag: Tag, value: T) -> Self
+    where
+        T: Into<PrimitiveValue>,
+    {
+        let vr = self.obj.dict.by_tag(tag).map(|e| e.vr()).unwrap_or(VR::UN);
+        self.add(tag, vr, value)
+    }
+
+    /// Add a primitive data element by its attribute name,
+    /// with the value representation given by the data dictionary.
+    /// The value is converted as in
+    /// [`InMemDicomObject::put_by_name`].
+    ///
+    /// Any previous element with the same tag is replaced.
+    pub fn add_by_name<T>(mut self, name: &str, value: T) -> Self
+    where
+        T: Into<PrimitiveValue>,
+    {
+        if self.error.is_some() {
+            return self;
+        }
+        let element = self.obj.lookup_name_vr(name).and_then(|(tag, vr)| {
+            convert_value_to_vr(tag, vr, value.into()).map(|value| (tag, vr, value))
+        });
+        match element {
+            Ok((tag, vr, value)) => self.add(tag, vr, value),
+            Err(e) => {
+                self.error = Some(e);
+                self
+            }
+        }
+    }
+
+    /// Add a sequence data element with the given items.
+    ///
+    /// Any previous element with the same tag is replaced.
+    pub fn add_seq<I>(mut self, tag: Tag, items: I) -> Self
+    where
+        I: IntoIterator<Item = InMemDicomObject<D>>,
+    {
+        if self.error.is_none() {
+            let items: C<_> = items.into_iter().collect();
+            self.obj.put(DataElement::new(
+                tag,
+                VR::SQ,
+                Value::Sequence {
+                    items,
+                    size: Length::UNDEFINED,
+                },
+            ));
+        }
+        self
+    }
+
+    /// Add an existing data element.
+    ///
+    /// Any previous element with the same tag is replaced.
+    pub fn add_element(mut self, elt: InMemElement<D>) -> Self {
+        if self.error.is_none() {
+            if let Value::Primitive(value) = elt.value() {
+                if let Err(e) = check_multiplicity(elt.tag(), elt.vr(), value) {
+                    self.error = Some(e);
+                    return self;
+                }
+            }
+            self.obj.put(elt);
+        }
+        self
+    }
+
+    /// Finish building the object,
+    /// or return the first error found while adding elements.
+    pub fn build(self) -> Result<InMemDicomObject<D>> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.obj),
+        }
+    }
+}
+
+/// Check that the multiplicity of a value
+/// is admitted by its value representation.
+///
+/// Value representations for which the standard
+/// always defines a value multiplicity of 1
+/// do not admit more than one value.
+fn check_multiplicity(tag: Tag, vr: VR, value: &PrimitiveValue) -> Result<()> {
+    match vr {
+        VR::LT | VR::ST | VR::UT | VR::UR => {
+            let multiplicity = value.multiplicity();
+            ensure!(
+                multiplicity <= 1,
+                InvalidMultiplicitySnafu {
+                    tag,
+                    vr,
+                    multiplicity
+                }
+            );
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+impl<D> InMemDicomObject<D> {
+    /// Insert an element into the element map,
+    /// keeping track of the insertion order.
+    fn insert_entry(&mut self, elt: InMemElement<D>) -> Option<InMemElement<D>> {
+        let tag = elt.tag();
+        self.record_order(tag);
+        self.entries.insert(tag, elt)
+    }
+
+    /// Remove an element from the element map,
+    /// keeping track of the insertion order.
+    fn remove_entry(&mut self, tag: Tag) -> Option<InMemElement<D>> {
+        let elt = self.entries.remove(&tag)?;
+        if let Some(order) = &mut self.order {
+            order.retain(|t| *t != tag);
+        }
+        self.forget_sorted_order();
+        if let Some(raw) = &mut self.raw {
+            raw.elements.remove(&tag);
+        }
+        Some(elt)
+    }
+
+    /// Record that an element with the given tag is about to be inserted.
+    ///
+    /// The insertion order only starts being kept
+    /// once an element is inserted out of ascending tag order.
+    /// The original bytes of a replaced element are discarded.
+    fn record_order(&mut self, tag: Tag) {
+        if let Some(raw) = &mut self.raw {
+            raw.elements.remove(&tag);
+        }
+        if self.entries.contains_key(&tag) {
+            return;
+        }
+        match &mut self.order {
+            Some(order) => order.push(tag),
+            None => {
+                if matches!(self.entries.keys().next_back(), Some(last) if *last > tag) {
+                    let mut order: Vec<Tag> = self.entries.keys().copied().collect();
+                    order.push(tag);
+                    self.order = Some(order);
+                }
+            }
+        }
+    }
+
+    /// Remove the tags of the elements no longer present
+    /// from the insertion order and from the original element bytes.
+    fn sync_order(&mut self) {
+        let entries = &self.entries;
+        if let Some(order) = &mut self.order {
+            order.retain(|tag| entries.contains_key(tag));
+        }
+        if let Some(raw) = &mut self.raw {
+            raw.elements.retain(|tag, _| entries.contains_key(tag));
+        }
+        self.forget_sorted_order();
+    }
+
+    /// Stop keeping the insertion order
+    /// if it became the same as ascending tag order
+    /// after removing elements.
+    fn forget_sorted_order(&mut self) {
+        if matches!(&self.order, Some(order) if order.windows(2).all(|w| w[0] < w[1])) {
+            self.order = None;
+        }
+    }
 }
 
 impl<'a, D> IntoIterator for &'a InMemDicomObject<D> {
@@ -910,7 +2274,9 @@ impl<D> Extend<InMemElement<D>> for InMemDicomObject<D> {
     where
         I: IntoIterator<Item = InMemElement<D>>,
     {
-        self.entries.extend(iter.into_iter().map(|e| (e.tag(), e)))
+        for elem in iter {
+            self.insert_entry(elem);
+        }
     }
 }
 
@@ -934,11 +2300,16 @@ mod tests {
 
     fn assert_obj_eq<D>(obj1: &InMemDicomObject<D>, obj2: &InMemDicomObject<D>)
     where
-        D: std::fmt::Debug,
+        D: std::fmt::Debug + DataDictionary + Clone,
     {
         // debug representation because it makes a stricter comparison and
         // assumes that Undefined lengths are equal.
-        assert_eq!(format!("{:?}", obj1), format!("{:?}", obj2))
+        // The insertion order of the elements is discarded beforehand,
+        // so that it is not compared.
+        assert_eq!(
+            format!("{:?}", obj1.clone().into_sorted()),
+            format!("{:?}", obj2.clone().into_sorted())
+        )
     }
 
     #[test]
@@ -1017,6 +2388,256 @@ mod tests {
         assert_eq!(obj, gt);
     }
 
+    #[test]
+    fn inmem_object_read_dataset_out_of_order() {
+        #[rustfmt::skip]
+        let data_in = [
+            0x10, 0x00, 0x10, 0x00, // Tag(0x0010, 0x0010)
+            0x08, 0x00, 0x00, 0x00, // Length: 8
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+            0x08, 0x00, 0x60, 0x00, // Tag(0x0008, 0x0060)
+            0x02, 0x00, 0x00, 0x00, // Length: 2
+            b'M', b'R',
+            0x10, 0x00, 0x20, 0x00, // Tag(0x0010, 0x0020)
+            0x02, 0x00, 0x00, 0x00, // Length: 2
+            b'1', b'2',
+        ];
+
+        let ts = TransferSyntaxRegistry.get("1.2.840.10008.1.2").unwrap();
+        let obj = InMemDicomObject::read_dataset_with_ts(&data_in[..], &ts).unwrap();
+
+        assert!(!obj.is_sorted());
+        let tags: Vec<_> = obj.tags().collect();
+        assert_eq!(
+            tags,
+            vec![tags::MODALITY, tags::PATIENT_NAME, tags::PATIENT_ID]
+        );
+        let tags: Vec<_> = obj.tags_in_order().collect();
+        assert_eq!(
+            tags,
+            vec![tags::PATIENT_NAME, tags::MODALITY, tags::PATIENT_ID]
+        );
+
+        // the original order does not affect equality
+        let sorted = obj.clone().into_sorted();
+        assert!(sorted.is_sorted());
+        assert_eq!(sorted, obj);
+        let tags: Vec<_> = sorted.tags_in_order().collect();
+        assert_eq!(
+            tags,
+            vec![tags::MODALITY, tags::PATIENT_NAME, tags::PATIENT_ID]
+        );
+    }
+
+    #[test]
+    fn inmem_object_insertion_order() {
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::MODALITY,
+            VR::CS,
+            PrimitiveValue::from("MR"),
+        ));
+        obj.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("12"),
+        ));
+        assert!(obj.is_sorted());
+
+        obj.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        assert!(!obj.is_sorted());
+
+        // replacing an element keeps its position
+        obj.put(DataElement::new(
+            tags::MODALITY,
+            VR::CS,
+            PrimitiveValue::from("CT"),
+        ));
+        let tags: Vec<_> = obj.tags_in_order().collect();
+        assert_eq!(
+            tags,
+            vec![tags::MODALITY, tags::PATIENT_ID, tags::PATIENT_NAME]
+        );
+
+        assert!(obj.remove_element(tags::PATIENT_ID));
+        let tags: Vec<_> = obj.tags_in_order().collect();
+        assert_eq!(tags, vec![tags::MODALITY, tags::PATIENT_NAME]);
+        // the remaining elements are in ascending tag order again
+        assert!(obj.is_sorted());
+
+        obj.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("12"),
+        ));
+        obj.put(DataElement::new(
+            tags::ACCESSION_NUMBER,
+            VR::SH,
+            PrimitiveValue::from("A1"),
+        ));
+        assert!(!obj.is_sorted());
+        obj.retain(|elem| elem.tag() != tags::ACCESSION_NUMBER);
+        assert!(obj.is_sorted());
+    }
+
+    #[test]
+    fn inmem_object_iter_in_order() {
+        let mut obj = InMemDicomObject::new_empty();
+        assert_eq!(obj.iter_in_order().count(), 0);
+        obj.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        obj.put(DataElement::new(
+            tags::MODALITY,
+            VR::CS,
+            PrimitiveValue::from("MR"),
+        ));
+        obj.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("12"),
+        ));
+
+        let values: Vec<_> = obj
+            .iter_in_order()
+            .map(|elem| (elem.tag(), elem.to_str().unwrap().into_owned()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (tags::PATIENT_NAME, "Doe^John".to_string()),
+                (tags::MODALITY, "MR".to_string()),
+                (tags::PATIENT_ID, "12".to_string()),
+            ]
+        );
+        // ascending tag order is kept in the other iterators
+        let tags: Vec<_> = obj.iter().map(|elem| elem.tag()).collect();
+        assert_eq!(
+            tags,
+            vec![tags::MODALITY, tags::PATIENT_NAME, tags::PATIENT_ID]
+        );
+
+        // the order of sequence items is kept as well
+        let item = obj.clone();
+        let mut parent = InMemDicomObject::new_empty();
+        parent.put(DataElement::new(
+            tags::REFERENCED_IMAGE_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: smallvec::smallvec![item],
+                size: Length::UNDEFINED,
+            },
+        ));
+        let items = parent
+            .get_sequence(tags::REFERENCED_IMAGE_SEQUENCE)
+            .unwrap();
+        let tags: Vec<_> = items[0].tags_in_order().collect();
+        assert_eq!(
+            tags,
+            vec![tags::PATIENT_NAME, tags::MODALITY, tags::PATIENT_ID]
+        );
+
+        // and is discarded along with the order of the items
+        let sorted = parent.into_sorted();
+        let items = sorted
+            .get_sequence(tags::REFERENCED_IMAGE_SEQUENCE)
+            .unwrap();
+        assert!(items[0].is_sorted());
+        let values: Vec<_> = items[0].iter_in_order().map(|elem| elem.tag()).collect();
+        assert_eq!(
+            values,
+            vec![tags::MODALITY, tags::PATIENT_NAME, tags::PATIENT_ID]
+        );
+    }
+
+    #[test]
+    fn assert_obj_eq_ignores_insertion_order() {
+        let name = DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John"));
+        let modality = DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from("MR"));
+        let obj1 = InMemDicomObject::from_element_iter([name.clone(), modality.clone()]);
+        let obj2 = InMemDicomObject::from_element_iter([modality, name]);
+        assert!(!obj1.is_sorted());
+        assert!(obj2.is_sorted());
+        assert_obj_eq(&obj1, &obj2);
+    }
+
+    #[test]
+    fn inmem_object_preserve_raw_elements() {
+        let meta = FileMetaTableBuilder::default()
+            // Explicit VR Little Endian
+            .transfer_syntax("1.2.840.10008.1.2.1")
+            // Computed Radiography image storage
+            .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1")
+            .media_storage_sop_instance_uid("1.2.3.4")
+            .build()
+            .unwrap();
+        let mut meta_data = b"DICM".to_vec();
+        meta.write(&mut meta_data).unwrap();
+
+        #[rustfmt::skip]
+        let data_set: &[u8] = &[
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00, // (0010,0010) PN, len 8
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+            // out of order
+            0x08, 0x00, 0x60, 0x00, b'C', b'S', 0x02, 0x00, // (0008,0060) CS, len 2
+            b'M', b'R',
+            // sequence and item with explicit length
+            0x08, 0x00, 0x15, 0x11, b'S', b'Q', 0x00, 0x00, // (0008,1115) SQ
+            0x14, 0x00, 0x00, 0x00, // len 20
+            0xFE, 0xFF, 0x00, 0xE0, 0x0C, 0x00, 0x00, 0x00, // item, len 12
+            0x08, 0x00, 0x50, 0x11, b'U', b'I', 0x04, 0x00, // (0008,1150) UI, len 4
+            b'1', b'.', b'2', 0x00,
+            0x10, 0x00, 0x20, 0x00, b'L', b'O', 0x02, 0x00, // (0010,0020) LO, len 2
+            b'1', b'2',
+        ];
+        let mut data = meta_data.clone();
+        data.extend_from_slice(data_set);
+
+        let mut obj = crate::OpenFileOptions::new()
+            .preserve_raw_elements(true)
+            .from_bytes(Bytes::from(data))
+            .unwrap();
+        assert!(obj.has_raw_elements());
+
+        let mut expected = vec![0; 128];
+        expected.extend_from_slice(&meta_data);
+        expected.extend_from_slice(data_set);
+
+        let mut out = Vec::new();
+        obj.write_all_preserving_raw(&mut out).unwrap();
+        assert_eq!(out, expected);
+
+        // without the original bytes, the sequence is encoded anew
+        let mut out = Vec::new();
+        obj.write_all(&mut out).unwrap();
+        assert_ne!(out, expected);
+
+        // only the modified element is encoded anew
+        obj.put(DataElement::new(
+            tags::PATIENT_ID,
+            VR::LO,
+            PrimitiveValue::from("34"),
+        ));
+        let len = expected.len();
+        expected[len - 2..].copy_from_slice(b"34");
+
+        let mut out = Vec::new();
+        obj.write_all_preserving_raw(&mut out).unwrap();
+        assert_eq!(out, expected);
+
+        obj.discard_raw_elements();
+        assert!(!obj.has_raw_elements());
+        let mut out = Vec::new();
+        obj.write_all_preserving_raw(&mut out).unwrap();
+        assert_ne!(out, expected);
+    }
+
     /// Reading a data set
     /// saves the original length of a text element.
     #[test]
@@ -1154,71 +2775,285 @@ mod tests {
             )
             .unwrap();
 
-        // create temporary file path and write object to that file
+        // create temporary file path and write object to that file
+        let dir = tempfile::tempdir().unwrap();
+        let mut file_path = dir.into_path();
+        file_path.push(format!("{}.dcm", sop_uid));
+
+        file_object.write_to_file(&file_path).unwrap();
+
+        // read the file back to validate the outcome
+        let saved_object = open_file(file_path).unwrap();
+        assert_eq!(file_object, saved_object);
+    }
+
+    /// Creating a file DICOM object from an in-mem DICOM object
+    /// infers the SOP instance UID.
+    #[test]
+    fn inmem_with_meta_infers_sop_instance_uid() {
+        let sop_uid = "1.4.645.252521";
+        let mut obj = InMemDicomObject::new_empty();
+
+        obj.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_uid),
+        ));
+
+        let file_object = obj
+            .with_meta(
+                // Media Storage SOP Instance UID deliberately not set
+                FileMetaTableBuilder::default()
+                    // Explicit VR Little Endian
+                    .transfer_syntax("1.2.840.10008.1.2.1")
+                    // Computed Radiography image storage
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1"),
+            )
+            .unwrap();
+
+        let meta = file_object.meta();
+
+        assert_eq!(
+            meta.media_storage_sop_instance_uid
+                .trim_end_matches(|c| c == '\0'),
+            sop_uid.trim_end_matches(|c| c == '\0'),
+        );
+    }
+
+    /// Write a file from scratch, with exact file meta table.
+    #[test]
+    fn inmem_write_to_file_with_exact_meta() {
+        let sop_uid = "1.4.645.212121";
+        let mut obj = InMemDicomObject::new_empty();
+
+        obj.put(DataElement::new(
+            Tag(0x0010, 0x0010),
+            VR::PN,
+            dicom_value!(Strs, ["Doe^John"]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0008, 0x0060),
+            VR::CS,
+            dicom_value!(Strs, ["CR"]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0008, 0x0018),
+            VR::UI,
+            dicom_value!(Strs, [sop_uid]),
+        ));
+
+        let file_object = obj.with_exact_meta(
+            FileMetaTableBuilder::default()
+                // Explicit VR Little Endian
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                // Computed Radiography image storage
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1")
+                .media_storage_sop_instance_uid(sop_uid)
+                .build()
+                .unwrap(),
+        );
+
+        // create temporary file path and write object to that file
+        let dir = tempfile::tempdir().unwrap();
+        let mut file_path = dir.into_path();
+        file_path.push(format!("{}.dcm", sop_uid));
+
+        file_object.write_to_file(&file_path).unwrap();
+
+        // read the file back to validate the outcome
+        let saved_object = open_file(file_path).unwrap();
+        assert_eq!(file_object, saved_object);
+    }
+
+    /// Read a file back with a very small read buffer.
+    #[test]
+    fn inmem_read_file_small_buffer() {
+        let sop_uid = "1.4.645.212123";
+        let mut obj = InMemDicomObject::new_empty();
+
+        obj.put(DataElement::new(
+            Tag(0x0008, 0x0018),
+            VR::UI,
+            dicom_value!(Strs, [sop_uid]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0010, 0x0010),
+            VR::PN,
+            dicom_value!(Strs, ["Doe^John"]),
+        ));
+
+        let file_object = obj.with_exact_meta(
+            FileMetaTableBuilder::default()
+                // Explicit VR Little Endian
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                // Computed Radiography image storage
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1")
+                .media_storage_sop_instance_uid(sop_uid)
+                .build()
+                .unwrap(),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join(format!("{}.dcm", sop_uid));
+        file_object.write_to_file(&file_path).unwrap();
+
+        let saved_object = crate::OpenFileOptions::new()
+            .read_buffer_capacity(3)
+            .open_file(&file_path)
+            .unwrap();
+        assert_eq!(file_object, saved_object);
+    }
+
+    /// Write a file with binary data and read it back through a memory map.
+    #[cfg(feature = "mmap")]
+    #[test]
+    fn inmem_read_file_mmap() {
+        let sop_uid = "1.4.645.212122";
+        let mut obj = InMemDicomObject::new_empty();
+
+        obj.put(DataElement::new(
+            Tag(0x0008, 0x0018),
+            VR::UI,
+            dicom_value!(Strs, [sop_uid]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x7FE0, 0x0010),
+            VR::OB,
+            PrimitiveValue::from(vec![1_u8, 2, 3, 4, 5, 6, 7, 8]),
+        ));
+
+        let file_object = obj.with_exact_meta(
+            FileMetaTableBuilder::default()
+                // Explicit VR Little Endian
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                // Computed Radiography image storage
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1")
+                .media_storage_sop_instance_uid(sop_uid)
+                .build()
+                .unwrap(),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let file_path = dir.path().join(format!("{}.dcm", sop_uid));
+        file_object.write_to_file(&file_path).unwrap();
+
+        // Safety: the file is not modified while the object is alive
+        let saved_object = unsafe { crate::open_file_mmap(&file_path) }.unwrap();
+        assert_eq!(file_object, saved_object);
+
+        let pixel_data = saved_object.element(Tag(0x7FE0, 0x0010)).unwrap();
+        assert!(matches!(
+            pixel_data.value(),
+            Value::Primitive(PrimitiveValue::SharedBytes(_))
+        ));
+    }
+
+    /// Binary values read from a shared buffer
+    /// are equal to those read from a file,
+    /// and keep their natural width.
+    #[test]
+    fn inmem_read_shared_bytes_same_as_file() {
+        let sop_uid = "1.4.645.212124";
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            Tag(0x0008, 0x0018),
+            VR::UI,
+            dicom_value!(Strs, [sop_uid]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0028, 0x1201),
+            VR::OW,
+            dicom_value!(U16, [0x0102, 0x0304, 0xFFFF]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0066, 0x0016),
+            VR::OF,
+            dicom_value!(F32, [1.5, -2.]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0066, 0x0040),
+            VR::OL,
+            dicom_value!(U32, [7, 0x1000_0000]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x7FE0, 0x0010),
+            VR::OB,
+            PrimitiveValue::from(vec![1_u8, 2, 3, 4]),
+        ));
+
+        let file_object = obj.with_exact_meta(
+            FileMetaTableBuilder::default()
+                // Explicit VR Little Endian
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                // Computed Radiography image storage
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1")
+                .media_storage_sop_instance_uid(sop_uid)
+                .build()
+                .unwrap(),
+        );
+
         let dir = tempfile::tempdir().unwrap();
-        let mut file_path = dir.into_path();
-        file_path.push(format!("{}.dcm", sop_uid));
-
+        let file_path = dir.path().join(format!("{}.dcm", sop_uid));
         file_object.write_to_file(&file_path).unwrap();
 
-        // read the file back to validate the outcome
-        let saved_object = open_file(file_path).unwrap();
-        assert_eq!(file_object, saved_object);
-    }
-
-    /// Creating a file DICOM object from an in-mem DICOM object
-    /// infers the SOP instance UID.
-    #[test]
-    fn inmem_with_meta_infers_sop_instance_uid() {
-        let sop_uid = "1.4.645.252521";
-        let mut obj = InMemDicomObject::new_empty();
-
-        obj.put(DataElement::new(
-            tags::SOP_INSTANCE_UID,
-            VR::UI,
-            PrimitiveValue::from(sop_uid),
-        ));
-
-        let file_object = obj
-            .with_meta(
-                // Media Storage SOP Instance UID deliberately not set
-                FileMetaTableBuilder::default()
-                    // Explicit VR Little Endian
-                    .transfer_syntax("1.2.840.10008.1.2.1")
-                    // Computed Radiography image storage
-                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.1"),
-            )
+        let from_file = open_file(&file_path).unwrap();
+        // skip the preamble
+        let data = std::fs::read(&file_path).unwrap().split_off(128);
+        let from_bytes = crate::OpenFileOptions::new()
+            .from_bytes(Bytes::from(data))
             .unwrap();
+        assert_eq!(from_file, from_bytes);
+        assert_eq!(from_bytes, file_object);
 
-        let meta = file_object.meta();
+        for obj in [&from_file, &from_bytes] {
+            assert_eq!(
+                obj.element(Tag(0x0028, 0x1201)).unwrap().value(),
+                &Value::Primitive(dicom_value!(U16, [0x0102, 0x0304, 0xFFFF]))
+            );
+            assert!(matches!(
+                obj.element(Tag(0x0066, 0x0016)).unwrap().value(),
+                Value::Primitive(PrimitiveValue::F32(_))
+            ));
+            assert!(matches!(
+                obj.element(Tag(0x0066, 0x0040)).unwrap().value(),
+                Value::Primitive(PrimitiveValue::U32(_))
+            ));
+        }
+        // only byte values are shared
+        assert!(matches!(
+            from_bytes.element(Tag(0x7FE0, 0x0010)).unwrap().value(),
+            Value::Primitive(PrimitiveValue::SharedBytes(_))
+        ));
 
-        assert_eq!(
-            meta.media_storage_sop_instance_uid
-                .trim_end_matches(|c| c == '\0'),
-            sop_uid.trim_end_matches(|c| c == '\0'),
-        );
+        #[cfg(feature = "mmap")]
+        {
+            // Safety: the file is not modified while the object is alive
+            let from_mmap = unsafe { crate::open_file_mmap(&file_path) }.unwrap();
+            assert_eq!(from_mmap, from_file);
+        }
     }
 
-    /// Write a file from scratch, with exact file meta table.
-    #[test]
-    fn inmem_write_to_file_with_exact_meta() {
-        let sop_uid = "1.4.645.212121";
+    /// Write a file and read it back asynchronously.
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn inmem_read_file_async() {
+        let sop_uid = "1.4.645.212123";
         let mut obj = InMemDicomObject::new_empty();
 
+        obj.put(DataElement::new(
+            Tag(0x0008, 0x0018),
+            VR::UI,
+            dicom_value!(Strs, [sop_uid]),
+        ));
         obj.put(DataElement::new(
             Tag(0x0010, 0x0010),
             VR::PN,
             dicom_value!(Strs, ["Doe^John"]),
         ));
         obj.put(DataElement::new(
-            Tag(0x0008, 0x0060),
-            VR::CS,
-            dicom_value!(Strs, ["CR"]),
-        ));
-        obj.put(DataElement::new(
-            Tag(0x0008, 0x0018),
-            VR::UI,
-            dicom_value!(Strs, [sop_uid]),
+            Tag(0x7FE0, 0x0010),
+            VR::OB,
+            PrimitiveValue::from(vec![1_u8, 2, 3, 4, 5, 6, 7, 8]),
         ));
 
         let file_object = obj.with_exact_meta(
@@ -1232,15 +3067,20 @@ mod tests {
                 .unwrap(),
         );
 
-        // create temporary file path and write object to that file
         let dir = tempfile::tempdir().unwrap();
-        let mut file_path = dir.into_path();
-        file_path.push(format!("{}.dcm", sop_uid));
-
+        let file_path = dir.path().join(format!("{}.dcm", sop_uid));
         file_object.write_to_file(&file_path).unwrap();
 
-        // read the file back to validate the outcome
-        let saved_object = open_file(file_path).unwrap();
+        let saved_object = crate::open_file_async(&file_path).await.unwrap();
+        assert_eq!(file_object, saved_object);
+
+        // also read without the preamble
+        let mut data = Vec::new();
+        file_object.write_all(&mut data).unwrap();
+        let saved_object = crate::OpenFileOptions::new()
+            .from_reader_async(&data[128..])
+            .await
+            .unwrap();
         assert_eq!(file_object, saved_object);
     }
 
@@ -1302,6 +3142,223 @@ mod tests {
         assert_eq!(obj.element_by_name_opt("PatientID").unwrap(), None);
     }
 
+    #[test]
+    fn inmem_object_typed_getters() {
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John "),
+        ));
+        obj.put(DataElement::new(
+            tags::ROWS,
+            VR::US,
+            dicom_value!(U16, [512]),
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_SPACING,
+            VR::DS,
+            dicom_value!(Strs, ["0.25", "0.5"]),
+        ));
+        obj.put(DataElement::new(
+            tags::STUDY_DATE,
+            VR::DA,
+            PrimitiveValue::from("20230101"),
+        ));
+        obj.put(DataElement::new(
+            tags::REFERENCED_IMAGE_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: vec![InMemDicomObject::new_empty()].into(),
+                size: Length::UNDEFINED,
+            },
+        ));
+
+        assert_eq!(obj.get_string(tags::PATIENT_NAME).unwrap(), "Doe^John");
+        assert_eq!(obj.get_u16(tags::ROWS).unwrap(), 512);
+        assert_eq!(obj.get_u32(tags::ROWS).unwrap(), 512);
+        assert_eq!(obj.get_f64s(tags::PIXEL_SPACING).unwrap(), vec![0.25, 0.5]);
+        assert_eq!(obj.get_f64(tags::PIXEL_SPACING).unwrap(), 0.25);
+        assert_eq!(
+            obj.get_date(tags::STUDY_DATE).unwrap(),
+            DicomDate::from_ymd(2023, 1, 1).unwrap()
+        );
+        assert_eq!(
+            obj.get_sequence(tags::REFERENCED_IMAGE_SEQUENCE)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        // missing element
+        assert!(matches!(
+            obj.get_u16(tags::COLUMNS),
+            Err(Error::NoSuchDataElementTag { .. })
+        ));
+        // not convertible
+        assert!(matches!(
+            obj.get_u16(tags::PATIENT_NAME),
+            Err(Error::ConvertValue { .. })
+        ));
+        // not a sequence
+        assert!(matches!(
+            obj.get_sequence(tags::ROWS),
+            Err(Error::NotASequence { .. })
+        ));
+    }
+
+    #[test]
+    fn inmem_object_value_at_path() {
+        let pixel_measures = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            tags::PIXEL_SPACING,
+            VR::DS,
+            dicom_value!(Strs, ["0.25", "0.5"]),
+        )]);
+        let shared_groups = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            tags::PIXEL_MEASURES_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: vec![pixel_measures].into(),
+                size: Length::UNDEFINED,
+            },
+        )]);
+        let obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![shared_groups].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+            DataElement::new(tags::ROWS, VR::US, dicom_value!(U16, [512])),
+        ]);
+
+        let value = obj
+            .value_at("SharedFunctionalGroupsSequence[0].PixelMeasuresSequence[0].PixelSpacing")
+            .unwrap();
+        assert_eq!(value.to_multi_float64().unwrap(), vec![0.25, 0.5]);
+
+        // tags are also accepted
+        let value = obj
+            .value_at("(5200,9229)[0].(0028,9110)[0].(0028,0030)")
+            .unwrap();
+        assert_eq!(value.to_multi_float64().unwrap(), vec![0.25, 0.5]);
+
+        // top level attribute
+        assert_eq!(obj.value_at("Rows").unwrap().to_int::<u16>().unwrap(), 512);
+
+        // out of range item
+        assert!(matches!(
+            obj.value_at("SharedFunctionalGroupsSequence[1].PixelMeasuresSequence[0].PixelSpacing"),
+            Err(Error::NoSuchSequenceItem { index: 1, .. })
+        ));
+        // not a sequence
+        assert!(matches!(
+            obj.value_at("Rows[0].PixelSpacing"),
+            Err(Error::NotASequence { .. })
+        ));
+        // missing attribute
+        assert!(matches!(
+            obj.value_at("SharedFunctionalGroupsSequence[0].PixelSpacing"),
+            Err(Error::NoSuchDataElementAlias { .. })
+        ));
+        // bad syntax
+        assert!(matches!(
+            obj.value_at("SharedFunctionalGroupsSequence.PixelSpacing"),
+            Err(Error::ParsePath { .. })
+        ));
+    }
+
+    #[test]
+    fn inmem_object_put_by_name() {
+        let mut obj = InMemDicomObject::new_empty();
+        assert_eq!(obj.put_str("StudyDate", "20230101").unwrap(), None);
+        obj.put_by_name("Rows", 512_u16).unwrap();
+        obj.put_str("(0010,0010)", "Doe^John").unwrap();
+
+        let study_date = obj.element(tags::STUDY_DATE).unwrap();
+        assert_eq!(study_date.vr(), VR::DA);
+        assert_eq!(study_date.to_str().unwrap(), "20230101");
+        assert_eq!(obj.element_by_name("Rows").unwrap().vr(), VR::US);
+        assert_eq!(obj.get_u16(tags::ROWS).unwrap(), 512);
+        let patient_name = obj.element_by_name("PatientName").unwrap();
+        assert_eq!(patient_name.vr(), VR::PN);
+        assert_eq!(patient_name.to_str().unwrap(), "Doe^John");
+
+        // replace existing value
+        let old = obj.put_str("StudyDate", "20240202").unwrap();
+        assert_eq!(old.unwrap().to_str().unwrap(), "20230101");
+
+        // text is converted to the attribute's value representation
+        obj.put_str("Columns", "256").unwrap();
+        assert_eq!(
+            obj.element(tags::COLUMNS).unwrap().value().primitive(),
+            Some(&PrimitiveValue::from(256_u16)),
+        );
+        obj.put_by_name("SeriesNumber", 7_u16).unwrap();
+        assert_eq!(
+            obj.element(tags::SERIES_NUMBER).unwrap().to_str().unwrap(),
+            "7"
+        );
+        assert!(matches!(
+            obj.put_str("Columns", "abc"),
+            Err(Error::ConvertValue { .. })
+        ));
+
+        // unknown attribute name
+        assert!(matches!(
+            obj.put_str("NoSuchAttribute", "1"),
+            Err(Error::NoSuchAttributeName { .. })
+        ));
+
+        // tag expressions are not attribute names
+        assert!(obj.element_by_name("(0010,0010)").is_err());
+    }
+
+    #[test]
+    fn inmem_object_by_name_with_custom_dictionary() {
+        use dicom_core::dictionary::{DictionaryEntryBuf, TagRange, ValueMultiplicity};
+
+        #[derive(Debug, Clone)]
+        struct CustomDictionary(DictionaryEntryBuf);
+
+        impl DataDictionary for CustomDictionary {
+            type Entry = DictionaryEntryBuf;
+
+            fn by_name(&self, name: &str) -> Option<&Self::Entry> {
+                Some(&self.0).filter(|e| e.alias == name)
+            }
+
+            fn by_tag(&self, tag: Tag) -> Option<&Self::Entry> {
+                Some(&self.0).filter(|e| e.tag.inner() == tag)
+            }
+        }
+
+        let dict = CustomDictionary(DictionaryEntryBuf {
+            tag: TagRange::Single(Tag(0x0009, 0x1001)),
+            alias: "VendorSpecificNote".to_string(),
+            vr: VR::LO,
+            name: "Vendor Specific Note".to_string(),
+            vm: ValueMultiplicity::ONE,
+            retired: false,
+        });
+        let mut obj = InMemDicomObject::new_empty_with_dict(dict);
+        obj.put_str("VendorSpecificNote", "hello").unwrap();
+
+        let elem = obj.element(Tag(0x0009, 0x1001)).unwrap();
+        assert_eq!(elem.vr(), VR::LO);
+        assert_eq!(
+            obj.element_by_name("VendorSpecificNote")
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "hello"
+        );
+        // standard names are not known by this dictionary
+        assert!(obj.put_str("PatientName", "Doe^John").is_err());
+    }
+
     #[test]
     fn inmem_object_take_element() {
         let another_patient_name = DataElement::new(
@@ -1368,6 +3425,167 @@ mod tests {
         assert_eq!(obj.remove_element_by_name("PatientName").unwrap(), false);
     }
 
+    #[test]
+    fn inmem_object_update_from() {
+        let mut obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(tags::MANUFACTURER, VR::LO, PrimitiveValue::from("Old")),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+        ]);
+        let template = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(tags::MANUFACTURER, VR::LO, PrimitiveValue::from("ACME")),
+            DataElement::new(tags::STATION_NAME, VR::SH, PrimitiveValue::from("STATION1")),
+        ]);
+
+        obj.update_from(&template);
+
+        assert_eq!(
+            obj.element(tags::MANUFACTURER).unwrap().to_str().unwrap(),
+            "ACME"
+        );
+        assert_eq!(
+            obj.element(tags::STATION_NAME).unwrap().to_str().unwrap(),
+            "STATION1"
+        );
+        assert_eq!(
+            obj.element(tags::PATIENT_NAME).unwrap().to_str().unwrap(),
+            "Doe^John"
+        );
+    }
+
+    #[test]
+    fn inmem_object_merge_sequences() {
+        fn item(code: &str, meaning: Option<&str>) -> InMemDicomObject {
+            let mut item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+                tags::CODE_VALUE,
+                VR::SH,
+                PrimitiveValue::from(code),
+            )]);
+            if let Some(meaning) = meaning {
+                item.put(DataElement::new(
+                    tags::CODE_MEANING,
+                    VR::LO,
+                    PrimitiveValue::from(meaning),
+                ));
+            }
+            item
+        }
+        fn with_sequence(items: Vec<InMemDicomObject>) -> InMemDicomObject {
+            InMemDicomObject::from_element_iter(vec![DataElement::new(
+                tags::ANATOMIC_REGION_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: items.into(),
+                    size: Length::UNDEFINED,
+                },
+            )])
+        }
+        fn items_of(obj: &InMemDicomObject) -> &[InMemDicomObject] {
+            obj.element(tags::ANATOMIC_REGION_SEQUENCE)
+                .unwrap()
+                .items()
+                .unwrap()
+        }
+
+        let base = with_sequence(vec![item("T-D3000", None)]);
+        let other = with_sequence(vec![item("T-D3000", Some("Chest")), item("T-D4000", None)]);
+
+        // replace
+        let mut obj = base.clone();
+        obj.merge_from(&other, SequenceMerge::Replace);
+        assert_eq!(items_of(&obj), items_of(&other));
+
+        // append
+        let mut obj = base.clone();
+        obj.merge_from(&other, SequenceMerge::Append);
+        let items = items_of(&obj);
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0], item("T-D3000", None));
+        assert_eq!(items[1], item("T-D3000", Some("Chest")));
+        assert_eq!(items[2], item("T-D4000", None));
+
+        // merge
+        let mut obj = with_sequence(vec![item("T-D3000", Some("Thorax"))]);
+        obj.merge_from(
+            &with_sequence(vec![item("T-D3000", None), item("T-D4000", None)]),
+            SequenceMerge::Merge,
+        );
+        let items = items_of(&obj);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0], item("T-D3000", Some("Thorax")));
+        assert_eq!(items[1], item("T-D4000", None));
+    }
+
+    #[test]
+    fn inmem_object_remove_group_lengths() {
+        let item = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(Tag(0x0008, 0x0000), VR::UL, PrimitiveValue::from(10_u32)),
+            DataElement::new(tags::CODE_VALUE, VR::SH, PrimitiveValue::from("T-D3000")),
+        ]);
+        let mut obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(Tag(0x0008, 0x0000), VR::UL, PrimitiveValue::from(30_u32)),
+            DataElement::new(
+                tags::ANATOMIC_REGION_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: vec![item].into(),
+                    size: Length::UNDEFINED,
+                },
+            ),
+            DataElement::new(Tag(0x0010, 0x0000), VR::UL, PrimitiveValue::from(16_u32)),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+        ]);
+
+        obj.remove_group_lengths();
+
+        let tags: Vec<_> = obj.tags().collect();
+        assert_eq!(
+            tags,
+            vec![tags::ANATOMIC_REGION_SEQUENCE, tags::PATIENT_NAME]
+        );
+        let items = obj
+            .element(tags::ANATOMIC_REGION_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        let item_tags: Vec<_> = items[0].tags().collect();
+        assert_eq!(item_tags, vec![tags::CODE_VALUE]);
+    }
+
+    #[test]
+    fn inmem_object_update_group_lengths() {
+        let mut obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(Tag(0x0008, 0x0000), VR::UL, PrimitiveValue::from(1_u32)),
+            DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from("MR")),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from("123456")),
+        ]);
+        let ts = TransferSyntaxRegistry.get("1.2.840.10008.1.2.1").unwrap();
+
+        obj.update_group_lengths_with_ts(&ts).unwrap();
+
+        // (0008,0060) CS: 8 + 2
+        assert_eq!(
+            obj.element(Tag(0x0008, 0x0000))
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            10
+        );
+        // (0010,0010) PN: 8 + 8, (0010,0020) LO: 8 + 6
+        assert_eq!(
+            obj.element(Tag(0x0010, 0x0000))
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            30
+        );
+
+        // the group length elements are written along with the data set
+        let mut out = Vec::new();
+        obj.write_dataset_with_ts(&mut out, &ts).unwrap();
+        assert_eq!(out.len(), 12 + 10 + 12 + 30);
+    }
+
     /// Elements are traversed in tag order.
     #[test]
     fn inmem_traverse_elements() {
@@ -1681,6 +3899,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn inmem_object_from_tokens() {
+        use smallvec::smallvec;
+
+        let item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            Tag(0x0018, 0x6012),
+            VR::US,
+            Value::Primitive(4_u16.into()),
+        )]);
+        let gt_obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                Tag(0x0018, 0x6011),
+                VR::SQ,
+                Value::Sequence {
+                    items: smallvec![item],
+                    size: Length::UNDEFINED,
+                },
+            ),
+            DataElement::new(Tag(0x0020, 0x4000), VR::LT, Value::Primitive("TEST".into())),
+        ]);
+
+        let obj = InMemDicomObject::from_tokens(gt_obj.clone().into_tokens()).unwrap();
+        assert_obj_eq(&obj, &gt_obj);
+
+        // a sequence without its delimiter is rejected
+        let tokens = vec![DataToken::SequenceStart {
+            tag: Tag(0x0018, 0x6011),
+            len: Length::UNDEFINED,
+        }];
+        assert!(InMemDicomObject::from_tokens(tokens).is_err());
+    }
+
     #[test]
     fn inmem_encapsulated_pixel_data_from_tokens() {
         use smallvec::smallvec;
@@ -1744,4 +3994,55 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn inmem_object_builder() {
+        let item = InMemDicomObject::builder()
+            .add_value(tags::REFERENCED_SOP_INSTANCE_UID, "1.2.3.4")
+            .build()
+            .unwrap();
+        let obj = InMemDicomObject::builder()
+            .add(tags::PATIENT_NAME, VR::PN, "Doe^John")
+            .add_value(tags::PATIENT_ID, "12345")
+            .add_by_name("StudyDescription", "Head CT")
+            .add_seq(tags::REFERENCED_STUDY_SEQUENCE, vec![item.clone()])
+            .build()
+            .unwrap();
+
+        let patient_name = obj.element(tags::PATIENT_NAME).unwrap();
+        assert_eq!(patient_name.vr(), VR::PN);
+        assert_eq!(patient_name.to_str().unwrap(), "Doe^John");
+        // VR looked up from the dictionary
+        assert_eq!(obj.element(tags::PATIENT_ID).unwrap().vr(), VR::LO);
+        assert_eq!(obj.element(tags::STUDY_DESCRIPTION).unwrap().vr(), VR::LO);
+
+        let items = obj.get_sequence(tags::REFERENCED_STUDY_SEQUENCE).unwrap();
+        assert_eq!(items, &[item][..]);
+    }
+
+    #[test]
+    fn inmem_object_builder_errors() {
+        // unknown attribute name
+        let result = InMemDicomObject::builder()
+            .add_by_name("NotAnAttribute", "1")
+            .add_value(tags::PATIENT_ID, "12345")
+            .build();
+        assert!(matches!(result, Err(Error::NoSuchAttributeName { .. })));
+
+        // multiple values in a single-valued VR
+        let result = InMemDicomObject::builder()
+            .add(
+                tags::IMAGE_COMMENTS,
+                VR::LT,
+                dicom_value!(Strs, ["one", "two"]),
+            )
+            .build();
+        assert!(matches!(
+            result,
+            Err(Error::InvalidMultiplicity {
+                multiplicity: 2,
+                ..
+            })
+        ));
+    }
 }
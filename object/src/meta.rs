@@ -9,6 +9,7 @@ use dicom_encoding::encode::explicit_le::ExplicitVRLittleEndianEncoder;
 use dicom_encoding::encode::EncoderFor;
 use dicom_encoding::text::{self, TextCodec};
 use dicom_parser::dataset::{DataSetWriter, IntoTokens};
+use dicom_parser::ErrorKind;
 use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
 use std::io::{Read, Write};
 
@@ -99,6 +100,37 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Classify this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::ReadMagicCode { source, .. } | Error::ReadValueData { source, .. } => {
+                ErrorKind::from(source)
+            }
+            Error::DecodeElement { source } => ErrorKind::from(source),
+            Error::DecodeText { .. } => ErrorKind::InvalidValue,
+            Error::NotDicom { .. } => ErrorKind::InvalidHeader,
+            Error::UnexpectedTag { .. }
+            | Error::MissingElement { .. }
+            | Error::UnexpectedDataValueLength { .. }
+            | Error::UndefinedValueLength { .. } => ErrorKind::InvalidStructure,
+            Error::AllocationSize { .. } | Error::WriteSet { .. } => ErrorKind::Other,
+        }
+    }
+
+    /// Retrieve the tag of the data element being read
+    /// when the error occurred,
+    /// if known.
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            Error::UnexpectedTag { tag, .. }
+            | Error::UnexpectedDataValueLength { tag, .. }
+            | Error::UndefinedValueLength { tag, .. } => Some(*tag),
+            _ => None,
+        }
+    }
+}
+
 type Result<T> = std::result::Result<T, Error>;
 
 /// DICOM File Meta Information Table.
@@ -481,6 +513,20 @@ impl FileMetaTable {
         )
         .context(WriteSetSnafu)
     }
+
+    /// Recompute the file meta information group length (0002,0000)
+    /// from the other elements of the table,
+    /// as they would be written by [`write`](FileMetaTable::write).
+    ///
+    /// This should be called after any change to the table's attributes,
+    /// so that the group length stays consistent.
+    pub fn update_information_group_length(&mut self) -> Result<()> {
+        let mut data = Vec::new();
+        self.write(&mut data)?;
+        // discount the group length element itself (tag, VR, length, UL value)
+        self.information_group_length = data.len() as u32 - 12;
+        Ok(())
+    }
 }
 
 /// A builder for DICOM meta information tables.
@@ -528,7 +574,7 @@ where
 }
 
 /// Ensure that the string is even lengthed with trailing '\0's.
-fn ui_padded<T>(s: T) -> String
+pub(crate) fn ui_padded<T>(s: T) -> String
 where
     T: Into<String>,
 {
@@ -837,6 +883,20 @@ mod tests {
         assert_eq!(table, gt);
     }
 
+    #[test]
+    fn update_meta_table_group_length() {
+        let mut source = TEST_META_1;
+
+        let mut table = FileMetaTable::from_reader(&mut source).unwrap();
+        table.information_group_length = 0;
+        table.update_information_group_length().unwrap();
+        assert_eq!(table.information_group_length, 200);
+
+        table.implementation_version_name = None;
+        table.update_information_group_length().unwrap();
+        assert_eq!(table.information_group_length, 200 - 24);
+    }
+
     #[test]
     fn create_meta_table_with_builder() {
         let table = FileMetaTableBuilder::new()
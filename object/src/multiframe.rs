@@ -0,0 +1,1395 @@
+//! Conversion between enhanced multi-frame objects
+//! and legacy single-frame instances.
+//!
+//! Older PACS and viewers often only understand the classic,
+//! single-frame image storage SOP classes.
+//! [`split_multiframe`] breaks an enhanced multi-frame object
+//! into one legacy instance per frame,
+//! moving the attributes in the shared and per-frame functional groups
+//! back to the top level of each instance.
+//! [`enhance`] performs the reverse operation,
+//! producing a _Legacy Converted Enhanced_ multi-frame object
+//! from a series of single-frame instances.
+//! [`FunctionalGroups`] provides a typed view
+//! over the functional groups of an enhanced multi-frame object,
+//! merging the shared and per-frame functional groups
+//! into a [`FrameInfo`] for each frame.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::multiframe::split_multiframe;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("enhanced_ct.dcm")?;
+//! for (i, frame) in split_multiframe(&obj)?.into_iter().enumerate() {
+//!     frame.write_to_file(format!("frame_{:04}.dcm", i + 1))?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Read the geometry of each frame of an enhanced CT:
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::multiframe::FunctionalGroups;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("enhanced_ct.dcm")?;
+//! let groups = FunctionalGroups::new(&obj)?;
+//! for i in 0..groups.number_of_frames() {
+//!     let info = groups.frame_info(i)?;
+//!     println!("frame {}: {:?} {:?}", i + 1, info.image_position, info.pixel_spacing);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use smallvec::smallvec;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use std::convert::TryFrom;
+
+use crate::mem::{InMemElement, InMemFragment};
+use crate::{FileDicomObject, FileMetaTableBuilder, InMemDicomObject};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from the object.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// The SOP class of the object has no known counterpart.
+    #[snafu(display("No known conversion for SOP class `{}`", uid))]
+    UnsupportedSopClass { uid: String, backtrace: Backtrace },
+
+    /// The number of per-frame functional group items
+    /// does not match the number of frames.
+    #[snafu(display(
+        "Expected {} per-frame functional group items, found {}",
+        expected,
+        got
+    ))]
+    FunctionalGroupMismatch {
+        expected: u32,
+        got: usize,
+        backtrace: Backtrace,
+    },
+
+    /// The pixel data could not be divided into frames.
+    #[snafu(display("Could not split pixel data into {} frames", frames))]
+    SplitPixelData { frames: u32, backtrace: Backtrace },
+
+    /// The given instances cannot be combined into a single object.
+    #[snafu(display("Incompatible instance #{}: {}", index, reason))]
+    IncompatibleInstance {
+        index: usize,
+        reason: &'static str,
+        backtrace: Backtrace,
+    },
+
+    /// No instances were given.
+    #[snafu(display("No instances to combine"))]
+    NoInstances { backtrace: Backtrace },
+
+    /// The frame index is not lower than the number of frames.
+    #[snafu(display("Frame #{} is out of range for {} frames", index, frames))]
+    FrameOutOfRange {
+        index: u32,
+        frames: u32,
+        backtrace: Backtrace,
+    },
+
+    /// An attribute has an unexpected number of values.
+    #[snafu(display("Expected {} values in attribute {}, found {}", expected, tag, got))]
+    UnexpectedMultiplicity {
+        tag: Tag,
+        expected: usize,
+        got: usize,
+        backtrace: Backtrace,
+    },
+
+    /// The new file meta group could not be built.
+    #[snafu(display("Could not build file meta group"))]
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: crate::meta::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Pairs of enhanced (or legacy converted enhanced) SOP classes
+/// and their respective legacy single-frame SOP class.
+const SOP_CLASS_PAIRS: &[(&str, &str)] = &[
+    // Enhanced CT Image Storage
+    ("1.2.840.10008.5.1.4.1.1.2.1", "1.2.840.10008.5.1.4.1.1.2"),
+    // Legacy Converted Enhanced CT Image Storage
+    ("1.2.840.10008.5.1.4.1.1.2.2", "1.2.840.10008.5.1.4.1.1.2"),
+    // Enhanced MR Image Storage
+    ("1.2.840.10008.5.1.4.1.1.4.1", "1.2.840.10008.5.1.4.1.1.4"),
+    // Legacy Converted Enhanced MR Image Storage
+    ("1.2.840.10008.5.1.4.1.1.4.4", "1.2.840.10008.5.1.4.1.1.4"),
+    // Enhanced PET Image Storage
+    ("1.2.840.10008.5.1.4.1.1.130", "1.2.840.10008.5.1.4.1.1.128"),
+    // Legacy Converted Enhanced PET Image Storage
+    (
+        "1.2.840.10008.5.1.4.1.1.128.1",
+        "1.2.840.10008.5.1.4.1.1.128",
+    ),
+    // Enhanced XA Image Storage
+    (
+        "1.2.840.10008.5.1.4.1.1.12.1.1",
+        "1.2.840.10008.5.1.4.1.1.12.1",
+    ),
+];
+
+/// Legacy single-frame SOP classes
+/// and their respective Legacy Converted Enhanced SOP class.
+const LEGACY_CONVERTED_PAIRS: &[(&str, &str)] = &[
+    ("1.2.840.10008.5.1.4.1.1.2", "1.2.840.10008.5.1.4.1.1.2.2"),
+    ("1.2.840.10008.5.1.4.1.1.4", "1.2.840.10008.5.1.4.1.1.4.4"),
+    (
+        "1.2.840.10008.5.1.4.1.1.128",
+        "1.2.840.10008.5.1.4.1.1.128.1",
+    ),
+];
+
+/// Functional group macros which are dissolved into the top level
+/// when splitting, and the attributes which they may contain.
+const FUNCTIONAL_GROUPS: &[(Tag, &[Tag])] = &[
+    (
+        tags::PIXEL_MEASURES_SEQUENCE,
+        &[
+            tags::PIXEL_SPACING,
+            tags::SLICE_THICKNESS,
+            tags::SPACING_BETWEEN_SLICES,
+        ],
+    ),
+    (
+        tags::PLANE_POSITION_SEQUENCE,
+        &[tags::IMAGE_POSITION_PATIENT],
+    ),
+    (
+        tags::PLANE_ORIENTATION_SEQUENCE,
+        &[tags::IMAGE_ORIENTATION_PATIENT],
+    ),
+    (
+        tags::FRAME_VOILUT_SEQUENCE,
+        &[
+            tags::WINDOW_CENTER,
+            tags::WINDOW_WIDTH,
+            tags::WINDOW_CENTER_WIDTH_EXPLANATION,
+        ],
+    ),
+    (
+        tags::PIXEL_VALUE_TRANSFORMATION_SEQUENCE,
+        &[
+            tags::RESCALE_INTERCEPT,
+            tags::RESCALE_SLOPE,
+            tags::RESCALE_TYPE,
+        ],
+    ),
+];
+
+/// Functional group sequences whose single item
+/// is dissolved into the top level when splitting,
+/// in addition to those in [`FUNCTIONAL_GROUPS`].
+const DISSOLVED_SEQUENCES: &[Tag] = &[
+    tags::FRAME_CONTENT_SEQUENCE,
+    tags::CT_IMAGE_FRAME_TYPE_SEQUENCE,
+    tags::MR_IMAGE_FRAME_TYPE_SEQUENCE,
+    tags::UNASSIGNED_SHARED_CONVERTED_ATTRIBUTES_SEQUENCE,
+    tags::UNASSIGNED_PER_FRAME_CONVERTED_ATTRIBUTES_SEQUENCE,
+];
+
+/// Attributes renamed when moving from a functional group to the top level.
+const RENAMED: &[(Tag, Tag, VR)] = &[
+    (tags::FRAME_TYPE, tags::IMAGE_TYPE, VR::CS),
+    (
+        tags::FRAME_ACQUISITION_DATE_TIME,
+        tags::ACQUISITION_DATE_TIME,
+        VR::DT,
+    ),
+];
+
+/// Attributes which never take part in the comparison of instances
+/// when combining them into a multi-frame object.
+const INSTANCE_SPECIFIC: &[Tag] = &[
+    tags::SOP_INSTANCE_UID,
+    tags::INSTANCE_NUMBER,
+    tags::PIXEL_DATA,
+];
+
+/// Generate a new unique identifier under the `2.25` root,
+/// as described in the standard, part 5, section B.2.
+pub(crate) fn new_uid() -> String {
+    format!("2.25.{}", uuid::Uuid::new_v4().as_u128())
+}
+
+/// Look up the legacy single-frame SOP class
+/// corresponding to the given enhanced SOP class.
+pub fn legacy_sop_class(enhanced: &str) -> Option<&'static str> {
+    let enhanced = enhanced.trim_end_matches(|c| c == '\0' || c == ' ');
+    SOP_CLASS_PAIRS
+        .iter()
+        .find(|(e, _)| *e == enhanced)
+        .map(|(_, l)| *l)
+}
+
+/// Look up the Legacy Converted Enhanced SOP class
+/// corresponding to the given legacy single-frame SOP class.
+pub fn legacy_converted_sop_class(legacy: &str) -> Option<&'static str> {
+    let legacy = legacy.trim_end_matches(|c| c == '\0' || c == ' ');
+    LEGACY_CONVERTED_PAIRS
+        .iter()
+        .find(|(l, _)| *l == legacy)
+        .map(|(_, e)| *e)
+}
+
+/// The information about a single frame of an enhanced multi-frame object,
+/// taken from its shared and per-frame functional groups.
+///
+/// Attributes which are not present
+/// in any of the functional groups of the frame
+/// are left empty.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct FrameInfo {
+    /// _Image Position (Patient)_, from the _Plane Position Sequence_.
+    pub image_position: Option<[f64; 3]>,
+    /// _Image Orientation (Patient)_, from the _Plane Orientation Sequence_.
+    pub image_orientation: Option<[f64; 6]>,
+    /// _Pixel Spacing_ (row spacing, column spacing),
+    /// from the _Pixel Measures Sequence_.
+    pub pixel_spacing: Option<[f64; 2]>,
+    /// _Slice Thickness_, from the _Pixel Measures Sequence_.
+    pub slice_thickness: Option<f64>,
+    /// _Spacing Between Slices_, from the _Pixel Measures Sequence_.
+    pub spacing_between_slices: Option<f64>,
+    /// The values of _Window Center_,
+    /// from the _Frame VOI LUT Sequence_.
+    pub window_center: Vec<f64>,
+    /// The values of _Window Width_,
+    /// from the _Frame VOI LUT Sequence_.
+    pub window_width: Vec<f64>,
+    /// The values of _Window Center & Width Explanation_,
+    /// from the _Frame VOI LUT Sequence_.
+    pub window_explanation: Vec<String>,
+}
+
+/// A view over the functional groups of an enhanced multi-frame object.
+///
+/// A functional group macro of a frame is looked up
+/// in the respective item of the _Per-Frame Functional Groups Sequence_,
+/// and then in the _Shared Functional Groups Sequence_.
+#[derive(Debug)]
+pub struct FunctionalGroups<'a, D> {
+    shared: Option<&'a InMemDicomObject<D>>,
+    per_frame: &'a [InMemDicomObject<D>],
+    number_of_frames: u32,
+}
+
+impl<'a, D> FunctionalGroups<'a, D>
+where
+    D: DataDictionary + Clone,
+{
+    /// Create a view over the functional groups of the given object.
+    ///
+    /// Fails if the number of per-frame functional group items
+    /// does not match _Number of Frames_.
+    pub fn new(obj: &'a InMemDicomObject<D>) -> Result<Self> {
+        let number_of_frames: u32 = match obj.element_opt(tags::NUMBER_OF_FRAMES) {
+            Ok(Some(e)) => e.to_int().context(InvalidAttributeSnafu {
+                tag: tags::NUMBER_OF_FRAMES,
+            })?,
+            _ => 1,
+        };
+
+        let shared = obj
+            .element_opt(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE)
+            .ok()
+            .flatten()
+            .and_then(|e| e.items())
+            .and_then(|items| items.first());
+        let per_frame = obj
+            .element_opt(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE)
+            .ok()
+            .flatten()
+            .and_then(|e| e.items())
+            .unwrap_or(&[]);
+        ensure!(
+            per_frame.is_empty() || per_frame.len() == number_of_frames as usize,
+            FunctionalGroupMismatchSnafu {
+                expected: number_of_frames,
+                got: per_frame.len(),
+            }
+        );
+
+        Ok(FunctionalGroups {
+            shared,
+            per_frame,
+            number_of_frames,
+        })
+    }
+
+    /// Obtain the number of frames of the object.
+    pub fn number_of_frames(&self) -> u32 {
+        self.number_of_frames
+    }
+
+    /// Obtain the item of the given functional group macro sequence
+    /// (such as _Plane Position Sequence_)
+    /// applicable to the frame at the given index, starting from 0.
+    pub fn group(&self, index: u32, sequence: Tag) -> Option<&'a InMemDicomObject<D>> {
+        let first_item = |groups: &'a InMemDicomObject<D>| {
+            groups
+                .element_opt(sequence)
+                .ok()
+                .flatten()
+                .and_then(|e| e.items())
+                .and_then(|items| items.first())
+        };
+        self.per_frame
+            .get(index as usize)
+            .and_then(first_item)
+            .or_else(|| self.shared.and_then(first_item))
+    }
+
+    /// Obtain an attribute of the given functional group macro sequence
+    /// applicable to the frame at the given index, starting from 0.
+    pub fn element(&self, index: u32, sequence: Tag, tag: Tag) -> Option<&'a InMemElement<D>> {
+        self.group(index, sequence)
+            .and_then(|group| group.element_opt(tag).ok().flatten())
+    }
+
+    /// Obtain the plane position, plane orientation, pixel measures,
+    /// and VOI LUT of the frame at the given index, starting from 0.
+    pub fn frame_info(&self, index: u32) -> Result<FrameInfo> {
+        ensure!(
+            index < self.number_of_frames,
+            FrameOutOfRangeSnafu {
+                index,
+                frames: self.number_of_frames,
+            }
+        );
+
+        let measure = |tag| self.element(index, tags::PIXEL_MEASURES_SEQUENCE, tag);
+        let voi = |tag| self.element(index, tags::FRAME_VOILUT_SEQUENCE, tag);
+
+        Ok(FrameInfo {
+            image_position: self
+                .element(
+                    index,
+                    tags::PLANE_POSITION_SEQUENCE,
+                    tags::IMAGE_POSITION_PATIENT,
+                )
+                .map(floats)
+                .transpose()?,
+            image_orientation: self
+                .element(
+                    index,
+                    tags::PLANE_ORIENTATION_SEQUENCE,
+                    tags::IMAGE_ORIENTATION_PATIENT,
+                )
+                .map(floats)
+                .transpose()?,
+            pixel_spacing: measure(tags::PIXEL_SPACING).map(floats).transpose()?,
+            slice_thickness: measure(tags::SLICE_THICKNESS).map(float).transpose()?,
+            spacing_between_slices: measure(tags::SPACING_BETWEEN_SLICES)
+                .map(float)
+                .transpose()?,
+            window_center: voi(tags::WINDOW_CENTER)
+                .map(multi_float)
+                .transpose()?
+                .unwrap_or_default(),
+            window_width: voi(tags::WINDOW_WIDTH)
+                .map(multi_float)
+                .transpose()?
+                .unwrap_or_default(),
+            window_explanation: voi(tags::WINDOW_CENTER_WIDTH_EXPLANATION)
+                .and_then(|e| e.to_multi_str().ok())
+                .map(|values| values.iter().map(|v| v.trim().to_string()).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Split an enhanced multi-frame object into legacy single-frame instances.
+///
+/// Each output instance contains:
+///
+/// - all top level attributes of the original object,
+///   except for the functional group sequences
+///   and _Number of Frames_;
+/// - the attributes in the shared functional groups,
+///   followed by those in the respective per-frame functional groups,
+///   moved to the top level;
+/// - the pixel data of the respective frame;
+/// - a new _SOP Instance UID_, an _Instance Number_ equal to the frame number,
+///   and a _Source Image Sequence_ referencing the original object and frame.
+///
+/// Study and series identification is kept intact.
+/// Encapsulated pixel data is only supported
+/// if each frame is contained in a single fragment,
+/// or if the basic offset table is present.
+pub fn split_multiframe<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+) -> Result<Vec<FileDicomObject<InMemDicomObject<D>>>>
+where
+    D: DataDictionary + Clone,
+{
+    let sop_class_uid = string_of(obj, tags::SOP_CLASS_UID)?;
+    let sop_instance_uid = string_of(obj, tags::SOP_INSTANCE_UID)?;
+    let legacy_class = legacy_sop_class(&sop_class_uid).context(UnsupportedSopClassSnafu {
+        uid: sop_class_uid.clone(),
+    })?;
+
+    let FunctionalGroups {
+        shared,
+        per_frame,
+        number_of_frames,
+    } = FunctionalGroups::new(obj)?;
+
+    let frames = split_pixel_data(obj, number_of_frames)?;
+
+    // the base object with all attributes common to every frame
+    let mut base = obj.obj.clone();
+    base.remove_element(tags::NUMBER_OF_FRAMES);
+    base.remove_element(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE);
+    base.remove_element(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE);
+    base.remove_element(tags::PIXEL_DATA);
+    if let Some(shared) = shared {
+        dissolve_functional_groups(&mut base, shared);
+    }
+    base.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        PrimitiveValue::from(legacy_class),
+    ));
+
+    let mut out = Vec::with_capacity(number_of_frames as usize);
+    for (i, pixel_data) in frames.into_iter().enumerate() {
+        let frame_number = i as u32 + 1;
+        let mut instance = base.clone();
+        if let Some(groups) = per_frame.get(i) {
+            dissolve_functional_groups(&mut instance, groups);
+        }
+
+        let uid = new_uid();
+        instance.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(uid.as_str()),
+        ));
+        instance.put(DataElement::new(
+            tags::INSTANCE_NUMBER,
+            VR::IS,
+            PrimitiveValue::from(frame_number.to_string()),
+        ));
+
+        // reference the original object and frame
+        let mut source = InMemDicomObject::new_empty_with_dict(obj.obj.dict().clone());
+        source.put(DataElement::new(
+            tags::REFERENCED_SOP_CLASS_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_class_uid.as_str()),
+        ));
+        source.put(DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(sop_instance_uid.as_str()),
+        ));
+        source.put(DataElement::new(
+            tags::REFERENCED_FRAME_NUMBER,
+            VR::IS,
+            PrimitiveValue::from(frame_number.to_string()),
+        ));
+        instance.put(sequence_element(
+            tags::SOURCE_IMAGE_SEQUENCE,
+            smallvec![source],
+        ));
+        instance.put(pixel_data);
+
+        let meta = derive_meta(&obj.meta, legacy_class, &uid)?;
+        out.push(FileDicomObject {
+            meta,
+            obj: instance,
+        });
+    }
+
+    Ok(out)
+}
+
+/// Combine a series of legacy single-frame instances
+/// into a single _Legacy Converted Enhanced_ multi-frame object.
+///
+/// The instances must share the same SOP class, transfer syntax,
+/// and pixel data description,
+/// and are placed in the output in the order given.
+///
+/// - Attributes with the same value in all instances
+///   remain at the top level of the new object,
+///   or are placed in the shared functional groups
+///   if they belong to a known functional group macro.
+/// - Attributes with different values are placed
+///   in the per-frame functional groups,
+///   either in their respective functional group macro
+///   or in the _Unassigned Per-Frame Converted Attributes Sequence_.
+/// - Each per-frame item contains a _Conversion Source Attributes Sequence_
+///   referencing the original instance.
+///
+/// The new object is given a new _SOP Instance UID_.
+pub fn enhance<D>(
+    instances: &[FileDicomObject<InMemDicomObject<D>>],
+) -> Result<FileDicomObject<InMemDicomObject<D>>>
+where
+    D: DataDictionary + Clone,
+{
+    let first = instances.first().context(NoInstancesSnafu)?;
+    let legacy_class = string_of(first, tags::SOP_CLASS_UID)?;
+    let enhanced_class =
+        legacy_converted_sop_class(&legacy_class).context(UnsupportedSopClassSnafu {
+            uid: legacy_class.clone(),
+        })?;
+
+    for (index, instance) in instances.iter().enumerate() {
+        ensure!(
+            instance.meta.transfer_syntax == first.meta.transfer_syntax,
+            IncompatibleInstanceSnafu {
+                index,
+                reason: "transfer syntax differs",
+            }
+        );
+        ensure!(
+            string_of(instance, tags::SOP_CLASS_UID)? == legacy_class,
+            IncompatibleInstanceSnafu {
+                index,
+                reason: "SOP class differs",
+            }
+        );
+        for tag in [
+            tags::ROWS,
+            tags::COLUMNS,
+            tags::BITS_ALLOCATED,
+            tags::SAMPLES_PER_PIXEL,
+        ] {
+            ensure!(
+                value_of(instance, tag) == value_of(first, tag),
+                IncompatibleInstanceSnafu {
+                    index,
+                    reason: "pixel data description differs",
+                }
+            );
+        }
+    }
+
+    let dict = first.obj.dict().clone();
+    let is_shared = |tag: Tag| {
+        let value = value_of(first, tag);
+        instances.iter().all(|o| value_of(o, tag) == value)
+    };
+
+    let mut base = InMemDicomObject::new_empty_with_dict(dict.clone());
+    let mut shared = InMemDicomObject::new_empty_with_dict(dict.clone());
+    let mut per_frame: Vec<InMemDicomObject<D>> = instances
+        .iter()
+        .map(|_| InMemDicomObject::new_empty_with_dict(dict.clone()))
+        .collect();
+
+    // functional group macros
+    let mut grouped: Vec<Tag> = Vec::new();
+    for (group_tag, attributes) in FUNCTIONAL_GROUPS {
+        let present: Vec<Tag> = attributes
+            .iter()
+            .copied()
+            .filter(|&tag| instances.iter().any(|o| o.element(tag).is_ok()))
+            .collect();
+        if present.is_empty() {
+            continue;
+        }
+        grouped.extend(present.iter().copied());
+
+        if present.iter().all(|&tag| is_shared(tag)) {
+            let mut item = InMemDicomObject::new_empty_with_dict(dict.clone());
+            item.extend(
+                present
+                    .iter()
+                    .filter_map(|&tag| first.element(tag).ok().cloned()),
+            );
+            shared.put(sequence_element(*group_tag, smallvec![item]));
+        } else {
+            for (instance, groups) in instances.iter().zip(&mut per_frame) {
+                let mut item = InMemDicomObject::new_empty_with_dict(dict.clone());
+                item.extend(
+                    present
+                        .iter()
+                        .filter_map(|&tag| instance.element(tag).ok().cloned()),
+                );
+                groups.put(sequence_element(*group_tag, smallvec![item]));
+            }
+        }
+    }
+
+    // all other attributes
+    let mut all_tags: Vec<Tag> = instances.iter().flat_map(|o| o.tags()).collect();
+    all_tags.sort();
+    all_tags.dedup();
+    let mut unassigned_tags = Vec::new();
+    for tag in all_tags {
+        if grouped.contains(&tag) || INSTANCE_SPECIFIC.contains(&tag) {
+            continue;
+        }
+        if is_shared(tag) {
+            if let Ok(e) = first.element(tag) {
+                base.put(e.clone());
+            }
+        } else {
+            unassigned_tags.push(tag);
+        }
+    }
+
+    let mut fragments: C<InMemFragment> = C::new();
+    let mut native: Vec<u8> = Vec::new();
+    let mut pixel_vr = VR::OB;
+    for (i, (instance, groups)) in instances.iter().zip(&mut per_frame).enumerate() {
+        // frame content
+        let mut content = InMemDicomObject::new_empty_with_dict(dict.clone());
+        if let Ok(e) = instance.element(tags::ACQUISITION_DATE_TIME) {
+            content.put(DataElement::new(
+                tags::FRAME_ACQUISITION_DATE_TIME,
+                VR::DT,
+                e.value().clone(),
+            ));
+        }
+        groups.put(sequence_element(
+            tags::FRAME_CONTENT_SEQUENCE,
+            smallvec![content],
+        ));
+
+        // unassigned per-frame attributes
+        if !unassigned_tags.is_empty() {
+            let mut item = InMemDicomObject::new_empty_with_dict(dict.clone());
+            item.extend(
+                unassigned_tags
+                    .iter()
+                    .filter_map(|&tag| instance.element(tag).ok().cloned()),
+            );
+            groups.put(sequence_element(
+                tags::UNASSIGNED_PER_FRAME_CONVERTED_ATTRIBUTES_SEQUENCE,
+                smallvec![item],
+            ));
+        }
+
+        // reference to the source instance
+        let mut source = InMemDicomObject::new_empty_with_dict(dict.clone());
+        source.put(DataElement::new(
+            tags::REFERENCED_SOP_CLASS_UID,
+            VR::UI,
+            PrimitiveValue::from(legacy_class.as_str()),
+        ));
+        source.put(DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from(string_of(instance, tags::SOP_INSTANCE_UID)?),
+        ));
+        groups.put(sequence_element(
+            tags::CONVERSION_SOURCE_ATTRIBUTES_SEQUENCE,
+            smallvec![source],
+        ));
+
+        // pixel data
+        let pixel_data =
+            instance
+                .element(tags::PIXEL_DATA)
+                .ok()
+                .context(MissingAttributeSnafu {
+                    tag: tags::PIXEL_DATA,
+                })?;
+        match pixel_data.value() {
+            Value::Primitive(v) => {
+                ensure!(
+                    fragments.is_empty(),
+                    IncompatibleInstanceSnafu {
+                        index: i,
+                        reason: "pixel data encoding differs",
+                    }
+                );
+                pixel_vr = pixel_data.vr();
+                native.extend_from_slice(&v.to_bytes());
+            }
+            Value::PixelSequence {
+                fragments: frame_fragments,
+                ..
+            } => {
+                ensure!(
+                    native.is_empty(),
+                    IncompatibleInstanceSnafu {
+                        index: i,
+                        reason: "pixel data encoding differs",
+                    }
+                );
+                // one fragment per frame
+                fragments.push(frame_fragments.iter().flatten().copied().collect());
+            }
+            Value::Sequence { .. } => {
+                return IncompatibleInstanceSnafu {
+                    index: i,
+                    reason: "pixel data is not a valid value",
+                }
+                .fail();
+            }
+        }
+    }
+
+    let uid = new_uid();
+    base.put(DataElement::new(
+        tags::SOP_CLASS_UID,
+        VR::UI,
+        PrimitiveValue::from(enhanced_class),
+    ));
+    base.put(DataElement::new(
+        tags::SOP_INSTANCE_UID,
+        VR::UI,
+        PrimitiveValue::from(uid.as_str()),
+    ));
+    if let Ok(e) = first.element(tags::INSTANCE_NUMBER) {
+        base.put(e.clone());
+    }
+    base.put(DataElement::new(
+        tags::NUMBER_OF_FRAMES,
+        VR::IS,
+        PrimitiveValue::from(instances.len().to_string()),
+    ));
+    base.put(sequence_element(
+        tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE,
+        smallvec![shared],
+    ));
+    base.put(sequence_element(
+        tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+        per_frame.into_iter().collect(),
+    ));
+    if fragments.is_empty() {
+        base.put(DataElement::new(
+            tags::PIXEL_DATA,
+            pixel_vr,
+            PrimitiveValue::from(native),
+        ));
+    } else {
+        base.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            Value::PixelSequence {
+                offset_table: C::new(),
+                fragments,
+            },
+        ));
+    }
+
+    let meta = derive_meta(&first.meta, enhanced_class, &uid)?;
+    Ok(FileDicomObject { meta, obj: base })
+}
+
+/// Move the contents of the functional groups in the given item
+/// to the top level of the object.
+fn dissolve_functional_groups<D>(obj: &mut InMemDicomObject<D>, groups: &InMemDicomObject<D>)
+where
+    D: DataDictionary + Clone,
+{
+    for group in groups {
+        let tag = group.tag();
+        let dissolve =
+            DISSOLVED_SEQUENCES.contains(&tag) || FUNCTIONAL_GROUPS.iter().any(|(t, _)| *t == tag);
+        match group.items() {
+            Some(items) if dissolve => {
+                for elem in items.iter().flat_map(|item| item.iter()) {
+                    obj.put(renamed(elem.clone()));
+                }
+            }
+            _ => {
+                obj.put(group.clone());
+            }
+        }
+    }
+}
+
+/// Rename an attribute which only exists in functional groups
+/// to its legacy counterpart.
+fn renamed<D>(elem: InMemElement<D>) -> InMemElement<D> {
+    match RENAMED.iter().find(|(from, _, _)| *from == elem.tag()) {
+        Some((_, to, vr)) => DataElement::new(*to, *vr, elem.into_value()),
+        None => elem,
+    }
+}
+
+/// Divide the pixel data of the object into one data element per frame.
+fn split_pixel_data<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    number_of_frames: u32,
+) -> Result<Vec<InMemElement<D>>>
+where
+    D: DataDictionary + Clone,
+{
+    let pixel_data = obj
+        .element(tags::PIXEL_DATA)
+        .ok()
+        .context(MissingAttributeSnafu {
+            tag: tags::PIXEL_DATA,
+        })?;
+    let frames = number_of_frames as usize;
+    ensure!(
+        frames > 0,
+        SplitPixelDataSnafu {
+            frames: number_of_frames
+        }
+    );
+
+    match pixel_data.value() {
+        Value::Primitive(value) => {
+            let rows: usize = int_of(obj, tags::ROWS)?;
+            let columns: usize = int_of(obj, tags::COLUMNS)?;
+            let bits_allocated: usize = int_of(obj, tags::BITS_ALLOCATED)?;
+            let samples_per_pixel: usize = obj
+                .element(tags::SAMPLES_PER_PIXEL)
+                .ok()
+                .and_then(|e| e.to_int().ok())
+                .unwrap_or(1);
+            let frame_len = rows * columns * samples_per_pixel * (bits_allocated / 8);
+            // bit-packed frames cannot be split on byte boundaries
+            ensure!(
+                bits_allocated % 8 == 0 && frame_len > 0,
+                SplitPixelDataSnafu {
+                    frames: number_of_frames
+                }
+            );
+
+            let out: Option<Vec<PrimitiveValue>> = match value {
+                PrimitiveValue::U8(data) if data.len() >= frame_len * frames => Some(
+                    data.chunks(frame_len)
+                        .take(frames)
+                        .map(|c| PrimitiveValue::U8(c.into()))
+                        .collect(),
+                ),
+                PrimitiveValue::SharedBytes(data) if data.len() >= frame_len * frames => Some(
+                    (0..frames)
+                        .map(|i| {
+                            PrimitiveValue::SharedBytes(
+                                data.slice(i * frame_len..(i + 1) * frame_len),
+                            )
+                        })
+                        .collect(),
+                ),
+                PrimitiveValue::U16(data) if data.len() * 2 >= frame_len * frames => Some(
+                    data.chunks(frame_len / 2)
+                        .take(frames)
+                        .map(|c| PrimitiveValue::U16(c.into()))
+                        .collect(),
+                ),
+                _ => None,
+            };
+            let out = out.context(SplitPixelDataSnafu {
+                frames: number_of_frames,
+            })?;
+            Ok(out
+                .into_iter()
+                .map(|v| DataElement::new(tags::PIXEL_DATA, pixel_data.vr(), v))
+                .collect())
+        }
+        Value::PixelSequence {
+            offset_table,
+            fragments,
+        } => {
+            let frame_fragments: Vec<C<InMemFragment>> = if fragments.len() == frames {
+                fragments.iter().map(|f| smallvec![f.clone()]).collect()
+            } else if offset_table.len() == frames {
+                // group fragments by the offsets in the basic offset table
+                let mut out: Vec<C<InMemFragment>> = vec![C::new(); frames];
+                let mut position = 0_u32;
+                for fragment in fragments {
+                    let frame = offset_table
+                        .iter()
+                        .rposition(|&offset| offset <= position)
+                        .unwrap_or(0);
+                    out[frame].push(fragment.clone());
+                    // fragment item header + fragment data
+                    position += 8 + fragment.len() as u32;
+                }
+                out
+            } else {
+                return SplitPixelDataSnafu {
+                    frames: number_of_frames,
+                }
+                .fail();
+            };
+
+            Ok(frame_fragments
+                .into_iter()
+                .map(|fragments| {
+                    DataElement::new(
+                        tags::PIXEL_DATA,
+                        VR::OB,
+                        Value::PixelSequence {
+                            offset_table: C::new(),
+                            fragments,
+                        },
+                    )
+                })
+                .collect())
+        }
+        Value::Sequence { .. } => SplitPixelDataSnafu {
+            frames: number_of_frames,
+        }
+        .fail(),
+    }
+}
+
+/// Obtain a fixed number of values of the given attribute as floats.
+fn floats<D, const N: usize>(elem: &InMemElement<D>) -> Result<[f64; N]> {
+    let values = multi_float(elem)?;
+    let got = values.len();
+    <[f64; N]>::try_from(values).map_err(|_| {
+        UnexpectedMultiplicitySnafu {
+            tag: elem.tag(),
+            expected: N,
+            got,
+        }
+        .build()
+    })
+}
+
+/// Obtain all values of the given attribute as floats.
+fn multi_float<D>(elem: &InMemElement<D>) -> Result<Vec<f64>> {
+    elem.to_multi_float64()
+        .context(InvalidAttributeSnafu { tag: elem.tag() })
+}
+
+/// Obtain the value of the given attribute as a float.
+fn float<D>(elem: &InMemElement<D>) -> Result<f64> {
+    elem.to_float64()
+        .context(InvalidAttributeSnafu { tag: elem.tag() })
+}
+
+/// Create a file meta group for a derived object.
+fn derive_meta(
+    meta: &crate::FileMetaTable,
+    sop_class_uid: &str,
+    sop_instance_uid: &str,
+) -> Result<crate::FileMetaTable> {
+    let mut builder = FileMetaTableBuilder::new()
+        .transfer_syntax(meta.transfer_syntax.trim_end_matches('\0'))
+        .media_storage_sop_class_uid(sop_class_uid)
+        .media_storage_sop_instance_uid(sop_instance_uid);
+    if let Some(aet) = &meta.source_application_entity_title {
+        builder = builder.source_application_entity_title(aet.trim_end());
+    }
+    builder.build().context(BuildMetaSnafu)
+}
+
+/// Create a sequence element with the given items.
+fn sequence_element<D>(tag: Tag, items: C<InMemDicomObject<D>>) -> InMemElement<D> {
+    DataElement::new(
+        tag,
+        VR::SQ,
+        Value::Sequence {
+            items,
+            size: Length::UNDEFINED,
+        },
+    )
+}
+
+/// Obtain the value of the given attribute, if present.
+fn value_of<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    tag: Tag,
+) -> Option<&Value<InMemDicomObject<D>, InMemFragment>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element(tag).ok().map(|e| e.value())
+}
+
+/// Obtain the value of the given attribute as a trimmed string.
+fn string_of<D>(obj: &FileDicomObject<InMemDicomObject<D>>, tag: Tag) -> Result<String>
+where
+    D: DataDictionary + Clone,
+{
+    let elem = obj
+        .element(tag)
+        .ok()
+        .context(MissingAttributeSnafu { tag })?;
+    let value = elem
+        .to_str()
+        .map_err(|_| MissingAttributeSnafu { tag }.build())?;
+    Ok(value
+        .trim_end_matches(|c| c == '\0' || c == ' ')
+        .to_string())
+}
+
+/// Obtain the value of the given attribute as an integer.
+fn int_of<D>(obj: &FileDicomObject<InMemDicomObject<D>>, tag: Tag) -> Result<usize>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element(tag)
+        .ok()
+        .context(MissingAttributeSnafu { tag })?
+        .to_int()
+        .context(InvalidAttributeSnafu { tag })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dicom_value;
+
+    fn enhanced_ct() -> FileDicomObject<InMemDicomObject> {
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::SOP_CLASS_UID,
+            VR::UI,
+            PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.2.1"),
+        ));
+        obj.put(DataElement::new(
+            tags::SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from("2.25.1234"),
+        ));
+        obj.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^John"),
+        ));
+        obj.put(DataElement::new(
+            tags::NUMBER_OF_FRAMES,
+            VR::IS,
+            PrimitiveValue::from("2"),
+        ));
+        obj.put(DataElement::new(tags::ROWS, VR::US, dicom_value!(U16, [2])));
+        obj.put(DataElement::new(
+            tags::COLUMNS,
+            VR::US,
+            dicom_value!(U16, [2]),
+        ));
+        obj.put(DataElement::new(
+            tags::BITS_ALLOCATED,
+            VR::US,
+            dicom_value!(U16, [8]),
+        ));
+        obj.put(DataElement::new(
+            tags::SAMPLES_PER_PIXEL,
+            VR::US,
+            dicom_value!(U16, [1]),
+        ));
+
+        let mut measures = InMemDicomObject::new_empty();
+        measures.put(DataElement::new(
+            tags::PIXEL_SPACING,
+            VR::DS,
+            dicom_value!(Strs, ["0.5", "0.5"]),
+        ));
+        let mut shared = InMemDicomObject::new_empty();
+        shared.put(sequence_element(
+            tags::PIXEL_MEASURES_SEQUENCE,
+            smallvec![measures],
+        ));
+        obj.put(sequence_element(
+            tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE,
+            smallvec![shared],
+        ));
+
+        let per_frame: C<_> = ["0", "1"]
+            .iter()
+            .map(|z| {
+                let mut plane = InMemDicomObject::new_empty();
+                plane.put(DataElement::new(
+                    tags::IMAGE_POSITION_PATIENT,
+                    VR::DS,
+                    dicom_value!(Strs, ["0", "0", *z]),
+                ));
+                let mut item = InMemDicomObject::new_empty();
+                item.put(sequence_element(
+                    tags::PLANE_POSITION_SEQUENCE,
+                    smallvec![plane],
+                ));
+                item
+            })
+            .collect();
+        obj.put(sequence_element(
+            tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+            per_frame,
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            dicom_value!(U8, [1, 2, 3, 4, 5, 6, 7, 8]),
+        ));
+
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.2.1")
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn split_enhanced_ct_into_legacy_frames() {
+        let obj = enhanced_ct();
+        let frames = split_multiframe(&obj).unwrap();
+        assert_eq!(frames.len(), 2);
+
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(
+                frame
+                    .meta()
+                    .media_storage_sop_class_uid
+                    .trim_end_matches('\0'),
+                "1.2.840.10008.5.1.4.1.1.2"
+            );
+            assert_eq!(
+                frame
+                    .element(tags::SOP_CLASS_UID)
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+                "1.2.840.10008.5.1.4.1.1.2"
+            );
+            assert!(frame.element(tags::NUMBER_OF_FRAMES).is_err());
+            assert!(frame
+                .element(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE)
+                .is_err());
+            assert_eq!(
+                frame.element(tags::PATIENT_NAME).unwrap().to_str().unwrap(),
+                "Doe^John"
+            );
+            assert_eq!(
+                frame
+                    .element(tags::PIXEL_SPACING)
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+                "0.5\\0.5"
+            );
+            assert_eq!(
+                frame
+                    .element(tags::INSTANCE_NUMBER)
+                    .unwrap()
+                    .to_int::<u32>()
+                    .unwrap(),
+                i as u32 + 1
+            );
+            let source = &frame
+                .element(tags::SOURCE_IMAGE_SEQUENCE)
+                .unwrap()
+                .items()
+                .unwrap()[0];
+            assert_eq!(
+                source
+                    .element(tags::REFERENCED_SOP_INSTANCE_UID)
+                    .unwrap()
+                    .to_str()
+                    .unwrap(),
+                "2.25.1234"
+            );
+        }
+
+        assert_eq!(
+            frames[1]
+                .element(tags::IMAGE_POSITION_PATIENT)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "0\\0\\1"
+        );
+        assert_eq!(
+            &*frames[1]
+                .element(tags::PIXEL_DATA)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            &[5, 6, 7, 8]
+        );
+        assert_ne!(
+            frames[0]
+                .element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            frames[1]
+                .element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+        );
+    }
+
+    #[test]
+    fn merges_shared_and_per_frame_groups() {
+        let obj = enhanced_ct();
+        let groups = FunctionalGroups::new(&obj).unwrap();
+        assert_eq!(groups.number_of_frames(), 2);
+
+        let info = groups.frame_info(1).unwrap();
+        assert_eq!(info.image_position, Some([0., 0., 1.]));
+        assert_eq!(info.pixel_spacing, Some([0.5, 0.5]));
+        assert_eq!(info.image_orientation, None);
+        assert_eq!(info.slice_thickness, None);
+        assert!(info.window_center.is_empty());
+        assert_eq!(
+            groups.frame_info(0).unwrap().image_position,
+            Some([0., 0., 0.])
+        );
+
+        assert!(matches!(
+            groups.frame_info(2),
+            Err(Error::FrameOutOfRange {
+                index: 2,
+                frames: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn frame_info_checks_multiplicity() {
+        let mut obj = enhanced_ct();
+        let mut voi = InMemDicomObject::new_empty();
+        voi.put(DataElement::new(
+            tags::WINDOW_CENTER,
+            VR::DS,
+            dicom_value!(Strs, ["40", "400"]),
+        ));
+        voi.put(DataElement::new(
+            tags::WINDOW_WIDTH,
+            VR::DS,
+            dicom_value!(Strs, ["400", "2000"]),
+        ));
+        let mut plane = InMemDicomObject::new_empty();
+        plane.put(DataElement::new(
+            tags::IMAGE_ORIENTATION_PATIENT,
+            VR::DS,
+            dicom_value!(Strs, ["1", "0", "0", "0", "1"]),
+        ));
+        let mut shared = InMemDicomObject::new_empty();
+        shared.put(sequence_element(
+            tags::FRAME_VOILUT_SEQUENCE,
+            smallvec![voi],
+        ));
+        shared.put(sequence_element(
+            tags::PLANE_ORIENTATION_SEQUENCE,
+            smallvec![plane],
+        ));
+        obj.put(sequence_element(
+            tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE,
+            smallvec![shared],
+        ));
+
+        let groups = FunctionalGroups::new(&obj).unwrap();
+        assert_eq!(
+            groups
+                .element(0, tags::FRAME_VOILUT_SEQUENCE, tags::WINDOW_WIDTH)
+                .unwrap()
+                .to_multi_float64()
+                .unwrap(),
+            vec![400., 2000.]
+        );
+        assert!(matches!(
+            groups.frame_info(0),
+            Err(Error::UnexpectedMultiplicity {
+                expected: 6,
+                got: 5,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn split_and_enhance_roundtrip() {
+        let obj = enhanced_ct();
+        let frames = split_multiframe(&obj).unwrap();
+        let enhanced = enhance(&frames).unwrap();
+
+        assert_eq!(
+            enhanced
+                .element(tags::SOP_CLASS_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "1.2.840.10008.5.1.4.1.1.2.2"
+        );
+        assert_eq!(
+            enhanced
+                .element(tags::NUMBER_OF_FRAMES)
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            &*enhanced
+                .element(tags::PIXEL_DATA)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            &[1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        // pixel spacing is common to all frames
+        let shared = &enhanced
+            .element(tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap()[0];
+        assert!(shared.element(tags::PIXEL_MEASURES_SEQUENCE).is_ok());
+        assert!(enhanced.element(tags::PIXEL_SPACING).is_err());
+
+        // image position differs per frame
+        let per_frame = enhanced
+            .element(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(per_frame.len(), 2);
+        let position = &per_frame[1]
+            .element(tags::PLANE_POSITION_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap()[0];
+        assert_eq!(
+            position
+                .element(tags::IMAGE_POSITION_PATIENT)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "0\\0\\1"
+        );
+        let source = &per_frame[0]
+            .element(tags::CONVERSION_SOURCE_ATTRIBUTES_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap()[0];
+        assert_eq!(
+            source
+                .element(tags::REFERENCED_SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            frames[0]
+                .element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+        );
+    }
+}
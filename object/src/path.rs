@@ -0,0 +1,210 @@
+//! Paths for accessing elements deep within nested sequences.
+//!
+//! A [`DicomPath`] is written as a dot-separated list of attribute selectors,
+//! where every selector except the last one
+//! refers to a sequence and names one of its items
+//! by a zero-based index in square brackets.
+//! Attributes may be given by keyword
+//! or by tag in the form `(gggg,eeee)`.
+//!
+//! ```
+//! # use dicom_object::path::DicomPath;
+//! let path: DicomPath =
+//!     "SharedFunctionalGroupsSequence[0].PixelMeasuresSequence[0].PixelSpacing".parse()?;
+//! assert_eq!(path.steps().len(), 2);
+//! assert_eq!(path.attribute(), "PixelSpacing");
+//! # Ok::<(), dicom_object::path::ParseError>(())
+//! ```
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+use std::fmt;
+use std::str::FromStr;
+
+/// An error returned when parsing an invalid DICOM path.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum ParseError {
+    #[snafu(display("Empty attribute selector at position {}", position))]
+    EmptySelector {
+        position: usize,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Sequence selector `{}` is missing an item index", selector))]
+    MissingItemIndex {
+        selector: String,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Invalid item index in selector `{}`", selector))]
+    InvalidItemIndex {
+        selector: String,
+        backtrace: Backtrace,
+        source: std::num::ParseIntError,
+    },
+    #[snafu(display("Malformed item index in selector `{}`", selector))]
+    MalformedItemIndex {
+        selector: String,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Unexpected item index in last selector `{}`", selector))]
+    UnexpectedItemIndex {
+        selector: String,
+        backtrace: Backtrace,
+    },
+}
+
+/// A step into a specific item of a sequence.
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct SequenceStep {
+    /// the keyword or tag of the sequence attribute
+    pub attribute: String,
+    /// the zero-based index of the item in the sequence
+    pub item: u32,
+}
+
+/// A path to a data element, possibly nested in sequences.
+///
+/// See the [module-level documentation](self) for the syntax.
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+pub struct DicomPath {
+    steps: Vec<SequenceStep>,
+    attribute: String,
+}
+
+impl DicomPath {
+    /// Create a path from its sequence steps and the target attribute.
+    pub fn new(steps: Vec<SequenceStep>, attribute: impl Into<String>) -> Self {
+        DicomPath {
+            steps,
+            attribute: attribute.into(),
+        }
+    }
+
+    /// Parse a path from its textual form.
+    pub fn parse(path: &str) -> Result<Self, ParseError> {
+        let mut steps = Vec::new();
+        let mut selectors = path.split('.').enumerate().peekable();
+        while let Some((position, selector)) = selectors.next() {
+            ensure!(!selector.is_empty(), EmptySelectorSnafu { position });
+            let (attribute, item) = split_selector(selector)?;
+            ensure!(!attribute.is_empty(), EmptySelectorSnafu { position });
+            if selectors.peek().is_none() {
+                ensure!(item.is_none(), UnexpectedItemIndexSnafu { selector });
+                return Ok(DicomPath {
+                    steps,
+                    attribute: attribute.to_string(),
+                });
+            }
+            let item = item.context(MissingItemIndexSnafu { selector })?;
+            steps.push(SequenceStep {
+                attribute: attribute.to_string(),
+                item,
+            });
+        }
+        unreachable!("splitting a string always yields at least one selector")
+    }
+
+    /// Obtain the sequence steps leading to the target attribute.
+    pub fn steps(&self) -> &[SequenceStep] {
+        &self.steps
+    }
+
+    /// Obtain the keyword or tag of the target attribute.
+    pub fn attribute(&self) -> &str {
+        &self.attribute
+    }
+}
+
+/// Split a selector into its attribute and optional item index.
+fn split_selector(selector: &str) -> Result<(&str, Option<u32>), ParseError> {
+    match selector.find('[') {
+        None => Ok((selector, None)),
+        Some(start) => {
+            ensure!(
+                selector.ends_with(']'),
+                MalformedItemIndexSnafu { selector }
+            );
+            let index = selector[start + 1..selector.len() - 1]
+                .parse()
+                .context(InvalidItemIndexSnafu { selector })?;
+            Ok((&selector[..start], Some(index)))
+        }
+    }
+}
+
+impl FromStr for DicomPath {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DicomPath::parse(s)
+    }
+}
+
+impl fmt::Display for DicomPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            write!(f, "{}[{}].", step.attribute, step.item)?;
+        }
+        f.write_str(&self.attribute)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DicomPath, ParseError, SequenceStep};
+
+    #[test]
+    fn parse_and_display_paths() {
+        let path: DicomPath = "ReferencedSeriesSequence[1].(0008,1140)[0].ReferencedSOPInstanceUID"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            path.steps(),
+            &[
+                SequenceStep {
+                    attribute: "ReferencedSeriesSequence".to_string(),
+                    item: 1,
+                },
+                SequenceStep {
+                    attribute: "(0008,1140)".to_string(),
+                    item: 0,
+                },
+            ][..],
+        );
+        assert_eq!(path.attribute(), "ReferencedSOPInstanceUID");
+        assert_eq!(
+            path.to_string(),
+            "ReferencedSeriesSequence[1].(0008,1140)[0].ReferencedSOPInstanceUID"
+        );
+
+        let path: DicomPath = "PatientName".parse().unwrap();
+        assert!(path.steps().is_empty());
+        assert_eq!(path.attribute(), "PatientName");
+    }
+
+    #[test]
+    fn parse_invalid_paths() {
+        assert!(matches!(
+            DicomPath::parse(""),
+            Err(ParseError::EmptySelector { position: 0, .. })
+        ));
+        assert!(matches!(
+            DicomPath::parse("ReferencedSeriesSequence.PatientName"),
+            Err(ParseError::MissingItemIndex { .. })
+        ));
+        assert!(matches!(
+            DicomPath::parse("ReferencedSeriesSequence[x].PatientName"),
+            Err(ParseError::InvalidItemIndex { .. })
+        ));
+        assert!(matches!(
+            DicomPath::parse("ReferencedSeriesSequence[0"),
+            Err(ParseError::MalformedItemIndex { .. })
+        ));
+        assert!(matches!(
+            DicomPath::parse("ReferencedSeriesSequence[0]"),
+            Err(ParseError::UnexpectedItemIndex { .. })
+        ));
+        assert!(matches!(
+            DicomPath::parse("ReferencedSeriesSequence[0]..PatientName"),
+            Err(ParseError::EmptySelector { position: 1, .. })
+        ));
+    }
+}
@@ -0,0 +1,366 @@
+//! Reinterpretation of elements of unknown value representation.
+//!
+//! Elements read with the value representation UN
+//! keep their value as raw bytes.
+//! This happens when reading implicit VR data sets
+//! with attributes missing from the data dictionary,
+//! or explicit VR data sets written by an application
+//! which did not know the attribute.
+//! The [`Reinterpret`] trait turns such an element
+//! into one of a concrete value representation
+//! by decoding its bytes again,
+//! which is useful once the missing dictionary entries become available.
+//!
+//! Reinterpreting an element as a sequence (SQ)
+//! decodes its bytes as a sequence of items in implicit VR little endian,
+//! as prescribed by CP-246 for sequences encoded as UN.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_core::{DataElement, PrimitiveValue, Tag, VR};
+//! # use dicom_object::mem::InMemElement;
+//! use dicom_encoding::transfer_syntax::Endianness;
+//! use dicom_object::reinterpret::Reinterpret;
+//!
+//! let elem: InMemElement = DataElement::new(
+//!     Tag(0x0009, 0x1001),
+//!     VR::UN,
+//!     PrimitiveValue::from(vec![0x00, 0x02]),
+//! );
+//! let elem = elem.reinterpret_as(VR::US, Endianness::Little)?;
+//! assert_eq!(elem.vr(), VR::US);
+//! assert_eq!(elem.to_int::<u16>()?, 512);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{DataElement, DataElementHeader, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::Endianness;
+use dicom_parser::stateful::decode::{StatefulDecode, StatefulDecoder};
+use dicom_transfer_syntax_registry::entries;
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+use std::convert::TryFrom;
+
+use crate::mem::{InMemDicomObject, InMemElement};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// The element's value is not a sequence of raw bytes.
+    #[snafu(display("Element {} of VR {} does not hold raw bytes", tag, vr))]
+    NotRawBytes {
+        tag: Tag,
+        vr: VR,
+        backtrace: Backtrace,
+    },
+    /// The element's value is too long to be decoded.
+    #[snafu(display("Element {} is too long to be reinterpreted", tag))]
+    ValueTooLong { tag: Tag, backtrace: Backtrace },
+    #[snafu(display("Could not create decoder"))]
+    CreateDecoder {
+        #[snafu(backtrace)]
+        source: dicom_parser::stateful::decode::Error,
+    },
+    #[snafu(display("Could not decode value of element {} as {}", tag, vr))]
+    DecodeValue {
+        tag: Tag,
+        vr: VR,
+        #[snafu(backtrace)]
+        source: dicom_parser::stateful::decode::Error,
+    },
+    #[snafu(display("Could not decode items of element {}", tag))]
+    DecodeItems {
+        tag: Tag,
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The tag of the placeholder sequence
+/// used to decode the items of a sequence encoded as UN.
+const PLACEHOLDER_SEQUENCE: Tag = tags::REFERENCED_SERIES_SEQUENCE;
+
+/// Extension trait for reinterpreting the raw bytes of an element
+/// under a concrete value representation.
+pub trait Reinterpret: Sized {
+    /// Decode the element's raw bytes
+    /// as a value of the given value representation and byte order,
+    /// returning a new element with the same tag.
+    ///
+    /// Textual values are decoded with the default character set.
+    /// See [`reinterpret_as_with_charset`](Reinterpret::reinterpret_as_with_charset)
+    /// to use another one.
+    /// An empty value is reinterpreted as an empty value
+    /// of the given value representation.
+    /// The value must otherwise be a sequence of bytes,
+    /// as obtained when reading elements with the VR UN or OB.
+    fn reinterpret_as(&self, vr: VR, endianness: Endianness) -> Result<Self> {
+        self.reinterpret_as_with_charset(vr, endianness, SpecificCharacterSet::Default)
+    }
+
+    /// Decode the element's raw bytes
+    /// as a value of the given value representation and byte order,
+    /// decoding text with the given character set.
+    fn reinterpret_as_with_charset(
+        &self,
+        vr: VR,
+        endianness: Endianness,
+        charset: SpecificCharacterSet,
+    ) -> Result<Self>;
+}
+
+impl<D> Reinterpret for InMemElement<D>
+where
+    D: DataDictionary + Clone + Default,
+{
+    fn reinterpret_as_with_charset(
+        &self,
+        vr: VR,
+        endianness: Endianness,
+        charset: SpecificCharacterSet,
+    ) -> Result<Self> {
+        reinterpret(self, vr, endianness, charset, D::default())
+    }
+}
+
+/// Reinterpret all elements of the object with the value representation UN
+/// which are known to the object's data dictionary,
+/// including those in nested data sets.
+///
+/// Text is decoded with the character set declared
+/// by the _Specific Character Set_ attribute in the object,
+/// or the default character set if there is none.
+/// Elements not in the dictionary are left untouched.
+pub fn reinterpret_unknown<D>(obj: &mut InMemDicomObject<D>, endianness: Endianness) -> Result<()>
+where
+    D: DataDictionary + Clone,
+{
+    reinterpret_unknown_impl(obj, endianness, SpecificCharacterSet::Default)
+}
+
+fn reinterpret_unknown_impl<D>(
+    obj: &mut InMemDicomObject<D>,
+    endianness: Endianness,
+    charset: SpecificCharacterSet,
+) -> Result<()>
+where
+    D: DataDictionary + Clone,
+{
+    let charset = obj
+        .get_string(tags::SPECIFIC_CHARACTER_SET)
+        .ok()
+        .and_then(|code| SpecificCharacterSet::from_code(code.trim()))
+        .unwrap_or(charset);
+
+    let tags: Vec<Tag> = obj.tags().collect();
+    for tag in tags {
+        let elem = obj.element(tag).expect("element should exist");
+        match elem.vr() {
+            VR::UN => {
+                let vr = obj.dict().by_tag(tag).map(|entry| entry.vr());
+                if let Some(vr) = vr.filter(|vr| *vr != VR::UN) {
+                    let elem = reinterpret(elem, vr, endianness, charset, obj.dict().clone())?;
+                    obj.put(elem);
+                }
+            }
+            VR::SQ => {
+                let elem = obj.take_element(tag).expect("element should exist");
+                let len = elem.header().len;
+                match elem.into_value() {
+                    Value::Sequence { mut items, size } => {
+                        for item in &mut items {
+                            reinterpret_unknown_impl(item, endianness, charset)?;
+                        }
+                        obj.put(DataElement::new_with_len(
+                            tag,
+                            VR::SQ,
+                            len,
+                            Value::Sequence { items, size },
+                        ));
+                    }
+                    value => {
+                        obj.put(DataElement::new_with_len(tag, VR::SQ, len, value));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn reinterpret<D>(
+    elem: &InMemElement<D>,
+    vr: VR,
+    endianness: Endianness,
+    charset: SpecificCharacterSet,
+    dict: D,
+) -> Result<InMemElement<D>>
+where
+    D: DataDictionary + Clone,
+{
+    let tag = elem.tag();
+    let bytes = match elem.value() {
+        Value::Primitive(PrimitiveValue::Empty) if vr == VR::SQ => {
+            return Ok(DataElement::new_with_len(
+                tag,
+                VR::SQ,
+                Length(0),
+                Value::Sequence {
+                    items: Default::default(),
+                    size: Length(0),
+                },
+            ));
+        }
+        Value::Primitive(PrimitiveValue::Empty) => return Ok(DataElement::empty(tag, vr)),
+        Value::Primitive(PrimitiveValue::U8(bytes)) => &bytes[..],
+        Value::Primitive(PrimitiveValue::SharedBytes(bytes)) => &bytes[..],
+        _ => return NotRawBytesSnafu { tag, vr: elem.vr() }.fail(),
+    };
+    let len = u32::try_from(bytes.len())
+        .ok()
+        .filter(|len| *len < u32::MAX - 8)
+        .context(ValueTooLongSnafu { tag })?;
+
+    if vr == VR::SQ {
+        // place the items in a sequence of known VR and decode it
+        let mut data = Vec::with_capacity(bytes.len() + 8);
+        data.extend_from_slice(&PLACEHOLDER_SEQUENCE.0.to_le_bytes());
+        data.extend_from_slice(&PLACEHOLDER_SEQUENCE.1.to_le_bytes());
+        data.extend_from_slice(&len.to_le_bytes());
+        data.extend_from_slice(bytes);
+        let mut obj = InMemDicomObject::read_dataset_with_dict_ts_cs(
+            &data[..],
+            dict,
+            &entries::IMPLICIT_VR_LITTLE_ENDIAN.erased(),
+            charset,
+        )
+        .context(DecodeItemsSnafu { tag })?;
+        let items = obj
+            .take_element(PLACEHOLDER_SEQUENCE)
+            .ok()
+            .and_then(|elem| match elem.into_value() {
+                Value::Sequence { items, .. } => Some(items),
+                _ => None,
+            })
+            .unwrap_or_default();
+        return Ok(DataElement::new_with_len(
+            tag,
+            VR::SQ,
+            Length(len),
+            Value::Sequence {
+                items,
+                size: Length(len),
+            },
+        ));
+    }
+
+    let ts = match endianness {
+        Endianness::Little => entries::EXPLICIT_VR_LITTLE_ENDIAN.erased(),
+        Endianness::Big => entries::EXPLICIT_VR_BIG_ENDIAN.erased(),
+    };
+    let mut decoder =
+        StatefulDecoder::new_with(&bytes[..], &ts, charset, 0).context(CreateDecoderSnafu)?;
+    let header = DataElementHeader::new(tag, vr, Length(len));
+    let value = decoder
+        .read_value(&header)
+        .context(DecodeValueSnafu { tag, vr })?;
+    Ok(DataElement::new_with_len(tag, vr, Length(len), value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_dictionary_std::StandardDataDictionary;
+
+    #[test]
+    fn reinterpret_primitive_values() {
+        let elem: InMemElement = DataElement::new(
+            Tag(0x0009, 0x1001),
+            VR::UN,
+            PrimitiveValue::from(vec![0x01, 0x00, 0x00, 0x02]),
+        );
+
+        let little = elem.reinterpret_as(VR::US, Endianness::Little).unwrap();
+        assert_eq!(little.header().len, Length(4));
+        assert_eq!(little.to_multi_int::<u16>().unwrap(), vec![1, 512]);
+
+        let big = elem.reinterpret_as(VR::US, Endianness::Big).unwrap();
+        assert_eq!(big.to_multi_int::<u16>().unwrap(), vec![256, 2]);
+
+        let text: InMemElement = DataElement::new(
+            Tag(0x0009, 0x1002),
+            VR::UN,
+            PrimitiveValue::from(&b"ONE\\TWO "[..]),
+        );
+        let text = text.reinterpret_as(VR::CS, Endianness::Little).unwrap();
+        assert_eq!(text.to_str().unwrap(), "ONE\\TWO");
+
+        let seq: InMemElement = DataElement::new(
+            Tag(0x0008, 0x1115),
+            VR::SQ,
+            Value::Sequence {
+                items: Default::default(),
+                size: Length(0),
+            },
+        );
+        assert!(matches!(
+            seq.reinterpret_as(VR::US, Endianness::Little),
+            Err(Error::NotRawBytes { .. })
+        ));
+    }
+
+    #[test]
+    fn reinterpret_sequence_in_unknown() {
+        #[rustfmt::skip]
+        let data = vec![
+            // item, undefined length
+            0xFE, 0xFF, 0x00, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF,
+            // (0008,1150) ReferencedSOPClassUID
+            0x08, 0x00, 0x50, 0x11, 0x04, 0x00, 0x00, 0x00,
+            b'1', b'.', b'2', 0x00,
+            // item delimiter
+            0xFE, 0xFF, 0x0D, 0xE0, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::REFERENCED_IMAGE_SEQUENCE,
+            VR::UN,
+            PrimitiveValue::from(data),
+        ));
+        obj.put(DataElement::new(
+            tags::ROWS,
+            VR::UN,
+            PrimitiveValue::from(vec![0x00, 0x01]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x0009, 0x1001),
+            VR::UN,
+            PrimitiveValue::from(vec![0x00, 0x01]),
+        ));
+
+        reinterpret_unknown(&mut obj, Endianness::Little).unwrap();
+
+        assert_eq!(obj.element(tags::ROWS).unwrap().vr(), VR::US);
+        assert_eq!(
+            obj.element(tags::ROWS).unwrap().to_int::<u16>().unwrap(),
+            256
+        );
+        assert_eq!(obj.element(Tag(0x0009, 0x1001)).unwrap().vr(), VR::UN);
+
+        let items = obj.get_sequence(tags::REFERENCED_IMAGE_SEQUENCE).unwrap();
+        assert_eq!(items.len(), 1);
+        let item: &InMemDicomObject<StandardDataDictionary> = &items[0];
+        assert_eq!(
+            item.get_string(tags::REFERENCED_SOP_CLASS_UID).unwrap(),
+            "1.2"
+        );
+    }
+}
@@ -0,0 +1,505 @@
+//! Interpretation of RT Structure Set (RTSTRUCT) objects.
+//!
+//! An RT structure set describes regions of interest (ROIs),
+//! such as organs at risk and target volumes,
+//! as planar contours over the slices of an image series.
+//! The description of each ROI is spread over three sequences:
+//! the _Structure Set ROI Sequence_ (number, name, frame of reference),
+//! the _ROI Contour Sequence_ (display color and contours),
+//! and the _RT ROI Observations Sequence_ (interpreted type).
+//! [`structure_set`] joins them by ROI number into a list of [`Roi`],
+//! with the contour points of each slice
+//! as triplets of patient coordinates in millimeters.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::rtstruct::structure_set;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("rtstruct.dcm")?;
+//! let set = structure_set(&obj)?;
+//! for roi in &set.rois {
+//!     println!("#{} {} ({:?})", roi.number, roi.name, roi.color);
+//!     for contour in &roi.contours {
+//!         println!("  {} points at z = {:?}", contour.points.len(), contour.z());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::Tag;
+use dicom_dictionary_std::tags;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::mem::InMemElement;
+use crate::InMemDicomObject;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from the structure set.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// The contour data of a contour is not a sequence of point triplets.
+    #[snafu(display(
+        "Contour data of ROI #{} has {} values, which is not a multiple of 3",
+        roi,
+        len
+    ))]
+    InvalidContourData {
+        roi: i32,
+        len: usize,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The geometric type of a contour.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ContourGeometricType {
+    /// `POINT`: a single point.
+    Point,
+    /// `OPEN_PLANAR`: an open polyline in a plane.
+    OpenPlanar,
+    /// `OPEN_NONPLANAR`: an open polyline not contained in a plane.
+    OpenNonplanar,
+    /// `CLOSED_PLANAR`: a closed polygon in a plane,
+    /// where the last point connects back to the first one.
+    ClosedPlanar,
+    /// `CLOSED_PLANAR_XOR`: a closed planar polygon
+    /// combined with the other contours of the ROI
+    /// in the same plane by exclusive disjunction.
+    ClosedPlanarXor,
+    /// Any other geometric type.
+    Other(String),
+}
+
+impl ContourGeometricType {
+    /// Obtain the geometric type from its defined term.
+    pub fn from_value(value: &str) -> Self {
+        match value {
+            "POINT" => ContourGeometricType::Point,
+            "OPEN_PLANAR" => ContourGeometricType::OpenPlanar,
+            "OPEN_NONPLANAR" => ContourGeometricType::OpenNonplanar,
+            "CLOSED_PLANAR" => ContourGeometricType::ClosedPlanar,
+            "CLOSED_PLANAR_XOR" => ContourGeometricType::ClosedPlanarXor,
+            other => ContourGeometricType::Other(other.to_string()),
+        }
+    }
+
+    /// Obtain the defined term of this geometric type.
+    pub fn as_str(&self) -> &str {
+        match self {
+            ContourGeometricType::Point => "POINT",
+            ContourGeometricType::OpenPlanar => "OPEN_PLANAR",
+            ContourGeometricType::OpenNonplanar => "OPEN_NONPLANAR",
+            ContourGeometricType::ClosedPlanar => "CLOSED_PLANAR",
+            ContourGeometricType::ClosedPlanarXor => "CLOSED_PLANAR_XOR",
+            ContourGeometricType::Other(value) => value,
+        }
+    }
+}
+
+/// A single contour of a region of interest,
+/// usually lying on one slice of the referenced image series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Contour {
+    /// The geometric type of the contour.
+    pub geometric_type: ContourGeometricType,
+    /// The points of the contour,
+    /// as (x, y, z) coordinates in the patient based coordinate system,
+    /// in millimeters.
+    pub points: Vec<[f64; 3]>,
+    /// The SOP instance UIDs of the images
+    /// on which the contour was defined,
+    /// from the _Contour Image Sequence_.
+    pub referenced_images: Vec<String>,
+}
+
+impl Contour {
+    /// Obtain the z coordinate of the first point of the contour,
+    /// which identifies the slice of an axial planar contour.
+    pub fn z(&self) -> Option<f64> {
+        self.points.first().map(|p| p[2])
+    }
+}
+
+/// A region of interest of a structure set,
+/// joined from its items in the structure set sequences.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Roi {
+    /// The ROI number, unique within the structure set.
+    pub number: i32,
+    /// The ROI name.
+    pub name: String,
+    /// The ROI description, if present.
+    pub description: Option<String>,
+    /// The UID of the frame of reference in which the ROI is defined.
+    pub frame_of_reference_uid: String,
+    /// The type of algorithm used to generate the ROI
+    /// (`AUTOMATIC`, `SEMIAUTOMATIC`, or `MANUAL`), if present.
+    pub generation_algorithm: Option<String>,
+    /// The RGB display color of the ROI, if present.
+    pub color: Option<[u8; 3]>,
+    /// The RT ROI interpreted type,
+    /// such as `ORGAN`, `PTV`, or `EXTERNAL`, if present.
+    pub interpreted_type: Option<String>,
+    /// The contours of the ROI.
+    pub contours: Vec<Contour>,
+}
+
+/// The contents of an RT structure set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructureSet {
+    /// The structure set label.
+    pub label: String,
+    /// The structure set name, if present.
+    pub name: Option<String>,
+    /// The UIDs of the frames of reference
+    /// in which the ROIs are defined.
+    pub frames_of_reference: Vec<String>,
+    /// The regions of interest, in the order of the
+    /// _Structure Set ROI Sequence_.
+    pub rois: Vec<Roi>,
+}
+
+impl StructureSet {
+    /// Look up a region of interest by its name,
+    /// ignoring case and surrounding whitespace.
+    pub fn roi_by_name(&self, name: &str) -> Option<&Roi> {
+        let name = name.trim();
+        self.rois
+            .iter()
+            .find(|roi| roi.name.trim().eq_ignore_ascii_case(name))
+    }
+
+    /// Look up a region of interest by its number.
+    pub fn roi(&self, number: i32) -> Option<&Roi> {
+        self.rois.iter().find(|roi| roi.number == number)
+    }
+}
+
+/// Read the regions of interest of an RT structure set object.
+///
+/// ROIs without an item in the _ROI Contour Sequence_ have no contours,
+/// and those without an item in the _RT ROI Observations Sequence_
+/// have no interpreted type.
+pub fn structure_set<D>(obj: &InMemDicomObject<D>) -> Result<StructureSet>
+where
+    D: DataDictionary + Clone,
+{
+    let roi_contours = items(obj, tags::ROI_CONTOUR_SEQUENCE);
+    let observations = items(obj, tags::RTROI_OBSERVATIONS_SEQUENCE);
+
+    let mut rois = Vec::new();
+    for item in items(obj, tags::STRUCTURE_SET_ROI_SEQUENCE) {
+        let number = int(item, tags::ROI_NUMBER)?;
+        let referenced_by = |item: &&InMemDicomObject<D>| matches!(int(item, tags::REFERENCED_ROI_NUMBER), Ok(n) if n == number);
+
+        let mut color = None;
+        let mut contours = Vec::new();
+        if let Some(roi_contour) = roi_contours.iter().find(referenced_by) {
+            color = match element(roi_contour, tags::ROI_DISPLAY_COLOR) {
+                Some(e) => {
+                    let values: Vec<u8> = e.to_multi_int().context(InvalidAttributeSnafu {
+                        tag: tags::ROI_DISPLAY_COLOR,
+                    })?;
+                    match values[..] {
+                        [r, g, b] => Some([r, g, b]),
+                        _ => None,
+                    }
+                }
+                None => None,
+            };
+            for contour in items(roi_contour, tags::CONTOUR_SEQUENCE) {
+                contours.push(read_contour(contour, number)?);
+            }
+        }
+
+        rois.push(Roi {
+            number,
+            name: opt_string(item, tags::ROI_NAME).unwrap_or_default(),
+            description: opt_string(item, tags::ROI_DESCRIPTION),
+            frame_of_reference_uid: opt_string(item, tags::REFERENCED_FRAME_OF_REFERENCE_UID)
+                .unwrap_or_default(),
+            generation_algorithm: opt_string(item, tags::ROI_GENERATION_ALGORITHM),
+            color,
+            interpreted_type: observations
+                .iter()
+                .find(referenced_by)
+                .and_then(|observation| opt_string(observation, tags::RTROI_INTERPRETED_TYPE)),
+            contours,
+        });
+    }
+
+    Ok(StructureSet {
+        label: opt_string(obj, tags::STRUCTURE_SET_LABEL).unwrap_or_default(),
+        name: opt_string(obj, tags::STRUCTURE_SET_NAME),
+        frames_of_reference: items(obj, tags::REFERENCED_FRAME_OF_REFERENCE_SEQUENCE)
+            .iter()
+            .filter_map(|item| opt_string(item, tags::FRAME_OF_REFERENCE_UID))
+            .collect(),
+        rois,
+    })
+}
+
+fn read_contour<D>(obj: &InMemDicomObject<D>, roi: i32) -> Result<Contour>
+where
+    D: DataDictionary + Clone,
+{
+    let geometric_type =
+        opt_string(obj, tags::CONTOUR_GEOMETRIC_TYPE).context(MissingAttributeSnafu {
+            tag: tags::CONTOUR_GEOMETRIC_TYPE,
+        })?;
+    let data = match element(obj, tags::CONTOUR_DATA) {
+        Some(e) => e.to_multi_float64().context(InvalidAttributeSnafu {
+            tag: tags::CONTOUR_DATA,
+        })?,
+        None => Vec::new(),
+    };
+    ensure!(
+        data.len() % 3 == 0,
+        InvalidContourDataSnafu {
+            roi,
+            len: data.len(),
+        }
+    );
+
+    Ok(Contour {
+        geometric_type: ContourGeometricType::from_value(&geometric_type),
+        points: data.chunks_exact(3).map(|p| [p[0], p[1], p[2]]).collect(),
+        referenced_images: items(obj, tags::CONTOUR_IMAGE_SEQUENCE)
+            .iter()
+            .filter_map(|item| opt_string(item, tags::REFERENCED_SOP_INSTANCE_UID))
+            .collect(),
+    })
+}
+
+fn items<D>(obj: &InMemDicomObject<D>, tag: Tag) -> &[InMemDicomObject<D>]
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag).and_then(|e| e.items()).unwrap_or(&[])
+}
+
+fn element<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<&InMemElement<D>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag).ok().flatten()
+}
+
+fn opt_string<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<String>
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag)
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.into_owned())
+}
+
+fn int<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Result<i32>
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag)
+        .context(MissingAttributeSnafu { tag })?
+        .to_int()
+        .context(InvalidAttributeSnafu { tag })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dicom_value;
+    use dicom_core::value::{PrimitiveValue, Value, C};
+    use dicom_core::{DataElement, Length, VR};
+
+    fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> InMemElement {
+        DataElement::new(
+            tag,
+            VR::SQ,
+            Value::Sequence {
+                items: C::from_vec(items),
+                size: Length::UNDEFINED,
+            },
+        )
+    }
+
+    fn item(elements: Vec<InMemElement>) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter(elements)
+    }
+
+    fn is(tag: Tag, value: &str) -> InMemElement {
+        DataElement::new(tag, VR::IS, PrimitiveValue::from(value))
+    }
+
+    fn text(tag: Tag, vr: VR, value: &str) -> InMemElement {
+        DataElement::new(tag, vr, PrimitiveValue::from(value))
+    }
+
+    fn contour(data: &[&str], image: &str) -> InMemDicomObject {
+        item(vec![
+            sequence(
+                tags::CONTOUR_IMAGE_SEQUENCE,
+                vec![item(vec![text(
+                    tags::REFERENCED_SOP_INSTANCE_UID,
+                    VR::UI,
+                    image,
+                )])],
+            ),
+            text(tags::CONTOUR_GEOMETRIC_TYPE, VR::CS, "CLOSED_PLANAR"),
+            is(
+                tags::NUMBER_OF_CONTOUR_POINTS,
+                &(data.len() / 3).to_string(),
+            ),
+            DataElement::new(
+                tags::CONTOUR_DATA,
+                VR::DS,
+                PrimitiveValue::Strs(data.iter().map(|s| s.to_string()).collect()),
+            ),
+        ])
+    }
+
+    fn rtstruct() -> InMemDicomObject {
+        item(vec![
+            text(tags::STRUCTURE_SET_LABEL, VR::SH, "Plan1 "),
+            sequence(
+                tags::REFERENCED_FRAME_OF_REFERENCE_SEQUENCE,
+                vec![item(vec![text(
+                    tags::FRAME_OF_REFERENCE_UID,
+                    VR::UI,
+                    "1.2.3.9",
+                )])],
+            ),
+            sequence(
+                tags::STRUCTURE_SET_ROI_SEQUENCE,
+                vec![
+                    item(vec![
+                        is(tags::ROI_NUMBER, "1"),
+                        text(tags::REFERENCED_FRAME_OF_REFERENCE_UID, VR::UI, "1.2.3.9"),
+                        text(tags::ROI_NAME, VR::LO, "External"),
+                        text(tags::ROI_GENERATION_ALGORITHM, VR::CS, "AUTOMATIC"),
+                    ]),
+                    item(vec![
+                        is(tags::ROI_NUMBER, "2"),
+                        text(tags::REFERENCED_FRAME_OF_REFERENCE_UID, VR::UI, "1.2.3.9"),
+                        text(tags::ROI_NAME, VR::LO, "PTV"),
+                    ]),
+                ],
+            ),
+            sequence(
+                tags::ROI_CONTOUR_SEQUENCE,
+                vec![
+                    // listed out of order on purpose
+                    item(vec![
+                        DataElement::new(
+                            tags::ROI_DISPLAY_COLOR,
+                            VR::IS,
+                            dicom_value!(Strs, ["255", "0", "0"]),
+                        ),
+                        sequence(
+                            tags::CONTOUR_SEQUENCE,
+                            vec![
+                                contour(
+                                    &["0", "0", "-10", "5", "0", "-10", "5", "5", "-10"],
+                                    "1.2.3.4.1",
+                                ),
+                                contour(
+                                    &["0", "0", "-7.5", "5", "0", "-7.5", "5", "5", "-7.5"],
+                                    "1.2.3.4.2",
+                                ),
+                            ],
+                        ),
+                        is(tags::REFERENCED_ROI_NUMBER, "2"),
+                    ]),
+                    item(vec![
+                        DataElement::new(
+                            tags::ROI_DISPLAY_COLOR,
+                            VR::IS,
+                            dicom_value!(Strs, ["0", "255", "0"]),
+                        ),
+                        sequence(tags::CONTOUR_SEQUENCE, vec![]),
+                        is(tags::REFERENCED_ROI_NUMBER, "1"),
+                    ]),
+                ],
+            ),
+            sequence(
+                tags::RTROI_OBSERVATIONS_SEQUENCE,
+                vec![
+                    item(vec![
+                        is(tags::OBSERVATION_NUMBER, "1"),
+                        is(tags::REFERENCED_ROI_NUMBER, "1"),
+                        text(tags::RTROI_INTERPRETED_TYPE, VR::CS, "EXTERNAL"),
+                    ]),
+                    item(vec![
+                        is(tags::OBSERVATION_NUMBER, "2"),
+                        is(tags::REFERENCED_ROI_NUMBER, "2"),
+                        text(tags::RTROI_INTERPRETED_TYPE, VR::CS, "PTV"),
+                    ]),
+                ],
+            ),
+        ])
+    }
+
+    #[test]
+    fn reads_structure_set() {
+        let set = structure_set(&rtstruct()).unwrap();
+        assert_eq!(set.label, "Plan1");
+        assert_eq!(set.frames_of_reference, vec!["1.2.3.9".to_string()]);
+        assert_eq!(set.rois.len(), 2);
+
+        let external = set.roi_by_name("external").unwrap();
+        assert_eq!(external.number, 1);
+        assert_eq!(external.color, Some([0, 255, 0]));
+        assert_eq!(external.interpreted_type.as_deref(), Some("EXTERNAL"));
+        assert_eq!(external.generation_algorithm.as_deref(), Some("AUTOMATIC"));
+        assert!(external.contours.is_empty());
+
+        let ptv = set.roi(2).unwrap();
+        assert_eq!(ptv.name, "PTV");
+        assert_eq!(ptv.frame_of_reference_uid, "1.2.3.9");
+        assert_eq!(ptv.color, Some([255, 0, 0]));
+        assert_eq!(ptv.contours.len(), 2);
+        let contour = &ptv.contours[1];
+        assert_eq!(contour.geometric_type, ContourGeometricType::ClosedPlanar);
+        assert_eq!(
+            contour.points,
+            vec![[0., 0., -7.5], [5., 0., -7.5], [5., 5., -7.5]]
+        );
+        assert_eq!(contour.z(), Some(-7.5));
+        assert_eq!(contour.referenced_images, vec!["1.2.3.4.2".to_string()]);
+    }
+
+    #[test]
+    fn rejects_incomplete_contour_data() {
+        let mut obj = rtstruct();
+        obj.put(sequence(
+            tags::ROI_CONTOUR_SEQUENCE,
+            vec![item(vec![
+                sequence(
+                    tags::CONTOUR_SEQUENCE,
+                    vec![contour(&["0", "0", "-10", "5"], "1.2.3.4.1")],
+                ),
+                is(tags::REFERENCED_ROI_NUMBER, "2"),
+            ])],
+        ));
+        assert!(matches!(
+            structure_set(&obj),
+            Err(Error::InvalidContourData { roi: 2, len: 4, .. })
+        ));
+    }
+}
@@ -0,0 +1,450 @@
+//! Scanning of directory trees for DICOM files.
+//!
+//! A [`Scanner`] walks a directory tree,
+//! identifies which files contain DICOM data,
+//! and reads a selection of attributes from each of them
+//! into an [`IndexRecord`].
+//! This is the basis for indexing the contents of an archive
+//! without keeping whole objects in memory.
+//!
+//! DICOM files are identified by their content rather than their name
+//! (see [`sniff`]):
+//! standard DICOM files are recognized by the `DICM` magic code,
+//! with or without the 128-byte preamble,
+//! and data sets without a file meta group
+//! are recognized by their first data element header.
+//!
+//! With the `rayon` feature enabled,
+//! files are read in parallel.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_dictionary_std::tags;
+//! use dicom_object::scan::Scanner;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let scanner = Scanner::new().with_tag(tags::STUDY_DESCRIPTION);
+//! for record in scanner.scan("/data/archive") {
+//!     let record = record?;
+//!     println!(
+//!         "{}: {:?}",
+//!         record.path.display(),
+//!         record.elements.get_string(tags::SOP_INSTANCE_UID).ok()
+//!     );
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use dicom_core::header::Header;
+use dicom_core::{Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_transfer_syntax_registry::entries;
+use snafu::{Backtrace, IntoError, ResultExt, Snafu};
+
+use crate::file::ReadPreamble;
+use crate::mem::InMemDicomObject;
+use crate::meta::FileMetaTable;
+use crate::OpenFileOptions;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A directory could not be scanned for files.
+    #[snafu(display("Could not read directory '{}'", path.display()))]
+    ReadDirectory {
+        path: PathBuf,
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+
+    /// The header of a file could not be read.
+    #[snafu(display("Could not read file '{}'", path.display()))]
+    ReadFile {
+        path: PathBuf,
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+
+    /// A DICOM file could not be parsed.
+    #[snafu(display("Could not parse DICOM file '{}'", path.display()))]
+    ParseFile {
+        path: PathBuf,
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The number of bytes inspected to identify a DICOM file.
+const SNIFF_LENGTH: usize = 132;
+
+/// The kind of DICOM content found in a file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FileKind {
+    /// A standard DICOM file,
+    /// with preamble, magic code, and file meta group.
+    Part10,
+    /// A DICOM file with magic code and file meta group,
+    /// but without the preamble.
+    Part10NoPreamble,
+    /// A bare data set in explicit VR little endian,
+    /// without a file meta group.
+    ExplicitVrDataSet,
+    /// A bare data set in implicit VR little endian,
+    /// without a file meta group.
+    ImplicitVrDataSet,
+}
+
+/// Identify the kind of DICOM content
+/// from the first bytes of a file.
+///
+/// At least 132 bytes should be given
+/// for standard DICOM files to be recognized.
+/// Bare data sets are only recognized
+/// if they start with an element of group 0008,
+/// which is the case for most composite objects.
+pub fn sniff(header: &[u8]) -> Option<FileKind> {
+    if header.len() >= 132 && &header[128..132] == b"DICM" {
+        return Some(FileKind::Part10);
+    }
+    if header.starts_with(b"DICM") {
+        return Some(FileKind::Part10NoPreamble);
+    }
+    if header.len() < 8 {
+        return None;
+    }
+    let group = u16::from_le_bytes([header[0], header[1]]);
+    let element = u16::from_le_bytes([header[2], header[3]]);
+    if group != 0x0008 || element >= 0x0100 {
+        return None;
+    }
+    if VR::from_binary([header[4], header[5]]).is_some() {
+        Some(FileKind::ExplicitVrDataSet)
+    } else if u32::from_le_bytes([header[4], header[5], header[6], header[7]]) < 0x1_0000 {
+        // a plausible value length for one of the first attributes
+        Some(FileKind::ImplicitVrDataSet)
+    } else {
+        None
+    }
+}
+
+/// Identify the kind of DICOM content in the file at the given path.
+///
+/// Returns `None` if the file does not seem to contain DICOM data.
+pub fn sniff_file(path: impl AsRef<Path>) -> Result<Option<FileKind>> {
+    let path = path.as_ref();
+    let file = File::open(path).context(ReadFileSnafu { path })?;
+    let mut header = Vec::with_capacity(SNIFF_LENGTH);
+    file.take(SNIFF_LENGTH as u64)
+        .read_to_end(&mut header)
+        .context(ReadFileSnafu { path })?;
+    Ok(sniff(&header))
+}
+
+/// The attributes read from a DICOM file found while scanning.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexRecord {
+    /// the path to the file
+    pub path: PathBuf,
+    /// the kind of DICOM content in the file
+    pub kind: FileKind,
+    /// the file meta group, if the file has one
+    pub meta: Option<FileMetaTable>,
+    /// the selected attributes which are present in the file
+    pub elements: InMemDicomObject,
+}
+
+/// A directory scanner for DICOM files.
+///
+/// By default, the scanner reads
+/// the patient ID, the study, series, and SOP instance UIDs,
+/// the SOP class UID, and the modality of each file.
+/// The pixel data and subsequent attributes are never read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scanner {
+    tags: Vec<Tag>,
+}
+
+impl Default for Scanner {
+    fn default() -> Self {
+        Scanner {
+            tags: vec![
+                tags::SOP_CLASS_UID,
+                tags::SOP_INSTANCE_UID,
+                tags::MODALITY,
+                tags::PATIENT_ID,
+                tags::STUDY_INSTANCE_UID,
+                tags::SERIES_INSTANCE_UID,
+            ],
+        }
+    }
+}
+
+impl Scanner {
+    /// Create a scanner which reads the default selection of attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the selection of attributes to read.
+    pub fn with_tags(mut self, tags: impl IntoIterator<Item = Tag>) -> Self {
+        self.tags = tags.into_iter().collect();
+        self
+    }
+
+    /// Add an attribute to the selection of attributes to read.
+    pub fn with_tag(mut self, tag: Tag) -> Self {
+        self.tags.push(tag);
+        self
+    }
+
+    /// Retrieve the selection of attributes to read.
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
+    /// Scan the directory tree at the given path,
+    /// yielding a record for each DICOM file found,
+    /// ordered by path.
+    ///
+    /// Files which do not seem to contain DICOM data are skipped.
+    /// Failing to read a directory or a DICOM file
+    /// is reported as an error entry,
+    /// without interrupting the scan.
+    /// Errors reading directories come before all records.
+    pub fn scan(&self, root: impl AsRef<Path>) -> Vec<Result<IndexRecord>> {
+        let mut files = Vec::new();
+        let mut errors = Vec::new();
+        collect_files(root.as_ref(), &mut files, &mut errors);
+        files.sort();
+
+        let records = self.index_files(&files);
+        errors
+            .into_iter()
+            .map(Err)
+            .chain(records.into_iter().filter_map(Result::transpose))
+            .collect()
+    }
+
+    /// Read the selected attributes of the file at the given path,
+    /// or `None` if the file does not seem to contain DICOM data.
+    pub fn index_file(&self, path: impl AsRef<Path>) -> Result<Option<IndexRecord>> {
+        let path = path.as_ref();
+        let kind = match sniff_file(path)? {
+            Some(kind) => kind,
+            None => return Ok(None),
+        };
+
+        let (meta, mut elements) = match kind {
+            FileKind::Part10 | FileKind::Part10NoPreamble => {
+                let read_preamble = if kind == FileKind::Part10 {
+                    ReadPreamble::Always
+                } else {
+                    ReadPreamble::Never
+                };
+                let obj = OpenFileOptions::new()
+                    .read_preamble(read_preamble)
+                    .read_until(self.read_until())
+                    .open_file(path)
+                    .context(ParseFileSnafu { path })?;
+                let meta = obj.meta().clone();
+                (Some(meta), obj.into_inner())
+            }
+            FileKind::ExplicitVrDataSet | FileKind::ImplicitVrDataSet => {
+                let ts = if kind == FileKind::ExplicitVrDataSet {
+                    entries::EXPLICIT_VR_LITTLE_ENDIAN.erased()
+                } else {
+                    entries::IMPLICIT_VR_LITTLE_ENDIAN.erased()
+                };
+                let file = File::open(path).context(ReadFileSnafu { path })?;
+                let obj = InMemDicomObject::read_dataset_with_ts(BufReader::new(file), &ts)
+                    .context(ParseFileSnafu { path })?;
+                (None, obj)
+            }
+        };
+        elements.retain(|elem| self.tags.contains(&elem.tag()));
+
+        Ok(Some(IndexRecord {
+            path: path.to_path_buf(),
+            kind,
+            meta,
+            elements,
+        }))
+    }
+
+    /// Index the given files, in parallel if possible.
+    #[cfg(feature = "rayon")]
+    fn index_files(&self, files: &[PathBuf]) -> Vec<Result<Option<IndexRecord>>> {
+        use rayon::prelude::*;
+        files.par_iter().map(|path| self.index_file(path)).collect()
+    }
+
+    /// Index the given files.
+    #[cfg(not(feature = "rayon"))]
+    fn index_files(&self, files: &[PathBuf]) -> Vec<Result<Option<IndexRecord>>> {
+        files.iter().map(|path| self.index_file(path)).collect()
+    }
+
+    /// Determine the tag at which reading can stop,
+    /// which is right after the last selected attribute
+    /// and never past the pixel data.
+    fn read_until(&self) -> Tag {
+        self.tags
+            .iter()
+            .max()
+            .map(|&Tag(group, element)| match element.checked_add(1) {
+                Some(element) => Tag(group, element),
+                None => Tag(group.saturating_add(1), 0),
+            })
+            .filter(|&tag| tag < tags::PIXEL_DATA)
+            .unwrap_or(tags::PIXEL_DATA)
+    }
+}
+
+/// Recursively collect the paths of all files in a directory,
+/// along with any errors found along the way.
+fn collect_files(dir: &Path, files: &mut Vec<PathBuf>, errors: &mut Vec<Error>) {
+    walk_files(dir, files, &mut |path, source| {
+        errors.push(ReadDirectorySnafu { path }.into_error(source))
+    });
+}
+
+/// Recursively collect the paths of all files in a directory,
+/// calling `on_error` with the directory and the error
+/// for each directory which could not be read.
+///
+/// Symbolic links to directories are not followed,
+/// so that a link to a parent directory does not lead to an endless walk.
+/// Symbolic links to files are collected as files.
+pub(crate) fn walk_files(
+    dir: &Path,
+    files: &mut Vec<PathBuf>,
+    on_error: &mut dyn FnMut(&Path, std::io::Error),
+) {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            on_error(dir, e);
+            return;
+        }
+    };
+    for entry in entries {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                on_error(dir, e);
+                continue;
+            }
+        };
+        let path = entry.path();
+        match entry.file_type() {
+            Ok(file_type) if file_type.is_dir() => walk_files(&path, files, on_error),
+            // links to directories are skipped
+            Ok(file_type) if file_type.is_symlink() && path.is_dir() => {}
+            Ok(_) => files.push(path),
+            Err(e) => on_error(dir, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileMetaTableBuilder;
+    use dicom_core::{DataElement, PrimitiveValue};
+
+    #[test]
+    fn sniff_headers() {
+        let mut part10 = vec![0; 132];
+        part10[128..].copy_from_slice(b"DICM");
+        assert_eq!(sniff(&part10), Some(FileKind::Part10));
+        assert_eq!(
+            sniff(b"DICM\x02\x00\x00\x00"),
+            Some(FileKind::Part10NoPreamble)
+        );
+        // (0008,0005) CS, length 10
+        assert_eq!(
+            sniff(b"\x08\x00\x05\x00CS\x0A\x00ISO_IR 100"),
+            Some(FileKind::ExplicitVrDataSet)
+        );
+        // (0008,0016) length 26
+        assert_eq!(
+            sniff(b"\x08\x00\x16\x00\x1A\x00\x00\x00"),
+            Some(FileKind::ImplicitVrDataSet)
+        );
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n"), None);
+        assert_eq!(sniff(b"DIC"), None);
+    }
+
+    #[test]
+    fn scan_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("series")).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not DICOM").unwrap();
+
+        let obj = InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.888.123"),
+            ),
+            DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from("CT")),
+            DataElement::new(
+                tags::STUDY_DESCRIPTION,
+                VR::LO,
+                PrimitiveValue::from("HEAD"),
+            ),
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(64_u16)),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.2"),
+        )
+        .unwrap();
+        let file_path = dir.path().join("series").join("1");
+        obj.write_to_file(&file_path).unwrap();
+
+        let scanner = Scanner::new().with_tag(tags::STUDY_DESCRIPTION);
+        let records: Vec<_> = scanner
+            .scan(dir.path())
+            .into_iter()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record.path, file_path);
+        assert_eq!(record.kind, FileKind::Part10);
+        assert!(record.meta.is_some());
+        assert_eq!(record.elements.get_string(tags::MODALITY).unwrap(), "CT");
+        assert_eq!(
+            record.elements.get_string(tags::STUDY_DESCRIPTION).unwrap(),
+            "HEAD"
+        );
+        assert!(record.elements.element_opt(tags::ROWS).unwrap().is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn walk_files_skips_directory_links() {
+        let dir = tempfile::tempdir().unwrap();
+        let series = dir.path().join("series");
+        std::fs::create_dir(&series).unwrap();
+        std::fs::write(series.join("1"), "data").unwrap();
+        // a link back to the root would make the walk endless
+        std::os::unix::fs::symlink(dir.path(), series.join("root")).unwrap();
+        std::os::unix::fs::symlink(series.join("1"), dir.path().join("2")).unwrap();
+
+        let mut files = Vec::new();
+        walk_files(dir.path(), &mut files, &mut |path, e| {
+            panic!("could not read {}: {}", path.display(), e)
+        });
+        files.sort();
+        assert_eq!(files, vec![dir.path().join("2"), series.join("1")]);
+    }
+}
@@ -0,0 +1,1130 @@
+//! Creation of Segmentation (SEG) objects.
+//!
+//! A DICOM segmentation is an enhanced multi-frame object
+//! where each frame is a bitmap of one segment
+//! over one of the source images,
+//! identified through the per-frame functional groups.
+//! [`SegmentationBuilder`] takes the source images,
+//! the description of each [`Segment`],
+//! and the label masks over the source images,
+//! and assembles a complete _Segmentation_ object
+//! of segmentation type `BINARY`,
+//! including the functional groups, the dimension index,
+//! the references to the source images,
+//! and the bit-packed pixel data.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::code::CodedConcept;
+//! use dicom_object::open_file;
+//! use dicom_object::seg::{Segment, SegmentAlgorithmType, SegmentationBuilder};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let slices = [open_file("ct_0001.dcm")?, open_file("ct_0002.dcm")?];
+//! # let labels: Vec<Vec<u8>> = vec![];
+//! // one label map per slice: 0 is background, 1 is liver, 2 is tumor
+//! let mut builder = SegmentationBuilder::new()
+//!     .content_label("LIVER")
+//!     .segment(Segment::new(
+//!         "Liver",
+//!         CodedConcept::new("123037004", "SCT", "Anatomical Structure"),
+//!         CodedConcept::new("10200004", "SCT", "Liver"),
+//!     ))
+//!     .segment(
+//!         Segment::new(
+//!             "Tumor",
+//!             CodedConcept::new("49755003", "SCT", "Morphologically Altered Structure"),
+//!             CodedConcept::new("108369006", "SCT", "Neoplasm"),
+//!         )
+//!         .algorithm(SegmentAlgorithmType::Automatic, "my-model"),
+//!     );
+//! for (i, slice) in slices.iter().enumerate() {
+//!     builder = builder.source_image(slice).label_mask(i, &labels[i]);
+//! }
+//! builder.build()?.write_to_file("seg.dcm")?;
+//! # Ok(())
+//! # }
+//! ```
+use std::collections::BTreeMap;
+
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::code::CodedConcept;
+use crate::mem::InMemElement;
+use crate::multiframe::new_uid;
+use crate::sr::{current_date_time, PATIENT_STUDY_ATTRIBUTES};
+use crate::{FileDicomObject, FileMetaTableBuilder, InMemDicomObject};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// No source images were given.
+    #[snafu(display("No source images"))]
+    NoSourceImages { backtrace: Backtrace },
+
+    /// No segments were described.
+    #[snafu(display("No segments"))]
+    NoSegments { backtrace: Backtrace },
+
+    /// All masks given are empty,
+    /// so the segmentation would have no frames.
+    #[snafu(display("All segment masks are empty"))]
+    EmptySegmentation { backtrace: Backtrace },
+
+    /// A required attribute is missing from a source image.
+    #[snafu(display("Missing attribute {} in source image #{}", tag, index))]
+    MissingAttribute {
+        index: usize,
+        tag: Tag,
+        backtrace: Backtrace,
+    },
+
+    /// An attribute of a source image could not be converted
+    /// to the expected type.
+    #[snafu(display("Invalid value for attribute {} in source image #{}", tag, index))]
+    InvalidAttribute {
+        index: usize,
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// A source image cannot be segmented along with the first one.
+    #[snafu(display("Incompatible source image #{}: {}", index, reason))]
+    IncompatibleSource {
+        index: usize,
+        reason: &'static str,
+        backtrace: Backtrace,
+    },
+
+    /// A mask refers to a segment which was not described.
+    #[snafu(display("Unknown segment number {}", number))]
+    UnknownSegment { number: u16, backtrace: Backtrace },
+
+    /// A mask refers to a source image which was not given.
+    #[snafu(display("Unknown source image #{}", index))]
+    UnknownSource { index: usize, backtrace: Backtrace },
+
+    /// The size of a mask does not match the size of the source images.
+    #[snafu(display("Expected a mask of {} pixels, got {}", expected, got))]
+    MaskSize {
+        expected: usize,
+        got: usize,
+        backtrace: Backtrace,
+    },
+
+    /// The file meta group of the new object could not be built.
+    #[snafu(display("Could not build file meta group"))]
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The SOP class UID of _Segmentation Storage_.
+pub const SEGMENTATION_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.66.4";
+
+/// Attributes of a source image needed to reference it
+/// and to describe the geometry of the segmentation.
+const SOURCE_ATTRIBUTES: &[Tag] = &[
+    tags::SOP_CLASS_UID,
+    tags::SOP_INSTANCE_UID,
+    tags::SERIES_INSTANCE_UID,
+    tags::FRAME_OF_REFERENCE_UID,
+    tags::ROWS,
+    tags::COLUMNS,
+    tags::IMAGE_POSITION_PATIENT,
+    tags::IMAGE_ORIENTATION_PATIENT,
+    tags::PIXEL_SPACING,
+    tags::SLICE_THICKNESS,
+    tags::SPACING_BETWEEN_SLICES,
+];
+
+/// Attributes of the _Pixel Measures_ functional group.
+const PIXEL_MEASURES: &[Tag] = &[
+    tags::PIXEL_SPACING,
+    tags::SLICE_THICKNESS,
+    tags::SPACING_BETWEEN_SLICES,
+];
+
+/// The type of algorithm used to create a segment.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SegmentAlgorithmType {
+    /// `MANUAL`
+    Manual,
+    /// `SEMIAUTOMATIC`
+    SemiAutomatic,
+    /// `AUTOMATIC`
+    Automatic,
+}
+
+impl SegmentAlgorithmType {
+    /// Obtain the defined term of this algorithm type.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SegmentAlgorithmType::Manual => "MANUAL",
+            SegmentAlgorithmType::SemiAutomatic => "SEMIAUTOMATIC",
+            SegmentAlgorithmType::Automatic => "AUTOMATIC",
+        }
+    }
+}
+
+/// The description of a segment,
+/// written as an item of the _Segment Sequence_.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    label: String,
+    description: Option<String>,
+    category: CodedConcept,
+    property_type: CodedConcept,
+    anatomic_region: Option<CodedConcept>,
+    algorithm_type: SegmentAlgorithmType,
+    algorithm_name: Option<String>,
+    display_color: Option<[u16; 3]>,
+}
+
+impl Segment {
+    /// Describe a manually created segment
+    /// with the given label, segmented property category,
+    /// and segmented property type.
+    pub fn new(
+        label: impl Into<String>,
+        category: CodedConcept,
+        property_type: CodedConcept,
+    ) -> Self {
+        Segment {
+            label: label.into(),
+            description: None,
+            category,
+            property_type,
+            anatomic_region: None,
+            algorithm_type: SegmentAlgorithmType::Manual,
+            algorithm_name: None,
+            display_color: None,
+        }
+    }
+
+    /// Set a description of the segment.
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Set the anatomic region containing the segment.
+    pub fn anatomic_region(mut self, region: CodedConcept) -> Self {
+        self.anatomic_region = Some(region);
+        self
+    }
+
+    /// Set the type and name of the algorithm
+    /// which created the segment.
+    pub fn algorithm(
+        mut self,
+        algorithm_type: SegmentAlgorithmType,
+        name: impl Into<String>,
+    ) -> Self {
+        self.algorithm_type = algorithm_type;
+        self.algorithm_name = Some(name.into());
+        self
+    }
+
+    /// Set the recommended display color of the segment,
+    /// as a CIELab value scaled to the range of 16-bit integers.
+    pub fn recommended_display_color(mut self, cielab: [u16; 3]) -> Self {
+        self.display_color = Some(cielab);
+        self
+    }
+
+    /// Create the _Segment Sequence_ item of this segment
+    /// with the given segment number.
+    fn to_item(&self, number: u16) -> InMemDicomObject {
+        let mut item = InMemDicomObject::new_empty();
+        item.put(DataElement::new(
+            tags::SEGMENT_NUMBER,
+            VR::US,
+            PrimitiveValue::from(number),
+        ));
+        item.put(DataElement::new(
+            tags::SEGMENT_LABEL,
+            VR::LO,
+            PrimitiveValue::from(self.label.as_str()),
+        ));
+        if let Some(description) = &self.description {
+            item.put(DataElement::new(
+                tags::SEGMENT_DESCRIPTION,
+                VR::ST,
+                PrimitiveValue::from(description.as_str()),
+            ));
+        }
+        item.put(
+            self.category
+                .to_sequence_element(tags::SEGMENTED_PROPERTY_CATEGORY_CODE_SEQUENCE),
+        );
+        item.put(
+            self.property_type
+                .to_sequence_element(tags::SEGMENTED_PROPERTY_TYPE_CODE_SEQUENCE),
+        );
+        if let Some(region) = &self.anatomic_region {
+            item.put(region.to_sequence_element(tags::ANATOMIC_REGION_SEQUENCE));
+        }
+        item.put(cs(
+            tags::SEGMENT_ALGORITHM_TYPE,
+            self.algorithm_type.as_str(),
+        ));
+        if let Some(name) = &self.algorithm_name {
+            item.put(DataElement::new(
+                tags::SEGMENT_ALGORITHM_NAME,
+                VR::LO,
+                PrimitiveValue::from(name.as_str()),
+            ));
+        }
+        if let Some(color) = self.display_color {
+            item.put(DataElement::new(
+                tags::RECOMMENDED_DISPLAY_CIE_LAB_VALUE,
+                VR::US,
+                PrimitiveValue::from(color),
+            ));
+        }
+        item
+    }
+}
+
+/// A builder for binary _Segmentation_ objects.
+///
+/// Segments are numbered from 1 in the order in which they are added.
+/// The source images are indexed from 0 in the order in which they are added,
+/// and must all have the same number of rows and columns
+/// and the same frame of reference.
+/// Each combination of segment and source image with a non-empty mask
+/// becomes one frame of the segmentation,
+/// ordered by segment number and then by source image.
+#[derive(Debug, Clone)]
+pub struct SegmentationBuilder {
+    sources: Vec<InMemDicomObject>,
+    segments: Vec<Segment>,
+    masks: Vec<(u16, usize, Vec<bool>)>,
+    content_label: String,
+    content_description: Option<String>,
+    content_creator: Option<String>,
+    series_description: Option<String>,
+    content_date_time: Option<(String, String)>,
+    series_number: i32,
+    instance_number: i32,
+    manufacturer: String,
+    model_name: String,
+    device_serial_number: String,
+}
+
+impl Default for SegmentationBuilder {
+    fn default() -> Self {
+        SegmentationBuilder {
+            sources: Vec::new(),
+            segments: Vec::new(),
+            masks: Vec::new(),
+            content_label: "SEGMENTATION".to_string(),
+            content_description: None,
+            content_creator: None,
+            series_description: None,
+            content_date_time: None,
+            series_number: 1,
+            instance_number: 1,
+            manufacturer: "DICOM-rs".to_string(),
+            model_name: "dicom-object".to_string(),
+            device_serial_number: "1".to_string(),
+        }
+    }
+}
+
+impl SegmentationBuilder {
+    /// Create a new segmentation builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a source image of the segmentation.
+    ///
+    /// The patient and study attributes of the segmentation
+    /// are copied from the first source image.
+    pub fn source_image<D>(mut self, obj: &InMemDicomObject<D>) -> Self
+    where
+        D: DataDictionary + Clone,
+    {
+        let mut source = InMemDicomObject::new_empty();
+        let patient_study = PATIENT_STUDY_ATTRIBUTES.iter().map(|&(tag, _)| tag);
+        for tag in SOURCE_ATTRIBUTES.iter().copied().chain(patient_study) {
+            if let Ok(Some(e)) = obj.element_opt(tag) {
+                if let Value::Primitive(value) = e.value() {
+                    source.put(DataElement::new(tag, e.vr(), value.clone()));
+                }
+            }
+        }
+        self.sources.push(source);
+        self
+    }
+
+    /// Add a segment description.
+    pub fn segment(mut self, segment: Segment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Add the mask of a segment over a source image,
+    /// with one byte per pixel in row-major order,
+    /// where any non-zero value marks the pixel as part of the segment.
+    ///
+    /// Masks given more than once for the same segment and source image
+    /// are combined.
+    pub fn segment_mask(mut self, segment_number: u16, source_index: usize, mask: &[u8]) -> Self {
+        self.masks.push((
+            segment_number,
+            source_index,
+            mask.iter().map(|&v| v != 0).collect(),
+        ));
+        self
+    }
+
+    /// Add a label map over a source image,
+    /// with one byte per pixel in row-major order,
+    /// where each value is the number of the segment
+    /// which the pixel belongs to,
+    /// or 0 for the background.
+    pub fn label_mask(mut self, source_index: usize, labels: &[u8]) -> Self {
+        let mut numbers: Vec<u8> = labels.iter().copied().filter(|&v| v != 0).collect();
+        numbers.sort_unstable();
+        numbers.dedup();
+        for number in numbers {
+            self.masks.push((
+                u16::from(number),
+                source_index,
+                labels.iter().map(|&v| v == number).collect(),
+            ));
+        }
+        self
+    }
+
+    /// Set the content label of the segmentation,
+    /// in upper case. The default is `SEGMENTATION`.
+    pub fn content_label(mut self, label: impl Into<String>) -> Self {
+        self.content_label = label.into();
+        self
+    }
+
+    /// Set the content description of the segmentation.
+    pub fn content_description(mut self, description: impl Into<String>) -> Self {
+        self.content_description = Some(description.into());
+        self
+    }
+
+    /// Set the name of the person who created the segmentation.
+    pub fn content_creator(mut self, name: impl Into<String>) -> Self {
+        self.content_creator = Some(name.into());
+        self
+    }
+
+    /// Set the series description of the segmentation.
+    pub fn series_description(mut self, description: impl Into<String>) -> Self {
+        self.series_description = Some(description.into());
+        self
+    }
+
+    /// Set the content date (DA) and time (TM) of the segmentation.
+    ///
+    /// If not set, the current time in UTC is used.
+    pub fn content_date_time(mut self, date: impl Into<String>, time: impl Into<String>) -> Self {
+        self.content_date_time = Some((date.into(), time.into()));
+        self
+    }
+
+    /// Set the series number of the segmentation. The default is 1.
+    pub fn series_number(mut self, number: i32) -> Self {
+        self.series_number = number;
+        self
+    }
+
+    /// Set the instance number of the segmentation. The default is 1.
+    pub fn instance_number(mut self, number: i32) -> Self {
+        self.instance_number = number;
+        self
+    }
+
+    /// Set the manufacturer, model name, and serial number
+    /// of the equipment creating the segmentation.
+    /// The default identifies DICOM-rs.
+    pub fn equipment(
+        mut self,
+        manufacturer: impl Into<String>,
+        model_name: impl Into<String>,
+        device_serial_number: impl Into<String>,
+    ) -> Self {
+        self.manufacturer = manufacturer.into();
+        self.model_name = model_name.into();
+        self.device_serial_number = device_serial_number.into();
+        self
+    }
+
+    /// Build the segmentation as a new DICOM file object.
+    pub fn build(self) -> Result<FileDicomObject<InMemDicomObject>> {
+        ensure!(!self.sources.is_empty(), NoSourceImagesSnafu);
+        ensure!(!self.segments.is_empty(), NoSegmentsSnafu);
+
+        let first = &self.sources[0];
+        let rows = int_of(first, 0, tags::ROWS)?;
+        let columns = int_of(first, 0, tags::COLUMNS)?;
+        let frame_of_reference = opt_string(first, tags::FRAME_OF_REFERENCE_UID);
+        for (index, source) in self.sources.iter().enumerate().skip(1) {
+            ensure!(
+                int_of(source, index, tags::ROWS)? == rows
+                    && int_of(source, index, tags::COLUMNS)? == columns,
+                IncompatibleSourceSnafu {
+                    index,
+                    reason: "image size differs from the first source image",
+                }
+            );
+            ensure!(
+                opt_string(source, tags::FRAME_OF_REFERENCE_UID) == frame_of_reference,
+                IncompatibleSourceSnafu {
+                    index,
+                    reason: "frame of reference differs from the first source image",
+                }
+            );
+        }
+        let with_position = self
+            .sources
+            .iter()
+            .all(|source| has(source, tags::IMAGE_POSITION_PATIENT));
+
+        // merge the masks into one frame per segment and source image
+        let pixels = usize::from(rows) * usize::from(columns);
+        let mut frames: BTreeMap<(u16, usize), Vec<bool>> = BTreeMap::new();
+        for (segment_number, source_index, mask) in self.masks {
+            ensure!(
+                segment_number >= 1 && usize::from(segment_number) <= self.segments.len(),
+                UnknownSegmentSnafu {
+                    number: segment_number
+                }
+            );
+            ensure!(
+                source_index < self.sources.len(),
+                UnknownSourceSnafu {
+                    index: source_index
+                }
+            );
+            ensure!(
+                mask.len() == pixels,
+                MaskSizeSnafu {
+                    expected: pixels,
+                    got: mask.len(),
+                }
+            );
+            let frame = frames
+                .entry((segment_number, source_index))
+                .or_insert_with(|| vec![false; pixels]);
+            for (pixel, set) in frame.iter_mut().zip(mask) {
+                *pixel |= set;
+            }
+        }
+        frames.retain(|_, mask| mask.iter().any(|&set| set));
+        ensure!(!frames.is_empty(), EmptySegmentationSnafu);
+
+        let mut references = Vec::with_capacity(self.sources.len());
+        for (index, source) in self.sources.iter().enumerate() {
+            references.push((
+                string_of(source, index, tags::SOP_CLASS_UID)?,
+                string_of(source, index, tags::SOP_INSTANCE_UID)?,
+            ));
+        }
+
+        let mut obj = InMemDicomObject::new_empty();
+
+        // patient and study
+        let mut patient_study = first.clone();
+        if !has(&patient_study, tags::STUDY_INSTANCE_UID) {
+            patient_study.put(DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(new_uid()),
+            ));
+        }
+        for &(tag, vr) in PATIENT_STUDY_ATTRIBUTES {
+            let elem = patient_study
+                .take_element(tag)
+                .unwrap_or_else(|_| DataElement::new(tag, vr, PrimitiveValue::Empty));
+            obj.put(elem);
+        }
+
+        let (date, time) = match self.content_date_time {
+            Some(date_time) => date_time,
+            None => current_date_time(),
+        };
+        for (tag, vr, value) in [
+            (
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                SEGMENTATION_STORAGE.to_string(),
+            ),
+            (tags::SOP_INSTANCE_UID, VR::UI, new_uid()),
+            (tags::MODALITY, VR::CS, "SEG".to_string()),
+            (tags::SERIES_INSTANCE_UID, VR::UI, new_uid()),
+            (tags::SERIES_NUMBER, VR::IS, self.series_number.to_string()),
+            (
+                tags::INSTANCE_NUMBER,
+                VR::IS,
+                self.instance_number.to_string(),
+            ),
+            (tags::MANUFACTURER, VR::LO, self.manufacturer),
+            (tags::MANUFACTURER_MODEL_NAME, VR::LO, self.model_name),
+            (
+                tags::DEVICE_SERIAL_NUMBER,
+                VR::LO,
+                self.device_serial_number,
+            ),
+            (
+                tags::SOFTWARE_VERSIONS,
+                VR::LO,
+                env!("CARGO_PKG_VERSION").to_string(),
+            ),
+            (tags::CONTENT_LABEL, VR::CS, self.content_label),
+            (
+                tags::CONTENT_DESCRIPTION,
+                VR::LO,
+                self.content_description.unwrap_or_default(),
+            ),
+            (
+                tags::CONTENT_CREATOR_NAME,
+                VR::PN,
+                self.content_creator.unwrap_or_default(),
+            ),
+            (tags::CONTENT_DATE, VR::DA, date),
+            (tags::CONTENT_TIME, VR::TM, time),
+            (
+                tags::PHOTOMETRIC_INTERPRETATION,
+                VR::CS,
+                "MONOCHROME2".to_string(),
+            ),
+            (tags::LOSSY_IMAGE_COMPRESSION, VR::CS, "00".to_string()),
+            (tags::SEGMENTATION_TYPE, VR::CS, "BINARY".to_string()),
+            (tags::NUMBER_OF_FRAMES, VR::IS, frames.len().to_string()),
+        ] {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        }
+        if let Some(description) = self.series_description {
+            obj.put(DataElement::new(
+                tags::SERIES_DESCRIPTION,
+                VR::LO,
+                PrimitiveValue::from(description),
+            ));
+        }
+        if let Some(uid) = frame_of_reference {
+            obj.put(DataElement::new(
+                tags::FRAME_OF_REFERENCE_UID,
+                VR::UI,
+                PrimitiveValue::from(uid),
+            ));
+        }
+        obj.put(DataElement::new(
+            tags::IMAGE_TYPE,
+            VR::CS,
+            PrimitiveValue::Strs(
+                ["DERIVED", "PRIMARY"]
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect(),
+            ),
+        ));
+        for (tag, value) in [
+            (tags::SAMPLES_PER_PIXEL, 1),
+            (tags::ROWS, rows),
+            (tags::COLUMNS, columns),
+            (tags::BITS_ALLOCATED, 1),
+            (tags::BITS_STORED, 1),
+            (tags::HIGH_BIT, 0),
+            (tags::PIXEL_REPRESENTATION, 0),
+        ] {
+            obj.put(DataElement::new(tag, VR::US, PrimitiveValue::from(value)));
+        }
+
+        obj.put(sequence(
+            tags::SEGMENT_SEQUENCE,
+            self.segments
+                .iter()
+                .zip(1..)
+                .map(|(segment, number)| segment.to_item(number))
+                .collect(),
+        ));
+
+        // dimensions: segment number, then image position if available
+        let dimension_organization_uid = new_uid();
+        let mut organization = InMemDicomObject::new_empty();
+        organization.put(uid(
+            tags::DIMENSION_ORGANIZATION_UID,
+            &dimension_organization_uid,
+        ));
+        obj.put(sequence(
+            tags::DIMENSION_ORGANIZATION_SEQUENCE,
+            vec![organization],
+        ));
+        let mut dimensions = vec![(
+            tags::REFERENCED_SEGMENT_NUMBER,
+            tags::SEGMENT_IDENTIFICATION_SEQUENCE,
+            "ReferencedSegmentNumber",
+        )];
+        if with_position {
+            dimensions.push((
+                tags::IMAGE_POSITION_PATIENT,
+                tags::PLANE_POSITION_SEQUENCE,
+                "ImagePositionPatient",
+            ));
+        }
+        obj.put(sequence(
+            tags::DIMENSION_INDEX_SEQUENCE,
+            dimensions
+                .into_iter()
+                .map(|(pointer, group, label)| {
+                    let mut item = InMemDicomObject::new_empty();
+                    item.put(uid(
+                        tags::DIMENSION_ORGANIZATION_UID,
+                        &dimension_organization_uid,
+                    ));
+                    item.put(DataElement::new(
+                        tags::DIMENSION_INDEX_POINTER,
+                        VR::AT,
+                        PrimitiveValue::from(pointer),
+                    ));
+                    item.put(DataElement::new(
+                        tags::FUNCTIONAL_GROUP_POINTER,
+                        VR::AT,
+                        PrimitiveValue::from(group),
+                    ));
+                    item.put(DataElement::new(
+                        tags::DIMENSION_DESCRIPTION_LABEL,
+                        VR::LO,
+                        PrimitiveValue::from(label),
+                    ));
+                    item
+                })
+                .collect(),
+        ));
+
+        // shared functional groups: plane orientation and pixel measures
+        let mut shared = InMemDicomObject::new_empty();
+        if let Some(orientation) = element(first, tags::IMAGE_ORIENTATION_PATIENT) {
+            let mut item = InMemDicomObject::new_empty();
+            item.put(orientation.clone());
+            shared.put(sequence(tags::PLANE_ORIENTATION_SEQUENCE, vec![item]));
+        }
+        let mut measures = InMemDicomObject::new_empty();
+        for &tag in PIXEL_MEASURES {
+            if let Some(e) = element(first, tag) {
+                measures.put(e.clone());
+            }
+        }
+        if measures.iter().next().is_some() {
+            shared.put(sequence(tags::PIXEL_MEASURES_SEQUENCE, vec![measures]));
+        }
+        obj.put(sequence(
+            tags::SHARED_FUNCTIONAL_GROUPS_SEQUENCE,
+            vec![shared],
+        ));
+
+        // per-frame functional groups
+        let sources = &self.sources;
+        obj.put(sequence(
+            tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE,
+            frames
+                .keys()
+                .map(|&(segment_number, source_index)| {
+                    let source = &sources[source_index];
+                    let (sop_class_uid, sop_instance_uid) = &references[source_index];
+                    per_frame_item(
+                        segment_number,
+                        source_index,
+                        source,
+                        sop_class_uid,
+                        sop_instance_uid,
+                        with_position,
+                    )
+                })
+                .collect(),
+        ));
+
+        // references to the source images, by series
+        let mut series: Vec<(String, Vec<InMemDicomObject>)> = Vec::new();
+        for (source, (sop_class_uid, sop_instance_uid)) in self.sources.iter().zip(&references) {
+            let series_uid = opt_string(source, tags::SERIES_INSTANCE_UID).unwrap_or_default();
+            let item = sop_reference(sop_class_uid, sop_instance_uid);
+            match series.iter_mut().find(|(uid, _)| *uid == series_uid) {
+                Some((_, instances)) => instances.push(item),
+                None => series.push((series_uid, vec![item])),
+            }
+        }
+        obj.put(sequence(
+            tags::REFERENCED_SERIES_SEQUENCE,
+            series
+                .into_iter()
+                .map(|(series_uid, instances)| {
+                    let mut item = InMemDicomObject::new_empty();
+                    item.put(uid(tags::SERIES_INSTANCE_UID, &series_uid));
+                    item.put(sequence(tags::REFERENCED_INSTANCE_SEQUENCE, instances));
+                    item
+                })
+                .collect(),
+        ));
+
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            PrimitiveValue::from(pack_frames(frames.values().map(|f| f.as_slice()))),
+        ));
+
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid(SEGMENTATION_STORAGE)
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .context(BuildMetaSnafu)
+    }
+}
+
+/// Create the per-frame functional groups item
+/// of the frame of a segment over a source image.
+fn per_frame_item(
+    segment_number: u16,
+    source_index: usize,
+    source: &InMemDicomObject,
+    sop_class_uid: &str,
+    sop_instance_uid: &str,
+    with_position: bool,
+) -> InMemDicomObject {
+    let mut item = InMemDicomObject::new_empty();
+
+    let mut source_image = sop_reference(sop_class_uid, sop_instance_uid);
+    source_image.put(
+        CodedConcept::new(
+            "121322",
+            "DCM",
+            "Source image for image processing operation",
+        )
+        .to_sequence_element(tags::PURPOSE_OF_REFERENCE_CODE_SEQUENCE),
+    );
+    let mut derivation = InMemDicomObject::new_empty();
+    derivation.put(sequence(tags::SOURCE_IMAGE_SEQUENCE, vec![source_image]));
+    derivation.put(
+        CodedConcept::new("113076", "DCM", "Segmentation")
+            .to_sequence_element(tags::DERIVATION_CODE_SEQUENCE),
+    );
+    item.put(sequence(tags::DERIVATION_IMAGE_SEQUENCE, vec![derivation]));
+
+    let mut index_values = vec![u32::from(segment_number)];
+    if with_position {
+        index_values.push(source_index as u32 + 1);
+    }
+    let mut frame_content = InMemDicomObject::new_empty();
+    frame_content.put(DataElement::new(
+        tags::DIMENSION_INDEX_VALUES,
+        VR::UL,
+        PrimitiveValue::U32(C::from_vec(index_values)),
+    ));
+    item.put(sequence(tags::FRAME_CONTENT_SEQUENCE, vec![frame_content]));
+
+    if let Some(position) = element(source, tags::IMAGE_POSITION_PATIENT) {
+        let mut plane_position = InMemDicomObject::new_empty();
+        plane_position.put(position.clone());
+        item.put(sequence(
+            tags::PLANE_POSITION_SEQUENCE,
+            vec![plane_position],
+        ));
+    }
+
+    let mut segment = InMemDicomObject::new_empty();
+    segment.put(DataElement::new(
+        tags::REFERENCED_SEGMENT_NUMBER,
+        VR::US,
+        PrimitiveValue::from(segment_number),
+    ));
+    item.put(sequence(
+        tags::SEGMENT_IDENTIFICATION_SEQUENCE,
+        vec![segment],
+    ));
+
+    item
+}
+
+/// Pack binary frames into pixel data with 1 bit allocated per pixel.
+///
+/// Pixels are packed from the least significant bit of each byte,
+/// and frames follow each other without padding,
+/// so a frame may start in the middle of a byte.
+/// The result is padded to an even length.
+fn pack_frames<'a>(frames: impl IntoIterator<Item = &'a [bool]>) -> Vec<u8> {
+    let mut data = Vec::new();
+    let mut bit = 0;
+    for frame in frames {
+        for &set in frame {
+            if bit == 0 {
+                data.push(0);
+            }
+            if set {
+                *data.last_mut().unwrap() |= 1 << bit;
+            }
+            bit = (bit + 1) % 8;
+        }
+    }
+    if data.len() % 2 == 1 {
+        data.push(0);
+    }
+    data
+}
+
+/// Create an item referencing a SOP instance.
+fn sop_reference(sop_class_uid: &str, sop_instance_uid: &str) -> InMemDicomObject {
+    let mut item = InMemDicomObject::new_empty();
+    item.put(uid(tags::REFERENCED_SOP_CLASS_UID, sop_class_uid));
+    item.put(uid(tags::REFERENCED_SOP_INSTANCE_UID, sop_instance_uid));
+    item
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> InMemElement {
+    DataElement::new(
+        tag,
+        VR::SQ,
+        Value::Sequence {
+            items: C::from_vec(items),
+            size: Length::UNDEFINED,
+        },
+    )
+}
+
+fn cs(tag: Tag, value: &str) -> InMemElement {
+    DataElement::new(tag, VR::CS, PrimitiveValue::from(value))
+}
+
+fn uid(tag: Tag, value: &str) -> InMemElement {
+    DataElement::new(tag, VR::UI, PrimitiveValue::from(value))
+}
+
+fn element(obj: &InMemDicomObject, tag: Tag) -> Option<&InMemElement> {
+    obj.element_opt(tag).ok().flatten()
+}
+
+fn has(obj: &InMemDicomObject, tag: Tag) -> bool {
+    element(obj, tag).is_some()
+}
+
+fn opt_string(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+    element(obj, tag)
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.into_owned())
+}
+
+fn string_of(obj: &InMemDicomObject, index: usize, tag: Tag) -> Result<String> {
+    opt_string(obj, tag).context(MissingAttributeSnafu { index, tag })
+}
+
+fn int_of(obj: &InMemDicomObject, index: usize, tag: Tag) -> Result<u16> {
+    element(obj, tag)
+        .context(MissingAttributeSnafu { index, tag })?
+        .to_int()
+        .context(InvalidAttributeSnafu { index, tag })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dicom_value;
+
+    fn source_image(instance: &str, z: &str) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.3"),
+            ),
+            DataElement::new(
+                tags::SERIES_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.3.4"),
+            ),
+            DataElement::new(
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.2"),
+            ),
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(instance),
+            ),
+            DataElement::new(
+                tags::FRAME_OF_REFERENCE_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.3.9"),
+            ),
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(2_u16)),
+            DataElement::new(tags::COLUMNS, VR::US, PrimitiveValue::from(3_u16)),
+            DataElement::new(
+                tags::IMAGE_POSITION_PATIENT,
+                VR::DS,
+                dicom_value!(Strs, ["0", "0", z]),
+            ),
+            DataElement::new(
+                tags::IMAGE_ORIENTATION_PATIENT,
+                VR::DS,
+                dicom_value!(Strs, ["1", "0", "0", "0", "1", "0"]),
+            ),
+            DataElement::new(
+                tags::PIXEL_SPACING,
+                VR::DS,
+                dicom_value!(Strs, ["0.5", "0.5"]),
+            ),
+        ])
+    }
+
+    fn segment(label: &str) -> Segment {
+        Segment::new(
+            label,
+            CodedConcept::new("123037004", "SCT", "Anatomical Structure"),
+            CodedConcept::new("10200004", "SCT", "Liver"),
+        )
+    }
+
+    fn first_item(obj: &InMemDicomObject, tag: Tag) -> &InMemDicomObject {
+        &obj.element(tag).unwrap().items().unwrap()[0]
+    }
+
+    #[test]
+    fn packs_frames_without_padding() {
+        let a = [true, false, true, false, false];
+        let b = [true, true, false, true, true];
+        assert_eq!(
+            pack_frames(vec![&a[..], &b[..]]),
+            vec![0b0110_0101, 0b0000_0011]
+        );
+        assert_eq!(pack_frames(vec![&a[..]]), vec![0b0000_0101, 0]);
+    }
+
+    #[test]
+    fn builds_segmentation_from_label_masks() {
+        let seg = SegmentationBuilder::new()
+            .source_image(&source_image("1.2.3.4.1", "0"))
+            .source_image(&source_image("1.2.3.4.2", "2.5"))
+            .segment(segment("Liver"))
+            .segment(segment("Tumor").algorithm(SegmentAlgorithmType::Automatic, "model"))
+            .label_mask(0, &[0, 1, 1, 0, 2, 0])
+            .label_mask(1, &[0, 0, 0, 0, 0, 0])
+            .segment_mask(1, 1, &[1, 1, 0, 0, 0, 0])
+            .content_date_time("20240101", "120000")
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            seg.meta()
+                .media_storage_sop_class_uid
+                .trim_end_matches('\0'),
+            SEGMENTATION_STORAGE
+        );
+        assert_eq!(
+            seg.element(tags::PATIENT_NAME).unwrap().to_str().unwrap(),
+            "Doe^John"
+        );
+        assert_eq!(
+            seg.element(tags::NUMBER_OF_FRAMES)
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            3
+        );
+
+        // frames: (segment 1, image 0), (segment 1, image 1), (segment 2, image 0)
+        let per_frame = seg
+            .element(tags::PER_FRAME_FUNCTIONAL_GROUPS_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(per_frame.len(), 3);
+        let frame = &per_frame[1];
+        let index_values = first_item(frame, tags::FRAME_CONTENT_SEQUENCE)
+            .element(tags::DIMENSION_INDEX_VALUES)
+            .unwrap()
+            .to_multi_int::<u32>()
+            .unwrap();
+        assert_eq!(index_values, vec![1, 2]);
+        let source = first_item(
+            first_item(frame, tags::DERIVATION_IMAGE_SEQUENCE),
+            tags::SOURCE_IMAGE_SEQUENCE,
+        );
+        assert_eq!(
+            source
+                .element(tags::REFERENCED_SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "1.2.3.4.2"
+        );
+        let segment_number = first_item(&per_frame[2], tags::SEGMENT_IDENTIFICATION_SEQUENCE)
+            .element(tags::REFERENCED_SEGMENT_NUMBER)
+            .unwrap()
+            .to_int::<u16>()
+            .unwrap();
+        assert_eq!(segment_number, 2);
+
+        let segments = seg
+            .element(tags::SEGMENT_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap();
+        assert_eq!(segments.len(), 2);
+        assert_eq!(
+            segments[1]
+                .element(tags::SEGMENT_ALGORITHM_TYPE)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "AUTOMATIC"
+        );
+
+        // 3 frames of 6 pixels, 18 bits packed into 3 bytes, padded to 4
+        assert_eq!(
+            seg.element(tags::PIXEL_DATA)
+                .unwrap()
+                .to_bytes()
+                .unwrap()
+                .as_ref(),
+            &[0b1100_0110, 0, 0b0000_0001, 0][..]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_masks() {
+        let builder = SegmentationBuilder::new()
+            .source_image(&source_image("1.2.3.4.1", "0"))
+            .segment(segment("Liver"));
+
+        assert!(matches!(
+            builder.clone().segment_mask(1, 0, &[1, 0]).build(),
+            Err(Error::MaskSize {
+                expected: 6,
+                got: 2,
+                ..
+            })
+        ));
+        assert!(matches!(
+            builder.clone().label_mask(0, &[0, 0, 3, 0, 0, 0]).build(),
+            Err(Error::UnknownSegment { number: 3, .. })
+        ));
+        assert!(matches!(
+            builder.clone().segment_mask(1, 1, &[1; 6]).build(),
+            Err(Error::UnknownSource { index: 1, .. })
+        ));
+        assert!(matches!(
+            builder.label_mask(0, &[0; 6]).build(),
+            Err(Error::EmptySegmentation { .. })
+        ));
+    }
+}
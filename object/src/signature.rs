@@ -0,0 +1,912 @@
+//! Creation and verification of digital signatures,
+//! as in the Digital Signatures profile of DICOM PS3.15.
+//!
+//! A digital signature covers a selection of the root data elements
+//! of an object, recorded in an item of the _MAC Parameters Sequence_,
+//! along with the algorithm and transfer syntax
+//! with which the message authentication code (MAC) is computed.
+//! The signature proper is kept in an item of the
+//! _Digital Signatures Sequence_,
+//! next to the certificate of the signer
+//! and the date and time of signing.
+//!
+//! The MAC is a SHA-256 digest over the following elements,
+//! encoded in the MAC calculation transfer syntax,
+//! with all sequences and items of undefined length:
+//!
+//! 1. the signed data elements, in ascending tag order;
+//! 2. _MAC ID Number_, _MAC Calculation Transfer Syntax UID_,
+//!    _MAC Algorithm_ and _Data Elements Signed_
+//!    of the MAC parameters item;
+//! 3. _MAC ID Number_, _Digital Signature UID_,
+//!    _Digital Signature DateTime_, _Certificate Type_
+//!    and _Certificate of Signer_ of the digital signature item.
+//!
+//! The cryptographic operations are left to implementations
+//! of [`Signer`] and [`Verifier`],
+//! so that any RSA or ECDSA library may be plugged in.
+//! Both receive the MAC as a precomputed SHA-256 digest.
+//! Certified timestamps are not created nor verified.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_object::open_file;
+//! use dicom_object::signature::{sign, verify, BoxError, SignOptions, Signer, Verifier};
+//!
+//! struct MySigner {
+//!     certificate: Vec<u8>,
+//!     // ...
+//! }
+//!
+//! impl Signer for MySigner {
+//!     fn certificate(&self) -> &[u8] {
+//!         &self.certificate
+//!     }
+//!
+//!     fn sign(&self, mac: &[u8]) -> Result<Vec<u8>, BoxError> {
+//!         // sign the digest with the private key
+//! #       unimplemented!()
+//!     }
+//! }
+//! # struct MyVerifier;
+//! # impl Verifier for MyVerifier {
+//! #     fn verify(&self, _: &str, _: &[u8], _: &[u8], _: &[u8]) -> Result<bool, BoxError> {
+//! #         unimplemented!()
+//! #     }
+//! # }
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! # let signer = MySigner { certificate: vec![] };
+//! # let verifier = MyVerifier;
+//! let mut obj = open_file("0001.dcm")?;
+//! let signature = sign(&mut obj, &signer, SignOptions::new())?;
+//! println!("signed with {}", signature.uid);
+//!
+//! for status in verify(&obj, &verifier)? {
+//!     println!("{}: {}", status.signature.uid, if status.valid { "valid" } else { "INVALID" });
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::dictionary::{DataDictionary, TagSet};
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_parser::dataset::filter::{TokenFilter, UndefinedLengths};
+use dicom_parser::dataset::IntoTokens;
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::integrity::{ElementDigester, ElementsDigest};
+use crate::mem::InMemDicomObject;
+
+/// An error produced by a [`Signer`] or a [`Verifier`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from the object.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute does not have a value of the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// A digital signature refers to MAC parameters which do not exist.
+    #[snafu(display("No MAC parameters with MAC ID number {}", id))]
+    MissingMacParameters { id: u16, backtrace: Backtrace },
+
+    /// The MAC algorithm is not supported.
+    #[snafu(display("Unsupported MAC algorithm `{}`", algorithm))]
+    UnsupportedMacAlgorithm {
+        algorithm: String,
+        backtrace: Backtrace,
+    },
+
+    /// The MAC calculation transfer syntax is not supported.
+    #[snafu(display("Unsupported MAC calculation transfer syntax `{}`", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// The data element cannot be covered by a digital signature.
+    #[snafu(display("Data element {} cannot be signed", tag))]
+    UnsignableElement { tag: Tag, backtrace: Backtrace },
+
+    /// The MAC could not be computed.
+    #[snafu(display("Could not compute MAC"))]
+    ComputeMac {
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+
+    /// The signer failed to sign the MAC.
+    #[snafu(display("Could not sign MAC"))]
+    Sign {
+        source: BoxError,
+        backtrace: Backtrace,
+    },
+
+    /// The verifier failed to check a signature.
+    #[snafu(display("Could not verify signature {}", uid))]
+    Verify {
+        uid: String,
+        source: BoxError,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The MAC calculation transfer syntax of new signatures,
+/// Explicit VR Little Endian.
+const MAC_TRANSFER_SYNTAX: &str = "1.2.840.10008.1.2.1";
+
+/// The MAC algorithm of new signatures.
+const MAC_ALGORITHM: &str = "SHA256";
+
+/// Data elements which are never signed,
+/// as they hold the digital signatures themselves.
+const UNSIGNABLE: &[Tag] = &[
+    tags::MAC_PARAMETERS_SEQUENCE,
+    tags::DIGITAL_SIGNATURES_SEQUENCE,
+];
+
+/// Check whether a data element is left out of the signed elements
+/// even when selected, as per PS3.15 section C.12.1.1.3:
+/// group lengths and data set trailing padding.
+fn is_skipped(tag: Tag) -> bool {
+    tag.element() == 0x0000 || tag == tags::DATA_SET_TRAILING_PADDING
+}
+
+/// Attributes of a MAC parameters item covered by the MAC.
+const MAC_PARAMETERS_COVERED: &[Tag] = &[
+    tags::MACID_NUMBER,
+    tags::MAC_CALCULATION_TRANSFER_SYNTAX_UID,
+    tags::MAC_ALGORITHM,
+    tags::DATA_ELEMENTS_SIGNED,
+];
+
+/// Attributes of a digital signature item covered by the MAC.
+const SIGNATURE_COVERED: &[Tag] = &[
+    tags::MACID_NUMBER,
+    tags::DIGITAL_SIGNATURE_UID,
+    tags::DIGITAL_SIGNATURE_DATE_TIME,
+    tags::CERTIFICATE_TYPE,
+    tags::CERTIFICATE_OF_SIGNER,
+];
+
+/// A holder of a private key,
+/// able to sign the MAC of a DICOM object.
+pub trait Signer {
+    /// The type of certificate of the signer,
+    /// as recorded in _Certificate Type_.
+    ///
+    /// The default is `X509_1993_SIG`.
+    fn certificate_type(&self) -> &str {
+        "X509_1993_SIG"
+    }
+
+    /// The encoded certificate of the signer,
+    /// as recorded in _Certificate of Signer_.
+    fn certificate(&self) -> &[u8];
+
+    /// Sign the given MAC, a SHA-256 digest,
+    /// with the private key of the signer.
+    fn sign(&self, mac: &[u8]) -> Result<Vec<u8>, BoxError>;
+}
+
+/// A checker of digital signatures.
+pub trait Verifier {
+    /// Check whether the given signature of the given MAC,
+    /// a SHA-256 digest,
+    /// was made with the private key of the given certificate.
+    ///
+    /// Certificates which are not trusted should be rejected
+    /// by returning `false`.
+    /// The trailing padding byte of a DER encoded certificate
+    /// of odd length is removed before calling this method.
+    fn verify(
+        &self,
+        certificate_type: &str,
+        certificate: &[u8],
+        mac: &[u8],
+        signature: &[u8],
+    ) -> Result<bool, BoxError>;
+}
+
+/// A set of options for creating a digital signature.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct SignOptions {
+    /// the tags of the elements to sign, or all elements if `None`
+    tags: Option<TagSet>,
+    /// the digital signature UID, or a new UID if `None`
+    uid: Option<String>,
+    /// the digital signature date time, or the current time if `None`
+    date_time: Option<String>,
+}
+
+impl SignOptions {
+    /// Create a new set of options,
+    /// in which all root data elements are signed.
+    pub fn new() -> Self {
+        SignOptions::default()
+    }
+
+    /// Sign only the root data elements with the given tags.
+    ///
+    /// Tags of elements not in the object are ignored,
+    /// and so are group lengths and _Data Set Trailing Padding_,
+    /// which are never signed.
+    pub fn tags(mut self, tags: TagSet) -> Self {
+        self.tags = Some(tags);
+        self
+    }
+
+    /// Set the _Digital Signature UID_,
+    /// instead of generating a new one.
+    pub fn signature_uid(mut self, uid: impl Into<String>) -> Self {
+        self.uid = Some(uid.into());
+        self
+    }
+
+    /// Set the _Digital Signature DateTime_ (DT),
+    /// instead of using the current date and time.
+    pub fn date_time(mut self, date_time: impl Into<String>) -> Self {
+        self.date_time = Some(date_time.into());
+        self
+    }
+}
+
+/// The description of a digital signature in an object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureInfo {
+    /// the MAC ID number, shared by the MAC parameters and the signature
+    pub mac_id: u16,
+    /// the digital signature UID
+    pub uid: String,
+    /// the digital signature date time
+    pub date_time: String,
+    /// the tags of the signed data elements
+    pub tags: Vec<Tag>,
+    /// the type of certificate of the signer
+    pub certificate_type: String,
+    /// the encoded certificate of the signer
+    pub certificate: Vec<u8>,
+}
+
+/// The outcome of verifying a digital signature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureStatus {
+    /// the signature verified
+    pub signature: SignatureInfo,
+    /// whether the signature matches the current contents of the object
+    pub valid: bool,
+}
+
+/// Sign the root data elements of an object,
+/// adding an item to its MAC Parameters Sequence
+/// and to its Digital Signatures Sequence.
+///
+/// The MAC is computed with SHA-256
+/// over the elements encoded in Explicit VR Little Endian.
+/// Group lengths, _Data Set Trailing Padding_
+/// and the sequences of existing signatures are not signed.
+/// Existing signatures are kept.
+pub fn sign<D, S>(
+    obj: &mut InMemDicomObject<D>,
+    signer: &S,
+    options: SignOptions,
+) -> Result<SignatureInfo>
+where
+    D: DataDictionary + Clone,
+    S: Signer + ?Sized,
+{
+    let signed: Vec<Tag> = match &options.tags {
+        Some(selected) => {
+            if let Some(tag) = UNSIGNABLE.iter().find(|tag| selected.contains(**tag)) {
+                return UnsignableElementSnafu { tag: *tag }.fail();
+            }
+            obj.tags()
+                .filter(|tag| selected.contains(*tag) && !is_skipped(*tag))
+                .collect()
+        }
+        None => obj
+            .tags()
+            .filter(|tag| !UNSIGNABLE.contains(tag) && !is_skipped(*tag))
+            .collect(),
+    };
+
+    let mut mac_parameters = items_of(obj, tags::MAC_PARAMETERS_SEQUENCE).to_vec();
+    let mut signatures = items_of(obj, tags::DIGITAL_SIGNATURES_SEQUENCE).to_vec();
+    let mac_id = mac_parameters
+        .iter()
+        .filter_map(|item| mac_id_of(item).ok())
+        .max()
+        .unwrap_or(0)
+        + 1;
+
+    let info = SignatureInfo {
+        mac_id,
+        uid: options.uid.unwrap_or_else(crate::multiframe::new_uid),
+        date_time: options.date_time.unwrap_or_else(|| {
+            let (date, time) = crate::sr::current_date_time();
+            format!("{}{}+0000", date, time)
+        }),
+        tags: signed,
+        certificate_type: signer.certificate_type().to_string(),
+        certificate: signer.certificate().to_vec(),
+    };
+
+    let dict = obj.dict().clone();
+    let mut parameters_item = InMemDicomObject::new_empty_with_dict(dict.clone());
+    parameters_item.put(DataElement::new(
+        tags::MACID_NUMBER,
+        VR::US,
+        PrimitiveValue::from(mac_id),
+    ));
+    parameters_item.put(DataElement::new(
+        tags::MAC_CALCULATION_TRANSFER_SYNTAX_UID,
+        VR::UI,
+        PrimitiveValue::from(MAC_TRANSFER_SYNTAX),
+    ));
+    parameters_item.put(DataElement::new(
+        tags::MAC_ALGORITHM,
+        VR::CS,
+        PrimitiveValue::from(MAC_ALGORITHM),
+    ));
+    parameters_item.put(DataElement::new(
+        tags::DATA_ELEMENTS_SIGNED,
+        VR::AT,
+        PrimitiveValue::Tags(info.tags.iter().copied().collect()),
+    ));
+
+    let mut signature_item = InMemDicomObject::new_empty_with_dict(dict);
+    signature_item.put(DataElement::new(
+        tags::MACID_NUMBER,
+        VR::US,
+        PrimitiveValue::from(mac_id),
+    ));
+    signature_item.put(DataElement::new(
+        tags::DIGITAL_SIGNATURE_UID,
+        VR::UI,
+        PrimitiveValue::from(info.uid.as_str()),
+    ));
+    signature_item.put(DataElement::new(
+        tags::DIGITAL_SIGNATURE_DATE_TIME,
+        VR::DT,
+        PrimitiveValue::from(info.date_time.as_str()),
+    ));
+    signature_item.put(DataElement::new(
+        tags::CERTIFICATE_TYPE,
+        VR::CS,
+        PrimitiveValue::from(info.certificate_type.as_str()),
+    ));
+    signature_item.put(DataElement::new(
+        tags::CERTIFICATE_OF_SIGNER,
+        VR::OB,
+        PrimitiveValue::from(info.certificate.clone()),
+    ));
+
+    let mac = compute_mac(obj, &parameters_item, &signature_item)?;
+    let signature = signer.sign(mac.as_bytes()).context(SignSnafu)?;
+    signature_item.put(DataElement::new(
+        tags::SIGNATURE,
+        VR::OB,
+        PrimitiveValue::from(signature),
+    ));
+
+    mac_parameters.push(parameters_item);
+    signatures.push(signature_item);
+    obj.put(DataElement::new(
+        tags::MAC_PARAMETERS_SEQUENCE,
+        VR::SQ,
+        Value::Sequence {
+            items: mac_parameters.into(),
+            size: Length::UNDEFINED,
+        },
+    ));
+    obj.put(DataElement::new(
+        tags::DIGITAL_SIGNATURES_SEQUENCE,
+        VR::SQ,
+        Value::Sequence {
+            items: signatures.into(),
+            size: Length::UNDEFINED,
+        },
+    ));
+
+    Ok(info)
+}
+
+/// Verify all digital signatures of the root data set of an object,
+/// in the order in which they appear in its Digital Signatures Sequence.
+///
+/// An object without digital signatures yields an empty list.
+/// Signatures with a MAC algorithm other than `SHA256`,
+/// or with missing attributes, result in an error.
+pub fn verify<D, V>(obj: &InMemDicomObject<D>, verifier: &V) -> Result<Vec<SignatureStatus>>
+where
+    D: DataDictionary + Clone,
+    V: Verifier + ?Sized,
+{
+    let mac_parameters = items_of(obj, tags::MAC_PARAMETERS_SEQUENCE);
+    items_of(obj, tags::DIGITAL_SIGNATURES_SEQUENCE)
+        .iter()
+        .map(|signature_item| -> Result<SignatureStatus> {
+            let mac_id = mac_id_of(signature_item)?;
+            let parameters_item = mac_parameters
+                .iter()
+                .find(|item| mac_id_of(item).ok() == Some(mac_id))
+                .context(MissingMacParametersSnafu { id: mac_id })?;
+
+            let info = SignatureInfo {
+                mac_id,
+                uid: string_of(signature_item, tags::DIGITAL_SIGNATURE_UID)?,
+                date_time: string_of(signature_item, tags::DIGITAL_SIGNATURE_DATE_TIME)?,
+                tags: tags_of(parameters_item, tags::DATA_ELEMENTS_SIGNED)?,
+                certificate_type: string_of(signature_item, tags::CERTIFICATE_TYPE)?,
+                certificate: unpad_certificate(bytes_of(
+                    signature_item,
+                    tags::CERTIFICATE_OF_SIGNER,
+                )?),
+            };
+            let signature = bytes_of(signature_item, tags::SIGNATURE)?;
+
+            let mac = compute_mac(obj, parameters_item, signature_item)?;
+            let valid = verifier
+                .verify(
+                    &info.certificate_type,
+                    &info.certificate,
+                    mac.as_bytes(),
+                    &signature,
+                )
+                .context(VerifySnafu {
+                    uid: info.uid.clone(),
+                })?;
+            Ok(SignatureStatus {
+                signature: info,
+                valid,
+            })
+        })
+        .collect()
+}
+
+/// Compute the MAC of a digital signature,
+/// as described in the [module-level documentation](self).
+fn compute_mac<D>(
+    obj: &InMemDicomObject<D>,
+    parameters_item: &InMemDicomObject<D>,
+    signature_item: &InMemDicomObject<D>,
+) -> Result<ElementsDigest>
+where
+    D: DataDictionary + Clone,
+{
+    let algorithm = string_of(parameters_item, tags::MAC_ALGORITHM)?;
+    ensure!(
+        algorithm == MAC_ALGORITHM,
+        UnsupportedMacAlgorithmSnafu { algorithm }
+    );
+    let uid = string_of(parameters_item, tags::MAC_CALCULATION_TRANSFER_SYNTAX_UID)?;
+    let ts = TransferSyntaxRegistry
+        .get(&uid)
+        .context(UnsupportedTransferSyntaxSnafu { uid: uid.clone() })?;
+    let signed = tags_of(parameters_item, tags::DATA_ELEMENTS_SIGNED)?;
+
+    let mut digester =
+        ElementDigester::new(ts, signed.into_iter().collect()).context(ComputeMacSnafu)?;
+    UndefinedLengths::new()
+        .then(&mut digester)
+        .apply(obj.into_tokens())
+        .for_each(drop);
+    digester.set_tags(MAC_PARAMETERS_COVERED.iter().copied().collect());
+    UndefinedLengths::new()
+        .then(&mut digester)
+        .apply(parameters_item.into_tokens())
+        .for_each(drop);
+    digester.set_tags(SIGNATURE_COVERED.iter().copied().collect());
+    UndefinedLengths::new()
+        .then(&mut digester)
+        .apply(signature_item.into_tokens())
+        .for_each(drop);
+    digester.finalize().context(ComputeMacSnafu)
+}
+
+/// Retrieve the items of a sequence,
+/// or none if the sequence is not in the object.
+fn items_of<D: DataDictionary + Clone>(
+    obj: &InMemDicomObject<D>,
+    tag: Tag,
+) -> &[InMemDicomObject<D>] {
+    obj.get_sequence(tag).unwrap_or(&[])
+}
+
+fn element_of<D: DataDictionary + Clone>(
+    item: &InMemDicomObject<D>,
+    tag: Tag,
+) -> Result<&crate::mem::InMemElement<D>> {
+    item.element(tag)
+        .ok()
+        .context(MissingAttributeSnafu { tag })
+}
+
+fn mac_id_of<D: DataDictionary + Clone>(item: &InMemDicomObject<D>) -> Result<u16> {
+    let tag = tags::MACID_NUMBER;
+    element_of(item, tag)?
+        .to_int()
+        .ok()
+        .context(InvalidAttributeSnafu { tag })
+}
+
+fn string_of<D: DataDictionary + Clone>(item: &InMemDicomObject<D>, tag: Tag) -> Result<String> {
+    let value = element_of(item, tag)?
+        .to_str()
+        .ok()
+        .context(InvalidAttributeSnafu { tag })?;
+    Ok(value
+        .trim_end_matches(|c| c == '\0' || c == ' ')
+        .to_string())
+}
+
+fn bytes_of<D: DataDictionary + Clone>(item: &InMemDicomObject<D>, tag: Tag) -> Result<Vec<u8>> {
+    Ok(element_of(item, tag)?
+        .to_bytes()
+        .ok()
+        .context(InvalidAttributeSnafu { tag })?
+        .into_owned())
+}
+
+/// Remove the trailing padding byte of a DER encoded certificate
+/// of odd length, as read back from a file.
+///
+/// The length of the outer DER element tells whether the last byte
+/// is padding, so other certificates are returned unchanged.
+fn unpad_certificate(mut certificate: Vec<u8>) -> Vec<u8> {
+    let der_len = match certificate.get(..2) {
+        Some([0x30, len]) if *len < 0x80 => Some(2 + usize::from(*len)),
+        Some([0x30, len]) if (0x81..=0x84).contains(len) => {
+            let n = usize::from(len & 0x7F);
+            certificate.get(2..2 + n).map(|bytes| {
+                2 + n
+                    + bytes
+                        .iter()
+                        .fold(0_usize, |acc, b| (acc << 8) | usize::from(*b))
+            })
+        }
+        _ => None,
+    };
+    if der_len.map(|len| len + 1) == Some(certificate.len()) && certificate.last() == Some(&0) {
+        certificate.pop();
+    }
+    certificate
+}
+
+fn tags_of<D: DataDictionary + Clone>(item: &InMemDicomObject<D>, tag: Tag) -> Result<Vec<Tag>> {
+    match element_of(item, tag)?.value() {
+        Value::Primitive(PrimitiveValue::Tags(values)) => Ok(values.to_vec()),
+        Value::Primitive(PrimitiveValue::Empty) => Ok(Vec::new()),
+        _ => InvalidAttributeSnafu { tag }.fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::{OpenFileOptions, ReadPreamble};
+    use crate::FileMetaTableBuilder;
+    use dicom_transfer_syntax_registry::entries::IMPLICIT_VR_LITTLE_ENDIAN;
+    use sha2::{Digest, Sha256};
+    use smallvec::smallvec;
+
+    /// A toy signature scheme,
+    /// in which the certificate doubles as the key.
+    struct TestKey(Vec<u8>);
+
+    impl TestKey {
+        /// Create a key with a certificate
+        /// shaped like a DER encoded sequence with the given contents.
+        fn new(contents: &[u8]) -> Self {
+            let mut certificate = vec![0x30, contents.len() as u8];
+            certificate.extend_from_slice(contents);
+            TestKey(certificate)
+        }
+
+        fn signature(certificate: &[u8], mac: &[u8]) -> Vec<u8> {
+            let mut hasher = Sha256::new();
+            hasher.update(certificate);
+            hasher.update(mac);
+            hasher.finalize().to_vec()
+        }
+    }
+
+    impl Signer for TestKey {
+        fn certificate(&self) -> &[u8] {
+            &self.0
+        }
+
+        fn sign(&self, mac: &[u8]) -> Result<Vec<u8>, BoxError> {
+            assert_eq!(mac.len(), 32);
+            Ok(Self::signature(&self.0, mac))
+        }
+    }
+
+    impl Verifier for TestKey {
+        fn verify(
+            &self,
+            certificate_type: &str,
+            certificate: &[u8],
+            mac: &[u8],
+            signature: &[u8],
+        ) -> Result<bool, BoxError> {
+            if certificate_type != "X509_1993_SIG" {
+                return Err("unsupported certificate type".into());
+            }
+            Ok(certificate == &self.0[..] && signature == Self::signature(certificate, mac))
+        }
+    }
+
+    fn sample_object() -> InMemDicomObject {
+        let item = InMemDicomObject::from_element_iter(vec![DataElement::new(
+            tags::REFERENCED_SOP_INSTANCE_UID,
+            VR::UI,
+            PrimitiveValue::from("1.2.3.4"),
+        )]);
+        InMemDicomObject::from_element_iter(vec![
+            DataElement::new(
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.7"),
+            ),
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.1"),
+            ),
+            DataElement::new(
+                tags::REFERENCED_IMAGE_SEQUENCE,
+                VR::SQ,
+                Value::Sequence {
+                    items: smallvec![item],
+                    size: Length::UNDEFINED,
+                },
+            ),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+        ])
+    }
+
+    #[test]
+    fn sign_and_verify() {
+        let key = TestKey::new(b"certificate");
+        let mut obj = sample_object();
+        let info = sign(
+            &mut obj,
+            &key,
+            SignOptions::new()
+                .signature_uid("2.25.99")
+                .date_time("20240101120000+0000"),
+        )
+        .unwrap();
+        assert_eq!(info.mac_id, 1);
+        assert_eq!(
+            info.tags,
+            vec![
+                tags::SOP_CLASS_UID,
+                tags::SOP_INSTANCE_UID,
+                tags::REFERENCED_IMAGE_SEQUENCE,
+                tags::PATIENT_NAME,
+            ]
+        );
+
+        let parameters = obj.get_sequence(tags::MAC_PARAMETERS_SEQUENCE).unwrap();
+        assert_eq!(parameters.len(), 1);
+        assert_eq!(
+            string_of(&parameters[0], tags::MAC_ALGORITHM).unwrap(),
+            "SHA256"
+        );
+        assert_eq!(
+            string_of(&parameters[0], tags::MAC_CALCULATION_TRANSFER_SYNTAX_UID).unwrap(),
+            "1.2.840.10008.1.2.1"
+        );
+        let signatures = obj.get_sequence(tags::DIGITAL_SIGNATURES_SEQUENCE).unwrap();
+        assert_eq!(signatures.len(), 1);
+        assert_eq!(
+            string_of(&signatures[0], tags::DIGITAL_SIGNATURE_UID).unwrap(),
+            "2.25.99"
+        );
+        assert_eq!(bytes_of(&signatures[0], tags::SIGNATURE).unwrap().len(), 32);
+
+        let status = verify(&obj, &key).unwrap();
+        assert_eq!(
+            status,
+            vec![SignatureStatus {
+                signature: info,
+                valid: true,
+            }]
+        );
+
+        // a different key does not verify
+        let other_key = TestKey::new(b"other certificate");
+        assert!(!verify(&obj, &other_key).unwrap()[0].valid);
+
+        // changing a signed element invalidates the signature
+        let mut tampered = obj.clone();
+        tampered.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^Jane"),
+        ));
+        assert!(!verify(&tampered, &key).unwrap()[0].valid);
+
+        // and so does changing the signature attributes
+        let mut tampered = obj.clone();
+        let mut signatures = signatures.to_vec();
+        signatures[0].put(DataElement::new(
+            tags::DIGITAL_SIGNATURE_DATE_TIME,
+            VR::DT,
+            PrimitiveValue::from("20250101120000+0000"),
+        ));
+        tampered.put(DataElement::new(
+            tags::DIGITAL_SIGNATURES_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: signatures.into(),
+                size: Length::UNDEFINED,
+            },
+        ));
+        assert!(!verify(&tampered, &key).unwrap()[0].valid);
+    }
+
+    #[test]
+    fn sign_selected_elements() {
+        let key = TestKey::new(b"certificate");
+        let mut obj = sample_object();
+        let selected: TagSet = vec![tags::SOP_INSTANCE_UID, tags::PIXEL_DATA]
+            .into_iter()
+            .collect();
+        let first = sign(&mut obj, &key, SignOptions::new().tags(selected)).unwrap();
+        assert_eq!(first.tags, vec![tags::SOP_INSTANCE_UID]);
+        let second = sign(&mut obj, &key, SignOptions::new()).unwrap();
+        assert_eq!(second.mac_id, 2);
+        assert_ne!(first.uid, second.uid);
+        // previous signatures are not signed again
+        assert!(!second.tags.contains(&tags::DIGITAL_SIGNATURES_SEQUENCE));
+
+        // changing an element only covered by the second signature
+        obj.put(DataElement::new(
+            tags::PATIENT_NAME,
+            VR::PN,
+            PrimitiveValue::from("Doe^Jane"),
+        ));
+        let status = verify(&obj, &key).unwrap();
+        assert_eq!(status.len(), 2);
+        assert!(status[0].valid);
+        assert!(!status[1].valid);
+
+        let selected: TagSet = vec![tags::DIGITAL_SIGNATURES_SEQUENCE]
+            .into_iter()
+            .collect();
+        assert!(matches!(
+            sign(&mut obj, &key, SignOptions::new().tags(selected)),
+            Err(Error::UnsignableElement { .. })
+        ));
+    }
+
+    #[test]
+    fn sign_skips_group_lengths_and_padding() {
+        let key = TestKey::new(b"certificate");
+        let mut obj = sample_object();
+        obj.put(DataElement::new(
+            Tag(0x0010, 0x0000),
+            VR::UL,
+            PrimitiveValue::from(18_u32),
+        ));
+        obj.put(DataElement::new(
+            tags::DATA_SET_TRAILING_PADDING,
+            VR::OB,
+            PrimitiveValue::from(vec![0_u8; 4]),
+        ));
+
+        let info = sign(&mut obj, &key, SignOptions::new()).unwrap();
+        assert_eq!(
+            info.tags,
+            vec![
+                tags::SOP_CLASS_UID,
+                tags::SOP_INSTANCE_UID,
+                tags::REFERENCED_IMAGE_SEQUENCE,
+                tags::PATIENT_NAME,
+            ]
+        );
+
+        let selected: TagSet = "(0010,xxxx)".parse().unwrap();
+        let info = sign(&mut obj, &key, SignOptions::new().tags(selected)).unwrap();
+        assert_eq!(info.tags, vec![tags::PATIENT_NAME]);
+
+        // the padding may change without invalidating the signatures
+        obj.put(DataElement::new(
+            tags::DATA_SET_TRAILING_PADDING,
+            VR::OB,
+            PrimitiveValue::from(vec![0_u8; 8]),
+        ));
+        assert!(verify(&obj, &key).unwrap().iter().all(|s| s.valid));
+    }
+
+    #[test]
+    fn verify_after_round_trip() {
+        // odd length, so that the value is padded when written
+        let key = TestKey::new(b"certificate");
+        assert_eq!(key.0.len() % 2, 1);
+        let mut obj = sample_object();
+        let info = sign(&mut obj, &key, SignOptions::new()).unwrap();
+
+        // store in another transfer syntax
+        let file = obj
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .transfer_syntax(IMPLICIT_VR_LITTLE_ENDIAN.uid())
+                    .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                    .media_storage_sop_instance_uid("2.25.1"),
+            )
+            .unwrap();
+        let mut data = Vec::new();
+        file.write_all(&mut data).unwrap();
+        let file = OpenFileOptions::new()
+            .read_preamble(ReadPreamble::Always)
+            .from_reader(&data[..])
+            .unwrap();
+
+        let status = verify(&file, &key).unwrap();
+        assert_eq!(
+            status,
+            vec![SignatureStatus {
+                signature: info,
+                valid: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn certificate_padding() {
+        // short and long form lengths
+        assert_eq!(unpad_certificate(vec![0x30, 1, 7, 0]), vec![0x30, 1, 7]);
+        let mut long = vec![0x30, 0x81, 0x81];
+        long.extend(vec![1; 0x81]);
+        assert_eq!(unpad_certificate([&long[..], &[0]].concat()), long);
+        // no padding
+        assert_eq!(unpad_certificate(vec![0x30, 2, 7, 0]), vec![0x30, 2, 7, 0]);
+        // not DER
+        assert_eq!(unpad_certificate(vec![1, 2, 3, 0]), vec![1, 2, 3, 0]);
+        assert_eq!(unpad_certificate(vec![]), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn verify_unsupported_algorithm() {
+        let key = TestKey::new(b"certificate");
+        let mut obj = sample_object();
+        sign(&mut obj, &key, SignOptions::new()).unwrap();
+
+        let mut parameters = obj
+            .get_sequence(tags::MAC_PARAMETERS_SEQUENCE)
+            .unwrap()
+            .to_vec();
+        parameters[0].put(DataElement::new(
+            tags::MAC_ALGORITHM,
+            VR::CS,
+            PrimitiveValue::from("MD5"),
+        ));
+        obj.put(DataElement::new(
+            tags::MAC_PARAMETERS_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: parameters.into(),
+                size: Length::UNDEFINED,
+            },
+        ));
+        assert!(matches!(
+            verify(&obj, &key),
+            Err(Error::UnsupportedMacAlgorithm { .. })
+        ));
+
+        assert_eq!(verify(&sample_object(), &key).unwrap(), vec![]);
+    }
+}
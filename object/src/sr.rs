@@ -0,0 +1,1529 @@
+//! Interpretation of Structured Report (SR) documents.
+//!
+//! The content of an SR document is a tree of content items,
+//! encoded as nested _Content Sequence_ items
+//! with a _Value Type_, a _Concept Name Code Sequence_,
+//! and a value whose attributes depend on the value type.
+//! [`content_tree`] reads this tree into a [`ContentItem`],
+//! holding a typed [`ContentValue`] and its children,
+//! which can then be traversed and queried
+//! by concept name without navigating the sequences by hand.
+//! Content trees can also be written back
+//! with [`ContentItem::to_dataset`],
+//! and [`MeasurementReportBuilder`] creates complete
+//! _Imaging Measurement Report_ documents (TID 1500).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::sr::{content_tree, ContentValue};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("measurements_sr.dcm")?;
+//! let root = content_tree(&obj)?;
+//! println!("{}", root.concept_name().map(|c| c.meaning.as_str()).unwrap_or("?"));
+//!
+//! // list all numeric measurements in the document
+//! for item in root.measurements() {
+//!     if let ContentValue::Num { value, units } = item.value() {
+//!         println!(
+//!             "{}: {:?} {}",
+//!             item.concept_name().map(|c| c.meaning.as_str()).unwrap_or("?"),
+//!             value,
+//!             units.as_ref().map(|u| u.value.as_str()).unwrap_or(""),
+//!         );
+//!     }
+//! }
+//!
+//! // look up a finding by its concept name (code value and scheme)
+//! if let Some(finding) = root.find("121071", "DCM") {
+//!     println!("{:?}", finding.value());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Create a measurement report for a lesion in an image:
+//!
+//! ```no_run
+//! use dicom_object::code::CodedConcept;
+//! use dicom_object::open_file;
+//! use dicom_object::sr::{MeasurementGroup, MeasurementReportBuilder, SopReference};
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let image = open_file("ct_0001.dcm")?;
+//! let report = MeasurementReportBuilder::new()
+//!     .source(&image)
+//!     .procedure_reported(CodedConcept::new("77477000", "SCT", "CT"))
+//!     .person_observer("Doe^Jane")
+//!     .measurement_group(
+//!         MeasurementGroup::new("Lesion 1")
+//!             .finding(CodedConcept::new("52988006", "SCT", "Lesion"))
+//!             .source_image(SopReference {
+//!                 sop_class_uid: "1.2.840.10008.5.1.4.1.1.2".to_string(),
+//!                 sop_instance_uid: "1.2.3.4.5".to_string(),
+//!                 frames: vec![],
+//!             })
+//!             .measurement(
+//!                 CodedConcept::new("410668003", "SCT", "Length"),
+//!                 12.5,
+//!                 CodedConcept::new("mm", "UCUM", "millimeter"),
+//!             ),
+//!     )
+//!     .build()?;
+//! report.write_to_file("report.dcm")?;
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Length, Tag, VR};
+use dicom_dictionary_std::tags;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::code::CodedConcept;
+use crate::mem::InMemElement;
+use crate::multiframe::new_uid;
+use crate::{FileDicomObject, FileMetaTableBuilder, InMemDicomObject};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from a content item.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// The report is missing content required by its template.
+    #[snafu(display("Incomplete report: {}", reason))]
+    IncompleteReport {
+        reason: &'static str,
+        backtrace: Backtrace,
+    },
+
+    /// The file meta group of the new document could not be built.
+    #[snafu(display("Could not build file meta group"))]
+    BuildMeta {
+        #[snafu(backtrace)]
+        source: crate::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The relationship between a content item and its parent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum RelationshipType {
+    /// `CONTAINS`
+    Contains,
+    /// `HAS PROPERTIES`
+    HasProperties,
+    /// `HAS OBS CONTEXT`
+    HasObsContext,
+    /// `HAS ACQ CONTEXT`
+    HasAcqContext,
+    /// `INFERRED FROM`
+    InferredFrom,
+    /// `SELECTED FROM`
+    SelectedFrom,
+    /// `HAS CONCEPT MOD`
+    HasConceptMod,
+    /// Any other relationship type.
+    Other(String),
+}
+
+impl RelationshipType {
+    fn from_value(value: &str) -> Self {
+        match value {
+            "CONTAINS" => RelationshipType::Contains,
+            "HAS PROPERTIES" => RelationshipType::HasProperties,
+            "HAS OBS CONTEXT" => RelationshipType::HasObsContext,
+            "HAS ACQ CONTEXT" => RelationshipType::HasAcqContext,
+            "INFERRED FROM" => RelationshipType::InferredFrom,
+            "SELECTED FROM" => RelationshipType::SelectedFrom,
+            "HAS CONCEPT MOD" => RelationshipType::HasConceptMod,
+            other => RelationshipType::Other(other.to_string()),
+        }
+    }
+
+    /// The relationship type as written in _Relationship Type_.
+    pub fn as_str(&self) -> &str {
+        match self {
+            RelationshipType::Contains => "CONTAINS",
+            RelationshipType::HasProperties => "HAS PROPERTIES",
+            RelationshipType::HasObsContext => "HAS OBS CONTEXT",
+            RelationshipType::HasAcqContext => "HAS ACQ CONTEXT",
+            RelationshipType::InferredFrom => "INFERRED FROM",
+            RelationshipType::SelectedFrom => "SELECTED FROM",
+            RelationshipType::HasConceptMod => "HAS CONCEPT MOD",
+            RelationshipType::Other(other) => other,
+        }
+    }
+}
+
+/// A reference to a composite object,
+/// from the _Referenced SOP Sequence_ of a content item.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SopReference {
+    /// The referenced SOP class UID.
+    pub sop_class_uid: String,
+    /// The referenced SOP instance UID.
+    pub sop_instance_uid: String,
+    /// The referenced frame numbers, if any.
+    pub frames: Vec<i32>,
+}
+
+/// The value of a content item,
+/// interpreted according to its _Value Type_.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ContentValue {
+    /// `CONTAINER`: a grouping of the child content items.
+    Container {
+        /// Whether the children are to be interpreted
+        /// as a continuous text (`CONTINUOUS`)
+        /// rather than as separate items (`SEPARATE`).
+        continuous: bool,
+        /// The identifier of the template
+        /// followed by the container, if any.
+        template: Option<String>,
+    },
+    /// `TEXT`: free text.
+    Text(String),
+    /// `CODE`: a coded concept.
+    Code(CodedConcept),
+    /// `NUM`: a numeric measurement.
+    Num {
+        /// The measured value,
+        /// or `None` if the measurement is absent.
+        value: Option<f64>,
+        /// The units of measurement.
+        units: Option<CodedConcept>,
+    },
+    /// `DATETIME`: a date and time, in the DT format.
+    DateTime(String),
+    /// `DATE`: a date, in the DA format.
+    Date(String),
+    /// `TIME`: a time, in the TM format.
+    Time(String),
+    /// `PNAME`: a person name.
+    PersonName(String),
+    /// `UIDREF`: a unique identifier.
+    UidRef(String),
+    /// `COMPOSITE`: a reference to a composite object.
+    Composite(SopReference),
+    /// `IMAGE`: a reference to an image.
+    Image(SopReference),
+    /// `WAVEFORM`: a reference to a waveform.
+    Waveform(SopReference),
+    /// `SCOORD`: spatial coordinates in an image.
+    SpatialCoordinates {
+        /// The graphic type, such as `POINT` or `POLYLINE`.
+        graphic_type: String,
+        /// The column and row pairs of the coordinates.
+        data: Vec<f32>,
+    },
+    /// `SCOORD3D`: spatial coordinates in a frame of reference.
+    SpatialCoordinates3D {
+        /// The graphic type, such as `POINT` or `POLYGON`.
+        graphic_type: String,
+        /// The x, y, and z triplets of the coordinates.
+        data: Vec<f32>,
+        /// The frame of reference of the coordinates.
+        frame_of_reference_uid: String,
+    },
+    /// A by-reference relationship
+    /// to another content item in the same document,
+    /// identified by its position in the tree
+    /// (see _Referenced Content Item Identifier_).
+    ByReference(Vec<u32>),
+    /// Any other value type,
+    /// which is not interpreted.
+    Other {
+        /// The value type of the content item.
+        value_type: String,
+    },
+}
+
+/// A content item of an SR document,
+/// along with its children.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContentItem {
+    relationship: Option<RelationshipType>,
+    concept_name: Option<CodedConcept>,
+    value: ContentValue,
+    children: Vec<ContentItem>,
+}
+
+impl ContentItem {
+    /// Create a content item without children.
+    ///
+    /// The relationship type should be `None` only for the root item.
+    pub fn new(
+        relationship: Option<RelationshipType>,
+        concept_name: Option<CodedConcept>,
+        value: ContentValue,
+    ) -> Self {
+        ContentItem {
+            relationship,
+            concept_name,
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// Add a child content item, returning the modified item.
+    pub fn with_child(mut self, child: ContentItem) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Add a child content item.
+    pub fn push_child(&mut self, child: ContentItem) {
+        self.children.push(child);
+    }
+
+    /// Read a content item and its children from a data set.
+    ///
+    /// The data set is either the root of an SR document
+    /// or an item of a _Content Sequence_.
+    pub fn from_dataset<D>(obj: &InMemDicomObject<D>) -> Result<Self>
+    where
+        D: DataDictionary + Clone,
+    {
+        let relationship =
+            opt_string(obj, tags::RELATIONSHIP_TYPE).map(|r| RelationshipType::from_value(&r));
+        let concept_name = CodedConcept::from_sequence(obj, tags::CONCEPT_NAME_CODE_SEQUENCE);
+
+        let value = match opt_string(obj, tags::VALUE_TYPE) {
+            Some(value_type) => read_value(obj, &value_type)?,
+            None => {
+                let ids = obj
+                    .element_opt(tags::REFERENCED_CONTENT_ITEM_IDENTIFIER)
+                    .ok()
+                    .flatten()
+                    .context(MissingAttributeSnafu {
+                        tag: tags::VALUE_TYPE,
+                    })?;
+                ContentValue::ByReference(ids.to_multi_int().context(InvalidAttributeSnafu {
+                    tag: tags::REFERENCED_CONTENT_ITEM_IDENTIFIER,
+                })?)
+            }
+        };
+
+        let children = obj
+            .element_opt(tags::CONTENT_SEQUENCE)
+            .ok()
+            .flatten()
+            .and_then(|e| e.items())
+            .unwrap_or(&[])
+            .iter()
+            .map(ContentItem::from_dataset)
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ContentItem {
+            relationship,
+            concept_name,
+            value,
+            children,
+        })
+    }
+
+    /// The relationship of this item with its parent,
+    /// or `None` for the root item.
+    pub fn relationship(&self) -> Option<&RelationshipType> {
+        self.relationship.as_ref()
+    }
+
+    /// The concept name of this item, if any.
+    pub fn concept_name(&self) -> Option<&CodedConcept> {
+        self.concept_name.as_ref()
+    }
+
+    /// The value of this item.
+    pub fn value(&self) -> &ContentValue {
+        &self.value
+    }
+
+    /// The direct children of this item.
+    pub fn children(&self) -> &[ContentItem] {
+        &self.children
+    }
+
+    /// Check whether the concept name of this item
+    /// has the given code value and coding scheme designator.
+    pub fn has_concept(&self, value: &str, scheme: &str) -> bool {
+        self.concept_name
+            .as_ref()
+            .map(|c| c.matches(value, scheme))
+            .unwrap_or(false)
+    }
+
+    /// Iterate over this item and all of its descendants,
+    /// in depth-first pre-order.
+    pub fn iter(&self) -> Iter<'_> {
+        Iter { stack: vec![self] }
+    }
+
+    /// Retrieve the content item at the given position in the tree,
+    /// as in a _Referenced Content Item Identifier_:
+    /// the first number identifies this item,
+    /// and each following number is the 1-based index
+    /// of a child in the previous item.
+    pub fn get_by_id(&self, id: &[u32]) -> Option<&ContentItem> {
+        let (first, rest) = id.split_first()?;
+        if *first != 1 {
+            return None;
+        }
+        rest.iter().try_fold(self, |item, &i| {
+            item.children.get((i as usize).checked_sub(1)?)
+        })
+    }
+
+    /// Find the first item in the tree (including this one)
+    /// with the given concept name.
+    pub fn find(&self, value: &str, scheme: &str) -> Option<&ContentItem> {
+        self.iter().find(|item| item.has_concept(value, scheme))
+    }
+
+    /// Find all items in the tree (including this one)
+    /// with the given concept name.
+    pub fn find_all<'a>(
+        &'a self,
+        value: &'a str,
+        scheme: &'a str,
+    ) -> impl Iterator<Item = &'a ContentItem> + 'a {
+        self.iter()
+            .filter(move |item| item.has_concept(value, scheme))
+    }
+
+    /// Iterate over all numeric measurement items in the tree.
+    pub fn measurements(&self) -> impl Iterator<Item = &ContentItem> + '_ {
+        self.iter()
+            .filter(|item| matches!(item.value, ContentValue::Num { .. }))
+    }
+
+    /// Retrieve the text value of this item,
+    /// if it is a `TEXT` item.
+    pub fn as_text(&self) -> Option<&str> {
+        match &self.value {
+            ContentValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the coded value of this item,
+    /// if it is a `CODE` item.
+    pub fn as_code(&self) -> Option<&CodedConcept> {
+        match &self.value {
+            ContentValue::Code(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Retrieve the measured value of this item,
+    /// if it is a `NUM` item with a measurement.
+    pub fn as_num(&self) -> Option<f64> {
+        match &self.value {
+            ContentValue::Num { value, .. } => *value,
+            _ => None,
+        }
+    }
+
+    /// Write this content item and its children into a data set,
+    /// which can be used as the root of an SR document
+    /// or as an item of a _Content Sequence_.
+    pub fn to_dataset(&self) -> InMemDicomObject {
+        let mut obj = InMemDicomObject::new_empty();
+        if let Some(relationship) = &self.relationship {
+            obj.put(cs(tags::RELATIONSHIP_TYPE, relationship.as_str()));
+        }
+        if let Some(code) = &self.concept_name {
+            obj.put(code.to_sequence_element(tags::CONCEPT_NAME_CODE_SEQUENCE));
+        }
+        write_value(&mut obj, &self.value);
+        if !self.children.is_empty() {
+            obj.put(sequence(
+                tags::CONTENT_SEQUENCE,
+                self.children.iter().map(ContentItem::to_dataset).collect(),
+            ));
+        }
+        obj
+    }
+}
+
+/// A depth-first iterator over the content items of a tree.
+///
+/// See [`ContentItem::iter`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a> {
+    stack: Vec<&'a ContentItem>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = &'a ContentItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.stack.pop()?;
+        self.stack.extend(item.children.iter().rev());
+        Some(item)
+    }
+}
+
+/// Read the content tree of an SR document,
+/// starting from the root content item at the top level of the object.
+pub fn content_tree<D>(obj: &InMemDicomObject<D>) -> Result<ContentItem>
+where
+    D: DataDictionary + Clone,
+{
+    ContentItem::from_dataset(obj)
+}
+
+fn read_value<D>(obj: &InMemDicomObject<D>, value_type: &str) -> Result<ContentValue>
+where
+    D: DataDictionary + Clone,
+{
+    Ok(match value_type {
+        "CONTAINER" => ContentValue::Container {
+            continuous: opt_string(obj, tags::CONTINUITY_OF_CONTENT).as_deref()
+                == Some("CONTINUOUS"),
+            template: first_item(obj, tags::CONTENT_TEMPLATE_SEQUENCE)
+                .and_then(|item| opt_string(item, tags::TEMPLATE_IDENTIFIER)),
+        },
+        "TEXT" => ContentValue::Text(string(obj, tags::TEXT_VALUE)?),
+        "CODE" => ContentValue::Code(
+            CodedConcept::from_sequence(obj, tags::CONCEPT_CODE_SEQUENCE).context(
+                MissingAttributeSnafu {
+                    tag: tags::CONCEPT_CODE_SEQUENCE,
+                },
+            )?,
+        ),
+        "NUM" => match first_item(obj, tags::MEASURED_VALUE_SEQUENCE) {
+            Some(measured) => ContentValue::Num {
+                value: match measured.element_opt(tags::NUMERIC_VALUE).ok().flatten() {
+                    Some(e) => Some(e.to_float64().context(InvalidAttributeSnafu {
+                        tag: tags::NUMERIC_VALUE,
+                    })?),
+                    None => None,
+                },
+                units: CodedConcept::from_sequence(measured, tags::MEASUREMENT_UNITS_CODE_SEQUENCE),
+            },
+            None => ContentValue::Num {
+                value: None,
+                units: None,
+            },
+        },
+        "DATETIME" => ContentValue::DateTime(string(obj, tags::DATE_TIME)?),
+        "DATE" => ContentValue::Date(string(obj, tags::DATE)?),
+        "TIME" => ContentValue::Time(string(obj, tags::TIME)?),
+        "PNAME" => ContentValue::PersonName(string(obj, tags::PERSON_NAME)?),
+        "UIDREF" => ContentValue::UidRef(string(obj, tags::UID)?),
+        "COMPOSITE" => ContentValue::Composite(sop_reference(obj)?),
+        "IMAGE" => ContentValue::Image(sop_reference(obj)?),
+        "WAVEFORM" => ContentValue::Waveform(sop_reference(obj)?),
+        "SCOORD" => ContentValue::SpatialCoordinates {
+            graphic_type: string(obj, tags::GRAPHIC_TYPE)?,
+            data: graphic_data(obj)?,
+        },
+        "SCOORD3D" => ContentValue::SpatialCoordinates3D {
+            graphic_type: string(obj, tags::GRAPHIC_TYPE)?,
+            data: graphic_data(obj)?,
+            frame_of_reference_uid: string(obj, tags::REFERENCED_FRAME_OF_REFERENCE_UID)?,
+        },
+        other => ContentValue::Other {
+            value_type: other.to_string(),
+        },
+    })
+}
+
+fn write_value(obj: &mut InMemDicomObject, value: &ContentValue) {
+    let value_type = match value {
+        ContentValue::Container {
+            continuous,
+            template,
+        } => {
+            let continuity = if *continuous {
+                "CONTINUOUS"
+            } else {
+                "SEPARATE"
+            };
+            obj.put(cs(tags::CONTINUITY_OF_CONTENT, continuity));
+            if let Some(template) = template {
+                obj.put(template_sequence(template));
+            }
+            "CONTAINER"
+        }
+        ContentValue::Text(text) => {
+            obj.put(DataElement::new(
+                tags::TEXT_VALUE,
+                VR::UT,
+                PrimitiveValue::from(text.as_str()),
+            ));
+            "TEXT"
+        }
+        ContentValue::Code(code) => {
+            obj.put(code.to_sequence_element(tags::CONCEPT_CODE_SEQUENCE));
+            "CODE"
+        }
+        ContentValue::Num { value, units } => {
+            let mut measured = InMemDicomObject::new_empty();
+            if let Some(value) = value {
+                measured.put(DataElement::new(
+                    tags::NUMERIC_VALUE,
+                    VR::DS,
+                    PrimitiveValue::from(value.to_string()),
+                ));
+            }
+            if let Some(units) = units {
+                measured.put(units.to_sequence_element(tags::MEASUREMENT_UNITS_CODE_SEQUENCE));
+            }
+            let items = if value.is_some() || units.is_some() {
+                vec![measured]
+            } else {
+                vec![]
+            };
+            obj.put(sequence(tags::MEASURED_VALUE_SEQUENCE, items));
+            "NUM"
+        }
+        ContentValue::DateTime(value) => {
+            obj.put(DataElement::new(
+                tags::DATE_TIME,
+                VR::DT,
+                PrimitiveValue::from(value.as_str()),
+            ));
+            "DATETIME"
+        }
+        ContentValue::Date(value) => {
+            obj.put(DataElement::new(
+                tags::DATE,
+                VR::DA,
+                PrimitiveValue::from(value.as_str()),
+            ));
+            "DATE"
+        }
+        ContentValue::Time(value) => {
+            obj.put(DataElement::new(
+                tags::TIME,
+                VR::TM,
+                PrimitiveValue::from(value.as_str()),
+            ));
+            "TIME"
+        }
+        ContentValue::PersonName(value) => {
+            obj.put(DataElement::new(
+                tags::PERSON_NAME,
+                VR::PN,
+                PrimitiveValue::from(value.as_str()),
+            ));
+            "PNAME"
+        }
+        ContentValue::UidRef(value) => {
+            obj.put(DataElement::new(
+                tags::UID,
+                VR::UI,
+                PrimitiveValue::from(value.as_str()),
+            ));
+            "UIDREF"
+        }
+        ContentValue::Composite(reference) => {
+            obj.put(sop_reference_sequence(reference));
+            "COMPOSITE"
+        }
+        ContentValue::Image(reference) => {
+            obj.put(sop_reference_sequence(reference));
+            "IMAGE"
+        }
+        ContentValue::Waveform(reference) => {
+            obj.put(sop_reference_sequence(reference));
+            "WAVEFORM"
+        }
+        ContentValue::SpatialCoordinates { graphic_type, data } => {
+            obj.put(cs(tags::GRAPHIC_TYPE, graphic_type));
+            obj.put(DataElement::new(
+                tags::GRAPHIC_DATA,
+                VR::FL,
+                PrimitiveValue::F32(data.iter().copied().collect()),
+            ));
+            "SCOORD"
+        }
+        ContentValue::SpatialCoordinates3D {
+            graphic_type,
+            data,
+            frame_of_reference_uid,
+        } => {
+            obj.put(cs(tags::GRAPHIC_TYPE, graphic_type));
+            obj.put(DataElement::new(
+                tags::GRAPHIC_DATA,
+                VR::FL,
+                PrimitiveValue::F32(data.iter().copied().collect()),
+            ));
+            obj.put(DataElement::new(
+                tags::REFERENCED_FRAME_OF_REFERENCE_UID,
+                VR::UI,
+                PrimitiveValue::from(frame_of_reference_uid.as_str()),
+            ));
+            "SCOORD3D"
+        }
+        ContentValue::ByReference(ids) => {
+            obj.put(DataElement::new(
+                tags::REFERENCED_CONTENT_ITEM_IDENTIFIER,
+                VR::UL,
+                PrimitiveValue::U32(ids.iter().copied().collect()),
+            ));
+            // by-reference items have no value type
+            return;
+        }
+        ContentValue::Other { value_type } => value_type,
+    };
+    obj.put(cs(tags::VALUE_TYPE, value_type));
+}
+
+fn sop_reference_sequence(reference: &SopReference) -> InMemElement {
+    let mut item = InMemDicomObject::new_empty();
+    item.put(DataElement::new(
+        tags::REFERENCED_SOP_CLASS_UID,
+        VR::UI,
+        PrimitiveValue::from(reference.sop_class_uid.as_str()),
+    ));
+    item.put(DataElement::new(
+        tags::REFERENCED_SOP_INSTANCE_UID,
+        VR::UI,
+        PrimitiveValue::from(reference.sop_instance_uid.as_str()),
+    ));
+    if !reference.frames.is_empty() {
+        item.put(DataElement::new(
+            tags::REFERENCED_FRAME_NUMBER,
+            VR::IS,
+            PrimitiveValue::Strs(reference.frames.iter().map(|f| f.to_string()).collect()),
+        ));
+    }
+    sequence(tags::REFERENCED_SOP_SEQUENCE, vec![item])
+}
+
+/// Create a _Content Template Sequence_
+/// identifying a template of the DICOM Content Mapping Resource.
+fn template_sequence(template: &str) -> InMemElement {
+    let mut item = InMemDicomObject::new_empty();
+    item.put(cs(tags::MAPPING_RESOURCE, "DCMR"));
+    item.put(cs(tags::TEMPLATE_IDENTIFIER, template));
+    sequence(tags::CONTENT_TEMPLATE_SEQUENCE, vec![item])
+}
+
+fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> InMemElement {
+    DataElement::new(
+        tag,
+        VR::SQ,
+        Value::Sequence {
+            items: C::from_vec(items),
+            size: Length::UNDEFINED,
+        },
+    )
+}
+
+fn cs(tag: Tag, value: &str) -> InMemElement {
+    DataElement::new(tag, VR::CS, PrimitiveValue::from(value))
+}
+
+fn sop_reference<D>(obj: &InMemDicomObject<D>) -> Result<SopReference>
+where
+    D: DataDictionary + Clone,
+{
+    let item = first_item(obj, tags::REFERENCED_SOP_SEQUENCE).context(MissingAttributeSnafu {
+        tag: tags::REFERENCED_SOP_SEQUENCE,
+    })?;
+    let frames = match item
+        .element_opt(tags::REFERENCED_FRAME_NUMBER)
+        .ok()
+        .flatten()
+    {
+        Some(e) => e.to_multi_int().context(InvalidAttributeSnafu {
+            tag: tags::REFERENCED_FRAME_NUMBER,
+        })?,
+        None => Vec::new(),
+    };
+    Ok(SopReference {
+        sop_class_uid: string(item, tags::REFERENCED_SOP_CLASS_UID)?,
+        sop_instance_uid: string(item, tags::REFERENCED_SOP_INSTANCE_UID)?,
+        frames,
+    })
+}
+
+fn graphic_data<D>(obj: &InMemDicomObject<D>) -> Result<Vec<f32>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tags::GRAPHIC_DATA)
+        .ok()
+        .flatten()
+        .context(MissingAttributeSnafu {
+            tag: tags::GRAPHIC_DATA,
+        })?
+        .to_multi_float32()
+        .context(InvalidAttributeSnafu {
+            tag: tags::GRAPHIC_DATA,
+        })
+}
+
+fn first_item<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<&InMemDicomObject<D>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag)
+        .ok()
+        .flatten()
+        .and_then(|e| e.items())
+        .and_then(|items| items.first())
+}
+
+fn opt_string<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<String>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag)
+        .ok()
+        .flatten()
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.into_owned())
+}
+
+fn string<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Result<String>
+where
+    D: DataDictionary + Clone,
+{
+    opt_string(obj, tag).context(MissingAttributeSnafu { tag })
+}
+
+/// The SOP class UID of _Comprehensive SR Storage_.
+pub const COMPREHENSIVE_SR_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.88.33";
+
+/// Attributes of the _Patient_ and _General Study_ modules
+/// copied from the source object of a derived document.
+pub(crate) const PATIENT_STUDY_ATTRIBUTES: &[(Tag, VR)] = &[
+    (tags::PATIENT_NAME, VR::PN),
+    (tags::PATIENT_ID, VR::LO),
+    (tags::PATIENT_BIRTH_DATE, VR::DA),
+    (tags::PATIENT_SEX, VR::CS),
+    (tags::STUDY_INSTANCE_UID, VR::UI),
+    (tags::STUDY_DATE, VR::DA),
+    (tags::STUDY_TIME, VR::TM),
+    (tags::REFERRING_PHYSICIAN_NAME, VR::PN),
+    (tags::STUDY_ID, VR::SH),
+    (tags::ACCESSION_NUMBER, VR::SH),
+];
+
+/// A measurement group of an imaging measurement report (TID 1501),
+/// holding the measurements of a single tracked finding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MeasurementGroup {
+    tracking_identifier: String,
+    tracking_uid: Option<String>,
+    finding: Option<CodedConcept>,
+    finding_site: Option<CodedConcept>,
+    source_images: Vec<SopReference>,
+    measurements: Vec<(CodedConcept, f64, CodedConcept)>,
+}
+
+impl MeasurementGroup {
+    /// Create a measurement group
+    /// with the given human readable tracking identifier.
+    pub fn new(tracking_identifier: impl Into<String>) -> Self {
+        MeasurementGroup {
+            tracking_identifier: tracking_identifier.into(),
+            tracking_uid: None,
+            finding: None,
+            finding_site: None,
+            source_images: Vec::new(),
+            measurements: Vec::new(),
+        }
+    }
+
+    /// Set the tracking unique identifier of the finding.
+    ///
+    /// A new UID is generated if none is given.
+    pub fn tracking_uid(mut self, uid: impl Into<String>) -> Self {
+        self.tracking_uid = Some(uid.into());
+        self
+    }
+
+    /// Set the type of finding, such as `(52988006, SCT, "Lesion")`.
+    pub fn finding(mut self, finding: CodedConcept) -> Self {
+        self.finding = Some(finding);
+        self
+    }
+
+    /// Set the anatomic location of the finding.
+    pub fn finding_site(mut self, site: CodedConcept) -> Self {
+        self.finding_site = Some(site);
+        self
+    }
+
+    /// Add a reference to an image in which the measurements were made.
+    pub fn source_image(mut self, image: SopReference) -> Self {
+        self.source_images.push(image);
+        self
+    }
+
+    /// Add a numeric measurement (TID 300)
+    /// with the given concept name, value, and units.
+    pub fn measurement(mut self, concept: CodedConcept, value: f64, units: CodedConcept) -> Self {
+        self.measurements.push((concept, value, units));
+        self
+    }
+
+    fn to_content_item(&self) -> ContentItem {
+        let mut group = ContentItem::new(
+            Some(RelationshipType::Contains),
+            Some(CodedConcept::new("125007", "DCM", "Measurement Group")),
+            ContentValue::Container {
+                continuous: false,
+                template: None,
+            },
+        )
+        .with_child(ContentItem::new(
+            Some(RelationshipType::HasObsContext),
+            Some(CodedConcept::new("112039", "DCM", "Tracking Identifier")),
+            ContentValue::Text(self.tracking_identifier.clone()),
+        ))
+        .with_child(ContentItem::new(
+            Some(RelationshipType::HasObsContext),
+            Some(CodedConcept::new(
+                "112040",
+                "DCM",
+                "Tracking Unique Identifier",
+            )),
+            ContentValue::UidRef(self.tracking_uid.clone().unwrap_or_else(new_uid)),
+        ));
+        if let Some(finding) = &self.finding {
+            group.push_child(ContentItem::new(
+                Some(RelationshipType::Contains),
+                Some(CodedConcept::new("121071", "DCM", "Finding")),
+                ContentValue::Code(finding.clone()),
+            ));
+        }
+        if let Some(site) = &self.finding_site {
+            group.push_child(ContentItem::new(
+                Some(RelationshipType::HasConceptMod),
+                Some(CodedConcept::new("363698007", "SCT", "Finding Site")),
+                ContentValue::Code(site.clone()),
+            ));
+        }
+        for image in &self.source_images {
+            group.push_child(ContentItem::new(
+                Some(RelationshipType::Contains),
+                Some(CodedConcept::new("260753009", "SCT", "Source")),
+                ContentValue::Image(image.clone()),
+            ));
+        }
+        for (concept, value, units) in &self.measurements {
+            group.push_child(ContentItem::new(
+                Some(RelationshipType::Contains),
+                Some(concept.clone()),
+                ContentValue::Num {
+                    value: Some(*value),
+                    units: Some(units.clone()),
+                },
+            ));
+        }
+        group
+    }
+}
+
+/// The observer of a measurement report (TID 1002).
+#[derive(Debug, Clone, PartialEq)]
+enum Observer {
+    Person(String),
+    Device(String),
+}
+
+/// A builder for _Imaging Measurement Report_ SR documents (TID 1500).
+///
+/// The document is created as a _Comprehensive SR_ object,
+/// with the root container identified as template 1500,
+/// the language of content, the observer context,
+/// the procedures reported,
+/// and an _Imaging Measurements_ container
+/// with one measurement group per [`MeasurementGroup`].
+#[derive(Debug, Clone)]
+pub struct MeasurementReportBuilder {
+    source: Option<InMemDicomObject>,
+    procedures: Vec<CodedConcept>,
+    observer: Option<Observer>,
+    language: CodedConcept,
+    groups: Vec<MeasurementGroup>,
+    content_date_time: Option<(String, String)>,
+    series_number: i32,
+    instance_number: i32,
+}
+
+impl Default for MeasurementReportBuilder {
+    fn default() -> Self {
+        MeasurementReportBuilder {
+            source: None,
+            procedures: Vec::new(),
+            observer: None,
+            language: CodedConcept::new("en-US", "RFC5646", "English (United States)"),
+            groups: Vec::new(),
+            content_date_time: None,
+            series_number: 1,
+            instance_number: 1,
+        }
+    }
+}
+
+impl MeasurementReportBuilder {
+    /// Create a new measurement report builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy the patient and study attributes of the report
+    /// from the given object,
+    /// usually one of the images measured.
+    ///
+    /// Without a source object,
+    /// the patient attributes are left empty
+    /// and a new study instance UID is generated.
+    pub fn source<D>(mut self, obj: &InMemDicomObject<D>) -> Self
+    where
+        D: DataDictionary + Clone,
+    {
+        let mut source = InMemDicomObject::new_empty();
+        for &(tag, vr) in PATIENT_STUDY_ATTRIBUTES {
+            if let Ok(Some(e)) = obj.element_opt(tag) {
+                if let Value::Primitive(value) = e.value() {
+                    source.put(DataElement::new(tag, vr, value.clone()));
+                }
+            }
+        }
+        self.source = Some(source);
+        self
+    }
+
+    /// Add a procedure reported,
+    /// such as `(77477000, SCT, "CT")`.
+    /// At least one is required.
+    pub fn procedure_reported(mut self, procedure: CodedConcept) -> Self {
+        self.procedures.push(procedure);
+        self
+    }
+
+    /// Identify the observer as the person with the given name.
+    pub fn person_observer(mut self, name: impl Into<String>) -> Self {
+        self.observer = Some(Observer::Person(name.into()));
+        self
+    }
+
+    /// Identify the observer as the device with the given UID.
+    ///
+    /// If no observer is given,
+    /// the device observer is identified by
+    /// the [implementation class UID](crate::IMPLEMENTATION_CLASS_UID) of DICOM-rs.
+    pub fn device_observer(mut self, uid: impl Into<String>) -> Self {
+        self.observer = Some(Observer::Device(uid.into()));
+        self
+    }
+
+    /// Set the language of the report content.
+    /// The default is `(en-US, RFC5646, "English (United States)")`.
+    pub fn language(mut self, language: CodedConcept) -> Self {
+        self.language = language;
+        self
+    }
+
+    /// Add a measurement group.
+    pub fn measurement_group(mut self, group: MeasurementGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
+    /// Set the content date (DA) and time (TM) of the report.
+    ///
+    /// If not set, the current time in UTC is used.
+    pub fn content_date_time(mut self, date: impl Into<String>, time: impl Into<String>) -> Self {
+        self.content_date_time = Some((date.into(), time.into()));
+        self
+    }
+
+    /// Set the series number of the report. The default is 1.
+    pub fn series_number(mut self, number: i32) -> Self {
+        self.series_number = number;
+        self
+    }
+
+    /// Set the instance number of the report. The default is 1.
+    pub fn instance_number(mut self, number: i32) -> Self {
+        self.instance_number = number;
+        self
+    }
+
+    /// Build the content tree of the report.
+    pub fn build_content(&self) -> Result<ContentItem> {
+        ensure!(
+            !self.procedures.is_empty(),
+            IncompleteReportSnafu {
+                reason: "at least one procedure reported is required",
+            }
+        );
+
+        let mut root = ContentItem::new(
+            None,
+            Some(CodedConcept::new(
+                "126000",
+                "DCM",
+                "Imaging Measurement Report",
+            )),
+            ContentValue::Container {
+                continuous: false,
+                template: Some("1500".to_string()),
+            },
+        )
+        .with_child(ContentItem::new(
+            Some(RelationshipType::HasConceptMod),
+            Some(CodedConcept::new(
+                "121049",
+                "DCM",
+                "Language of Content Item and Descendants",
+            )),
+            ContentValue::Code(self.language.clone()),
+        ));
+
+        match &self.observer {
+            Some(Observer::Person(name)) => {
+                root.push_child(ContentItem::new(
+                    Some(RelationshipType::HasObsContext),
+                    Some(CodedConcept::new("121005", "DCM", "Observer Type")),
+                    ContentValue::Code(CodedConcept::new("121006", "DCM", "Person")),
+                ));
+                root.push_child(ContentItem::new(
+                    Some(RelationshipType::HasObsContext),
+                    Some(CodedConcept::new("121008", "DCM", "Person Observer Name")),
+                    ContentValue::PersonName(name.clone()),
+                ));
+            }
+            observer => {
+                let uid = match observer {
+                    Some(Observer::Device(uid)) => uid.clone(),
+                    _ => crate::IMPLEMENTATION_CLASS_UID.to_string(),
+                };
+                root.push_child(ContentItem::new(
+                    Some(RelationshipType::HasObsContext),
+                    Some(CodedConcept::new("121005", "DCM", "Observer Type")),
+                    ContentValue::Code(CodedConcept::new("121007", "DCM", "Device")),
+                ));
+                root.push_child(ContentItem::new(
+                    Some(RelationshipType::HasObsContext),
+                    Some(CodedConcept::new("121012", "DCM", "Device Observer UID")),
+                    ContentValue::UidRef(uid),
+                ));
+            }
+        }
+
+        for procedure in &self.procedures {
+            root.push_child(ContentItem::new(
+                Some(RelationshipType::HasConceptMod),
+                Some(CodedConcept::new("121058", "DCM", "Procedure reported")),
+                ContentValue::Code(procedure.clone()),
+            ));
+        }
+
+        let mut measurements = ContentItem::new(
+            Some(RelationshipType::Contains),
+            Some(CodedConcept::new("126010", "DCM", "Imaging Measurements")),
+            ContentValue::Container {
+                continuous: false,
+                template: None,
+            },
+        );
+        for group in &self.groups {
+            measurements.push_child(group.to_content_item());
+        }
+        root.push_child(measurements);
+
+        Ok(root)
+    }
+
+    /// Build the report as a new DICOM file object.
+    pub fn build(self) -> Result<FileDicomObject<InMemDicomObject>> {
+        let content = self.build_content()?;
+        let mut obj = content.to_dataset();
+
+        let mut source = self.source.unwrap_or_else(InMemDicomObject::new_empty);
+        if source
+            .element_opt(tags::STUDY_INSTANCE_UID)
+            .ok()
+            .flatten()
+            .is_none()
+        {
+            source.put(DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(new_uid()),
+            ));
+        }
+        for &(tag, vr) in PATIENT_STUDY_ATTRIBUTES {
+            let elem = source
+                .take_element(tag)
+                .unwrap_or_else(|_| DataElement::new(tag, vr, PrimitiveValue::Empty));
+            obj.put(elem);
+        }
+
+        let (date, time) = match self.content_date_time {
+            Some(date_time) => date_time,
+            None => current_date_time(),
+        };
+        let sop_instance_uid = new_uid();
+        for (tag, vr, value) in [
+            (
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                COMPREHENSIVE_SR_STORAGE.to_string(),
+            ),
+            (tags::SOP_INSTANCE_UID, VR::UI, sop_instance_uid),
+            (tags::MODALITY, VR::CS, "SR".to_string()),
+            (tags::SERIES_INSTANCE_UID, VR::UI, new_uid()),
+            (tags::SERIES_NUMBER, VR::IS, self.series_number.to_string()),
+            (
+                tags::INSTANCE_NUMBER,
+                VR::IS,
+                self.instance_number.to_string(),
+            ),
+            (tags::CONTENT_DATE, VR::DA, date),
+            (tags::CONTENT_TIME, VR::TM, time),
+            (tags::COMPLETION_FLAG, VR::CS, "COMPLETE".to_string()),
+            (tags::VERIFICATION_FLAG, VR::CS, "UNVERIFIED".to_string()),
+        ] {
+            obj.put(DataElement::new(tag, vr, PrimitiveValue::from(value)));
+        }
+
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid(COMPREHENSIVE_SR_STORAGE)
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .context(BuildMetaSnafu)
+    }
+}
+
+/// Obtain the current date (DA) and time (TM) in UTC.
+pub(crate) fn current_date_time() -> (String, String) {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    // 719163 days from 0001-01-01 to 1970-01-01
+    let date =
+        dicom_core::chrono::NaiveDate::from_num_days_from_ce_opt((secs / 86_400) as i32 + 719_163)
+            .expect("current date should be within the supported range");
+    let seconds = secs % 86_400;
+    (
+        date.format("%Y%m%d").to_string(),
+        format!(
+            "{:02}{:02}{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::dicom_value;
+
+    fn code_item(value: &str, scheme: &str, meaning: &str) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(tags::CODE_VALUE, VR::SH, PrimitiveValue::from(value)),
+            DataElement::new(
+                tags::CODING_SCHEME_DESIGNATOR,
+                VR::SH,
+                PrimitiveValue::from(scheme),
+            ),
+            DataElement::new(tags::CODE_MEANING, VR::LO, PrimitiveValue::from(meaning)),
+        ])
+    }
+
+    fn sample_report() -> InMemDicomObject {
+        let text = InMemDicomObject::from_element_iter([
+            cs(tags::RELATIONSHIP_TYPE, "CONTAINS"),
+            cs(tags::VALUE_TYPE, "TEXT"),
+            sequence(
+                tags::CONCEPT_NAME_CODE_SEQUENCE,
+                vec![code_item("121071", "DCM", "Finding")],
+            ),
+            DataElement::new(
+                tags::TEXT_VALUE,
+                VR::UT,
+                PrimitiveValue::from("No abnormality"),
+            ),
+        ]);
+        let num = InMemDicomObject::from_element_iter([
+            cs(tags::RELATIONSHIP_TYPE, "CONTAINS"),
+            cs(tags::VALUE_TYPE, "NUM"),
+            sequence(
+                tags::CONCEPT_NAME_CODE_SEQUENCE,
+                vec![code_item("410668003", "SCT", "Length")],
+            ),
+            sequence(
+                tags::MEASURED_VALUE_SEQUENCE,
+                vec![InMemDicomObject::from_element_iter([
+                    DataElement::new(tags::NUMERIC_VALUE, VR::DS, PrimitiveValue::from("12.5")),
+                    sequence(
+                        tags::MEASUREMENT_UNITS_CODE_SEQUENCE,
+                        vec![code_item("mm", "UCUM", "millimeter")],
+                    ),
+                ])],
+            ),
+        ]);
+        let image = InMemDicomObject::from_element_iter([
+            cs(tags::RELATIONSHIP_TYPE, "INFERRED FROM"),
+            cs(tags::VALUE_TYPE, "IMAGE"),
+            sequence(
+                tags::REFERENCED_SOP_SEQUENCE,
+                vec![InMemDicomObject::from_element_iter([
+                    DataElement::new(
+                        tags::REFERENCED_SOP_CLASS_UID,
+                        VR::UI,
+                        PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.2"),
+                    ),
+                    DataElement::new(
+                        tags::REFERENCED_SOP_INSTANCE_UID,
+                        VR::UI,
+                        PrimitiveValue::from("2.25.1"),
+                    ),
+                ])],
+            ),
+        ]);
+        let by_ref = InMemDicomObject::from_element_iter([
+            cs(tags::RELATIONSHIP_TYPE, "INFERRED FROM"),
+            DataElement::new(
+                tags::REFERENCED_CONTENT_ITEM_IDENTIFIER,
+                VR::UL,
+                dicom_value!(U32, [1, 1]),
+            ),
+        ]);
+        let num = {
+            let mut num = num;
+            num.put(sequence(tags::CONTENT_SEQUENCE, vec![image, by_ref]));
+            num
+        };
+
+        InMemDicomObject::from_element_iter([
+            cs(tags::VALUE_TYPE, "CONTAINER"),
+            cs(tags::CONTINUITY_OF_CONTENT, "SEPARATE"),
+            sequence(
+                tags::CONCEPT_NAME_CODE_SEQUENCE,
+                vec![code_item("126000", "DCM", "Imaging Measurement Report")],
+            ),
+            sequence(tags::CONTENT_SEQUENCE, vec![text, num]),
+        ])
+    }
+
+    #[test]
+    fn reads_content_tree() {
+        let root = content_tree(&sample_report()).unwrap();
+
+        assert_eq!(root.relationship(), None);
+        assert!(root.has_concept("126000", "DCM"));
+        assert_eq!(
+            root.value(),
+            &ContentValue::Container {
+                continuous: false,
+                template: None
+            }
+        );
+        assert_eq!(root.children().len(), 2);
+
+        let text = &root.children()[0];
+        assert_eq!(text.relationship(), Some(&RelationshipType::Contains));
+        assert_eq!(text.as_text(), Some("No abnormality"));
+
+        let num = &root.children()[1];
+        assert_eq!(num.as_num(), Some(12.5));
+        match num.value() {
+            ContentValue::Num { units, .. } => {
+                assert!(units.as_ref().unwrap().matches("mm", "UCUM"));
+            }
+            value => panic!("unexpected value {:?}", value),
+        }
+
+        let image = &num.children()[0];
+        assert_eq!(image.relationship(), Some(&RelationshipType::InferredFrom));
+        assert_eq!(
+            image.value(),
+            &ContentValue::Image(SopReference {
+                sop_class_uid: "1.2.840.10008.5.1.4.1.1.2".to_string(),
+                sop_instance_uid: "2.25.1".to_string(),
+                frames: vec![],
+            })
+        );
+        assert_eq!(
+            num.children()[1].value(),
+            &ContentValue::ByReference(vec![1, 1])
+        );
+    }
+
+    #[test]
+    fn traverses_and_queries() {
+        let root = content_tree(&sample_report()).unwrap();
+
+        // depth-first pre-order
+        let value_types: Vec<_> = root
+            .iter()
+            .map(|item| match item.value() {
+                ContentValue::Container { .. } => "CONTAINER",
+                ContentValue::Text(_) => "TEXT",
+                ContentValue::Num { .. } => "NUM",
+                ContentValue::Image(_) => "IMAGE",
+                ContentValue::ByReference(_) => "BYREF",
+                _ => "OTHER",
+            })
+            .collect();
+        assert_eq!(
+            value_types,
+            vec!["CONTAINER", "TEXT", "NUM", "IMAGE", "BYREF"]
+        );
+
+        let finding = root.find("121071", "DCM").unwrap();
+        assert_eq!(finding.as_text(), Some("No abnormality"));
+        assert!(root.find("121071", "SCT").is_none());
+        assert_eq!(root.find_all("410668003", "SCT").count(), 1);
+        assert_eq!(root.measurements().count(), 1);
+
+        // resolve a by-reference relationship
+        assert_eq!(root.get_by_id(&[1]), Some(&root));
+        assert_eq!(root.get_by_id(&[1, 1]), Some(finding));
+        assert_eq!(root.get_by_id(&[1, 2, 1]).unwrap().children().len(), 0);
+        assert_eq!(root.get_by_id(&[1, 3]), None);
+        assert_eq!(root.get_by_id(&[2]), None);
+    }
+
+    #[test]
+    fn missing_value_type_is_an_error() {
+        let obj = InMemDicomObject::from_element_iter([cs(tags::RELATIONSHIP_TYPE, "CONTAINS")]);
+        assert!(matches!(
+            content_tree(&obj),
+            Err(Error::MissingAttribute { tag, .. }) if tag == tags::VALUE_TYPE
+        ));
+    }
+
+    #[test]
+    fn writes_content_tree_back() {
+        let root = content_tree(&sample_report()).unwrap();
+        let written = root.to_dataset();
+        assert_eq!(content_tree(&written).unwrap(), root);
+
+        let text = &written
+            .element(tags::CONTENT_SEQUENCE)
+            .unwrap()
+            .items()
+            .unwrap()[0];
+        assert_eq!(
+            text.element(tags::RELATIONSHIP_TYPE)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "CONTAINS"
+        );
+        assert_eq!(
+            text.element(tags::VALUE_TYPE).unwrap().to_str().unwrap(),
+            "TEXT"
+        );
+    }
+
+    #[test]
+    fn builds_measurement_report() {
+        let source = InMemDicomObject::from_element_iter([
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.42"),
+            ),
+        ]);
+        let report = MeasurementReportBuilder::new()
+            .source(&source)
+            .procedure_reported(CodedConcept::new("77477000", "SCT", "CT"))
+            .person_observer("Smith^Jane")
+            .content_date_time("20240102", "030405")
+            .measurement_group(
+                MeasurementGroup::new("Lesion 1")
+                    .tracking_uid("2.25.7")
+                    .finding(CodedConcept::new("52988006", "SCT", "Lesion"))
+                    .measurement(
+                        CodedConcept::new("410668003", "SCT", "Length"),
+                        12.5,
+                        CodedConcept::new("mm", "UCUM", "millimeter"),
+                    ),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            report
+                .meta()
+                .media_storage_sop_class_uid
+                .trim_end_matches('\0'),
+            COMPREHENSIVE_SR_STORAGE
+        );
+        for (tag, value) in [
+            (tags::SOP_CLASS_UID, COMPREHENSIVE_SR_STORAGE),
+            (tags::MODALITY, "SR"),
+            (tags::PATIENT_NAME, "Doe^John"),
+            (tags::STUDY_INSTANCE_UID, "2.25.42"),
+            (tags::CONTENT_DATE, "20240102"),
+            (tags::CONTENT_TIME, "030405"),
+            (tags::COMPLETION_FLAG, "COMPLETE"),
+        ] {
+            assert_eq!(report.element(tag).unwrap().to_str().unwrap(), value);
+        }
+        // type 2 attributes are present even if not in the source
+        assert!(report.element(tags::PATIENT_ID).is_ok());
+
+        let root = content_tree(&report).unwrap();
+        assert!(root.has_concept("126000", "DCM"));
+        assert_eq!(
+            root.value(),
+            &ContentValue::Container {
+                continuous: false,
+                template: Some("1500".to_string()),
+            }
+        );
+        assert_eq!(
+            root.find("121058", "DCM").unwrap().relationship(),
+            Some(&RelationshipType::HasConceptMod)
+        );
+        assert_eq!(
+            root.find("121008", "DCM").unwrap().value(),
+            &ContentValue::PersonName("Smith^Jane".to_string())
+        );
+
+        let group = root.find("125007", "DCM").unwrap();
+        assert_eq!(
+            group.find("112039", "DCM").unwrap().as_text(),
+            Some("Lesion 1")
+        );
+        assert_eq!(
+            group.find("112040", "DCM").unwrap().value(),
+            &ContentValue::UidRef("2.25.7".to_string())
+        );
+        let length = group.find("410668003", "SCT").unwrap();
+        assert_eq!(length.relationship(), Some(&RelationshipType::Contains));
+        assert_eq!(length.as_num(), Some(12.5));
+    }
+
+    #[test]
+    fn measurement_report_requires_procedure() {
+        assert!(matches!(
+            MeasurementReportBuilder::new().build(),
+            Err(Error::IncompleteReport { .. })
+        ));
+    }
+}
@@ -0,0 +1,314 @@
+//! Extraction of key identifiers and descriptors of a DICOM instance.
+//!
+//! An [`InstanceSummary`] gathers the attributes
+//! which ingest and indexing services usually need
+//! to place an instance in the patient/study/series/instance hierarchy,
+//! in a normalized form:
+//! text values are trimmed of padding,
+//! empty or malformed values are left out,
+//! and dates, times, and numbers are parsed.
+//!
+//! A summary can be taken from an object already in memory
+//! or read from a file or byte stream,
+//! in which case reading stops
+//! right after the last attribute of interest,
+//! well before the pixel data.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::summary;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let summary = summary::open_file("0001.dcm")?;
+//! println!(
+//!     "{:?} / {:?} / {:?}",
+//!     summary.study_instance_uid, summary.series_instance_uid, summary.sop_instance_uid,
+//! );
+//! # Ok(())
+//! # }
+//! ```
+use std::io::Read;
+#[cfg(feature = "fs")]
+use std::path::Path;
+
+use dicom_core::value::{DicomDate, DicomTime};
+use dicom_core::{DataDictionary, Tag};
+use dicom_dictionary_std::tags;
+
+use crate::mem::InMemDicomObject;
+use crate::{FileDicomObject, OpenFileOptions, Result};
+
+/// The tag at which reading can stop when summarizing a data set,
+/// right after _Number of Frames_ (0028,0008).
+const READ_UNTIL: Tag = Tag(0x0028, 0x0009);
+
+/// A normalized summary of a DICOM instance,
+/// comprising its key identifiers and descriptors
+/// at the patient, study, series, and instance levels.
+///
+/// All fields are optional,
+/// since any of them may be missing or malformed in the source.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct InstanceSummary {
+    /// _Patient ID_ (0010,0020)
+    pub patient_id: Option<String>,
+    /// _Patient's Name_ (0010,0010)
+    pub patient_name: Option<String>,
+    /// _Patient's Birth Date_ (0010,0030)
+    pub patient_birth_date: Option<DicomDate>,
+    /// _Patient's Sex_ (0010,0040)
+    pub patient_sex: Option<String>,
+
+    /// _Study Instance UID_ (0020,000D)
+    pub study_instance_uid: Option<String>,
+    /// _Study ID_ (0020,0010)
+    pub study_id: Option<String>,
+    /// _Accession Number_ (0008,0050)
+    pub accession_number: Option<String>,
+    /// _Study Date_ (0008,0020)
+    pub study_date: Option<DicomDate>,
+    /// _Study Time_ (0008,0030)
+    pub study_time: Option<DicomTime>,
+    /// _Study Description_ (0008,1030)
+    pub study_description: Option<String>,
+
+    /// _Series Instance UID_ (0020,000E)
+    pub series_instance_uid: Option<String>,
+    /// _Series Number_ (0020,0011)
+    pub series_number: Option<i32>,
+    /// _Modality_ (0008,0060)
+    pub modality: Option<String>,
+    /// _Series Date_ (0008,0021)
+    pub series_date: Option<DicomDate>,
+    /// _Series Description_ (0008,103E)
+    pub series_description: Option<String>,
+
+    /// _SOP Class UID_ (0008,0016),
+    /// or the media storage SOP class UID of the file meta group
+    pub sop_class_uid: Option<String>,
+    /// _SOP Instance UID_ (0008,0018),
+    /// or the media storage SOP instance UID of the file meta group
+    pub sop_instance_uid: Option<String>,
+    /// _Instance Number_ (0020,0013)
+    pub instance_number: Option<i32>,
+    /// _Content Date_ (0008,0023)
+    pub content_date: Option<DicomDate>,
+    /// _Content Time_ (0008,0033)
+    pub content_time: Option<DicomTime>,
+
+    /// _Number of Frames_ (0028,0008)
+    pub number_of_frames: Option<u32>,
+    /// _Number of Study Related Series_ (0020,1206),
+    /// usually only present in query results
+    pub number_of_study_related_series: Option<u32>,
+    /// _Number of Study Related Instances_ (0020,1208),
+    /// usually only present in query results
+    pub number_of_study_related_instances: Option<u32>,
+    /// _Number of Series Related Instances_ (0020,1209),
+    /// usually only present in query results
+    pub number_of_series_related_instances: Option<u32>,
+}
+
+impl InstanceSummary {
+    /// Summarize the given data set.
+    pub fn from_object<D>(obj: &InMemDicomObject<D>) -> Self
+    where
+        D: DataDictionary + Clone,
+    {
+        let string = |tag| {
+            obj.get_string(tag)
+                .ok()
+                .map(|s| s.trim_matches(|c: char| c == ' ' || c == '\0').to_string())
+                .filter(|s| !s.is_empty())
+        };
+
+        InstanceSummary {
+            patient_id: string(tags::PATIENT_ID),
+            patient_name: string(tags::PATIENT_NAME),
+            patient_birth_date: obj.get_date(tags::PATIENT_BIRTH_DATE).ok(),
+            patient_sex: string(tags::PATIENT_SEX),
+            study_instance_uid: string(tags::STUDY_INSTANCE_UID),
+            study_id: string(tags::STUDY_ID),
+            accession_number: string(tags::ACCESSION_NUMBER),
+            study_date: obj.get_date(tags::STUDY_DATE).ok(),
+            study_time: obj.get_time(tags::STUDY_TIME).ok(),
+            study_description: string(tags::STUDY_DESCRIPTION),
+            series_instance_uid: string(tags::SERIES_INSTANCE_UID),
+            series_number: obj.get_i32(tags::SERIES_NUMBER).ok(),
+            modality: string(tags::MODALITY),
+            series_date: obj.get_date(tags::SERIES_DATE).ok(),
+            series_description: string(tags::SERIES_DESCRIPTION),
+            sop_class_uid: string(tags::SOP_CLASS_UID),
+            sop_instance_uid: string(tags::SOP_INSTANCE_UID),
+            instance_number: obj.get_i32(tags::INSTANCE_NUMBER).ok(),
+            content_date: obj.get_date(tags::CONTENT_DATE).ok(),
+            content_time: obj.get_time(tags::CONTENT_TIME).ok(),
+            number_of_frames: obj.get_u32(tags::NUMBER_OF_FRAMES).ok(),
+            number_of_study_related_series: obj.get_u32(tags::NUMBER_OF_STUDY_RELATED_SERIES).ok(),
+            number_of_study_related_instances: obj
+                .get_u32(tags::NUMBER_OF_STUDY_RELATED_INSTANCES)
+                .ok(),
+            number_of_series_related_instances: obj
+                .get_u32(tags::NUMBER_OF_SERIES_RELATED_INSTANCES)
+                .ok(),
+        }
+    }
+
+    /// Summarize the given file DICOM object.
+    ///
+    /// The SOP class and instance UIDs are taken from the file meta group
+    /// when they are missing in the main data set.
+    pub fn from_file<D>(obj: &FileDicomObject<InMemDicomObject<D>>) -> Self
+    where
+        D: DataDictionary + Clone,
+    {
+        let mut summary = InstanceSummary::from_object(&**obj);
+        let uid = |uid: &str| {
+            Some(
+                uid.trim_end_matches(|c: char| c == ' ' || c == '\0')
+                    .to_string(),
+            )
+            .filter(|s| !s.is_empty())
+        };
+        if summary.sop_class_uid.is_none() {
+            summary.sop_class_uid = uid(&obj.meta().media_storage_sop_class_uid);
+        }
+        if summary.sop_instance_uid.is_none() {
+            summary.sop_instance_uid = uid(&obj.meta().media_storage_sop_instance_uid);
+        }
+        summary
+    }
+}
+
+/// Read a summary of the DICOM file at the given path.
+///
+/// Only the file meta group
+/// and the attributes up to the last one summarized are read.
+#[cfg(feature = "fs")]
+pub fn open_file<P>(path: P) -> Result<InstanceSummary>
+where
+    P: AsRef<Path>,
+{
+    let obj = OpenFileOptions::new()
+        .read_until(READ_UNTIL)
+        .open_file(path)?;
+    Ok(InstanceSummary::from_file(&obj))
+}
+
+/// Read a summary of a DICOM file from a byte source,
+/// which should start with the file meta group,
+/// without the preamble.
+///
+/// Only the file meta group
+/// and the attributes up to the last one summarized are read,
+/// so the source is left right before the remaining attributes.
+pub fn from_reader<R>(from: R) -> Result<InstanceSummary>
+where
+    R: Read,
+{
+    let obj = OpenFileOptions::new()
+        .read_until(READ_UNTIL)
+        .from_reader(from)?;
+    Ok(InstanceSummary::from_file(&obj))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileMetaTableBuilder;
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+
+    fn sample_file() -> Vec<u8> {
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(tags::STUDY_DATE, VR::DA, PrimitiveValue::from("20221003")),
+            DataElement::new(tags::STUDY_TIME, VR::TM, PrimitiveValue::from("1030")),
+            DataElement::new(tags::MODALITY, VR::CS, PrimitiveValue::from("CT")),
+            DataElement::new(tags::STUDY_DESCRIPTION, VR::LO, PrimitiveValue::from("")),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+            DataElement::new(tags::PATIENT_ID, VR::LO, PrimitiveValue::from(" P1 ")),
+            DataElement::new(
+                tags::PATIENT_BIRTH_DATE,
+                VR::DA,
+                PrimitiveValue::from("N/A"),
+            ),
+            DataElement::new(
+                tags::STUDY_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.1\0"),
+            ),
+            DataElement::new(
+                tags::SERIES_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.1.2"),
+            ),
+            DataElement::new(tags::SERIES_NUMBER, VR::IS, PrimitiveValue::from("3 ")),
+            DataElement::new(tags::NUMBER_OF_FRAMES, VR::IS, PrimitiveValue::from("12")),
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(64_u16)),
+            DataElement::new(
+                tags::PIXEL_DATA,
+                VR::OW,
+                PrimitiveValue::U16(vec![0; 16].into()),
+            ),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .transfer_syntax("1.2.840.10008.1.2.1")
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.2")
+                .media_storage_sop_instance_uid("2.25.1.2.3"),
+        )
+        .unwrap();
+
+        let mut data = Vec::new();
+        obj.write_all(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn summarize_file_stream() {
+        let data = sample_file();
+        // skip the preamble
+        let summary = from_reader(&data[128..]).unwrap();
+
+        assert_eq!(summary.patient_id.as_deref(), Some("P1"));
+        assert_eq!(summary.patient_name.as_deref(), Some("Doe^John"));
+        assert_eq!(summary.patient_birth_date, None);
+        assert_eq!(summary.study_instance_uid.as_deref(), Some("2.25.1"));
+        assert_eq!(
+            summary.study_date,
+            Some(DicomDate::from_ymd(2022, 10, 3).unwrap())
+        );
+        assert_eq!(
+            summary.study_time,
+            Some(DicomTime::from_hm(10, 30).unwrap())
+        );
+        assert_eq!(summary.study_description, None);
+        assert_eq!(summary.series_instance_uid.as_deref(), Some("2.25.1.2"));
+        assert_eq!(summary.series_number, Some(3));
+        assert_eq!(summary.modality.as_deref(), Some("CT"));
+        assert_eq!(
+            summary.sop_class_uid.as_deref(),
+            Some("1.2.840.10008.5.1.4.1.1.2")
+        );
+        assert_eq!(summary.sop_instance_uid.as_deref(), Some("2.25.1.2.3"));
+        assert_eq!(summary.instance_number, None);
+        assert_eq!(summary.number_of_frames, Some(12));
+    }
+
+    #[test]
+    fn summarize_object() {
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from("2.25.9"),
+            ),
+            DataElement::new(tags::INSTANCE_NUMBER, VR::IS, PrimitiveValue::from("7")),
+        ]);
+        let summary = InstanceSummary::from_object(&obj);
+        assert_eq!(summary.sop_instance_uid.as_deref(), Some("2.25.9"));
+        assert_eq!(summary.instance_number, Some(7));
+        assert_eq!(summary.patient_id, None);
+        assert_eq!(summary.number_of_frames, None);
+    }
+}
@@ -0,0 +1,1032 @@
+//! Validation of DICOM objects against information object definitions.
+//!
+//! This module checks a data set against the requirements
+//! of its information object definition (IOD),
+//! as described in the standard, part 3:
+//!
+//! - the presence of attributes according to their type
+//!   (1, 1C, 2, 2C, 3) in each module of the IOD;
+//! - the compliance of values with enumerated values;
+//! - the value representation of each attribute,
+//!   according to the standard data dictionary;
+//! - the value multiplicity of each attribute,
+//!   according to the standard data dictionary.
+//!
+//! The file meta group of a DICOM file is validated as well.
+//! The outcome is a [`ValidationReport`] listing every [`Issue`] found.
+//!
+//! IOD definitions are currently available for
+//! CT images, MR images, and secondary capture images
+//! (see [`iod_for_sop_class`]).
+//! Objects of other SOP classes are only checked
+//! for value representation and multiplicity.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::validate::validate_file;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("ct_0001.dcm")?;
+//! let report = validate_file(&obj);
+//! if !report.is_valid() {
+//!     println!("{}", report);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::borrow::Cow;
+use std::fmt;
+
+use dicom_core::dictionary::{DataDictionary, DictionaryEntry, ValueMultiplicity};
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{Tag, VR};
+use dicom_dictionary_std::{tags, uids, StandardDataDictionary};
+
+use crate::mem::{InMemDicomObject, InMemElement};
+use crate::meta::FileMetaTable;
+use crate::DefaultDicomObject;
+
+/// The type of an attribute in a module,
+/// which determines whether it must be present and have a value.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AttributeType {
+    /// Required, with a value.
+    Type1,
+    /// Required with a value, under a condition.
+    Type1C,
+    /// Required, possibly empty.
+    Type2,
+    /// Required under a condition, possibly empty.
+    Type2C,
+    /// Optional.
+    Type3,
+}
+
+impl fmt::Display for AttributeType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            AttributeType::Type1 => "1",
+            AttributeType::Type1C => "1C",
+            AttributeType::Type2 => "2",
+            AttributeType::Type2C => "2C",
+            AttributeType::Type3 => "3",
+        })
+    }
+}
+
+/// A condition on a data set,
+/// determining whether a conditional attribute is required.
+pub type Condition = fn(&InMemDicomObject) -> bool;
+
+/// The enumerated values of an attribute.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct EnumeratedValues {
+    /// the index of the value to which the constraint applies,
+    /// or `None` if it applies to all values
+    pub index: Option<usize>,
+    /// the values allowed
+    pub values: &'static [&'static str],
+}
+
+/// The specification of an attribute in a module.
+#[derive(Debug, Copy, Clone)]
+pub struct AttributeSpec {
+    /// the attribute tag
+    pub tag: Tag,
+    /// the attribute type
+    pub attribute_type: AttributeType,
+    /// the condition under which a type 1C or 2C attribute is required
+    pub condition: Option<Condition>,
+    /// the constraints on the attribute's values
+    pub enumerated_values: &'static [EnumeratedValues],
+}
+
+impl AttributeSpec {
+    /// Create an attribute specification
+    /// without conditions or enumerated values.
+    pub const fn new(tag: Tag, attribute_type: AttributeType) -> Self {
+        AttributeSpec {
+            tag,
+            attribute_type,
+            condition: None,
+            enumerated_values: &[],
+        }
+    }
+
+    /// Set the condition under which the attribute is required.
+    pub const fn when(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Set the constraints on the attribute's values.
+    pub const fn enumerated(mut self, enumerated_values: &'static [EnumeratedValues]) -> Self {
+        self.enumerated_values = enumerated_values;
+        self
+    }
+
+    /// Check whether the attribute is required in the given data set.
+    fn is_required(&self, obj: &InMemDicomObject) -> bool {
+        match self.attribute_type {
+            AttributeType::Type1 | AttributeType::Type2 => true,
+            AttributeType::Type1C | AttributeType::Type2C => self
+                .condition
+                .map(|condition| condition(obj))
+                .unwrap_or(false),
+            AttributeType::Type3 => false,
+        }
+    }
+
+    /// Check whether the attribute must have a value when present.
+    fn requires_value(&self) -> bool {
+        matches!(
+            self.attribute_type,
+            AttributeType::Type1 | AttributeType::Type1C
+        )
+    }
+}
+
+/// The specification of a module.
+#[derive(Debug, Copy, Clone)]
+pub struct ModuleSpec {
+    /// the module name
+    pub name: &'static str,
+    /// the attributes of the module
+    pub attributes: &'static [AttributeSpec],
+}
+
+/// The specification of an information object definition.
+#[derive(Debug, Copy, Clone)]
+pub struct IodSpec {
+    /// the IOD name
+    pub name: &'static str,
+    /// the mandatory modules of the IOD
+    pub modules: &'static [&'static ModuleSpec],
+}
+
+/// The severity of a validation issue.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The object may still be usable,
+    /// but does not conform to the standard.
+    Warning,
+    /// The object violates a requirement of the standard.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// The kind of a validation issue.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum IssueKind {
+    /// A required attribute is missing.
+    Missing { attribute_type: AttributeType },
+    /// An attribute which requires a value is empty.
+    Empty { attribute_type: AttributeType },
+    /// An attribute has a value representation
+    /// other than the one in the data dictionary.
+    UnexpectedVr { expected: VR, found: VR },
+    /// An attribute has a number of values
+    /// not allowed by the data dictionary.
+    InvalidVm {
+        expected: ValueMultiplicity,
+        found: u32,
+    },
+    /// A value is not one of the enumerated values of the attribute.
+    InvalidEnumeratedValue {
+        value: String,
+        allowed: &'static [&'static str],
+    },
+    /// A value in the file meta group does not match
+    /// the corresponding value in the data set.
+    MetaMismatch { meta: String, data_set: String },
+}
+
+impl fmt::Display for IssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IssueKind::Missing { attribute_type } => {
+                write!(f, "missing type {} attribute", attribute_type)
+            }
+            IssueKind::Empty { attribute_type } => {
+                write!(f, "empty type {} attribute", attribute_type)
+            }
+            IssueKind::UnexpectedVr { expected, found } => {
+                write!(f, "value representation {}, expected {}", found, expected)
+            }
+            IssueKind::InvalidVm { expected, found } => {
+                write!(f, "{} values, expected {}", found, expected)
+            }
+            IssueKind::InvalidEnumeratedValue { value, allowed } => {
+                write!(f, "value `{}` not in {:?}", value, allowed)
+            }
+            IssueKind::MetaMismatch { meta, data_set } => write!(
+                f,
+                "file meta group has `{}`, data set has `{}`",
+                meta, data_set
+            ),
+        }
+    }
+}
+
+/// An issue found while validating a DICOM object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Issue {
+    /// the issue's severity
+    pub severity: Severity,
+    /// the tags of the enclosing sequences,
+    /// followed by the tag of the attribute concerned
+    pub path: Vec<Tag>,
+    /// the module which defines the requirement, if applicable
+    pub module: Option<&'static str>,
+    /// the kind of issue
+    pub kind: IssueKind,
+}
+
+impl Issue {
+    /// Retrieve the tag of the attribute concerned.
+    pub fn tag(&self) -> Tag {
+        *self.path.last().expect("issue path should not be empty")
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.severity)?;
+        for (i, tag) in self.path.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", tag)?;
+        }
+        if let Some(entry) = StandardDataDictionary.by_tag(self.tag()) {
+            write!(f, " {}", entry.alias())?;
+        }
+        write!(f, ": {}", self.kind)?;
+        if let Some(module) = self.module {
+            write!(f, " ({} module)", module)?;
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of validating a DICOM object.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    /// the name of the IOD validated against, if known
+    pub iod: Option<&'static str>,
+    /// the issues found
+    pub issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Whether no errors were found.
+    /// Warnings are allowed.
+    pub fn is_valid(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Iterate over the issues of error severity.
+    pub fn errors(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Error)
+    }
+
+    /// Iterate over the issues of warning severity.
+    pub fn warnings(&self) -> impl Iterator<Item = &Issue> {
+        self.issues
+            .iter()
+            .filter(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn push(&mut self, severity: Severity, path: Vec<Tag>, kind: IssueKind) {
+        self.issues.push(Issue {
+            severity,
+            path,
+            module: None,
+            kind,
+        });
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.iod {
+            Some(iod) => writeln!(f, "{} IOD", iod)?,
+            None => writeln!(f, "Unknown IOD")?,
+        }
+        for issue in &self.issues {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate a DICOM file,
+/// including its file meta group.
+pub fn validate_file(obj: &DefaultDicomObject) -> ValidationReport {
+    let mut report = validate(obj);
+    let meta_report = validate_meta(obj.meta());
+    report.issues.extend(meta_report.issues);
+
+    let meta = obj.meta();
+    for (meta_tag, meta_value, tag) in [
+        (
+            tags::MEDIA_STORAGE_SOP_CLASS_UID,
+            &meta.media_storage_sop_class_uid,
+            tags::SOP_CLASS_UID,
+        ),
+        (
+            tags::MEDIA_STORAGE_SOP_INSTANCE_UID,
+            &meta.media_storage_sop_instance_uid,
+            tags::SOP_INSTANCE_UID,
+        ),
+    ] {
+        if let Ok(value) = obj.get_string(tag) {
+            let meta_value = trim_uid(meta_value);
+            let value = trim_uid(&value);
+            if meta_value != value {
+                report.push(
+                    Severity::Error,
+                    vec![meta_tag],
+                    IssueKind::MetaMismatch {
+                        meta: meta_value.to_string(),
+                        data_set: value.to_string(),
+                    },
+                );
+            }
+        }
+    }
+    report
+}
+
+/// Validate a DICOM object
+/// against the IOD of its SOP class, if known,
+/// and the data dictionary.
+pub fn validate(obj: &InMemDicomObject) -> ValidationReport {
+    let iod = obj
+        .get_string(tags::SOP_CLASS_UID)
+        .ok()
+        .and_then(|uid| iod_for_sop_class(trim_uid(&uid)));
+    match iod {
+        Some(iod) => validate_iod(obj, iod),
+        None => {
+            let mut report = ValidationReport::default();
+            check_dictionary(obj, &mut Vec::new(), &mut report);
+            report
+        }
+    }
+}
+
+/// Validate a DICOM object
+/// against the given IOD and the data dictionary.
+pub fn validate_iod(obj: &InMemDicomObject, iod: &IodSpec) -> ValidationReport {
+    let mut report = ValidationReport {
+        iod: Some(iod.name),
+        issues: Vec::new(),
+    };
+    for module in iod.modules {
+        check_module(obj, module, &mut report);
+    }
+    check_dictionary(obj, &mut Vec::new(), &mut report);
+    report
+}
+
+/// Validate the file meta group of a DICOM file.
+pub fn validate_meta(meta: &FileMetaTable) -> ValidationReport {
+    let mut report = ValidationReport::default();
+    if meta.information_version != [0, 1] {
+        report.push(
+            Severity::Error,
+            vec![tags::FILE_META_INFORMATION_VERSION],
+            IssueKind::InvalidEnumeratedValue {
+                value: format!("{:?}", meta.information_version),
+                allowed: &["[0, 1]"],
+            },
+        );
+    }
+    for (tag, value) in [
+        (
+            tags::MEDIA_STORAGE_SOP_CLASS_UID,
+            &meta.media_storage_sop_class_uid,
+        ),
+        (
+            tags::MEDIA_STORAGE_SOP_INSTANCE_UID,
+            &meta.media_storage_sop_instance_uid,
+        ),
+        (tags::TRANSFER_SYNTAX_UID, &meta.transfer_syntax),
+        (
+            tags::IMPLEMENTATION_CLASS_UID,
+            &meta.implementation_class_uid,
+        ),
+    ] {
+        if trim_uid(value).is_empty() {
+            report.push(
+                Severity::Error,
+                vec![tag],
+                IssueKind::Empty {
+                    attribute_type: AttributeType::Type1,
+                },
+            );
+        }
+    }
+    for issue in &mut report.issues {
+        issue.module = Some("File Meta Information");
+    }
+    report
+}
+
+/// Retrieve the IOD specification for the given SOP class UID,
+/// if available.
+pub fn iod_for_sop_class(sop_class_uid: &str) -> Option<&'static IodSpec> {
+    match sop_class_uid {
+        uids::CT_IMAGE_STORAGE => Some(&CT_IMAGE_IOD),
+        uids::MR_IMAGE_STORAGE => Some(&MR_IMAGE_IOD),
+        uids::SECONDARY_CAPTURE_IMAGE_STORAGE => Some(&SC_IMAGE_IOD),
+        _ => None,
+    }
+}
+
+fn check_module(obj: &InMemDicomObject, module: &ModuleSpec, report: &mut ValidationReport) {
+    for spec in module.attributes {
+        let mut push = |kind| {
+            report.issues.push(Issue {
+                severity: Severity::Error,
+                path: vec![spec.tag],
+                module: Some(module.name),
+                kind,
+            })
+        };
+        let elem = match obj.element_opt(spec.tag).ok().flatten() {
+            Some(elem) => elem,
+            None => {
+                if spec.is_required(obj) {
+                    push(IssueKind::Missing {
+                        attribute_type: spec.attribute_type,
+                    });
+                }
+                continue;
+            }
+        };
+        if is_empty(elem) {
+            if spec.requires_value() {
+                push(IssueKind::Empty {
+                    attribute_type: spec.attribute_type,
+                });
+            }
+            continue;
+        }
+        if spec.enumerated_values.is_empty() {
+            continue;
+        }
+        let values = match elem.to_multi_str() {
+            Ok(values) => values,
+            Err(_) => continue,
+        };
+        for constraint in spec.enumerated_values {
+            let candidates = values
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| match constraint.index {
+                    Some(index) => *i == index,
+                    None => true,
+                });
+            for (_, value) in candidates {
+                let value = value.trim_matches(|c| c == ' ' || c == '\0');
+                if !constraint.values.contains(&value) {
+                    push(IssueKind::InvalidEnumeratedValue {
+                        value: value.to_string(),
+                        allowed: constraint.values,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Check the value representation and multiplicity of all attributes,
+/// recursively, against the standard data dictionary.
+fn check_dictionary(obj: &InMemDicomObject, path: &mut Vec<Tag>, report: &mut ValidationReport) {
+    for elem in obj {
+        path.push(elem.tag());
+        if let Some(entry) = StandardDataDictionary.by_tag(elem.tag()) {
+            let expected = entry.vr();
+            let found = elem.vr();
+            if !is_compatible_vr(expected, found) {
+                report.push(
+                    Severity::Error,
+                    path.clone(),
+                    IssueKind::UnexpectedVr { expected, found },
+                );
+            } else if has_vm(found) && !is_empty(elem) {
+                let count = value_count(elem);
+                if !entry.vm().contains(count) {
+                    report.push(
+                        Severity::Warning,
+                        path.clone(),
+                        IssueKind::InvalidVm {
+                            expected: entry.vm(),
+                            found: count,
+                        },
+                    );
+                }
+            }
+        }
+        for item in elem.items().unwrap_or_default() {
+            check_dictionary(item, path, report);
+        }
+        path.pop();
+    }
+}
+
+/// Check whether a value representation is acceptable
+/// for an attribute with the given value representation in the dictionary,
+/// taking into account attributes with more than one possible VR.
+fn is_compatible_vr(expected: VR, found: VR) -> bool {
+    expected == found
+        || found == VR::UN
+        || matches!(
+            (expected, found),
+            (VR::OB, VR::OW) | (VR::OW, VR::OB) | (VR::US, VR::SS) | (VR::SS, VR::US)
+        )
+}
+
+/// Whether the value multiplicity of values of this VR is meaningful.
+fn has_vm(vr: VR) -> bool {
+    !matches!(
+        vr,
+        VR::LT
+            | VR::OB
+            | VR::OD
+            | VR::OF
+            | VR::OL
+            | VR::OV
+            | VR::OW
+            | VR::SQ
+            | VR::ST
+            | VR::UN
+            | VR::UR
+            | VR::UT
+    )
+}
+
+/// Count the values of an element,
+/// including those separated by backslashes in a single string.
+fn value_count(elem: &InMemElement) -> u32 {
+    match elem.value() {
+        Value::Primitive(PrimitiveValue::Str(s)) => s.split('\\').count() as u32,
+        value => value.multiplicity(),
+    }
+}
+
+/// Check whether an element has no value,
+/// or only blank text.
+fn is_empty(elem: &InMemElement) -> bool {
+    match elem.value() {
+        Value::Primitive(PrimitiveValue::Str(s)) => is_blank(s),
+        Value::Primitive(PrimitiveValue::Strs(s)) => s.iter().all(|s| is_blank(s)),
+        Value::Primitive(PrimitiveValue::SharedStrs(s)) => s.iter().all(|s| is_blank(s)),
+        Value::Sequence { items, .. } => items.is_empty(),
+        value => value.multiplicity() == 0,
+    }
+}
+
+fn is_blank(s: &str) -> bool {
+    s.trim_matches(|c| c == ' ' || c == '\0').is_empty()
+}
+
+fn trim_uid(uid: &str) -> &str {
+    uid.trim_end_matches(|c| c == '\0' || c == ' ')
+}
+
+fn has(obj: &InMemDicomObject, tag: Tag) -> bool {
+    obj.element_opt(tag).ok().flatten().is_some()
+}
+
+fn value_of(obj: &InMemDicomObject, tag: Tag) -> Option<Cow<str>> {
+    obj.get_string(tag).ok()
+}
+
+// Conditions
+
+fn pixel_data_not_provided_by_url(obj: &InMemDicomObject) -> bool {
+    !has(obj, tags::PIXEL_DATA_PROVIDER_URL)
+}
+
+fn more_than_one_sample_per_pixel(obj: &InMemDicomObject) -> bool {
+    obj.element_opt(tags::SAMPLES_PER_PIXEL)
+        .ok()
+        .flatten()
+        .and_then(|e| e.to_int::<u16>().ok())
+        .map(|n| n > 1)
+        .unwrap_or(false)
+}
+
+fn no_image_plane(obj: &InMemDicomObject) -> bool {
+    !has(obj, tags::IMAGE_ORIENTATION_PATIENT) || !has(obj, tags::IMAGE_POSITION_PATIENT)
+}
+
+fn not_echo_planar(obj: &InMemDicomObject) -> bool {
+    value_of(obj, tags::SCANNING_SEQUENCE)
+        .map(|s| !s.split('\\').any(|s| s.trim() == "EP"))
+        .unwrap_or(true)
+}
+
+// Modules
+
+use AttributeType::*;
+
+const fn attr(tag: Tag, attribute_type: AttributeType) -> AttributeSpec {
+    AttributeSpec::new(tag, attribute_type)
+}
+
+const fn all(values: &'static [&'static str]) -> EnumeratedValues {
+    EnumeratedValues {
+        index: None,
+        values,
+    }
+}
+
+const fn nth(index: usize, values: &'static [&'static str]) -> EnumeratedValues {
+    EnumeratedValues {
+        index: Some(index),
+        values,
+    }
+}
+
+/// The Patient module (PS3.3 C.7.1.1).
+pub static PATIENT_MODULE: ModuleSpec = ModuleSpec {
+    name: "Patient",
+    attributes: &[
+        attr(tags::PATIENT_NAME, Type2),
+        attr(tags::PATIENT_ID, Type2),
+        attr(tags::PATIENT_BIRTH_DATE, Type2),
+        attr(tags::PATIENT_SEX, Type2).enumerated(&[all(&["M", "F", "O"])]),
+    ],
+};
+
+/// The General Study module (PS3.3 C.7.2.1).
+pub static GENERAL_STUDY_MODULE: ModuleSpec = ModuleSpec {
+    name: "General Study",
+    attributes: &[
+        attr(tags::STUDY_INSTANCE_UID, Type1),
+        attr(tags::STUDY_DATE, Type2),
+        attr(tags::STUDY_TIME, Type2),
+        attr(tags::REFERRING_PHYSICIAN_NAME, Type2),
+        attr(tags::STUDY_ID, Type2),
+        attr(tags::ACCESSION_NUMBER, Type2),
+    ],
+};
+
+/// The General Series module (PS3.3 C.7.3.1).
+pub static GENERAL_SERIES_MODULE: ModuleSpec = ModuleSpec {
+    name: "General Series",
+    attributes: &[
+        attr(tags::MODALITY, Type1),
+        attr(tags::SERIES_INSTANCE_UID, Type1),
+        attr(tags::SERIES_NUMBER, Type2),
+        attr(tags::LATERALITY, Type2C).enumerated(&[all(&["R", "L"])]),
+    ],
+};
+
+/// The Frame of Reference module (PS3.3 C.7.4.1).
+pub static FRAME_OF_REFERENCE_MODULE: ModuleSpec = ModuleSpec {
+    name: "Frame of Reference",
+    attributes: &[
+        attr(tags::FRAME_OF_REFERENCE_UID, Type1),
+        attr(tags::POSITION_REFERENCE_INDICATOR, Type2),
+    ],
+};
+
+/// The General Equipment module (PS3.3 C.7.5.1).
+pub static GENERAL_EQUIPMENT_MODULE: ModuleSpec = ModuleSpec {
+    name: "General Equipment",
+    attributes: &[attr(tags::MANUFACTURER, Type2)],
+};
+
+/// The SC Equipment module (PS3.3 C.8.6.1).
+pub static SC_EQUIPMENT_MODULE: ModuleSpec = ModuleSpec {
+    name: "SC Equipment",
+    attributes: &[attr(tags::CONVERSION_TYPE, Type1)
+        .enumerated(&[all(&["DV", "DI", "DF", "WSD", "SD", "SI", "DRW", "SYN"])])],
+};
+
+/// The General Image module (PS3.3 C.7.6.1).
+pub static GENERAL_IMAGE_MODULE: ModuleSpec = ModuleSpec {
+    name: "General Image",
+    attributes: &[
+        attr(tags::INSTANCE_NUMBER, Type2),
+        attr(tags::PATIENT_ORIENTATION, Type2C).when(no_image_plane),
+    ],
+};
+
+/// The Image Plane module (PS3.3 C.7.6.2).
+pub static IMAGE_PLANE_MODULE: ModuleSpec = ModuleSpec {
+    name: "Image Plane",
+    attributes: &[
+        attr(tags::PIXEL_SPACING, Type1),
+        attr(tags::IMAGE_ORIENTATION_PATIENT, Type1),
+        attr(tags::IMAGE_POSITION_PATIENT, Type1),
+        attr(tags::SLICE_THICKNESS, Type2),
+    ],
+};
+
+/// The Image Pixel module (PS3.3 C.7.6.3).
+pub static IMAGE_PIXEL_MODULE: ModuleSpec = ModuleSpec {
+    name: "Image Pixel",
+    attributes: &[
+        attr(tags::SAMPLES_PER_PIXEL, Type1),
+        attr(tags::PHOTOMETRIC_INTERPRETATION, Type1),
+        attr(tags::ROWS, Type1),
+        attr(tags::COLUMNS, Type1),
+        attr(tags::BITS_ALLOCATED, Type1),
+        attr(tags::BITS_STORED, Type1),
+        attr(tags::HIGH_BIT, Type1),
+        attr(tags::PIXEL_REPRESENTATION, Type1).enumerated(&[all(&["0", "1"])]),
+        attr(tags::PLANAR_CONFIGURATION, Type1C)
+            .when(more_than_one_sample_per_pixel)
+            .enumerated(&[all(&["0", "1"])]),
+        attr(tags::PIXEL_DATA, Type1C).when(pixel_data_not_provided_by_url),
+    ],
+};
+
+/// The CT Image module (PS3.3 C.8.2.1).
+pub static CT_IMAGE_MODULE: ModuleSpec = ModuleSpec {
+    name: "CT Image",
+    attributes: &[
+        attr(tags::IMAGE_TYPE, Type1).enumerated(&[
+            nth(0, &["ORIGINAL", "DERIVED"]),
+            nth(1, &["PRIMARY", "SECONDARY"]),
+            nth(2, &["AXIAL", "LOCALIZER"]),
+        ]),
+        attr(tags::SAMPLES_PER_PIXEL, Type1).enumerated(&[all(&["1"])]),
+        attr(tags::PHOTOMETRIC_INTERPRETATION, Type1)
+            .enumerated(&[all(&["MONOCHROME1", "MONOCHROME2"])]),
+        attr(tags::BITS_ALLOCATED, Type1).enumerated(&[all(&["16"])]),
+        attr(tags::RESCALE_INTERCEPT, Type1),
+        attr(tags::RESCALE_SLOPE, Type1),
+        attr(tags::KVP, Type2),
+        attr(tags::ACQUISITION_NUMBER, Type2),
+    ],
+};
+
+/// The MR Image module (PS3.3 C.8.3.1).
+pub static MR_IMAGE_MODULE: ModuleSpec = ModuleSpec {
+    name: "MR Image",
+    attributes: &[
+        attr(tags::IMAGE_TYPE, Type1).enumerated(&[
+            nth(0, &["ORIGINAL", "DERIVED"]),
+            nth(1, &["PRIMARY", "SECONDARY"]),
+        ]),
+        attr(tags::SAMPLES_PER_PIXEL, Type1).enumerated(&[all(&["1"])]),
+        attr(tags::PHOTOMETRIC_INTERPRETATION, Type1)
+            .enumerated(&[all(&["MONOCHROME1", "MONOCHROME2"])]),
+        attr(tags::BITS_ALLOCATED, Type1).enumerated(&[all(&["16"])]),
+        attr(tags::SCANNING_SEQUENCE, Type1).enumerated(&[all(&["SE", "IR", "GR", "EP", "RM"])]),
+        attr(tags::SEQUENCE_VARIANT, Type1),
+        attr(tags::SCAN_OPTIONS, Type2),
+        attr(tags::MR_ACQUISITION_TYPE, Type2).enumerated(&[all(&["2D", "3D"])]),
+        attr(tags::REPETITION_TIME, Type2C).when(not_echo_planar),
+        attr(tags::ECHO_TIME, Type2),
+        attr(tags::ECHO_TRAIN_LENGTH, Type2),
+    ],
+};
+
+/// The SOP Common module (PS3.3 C.12.1).
+pub static SOP_COMMON_MODULE: ModuleSpec = ModuleSpec {
+    name: "SOP Common",
+    attributes: &[
+        attr(tags::SOP_CLASS_UID, Type1),
+        attr(tags::SOP_INSTANCE_UID, Type1),
+        attr(tags::SPECIFIC_CHARACTER_SET, Type1C),
+    ],
+};
+
+/// The CT Image IOD (PS3.3 A.3).
+pub static CT_IMAGE_IOD: IodSpec = IodSpec {
+    name: "CT Image",
+    modules: &[
+        &PATIENT_MODULE,
+        &GENERAL_STUDY_MODULE,
+        &GENERAL_SERIES_MODULE,
+        &FRAME_OF_REFERENCE_MODULE,
+        &GENERAL_EQUIPMENT_MODULE,
+        &GENERAL_IMAGE_MODULE,
+        &IMAGE_PLANE_MODULE,
+        &IMAGE_PIXEL_MODULE,
+        &CT_IMAGE_MODULE,
+        &SOP_COMMON_MODULE,
+    ],
+};
+
+/// The MR Image IOD (PS3.3 A.4).
+pub static MR_IMAGE_IOD: IodSpec = IodSpec {
+    name: "MR Image",
+    modules: &[
+        &PATIENT_MODULE,
+        &GENERAL_STUDY_MODULE,
+        &GENERAL_SERIES_MODULE,
+        &FRAME_OF_REFERENCE_MODULE,
+        &GENERAL_EQUIPMENT_MODULE,
+        &GENERAL_IMAGE_MODULE,
+        &IMAGE_PLANE_MODULE,
+        &IMAGE_PIXEL_MODULE,
+        &MR_IMAGE_MODULE,
+        &SOP_COMMON_MODULE,
+    ],
+};
+
+/// The Secondary Capture Image IOD (PS3.3 A.8.1).
+pub static SC_IMAGE_IOD: IodSpec = IodSpec {
+    name: "Secondary Capture Image",
+    modules: &[
+        &PATIENT_MODULE,
+        &GENERAL_STUDY_MODULE,
+        &GENERAL_SERIES_MODULE,
+        &GENERAL_EQUIPMENT_MODULE,
+        &SC_EQUIPMENT_MODULE,
+        &GENERAL_IMAGE_MODULE,
+        &IMAGE_PIXEL_MODULE,
+        &SOP_COMMON_MODULE,
+    ],
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FileMetaTableBuilder;
+    use dicom_core::{dicom_value, DataElement};
+
+    fn sc_image() -> InMemDicomObject {
+        let elements = vec![
+            (
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                uids::SECONDARY_CAPTURE_IMAGE_STORAGE.into(),
+            ),
+            (tags::SOP_INSTANCE_UID, VR::UI, "1.2.888.123".into()),
+            (tags::MODALITY, VR::CS, "OT".into()),
+            (tags::CONVERSION_TYPE, VR::CS, "WSD".into()),
+            (tags::PATIENT_NAME, VR::PN, "Doe^John".into()),
+            (tags::PATIENT_ID, VR::LO, "1".into()),
+            (tags::PATIENT_BIRTH_DATE, VR::DA, PrimitiveValue::Empty),
+            (tags::PATIENT_SEX, VR::CS, "O".into()),
+            (tags::STUDY_INSTANCE_UID, VR::UI, "1.2.888.1".into()),
+            (tags::STUDY_DATE, VR::DA, "20230101".into()),
+            (tags::STUDY_TIME, VR::TM, "120000".into()),
+            (
+                tags::REFERRING_PHYSICIAN_NAME,
+                VR::PN,
+                PrimitiveValue::Empty,
+            ),
+            (tags::STUDY_ID, VR::SH, "1".into()),
+            (tags::ACCESSION_NUMBER, VR::SH, PrimitiveValue::Empty),
+            (tags::SERIES_INSTANCE_UID, VR::UI, "1.2.888.1.1".into()),
+            (tags::SERIES_NUMBER, VR::IS, "1".into()),
+            (tags::MANUFACTURER, VR::LO, PrimitiveValue::Empty),
+            (tags::INSTANCE_NUMBER, VR::IS, "1".into()),
+            (tags::PATIENT_ORIENTATION, VR::CS, PrimitiveValue::Empty),
+            (tags::SAMPLES_PER_PIXEL, VR::US, dicom_value!(U16, [1])),
+            (
+                tags::PHOTOMETRIC_INTERPRETATION,
+                VR::CS,
+                "MONOCHROME2".into(),
+            ),
+            (tags::ROWS, VR::US, dicom_value!(U16, [2])),
+            (tags::COLUMNS, VR::US, dicom_value!(U16, [2])),
+            (tags::BITS_ALLOCATED, VR::US, dicom_value!(U16, [8])),
+            (tags::BITS_STORED, VR::US, dicom_value!(U16, [8])),
+            (tags::HIGH_BIT, VR::US, dicom_value!(U16, [7])),
+            (tags::PIXEL_REPRESENTATION, VR::US, dicom_value!(U16, [0])),
+            (
+                tags::PIXEL_DATA,
+                VR::OB,
+                PrimitiveValue::from(vec![0_u8; 4]),
+            ),
+        ];
+        InMemDicomObject::from_element_iter(
+            elements
+                .into_iter()
+                .map(|(tag, vr, value)| DataElement::new(tag, vr, value)),
+        )
+    }
+
+    #[test]
+    fn valid_sc_image() {
+        let report = validate(&sc_image());
+        assert_eq!(report.iod, Some("Secondary Capture Image"));
+        assert_eq!(report.issues, vec![]);
+        assert!(report.is_valid());
+    }
+
+    #[test]
+    fn invalid_sc_image() {
+        let mut obj = sc_image();
+        obj.remove_element(tags::PATIENT_NAME);
+        obj.remove_element(tags::ROWS);
+        obj.put(DataElement::new(
+            tags::PATIENT_SEX,
+            VR::CS,
+            PrimitiveValue::from("X"),
+        ));
+        obj.put(DataElement::new(
+            tags::MODALITY,
+            VR::CS,
+            PrimitiveValue::Empty,
+        ));
+        obj.put(DataElement::new(
+            tags::SAMPLES_PER_PIXEL,
+            VR::US,
+            dicom_value!(U16, [3]),
+        ));
+        obj.put(DataElement::new(
+            tags::COLUMNS,
+            VR::UL,
+            dicom_value!(U32, [2]),
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_SPACING,
+            VR::DS,
+            PrimitiveValue::from("1.0"),
+        ));
+
+        let report = validate(&obj);
+        assert!(!report.is_valid());
+        let issue = |tag| {
+            report
+                .issues
+                .iter()
+                .find(|issue| issue.tag() == tag)
+                .map(|issue| &issue.kind)
+        };
+        assert_eq!(
+            issue(tags::PATIENT_NAME),
+            Some(&IssueKind::Missing {
+                attribute_type: Type2
+            })
+        );
+        assert_eq!(
+            issue(tags::ROWS),
+            Some(&IssueKind::Missing {
+                attribute_type: Type1
+            })
+        );
+        assert!(matches!(
+            issue(tags::PATIENT_SEX),
+            Some(IssueKind::InvalidEnumeratedValue { value, .. }) if value == "X"
+        ));
+        assert_eq!(
+            issue(tags::MODALITY),
+            Some(&IssueKind::Empty {
+                attribute_type: Type1
+            })
+        );
+        assert_eq!(
+            issue(tags::PLANAR_CONFIGURATION),
+            Some(&IssueKind::Missing {
+                attribute_type: Type1C
+            })
+        );
+        assert_eq!(
+            issue(tags::COLUMNS),
+            Some(&IssueKind::UnexpectedVr {
+                expected: VR::US,
+                found: VR::UL
+            })
+        );
+        assert!(matches!(
+            issue(tags::PIXEL_SPACING),
+            Some(IssueKind::InvalidVm { found: 1, .. })
+        ));
+        assert_eq!(report.warnings().count(), 1);
+    }
+
+    #[test]
+    fn validate_file_meta() {
+        let obj = sc_image()
+            .with_meta(
+                FileMetaTableBuilder::new()
+                    .transfer_syntax(uids::EXPLICIT_VR_LITTLE_ENDIAN)
+                    .media_storage_sop_class_uid(uids::CT_IMAGE_STORAGE),
+            )
+            .unwrap();
+        let report = validate_file(&obj);
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].tag(), tags::MEDIA_STORAGE_SOP_CLASS_UID);
+        assert!(matches!(
+            report.issues[0].kind,
+            IssueKind::MetaMismatch { .. }
+        ));
+    }
+}
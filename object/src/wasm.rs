@@ -0,0 +1,247 @@
+//! JavaScript bindings for reading DICOM files in the browser.
+//!
+//! This module is only available with the `wasm` feature,
+//! and is meant to be built for the `wasm32-unknown-unknown` target
+//! (e.g. through `wasm-pack`),
+//! usually along with `default-features = false`
+//! so that file system access is left out.
+//!
+//! The exported function [`parse_dicom`] (`parseDicom` in JavaScript)
+//! takes the full contents of a DICOM file as a `Uint8Array`,
+//! such as one obtained from a `File` or a `fetch` response,
+//! and returns an array of data elements.
+//! Each element is a plain object with the following properties:
+//!
+//! - `tag`: the attribute tag, in the form `"(GGGG,EEEE)"`;
+//! - `alias`: the attribute keyword in the standard data dictionary,
+//!   or `null` if the tag is not in the dictionary;
+//! - `vr`: the value representation, such as `"PN"`;
+//! - `value`: the value of the element, which is
+//!   a `Uint8Array` for binary values (OB and UN),
+//!   an array of items (each an array of elements) for sequences,
+//!   an array of `Uint8Array` fragments for encapsulated pixel data,
+//!   `null` for empty values,
+//!   or a string with the textual representation of the value otherwise
+//!   (multiple values are separated by a backslash).
+//!
+//! ```js
+//! import init, { parseDicom } from "./dicom_object.js";
+//!
+//! await init();
+//! const bytes = new Uint8Array(await file.arrayBuffer());
+//! for (const element of parseDicom(bytes)) {
+//!     console.log(element.tag, element.alias, element.value);
+//! }
+//! ```
+use bytes::Bytes;
+use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{DataElement, Tag};
+use dicom_dictionary_std::StandardDataDictionary;
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::file::ReadPreamble;
+use crate::{InMemDicomObject, OpenFileOptions};
+
+/// Parse the contents of a DICOM file
+/// into an array of data elements,
+/// starting with the file meta group.
+///
+/// The 128-byte preamble is skipped if present.
+/// Fails with a JavaScript `Error`
+/// if the data is not a valid DICOM file.
+#[wasm_bindgen(js_name = parseDicom)]
+pub fn parse_dicom(data: &[u8]) -> Result<Array, JsValue> {
+    let obj = OpenFileOptions::new()
+        .read_preamble(read_preamble_of(data))
+        .from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| JsError::new(&e.to_string()))?;
+
+    let out = Array::new();
+    for elem in obj.meta().to_element_iter() {
+        out.push(&JsValue::from(element_to_js(&elem, |_| Ok(Array::new()))?));
+    }
+    for elem in obj.iter_in_order() {
+        out.push(&JsValue::from(element_to_js(elem, dataset_to_js)?));
+    }
+    Ok(out)
+}
+
+/// Determine whether the given file contents start with a preamble.
+fn read_preamble_of(data: &[u8]) -> ReadPreamble {
+    if data.get(128..132) == Some(&b"DICM"[..]) {
+        ReadPreamble::Always
+    } else {
+        ReadPreamble::Never
+    }
+}
+
+fn dataset_to_js(obj: &InMemDicomObject) -> Result<Array, JsValue> {
+    let out = Array::new();
+    for elem in obj.iter_in_order() {
+        out.push(&JsValue::from(element_to_js(elem, dataset_to_js)?));
+    }
+    Ok(out)
+}
+
+/// The value of a data element, as exposed to JavaScript.
+#[derive(Debug, PartialEq)]
+enum ElementValue<'a, I> {
+    /// `null`
+    Null,
+    /// a `Uint8Array`
+    Bytes(&'a [u8]),
+    /// a string
+    Text(String),
+    /// an array of items
+    Items(&'a [I]),
+    /// an array of `Uint8Array` fragments
+    Fragments(Vec<&'a [u8]>),
+}
+
+fn element_value<I, P>(elem: &DataElement<I, P>) -> ElementValue<'_, I>
+where
+    P: AsRef<[u8]>,
+{
+    match elem.value() {
+        Value::Primitive(PrimitiveValue::Empty) => ElementValue::Null,
+        Value::Primitive(PrimitiveValue::U8(bytes)) => ElementValue::Bytes(bytes),
+        Value::Primitive(PrimitiveValue::SharedBytes(bytes)) => ElementValue::Bytes(bytes),
+        Value::Primitive(v) => ElementValue::Text(v.to_str().into_owned()),
+        Value::Sequence { items, .. } => ElementValue::Items(items),
+        Value::PixelSequence { fragments, .. } => {
+            ElementValue::Fragments(fragments.iter().map(|f| f.as_ref()).collect())
+        }
+    }
+}
+
+/// Obtain the keyword of the attribute with the given tag,
+/// if it is in the standard data dictionary.
+fn alias_of(tag: Tag) -> Option<&'static str> {
+    StandardDataDictionary
+        .by_tag(tag)
+        .map(|entry| entry.alias())
+}
+
+fn element_to_js<I, P>(
+    elem: &DataElement<I, P>,
+    item_to_js: fn(&I) -> Result<Array, JsValue>,
+) -> Result<Object, JsValue>
+where
+    P: AsRef<[u8]>,
+{
+    let tag = elem.tag();
+    let alias = alias_of(tag)
+        .map(JsValue::from_str)
+        .unwrap_or(JsValue::NULL);
+    let value = match element_value(elem) {
+        ElementValue::Null => JsValue::NULL,
+        ElementValue::Bytes(bytes) => Uint8Array::from(bytes).into(),
+        ElementValue::Text(text) => JsValue::from_str(&text),
+        ElementValue::Items(items) => {
+            let out = Array::new();
+            for item in items {
+                out.push(&JsValue::from(item_to_js(item)?));
+            }
+            out.into()
+        }
+        ElementValue::Fragments(fragments) => fragments
+            .into_iter()
+            .map(|fragment| JsValue::from(Uint8Array::from(fragment)))
+            .collect::<Array>()
+            .into(),
+    };
+
+    let out = Object::new();
+    Reflect::set(&out, &"tag".into(), &tag.to_string().into())?;
+    Reflect::set(&out, &"alias".into(), &alias)?;
+    Reflect::set(&out, &"vr".into(), &elem.vr().to_string().into())?;
+    Reflect::set(&out, &"value".into(), &value)?;
+    Ok(out)
+}
+
+// JavaScript values can only be created on the wasm32 target,
+// so only the parts of the conversion before that are tested natively.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mem::InMemElement;
+    use dicom_core::{dicom_value, Length, VR};
+    use dicom_dictionary_std::tags;
+    use smallvec::smallvec;
+
+    #[test]
+    fn element_values() {
+        let elem: InMemElement = DataElement::empty(tags::PATIENT_NAME, VR::PN);
+        assert_eq!(element_value(&elem), ElementValue::Null);
+
+        let elem: InMemElement = DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            PrimitiveValue::from(vec![1_u8, 2, 3, 4]),
+        );
+        assert_eq!(element_value(&elem), ElementValue::Bytes(&[1, 2, 3, 4]));
+
+        let elem: InMemElement = DataElement::new(
+            tags::IMAGE_TYPE,
+            VR::CS,
+            dicom_value!(Strs, ["ORIGINAL", "PRIMARY"]),
+        );
+        assert_eq!(
+            element_value(&elem),
+            ElementValue::Text("ORIGINAL\\PRIMARY".to_string())
+        );
+
+        let elem: InMemElement =
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from(512_u16));
+        assert_eq!(element_value(&elem), ElementValue::Text("512".to_string()));
+
+        let item = InMemDicomObject::from_element_iter([DataElement::new(
+            tags::CODE_VALUE,
+            VR::SH,
+            PrimitiveValue::from("121071"),
+        )]);
+        let elem: InMemElement = DataElement::new(
+            tags::CONCEPT_NAME_CODE_SEQUENCE,
+            VR::SQ,
+            Value::Sequence {
+                items: smallvec![item.clone()],
+                size: Length::UNDEFINED,
+            },
+        );
+        match element_value(&elem) {
+            ElementValue::Items(items) => assert_eq!(items, &[item][..]),
+            value => panic!("unexpected value {:?}", value),
+        }
+
+        let elem: InMemElement = DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            Value::PixelSequence {
+                offset_table: smallvec![0],
+                fragments: smallvec![vec![1, 2], vec![3, 4]],
+            },
+        );
+        assert_eq!(
+            element_value(&elem),
+            ElementValue::Fragments(vec![&[1, 2][..], &[3, 4][..]])
+        );
+    }
+
+    #[test]
+    fn aliases() {
+        assert_eq!(alias_of(tags::PATIENT_NAME), Some("PatientName"));
+        assert_eq!(alias_of(Tag(0x0009, 0x1001)), None);
+    }
+
+    #[test]
+    fn preamble_detection() {
+        let mut data = vec![0_u8; 132];
+        data[128..].copy_from_slice(b"DICM");
+        assert_eq!(read_preamble_of(&data), ReadPreamble::Always);
+        assert_eq!(read_preamble_of(&data[128..]), ReadPreamble::Never);
+        assert_eq!(read_preamble_of(&[]), ReadPreamble::Never);
+    }
+}
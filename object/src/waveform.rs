@@ -0,0 +1,602 @@
+//! Interpretation of waveform data, such as ECG and hemodynamic signals.
+//!
+//! Waveform objects hold one or more _multiplex groups_
+//! in the _Waveform Sequence_ (5400,0100).
+//! Each group contains the samples of several channels
+//! taken at the same sampling frequency,
+//! interleaved in _Waveform Data_ (5400,1010),
+//! and describes each channel in the _Channel Definition Sequence_.
+//! [`waveforms`] reads every multiplex group into a [`MultiplexGroup`],
+//! holding the samples of each [`Channel`] separately,
+//! along with their conversion to the channel sensitivity units.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_object::open_file;
+//! use dicom_object::waveform::waveforms;
+//! # fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let obj = open_file("ecg.dcm")?;
+//! for group in waveforms(&obj)? {
+//!     println!("{} Hz, {} s", group.sampling_frequency, group.duration());
+//!     for channel in &group.channels {
+//!         let values = channel.values();
+//!         println!(
+//!             "{}: {} samples in {}",
+//!             channel.definition.label.as_deref().unwrap_or("?"),
+//!             values.len(),
+//!             channel.units().map(|u| u.value.as_str()).unwrap_or("?"),
+//!         );
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use dicom_core::dictionary::DataDictionary;
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::Tag;
+use dicom_dictionary_std::tags;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::code::CodedConcept;
+use crate::mem::InMemElement;
+use crate::InMemDicomObject;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// A required attribute is missing from a multiplex group.
+    #[snafu(display("Missing attribute {}", tag))]
+    MissingAttribute { tag: Tag, backtrace: Backtrace },
+
+    /// An attribute could not be converted to the expected type.
+    #[snafu(display("Invalid value for attribute {}", tag))]
+    InvalidAttribute {
+        tag: Tag,
+        backtrace: Backtrace,
+        source: dicom_core::value::ConvertValueError,
+    },
+
+    /// The waveform sample interpretation is not supported.
+    #[snafu(display("Unsupported waveform sample interpretation `{}`", value))]
+    UnsupportedInterpretation { value: String, backtrace: Backtrace },
+
+    /// _Waveform Bits Allocated_ does not match
+    /// the waveform sample interpretation.
+    #[snafu(display(
+        "Waveform bits allocated {} does not match sample interpretation {}",
+        bits_allocated,
+        interpretation
+    ))]
+    BitsAllocatedMismatch {
+        bits_allocated: u16,
+        interpretation: &'static str,
+        backtrace: Backtrace,
+    },
+
+    /// The number of channel definitions does not match
+    /// _Number of Waveform Channels_.
+    #[snafu(display("Expected {} channel definitions, found {}", expected, got))]
+    ChannelCountMismatch {
+        expected: usize,
+        got: usize,
+        backtrace: Backtrace,
+    },
+
+    /// The waveform data is shorter than
+    /// the number of channels and samples require.
+    #[snafu(display("Expected {} bytes of waveform data, found {}", expected, got))]
+    NotEnoughData {
+        expected: usize,
+        got: usize,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The data type of the samples of a multiplex group,
+/// from _Waveform Sample Interpretation_.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum SampleInterpretation {
+    /// `SB`: signed 8-bit linear
+    SignedByte,
+    /// `UB`: unsigned 8-bit linear
+    UnsignedByte,
+    /// `MB`: 8-bit mu-law (ITU-T G.711)
+    MuLaw,
+    /// `AB`: 8-bit A-law (ITU-T G.711)
+    ALaw,
+    /// `SS`: signed 16-bit linear
+    SignedShort,
+    /// `US`: unsigned 16-bit linear
+    UnsignedShort,
+}
+
+impl SampleInterpretation {
+    /// Obtain the sample interpretation from its defined term.
+    pub fn from_value(value: &str) -> Option<Self> {
+        match value {
+            "SB" => Some(SampleInterpretation::SignedByte),
+            "UB" => Some(SampleInterpretation::UnsignedByte),
+            "MB" => Some(SampleInterpretation::MuLaw),
+            "AB" => Some(SampleInterpretation::ALaw),
+            "SS" => Some(SampleInterpretation::SignedShort),
+            "US" => Some(SampleInterpretation::UnsignedShort),
+            _ => None,
+        }
+    }
+
+    /// Obtain the defined term of this sample interpretation.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SampleInterpretation::SignedByte => "SB",
+            SampleInterpretation::UnsignedByte => "UB",
+            SampleInterpretation::MuLaw => "MB",
+            SampleInterpretation::ALaw => "AB",
+            SampleInterpretation::SignedShort => "SS",
+            SampleInterpretation::UnsignedShort => "US",
+        }
+    }
+
+    /// Obtain the number of bits allocated per sample.
+    pub fn bits_allocated(self) -> u16 {
+        match self {
+            SampleInterpretation::SignedShort | SampleInterpretation::UnsignedShort => 16,
+            _ => 8,
+        }
+    }
+}
+
+/// The definition of a waveform channel,
+/// from an item of the _Channel Definition Sequence_.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelDefinition {
+    /// The channel label, if present.
+    pub label: Option<String>,
+    /// The source of the signal, such as an ECG lead,
+    /// from the _Channel Source Sequence_.
+    pub source: Option<CodedConcept>,
+    /// The value of one unit of the encoded samples
+    /// in the channel sensitivity units, if present.
+    pub sensitivity: Option<f64>,
+    /// The units of the channel sensitivity,
+    /// usually a UCUM code such as `uV`,
+    /// from the _Channel Sensitivity Units Sequence_.
+    pub sensitivity_units: Option<CodedConcept>,
+    /// The channel sensitivity correction factor (1 if absent).
+    pub sensitivity_correction_factor: f64,
+    /// The offset of encoded sample value 0 from actual 0,
+    /// in the channel sensitivity units (0 if absent).
+    pub baseline: f64,
+    /// The number of significant bits of each sample, if present.
+    pub bits_stored: Option<u16>,
+}
+
+/// A single waveform channel with its samples.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Channel {
+    /// The definition of the channel.
+    pub definition: ChannelDefinition,
+    /// The samples of the channel as encoded,
+    /// with mu-law and A-law samples expanded to 16-bit linear values.
+    pub samples: Vec<i32>,
+}
+
+impl Channel {
+    /// Obtain the units of the channel values, if known.
+    pub fn units(&self) -> Option<&CodedConcept> {
+        self.definition.sensitivity_units.as_ref()
+    }
+
+    /// Obtain the samples of the channel in the channel sensitivity units,
+    /// by applying the channel sensitivity, correction factor, and baseline.
+    ///
+    /// If the channel has no sensitivity,
+    /// the samples are only offset by the baseline.
+    pub fn values(&self) -> Vec<f64> {
+        let definition = &self.definition;
+        let scale = definition.sensitivity.unwrap_or(1.) * definition.sensitivity_correction_factor;
+        self.samples
+            .iter()
+            .map(|&sample| f64::from(sample) * scale + definition.baseline)
+            .collect()
+    }
+}
+
+/// A multiplex group of a waveform object,
+/// from an item of the _Waveform Sequence_.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiplexGroup {
+    /// The multiplex group label, if present.
+    pub label: Option<String>,
+    /// The sampling frequency, in hertz.
+    pub sampling_frequency: f64,
+    /// The number of samples in each channel.
+    pub number_of_samples: usize,
+    /// The offset of the first sample
+    /// from the acquisition time of the object, in milliseconds.
+    pub time_offset: Option<f64>,
+    /// The data type of the encoded samples.
+    pub interpretation: SampleInterpretation,
+    /// The channels of the group, in the order of their definitions.
+    pub channels: Vec<Channel>,
+}
+
+impl MultiplexGroup {
+    /// Obtain the duration of the waveform, in seconds.
+    pub fn duration(&self) -> f64 {
+        self.number_of_samples as f64 / self.sampling_frequency
+    }
+
+    /// Look up a channel by its label,
+    /// ignoring surrounding whitespace.
+    pub fn channel_by_label(&self, label: &str) -> Option<&Channel> {
+        let label = label.trim();
+        self.channels
+            .iter()
+            .find(|channel| channel.definition.label.as_deref().map(str::trim) == Some(label))
+    }
+}
+
+/// Read all multiplex groups of a waveform object.
+pub fn waveforms<D>(obj: &InMemDicomObject<D>) -> Result<Vec<MultiplexGroup>>
+where
+    D: DataDictionary + Clone,
+{
+    items(obj, tags::WAVEFORM_SEQUENCE)
+        .iter()
+        .map(multiplex_group)
+        .collect()
+}
+
+fn multiplex_group<D>(obj: &InMemDicomObject<D>) -> Result<MultiplexGroup>
+where
+    D: DataDictionary + Clone,
+{
+    let number_of_channels = int(obj, tags::NUMBER_OF_WAVEFORM_CHANNELS)? as usize;
+    let number_of_samples = int(obj, tags::NUMBER_OF_WAVEFORM_SAMPLES)? as usize;
+    let sampling_frequency =
+        float(obj, tags::SAMPLING_FREQUENCY)?.context(MissingAttributeSnafu {
+            tag: tags::SAMPLING_FREQUENCY,
+        })?;
+
+    let value =
+        opt_string(obj, tags::WAVEFORM_SAMPLE_INTERPRETATION).context(MissingAttributeSnafu {
+            tag: tags::WAVEFORM_SAMPLE_INTERPRETATION,
+        })?;
+    let interpretation = SampleInterpretation::from_value(&value)
+        .context(UnsupportedInterpretationSnafu { value })?;
+    let bits_allocated = int(obj, tags::WAVEFORM_BITS_ALLOCATED)? as u16;
+    ensure!(
+        bits_allocated == interpretation.bits_allocated(),
+        BitsAllocatedMismatchSnafu {
+            bits_allocated,
+            interpretation: interpretation.as_str(),
+        }
+    );
+
+    let definitions = items(obj, tags::CHANNEL_DEFINITION_SEQUENCE);
+    ensure!(
+        definitions.len() == number_of_channels,
+        ChannelCountMismatchSnafu {
+            expected: number_of_channels,
+            got: definitions.len(),
+        }
+    );
+
+    let data = match element(obj, tags::WAVEFORM_DATA).map(|e| e.value()) {
+        Some(Value::Primitive(value)) => decode_samples(value, interpretation),
+        _ => {
+            return MissingAttributeSnafu {
+                tag: tags::WAVEFORM_DATA,
+            }
+            .fail()
+        }
+    };
+    let bytes_per_sample = usize::from(bits_allocated / 8);
+    ensure!(
+        data.len() >= number_of_channels * number_of_samples,
+        NotEnoughDataSnafu {
+            expected: number_of_channels * number_of_samples * bytes_per_sample,
+            got: data.len() * bytes_per_sample,
+        }
+    );
+
+    let mut channels = Vec::with_capacity(number_of_channels);
+    for (i, definition) in definitions.iter().enumerate() {
+        channels.push(Channel {
+            definition: channel_definition(definition)?,
+            samples: data
+                .iter()
+                .skip(i)
+                .step_by(number_of_channels)
+                .take(number_of_samples)
+                .copied()
+                .collect(),
+        });
+    }
+
+    Ok(MultiplexGroup {
+        label: opt_string(obj, tags::MULTIPLEX_GROUP_LABEL),
+        sampling_frequency,
+        number_of_samples,
+        time_offset: float(obj, tags::MULTIPLEX_GROUP_TIME_OFFSET)?,
+        interpretation,
+        channels,
+    })
+}
+
+fn channel_definition<D>(obj: &InMemDicomObject<D>) -> Result<ChannelDefinition>
+where
+    D: DataDictionary + Clone,
+{
+    Ok(ChannelDefinition {
+        label: opt_string(obj, tags::CHANNEL_LABEL),
+        source: CodedConcept::from_sequence(obj, tags::CHANNEL_SOURCE_SEQUENCE),
+        sensitivity: float(obj, tags::CHANNEL_SENSITIVITY)?,
+        sensitivity_units: CodedConcept::from_sequence(
+            obj,
+            tags::CHANNEL_SENSITIVITY_UNITS_SEQUENCE,
+        ),
+        sensitivity_correction_factor: float(obj, tags::CHANNEL_SENSITIVITY_CORRECTION_FACTOR)?
+            .unwrap_or(1.),
+        baseline: float(obj, tags::CHANNEL_BASELINE)?.unwrap_or(0.),
+        bits_stored: match element(obj, tags::WAVEFORM_BITS_STORED) {
+            Some(_) => Some(int(obj, tags::WAVEFORM_BITS_STORED)? as u16),
+            None => None,
+        },
+    })
+}
+
+/// Decode the interleaved samples of _Waveform Data_.
+///
+/// 16-bit samples are taken from the values as read
+/// if the data was decoded as OW,
+/// or from the bytes in little endian otherwise.
+fn decode_samples(value: &PrimitiveValue, interpretation: SampleInterpretation) -> Vec<i32> {
+    match (interpretation, value) {
+        (SampleInterpretation::SignedShort, PrimitiveValue::U16(values)) => {
+            values.iter().map(|&v| i32::from(v as i16)).collect()
+        }
+        (SampleInterpretation::UnsignedShort, PrimitiveValue::U16(values)) => {
+            values.iter().map(|&v| i32::from(v)).collect()
+        }
+        (SampleInterpretation::SignedShort, PrimitiveValue::I16(values)) => {
+            values.iter().map(|&v| i32::from(v)).collect()
+        }
+        (SampleInterpretation::UnsignedShort, PrimitiveValue::I16(values)) => {
+            values.iter().map(|&v| i32::from(v as u16)).collect()
+        }
+        (SampleInterpretation::SignedShort, value) => value
+            .to_bytes()
+            .chunks_exact(2)
+            .map(|b| i32::from(i16::from_le_bytes([b[0], b[1]])))
+            .collect(),
+        (SampleInterpretation::UnsignedShort, value) => value
+            .to_bytes()
+            .chunks_exact(2)
+            .map(|b| i32::from(u16::from_le_bytes([b[0], b[1]])))
+            .collect(),
+        (SampleInterpretation::SignedByte, value) => value
+            .to_bytes()
+            .iter()
+            .map(|&b| i32::from(b as i8))
+            .collect(),
+        (SampleInterpretation::UnsignedByte, value) => {
+            value.to_bytes().iter().map(|&b| i32::from(b)).collect()
+        }
+        (SampleInterpretation::MuLaw, value) => {
+            value.to_bytes().iter().map(|&b| mu_law(b)).collect()
+        }
+        (SampleInterpretation::ALaw, value) => value.to_bytes().iter().map(|&b| a_law(b)).collect(),
+    }
+}
+
+/// Expand an 8-bit mu-law sample (ITU-T G.711) to 16-bit linear.
+fn mu_law(sample: u8) -> i32 {
+    let sample = !sample;
+    let exponent = (sample >> 4) & 0x07;
+    let mantissa = i32::from(sample & 0x0F);
+    let magnitude = (((mantissa << 3) + 0x84) << exponent) - 0x84;
+    if sample & 0x80 != 0 {
+        -magnitude
+    } else {
+        magnitude
+    }
+}
+
+/// Expand an 8-bit A-law sample (ITU-T G.711) to 16-bit linear.
+fn a_law(sample: u8) -> i32 {
+    let sample = sample ^ 0x55;
+    let exponent = (sample >> 4) & 0x07;
+    let mantissa = i32::from(sample & 0x0F);
+    let magnitude = if exponent == 0 {
+        (mantissa << 4) + 8
+    } else {
+        ((mantissa << 4) + 0x108) << (exponent - 1)
+    };
+    if sample & 0x80 != 0 {
+        magnitude
+    } else {
+        -magnitude
+    }
+}
+
+fn items<D>(obj: &InMemDicomObject<D>, tag: Tag) -> &[InMemDicomObject<D>]
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag).and_then(|e| e.items()).unwrap_or(&[])
+}
+
+fn element<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<&InMemElement<D>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tag).ok().flatten()
+}
+
+fn opt_string<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Option<String>
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag)
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.into_owned())
+}
+
+fn int<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Result<u32>
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag)
+        .context(MissingAttributeSnafu { tag })?
+        .to_int()
+        .context(InvalidAttributeSnafu { tag })
+}
+
+fn float<D>(obj: &InMemDicomObject<D>, tag: Tag) -> Result<Option<f64>>
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, tag)
+        .map(|e| e.to_float64().context(InvalidAttributeSnafu { tag }))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::value::C;
+    use dicom_core::{DataElement, Length, VR};
+
+    fn sequence(tag: Tag, items: Vec<InMemDicomObject>) -> InMemElement {
+        DataElement::new(
+            tag,
+            VR::SQ,
+            Value::Sequence {
+                items: C::from_vec(items),
+                size: Length::UNDEFINED,
+            },
+        )
+    }
+
+    fn channel(label: &str, sensitivity: &str, baseline: &str) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(tags::CHANNEL_LABEL, VR::SH, PrimitiveValue::from(label)),
+            DataElement::new(
+                tags::CHANNEL_SENSITIVITY,
+                VR::DS,
+                PrimitiveValue::from(sensitivity),
+            ),
+            CodedConcept::new("uV", "UCUM", "microvolt")
+                .to_sequence_element(tags::CHANNEL_SENSITIVITY_UNITS_SEQUENCE),
+            DataElement::new(
+                tags::CHANNEL_BASELINE,
+                VR::DS,
+                PrimitiveValue::from(baseline),
+            ),
+            DataElement::new(
+                tags::WAVEFORM_BITS_STORED,
+                VR::US,
+                PrimitiveValue::from(16_u16),
+            ),
+        ])
+    }
+
+    fn ecg(data: PrimitiveValue) -> InMemDicomObject {
+        let group = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::NUMBER_OF_WAVEFORM_CHANNELS,
+                VR::US,
+                PrimitiveValue::from(2_u16),
+            ),
+            DataElement::new(
+                tags::NUMBER_OF_WAVEFORM_SAMPLES,
+                VR::UL,
+                PrimitiveValue::from(3_u32),
+            ),
+            DataElement::new(
+                tags::SAMPLING_FREQUENCY,
+                VR::DS,
+                PrimitiveValue::from("500"),
+            ),
+            DataElement::new(
+                tags::MULTIPLEX_GROUP_LABEL,
+                VR::SH,
+                PrimitiveValue::from("RHYTHM"),
+            ),
+            sequence(
+                tags::CHANNEL_DEFINITION_SEQUENCE,
+                vec![channel("Lead I", "2.5", "0"), channel("Lead II", "1", "10")],
+            ),
+            DataElement::new(
+                tags::WAVEFORM_BITS_ALLOCATED,
+                VR::US,
+                PrimitiveValue::from(16_u16),
+            ),
+            DataElement::new(
+                tags::WAVEFORM_SAMPLE_INTERPRETATION,
+                VR::CS,
+                PrimitiveValue::from("SS"),
+            ),
+            DataElement::new(tags::WAVEFORM_DATA, VR::OW, data),
+        ]);
+        InMemDicomObject::from_element_iter([sequence(tags::WAVEFORM_SEQUENCE, vec![group])])
+    }
+
+    #[test]
+    fn reads_multiplex_group() {
+        // samples interleaved by channel: (I, II), (I, II), (I, II)
+        let samples: [i16; 6] = [4, -1, 8, -2, -12, 3];
+        let obj = ecg(PrimitiveValue::U16(
+            samples.iter().map(|&v| v as u16).collect(),
+        ));
+        let groups = waveforms(&obj).unwrap();
+        assert_eq!(groups.len(), 1);
+        let group = &groups[0];
+        assert_eq!(group.label.as_deref(), Some("RHYTHM"));
+        assert_eq!(group.number_of_samples, 3);
+        assert_eq!(group.interpretation, SampleInterpretation::SignedShort);
+        assert!((group.duration() - 0.006).abs() < 1e-9);
+
+        let lead_1 = group.channel_by_label("Lead I").unwrap();
+        assert_eq!(lead_1.samples, vec![4, 8, -12]);
+        assert_eq!(lead_1.values(), vec![10., 20., -30.]);
+        assert_eq!(lead_1.units().unwrap().value, "uV");
+        assert_eq!(lead_1.definition.bits_stored, Some(16));
+
+        let lead_2 = &group.channels[1];
+        assert_eq!(lead_2.samples, vec![-1, -2, 3]);
+        assert_eq!(lead_2.values(), vec![9., 8., 13.]);
+
+        // same samples as little endian bytes
+        let bytes: Vec<u8> = samples.iter().flat_map(|v| v.to_le_bytes()).collect();
+        let groups = waveforms(&ecg(PrimitiveValue::from(bytes))).unwrap();
+        assert_eq!(groups[0].channels[0].samples, vec![4, 8, -12]);
+    }
+
+    #[test]
+    fn rejects_short_data() {
+        let obj = ecg(PrimitiveValue::U16(C::from_vec(vec![1, 2, 3])));
+        assert!(matches!(
+            waveforms(&obj),
+            Err(Error::NotEnoughData {
+                expected: 12,
+                got: 6,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn expands_companded_samples() {
+        assert_eq!(mu_law(0xFF), 0);
+        assert_eq!(mu_law(0x80), 32124);
+        assert_eq!(mu_law(0x00), -32124);
+        assert_eq!(a_law(0xD5), 8);
+        assert_eq!(a_law(0x55), -8);
+        assert_eq!(a_law(0xAA), 32256);
+    }
+}
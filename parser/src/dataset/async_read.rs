@@ -0,0 +1,281 @@
+//! This module contains an asynchronous abstraction for reading DICOM content
+//! sequentially from a [`tokio::io::AsyncRead`] source.
+//!
+//! The [`AsyncDataSetReader`] reads the source in chunks
+//! and feeds them to a [`PushDataSetReader`],
+//! so that it shares the decoding state machine
+//! of the synchronous [`DataSetReader`](super::DataSetReader)
+//! and produces the same tokens for the same data.
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::TransferSyntax;
+use snafu::{Backtrace, ResultExt, Snafu};
+use std::collections::VecDeque;
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use super::push::PushDataSetReader;
+use super::read::{DataSetReaderOptions, Error as ReadError, ParseIssue};
+use super::DataToken;
+use crate::ErrorKind;
+
+/// The number of bytes requested from the source in each read.
+const READ_CHUNK_SIZE: usize = 8 * 1024;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("Could not create data set reader"))]
+    CreateReader {
+        #[snafu(backtrace)]
+        source: ReadError,
+    },
+    #[snafu(display("Could not read from source"))]
+    ReadSource {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Could not read data set token"))]
+    ReadToken {
+        #[snafu(backtrace)]
+        source: ReadError,
+    },
+}
+
+impl Error {
+    /// Classify this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::CreateReader { source } | Error::ReadToken { source } => source.kind(),
+            Error::ReadSource { source, .. } => ErrorKind::from(source),
+        }
+    }
+
+    /// Retrieve the position in the data at which the error occurred,
+    /// if known.
+    pub fn position(&self) -> Option<u64> {
+        match self {
+            Error::CreateReader { source } | Error::ReadToken { source } => source.position(),
+            Error::ReadSource { .. } => None,
+        }
+    }
+
+    /// Retrieve the tag of the data element being read
+    /// when the error occurred,
+    /// if known.
+    pub fn tag(&self) -> Option<dicom_core::Tag> {
+        match self {
+            Error::CreateReader { source } | Error::ReadToken { source } => source.tag(),
+            Error::ReadSource { .. } => None,
+        }
+    }
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// An asynchronous reader for retrieving structure in a DICOM data set
+/// from a [`tokio::io::AsyncRead`] source.
+///
+/// Tokens are retrieved one at a time with [`next`](Self::next).
+/// No seeking is performed on the source.
+#[derive(Debug)]
+pub struct AsyncDataSetReader<R> {
+    /// the data source
+    source: R,
+    /// the state machine fed with the data read
+    reader: PushDataSetReader,
+    /// the tokens decoded but not yet retrieved
+    tokens: VecDeque<DataToken>,
+    /// the buffer for reading from the source
+    chunk: Vec<u8>,
+    /// whether the source has ended or failed
+    end_of_data: bool,
+}
+
+impl<R> AsyncDataSetReader<R>
+where
+    R: AsyncRead + Unpin,
+{
+    /// Create a new asynchronous reader
+    /// for a data set in the given transfer syntax.
+    pub fn new_with_ts(source: R, ts: &TransferSyntax) -> Result<Self> {
+        Self::new_with_ts_cs_options(
+            source,
+            ts,
+            SpecificCharacterSet::Default,
+            Default::default(),
+        )
+    }
+
+    /// Create a new asynchronous reader
+    /// for a data set in the given transfer syntax,
+    /// with the given specific character set and options.
+    pub fn new_with_ts_cs_options(
+        source: R,
+        ts: &TransferSyntax,
+        cs: SpecificCharacterSet,
+        options: DataSetReaderOptions,
+    ) -> Result<Self> {
+        let reader = PushDataSetReader::new_with_ts_cs_options(ts, cs, options)
+            .context(CreateReaderSnafu)?;
+        Ok(AsyncDataSetReader {
+            source,
+            reader,
+            tokens: VecDeque::new(),
+            chunk: vec![0; READ_CHUNK_SIZE],
+            end_of_data: false,
+        })
+    }
+
+    /// Report the [issues](ParseIssue) found while reading
+    /// to the given callback.
+    pub fn with_issue_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ParseIssue) + Send + 'static,
+    {
+        self.reader = self.reader.with_issue_callback(callback);
+        self
+    }
+
+    /// Gather the [issues](ParseIssue) found while reading,
+    /// so that they can be retrieved with [`take_issues`](Self::take_issues).
+    pub fn collect_issues(mut self) -> Self {
+        self.reader = self.reader.collect_issues();
+        self
+    }
+
+    /// Retrieve the issues gathered so far,
+    /// leaving the reader's collection empty.
+    pub fn take_issues(&mut self) -> Vec<ParseIssue> {
+        self.reader.take_issues()
+    }
+
+    /// Retrieve the next token of the data set,
+    /// reading more data from the source as needed.
+    ///
+    /// Returns `None` once the end of the data set is reached.
+    /// No more tokens are produced after an error.
+    pub async fn next(&mut self) -> Option<Result<DataToken>> {
+        loop {
+            if let Some(token) = self.tokens.pop_front() {
+                return Some(Ok(token));
+            }
+            if self.end_of_data || self.reader.is_finished() {
+                return None;
+            }
+
+            let tokens = match self.source.read(&mut self.chunk).await {
+                Ok(0) => {
+                    self.end_of_data = true;
+                    self.reader.finish()
+                }
+                Ok(n) => self.reader.feed(&self.chunk[..n]),
+                Err(e) => {
+                    self.end_of_data = true;
+                    return Some(Err(e).context(ReadSourceSnafu));
+                }
+            };
+            match tokens {
+                Ok(tokens) => self.tokens.extend(tokens),
+                Err(e) => {
+                    self.end_of_data = true;
+                    return Some(Err(e).context(ReadTokenSnafu));
+                }
+            }
+        }
+    }
+
+    /// Read all remaining tokens of the data set.
+    pub async fn read_to_end(&mut self) -> Result<Vec<DataToken>> {
+        let mut tokens = Vec::new();
+        while let Some(token) = self.next().await {
+            tokens.push(token?);
+        }
+        Ok(tokens)
+    }
+
+    /// Retrieve the underlying data source,
+    /// discarding any data already read from it.
+    pub fn into_inner(self) -> R {
+        self.source
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AsyncDataSetReader;
+    use crate::dataset::{DataSetReader, DataToken};
+    use dicom_encoding::text::SpecificCharacterSet;
+    use dicom_encoding::transfer_syntax::{AdapterFreeTransferSyntax, Codec, Endianness};
+
+    #[tokio::test]
+    async fn read_tokens_async() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0008,0060) Modality; CS; len = 2
+            0x08, 0x00, 0x60, 0x00, b'C', b'S', 0x02, 0x00,
+            b'M', b'R',
+            // (0040,0555) AcquisitionContextSequence; len = undefined
+            0x40, 0x00, 0x55, 0x05, b'S', b'Q', 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+            // ItemStart: len = 10
+            0xfe, 0xff, 0x00, 0xe0, 0x0a, 0x00, 0x00, 0x00,
+            // (0008,0100) CodeValue; SH; len = 2
+            0x08, 0x00, 0x00, 0x01, b'S', b'H', 0x02, 0x00,
+            b'1', b'2',
+            // SequenceEnd
+            0xfe, 0xff, 0xdd, 0xe0, 0x00, 0x00, 0x00, 0x00,
+            // (0010,0010) PatientName; PN; len = 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+        ];
+
+        let ts = AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2.1",
+            "Explicit VR Little Endian",
+            Endianness::Little,
+            true,
+            Codec::None,
+        )
+        .erased();
+
+        let expected: Vec<DataToken> = DataSetReader::new_with_ts_cs_options(
+            DATA,
+            &ts,
+            SpecificCharacterSet::Default,
+            Default::default(),
+        )
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+        let mut reader = AsyncDataSetReader::new_with_ts(DATA, &ts).unwrap();
+        let tokens = reader.read_to_end().await.unwrap();
+        assert_eq!(tokens, expected);
+        assert!(reader.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn read_truncated_async() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0010,0010) PatientName; PN; len = 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e',
+        ];
+
+        let ts = AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2.1",
+            "Explicit VR Little Endian",
+            Endianness::Little,
+            true,
+            Codec::None,
+        )
+        .erased();
+
+        let mut reader = AsyncDataSetReader::new_with_ts(DATA, &ts).unwrap();
+        assert!(matches!(
+            reader.next().await,
+            Some(Ok(DataToken::ElementHeader(_)))
+        ));
+        assert!(matches!(reader.next().await, Some(Err(_))));
+        assert!(reader.next().await.is_none());
+    }
+}
@@ -0,0 +1,831 @@
+//! Token filters for streaming data set pipelines.
+//!
+//! A [`TokenFilter`] receives the tokens of a data set one by one
+//! and produces zero or more tokens in their place,
+//! so that a data set can be changed while it flows
+//! from a [`DataSetReader`](super::DataSetReader)
+//! into a [`DataSetWriter`](super::DataSetWriter),
+//! without ever building the full data set in memory.
+//! Filters are combined with [`then`](TokenFilter::then).
+//!
+//! This module provides filters for the most common operations:
+//!
+//! - [`MapValues`] changes the values of primitive elements;
+//! - [`DropTags`] and [`DropElements`] remove elements,
+//!   including whole sequences;
+//! - [`InjectElements`] inserts or replaces elements of the root data set
+//!   at their position in ascending tag order;
+//! - [`RenamePrivateCreators`] renames private creator identifiers;
+//! - [`UndefinedLengths`] makes all sequences and items of undefined length;
+//! - [`FilterMap`] applies an arbitrary function to each token.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_core::{dicom_value, DataElement, Tag, VR};
+//! # use dicom_parser::dataset::{DataToken, IntoTokens};
+//! # use dicom_parser::dataset::filter::{DropTags, InjectElements, TokenFilter};
+//! let elements: Vec<DataElement> = vec![
+//!     DataElement::new(Tag(0x0010, 0x0010), VR::PN, dicom_value!(Str, "Doe^John")),
+//!     DataElement::new(Tag(0x0010, 0x0030), VR::DA, dicom_value!(Str, "19800101")),
+//! ];
+//! let patient_id: DataElement = DataElement::new(
+//!     Tag(0x0010, 0x0020),
+//!     VR::LO,
+//!     dicom_value!(Str, "P1"),
+//! );
+//!
+//! let filter = DropTags::new([Tag(0x0010, 0x0030)])
+//!     .then(InjectElements::new().with_element(patient_id));
+//! let tokens: Vec<_> = filter.apply(elements.into_tokens()).collect();
+//! assert_eq!(tokens.len(), 4);
+//! assert!(matches!(
+//!     &tokens[2],
+//!     DataToken::ElementHeader(header) if header.tag == Tag(0x0010, 0x0020)
+//! ));
+//! ```
+use super::{DataToken, IntoTokens};
+use dicom_core::dictionary::TagSet;
+use dicom_core::header::{DataElementHeader, Header, Length};
+use dicom_core::value::PrimitiveValue;
+use dicom_core::Tag;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+/// A stage of a data set token pipeline.
+///
+/// A filter is fed every token of a data set in order,
+/// and pushes the tokens which should take their place
+/// into an output buffer:
+/// none to drop the token,
+/// the token itself to keep it,
+/// or any number of other tokens.
+/// The output of a filter must be a well formed token stream.
+pub trait TokenFilter {
+    /// Process the next token of the data set,
+    /// pushing the resulting tokens into `out`.
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>);
+
+    /// Signal the end of the data set,
+    /// pushing any tokens still pending into `out`.
+    ///
+    /// The default implementation does nothing.
+    fn finish(&mut self, out: &mut Vec<DataToken>) {
+        let _ = out;
+    }
+
+    /// Combine this filter with another one,
+    /// so that the tokens produced by this filter
+    /// are fed to the next one.
+    fn then<F>(self, next: F) -> Then<Self, F>
+    where
+        Self: Sized,
+        F: TokenFilter,
+    {
+        Then {
+            first: self,
+            second: next,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Apply this filter to a sequence of tokens.
+    fn apply<I>(self, tokens: I) -> FilterTokens<I::IntoIter, Self>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = DataToken>,
+    {
+        FilterTokens {
+            tokens: tokens.into_iter(),
+            filter: self,
+            pending: VecDeque::new(),
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+
+    /// Apply this filter to a sequence of fallible tokens,
+    /// such as those of a [`DataSetReader`](super::DataSetReader).
+    ///
+    /// Errors are passed through as they are found.
+    fn try_apply<I, E>(self, tokens: I) -> TryFilterTokens<I::IntoIter, Self>
+    where
+        Self: Sized,
+        I: IntoIterator<Item = Result<DataToken, E>>,
+    {
+        TryFilterTokens {
+            tokens: tokens.into_iter(),
+            filter: self,
+            pending: VecDeque::new(),
+            buffer: Vec::new(),
+            finished: false,
+        }
+    }
+}
+
+impl<F> TokenFilter for &mut F
+where
+    F: TokenFilter + ?Sized,
+{
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        (**self).filter(token, out)
+    }
+
+    fn finish(&mut self, out: &mut Vec<DataToken>) {
+        (**self).finish(out)
+    }
+}
+
+impl<F> TokenFilter for Box<F>
+where
+    F: TokenFilter + ?Sized,
+{
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        (**self).filter(token, out)
+    }
+
+    fn finish(&mut self, out: &mut Vec<DataToken>) {
+        (**self).finish(out)
+    }
+}
+
+/// Two token filters applied one after the other.
+///
+/// See [`TokenFilter::then`].
+#[derive(Debug, Clone)]
+pub struct Then<A, B> {
+    first: A,
+    second: B,
+    buffer: Vec<DataToken>,
+}
+
+impl<A, B> TokenFilter for Then<A, B>
+where
+    A: TokenFilter,
+    B: TokenFilter,
+{
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        self.first.filter(token, &mut self.buffer);
+        for token in self.buffer.drain(..) {
+            self.second.filter(token, out);
+        }
+    }
+
+    fn finish(&mut self, out: &mut Vec<DataToken>) {
+        self.first.finish(&mut self.buffer);
+        for token in self.buffer.drain(..) {
+            self.second.filter(token, out);
+        }
+        self.second.finish(out);
+    }
+}
+
+/// An iterator of tokens going through a token filter.
+///
+/// See [`TokenFilter::apply`].
+#[derive(Debug)]
+pub struct FilterTokens<I, F> {
+    tokens: I,
+    filter: F,
+    pending: VecDeque<DataToken>,
+    buffer: Vec<DataToken>,
+    finished: bool,
+}
+
+impl<I, F> Iterator for FilterTokens<I, F>
+where
+    I: Iterator<Item = DataToken>,
+    F: TokenFilter,
+{
+    type Item = DataToken;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                return Some(token);
+            }
+            if self.finished {
+                return None;
+            }
+            match self.tokens.next() {
+                Some(token) => self.filter.filter(token, &mut self.buffer),
+                None => {
+                    self.filter.finish(&mut self.buffer);
+                    self.finished = true;
+                }
+            }
+            self.pending.extend(self.buffer.drain(..));
+        }
+    }
+}
+
+/// An iterator of fallible tokens going through a token filter.
+///
+/// See [`TokenFilter::try_apply`].
+#[derive(Debug)]
+pub struct TryFilterTokens<I, F> {
+    tokens: I,
+    filter: F,
+    pending: VecDeque<DataToken>,
+    buffer: Vec<DataToken>,
+    finished: bool,
+}
+
+impl<I, F, E> Iterator for TryFilterTokens<I, F>
+where
+    I: Iterator<Item = Result<DataToken, E>>,
+    F: TokenFilter,
+{
+    type Item = Result<DataToken, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                return Some(Ok(token));
+            }
+            if self.finished {
+                return None;
+            }
+            match self.tokens.next() {
+                Some(Ok(token)) => self.filter.filter(token, &mut self.buffer),
+                Some(Err(e)) => return Some(Err(e)),
+                None => {
+                    self.filter.finish(&mut self.buffer);
+                    self.finished = true;
+                }
+            }
+            self.pending.extend(self.buffer.drain(..));
+        }
+    }
+}
+
+/// A token filter applying a function to each token,
+/// which returns the token to write in its place,
+/// or `None` to drop it.
+#[derive(Debug, Clone)]
+pub struct FilterMap<F> {
+    f: F,
+}
+
+impl<F> FilterMap<F>
+where
+    F: FnMut(DataToken) -> Option<DataToken>,
+{
+    pub fn new(f: F) -> Self {
+        FilterMap { f }
+    }
+}
+
+impl<F> TokenFilter for FilterMap<F>
+where
+    F: FnMut(DataToken) -> Option<DataToken>,
+{
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        out.extend((self.f)(token));
+    }
+}
+
+/// A token filter changing the values of primitive data elements.
+///
+/// The function is called with the header of each primitive element
+/// and its value, and returns the value to write in its place.
+/// The length of the element is recomputed by the writer.
+/// Elements at all nesting levels are visited.
+#[derive(Debug, Clone)]
+pub struct MapValues<F> {
+    f: F,
+    header: Option<DataElementHeader>,
+}
+
+impl<F> MapValues<F>
+where
+    F: FnMut(&DataElementHeader, PrimitiveValue) -> PrimitiveValue,
+{
+    pub fn new(f: F) -> Self {
+        MapValues { f, header: None }
+    }
+}
+
+impl<F> TokenFilter for MapValues<F>
+where
+    F: FnMut(&DataElementHeader, PrimitiveValue) -> PrimitiveValue,
+{
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        match token {
+            DataToken::ElementHeader(header) => {
+                self.header = Some(header);
+                out.push(DataToken::ElementHeader(header));
+            }
+            DataToken::PrimitiveValue(value) => match self.header.take() {
+                Some(header) => out.push(DataToken::PrimitiveValue((self.f)(&header, value))),
+                None => out.push(DataToken::PrimitiveValue(value)),
+            },
+            token => out.push(token),
+        }
+    }
+}
+
+/// A token filter removing all data elements with one of the given tags,
+/// at any nesting level.
+///
+/// Sequences and encapsulated pixel data are removed as a whole.
+/// Whole ranges of tags, such as `(60xx,3000)`,
+/// can be removed by creating the filter from a [`TagSet`].
+#[derive(Debug, Clone)]
+pub struct DropTags {
+    tags: TagSet,
+    skip: ElementSkip,
+}
+
+impl DropTags {
+    pub fn new<I>(tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        Self::from_set(tags.into_iter().collect())
+    }
+
+    /// Create a filter removing all data elements
+    /// with a tag in the given set.
+    pub fn from_set(tags: TagSet) -> Self {
+        DropTags {
+            tags,
+            skip: ElementSkip::default(),
+        }
+    }
+}
+
+impl TokenFilter for DropTags {
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        if self.skip.consume(&token) {
+            return;
+        }
+        match element_tag(&token) {
+            Some(tag) if self.tags.contains(tag) => self.skip.start(&token),
+            _ => out.push(token),
+        }
+    }
+}
+
+/// A token filter removing all data elements
+/// for which the given predicate returns `true`,
+/// at any nesting level.
+///
+/// The predicate is called with the tag of each element.
+/// Sequences and encapsulated pixel data are removed as a whole.
+#[derive(Debug, Clone)]
+pub struct DropElements<F> {
+    predicate: F,
+    skip: ElementSkip,
+}
+
+impl<F> DropElements<F>
+where
+    F: FnMut(Tag) -> bool,
+{
+    pub fn new(predicate: F) -> Self {
+        DropElements {
+            predicate,
+            skip: ElementSkip::default(),
+        }
+    }
+}
+
+impl<F> TokenFilter for DropElements<F>
+where
+    F: FnMut(Tag) -> bool,
+{
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        if self.skip.consume(&token) {
+            return;
+        }
+        match element_tag(&token) {
+            Some(tag) if (self.predicate)(tag) => self.skip.start(&token),
+            _ => out.push(token),
+        }
+    }
+}
+
+/// A token filter inserting data elements into the root data set,
+/// each at its position in ascending tag order.
+///
+/// An element already in the data set with the same tag as
+/// an injected element is replaced.
+/// Elements injected with a tag greater than
+/// all elements in the data set are written at the end of it.
+#[derive(Debug, Clone, Default)]
+pub struct InjectElements {
+    elements: BTreeMap<Tag, Vec<DataToken>>,
+    depth: u32,
+    skip: ElementSkip,
+}
+
+impl InjectElements {
+    pub fn new() -> Self {
+        InjectElements::default()
+    }
+
+    /// Add an element to inject,
+    /// replacing any element previously added with the same tag.
+    pub fn with_element<E>(mut self, element: E) -> Self
+    where
+        E: Header,
+        E: IntoTokens,
+    {
+        self.elements
+            .insert(element.tag(), element.into_tokens().collect());
+        self
+    }
+
+    /// Push all pending elements with a tag lower than `tag`.
+    fn flush_before(&mut self, tag: Tag, out: &mut Vec<DataToken>) {
+        let remaining = self.elements.split_off(&tag);
+        for (_, tokens) in std::mem::replace(&mut self.elements, remaining) {
+            out.extend(tokens);
+        }
+    }
+}
+
+impl TokenFilter for InjectElements {
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        if self.skip.consume(&token) {
+            return;
+        }
+        if self.depth == 0 {
+            if let Some(tag) = element_tag(&token) {
+                self.flush_before(tag, out);
+                if let Some(tokens) = self.elements.remove(&tag) {
+                    out.extend(tokens);
+                    self.skip.start(&token);
+                    return;
+                }
+            }
+        }
+        match token {
+            DataToken::SequenceStart { .. } | DataToken::PixelSequenceStart => self.depth += 1,
+            DataToken::SequenceEnd => self.depth = self.depth.saturating_sub(1),
+            _ => {}
+        }
+        out.push(token);
+    }
+
+    fn finish(&mut self, out: &mut Vec<DataToken>) {
+        for (_, tokens) in std::mem::take(&mut self.elements) {
+            out.extend(tokens);
+        }
+    }
+}
+
+/// A token filter renaming private creators,
+/// by replacing the values of private creator data elements
+/// (gggg,0010-00FF) of any private group.
+///
+/// Since private data elements are identified by the creator
+/// of the block they belong to,
+/// renaming a creator moves the whole block to another identity
+/// without changing its element tags.
+#[derive(Debug, Clone, Default)]
+pub struct RenamePrivateCreators {
+    names: HashMap<String, String>,
+    in_creator: bool,
+}
+
+impl RenamePrivateCreators {
+    pub fn new() -> Self {
+        RenamePrivateCreators::default()
+    }
+
+    /// Add a private creator to rename.
+    /// Trailing spaces are not significant in the creator name.
+    pub fn with_rename<S, T>(mut self, from: S, to: T) -> Self
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        self.names
+            .insert(from.into().trim_end_matches(' ').to_string(), to.into());
+        self
+    }
+}
+
+impl TokenFilter for RenamePrivateCreators {
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        match token {
+            DataToken::ElementHeader(header) => {
+                self.in_creator = header.tag.group() % 2 == 1
+                    && (0x0010..=0x00FF).contains(&header.tag.element());
+                out.push(DataToken::ElementHeader(header));
+            }
+            DataToken::PrimitiveValue(value) if self.in_creator => {
+                self.in_creator = false;
+                let name = value.to_str();
+                match self
+                    .names
+                    .get(name.trim_end_matches(|c: char| c == ' ' || c == '\0'))
+                {
+                    Some(new_name) => {
+                        out.push(DataToken::PrimitiveValue(PrimitiveValue::from(
+                            new_name.as_str(),
+                        )));
+                    }
+                    None => out.push(DataToken::PrimitiveValue(value)),
+                }
+            }
+            token => out.push(token),
+        }
+    }
+}
+
+/// A token filter making all sequences and items of undefined length,
+/// except for pixel data fragments.
+///
+/// Sequences and items of undefined length are closed by delimiters
+/// instead of declaring their length up front,
+/// so that their contents can be freely changed
+/// by the filters which come before this one.
+#[derive(Debug, Clone, Default)]
+pub struct UndefinedLengths {
+    /// whether each open sequence is a pixel data sequence
+    sequences: Vec<bool>,
+}
+
+impl UndefinedLengths {
+    pub fn new() -> Self {
+        UndefinedLengths::default()
+    }
+}
+
+impl TokenFilter for UndefinedLengths {
+    fn filter(&mut self, token: DataToken, out: &mut Vec<DataToken>) {
+        let token = match token {
+            DataToken::SequenceStart { tag, .. } => {
+                self.sequences.push(false);
+                DataToken::SequenceStart {
+                    tag,
+                    len: Length::UNDEFINED,
+                }
+            }
+            DataToken::PixelSequenceStart => {
+                self.sequences.push(true);
+                DataToken::PixelSequenceStart
+            }
+            DataToken::SequenceEnd => {
+                self.sequences.pop();
+                DataToken::SequenceEnd
+            }
+            DataToken::ItemStart { len } if self.sequences.last() == Some(&true) => {
+                DataToken::ItemStart { len }
+            }
+            DataToken::ItemStart { .. } => DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            token => token,
+        };
+        out.push(token);
+    }
+}
+
+/// Obtain the tag of the element starting with the given token, if any.
+fn element_tag(token: &DataToken) -> Option<Tag> {
+    match token {
+        DataToken::ElementHeader(header) => Some(header.tag),
+        DataToken::SequenceStart { tag, .. } => Some(*tag),
+        DataToken::PixelSequenceStart => Some(Tag(0x7FE0, 0x0010)),
+        _ => None,
+    }
+}
+
+/// Keeps track of the remaining tokens of an element being dropped.
+#[derive(Debug, Default, Clone)]
+struct ElementSkip {
+    active: bool,
+    depth: u32,
+}
+
+impl ElementSkip {
+    /// Start skipping the element beginning with the given token.
+    fn start(&mut self, token: &DataToken) {
+        self.active = true;
+        self.depth = match token {
+            DataToken::SequenceStart { .. } | DataToken::PixelSequenceStart => 1,
+            _ => 0,
+        };
+    }
+
+    /// Check whether the token belongs to the element being skipped.
+    fn consume(&mut self, token: &DataToken) -> bool {
+        if !self.active {
+            return false;
+        }
+        if self.depth == 0 {
+            // only the value of a primitive element is left
+            self.active = false;
+            return matches!(token, DataToken::PrimitiveValue(_));
+        }
+        match token {
+            DataToken::SequenceStart { .. } | DataToken::PixelSequenceStart => self.depth += 1,
+            DataToken::SequenceEnd => {
+                self.depth -= 1;
+                self.active = self.depth > 0;
+            }
+            _ => {}
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataset::DataSetWriter;
+    use dicom_core::header::EmptyObject;
+    use dicom_core::value::Value;
+    use dicom_core::{dicom_value, DataElement, VR};
+    use dicom_encoding::encode::{explicit_le::ExplicitVRLittleEndianEncoder, EncoderFor};
+
+    type Element = DataElement<EmptyObject, [u8; 0]>;
+
+    fn tags_of(tokens: &[DataToken]) -> Vec<Tag> {
+        tokens.iter().filter_map(element_tag).collect()
+    }
+
+    fn sample_tokens() -> Vec<DataToken> {
+        vec![
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0009, 0x0010),
+                VR::LO,
+                Length(8),
+            )),
+            DataToken::PrimitiveValue(dicom_value!(Str, "ACME 1.0")),
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0010, 0x0010),
+                VR::PN,
+                Length(8),
+            )),
+            DataToken::PrimitiveValue(dicom_value!(Str, "Doe^John")),
+            DataToken::SequenceStart {
+                tag: Tag(0x0040, 0xA730),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0010, 0x0010),
+                VR::PN,
+                Length(6),
+            )),
+            DataToken::PrimitiveValue(dicom_value!(Str, "Nobody")),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::PixelSequenceStart,
+            DataToken::ItemStart { len: Length(0) },
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+        ]
+    }
+
+    #[test]
+    fn map_values_at_all_levels() {
+        let tokens: Vec<_> = MapValues::new(|header: &DataElementHeader, value| {
+            if header.tag == Tag(0x0010, 0x0010) {
+                dicom_value!(Str, "Anonymous")
+            } else {
+                value
+            }
+        })
+        .apply(sample_tokens())
+        .collect();
+
+        let names: Vec<_> = tokens
+            .iter()
+            .filter_map(|token| match token {
+                DataToken::PrimitiveValue(value) => Some(value.to_str().into_owned()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(names, vec!["ACME 1.0", "Anonymous", "Anonymous"]);
+    }
+
+    #[test]
+    fn drop_whole_sequences() {
+        let tokens: Vec<_> = DropTags::new([Tag(0x0040, 0xA730), Tag(0x7FE0, 0x0010)])
+            .apply(sample_tokens())
+            .collect();
+        assert_eq!(tokens.len(), 4);
+        assert_eq!(
+            tags_of(&tokens),
+            vec![Tag(0x0009, 0x0010), Tag(0x0010, 0x0010)]
+        );
+
+        // nested elements are dropped too
+        let tokens: Vec<_> = DropTags::new([Tag(0x0010, 0x0010)])
+            .apply(sample_tokens())
+            .collect();
+        assert_eq!(
+            tags_of(&tokens),
+            vec![
+                Tag(0x0009, 0x0010),
+                Tag(0x0040, 0xA730),
+                Tag(0x7FE0, 0x0010)
+            ]
+        );
+
+        // tag ranges
+        let tokens: Vec<_> = DropTags::from_set("(0009,xxxx) (7FE0,0010)".parse().unwrap())
+            .apply(sample_tokens())
+            .collect();
+        assert_eq!(
+            tags_of(&tokens),
+            vec![
+                Tag(0x0010, 0x0010),
+                Tag(0x0040, 0xA730),
+                Tag(0x0010, 0x0010)
+            ]
+        );
+
+        // private elements only
+        let tokens: Vec<_> = DropElements::new(|tag: Tag| tag.group() % 2 == 1)
+            .apply(sample_tokens())
+            .collect();
+        assert_eq!(
+            tags_of(&tokens),
+            vec![
+                Tag(0x0010, 0x0010),
+                Tag(0x0040, 0xA730),
+                Tag(0x0010, 0x0010),
+                Tag(0x7FE0, 0x0010)
+            ]
+        );
+    }
+
+    #[test]
+    fn inject_in_tag_order() {
+        let filter = InjectElements::new()
+            .with_element(Element::new(
+                Tag(0x0008, 0x0060),
+                VR::CS,
+                Value::Primitive(dicom_value!(Str, "OT")),
+            ))
+            .with_element(Element::new(
+                Tag(0x0010, 0x0010),
+                VR::PN,
+                Value::Primitive(dicom_value!(Str, "Anonymous")),
+            ))
+            .with_element(Element::new(
+                Tag(0x0020, 0x0013),
+                VR::IS,
+                Value::Primitive(dicom_value!(Str, "1")),
+            ))
+            .with_element(Element::new(
+                Tag(0xFFFA, 0xFFFA),
+                VR::OB,
+                Value::Primitive(PrimitiveValue::Empty),
+            ));
+        let tokens: Vec<_> = filter.apply(sample_tokens()).collect();
+        assert_eq!(
+            tags_of(&tokens),
+            vec![
+                Tag(0x0008, 0x0060),
+                Tag(0x0009, 0x0010),
+                Tag(0x0010, 0x0010),
+                Tag(0x0020, 0x0013),
+                Tag(0x0040, 0xA730),
+                // nested elements are left alone
+                Tag(0x0010, 0x0010),
+                Tag(0x7FE0, 0x0010),
+                Tag(0xFFFA, 0xFFFA),
+            ]
+        );
+        assert!(matches!(
+            &tokens[5],
+            DataToken::PrimitiveValue(value) if value.to_str() == "Anonymous"
+        ));
+    }
+
+    #[test]
+    fn rename_creators_and_write() {
+        let filter = RenamePrivateCreators::new()
+            .with_rename("ACME 1.0", "ACME 2.0")
+            .then(DropTags::new([Tag(0x7FE0, 0x0010)]));
+
+        let mut raw = Vec::new();
+        let mut writer = DataSetWriter::new(
+            &mut raw,
+            EncoderFor::new(ExplicitVRLittleEndianEncoder::default()),
+        );
+        writer
+            .write_sequence(filter.apply(sample_tokens()))
+            .unwrap();
+
+        // private creator element with the new value
+        assert_eq!(
+            &raw[..16],
+            &[
+                0x09, 0x00, 0x10, 0x00, b'L', b'O', 0x08, 0x00, b'A', b'C', b'M', b'E', b' ', b'2',
+                b'.', b'0',
+            ]
+        );
+        // no pixel data
+        assert!(!raw.windows(4).any(|w| w == [0xE0, 0x7F, 0x10, 0x00]));
+    }
+}
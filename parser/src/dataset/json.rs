@@ -0,0 +1,765 @@
+//! Module for the streaming DICOM JSON writer
+//!
+//! This module contains a mid-level abstraction for printing DICOM data sets
+//! in the DICOM JSON Model (PS3.18 section F.2),
+//! one attribute at a time.
+//! The [`JsonDataSetWriter`] receives data tokens,
+//! such as the ones produced by a [`DataSetReader`](super::DataSetReader),
+//! and writes the respective JSON text to a writer
+//! without building a full in-memory object or JSON tree.
+//! Large binary values can be externalized as bulk data URIs
+//! via a [`BulkDataUri`] provider.
+//!
+//! With the `json` feature enabled,
+//! the [`read`] module provides the opposite direction,
+//! turning DICOM JSON text into data set tokens.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_encoding::text::SpecificCharacterSet;
+//! # use dicom_encoding::TransferSyntax;
+//! use dicom_parser::dataset::json::JsonDataSetWriter;
+//! use dicom_parser::DataSetReader;
+//! # fn run(ts: &TransferSyntax) -> Result<(), Box<dyn std::error::Error>> {
+//! let file = std::io::BufReader::new(std::fs::File::open("dataset.bin")?);
+//! let reader = DataSetReader::new_with_ts_cs(file, ts, SpecificCharacterSet::Default)?;
+//!
+//! let out = std::io::BufWriter::new(std::io::stdout());
+//! let mut writer = JsonDataSetWriter::with_bulk_data(
+//!     out,
+//!     |path: &[dicom_core::Tag], _: &dicom_core::DataElementHeader| {
+//!         let tag = path.last().unwrap();
+//!         Some(format!("bulk/{:04X}{:04X}", tag.0, tag.1))
+//!     },
+//!     1024,
+//! );
+//! for token in reader {
+//!     writer.write(token?)?;
+//! }
+//! writer.finish()?;
+//! # Ok(())
+//! # }
+//! ```
+use super::DataToken;
+use dicom_core::header::{DataElementHeader, Length, VR};
+use dicom_core::value::PrimitiveValue;
+use dicom_core::Tag;
+use snafu::{Backtrace, ResultExt, Snafu};
+use std::io::Write;
+
+#[cfg(feature = "json")]
+pub mod read;
+
+#[cfg(feature = "json")]
+pub use self::read::{
+    read_tokens, read_tokens_from_slice, read_tokens_from_slice_with_bulk_data,
+    read_tokens_with_bulk_data,
+};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("Could not write JSON output"))]
+    WriteJson {
+        backtrace: Backtrace,
+        source: std::io::Error,
+    },
+    /// A data set token appeared out of place
+    #[snafu(display("Unexpected token {:?}", token))]
+    UnexpectedToken {
+        token: DataToken,
+        backtrace: Backtrace,
+    },
+    /// The data set was finished while a sequence was still open
+    #[snafu(display("Data set ended before all sequences were closed"))]
+    UnfinishedDataSet { backtrace: Backtrace },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A provider of URIs for element values which are to be externalized
+/// as bulk data, instead of being written inline.
+pub trait BulkDataUri {
+    /// Obtain the bulk data URI for the element with the given header.
+    ///
+    /// `path` contains the tags of all enclosing sequences,
+    /// followed by the tag of the element itself.
+    /// Returning `None` will make the writer
+    /// include the value inline.
+    fn bulk_data_uri(&mut self, path: &[Tag], header: &DataElementHeader) -> Option<String>;
+}
+
+impl<F> BulkDataUri for F
+where
+    F: FnMut(&[Tag], &DataElementHeader) -> Option<String>,
+{
+    fn bulk_data_uri(&mut self, path: &[Tag], header: &DataElementHeader) -> Option<String> {
+        self(path, header)
+    }
+}
+
+/// A bulk data URI provider which never externalizes any values.
+///
+/// Encapsulated pixel data is written without a value
+/// when using this provider.
+#[derive(Debug, Default, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct InlineBulkData;
+
+impl BulkDataUri for InlineBulkData {
+    fn bulk_data_uri(&mut self, _: &[Tag], _: &DataElementHeader) -> Option<String> {
+        None
+    }
+}
+
+/// A writer-specific frame of the JSON output being constructed.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Frame {
+    /// A data set or sequence item (a JSON object).
+    DataSet { first: bool },
+    /// A data set sequence (a JSON array of objects).
+    Sequence { empty: bool },
+    /// An encapsulated pixel data sequence,
+    /// of which all item tokens are ignored.
+    PixelSequence,
+}
+
+/// A stateful device for printing a DICOM data set in the DICOM JSON format.
+///
+/// This is analogous to the [`DataSetWriter`](super::DataSetWriter) type,
+/// but producing JSON text instead of DICOM encoded bytes.
+/// Only one element value is held at a time,
+/// so memory usage does not grow with the size of the data set.
+/// The writer performs many small writes,
+/// so wrapping `W` in a buffered writer is recommended.
+#[derive(Debug)]
+pub struct JsonDataSetWriter<W, B = InlineBulkData> {
+    to: W,
+    bulk_data: B,
+    bulk_data_threshold: u32,
+    stack: Vec<Frame>,
+    path: Vec<Tag>,
+    last_header: Option<DataElementHeader>,
+    started: bool,
+}
+
+impl<W> JsonDataSetWriter<W> {
+    /// Create a new JSON writer which writes all values inline.
+    pub fn new(to: W) -> Self {
+        JsonDataSetWriter::with_bulk_data(to, InlineBulkData, u32::max_value())
+    }
+}
+
+impl<W, B> JsonDataSetWriter<W, B> {
+    /// Create a new JSON writer with the given bulk data URI provider.
+    ///
+    /// Binary values (OB, OD, OF, OL, OV, OW, UN)
+    /// longer than `threshold` bytes
+    /// and encapsulated pixel data
+    /// are offered to the provider for externalization.
+    pub fn with_bulk_data(to: W, bulk_data: B, threshold: u32) -> Self {
+        JsonDataSetWriter {
+            to,
+            bulk_data,
+            bulk_data_threshold: threshold,
+            stack: vec![Frame::DataSet { first: true }],
+            path: Vec::new(),
+            last_header: None,
+            started: false,
+        }
+    }
+}
+
+impl<W, B> JsonDataSetWriter<W, B>
+where
+    W: Write,
+    B: BulkDataUri,
+{
+    /// Feed the given sequence of tokens which are part of the same data set.
+    #[inline]
+    pub fn write_sequence<I>(&mut self, tokens: I) -> Result<()>
+    where
+        I: IntoIterator<Item = DataToken>,
+    {
+        for token in tokens {
+            self.write(token)?;
+        }
+
+        Ok(())
+    }
+
+    /// Feed the given data set token for writing the data set.
+    pub fn write(&mut self, token: DataToken) -> Result<()> {
+        self.start()?;
+
+        match (token, self.stack.last().copied()) {
+            // encapsulated pixel data items are never written
+            (DataToken::ItemStart { .. }, Some(Frame::PixelSequence))
+            | (DataToken::ItemEnd, Some(Frame::PixelSequence))
+            | (DataToken::ItemValue(_), Some(Frame::PixelSequence))
+            | (DataToken::OffsetTable(_), Some(Frame::PixelSequence)) => Ok(()),
+            (DataToken::SequenceEnd, Some(Frame::PixelSequence)) => {
+                self.stack.pop();
+                Ok(())
+            }
+            (DataToken::ElementHeader(header), Some(Frame::DataSet { .. }))
+                if self.last_header.is_none() =>
+            {
+                // postpone writing until the value token is given
+                self.last_header = Some(header);
+                Ok(())
+            }
+            (DataToken::PrimitiveValue(value), Some(Frame::DataSet { .. }))
+                if self.last_header.is_some() =>
+            {
+                let header = self.last_header.take().unwrap();
+                self.begin_member(header.tag)?;
+                self.write_element(&header, &value)
+            }
+            (DataToken::SequenceStart { tag, .. }, Some(Frame::DataSet { .. }))
+                if self.last_header.is_none() =>
+            {
+                self.begin_member(tag)?;
+                self.to
+                    .write_all(br#"{"vr":"SQ""#)
+                    .context(WriteJsonSnafu)?;
+                self.path.push(tag);
+                self.stack.push(Frame::Sequence { empty: true });
+                Ok(())
+            }
+            (DataToken::PixelSequenceStart, Some(Frame::DataSet { .. }))
+                if self.last_header.is_none() =>
+            {
+                let tag = Tag(0x7FE0, 0x0010);
+                self.begin_member(tag)?;
+                self.to
+                    .write_all(br#"{"vr":"OB""#)
+                    .context(WriteJsonSnafu)?;
+                let header = DataElementHeader::new(tag, VR::OB, Length::UNDEFINED);
+                self.path.push(tag);
+                let uri = self.bulk_data.bulk_data_uri(&self.path, &header);
+                self.path.pop();
+                if let Some(uri) = uri {
+                    self.to
+                        .write_all(br#","BulkDataURI":"#)
+                        .context(WriteJsonSnafu)?;
+                    write_json_str(&mut self.to, &uri)?;
+                }
+                self.to.write_all(b"}").context(WriteJsonSnafu)?;
+                self.stack.push(Frame::PixelSequence);
+                Ok(())
+            }
+            (DataToken::ItemStart { .. }, Some(Frame::Sequence { empty })) => {
+                let prefix: &[u8] = if empty { br#","Value":[{"# } else { b",{" };
+                self.to.write_all(prefix).context(WriteJsonSnafu)?;
+                if let Some(frame) = self.stack.last_mut() {
+                    *frame = Frame::Sequence { empty: false };
+                }
+                self.stack.push(Frame::DataSet { first: true });
+                Ok(())
+            }
+            (DataToken::ItemEnd, Some(Frame::DataSet { .. }))
+                if self.stack.len() > 1 && self.last_header.is_none() =>
+            {
+                self.stack.pop();
+                self.to.write_all(b"}").context(WriteJsonSnafu)
+            }
+            (DataToken::SequenceEnd, Some(Frame::Sequence { empty })) => {
+                self.stack.pop();
+                self.path.pop();
+                let suffix: &[u8] = if empty { b"}" } else { b"]}" };
+                self.to.write_all(suffix).context(WriteJsonSnafu)
+            }
+            (token, _) => UnexpectedTokenSnafu { token }.fail(),
+        }
+    }
+
+    /// Finish writing the JSON data set,
+    /// closing the root object and flushing the inner writer.
+    ///
+    /// Fails if the data set ended in the middle of a sequence.
+    pub fn finish(mut self) -> Result<W> {
+        if self.stack.len() > 1 || self.last_header.is_some() {
+            return UnfinishedDataSetSnafu.fail();
+        }
+        self.start()?;
+        self.to.write_all(b"}").context(WriteJsonSnafu)?;
+        self.to.flush().context(WriteJsonSnafu)?;
+        Ok(self.to)
+    }
+
+    /// Write the opening brace of the root object if not done yet.
+    fn start(&mut self) -> Result<()> {
+        if !self.started {
+            self.started = true;
+            self.to.write_all(b"{").context(WriteJsonSnafu)?;
+        }
+        Ok(())
+    }
+
+    /// Write the key of a new attribute in the current data set.
+    fn begin_member(&mut self, tag: Tag) -> Result<()> {
+        if let Some(Frame::DataSet { first }) = self.stack.last_mut() {
+            if !*first {
+                self.to.write_all(b",").context(WriteJsonSnafu)?;
+            }
+            *first = false;
+        }
+        write!(self.to, "\"{:04X}{:04X}\":", tag.0, tag.1).context(WriteJsonSnafu)
+    }
+
+    /// Write the JSON object of a primitive data element.
+    fn write_element(&mut self, header: &DataElementHeader, value: &PrimitiveValue) -> Result<()> {
+        write!(self.to, "{{\"vr\":\"{}\"", header.vr).context(WriteJsonSnafu)?;
+
+        match header.vr {
+            VR::OB | VR::OD | VR::OF | VR::OL | VR::OV | VR::OW | VR::UN => {
+                let byte_len = value.calculate_byte_len();
+                let uri = if byte_len as u64 > u64::from(self.bulk_data_threshold) {
+                    self.path.push(header.tag);
+                    let uri = self.bulk_data.bulk_data_uri(&self.path, header);
+                    self.path.pop();
+                    uri
+                } else {
+                    None
+                };
+                if let Some(uri) = uri {
+                    self.to
+                        .write_all(br#","BulkDataURI":"#)
+                        .context(WriteJsonSnafu)?;
+                    write_json_str(&mut self.to, &uri)?;
+                } else if byte_len > 0 {
+                    self.to
+                        .write_all(br#","InlineBinary":""#)
+                        .context(WriteJsonSnafu)?;
+                    write_inline_binary(&mut self.to, value)?;
+                    self.to.write_all(b"\"").context(WriteJsonSnafu)?;
+                }
+            }
+            vr => {
+                let values = value.to_multi_str();
+                let is_empty =
+                    values.is_empty() || (values.len() == 1 && trim_value(&values[0]).is_empty());
+                if !is_empty {
+                    self.to
+                        .write_all(br#","Value":["#)
+                        .context(WriteJsonSnafu)?;
+                    match (vr, value) {
+                        (VR::AT, PrimitiveValue::Tags(tags)) => {
+                            for (i, tag) in tags.iter().enumerate() {
+                                if i > 0 {
+                                    self.to.write_all(b",").context(WriteJsonSnafu)?;
+                                }
+                                write!(self.to, "\"{:04X}{:04X}\"", tag.0, tag.1)
+                                    .context(WriteJsonSnafu)?;
+                            }
+                        }
+                        (VR::PN, _) => write_person_names(&mut self.to, &values)?,
+                        (VR::DS, _)
+                        | (VR::IS, _)
+                        | (VR::FL, _)
+                        | (VR::FD, _)
+                        | (VR::SL, _)
+                        | (VR::SS, _)
+                        | (VR::SV, _)
+                        | (VR::UL, _)
+                        | (VR::US, _)
+                        | (VR::UV, _) => write_numbers(&mut self.to, value, &values)?,
+                        _ => write_strings(&mut self.to, &values)?,
+                    }
+                    self.to.write_all(b"]").context(WriteJsonSnafu)?;
+                }
+            }
+        }
+
+        self.to.write_all(b"}").context(WriteJsonSnafu)
+    }
+}
+
+/// Remove the padding characters at the end of a textual value.
+fn trim_value(value: &str) -> &str {
+    value.trim_end_matches(|c| c == ' ' || c == '\0')
+}
+
+/// Write a JSON string literal, escaping characters as necessary.
+fn write_json_str<W>(to: &mut W, value: &str) -> Result<()>
+where
+    W: Write,
+{
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    to.write_all(out.as_bytes()).context(WriteJsonSnafu)
+}
+
+/// Write textual values as JSON strings,
+/// where empty values are written as `null`.
+fn write_strings<W>(to: &mut W, values: &[String]) -> Result<()>
+where
+    W: Write,
+{
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            to.write_all(b",").context(WriteJsonSnafu)?;
+        }
+        let value = trim_value(value);
+        if value.is_empty() {
+            to.write_all(b"null").context(WriteJsonSnafu)?;
+        } else {
+            write_json_str(to, value)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write person names as JSON objects
+/// with one field per component group.
+fn write_person_names<W>(to: &mut W, values: &[String]) -> Result<()>
+where
+    W: Write,
+{
+    const GROUPS: [&str; 3] = ["Alphabetic", "Ideographic", "Phonetic"];
+
+    for (i, value) in values.iter().enumerate() {
+        if i > 0 {
+            to.write_all(b",").context(WriteJsonSnafu)?;
+        }
+        let value = trim_value(value);
+        if value.is_empty() {
+            to.write_all(b"null").context(WriteJsonSnafu)?;
+            continue;
+        }
+        to.write_all(b"{").context(WriteJsonSnafu)?;
+        let mut first = true;
+        for (group, name) in GROUPS.iter().zip(value.split('=')) {
+            if name.is_empty() {
+                continue;
+            }
+            if !first {
+                to.write_all(b",").context(WriteJsonSnafu)?;
+            }
+            first = false;
+            write!(to, "\"{}\":", group).context(WriteJsonSnafu)?;
+            write_json_str(to, name)?;
+        }
+        to.write_all(b"}").context(WriteJsonSnafu)?;
+    }
+    Ok(())
+}
+
+/// Write numeric values as JSON numbers.
+///
+/// Textual numbers which cannot be parsed
+/// are kept as JSON strings,
+/// and non-finite floating point numbers are written as `null`.
+fn write_numbers<W>(to: &mut W, value: &PrimitiveValue, values: &[String]) -> Result<()>
+where
+    W: Write,
+{
+    fn write_f64<W: Write>(to: &mut W, value: f64) -> Result<()> {
+        if value.is_finite() {
+            write!(to, "{}", value).context(WriteJsonSnafu)
+        } else {
+            to.write_all(b"null").context(WriteJsonSnafu)
+        }
+    }
+
+    match value {
+        PrimitiveValue::F32(values) => {
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    to.write_all(b",").context(WriteJsonSnafu)?;
+                }
+                write_f64(to, f64::from(*v))?;
+            }
+        }
+        PrimitiveValue::F64(values) => {
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    to.write_all(b",").context(WriteJsonSnafu)?;
+                }
+                write_f64(to, *v)?;
+            }
+        }
+        PrimitiveValue::Str(_) | PrimitiveValue::Strs(_) | PrimitiveValue::SharedStrs(_) => {
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    to.write_all(b",").context(WriteJsonSnafu)?;
+                }
+                let v = v.trim();
+                if v.is_empty() {
+                    to.write_all(b"null").context(WriteJsonSnafu)?;
+                } else if let Ok(n) = v.parse::<i64>() {
+                    write!(to, "{}", n).context(WriteJsonSnafu)?;
+                } else if let Ok(n) = v.parse::<f64>() {
+                    write_f64(to, n)?;
+                } else {
+                    write_json_str(to, v)?;
+                }
+            }
+        }
+        _ => {
+            // integers are already in their canonical textual form
+            for (i, v) in values.iter().enumerate() {
+                if i > 0 {
+                    to.write_all(b",").context(WriteJsonSnafu)?;
+                }
+                to.write_all(v.as_bytes()).context(WriteJsonSnafu)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write the little endian byte representation of a binary value
+/// in base64, without an intermediate buffer for the whole value.
+fn write_inline_binary<W>(to: &mut W, value: &PrimitiveValue) -> Result<()>
+where
+    W: Write,
+{
+    let mut encoder = Base64Encoder {
+        to,
+        buf: [0; 3],
+        len: 0,
+    };
+    match value {
+        PrimitiveValue::I16(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::U16(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::I32(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::U32(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::I64(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::U64(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::F32(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        PrimitiveValue::F64(values) => values
+            .iter()
+            .try_for_each(|v| encoder.push_all(&v.to_le_bytes()))?,
+        value => encoder.push_all(&value.to_bytes())?,
+    }
+    encoder.finish()
+}
+
+/// A minimal streaming base64 encoder (standard alphabet, with padding).
+struct Base64Encoder<'a, W> {
+    to: &'a mut W,
+    buf: [u8; 3],
+    len: usize,
+}
+
+impl<W> Base64Encoder<'_, W>
+where
+    W: Write,
+{
+    const ALPHABET: &'static [u8; 64] =
+        b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    fn push_all(&mut self, bytes: &[u8]) -> Result<()> {
+        for b in bytes {
+            self.buf[self.len] = *b;
+            self.len += 1;
+            if self.len == 3 {
+                self.flush_chunk()?;
+            }
+        }
+        Ok(())
+    }
+
+    fn flush_chunk(&mut self) -> Result<()> {
+        let [b0, b1, b2] = self.buf;
+        let out = [
+            Self::ALPHABET[usize::from(b0 >> 2)],
+            Self::ALPHABET[usize::from(((b0 & 0x03) << 4) | (b1 >> 4))],
+            if self.len > 1 {
+                Self::ALPHABET[usize::from(((b1 & 0x0F) << 2) | (b2 >> 6))]
+            } else {
+                b'='
+            },
+            if self.len > 2 {
+                Self::ALPHABET[usize::from(b2 & 0x3F)]
+            } else {
+                b'='
+            },
+        ];
+        self.buf = [0; 3];
+        self.len = 0;
+        self.to.write_all(&out).context(WriteJsonSnafu)
+    }
+
+    fn finish(mut self) -> Result<()> {
+        if self.len > 0 {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::DataToken;
+    use super::JsonDataSetWriter;
+    use dicom_core::{
+        header::{DataElementHeader, Length},
+        value::PrimitiveValue,
+        Tag, VR,
+    };
+
+    fn write_json<I>(tokens: I) -> String
+    where
+        I: IntoIterator<Item = DataToken>,
+    {
+        let mut writer = JsonDataSetWriter::new(Vec::new());
+        writer.write_sequence(tokens).unwrap();
+        String::from_utf8(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn write_json_primitive_elements() {
+        let tokens = vec![
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0008, 0x0060),
+                VR::CS,
+                Length(2),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from("MR")),
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0010, 0x0010),
+                VR::PN,
+                Length(10),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from("Doe^John ")),
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0018, 0x0050),
+                VR::DS,
+                Length(8),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::Strs(
+                ["1.5".to_owned(), "2 ".to_owned()].as_ref().into(),
+            )),
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0028, 0x0010),
+                VR::US,
+                Length(2),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from(512_u16)),
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0029, 0x1010),
+                VR::OB,
+                Length(4),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::U8([1, 2, 3, 4].as_ref().into())),
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0032, 0x1032),
+                VR::PN,
+                Length(0),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::Empty),
+        ];
+
+        assert_eq!(
+            write_json(tokens),
+            concat!(
+                r#"{"00080060":{"vr":"CS","Value":["MR"]},"#,
+                r#""00100010":{"vr":"PN","Value":[{"Alphabetic":"Doe^John"}]},"#,
+                r#""00180050":{"vr":"DS","Value":[1.5,2]},"#,
+                r#""00280010":{"vr":"US","Value":[512]},"#,
+                r#""00291010":{"vr":"OB","InlineBinary":"AQIDBA=="},"#,
+                r#""00321032":{"vr":"PN"}}"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn write_json_sequences_and_bulk_data() {
+        let tokens = vec![
+            DataToken::SequenceStart {
+                tag: Tag(0x0008, 0x1115),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0020, 0x000E),
+                VR::UI,
+                Length(6),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from("1.2.3\0")),
+            DataToken::ItemEnd,
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::SequenceStart {
+                tag: Tag(0x0040, 0x0275),
+                len: Length(0),
+            },
+            DataToken::SequenceEnd,
+            DataToken::PixelSequenceStart,
+            DataToken::ItemStart { len: Length(0) },
+            DataToken::ItemEnd,
+            DataToken::ItemStart { len: Length(4) },
+            DataToken::ItemValue(vec![0x99; 4]),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+        ];
+
+        let mut writer = JsonDataSetWriter::with_bulk_data(
+            Vec::new(),
+            |path: &[Tag], _: &DataElementHeader| {
+                let tag = path.last().unwrap();
+                Some(format!("bulk/{:04X}{:04X}", tag.0, tag.1))
+            },
+            1024,
+        );
+        writer.write_sequence(tokens).unwrap();
+        let json = String::from_utf8(writer.finish().unwrap()).unwrap();
+
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"00081115":{"vr":"SQ","Value":["#,
+                r#"{"0020000E":{"vr":"UI","Value":["1.2.3"]}},{}"#,
+                r#"]},"#,
+                r#""00400275":{"vr":"SQ"},"#,
+                r#""7FE00010":{"vr":"OB","BulkDataURI":"bulk/7FE00010"}}"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn unfinished_data_set_is_an_error() {
+        let mut writer = JsonDataSetWriter::new(Vec::new());
+        writer
+            .write(DataToken::SequenceStart {
+                tag: Tag(0x0008, 0x1115),
+                len: Length::UNDEFINED,
+            })
+            .unwrap();
+        assert!(writer.finish().is_err());
+    }
+}
@@ -0,0 +1,434 @@
+//! Module for reading DICOM JSON
+//!
+//! This module reads data sets in the DICOM JSON Model (PS3.18 section F.2),
+//! as found in QIDO-RS and STOW-RS responses,
+//! into a sequence of data set tokens.
+//! The tokens can then be collected into an in-memory DICOM object,
+//! or passed to a data set writer.
+//!
+//! Values referred to by a `BulkDataURI` are not retrieved,
+//! and the respective elements are kept with an empty value,
+//! unless the data set is read with a bulk data resolver
+//! (see [`read_tokens_with_bulk_data`]).
+use super::super::DataToken;
+use dicom_core::header::{DataElementHeader, HasLength, Length, VR};
+use dicom_core::value::{PrimitiveValue, C};
+use dicom_core::Tag;
+use serde_json::{Map, Value as JsonValue};
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+use std::convert::TryFrom;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("Could not parse JSON text"))]
+    ParseJson {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Expected a JSON object for a data set"))]
+    NotADataSet { backtrace: Backtrace },
+    #[snafu(display("Invalid attribute tag `{}`", key))]
+    InvalidTag { key: String, backtrace: Backtrace },
+    #[snafu(display("Missing VR in attribute {}", tag))]
+    MissingVr { tag: Tag, backtrace: Backtrace },
+    #[snafu(display("Unknown VR `{}` in attribute {}", vr, tag))]
+    UnknownVr {
+        tag: Tag,
+        vr: String,
+        backtrace: Backtrace,
+    },
+    #[snafu(display("Invalid value in attribute {}", tag))]
+    InvalidValue { tag: Tag, backtrace: Backtrace },
+    #[snafu(display("Invalid inline binary in attribute {}", tag))]
+    InvalidInlineBinary { tag: Tag, backtrace: Backtrace },
+    #[snafu(display("Could not retrieve bulk data `{}` of attribute {}", uri, tag))]
+    UnresolvedBulkData {
+        tag: Tag,
+        uri: String,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Read the tokens of a single data set from DICOM JSON text.
+pub fn read_tokens_from_slice(json: &[u8]) -> Result<Vec<DataToken>> {
+    let value: JsonValue = serde_json::from_slice(json).context(ParseJsonSnafu)?;
+    read_tokens(&value)
+}
+
+/// Read the tokens of a data set from a parsed JSON value.
+pub fn read_tokens(value: &JsonValue) -> Result<Vec<DataToken>> {
+    let mut tokens = Vec::new();
+    read_data_set(value, &mut |_, _| Ok(None), &mut tokens)?;
+    Ok(tokens)
+}
+
+/// Read the tokens of a single data set from DICOM JSON text,
+/// retrieving the values referred to by a `BulkDataURI`
+/// with the given function.
+///
+/// See [`read_tokens_with_bulk_data`] for more information.
+pub fn read_tokens_from_slice_with_bulk_data<F>(json: &[u8], bulk_data: F) -> Result<Vec<DataToken>>
+where
+    F: FnMut(&str) -> Option<Vec<u8>>,
+{
+    let value: JsonValue = serde_json::from_slice(json).context(ParseJsonSnafu)?;
+    read_tokens_with_bulk_data(&value, bulk_data)
+}
+
+/// Read the tokens of a data set from a parsed JSON value,
+/// retrieving the values referred to by a `BulkDataURI`
+/// with the given function.
+///
+/// The function receives the bulk data URI
+/// and returns the bytes of the value in little endian,
+/// or `None` if the value could not be retrieved,
+/// in which case reading fails.
+pub fn read_tokens_with_bulk_data<F>(value: &JsonValue, mut bulk_data: F) -> Result<Vec<DataToken>>
+where
+    F: FnMut(&str) -> Option<Vec<u8>>,
+{
+    let mut tokens = Vec::new();
+    read_data_set(
+        value,
+        &mut |tag, uri| {
+            bulk_data(uri)
+                .map(Some)
+                .context(UnresolvedBulkDataSnafu { tag, uri })
+        },
+        &mut tokens,
+    )?;
+    Ok(tokens)
+}
+
+/// A function retrieving bulk data by tag and URI.
+type BulkDataFn<'a> = dyn FnMut(Tag, &str) -> Result<Option<Vec<u8>>> + 'a;
+
+fn read_data_set(
+    value: &JsonValue,
+    bulk_data: &mut BulkDataFn,
+    tokens: &mut Vec<DataToken>,
+) -> Result<()> {
+    let attributes = value.as_object().context(NotADataSetSnafu)?;
+    for (key, attribute) in attributes {
+        read_element(key, attribute, bulk_data, tokens)?;
+    }
+    Ok(())
+}
+
+fn read_element(
+    key: &str,
+    attribute: &JsonValue,
+    bulk_data: &mut BulkDataFn,
+    tokens: &mut Vec<DataToken>,
+) -> Result<()> {
+    let tag = parse_tag(key).context(InvalidTagSnafu { key })?;
+    let attribute = attribute.as_object().context(InvalidValueSnafu { tag })?;
+    let vr = attribute
+        .get("vr")
+        .and_then(JsonValue::as_str)
+        .context(MissingVrSnafu { tag })?;
+    let vr: VR = vr.parse().ok().context(UnknownVrSnafu { tag, vr })?;
+
+    if vr == VR::SQ {
+        tokens.push(DataToken::SequenceStart {
+            tag,
+            len: Length::UNDEFINED,
+        });
+        for item in values(attribute) {
+            tokens.push(DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            });
+            read_data_set(item, bulk_data, tokens)?;
+            tokens.push(DataToken::ItemEnd);
+        }
+        tokens.push(DataToken::SequenceEnd);
+        return Ok(());
+    }
+
+    let value = read_value(tag, vr, attribute, bulk_data)?;
+    tokens.push(DataToken::ElementHeader(DataElementHeader::new(
+        tag,
+        vr,
+        value.length(),
+    )));
+    tokens.push(DataToken::PrimitiveValue(value));
+    Ok(())
+}
+
+fn read_value(
+    tag: Tag,
+    vr: VR,
+    attribute: &Map<String, JsonValue>,
+    bulk_data: &mut BulkDataFn,
+) -> Result<PrimitiveValue> {
+    if let Some(uri) = attribute.get("BulkDataURI") {
+        let uri = uri.as_str().context(InvalidValueSnafu { tag })?;
+        if let Some(data) = bulk_data(tag, uri)? {
+            return Ok(match vr {
+                VR::OB | VR::OD | VR::OF | VR::OL | VR::OV | VR::OW | VR::UN => {
+                    binary_value(vr, data)
+                }
+                _ => PrimitiveValue::from(String::from_utf8_lossy(&data).into_owned()),
+            });
+        }
+    }
+
+    if let Some(data) = attribute.get("InlineBinary") {
+        let data = data
+            .as_str()
+            .and_then(decode_base64)
+            .context(InvalidInlineBinarySnafu { tag })?;
+        return Ok(binary_value(vr, data));
+    }
+
+    read_primitive(tag, vr, attribute)
+}
+
+/// Iterate over the elements of the `Value` array, if any.
+fn values(attribute: &Map<String, JsonValue>) -> impl Iterator<Item = &JsonValue> {
+    attribute
+        .get("Value")
+        .and_then(JsonValue::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn read_primitive(tag: Tag, vr: VR, attribute: &Map<String, JsonValue>) -> Result<PrimitiveValue> {
+    macro_rules! numbers {
+        ($variant: ident, $convert: expr) => {
+            values(attribute)
+                .map($convert)
+                .collect::<Option<C<_>>>()
+                .map(PrimitiveValue::$variant)
+                .context(InvalidValueSnafu { tag })
+        };
+    }
+
+    match vr {
+        VR::US => numbers!(U16, |v: &JsonValue| v
+            .as_u64()
+            .and_then(|v| u16::try_from(v).ok())),
+        VR::SS => numbers!(I16, |v: &JsonValue| v
+            .as_i64()
+            .and_then(|v| i16::try_from(v).ok())),
+        VR::UL => numbers!(U32, |v: &JsonValue| v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())),
+        VR::SL => numbers!(I32, |v: &JsonValue| v
+            .as_i64()
+            .and_then(|v| i32::try_from(v).ok())),
+        VR::UV => numbers!(U64, |v: &JsonValue| v.as_u64()),
+        VR::SV => numbers!(I64, |v: &JsonValue| v.as_i64()),
+        VR::FL => numbers!(F32, |v: &JsonValue| v.as_f64().map(|v| v as f32)),
+        VR::FD => numbers!(F64, |v: &JsonValue| v.as_f64()),
+        VR::AT => numbers!(Tags, |v: &JsonValue| v.as_str().and_then(parse_tag)),
+        VR::PN => values(attribute)
+            .map(|v| match v {
+                JsonValue::Null => Some(String::new()),
+                JsonValue::Object(groups) => Some(person_name(groups)),
+                _ => None,
+            })
+            .collect::<Option<C<_>>>()
+            .map(PrimitiveValue::Strs)
+            .context(InvalidValueSnafu { tag }),
+        VR::OB | VR::OD | VR::OF | VR::OL | VR::OV | VR::OW | VR::UN => {
+            // bulk data is not retrieved
+            Ok(PrimitiveValue::Empty)
+        }
+        _ => values(attribute)
+            .map(|v| match v {
+                JsonValue::Null => Some(String::new()),
+                JsonValue::String(s) => Some(s.clone()),
+                // DS and IS values may be written as numbers
+                JsonValue::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+            .collect::<Option<C<_>>>()
+            .map(PrimitiveValue::Strs)
+            .context(InvalidValueSnafu { tag }),
+    }
+    .map(|value| {
+        if value.multiplicity() == 0 {
+            PrimitiveValue::Empty
+        } else {
+            value
+        }
+    })
+}
+
+/// Join the component groups of a person name with `=`,
+/// omitting trailing empty groups.
+fn person_name(groups: &Map<String, JsonValue>) -> String {
+    let mut name = ["Alphabetic", "Ideographic", "Phonetic"]
+        .iter()
+        .map(|key| groups.get(*key).and_then(JsonValue::as_str).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("=");
+    while name.ends_with('=') {
+        name.pop();
+    }
+    name
+}
+
+/// Interpret the bytes of a binary value according to its VR,
+/// in little endian.
+fn binary_value(vr: VR, data: Vec<u8>) -> PrimitiveValue {
+    match vr {
+        VR::OW => PrimitiveValue::U16(
+            data.chunks_exact(2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+                .collect(),
+        ),
+        VR::OL => PrimitiveValue::U32(
+            data.chunks_exact(4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+        ),
+        VR::OF => PrimitiveValue::F32(
+            data.chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect(),
+        ),
+        VR::OD => PrimitiveValue::F64(
+            data.chunks_exact(8)
+                .map(|b| f64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                .collect(),
+        ),
+        VR::OV => PrimitiveValue::U64(
+            data.chunks_exact(8)
+                .map(|b| u64::from_le_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+                .collect(),
+        ),
+        _ => PrimitiveValue::U8(data.into()),
+    }
+}
+
+/// Parse an attribute tag in the form `GGGGEEEE`.
+fn parse_tag(key: &str) -> Option<Tag> {
+    if key.len() != 8 {
+        return None;
+    }
+    let group = u16::from_str_radix(key.get(0..4)?, 16).ok()?;
+    let element = u16::from_str_radix(key.get(4..8)?, 16).ok()?;
+    Some(Tag(group, element))
+}
+
+/// A minimal base64 decoder (standard alphabet, with padding).
+fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    fn sextet(c: u8) -> Option<u32> {
+        match c {
+            b'A'..=b'Z' => Some((c - b'A') as u32),
+            b'a'..=b'z' => Some((c - b'a' + 26) as u32),
+            b'0'..=b'9' => Some((c - b'0' + 52) as u32),
+            b'+' => Some(62),
+            b'/' => Some(63),
+            _ => None,
+        }
+    }
+
+    let text = text.trim_end_matches('=').as_bytes();
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.chunks(4) {
+        if chunk.len() < 2 {
+            return None;
+        }
+        let mut bits = 0_u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            bits |= sextet(c)? << (18 - 6 * i);
+        }
+        let bytes = bits.to_be_bytes();
+        out.extend_from_slice(&bytes[1..chunk.len()]);
+    }
+    Some(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_dictionary_std::tags;
+
+    #[test]
+    fn read_data_set_tokens() {
+        let json = br#"{
+            "00100010": { "vr": "PN", "Value": [{ "Alphabetic": "Doe^John" }] },
+            "00280010": { "vr": "US", "Value": [512] },
+            "00091002": { "vr": "OB", "InlineBinary": "AQID" },
+            "00081199": { "vr": "SQ", "Value": [
+                { "00081155": { "vr": "UI", "Value": ["1.2.3.4"] } }
+            ] }
+        }"#;
+
+        let tokens = read_tokens_from_slice(json).unwrap();
+        let ground_truth = vec![
+            DataToken::SequenceStart {
+                tag: tags::REFERENCED_SOP_SEQUENCE,
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader::new(
+                tags::REFERENCED_SOP_INSTANCE_UID,
+                VR::UI,
+                Length(8),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from("1.2.3.4")),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0009, 0x1002),
+                VR::OB,
+                Length(3),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from(vec![1_u8, 2, 3])),
+            DataToken::ElementHeader(DataElementHeader::new(
+                tags::PATIENT_NAME,
+                VR::PN,
+                Length(8),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from("Doe^John")),
+            DataToken::ElementHeader(DataElementHeader::new(tags::ROWS, VR::US, Length(2))),
+            DataToken::PrimitiveValue(PrimitiveValue::from(512_u16)),
+        ];
+        assert_eq!(tokens, ground_truth);
+    }
+
+    #[test]
+    fn read_tokens_with_unresolved_bulk_data() {
+        let json = br#"{ "7FE00010": { "vr": "OW", "BulkDataURI": "bulk/1" } }"#;
+        let tokens = read_tokens_from_slice(json).unwrap();
+        assert_eq!(tokens[1], DataToken::PrimitiveValue(PrimitiveValue::Empty));
+        assert!(matches!(
+            read_tokens_from_slice_with_bulk_data(json, |_| None),
+            Err(Error::UnresolvedBulkData { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_malformed_attributes() {
+        assert!(matches!(
+            read_tokens_from_slice(br#"{ "0010": { "vr": "LO" } }"#),
+            Err(Error::InvalidTag { .. })
+        ));
+        assert!(matches!(
+            read_tokens_from_slice(br#"{ "00100010": { "Value": ["x"] } }"#),
+            Err(Error::MissingVr { .. })
+        ));
+        assert!(matches!(
+            read_tokens_from_slice(br#"{ "00280010": { "vr": "US", "Value": ["x"] } }"#),
+            Err(Error::InvalidValue { .. })
+        ));
+    }
+
+    #[test]
+    fn base64() {
+        assert_eq!(decode_base64("TWFu").unwrap(), b"Man");
+        assert_eq!(decode_base64("TWE=").unwrap(), b"Ma");
+        assert_eq!(decode_base64("TQ==").unwrap(), b"M");
+        assert_eq!(decode_base64("").unwrap(), b"");
+        assert_eq!(decode_base64("T$=="), None);
+    }
+}
@@ -16,10 +16,12 @@ use crate::stateful::decode::{DynStatefulDecoder, Error as DecoderError, Statefu
 use crate::util::ReadSeek;
 use dicom_core::header::{DataElementHeader, Header, Length, SequenceItemHeader};
 use dicom_core::{Tag, VR};
+use dicom_encoding::source::DataSource;
 use dicom_encoding::text::SpecificCharacterSet;
 use dicom_encoding::transfer_syntax::TransferSyntax;
 use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
-use std::{cmp::Ordering, io::SeekFrom};
+use std::cmp::Ordering;
+use std::io::{Read, SeekFrom};
 
 use super::{LazyDataToken, SeqTokenType};
 
@@ -128,6 +130,43 @@ impl<R> LazyDataSetReader<DynStatefulDecoder<R>> {
         let position = source
             .seek(SeekFrom::Current(0))
             .context(GetPositionSnafu)?;
+        Self::with_position(source, ts, cs, position)
+    }
+
+    /// Create a new lazy data set reader
+    /// with the given data source and element dictionary,
+    /// while considering the given transfer syntax and specific character set.
+    ///
+    /// Unlike [`new_with_ts_cs`](LazyDataSetReader::new_with_ts_cs),
+    /// the source does not need to be seekable.
+    /// If the source is able to report its current position,
+    /// byte offsets are relative to the start of the source.
+    /// Otherwise, they are relative to the source's current position.
+    pub fn from_source_with_ts_cs(
+        mut source: R,
+        ts: &TransferSyntax,
+        cs: SpecificCharacterSet,
+    ) -> Result<Self>
+    where
+        R: DataSource,
+    {
+        let position = source
+            .try_position()
+            .transpose()
+            .context(GetPositionSnafu)?
+            .unwrap_or(0);
+        Self::with_position(source, ts, cs, position)
+    }
+
+    fn with_position(
+        source: R,
+        ts: &TransferSyntax,
+        cs: SpecificCharacterSet,
+        position: u64,
+    ) -> Result<Self>
+    where
+        R: Read,
+    {
         let parser =
             DynStatefulDecoder::new_with(source, ts, cs, position).context(CreateDecoderSnafu)?;
 
@@ -1146,4 +1185,60 @@ mod tests {
             "unexpected number of tokens remaining"
         );
     }
+
+    #[test]
+    fn lazy_read_from_seekable_and_forward_only_sources() {
+        use dicom_encoding::source::StreamSource;
+        use dicom_encoding::transfer_syntax::{AdapterFreeTransferSyntax, Codec};
+        use dicom_encoding::Endianness;
+        use std::io::{Cursor, Seek, SeekFrom};
+
+        // 4 bytes of garbage, then
+        //  Tag: (0010,0010) Patient Name
+        //  VR: PN
+        //  Length: 8
+        //  Value: "Doe^John"
+        const RAW: &[u8] = &[
+            0xFF, 0xFF, 0xFF, 0xFF, 0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00, b'D', b'o',
+            b'e', b'^', b'J', b'o', b'h', b'n',
+        ];
+        let ts = AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2.1",
+            "Explicit VR Little Endian",
+            Endianness::Little,
+            true,
+            Codec::None,
+        )
+        .erased();
+
+        fn read_all<S: StatefulDecode>(mut dset_reader: LazyDataSetReader<S>) -> u64 {
+            while let Some(token) = dset_reader.next() {
+                token.unwrap().skip().unwrap();
+            }
+            dset_reader.parser.position()
+        }
+
+        // offsets of seekable sources are relative to their start
+        let mut cursor = Cursor::new(RAW);
+        cursor.seek(SeekFrom::Start(4)).unwrap();
+        let dset_reader =
+            LazyDataSetReader::new_with_ts_cs(cursor, &ts, SpecificCharacterSet::Default).unwrap();
+        assert_eq!(read_all(dset_reader), RAW.len() as u64);
+
+        let mut cursor = Cursor::new(RAW);
+        cursor.seek(SeekFrom::Start(4)).unwrap();
+        let dset_reader =
+            LazyDataSetReader::from_source_with_ts_cs(cursor, &ts, SpecificCharacterSet::Default)
+                .unwrap();
+        assert_eq!(read_all(dset_reader), RAW.len() as u64);
+
+        // offsets of forward-only sources are relative to where reading started
+        let dset_reader = LazyDataSetReader::from_source_with_ts_cs(
+            StreamSource(&RAW[4..]),
+            &ts,
+            SpecificCharacterSet::Default,
+        )
+        .unwrap();
+        assert_eq!(read_all(dset_reader), RAW.len() as u64 - 4);
+    }
 }
@@ -0,0 +1,484 @@
+//! Computation of explicit lengths for sequences and items.
+//!
+//! Data sets are usually written with sequences and items
+//! of undefined length, which are closed by delimitation items.
+//! Some applications, such as certain archives,
+//! only accept sequences and items of defined length.
+//! A [`SizedDataSet`] is the outcome of a sizing pass over a data set,
+//! in which the byte length of each sequence and item is computed
+//! before anything is written.
+//!
+//! Since the length of a textual value
+//! depends on the character set in use,
+//! each primitive element is encoded once during the sizing pass,
+//! and the encoded bytes are kept
+//! so that they are not encoded again when writing the data set.
+//! Encapsulated pixel data keeps its undefined length,
+//! as required by the standard,
+//! but still counts towards the length of the enclosing item.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_core::{DataElementHeader, Length, PrimitiveValue, Tag, VR};
+//! # use dicom_encoding::text::SpecificCharacterSet;
+//! # use dicom_encoding::transfer_syntax::{AdapterFreeTransferSyntax, Codec, Endianness};
+//! use dicom_parser::dataset::length::SizedDataSet;
+//! use dicom_parser::dataset::{DataSetWriter, DataToken};
+//!
+//! # let ts = AdapterFreeTransferSyntax::new(
+//! #     "1.2.840.10008.1.2.1",
+//! #     "Explicit VR Little Endian",
+//! #     Endianness::Little,
+//! #     true,
+//! #     Codec::None,
+//! # )
+//! # .erased();
+//! let tokens = vec![
+//!     DataToken::SequenceStart {
+//!         tag: Tag(0x0008, 0x1140),
+//!         len: Length::UNDEFINED,
+//!     },
+//!     DataToken::ItemStart {
+//!         len: Length::UNDEFINED,
+//!     },
+//!     DataToken::ElementHeader(DataElementHeader::new(
+//!         Tag(0x0008, 0x1155),
+//!         VR::UI,
+//!         Length::UNDEFINED,
+//!     )),
+//!     DataToken::PrimitiveValue(PrimitiveValue::from("1.2.3.4")),
+//!     DataToken::ItemEnd,
+//!     DataToken::SequenceEnd,
+//! ];
+//!
+//! let sized = SizedDataSet::new(tokens, &ts, SpecificCharacterSet::Default)?;
+//! // 12 bytes of sequence header, 8 bytes of item header,
+//! // and 16 bytes of the referenced SOP instance UID
+//! assert_eq!(sized.encoded_len(), 36);
+//!
+//! let mut out = Vec::new();
+//! let mut writer = DataSetWriter::with_ts_cs(&mut out, &ts, SpecificCharacterSet::Default)?;
+//! sized.write_to(&mut writer)?;
+//! drop(writer);
+//! assert_eq!(out.len(), 36);
+//! // sequence length
+//! assert_eq!(&out[8..12], &[24, 0, 0, 0]);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+use crate::dataset::write::{
+    DataSetWriter, LengthOverflowSnafu, PrematureEndSnafu, Result, UnexpectedTokenSnafu,
+};
+use crate::dataset::DataToken;
+use dicom_core::{Length, Tag};
+use dicom_encoding::encode::EncodeTo;
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::TransferSyntax;
+use snafu::{ensure, OptionExt};
+use std::convert::TryFrom;
+use std::io::Write;
+use std::ops::Range;
+
+/// A token of a sized data set.
+#[derive(Debug, Clone, PartialEq)]
+enum SizedToken {
+    /// The beginning of a sequence with the length of its items
+    SequenceStart { tag: Tag, len: u32 },
+    /// The beginning of an item with the length of its elements
+    ItemStart { len: u32 },
+    /// The end of an item
+    ItemEnd,
+    /// The end of a sequence
+    SequenceEnd,
+    /// The range of one or more encoded data elements in the buffer
+    Encoded(Range<usize>),
+}
+
+/// An open sequence or item during the sizing pass.
+#[derive(Debug)]
+struct Frame {
+    /// whether this is an item rather than a sequence
+    item: bool,
+    /// the index of the starting token
+    token: usize,
+    /// the byte length of the contents so far
+    len: u64,
+    /// the character set in effect before the start
+    charset: SpecificCharacterSet,
+}
+
+/// A data set with the lengths of all of its sequences and items computed
+/// and its primitive data elements already encoded,
+/// ready to be written with defined lengths.
+///
+/// See the [module-level documentation](self) for more details.
+#[derive(Debug, Clone)]
+pub struct SizedDataSet {
+    /// the sequence and item delimiters, plus ranges of encoded elements
+    tokens: Vec<SizedToken>,
+    /// the encoded data elements
+    data: Vec<u8>,
+    /// the byte length of the whole data set
+    len: u64,
+}
+
+impl SizedDataSet {
+    /// Perform the sizing pass over the given data set tokens,
+    /// as they would be encoded with the given transfer syntax,
+    /// starting with the given character set.
+    ///
+    /// The lengths of the sequences and items in the tokens are ignored.
+    /// An error is returned if the tokens are not well nested,
+    /// or if a sequence or item is too large for a defined length.
+    pub fn new<I>(tokens: I, ts: &TransferSyntax, charset: SpecificCharacterSet) -> Result<Self>
+    where
+        I: IntoIterator<Item = DataToken>,
+    {
+        let sequence_header_len = if ts.explicit_vr() { 12 } else { 8 };
+        let mut out = Vec::new();
+        let mut frames: Vec<Frame> = Vec::new();
+        let mut len = 0;
+        let mut data = Vec::new();
+        let mut writer = DataSetWriter::with_ts_cs(&mut data, ts, charset)?;
+        // whether the tokens are in encapsulated pixel data,
+        // which is written as is
+        let mut in_pixel_data = false;
+
+        for token in tokens {
+            let (start_token, content_len) = match token {
+                DataToken::SequenceStart { tag, .. } if !in_pixel_data => {
+                    out.push(SizedToken::SequenceStart { tag, len: 0 });
+                    frames.push(Frame {
+                        item: false,
+                        token: out.len() - 1,
+                        len: 0,
+                        charset: writer.character_set(),
+                    });
+                    continue;
+                }
+                DataToken::ItemStart { .. } if !in_pixel_data => {
+                    out.push(SizedToken::ItemStart { len: 0 });
+                    frames.push(Frame {
+                        item: true,
+                        token: out.len() - 1,
+                        len: 0,
+                        charset: writer.character_set(),
+                    });
+                    continue;
+                }
+                DataToken::ItemEnd if !in_pixel_data => {
+                    let frame = frames
+                        .pop()
+                        .filter(|frame| frame.item)
+                        .context(UnexpectedTokenSnafu { token })?;
+                    // a character set declared in the item ends with it
+                    writer.set_character_set(frame.charset);
+                    out.push(SizedToken::ItemEnd);
+                    (frame.token, frame.len)
+                }
+                DataToken::SequenceEnd if !in_pixel_data => {
+                    let frame = frames
+                        .pop()
+                        .filter(|frame| !frame.item)
+                        .context(UnexpectedTokenSnafu { token })?;
+                    out.push(SizedToken::SequenceEnd);
+                    (frame.token, frame.len)
+                }
+                token => {
+                    // element headers, values, and encapsulated pixel data
+                    // go through the writer to be encoded right away
+                    match token {
+                        DataToken::PixelSequenceStart => in_pixel_data = true,
+                        DataToken::SequenceEnd => in_pixel_data = false,
+                        _ => {}
+                    }
+                    let start = writer.bytes_written();
+                    writer.write(token)?;
+                    let end = writer.bytes_written();
+                    if end > start {
+                        let range = start as usize..end as usize;
+                        match out.last_mut() {
+                            // extend the previous range of encoded elements
+                            Some(SizedToken::Encoded(last)) => last.end = range.end,
+                            _ => out.push(SizedToken::Encoded(range)),
+                        }
+                        match frames.last_mut() {
+                            Some(frame) => frame.len += end - start,
+                            None => len += end - start,
+                        }
+                    }
+                    continue;
+                }
+            };
+
+            // close the sequence or item
+            let defined_len = u32::try_from(content_len)
+                .ok()
+                .filter(|len| *len != Length::UNDEFINED.0)
+                .context(LengthOverflowSnafu { len: content_len })?;
+            let header_len = match &mut out[start_token] {
+                SizedToken::SequenceStart { len, .. } => {
+                    *len = defined_len;
+                    sequence_header_len
+                }
+                SizedToken::ItemStart { len } => {
+                    *len = defined_len;
+                    8
+                }
+                _ => unreachable!("frame should refer to a sequence or item start"),
+            };
+            match frames.last_mut() {
+                Some(frame) => frame.len += header_len + content_len,
+                None => len += header_len + content_len,
+            }
+        }
+        ensure!(frames.is_empty(), PrematureEndSnafu);
+        drop(writer);
+
+        Ok(SizedDataSet {
+            tokens: out,
+            data,
+            len,
+        })
+    }
+
+    /// Get the byte length of the data set
+    /// when written with defined lengths.
+    pub fn encoded_len(&self) -> u64 {
+        self.len
+    }
+
+    /// Write the data set into the given data set writer,
+    /// with all sequences and items in defined length.
+    ///
+    /// The writer should be of the same transfer syntax
+    /// as the one used in the sizing pass.
+    pub fn write_to<W, E>(&self, writer: &mut DataSetWriter<W, E>) -> Result<()>
+    where
+        W: Write,
+        E: EncodeTo<W>,
+    {
+        for token in &self.tokens {
+            match token {
+                SizedToken::SequenceStart { tag, len } => {
+                    writer.write(DataToken::SequenceStart {
+                        tag: *tag,
+                        len: Length(*len),
+                    })?
+                }
+                SizedToken::ItemStart { len } => {
+                    writer.write(DataToken::ItemStart { len: Length(*len) })?
+                }
+                SizedToken::ItemEnd => writer.write(DataToken::ItemEnd)?,
+                SizedToken::SequenceEnd => writer.write(DataToken::SequenceEnd)?,
+                SizedToken::Encoded(range) => {
+                    writer.write_raw_elements(&self.data[range.clone()])?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SizedDataSet;
+    use crate::dataset::write::Error;
+    use crate::dataset::{DataSetWriter, DataToken};
+    use dicom_core::header::{DataElementHeader, Length};
+    use dicom_core::value::PrimitiveValue;
+    use dicom_core::{dicom_value, Tag, VR};
+    use dicom_encoding::text::SpecificCharacterSet;
+    use dicom_encoding::transfer_syntax::{
+        AdapterFreeTransferSyntax, Codec, Endianness, TransferSyntax,
+    };
+
+    fn explicit_vr_le() -> TransferSyntax {
+        AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2.1",
+            "Explicit VR Little Endian",
+            Endianness::Little,
+            true,
+            Codec::None,
+        )
+        .erased()
+    }
+
+    fn write_sized(tokens: Vec<DataToken>) -> (u64, Vec<u8>) {
+        let ts = explicit_vr_le();
+        let sized = SizedDataSet::new(tokens, &ts, SpecificCharacterSet::Default).unwrap();
+        let mut out = Vec::new();
+        let mut writer =
+            DataSetWriter::with_ts_cs(&mut out, &ts, SpecificCharacterSet::Default).unwrap();
+        sized.write_to(&mut writer).unwrap();
+        drop(writer);
+        (sized.encoded_len(), out)
+    }
+
+    #[test]
+    fn nested_sequences_have_defined_lengths() {
+        let tokens = vec![
+            DataToken::SequenceStart {
+                tag: Tag(0x0018, 0x6011),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0018, 0x6012),
+                VR::US,
+                Length(2),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from(1_u16)),
+            DataToken::SequenceStart {
+                tag: Tag(0x0018, 0x6013),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0018, 0x6014),
+                VR::US,
+                Length(2),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from(2_u16)),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::ItemEnd,
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0020, 0x4000),
+                VR::LT,
+                Length(4),
+            )),
+            DataToken::PrimitiveValue(PrimitiveValue::from("TEST")),
+        ];
+
+        let (len, out) = write_sized(tokens);
+
+        #[rustfmt::skip]
+        static GROUND_TRUTH: &[u8] = &[
+            // (0018,6011) SQ, length: 56
+            0x18, 0x00, 0x11, 0x60, b'S', b'Q', 0x00, 0x00, 0x38, 0x00, 0x00, 0x00,
+            // item, length: 40
+            0xfe, 0xff, 0x00, 0xe0, 0x28, 0x00, 0x00, 0x00,
+            // (0018,6012) US, value = 1
+            0x18, 0x00, 0x12, 0x60, b'U', b'S', 0x02, 0x00, 0x01, 0x00,
+            // (0018,6013) SQ, length: 18
+            0x18, 0x00, 0x13, 0x60, b'S', b'Q', 0x00, 0x00, 0x12, 0x00, 0x00, 0x00,
+            // item, length: 10
+            0xfe, 0xff, 0x00, 0xe0, 0x0a, 0x00, 0x00, 0x00,
+            // (0018,6014) US, value = 2
+            0x18, 0x00, 0x14, 0x60, b'U', b'S', 0x02, 0x00, 0x02, 0x00,
+            // empty item
+            0xfe, 0xff, 0x00, 0xe0, 0x00, 0x00, 0x00, 0x00,
+            // (0020,4000) LT, value = "TEST"
+            0x20, 0x00, 0x00, 0x40, b'L', b'T', 0x04, 0x00, b'T', b'E', b'S', b'T',
+        ];
+        assert_eq!(out, GROUND_TRUTH);
+        assert_eq!(len, GROUND_TRUTH.len() as u64);
+    }
+
+    #[test]
+    fn text_lengths_follow_character_set() {
+        let tokens = vec![
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0008, 0x0005),
+                VR::CS,
+                Length::UNDEFINED,
+            )),
+            DataToken::PrimitiveValue(dicom_value!(Strs, ["ISO_IR 192"])),
+            DataToken::SequenceStart {
+                tag: Tag(0x0040, 0xA730),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader::new(
+                Tag(0x0040, 0xA160),
+                VR::UT,
+                Length::UNDEFINED,
+            )),
+            // 5 bytes in UTF-8, padded to 6
+            DataToken::PrimitiveValue(PrimitiveValue::from("Olá!")),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+        ];
+
+        let (len, out) = write_sized(tokens);
+        // character set element
+        assert_eq!(&out[8..18], b"ISO_IR 192");
+        // sequence length: item header + UT header + value
+        assert_eq!(&out[26..30], &[26, 0, 0, 0]);
+        // item length
+        assert_eq!(&out[34..38], &[18, 0, 0, 0]);
+        // value length
+        assert_eq!(&out[46..50], &[6, 0, 0, 0]);
+        assert_eq!(&out[50..], "Olá! ".as_bytes());
+        assert_eq!(len, out.len() as u64);
+    }
+
+    #[test]
+    fn pixel_sequences_keep_undefined_length() {
+        let tokens = vec![
+            DataToken::SequenceStart {
+                tag: Tag(0x0088, 0x0200),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::PixelSequenceStart,
+            DataToken::ItemStart { len: Length(0) },
+            DataToken::ItemEnd,
+            DataToken::ItemStart { len: Length(4) },
+            DataToken::ItemValue(vec![1, 2, 3, 4]),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+        ];
+
+        let (len, out) = write_sized(tokens);
+        // pixel data header, offset table, one fragment, sequence delimiter
+        let pixel_len = 12 + 8 + 12 + 8;
+        assert_eq!(len, 12 + 8 + pixel_len);
+        assert_eq!(out.len() as u64, len);
+        // icon image sequence length
+        assert_eq!(&out[8..12], &[48, 0, 0, 0]);
+        // item length
+        assert_eq!(&out[16..20], &[40, 0, 0, 0]);
+        // pixel data still has an undefined length
+        assert_eq!(&out[28..32], &[0xFF, 0xFF, 0xFF, 0xFF]);
+        // and ends with a sequence delimiter
+        assert_eq!(&out[52..], &[0xFE, 0xFF, 0xDD, 0xE0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn unbalanced_tokens_fail() {
+        let ts = explicit_vr_le();
+        let tokens = vec![
+            DataToken::SequenceStart {
+                tag: Tag(0x0008, 0x1140),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemEnd,
+        ];
+        let err = SizedDataSet::new(tokens, &ts, SpecificCharacterSet::Default).unwrap_err();
+        assert!(matches!(err, Error::UnexpectedToken { .. }));
+
+        let tokens = vec![DataToken::SequenceStart {
+            tag: Tag(0x0008, 0x1140),
+            len: Length::UNDEFINED,
+        }];
+        let err = SizedDataSet::new(tokens, &ts, SpecificCharacterSet::Default).unwrap_err();
+        assert!(matches!(err, Error::PrematureEnd { .. }));
+    }
+}
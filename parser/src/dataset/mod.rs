@@ -6,7 +6,13 @@ use dicom_core::{value::Value, DataElement, Tag};
 use snafu::{OptionExt, ResultExt, Snafu};
 use std::fmt;
 
+#[cfg(feature = "async")]
+pub mod async_read;
+pub mod filter;
+pub mod json;
 pub mod lazy_read;
+pub mod length;
+pub mod push;
 pub mod read;
 pub mod write;
 
@@ -141,6 +147,29 @@ impl DataToken {
     }
 }
 
+/// A data token accompanied by the location
+/// of its originating data in the source.
+///
+/// Tokens which do not originate from any encoded data,
+/// such as the end of a sequence with an explicit length,
+/// have a length of zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionedToken {
+    /// The data token.
+    pub token: DataToken,
+    /// The absolute byte offset of the token's header or value in the source.
+    pub offset: u64,
+    /// The number of bytes of the token's header or value in the source.
+    pub len: u64,
+}
+
+impl PositionedToken {
+    /// Obtain the byte offset right after the token's data in the source.
+    pub fn end(&self) -> u64 {
+        self.offset + self.len
+    }
+}
+
 /// A lazy data token for reading a data set
 /// without requiring values to be fully read in memory.
 /// This is part of the interpretation of a
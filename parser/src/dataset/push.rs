@@ -0,0 +1,475 @@
+//! This module contains a push-based abstraction for reading DICOM content
+//! which arrives incrementally,
+//! such as from the P-DATA PDUs of a DICOM association
+//! or from the chunks of an HTTP response.
+//!
+//! Unlike the reader in the [`read`](super::read) module,
+//! the [`PushDataSetReader`] does not own a data source.
+//! Instead, bytes are fed to it as they become available,
+//! and it produces all tokens which can be fully decoded
+//! from the data received so far.
+//! The internal reading state is the same as in [`DataSetReader`],
+//! so both readers produce the same tokens for the same data.
+use crate::stateful::decode::DynStatefulDecoder;
+use dicom_core::{Tag, VR};
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::{Endianness, TransferSyntax};
+use std::io::Read;
+
+use super::read::{DataSetReaderOptions, ParseIssue, PendingRead, Result, TrailingPaddingStrategy};
+use super::{DataSetReader, DataToken};
+
+/// A growing buffer of the bytes fed to a push-based reader
+/// which have not been consumed yet.
+#[derive(Debug, Default)]
+pub(crate) struct PushBuffer {
+    /// the bytes received
+    data: Vec<u8>,
+    /// the position of the first byte not yet consumed
+    position: usize,
+}
+
+impl PushBuffer {
+    /// Retrieve the bytes which were received but not consumed yet.
+    fn available(&self) -> &[u8] {
+        &self.data[self.position..]
+    }
+
+    /// Append the given bytes,
+    /// discarding those which were already consumed.
+    fn push(&mut self, bytes: &[u8]) {
+        if self.position > 0 {
+            self.data.drain(..self.position);
+            self.position = 0;
+        }
+        self.data.extend_from_slice(bytes);
+    }
+}
+
+impl Read for PushBuffer {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.available().read(buf)?;
+        self.position += n;
+        Ok(n)
+    }
+}
+
+/// A push-based reader for retrieving structure in a DICOM data set
+/// from data which arrives incrementally.
+///
+/// Bytes are provided through [`feed`](Self::feed),
+/// which returns the tokens that could be read so far.
+/// Tokens are only produced once all of their data is available,
+/// so the outcome does not depend on how the data is split.
+/// Once no more data is expected,
+/// [`finish`](Self::finish) retrieves any remaining tokens.
+///
+/// Deflated transfer syntaxes are not inflated by this reader.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_parser::dataset::push::PushDataSetReader;
+/// # use dicom_parser::dataset::DataToken;
+/// # use dicom_encoding::transfer_syntax::{AdapterFreeTransferSyntax, Codec, Endianness};
+/// # let ts = AdapterFreeTransferSyntax::new(
+/// #     "1.2.840.10008.1.2.1",
+/// #     "Explicit VR Little Endian",
+/// #     Endianness::Little,
+/// #     true,
+/// #     Codec::None,
+/// # )
+/// # .erased();
+/// let mut reader = PushDataSetReader::new_with_ts(&ts)?;
+/// // (0008,0060) Modality, CS, "MR"
+/// let tokens = reader.feed(&[0x08, 0x00, 0x60, 0x00, b'C', b'S'])?;
+/// assert!(tokens.is_empty());
+/// assert!(reader.needs_more());
+/// let tokens = reader.feed(&[0x02, 0x00, b'M', b'R'])?;
+/// assert_eq!(tokens.len(), 2);
+/// # Result::<(), Box<dyn std::error::Error>>::Ok(())
+/// ```
+pub struct PushDataSetReader {
+    /// the underlying data set reader
+    reader: DataSetReader<DynStatefulDecoder<PushBuffer>>,
+    /// the byte order of the data set
+    endianness: Endianness,
+    /// whether the data set trailing padding is skipped
+    skip_padding: bool,
+    /// whether no more data is expected
+    end_of_data: bool,
+}
+
+impl std::fmt::Debug for PushDataSetReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PushDataSetReader")
+            .field("endianness", &self.endianness)
+            .field("skip_padding", &self.skip_padding)
+            .field("end_of_data", &self.end_of_data)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PushDataSetReader {
+    /// Create a new push-based reader
+    /// for a data set in the given transfer syntax.
+    pub fn new_with_ts(ts: &TransferSyntax) -> Result<Self> {
+        Self::new_with_ts_cs_options(ts, SpecificCharacterSet::Default, Default::default())
+    }
+
+    /// Create a new push-based reader
+    /// for a data set in the given transfer syntax,
+    /// with the given specific character set and options.
+    pub fn new_with_ts_cs_options(
+        ts: &TransferSyntax,
+        cs: SpecificCharacterSet,
+        options: DataSetReaderOptions,
+    ) -> Result<Self> {
+        let reader = DataSetReader::new_with_ts_cs_options(PushBuffer::default(), ts, cs, options)?;
+        Ok(PushDataSetReader {
+            reader,
+            endianness: ts.endianness(),
+            skip_padding: options.trailing_padding == TrailingPaddingStrategy::Skip,
+            end_of_data: false,
+        })
+    }
+
+    /// Report the [issues](ParseIssue) found while reading
+    /// to the given callback.
+    pub fn with_issue_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ParseIssue) + Send + 'static,
+    {
+        self.reader = self.reader.with_issue_callback(callback);
+        self
+    }
+
+    /// Gather the [issues](ParseIssue) found while reading,
+    /// so that they can be retrieved with [`take_issues`](Self::take_issues).
+    pub fn collect_issues(mut self) -> Self {
+        self.reader = self.reader.collect_issues();
+        self
+    }
+
+    /// Retrieve the issues gathered so far,
+    /// leaving the reader's collection empty.
+    pub fn take_issues(&mut self) -> Vec<ParseIssue> {
+        self.reader.take_issues()
+    }
+
+    /// Feed the next bytes of the data set to the reader,
+    /// retrieving all tokens which can now be fully read.
+    ///
+    /// Tokens produced before an error are discarded,
+    /// and the reader produces no more tokens after an error.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<Vec<DataToken>> {
+        self.reader.decoder_mut().source_mut().push(bytes);
+        self.read_available()
+    }
+
+    /// Declare that no more data is expected,
+    /// retrieving the remaining tokens.
+    ///
+    /// Reading ends gracefully if the data ends between data elements.
+    /// Otherwise, the data is handled as truncated,
+    /// as it would be by a [`DataSetReader`] with the same options.
+    pub fn finish(&mut self) -> Result<Vec<DataToken>> {
+        self.end_of_data = true;
+        self.read_available()
+    }
+
+    /// Check whether the reader needs more data
+    /// before it can produce its next token.
+    ///
+    /// This is `false` once the reader has reached the end of the data set,
+    /// such as after an error or after [`finish`](Self::finish).
+    pub fn needs_more(&self) -> bool {
+        !self.end_of_data && !self.is_finished() && !self.can_advance()
+    }
+
+    /// Check whether the reader has reached the end of the data set,
+    /// in which case no more tokens will be produced.
+    pub fn is_finished(&self) -> bool {
+        self.reader.pending_read() == PendingRead::Finished
+    }
+
+    fn read_available(&mut self) -> Result<Vec<DataToken>> {
+        let mut tokens = Vec::new();
+        while self.end_of_data || self.can_advance() {
+            match self.reader.next() {
+                Some(token) => tokens.push(token?),
+                None => break,
+            }
+        }
+        Ok(tokens)
+    }
+
+    /// Check whether enough data is available
+    /// for the underlying reader to produce its next token.
+    fn can_advance(&self) -> bool {
+        let available = self.reader.decoder().source().available();
+        match self.reader.pending_read() {
+            PendingRead::Nothing => true,
+            PendingRead::Finished => false,
+            PendingRead::ItemHeader => available.len() >= 8,
+            PendingRead::Value(len) => available.len() as u64 >= len,
+            PendingRead::Header { explicit_vr, root } => self
+                .header_data_length(available, explicit_vr, root)
+                .map(|len| available.len() >= len)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Determine the number of bytes needed for the reader
+    /// to produce a token from the element header at the start of `data`,
+    /// including any elements at the root of the data set
+    /// which the reader ignores before reaching it.
+    ///
+    /// Returns `None` if not enough data is available to tell.
+    fn header_data_length(&self, data: &[u8], explicit_vr: bool, root: bool) -> Option<usize> {
+        let mut offset = 0;
+        loop {
+            let header = data.get(offset..offset + 8)?;
+            let tag = Tag(self.read_u16(&header[0..2]), self.read_u16(&header[2..4]));
+            let long_header = explicit_vr
+                && tag.group() != 0xFFFE
+                && matches!(
+                    VR::from_binary([header[4], header[5]]).unwrap_or(VR::UN),
+                    VR::OB
+                        | VR::OD
+                        | VR::OF
+                        | VR::OL
+                        | VR::OW
+                        | VR::SQ
+                        | VR::UC
+                        | VR::UR
+                        | VR::UT
+                        | VR::UN
+                );
+            let header_len = if long_header { 12 } else { 8 };
+
+            if root && tag == Tag(0xFFFE, 0xE00D) {
+                // item delimiter outside of a sequence, ignored
+                offset += header_len;
+                continue;
+            }
+            if root && self.skip_padding && tag == Tag(0xFFFC, 0xFFFC) {
+                // trailing padding, skipped along with its value
+                let len = if long_header || !explicit_vr {
+                    let len = data.get(offset + header_len - 4..offset + header_len)?;
+                    self.read_u32(len)
+                } else {
+                    u32::from(self.read_u16(&header[6..8]))
+                };
+                if len != u32::MAX {
+                    offset += header_len + len as usize;
+                    continue;
+                }
+            }
+            return Some(offset + header_len);
+        }
+    }
+
+    fn read_u16(&self, bytes: &[u8]) -> u16 {
+        let bytes = [bytes[0], bytes[1]];
+        match self.endianness {
+            Endianness::Little => u16::from_le_bytes(bytes),
+            Endianness::Big => u16::from_be_bytes(bytes),
+        }
+    }
+
+    fn read_u32(&self, bytes: &[u8]) -> u32 {
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        match self.endianness {
+            Endianness::Little => u32::from_le_bytes(bytes),
+            Endianness::Big => u32::from_be_bytes(bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PushDataSetReader;
+    use crate::dataset::read::{DataSetReaderOptions, TrailingPaddingStrategy};
+    use crate::dataset::{DataSetReader, DataToken};
+    use dicom_encoding::text::SpecificCharacterSet;
+    use dicom_encoding::transfer_syntax::{
+        AdapterFreeTransferSyntax, Codec, Endianness, TransferSyntax,
+    };
+
+    fn explicit_vr_le() -> TransferSyntax {
+        AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2.1",
+            "Explicit VR Little Endian",
+            Endianness::Little,
+            true,
+            Codec::None,
+        )
+        .erased()
+    }
+
+    fn implicit_vr_le() -> TransferSyntax {
+        AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2",
+            "Implicit VR Little Endian",
+            Endianness::Little,
+            false,
+            Codec::None,
+        )
+        .erased()
+    }
+
+    /// Check that feeding the data in chunks of any size
+    /// produces the same tokens as the data set reader.
+    fn validate_push_reader(data: &[u8], ts: &TransferSyntax, options: DataSetReaderOptions) {
+        let expected: Vec<DataToken> =
+            DataSetReader::new_with_ts_cs_options(data, ts, SpecificCharacterSet::Default, options)
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+        for chunk_size in 1..=data.len() {
+            let mut reader = PushDataSetReader::new_with_ts_cs_options(
+                ts,
+                SpecificCharacterSet::Default,
+                options,
+            )
+            .unwrap();
+            let mut tokens = Vec::new();
+            for chunk in data.chunks(chunk_size) {
+                tokens.extend(reader.feed(chunk).unwrap());
+            }
+            tokens.extend(reader.finish().unwrap());
+            assert_eq!(tokens, expected, "mismatch with chunks of {}", chunk_size);
+            assert!(reader.is_finished());
+            assert!(!reader.needs_more());
+        }
+    }
+
+    #[test]
+    fn push_sequence_explicit() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // SequenceStart: (0008,2218) ; len = 54
+            0x08, 0x00, 0x18, 0x22, b'S', b'Q', 0x00, 0x00, 0x36, 0x00, 0x00, 0x00,
+            // ItemStart: len = 46
+            0xfe, 0xff, 0x00, 0xe0, 0x2e, 0x00, 0x00, 0x00,
+            // (0008,0100) CodeValue; len = 8
+            0x08, 0x00, 0x00, 0x01, b'S', b'H', 0x08, 0x00,
+            0x54, 0x2d, 0x44, 0x31, 0x32, 0x31, 0x33, b' ',
+            // (0008,0102) CodingSchemeDesignator; len = 4
+            0x08, 0x00, 0x02, 0x01, b'S', b'H', 0x04, 0x00,
+            0x53, 0x52, 0x54, b' ',
+            // (0008,0104) CodeMeaning; len = 10
+            0x08, 0x00, 0x04, 0x01, b'L', b'O', 0x0a, 0x00,
+            0x4a, 0x61, 0x77, b' ', 0x72, 0x65, 0x67, 0x69, 0x6f, 0x6e,
+            // SequenceStart: (0040,0555) AcquisitionContextSequence; len = 0
+            0x40, 0x00, 0x55, 0x05, b'S', b'Q', 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            // (2050,0020) PresentationLUTShape; len = 8
+            0x50, 0x20, 0x20, 0x00, b'C', b'S', 0x08, 0x00,
+            b'I', b'D', b'E', b'N', b'T', b'I', b'T', b'Y',
+        ];
+
+        validate_push_reader(DATA, &explicit_vr_le(), Default::default());
+    }
+
+    #[test]
+    fn push_sequence_implicit() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0018,6011) SequenceOfUltrasoundRegions; len = undefined
+            0x18, 0x00, 0x11, 0x60, 0xff, 0xff, 0xff, 0xff,
+            // ItemStart: len = undefined
+            0xfe, 0xff, 0x00, 0xe0, 0xff, 0xff, 0xff, 0xff,
+            // (0018,6012) RegionSpatialFormat; len = 2
+            0x18, 0x00, 0x12, 0x60, 0x02, 0x00, 0x00, 0x00,
+            0x01, 0x00,
+            // ItemEnd
+            0xfe, 0xff, 0x0d, 0xe0, 0x00, 0x00, 0x00, 0x00,
+            // SequenceEnd
+            0xfe, 0xff, 0xdd, 0xe0, 0x00, 0x00, 0x00, 0x00,
+            // (0020,4000) ImageComments; len = 4
+            0x20, 0x00, 0x00, 0x40, 0x04, 0x00, 0x00, 0x00,
+            b'T', b'E', b'S', b'T',
+        ];
+
+        validate_push_reader(DATA, &implicit_vr_le(), Default::default());
+    }
+
+    #[test]
+    fn push_encapsulated_pixeldata_with_padding() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (7FE0,0010) PixelData; OB; len = undefined
+            0xe0, 0x7f, 0x10, 0x00, b'O', b'B', 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+            // basic offset table; len = 4
+            0xfe, 0xff, 0x00, 0xe0, 0x04, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+            // fragment; len = 8
+            0xfe, 0xff, 0x00, 0xe0, 0x08, 0x00, 0x00, 0x00,
+            0x99, 0x99, 0x99, 0x99, 0x99, 0x99, 0x99, 0x99,
+            // SequenceEnd
+            0xfe, 0xff, 0xdd, 0xe0, 0x00, 0x00, 0x00, 0x00,
+            // (FFFC,FFFC) DataSetTrailingPadding; OB; len = 4
+            0xfc, 0xff, 0xfc, 0xff, b'O', b'B', 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+            0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let ts = explicit_vr_le();
+        validate_push_reader(DATA, &ts, Default::default());
+        validate_push_reader(
+            DATA,
+            &ts,
+            DataSetReaderOptions::default().trailing_padding(TrailingPaddingStrategy::Skip),
+        );
+    }
+
+    #[test]
+    fn push_needs_more() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0010,0010) PatientName; PN; len = 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+        ];
+
+        let mut reader = PushDataSetReader::new_with_ts(&explicit_vr_le()).unwrap();
+        assert!(reader.needs_more());
+
+        let tokens = reader.feed(&DATA[..6]).unwrap();
+        assert!(tokens.is_empty());
+        assert!(reader.needs_more());
+
+        // header complete, value still missing
+        let tokens = reader.feed(&DATA[6..12]).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(matches!(tokens[0], DataToken::ElementHeader(_)));
+        assert!(reader.needs_more());
+
+        let tokens = reader.feed(&DATA[12..]).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(matches!(tokens[0], DataToken::PrimitiveValue(_)));
+        assert!(reader.needs_more());
+        assert!(!reader.is_finished());
+
+        let tokens = reader.finish().unwrap();
+        assert!(tokens.is_empty());
+        assert!(reader.is_finished());
+    }
+
+    #[test]
+    fn push_finish_truncated_value() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0010,0010) PatientName; PN; len = 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e',
+        ];
+
+        let mut reader = PushDataSetReader::new_with_ts(&explicit_vr_le()).unwrap();
+        let tokens = reader.feed(DATA).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert!(reader.finish().is_err());
+        assert!(reader.is_finished());
+    }
+}
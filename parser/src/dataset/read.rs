@@ -5,16 +5,21 @@
 //! At this level, headers and values are treated as tokens which can be used
 //! to form a syntax tree of a full data set.
 use crate::stateful::decode::{DynStatefulDecoder, Error as DecoderError, StatefulDecode};
+use crate::ErrorKind;
+use bytes::Bytes;
+use dicom_core::dictionary::private::{PrivateCreators, PrivateDictionary};
+use dicom_core::dictionary::DataDictionary;
 use dicom_core::header::{DataElementHeader, Header, Length, SequenceItemHeader};
 use dicom_core::{PrimitiveValue, Tag, VR};
 use dicom_encoding::text::SpecificCharacterSet;
 use dicom_encoding::transfer_syntax::TransferSyntax;
 use snafu::{Backtrace, ResultExt, Snafu};
 use std::cmp::Ordering;
-use std::io::Read;
+use std::io::{Cursor, Read};
 use std::iter::Iterator;
+use std::sync::Arc;
 
-use super::{DataToken, SeqTokenType};
+use super::{DataToken, PositionedToken, SeqTokenType};
 
 fn is_stateful_decode<T>(_: &T)
 where
@@ -65,8 +70,122 @@ pub enum Error {
     },
     #[snafu(display("Unexpected item tag {} while reading element header", tag))]
     UnexpectedItemTag { tag: Tag, backtrace: Backtrace },
+    #[snafu(display(
+        "Element tagged {} has odd length {} in position {}",
+        tag,
+        len,
+        position
+    ))]
+    OddLength {
+        tag: Tag,
+        len: u32,
+        position: u64,
+        backtrace: Backtrace,
+    },
+    #[snafu(display(
+        "Element tagged {} in position {} has length {}, but only {} bytes are available",
+        tag,
+        position,
+        len,
+        available
+    ))]
+    ExcessiveLength {
+        tag: Tag,
+        len: u32,
+        available: u32,
+        position: u64,
+        backtrace: Backtrace,
+    },
     /// Undefined pixel item length
     UndefinedItemLength,
+    #[snafu(display(
+        "Element tagged {} in position {} has length {}, above the maximum of {}",
+        tag,
+        position,
+        len,
+        max
+    ))]
+    ValueLengthLimit {
+        tag: Tag,
+        len: u32,
+        max: u32,
+        position: u64,
+        backtrace: Backtrace,
+    },
+    #[snafu(display(
+        "Element tagged {} in position {} has length {}, which would exceed the allocation budget of {} bytes",
+        tag,
+        position,
+        len,
+        budget
+    ))]
+    AllocationBudget {
+        tag: Tag,
+        len: u32,
+        budget: u64,
+        position: u64,
+        backtrace: Backtrace,
+    },
+}
+
+impl Error {
+    /// Classify this error,
+    /// so that truncated data can be told apart
+    /// from invalid data or a failing data source.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::CreateDecoder { source }
+            | Error::ReadItemHeader { source }
+            | Error::ReadHeader { source }
+            | Error::ReadValue { source, .. }
+            | Error::ReadItemValue { source, .. } => source.kind(),
+            Error::UnexpectedItemTag { .. } => ErrorKind::InvalidHeader,
+            Error::ExcessiveLength { .. } => ErrorKind::Truncated,
+            Error::InconsistentSequenceEnd { .. }
+            | Error::OddLength { .. }
+            | Error::UndefinedItemLength => ErrorKind::InvalidStructure,
+            Error::ValueLengthLimit { .. } | Error::AllocationBudget { .. } => {
+                ErrorKind::LimitExceeded
+            }
+        }
+    }
+
+    /// Retrieve the position in the source at which the error occurred,
+    /// if known.
+    pub fn position(&self) -> Option<u64> {
+        match self {
+            Error::CreateDecoder { source }
+            | Error::ReadItemHeader { source }
+            | Error::ReadHeader { source }
+            | Error::ReadValue { source, .. }
+            | Error::ReadItemValue { source, .. } => source.position(),
+            Error::InconsistentSequenceEnd { bytes_read, .. } => Some(*bytes_read),
+            Error::OddLength { position, .. }
+            | Error::ExcessiveLength { position, .. }
+            | Error::ValueLengthLimit { position, .. }
+            | Error::AllocationBudget { position, .. } => Some(*position),
+            Error::UnexpectedItemTag { .. } | Error::UndefinedItemLength => None,
+        }
+    }
+
+    /// Retrieve the tag of the data element being read
+    /// when the error occurred,
+    /// if known.
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            Error::ReadValue { tag, .. }
+            | Error::UnexpectedItemTag { tag, .. }
+            | Error::OddLength { tag, .. }
+            | Error::ExcessiveLength { tag, .. }
+            | Error::ValueLengthLimit { tag, .. }
+            | Error::AllocationBudget { tag, .. } => Some(*tag),
+            Error::CreateDecoder { source }
+            | Error::ReadItemHeader { source }
+            | Error::ReadHeader { source }
+            | Error::ReadItemValue { source, .. } => source.tag(),
+            Error::InconsistentSequenceEnd { .. } | Error::UndefinedItemLength => None,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -84,6 +203,9 @@ struct SeqToken {
     /// The number of bytes the parser has read until it reached the
     /// beginning of the sequence or item value data.
     base_offset: u64,
+    /// Whether this is a sequence encoded as UN with undefined length,
+    /// whose contents are in implicit VR little endian (CP-246).
+    implicit_vr: bool,
 }
 
 /// The value reading strategy for the data set reader.
@@ -126,6 +248,303 @@ impl Default for ValueReadStrategy {
     }
 }
 
+/// The strategy for handling the data set trailing padding element
+/// (FFFC,FFFC).
+///
+/// As per the standard (PS3.10 section 7.2),
+/// this element has no meaning and shall be ignored
+/// by the receiving application.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum TrailingPaddingStrategy {
+    /// The trailing padding element is reported like any other element,
+    /// with its header and value tokens.
+    Expose,
+    /// The trailing padding element is skipped over entirely,
+    /// producing no tokens.
+    Skip,
+}
+
+impl Default for TrailingPaddingStrategy {
+    fn default() -> Self {
+        TrailingPaddingStrategy::Expose
+    }
+}
+
+/// The parsing mode of the data set reader,
+/// which determines how violations of the standard are handled.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum ParseMode {
+    /// Violations which prevent the data set from being read
+    /// are reported as errors, ending the reading process.
+    Strict,
+    /// The reader recovers from common violations
+    /// found in real-world files,
+    /// reporting them as [issues](ParseIssue) instead.
+    ///
+    /// In particular:
+    ///
+    /// - a value cut short by the end of the data
+    ///   is read as an empty value;
+    /// - sequences and items still open at the end of the data,
+    ///   or when the data can no longer be read,
+    ///   are closed with the respective delimiter tokens;
+    /// - sequences and items which go beyond their explicit length
+    ///   are closed right away.
+    Lenient,
+}
+
+impl Default for ParseMode {
+    fn default() -> Self {
+        ParseMode::Strict
+    }
+}
+
+/// The strategy for handling an element value
+/// whose declared length is inconsistent with the encoded data,
+/// either because it is odd
+/// or because it goes beyond the data available to it.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum ValueLengthStrategy {
+    /// The element is rejected, ending the reading process with an error.
+    Fail,
+    /// The value is truncated:
+    /// an odd length value loses its last byte,
+    /// and a value going beyond the available data
+    /// is read only up to the end of that data.
+    Truncate,
+    /// The value is padded:
+    /// an odd length value gains a trailing padding byte,
+    /// and a value going beyond the available data
+    /// is read up to the end of that data
+    /// and then padded to its declared length.
+    Pad,
+}
+
+impl ValueLengthStrategy {
+    /// Describe the action taken on a value by this strategy.
+    fn action(self) -> &'static str {
+        match self {
+            ValueLengthStrategy::Fail => "rejected",
+            ValueLengthStrategy::Truncate => "truncated",
+            ValueLengthStrategy::Pad => "padded",
+        }
+    }
+}
+
+/// A non-fatal anomaly found by the data set reader,
+/// such as a data quality issue in the encoded data set.
+///
+/// Issues are logged through `tracing`,
+/// reported to the callback installed with
+/// [`with_issue_callback`](DataSetReader::with_issue_callback),
+/// and gathered if [`collect_issues`](DataSetReader::collect_issues) was called.
+/// Each issue records the byte offset in the source where it was found.
+/// Some of them are only reported in [lenient mode](ParseMode::Lenient),
+/// where the reader recovers from the respective violation.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum ParseIssue {
+    /// An item delimiter was found outside of a sequence,
+    /// and was ignored.
+    ItemDelimiterOutsideSequence { position: u64 },
+    /// An element which is not a sequence has an undefined length,
+    /// and was read as a sequence.
+    UndefinedLength { tag: Tag, vr: VR, position: u64 },
+    /// An element has an odd value length.
+    OddLength {
+        tag: Tag,
+        vr: VR,
+        len: u32,
+        position: u64,
+    },
+    /// The value of an element was cut short by the end of the data,
+    /// and was replaced with an empty value.
+    TruncatedValue { tag: Tag, len: u32, position: u64 },
+    /// The data ended while sequences or items were still open,
+    /// which were closed.
+    UnexpectedEndOfData { position: u64 },
+    /// The remaining data could not be read as data elements,
+    /// and was ignored.
+    UnreadableData { position: u64 },
+    /// A sequence or item went beyond its explicit length,
+    /// and was closed.
+    InconsistentSequenceEnd {
+        end_of_sequence: u64,
+        bytes_read: u64,
+    },
+    /// A value is longer than the maximum length of its value representation.
+    ValueTooLong {
+        tag: Tag,
+        vr: VR,
+        len: usize,
+        max: usize,
+        position: u64,
+    },
+    /// A textual value is padded with the wrong character
+    /// (a null character instead of a space, or vice versa for UIDs).
+    InvalidPadding { tag: Tag, vr: VR, position: u64 },
+    /// An element has an odd value length,
+    /// and its value was handled according to the
+    /// [odd length strategy](DataSetReaderOptions::odd_length).
+    OddLengthAdjusted {
+        tag: Tag,
+        vr: VR,
+        len: u32,
+        strategy: ValueLengthStrategy,
+        position: u64,
+    },
+    /// The value length of an element goes beyond
+    /// the end of its enclosing item or sequence, or the end of the data,
+    /// and its value was handled according to the
+    /// [excessive length strategy](DataSetReaderOptions::excessive_length).
+    ExcessiveLength {
+        tag: Tag,
+        vr: VR,
+        len: u32,
+        available: u32,
+        strategy: ValueLengthStrategy,
+        position: u64,
+    },
+}
+
+impl ParseIssue {
+    /// Obtain the byte offset in the source where the issue was found.
+    pub fn position(&self) -> u64 {
+        match self {
+            ParseIssue::ItemDelimiterOutsideSequence { position }
+            | ParseIssue::UndefinedLength { position, .. }
+            | ParseIssue::OddLength { position, .. }
+            | ParseIssue::TruncatedValue { position, .. }
+            | ParseIssue::UnexpectedEndOfData { position }
+            | ParseIssue::UnreadableData { position }
+            | ParseIssue::ValueTooLong { position, .. }
+            | ParseIssue::InvalidPadding { position, .. }
+            | ParseIssue::OddLengthAdjusted { position, .. }
+            | ParseIssue::ExcessiveLength { position, .. } => *position,
+            ParseIssue::InconsistentSequenceEnd { bytes_read, .. } => *bytes_read,
+        }
+    }
+
+    /// Obtain the tag of the data element affected by the issue, if any.
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            ParseIssue::UndefinedLength { tag, .. }
+            | ParseIssue::OddLength { tag, .. }
+            | ParseIssue::TruncatedValue { tag, .. }
+            | ParseIssue::ValueTooLong { tag, .. }
+            | ParseIssue::InvalidPadding { tag, .. }
+            | ParseIssue::OddLengthAdjusted { tag, .. }
+            | ParseIssue::ExcessiveLength { tag, .. } => Some(*tag),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ParseIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseIssue::ItemDelimiterOutsideSequence { position } => write!(
+                f,
+                "Item delimitation item outside of a sequence in position {}",
+                position
+            ),
+            ParseIssue::UndefinedLength { tag, vr, position } => write!(
+                f,
+                "Element tagged {} ({}) with undefined length in position {}, read as a sequence",
+                tag, vr, position
+            ),
+            ParseIssue::OddLength {
+                tag,
+                vr,
+                len,
+                position,
+            } => write!(
+                f,
+                "Element tagged {} ({}) has odd length {} in position {}",
+                tag, vr, len, position
+            ),
+            ParseIssue::TruncatedValue { tag, len, position } => write!(
+                f,
+                "Value of element tagged {} ({} bytes) truncated in position {}",
+                tag, len, position
+            ),
+            ParseIssue::UnexpectedEndOfData { position } => write!(
+                f,
+                "Unexpected end of data in position {} inside a sequence",
+                position
+            ),
+            ParseIssue::UnreadableData { position } => {
+                write!(f, "Unreadable data in position {} was ignored", position)
+            }
+            ParseIssue::InconsistentSequenceEnd {
+                end_of_sequence,
+                bytes_read,
+            } => write!(
+                f,
+                "Inconsistent sequence end: expected end at {} bytes but read {}",
+                end_of_sequence, bytes_read
+            ),
+            ParseIssue::ValueTooLong {
+                tag,
+                vr,
+                len,
+                max,
+                position,
+            } => write!(
+                f,
+                "Value of element tagged {} ({}) in position {} has length {}, maximum is {}",
+                tag, vr, position, len, max
+            ),
+            ParseIssue::InvalidPadding { tag, vr, position } => write!(
+                f,
+                "Value of element tagged {} ({}) in position {} has invalid padding",
+                tag, vr, position
+            ),
+            ParseIssue::OddLengthAdjusted {
+                tag,
+                vr,
+                len,
+                strategy,
+                position,
+            } => write!(
+                f,
+                "Element tagged {} ({}) has odd length {} in position {}, value {}",
+                tag,
+                vr,
+                len,
+                position,
+                strategy.action()
+            ),
+            ParseIssue::ExcessiveLength {
+                tag,
+                vr,
+                len,
+                available,
+                strategy,
+                position,
+            } => write!(
+                f,
+                "Element tagged {} ({}) in position {} has length {}, but only {} bytes are available, value {}",
+                tag,
+                vr,
+                position,
+                len,
+                available,
+                strategy.action()
+            ),
+        }
+    }
+}
+
+/// A callback receiving the issues found by a data set reader.
+struct IssueCallback(Box<dyn FnMut(&ParseIssue) + Send>);
+
+impl std::fmt::Debug for IssueCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("IssueCallback")
+    }
+}
+
 /// The set of options for the data set reader.
 #[derive(Debug, Default, Copy, Clone, Eq, Hash, PartialEq)]
 #[non_exhaustive]
@@ -134,6 +553,28 @@ pub struct DataSetReaderOptions {
     pub value_read: ValueReadStrategy,
     /// the position of the reader as received at building time
     pub base_offset: u64,
+    /// the strategy for handling data set trailing padding
+    pub trailing_padding: TrailingPaddingStrategy,
+    /// the parsing mode
+    pub parse_mode: ParseMode,
+    /// the tag at which to stop reading the root data set, if any
+    pub read_until: Option<Tag>,
+    /// the strategy for handling element values with an odd length,
+    /// if other than reading them as declared
+    pub odd_length: Option<ValueLengthStrategy>,
+    /// the strategy for handling element values which go beyond
+    /// the end of their enclosing item or sequence, or the end of the data,
+    /// if other than reading them as declared
+    pub excessive_length: Option<ValueLengthStrategy>,
+    /// the position at which the data ends, if known
+    pub data_end: Option<u64>,
+    /// the maximum length of a single element value or item, if any
+    pub max_value_length: Option<u32>,
+    /// the maximum number of bytes to read into
+    /// element values and items in total, if any
+    pub allocation_budget: Option<u64>,
+    /// whether to remove the trailing padding of textual values
+    pub trim_padding: bool,
 }
 
 impl DataSetReaderOptions {
@@ -147,6 +588,120 @@ impl DataSetReaderOptions {
         self.base_offset = base_offset;
         self
     }
+    /// Replace the trailing padding strategy of the options.
+    pub fn trailing_padding(mut self, trailing_padding: TrailingPaddingStrategy) -> Self {
+        self.trailing_padding = trailing_padding;
+        self
+    }
+    /// Replace the parsing mode of the options.
+    pub fn parse_mode(mut self, parse_mode: ParseMode) -> Self {
+        self.parse_mode = parse_mode;
+        self
+    }
+    /// Set the parsing mode of the options to [lenient](ParseMode::Lenient).
+    pub fn lenient(self) -> Self {
+        self.parse_mode(ParseMode::Lenient)
+    }
+    /// Set the reader to stop once the given tag is found.
+    ///
+    /// The reading process ends immediately after the header of this tag,
+    /// or any other tag that is next in the standard DICOM tag ordering,
+    /// is read in the root data set,
+    /// without reading its value.
+    /// An element with the exact tag will be excluded from the output.
+    /// This is commonly used to stop right before _Pixel Data_
+    /// in metadata-only reads.
+    pub fn read_until(mut self, tag: Tag) -> Self {
+        self.read_until = Some(tag);
+        self
+    }
+    /// Set how to handle element values with an odd length.
+    ///
+    /// By default, such values are read as declared
+    /// and reported as an [issue](ParseIssue::OddLength).
+    pub fn odd_length(mut self, strategy: ValueLengthStrategy) -> Self {
+        self.odd_length = Some(strategy);
+        self
+    }
+    /// Set how to handle element values whose length goes beyond
+    /// the end of their enclosing item or sequence with explicit length,
+    /// or beyond the end of the data.
+    ///
+    /// By default, such values are read as declared,
+    /// which fails in [strict mode](ParseMode::Strict).
+    pub fn excessive_length(mut self, strategy: ValueLengthStrategy) -> Self {
+        self.excessive_length = Some(strategy);
+        self
+    }
+    /// Declare the position at which the data ends,
+    /// such as the size of the file being read.
+    ///
+    /// This allows the reader to detect values going beyond the end of the data
+    /// before reading them.
+    /// Otherwise, they are only detected once the data ends prematurely,
+    /// at which point a [truncated](ValueLengthStrategy::Truncate)
+    /// or [padded](ValueLengthStrategy::Pad) value can no longer be recovered
+    /// and is read as an empty value.
+    pub fn data_end(mut self, position: u64) -> Self {
+        self.data_end = Some(position);
+        self
+    }
+    /// Set the maximum length of a single element value
+    /// or pixel data item to read.
+    ///
+    /// Values declaring a longer length fail with an error
+    /// before any memory is allocated for them,
+    /// so that a malformed length field cannot make the reader
+    /// allocate an arbitrary amount of memory.
+    /// This is recommended when reading untrusted data.
+    ///
+    /// By default, there is no maximum length.
+    pub fn max_value_length(mut self, max: u32) -> Self {
+        self.max_value_length = Some(max);
+        self
+    }
+    /// Set the maximum number of bytes to read
+    /// into element values and pixel data items
+    /// over the whole data set.
+    ///
+    /// Once a value would go beyond this budget,
+    /// the reader fails with an error before allocating memory for it.
+    ///
+    /// By default, there is no budget.
+    pub fn allocation_budget(mut self, budget: u64) -> Self {
+        self.allocation_budget = Some(budget);
+        self
+    }
+    /// Set whether to remove the trailing spaces and null characters
+    /// of textual values as they are read.
+    ///
+    /// Devices differ in how they pad their values,
+    /// so trimming makes values compare equal regardless of their padding.
+    /// The padding is added back when the values are encoded.
+    ///
+    /// By default, textual values are read as they were encoded.
+    pub fn trim_padding(mut self, trim: bool) -> Self {
+        self.trim_padding = trim;
+        self
+    }
+}
+
+/// The input which a data set reader requires
+/// in order to produce its next token.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub(crate) enum PendingRead {
+    /// no further input is needed
+    Nothing,
+    /// an item or delimiter header of 8 bytes is expected
+    ItemHeader,
+    /// a data element header is expected,
+    /// in explicit VR if `explicit_vr` is true,
+    /// in the root data set if `root` is true
+    Header { explicit_vr: bool, root: bool },
+    /// a value with the given number of bytes is expected
+    Value(u64),
+    /// the reader has reached the end of the data set
+    Finished,
 }
 
 /// A higher-level reader for retrieving structure in a DICOM data set from an
@@ -170,6 +725,37 @@ pub struct DataSetReader<S> {
     hard_break: bool,
     /// last decoded header
     last_header: Option<DataElementHeader>,
+    /// the private dictionary for resolving private attributes, if any
+    private_dict: Option<Arc<PrivateDictionary>>,
+    /// a stack of the private creators declared in each data set level
+    private_creators: Vec<PrivateCreators>,
+    /// the tag of the private creator element whose value is expected next
+    pending_creator: Option<Tag>,
+    /// whether the data set is encoded with implicit VR,
+    /// in which case value representations are resolved from context
+    implicit_vr: bool,
+    /// a stack of the pixel representations declared in each data set level
+    pixel_representations: Vec<Option<u16>>,
+    /// whether the value of Pixel Representation is expected next
+    pending_pixel_representation: bool,
+    /// a stack of the character sets in effect
+    /// before each of the items currently open
+    character_sets: Vec<SpecificCharacterSet>,
+    /// whether the decoder is reading the contents
+    /// of a sequence encoded as UN in implicit VR little endian
+    in_implicit_vr_sequence: bool,
+    /// the callback for reporting issues, if any
+    issue_callback: Option<IssueCallback>,
+    /// the issues gathered so far, if collecting them
+    issues: Option<Vec<ParseIssue>>,
+    /// the position where the data of the token being read starts
+    token_start: u64,
+    /// whether the reader is closing all open sequences and items
+    /// before ending the iteration process (lenient mode)
+    closing: bool,
+    /// the number of bytes read into values so far,
+    /// as counted against the allocation budget
+    bytes_allocated: u64,
 }
 
 impl<R> DataSetReader<DynStatefulDecoder<R>> {
@@ -217,8 +803,86 @@ impl<R> DataSetReader<DynStatefulDecoder<R>> {
             in_sequence: false,
             hard_break: false,
             last_header: None,
+            private_dict: None,
+            private_creators: vec![PrivateCreators::new()],
+            pending_creator: None,
+            implicit_vr: !ts.explicit_vr(),
+            pixel_representations: vec![None],
+            pending_pixel_representation: false,
+            character_sets: Vec::new(),
+            in_implicit_vr_sequence: false,
+            issue_callback: None,
+            issues: None,
+            token_start: 0,
+            closing: false,
+            bytes_allocated: 0,
         })
     }
+
+    /// Create a new iterator with the given source,
+    /// transfer syntax, specific character set, and options,
+    /// which resolves the value representations of attributes
+    /// in implicit VR transfer syntaxes through the given data dictionary.
+    ///
+    /// This enables the use of dictionaries loaded at run-time,
+    /// such as [`RuntimeDataDictionary`].
+    ///
+    /// [`RuntimeDataDictionary`]: dicom_core::dictionary::runtime::RuntimeDataDictionary
+    pub fn new_with_dictionary<D>(
+        source: R,
+        dict: D,
+        ts: &TransferSyntax,
+        cs: SpecificCharacterSet,
+        options: DataSetReaderOptions,
+    ) -> Result<Self>
+    where
+        R: Read,
+        D: DataDictionary + Send + 'static,
+    {
+        let parser = DynStatefulDecoder::new_with_dict(source, dict, ts, cs, 0)
+            .context(CreateDecoderSnafu)?;
+
+        is_stateful_decode(&parser);
+
+        let reader = DataSetReader::new(parser, options);
+        if ts.explicit_vr() {
+            Ok(reader)
+        } else {
+            Ok(reader.with_implicit_vr())
+        }
+    }
+}
+
+impl DataSetReader<DynStatefulDecoder<Cursor<Bytes>>> {
+    /// Create a new iterator over the data set in the given in-memory buffer,
+    /// with the given transfer syntax, specific character set, and options.
+    ///
+    /// Binary values are obtained as slices of `data` without copying,
+    /// as described in [`StatefulDecoder::new_shared`](crate::StatefulDecoder::new_shared).
+    /// Unless set in the options,
+    /// the [end of the data](DataSetReaderOptions::data_end)
+    /// is the end of the buffer.
+    pub fn new_shared_with_ts_cs_options(
+        data: Bytes,
+        ts: &TransferSyntax,
+        cs: SpecificCharacterSet,
+        mut options: DataSetReaderOptions,
+    ) -> Result<Self> {
+        if options.data_end.is_none() {
+            options.data_end = Some(data.len() as u64);
+        }
+        let parser = DynStatefulDecoder::<Cursor<Bytes>>::new_shared_with(data, ts, cs, 0)
+            .context(CreateDecoderSnafu)?;
+
+        is_stateful_decode(&parser);
+
+        let reader = DataSetReader::new(parser, options);
+        if ts.explicit_vr() {
+            Ok(reader)
+        } else {
+            Ok(reader.with_implicit_vr())
+        }
+    }
 }
 
 impl<S> DataSetReader<S> {
@@ -233,8 +897,138 @@ impl<S> DataSetReader<S> {
             in_sequence: false,
             hard_break: false,
             last_header: None,
+            private_dict: None,
+            private_creators: vec![PrivateCreators::new()],
+            pending_creator: None,
+            implicit_vr: false,
+            pixel_representations: vec![None],
+            pending_pixel_representation: false,
+            character_sets: Vec::new(),
+            in_implicit_vr_sequence: false,
+            issue_callback: None,
+            issues: None,
+            token_start: 0,
+            closing: false,
+            bytes_allocated: 0,
         }
     }
+
+    /// Use the given private dictionary
+    /// to resolve the value representation of private attributes
+    /// which are read with an unknown VR (UN),
+    /// such as those in data sets with implicit VR.
+    ///
+    /// Private attributes are resolved according to
+    /// the private creator elements found in the same data set.
+    pub fn with_private_dictionary(mut self, dict: Arc<PrivateDictionary>) -> Self {
+        self.private_dict = Some(dict);
+        self
+    }
+
+    /// Declare that the data set is encoded with implicit VR,
+    /// so that the value representations of attributes
+    /// which the decoder could not determine on its own
+    /// are resolved from the context of the data set:
+    ///
+    /// - group length attributes are read as UL;
+    /// - private creator elements are read as LO;
+    /// - attributes which are either US or SS
+    ///   according to the standard,
+    ///   such as _Smallest Image Pixel Value_,
+    ///   are read as SS if the data set declares
+    ///   a signed _Pixel Representation_.
+    ///
+    /// This is done automatically
+    /// when the reader is created with a transfer syntax.
+    pub fn with_implicit_vr(mut self) -> Self {
+        self.implicit_vr = true;
+        self
+    }
+
+    /// Report the [issues](ParseIssue) found while reading
+    /// to the given callback.
+    pub fn with_issue_callback<F>(mut self, callback: F) -> Self
+    where
+        F: FnMut(&ParseIssue) + Send + 'static,
+    {
+        self.issue_callback = Some(IssueCallback(Box::new(callback)));
+        self
+    }
+
+    /// Gather the [issues](ParseIssue) found while reading,
+    /// so that they can be retrieved with [`take_issues`](Self::take_issues).
+    pub fn collect_issues(mut self) -> Self {
+        self.issues = Some(Vec::new());
+        self
+    }
+
+    /// Retrieve the issues gathered so far,
+    /// leaving the reader's collection empty.
+    ///
+    /// Always returns an empty vector
+    /// if the reader is not [collecting issues](Self::collect_issues).
+    pub fn take_issues(&mut self) -> Vec<ParseIssue> {
+        self.issues.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// Retrieve a reference to the underlying stateful decoder.
+    pub(crate) fn decoder(&self) -> &S {
+        &self.parser
+    }
+
+    /// Retrieve a mutable reference to the underlying stateful decoder.
+    pub(crate) fn decoder_mut(&mut self) -> &mut S {
+        &mut self.parser
+    }
+
+    fn report(&mut self, issue: ParseIssue) {
+        tracing::warn!("{}", issue);
+        if let Some(IssueCallback(callback)) = &mut self.issue_callback {
+            callback(&issue);
+        }
+        if let Some(issues) = &mut self.issues {
+            issues.push(issue);
+        }
+    }
+
+    /// Check a value which was just read
+    /// for length and padding issues.
+    fn check_value(&mut self, header: &DataElementHeader, value: &PrimitiveValue, position: u64) {
+        let strings: &[String] = match value {
+            PrimitiveValue::Strs(strings) => strings,
+            PrimitiveValue::Str(string) => std::slice::from_ref(string),
+            _ => return,
+        };
+        if let Some(max) = max_value_length(header.vr) {
+            let len = strings.iter().map(|s| s.chars().count()).max().unwrap_or(0);
+            if len > max {
+                self.report(ParseIssue::ValueTooLong {
+                    tag: header.tag,
+                    vr: header.vr,
+                    len,
+                    max,
+                    position,
+                });
+            }
+        }
+        let invalid_padding = match (header.vr, strings.last()) {
+            (VR::UI, Some(s)) => s.ends_with(' '),
+            (_, Some(s)) => s.ends_with('\0'),
+            (_, None) => false,
+        };
+        if invalid_padding {
+            self.report(ParseIssue::InvalidPadding {
+                tag: header.tag,
+                vr: header.vr,
+                position,
+            });
+        }
+    }
+
+    #[inline]
+    fn is_lenient(&self) -> bool {
+        self.options.parse_mode == ParseMode::Lenient
+    }
 }
 
 impl<S> Iterator for DataSetReader<S>
@@ -244,6 +1038,46 @@ where
     type Item = Result<DataToken>;
 
     fn next(&mut self) -> Option<Self::Item> {
+        let mut token = self.next_token();
+        if let Some(Ok(token)) = &token {
+            self.scope_character_set(token);
+        }
+        if self.implicit_vr || self.in_implicit_vr_sequence || self.private_dict.is_some() {
+            if let Some(Ok(token)) = &mut token {
+                self.resolve_vr(token);
+            }
+        }
+        token
+    }
+}
+
+impl<S> DataSetReader<S>
+where
+    S: StatefulDecode,
+{
+    /// Retrieve the next token along with
+    /// the byte offset and length of its data in the source.
+    ///
+    /// The tokens are the same as those produced by
+    /// iterating over the reader.
+    pub fn next_positioned(&mut self) -> Option<Result<PositionedToken>> {
+        self.token_start = self.parser.position();
+        let token = Iterator::next(self)?;
+        let offset = self.token_start;
+        let len = self.parser.position() - offset;
+        Some(token.map(|token| PositionedToken { token, offset, len }))
+    }
+
+    /// Obtain an iterator over the remaining tokens,
+    /// along with the byte offset and length of their data in the source.
+    pub fn positioned(&mut self) -> impl Iterator<Item = Result<PositionedToken>> + '_ {
+        std::iter::from_fn(move || self.next_positioned())
+    }
+
+    fn next_token(&mut self) -> Option<Result<DataToken>> {
+        if self.closing {
+            return self.close_delimiter();
+        }
         if self.hard_break {
             return None;
         }
@@ -260,9 +1094,12 @@ where
             }
         }
 
+        self.update_implicit_vr();
+
         if self.in_sequence {
             // at sequence level, expecting item header
 
+            self.token_start = self.parser.position();
             match self.parser.decode_item_header() {
                 Ok(header) => {
                     match header {
@@ -299,6 +1136,10 @@ where
                         }
                     }
                 }
+                Err(e) if self.is_lenient() => {
+                    self.recover_from_header_error(&e);
+                    self.next_token()
+                }
                 Err(e) => {
                     self.hard_break = true;
                     Some(Err(e).context(ReadItemHeaderSnafu))
@@ -315,6 +1156,11 @@ where
                 Some(len) => len as usize,
                 None => return Some(UndefinedItemLengthSnafu.fail()),
             };
+            let position = self.parser.position();
+            if let Err(e) = self.check_allocation(Tag(0xFFFE, 0xE000), len as u32, position) {
+                self.hard_break = true;
+                return Some(Err(e));
+            }
 
             if self.offset_table_next {
                 // offset table
@@ -337,12 +1183,20 @@ where
 
                 // need to pop item delimiter on the next iteration
                 self.delimiter_check_pending = true;
-                Some(
-                    self.parser
-                        .read_to_vec(len as u32, &mut value)
-                        .map(|_| Ok(DataToken::ItemValue(value)))
-                        .unwrap_or_else(|e| Err(e).context(ReadItemValueSnafu { len: len as u32 })),
-                )
+                if let Err(e) = self.parser.read_to_vec(len as u32, &mut value) {
+                    return Some(Err(e).context(ReadItemValueSnafu { len: len as u32 }));
+                }
+                if value.len() < len && self.is_lenient() {
+                    // fragment cut short by the end of the data
+                    self.report(ParseIssue::TruncatedValue {
+                        tag: Tag(0xFFFE, 0xE000),
+                        len: len as u32,
+                        position,
+                    });
+                    self.delimiter_check_pending = false;
+                    self.closing = true;
+                }
+                Some(Ok(DataToken::ItemValue(value)))
             }
         } else if let Some(header) = self.last_header {
             if header.is_encapsulated_pixeldata() {
@@ -350,6 +1204,7 @@ where
                 self.last_header = None;
 
                 // encapsulated pixel data, expecting offset table
+                self.token_start = self.parser.position();
                 match self.parser.decode_item_header() {
                     Ok(header) => match header {
                         SequenceItemHeader::Item { len } => {
@@ -375,6 +1230,10 @@ where
                             Some(UnexpectedItemTagSnafu { tag: item.tag() }.fail())
                         }
                     },
+                    Err(e) if self.is_lenient() => {
+                        self.recover_from_header_error(&e);
+                        self.next_token()
+                    }
                     Err(e) => {
                         self.hard_break = true;
                         Some(Err(e).context(ReadItemHeaderSnafu))
@@ -382,8 +1241,31 @@ where
                 }
             } else {
                 // a plain element header was read, so a value is expected
-                let value = match self.read_value(&header) {
+                let position = self.parser.position();
+                let read_header = match self
+                    .fit_value_length(&header, position)
+                    .and_then(|h| self.check_allocation(h.tag, h.len.0, position).map(|_| h))
+                {
+                    Ok(h) => h,
+                    Err(e) => {
+                        self.hard_break = true;
+                        self.last_header = None;
+                        return Some(Err(e));
+                    }
+                };
+                let value = match self.read_value(&read_header) {
                     Ok(v) => v,
+                    Err(e) if self.recovers_from_truncation() && is_unexpected_eof(&e) => {
+                        // value cut short by the end of the data
+                        self.report(ParseIssue::TruncatedValue {
+                            tag: header.tag,
+                            len: header.len.0,
+                            position,
+                        });
+                        self.last_header = None;
+                        self.closing = true;
+                        return Some(Ok(DataToken::PrimitiveValue(PrimitiveValue::Empty)));
+                    }
                     Err(e) => {
                         self.hard_break = true;
                         self.last_header = None;
@@ -392,6 +1274,14 @@ where
                 };
 
                 self.last_header = None;
+                let value = match self.adjust_value(&header, &read_header, value, position) {
+                    Ok(v) => v,
+                    Err(e) => {
+                        self.hard_break = true;
+                        return Some(Err(e));
+                    }
+                };
+                self.check_value(&header, &value, position);
 
                 // sequences can end after this token
                 self.delimiter_check_pending = true;
@@ -400,7 +1290,20 @@ where
             }
         } else {
             // a data element header or item delimiter is expected
+            self.token_start = self.parser.position();
             match self.parser.decode_header() {
+                Ok(header)
+                    if self.seq_delimiters.is_empty()
+                        && self
+                            .options
+                            .read_until
+                            .map(|t| t <= header.tag)
+                            .unwrap_or(false) =>
+                {
+                    // stop reading if reached `read_until` tag
+                    self.hard_break = true;
+                    None
+                }
                 Ok(DataElementHeader {
                     tag,
                     vr: VR::SQ,
@@ -421,12 +1324,11 @@ where
                     ..
                 }) if self.seq_delimiters.is_empty() => {
                     // ignore delimiter, we are not in a sequence
-                    tracing::warn!(
-                        "Item delimitation item outside of a sequence in position {}",
-                        self.parser.position()
-                    );
+                    self.report(ParseIssue::ItemDelimiterOutsideSequence {
+                        position: self.parser.position(),
+                    });
                     // return a new token by calling the method again
-                    self.next()
+                    self.next_token()
                 }
                 Ok(DataElementHeader {
                     tag: Tag(0xFFFE, 0xE00D),
@@ -439,6 +1341,25 @@ where
                     self.delimiter_check_pending = true;
                     Some(Ok(DataToken::ItemEnd))
                 }
+                Ok(DataElementHeader {
+                    tag: Tag(0xFFFC, 0xFFFC),
+                    len,
+                    ..
+                }) if self.options.trailing_padding == TrailingPaddingStrategy::Skip
+                    && self.seq_delimiters.is_empty()
+                    && !len.is_undefined() =>
+                {
+                    // skip data set trailing padding altogether
+                    if let Err(e) = self.parser.skip_bytes(len.0) {
+                        self.hard_break = true;
+                        return Some(Err(e).context(ReadValueSnafu {
+                            len: len.0,
+                            tag: Tag(0xFFFC, 0xFFFC),
+                        }));
+                    }
+                    // return a new token by calling the method again
+                    self.next_token()
+                }
                 Ok(header) if header.is_encapsulated_pixeldata() => {
                     // encapsulated pixel data conditions:
                     // expect a sequence of pixel data fragments
@@ -447,10 +1368,34 @@ where
                     self.last_header = Some(header);
                     Some(Ok(DataToken::PixelSequenceStart))
                 }
+                Ok(DataElementHeader {
+                    tag,
+                    vr: VR::UN,
+                    len,
+                }) if len.is_undefined()
+                    && !self.implicit_vr
+                    && self.parser.set_implicit_vr(true) =>
+                {
+                    // sequence encoded as UN (CP-246):
+                    // its contents are in implicit VR little endian
+                    self.in_implicit_vr_sequence = true;
+                    self.in_sequence = true;
+                    self.push_sequence_token(SeqTokenType::Sequence, len, false);
+                    if let Some(token) = self.seq_delimiters.last_mut() {
+                        token.implicit_vr = true;
+                    }
+
+                    Some(Ok(DataToken::SequenceStart { tag, len }))
+                }
                 Ok(header) if header.len.is_undefined() => {
                     // treat other undefined length elements
                     // as data set sequences,
                     // discarding the VR in the process
+                    self.report(ParseIssue::UndefinedLength {
+                        tag: header.tag,
+                        vr: header.vr,
+                        position: self.parser.position(),
+                    });
                     self.in_sequence = true;
 
                     let DataElementHeader { tag, len, .. } = header;
@@ -459,10 +1404,43 @@ where
                     Some(Ok(DataToken::SequenceStart { tag, len }))
                 }
                 Ok(header) => {
+                    if header.len.0 % 2 != 0 {
+                        let position = self.parser.position();
+                        match self.options.odd_length {
+                            None => self.report(ParseIssue::OddLength {
+                                tag: header.tag,
+                                vr: header.vr,
+                                len: header.len.0,
+                                position,
+                            }),
+                            Some(ValueLengthStrategy::Fail) => {
+                                self.hard_break = true;
+                                return Some(
+                                    OddLengthSnafu {
+                                        tag: header.tag,
+                                        len: header.len.0,
+                                        position,
+                                    }
+                                    .fail(),
+                                );
+                            }
+                            Some(strategy) => self.report(ParseIssue::OddLengthAdjusted {
+                                tag: header.tag,
+                                vr: header.vr,
+                                len: header.len.0,
+                                strategy,
+                                position,
+                            }),
+                        }
+                    }
                     // save it for the next step
                     self.last_header = Some(header);
                     Some(Ok(DataToken::ElementHeader(header)))
                 }
+                Err(e) if self.is_lenient() && !self.seq_delimiters.is_empty() => {
+                    self.recover_from_header_error(&e);
+                    self.next_token()
+                }
                 Err(DecoderError::DecodeElementHeader {
                     source: dicom_encoding::decode::Error::ReadHeaderTag { source, .. },
                     ..
@@ -475,6 +1453,13 @@ where
                     self.hard_break = true;
                     None
                 }
+                Err(_) if self.is_lenient() => {
+                    self.report(ParseIssue::UnreadableData {
+                        position: self.parser.position(),
+                    });
+                    self.hard_break = true;
+                    None
+                }
                 Err(e) => {
                     self.hard_break = true;
                     Some(Err(e).context(ReadHeaderSnafu))
@@ -482,12 +1467,238 @@ where
             }
         }
     }
+
+    /// Report a failure to read an element or item header
+    /// and start closing all open sequences and items (lenient mode).
+    fn recover_from_header_error(&mut self, e: &DecoderError) {
+        let position = self.parser.position();
+        if is_unexpected_eof(e) {
+            self.report(ParseIssue::UnexpectedEndOfData { position });
+        } else {
+            self.report(ParseIssue::UnreadableData { position });
+        }
+        self.last_header = None;
+        self.closing = true;
+    }
+
+    /// Produce the end token of the innermost open sequence or item,
+    /// or end the iteration process if none is left (lenient mode).
+    fn close_delimiter(&mut self) -> Option<Result<DataToken>> {
+        match self.seq_delimiters.pop() {
+            Some(SeqToken {
+                typ: SeqTokenType::Item,
+                ..
+            }) => Some(Ok(DataToken::ItemEnd)),
+            Some(SeqToken {
+                typ: SeqTokenType::Sequence,
+                ..
+            }) => Some(Ok(DataToken::SequenceEnd)),
+            None => {
+                self.closing = false;
+                self.hard_break = true;
+                None
+            }
+        }
+    }
+}
+
+/// Obtain the maximum length of a single value
+/// in the given textual value representation, in characters.
+fn max_value_length(vr: VR) -> Option<usize> {
+    match vr {
+        VR::AE | VR::CS | VR::DS | VR::SH => Some(16),
+        VR::AS => Some(4),
+        VR::IS => Some(12),
+        VR::LO | VR::UI => Some(64),
+        VR::ST => Some(1024),
+        VR::LT => Some(10240),
+        _ => None,
+    }
+}
+
+/// Resize a value to the given length in bytes,
+/// discarding trailing data or appending padding as necessary.
+///
+/// Binary values are resized to the number of whole values
+/// which fit in the given length, padded with zeros.
+/// Textual values are only truncated,
+/// since trailing padding is not retained in decoded text.
+fn resize_value(value: PrimitiveValue, vr: VR, len: usize) -> PrimitiveValue {
+    match value {
+        PrimitiveValue::U8(mut v) => {
+            let padding = match vr {
+                VR::AE
+                | VR::AS
+                | VR::CS
+                | VR::DA
+                | VR::DS
+                | VR::DT
+                | VR::IS
+                | VR::LO
+                | VR::LT
+                | VR::PN
+                | VR::SH
+                | VR::ST
+                | VR::TM
+                | VR::UC
+                | VR::UR
+                | VR::UT => b' ',
+                _ => 0,
+            };
+            v.resize(len, padding);
+            PrimitiveValue::U8(v)
+        }
+        PrimitiveValue::SharedBytes(v) if v.len() >= len => {
+            PrimitiveValue::SharedBytes(v.slice(..len))
+        }
+        PrimitiveValue::SharedBytes(v) => resize_value(PrimitiveValue::from(&v[..]), vr, len),
+        PrimitiveValue::I16(mut v) => {
+            v.resize(len / 2, 0);
+            PrimitiveValue::I16(v)
+        }
+        PrimitiveValue::U16(mut v) => {
+            v.resize(len / 2, 0);
+            PrimitiveValue::U16(v)
+        }
+        PrimitiveValue::I32(mut v) => {
+            v.resize(len / 4, 0);
+            PrimitiveValue::I32(v)
+        }
+        PrimitiveValue::U32(mut v) => {
+            v.resize(len / 4, 0);
+            PrimitiveValue::U32(v)
+        }
+        PrimitiveValue::F32(mut v) => {
+            v.resize(len / 4, 0.);
+            PrimitiveValue::F32(v)
+        }
+        PrimitiveValue::I64(mut v) => {
+            v.resize(len / 8, 0);
+            PrimitiveValue::I64(v)
+        }
+        PrimitiveValue::U64(mut v) => {
+            v.resize(len / 8, 0);
+            PrimitiveValue::U64(v)
+        }
+        PrimitiveValue::F64(mut v) => {
+            v.resize(len / 8, 0.);
+            PrimitiveValue::F64(v)
+        }
+        PrimitiveValue::Str(s) if s.len() > len => {
+            PrimitiveValue::Str(truncate_text(&s, len).to_string())
+        }
+        PrimitiveValue::Strs(v) if v.iter().map(|s| s.len() + 1).sum::<usize>() > len + 1 => {
+            let text = v.join("\\");
+            PrimitiveValue::Strs(
+                truncate_text(&text, len)
+                    .split('\\')
+                    .map(str::to_string)
+                    .collect(),
+            )
+        }
+        value => value,
+    }
+}
+
+/// Truncate the given text to at most `len` bytes,
+/// without splitting a character.
+fn truncate_text(text: &str, mut len: usize) -> &str {
+    while !text.is_char_boundary(len) {
+        len -= 1;
+    }
+    &text[..len]
+}
+
+/// Check whether the attribute is defined as either US or SS,
+/// depending on the Pixel Representation of the data set.
+fn is_us_or_ss(tag: Tag) -> bool {
+    matches!(
+        tag,
+        Tag(0x0028, 0x0106)
+            | Tag(0x0028, 0x0107)
+            | Tag(0x0028, 0x0108)
+            | Tag(0x0028, 0x0109)
+            | Tag(0x0028, 0x0110)
+            | Tag(0x0028, 0x0111)
+            | Tag(0x0028, 0x0120)
+            | Tag(0x0028, 0x0121)
+            | Tag(0x0060, 0x3004)
+            | Tag(0x0060, 0x3006)
+    )
+}
+
+/// Check whether the given error was caused
+/// by reaching the end of the data source prematurely.
+fn is_unexpected_eof(e: &(dyn std::error::Error + 'static)) -> bool {
+    let mut e = Some(e);
+    while let Some(err) = e {
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            return err.kind() == std::io::ErrorKind::UnexpectedEof;
+        }
+        e = err.source();
+    }
+    false
 }
 
 impl<S> DataSetReader<S>
 where
     S: StatefulDecode,
 {
+    /// Determine the input which the reader needs
+    /// before it can produce its next token,
+    /// following the same decision process as `next_token`.
+    pub(crate) fn pending_read(&self) -> PendingRead {
+        if self.closing {
+            return PendingRead::Nothing;
+        }
+        if self.hard_break {
+            return PendingRead::Finished;
+        }
+        if self.delimiter_check_pending {
+            let end_of_sequence = self
+                .seq_delimiters
+                .last()
+                .and_then(|sd| sd.len.get().map(|len| sd.base_offset + u64::from(len)));
+            if matches!(end_of_sequence, Some(end) if end <= self.parser.position()) {
+                return PendingRead::Nothing;
+            }
+        }
+
+        if self.in_sequence {
+            return PendingRead::ItemHeader;
+        }
+        if let Some(SeqToken {
+            typ: SeqTokenType::Item,
+            pixel_data: true,
+            len,
+            ..
+        }) = self.seq_delimiters.last()
+        {
+            return match len.get() {
+                Some(len) => PendingRead::Value(u64::from(len)),
+                None => PendingRead::Nothing,
+            };
+        }
+        match self.last_header {
+            Some(header) if header.is_encapsulated_pixeldata() => PendingRead::ItemHeader,
+            Some(header) => {
+                let len = u64::from(header.len.0);
+                match (
+                    self.options.excessive_length,
+                    self.available_length(self.parser.position()),
+                ) {
+                    (Some(_), Some(available)) => PendingRead::Value(len.min(available)),
+                    _ => PendingRead::Value(len),
+                }
+            }
+            None => PendingRead::Header {
+                explicit_vr: !self.implicit_vr
+                    && !self.seq_delimiters.iter().any(|sd| sd.implicit_vr),
+                root: self.seq_delimiters.is_empty(),
+            },
+        }
+    }
+
     fn update_seq_delimiters(&mut self) -> Result<Option<DataToken>> {
         if let Some(sd) = self.seq_delimiters.last() {
             if let Some(len) = sd.len.get() {
@@ -510,6 +1721,24 @@ where
                         self.seq_delimiters.pop();
                         return Ok(Some(token));
                     }
+                    Ordering::Less if self.is_lenient() => {
+                        // close the sequence or item right away
+                        self.report(ParseIssue::InconsistentSequenceEnd {
+                            end_of_sequence,
+                            bytes_read,
+                        });
+                        let token = match self.seq_delimiters.pop().map(|sd| sd.typ) {
+                            Some(SeqTokenType::Item) => {
+                                self.in_sequence = true;
+                                DataToken::ItemEnd
+                            }
+                            _ => {
+                                self.in_sequence = false;
+                                DataToken::SequenceEnd
+                            }
+                        };
+                        return Ok(Some(token));
+                    }
                     Ordering::Less => {
                         return InconsistentSequenceEndSnafu {
                             end_of_sequence,
@@ -525,6 +1754,105 @@ where
         Ok(None)
     }
 
+    /// Keep the character set declared in an item
+    /// from applying beyond the end of the item.
+    ///
+    /// The decoder switches its character set
+    /// whenever it reads _Specific Character Set_ (0008,0005),
+    /// so the character set in effect before each item
+    /// is restored once the item ends.
+    fn scope_character_set(&mut self, token: &DataToken) {
+        match token {
+            DataToken::ItemStart { .. } => {
+                if let Some(charset) = self.parser.character_set() {
+                    self.character_sets.push(charset);
+                }
+            }
+            DataToken::ItemEnd => {
+                if let Some(charset) = self.character_sets.pop() {
+                    self.parser.replace_character_set(charset);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Keep track of private creators and pixel representations,
+    /// and resolve the VR of the attribute in the given token
+    /// if it could not be determined by the decoder.
+    fn resolve_vr(&mut self, token: &mut DataToken) {
+        match token {
+            DataToken::ItemStart { .. } => {
+                self.private_creators.push(PrivateCreators::new());
+                self.pixel_representations.push(None);
+            }
+            DataToken::ItemEnd => {
+                if self.private_creators.len() > 1 {
+                    self.private_creators.pop();
+                }
+                if self.pixel_representations.len() > 1 {
+                    self.pixel_representations.pop();
+                }
+            }
+            DataToken::ElementHeader(header) if PrivateCreators::is_creator_tag(header.tag) => {
+                // private creators are always LO
+                if header.vr == VR::UN {
+                    header.vr = VR::LO;
+                    self.last_header = Some(*header);
+                }
+                self.pending_creator = Some(header.tag);
+            }
+            DataToken::ElementHeader(header) if header.vr == VR::UN && header.tag.1 == 0 => {
+                // group length
+                header.vr = VR::UL;
+                self.last_header = Some(*header);
+            }
+            DataToken::ElementHeader(header) if header.vr == VR::UN => {
+                let entry = match (&self.private_dict, self.private_creators.last()) {
+                    (Some(dict), Some(creators)) => dict.resolve(creators, header.tag),
+                    _ => None,
+                };
+                if let Some(entry) = entry.filter(|e| e.vr != VR::SQ) {
+                    header.vr = entry.vr;
+                    self.last_header = Some(*header);
+                }
+            }
+            DataToken::ElementHeader(header)
+                if self.implicit_vr || self.in_implicit_vr_sequence =>
+            {
+                if header.tag == Tag(0x0028, 0x0103) {
+                    self.pending_pixel_representation = true;
+                } else if header.vr == VR::US && is_us_or_ss(header.tag) {
+                    let signed = self
+                        .pixel_representations
+                        .iter()
+                        .rev()
+                        .find_map(|p| *p)
+                        .map(|p| p == 1)
+                        .unwrap_or(false);
+                    if signed {
+                        header.vr = VR::SS;
+                        self.last_header = Some(*header);
+                    }
+                }
+            }
+            DataToken::PrimitiveValue(value) => {
+                if let Some(tag) = self.pending_creator.take() {
+                    if let Some(creators) = self.private_creators.last_mut() {
+                        creators.declare(tag, &value.to_str());
+                    }
+                }
+                if self.pending_pixel_representation {
+                    self.pending_pixel_representation = false;
+                    if let Some(p) = self.pixel_representations.last_mut() {
+                        *p = value.to_int::<u16>().ok();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
     #[inline]
     fn push_sequence_token(&mut self, typ: SeqTokenType, len: Length, pixel_data: bool) {
         self.seq_delimiters.push(SeqToken {
@@ -532,11 +1860,153 @@ where
             pixel_data,
             len,
             base_offset: self.parser.position(),
+            implicit_vr: false,
         })
     }
 
+    /// Make the decoder read element headers in implicit VR little endian
+    /// for as long as the reader is inside a sequence encoded as UN.
+    fn update_implicit_vr(&mut self) {
+        let implicit_vr = self.seq_delimiters.iter().any(|t| t.implicit_vr);
+        if implicit_vr != self.in_implicit_vr_sequence {
+            self.parser.set_implicit_vr(implicit_vr);
+            self.in_implicit_vr_sequence = implicit_vr;
+        }
+    }
+
+    /// Whether a value cut short by the end of the data
+    /// is read as an empty value instead of failing.
+    fn recovers_from_truncation(&self) -> bool {
+        match self.options.excessive_length {
+            Some(ValueLengthStrategy::Fail) => false,
+            Some(_) => true,
+            None => self.is_lenient(),
+        }
+    }
+
+    /// Obtain the number of bytes available to a value starting at `position`,
+    /// as limited by the enclosing items and sequences with explicit length
+    /// and by the end of the data, if known.
+    fn available_length(&self, position: u64) -> Option<u64> {
+        self.seq_delimiters
+            .iter()
+            .filter_map(|sd| sd.len.get().map(|len| sd.base_offset + u64::from(len)))
+            .chain(self.options.data_end)
+            .min()
+            .map(|end| end.saturating_sub(position))
+    }
+
+    /// Determine the header with which to read the value of an element,
+    /// according to the strategy for values going beyond the available data.
+    fn fit_value_length(
+        &mut self,
+        header: &DataElementHeader,
+        position: u64,
+    ) -> Result<DataElementHeader> {
+        let strategy = match self.options.excessive_length {
+            Some(strategy) => strategy,
+            None => return Ok(*header),
+        };
+        let available = match self.available_length(position) {
+            Some(available) if available < u64::from(header.len.0) => available as u32,
+            _ => return Ok(*header),
+        };
+        if strategy == ValueLengthStrategy::Fail {
+            return ExcessiveLengthSnafu {
+                tag: header.tag,
+                len: header.len.0,
+                available,
+                position,
+            }
+            .fail();
+        }
+        self.report(ParseIssue::ExcessiveLength {
+            tag: header.tag,
+            vr: header.vr,
+            len: header.len.0,
+            available,
+            strategy,
+            position,
+        });
+        Ok(DataElementHeader::new(
+            header.tag,
+            header.vr,
+            Length(available),
+        ))
+    }
+
+    /// Bring a value which was just read to its intended length,
+    /// according to the configured value length strategies.
+    fn adjust_value(
+        &mut self,
+        header: &DataElementHeader,
+        read_header: &DataElementHeader,
+        value: PrimitiveValue,
+        position: u64,
+    ) -> Result<PrimitiveValue> {
+        if read_header.len != header.len {
+            // skip the bytes which were not consumed by the value decoder
+            let end = position + u64::from(read_header.len.0);
+            let remaining = end.saturating_sub(self.parser.position());
+            if remaining > 0 {
+                self.parser
+                    .skip_bytes(remaining as u32)
+                    .context(ReadValueSnafu {
+                        len: read_header.len.0,
+                        tag: header.tag,
+                    })?;
+            }
+            if self.options.excessive_length == Some(ValueLengthStrategy::Pad) {
+                return Ok(resize_value(value, header.vr, header.len.0 as usize));
+            }
+            return Ok(value);
+        }
+        if header.len.0 % 2 != 0 {
+            match self.options.odd_length {
+                Some(ValueLengthStrategy::Truncate) => {
+                    return Ok(resize_value(value, header.vr, header.len.0 as usize - 1));
+                }
+                Some(ValueLengthStrategy::Pad) => {
+                    return Ok(resize_value(value, header.vr, header.len.0 as usize + 1));
+                }
+                _ => {}
+            }
+        }
+        Ok(value)
+    }
+
+    /// Account for a value of the given length about to be read,
+    /// failing if it goes beyond the configured allocation limits.
+    fn check_allocation(&mut self, tag: Tag, len: u32, position: u64) -> Result<()> {
+        if let Some(max) = self.options.max_value_length {
+            if len > max {
+                return ValueLengthLimitSnafu {
+                    tag,
+                    len,
+                    max,
+                    position,
+                }
+                .fail();
+            }
+        }
+        let allocated = self.bytes_allocated + u64::from(len);
+        if let Some(budget) = self.options.allocation_budget {
+            if allocated > budget {
+                return AllocationBudgetSnafu {
+                    tag,
+                    len,
+                    budget,
+                    position,
+                }
+                .fail();
+            }
+        }
+        self.bytes_allocated = allocated;
+        Ok(())
+    }
+
     fn read_value(&mut self, header: &DataElementHeader) -> Result<PrimitiveValue> {
-        match self.options.value_read {
+        let value = match self.options.value_read {
             ValueReadStrategy::Interpreted => self.parser.read_value(header),
             ValueReadStrategy::Preserved => self.parser.read_value_preserved(header),
             ValueReadStrategy::Raw => self.parser.read_value_bytes(header),
@@ -544,22 +2014,55 @@ where
         .context(ReadValueSnafu {
             len: header.len.0,
             tag: header.tag,
-        })
+        })?;
+        if self.options.trim_padding {
+            Ok(trim_text_padding(value))
+        } else {
+            Ok(value)
+        }
+    }
+}
+
+/// Remove the trailing spaces and null characters of textual values.
+fn trim_text_padding(value: PrimitiveValue) -> PrimitiveValue {
+    fn trim(s: &str) -> &str {
+        s.trim_end_matches(|c: char| c == ' ' || c == '\0')
+    }
+
+    match value {
+        PrimitiveValue::Str(mut s) => {
+            let len = trim(&s).len();
+            s.truncate(len);
+            PrimitiveValue::Str(s)
+        }
+        PrimitiveValue::Strs(mut values) => {
+            for s in values.iter_mut() {
+                let len = trim(s).len();
+                s.truncate(len);
+            }
+            PrimitiveValue::Strs(values)
+        }
+        value => value,
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{DataSetReader, DataToken, StatefulDecode};
+    use super::{
+        DataSetReader, DataSetReaderOptions, DataToken, ParseIssue, Result, StatefulDecode,
+        TrailingPaddingStrategy, ValueLengthStrategy,
+    };
     use crate::stateful::decode::StatefulDecoder;
+    use crate::ErrorKind;
     use dicom_core::header::{DataElementHeader, Length};
     use dicom_core::value::PrimitiveValue;
-    use dicom_core::{Tag, VR};
+    use dicom_core::{dicom_value, Tag, VR};
     use dicom_encoding::decode::basic::LittleEndianBasicDecoder;
     use dicom_encoding::decode::{
         explicit_le::ExplicitVRLittleEndianDecoder, implicit_le::ImplicitVRLittleEndianDecoder,
     };
     use dicom_encoding::text::SpecificCharacterSet;
+    use std::sync::Arc;
 
     fn validate_dataset_reader_implicit_vr<I>(data: &[u8], ground_truth: I)
     where
@@ -1020,6 +2523,154 @@ mod tests {
         validate_dataset_reader_explicit_vr(DATA, ground_truth);
     }
 
+    #[test]
+    fn skip_trailing_padding() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            0x10, 0x00, 0x10, 0x00, // (0010, 0010) PatientName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'D', b'o', b'e', b' ',
+            // -- 12 -- padding
+            0xfc, 0xff, 0xfc, 0xff, // (fffc,fffc) DataSetTrailingPadding
+            b'O', b'B', // VR
+            0x00, 0x00, // reserved
+            0x08, 0x00, 0x00, 0x00, // length: 8
+            0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        ];
+
+        let mut cursor = DATA;
+        let parser = StatefulDecoder::new(
+            &mut cursor,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let options =
+            DataSetReaderOptions::default().trailing_padding(TrailingPaddingStrategy::Skip);
+        let dset_reader = DataSetReader::new(parser, options);
+
+        let tokens: Vec<_> = dset_reader
+            .collect::<Result<_>>()
+            .expect("should read all tokens without errors");
+
+        assert_eq!(
+            tokens,
+            vec![
+                DataToken::ElementHeader(DataElementHeader::new(
+                    Tag(0x0010, 0x0010),
+                    VR::PN,
+                    Length(4),
+                )),
+                DataToken::PrimitiveValue(PrimitiveValue::Strs(
+                    ["Doe ".to_owned()].as_ref().into()
+                )),
+            ],
+        );
+        // the whole padding element was consumed
+        assert_eq!(cursor.len(), 0);
+    }
+
+    #[test]
+    fn read_with_trimmed_padding() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            0x08, 0x00, 0x18, 0x00, // (0008, 0018) SOPInstanceUID
+            b'U', b'I', // VR
+            0x06, 0x00, // length: 6
+            b'1', b'.', b'2', b'.', b'3', 0x00,
+            0x10, 0x00, 0x10, 0x00, // (0010, 0010) PatientName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'D', b'o', b'e', b' ',
+            0x20, 0x00, 0x00, 0x40, // (0020, 4000) ImageComments
+            b'L', b'T', // VR
+            0x06, 0x00, // length: 6
+            b'O', b'K', b' ', b' ', b' ', b' ',
+        ];
+
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let options = DataSetReaderOptions::default().trim_padding(true);
+        let dset_reader = DataSetReader::new(parser, options);
+
+        let values: Vec<_> = dset_reader
+            .collect::<Result<Vec<_>>>()
+            .expect("should read all tokens without errors")
+            .into_iter()
+            .filter_map(|token| match token {
+                DataToken::PrimitiveValue(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+
+        // values compare equal regardless of padding,
+        // so the strings are checked instead
+        let strings: Vec<_> = values
+            .iter()
+            .map(|value| value.strings().unwrap().to_vec())
+            .collect();
+        assert_eq!(strings, vec![vec!["1.2.3"], vec!["Doe"], vec!["OK"]]);
+        assert!(matches!(values[2], PrimitiveValue::Str(_)));
+    }
+
+    #[test]
+    fn read_character_set_scoped_to_item() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            0x08, 0x00, 0x05, 0x00, // (0008, 0005) SpecificCharacterSet
+            b'C', b'S', // VR
+            0x0a, 0x00, // length: 10
+            b'I', b'S', b'O', b'_', b'I', b'R', b' ', b'1', b'0', b'0',
+            0x40, 0x00, 0x30, 0xa7, // (0040, A730) ContentSequence
+            b'S', b'Q', // VR
+            0x00, 0x00, // reserved
+            0xff, 0xff, 0xff, 0xff, // length: undefined
+            0xfe, 0xff, 0x00, 0xe0, // item start
+            0xff, 0xff, 0xff, 0xff, // length: undefined
+            0x08, 0x00, 0x05, 0x00, // (0008, 0005) SpecificCharacterSet
+            b'C', b'S', // VR
+            0x0a, 0x00, // length: 10
+            b'I', b'S', b'O', b'_', b'I', b'R', b' ', b'1', b'9', b'2',
+            0x10, 0x00, 0x10, 0x00, // (0010, 0010) PatientName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'O', b'l', 0xc3, 0xa1, // "Olá" in UTF-8
+            0xfe, 0xff, 0x0d, 0xe0, // item end
+            0x00, 0x00, 0x00, 0x00,
+            0xfe, 0xff, 0xdd, 0xe0, // sequence end
+            0x00, 0x00, 0x00, 0x00,
+            0x70, 0x00, 0x84, 0x00, // (0070, 0084) ContentCreatorName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'J', b'o', b's', 0xe9, // "José" in ISO-8859-1
+        ];
+
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let dset_reader = DataSetReader::new(parser, Default::default());
+
+        let values: Vec<_> = dset_reader
+            .collect::<Result<Vec<_>>>()
+            .expect("should read all tokens without errors")
+            .into_iter()
+            .filter_map(|token| match token {
+                DataToken::PrimitiveValue(value) => Some(value.to_str().to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(values, vec!["ISO_IR 100", "ISO_IR 192", "Olá", "José"]);
+    }
+
     #[test]
     fn read_dataset_in_dataset() {
         #[rustfmt::skip]
@@ -1119,4 +2770,685 @@ mod tests {
 
         validate_dataset_reader_implicit_vr(DATA, ground_truth);
     }
+
+    #[test]
+    fn read_private_attributes_with_private_dictionary() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0019,0010) private creator
+            0x19, 0x00, 0x10, 0x00, //
+            // length: 18
+            0x12, 0x00, 0x00, 0x00, //
+            b'S', b'I', b'E', b'M', b'E', b'N', b'S', b' ', b'M', b'R', b' ',
+            b'H', b'E', b'A', b'D', b'E', b'R', b' ',
+            // (0019,100C) B_value
+            0x19, 0x00, 0x0C, 0x10, //
+            // length: 4
+            0x04, 0x00, 0x00, 0x00, //
+            b'1', b'0', b'0', b'0',
+            // (0019,1108) unknown private block
+            0x19, 0x00, 0x08, 0x11, //
+            // length: 2
+            0x02, 0x00, 0x00, 0x00, //
+            b'M', b'R',
+        ];
+
+        let mut cursor = DATA;
+        let parser = StatefulDecoder::new(
+            &mut cursor,
+            ImplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let dict = Arc::new(dicom_dictionary_std::private::vendor_dictionary().clone());
+        let dset_reader =
+            DataSetReader::new(parser, Default::default()).with_private_dictionary(dict);
+        let tokens: Vec<_> = dset_reader.collect::<Result<_>>().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0019, 0x0010),
+                    vr: VR::LO,
+                    len: Length(18),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("SIEMENS MR HEADER ")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0019, 0x100C),
+                    vr: VR::IS,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("1000")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0019, 0x1108),
+                    vr: VR::UN,
+                    len: Length(2),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from(&b"MR"[..])),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_implicit_vr_with_runtime_dictionary() {
+        use dicom_core::dictionary::runtime::RuntimeDataDictionary;
+        use dicom_encoding::transfer_syntax::{AdapterFreeTransferSyntax, Codec, Endianness};
+
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0009,1001) private attribute
+            0x09, 0x00, 0x01, 0x10, //
+            // length: 4
+            0x04, 0x00, 0x00, 0x00, //
+            b'F', b'A', b'S', b'T',
+            // (0010,0010) PatientName
+            0x10, 0x00, 0x10, 0x00, //
+            // length: 8
+            0x08, 0x00, 0x00, 0x00, //
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+        ];
+
+        let ts = AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2",
+            "Implicit VR Little Endian",
+            Endianness::Little,
+            false,
+            Codec::None,
+        )
+        .erased();
+        let dict = RuntimeDataDictionary::from_csv_reader(
+            &b"(0009,1001),CS,AcmeScanMode\n(0010,0010),PN,PatientName\n"[..],
+        )
+        .unwrap();
+
+        let dset_reader = DataSetReader::new_with_dictionary(
+            DATA,
+            dict,
+            &ts,
+            SpecificCharacterSet::Default,
+            Default::default(),
+        )
+        .unwrap();
+        let tokens: Vec<_> = dset_reader.collect::<Result<_>>().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0009, 0x1001),
+                    vr: VR::CS,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("FAST")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0010),
+                    vr: VR::PN,
+                    len: Length(8),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("Doe^John")),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_implicit_vr_resolved_from_context() {
+        use dicom_encoding::transfer_syntax::{AdapterFreeTransferSyntax, Codec, Endianness};
+
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0009,0010) private creator
+            0x09, 0x00, 0x10, 0x00, //
+            // length: 4
+            0x04, 0x00, 0x00, 0x00, //
+            b'A', b'C', b'M', b'E',
+            // (0028,0000) group length
+            0x28, 0x00, 0x00, 0x00, //
+            // length: 4
+            0x04, 0x00, 0x00, 0x00, //
+            0x14, 0x00, 0x00, 0x00,
+            // (0028,0103) PixelRepresentation
+            0x28, 0x00, 0x03, 0x01, //
+            // length: 2
+            0x02, 0x00, 0x00, 0x00, //
+            0x01, 0x00,
+            // (0028,0106) SmallestImagePixelValue
+            0x28, 0x00, 0x06, 0x01, //
+            // length: 2
+            0x02, 0x00, 0x00, 0x00, //
+            0x00, 0xFC,
+        ];
+
+        let ts = AdapterFreeTransferSyntax::new(
+            "1.2.840.10008.1.2",
+            "Implicit VR Little Endian",
+            Endianness::Little,
+            false,
+            Codec::None,
+        )
+        .erased();
+
+        let dset_reader =
+            DataSetReader::new_with_ts_cs(DATA, &ts, SpecificCharacterSet::Default).unwrap();
+        let tokens: Vec<_> = dset_reader.collect::<Result<_>>().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0009, 0x0010),
+                    vr: VR::LO,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("ACME")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0028, 0x0000),
+                    vr: VR::UL,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from(20_u32)),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0028, 0x0103),
+                    vr: VR::US,
+                    len: Length(2),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from(1_u16)),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0028, 0x0106),
+                    vr: VR::SS,
+                    len: Length(2),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from(-1024_i16)),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_sequence_encoded_as_un() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0008,1140) ReferencedImageSequence, UN, undefined length
+            0x08, 0x00, 0x40, 0x11, b'U', b'N', 0x00, 0x00, //
+            0xFF, 0xFF, 0xFF, 0xFF, //
+            // item start, undefined length
+            0xFE, 0xFF, 0x00, 0xE0, 0xFF, 0xFF, 0xFF, 0xFF, //
+            // (0008,1150) ReferencedSOPClassUID in implicit VR, length 4
+            0x08, 0x00, 0x50, 0x11, 0x04, 0x00, 0x00, 0x00, //
+            b'1', b'.', b'2', 0x00,
+            // item end
+            0xFE, 0xFF, 0x0D, 0xE0, 0x00, 0x00, 0x00, 0x00, //
+            // sequence end
+            0xFE, 0xFF, 0xDD, 0xE0, 0x00, 0x00, 0x00, 0x00, //
+            // (0020,0013) InstanceNumber, IS, length 2, back in explicit VR
+            0x20, 0x00, 0x13, 0x00, b'I', b'S', 0x02, 0x00, //
+            b'1', b' ',
+        ];
+
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let dset_reader = DataSetReader::new(parser, Default::default());
+        let tokens: Vec<_> = dset_reader.collect::<Result<_>>().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                DataToken::SequenceStart {
+                    tag: Tag(0x0008, 0x1140),
+                    len: Length::UNDEFINED,
+                },
+                DataToken::ItemStart {
+                    len: Length::UNDEFINED,
+                },
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0008, 0x1150),
+                    vr: VR::UI,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("1.2\0")),
+                DataToken::ItemEnd,
+                DataToken::SequenceEnd,
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0020, 0x0013),
+                    vr: VR::IS,
+                    len: Length(2),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("1 ")),
+            ]
+        );
+    }
+
+    /// Read the given explicit VR little endian data set in lenient mode,
+    /// collecting the tokens and the issues reported.
+    fn read_lenient(data: &[u8]) -> (Result<Vec<DataToken>>, Vec<ParseIssue>) {
+        use std::sync::Mutex;
+
+        let issues = Arc::new(Mutex::new(Vec::new()));
+        let parser = StatefulDecoder::new(
+            data,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let sink = Arc::clone(&issues);
+        let dset_reader = DataSetReader::new(parser, DataSetReaderOptions::default().lenient())
+            .with_issue_callback(move |w| sink.lock().unwrap().push(w.clone()));
+        let tokens = dset_reader.collect::<Result<Vec<_>>>();
+        let issues = issues.lock().unwrap().clone();
+        (tokens, issues)
+    }
+
+    #[test]
+    fn lenient_truncated_last_element() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0010,0010) PatientName, PN, len 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+            // (0010,0020) PatientID, LO, len 10, only 4 bytes left
+            0x10, 0x00, 0x20, 0x00, b'L', b'O', 0x0a, 0x00,
+            b'1', b'2', b'3', b'4',
+        ];
+
+        // strict mode fails on the truncated value
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let dset_reader = DataSetReader::new(parser, Default::default());
+        let e = dset_reader.collect::<Result<Vec<_>>>().unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::Truncated);
+        assert_eq!(e.tag(), Some(Tag(0x0010, 0x0020)));
+        assert_eq!(e.position(), Some(24));
+
+        let (tokens, issues) = read_lenient(DATA);
+        assert_eq!(
+            tokens.unwrap(),
+            vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0010),
+                    vr: VR::PN,
+                    len: Length(8),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("Doe^John")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0020),
+                    vr: VR::LO,
+                    len: Length(10),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::Empty),
+            ]
+        );
+        assert_eq!(
+            issues,
+            vec![ParseIssue::TruncatedValue {
+                tag: Tag(0x0010, 0x0020),
+                len: 10,
+                position: 24,
+            }]
+        );
+    }
+
+    #[test]
+    fn lenient_closes_open_sequence() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0008,1115) ReferencedSeriesSequence, SQ, undefined length
+            0x08, 0x00, 0x15, 0x11, b'S', b'Q', 0x00, 0x00, 0xff, 0xff, 0xff, 0xff,
+            // item, undefined length
+            0xfe, 0xff, 0x00, 0xe0, 0xff, 0xff, 0xff, 0xff,
+            // (0020,000E) SeriesInstanceUID, UI, len 4
+            0x20, 0x00, 0x0e, 0x00, b'U', b'I', 0x04, 0x00,
+            b'1', b'.', b'2', b'3',
+            // (0008,0016) SOPClassUID, header cut short
+            0x08, 0x00, 0x16, 0x00, b'U',
+        ];
+
+        let (tokens, issues) = read_lenient(DATA);
+        assert_eq!(
+            tokens.unwrap(),
+            vec![
+                DataToken::SequenceStart {
+                    tag: Tag(0x0008, 0x1115),
+                    len: Length::UNDEFINED,
+                },
+                DataToken::ItemStart {
+                    len: Length::UNDEFINED,
+                },
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0020, 0x000E),
+                    vr: VR::UI,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("1.23")),
+                DataToken::ItemEnd,
+                DataToken::SequenceEnd,
+            ]
+        );
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0], ParseIssue::UnexpectedEndOfData { .. }));
+    }
+
+    #[test]
+    fn reports_odd_length() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0010,0020) PatientID, LO, len 3
+            0x10, 0x00, 0x20, 0x00, b'L', b'O', 0x03, 0x00,
+            b'1', b'2', b'3',
+        ];
+
+        let (tokens, issues) = read_lenient(DATA);
+        assert_eq!(tokens.unwrap().len(), 2);
+        assert_eq!(
+            issues,
+            vec![ParseIssue::OddLength {
+                tag: Tag(0x0010, 0x0020),
+                vr: VR::LO,
+                len: 3,
+                position: 8,
+            }]
+        );
+    }
+
+    fn read_with_options(
+        data: &[u8],
+        options: DataSetReaderOptions,
+    ) -> (Result<Vec<DataToken>>, Vec<ParseIssue>) {
+        let parser = StatefulDecoder::new(
+            data,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let mut dset_reader = DataSetReader::new(parser, options).collect_issues();
+        let tokens = (&mut dset_reader).collect();
+        (tokens, dset_reader.take_issues())
+    }
+
+    #[test]
+    fn odd_length_strategies() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0009,0010) private OB, len 3
+            0x09, 0x00, 0x10, 0x00, b'O', b'B', 0x00, 0x00, 0x03, 0x00, 0x00, 0x00,
+            0x01, 0x02, 0x03,
+        ];
+
+        let options = DataSetReaderOptions::default().odd_length(ValueLengthStrategy::Fail);
+        let (tokens, _) = read_with_options(DATA, options);
+        assert!(tokens.is_err());
+
+        let options = DataSetReaderOptions::default().odd_length(ValueLengthStrategy::Truncate);
+        let (tokens, issues) = read_with_options(DATA, options);
+        assert_eq!(
+            tokens.unwrap()[1],
+            DataToken::PrimitiveValue(PrimitiveValue::from(vec![0x01_u8, 0x02])),
+        );
+        assert_eq!(
+            issues,
+            vec![ParseIssue::OddLengthAdjusted {
+                tag: Tag(0x0009, 0x0010),
+                vr: VR::OB,
+                len: 3,
+                strategy: ValueLengthStrategy::Truncate,
+                position: 12,
+            }]
+        );
+
+        let options = DataSetReaderOptions::default().odd_length(ValueLengthStrategy::Pad);
+        let (tokens, _) = read_with_options(DATA, options);
+        assert_eq!(
+            tokens.unwrap()[1],
+            DataToken::PrimitiveValue(PrimitiveValue::from(vec![0x01_u8, 0x02, 0x03, 0x00])),
+        );
+    }
+
+    #[test]
+    fn allocation_limits() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0008,0060) Modality, CS, len 2
+            0x08, 0x00, 0x60, 0x00, b'C', b'S', 0x02, 0x00, b'M', b'R',
+            // (0009,0010) private OB, len 0x7FFF_FFF0
+            0x09, 0x00, 0x10, 0x00, b'O', b'B', 0x00, 0x00, 0xF0, 0xFF, 0xFF, 0x7F,
+            0x01, 0x02, 0x03, 0x04,
+        ];
+
+        let options = DataSetReaderOptions::default().max_value_length(1024);
+        let (tokens, _) = read_with_options(DATA, options);
+        let e = tokens.unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::LimitExceeded);
+        assert_eq!(e.tag(), Some(Tag(0x0009, 0x0010)));
+        assert_eq!(e.position(), Some(22));
+
+        // the budget is shared by all values
+        let options = DataSetReaderOptions::default().allocation_budget(2);
+        let mut parser = DataSetReader::new(
+            StatefulDecoder::new(
+                DATA,
+                ExplicitVRLittleEndianDecoder::default(),
+                LittleEndianBasicDecoder::default(),
+                SpecificCharacterSet::Default,
+            ),
+            options,
+        );
+        // Modality header, value, and OB header
+        for _ in 0..3 {
+            assert!(parser.next().unwrap().is_ok());
+        }
+        assert!(matches!(
+            parser.next(),
+            Some(Err(super::Error::AllocationBudget { budget: 2, .. }))
+        ));
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn excessive_length_strategies() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0018,6011) SequenceOfUltrasoundRegions, len 18
+            0x18, 0x00, 0x11, 0x60, b'S', b'Q', 0x00, 0x00, 0x12, 0x00, 0x00, 0x00,
+            // item, len 10
+            0xfe, 0xff, 0x00, 0xe0, 0x0a, 0x00, 0x00, 0x00,
+            // (0018,6012) RegionSpatialFormat, US, len 4, only 2 bytes in the item
+            0x18, 0x00, 0x12, 0x60, b'U', b'S', 0x04, 0x00, 0x01, 0x00,
+            // (0020,4000) ImageComments, LT, len 8, only 4 bytes in the data
+            0x20, 0x00, 0x00, 0x40, b'L', b'T', 0x08, 0x00,
+            b'T', b'E', b'S', b'T',
+        ];
+
+        let options = DataSetReaderOptions::default()
+            .excessive_length(ValueLengthStrategy::Fail)
+            .data_end(DATA.len() as u64);
+        let (tokens, _) = read_with_options(DATA, options);
+        let e = tokens.unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::Truncated);
+        assert_eq!(e.tag(), Some(Tag(0x0018, 0x6012)));
+
+        let options = DataSetReaderOptions::default()
+            .excessive_length(ValueLengthStrategy::Truncate)
+            .data_end(DATA.len() as u64);
+        let (tokens, issues) = read_with_options(DATA, options);
+        let tokens = tokens.unwrap();
+        assert_eq!(tokens.len(), 8);
+        assert_eq!(
+            tokens[3],
+            DataToken::PrimitiveValue(PrimitiveValue::from(1_u16))
+        );
+        assert_eq!(tokens[4], DataToken::ItemEnd);
+        assert_eq!(tokens[5], DataToken::SequenceEnd);
+        assert_eq!(
+            tokens[7],
+            DataToken::PrimitiveValue(PrimitiveValue::from("TEST"))
+        );
+        assert_eq!(
+            issues,
+            vec![
+                ParseIssue::ExcessiveLength {
+                    tag: Tag(0x0018, 0x6012),
+                    vr: VR::US,
+                    len: 4,
+                    available: 2,
+                    strategy: ValueLengthStrategy::Truncate,
+                    position: 28,
+                },
+                ParseIssue::ExcessiveLength {
+                    tag: Tag(0x0020, 0x4000),
+                    vr: VR::LT,
+                    len: 8,
+                    available: 4,
+                    strategy: ValueLengthStrategy::Truncate,
+                    position: 38,
+                },
+            ]
+        );
+
+        let options = DataSetReaderOptions::default()
+            .excessive_length(ValueLengthStrategy::Pad)
+            .data_end(DATA.len() as u64);
+        let (tokens, _) = read_with_options(DATA, options);
+        assert_eq!(
+            tokens.unwrap()[3],
+            DataToken::PrimitiveValue(dicom_value!(U16, [1, 0]))
+        );
+    }
+
+    #[test]
+    fn collect_value_issues() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0008,0016) SOPClassUID, UI, len 6, padded with a space
+            0x08, 0x00, 0x16, 0x00, b'U', b'I', 0x06, 0x00,
+            b'1', b'.', b'2', b'.', b'3', b' ',
+            // (0008,0060) Modality, CS, len 18
+            0x08, 0x00, 0x60, 0x00, b'C', b'S', 0x12, 0x00,
+            b'A', b'B', b'C', b'D', b'E', b'F', b'G', b'H', b'I',
+            b'J', b'K', b'L', b'M', b'N', b'O', b'P', b'Q', b'R',
+        ];
+
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let mut dset_reader = DataSetReader::new(parser, Default::default()).collect_issues();
+        let tokens = dset_reader.by_ref().collect::<Result<Vec<_>>>().unwrap();
+        assert_eq!(tokens.len(), 4);
+
+        let issues = dset_reader.take_issues();
+        assert_eq!(
+            issues,
+            vec![
+                ParseIssue::InvalidPadding {
+                    tag: Tag(0x0008, 0x0016),
+                    vr: VR::UI,
+                    position: 8,
+                },
+                ParseIssue::ValueTooLong {
+                    tag: Tag(0x0008, 0x0060),
+                    vr: VR::CS,
+                    len: 18,
+                    max: 16,
+                    position: 22,
+                },
+            ]
+        );
+        assert_eq!(issues[1].position(), 22);
+        assert_eq!(issues[1].tag(), Some(Tag(0x0008, 0x0060)));
+        assert!(dset_reader.take_issues().is_empty());
+    }
+
+    #[test]
+    fn read_until_pixel_data() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0010,0010) PatientName, PN, len 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+            // (7FE0,0010) PixelData, OB, len 4
+            0xe0, 0x7f, 0x10, 0x00, b'O', b'B', 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+            0x01, 0x02, 0x03, 0x04,
+        ];
+
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let options = DataSetReaderOptions::default().read_until(Tag(0x7FE0, 0x0010));
+        let dset_reader = DataSetReader::new(parser, options);
+        let tokens = dset_reader.collect::<Result<Vec<_>>>().unwrap();
+
+        assert_eq!(
+            tokens,
+            vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0010),
+                    vr: VR::PN,
+                    len: Length(8),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("Doe^John")),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_positioned_tokens() {
+        #[rustfmt::skip]
+        static DATA: &[u8] = &[
+            // (0008,1115) ReferencedSeriesSequence, SQ, len 20
+            0x08, 0x00, 0x15, 0x11, b'S', b'Q', 0x00, 0x00, 0x14, 0x00, 0x00, 0x00,
+            // item, len 12
+            0xfe, 0xff, 0x00, 0xe0, 0x0c, 0x00, 0x00, 0x00,
+            // (0020,000E) SeriesInstanceUID, UI, len 4
+            0x20, 0x00, 0x0e, 0x00, b'U', b'I', 0x04, 0x00,
+            b'1', b'.', b'2', b'3',
+            // (0010,0010) PatientName, PN, len 8
+            0x10, 0x00, 0x10, 0x00, b'P', b'N', 0x08, 0x00,
+            b'D', b'o', b'e', b'^', b'J', b'o', b'h', b'n',
+        ];
+
+        let parser = StatefulDecoder::new(
+            DATA,
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder::default(),
+            SpecificCharacterSet::Default,
+        );
+        let mut dset_reader = DataSetReader::new(parser, Default::default());
+        let tokens: Vec<_> = dset_reader.positioned().collect::<Result<_>>().unwrap();
+
+        let locations: Vec<_> = tokens.iter().map(|t| (t.offset, t.len)).collect();
+        assert_eq!(
+            locations,
+            vec![
+                // sequence start
+                (0, 12),
+                // item start
+                (12, 8),
+                // element header and value
+                (20, 8),
+                (28, 4),
+                // item end and sequence end
+                (32, 0),
+                (32, 0),
+                // element header and value
+                (32, 8),
+                (40, 8),
+            ]
+        );
+        assert_eq!(
+            tokens[3].token,
+            DataToken::PrimitiveValue(PrimitiveValue::from("1.23"))
+        );
+        assert_eq!(tokens[7].end(), DATA.len() as u64);
+    }
 }
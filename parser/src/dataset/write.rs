@@ -7,7 +7,7 @@
 //! In this process, the writer will also adapt values
 //! to the necessary DICOM encoding rules.
 use crate::dataset::*;
-use crate::stateful::encode::StatefulEncoder;
+use crate::stateful::encode::{PaddingPolicy, StatefulEncoder};
 use dicom_core::{DataElementHeader, Length, VR};
 use dicom_encoding::encode::EncodeTo;
 use dicom_encoding::text::SpecificCharacterSet;
@@ -18,6 +18,7 @@ use std::io::Write;
 
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
+#[snafu(visibility(pub(crate)))]
 pub enum Error {
     /// Unsupported transfer syntax for encoding
     #[snafu(display("Unsupported transfer syntax {} ({})", ts_uid, ts_alias))]
@@ -67,6 +68,15 @@ pub enum Error {
         #[snafu(backtrace)]
         source: crate::stateful::encode::Error,
     },
+
+    /// The byte length of a sequence or item
+    /// does not fit in a defined length
+    #[snafu(display("Length {} of sequence or item is too large", len))]
+    LengthOverflow { len: u64, backtrace: Backtrace },
+
+    /// The data set ended before all sequences and items were closed
+    #[snafu(display("Data set ended inside a sequence or item"))]
+    PrematureEnd { backtrace: Backtrace },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -79,6 +89,9 @@ struct SeqToken {
     /// The length of the value, as indicated by the starting element,
     /// can be unknown.
     len: Length,
+    /// The character set in effect before the start,
+    /// restored at the end of an item.
+    charset: SpecificCharacterSet,
 }
 
 /// A stateful device for printing a DICOM data set in sequential order.
@@ -122,6 +135,21 @@ impl<W, E, T> DataSetWriter<W, E, T> {
             last_de: None,
         }
     }
+
+    /// Set the policy for padding textual values of odd length.
+    ///
+    /// By default, values are padded as prescribed by the standard.
+    pub fn with_padding(mut self, padding: PaddingPolicy) -> Self {
+        self.printer.set_padding(padding);
+        self
+    }
+
+    /// Obtain a mutable reference to the inner writer.
+    ///
+    /// Writing to it directly may result in a malformed data set.
+    pub fn get_mut(&mut self) -> &mut W {
+        self.printer.get_mut()
+    }
 }
 
 impl<W, E> DataSetWriter<W, E>
@@ -142,6 +170,51 @@ where
         Ok(())
     }
 
+    /// Write a data set trailing padding element (FFFC,FFFC)
+    /// with `len` bytes of zeros as its value.
+    ///
+    /// This should only be called after the full data set was written,
+    /// as no other elements are expected to follow this one.
+    /// Odd lengths are rounded up to the next even number.
+    pub fn write_trailing_padding(&mut self, len: u32) -> Result<()> {
+        // (u32::MAX stands for an undefined length, so it is rounded down)
+        let len = len.saturating_add(len % 2) & !1;
+        let tag = Tag(0xFFFC, 0xFFFC);
+        self.printer
+            .encode_element_header(DataElementHeader::new(tag, VR::OB, Length(len)))
+            .context(WriteHeaderSnafu { tag })?;
+        self.printer
+            .write_bytes(&vec![0; len as usize])
+            .context(WriteValueSnafu)?;
+        Ok(())
+    }
+
+    /// Write the bytes of one or more data elements as they are,
+    /// without any further encoding.
+    ///
+    /// The bytes must already be encoded
+    /// in the transfer syntax of this writer.
+    /// This should only be called in between data elements,
+    /// otherwise the resulting data set will be malformed.
+    pub fn write_raw_elements(&mut self, bytes: &[u8]) -> Result<()> {
+        self.printer.write_raw_bytes(bytes).context(WriteValueSnafu)
+    }
+
+    /// Retrieve the number of bytes written so far by this writer.
+    pub fn bytes_written(&self) -> u64 {
+        self.printer.bytes_written()
+    }
+
+    /// Retrieve the character set currently used for encoding text.
+    pub(crate) fn character_set(&self) -> SpecificCharacterSet {
+        self.printer.character_set()
+    }
+
+    /// Replace the character set used for encoding text.
+    pub(crate) fn set_character_set(&mut self, charset: SpecificCharacterSet) {
+        self.printer.set_character_set(charset)
+    }
+
     /// Feed the given data set token for writing the data set.
     pub fn write(&mut self, token: DataToken) -> Result<()> {
         // adjust the logic of sequence printing:
@@ -153,6 +226,7 @@ where
                 self.seq_tokens.push(SeqToken {
                     typ: SeqTokenType::Sequence,
                     len,
+                    charset: self.printer.character_set(),
                 });
                 self.write_impl(&token)?;
                 Ok(())
@@ -161,6 +235,7 @@ where
                 self.seq_tokens.push(SeqToken {
                     typ: SeqTokenType::Item,
                     len,
+                    charset: self.printer.character_set(),
                 });
                 self.write_impl(&token)?;
                 Ok(())
@@ -171,6 +246,8 @@ where
                     if seq_start.typ == SeqTokenType::Item && seq_start.len.is_undefined() {
                         self.write_impl(&token)?;
                     }
+                    // a character set declared in the item ends with it
+                    self.printer.set_character_set(seq_start.charset);
                 }
                 Ok(())
             }
@@ -194,6 +271,7 @@ where
                 self.seq_tokens.push(SeqToken {
                     typ: SeqTokenType::Sequence,
                     len: Length::UNDEFINED,
+                    charset: self.printer.character_set(),
                 });
                 self.write_impl(&token)
             }
@@ -579,4 +657,168 @@ mod tests {
 
         validate_dataset_writer(tokens, GROUND_TRUTH);
     }
+
+    #[test]
+    fn write_trailing_padding() {
+        let mut raw_out: Vec<u8> = vec![];
+        let encoder = EncoderFor::new(ExplicitVRLittleEndianEncoder::default());
+        let mut dset_writer = DataSetWriter::new(&mut raw_out, encoder);
+
+        dset_writer
+            .write_sequence(vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0010),
+                    vr: VR::PN,
+                    len: Length(4),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("Doe")),
+            ])
+            .expect("should write data set");
+        assert_eq!(dset_writer.bytes_written(), 12);
+        // odd lengths are rounded up
+        dset_writer
+            .write_trailing_padding(3)
+            .expect("should write trailing padding");
+        assert_eq!(dset_writer.bytes_written(), 28);
+
+        #[rustfmt::skip]
+        static GROUND_TRUTH: &[u8] = &[
+            0x10, 0x00, 0x10, 0x00, // (0010, 0010) PatientName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'D', b'o', b'e', b' ',
+            // -- 12 -- padding
+            0xfc, 0xff, 0xfc, 0xff, // (fffc,fffc) DataSetTrailingPadding
+            b'O', b'B', // VR
+            0x00, 0x00, // reserved
+            0x04, 0x00, 0x00, 0x00, // length: 4
+            0x00, 0x00, 0x00, 0x00,
+        ];
+
+        assert_eq!(raw_out, GROUND_TRUTH);
+    }
+
+    #[test]
+    fn write_with_padding_policy() {
+        use crate::stateful::encode::{Padding, PaddingPolicy};
+
+        let mut raw_out: Vec<u8> = vec![];
+        let encoder = EncoderFor::new(ExplicitVRLittleEndianEncoder::default());
+        let padding = PaddingPolicy::new()
+            .ui_null_padded(false)
+            .pad(VR::PN, Padding::Null);
+        let mut dset_writer = DataSetWriter::new(&mut raw_out, encoder).with_padding(padding);
+
+        dset_writer
+            .write_sequence(vec![
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0008, 0x0018),
+                    vr: VR::UI,
+                    len: Length(5),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("1.2.3")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0010),
+                    vr: VR::PN,
+                    len: Length(3),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("Doe")),
+                DataToken::ElementHeader(DataElementHeader {
+                    tag: Tag(0x0010, 0x0020),
+                    vr: VR::LO,
+                    len: Length(3),
+                }),
+                DataToken::PrimitiveValue(PrimitiveValue::from("ID1")),
+            ])
+            .expect("should write data set");
+
+        #[rustfmt::skip]
+        static GROUND_TRUTH: &[u8] = &[
+            0x08, 0x00, 0x18, 0x00, // (0008, 0018) SOPInstanceUID
+            b'U', b'I', // VR
+            0x06, 0x00, // length: 6
+            b'1', b'.', b'2', b'.', b'3', b' ',
+            0x10, 0x00, 0x10, 0x00, // (0010, 0010) PatientName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'D', b'o', b'e', 0x00,
+            0x10, 0x00, 0x20, 0x00, // (0010, 0020) PatientID
+            b'L', b'O', // VR
+            0x04, 0x00, // length: 4
+            b'I', b'D', b'1', b' ',
+        ];
+
+        assert_eq!(raw_out, GROUND_TRUTH);
+    }
+
+    #[test]
+    fn write_character_set_scoped_to_item() {
+        let tokens = vec![
+            DataToken::ElementHeader(DataElementHeader {
+                tag: Tag(0x0008, 0x0005),
+                vr: VR::CS,
+                len: Length(10),
+            }),
+            DataToken::PrimitiveValue(PrimitiveValue::from("ISO_IR 100")),
+            DataToken::SequenceStart {
+                tag: Tag(0x0040, 0xA730),
+                len: Length::UNDEFINED,
+            },
+            DataToken::ItemStart {
+                len: Length::UNDEFINED,
+            },
+            DataToken::ElementHeader(DataElementHeader {
+                tag: Tag(0x0008, 0x0005),
+                vr: VR::CS,
+                len: Length(10),
+            }),
+            DataToken::PrimitiveValue(PrimitiveValue::from("ISO_IR 192")),
+            DataToken::ElementHeader(DataElementHeader {
+                tag: Tag(0x0010, 0x0010),
+                vr: VR::PN,
+                len: Length::UNDEFINED,
+            }),
+            DataToken::PrimitiveValue(PrimitiveValue::from("Olá")),
+            DataToken::ItemEnd,
+            DataToken::SequenceEnd,
+            DataToken::ElementHeader(DataElementHeader {
+                tag: Tag(0x0070, 0x0084),
+                vr: VR::PN,
+                len: Length::UNDEFINED,
+            }),
+            DataToken::PrimitiveValue(PrimitiveValue::from("José")),
+        ];
+
+        #[rustfmt::skip]
+        static GROUND_TRUTH: &[u8] = &[
+            0x08, 0x00, 0x05, 0x00, // (0008, 0005) SpecificCharacterSet
+            b'C', b'S', // VR
+            0x0a, 0x00, // length: 10
+            b'I', b'S', b'O', b'_', b'I', b'R', b' ', b'1', b'0', b'0',
+            0x40, 0x00, 0x30, 0xa7, // (0040, A730) ContentSequence
+            b'S', b'Q', // VR
+            0x00, 0x00, // reserved
+            0xff, 0xff, 0xff, 0xff, // length: undefined
+            0xfe, 0xff, 0x00, 0xe0, // item start
+            0xff, 0xff, 0xff, 0xff, // length: undefined
+            0x08, 0x00, 0x05, 0x00, // (0008, 0005) SpecificCharacterSet
+            b'C', b'S', // VR
+            0x0a, 0x00, // length: 10
+            b'I', b'S', b'O', b'_', b'I', b'R', b' ', b'1', b'9', b'2',
+            0x10, 0x00, 0x10, 0x00, // (0010, 0010) PatientName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'O', b'l', 0xc3, 0xa1, // "Olá" in UTF-8
+            0xfe, 0xff, 0x0d, 0xe0, // item end
+            0x00, 0x00, 0x00, 0x00,
+            0xfe, 0xff, 0xdd, 0xe0, // sequence end
+            0x00, 0x00, 0x00, 0x00,
+            0x70, 0x00, 0x84, 0x00, // (0070, 0084) ContentCreatorName
+            b'P', b'N', // VR
+            0x04, 0x00, // length: 4
+            b'J', b'o', b's', 0xe9, // "José" in ISO-8859-1
+        ];
+
+        validate_dataset_writer(tokens, GROUND_TRUTH);
+    }
 }
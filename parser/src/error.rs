@@ -0,0 +1,83 @@
+//! Classification of parsing errors.
+//!
+//! The error types of this crate are specific to each module,
+//! and form a chain of sources down to the original failure.
+//! [`ErrorKind`] condenses that chain into a single category,
+//! so that applications can react to a failure programmatically
+//! without matching on every error variant.
+use dicom_encoding::decode::Error as DecodeError;
+
+/// The category of an error which occurred while reading DICOM data.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// The data ended before the header or value being read was complete.
+    Truncated,
+    /// The underlying data source failed,
+    /// for a reason other than reaching the end of the data.
+    Io,
+    /// A data element or item header could not be decoded,
+    /// or the data does not start like DICOM content.
+    InvalidHeader,
+    /// A data element value could not be decoded
+    /// according to its value representation,
+    /// such as an invalid date or number.
+    InvalidValue,
+    /// The data set structure is inconsistent,
+    /// such as a sequence going beyond its declared length
+    /// or an element with an unexpected length.
+    InvalidStructure,
+    /// A value is longer than the limits configured for the reader,
+    /// such as the maximum value length or the allocation budget.
+    LimitExceeded,
+    /// The transfer syntax or character set is not supported.
+    Unsupported,
+    /// The error is not related to the contents of the data.
+    Other,
+}
+
+impl From<&std::io::Error> for ErrorKind {
+    fn from(e: &std::io::Error) -> Self {
+        if e.kind() == std::io::ErrorKind::UnexpectedEof {
+            ErrorKind::Truncated
+        } else {
+            ErrorKind::Io
+        }
+    }
+}
+
+impl From<&DecodeError> for ErrorKind {
+    fn from(e: &DecodeError) -> Self {
+        match e {
+            DecodeError::ReadHeaderTag { source, .. }
+            | DecodeError::ReadItemHeader { source, .. }
+            | DecodeError::ReadItemLength { source, .. }
+            | DecodeError::ReadTag { source, .. }
+            | DecodeError::ReadReserved { source, .. }
+            | DecodeError::ReadLength { source, .. }
+            | DecodeError::ReadVr { source, .. } => ErrorKind::from(source),
+            DecodeError::BadSequenceHeader { .. } => ErrorKind::InvalidHeader,
+            _ => ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ErrorKind;
+    use dicom_encoding::decode::explicit_le::ExplicitVRLittleEndianDecoder;
+    use dicom_encoding::decode::Decode;
+
+    #[test]
+    fn classify_header_errors() {
+        let decoder = ExplicitVRLittleEndianDecoder::default();
+
+        // tag and VR, but no length
+        let data: &[u8] = &[0x10, 0x00, 0x10, 0x00, b'P', b'N'];
+        let e = decoder.decode_header(&mut &data[..]).unwrap_err();
+        assert_eq!(ErrorKind::from(&e), ErrorKind::Truncated);
+
+        let e = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        assert_eq!(ErrorKind::from(&e), ErrorKind::Io);
+    }
+}
@@ -3,15 +3,23 @@
 //! ultimately enables the user to perceive the DICOM object as a sequence of
 //! tokens.
 //!
-//! For the time being, all APIs are based on synchronous I/O.
+//! Most APIs are based on synchronous I/O.
+//! Data which arrives incrementally can be parsed
+//! with the push-based reader in [`dataset::push`],
+//! and data sets can be read from `tokio` sources
+//! through the `async` feature.
+//! Token streams can be changed on the fly
+//! by the [token filters](dataset::filter) in between a reader and a writer.
 //!
 //! For a more intuitive, object-oriented API, please see the `dicom-object`
 //! crate.
 pub mod dataset;
+pub mod error;
 pub mod stateful;
 
 mod util;
 
 pub use dataset::DataSetReader;
+pub use error::ErrorKind;
 pub use stateful::decode::{DynStatefulDecoder, StatefulDecode, StatefulDecoder};
 pub use stateful::encode::StatefulEncoder;
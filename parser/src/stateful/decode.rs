@@ -2,7 +2,10 @@
 //! which also supports text decoding.
 
 use crate::util::n_times;
+use crate::ErrorKind;
+use bytes::Bytes;
 use chrono::FixedOffset;
+use dicom_core::dictionary::DataDictionary;
 use dicom_core::header::{DataElementHeader, HasLength, Length, SequenceItemHeader, Tag, VR};
 use dicom_core::value::deserialize::{
     parse_date_partial, parse_datetime_partial, parse_time_partial,
@@ -10,15 +13,16 @@ use dicom_core::value::deserialize::{
 use dicom_core::value::PrimitiveValue;
 use dicom_encoding::decode::basic::{BasicDecoder, LittleEndianBasicDecoder};
 use dicom_encoding::decode::explicit_le::ExplicitVRLittleEndianDecoder;
+use dicom_encoding::decode::implicit_le::ImplicitVRLittleEndianDecoder;
 use dicom_encoding::decode::{BasicDecode, DecodeFrom};
 use dicom_encoding::text::{
     validate_da, validate_dt, validate_tm, DefaultCharacterSetCodec, SpecificCharacterSet,
     TextCodec, TextValidationOutcome,
 };
-use dicom_encoding::transfer_syntax::{DynDecoder, TransferSyntax};
+use dicom_encoding::transfer_syntax::{DynDecoder, Endianness, TransferSyntax};
 use smallvec::smallvec;
 use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
-use std::io::Read;
+use std::io::{Cursor, Read};
 use std::iter::Iterator;
 use std::{fmt::Debug, io::Seek, io::SeekFrom};
 
@@ -131,6 +135,64 @@ pub enum Error {
     },
 }
 
+impl Error {
+    /// Classify this error.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::UnsupportedTransferSyntax { .. } | Error::UnsupportedCharacterSet { .. } => {
+                ErrorKind::Unsupported
+            }
+            Error::NonPrimitiveType { .. } | Error::UndefinedValueLength { .. } => {
+                ErrorKind::InvalidStructure
+            }
+            Error::DecodeElementHeader { source, .. } | Error::DecodeItemHeader { source, .. } => {
+                ErrorKind::from(source)
+            }
+            Error::ReadValueData { source, .. } | Error::SeekReader { source, .. } => {
+                ErrorKind::from(source)
+            }
+            Error::DecodeText { .. }
+            | Error::DeserializeValue { .. }
+            | Error::ReadInt { .. }
+            | Error::ReadFloat { .. }
+            | Error::InvalidDateValue { .. }
+            | Error::InvalidTimeValue { .. }
+            | Error::InvalidDateTimeValue { .. } => ErrorKind::InvalidValue,
+        }
+    }
+
+    /// Retrieve the position in the source at which the error occurred,
+    /// if known.
+    pub fn position(&self) -> Option<u64> {
+        match self {
+            Error::UnsupportedTransferSyntax { .. } | Error::UnsupportedCharacterSet { .. } => None,
+            Error::NonPrimitiveType { position, .. }
+            | Error::UndefinedValueLength { position, .. }
+            | Error::DecodeElementHeader { position, .. }
+            | Error::DecodeItemHeader { position, .. }
+            | Error::DecodeText { position, .. }
+            | Error::ReadValueData { position, .. }
+            | Error::SeekReader { position, .. }
+            | Error::DeserializeValue { position, .. }
+            | Error::ReadInt { position, .. }
+            | Error::ReadFloat { position, .. }
+            | Error::InvalidDateValue { position, .. }
+            | Error::InvalidTimeValue { position, .. }
+            | Error::InvalidDateTimeValue { position, .. } => Some(*position),
+        }
+    }
+
+    /// Retrieve the tag of the data element being decoded
+    /// when the error occurred,
+    /// if known.
+    pub fn tag(&self) -> Option<Tag> {
+        match self {
+            Error::UndefinedValueLength { tag, .. } => Some(*tag),
+            _ => None,
+        }
+    }
+}
+
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 pub trait StatefulDecode {
@@ -206,6 +268,39 @@ pub trait StatefulDecode {
     /// If the stateful decoder was constructed at the beginning of the reader,
     /// this equals to the number of bytes read so far.
     fn position(&self) -> u64;
+
+    /// Decode subsequent element and item headers
+    /// in implicit VR little endian if `implicit_vr` is true,
+    /// or resume decoding them with the original decoder otherwise.
+    ///
+    /// This is used for reading the contents of a sequence
+    /// encoded with the VR UN and an undefined length,
+    /// which are always in implicit VR little endian (CP-246).
+    /// Returns whether the decoder supports this switch,
+    /// which is only the case for little endian data sets.
+    /// The default implementation does not support it.
+    fn set_implicit_vr(&mut self, implicit_vr: bool) -> bool {
+        let _ = implicit_vr;
+        false
+    }
+
+    /// Retrieve the character set currently used for decoding text,
+    /// if the decoder keeps track of it.
+    ///
+    /// The default implementation returns `None`.
+    fn character_set(&self) -> Option<SpecificCharacterSet> {
+        None
+    }
+
+    /// Replace the character set used for decoding text,
+    /// such as when leaving an item
+    /// with its own _Specific Character Set_ (0008,0005).
+    /// Returns whether the decoder supports this replacement.
+    /// The default implementation does not support it.
+    fn replace_character_set(&mut self, charset: SpecificCharacterSet) -> bool {
+        let _ = charset;
+        false
+    }
 }
 
 /// Alias for a dynamically resolved DICOM stateful decoder. Although the data
@@ -234,6 +329,31 @@ pub struct StatefulDecoder<D, S, BD = BasicDecoder, TC = SpecificCharacterSet> {
     buffer: Vec<u8>,
     /// the assumed position of the reader source
     position: u64,
+    /// whether headers are decoded in implicit VR little endian
+    /// instead of through `decoder`
+    implicit_vr: bool,
+    /// the buffer shared with the decoded values, if any
+    shared: Option<SharedSource<S>>,
+}
+
+/// A buffer with the full contents of a decoder's source,
+/// from which values are obtained without copying.
+struct SharedSource<S> {
+    /// the contents of the source
+    data: Bytes,
+    /// the assumed position of the reader source at the start of `data`
+    origin: u64,
+    /// move the reader source forward by the given number of bytes
+    advance: fn(&mut S, u64),
+}
+
+impl<S> Debug for SharedSource<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedSource")
+            .field("data", &self.data)
+            .field("origin", &self.origin)
+            .finish()
+    }
 }
 
 impl<S> StatefulDecoder<DynDecoder<S>, S> {
@@ -258,6 +378,31 @@ impl<S> StatefulDecoder<DynDecoder<S>, S> {
         ))
     }
 
+    /// Create a new DICOM parser for the given transfer syntax, character set,
+    /// and assumed position of the reader source,
+    /// using the given data dictionary
+    /// to resolve value representations in implicit VR transfer syntaxes.
+    pub fn new_with_dict<D>(
+        from: S,
+        dict: D,
+        ts: &TransferSyntax,
+        charset: SpecificCharacterSet,
+        position: u64,
+    ) -> Result<Self>
+    where
+        S: Read,
+        D: DataDictionary + Send + 'static,
+    {
+        let basic = ts.basic_decoder();
+        let decoder = ts
+            .decoder_for_with_dict::<S, D>(dict)
+            .context(UnsupportedTransferSyntaxSnafu { ts: ts.name() })?;
+
+        Ok(StatefulDecoder::new_with_position(
+            from, decoder, basic, charset, position,
+        ))
+    }
+
     /// Create a new DICOM parser for the given transfer syntax
     /// and assumed position of the reader source.
     ///
@@ -294,6 +439,8 @@ where
             dt_utc_offset: FixedOffset::east(0),
             buffer: Vec::with_capacity(PARSER_BUFFER_CAPACITY),
             position: 0,
+            implicit_vr: false,
+            shared: None,
         }
     }
 }
@@ -325,8 +472,74 @@ where
             dt_utc_offset: FixedOffset::east(0),
             buffer: Vec::with_capacity(PARSER_BUFFER_CAPACITY),
             position,
+            implicit_vr: false,
+            shared: None,
         }
     }
+
+    /// Retrieve a reference to the underlying reader source.
+    pub(crate) fn source(&self) -> &S {
+        &self.from
+    }
+
+    /// Retrieve a mutable reference to the underlying reader source.
+    ///
+    /// Reading from the source directly
+    /// leaves the decoder's assumed position out of sync.
+    pub(crate) fn source_mut(&mut self) -> &mut S {
+        &mut self.from
+    }
+}
+
+impl<D, BD, TC> StatefulDecoder<D, Cursor<Bytes>, BD, TC>
+where
+    BD: BasicDecode,
+    TC: TextCodec,
+{
+    /// Create a new DICOM stateful decoder from its parts,
+    /// reading from an in-memory buffer
+    /// which is shared with the decoded values,
+    /// while assuming a base reading position.
+    ///
+    /// Values in OB and UN
+    /// are obtained as [slices of `data`](PrimitiveValue::SharedBytes)
+    /// without copying.
+    /// Values in other binary representations such as OW
+    /// are decoded to their natural width, as with any other source.
+    pub fn new_shared(data: Bytes, decoder: D, basic: BD, text: TC, position: u64) -> Self {
+        let from = Cursor::new(data.clone());
+        let mut decoder = Self::new_with_position(from, decoder, basic, text, position);
+        decoder.shared = Some(SharedSource {
+            data,
+            origin: position,
+            advance: |from, n| from.set_position(from.position() + n),
+        });
+        decoder
+    }
+}
+
+impl StatefulDecoder<DynDecoder<Cursor<Bytes>>, Cursor<Bytes>> {
+    /// Create a new DICOM parser for the given transfer syntax, character set,
+    /// and assumed position of the reader source,
+    /// reading from an in-memory buffer
+    /// which is shared with the decoded values.
+    ///
+    /// See [`new_shared`](StatefulDecoder::new_shared) for more details.
+    pub fn new_shared_with(
+        data: Bytes,
+        ts: &TransferSyntax,
+        charset: SpecificCharacterSet,
+        position: u64,
+    ) -> Result<Self> {
+        let basic = ts.basic_decoder();
+        let decoder = ts
+            .decoder_for::<Cursor<Bytes>>()
+            .context(UnsupportedTransferSyntaxSnafu { ts: ts.name() })?;
+
+        Ok(StatefulDecoder::new_shared(
+            data, decoder, basic, charset, position,
+        ))
+    }
 }
 
 impl<D, S, BD, TC> StatefulDecoder<D, S, BD, TC>
@@ -388,11 +601,36 @@ where
         Ok(PrimitiveValue::Tags(parts?))
     }
 
+    /// Obtain the next `len` bytes of the source
+    /// as a slice of the shared buffer, if the decoder has one.
+    fn read_shared(&mut self, len: usize) -> Result<Option<Bytes>> {
+        let shared = match &self.shared {
+            Some(shared) => shared,
+            None => return Ok(None),
+        };
+        let start = (self.position - shared.origin) as usize;
+        if start + len > shared.data.len() {
+            return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof)).context(
+                ReadValueDataSnafu {
+                    position: self.position,
+                },
+            );
+        }
+        let bytes = shared.data.slice(start..start + len);
+        (shared.advance)(&mut self.from, len as u64);
+        self.position += len as u64;
+        Ok(Some(bytes))
+    }
+
     fn read_value_ob(&mut self, header: &DataElementHeader) -> Result<PrimitiveValue> {
         // Note: this function always expects a defined length OB value
         // (pixel sequence detection needs to be done by the caller)
         let len = self.require_known_length(header)?;
 
+        if let Some(bytes) = self.read_shared(len)? {
+            return Ok(PrimitiveValue::SharedBytes(bytes));
+        }
+
         // sequence of 8-bit integers (or arbitrary byte data)
         let mut buf = smallvec![0u8; len];
         self.from.read_exact(&mut buf).context(ReadValueDataSnafu {
@@ -863,6 +1101,18 @@ where
         (**self).position()
     }
 
+    fn set_implicit_vr(&mut self, implicit_vr: bool) -> bool {
+        (**self).set_implicit_vr(implicit_vr)
+    }
+
+    fn character_set(&self) -> Option<SpecificCharacterSet> {
+        (**self).character_set()
+    }
+
+    fn replace_character_set(&mut self, charset: SpecificCharacterSet) -> bool {
+        (**self).replace_character_set(charset)
+    }
+
     fn seek(&mut self, position: u64) -> Result<()>
     where
         Self::Reader: Seek,
@@ -880,8 +1130,15 @@ where
     type Reader = S;
 
     fn decode_header(&mut self) -> Result<DataElementHeader> {
-        self.decoder
-            .decode_header(&mut self.from)
+        let result = if self.implicit_vr {
+            DecodeFrom::<S>::decode_header(
+                &ImplicitVRLittleEndianDecoder::default(),
+                &mut self.from,
+            )
+        } else {
+            self.decoder.decode_header(&mut self.from)
+        };
+        result
             .context(DecodeElementHeaderSnafu {
                 position: self.position,
             })
@@ -893,8 +1150,15 @@ where
     }
 
     fn decode_item_header(&mut self) -> Result<SequenceItemHeader> {
-        self.decoder
-            .decode_item_header(&mut self.from)
+        let result = if self.implicit_vr {
+            DecodeFrom::<S>::decode_item_header(
+                &ImplicitVRLittleEndianDecoder::default(),
+                &mut self.from,
+            )
+        } else {
+            self.decoder.decode_item_header(&mut self.from)
+        };
+        result
             .context(DecodeItemHeaderSnafu {
                 position: self.position,
             })
@@ -1003,6 +1267,23 @@ where
         self.position
     }
 
+    fn set_implicit_vr(&mut self, implicit_vr: bool) -> bool {
+        if self.basic.endianness() != Endianness::Little {
+            return false;
+        }
+        self.implicit_vr = implicit_vr;
+        true
+    }
+
+    fn character_set(&self) -> Option<SpecificCharacterSet> {
+        Some(self.text)
+    }
+
+    fn replace_character_set(&mut self, charset: SpecificCharacterSet) -> bool {
+        self.text = charset;
+        true
+    }
+
     fn read_to_vec(&mut self, length: u32, vec: &mut Vec<u8>) -> Result<()> {
         self.read_to(length, vec)
     }
@@ -1065,6 +1346,7 @@ fn trim_trail_empty_bytes(mut x: &[u8]) -> &[u8] {
 mod tests {
     use super::{StatefulDecode, StatefulDecoder};
     use dicom_core::header::{DataElementHeader, HasLength, Header, Length, SequenceItemHeader};
+    use dicom_core::value::PrimitiveValue;
     use dicom_core::{Tag, VR};
     use dicom_encoding::decode::basic::LittleEndianBasicDecoder;
     use dicom_encoding::decode::{
@@ -1479,4 +1761,42 @@ mod tests {
 
         assert_eq!(decoder.position(), 138);
     }
+
+    #[test]
+    fn read_values_from_shared_buffer() {
+        #[rustfmt::skip]
+        let data = bytes::Bytes::from_static(&[
+            // (0009,1001) OB, len 4
+            0x09, 0x00, 0x01, 0x10, b'O', b'B', 0x00, 0x00, 0x04, 0x00, 0x00, 0x00,
+            0x01, 0x02, 0x03, 0x04,
+            // (0010,0020) PatientID, LO, len 4
+            0x10, 0x00, 0x20, 0x00, b'L', b'O', 0x04, 0x00,
+            b'1', b'2', b'3', b'4',
+        ]);
+        let mut decoder = StatefulDecoder::new_shared(
+            data.clone(),
+            ExplicitVRLittleEndianDecoder::default(),
+            LittleEndianBasicDecoder,
+            SpecificCharacterSet::Default,
+            0,
+        );
+
+        let header = decoder.decode_header().unwrap();
+        let value = decoder.read_value(&header).unwrap();
+        match &value {
+            PrimitiveValue::SharedBytes(bytes) => {
+                // the value points to the original buffer
+                assert_eq!(bytes.as_ptr(), data[12..].as_ptr());
+            }
+            value => panic!("expected shared bytes, got {:?}", value),
+        }
+        assert_eq!(value, PrimitiveValue::from(vec![0x01_u8, 0x02, 0x03, 0x04]));
+        assert_eq!(decoder.position(), 16);
+
+        let header = decoder.decode_header().unwrap();
+        assert_eq!(header.tag(), Tag(0x0010, 0x0020));
+        let value = decoder.read_value(&header).unwrap();
+        assert_eq!(value.to_str(), "1234");
+        assert_eq!(decoder.position(), 28);
+    }
 }
@@ -2,6 +2,8 @@
 //!
 //! The [`StatefulEncoder`] supports encoding of binary data and text
 //! while applying the necessary padding to conform to DICOM encoding rules.
+//! The padding of textual values can be configured
+//! through a [`PaddingPolicy`].
 
 use dicom_core::{value::PrimitiveValue, DataElementHeader, Length, Tag, VR};
 use dicom_encoding::transfer_syntax::DynEncoder;
@@ -50,6 +52,139 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// A character used to pad a textual value to an even length.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum Padding {
+    /// A space character (`0x20`),
+    /// the standard padding for all textual values but UIDs.
+    Space,
+    /// A null character (`0x00`),
+    /// the standard padding for UIDs.
+    Null,
+}
+
+impl Padding {
+    fn byte(self) -> u8 {
+        match self {
+            Padding::Space => b' ',
+            Padding::Null => b'\0',
+        }
+    }
+}
+
+/// The textual value representations subject to a padding policy.
+const TEXT_VRS: [VR; 17] = [
+    VR::AE,
+    VR::AS,
+    VR::CS,
+    VR::DA,
+    VR::DS,
+    VR::DT,
+    VR::IS,
+    VR::LO,
+    VR::LT,
+    VR::PN,
+    VR::SH,
+    VR::ST,
+    VR::TM,
+    VR::UC,
+    VR::UI,
+    VR::UR,
+    VR::UT,
+];
+
+/// A policy for padding textual values of odd length when encoding.
+///
+/// By default, values are padded as prescribed by the standard:
+/// UIDs with a null character,
+/// and all other textual values with a space.
+/// Some devices and validators expect otherwise,
+/// so the padding character can be replaced for each value representation.
+/// Binary values are always padded with a zero byte.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_core::VR;
+/// # use dicom_parser::stateful::encode::{Padding, PaddingPolicy};
+/// let policy = PaddingPolicy::new()
+///     .ui_null_padded(false)
+///     .pad(VR::PN, Padding::Null);
+/// assert_eq!(policy.padding(VR::UI), Some(Padding::Space));
+/// assert_eq!(policy.padding(VR::PN), Some(Padding::Null));
+/// assert_eq!(policy.padding(VR::LO), Some(Padding::Space));
+/// assert_eq!(policy.padding(VR::OB), None);
+/// ```
+#[derive(Debug, Default, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct PaddingPolicy {
+    /// bit flags by index in `TEXT_VRS`
+    /// of the value representations not padded as in the standard
+    overrides: u32,
+}
+
+impl PaddingPolicy {
+    /// Create a padding policy following the standard.
+    pub fn new() -> Self {
+        PaddingPolicy::default()
+    }
+
+    /// Set the padding character of values with the given
+    /// textual value representation.
+    ///
+    /// Value representations which are not textual are ignored.
+    pub fn pad(mut self, vr: VR, padding: Padding) -> Self {
+        if let Some(i) = TEXT_VRS.iter().position(|v| *v == vr) {
+            if padding == standard_padding(vr) {
+                self.overrides &= !(1 << i);
+            } else {
+                self.overrides |= 1 << i;
+            }
+        }
+        self
+    }
+
+    /// Set whether UIDs are padded with a null character,
+    /// as in the standard,
+    /// or with a space otherwise.
+    pub fn ui_null_padded(self, null_padded: bool) -> Self {
+        let padding = if null_padded {
+            Padding::Null
+        } else {
+            Padding::Space
+        };
+        self.pad(VR::UI, padding)
+    }
+
+    /// Get the padding character of values
+    /// with the given value representation,
+    /// or `None` if the value representation is not textual.
+    pub fn padding(&self, vr: VR) -> Option<Padding> {
+        let i = TEXT_VRS.iter().position(|v| *v == vr)?;
+        let padding = standard_padding(vr);
+        if self.overrides & (1 << i) == 0 {
+            Some(padding)
+        } else if padding == Padding::Space {
+            Some(Padding::Null)
+        } else {
+            Some(Padding::Space)
+        }
+    }
+
+    /// Get the byte with which to pad a value
+    /// of the given value representation.
+    fn pad_byte(&self, vr: VR) -> u8 {
+        self.padding(vr).map(Padding::byte).unwrap_or(0)
+    }
+}
+
+fn standard_padding(vr: VR) -> Padding {
+    if vr == VR::UI {
+        Padding::Null
+    } else {
+        Padding::Space
+    }
+}
+
 /// Also called a printer, this encoder type provides a stateful mid-level
 /// abstraction for writing DICOM content. Unlike `Encode`,
 /// the stateful encoder knows how to write text values and keeps track
@@ -62,6 +197,7 @@ pub struct StatefulEncoder<W, E, T = SpecificCharacterSet> {
     text: T,
     bytes_written: u64,
     buffer: Vec<u8>,
+    padding: PaddingPolicy,
 }
 
 pub type DynStatefulEncoder<'w> = StatefulEncoder<Box<dyn Write + 'w>, DynEncoder<'w, dyn Write>>;
@@ -74,8 +210,24 @@ impl<W, E, T> StatefulEncoder<W, E, T> {
             text,
             bytes_written: 0,
             buffer: Vec::with_capacity(128),
+            padding: PaddingPolicy::default(),
         }
     }
+
+    /// Set the policy for padding textual values of odd length.
+    pub fn set_padding(&mut self, padding: PaddingPolicy) {
+        self.padding = padding;
+    }
+
+    /// Retrieve the policy for padding textual values of odd length.
+    pub fn padding(&self) -> PaddingPolicy {
+        self.padding
+    }
+
+    /// Obtain a mutable reference to the inner writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.to
+    }
 }
 
 impl<'s> DynStatefulEncoder<'s> {
@@ -91,6 +243,20 @@ impl<'s> DynStatefulEncoder<'s> {
     }
 }
 
+impl<W, E> StatefulEncoder<W, E> {
+    /// Retrieve the character set currently used for encoding text.
+    pub fn character_set(&self) -> SpecificCharacterSet {
+        self.text
+    }
+
+    /// Replace the character set used for encoding text,
+    /// such as when leaving an item
+    /// with its own _Specific Character Set_ (0008,0005).
+    pub fn set_character_set(&mut self, charset: SpecificCharacterSet) {
+        self.text = charset;
+    }
+}
+
 impl<W, E> StatefulEncoder<W, E>
 where
     W: Write,
@@ -225,6 +391,10 @@ where
                 self.encode_texts_element(&texts[..], *de)?;
                 Ok(())
             }
+            PrimitiveValue::SharedStrs(texts) => {
+                self.encode_texts_element(&texts[..], *de)?;
+                Ok(())
+            }
             _ => {
                 let byte_len = value.calculate_byte_len();
                 self.encode_element_header(DataElementHeader {
@@ -240,7 +410,8 @@ where
 
                 self.bytes_written += bytes as u64;
                 if bytes % 2 != 0 {
-                    self.to.write_all(&[0]).context(WriteValueDataSnafu {
+                    let pad = self.padding.pad_byte(de.vr);
+                    self.to.write_all(&[pad]).context(WriteValueDataSnafu {
                         position: self.bytes_written,
                     })?;
                     self.bytes_written += 1;
@@ -287,6 +458,18 @@ where
                 }
                 Ok(())
             }
+            PrimitiveValue::SharedStrs(texts) => {
+                self.encode_texts(&texts[..], de.vr())?;
+
+                // if element is Specific Character Set,
+                // update the text codec
+                if de.tag == Tag(0x0008, 0x0005) {
+                    if let Some(charset_name) = texts.first() {
+                        self.try_new_codec(charset_name);
+                    }
+                }
+                Ok(())
+            }
             _ => {
                 let bytes = self.encoder.encode_primitive(&mut self.to, value).context(
                     EncodeDataSnafu {
@@ -296,7 +479,8 @@ where
 
                 self.bytes_written += bytes as u64;
                 if bytes % 2 != 0 {
-                    self.to.write_all(&[0]).context(WriteValueDataSnafu {
+                    let pad = self.padding.pad_byte(de.vr);
+                    self.to.write_all(&[pad]).context(WriteValueDataSnafu {
                         position: self.bytes_written,
                     })?;
                     self.bytes_written += 1;
@@ -318,8 +502,8 @@ where
         let bytes = self.encode_text_untrailed(text, vr)?;
         // pad to even length
         if bytes % 2 == 1 {
-            let pad = if vr == VR::UI { b"\0" } else { b" " };
-            self.to.write_all(pad).context(WriteValueDataSnafu {
+            let pad = self.padding.pad_byte(vr);
+            self.to.write_all(&[pad]).context(WriteValueDataSnafu {
                 position: self.bytes_written,
             })?;
             self.bytes_written += 1;
@@ -332,7 +516,7 @@ where
         let mut encoded_value = self.convert_text_untrailed(text, de.vr)?;
         // pad to even length
         if encoded_value.len() % 2 == 1 {
-            let pad = if de.vr == VR::UI { b'\0' } else { b' ' };
+            let pad = self.padding.pad_byte(de.vr);
             encoded_value.push(pad);
         }
 
@@ -372,7 +556,7 @@ where
         }
         // pad to even length
         if self.buffer.len() % 2 == 1 {
-            let pad = if de.vr == VR::UI { b'\0' } else { b' ' };
+            let pad = self.padding.pad_byte(de.vr);
             self.buffer.push(pad);
         }
 
@@ -418,8 +602,8 @@ where
         }
         // pad to even length
         if acc % 2 == 1 {
-            let pad = if vr == VR::UI { b"\0" } else { b" " };
-            self.to.write_all(pad).context(WriteValueDataSnafu {
+            let pad = self.padding.pad_byte(vr);
+            self.to.write_all(&[pad]).context(WriteValueDataSnafu {
                 position: self.bytes_written,
             })?;
             self.bytes_written += 1;
@@ -0,0 +1,29 @@
+//! Benchmarks for decoding pixel data.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use dicom_object::open_file;
+use dicom_pixeldata::PixelDecoder;
+
+/// Test files with different pixel data encodings, by description.
+const FILES: [(&str, &str); 3] = [
+    // native 16-bit monochrome
+    ("native_ct", "pydicom/CT_small.dcm"),
+    // RLE lossless 8-bit RGB, 2 frames
+    ("rle_rgb", "pydicom/SC_rgb_rle_2frame.dcm"),
+    // RLE lossless 16-bit RGB, 2 frames
+    ("rle_rgb_16bit", "pydicom/SC_rgb_rle_16bit_2frame.dcm"),
+];
+
+fn bench_decode_pixel_data(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_pixel_data");
+    for (name, file) in FILES {
+        let path = dicom_test_files::path(file).unwrap();
+        let obj = open_file(path).unwrap();
+        group.bench_with_input(BenchmarkId::from_parameter(name), &obj, |b, obj| {
+            b.iter(|| black_box(obj.decode_pixel_data().unwrap()))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_decode_pixel_data);
+criterion_main!(benches);
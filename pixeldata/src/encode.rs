@@ -0,0 +1,629 @@
+//! Encoding of pixel data.
+//!
+//! [`NativePixelDataWriter`] collects image frames
+//! from flat sample buffers or [`ndarray`] arrays,
+//! and writes them into a DICOM object as native (uncompressed) pixel data,
+//! along with the image pixel attributes which describe it.
+//!
+//! [`EncapsulatedPixelDataWriter`] collects frames
+//! which were already compressed,
+//! and writes them into a DICOM object as encapsulated pixel data.
+
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{DataDictionary, DataElement, VR};
+use dicom_dictionary_std::tags;
+use dicom_object::InMemDicomObject;
+use ndarray::{ArrayView3, ArrayView4, Axis};
+use snafu::{ensure, OptionExt};
+use std::convert::TryFrom;
+
+use crate::{
+    InvalidBitsStoredSnafu, InvalidFrameLengthSnafu, NoFramesSnafu, OffsetTableOverflowSnafu,
+    PhotometricInterpretation, PixelRepresentation, PlanarConfiguration, Result,
+};
+
+/// A type which can be used as a native pixel data sample.
+///
+/// This is implemented for `u8`, `u16` and `i16`.
+pub trait NativeSample: Copy {
+    /// The value of _Bits Allocated_ for samples of this type.
+    const BITS_ALLOCATED: u16;
+
+    /// The pixel representation of samples of this type.
+    const PIXEL_REPRESENTATION: PixelRepresentation;
+
+    /// Turn the given samples into a pixel data value,
+    /// padded to an even length if necessary.
+    fn into_pixel_data(samples: Vec<Self>) -> PrimitiveValue;
+}
+
+impl NativeSample for u8 {
+    const BITS_ALLOCATED: u16 = 8;
+    const PIXEL_REPRESENTATION: PixelRepresentation = PixelRepresentation::Unsigned;
+
+    fn into_pixel_data(mut samples: Vec<Self>) -> PrimitiveValue {
+        if samples.len() % 2 != 0 {
+            samples.push(0);
+        }
+        PrimitiveValue::U8(samples.into())
+    }
+}
+
+impl NativeSample for u16 {
+    const BITS_ALLOCATED: u16 = 16;
+    const PIXEL_REPRESENTATION: PixelRepresentation = PixelRepresentation::Unsigned;
+
+    fn into_pixel_data(samples: Vec<Self>) -> PrimitiveValue {
+        PrimitiveValue::U16(samples.into())
+    }
+}
+
+impl NativeSample for i16 {
+    const BITS_ALLOCATED: u16 = 16;
+    const PIXEL_REPRESENTATION: PixelRepresentation = PixelRepresentation::Signed;
+
+    fn into_pixel_data(samples: Vec<Self>) -> PrimitiveValue {
+        // OW values are read back as unsigned words
+        PrimitiveValue::U16(samples.into_iter().map(|s| s as u16).collect())
+    }
+}
+
+/// A builder for native pixel data.
+///
+/// Frames are given with their samples in row-major order,
+/// and the samples of each pixel contiguous,
+/// regardless of the planar configuration in the output.
+/// Samples are written with _Bits Allocated_ following the sample type `T`,
+/// and the other image pixel attributes set accordingly.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_object::InMemDicomObject;
+/// use dicom_pixeldata::NativePixelDataWriter;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let mut obj = InMemDicomObject::new_empty();
+///
+/// let mut writer = NativePixelDataWriter::<u16>::new(2, 2).bits_stored(12);
+/// writer.push_frame(&[0, 1024, 2048, 4095])?;
+/// writer.push_frame(&[4095, 2048, 1024, 0])?;
+/// writer.write_to(&mut obj)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativePixelDataWriter<T> {
+    rows: u16,
+    columns: u16,
+    samples_per_pixel: u16,
+    photometric_interpretation: Option<PhotometricInterpretation>,
+    planar_configuration: PlanarConfiguration,
+    bits_stored: u16,
+    number_of_frames: u32,
+    samples: Vec<T>,
+}
+
+impl<T> NativePixelDataWriter<T>
+where
+    T: NativeSample,
+{
+    /// Create a new pixel data writer for single sample frames
+    /// of the given size.
+    pub fn new(rows: u16, columns: u16) -> Self {
+        NativePixelDataWriter {
+            rows,
+            columns,
+            samples_per_pixel: 1,
+            photometric_interpretation: None,
+            planar_configuration: PlanarConfiguration::Standard,
+            bits_stored: T::BITS_ALLOCATED,
+            number_of_frames: 0,
+            samples: Vec::new(),
+        }
+    }
+
+    /// Set the number of samples per pixel.
+    ///
+    /// Unless set explicitly, the photometric interpretation
+    /// is `MONOCHROME2` for single sample pixels and `RGB` otherwise.
+    pub fn samples_per_pixel(mut self, samples_per_pixel: u16) -> Self {
+        self.samples_per_pixel = samples_per_pixel;
+        self
+    }
+
+    /// Set the photometric interpretation of the pixel data.
+    pub fn photometric_interpretation(
+        mut self,
+        photometric_interpretation: PhotometricInterpretation,
+    ) -> Self {
+        self.photometric_interpretation = Some(photometric_interpretation);
+        self
+    }
+
+    /// Set the planar configuration of the encoded pixel data.
+    ///
+    /// This only applies to pixels with more than one sample.
+    pub fn planar_configuration(mut self, planar_configuration: PlanarConfiguration) -> Self {
+        self.planar_configuration = planar_configuration;
+        self
+    }
+
+    /// Set the number of bits stored in each sample.
+    ///
+    /// The high bit is always set to one less than this value.
+    /// By default, all allocated bits are stored.
+    pub fn bits_stored(mut self, bits_stored: u16) -> Self {
+        self.bits_stored = bits_stored;
+        self
+    }
+
+    /// Retrieve the number of frames added so far.
+    #[inline]
+    pub fn number_of_frames(&self) -> u32 {
+        self.number_of_frames
+    }
+
+    fn frame_length(&self) -> usize {
+        self.rows as usize * self.columns as usize * self.samples_per_pixel as usize
+    }
+
+    /// Add a frame from a flat buffer of samples.
+    ///
+    /// Fails if the buffer does not have exactly
+    /// _Rows_ × _Columns_ × _Samples per Pixel_ samples.
+    pub fn push_frame(&mut self, frame: &[T]) -> Result<()> {
+        let expected = self.frame_length();
+        ensure!(
+            frame.len() == expected,
+            InvalidFrameLengthSnafu {
+                expected,
+                got: frame.len(),
+            }
+        );
+        self.samples.extend_from_slice(frame);
+        self.number_of_frames += 1;
+        Ok(())
+    }
+
+    /// Add a frame from an array of shape
+    /// (rows, columns, samples per pixel).
+    pub fn push_frame_array(&mut self, frame: ArrayView3<T>) -> Result<()> {
+        let expected = self.frame_length();
+        ensure!(
+            frame.dim()
+                == (
+                    self.rows as usize,
+                    self.columns as usize,
+                    self.samples_per_pixel as usize
+                ),
+            InvalidFrameLengthSnafu {
+                expected,
+                got: frame.len(),
+            }
+        );
+        self.samples.extend(frame.iter().copied());
+        self.number_of_frames += 1;
+        Ok(())
+    }
+
+    /// Add all frames of an array of shape
+    /// (frames, rows, columns, samples per pixel),
+    /// as obtained from [`to_ndarray`](crate::DecodedPixelData::to_ndarray).
+    pub fn push_frames_array(&mut self, frames: ArrayView4<T>) -> Result<()> {
+        for frame in frames.axis_iter(Axis(0)) {
+            self.push_frame_array(frame)?;
+        }
+        Ok(())
+    }
+
+    /// Write the pixel data and the image pixel attributes
+    /// into the given DICOM object,
+    /// replacing any previous pixel data.
+    ///
+    /// _Number of Frames_ is only written for multi-frame pixel data,
+    /// and removed from the object otherwise.
+    pub fn write_to<D>(self, obj: &mut InMemDicomObject<D>) -> Result<()>
+    where
+        D: DataDictionary + Clone,
+    {
+        ensure!(self.number_of_frames > 0, NoFramesSnafu);
+        ensure!(
+            self.bits_stored > 0 && self.bits_stored <= T::BITS_ALLOCATED,
+            InvalidBitsStoredSnafu {
+                bits_stored: self.bits_stored,
+                bits_allocated: T::BITS_ALLOCATED,
+            }
+        );
+
+        let spp = self.samples_per_pixel;
+        let photometric_interpretation =
+            self.photometric_interpretation
+                .clone()
+                .unwrap_or(if spp == 1 {
+                    PhotometricInterpretation::Monochrome2
+                } else {
+                    PhotometricInterpretation::Rgb
+                });
+        let planar = spp > 1 && self.planar_configuration == PlanarConfiguration::PixelFirst;
+        let samples = if planar {
+            to_planes(&self.samples, self.frame_length(), spp as usize)
+        } else {
+            self.samples
+        };
+
+        obj.put(DataElement::new(tags::SAMPLES_PER_PIXEL, VR::US, us(spp)));
+        obj.put(DataElement::new(
+            tags::PHOTOMETRIC_INTERPRETATION,
+            VR::CS,
+            PrimitiveValue::from(photometric_interpretation.as_str()),
+        ));
+        if spp > 1 {
+            obj.put(DataElement::new(
+                tags::PLANAR_CONFIGURATION,
+                VR::US,
+                us(self.planar_configuration as u16),
+            ));
+        } else {
+            obj.remove_element(tags::PLANAR_CONFIGURATION);
+        }
+        if self.number_of_frames > 1 {
+            obj.put(DataElement::new(
+                tags::NUMBER_OF_FRAMES,
+                VR::IS,
+                PrimitiveValue::from(self.number_of_frames.to_string()),
+            ));
+        } else {
+            obj.remove_element(tags::NUMBER_OF_FRAMES);
+        }
+        obj.put(DataElement::new(tags::ROWS, VR::US, us(self.rows)));
+        obj.put(DataElement::new(tags::COLUMNS, VR::US, us(self.columns)));
+        obj.put(DataElement::new(
+            tags::BITS_ALLOCATED,
+            VR::US,
+            us(T::BITS_ALLOCATED),
+        ));
+        obj.put(DataElement::new(
+            tags::BITS_STORED,
+            VR::US,
+            us(self.bits_stored),
+        ));
+        obj.put(DataElement::new(
+            tags::HIGH_BIT,
+            VR::US,
+            us(self.bits_stored - 1),
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_REPRESENTATION,
+            VR::US,
+            us(T::PIXEL_REPRESENTATION as u16),
+        ));
+        let vr = if T::BITS_ALLOCATED == 8 {
+            VR::OB
+        } else {
+            VR::OW
+        };
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            vr,
+            T::into_pixel_data(samples),
+        ));
+        Ok(())
+    }
+}
+
+/// A builder for encapsulated pixel data.
+///
+/// Each frame is given as a single buffer of compressed data,
+/// which is split into fragments of at most the configured size.
+/// The resulting pixel data element has an undefined length
+/// and starts with a Basic Offset Table
+/// pointing to the first fragment of each frame,
+/// as expected for JPEG, JPEG 2000 and RLE transfer syntaxes.
+///
+/// Note that RLE Lossless requires exactly one fragment per frame,
+/// so the fragment size should be left unset in that case.
+///
+/// # Example
+///
+/// ```
+/// # use dicom_object::InMemDicomObject;
+/// use dicom_pixeldata::EncapsulatedPixelDataWriter;
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # let jpeg_frames: Vec<Vec<u8>> = vec![vec![0xFF, 0xD8, 0xFF, 0xD9]];
+/// let mut obj = InMemDicomObject::new_empty();
+///
+/// let mut writer = EncapsulatedPixelDataWriter::new().fragment_size(8192);
+/// for frame in &jpeg_frames {
+///     writer.push_frame(frame)?;
+/// }
+/// writer.write_to(&mut obj)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct EncapsulatedPixelDataWriter {
+    fragment_size: Option<usize>,
+    offset_table: Vec<u32>,
+    fragments: Vec<Vec<u8>>,
+    /// the position of the next fragment item,
+    /// relative to the first fragment item
+    next_offset: u64,
+}
+
+impl EncapsulatedPixelDataWriter {
+    /// Create a new encapsulated pixel data writer
+    /// which writes each frame in a single fragment.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the maximum size of each fragment in bytes.
+    ///
+    /// Since fragments must have an even length,
+    /// odd sizes are rounded down.
+    pub fn fragment_size(mut self, fragment_size: usize) -> Self {
+        self.fragment_size = Some((fragment_size & !1).max(2));
+        self
+    }
+
+    /// Retrieve the number of frames added so far.
+    #[inline]
+    pub fn number_of_frames(&self) -> u32 {
+        self.offset_table.len() as u32
+    }
+
+    /// Retrieve the number of fragments produced so far.
+    #[inline]
+    pub fn number_of_fragments(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Add a frame of compressed data.
+    ///
+    /// The last fragment of the frame is padded
+    /// with a trailing zero byte if it has an odd length.
+    ///
+    /// Fails if the offset of the frame
+    /// does not fit in the Basic Offset Table.
+    pub fn push_frame(&mut self, frame: &[u8]) -> Result<()> {
+        let offset = u32::try_from(self.next_offset)
+            .ok()
+            .context(OffsetTableOverflowSnafu)?;
+        self.offset_table.push(offset);
+
+        let fragment_size = self.fragment_size.unwrap_or(usize::MAX);
+        let mut chunks: Vec<&[u8]> = frame.chunks(fragment_size).collect();
+        if chunks.is_empty() {
+            chunks.push(&[]);
+        }
+        for chunk in chunks {
+            let mut fragment = chunk.to_vec();
+            if fragment.len() % 2 != 0 {
+                fragment.push(0);
+            }
+            // item header + item value
+            self.next_offset += 8 + fragment.len() as u64;
+            self.fragments.push(fragment);
+        }
+        Ok(())
+    }
+
+    /// Write the encapsulated pixel data into the given DICOM object,
+    /// replacing any previous pixel data.
+    ///
+    /// _Number of Frames_ is always written,
+    /// even for a single frame.
+    /// The remaining image pixel attributes
+    /// must be set by the caller.
+    pub fn write_to<D>(self, obj: &mut InMemDicomObject<D>) -> Result<()>
+    where
+        D: DataDictionary + Clone,
+    {
+        ensure!(!self.offset_table.is_empty(), NoFramesSnafu);
+
+        obj.put(DataElement::new(
+            tags::NUMBER_OF_FRAMES,
+            VR::IS,
+            PrimitiveValue::from(self.offset_table.len().to_string()),
+        ));
+        obj.put(DataElement::new(
+            tags::PIXEL_DATA,
+            VR::OB,
+            Value::PixelSequence {
+                offset_table: self.offset_table.into(),
+                fragments: self.fragments.into(),
+            },
+        ));
+        Ok(())
+    }
+}
+
+fn us(value: u16) -> PrimitiveValue {
+    PrimitiveValue::from(value)
+}
+
+/// Rearrange interleaved samples so that
+/// each frame holds one plane per sample.
+fn to_planes<T: Copy>(samples: &[T], frame_length: usize, spp: usize) -> Vec<T> {
+    let mut out = Vec::with_capacity(samples.len());
+    for frame in samples.chunks(frame_length) {
+        for s in 0..spp {
+            out.extend(frame.iter().skip(s).step_by(spp).copied());
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PixelDecoder;
+    use dicom_core::header::HasLength;
+    use dicom_object::FileMetaTableBuilder;
+    use ndarray::Array4;
+
+    fn with_meta(obj: InMemDicomObject) -> dicom_object::FileDicomObject<InMemDicomObject> {
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .media_storage_sop_instance_uid("2.25.1")
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn writes_multi_frame_u16() {
+        let mut obj = InMemDicomObject::new_empty();
+        let mut writer = NativePixelDataWriter::<u16>::new(2, 3).bits_stored(12);
+        writer.push_frame(&[0, 1, 2, 3, 4, 5]).unwrap();
+        writer
+            .push_frame(&[4095, 4094, 4093, 4092, 4091, 4090])
+            .unwrap();
+        assert!(writer.push_frame(&[0; 5]).is_err());
+        writer.write_to(&mut obj).unwrap();
+
+        assert_eq!(
+            obj.element(tags::NUMBER_OF_FRAMES)
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            obj.element(tags::HIGH_BIT)
+                .unwrap()
+                .to_int::<u16>()
+                .unwrap(),
+            11
+        );
+        assert_eq!(obj.element(tags::PIXEL_DATA).unwrap().vr(), VR::OW);
+
+        let obj = with_meta(obj);
+        let decoded = obj.decode_pixel_data().unwrap();
+        assert_eq!(decoded.number_of_frames(), 2);
+        assert_eq!(decoded.rows(), 2);
+        assert_eq!(decoded.columns(), 3);
+        assert_eq!(
+            decoded.data_ow(),
+            vec![0, 1, 2, 3, 4, 5, 4095, 4094, 4093, 4092, 4091, 4090]
+        );
+    }
+
+    #[test]
+    fn writes_single_frame_u8_with_padding() {
+        let mut obj = InMemDicomObject::new_empty();
+        obj.put(DataElement::new(
+            tags::NUMBER_OF_FRAMES,
+            VR::IS,
+            PrimitiveValue::from("4"),
+        ));
+        let mut writer = NativePixelDataWriter::<u8>::new(1, 3);
+        writer.push_frame(&[10, 20, 30]).unwrap();
+        writer.write_to(&mut obj).unwrap();
+
+        assert!(obj.element(tags::NUMBER_OF_FRAMES).is_err());
+        let pixel_data = obj.element(tags::PIXEL_DATA).unwrap();
+        assert_eq!(pixel_data.vr(), VR::OB);
+        assert_eq!(pixel_data.to_bytes().unwrap().as_ref(), &[10, 20, 30, 0]);
+        assert_eq!(
+            obj.element(tags::PHOTOMETRIC_INTERPRETATION)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "MONOCHROME2"
+        );
+    }
+
+    #[test]
+    fn writes_planar_rgb_from_ndarray() {
+        let mut obj = InMemDicomObject::new_empty();
+        let frames = Array4::from_shape_vec((1, 1, 2, 3), vec![1_u8, 2, 3, 4, 5, 6]).unwrap();
+        let mut writer = NativePixelDataWriter::new(1, 2)
+            .samples_per_pixel(3)
+            .planar_configuration(PlanarConfiguration::PixelFirst);
+        writer.push_frames_array(frames.view()).unwrap();
+        writer.write_to(&mut obj).unwrap();
+
+        assert_eq!(
+            obj.element(tags::PHOTOMETRIC_INTERPRETATION)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+            "RGB"
+        );
+        assert_eq!(
+            obj.element(tags::PIXEL_DATA)
+                .unwrap()
+                .to_bytes()
+                .unwrap()
+                .as_ref(),
+            &[1, 4, 2, 5, 3, 6]
+        );
+    }
+
+    #[test]
+    fn writes_encapsulated_fragments_and_offset_table() {
+        let mut obj = InMemDicomObject::new_empty();
+        let mut writer = EncapsulatedPixelDataWriter::new().fragment_size(5);
+        // 4 + 4 + 1 (padded)
+        writer.push_frame(&[1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+        writer.push_frame(&[10, 11]).unwrap();
+        assert_eq!(writer.number_of_fragments(), 4);
+        writer.write_to(&mut obj).unwrap();
+
+        assert_eq!(
+            obj.element(tags::NUMBER_OF_FRAMES)
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            2
+        );
+        let pixel_data = obj.element(tags::PIXEL_DATA).unwrap();
+        assert_eq!(pixel_data.vr(), VR::OB);
+        assert!(pixel_data.length().is_undefined());
+        match pixel_data.value() {
+            Value::PixelSequence {
+                offset_table,
+                fragments,
+            } => {
+                assert_eq!(&offset_table[..], &[0, 8 + 4 + 8 + 4 + 8 + 2]);
+                assert_eq!(
+                    &fragments[..],
+                    &[vec![1, 2, 3, 4], vec![5, 6, 7, 8], vec![9, 0], vec![10, 11],]
+                );
+            }
+            value => panic!("unexpected pixel data value {:?}", value),
+        }
+    }
+
+    #[test]
+    fn writes_one_fragment_per_frame_by_default() {
+        let mut obj = InMemDicomObject::new_empty();
+        let mut writer = EncapsulatedPixelDataWriter::new();
+        writer.push_frame(&[0xFF; 9000]).unwrap();
+        writer.write_to(&mut obj).unwrap();
+
+        assert_eq!(
+            obj.element(tags::NUMBER_OF_FRAMES)
+                .unwrap()
+                .to_int::<u32>()
+                .unwrap(),
+            1
+        );
+        match obj.element(tags::PIXEL_DATA).unwrap().value() {
+            Value::PixelSequence {
+                offset_table,
+                fragments,
+            } => {
+                assert_eq!(&offset_table[..], &[0]);
+                assert_eq!(fragments.len(), 1);
+                assert_eq!(fragments[0].len(), 9000);
+            }
+            value => panic!("unexpected pixel data value {:?}", value),
+        }
+        assert!(EncapsulatedPixelDataWriter::new()
+            .write_to(&mut obj)
+            .is_err());
+    }
+}
@@ -26,6 +26,11 @@
 //! This conversion includes
 //! eventual Modality and value of interest (VOI) transformations.
 //!
+//! Series of single-frame images can be sorted and stacked
+//! into a 3D [`Volume`] with [`assemble_volume`].
+//! With the Cargo feature "nifti",
+//! volumes can also be exported to NIfTI-1 files.
+//!
 //! # WebAssembly support
 //! This library works in WebAssembly
 //! by ensuring that the "gdcm" feature is disabled.
@@ -87,7 +92,7 @@
 
 use byteorder::{ByteOrder, NativeEndian};
 use dicom_core::{value::Value, DataDictionary};
-use dicom_encoding::adapters::DecodeError;
+use dicom_encoding::adapters::{DecodeError, PixelDataObject};
 #[cfg(not(feature = "gdcm"))]
 use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
 #[cfg(not(feature = "gdcm"))]
@@ -98,23 +103,36 @@ use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
 use image::{DynamicImage, ImageBuffer, Luma, Rgb};
 use ndarray::{Array, Ix3, Ix4};
 use num_traits::NumCast;
-use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+use par::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use snafu::OptionExt;
 use snafu::{Backtrace, ResultExt, Snafu};
 use std::borrow::Cow;
+use std::collections::VecDeque;
 
 pub use image;
 pub use ndarray;
 
 mod attribute;
+mod encode;
 mod lut;
+#[cfg(feature = "nifti")]
+mod nifti;
+mod overlay;
+mod par;
+pub mod repack;
+mod volume;
 
 pub(crate) mod transform;
 
 // re-exports
 pub use attribute::{PhotometricInterpretation, PixelRepresentation, PlanarConfiguration};
+pub use encode::{EncapsulatedPixelDataWriter, NativePixelDataWriter, NativeSample};
 pub use lut::{CreateLutError, Lut};
+#[cfg(feature = "nifti")]
+pub use nifti::{nifti_affine, save_nifti, write_nifti, WriteNiftiError};
+pub use overlay::{overlays, Overlay};
 pub use transform::{Rescale, VoiLutFunction, WindowLevel, WindowLevelTransform};
+pub use volume::{assemble_volume, group_by_series, sort_slices, SliceOrder, SortMethod, Volume};
 
 #[cfg(feature = "gdcm")]
 mod gdcm;
@@ -195,6 +213,63 @@ pub enum InnerError {
         frame_number: u32,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Expected {} samples in frame, got {}", expected, got))]
+    InvalidFrameLength {
+        expected: usize,
+        got: usize,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("No frames to encode"))]
+    NoFrames { backtrace: Backtrace },
+
+    #[snafu(display(
+        "Invalid BitsStored {} for {} bits allocated",
+        bits_stored,
+        bits_allocated
+    ))]
+    InvalidBitsStored {
+        bits_stored: u16,
+        bits_allocated: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Invalid HighBit {} for {} bits stored", high_bit, bits_stored))]
+    InvalidHighBit {
+        high_bit: u16,
+        bits_stored: u16,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Encapsulated pixel data is too large for the offset table"))]
+    OffsetTableOverflow { backtrace: Backtrace },
+
+    #[snafu(display("No slices to sort"))]
+    NoSlices { backtrace: Backtrace },
+
+    #[snafu(display("Slice #{} has no InstanceNumber", index))]
+    MissingInstanceNumber { index: usize, backtrace: Backtrace },
+
+    #[snafu(display("Slice #{} is a duplicate of another slice", index))]
+    DuplicateSlice { index: usize, backtrace: Backtrace },
+
+    #[snafu(display("{} slices are missing after slice #{}", count, after))]
+    MissingSlices {
+        after: usize,
+        count: u32,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Slice #{} does not have the same size as the other slices", index))]
+    SliceSizeMismatch { index: usize, backtrace: Backtrace },
+
+    #[snafu(display("Invalid {} in overlay group {:04X}", name, group))]
+    InvalidOverlayAttribute {
+        group: u16,
+        name: &'static str,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -840,7 +915,7 @@ impl DecodedPixelData<'_> {
                             .context(CreateLutSnafu)?,
                         };
 
-                        let pixel_values = lut.map_par_iter(data.par_iter().copied());
+                        let pixel_values = data.par_iter().map(|&v| lut.get(v));
                         self.mono_image_with_extend_par(pixel_values, *bit_depth)?
                     }
                 }
@@ -944,7 +1019,7 @@ impl DecodedPixelData<'_> {
                         }
                         .context(CreateLutSnafu)?;
 
-                        let values = lut.map_par_iter(samples.par_iter().copied());
+                        let values = samples.par_iter().map(|&v| lut.get(v));
                         self.mono_image_with_narrow_par(values, *bit_depth)?
                     }
                 }
@@ -1155,7 +1230,7 @@ impl DecodedPixelData<'_> {
                         }
                         .context(CreateLutSnafu)?;
 
-                        let data: Vec<T> = lut.map_par_iter(data.par_iter().copied()).collect();
+                        let data: Vec<T> = data.par_iter().map(|&v| lut.get(v)).collect();
 
                         Ok(data)
                     }
@@ -1224,7 +1299,7 @@ impl DecodedPixelData<'_> {
                         }
                         .context(CreateLutSnafu)?;
 
-                        Ok(lut.map_par_iter(samples.into_par_iter()).collect())
+                        Ok(samples.into_par_iter().map(|v| lut.get(v)).collect())
                     }
                     _ => {
                         // no transformations
@@ -1480,117 +1555,201 @@ pub trait PixelDecoder {
     /// Decode compressed pixel data.
     /// A new buffer (Vec<u8>) is created holding the decoded pixel data.
     fn decode_pixel_data(&self) -> Result<DecodedPixelData>;
+
+    /// Decode the pixel data of a single frame,
+    /// where the first frame has index 0.
+    /// A new buffer (Vec<u8>) is created holding the decoded pixel data
+    /// of that frame only,
+    /// and the number of frames in the output is 1.
+    ///
+    /// The default implementation decodes all frames
+    /// and retains the requested one.
+    fn decode_pixel_data_frame(&self, frame: u32) -> Result<DecodedPixelData> {
+        let mut decoded = self.decode_pixel_data()?;
+        let data = decoded.frame_data(frame)?.to_vec();
+        decoded.data = Cow::Owned(data);
+        decoded.number_of_frames = 1;
+        Ok(decoded)
+    }
+
+    /// Obtain an iterator over the decoded frames of the pixel data,
+    /// in order.
+    ///
+    /// Frames are decoded in batches.
+    /// With the `parallel` Cargo feature (enabled by default),
+    /// the frames in each batch are decoded in parallel,
+    /// one per available thread,
+    /// which is considerably faster for images with many frames.
+    fn decode_pixel_data_frames(&self) -> DecodedFrames<'_, Self>
+    where
+        Self: PixelDataObject + Sync + Sized,
+    {
+        let number_of_frames = self.number_of_frames().unwrap_or(1).into();
+        DecodedFrames::new(self, number_of_frames)
+    }
+}
+
+/// An iterator over the decoded frames of a DICOM object's pixel data.
+///
+/// See [`PixelDecoder::decode_pixel_data_frames`].
+#[derive(Debug)]
+pub struct DecodedFrames<'a, O> {
+    obj: &'a O,
+    next_frame: u32,
+    number_of_frames: u32,
+    batch: VecDeque<Result<DecodedPixelData<'a>>>,
+}
+
+impl<'a, O> DecodedFrames<'a, O> {
+    fn new(obj: &'a O, number_of_frames: u32) -> Self {
+        DecodedFrames {
+            obj,
+            next_frame: 0,
+            number_of_frames,
+            batch: VecDeque::new(),
+        }
+    }
 }
 
+impl<'a, O> Iterator for DecodedFrames<'a, O>
+where
+    O: PixelDecoder + Sync,
+{
+    type Item = Result<DecodedPixelData<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.batch.is_empty() && self.next_frame < self.number_of_frames {
+            let obj = self.obj;
+            #[cfg(feature = "parallel")]
+            let batch_size = rayon::current_num_threads() as u32;
+            #[cfg(not(feature = "parallel"))]
+            let batch_size = 1;
+            let end = self
+                .next_frame
+                .saturating_add(batch_size)
+                .min(self.number_of_frames);
+
+            #[cfg(feature = "parallel")]
+            {
+                use rayon::iter::ParallelExtend;
+                self.batch.par_extend(
+                    (self.next_frame..end)
+                        .into_par_iter()
+                        .map(|i| obj.decode_pixel_data_frame(i)),
+                );
+            }
+            #[cfg(not(feature = "parallel"))]
+            self.batch
+                .extend((self.next_frame..end).map(|i| obj.decode_pixel_data_frame(i)));
+            self.next_frame = end;
+        }
+        self.batch.pop_front()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.batch.len() + (self.number_of_frames - self.next_frame) as usize;
+        (len, Some(len))
+    }
+}
+
+impl<O> ExactSizeIterator for DecodedFrames<'_, O> where O: PixelDecoder + Sync {}
+
 #[cfg(not(feature = "gdcm"))]
 impl<D> PixelDecoder for FileDicomObject<InMemDicomObject<D>>
 where
     D: DataDictionary + Clone,
 {
     fn decode_pixel_data(&self) -> Result<DecodedPixelData> {
-        use attribute::*;
-        use std::convert::TryFrom;
-
-        let pixel_data = pixel_data(self).context(GetAttributeSnafu)?;
-        let cols = cols(self).context(GetAttributeSnafu)?;
-        let rows = rows(self).context(GetAttributeSnafu)?;
-
-        let photometric_interpretation =
-            photometric_interpretation(self).context(GetAttributeSnafu)?;
-        let samples_per_pixel = samples_per_pixel(self).context(GetAttributeSnafu)?;
-        let planar_configuration = planar_configuration(self).context(GetAttributeSnafu)?;
-        let bits_allocated = bits_allocated(self).context(GetAttributeSnafu)?;
-        let bits_stored = bits_stored(self).context(GetAttributeSnafu)?;
-        let high_bit = high_bit(self).context(GetAttributeSnafu)?;
-        let pixel_representation = pixel_representation(self).context(GetAttributeSnafu)?;
-        let rescale_intercept = rescale_intercept(self);
-        let rescale_slope = rescale_slope(self);
-        let number_of_frames = number_of_frames(self).context(GetAttributeSnafu)?;
-        let voi_lut_function = voi_lut_function(self).context(GetAttributeSnafu)?;
-        let voi_lut_function = voi_lut_function.and_then(|v| VoiLutFunction::try_from(&*v).ok());
-
-        let window = if let Some(window_center) = window_center(self).context(GetAttributeSnafu)? {
-            let window_width = window_width(self).context(GetAttributeSnafu)?;
-
-            window_width.map(|width| WindowLevel {
-                center: window_center,
-                width,
-            })
-        } else {
-            None
-        };
+        decode_pixel_data_impl(self, None)
+    }
 
-        let transfer_syntax = &self.meta().transfer_syntax;
-        let ts = TransferSyntaxRegistry
-            .get(transfer_syntax)
-            .with_context(|| UnknownTransferSyntaxSnafu {
-                ts_uid: transfer_syntax,
-            })?;
+    fn decode_pixel_data_frame(&self, frame: u32) -> Result<DecodedPixelData> {
+        decode_pixel_data_impl(self, Some(frame))
+    }
+}
 
-        if !ts.fully_supported() {
-            return UnsupportedTransferSyntaxSnafu {
-                ts: transfer_syntax,
-            }
-            .fail()?;
+/// Decode the pixel data of the given object,
+/// either of all frames or of the given frame only.
+#[cfg(not(feature = "gdcm"))]
+fn decode_pixel_data_impl<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    frame: Option<u32>,
+) -> Result<DecodedPixelData>
+where
+    D: DataDictionary + Clone,
+{
+    use attribute::*;
+    use std::convert::TryFrom;
+
+    let pixel_data = pixel_data(obj).context(GetAttributeSnafu)?;
+    let cols = cols(obj).context(GetAttributeSnafu)?;
+    let rows = rows(obj).context(GetAttributeSnafu)?;
+
+    let photometric_interpretation = photometric_interpretation(obj).context(GetAttributeSnafu)?;
+    let samples_per_pixel = samples_per_pixel(obj).context(GetAttributeSnafu)?;
+    let planar_configuration = planar_configuration(obj).context(GetAttributeSnafu)?;
+    let bits_allocated = bits_allocated(obj).context(GetAttributeSnafu)?;
+    let bits_stored = bits_stored(obj).context(GetAttributeSnafu)?;
+    let high_bit = high_bit(obj).context(GetAttributeSnafu)?;
+    let pixel_representation = pixel_representation(obj).context(GetAttributeSnafu)?;
+    let rescale_intercept = rescale_intercept(obj);
+    let rescale_slope = rescale_slope(obj);
+    let number_of_frames = number_of_frames(obj).context(GetAttributeSnafu)?;
+    let voi_lut_function = voi_lut_function(obj).context(GetAttributeSnafu)?;
+    let voi_lut_function = voi_lut_function.and_then(|v| VoiLutFunction::try_from(&*v).ok());
+    // only one frame is decoded if requested
+    let number_of_frames = if frame.is_some() { 1 } else { number_of_frames };
+
+    let window = if let Some(window_center) = window_center(obj).context(GetAttributeSnafu)? {
+        let window_width = window_width(obj).context(GetAttributeSnafu)?;
+
+        window_width.map(|width| WindowLevel {
+            center: window_center,
+            width,
+        })
+    } else {
+        None
+    };
+
+    let transfer_syntax = &obj.meta().transfer_syntax;
+    let ts = TransferSyntaxRegistry
+        .get(transfer_syntax)
+        .with_context(|| UnknownTransferSyntaxSnafu {
+            ts_uid: transfer_syntax,
+        })?;
+
+    if !ts.fully_supported() {
+        return UnsupportedTransferSyntaxSnafu {
+            ts: transfer_syntax,
         }
+        .fail()?;
+    }
 
-        // Try decoding it using a native Rust decoder
-        if let Codec::PixelData(decoder) = ts.codec() {
-            let mut data: Vec<u8> = Vec::new();
-            (*decoder)
-                .decode(self, &mut data)
-                .context(DecodePixelDataSnafu)?;
-
-            // pixels are already interpreted,
-            // set new photometric interpretation
-            let new_pi = match samples_per_pixel {
-                1 => PhotometricInterpretation::Monochrome2,
-                3 => PhotometricInterpretation::Rgb,
-                _ => photometric_interpretation,
-            };
-
-            return Ok(DecodedPixelData {
-                data: Cow::from(data),
-                cols: cols.into(),
-                rows: rows.into(),
-                number_of_frames,
-                photometric_interpretation: new_pi,
-                samples_per_pixel,
-                planar_configuration: PlanarConfiguration::Standard,
-                bits_allocated,
-                bits_stored,
-                high_bit,
-                pixel_representation,
-                rescale_intercept,
-                rescale_slope,
-                voi_lut_function,
-                window,
-            });
+    // Try decoding it using a native Rust decoder
+    if let Codec::PixelData(decoder) = ts.codec() {
+        let mut data: Vec<u8> = Vec::new();
+        match frame {
+            Some(frame) => (*decoder).decode_frame(obj, frame, &mut data),
+            None => (*decoder).decode(obj, &mut data),
         }
-
-        let decoded_pixel_data = match pixel_data.value() {
-            Value::PixelSequence {
-                fragments,
-                offset_table: _,
-            } => {
-                // Return all fragments concatenated
-                fragments.into_iter().flatten().copied().collect()
-            }
-            Value::Primitive(p) => {
-                // Non-encoded, just return the pixel data for all frames
-                p.to_bytes().to_vec()
-            }
-            Value::Sequence { items: _, size: _ } => InvalidPixelDataSnafu.fail()?,
+        .context(DecodePixelDataSnafu)?;
+
+        // pixels are already interpreted,
+        // set new photometric interpretation
+        let new_pi = match samples_per_pixel {
+            1 => PhotometricInterpretation::Monochrome2,
+            3 => PhotometricInterpretation::Rgb,
+            _ => photometric_interpretation,
         };
 
-        Ok(DecodedPixelData {
-            data: Cow::from(decoded_pixel_data),
+        return Ok(DecodedPixelData {
+            data: Cow::from(data),
             cols: cols.into(),
             rows: rows.into(),
             number_of_frames,
-            photometric_interpretation,
+            photometric_interpretation: new_pi,
             samples_per_pixel,
-            planar_configuration,
+            planar_configuration: PlanarConfiguration::Standard,
             bits_allocated,
             bits_stored,
             high_bit,
@@ -1599,8 +1758,57 @@ where
             rescale_slope,
             voi_lut_function,
             window,
-        })
+        });
     }
+
+    let decoded_pixel_data = match pixel_data.value() {
+        Value::PixelSequence {
+            fragments,
+            offset_table: _,
+        } => {
+            // Return all fragments concatenated
+            fragments.into_iter().flatten().copied().collect()
+        }
+        Value::Primitive(p) => {
+            // Non-encoded, just return the pixel data for all frames
+            p.to_bytes().to_vec()
+        }
+        Value::Sequence { items: _, size: _ } => InvalidPixelDataSnafu.fail()?,
+    };
+
+    let decoded_pixel_data = if let Some(frame) = frame {
+        let frame_length = rows as usize
+            * cols as usize
+            * samples_per_pixel as usize
+            * (bits_allocated as usize / 8);
+        let frame_start = frame_length * frame as usize;
+        decoded_pixel_data
+            .get(frame_start..frame_start + frame_length)
+            .context(FrameOutOfRangeSnafu {
+                frame_number: frame,
+            })?
+            .to_vec()
+    } else {
+        decoded_pixel_data
+    };
+
+    Ok(DecodedPixelData {
+        data: Cow::from(decoded_pixel_data),
+        cols: cols.into(),
+        rows: rows.into(),
+        number_of_frames,
+        photometric_interpretation,
+        samples_per_pixel,
+        planar_configuration,
+        bits_allocated,
+        bits_stored,
+        high_bit,
+        pixel_representation,
+        rescale_intercept,
+        rescale_slope,
+        voi_lut_function,
+        window,
+    })
 }
 
 #[cfg(test)]
@@ -1896,6 +2104,30 @@ mod tests {
             assert_eq!(ndarray[[1, 75, 75, 2]], 255);
         }
 
+        #[test]
+        fn test_native_decoding_pixel_data_rle_frames() {
+            let path = dicom_test_files::path("pydicom/SC_rgb_rle_16bit_2frame.dcm")
+                .expect("test DICOM file should exist");
+            let object = open_file(&path).unwrap();
+            let decoded = object.decode_pixel_data().unwrap();
+
+            // decode each frame on its own
+            for i in 0..2 {
+                let frame = object.decode_pixel_data_frame(i).unwrap();
+                assert_eq!(frame.number_of_frames(), 1);
+                assert_eq!(frame.data(), decoded.frame_data(i).unwrap());
+            }
+            assert!(object.decode_pixel_data_frame(2).is_err());
+
+            // decode all frames in order
+            let frames = object.decode_pixel_data_frames();
+            assert_eq!(frames.len(), 2);
+            for (i, frame) in frames.enumerate() {
+                let frame = frame.unwrap();
+                assert_eq!(frame.data(), decoded.frame_data(i as u32).unwrap());
+            }
+        }
+
         #[test]
         fn test_native_decoding_pixel_data_rle_16bit_1frame() {
             let path = dicom_test_files::path("pydicom/SC_rgb_rle_16bit.dcm")
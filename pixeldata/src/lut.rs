@@ -9,9 +9,11 @@
 //! for common DICOM sample value transformations.
 
 use num_traits::{NumCast, ToPrimitive};
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+#[cfg(feature = "parallel")]
+use rayon::iter::ParallelIterator;
 use snafu::{OptionExt, Snafu};
 
+use crate::par::IntoParallelIterator;
 use crate::{Rescale, WindowLevelTransform};
 
 /// The LUT could not be created:
@@ -312,6 +314,9 @@ where
 
     /// Adapts a parallel iterator of pixel data sample values
     /// to a parallel iterator of transformed values.
+    ///
+    /// Only available with the `parallel` feature.
+    #[cfg(feature = "parallel")]
     pub fn map_par_iter<'a, I: 'static>(
         &'a self,
         iter: impl ParallelIterator<Item = I> + 'a,
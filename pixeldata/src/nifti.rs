@@ -0,0 +1,317 @@
+//! Export of assembled volumes to NIfTI-1.
+//!
+//! This module is only available with the Cargo feature "nifti".
+//! [`write_nifti`] writes a [`Volume`] as a single-file NIfTI-1 image (`.nii`)
+//! with 32-bit floating point voxels,
+//! and both the `qform` and `sform` transformations
+//! set from the geometry of the series.
+//! DICOM patient coordinates (LPS) are converted
+//! into the RAS+ coordinates expected by NIfTI.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! # use dicom_object::FileDicomObject;
+//! use dicom_pixeldata::{assemble_volume, save_nifti};
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! # let slices: Vec<FileDicomObject<dicom_object::InMemDicomObject>> = Vec::new();
+//! let volume = assemble_volume(&slices)?;
+//! save_nifti(&volume, "series.nii")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use byteorder::{LittleEndian, WriteBytesExt};
+use ndarray::Array3;
+use snafu::{Backtrace, ResultExt, Snafu};
+
+use crate::{Result, Volume};
+
+/// An error writing a NIfTI image.
+#[derive(Debug, Snafu)]
+#[snafu(display("Could not write NIfTI image"))]
+pub struct WriteNiftiError {
+    source: std::io::Error,
+    backtrace: Backtrace,
+}
+
+/// The size of the NIfTI-1 header.
+const HEADER_SIZE: i32 = 348;
+/// The offset of the voxel data in a single-file NIfTI-1 image,
+/// after the header and an empty extension flag.
+const VOX_OFFSET: f32 = 352.;
+/// `NIFTI_TYPE_FLOAT32`
+const DT_FLOAT32: i16 = 16;
+/// `NIFTI_XFORM_SCANNER_ANAT`
+const XFORM_SCANNER_ANAT: i16 = 1;
+/// `NIFTI_UNITS_MM`
+const UNITS_MM: u8 = 2;
+
+/// Obtain the transformation from voxel indices (i, j, k, 1)
+/// to NIfTI RAS+ coordinates,
+/// if the volume has a consistent geometry.
+///
+/// Voxel index `i` goes along the columns, `j` along the rows,
+/// and `k` along the slices of the volume.
+pub fn nifti_affine(volume: &Volume) -> Option<[[f64; 4]; 4]> {
+    volume.voxel_to_patient().map(lps_to_ras)
+}
+
+/// Write the volume as a single-file NIfTI-1 image.
+pub fn write_nifti<W: Write>(volume: &Volume, to: W) -> Result<(), WriteNiftiError> {
+    let spacing = volume.spacing().unwrap_or([1., 1., 1.]);
+    write_nifti_impl(volume.data(), spacing, nifti_affine(volume), to).context(WriteNiftiSnafu)?;
+    Ok(())
+}
+
+/// Save the volume as a single-file NIfTI-1 image
+/// to the given path, usually ending in `.nii`.
+pub fn save_nifti<P: AsRef<Path>>(volume: &Volume, path: P) -> Result<(), WriteNiftiError> {
+    let file = File::create(path).context(WriteNiftiSnafu)?;
+    write_nifti(volume, BufWriter::new(file))
+}
+
+fn lps_to_ras(mut matrix: [[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    for row in matrix.iter_mut().take(2) {
+        for value in row.iter_mut() {
+            *value = -*value;
+        }
+    }
+    matrix
+}
+
+/// Write a NIfTI-1 image from voxels indexed by (slice, row, column),
+/// the voxel spacing as (slice, row, column),
+/// and an optional RAS+ affine transformation.
+fn write_nifti_impl<W: Write>(
+    data: &Array3<f32>,
+    spacing: [f64; 3],
+    affine: Option<[[f64; 4]; 4]>,
+    mut to: W,
+) -> std::io::Result<()> {
+    let (slices, rows, columns) = data.dim();
+    let (qfac, quatern) = match affine {
+        Some(affine) => quaternion(&affine),
+        None => (1., [0., 0., 0.]),
+    };
+    let xform_code = if affine.is_some() {
+        XFORM_SCANNER_ANAT
+    } else {
+        0
+    };
+
+    let mut header = Vec::with_capacity(VOX_OFFSET as usize);
+    header.write_i32::<LittleEndian>(HEADER_SIZE)?;
+    // data_type, db_name, extents, session_error
+    header.extend_from_slice(&[0; 10 + 18 + 4 + 2]);
+    // regular, dim_info
+    header.extend_from_slice(&[b'r', 0]);
+    for dim in [3, columns, rows, slices, 1, 1, 1, 1] {
+        header.write_i16::<LittleEndian>(dim as i16)?;
+    }
+    // intent_p1, intent_p2, intent_p3, intent_code
+    header.extend_from_slice(&[0; 4 * 3 + 2]);
+    header.write_i16::<LittleEndian>(DT_FLOAT32)?;
+    // bitpix
+    header.write_i16::<LittleEndian>(32)?;
+    // slice_start
+    header.write_i16::<LittleEndian>(0)?;
+    let [slice_spacing, row_spacing, column_spacing] = spacing;
+    for pixdim in [
+        qfac,
+        column_spacing,
+        row_spacing,
+        slice_spacing,
+        0.,
+        0.,
+        0.,
+        0.,
+    ] {
+        header.write_f32::<LittleEndian>(pixdim as f32)?;
+    }
+    header.write_f32::<LittleEndian>(VOX_OFFSET)?;
+    // scl_slope, scl_inter
+    header.write_f32::<LittleEndian>(1.)?;
+    header.write_f32::<LittleEndian>(0.)?;
+    // slice_end, slice_code
+    header.extend_from_slice(&[0; 3]);
+    header.push(UNITS_MM);
+    // cal_max, cal_min, slice_duration, toffset, glmax, glmin
+    header.extend_from_slice(&[0; 4 * 6]);
+    let mut descrip = [0; 80];
+    let text = b"dicom-rs volume";
+    descrip[..text.len()].copy_from_slice(text);
+    header.extend_from_slice(&descrip);
+    // aux_file
+    header.extend_from_slice(&[0; 24]);
+    header.write_i16::<LittleEndian>(xform_code)?;
+    header.write_i16::<LittleEndian>(xform_code)?;
+    for q in quatern {
+        header.write_f32::<LittleEndian>(q as f32)?;
+    }
+    let affine = affine.unwrap_or([
+        [column_spacing, 0., 0., 0.],
+        [0., row_spacing, 0., 0.],
+        [0., 0., slice_spacing, 0.],
+        [0., 0., 0., 1.],
+    ]);
+    // qoffset
+    for row in &affine[..3] {
+        header.write_f32::<LittleEndian>(row[3] as f32)?;
+    }
+    // srow_x, srow_y, srow_z
+    for row in &affine[..3] {
+        for &value in row {
+            header.write_f32::<LittleEndian>(value as f32)?;
+        }
+    }
+    // intent_name
+    header.extend_from_slice(&[0; 16]);
+    header.extend_from_slice(b"n+1\0");
+    debug_assert_eq!(header.len(), HEADER_SIZE as usize);
+    // no extensions
+    header.extend_from_slice(&[0; 4]);
+    to.write_all(&header)?;
+
+    // the column index varies the fastest in both layouts
+    for &value in data.iter() {
+        to.write_f32::<LittleEndian>(value)?;
+    }
+    to.flush()
+}
+
+/// Obtain the `qfac` and the quaternion parameters (b, c, d)
+/// of the rotation in the given affine transformation,
+/// following `nifti_mat44_to_quatern`.
+fn quaternion(affine: &[[f64; 4]; 4]) -> (f64, [f64; 3]) {
+    // normalized columns
+    let mut r = [[0.; 3]; 3];
+    for j in 0..3 {
+        let norm = (0..3).map(|i| affine[i][j].powi(2)).sum::<f64>().sqrt();
+        for (i, row) in r.iter_mut().enumerate() {
+            row[j] = if norm > 0. { affine[i][j] / norm } else { 0. };
+        }
+    }
+
+    let det = r[0][0] * (r[1][1] * r[2][2] - r[1][2] * r[2][1])
+        - r[0][1] * (r[1][0] * r[2][2] - r[1][2] * r[2][0])
+        + r[0][2] * (r[1][0] * r[2][1] - r[1][1] * r[2][0]);
+    let qfac = if det < 0. {
+        for row in r.iter_mut() {
+            row[2] = -row[2];
+        }
+        -1.
+    } else {
+        1.
+    };
+
+    let a = r[0][0] + r[1][1] + r[2][2] + 1.;
+    let (a, b, c, d) = if a > 0.5 {
+        let a = 0.5 * a.sqrt();
+        (
+            a,
+            0.25 * (r[2][1] - r[1][2]) / a,
+            0.25 * (r[0][2] - r[2][0]) / a,
+            0.25 * (r[1][0] - r[0][1]) / a,
+        )
+    } else {
+        let xd = 1. + r[0][0] - (r[1][1] + r[2][2]);
+        let yd = 1. + r[1][1] - (r[0][0] + r[2][2]);
+        let zd = 1. + r[2][2] - (r[0][0] + r[1][1]);
+        if xd > 1. {
+            let b = 0.5 * xd.sqrt();
+            (
+                0.25 * (r[2][1] - r[1][2]) / b,
+                b,
+                0.25 * (r[0][1] + r[1][0]) / b,
+                0.25 * (r[0][2] + r[2][0]) / b,
+            )
+        } else if yd > 1. {
+            let c = 0.5 * yd.sqrt();
+            (
+                0.25 * (r[0][2] - r[2][0]) / c,
+                0.25 * (r[0][1] + r[1][0]) / c,
+                c,
+                0.25 * (r[1][2] + r[2][1]) / c,
+            )
+        } else {
+            let d = 0.5 * zd.sqrt();
+            (
+                0.25 * (r[1][0] - r[0][1]) / d,
+                0.25 * (r[0][2] + r[2][0]) / d,
+                0.25 * (r[1][2] + r[2][1]) / d,
+                d,
+            )
+        }
+    };
+    if a < 0. {
+        (qfac, [-b, -c, -d])
+    } else {
+        (qfac, [b, c, d])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use byteorder::{ByteOrder, LittleEndian};
+
+    #[test]
+    fn converts_lps_to_ras() {
+        let lps = [
+            [0.5, 0., 0., -100.],
+            [0., 0.5, 0., -120.],
+            [0., 0., 2., 30.],
+            [0., 0., 0., 1.],
+        ];
+        let ras = lps_to_ras(lps);
+        assert_eq!(ras[0], [-0.5, 0., 0., 100.]);
+        assert_eq!(ras[1], [0., -0.5, 0., 120.]);
+        assert_eq!(ras[2], lps[2]);
+
+        // a rotation of 180 degrees around z
+        let (qfac, q) = quaternion(&ras);
+        assert_eq!(qfac, 1.);
+        assert!(q[0].abs() < 1e-9 && q[1].abs() < 1e-9);
+        assert!((q[2].abs() - 1.).abs() < 1e-9);
+
+        // flipping the slice direction is an improper rotation
+        let mut flipped = ras;
+        flipped[2][2] = -2.;
+        assert_eq!(quaternion(&flipped).0, -1.);
+    }
+
+    #[test]
+    fn writes_header_and_voxels() {
+        let data = Array3::from_shape_vec((2, 1, 3), vec![0., 1., 2., 3., 4., 5.]).unwrap();
+        let affine = [
+            [-0.5, 0., 0., 100.],
+            [0., -0.75, 0., 120.],
+            [0., 0., 2., 30.],
+            [0., 0., 0., 1.],
+        ];
+        let mut out = Vec::new();
+        write_nifti_impl(&data, [2., 0.75, 0.5], Some(affine), &mut out).unwrap();
+
+        assert_eq!(out.len(), 352 + 6 * 4);
+        assert_eq!(LittleEndian::read_i32(&out[0..4]), 348);
+        let dims: Vec<i16> = out[40..56].chunks(2).map(LittleEndian::read_i16).collect();
+        assert_eq!(dims, vec![3, 3, 1, 2, 1, 1, 1, 1]);
+        assert_eq!(LittleEndian::read_i16(&out[70..72]), DT_FLOAT32);
+        assert_eq!(LittleEndian::read_f32(&out[80..84]), 0.5);
+        assert_eq!(LittleEndian::read_f32(&out[88..92]), 2.);
+        assert_eq!(LittleEndian::read_f32(&out[108..112]), 352.);
+        assert_eq!(LittleEndian::read_i16(&out[254..256]), XFORM_SCANNER_ANAT);
+        // srow_y
+        assert_eq!(LittleEndian::read_f32(&out[296..300]), 0.);
+        assert_eq!(LittleEndian::read_f32(&out[300..304]), -0.75);
+        assert_eq!(LittleEndian::read_f32(&out[308..312]), 120.);
+        assert_eq!(&out[344..348], b"n+1\0");
+        assert_eq!(LittleEndian::read_f32(&out[352 + 4 * 5..]), 5.);
+    }
+}
@@ -0,0 +1,503 @@
+//! Decoding of overlay planes.
+//!
+//! Overlay planes are 1-bit graphics or regions of interest
+//! drawn over an image, described in the repeating groups 6000 to 601E.
+//! The overlay bits are usually packed in _Overlay Data_ (60xx,3000),
+//! but older files may embed them in an unused bit
+//! of the pixel data samples instead,
+//! as given by _Overlay Bit Position_ (60xx,0102).
+//! [`overlays`] decodes every overlay plane of an object
+//! into an [`Overlay`] mask, regardless of how it is stored.
+
+use dicom_core::value::{PrimitiveValue, Value};
+use dicom_core::{DataDictionary, Tag};
+use dicom_object::mem::InMemElement;
+use dicom_object::{FileDicomObject, InMemDicomObject};
+use snafu::{ensure, OptionExt};
+
+use crate::repack::unpack_bits;
+use crate::{InvalidOverlayAttributeSnafu, PixelDecoder, Result};
+
+/// The first and last groups of overlay planes.
+const OVERLAY_GROUPS: std::ops::RangeInclusive<u16> = 0x6000..=0x601E;
+
+const OVERLAY_ROWS: u16 = 0x0010;
+const OVERLAY_COLUMNS: u16 = 0x0011;
+const NUMBER_OF_FRAMES_IN_OVERLAY: u16 = 0x0015;
+const OVERLAY_DESCRIPTION: u16 = 0x0022;
+const OVERLAY_TYPE: u16 = 0x0040;
+const OVERLAY_ORIGIN: u16 = 0x0050;
+const IMAGE_FRAME_ORIGIN: u16 = 0x0051;
+const OVERLAY_BIT_POSITION: u16 = 0x0102;
+const OVERLAY_LABEL: u16 = 0x1500;
+const OVERLAY_DATA: u16 = 0x3000;
+
+/// An overlay plane decoded into a mask,
+/// with one boolean per overlay pixel,
+/// in row-major order, frame after frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Overlay {
+    group: u16,
+    rows: u16,
+    columns: u16,
+    origin: (i16, i16),
+    overlay_type: String,
+    label: Option<String>,
+    description: Option<String>,
+    number_of_frames: u32,
+    image_frame_origin: u32,
+    embedded: bool,
+    data: Vec<bool>,
+}
+
+impl Overlay {
+    /// Retrieves the group of the overlay plane, between `0x6000` and `0x601E`.
+    #[inline]
+    pub fn group(&self) -> u16 {
+        self.group
+    }
+
+    /// Retrieves the number of rows of the overlay.
+    #[inline]
+    pub fn rows(&self) -> u16 {
+        self.rows
+    }
+
+    /// Retrieves the number of columns of the overlay.
+    #[inline]
+    pub fn columns(&self) -> u16 {
+        self.columns
+    }
+
+    /// Retrieves the position of the first overlay pixel
+    /// relative to the image, as (row, column),
+    /// where the first pixel of the image is at (1, 1).
+    #[inline]
+    pub fn origin(&self) -> (i16, i16) {
+        self.origin
+    }
+
+    /// Retrieves the overlay type:
+    /// `G` for graphics, or `R` for a region of interest.
+    #[inline]
+    pub fn overlay_type(&self) -> &str {
+        &self.overlay_type
+    }
+
+    /// Retrieves the overlay label, if present.
+    #[inline]
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    /// Retrieves the overlay description, if present.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Retrieves the number of frames of the overlay.
+    #[inline]
+    pub fn number_of_frames(&self) -> u32 {
+        self.number_of_frames
+    }
+
+    /// Retrieves the number of the image frame
+    /// to which the first overlay frame applies, starting from 1.
+    #[inline]
+    pub fn image_frame_origin(&self) -> u32 {
+        self.image_frame_origin
+    }
+
+    /// Whether the overlay was embedded in the pixel data
+    /// instead of stored in _Overlay Data_.
+    #[inline]
+    pub fn is_embedded(&self) -> bool {
+        self.embedded
+    }
+
+    /// Retrieves the mask of all overlay frames.
+    #[inline]
+    pub fn data(&self) -> &[bool] {
+        &self.data
+    }
+
+    /// Retrieves the mask of the overlay frame at the given index,
+    /// starting from 0.
+    pub fn frame(&self, frame: u32) -> Option<&[bool]> {
+        let frame_length = self.rows as usize * self.columns as usize;
+        let start = frame_length * frame as usize;
+        self.data.get(start..start + frame_length)
+    }
+
+    /// Check whether the overlay pixel at the given row and column
+    /// of the given overlay frame is set,
+    /// all starting from 0.
+    pub fn get(&self, frame: u32, row: u16, column: u16) -> bool {
+        if row >= self.rows || column >= self.columns {
+            return false;
+        }
+        self.frame(frame)
+            .map(|data| data[row as usize * self.columns as usize + column as usize])
+            .unwrap_or(false)
+    }
+
+    /// Obtain the mask of the given overlay frame
+    /// over an image of the given size,
+    /// placing the overlay according to its origin.
+    ///
+    /// Overlay pixels falling outside of the image are discarded.
+    pub fn to_image_mask(&self, frame: u32, rows: u32, columns: u32) -> Vec<bool> {
+        let mut mask = vec![false; rows as usize * columns as usize];
+        let data = match self.frame(frame) {
+            Some(data) => data,
+            None => return mask,
+        };
+        let (origin_row, origin_column) =
+            (i64::from(self.origin.0) - 1, i64::from(self.origin.1) - 1);
+        for (i, _) in data.iter().enumerate().filter(|(_, set)| **set) {
+            let row = origin_row + (i / self.columns as usize) as i64;
+            let column = origin_column + (i % self.columns as usize) as i64;
+            if row >= 0 && column >= 0 && row < i64::from(rows) && column < i64::from(columns) {
+                mask[row as usize * columns as usize + column as usize] = true;
+            }
+        }
+        mask
+    }
+}
+
+/// Decode all overlay planes of a DICOM object.
+///
+/// Overlays without _Overlay Data_ are taken from
+/// the bit at _Overlay Bit Position_ of each pixel data sample,
+/// in which case the pixel data is decoded
+/// and the overlay has as many frames as the image.
+pub fn overlays<D>(obj: &FileDicomObject<InMemDicomObject<D>>) -> Result<Vec<Overlay>>
+where
+    D: DataDictionary + Clone,
+{
+    let mut out = Vec::new();
+    let mut decoded = None;
+
+    for group in OVERLAY_GROUPS.step_by(2) {
+        let rows: u16 = match overlay_int(obj, group, OVERLAY_ROWS, "OverlayRows")? {
+            Some(rows) => rows,
+            None => continue,
+        };
+        let columns: u16 = overlay_int(obj, group, OVERLAY_COLUMNS, "OverlayColumns")?.context(
+            InvalidOverlayAttributeSnafu {
+                group,
+                name: "OverlayColumns",
+            },
+        )?;
+        let origin = match element(obj, group, OVERLAY_ORIGIN) {
+            Some(e) => match e.to_multi_int::<i16>().ok().as_deref() {
+                Some(&[row, column]) => (row, column),
+                _ => {
+                    return InvalidOverlayAttributeSnafu {
+                        group,
+                        name: "OverlayOrigin",
+                    }
+                    .fail()?
+                }
+            },
+            None => (1, 1),
+        };
+        let frame_length = rows as usize * columns as usize;
+
+        let (data, number_of_frames, image_frame_origin, embedded) =
+            match element(obj, group, OVERLAY_DATA).map(|e| e.value()) {
+                Some(Value::Primitive(value)) => {
+                    let number_of_frames: u32 = overlay_int(
+                        obj,
+                        group,
+                        NUMBER_OF_FRAMES_IN_OVERLAY,
+                        "NumberOfFramesInOverlay",
+                    )?
+                    .unwrap_or(1);
+                    let image_frame_origin: u32 =
+                        overlay_int(obj, group, IMAGE_FRAME_ORIGIN, "ImageFrameOrigin")?
+                            .unwrap_or(1);
+                    let bits = frame_length * number_of_frames as usize;
+                    let data = unpack_overlay_data(value, bits);
+                    ensure!(
+                        data.len() == bits,
+                        InvalidOverlayAttributeSnafu {
+                            group,
+                            name: "OverlayData",
+                        }
+                    );
+                    (data, number_of_frames, image_frame_origin, false)
+                }
+                Some(_) => {
+                    return InvalidOverlayAttributeSnafu {
+                        group,
+                        name: "OverlayData",
+                    }
+                    .fail()?
+                }
+                None => {
+                    let bit_position: u16 =
+                        overlay_int(obj, group, OVERLAY_BIT_POSITION, "OverlayBitPosition")?
+                            .context(InvalidOverlayAttributeSnafu {
+                                group,
+                                name: "OverlayData",
+                            })?;
+                    if decoded.is_none() {
+                        decoded = Some(obj.decode_pixel_data()?);
+                    }
+                    let pixels = decoded.as_ref().unwrap();
+                    ensure!(
+                        pixels.rows() == u32::from(rows)
+                            && pixels.columns() == u32::from(columns)
+                            && pixels.samples_per_pixel() == 1,
+                        InvalidOverlayAttributeSnafu {
+                            group,
+                            name: "OverlayRows",
+                        }
+                    );
+                    ensure!(
+                        bit_position < pixels.bits_allocated(),
+                        InvalidOverlayAttributeSnafu {
+                            group,
+                            name: "OverlayBitPosition",
+                        }
+                    );
+                    let data: Vec<bool> = match pixels.bits_allocated() {
+                        8 => pixels
+                            .data()
+                            .iter()
+                            .map(|&sample| (sample >> bit_position) & 1 == 1)
+                            .collect(),
+                        _ => pixels
+                            .data_ow()
+                            .into_iter()
+                            .map(|sample| (sample >> bit_position) & 1 == 1)
+                            .collect(),
+                    };
+                    (data, pixels.number_of_frames(), 1, true)
+                }
+            };
+
+        out.push(Overlay {
+            group,
+            rows,
+            columns,
+            origin,
+            overlay_type: overlay_string(obj, group, OVERLAY_TYPE).unwrap_or_else(|| "G".into()),
+            label: overlay_string(obj, group, OVERLAY_LABEL),
+            description: overlay_string(obj, group, OVERLAY_DESCRIPTION),
+            number_of_frames,
+            image_frame_origin,
+            embedded,
+            data,
+        });
+    }
+
+    Ok(out)
+}
+
+/// Unpack the given number of overlay bits,
+/// taken from the least significant bit of each byte
+/// (or 16-bit word, if the data was read as OW).
+///
+/// Returns fewer bits if the data is too short.
+fn unpack_overlay_data(value: &PrimitiveValue, bits: usize) -> Vec<bool> {
+    match value {
+        PrimitiveValue::U16(words) => {
+            let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_le_bytes()).collect();
+            unpack_bits(&bytes, bits)
+        }
+        value => unpack_bits(&value.to_bytes(), bits),
+    }
+}
+
+fn element<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    group: u16,
+    element: u16,
+) -> Option<&InMemElement<D>>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(Tag(group, element)).ok().flatten()
+}
+
+fn overlay_int<D, T>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    group: u16,
+    element_number: u16,
+    name: &'static str,
+) -> Result<Option<T>>
+where
+    D: DataDictionary + Clone,
+    T: Clone + num_traits::NumCast + std::str::FromStr<Err = std::num::ParseIntError>,
+{
+    match element(obj, group, element_number) {
+        Some(e) => Ok(Some(
+            e.to_int()
+                .ok()
+                .context(InvalidOverlayAttributeSnafu { group, name })?,
+        )),
+        None => Ok(None),
+    }
+}
+
+fn overlay_string<D>(
+    obj: &FileDicomObject<InMemDicomObject<D>>,
+    group: u16,
+    element_number: u16,
+) -> Option<String>
+where
+    D: DataDictionary + Clone,
+{
+    element(obj, group, element_number)
+        .and_then(|e| e.to_str().ok())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::{DataElement, VR};
+    use dicom_dictionary_std::tags;
+    use dicom_object::FileMetaTableBuilder;
+
+    fn image(pixels: PrimitiveValue, bits_allocated: u16) -> InMemDicomObject {
+        InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SAMPLES_PER_PIXEL,
+                VR::US,
+                PrimitiveValue::from([1_u16]),
+            ),
+            DataElement::new(
+                tags::PHOTOMETRIC_INTERPRETATION,
+                VR::CS,
+                PrimitiveValue::from("MONOCHROME2"),
+            ),
+            DataElement::new(tags::ROWS, VR::US, PrimitiveValue::from([2_u16])),
+            DataElement::new(tags::COLUMNS, VR::US, PrimitiveValue::from([3_u16])),
+            DataElement::new(
+                tags::BITS_ALLOCATED,
+                VR::US,
+                PrimitiveValue::from([bits_allocated]),
+            ),
+            DataElement::new(
+                tags::BITS_STORED,
+                VR::US,
+                PrimitiveValue::from([bits_allocated - 4]),
+            ),
+            DataElement::new(
+                tags::HIGH_BIT,
+                VR::US,
+                PrimitiveValue::from([bits_allocated - 5]),
+            ),
+            DataElement::new(
+                tags::PIXEL_REPRESENTATION,
+                VR::US,
+                PrimitiveValue::from([0_u16]),
+            ),
+            DataElement::new(tags::PIXEL_DATA, VR::OW, pixels),
+        ])
+    }
+
+    fn with_meta(obj: InMemDicomObject) -> FileDicomObject<InMemDicomObject> {
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .media_storage_sop_instance_uid("2.25.1")
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn decodes_overlay_data() {
+        let mut obj = image(PrimitiveValue::from([0_u16; 6]), 16);
+        obj.put(DataElement::new(
+            Tag(0x6002, OVERLAY_ROWS),
+            VR::US,
+            PrimitiveValue::from([2_u16]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x6002, OVERLAY_COLUMNS),
+            VR::US,
+            PrimitiveValue::from([3_u16]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x6002, OVERLAY_TYPE),
+            VR::CS,
+            PrimitiveValue::from("R"),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x6002, OVERLAY_ORIGIN),
+            VR::SS,
+            PrimitiveValue::from([1_i16, 2]),
+        ));
+        // bits 0, 2, and 4 set
+        obj.put(DataElement::new(
+            Tag(0x6002, OVERLAY_DATA),
+            VR::OW,
+            PrimitiveValue::from([0b0001_0101_u16]),
+        ));
+        let obj = with_meta(obj);
+
+        let overlays = overlays(&obj).unwrap();
+        assert_eq!(overlays.len(), 1);
+        let overlay = &overlays[0];
+        assert_eq!(overlay.group(), 0x6002);
+        assert_eq!(overlay.overlay_type(), "R");
+        assert!(!overlay.is_embedded());
+        assert_eq!(
+            overlay.frame(0).unwrap(),
+            &[true, false, true, false, true, false][..]
+        );
+        assert!(overlay.get(0, 1, 1));
+        assert!(!overlay.get(0, 1, 2));
+        assert_eq!(overlay.frame(1), None);
+
+        // shifted one column to the right
+        assert_eq!(
+            overlay.to_image_mask(0, 2, 3),
+            vec![false, true, false, false, false, true]
+        );
+    }
+
+    #[test]
+    fn decodes_overlay_embedded_in_pixel_data() {
+        let mut obj = image(
+            PrimitiveValue::from([0x1000_u16, 0x0FFF, 0x1001, 0, 0, 0x1ABC]),
+            16,
+        );
+        obj.put(DataElement::new(
+            Tag(0x6000, OVERLAY_ROWS),
+            VR::US,
+            PrimitiveValue::from([2_u16]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x6000, OVERLAY_COLUMNS),
+            VR::US,
+            PrimitiveValue::from([3_u16]),
+        ));
+        obj.put(DataElement::new(
+            Tag(0x6000, OVERLAY_BIT_POSITION),
+            VR::US,
+            PrimitiveValue::from([12_u16]),
+        ));
+        let obj = with_meta(obj);
+
+        let overlays = overlays(&obj).unwrap();
+        assert_eq!(overlays.len(), 1);
+        let overlay = &overlays[0];
+        assert!(overlay.is_embedded());
+        assert_eq!(overlay.number_of_frames(), 1);
+        assert_eq!(overlay.data(), &[true, false, true, false, false, true][..]);
+    }
+
+    #[test]
+    fn objects_without_overlays() {
+        let obj = with_meta(image(PrimitiveValue::from([0_u16; 6]), 16));
+        assert_eq!(overlays(&obj).unwrap(), vec![]);
+    }
+}
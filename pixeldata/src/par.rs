@@ -0,0 +1,48 @@
+//! Iterator traits for processing samples in parallel.
+//!
+//! With the `parallel` feature enabled,
+//! these are the parallel iterator traits from rayon.
+//! Otherwise, they are implemented over standard iterators,
+//! so that the same code runs sequentially.
+
+#[cfg(feature = "parallel")]
+pub(crate) use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
+
+#[cfg(not(feature = "parallel"))]
+pub(crate) use self::sequential::{
+    IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
+
+#[cfg(not(feature = "parallel"))]
+mod sequential {
+    /// An iterator which would be parallel with the `parallel` feature.
+    pub trait ParallelIterator: Iterator {}
+
+    impl<I: Iterator> ParallelIterator for I {}
+
+    /// Conversion into an iterator
+    /// which would be parallel with the `parallel` feature.
+    pub trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<I: IntoIterator> IntoParallelIterator for I {}
+
+    /// Iteration by reference
+    /// which would be parallel with the `parallel` feature.
+    pub trait IntoParallelRefIterator<'a> {
+        type Iter: Iterator;
+
+        fn par_iter(&'a self) -> Self::Iter;
+    }
+
+    impl<'a, T: 'a> IntoParallelRefIterator<'a> for [T] {
+        type Iter = std::slice::Iter<'a, T>;
+
+        fn par_iter(&'a self) -> Self::Iter {
+            self.iter()
+        }
+    }
+}
@@ -0,0 +1,363 @@
+//! Utilities for repacking native pixel data samples
+//! between different bit depths.
+//!
+//! A [`SampleLayout`] describes how sample values are laid out
+//! according to _Bits Allocated_, _Bits Stored_, _High Bit_
+//! and _Pixel Representation_.
+//! Sample buffers can be unpacked into plain integer values,
+//! packed back into another layout as is ([`repack`]),
+//! scaled to the range of another layout ([`rescale_bits`]),
+//! or mapped through a window level ([`window`]).
+//!
+//! Buffers are in native byte order,
+//! as in [`DecodedPixelData::data`](crate::DecodedPixelData::data).
+//!
+//! [`pack_bits`] and [`unpack_bits`] convert
+//! between boolean masks and 1-bit packed data,
+//! as used in overlays and binary segmentations.
+
+use byteorder::{ByteOrder, NativeEndian};
+use snafu::ensure;
+
+use crate::{
+    DecodedPixelData, InvalidBitsAllocatedSnafu, InvalidBitsStoredSnafu, InvalidHighBitSnafu,
+    PixelRepresentation, Result, WindowLevelTransform,
+};
+
+/// The layout of native pixel data samples.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub struct SampleLayout {
+    bits_allocated: u16,
+    bits_stored: u16,
+    high_bit: u16,
+    pixel_representation: PixelRepresentation,
+}
+
+impl SampleLayout {
+    /// Create a new sample layout.
+    ///
+    /// Fails if _Bits Allocated_ is not 8 or 16,
+    /// if _Bits Stored_ is not between 1 and _Bits Allocated_,
+    /// or if the stored bits do not fit under _High Bit_.
+    pub fn new(
+        bits_allocated: u16,
+        bits_stored: u16,
+        high_bit: u16,
+        pixel_representation: PixelRepresentation,
+    ) -> Result<Self> {
+        ensure!(
+            bits_allocated == 8 || bits_allocated == 16,
+            InvalidBitsAllocatedSnafu
+        );
+        ensure!(
+            bits_stored > 0 && bits_stored <= bits_allocated,
+            InvalidBitsStoredSnafu {
+                bits_stored,
+                bits_allocated,
+            }
+        );
+        ensure!(
+            high_bit < bits_allocated && high_bit + 1 >= bits_stored,
+            InvalidHighBitSnafu {
+                high_bit,
+                bits_stored,
+            }
+        );
+        Ok(SampleLayout {
+            bits_allocated,
+            bits_stored,
+            high_bit,
+            pixel_representation,
+        })
+    }
+
+    /// Create a new sample layout with the high bit
+    /// at `bits_stored - 1`.
+    pub fn with_bits_stored(
+        bits_allocated: u16,
+        bits_stored: u16,
+        pixel_representation: PixelRepresentation,
+    ) -> Result<Self> {
+        Self::new(
+            bits_allocated,
+            bits_stored,
+            bits_stored.saturating_sub(1),
+            pixel_representation,
+        )
+    }
+
+    /// Retrieve the sample layout of decoded pixel data.
+    pub fn of(pixel_data: &DecodedPixelData) -> Result<Self> {
+        Self::new(
+            pixel_data.bits_allocated(),
+            pixel_data.bits_stored(),
+            pixel_data.high_bit(),
+            pixel_data.pixel_representation(),
+        )
+    }
+
+    /// Retrieve the number of bits allocated per sample.
+    #[inline]
+    pub fn bits_allocated(&self) -> u16 {
+        self.bits_allocated
+    }
+
+    /// Retrieve the number of bits stored per sample.
+    #[inline]
+    pub fn bits_stored(&self) -> u16 {
+        self.bits_stored
+    }
+
+    /// Retrieve the position of the most significant stored bit.
+    #[inline]
+    pub fn high_bit(&self) -> u16 {
+        self.high_bit
+    }
+
+    /// Retrieve the pixel representation.
+    #[inline]
+    pub fn pixel_representation(&self) -> PixelRepresentation {
+        self.pixel_representation
+    }
+
+    /// The lowest value which can be stored in this layout.
+    pub fn min_value(&self) -> i32 {
+        match self.pixel_representation {
+            PixelRepresentation::Unsigned => 0,
+            PixelRepresentation::Signed => -(1 << (self.bits_stored - 1)),
+        }
+    }
+
+    /// The highest value which can be stored in this layout.
+    pub fn max_value(&self) -> i32 {
+        match self.pixel_representation {
+            PixelRepresentation::Unsigned => (1 << self.bits_stored) - 1,
+            PixelRepresentation::Signed => (1 << (self.bits_stored - 1)) - 1,
+        }
+    }
+
+    /// Retrieve the number of bytes per sample.
+    #[inline]
+    fn bytes_per_sample(&self) -> usize {
+        self.bits_allocated as usize / 8
+    }
+
+    /// Unpack the stored sample values of the given buffer,
+    /// discarding any bits outside of the stored bits
+    /// and sign-extending signed values.
+    ///
+    /// A trailing incomplete sample is ignored.
+    pub fn unpack(&self, data: &[u8]) -> Vec<i32> {
+        let shift = self.high_bit + 1 - self.bits_stored;
+        let mask = (1_u32 << self.bits_stored) - 1;
+        data.chunks_exact(self.bytes_per_sample())
+            .map(|bytes| {
+                let raw = match self.bits_allocated {
+                    8 => u32::from(bytes[0]),
+                    _ => u32::from(NativeEndian::read_u16(bytes)),
+                };
+                let value = (raw >> shift) & mask;
+                match self.pixel_representation {
+                    PixelRepresentation::Signed => {
+                        // sign-extend from the stored bits
+                        let unused = 32 - u32::from(self.bits_stored);
+                        ((value << unused) as i32) >> unused
+                    }
+                    PixelRepresentation::Unsigned => value as i32,
+                }
+            })
+            .collect()
+    }
+
+    /// Pack the given sample values into a new buffer,
+    /// clamping them to the range of this layout.
+    ///
+    /// Bits outside of the stored bits are left at zero.
+    /// The buffer is padded to an even length if necessary.
+    pub fn pack(&self, values: &[i32]) -> Vec<u8> {
+        let shift = self.high_bit + 1 - self.bits_stored;
+        let mask = (1_u32 << self.bits_stored) - 1;
+        let (min, max) = (self.min_value(), self.max_value());
+        let mut out = Vec::with_capacity(values.len() * self.bytes_per_sample() + 1);
+        for &value in values {
+            let raw = ((value.clamp(min, max) as u32) & mask) << shift;
+            match self.bits_allocated {
+                8 => out.push(raw as u8),
+                _ => {
+                    let mut bytes = [0; 2];
+                    NativeEndian::write_u16(&mut bytes, raw as u16);
+                    out.extend_from_slice(&bytes);
+                }
+            }
+        }
+        if out.len() % 2 != 0 {
+            out.push(0);
+        }
+        out
+    }
+}
+
+/// Repack samples from one layout into another,
+/// keeping their values.
+///
+/// Values which do not fit in the target layout are clamped.
+pub fn repack(data: &[u8], from: &SampleLayout, to: &SampleLayout) -> Vec<u8> {
+    to.pack(&from.unpack(data))
+}
+
+/// Repack samples from one layout into another,
+/// linearly scaling the full range of values of the source layout
+/// into the full range of values of the target layout.
+///
+/// For instance, scaling 8-bit samples to 16 bits maps 255 to 65535,
+/// and scaling 12-bit samples to 8 bits maps 4095 to 255.
+pub fn rescale_bits(data: &[u8], from: &SampleLayout, to: &SampleLayout) -> Vec<u8> {
+    let (from_min, from_range) = (
+        i64::from(from.min_value()),
+        i64::from(from.max_value()) - i64::from(from.min_value()),
+    );
+    let (to_min, to_range) = (
+        i64::from(to.min_value()),
+        i64::from(to.max_value()) - i64::from(to.min_value()),
+    );
+    let values: Vec<i32> = from
+        .unpack(data)
+        .into_iter()
+        .map(|value| {
+            let offset = i64::from(value) - from_min;
+            // round to nearest
+            (to_min + (offset * to_range * 2 + from_range) / (from_range * 2)) as i32
+        })
+        .collect();
+    to.pack(&values)
+}
+
+/// Map the given sample values through a window level transformation,
+/// into the full range of values of the target layout.
+///
+/// The values are expected to have already gone through
+/// the modality LUT, if any.
+pub fn window(values: &[f64], transform: &WindowLevelTransform, to: &SampleLayout) -> Vec<u8> {
+    let y_max = f64::from(to.max_value()) - f64::from(to.min_value());
+    let values: Vec<i32> = values
+        .iter()
+        .map(|&value| to.min_value() + transform.apply(value, y_max).round() as i32)
+        .collect();
+    to.pack(&values)
+}
+
+/// Pack a mask into 1-bit data,
+/// with the first value in the least significant bit of the first byte.
+///
+/// The data is padded with zeros to an even length.
+pub fn pack_bits(mask: &[bool]) -> Vec<u8> {
+    let mut out: Vec<u8> = mask
+        .chunks(8)
+        .map(|bits| {
+            bits.iter()
+                .enumerate()
+                .filter(|(_, set)| **set)
+                .fold(0, |byte, (i, _)| byte | (1 << i))
+        })
+        .collect();
+    if out.len() % 2 != 0 {
+        out.push(0);
+    }
+    out
+}
+
+/// Unpack `len` values from 1-bit data,
+/// starting from the least significant bit of the first byte.
+///
+/// Returns fewer values if the data is too short.
+pub fn unpack_bits(data: &[u8], len: usize) -> Vec<bool> {
+    data.iter()
+        .flat_map(|byte| (0..8).map(move |bit| (byte >> bit) & 1 == 1))
+        .take(len)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WindowLevel;
+
+    fn words(values: &[u16]) -> Vec<u8> {
+        let mut out = vec![0; values.len() * 2];
+        NativeEndian::write_u16_into(values, &mut out);
+        out
+    }
+
+    #[test]
+    fn unpacks_stored_bits_with_high_bit() {
+        // 12 bits stored at bits 4..=15
+        let layout = SampleLayout::new(16, 12, 15, PixelRepresentation::Unsigned).unwrap();
+        assert_eq!(
+            layout.unpack(&words(&[0xFFF0, 0x001F, 0x0010])),
+            vec![4095, 1, 1]
+        );
+
+        // signed 12 bits, with garbage in the unused bits
+        let layout = SampleLayout::with_bits_stored(16, 12, PixelRepresentation::Signed).unwrap();
+        assert_eq!(
+            layout.unpack(&words(&[0xFFFF, 0xF7FF, 0x0800])),
+            vec![-1, 2047, -2048]
+        );
+        assert_eq!(
+            layout.pack(&[-1, 2047, -5000]),
+            words(&[0x0FFF, 0x07FF, 0x0800])
+        );
+
+        assert!(SampleLayout::new(16, 12, 10, PixelRepresentation::Unsigned).is_err());
+        assert!(SampleLayout::new(16, 17, 16, PixelRepresentation::Unsigned).is_err());
+        assert!(SampleLayout::new(12, 12, 11, PixelRepresentation::Unsigned).is_err());
+    }
+
+    #[test]
+    fn repacks_and_rescales_between_bit_depths() {
+        let u8_layout =
+            SampleLayout::with_bits_stored(8, 8, PixelRepresentation::Unsigned).unwrap();
+        let u12_layout =
+            SampleLayout::with_bits_stored(16, 12, PixelRepresentation::Unsigned).unwrap();
+        let u16_layout =
+            SampleLayout::with_bits_stored(16, 16, PixelRepresentation::Unsigned).unwrap();
+
+        // values are kept, odd lengths are padded
+        assert_eq!(
+            repack(&words(&[0, 200, 4095]), &u12_layout, &u8_layout),
+            vec![0, 200, 255, 0]
+        );
+
+        assert_eq!(
+            rescale_bits(&[0, 128, 255, 0], &u8_layout, &u16_layout),
+            words(&[0, 32896, 65535, 0])
+        );
+        assert_eq!(
+            rescale_bits(&words(&[0, 2048, 4095]), &u12_layout, &u8_layout),
+            vec![0, 128, 255, 0]
+        );
+    }
+
+    #[test]
+    fn windows_into_8_bits() {
+        let u8_layout =
+            SampleLayout::with_bits_stored(8, 8, PixelRepresentation::Unsigned).unwrap();
+        let transform = WindowLevelTransform::linear(WindowLevel {
+            center: 40.,
+            width: 400.,
+        });
+        assert_eq!(
+            window(&[-1000., -160., 40., 240., 3000.], &transform, &u8_layout),
+            vec![0, 0, 128, 255, 255, 0]
+        );
+    }
+
+    #[test]
+    fn packs_and_unpacks_bits() {
+        let mask = [true, false, true, true, false, false, false, false, true];
+        let packed = pack_bits(&mask);
+        assert_eq!(packed, vec![0b0000_1101, 0b0000_0001]);
+        assert_eq!(unpack_bits(&packed, mask.len()), mask.to_vec());
+        assert_eq!(unpack_bits(&packed, 100).len(), 16);
+    }
+}
@@ -0,0 +1,488 @@
+//! Sorting of image series and assembly of 3D volumes.
+//!
+//! [`group_by_series`] splits a collection of single-frame image objects
+//! by _Series Instance UID_.
+//! [`sort_slices`] sorts the slices of a series in space,
+//! by projecting their positions onto the slice normal,
+//! or by _Instance Number_ when the geometry is missing or inconsistent,
+//! and reports duplicate and missing slices.
+//! [`assemble_volume`] stacks the pixel data of a complete series
+//! into a [`Volume`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::error::Error;
+//! use dicom_object::open_file;
+//! use dicom_pixeldata::{assemble_volume, group_by_series};
+//! # fn main() -> Result<(), Box<dyn Error>> {
+//! let objects = std::fs::read_dir("ct_study")?
+//!     .map(|entry| Ok(open_file(entry?.path())?))
+//!     .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+//! for (series_uid, slices) in group_by_series(objects) {
+//!     let volume = assemble_volume(&slices)?;
+//!     println!("{}: {:?}, {:?} mm apart", series_uid, volume.dim(), volume.slice_spacing());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use dicom_core::DataDictionary;
+use dicom_dictionary_std::tags;
+use dicom_object::geometry::{self, ImagePlane};
+use dicom_object::{FileDicomObject, InMemDicomObject};
+use ndarray::{s, Array3};
+use snafu::{ensure, OptionExt};
+
+use crate::{
+    DuplicateSliceSnafu, MissingInstanceNumberSnafu, MissingSlicesSnafu, NoSlicesSnafu,
+    PixelDecoder, Result, SliceSizeMismatchSnafu, UnsupportedMultiFrameSnafu,
+    UnsupportedSamplesPerPixelSnafu,
+};
+
+/// Split image objects by _Series Instance UID_,
+/// keeping their relative order.
+///
+/// Objects without a series instance UID are grouped under an empty string.
+pub fn group_by_series<D, I>(
+    objects: I,
+) -> BTreeMap<String, Vec<FileDicomObject<InMemDicomObject<D>>>>
+where
+    D: DataDictionary + Clone,
+    I: IntoIterator<Item = FileDicomObject<InMemDicomObject<D>>>,
+{
+    let mut series: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for obj in objects {
+        let uid = obj
+            .element_opt(tags::SERIES_INSTANCE_UID)
+            .ok()
+            .flatten()
+            .and_then(|e| e.to_str().ok())
+            .map(|uid| uid.trim_end_matches(|c| c == '\0' || c == ' ').to_string())
+            .unwrap_or_default();
+        series.entry(uid).or_default().push(obj);
+    }
+    series
+}
+
+/// The criterion used to sort the slices of a series.
+#[derive(Debug, Copy, Clone, Eq, Hash, PartialEq)]
+pub enum SortMethod {
+    /// Slices were sorted by the projection of their position
+    /// onto the slice normal.
+    Position,
+    /// Slices were sorted by _Instance Number_.
+    InstanceNumber,
+}
+
+/// The outcome of sorting the slices of a series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SliceOrder {
+    /// The indices of the slices in the original collection,
+    /// in sorted order.
+    pub order: Vec<usize>,
+    /// The criterion used to sort the slices.
+    pub method: SortMethod,
+    /// The original indices of slices
+    /// at the same position (or with the same instance number)
+    /// as the slice before them in sorted order.
+    pub duplicates: Vec<usize>,
+    /// The positions in sorted order after which slices are missing,
+    /// with the number of missing slices.
+    pub gaps: Vec<(usize, u32)>,
+    /// The distance between consecutive slices in millimeters,
+    /// if sorted by position.
+    pub spacing: Option<f64>,
+}
+
+impl SliceOrder {
+    /// Whether the series has no duplicate or missing slices.
+    pub fn is_complete(&self) -> bool {
+        self.duplicates.is_empty() && self.gaps.is_empty()
+    }
+}
+
+/// Sort the slices of a series in space.
+///
+/// Slices are sorted by their position along the slice normal
+/// when all of them have a consistent image plane,
+/// and by _Instance Number_ otherwise.
+pub fn sort_slices<D>(slices: &[FileDicomObject<InMemDicomObject<D>>]) -> Result<SliceOrder>
+where
+    D: DataDictionary + Clone,
+{
+    ensure!(!slices.is_empty(), NoSlicesSnafu);
+
+    let planes: Option<Vec<ImagePlane>> = slices
+        .iter()
+        .map(|obj| ImagePlane::from_object(obj).ok())
+        .collect();
+    let planes = planes
+        .filter(|planes| geometry::check_consistency(planes, geometry::DEFAULT_TOLERANCE).is_ok());
+
+    match planes {
+        Some(planes) => Ok(sort_by_position(slices, &planes)),
+        None => sort_by_instance_number(slices),
+    }
+}
+
+fn sort_by_position<D>(
+    slices: &[FileDicomObject<InMemDicomObject<D>>],
+    planes: &[ImagePlane],
+) -> SliceOrder
+where
+    D: DataDictionary + Clone,
+{
+    let normal = planes[0].normal();
+    let location = |i: usize| {
+        let p = planes[i].position;
+        p[0] * normal[0] + p[1] * normal[1] + p[2] * normal[2]
+    };
+    let mut order: Vec<usize> = (0..slices.len()).collect();
+    order.sort_by(|&a, &b| {
+        location(a)
+            .partial_cmp(&location(b))
+            .unwrap_or(Ordering::Equal)
+            .then_with(|| instance_number(&slices[a]).cmp(&instance_number(&slices[b])))
+    });
+
+    let mut duplicates = Vec::new();
+    let mut steps = Vec::new();
+    for pair in order.windows(2) {
+        let step = location(pair[1]) - location(pair[0]);
+        if step <= geometry::DEFAULT_TOLERANCE {
+            duplicates.push(pair[1]);
+        }
+        steps.push(step);
+    }
+
+    // the most common distance is the median of the distinct steps
+    let mut distinct: Vec<f64> = steps
+        .iter()
+        .copied()
+        .filter(|&step| step > geometry::DEFAULT_TOLERANCE)
+        .collect();
+    distinct.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    let spacing = distinct.get(distinct.len() / 2).copied();
+
+    let gaps = match spacing {
+        Some(spacing) => steps
+            .iter()
+            .enumerate()
+            .filter(|(_, &step)| step > spacing * 1.5)
+            .map(|(i, &step)| (i, (step / spacing).round() as u32 - 1))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    SliceOrder {
+        order,
+        method: SortMethod::Position,
+        duplicates,
+        gaps,
+        spacing,
+    }
+}
+
+fn sort_by_instance_number<D>(slices: &[FileDicomObject<InMemDicomObject<D>>]) -> Result<SliceOrder>
+where
+    D: DataDictionary + Clone,
+{
+    let numbers = slices
+        .iter()
+        .enumerate()
+        .map(|(index, obj)| instance_number(obj).context(MissingInstanceNumberSnafu { index }))
+        .collect::<Result<Vec<i64>, _>>()?;
+    let mut order: Vec<usize> = (0..slices.len()).collect();
+    order.sort_by_key(|&i| numbers[i]);
+
+    let mut duplicates = Vec::new();
+    let mut gaps = Vec::new();
+    for (i, pair) in order.windows(2).enumerate() {
+        let step = numbers[pair[1]] - numbers[pair[0]];
+        if step == 0 {
+            duplicates.push(pair[1]);
+        } else if step > 1 {
+            gaps.push((i, (step - 1) as u32));
+        }
+    }
+
+    Ok(SliceOrder {
+        order,
+        method: SortMethod::InstanceNumber,
+        duplicates,
+        gaps,
+        spacing: None,
+    })
+}
+
+fn instance_number<D>(obj: &FileDicomObject<InMemDicomObject<D>>) -> Option<i64>
+where
+    D: DataDictionary + Clone,
+{
+    obj.element_opt(tags::INSTANCE_NUMBER)
+        .ok()
+        .flatten()
+        .and_then(|e| e.to_int().ok())
+}
+
+/// A 3D volume assembled from the slices of a series.
+///
+/// Voxel values have the modality LUT applied,
+/// and are indexed by (slice, row, column).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Volume {
+    data: Array3<f32>,
+    order: SliceOrder,
+    plane: Option<ImagePlane>,
+}
+
+impl Volume {
+    /// Retrieve the voxel values.
+    #[inline]
+    pub fn data(&self) -> &Array3<f32> {
+        &self.data
+    }
+
+    /// Take the voxel values.
+    #[inline]
+    pub fn into_data(self) -> Array3<f32> {
+        self.data
+    }
+
+    /// Retrieve the dimensions of the volume,
+    /// as (slices, rows, columns).
+    #[inline]
+    pub fn dim(&self) -> (usize, usize, usize) {
+        self.data.dim()
+    }
+
+    /// Retrieve the order in which the slices of the series were stacked.
+    #[inline]
+    pub fn slice_order(&self) -> &SliceOrder {
+        &self.order
+    }
+
+    /// Retrieve the image plane of the first slice,
+    /// if the series has a consistent geometry.
+    #[inline]
+    pub fn plane(&self) -> Option<&ImagePlane> {
+        self.plane.as_ref()
+    }
+
+    /// Retrieve the distance between consecutive slices in millimeters,
+    /// if the series has a consistent geometry.
+    #[inline]
+    pub fn slice_spacing(&self) -> Option<f64> {
+        self.order.spacing
+    }
+
+    /// Retrieve the voxel spacing in millimeters,
+    /// as (slice spacing, row spacing, column spacing),
+    /// if the series has a consistent geometry.
+    pub fn spacing(&self) -> Option<[f64; 3]> {
+        let plane = self.plane.as_ref()?;
+        let slice_spacing = self.order.spacing.or(plane.slice_thickness).unwrap_or(1.);
+        Some([
+            slice_spacing,
+            plane.pixel_spacing[0],
+            plane.pixel_spacing[1],
+        ])
+    }
+
+    /// Obtain the affine transformation from voxel indices
+    /// (column, row, slice, 1) to patient coordinates,
+    /// if the series has a consistent geometry.
+    pub fn voxel_to_patient(&self) -> Option<[[f64; 4]; 4]> {
+        let [slice_spacing, _, _] = self.spacing()?;
+        Some(self.plane.as_ref()?.voxel_to_patient(slice_spacing))
+    }
+}
+
+/// Sort the slices of a series and stack their pixel data into a volume.
+///
+/// Fails if the series has duplicate or missing slices,
+/// or if the slices are not single-frame monochrome images
+/// of the same size.
+pub fn assemble_volume<D>(slices: &[FileDicomObject<InMemDicomObject<D>>]) -> Result<Volume>
+where
+    D: DataDictionary + Clone,
+{
+    let order = sort_slices(slices)?;
+    if let Some(&index) = order.duplicates.first() {
+        return DuplicateSliceSnafu { index }.fail()?;
+    }
+    if let Some(&(position, count)) = order.gaps.first() {
+        return MissingSlicesSnafu {
+            after: order.order[position],
+            count,
+        }
+        .fail()?;
+    }
+
+    let mut data: Option<Array3<f32>> = None;
+    for (k, &index) in order.order.iter().enumerate() {
+        let decoded = slices[index].decode_pixel_data()?;
+        ensure!(decoded.number_of_frames() == 1, UnsupportedMultiFrameSnafu);
+        ensure!(
+            decoded.samples_per_pixel() == 1,
+            UnsupportedSamplesPerPixelSnafu {
+                spp: decoded.samples_per_pixel(),
+            }
+        );
+        let frame = decoded.to_ndarray_frame::<f32>(0)?;
+        let (rows, columns) = (frame.dim().0, frame.dim().1);
+        let volume = data.get_or_insert_with(|| Array3::zeros((order.order.len(), rows, columns)));
+        ensure!(
+            volume.dim().1 == rows && volume.dim().2 == columns,
+            SliceSizeMismatchSnafu { index }
+        );
+        volume
+            .slice_mut(s![k, .., ..])
+            .assign(&frame.slice(s![.., .., 0]));
+    }
+
+    let plane = match order.method {
+        SortMethod::Position => ImagePlane::from_object(&slices[order.order[0]]).ok(),
+        SortMethod::InstanceNumber => None,
+    };
+
+    Ok(Volume {
+        // there is at least one slice
+        data: data.unwrap(),
+        order,
+        plane,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NativePixelDataWriter;
+    use dicom_core::value::PrimitiveValue;
+    use dicom_core::{DataElement, VR};
+    use dicom_object::FileMetaTableBuilder;
+
+    fn slice(
+        series: &str,
+        instance_number: i32,
+        z: Option<f64>,
+        value: u16,
+    ) -> FileDicomObject<InMemDicomObject> {
+        let mut obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SERIES_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(series),
+            ),
+            DataElement::new(
+                tags::INSTANCE_NUMBER,
+                VR::IS,
+                PrimitiveValue::from(instance_number.to_string()),
+            ),
+        ]);
+        if let Some(z) = z {
+            obj.put(DataElement::new(
+                tags::IMAGE_POSITION_PATIENT,
+                VR::DS,
+                PrimitiveValue::Strs(vec!["0".to_string(), "0".to_string(), z.to_string()].into()),
+            ));
+            obj.put(DataElement::new(
+                tags::IMAGE_ORIENTATION_PATIENT,
+                VR::DS,
+                PrimitiveValue::Strs(
+                    ["1", "0", "0", "0", "1", "0"]
+                        .iter()
+                        .map(|s| s.to_string())
+                        .collect(),
+                ),
+            ));
+            obj.put(DataElement::new(
+                tags::PIXEL_SPACING,
+                VR::DS,
+                PrimitiveValue::Strs(vec!["0.5".to_string(), "0.5".to_string()].into()),
+            ));
+        }
+        let mut writer = NativePixelDataWriter::<u16>::new(2, 2);
+        writer.push_frame(&[value; 4]).unwrap();
+        writer.write_to(&mut obj).unwrap();
+        obj.with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.2")
+                .media_storage_sop_instance_uid(format!("2.25.{}", instance_number))
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn groups_and_assembles_by_position() {
+        let objects = vec![
+            slice("1.2.3", 3, Some(-5.), 30),
+            slice("1.2.4", 1, Some(0.), 0),
+            slice("1.2.3", 1, Some(5.), 10),
+            slice("1.2.3", 2, Some(0.), 20),
+        ];
+        let series = group_by_series(objects);
+        assert_eq!(series.len(), 2);
+        let slices = &series["1.2.3"];
+
+        let order = sort_slices(slices).unwrap();
+        assert_eq!(order.method, SortMethod::Position);
+        assert_eq!(order.order, vec![0, 2, 1]);
+        assert!(order.is_complete());
+
+        let volume = assemble_volume(slices).unwrap();
+        assert_eq!(volume.dim(), (3, 2, 2));
+        assert_eq!(volume.slice_spacing(), Some(5.));
+        assert_eq!(volume.spacing(), Some([5., 0.5, 0.5]));
+        assert_eq!(volume.data()[[0, 1, 1]], 30.);
+        assert_eq!(volume.data()[[1, 0, 0]], 20.);
+        assert_eq!(volume.data()[[2, 0, 1]], 10.);
+        assert_eq!(volume.voxel_to_patient().unwrap()[2], [0., 0., 5., -5.]);
+    }
+
+    #[test]
+    fn detects_missing_and_duplicate_slices() {
+        let slices = vec![
+            slice("1", 1, Some(0.), 0),
+            slice("1", 2, Some(2.), 0),
+            slice("1", 3, Some(6.), 0),
+            slice("1", 4, Some(8.), 0),
+            slice("1", 5, Some(8.), 0),
+        ];
+        let order = sort_slices(&slices).unwrap();
+        assert_eq!(order.spacing, Some(2.));
+        assert_eq!(order.duplicates, vec![4]);
+        assert_eq!(order.gaps, vec![(1, 1)]);
+        assert!(assemble_volume(&slices).is_err());
+    }
+
+    #[test]
+    fn falls_back_to_instance_number() {
+        let slices = vec![
+            slice("1", 2, None, 20),
+            slice("1", 1, None, 10),
+            slice("1", 3, Some(0.), 30),
+        ];
+        let volume = assemble_volume(&slices).unwrap();
+        let order = volume.slice_order();
+        assert_eq!(order.method, SortMethod::InstanceNumber);
+        assert_eq!(order.order, vec![1, 0, 2]);
+        assert_eq!(volume.plane(), None);
+        assert_eq!(volume.data()[[0, 0, 0]], 10.);
+        assert_eq!(volume.data()[[2, 0, 0]], 30.);
+
+        let gap = vec![slice("1", 1, None, 0), slice("1", 4, None, 0)];
+        assert_eq!(sort_slices(&gap).unwrap().gaps, vec![(0, 2)]);
+    }
+
+    #[test]
+    fn rejects_empty_series() {
+        let slices: Vec<FileDicomObject<InMemDicomObject>> = Vec::new();
+        assert!(sort_slices(&slices).is_err());
+    }
+}
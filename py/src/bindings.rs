@@ -0,0 +1,405 @@
+//! The `dicom_py` Python module.
+use dicom_core::dictionary::{DataDictionary, DictionaryEntry};
+use dicom_core::header::Header;
+use dicom_core::value::{PrimitiveValue, Value, C};
+use dicom_core::{DataElement, Tag, VR};
+use dicom_dictionary_std::StandardDataDictionary;
+use dicom_object::file::ReadPreamble;
+use dicom_object::mem::{InMemElement, InMemFragment};
+use dicom_object::{DefaultDicomObject, InMemDicomObject, OpenFileOptions};
+use dicom_pixeldata::ndarray::IxDyn;
+use dicom_pixeldata::{ConvertOptions, ModalityLutOption, PixelDecoder, PixelRepresentation};
+use numpy::IntoPyArray;
+use pyo3::create_exception;
+use pyo3::exceptions::{PyAttributeError, PyException, PyKeyError, PyTypeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyList, PyString, PyTuple};
+
+create_exception!(
+    dicom_py,
+    DicomError,
+    PyException,
+    "Error raised when a DICOM object cannot be read, written, or decoded."
+);
+
+fn to_py_err(e: impl std::fmt::Display) -> PyErr {
+    DicomError::new_err(e.to_string())
+}
+
+/// The DICOM object held by a [`Dataset`].
+enum Inner {
+    /// A full DICOM file, with its file meta group.
+    File(DefaultDicomObject),
+    /// A data set without a file meta group,
+    /// such as a sequence item.
+    Item(InMemDicomObject),
+}
+
+/// A DICOM data set,
+/// either read from a DICOM file or taken from a sequence item.
+#[pyclass(module = "dicom_py")]
+pub struct Dataset {
+    inner: Inner,
+}
+
+impl Dataset {
+    fn obj(&self) -> &InMemDicomObject {
+        match &self.inner {
+            Inner::File(obj) => obj,
+            Inner::Item(obj) => obj,
+        }
+    }
+
+    fn obj_mut(&mut self) -> &mut InMemDicomObject {
+        match &mut self.inner {
+            Inner::File(obj) => obj,
+            Inner::Item(obj) => obj,
+        }
+    }
+
+    fn file(&self) -> PyResult<&DefaultDicomObject> {
+        match &self.inner {
+            Inner::File(obj) => Ok(obj),
+            Inner::Item(_) => Err(DicomError::new_err(
+                "data set is not a DICOM file (no file meta group)",
+            )),
+        }
+    }
+
+    fn element(&self, tag: Tag) -> PyResult<&InMemElement> {
+        self.obj()
+            .element_opt(tag)
+            .map_err(to_py_err)?
+            .ok_or_else(|| PyKeyError::new_err(tag_keyword(tag)))
+    }
+}
+
+#[pymethods]
+impl Dataset {
+    /// The transfer syntax UID of the DICOM file,
+    /// or `None` if the data set has no file meta group.
+    #[getter]
+    fn transfer_syntax(&self) -> Option<String> {
+        match &self.inner {
+            Inner::File(obj) => Some(
+                obj.meta()
+                    .transfer_syntax
+                    .trim_end_matches('\0')
+                    .to_string(),
+            ),
+            Inner::Item(_) => None,
+        }
+    }
+
+    /// Obtain the keywords of the attributes in the data set,
+    /// in ascending tag order.
+    /// Attributes not in the standard dictionary
+    /// are identified by their tag in the form `(GGGG,EEEE)`.
+    fn keys(&self) -> Vec<String> {
+        self.obj().tags().map(tag_keyword).collect()
+    }
+
+    /// Obtain the value representation of the given attribute.
+    fn vr(&self, key: &PyAny) -> PyResult<String> {
+        let tag = tag_from_key(key)?;
+        let elem = self.element(tag)?;
+        Ok(elem.vr().to_string().to_owned())
+    }
+
+    /// Decode the pixel data of the DICOM file into a NumPy array.
+    ///
+    /// The array holds the stored sample values without rescaling,
+    /// with a data type matching the bits allocated
+    /// and pixel representation of the object.
+    /// The shape is `(frames, rows, columns, samples)`,
+    /// where the frame axis is left out for single frame images
+    /// and the sample axis is left out for single sample images.
+    fn pixel_array(&self, py: Python) -> PyResult<PyObject> {
+        let pixels = self.file()?.decode_pixel_data().map_err(to_py_err)?;
+        let options = ConvertOptions::new().with_modality_lut(ModalityLutOption::None);
+
+        macro_rules! to_numpy {
+            ($t: ty) => {{
+                let array = pixels
+                    .to_ndarray_with_options::<$t>(&options)
+                    .map_err(to_py_err)?;
+                let shape: Vec<usize> = array
+                    .shape()
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, &len)| !((i == 0 || i == 3) && len == 1))
+                    .map(|(_, &len)| len)
+                    .collect();
+                let array = array.into_shape(IxDyn(&shape)).map_err(to_py_err)?;
+                array.into_pyarray(py).to_object(py)
+            }};
+        }
+
+        let signed = pixels.pixel_representation() == PixelRepresentation::Signed;
+        Ok(match (pixels.bits_allocated(), signed) {
+            (8, false) => to_numpy!(u8),
+            (8, true) => to_numpy!(i8),
+            (16, false) => to_numpy!(u16),
+            (16, true) => to_numpy!(i16),
+            (32, false) => to_numpy!(u32),
+            (32, true) => to_numpy!(i32),
+            (bits, _) => {
+                return Err(DicomError::new_err(format!(
+                    "unsupported bits allocated: {}",
+                    bits
+                )))
+            }
+        })
+    }
+
+    /// Write the DICOM file to the given path.
+    fn write(&self, path: &str) -> PyResult<()> {
+        self.file()?.write_to_file(path).map_err(to_py_err)
+    }
+
+    /// Encode the DICOM file into bytes,
+    /// including the preamble and the file meta group.
+    fn to_bytes(&self, py: Python) -> PyResult<PyObject> {
+        let mut out = Vec::new();
+        self.file()?.write_all(&mut out).map_err(to_py_err)?;
+        Ok(PyBytes::new(py, &out).to_object(py))
+    }
+
+    fn __len__(&self) -> usize {
+        self.obj().tags().count()
+    }
+
+    fn __contains__(&self, key: &PyAny) -> PyResult<bool> {
+        let tag = tag_from_key(key)?;
+        Ok(self.obj().element_opt(tag).ok().flatten().is_some())
+    }
+
+    fn __getitem__(&self, py: Python, key: &PyAny) -> PyResult<PyObject> {
+        let tag = tag_from_key(key)?;
+        value_to_py(py, self.element(tag)?.value())
+    }
+
+    fn __setitem__(&mut self, key: &PyAny, value: &PyAny) -> PyResult<()> {
+        let tag = tag_from_key(key)?;
+        let vr = match self.obj().element_opt(tag).ok().flatten() {
+            Some(elem) => elem.vr(),
+            None => StandardDataDictionary
+                .by_tag(tag)
+                .map(|entry| entry.vr())
+                .ok_or_else(|| {
+                    PyKeyError::new_err(format!("cannot infer the VR of unknown attribute {}", tag))
+                })?,
+        };
+        let value = primitive_from_py(value, vr)?;
+        self.obj_mut().put(DataElement::new(tag, vr, value));
+        Ok(())
+    }
+
+    fn __delitem__(&mut self, key: &PyAny) -> PyResult<()> {
+        let tag = tag_from_key(key)?;
+        if self.obj_mut().remove_element(tag) {
+            Ok(())
+        } else {
+            Err(PyKeyError::new_err(tag.to_string()))
+        }
+    }
+
+    fn __getattr__(&self, py: Python, name: &str) -> PyResult<PyObject> {
+        let tag = StandardDataDictionary
+            .by_name(name)
+            .map(|entry| entry.tag())
+            .ok_or_else(|| PyAttributeError::new_err(name.to_string()))?;
+        match self.obj().element_opt(tag).ok().flatten() {
+            Some(elem) => value_to_py(py, elem.value()),
+            None => Err(PyAttributeError::new_err(name.to_string())),
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("<Dataset with {} elements>", self.__len__())
+    }
+}
+
+/// Open the DICOM file at the given path.
+#[pyfunction]
+fn open_file(path: &str) -> PyResult<Dataset> {
+    let obj = dicom_object::open_file(path).map_err(to_py_err)?;
+    Ok(Dataset {
+        inner: Inner::File(obj),
+    })
+}
+
+/// Read a DICOM file from the given bytes.
+///
+/// The 128-byte preamble is skipped if present.
+#[pyfunction]
+fn read_bytes(data: &[u8]) -> PyResult<Dataset> {
+    let read_preamble = if data.get(128..132) == Some(&b"DICM"[..]) {
+        ReadPreamble::Always
+    } else {
+        ReadPreamble::Never
+    };
+    let obj = OpenFileOptions::new()
+        .read_preamble(read_preamble)
+        .from_reader(data)
+        .map_err(to_py_err)?;
+    Ok(Dataset {
+        inner: Inner::File(obj),
+    })
+}
+
+/// Resolve a key into an attribute tag.
+///
+/// The key can be an attribute keyword or tag expression,
+/// a `(group, element)` tuple,
+/// or a 32-bit integer with the group in the upper 16 bits.
+fn tag_from_key(key: &PyAny) -> PyResult<Tag> {
+    if let Ok(expr) = key.extract::<&str>() {
+        return StandardDataDictionary
+            .parse_tag(expr)
+            .ok_or_else(|| PyKeyError::new_err(expr.to_string()));
+    }
+    if let Ok((group, element)) = key.extract::<(u16, u16)>() {
+        return Ok(Tag(group, element));
+    }
+    if let Ok(tag) = key.extract::<u32>() {
+        return Ok(Tag((tag >> 16) as u16, tag as u16));
+    }
+    Err(PyTypeError::new_err(
+        "expected a keyword, a (group, element) tuple, or an integer tag",
+    ))
+}
+
+/// Obtain the keyword of the attribute with the given tag,
+/// or the tag in the form `(GGGG,EEEE)` if it is not in the dictionary.
+fn tag_keyword(tag: Tag) -> String {
+    StandardDataDictionary
+        .by_tag(tag)
+        .map(|entry| entry.alias().to_string())
+        .unwrap_or_else(|| tag.to_string())
+}
+
+fn value_to_py(py: Python, value: &Value<InMemDicomObject, InMemFragment>) -> PyResult<PyObject> {
+    match value {
+        Value::Primitive(value) => Ok(primitive_to_py(py, value)),
+        Value::Sequence { items, .. } => {
+            let items = items
+                .iter()
+                .map(|item| {
+                    Py::new(
+                        py,
+                        Dataset {
+                            inner: Inner::Item(item.clone()),
+                        },
+                    )
+                })
+                .collect::<PyResult<Vec<_>>>()?;
+            Ok(PyList::new(py, items).to_object(py))
+        }
+        Value::PixelSequence { fragments, .. } => Ok(PyList::new(
+            py,
+            fragments.iter().map(|fragment| PyBytes::new(py, fragment)),
+        )
+        .to_object(py)),
+    }
+}
+
+fn primitive_to_py(py: Python, value: &PrimitiveValue) -> PyObject {
+    /// Convert single values to a Python scalar
+    /// and multiple values to a list.
+    fn multi<T: ToPyObject>(py: Python, values: &[T]) -> PyObject {
+        match values {
+            [value] => value.to_object(py),
+            _ => values.to_object(py),
+        }
+    }
+
+    match value {
+        PrimitiveValue::Empty => py.None(),
+        PrimitiveValue::Str(value) => value.to_object(py),
+        PrimitiveValue::Strs(values) => multi(py, &values[..]),
+        PrimitiveValue::SharedStrs(values) => multi(py, &values[..]),
+        PrimitiveValue::Tags(tags) => {
+            let tags: Vec<_> = tags.iter().map(|tag| (tag.0, tag.1)).collect();
+            multi(py, &tags[..])
+        }
+        PrimitiveValue::U8(bytes) => PyBytes::new(py, bytes).to_object(py),
+        PrimitiveValue::SharedBytes(bytes) => PyBytes::new(py, bytes).to_object(py),
+        PrimitiveValue::I16(values) => multi(py, &values[..]),
+        PrimitiveValue::U16(values) => multi(py, &values[..]),
+        PrimitiveValue::I32(values) => multi(py, &values[..]),
+        PrimitiveValue::U32(values) => multi(py, &values[..]),
+        PrimitiveValue::I64(values) => multi(py, &values[..]),
+        PrimitiveValue::U64(values) => multi(py, &values[..]),
+        PrimitiveValue::F32(values) => multi(py, &values[..]),
+        PrimitiveValue::F64(values) => multi(py, &values[..]),
+        PrimitiveValue::Date(_) | PrimitiveValue::DateTime(_) | PrimitiveValue::Time(_) => {
+            multi(py, &value.to_multi_str()[..])
+        }
+    }
+}
+
+/// Convert a Python value into a DICOM value
+/// of the given value representation.
+fn primitive_from_py(value: &PyAny, vr: VR) -> PyResult<PrimitiveValue> {
+    /// Extract a single value or a list of values.
+    fn multi<'a, T: FromPyObject<'a>>(value: &'a PyAny) -> PyResult<C<T>> {
+        match value.extract::<T>() {
+            Ok(value) => Ok(std::iter::once(value).collect()),
+            Err(_) => value.extract::<Vec<T>>().map(C::from_vec),
+        }
+    }
+
+    /// Extract the textual representation
+    /// of a single value or a list of values.
+    fn strings(value: &PyAny) -> PyResult<C<String>> {
+        if value.is_instance_of::<PyList>() || value.is_instance_of::<PyTuple>() {
+            value
+                .iter()?
+                .map(|item| Ok(item?.str()?.to_string()))
+                .collect()
+        } else {
+            Ok(std::iter::once(value.str()?.to_string()).collect())
+        }
+    }
+
+    if value.is_none() {
+        return Ok(PrimitiveValue::Empty);
+    }
+    if let Ok(bytes) = value.downcast::<PyBytes>() {
+        return Ok(PrimitiveValue::U8(C::from_slice(bytes.as_bytes())));
+    }
+
+    Ok(match vr {
+        VR::SS => PrimitiveValue::I16(multi(value)?),
+        VR::US | VR::OW => PrimitiveValue::U16(multi(value)?),
+        VR::SL => PrimitiveValue::I32(multi(value)?),
+        VR::UL | VR::OL => PrimitiveValue::U32(multi(value)?),
+        VR::SV => PrimitiveValue::I64(multi(value)?),
+        VR::UV | VR::OV => PrimitiveValue::U64(multi(value)?),
+        VR::FL | VR::OF => PrimitiveValue::F32(multi(value)?),
+        VR::FD | VR::OD => PrimitiveValue::F64(multi(value)?),
+        VR::OB | VR::UN => PrimitiveValue::U8(multi(value)?),
+        VR::AT => PrimitiveValue::Tags(
+            multi::<(u16, u16)>(value)?
+                .into_iter()
+                .map(|(group, element)| Tag(group, element))
+                .collect(),
+        ),
+        VR::ST | VR::LT | VR::UT | VR::UR => {
+            PrimitiveValue::Str(value.downcast::<PyString>()?.to_str()?.to_string())
+        }
+        VR::SQ => return Err(PyTypeError::new_err("sequence values cannot be assigned")),
+        _ => PrimitiveValue::Strs(strings(value)?),
+    })
+}
+
+/// Python bindings for DICOM-rs.
+#[pymodule]
+fn dicom_py(py: Python, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Dataset>()?;
+    m.add_function(wrap_pyfunction!(open_file, m)?)?;
+    m.add_function(wrap_pyfunction!(read_bytes, m)?)?;
+    m.add("DicomError", py.get_type::<DicomError>())?;
+    Ok(())
+}
@@ -0,0 +1,22 @@
+//! Python bindings for DICOM-rs.
+//!
+//! This crate builds the `dicom_py` Python extension module,
+//! exposing DICOM objects to Python through [PyO3](https://pyo3.rs).
+//! The bindings are only compiled with the `python` feature,
+//! and the module is meant to be built with `maturin`
+//! using the `extension-module` feature.
+//!
+//! The module provides the following:
+//!
+//! - `open_file(path)` and `read_bytes(data)`,
+//!   which read a DICOM file into a `Dataset`;
+//! - the `Dataset` class,
+//!   with element access by keyword or tag through indexing
+//!   (`obj["PatientName"]`, `obj[0x0010, 0x0010]`)
+//!   and attribute lookup (`obj.PatientName`),
+//!   writing to a file or to bytes,
+//!   and `pixel_array()` for decoding the pixel data into a NumPy array;
+//! - the `DicomError` exception type,
+//!   raised when a DICOM object cannot be read, written, or decoded.
+#[cfg(feature = "python")]
+mod bindings;
@@ -25,7 +25,8 @@ use walkdir::WalkDir;
 /// DICOM C-STORE SCU
 #[derive(Debug, StructOpt)]
 struct App {
-    /// socket address to STORE SCP (example: "127.0.0.1:104")
+    /// socket address to STORE SCP,
+    /// optionally with the called AE title (example: "STORE-SCP@127.0.0.1:104")
     addr: String,
     /// the DICOM file(s) to store
     files: Vec<PathBuf>,
@@ -38,7 +39,7 @@ struct App {
     /// the calling AE title
     #[structopt(long = "calling-ae-title", default_value = "STORE-SCU")]
     calling_ae_title: String,
-    /// the called AE title
+    /// the called AE title, if not specified in the address
     #[structopt(long = "called-ae-title", default_value = "ANY-SCP")]
     called_ae_title: String,
     /// the maximum PDU length accepted by the SCU
@@ -202,7 +203,7 @@ fn run() -> Result<(), Error> {
         .calling_ae_title(calling_ae_title)
         .called_ae_title(called_ae_title)
         .max_pdu_length(max_pdu_length)
-        .establish(addr)
+        .establish_with_str(&addr)
         .context(InitScuSnafu)?;
 
     if verbose {
@@ -0,0 +1,203 @@
+//! Conversion of DICOM image files into general purpose image files.
+//!
+//! This is the library counterpart of the `dicom-toimage` tool.
+//! It decodes the pixel data of a single frame,
+//! applies the modality and VOI LUT pipeline of [`dicom_pixeldata`],
+//! and writes the outcome in the image format
+//! implied by the output file's extension
+//! (e.g. PNG, JPEG, or TIFF).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use dicom_toimage::{convert_file, ToImageOptions};
+//! # fn run() -> Result<(), dicom_toimage::Error> {
+//! // convert the third frame, using a custom window
+//! let options = ToImageOptions::new().frame(2).window(40., 400.);
+//! convert_file("ct.dcm", "ct.png", &options)?;
+//! # Ok(())
+//! # }
+//! ```
+use std::path::Path;
+
+use dicom_object::{open_file, DefaultDicomObject};
+use dicom_pixeldata::image::{DynamicImage, ImageFormat};
+use dicom_pixeldata::{BitDepthOption, ConvertOptions, PixelDecoder, VoiLutOption, WindowLevel};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+/// An error which may occur when converting a DICOM file into an image.
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// Could not open the DICOM file
+    OpenFile {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+    /// Could not decode the pixel data
+    DecodePixelData {
+        #[snafu(backtrace)]
+        source: dicom_pixeldata::Error,
+    },
+    /// Frame number is out of bounds
+    #[snafu(display("Frame #{} is out of bounds ({} frames)", frame, number_of_frames))]
+    FrameOutOfBounds { frame: u32, number_of_frames: u32 },
+    /// Could not convert the frame into an image
+    ConvertImage {
+        #[snafu(backtrace)]
+        source: dicom_pixeldata::Error,
+    },
+    /// Could not infer the image format from the output path
+    #[snafu(display("Unrecognized image format for output file `{}`", path))]
+    UnknownImageFormat { path: String },
+    /// Could not save the image file
+    SaveImage {
+        source: dicom_pixeldata::image::ImageError,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Options for converting a DICOM image into a general purpose image.
+///
+/// By default, the first frame is converted
+/// using the first VOI LUT function described in the object,
+/// at a bit depth inferred from the pixel data.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct ToImageOptions {
+    /// The frame number (0-indexed)
+    pub frame: u32,
+    /// The VOI LUT transformation to apply
+    pub voi_lut: VoiLutOption,
+    /// The bit depth of the output image
+    pub bit_depth: BitDepthOption,
+}
+
+impl ToImageOptions {
+    /// Create a new set of options with the default behavior.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the frame number to convert (0-indexed).
+    pub fn frame(mut self, frame: u32) -> Self {
+        self.frame = frame;
+        self
+    }
+
+    /// Apply the given window center and width
+    /// instead of the ones described in the object.
+    pub fn window(mut self, center: f64, width: f64) -> Self {
+        self.voi_lut = VoiLutOption::Custom(WindowLevel { width, center });
+        self
+    }
+
+    /// Set the VOI LUT transformation to apply.
+    pub fn voi_lut(mut self, voi_lut: VoiLutOption) -> Self {
+        self.voi_lut = voi_lut;
+        self
+    }
+
+    /// Set the bit depth of the output image.
+    pub fn bit_depth(mut self, bit_depth: BitDepthOption) -> Self {
+        self.bit_depth = bit_depth;
+        self
+    }
+}
+
+/// Convert a frame of the given DICOM object into a dynamic image.
+pub fn to_image(obj: &DefaultDicomObject, options: &ToImageOptions) -> Result<DynamicImage> {
+    let pixel = obj.decode_pixel_data().context(DecodePixelDataSnafu)?;
+    let number_of_frames = pixel.number_of_frames();
+    ensure!(
+        options.frame < number_of_frames,
+        FrameOutOfBoundsSnafu {
+            frame: options.frame,
+            number_of_frames,
+        }
+    );
+
+    let convert_options = ConvertOptions::new()
+        .with_voi_lut(options.voi_lut.clone())
+        .with_bit_depth(options.bit_depth);
+    pixel
+        .to_dynamic_image_with_options(options.frame, &convert_options)
+        .context(ConvertImageSnafu)
+}
+
+/// Convert a frame of the given DICOM object
+/// and save it to an image file.
+///
+/// The image format is inferred from the extension of `output`.
+/// Formats which cannot hold more than 8 bits per sample, such as JPEG,
+/// are written at 8 bits per sample
+/// unless a bit depth is explicitly requested.
+pub fn save_image<P>(obj: &DefaultDicomObject, output: P, options: &ToImageOptions) -> Result<()>
+where
+    P: AsRef<Path>,
+{
+    let output = output.as_ref();
+    let format = ImageFormat::from_path(output)
+        .ok()
+        .context(UnknownImageFormatSnafu {
+            path: output.display().to_string(),
+        })?;
+
+    let mut options = options.clone();
+    if options.bit_depth == BitDepthOption::Auto && !supports_16bit(format) {
+        options.bit_depth = BitDepthOption::Force8Bit;
+    }
+
+    let image = to_image(obj, &options)?;
+    image
+        .save_with_format(output, format)
+        .context(SaveImageSnafu)
+}
+
+/// Open a DICOM file, convert a frame of it,
+/// and save it to an image file.
+///
+/// See [`save_image`] for how the image format is chosen.
+pub fn convert_file<P, Q>(input: P, output: Q, options: &ToImageOptions) -> Result<()>
+where
+    P: AsRef<Path>,
+    Q: AsRef<Path>,
+{
+    let obj = open_file(input).context(OpenFileSnafu)?;
+    save_image(&obj, output, options)
+}
+
+/// Whether the image format can hold 16 bits per sample.
+fn supports_16bit(format: ImageFormat) -> bool {
+    matches!(
+        format,
+        ImageFormat::Png | ImageFormat::Tiff | ImageFormat::Pnm
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn options_window() {
+        let options = ToImageOptions::new().frame(1).window(40., 400.);
+        assert_eq!(options.frame, 1);
+        assert_eq!(
+            options.voi_lut,
+            VoiLutOption::Custom(WindowLevel {
+                width: 400.,
+                center: 40.,
+            })
+        );
+        assert_eq!(options.bit_depth, BitDepthOption::Auto);
+    }
+
+    #[test]
+    fn bit_depth_by_format() {
+        assert!(supports_16bit(ImageFormat::Png));
+        assert!(supports_16bit(ImageFormat::Tiff));
+        assert!(!supports_16bit(ImageFormat::Jpeg));
+    }
+}
@@ -2,8 +2,10 @@
 //! into a general purpose image file (e.g. PNG).
 use std::path::PathBuf;
 
-use dicom_object::open_file;
-use dicom_pixeldata::{ConvertOptions, PixelDecoder};
+use dicom_dictionary_std::tags;
+use dicom_object::{open_file, DefaultDicomObject, Tag};
+use dicom_pixeldata::{BitDepthOption, VoiLutOption};
+use dicom_toimage::{save_image, ToImageOptions};
 use snafu::ErrorCompat;
 use structopt::StructOpt;
 
@@ -22,6 +24,25 @@ struct App {
     #[structopt(short = "F", long = "frame", default_value = "0")]
     frame_number: u32,
 
+    /// Window center for the VOI LUT transformation
+    /// (replaces the window described in the file)
+    #[structopt(
+        long = "window-center",
+        requires = "window-width",
+        allow_hyphen_values = true
+    )]
+    window_center: Option<f64>,
+
+    /// Window width for the VOI LUT transformation
+    /// (replaces the window described in the file)
+    #[structopt(long = "window-width", requires = "window-center")]
+    window_width: Option<f64>,
+
+    /// Normalize the sample values to the full output range
+    /// instead of applying a window
+    #[structopt(long = "normalize", conflicts_with = "window-center")]
+    normalize: bool,
+
     /// Force output bit depth to 8 bits per sample
     #[structopt(long = "8bit", conflicts_with = "force_16bit")]
     force_8bit: bool,
@@ -84,6 +105,9 @@ fn main() {
         file,
         output,
         frame_number,
+        window_center,
+        window_width,
+        normalize,
         verbose,
         force_8bit,
         force_16bit,
@@ -100,42 +124,45 @@ fn main() {
         std::process::exit(-1);
     });
 
-    let pixel = obj.decode_pixel_data().unwrap_or_else(|e| {
-        report_with_backtrace(e);
-        std::process::exit(-2);
-    });
-
     if verbose {
-        println!(
-            "{}x{}x{} image, {}-bit",
-            pixel.columns(),
-            pixel.rows(),
-            pixel.samples_per_pixel(),
-            pixel.bits_stored()
-        );
+        print_image_info(&obj);
     }
 
-    let mut options = ConvertOptions::new();
+    let mut options = ToImageOptions::new().frame(frame_number);
+
+    if let (Some(center), Some(width)) = (window_center, window_width) {
+        options = options.window(center, width);
+    } else if normalize {
+        options = options.voi_lut(VoiLutOption::Normalize);
+    }
 
     if force_16bit {
-        options = options.force_16bit();
+        options = options.bit_depth(BitDepthOption::Force16Bit);
     } else if force_8bit {
-        options = options.force_8bit();
+        options = options.bit_depth(BitDepthOption::Force8Bit);
     }
 
-    let image = pixel
-        .to_dynamic_image_with_options(frame_number, &options)
-        .unwrap_or_else(|e| {
-            report_with_backtrace(e);
-            std::process::exit(-3);
-        });
-
-    image.save(&output).unwrap_or_else(|e| {
-        report(&e);
-        std::process::exit(-4);
+    save_image(&obj, &output, &options).unwrap_or_else(|e| {
+        report_with_backtrace(e);
+        std::process::exit(-2);
     });
 
     if verbose {
         println!("Image saved to {}", output.display());
     }
 }
+
+fn print_image_info(obj: &DefaultDicomObject) {
+    let attr = |tag: Tag| -> Option<u32> { obj.element(tag).ok()?.to_int().ok() };
+    if let (Some(columns), Some(rows), Some(samples_per_pixel), Some(bits_stored)) = (
+        attr(tags::COLUMNS),
+        attr(tags::ROWS),
+        attr(tags::SAMPLES_PER_PIXEL),
+        attr(tags::BITS_STORED),
+    ) {
+        println!(
+            "{}x{}x{} image, {}-bit",
+            columns, rows, samples_per_pixel, bits_stored
+        );
+    }
+}
@@ -0,0 +1,259 @@
+//! Application entity address module
+//!
+//! This module provides [`AeAddr`],
+//! a full address to a DICOM node
+//! comprising an optional application entity title
+//! and a network socket address,
+//! as well as the validation rules for AE titles.
+//!
+//! An AE address is written in the form `AETITLE@host:port`,
+//! where the AE title part and the `@` separator may be omitted.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_ul::address::AeAddr;
+//! let addr: AeAddr = "PACS@10.0.0.5:104".parse()?;
+//! assert_eq!(addr.ae_title(), Some("PACS"));
+//! assert_eq!(addr.socket_addr(), "10.0.0.5:104");
+//!
+//! let addr: AeAddr = "localhost:11112".parse()?;
+//! assert_eq!(addr.ae_title(), None);
+//! # Ok::<(), dicom_ul::address::ParseAeAddrError>(())
+//! ```
+use std::{
+    fmt,
+    net::{SocketAddr, ToSocketAddrs},
+    str::FromStr,
+};
+
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+
+/// The maximum number of characters in an application entity title.
+pub const MAX_AE_TITLE_LENGTH: usize = 16;
+
+/// An error describing why a string is not a valid AE title.
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
+pub enum AeTitleError {
+    /// AE title is empty or only contains spaces
+    Empty,
+
+    #[snafu(display(
+        "AE title is too long ({} characters, maximum is {})",
+        length,
+        MAX_AE_TITLE_LENGTH
+    ))]
+    TooLong { length: usize },
+
+    #[snafu(display("AE title contains invalid character {:?}", character))]
+    InvalidCharacter { character: char },
+}
+
+/// An error which may occur when parsing an [`AeAddr`].
+#[derive(Debug, Clone, PartialEq, Eq, Snafu)]
+#[non_exhaustive]
+pub enum ParseAeAddrError {
+    /// invalid AE title in address
+    InvalidAeTitle { source: AeTitleError },
+
+    /// missing host in address
+    MissingHost,
+
+    /// missing port in address
+    MissingPort,
+
+    #[snafu(display("invalid port `{}` in address", port))]
+    InvalidPort { port: String },
+}
+
+/// Check whether the given string is a valid application entity title.
+///
+/// As per the standard, an AE title has at most 16 characters
+/// of the default character repertoire,
+/// excluding backslash (`\`) and control characters.
+/// Leading and trailing spaces are not significant,
+/// but a title consisting only of spaces is not valid.
+pub fn check_ae_title(ae_title: &str) -> Result<(), AeTitleError> {
+    let length = ae_title.chars().count();
+    ensure!(length <= MAX_AE_TITLE_LENGTH, TooLongSnafu { length });
+    if let Some(character) = ae_title
+        .chars()
+        .find(|c| !c.is_ascii() || c.is_ascii_control() || *c == '\\')
+    {
+        return InvalidCharacterSnafu { character }.fail();
+    }
+    ensure!(!ae_title.trim().is_empty(), EmptySnafu);
+    Ok(())
+}
+
+/// A full address to a DICOM application entity:
+/// an optional AE title and a socket address in the form `host:port`.
+///
+/// This type can be parsed from and formatted to
+/// the textual form `AETITLE@host:port`.
+/// It also implements [`ToSocketAddrs`],
+/// so that it can be passed directly
+/// to functions establishing a TCP connection,
+/// in which case the AE title is ignored.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct AeAddr {
+    ae_title: Option<String>,
+    socket_addr: String,
+}
+
+impl AeAddr {
+    /// Create a new AE address from its parts,
+    /// validating both the AE title and the socket address.
+    pub fn new<T>(ae_title: Option<T>, socket_addr: &str) -> Result<Self, ParseAeAddrError>
+    where
+        T: Into<String>,
+    {
+        let ae_title = ae_title.map(Into::into);
+        if let Some(ae_title) = &ae_title {
+            check_ae_title(ae_title).context(InvalidAeTitleSnafu)?;
+        }
+        check_socket_addr(socket_addr)?;
+        Ok(AeAddr {
+            ae_title,
+            socket_addr: socket_addr.to_string(),
+        })
+    }
+
+    /// Retrieve the application entity title, if specified.
+    pub fn ae_title(&self) -> Option<&str> {
+        self.ae_title.as_deref()
+    }
+
+    /// Retrieve the socket address part, in the form `host:port`.
+    pub fn socket_addr(&self) -> &str {
+        &self.socket_addr
+    }
+
+    /// Replace the application entity title of this address.
+    pub fn with_ae_title<T>(self, ae_title: T) -> Result<Self, AeTitleError>
+    where
+        T: Into<String>,
+    {
+        let ae_title = ae_title.into();
+        check_ae_title(&ae_title)?;
+        Ok(AeAddr {
+            ae_title: Some(ae_title),
+            ..self
+        })
+    }
+
+    /// Decompose the address into its AE title and socket address parts.
+    pub fn into_parts(self) -> (Option<String>, String) {
+        (self.ae_title, self.socket_addr)
+    }
+}
+
+/// Check that the socket address is in the form `host:port`.
+fn check_socket_addr(socket_addr: &str) -> Result<(), ParseAeAddrError> {
+    let (host, port) = socket_addr.rsplit_once(':').context(MissingPortSnafu)?;
+    ensure!(!host.is_empty(), MissingHostSnafu);
+    ensure!(!port.is_empty(), MissingPortSnafu);
+    port.parse::<u16>()
+        .ok()
+        .context(InvalidPortSnafu { port })?;
+    Ok(())
+}
+
+impl FromStr for AeAddr {
+    type Err = ParseAeAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // hosts never contain `@`, so split on the last one
+        match s.rsplit_once('@') {
+            Some((ae_title, socket_addr)) => AeAddr::new(Some(ae_title), socket_addr),
+            None => AeAddr::new(None::<String>, s),
+        }
+    }
+}
+
+impl fmt::Display for AeAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(ae_title) = &self.ae_title {
+            write!(f, "{}@", ae_title)?;
+        }
+        f.write_str(&self.socket_addr)
+    }
+}
+
+impl ToSocketAddrs for AeAddr {
+    type Iter = std::vec::IntoIter<SocketAddr>;
+
+    fn to_socket_addrs(&self) -> std::io::Result<Self::Iter> {
+        self.socket_addr.to_socket_addrs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_full_address() {
+        let addr: AeAddr = "STORE-SCP@127.0.0.1:11112".parse().unwrap();
+        assert_eq!(addr.ae_title(), Some("STORE-SCP"));
+        assert_eq!(addr.socket_addr(), "127.0.0.1:11112");
+        assert_eq!(addr.to_string(), "STORE-SCP@127.0.0.1:11112");
+
+        let addr: AeAddr = "PACS@[::1]:104".parse().unwrap();
+        assert_eq!(addr.ae_title(), Some("PACS"));
+        assert_eq!(addr.socket_addr(), "[::1]:104");
+    }
+
+    #[test]
+    fn parse_address_without_ae_title() {
+        let addr: AeAddr = "pacs.example.com:104".parse().unwrap();
+        assert_eq!(addr.ae_title(), None);
+        assert_eq!(addr.socket_addr(), "pacs.example.com:104");
+        assert_eq!(addr.to_string(), "pacs.example.com:104");
+    }
+
+    #[test]
+    fn reject_bad_addresses() {
+        assert_eq!(
+            "PACS@localhost".parse::<AeAddr>(),
+            Err(ParseAeAddrError::MissingPort)
+        );
+        assert_eq!(
+            "PACS@:104".parse::<AeAddr>(),
+            Err(ParseAeAddrError::MissingHost)
+        );
+        assert_eq!(
+            "localhost:99999".parse::<AeAddr>(),
+            Err(ParseAeAddrError::InvalidPort {
+                port: "99999".to_string()
+            })
+        );
+        assert_eq!(
+            "@localhost:104".parse::<AeAddr>(),
+            Err(ParseAeAddrError::InvalidAeTitle {
+                source: AeTitleError::Empty
+            })
+        );
+    }
+
+    #[test]
+    fn ae_title_validation() {
+        assert_eq!(check_ae_title("ANY-SCP"), Ok(()));
+        assert_eq!(check_ae_title(" MY SCU "), Ok(()));
+        assert_eq!(check_ae_title("SIXTEEN-CHARS-AE"), Ok(()));
+        assert_eq!(
+            check_ae_title("SEVENTEEN-CHARS-A"),
+            Err(AeTitleError::TooLong { length: 17 })
+        );
+        assert_eq!(check_ae_title("    "), Err(AeTitleError::Empty));
+        assert_eq!(
+            check_ae_title("BACK\\SLASH"),
+            Err(AeTitleError::InvalidCharacter { character: '\\' })
+        );
+        assert_eq!(
+            check_ae_title("TAB\tAE"),
+            Err(AeTitleError::InvalidCharacter { character: '\t' })
+        );
+    }
+}
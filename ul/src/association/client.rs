@@ -8,9 +8,11 @@ use std::{
     borrow::Cow,
     io::Write,
     net::{TcpStream, ToSocketAddrs},
+    time::Duration,
 };
 
 use crate::{
+    address::{check_ae_title, AeAddr, AeTitleError, ParseAeAddrError},
     pdu::{
         reader::{read_pdu, DEFAULT_MAX_PDU, MAXIMUM_PDU_SIZE},
         writer::write_pdu,
@@ -21,7 +23,10 @@ use crate::{
 };
 use snafu::{ensure, ResultExt, Snafu};
 
+use super::negotiation::NegotiatedOptions;
 use super::pdata::PDataWriter;
+#[cfg(feature = "async")]
+use crate::pdu::reader::read_pdu_async;
 
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -29,9 +34,21 @@ pub enum Error {
     /// missing abstract syntax to begin negotiation
     MissingAbstractSyntax,
 
+    #[snafu(display("invalid AE title `{}`", ae_title))]
+    InvalidAeTitle {
+        ae_title: String,
+        source: AeTitleError,
+    },
+
+    /// invalid application entity address
+    InvalidAddress { source: ParseAeAddrError },
+
     /// could not connect to server
     Connect { source: std::io::Error },
 
+    /// could not set the timeouts of the TCP stream
+    SetTimeout { source: std::io::Error },
+
     /// failed to send association request
     SendRequest { source: crate::pdu::writer::Error },
 
@@ -138,6 +155,18 @@ pub struct ClientAssociationOptions<'a> {
     protocol_version: u16,
     /// the maximum PDU length requested for receiving PDUs
     max_pdu_length: u32,
+    /// the SCP/SCU role selections to propose, per SOP class
+    role_selections: Vec<(Cow<'a, str>, bool, bool)>,
+    /// the asynchronous operations window to propose, if any
+    async_operations_window: Option<(u16, u16)>,
+    /// the SOP class extended negotiation sub-items to propose
+    extended_negotiation: Vec<(Cow<'a, str>, Vec<u8>)>,
+    /// the maximum time to wait for the TCP connection to be established
+    connection_timeout: Option<Duration>,
+    /// the maximum time to wait when reading from the TCP stream
+    read_timeout: Option<Duration>,
+    /// the maximum time to wait when writing to the TCP stream
+    write_timeout: Option<Duration>,
 }
 
 impl<'a> Default for ClientAssociationOptions<'a> {
@@ -153,6 +182,12 @@ impl<'a> Default for ClientAssociationOptions<'a> {
             presentation_contexts: Vec::new(),
             protocol_version: 1,
             max_pdu_length: crate::pdu::reader::DEFAULT_MAX_PDU,
+            role_selections: Vec::new(),
+            async_operations_window: None,
+            extended_negotiation: Vec::new(),
+            connection_timeout: None,
+            read_timeout: None,
+            write_timeout: None,
         }
     }
 }
@@ -223,28 +258,281 @@ impl<'a> ClientAssociationOptions<'a> {
         self
     }
 
+    /// Propose the roles that this application entity may take
+    /// for the given SOP class.
+    ///
+    /// By default, the requester is the service class user (SCU)
+    /// of all SOP classes.
+    /// Some services, such as C-GET,
+    /// require the requester to also act as a service class provider (SCP)
+    /// of the storage SOP classes,
+    /// in which case `scp_role` should be `true`.
+    pub fn with_role_selection<T>(
+        mut self,
+        sop_class_uid: T,
+        scu_role: bool,
+        scp_role: bool,
+    ) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.role_selections
+            .push((sop_class_uid.into(), scu_role, scp_role));
+        self
+    }
+
+    /// Propose an asynchronous operations window:
+    /// the maximum number of outstanding operations
+    /// that this application entity may invoke and perform,
+    /// where 0 means unlimited.
+    ///
+    /// By default, no window is proposed,
+    /// so that operations are performed synchronously.
+    pub fn async_operations_window(mut self, invoked: u16, performed: u16) -> Self {
+        self.async_operations_window = Some((invoked, performed));
+        self
+    }
+
+    /// Propose SOP class extended negotiation for the given SOP class,
+    /// with the service class application information
+    /// as specified by the respective service class,
+    /// such as the relational-retrieval and date-time matching flags
+    /// of the query/retrieve service.
+    pub fn with_extended_negotiation<T>(mut self, sop_class_uid: T, info: Vec<u8>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.extended_negotiation.push((sop_class_uid.into(), info));
+        self
+    }
+
+    /// Set the maximum time to wait for the TCP connection
+    /// to the other node to be established.
+    ///
+    /// The default is to wait for as long as the operating system allows.
+    pub fn connection_timeout(mut self, timeout: Duration) -> Self {
+        self.connection_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for incoming data
+    /// during the lifetime of the association,
+    /// including the association negotiation.
+    ///
+    /// The default is to wait indefinitely.
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the maximum time to wait for outgoing data to be sent
+    /// during the lifetime of the association,
+    /// including the association negotiation.
+    ///
+    /// The default is to wait indefinitely.
+    pub fn write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+        self
+    }
+
+    /// Initiate the TCP connection to the given application entity address
+    /// and request a new DICOM association,
+    /// negotiating the presentation contexts in the process.
+    ///
+    /// If the address contains an AE title,
+    /// it is used as the called AE title,
+    /// overriding the one previously defined.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use dicom_ul::association::client::ClientAssociationOptions;
+    /// # use dicom_ul::AeAddr;
+    /// # fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let address: AeAddr = "MY-PACS@129.168.0.5:104".parse()?;
+    /// let association = ClientAssociationOptions::new()
+    ///    .with_abstract_syntax("1.2.840.10008.1.1")
+    ///    .establish_with(address)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn establish_with(self, ae_address: AeAddr) -> Result<ClientAssociation> {
+        let (options, address) = self.with_called_ae_address(ae_address);
+        options.establish(address)
+    }
+
+    /// Initiate the TCP connection to the given
+    /// application entity address in text form (`AETITLE@host:port`)
+    /// and request a new DICOM association,
+    /// negotiating the presentation contexts in the process.
+    ///
+    /// This is equivalent to parsing the address into an [`AeAddr`]
+    /// and calling [`establish_with`](Self::establish_with).
+    pub fn establish_with_str(self, ae_address: &str) -> Result<ClientAssociation> {
+        let ae_address: AeAddr = ae_address.parse().context(InvalidAddressSnafu)?;
+        self.establish_with(ae_address)
+    }
+
+    /// Initiate the TCP connection to the given application entity address
+    /// and request a new DICOM association asynchronously,
+    /// negotiating the presentation contexts in the process.
+    ///
+    /// If the address contains an AE title,
+    /// it is used as the called AE title,
+    /// overriding the one previously defined.
+    #[cfg(feature = "async")]
+    pub async fn establish_with_async(self, ae_address: AeAddr) -> Result<AsyncClientAssociation> {
+        let (options, address) = self.with_called_ae_address(ae_address);
+        options.establish_async(address).await
+    }
+
+    /// Take the called AE title from the given address, if present,
+    /// returning the socket address part.
+    fn with_called_ae_address(self, ae_address: AeAddr) -> (Self, String) {
+        match ae_address.into_parts() {
+            (Some(ae_title), address) => (self.called_ae_title(ae_title), address),
+            (None, address) => (self, address),
+        }
+    }
+
     /// Initiate the TCP connection to the given address
     /// and request a new DICOM association,
     /// negotiating the presentation contexts in the process.
     pub fn establish<A: ToSocketAddrs>(self, address: A) -> Result<ClientAssociation> {
-        let ClientAssociationOptions {
-            calling_ae_title,
-            called_ae_title,
-            application_context_name,
+        let (request, abstract_syntaxes) = self.association_request()?;
+
+        let mut socket = connect(address, self.connection_timeout)?;
+        socket
+            .set_read_timeout(self.read_timeout)
+            .context(SetTimeoutSnafu)?;
+        socket
+            .set_write_timeout(self.write_timeout)
+            .context(SetTimeoutSnafu)?;
+        let mut buffer: Vec<u8> = Vec::with_capacity(self.max_pdu_length as usize);
+        // send request
+
+        write_pdu(&mut buffer, &request).context(SendRequestSnafu)?;
+        socket.write_all(&buffer).context(WireSendSnafu)?;
+        buffer.clear();
+        // receive response
+        let msg = read_pdu(&mut socket, MAXIMUM_PDU_SIZE, true).context(ReceiveResponseSnafu)?;
+
+        let (presentation_contexts, acceptor_max_pdu_length, negotiated_options) =
+            match process_response(self.protocol_version, msg) {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    if e.requires_abort() {
+                        // abort connection
+                        let _ = write_pdu(
+                            &mut buffer,
+                            &Pdu::AbortRQ {
+                                source: AbortRQSource::ServiceUser,
+                            },
+                        );
+                        let _ = socket.write_all(&buffer);
+                        buffer.clear();
+                    }
+                    return Err(e);
+                }
+            };
+
+        Ok(ClientAssociation {
             presentation_contexts,
-            protocol_version,
-            max_pdu_length,
-        } = self;
+            abstract_syntaxes,
+            requestor_max_pdu_length: self.max_pdu_length,
+            acceptor_max_pdu_length,
+            negotiated_options,
+            socket,
+            buffer,
+        })
+    }
 
+    /// Initiate the TCP connection to the given address
+    /// and request a new DICOM association asynchronously,
+    /// negotiating the presentation contexts in the process.
+    ///
+    /// The connection timeout is honored,
+    /// but the read and write timeouts are not applied
+    /// to the resulting association.
+    /// Use [`tokio::time::timeout`] on its operations instead.
+    #[cfg(feature = "async")]
+    pub async fn establish_async<A>(self, address: A) -> Result<AsyncClientAssociation>
+    where
+        A: tokio::net::ToSocketAddrs,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        let (request, abstract_syntaxes) = self.association_request()?;
+
+        let connection = tokio::net::TcpStream::connect(address);
+        let mut socket = match self.connection_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, connection)
+                .await
+                .map_err(std::io::Error::from)
+                .and_then(|result| result),
+            None => connection.await,
+        }
+        .context(ConnectSnafu)?;
+        let mut buffer: Vec<u8> = Vec::with_capacity(self.max_pdu_length as usize);
+        // send request
+
+        write_pdu(&mut buffer, &request).context(SendRequestSnafu)?;
+        socket.write_all(&buffer).await.context(WireSendSnafu)?;
+        buffer.clear();
+        // receive response
+        let msg = read_pdu_async(&mut socket, MAXIMUM_PDU_SIZE, true)
+            .await
+            .context(ReceiveResponseSnafu)?;
+
+        let (presentation_contexts, acceptor_max_pdu_length, negotiated_options) =
+            match process_response(self.protocol_version, msg) {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    if e.requires_abort() {
+                        // abort connection
+                        let _ = write_pdu(
+                            &mut buffer,
+                            &Pdu::AbortRQ {
+                                source: AbortRQSource::ServiceUser,
+                            },
+                        );
+                        let _ = socket.write_all(&buffer).await;
+                        buffer.clear();
+                    }
+                    return Err(e);
+                }
+            };
+
+        Ok(AsyncClientAssociation {
+            presentation_contexts,
+            abstract_syntaxes,
+            requestor_max_pdu_length: self.max_pdu_length,
+            acceptor_max_pdu_length,
+            negotiated_options,
+            socket,
+            buffer,
+        })
+    }
+
+    /// Build the association request PDU,
+    /// along with the abstract syntax proposed
+    /// for each presentation context ID.
+    fn association_request(&self) -> Result<(Pdu, Vec<(u8, String)>)> {
         // fail if no presentation contexts were provided: they represent intent,
         // should not be omitted by the user
         ensure!(
-            !presentation_contexts.is_empty(),
+            !self.presentation_contexts.is_empty(),
             MissingAbstractSyntaxSnafu
         );
+        for ae_title in [&self.calling_ae_title, &self.called_ae_title] {
+            check_ae_title(ae_title).context(InvalidAeTitleSnafu {
+                ae_title: ae_title.to_string(),
+            })?;
+        }
 
-        let presentation_contexts: Vec<_> = presentation_contexts
-            .into_iter()
+        let presentation_contexts: Vec<_> = self
+            .presentation_contexts
+            .iter()
             .enumerate()
             .map(|(i, presentation_context)| PresentationContextProposed {
                 id: (i + 1) as u8,
@@ -256,120 +544,143 @@ impl<'a> ClientAssociationOptions<'a> {
                     .collect(),
             })
             .collect();
-        let msg = Pdu::AssociationRQ {
-            protocol_version,
-            calling_ae_title: calling_ae_title.to_string(),
-            called_ae_title: called_ae_title.to_string(),
-            application_context_name: application_context_name.to_string(),
+        let abstract_syntaxes: Vec<_> = presentation_contexts
+            .iter()
+            .map(|pc| (pc.id, pc.abstract_syntax.clone()))
+            .collect();
+        let mut user_variables = vec![
+            UserVariableItem::MaxLength(self.max_pdu_length),
+            UserVariableItem::ImplementationClassUID(IMPLEMENTATION_CLASS_UID.to_string()),
+            UserVariableItem::ImplementationVersionName(IMPLEMENTATION_VERSION_NAME.to_string()),
+        ];
+        user_variables.extend(
+            self.role_selections
+                .iter()
+                .map(|(uid, scu_role, scp_role)| {
+                    UserVariableItem::ScpScuRoleSelection(uid.to_string(), *scu_role, *scp_role)
+                }),
+        );
+        if let Some((invoked, performed)) = self.async_operations_window {
+            user_variables.push(UserVariableItem::AsyncOperationsWindow(invoked, performed));
+        }
+        user_variables.extend(self.extended_negotiation.iter().map(|(uid, info)| {
+            UserVariableItem::SopClassExtendedNegotiation(uid.to_string(), info.clone())
+        }));
+        let request = Pdu::AssociationRQ {
+            protocol_version: self.protocol_version,
+            calling_ae_title: self.calling_ae_title.to_string(),
+            called_ae_title: self.called_ae_title.to_string(),
+            application_context_name: self.application_context_name.to_string(),
             presentation_contexts,
-            user_variables: vec![
-                UserVariableItem::MaxLength(max_pdu_length),
-                UserVariableItem::ImplementationClassUID(IMPLEMENTATION_CLASS_UID.to_string()),
-                UserVariableItem::ImplementationVersionName(
-                    IMPLEMENTATION_VERSION_NAME.to_string(),
-                ),
-            ],
+            user_variables,
         };
+        Ok((request, abstract_syntaxes))
+    }
+}
 
-        let mut socket = std::net::TcpStream::connect(address).context(ConnectSnafu)?;
-        let mut buffer: Vec<u8> = Vec::with_capacity(max_pdu_length as usize);
-        // send request
-
-        write_pdu(&mut buffer, &msg).context(SendRequestSnafu)?;
-        socket.write_all(&buffer).context(WireSendSnafu)?;
-        buffer.clear();
-        // receive response
-        let msg = read_pdu(&mut socket, MAXIMUM_PDU_SIZE, true).context(ReceiveResponseSnafu)?;
-
-        match msg {
-            Pdu::AssociationAC {
-                protocol_version: protocol_version_scp,
-                application_context_name: _,
-                presentation_contexts: presentation_contexts_scp,
-                calling_ae_title: _,
-                called_ae_title: _,
-                user_variables,
-            } => {
-                ensure!(
-                    protocol_version == protocol_version_scp,
-                    ProtocolVersionMismatchSnafu {
-                        expected: protocol_version,
-                        got: protocol_version_scp,
-                    }
-                );
+impl Error {
+    /// Whether the association should be aborted
+    /// after this error occurred during negotiation.
+    fn requires_abort(&self) -> bool {
+        matches!(
+            self,
+            Error::NoAcceptedPresentationContexts
+                | Error::UnexpectedResponse { .. }
+                | Error::UnknownResponse { .. }
+        )
+    }
+}
 
-                let acceptor_max_pdu_length = user_variables
-                    .iter()
-                    .find_map(|item| match item {
-                        UserVariableItem::MaxLength(len) => Some(*len),
-                        _ => None,
-                    })
-                    .unwrap_or(DEFAULT_MAX_PDU);
-
-                // treat 0 as the maximum size admitted by the standard
-                let acceptor_max_pdu_length = if acceptor_max_pdu_length == 0 {
-                    MAXIMUM_PDU_SIZE
-                } else {
-                    acceptor_max_pdu_length
-                };
-
-                let presentation_contexts: Vec<_> = presentation_contexts_scp
-                    .into_iter()
-                    .filter(|c| c.reason == PresentationContextResultReason::Acceptance)
-                    .collect();
-                if presentation_contexts.is_empty() {
-                    // abort connection
-                    let _ = write_pdu(
-                        &mut buffer,
-                        &Pdu::AbortRQ {
-                            source: AbortRQSource::ServiceUser,
-                        },
-                    );
-                    let _ = socket.write_all(&buffer);
-                    buffer.clear();
-                    return NoAcceptedPresentationContextsSnafu.fail();
+/// Interpret the association response from the acceptor,
+/// obtaining the accepted presentation contexts,
+/// the maximum PDU length admitted by the acceptor,
+/// and the other negotiated options.
+fn process_response(
+    protocol_version: u16,
+    msg: Pdu,
+) -> Result<(Vec<PresentationContextResult>, u32, NegotiatedOptions)> {
+    match msg {
+        Pdu::AssociationAC {
+            protocol_version: protocol_version_scp,
+            application_context_name: _,
+            presentation_contexts: presentation_contexts_scp,
+            calling_ae_title: _,
+            called_ae_title: _,
+            user_variables,
+        } => {
+            ensure!(
+                protocol_version == protocol_version_scp,
+                ProtocolVersionMismatchSnafu {
+                    expected: protocol_version,
+                    got: protocol_version_scp,
                 }
-                Ok(ClientAssociation {
-                    presentation_contexts,
-                    requestor_max_pdu_length: max_pdu_length,
-                    acceptor_max_pdu_length,
-                    socket,
-                    buffer,
+            );
+
+            let acceptor_max_pdu_length = user_variables
+                .iter()
+                .find_map(|item| match item {
+                    UserVariableItem::MaxLength(len) => Some(*len),
+                    _ => None,
                 })
-            }
-            Pdu::AssociationRJ { result, source } => RejectedSnafu {
-                association_result: result,
-                association_source: source,
-            }
-            .fail(),
-            pdu @ Pdu::AbortRQ { .. }
-            | pdu @ Pdu::ReleaseRQ { .. }
-            | pdu @ Pdu::AssociationRQ { .. }
-            | pdu @ Pdu::PData { .. }
-            | pdu @ Pdu::ReleaseRP { .. } => {
-                // abort connection
-                let _ = write_pdu(
-                    &mut buffer,
-                    &Pdu::AbortRQ {
-                        source: AbortRQSource::ServiceUser,
-                    },
-                );
-                let _ = socket.write_all(&buffer);
-                UnexpectedResponseSnafu { pdu }.fail()
-            }
-            pdu @ Pdu::Unknown { .. } => {
-                // abort connection
-                let _ = write_pdu(
-                    &mut buffer,
-                    &Pdu::AbortRQ {
-                        source: AbortRQSource::ServiceUser,
-                    },
-                );
-                let _ = socket.write_all(&buffer);
-                UnknownResponseSnafu { pdu }.fail()
-            }
+                .unwrap_or(DEFAULT_MAX_PDU);
+
+            // treat 0 as the maximum size admitted by the standard
+            let acceptor_max_pdu_length = if acceptor_max_pdu_length == 0 {
+                MAXIMUM_PDU_SIZE
+            } else {
+                acceptor_max_pdu_length
+            };
+
+            let presentation_contexts: Vec<_> = presentation_contexts_scp
+                .into_iter()
+                .filter(|c| c.reason == PresentationContextResultReason::Acceptance)
+                .collect();
+            ensure!(
+                !presentation_contexts.is_empty(),
+                NoAcceptedPresentationContextsSnafu
+            );
+            Ok((
+                presentation_contexts,
+                acceptor_max_pdu_length,
+                NegotiatedOptions::from_user_variables(&user_variables),
+            ))
+        }
+        Pdu::AssociationRJ { result, source } => RejectedSnafu {
+            association_result: result,
+            association_source: source,
+        }
+        .fail(),
+        pdu @ Pdu::AbortRQ { .. }
+        | pdu @ Pdu::ReleaseRQ { .. }
+        | pdu @ Pdu::AssociationRQ { .. }
+        | pdu @ Pdu::PData { .. }
+        | pdu @ Pdu::ReleaseRP { .. } => UnexpectedResponseSnafu { pdu }.fail(),
+        pdu @ Pdu::Unknown { .. } => UnknownResponseSnafu { pdu }.fail(),
+    }
+}
+
+/// Open a TCP connection to the given address,
+/// waiting at most for the given duration for each resolved address.
+fn connect<A: ToSocketAddrs>(address: A, timeout: Option<Duration>) -> Result<TcpStream> {
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => return TcpStream::connect(address).context(ConnectSnafu),
+    };
+
+    let mut last_error = None;
+    for address in address.to_socket_addrs().context(ConnectSnafu)? {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(socket) => return Ok(socket),
+            Err(e) => last_error = Some(e),
         }
     }
+    Err(last_error.unwrap_or_else(|| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "could not resolve to any address",
+        )
+    }))
+    .context(ConnectSnafu)
 }
 
 /// A DICOM upper level association from the perspective
@@ -390,10 +701,14 @@ pub struct ClientAssociation {
     /// The presentation contexts accorded with the acceptor application entity,
     /// without the rejected ones.
     presentation_contexts: Vec<PresentationContextResult>,
+    /// The abstract syntax proposed for each presentation context ID
+    abstract_syntaxes: Vec<(u8, String)>,
     /// The maximum PDU length that this application entity is expecting to receive
     requestor_max_pdu_length: u32,
     /// The maximum PDU length that the remote application entity accepts
     acceptor_max_pdu_length: u32,
+    /// The options negotiated besides the presentation contexts
+    negotiated_options: NegotiatedOptions,
     /// The TCP stream to the other DICOM node
     socket: TcpStream,
     /// Buffer to assemble PDU before sending it on wire
@@ -406,6 +721,21 @@ impl ClientAssociation {
         &self.presentation_contexts
     }
 
+    /// Retrieve the abstract syntax which was proposed
+    /// for the presentation context with the given ID.
+    pub fn abstract_syntax(&self, presentation_context_id: u8) -> Option<&str> {
+        self.abstract_syntaxes
+            .iter()
+            .find(|(id, _)| *id == presentation_context_id)
+            .map(|(_, uid)| uid.as_str())
+    }
+
+    /// Retrieve the options negotiated with the association acceptor
+    /// besides the presentation contexts.
+    pub fn negotiated_options(&self) -> &NegotiatedOptions {
+        &self.negotiated_options
+    }
+
     /// Retrieve the maximum PDU length
     /// admitted by the association acceptor.
     pub fn acceptor_max_pdu_length(&self) -> u32 {
@@ -517,3 +847,153 @@ impl Drop for ClientAssociation {
         let _ = self.socket.shutdown(std::net::Shutdown::Both);
     }
 }
+
+/// An asynchronous DICOM upper level association from the perspective
+/// of a requesting application entity,
+/// created with [`ClientAssociationOptions::establish_async`].
+///
+/// This is the [tokio] counterpart of [`ClientAssociation`].
+/// Since the association cannot be released asynchronously on drop,
+/// it should be terminated explicitly
+/// with [`release`](Self::release) or [`abort`](Self::abort).
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncClientAssociation {
+    /// The presentation contexts accorded with the acceptor application entity,
+    /// without the rejected ones.
+    presentation_contexts: Vec<PresentationContextResult>,
+    /// The abstract syntax proposed for each presentation context ID
+    abstract_syntaxes: Vec<(u8, String)>,
+    /// The maximum PDU length that this application entity is expecting to receive
+    requestor_max_pdu_length: u32,
+    /// The maximum PDU length that the remote application entity accepts
+    acceptor_max_pdu_length: u32,
+    /// The options negotiated besides the presentation contexts
+    negotiated_options: NegotiatedOptions,
+    /// The TCP stream to the other DICOM node
+    socket: tokio::net::TcpStream,
+    /// Buffer to assemble PDU before sending it on wire
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncClientAssociation {
+    /// Retrieve the list of negotiated presentation contexts.
+    pub fn presentation_contexts(&self) -> &[PresentationContextResult] {
+        &self.presentation_contexts
+    }
+
+    /// Retrieve the abstract syntax which was proposed
+    /// for the presentation context with the given ID.
+    pub fn abstract_syntax(&self, presentation_context_id: u8) -> Option<&str> {
+        self.abstract_syntaxes
+            .iter()
+            .find(|(id, _)| *id == presentation_context_id)
+            .map(|(_, uid)| uid.as_str())
+    }
+
+    /// Retrieve the options negotiated with the association acceptor
+    /// besides the presentation contexts.
+    pub fn negotiated_options(&self) -> &NegotiatedOptions {
+        &self.negotiated_options
+    }
+
+    /// Retrieve the maximum PDU length
+    /// admitted by the association acceptor.
+    pub fn acceptor_max_pdu_length(&self) -> u32 {
+        self.acceptor_max_pdu_length
+    }
+
+    /// Retrieve the maximum PDU length
+    /// that this application entity is expecting to receive.
+    pub fn requestor_max_pdu_length(&self) -> u32 {
+        self.requestor_max_pdu_length
+    }
+
+    /// Send a PDU message to the other intervenient.
+    pub async fn send(&mut self, msg: &Pdu) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        self.buffer.clear();
+        write_pdu(&mut self.buffer, msg).context(SendSnafu)?;
+        if self.buffer.len() > self.acceptor_max_pdu_length as usize {
+            return SendTooLongPduSnafu {
+                length: self.buffer.len(),
+            }
+            .fail();
+        }
+        self.socket
+            .write_all(&self.buffer)
+            .await
+            .context(WireSendSnafu)
+    }
+
+    /// Read a PDU message from the other intervenient.
+    pub async fn receive(&mut self) -> Result<Pdu> {
+        read_pdu_async(&mut self.socket, self.requestor_max_pdu_length, true)
+            .await
+            .context(ReceiveSnafu)
+    }
+
+    /// Send the given data as one or more P-Data PDUs
+    /// in the given presentation context,
+    /// splitting it into separate PDUs if necessary.
+    pub async fn send_pdata(&mut self, presentation_context_id: u8, data: &[u8]) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        self.buffer.clear();
+        let mut writer = PDataWriter::new(
+            &mut self.buffer,
+            presentation_context_id,
+            self.acceptor_max_pdu_length,
+        );
+        writer.write_all(data).context(WireSendSnafu)?;
+        writer.finish().context(WireSendSnafu)?;
+        self.socket
+            .write_all(&self.buffer)
+            .await
+            .context(WireSendSnafu)
+    }
+
+    /// Gracefully terminate the association by exchanging release messages
+    /// and then shutting down the TCP connection.
+    pub async fn release(mut self) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let out = self.release_impl().await;
+        let _ = self.socket.shutdown().await;
+        out
+    }
+
+    /// Send an abort message and shut down the TCP connection,
+    /// terminating the association.
+    pub async fn abort(mut self) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let pdu = Pdu::AbortRQ {
+            source: AbortRQSource::ServiceUser,
+        };
+        let out = self.send(&pdu).await;
+        let _ = self.socket.shutdown().await;
+        out
+    }
+
+    /// Obtain access to the inner TCP stream
+    /// connected to the association acceptor.
+    ///
+    /// **Note:** reading and writing should be done with care
+    /// to avoid inconsistencies in the association state.
+    /// Do not call `send` and `receive` while not in a PDU boundary.
+    pub fn inner_stream(&mut self) -> &mut tokio::net::TcpStream {
+        &mut self.socket
+    }
+
+    async fn release_impl(&mut self) -> Result<()> {
+        self.send(&Pdu::ReleaseRQ).await?;
+        match self.receive().await? {
+            Pdu::ReleaseRP => Ok(()),
+            pdu @ Pdu::Unknown { .. } => UnknownResponseSnafu { pdu }.fail(),
+            pdu => UnexpectedResponseSnafu { pdu }.fail(),
+        }
+    }
+}
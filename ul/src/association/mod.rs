@@ -14,14 +14,32 @@
 //! a newly created [TCP stream][2] can be passed to
 //! a previously prepared [`ServerAssociationOptions`][3].
 //!
+//! With the `async` feature enabled,
+//! associations can also be established and used asynchronously
+//! on top of tokio,
+//! via `establish_async` in either of the option types.
+//!
+//! Besides the presentation contexts,
+//! both option types can negotiate the asynchronous operations window,
+//! SCP/SCU role selection and SOP class extended negotiation,
+//! the outcome of which is described by [`NegotiatedOptions`][4].
+//!
 //! [1]: crate::association::client::ClientAssociationOptions
 //! [2]: std::net::TcpStream
 //! [3]: crate::association::server::ServerAssociationOptions
+//! [4]: crate::association::negotiation::NegotiatedOptions
 pub mod client;
+pub mod negotiation;
 pub mod server;
 
 pub(crate) mod pdata;
 
 pub use client::{ClientAssociation, ClientAssociationOptions};
+pub use negotiation::{NegotiatedOptions, RoleSelection};
 pub use pdata::PDataWriter;
 pub use server::{ServerAssociation, ServerAssociationOptions};
+
+#[cfg(feature = "async")]
+pub use client::AsyncClientAssociation;
+#[cfg(feature = "async")]
+pub use server::AsyncServerAssociation;
@@ -0,0 +1,150 @@
+//! Negotiation of association options
+//! carried in the user information item of the association PDUs,
+//! besides the maximum PDU length:
+//! the asynchronous operations window,
+//! SCP/SCU role selection,
+//! and SOP class extended negotiation.
+//!
+//! The options are requested through the builder methods of
+//! [`ClientAssociationOptions`](super::ClientAssociationOptions)
+//! and [`ServerAssociationOptions`](super::ServerAssociationOptions),
+//! and the outcome is available in [`NegotiatedOptions`]
+//! once the association is established.
+use crate::pdu::UserVariableItem;
+
+/// The SCP/SCU roles negotiated for a SOP class.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RoleSelection {
+    /// The SOP class UID
+    pub sop_class_uid: String,
+    /// Whether the association requester may take the SCU role
+    pub scu_role: bool,
+    /// Whether the association requester may take the SCP role
+    pub scp_role: bool,
+}
+
+/// The options agreed between both nodes of an association.
+///
+/// Options which were not negotiated take their default values:
+/// a window of one outstanding operation in each direction,
+/// the default roles for all SOP classes,
+/// and no extended negotiation.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NegotiatedOptions {
+    /// The maximum number of outstanding operations
+    /// invoked and performed by the association requester,
+    /// if the window was negotiated
+    pub async_operations_window: Option<(u16, u16)>,
+    /// The roles accepted for each SOP class
+    pub role_selections: Vec<RoleSelection>,
+    /// The service class application information
+    /// accepted for each SOP class
+    pub extended_negotiation: Vec<(String, Vec<u8>)>,
+}
+
+impl NegotiatedOptions {
+    /// Collect the negotiated options
+    /// from the user variables of an association acceptance.
+    pub fn from_user_variables(user_variables: &[UserVariableItem]) -> Self {
+        let mut options = NegotiatedOptions::default();
+        for item in user_variables {
+            match item {
+                UserVariableItem::AsyncOperationsWindow(invoked, performed) => {
+                    options.async_operations_window = Some((*invoked, *performed));
+                }
+                UserVariableItem::ScpScuRoleSelection(uid, scu_role, scp_role) => {
+                    options.role_selections.push(RoleSelection {
+                        sop_class_uid: uid.clone(),
+                        scu_role: *scu_role,
+                        scp_role: *scp_role,
+                    })
+                }
+                UserVariableItem::SopClassExtendedNegotiation(uid, info) => options
+                    .extended_negotiation
+                    .push((uid.clone(), info.clone())),
+                _ => {}
+            }
+        }
+        options
+    }
+
+    /// The maximum number of outstanding operations
+    /// that the association requester may invoke,
+    /// where 0 means unlimited.
+    pub fn max_operations_invoked(&self) -> u16 {
+        self.async_operations_window.map(|w| w.0).unwrap_or(1)
+    }
+
+    /// The maximum number of outstanding operations
+    /// that the association requester may perform,
+    /// where 0 means unlimited.
+    pub fn max_operations_performed(&self) -> u16 {
+        self.async_operations_window.map(|w| w.1).unwrap_or(1)
+    }
+
+    /// The roles accepted for the given SOP class,
+    /// if role selection was negotiated for it.
+    pub fn role_selection(&self, sop_class_uid: &str) -> Option<&RoleSelection> {
+        self.role_selections
+            .iter()
+            .find(|r| r.sop_class_uid == sop_class_uid)
+    }
+
+    /// The service class application information
+    /// accepted for the given SOP class,
+    /// if extended negotiation took place for it.
+    pub fn extended_negotiation(&self, sop_class_uid: &str) -> Option<&[u8]> {
+        self.extended_negotiation
+            .iter()
+            .find(|(uid, _)| uid == sop_class_uid)
+            .map(|(_, info)| info.as_slice())
+    }
+}
+
+/// Reduce a proposed number of outstanding operations
+/// to the number supported, where 0 means unlimited.
+pub(crate) fn limit_operations(proposed: u16, supported: u16) -> u16 {
+    match (proposed, supported) {
+        (0, n) | (n, 0) => n,
+        (a, b) => a.min(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collect_negotiated_options() {
+        let options = NegotiatedOptions::from_user_variables(&[
+            UserVariableItem::MaxLength(16384),
+            UserVariableItem::AsyncOperationsWindow(4, 0),
+            UserVariableItem::ScpScuRoleSelection("1.2.3".to_string(), false, true),
+            UserVariableItem::SopClassExtendedNegotiation("1.2.4".to_string(), vec![1, 0]),
+        ]);
+        assert_eq!(options.max_operations_invoked(), 4);
+        assert_eq!(options.max_operations_performed(), 0);
+        assert_eq!(
+            options.role_selection("1.2.3"),
+            Some(&RoleSelection {
+                sop_class_uid: "1.2.3".to_string(),
+                scu_role: false,
+                scp_role: true,
+            })
+        );
+        assert_eq!(options.extended_negotiation("1.2.4"), Some(&[1, 0][..]));
+        assert_eq!(options.extended_negotiation("1.2.3"), None);
+
+        let options = NegotiatedOptions::default();
+        assert_eq!(options.max_operations_invoked(), 1);
+        assert_eq!(options.max_operations_performed(), 1);
+    }
+
+    #[test]
+    fn operations_limit() {
+        assert_eq!(limit_operations(0, 0), 0);
+        assert_eq!(limit_operations(0, 8), 8);
+        assert_eq!(limit_operations(16, 0), 16);
+        assert_eq!(limit_operations(16, 8), 8);
+    }
+}
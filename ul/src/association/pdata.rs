@@ -1,5 +1,6 @@
 use std::io::Write;
 
+use crate::pdu::codec::calculate_max_data_len_single;
 use crate::pdu::reader::PDU_HEADER_SIZE;
 
 /// A P-Data value writer.
@@ -194,16 +195,6 @@ where
     }
 }
 
-/// Determine the maximum length of actual PDV data
-/// when encapsulated in a PDU with the given length property.
-/// Does not account for the first 2 bytes (type + reserved).
-#[inline]
-fn calculate_max_data_len_single(pdu_len: u32) -> u32 {
-    // data length: 4 bytes
-    // control header: 2 bytes
-    pdu_len - 4 - 2
-}
-
 #[cfg(test)]
 mod tests {
     use std::io::Write;
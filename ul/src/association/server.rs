@@ -11,6 +11,7 @@ use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
 use snafu::{ensure, ResultExt, Snafu};
 
 use crate::{
+    address::{check_ae_title, AeTitleError},
     pdu::{
         reader::{read_pdu, DEFAULT_MAX_PDU, MAXIMUM_PDU_SIZE},
         writer::write_pdu,
@@ -21,7 +22,10 @@ use crate::{
     IMPLEMENTATION_CLASS_UID, IMPLEMENTATION_VERSION_NAME,
 };
 
+use super::negotiation::{limit_operations, NegotiatedOptions};
 use super::pdata::PDataWriter;
+#[cfg(feature = "async")]
+use crate::pdu::reader::read_pdu_async;
 
 #[derive(Debug, Snafu)]
 #[non_exhaustive]
@@ -29,6 +33,12 @@ pub enum Error {
     /// missing at least one abstract syntax to accept negotiations
     MissingAbstractSyntax,
 
+    #[snafu(display("invalid AE title `{}`", ae_title))]
+    InvalidAeTitle {
+        ae_title: String,
+        source: AeTitleError,
+    },
+
     /// failed to receive association request
     ReceiveRequest { source: crate::pdu::reader::Error },
 
@@ -192,6 +202,12 @@ pub struct ServerAssociationOptions<'a, A> {
     protocol_version: u16,
     /// the maximum PDU length
     max_pdu_length: u32,
+    /// the SCP/SCU roles accepted, per SOP class
+    role_selections: Vec<(Cow<'a, str>, bool, bool)>,
+    /// the asynchronous operations window supported, if any
+    async_operations_window: Option<(u16, u16)>,
+    /// the SOP class extended negotiation sub-items to accept
+    extended_negotiation: Vec<(Cow<'a, str>, Vec<u8>)>,
 }
 
 impl<'a> Default for ServerAssociationOptions<'a, AcceptAny> {
@@ -204,6 +220,9 @@ impl<'a> Default for ServerAssociationOptions<'a, AcceptAny> {
             transfer_syntax_uids: Vec::new(),
             protocol_version: 1,
             max_pdu_length: crate::pdu::reader::DEFAULT_MAX_PDU,
+            role_selections: Vec::new(),
+            async_operations_window: None,
+            extended_negotiation: Vec::new(),
         }
     }
 }
@@ -251,6 +270,9 @@ where
             transfer_syntax_uids,
             protocol_version,
             max_pdu_length,
+            role_selections,
+            async_operations_window,
+            extended_negotiation,
             ..
         } = self;
 
@@ -262,6 +284,9 @@ where
             transfer_syntax_uids,
             protocol_version,
             max_pdu_length,
+            role_selections,
+            async_operations_window,
+            extended_negotiation,
         }
     }
 
@@ -301,17 +326,146 @@ where
         self
     }
 
+    /// Define the SCP/SCU roles that the requester may take
+    /// for the given SOP class.
+    ///
+    /// Proposed roles are only accepted if allowed here.
+    /// By default, all roles proposed for a supported abstract syntax
+    /// are accepted.
+    pub fn with_role_selection<T>(
+        mut self,
+        sop_class_uid: T,
+        scu_role: bool,
+        scp_role: bool,
+    ) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.role_selections
+            .push((sop_class_uid.into(), scu_role, scp_role));
+        self
+    }
+
+    /// Support an asynchronous operations window:
+    /// the maximum number of outstanding operations
+    /// that the requester may invoke and perform,
+    /// where 0 means unlimited.
+    ///
+    /// A window proposed by the requester is reduced to these limits.
+    /// By default, proposed windows are not accepted,
+    /// so that operations are performed synchronously.
+    pub fn async_operations_window(mut self, invoked: u16, performed: u16) -> Self {
+        self.async_operations_window = Some((invoked, performed));
+        self
+    }
+
+    /// Accept SOP class extended negotiation for the given SOP class,
+    /// replying with the given service class application information
+    /// when the requester proposes it.
+    ///
+    /// By default, extended negotiation is not accepted.
+    pub fn with_extended_negotiation<T>(mut self, sop_class_uid: T, info: Vec<u8>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        self.extended_negotiation.push((sop_class_uid.into(), info));
+        self
+    }
+
     /// Negotiate an association with the given TCP stream.
     pub fn establish(&self, mut socket: TcpStream) -> Result<ServerAssociation> {
         ensure!(
             !self.abstract_syntax_uids.is_empty(),
             MissingAbstractSyntaxSnafu
         );
+        check_ae_title(&self.ae_title).context(InvalidAeTitleSnafu {
+            ae_title: &*self.ae_title,
+        })?;
 
         let max_pdu_length = self.max_pdu_length;
 
         let pdu = read_pdu(&mut socket, max_pdu_length, true).context(ReceiveRequestSnafu)?;
         let mut buffer: Vec<u8> = Vec::with_capacity(max_pdu_length as usize);
+        let (response, outcome) = self.negotiate(pdu);
+        if let Some(response) = response {
+            write_pdu(&mut buffer, &response).context(SendResponseSnafu)?;
+            socket.write_all(&buffer).context(WireSendSnafu)?;
+        }
+        let (presentation_contexts, requestor_max_pdu_length, negotiated_options) = outcome?;
+
+        Ok(ServerAssociation {
+            presentation_contexts,
+            requestor_max_pdu_length,
+            acceptor_max_pdu_length: max_pdu_length,
+            negotiated_options,
+            socket,
+            buffer,
+        })
+    }
+
+    /// Negotiate an association with the given TCP stream asynchronously.
+    #[cfg(feature = "async")]
+    pub async fn establish_async(
+        &self,
+        mut socket: tokio::net::TcpStream,
+    ) -> Result<AsyncServerAssociation> {
+        use tokio::io::AsyncWriteExt;
+
+        ensure!(
+            !self.abstract_syntax_uids.is_empty(),
+            MissingAbstractSyntaxSnafu
+        );
+        check_ae_title(&self.ae_title).context(InvalidAeTitleSnafu {
+            ae_title: &*self.ae_title,
+        })?;
+
+        let max_pdu_length = self.max_pdu_length;
+
+        let pdu = read_pdu_async(&mut socket, max_pdu_length, true)
+            .await
+            .context(ReceiveRequestSnafu)?;
+        let mut buffer: Vec<u8> = Vec::with_capacity(max_pdu_length as usize);
+        let (response, outcome) = self.negotiate(pdu);
+        if let Some(response) = response {
+            write_pdu(&mut buffer, &response).context(SendResponseSnafu)?;
+            socket.write_all(&buffer).await.context(WireSendSnafu)?;
+        }
+        let (presentation_contexts, requestor_max_pdu_length, negotiated_options) = outcome?;
+
+        Ok(AsyncServerAssociation {
+            presentation_contexts,
+            requestor_max_pdu_length,
+            acceptor_max_pdu_length: max_pdu_length,
+            negotiated_options,
+            socket,
+            buffer,
+        })
+    }
+
+    /// Process an incoming association request,
+    /// obtaining the PDU to send back to the requester, if any,
+    /// and the negotiated presentation contexts
+    /// along with the maximum PDU length admitted by the requester
+    /// and the other negotiated options.
+    #[allow(clippy::type_complexity)]
+    fn negotiate(
+        &self,
+        pdu: Pdu,
+    ) -> (
+        Option<Pdu>,
+        Result<(Vec<PresentationContextResult>, u32, NegotiatedOptions)>,
+    ) {
+        let max_pdu_length = self.max_pdu_length;
+        let reject = |reason| {
+            (
+                Some(Pdu::AssociationRJ {
+                    result: AssociationRJResult::Permanent,
+                    source: AssociationRJSource::ServiceUser(reason),
+                }),
+                RejectedSnafu.fail(),
+            )
+        };
+
         match pdu {
             Pdu::AssociationRQ {
                 protocol_version,
@@ -322,50 +476,22 @@ where
                 user_variables,
             } => {
                 if protocol_version != self.protocol_version {
-                    write_pdu(
-                        &mut buffer,
-                        &Pdu::AssociationRJ {
-                            result: AssociationRJResult::Permanent,
-                            source: AssociationRJSource::ServiceUser(
-                                AssociationRJServiceUserReason::NoReasonGiven,
-                            ),
-                        },
-                    )
-                    .context(SendResponseSnafu)?;
-                    socket.write_all(&buffer).context(WireSendSnafu)?;
-                    return RejectedSnafu.fail();
+                    return reject(AssociationRJServiceUserReason::NoReasonGiven);
                 }
 
                 if application_context_name != self.application_context_name {
-                    write_pdu(
-                        &mut buffer,
-                        &Pdu::AssociationRJ {
-                            result: AssociationRJResult::Permanent,
-                            source: AssociationRJSource::ServiceUser(
-                                AssociationRJServiceUserReason::ApplicationContextNameNotSupported,
-                            ),
-                        },
-                    )
-                    .context(SendResponseSnafu)?;
-                    socket.write_all(&buffer).context(WireSendSnafu)?;
-                    return RejectedSnafu.fail();
+                    return reject(
+                        AssociationRJServiceUserReason::ApplicationContextNameNotSupported,
+                    );
                 }
 
-                self.ae_access_control
-                    .check_access(&self.ae_title, &calling_ae_title, &called_ae_title)
-                    .map(Ok)
-                    .unwrap_or_else(|reason| {
-                        write_pdu(
-                            &mut buffer,
-                            &Pdu::AssociationRJ {
-                                result: AssociationRJResult::Permanent,
-                                source: AssociationRJSource::ServiceUser(reason),
-                            },
-                        )
-                        .context(SendResponseSnafu)?;
-                        socket.write_all(&buffer).context(WireSendSnafu)?;
-                        RejectedSnafu.fail()
-                    })?;
+                if let Err(reason) = self.ae_access_control.check_access(
+                    &self.ae_title,
+                    &calling_ae_title,
+                    &called_ae_title,
+                ) {
+                    return reject(reason);
+                }
 
                 // fetch requested maximum PDU length
                 let requestor_max_pdu_length = user_variables
@@ -383,6 +509,69 @@ where
                     requestor_max_pdu_length
                 };
 
+                // acknowledge the proposed SCP/SCU roles
+                // for the abstract syntaxes supported by this node,
+                // within the roles allowed
+                let mut negotiated_variables: Vec<_> = user_variables
+                    .iter()
+                    .filter_map(|item| match item {
+                        UserVariableItem::ScpScuRoleSelection(uid, scu_role, scp_role)
+                            if self.abstract_syntax_uids.contains(&Cow::from(uid.as_str())) =>
+                        {
+                            let (scu_role, scp_role) = self
+                                .role_selections
+                                .iter()
+                                .find(|(allowed_uid, _, _)| allowed_uid == uid)
+                                .map(|(_, allow_scu, allow_scp)| {
+                                    (*scu_role && *allow_scu, *scp_role && *allow_scp)
+                                })
+                                .unwrap_or((*scu_role, *scp_role));
+                            Some(UserVariableItem::ScpScuRoleSelection(
+                                uid.clone(),
+                                scu_role,
+                                scp_role,
+                            ))
+                        }
+                        _ => None,
+                    })
+                    .collect();
+
+                // reduce the proposed asynchronous operations window
+                // to the supported one
+                if let Some((supported_invoked, supported_performed)) = self.async_operations_window
+                {
+                    negotiated_variables.extend(user_variables.iter().find_map(
+                        |item| match item {
+                            UserVariableItem::AsyncOperationsWindow(invoked, performed) => {
+                                Some(UserVariableItem::AsyncOperationsWindow(
+                                    limit_operations(*invoked, supported_invoked),
+                                    limit_operations(*performed, supported_performed),
+                                ))
+                            }
+                            _ => None,
+                        },
+                    ));
+                }
+
+                // reply to extended negotiation of the SOP classes configured
+                negotiated_variables.extend(user_variables.iter().filter_map(|item| {
+                    match item {
+                        UserVariableItem::SopClassExtendedNegotiation(uid, _) => self
+                            .extended_negotiation
+                            .iter()
+                            .find(|(accepted_uid, _)| accepted_uid == uid)
+                            .map(|(_, info)| {
+                                UserVariableItem::SopClassExtendedNegotiation(
+                                    uid.clone(),
+                                    info.clone(),
+                                )
+                            }),
+                        _ => None,
+                    }
+                }));
+                let negotiated_options =
+                    NegotiatedOptions::from_user_variables(&negotiated_variables);
+
                 let presentation_contexts: Vec<_> = presentation_contexts
                     .into_iter()
                     .map(|pc| {
@@ -413,9 +602,8 @@ where
                     })
                     .collect();
 
-                write_pdu(
-                    &mut buffer,
-                    &Pdu::AssociationAC {
+                (
+                    Some(Pdu::AssociationAC {
                         protocol_version: self.protocol_version,
                         application_context_name,
                         presentation_contexts: presentation_contexts.clone(),
@@ -429,31 +617,25 @@ where
                             UserVariableItem::ImplementationVersionName(
                                 IMPLEMENTATION_VERSION_NAME.to_string(),
                             ),
-                        ],
-                    },
+                        ]
+                        .into_iter()
+                        .chain(negotiated_variables)
+                        .collect(),
+                    }),
+                    Ok((
+                        presentation_contexts,
+                        requestor_max_pdu_length,
+                        negotiated_options,
+                    )),
                 )
-                .context(SendResponseSnafu)?;
-                socket.write_all(&buffer).context(WireSendSnafu)?;
-
-                Ok(ServerAssociation {
-                    presentation_contexts,
-                    requestor_max_pdu_length,
-                    acceptor_max_pdu_length: max_pdu_length,
-                    socket,
-                    buffer,
-                })
-            }
-            Pdu::ReleaseRQ => {
-                write_pdu(&mut buffer, &Pdu::ReleaseRP).context(SendResponseSnafu)?;
-                socket.write_all(&buffer).context(WireSendSnafu)?;
-                AbortedSnafu.fail()
             }
+            Pdu::ReleaseRQ => (Some(Pdu::ReleaseRP), AbortedSnafu.fail()),
             pdu @ Pdu::AssociationAC { .. }
             | pdu @ Pdu::AssociationRJ { .. }
             | pdu @ Pdu::PData { .. }
             | pdu @ Pdu::ReleaseRP
-            | pdu @ Pdu::AbortRQ { .. } => UnexpectedRequestSnafu { pdu }.fail(),
-            pdu @ Pdu::Unknown { .. } => UnknownRequestSnafu { pdu }.fail(),
+            | pdu @ Pdu::AbortRQ { .. } => (None, UnexpectedRequestSnafu { pdu }.fail()),
+            pdu @ Pdu::Unknown { .. } => (None, UnknownRequestSnafu { pdu }.fail()),
         }
     }
 }
@@ -477,6 +659,8 @@ pub struct ServerAssociation {
     requestor_max_pdu_length: u32,
     /// The maximum PDU length that this application entity is expecting to receive
     acceptor_max_pdu_length: u32,
+    /// The options negotiated besides the presentation contexts
+    negotiated_options: NegotiatedOptions,
     /// The TCP stream to the other DICOM node
     socket: TcpStream,
     /// write buffer to send fully assembled PDUs on wire
@@ -489,6 +673,12 @@ impl ServerAssociation {
         &self.presentation_contexts
     }
 
+    /// Obtain the options negotiated with the association requester
+    /// besides the presentation contexts.
+    pub fn negotiated_options(&self) -> &NegotiatedOptions {
+        &self.negotiated_options
+    }
+
     /// Send a PDU message to the other intervenient.
     pub fn send(&mut self, msg: &Pdu) -> Result<()> {
         self.buffer.clear();
@@ -548,6 +738,113 @@ impl ServerAssociation {
     }
 }
 
+/// An asynchronous DICOM upper level association from the perspective
+/// of an accepting application entity,
+/// created with [`ServerAssociationOptions::establish_async`].
+///
+/// This is the [tokio] counterpart of [`ServerAssociation`].
+#[cfg(feature = "async")]
+#[derive(Debug)]
+pub struct AsyncServerAssociation {
+    /// The accorded presentation contexts
+    presentation_contexts: Vec<PresentationContextResult>,
+    /// The maximum PDU length that the remote application entity accepts
+    requestor_max_pdu_length: u32,
+    /// The maximum PDU length that this application entity is expecting to receive
+    acceptor_max_pdu_length: u32,
+    /// The options negotiated besides the presentation contexts
+    negotiated_options: NegotiatedOptions,
+    /// The TCP stream to the other DICOM node
+    socket: tokio::net::TcpStream,
+    /// write buffer to send fully assembled PDUs on wire
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "async")]
+impl AsyncServerAssociation {
+    /// Obtain a view of the negotiated presentation contexts.
+    pub fn presentation_contexts(&self) -> &[PresentationContextResult] {
+        &self.presentation_contexts
+    }
+
+    /// Obtain the options negotiated with the association requester
+    /// besides the presentation contexts.
+    pub fn negotiated_options(&self) -> &NegotiatedOptions {
+        &self.negotiated_options
+    }
+
+    /// Send a PDU message to the other intervenient.
+    pub async fn send(&mut self, msg: &Pdu) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        self.buffer.clear();
+        write_pdu(&mut self.buffer, msg).context(SendSnafu)?;
+        if self.buffer.len() > self.requestor_max_pdu_length as usize {
+            return SendTooLongPduSnafu {
+                length: self.buffer.len(),
+            }
+            .fail();
+        }
+        self.socket
+            .write_all(&self.buffer)
+            .await
+            .context(WireSendSnafu)
+    }
+
+    /// Read a PDU message from the other intervenient.
+    pub async fn receive(&mut self) -> Result<Pdu> {
+        read_pdu_async(&mut self.socket, self.acceptor_max_pdu_length, true)
+            .await
+            .context(ReceiveSnafu)
+    }
+
+    /// Send the given data as one or more P-Data PDUs
+    /// in the given presentation context,
+    /// splitting it into separate PDUs if necessary.
+    pub async fn send_pdata(&mut self, presentation_context_id: u8, data: &[u8]) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        self.buffer.clear();
+        let mut writer = PDataWriter::new(
+            &mut self.buffer,
+            presentation_context_id,
+            self.requestor_max_pdu_length,
+        );
+        writer.write_all(data).context(WireSendSnafu)?;
+        writer.finish().context(WireSendSnafu)?;
+        self.socket
+            .write_all(&self.buffer)
+            .await
+            .context(WireSendSnafu)
+    }
+
+    /// Send a provider initiated abort message
+    /// and shut down the TCP connection,
+    /// terminating the association.
+    pub async fn abort(mut self) -> Result<()> {
+        use tokio::io::AsyncWriteExt;
+
+        let pdu = Pdu::AbortRQ {
+            source: AbortRQSource::ServiceProvider(
+                AbortRQServiceProviderReason::ReasonNotSpecifiedUnrecognizedPdu,
+            ),
+        };
+        let out = self.send(&pdu).await;
+        let _ = self.socket.shutdown().await;
+        out
+    }
+
+    /// Obtain access to the inner TCP stream
+    /// connected to the association requester.
+    ///
+    /// **Note:** reading and writing should be done with care
+    /// to avoid inconsistencies in the association state.
+    /// Do not call `send` and `receive` while not in a PDU boundary.
+    pub fn inner_stream(&mut self) -> &mut tokio::net::TcpStream {
+        &mut self.socket
+    }
+}
+
 /// Check that a transfer syntax repository
 /// supports the given transfer syntax,
 /// meaning that it can parse and decode DICOM data sets.
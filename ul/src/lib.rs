@@ -9,11 +9,22 @@
 //! - The [`pdu`](crate::pdu) module
 //! provides data structures representing _protocol data units_,
 //! which are passed around as part of the DICOM network communication support.
+//! Its [`codec`](crate::pdu::codec) submodule
+//! encodes and decodes PDUs independently of any I/O,
+//! for use with custom transports.
+//! - The [`address`](crate::address) module
+//! provides the [`AeAddr`](crate::address::AeAddr) type
+//! for full application entity addresses (`AETITLE@host:port`)
+//! and the validation rules for AE titles.
 //! - The [`association`](crate::association) module
 //! comprises abstractions for establishing and negotiating associations
 //! between application entities,
 //! via the upper layer protocol by TCP.
+//!
+//! Enable the `async` feature for asynchronous counterparts
+//! of the association types, built on tokio.
 
+pub mod address;
 pub mod association;
 pub mod pdu;
 
@@ -31,8 +42,11 @@ pub const IMPLEMENTATION_VERSION_NAME: &str = "DICOM-rs 0.3";
 
 // re-exports
 
+pub use address::AeAddr;
 pub use association::client::{ClientAssociation, ClientAssociationOptions};
 pub use association::server::{ServerAssociation, ServerAssociationOptions};
 pub use pdu::reader::read_pdu;
+#[cfg(feature = "async")]
+pub use pdu::reader::read_pdu_async;
 pub use pdu::writer::write_pdu;
 pub use pdu::Pdu;
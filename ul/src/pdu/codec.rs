@@ -0,0 +1,435 @@
+//! Sans-I/O PDU codec module
+//!
+//! This module exposes the PDU layer of the upper layer protocol
+//! without binding it to any particular transport.
+//! Bytes are pushed into a [`PduDecoder`] as they arrive,
+//! and complete PDUs are pulled out of it,
+//! whereas [`encode_pdu`] turns a PDU into the bytes to send.
+//! This makes it possible to test the protocol
+//! against previously captured byte streams,
+//! or to implement associations over custom transports.
+//!
+//! P-DATA-TF messages can be split into PDUs of a maximum length
+//! with [`fragment_pdata`],
+//! and put back together with a [`PDataAssembler`].
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_ul::pdu::Pdu;
+//! # use dicom_ul::pdu::codec::{encode_pdu, PduDecoder};
+//! # use dicom_ul::pdu::reader::DEFAULT_MAX_PDU;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let bytes = encode_pdu(&Pdu::ReleaseRQ)?;
+//!
+//! let mut decoder = PduDecoder::new(DEFAULT_MAX_PDU, true);
+//! // feed the bytes in two parts, as if they came from the network
+//! decoder.feed(&bytes[..4]);
+//! assert_eq!(decoder.decode()?, None);
+//! decoder.feed(&bytes[4..]);
+//! assert_eq!(decoder.decode()?, Some(Pdu::ReleaseRQ));
+//! # Ok(())
+//! # }
+//! ```
+use crate::pdu::reader::{self, check_pdu_length, read_pdu, MAXIMUM_PDU_SIZE, PDU_HEADER_SIZE};
+use crate::pdu::writer::{self, write_pdu};
+use crate::pdu::{PDataValue, PDataValueType, Pdu};
+use snafu::{ensure, Backtrace, ResultExt, Snafu};
+use std::io::Cursor;
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("Could not decode PDU"))]
+    Decode {
+        #[snafu(backtrace)]
+        source: reader::Error,
+    },
+
+    #[snafu(display("Could not encode PDU"))]
+    Encode {
+        source: writer::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "P-Data fragment for presentation context {} interleaved with an incomplete message from presentation context {}",
+        presentation_context_id,
+        expected_presentation_context_id
+    ))]
+    InterleavedPData {
+        presentation_context_id: u8,
+        expected_presentation_context_id: u8,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Encode a PDU into a new byte vector,
+/// ready to be sent through any transport.
+pub fn encode_pdu(pdu: &Pdu) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    write_pdu(&mut bytes, pdu).context(EncodeSnafu)?;
+    Ok(bytes)
+}
+
+/// An incremental PDU decoder.
+///
+/// Incoming bytes are accumulated through [`feed`](PduDecoder::feed),
+/// and each call to [`decode`](PduDecoder::decode)
+/// yields the next PDU once all of its bytes are available.
+/// No I/O is performed by the decoder itself.
+#[derive(Debug, Clone)]
+pub struct PduDecoder {
+    buffer: Vec<u8>,
+    max_pdu_length: u32,
+    strict: bool,
+}
+
+impl PduDecoder {
+    /// Create a new PDU decoder.
+    ///
+    /// `max_pdu_length` is the maximum value of the PDU-length property
+    /// accepted by the decoder.
+    /// If `strict` is false,
+    /// larger PDUs are still admitted up to the maximum length
+    /// allowed by the standard.
+    pub fn new(max_pdu_length: u32, strict: bool) -> Self {
+        PduDecoder {
+            buffer: Vec::new(),
+            max_pdu_length,
+            strict,
+        }
+    }
+
+    /// Append the given bytes to the decoder's internal buffer.
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Retrieve the number of bytes fed to the decoder
+    /// which were not yet consumed by a decoded PDU.
+    pub fn buffered_len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Decode the next PDU from the bytes fed so far.
+    ///
+    /// Returns `Ok(None)` if more bytes are needed
+    /// to decode a complete PDU.
+    /// The bytes of a decoded PDU are removed from the buffer,
+    /// so this method should be called repeatedly
+    /// until it no longer produces a PDU.
+    ///
+    /// An error is raised as soon as the PDU header
+    /// declares a length beyond the decoder's limits,
+    /// without waiting for the rest of the PDU.
+    pub fn decode(&mut self) -> Result<Option<Pdu>> {
+        let header_size = PDU_HEADER_SIZE as usize;
+        if self.buffer.len() < header_size {
+            return Ok(None);
+        }
+
+        let pdu_length = u32::from_be_bytes([
+            self.buffer[2],
+            self.buffer[3],
+            self.buffer[4],
+            self.buffer[5],
+        ]);
+        check_pdu_length(pdu_length, self.max_pdu_length, self.strict).context(DecodeSnafu)?;
+
+        let total_length = header_size + pdu_length as usize;
+        if self.buffer.len() < total_length {
+            return Ok(None);
+        }
+
+        let rest = self.buffer.split_off(total_length);
+        let bytes = std::mem::replace(&mut self.buffer, rest);
+        read_pdu(&mut Cursor::new(bytes), self.max_pdu_length, self.strict)
+            .map(Some)
+            .context(DecodeSnafu)
+    }
+}
+
+/// Split a message into P-DATA-TF PDUs
+/// which do not exceed the given maximum PDU length.
+///
+/// Each PDU contains a single presentation data value,
+/// the last of which is marked as such.
+/// At least one PDU is always produced,
+/// even if `data` is empty.
+///
+/// A maximum PDU length of 0 is treated as [`MAXIMUM_PDU_SIZE`].
+/// Lengths too small to fit any data
+/// still produce PDUs with one byte of data each.
+pub fn fragment_pdata(
+    presentation_context_id: u8,
+    value_type: PDataValueType,
+    data: &[u8],
+    max_pdu_length: u32,
+) -> Vec<Pdu> {
+    let max_data_length = calculate_max_data_len_single(max_pdu_length) as usize;
+
+    let mut chunks: Vec<&[u8]> = data.chunks(max_data_length).collect();
+    if chunks.is_empty() {
+        chunks.push(&[]);
+    }
+    let last = chunks.len() - 1;
+
+    chunks
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| Pdu::PData {
+            data: vec![PDataValue {
+                presentation_context_id,
+                value_type: value_type.clone(),
+                is_last: i == last,
+                data: chunk.to_vec(),
+            }],
+        })
+        .collect()
+}
+
+/// Determine the maximum length of actual PDV data
+/// when encapsulated in a PDU with the given length property.
+/// Does not account for the first 2 bytes (type + reserved).
+///
+/// A length of 0 is treated as [`MAXIMUM_PDU_SIZE`],
+/// and the outcome is never less than 1.
+#[inline]
+pub(crate) fn calculate_max_data_len_single(pdu_len: u32) -> u32 {
+    let pdu_len = if pdu_len == 0 {
+        MAXIMUM_PDU_SIZE
+    } else {
+        pdu_len
+    };
+    // data length: 4 bytes
+    // control header: 2 bytes
+    pdu_len.saturating_sub(4 + 2).max(1)
+}
+
+/// A complete message reassembled from P-Data value fragments.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PDataMessage {
+    /// the presentation context identifier
+    pub presentation_context_id: u8,
+    /// whether the message is a command or a data set
+    pub value_type: PDataValueType,
+    /// the full message data
+    pub data: Vec<u8>,
+}
+
+/// A reassembler of P-Data value fragments into full messages.
+///
+/// Fragments are pushed in order of arrival,
+/// and a message is produced once its last fragment is pushed.
+/// Commands and data sets are assembled separately,
+/// but fragments from different presentation contexts
+/// must not be interleaved.
+#[derive(Debug, Default, Clone)]
+pub struct PDataAssembler {
+    presentation_context_id: Option<u8>,
+    command: Vec<u8>,
+    data: Vec<u8>,
+}
+
+impl PDataAssembler {
+    /// Create a new P-Data value assembler.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Push a P-Data value fragment,
+    /// returning the full message if it was the last one.
+    pub fn push(&mut self, value: PDataValue) -> Result<Option<PDataMessage>> {
+        if let Some(expected) = self.presentation_context_id {
+            ensure!(
+                expected == value.presentation_context_id,
+                InterleavedPDataSnafu {
+                    presentation_context_id: value.presentation_context_id,
+                    expected_presentation_context_id: expected,
+                }
+            );
+        }
+
+        let buffer = match value.value_type {
+            PDataValueType::Command => &mut self.command,
+            PDataValueType::Data => &mut self.data,
+        };
+        buffer.extend_from_slice(&value.data);
+
+        if !value.is_last {
+            self.presentation_context_id = Some(value.presentation_context_id);
+            return Ok(None);
+        }
+
+        let data = std::mem::take(buffer);
+        if self.command.is_empty() && self.data.is_empty() {
+            self.presentation_context_id = None;
+        }
+        Ok(Some(PDataMessage {
+            presentation_context_id: value.presentation_context_id,
+            value_type: value.value_type,
+            data,
+        }))
+    }
+
+    /// Whether a message was started by the values pushed so far
+    /// but not yet completed.
+    pub fn has_partial_message(&self) -> bool {
+        self.presentation_context_id.is_some()
+    }
+
+    /// Push all P-Data values in a PDU,
+    /// returning the messages completed by them.
+    ///
+    /// PDUs other than P-DATA-TF produce no messages.
+    pub fn push_pdu(&mut self, pdu: Pdu) -> Result<Vec<PDataMessage>> {
+        let mut messages = Vec::new();
+        if let Pdu::PData { data } = pdu {
+            for value in data {
+                if let Some(message) = self.push(value)? {
+                    messages.push(message);
+                }
+            }
+        }
+        Ok(messages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdu::reader::{DEFAULT_MAX_PDU, MINIMUM_PDU_SIZE};
+    use crate::pdu::{
+        AbortRQServiceProviderReason, AbortRQSource, AssociationRJResult,
+        AssociationRJServiceUserReason, AssociationRJSource,
+    };
+
+    #[test]
+    fn decode_captured_stream() {
+        // A-ASSOCIATE-RJ, A-RELEASE-RQ, A-ABORT, back to back
+        let stream: &[u8] = &[
+            0x03, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x01, 0x01, 0x07, //
+            0x05, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x00, 0x00, //
+            0x07, 0x00, 0x00, 0x00, 0x00, 0x04, 0x00, 0x00, 0x02, 0x02,
+        ];
+
+        let mut decoder = PduDecoder::new(DEFAULT_MAX_PDU, true);
+        let mut pdus = Vec::new();
+        // feed one byte at a time
+        for byte in stream {
+            decoder.feed(std::slice::from_ref(byte));
+            while let Some(pdu) = decoder.decode().unwrap() {
+                pdus.push(pdu);
+            }
+        }
+
+        assert_eq!(decoder.buffered_len(), 0);
+        assert_eq!(
+            pdus,
+            vec![
+                Pdu::AssociationRJ {
+                    result: AssociationRJResult::Permanent,
+                    source: AssociationRJSource::ServiceUser(
+                        AssociationRJServiceUserReason::CalledAETitleNotRecognized
+                    ),
+                },
+                Pdu::ReleaseRQ,
+                Pdu::AbortRQ {
+                    source: AbortRQSource::ServiceProvider(
+                        AbortRQServiceProviderReason::UnexpectedPdu
+                    ),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn decode_rejects_large_pdu_early() {
+        let mut decoder = PduDecoder::new(MINIMUM_PDU_SIZE, true);
+        decoder.feed(&[0x04, 0x00, 0x00, 0x01, 0x00, 0x00]);
+        assert!(matches!(
+            decoder.decode(),
+            Err(Error::Decode {
+                source: reader::Error::PduTooLarge { .. }
+            })
+        ));
+    }
+
+    #[test]
+    fn fragment_and_reassemble() {
+        let data: Vec<u8> = (0..10_000).map(|i| i as u8).collect();
+        let pdus = fragment_pdata(3, PDataValueType::Data, &data, MINIMUM_PDU_SIZE);
+        assert_eq!(pdus.len(), 3);
+
+        let mut decoder = PduDecoder::new(MINIMUM_PDU_SIZE, true);
+        for pdu in &pdus {
+            let bytes = encode_pdu(pdu).unwrap();
+            assert!(bytes.len() <= (MINIMUM_PDU_SIZE + PDU_HEADER_SIZE) as usize);
+            decoder.feed(&bytes);
+        }
+
+        let mut assembler = PDataAssembler::new();
+        let mut messages = Vec::new();
+        while let Some(pdu) = decoder.decode().unwrap() {
+            messages.extend(assembler.push_pdu(pdu).unwrap());
+        }
+
+        assert_eq!(
+            messages,
+            vec![PDataMessage {
+                presentation_context_id: 3,
+                value_type: PDataValueType::Data,
+                data,
+            }]
+        );
+    }
+
+    #[test]
+    fn fragment_empty_message() {
+        let pdus = fragment_pdata(1, PDataValueType::Command, &[], DEFAULT_MAX_PDU);
+        assert_eq!(
+            pdus,
+            vec![Pdu::PData {
+                data: vec![PDataValue {
+                    presentation_context_id: 1,
+                    value_type: PDataValueType::Command,
+                    is_last: true,
+                    data: vec![],
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn fragment_with_degenerate_pdu_lengths() {
+        let data = [1, 2, 3];
+        // 0 stands for the maximum PDU size
+        assert_eq!(fragment_pdata(1, PDataValueType::Data, &data, 0).len(), 1);
+        // no room for data, one byte per PDU
+        for max_pdu_length in [1, 6, 7] {
+            let pdus = fragment_pdata(1, PDataValueType::Data, &data, max_pdu_length);
+            assert_eq!(pdus.len(), 3);
+        }
+        assert_eq!(fragment_pdata(1, PDataValueType::Data, &data, 8).len(), 2);
+    }
+
+    #[test]
+    fn reject_interleaved_fragments() {
+        let mut assembler = PDataAssembler::new();
+        let fragment = |presentation_context_id| PDataValue {
+            presentation_context_id,
+            value_type: PDataValueType::Data,
+            is_last: false,
+            data: vec![0; 4],
+        };
+        assert_eq!(assembler.push(fragment(1)).unwrap(), None);
+        assert!(assembler.has_partial_message());
+        assert!(matches!(
+            assembler.push(fragment(3)),
+            Err(Error::InterleavedPData { .. })
+        ));
+    }
+}
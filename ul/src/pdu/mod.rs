@@ -4,6 +4,9 @@
 //! protocol data units (PDUs) according to
 //! the standard message exchange mechanisms,
 //! as well as readers and writers of PDUs from arbitrary data sources.
+//! The [`codec`] module provides the same functionality
+//! without performing any I/O.
+pub mod codec;
 pub mod reader;
 pub mod writer;
 
@@ -237,6 +240,18 @@ pub enum UserVariableItem {
     MaxLength(u32),
     ImplementationClassUID(String),
     ImplementationVersionName(String),
+    /// SCP/SCU role selection for a SOP class:
+    /// the SOP class UID, whether the SCU role is proposed or accepted,
+    /// and whether the SCP role is proposed or accepted.
+    ScpScuRoleSelection(String, bool, bool),
+    /// Asynchronous operations window:
+    /// the maximum number of outstanding operations invoked
+    /// and the maximum number of outstanding operations performed,
+    /// where 0 means unlimited.
+    AsyncOperationsWindow(u16, u16),
+    /// SOP class extended negotiation:
+    /// the SOP class UID and the service-class-application-information.
+    SopClassExtendedNegotiation(String, Vec<u8>),
 }
 
 #[derive(Clone, Eq, PartialEq, PartialOrd, Hash, Debug)]
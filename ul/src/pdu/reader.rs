@@ -106,6 +106,67 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Read a PDU from an asynchronous source.
+///
+/// The whole PDU is first read into memory,
+/// and then decoded in the same way as [`read_pdu`].
+#[cfg(feature = "async")]
+pub async fn read_pdu_async<R>(reader: &mut R, max_pdu_length: u32, strict: bool) -> Result<Pdu>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    ensure!(
+        (MINIMUM_PDU_SIZE..=MAXIMUM_PDU_SIZE).contains(&max_pdu_length),
+        InvalidMaxPduSnafu { max_pdu_length }
+    );
+
+    // see `read_pdu` on why the first 2 bytes are read separately
+    let mut bytes = vec![0; PDU_HEADER_SIZE as usize];
+    if let Err(e) = reader.read_exact(&mut bytes[..2]).await {
+        ensure!(e.kind() != ErrorKind::UnexpectedEof, NoPduAvailableSnafu);
+        return Err(e).context(ReadPduFieldSnafu { field: "type" });
+    }
+    reader
+        .read_exact(&mut bytes[2..])
+        .await
+        .context(ReadPduFieldSnafu { field: "length" })?;
+    let pdu_length = u32::from_be_bytes([bytes[2], bytes[3], bytes[4], bytes[5]]);
+
+    // check the length before reading the rest of the PDU into memory,
+    // warnings about non-strict lengths are left to `read_pdu`
+    check_pdu_length(pdu_length, max_pdu_length, strict)?;
+
+    bytes.resize(PDU_HEADER_SIZE as usize + pdu_length as usize, 0);
+    reader
+        .read_exact(&mut bytes[PDU_HEADER_SIZE as usize..])
+        .await
+        .context(ReadPduSnafu)?;
+    read_pdu(&mut Cursor::new(bytes), max_pdu_length, strict)
+}
+
+/// Check whether a PDU with the given length property
+/// may be read under the given constraints.
+///
+/// Lengths beyond `max_pdu_length` are still admitted in non-strict mode,
+/// as long as they do not exceed [`MAXIMUM_PDU_SIZE`].
+pub(crate) fn check_pdu_length(pdu_length: u32, max_pdu_length: u32, strict: bool) -> Result<()> {
+    let limit = if strict {
+        max_pdu_length
+    } else {
+        MAXIMUM_PDU_SIZE
+    };
+    ensure!(
+        pdu_length <= limit,
+        PduTooLargeSnafu {
+            pdu_length,
+            max_pdu_length: limit
+        }
+    );
+    Ok(())
+}
+
 pub fn read_pdu<R>(reader: &mut R, max_pdu_length: u32, strict: bool) -> Result<Pdu>
 where
     R: Read,
@@ -900,6 +961,98 @@ where
                             implementation_version_name,
                         ));
                     }
+                    0x54 => {
+                        // SCP/SCU Role Selection Sub-Item Structure
+
+                        // 5-6 - UID-length
+                        let uid_length =
+                            cursor.read_u16::<BigEndian>().context(ReadPduFieldSnafu {
+                                field: "UID-length",
+                            })?;
+
+                        // 7-xxx - SOP-class-uid - This variable field shall contain the
+                        // SOP Class UID to which the role selection applies.
+                        let sop_class_uid = codec
+                            .decode(&read_n(&mut cursor, uid_length as usize).context(
+                                ReadPduFieldSnafu {
+                                    field: "SOP-class-uid",
+                                },
+                            )?)
+                            .context(DecodeTextSnafu {
+                                field: "SOP-class-uid",
+                            })?
+                            .trim()
+                            .to_string();
+
+                        // xxx - SCU-role - 0 for non support of the SCU role, 1 for support
+                        let scu_role = cursor
+                            .read_u8()
+                            .context(ReadPduFieldSnafu { field: "SCU-role" })?;
+
+                        // xxx - SCP-role - 0 for non support of the SCP role, 1 for support
+                        let scp_role = cursor
+                            .read_u8()
+                            .context(ReadPduFieldSnafu { field: "SCP-role" })?;
+
+                        user_variables.push(UserVariableItem::ScpScuRoleSelection(
+                            sop_class_uid,
+                            scu_role != 0,
+                            scp_role != 0,
+                        ));
+                    }
+                    0x53 => {
+                        // Asynchronous Operations Window Sub-Item Structure
+
+                        // 5-6 - Maximum-number-operations-invoked
+                        let invoked =
+                            cursor.read_u16::<BigEndian>().context(ReadPduFieldSnafu {
+                                field: "Maximum-number-operations-invoked",
+                            })?;
+
+                        // 7-8 - Maximum-number-operations-performed
+                        let performed =
+                            cursor.read_u16::<BigEndian>().context(ReadPduFieldSnafu {
+                                field: "Maximum-number-operations-performed",
+                            })?;
+
+                        user_variables
+                            .push(UserVariableItem::AsyncOperationsWindow(invoked, performed));
+                    }
+                    0x56 => {
+                        // SOP Class Extended Negotiation Sub-Item Structure
+
+                        // 5-6 - SOP-class-uid-length
+                        let uid_length =
+                            cursor.read_u16::<BigEndian>().context(ReadPduFieldSnafu {
+                                field: "SOP-class-uid-length",
+                            })?;
+
+                        // 7-xxx - SOP-class-uid
+                        let sop_class_uid = codec
+                            .decode(&read_n(&mut cursor, uid_length as usize).context(
+                                ReadPduFieldSnafu {
+                                    field: "SOP-class-uid",
+                                },
+                            )?)
+                            .context(DecodeTextSnafu {
+                                field: "SOP-class-uid",
+                            })?
+                            .trim()
+                            .to_string();
+
+                        // xxx-xxx - Service-class-application-information -
+                        // the rest of the sub-item, as specified by the service class
+                        let info_length =
+                            (item_length as usize).saturating_sub(2 + uid_length as usize);
+                        let info = read_n(&mut cursor, info_length).context(ReadPduFieldSnafu {
+                            field: "Service-class-application-information",
+                        })?;
+
+                        user_variables.push(UserVariableItem::SopClassExtendedNegotiation(
+                            sop_class_uid,
+                            info,
+                        ));
+                    }
                     _ => {
                         user_variables.push(UserVariableItem::Unknown(
                             item_type,
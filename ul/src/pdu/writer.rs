@@ -982,6 +982,121 @@ fn write_pdu_variable_user_variables(
                         name: "Implementation-class-uid",
                     })?;
                 }
+                UserVariableItem::ScpScuRoleSelection(sop_class_uid, scu_role, scp_role) => {
+                    // 1 - Item-type - 54H
+                    writer
+                        .write_u8(0x54)
+                        .context(WriteFieldSnafu { field: "Item-type" })?;
+
+                    // 2 - Reserved - This reserved field shall be sent with a value 00H but not
+                    // tested to this value when received.
+                    writer
+                        .write_u8(0x00)
+                        .context(WriteReservedSnafu { bytes: 1_u32 })?;
+
+                    write_chunk_u16(writer, |writer| {
+                        // 5-6 - UID-length, followed by
+                        // 7-xxx - SOP-class-uid - This variable field shall contain the
+                        // SOP Class UID to which the role selection applies.
+                        write_chunk_u16(writer, |writer| {
+                            writer
+                                .write_all(&codec.encode(sop_class_uid).context(
+                                    EncodeFieldSnafu {
+                                        field: "SOP-class-uid",
+                                    },
+                                )?)
+                                .context(WriteFieldSnafu {
+                                    field: "SOP-class-uid",
+                                })
+                        })
+                        .context(WriteChunkSnafu {
+                            name: "SOP-class-uid",
+                        })?;
+
+                        // xxx - SCU-role
+                        writer
+                            .write_u8(*scu_role as u8)
+                            .context(WriteFieldSnafu { field: "SCU-role" })?;
+
+                        // xxx - SCP-role
+                        writer
+                            .write_u8(*scp_role as u8)
+                            .context(WriteFieldSnafu { field: "SCP-role" })
+                    })
+                    .context(WriteChunkSnafu {
+                        name: "SCP/SCU Role Selection",
+                    })?;
+                }
+                UserVariableItem::AsyncOperationsWindow(invoked, performed) => {
+                    // 1 - Item-type - 53H
+                    writer
+                        .write_u8(0x53)
+                        .context(WriteFieldSnafu { field: "Item-type" })?;
+
+                    // 2 - Reserved - This reserved field shall be sent with a value 00H but not
+                    // tested to this value when received.
+                    writer
+                        .write_u8(0x00)
+                        .context(WriteReservedSnafu { bytes: 1_u32 })?;
+
+                    write_chunk_u16(writer, |writer| {
+                        // 5-6 - Maximum-number-operations-invoked
+                        writer
+                            .write_u16::<BigEndian>(*invoked)
+                            .context(WriteFieldSnafu {
+                                field: "Maximum-number-operations-invoked",
+                            })?;
+
+                        // 7-8 - Maximum-number-operations-performed
+                        writer
+                            .write_u16::<BigEndian>(*performed)
+                            .context(WriteFieldSnafu {
+                                field: "Maximum-number-operations-performed",
+                            })
+                    })
+                    .context(WriteChunkSnafu {
+                        name: "Asynchronous Operations Window",
+                    })?;
+                }
+                UserVariableItem::SopClassExtendedNegotiation(sop_class_uid, info) => {
+                    // 1 - Item-type - 56H
+                    writer
+                        .write_u8(0x56)
+                        .context(WriteFieldSnafu { field: "Item-type" })?;
+
+                    // 2 - Reserved - This reserved field shall be sent with a value 00H but not
+                    // tested to this value when received.
+                    writer
+                        .write_u8(0x00)
+                        .context(WriteReservedSnafu { bytes: 1_u32 })?;
+
+                    write_chunk_u16(writer, |writer| {
+                        // 5-6 - SOP-class-uid-length, followed by
+                        // 7-xxx - SOP-class-uid
+                        write_chunk_u16(writer, |writer| {
+                            writer
+                                .write_all(&codec.encode(sop_class_uid).context(
+                                    EncodeFieldSnafu {
+                                        field: "SOP-class-uid",
+                                    },
+                                )?)
+                                .context(WriteFieldSnafu {
+                                    field: "SOP-class-uid",
+                                })
+                        })
+                        .context(WriteChunkSnafu {
+                            name: "SOP-class-uid",
+                        })?;
+
+                        // xxx-xxx - Service-class-application-information
+                        writer.write_all(info).context(WriteFieldSnafu {
+                            field: "Service-class-application-information",
+                        })
+                    })
+                    .context(WriteChunkSnafu {
+                        name: "SOP Class Extended Negotiation",
+                    })?;
+                }
                 UserVariableItem::Unknown(item_type, data) => {
                     writer
                         .write_u8(*item_type)
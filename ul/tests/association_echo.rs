@@ -1,4 +1,5 @@
 use dicom_ul::{
+    address::AeAddr,
     association::client::ClientAssociationOptions,
     pdu::{Pdu, PresentationContextResult, PresentationContextResultReason},
 };
@@ -84,3 +85,62 @@ fn scu_scp_association_test() {
         .expect("SCP panicked")
         .expect("Error at the SCP");
 }
+
+/// Negotiate an association with an application entity address,
+/// taking the called AE title from the address.
+#[test]
+fn scu_scp_association_with_ae_addr() {
+    let (scp_handle, scp_addr) = spawn_scp().unwrap();
+
+    let ae_addr = AeAddr::new(Some(SCP_AE_TITLE), &scp_addr.to_string()).unwrap();
+    let association = ClientAssociationOptions::new()
+        .calling_ae_title(SCU_AE_TITLE)
+        .with_presentation_context(VERIFICATION_SOP_CLASS, vec![IMPLICIT_VR_LE, EXPLICIT_VR_LE])
+        .with_presentation_context(
+            DIGITAL_MG_STORAGE_SOP_CLASS,
+            vec![IMPLICIT_VR_LE, EXPLICIT_VR_LE, JPEG_BASELINE],
+        )
+        .establish_with(ae_addr)
+        .unwrap();
+
+    association
+        .release()
+        .expect("did not have a peaceful release");
+
+    scp_handle
+        .join()
+        .expect("SCP panicked")
+        .expect("Error at the SCP");
+}
+
+/// Negotiate an association with an application entity address in text form.
+#[test]
+fn scu_scp_association_with_ae_addr_str() {
+    let (scp_handle, scp_addr) = spawn_scp().unwrap();
+
+    let association = ClientAssociationOptions::new()
+        .calling_ae_title(SCU_AE_TITLE)
+        .called_ae_title("ANOTHER-SCP")
+        .with_presentation_context(VERIFICATION_SOP_CLASS, vec![IMPLICIT_VR_LE, EXPLICIT_VR_LE])
+        .with_presentation_context(
+            DIGITAL_MG_STORAGE_SOP_CLASS,
+            vec![IMPLICIT_VR_LE, EXPLICIT_VR_LE, JPEG_BASELINE],
+        )
+        .establish_with_str(&format!("{}@{}", SCP_AE_TITLE, scp_addr))
+        .unwrap();
+
+    association
+        .release()
+        .expect("did not have a peaceful release");
+
+    scp_handle
+        .join()
+        .expect("SCP panicked")
+        .expect("Error at the SCP");
+
+    // malformed addresses are rejected before connecting
+    assert!(ClientAssociationOptions::new()
+        .with_abstract_syntax(VERIFICATION_SOP_CLASS)
+        .establish_with_str("ECHO-SCP@")
+        .is_err());
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "async")]
+use dicom_ul::{
+    association::client::ClientAssociationOptions,
+    pdu::{PDataValueType, Pdu, PresentationContextResult, PresentationContextResultReason},
+};
+use std::net::SocketAddr;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use dicom_ul::association::server::ServerAssociationOptions;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+static SCU_AE_TITLE: &str = "ECHO-SCU";
+static SCP_AE_TITLE: &str = "ECHO-SCP";
+
+static IMPLICIT_VR_LE: &str = "1.2.840.10008.1.2";
+static EXPLICIT_VR_LE: &str = "1.2.840.10008.1.2.1";
+static VERIFICATION_SOP_CLASS: &str = "1.2.840.10008.1.1";
+
+async fn spawn_scp() -> Result<(JoinHandle<Result<Vec<u8>>>, SocketAddr)> {
+    let listener = TcpListener::bind("localhost:0").await?;
+    let addr = listener.local_addr()?;
+    let scp = ServerAssociationOptions::new()
+        .accept_called_ae_title()
+        .ae_title(SCP_AE_TITLE)
+        .with_abstract_syntax(VERIFICATION_SOP_CLASS);
+
+    let h = tokio::spawn(async move {
+        let (stream, _addr) = listener.accept().await?;
+        let mut association = scp.establish_async(stream).await?;
+
+        assert_eq!(
+            association.presentation_contexts(),
+            &[PresentationContextResult {
+                id: 1,
+                reason: PresentationContextResultReason::Acceptance,
+                transfer_syntax: IMPLICIT_VR_LE.to_string(),
+            }],
+        );
+
+        // collect P-Data until the last fragment
+        let mut data = Vec::new();
+        let mut done = false;
+        while !done {
+            match association.receive().await? {
+                Pdu::PData { data: values } => {
+                    for value in values {
+                        assert_eq!(value.value_type, PDataValueType::Data);
+                        data.extend(value.data);
+                        done = value.is_last;
+                    }
+                }
+                pdu => panic!("unexpected PDU {:?}", pdu),
+            }
+        }
+
+        // handle one release request
+        let pdu = association.receive().await?;
+        assert_eq!(pdu, Pdu::ReleaseRQ);
+        association.send(&Pdu::ReleaseRP).await?;
+
+        Ok(data)
+    });
+    Ok((h, addr))
+}
+
+/// Run an SCP and an SCU concurrently as tasks,
+/// negotiate an association,
+/// send data spanning multiple PDUs,
+/// and release it.
+#[tokio::test]
+async fn scu_scp_association_async_test() {
+    let (scp_handle, scp_addr) = spawn_scp().await.unwrap();
+
+    let mut association = ClientAssociationOptions::new()
+        .calling_ae_title(SCU_AE_TITLE)
+        .called_ae_title(SCP_AE_TITLE)
+        .with_presentation_context(VERIFICATION_SOP_CLASS, vec![IMPLICIT_VR_LE, EXPLICIT_VR_LE])
+        .establish_async(scp_addr)
+        .await
+        .unwrap();
+
+    let data: Vec<u8> = (0..20_000).map(|x: u32| x as u8).collect();
+    association.send_pdata(1, &data).await.unwrap();
+
+    association
+        .release()
+        .await
+        .expect("did not have a peaceful release");
+
+    let received = scp_handle
+        .await
+        .expect("SCP panicked")
+        .expect("Error at the SCP");
+    assert_eq!(received, data);
+}
@@ -0,0 +1,114 @@
+use dicom_ul::association::client::ClientAssociationOptions;
+use dicom_ul::association::server::ServerAssociationOptions;
+use dicom_ul::association::{NegotiatedOptions, RoleSelection};
+use dicom_ul::pdu::Pdu;
+use std::net::TcpListener;
+use std::thread::spawn;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync + 'static>>;
+
+static STUDY_ROOT_FIND: &str = "1.2.840.10008.5.1.4.1.2.2.1";
+static STUDY_ROOT_GET: &str = "1.2.840.10008.5.1.4.1.2.2.3";
+static CT_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.2";
+static MR_IMAGE_STORAGE: &str = "1.2.840.10008.5.1.4.1.1.4";
+
+/// Negotiate the asynchronous operations window,
+/// role selection and extended negotiation,
+/// checking that both ends agree on the outcome.
+#[test]
+fn scu_scp_extended_negotiation() {
+    let listener = TcpListener::bind("localhost:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let scp = ServerAssociationOptions::new()
+        .with_abstract_syntax(STUDY_ROOT_FIND)
+        .with_abstract_syntax(STUDY_ROOT_GET)
+        .with_abstract_syntax(CT_IMAGE_STORAGE)
+        .with_abstract_syntax(MR_IMAGE_STORAGE)
+        // only CT images may be received by the requester
+        .with_role_selection(MR_IMAGE_STORAGE, true, false)
+        .async_operations_window(2, 1)
+        // relational queries are not supported
+        .with_extended_negotiation(STUDY_ROOT_FIND, vec![0]);
+
+    let scp_handle = spawn(move || -> Result<NegotiatedOptions> {
+        let (stream, _addr) = listener.accept()?;
+        let mut association = scp.establish(stream)?;
+        let options = association.negotiated_options().clone();
+
+        let pdu = association.receive()?;
+        assert_eq!(pdu, Pdu::ReleaseRQ);
+        association.send(&Pdu::ReleaseRP)?;
+        Ok(options)
+    });
+
+    let association = ClientAssociationOptions::new()
+        .with_abstract_syntax(STUDY_ROOT_FIND)
+        .with_abstract_syntax(STUDY_ROOT_GET)
+        .with_abstract_syntax(CT_IMAGE_STORAGE)
+        .with_abstract_syntax(MR_IMAGE_STORAGE)
+        .with_role_selection(CT_IMAGE_STORAGE, false, true)
+        .with_role_selection(MR_IMAGE_STORAGE, false, true)
+        .async_operations_window(8, 0)
+        .with_extended_negotiation(STUDY_ROOT_FIND, vec![1])
+        .with_extended_negotiation(STUDY_ROOT_GET, vec![1])
+        .establish(addr)
+        .unwrap();
+    let options = association.negotiated_options().clone();
+    association.release().unwrap();
+    let scp_options = scp_handle.join().unwrap().unwrap();
+
+    assert_eq!(options, scp_options);
+    assert_eq!(options.max_operations_invoked(), 2);
+    assert_eq!(options.max_operations_performed(), 1);
+    assert_eq!(
+        options.role_selections,
+        vec![
+            RoleSelection {
+                sop_class_uid: CT_IMAGE_STORAGE.to_string(),
+                scu_role: false,
+                scp_role: true,
+            },
+            RoleSelection {
+                sop_class_uid: MR_IMAGE_STORAGE.to_string(),
+                scu_role: false,
+                scp_role: false,
+            },
+        ]
+    );
+    assert_eq!(
+        options.extended_negotiation(STUDY_ROOT_FIND),
+        Some(&[0][..])
+    );
+    assert_eq!(options.extended_negotiation(STUDY_ROOT_GET), None);
+}
+
+/// Without any of the options, the defaults apply.
+#[test]
+fn scu_scp_default_negotiation() {
+    let listener = TcpListener::bind("localhost:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let scp = ServerAssociationOptions::new().with_abstract_syntax(STUDY_ROOT_FIND);
+
+    let scp_handle = spawn(move || -> Result<()> {
+        let (stream, _addr) = listener.accept()?;
+        let mut association = scp.establish(stream)?;
+        let pdu = association.receive()?;
+        assert_eq!(pdu, Pdu::ReleaseRQ);
+        association.send(&Pdu::ReleaseRP)?;
+        Ok(())
+    });
+
+    // a window proposed to a node which does not support it is dropped
+    let association = ClientAssociationOptions::new()
+        .with_abstract_syntax(STUDY_ROOT_FIND)
+        .async_operations_window(8, 8)
+        .establish(addr)
+        .unwrap();
+    assert_eq!(
+        association.negotiated_options(),
+        &NegotiatedOptions::default()
+    );
+    assert_eq!(association.negotiated_options().max_operations_invoked(), 1);
+    association.release().unwrap();
+    scp_handle.join().unwrap().unwrap();
+}
@@ -1,7 +1,8 @@
 use dicom_ul::pdu::reader::{read_pdu, DEFAULT_MAX_PDU};
 use dicom_ul::pdu::writer::write_pdu;
 use dicom_ul::pdu::{
-    PDataValue, PDataValueType, Pdu, PresentationContextProposed, UserVariableItem,
+    PDataValue, PDataValueType, Pdu, PresentationContextProposed, PresentationContextResult,
+    PresentationContextResultReason, UserVariableItem,
 };
 use matches::matches;
 use std::io::Cursor;
@@ -107,3 +108,65 @@ fn can_read_write_pdata() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn can_read_write_role_selection() -> Result<(), Box<dyn std::error::Error>> {
+    let association_rq = Pdu::AssociationRQ {
+        protocol_version: 1,
+        calling_ae_title: "calling ae".to_string(),
+        called_ae_title: "called ae".to_string(),
+        application_context_name: "1.2.840.10008.3.1.1.1".to_string(),
+        presentation_contexts: vec![PresentationContextProposed {
+            id: 1,
+            abstract_syntax: "1.2.840.10008.5.1.4.1.1.2".to_string(),
+            transfer_syntaxes: vec!["1.2.840.10008.1.2".to_string()],
+        }],
+        user_variables: vec![
+            UserVariableItem::MaxLength(16384),
+            UserVariableItem::ScpScuRoleSelection(
+                "1.2.840.10008.5.1.4.1.1.2".to_string(),
+                false,
+                true,
+            ),
+        ],
+    };
+
+    let mut bytes = Vec::new();
+    write_pdu(&mut bytes, &association_rq)?;
+
+    let result = read_pdu(&mut Cursor::new(&bytes), DEFAULT_MAX_PDU, true)?;
+    assert_eq!(result, association_rq);
+
+    Ok(())
+}
+
+#[test]
+fn can_read_write_extended_negotiation() -> Result<(), Box<dyn std::error::Error>> {
+    let association_ac = Pdu::AssociationAC {
+        protocol_version: 1,
+        calling_ae_title: "calling ae".to_string(),
+        called_ae_title: "called ae".to_string(),
+        application_context_name: "1.2.840.10008.3.1.1.1".to_string(),
+        presentation_contexts: vec![PresentationContextResult {
+            id: 1,
+            reason: PresentationContextResultReason::Acceptance,
+            transfer_syntax: "1.2.840.10008.1.2".to_string(),
+        }],
+        user_variables: vec![
+            UserVariableItem::MaxLength(16384),
+            UserVariableItem::AsyncOperationsWindow(4, 0),
+            UserVariableItem::SopClassExtendedNegotiation(
+                "1.2.840.10008.5.1.4.1.2.2.1".to_string(),
+                vec![1, 1, 0],
+            ),
+        ],
+    };
+
+    let mut bytes = Vec::new();
+    write_pdu(&mut bytes, &association_ac)?;
+
+    let result = read_pdu(&mut Cursor::new(&bytes), DEFAULT_MAX_PDU, true)?;
+    assert_eq!(result, association_ac);
+
+    Ok(())
+}
@@ -0,0 +1,229 @@
+//! DICOMweb client module.
+//!
+//! This module provides a blocking HTTP client for DICOMweb services,
+//! built on [`ureq`],
+//! supporting retrieval (WADO-RS), storage (STOW-RS), and search (QIDO-RS).
+//! It is only available with the `client` feature.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use dicom_web::client::WebClient;
+//! # use dicom_web::wado::Target;
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = WebClient::new("https://pacs.example.com/dicom-web");
+//!
+//! let target = Target::series("1.2.3", "1.2.3.4");
+//! for obj in client.retrieve(&target)? {
+//!     println!("{}", obj.meta().media_storage_sop_instance_uid);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+use std::io::Read;
+
+use dicom_object::{DefaultDicomObject, InMemDicomObject};
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::qido::{self, Query};
+use crate::stow::{self, StoreResponse, DICOM_JSON_MEDIA_TYPE};
+use crate::wado::{self, Frame, InstanceData, Target, ACCEPT_DICOM, ACCEPT_FRAMES};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("HTTP request to {} failed", url))]
+    Request {
+        url: String,
+        source: Box<ureq::Error>,
+        backtrace: Backtrace,
+    },
+
+    /// failed to read response body
+    ReadBody {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("missing content type in response from {}", url))]
+    MissingContentType { url: String, backtrace: Backtrace },
+
+    #[snafu(display("target {} is not an instance", target))]
+    NotAnInstance {
+        target: String,
+        backtrace: Backtrace,
+    },
+
+    /// failed to read WADO-RS response
+    Wado {
+        #[snafu(backtrace)]
+        source: wado::Error,
+    },
+
+    /// failed to encode or read STOW-RS message
+    Stow {
+        #[snafu(backtrace)]
+        source: stow::Error,
+    },
+
+    /// failed to read QIDO-RS response
+    Qido {
+        #[snafu(backtrace)]
+        source: qido::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A response body with its content type.
+struct Body {
+    content_type: Option<String>,
+    data: Vec<u8>,
+}
+
+impl Body {
+    /// Retrieve the content type of a response from the given URL,
+    /// which must be present.
+    fn content_type(&self, url: &str) -> Result<&str> {
+        self.content_type
+            .as_deref()
+            .context(MissingContentTypeSnafu { url })
+    }
+}
+
+/// A blocking client for a DICOMweb service.
+#[derive(Debug, Clone)]
+pub struct WebClient {
+    base_url: String,
+    agent: ureq::Agent,
+    authorization: Option<String>,
+}
+
+impl WebClient {
+    /// Create a new client for the service at the given base URL,
+    /// such as `https://pacs.example.com/dicom-web`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        WebClient {
+            base_url: base_url.into(),
+            agent: ureq::Agent::new(),
+            authorization: None,
+        }
+    }
+
+    /// Use the given HTTP agent,
+    /// so as to configure timeouts, proxies, or TLS.
+    pub fn with_agent(mut self, agent: ureq::Agent) -> Self {
+        self.agent = agent;
+        self
+    }
+
+    /// Authenticate all requests with the given bearer token.
+    pub fn with_bearer_token(mut self, token: impl AsRef<str>) -> Self {
+        self.authorization = Some(format!("Bearer {}", token.as_ref()));
+        self
+    }
+
+    /// Retrieve all instances of the target as in-memory DICOM objects.
+    pub fn retrieve(&self, target: &Target) -> Result<Vec<DefaultDicomObject>> {
+        let url = self.url(&target.path());
+        let body = self.get(&url, ACCEPT_DICOM)?;
+        wado::read_instances(body.content_type(&url)?, &body.data).context(WadoSnafu)
+    }
+
+    /// Retrieve all instances of the target in their encoded form,
+    /// so that they can be read as a stream of data set tokens.
+    pub fn retrieve_data(&self, target: &Target) -> Result<Vec<InstanceData>> {
+        let url = self.url(&target.path());
+        let body = self.get(&url, ACCEPT_DICOM)?;
+        wado::read_instance_data(body.content_type(&url)?, &body.data).context(WadoSnafu)
+    }
+
+    /// Retrieve the given frames of an instance,
+    /// starting at frame number 1.
+    pub fn retrieve_frames(&self, target: &Target, frame_numbers: &[u32]) -> Result<Vec<Frame>> {
+        let path = target
+            .frames_path(frame_numbers)
+            .with_context(|| NotAnInstanceSnafu {
+                target: target.path(),
+            })?;
+        let url = self.url(&path);
+        let body = self.get(&url, ACCEPT_FRAMES)?;
+        wado::read_frames(body.content_type(&url)?, &body.data).context(WadoSnafu)
+    }
+
+    /// Retrieve a rendered representation of the target
+    /// in the given media type, such as `image/jpeg`.
+    pub fn retrieve_rendered(&self, target: &Target, media_type: &str) -> Result<Vec<u8>> {
+        Ok(self
+            .get(&self.url(&target.rendered_path()), media_type)?
+            .data)
+    }
+
+    /// Store the given DICOM objects through STOW-RS.
+    ///
+    /// If a study is given,
+    /// the service will reject instances of other studies.
+    /// Instances which could not be stored
+    /// are listed in the response rather than reported as an error.
+    pub fn store<'a, I>(
+        &self,
+        study_instance_uid: Option<&str>,
+        objects: I,
+    ) -> Result<StoreResponse>
+    where
+        I: IntoIterator<Item = &'a DefaultDicomObject>,
+    {
+        let request = stow::encode_instances(objects).context(StowSnafu)?;
+        let url = self.url(&stow::store_path(study_instance_uid));
+        let response = self
+            .request("POST", &url, DICOM_JSON_MEDIA_TYPE)
+            .set("Content-Type", &request.content_type)
+            .send_bytes(&request.body);
+        // partial failures come with status 202 or 409,
+        // both of which include a response body
+        let response = match response {
+            Err(ureq::Error::Status(409, response)) => Ok(response),
+            response => response,
+        };
+        let body = read_body(&url, response)?;
+        stow::read_response(&body.data).context(StowSnafu)
+    }
+
+    /// Search for studies, series, or instances through QIDO-RS.
+    pub fn search(&self, query: &Query) -> Result<Vec<InMemDicomObject>> {
+        let body = self.get(&self.url(&query.path()), qido::ACCEPT_JSON)?;
+        qido::read_response(&body.data).context(QidoSnafu)
+    }
+
+    /// Build the full URL to the given path,
+    /// relative to the base URL.
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.base_url.trim_end_matches('/'), path)
+    }
+
+    /// Prepare a request with the given method to the given URL.
+    fn request(&self, method: &str, url: &str, accept: &str) -> ureq::Request {
+        let request = self.agent.request(method, url).set("Accept", accept);
+        match &self.authorization {
+            Some(authorization) => request.set("Authorization", authorization),
+            None => request,
+        }
+    }
+
+    /// Perform a GET request to the given URL.
+    fn get(&self, url: &str, accept: &str) -> Result<Body> {
+        read_body(url, self.request("GET", url, accept).call())
+    }
+}
+
+/// Read the body of a response to a request to the given URL.
+fn read_body(url: &str, response: Result<ureq::Response, ureq::Error>) -> Result<Body> {
+    let response = response.map_err(Box::new).context(RequestSnafu { url })?;
+    let content_type = response.header("Content-Type").map(str::to_string);
+    let mut data = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut data)
+        .context(ReadBodySnafu)?;
+    Ok(Body { content_type, data })
+}
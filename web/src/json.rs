@@ -0,0 +1,233 @@
+//! DICOM JSON module.
+//!
+//! This module reads data sets in the DICOM JSON Model (PS3.18 section F.2),
+//! as found in QIDO-RS and STOW-RS responses,
+//! into in-memory DICOM objects.
+//! The JSON text itself is interpreted by
+//! the DICOM JSON reader in [`dicom_parser::dataset::json`].
+//!
+//! Values referred to by a `BulkDataURI` are not retrieved,
+//! and the respective elements are kept with an empty value,
+//! unless the data set is read with a bulk data resolver
+//! (see [`from_value_with_bulk_data`]).
+use dicom_object::InMemDicomObject;
+use dicom_parser::dataset::json::read::{
+    read_tokens, read_tokens_with_bulk_data, Error as ReadError,
+};
+use serde_json::Value as JsonValue;
+use snafu::{Backtrace, OptionExt, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to parse JSON text
+    ParseJson {
+        source: serde_json::Error,
+        backtrace: Backtrace,
+    },
+
+    /// expected a JSON array of data sets
+    NotAnArray { backtrace: Backtrace },
+
+    /// failed to read the DICOM JSON data set
+    ReadDataSet {
+        #[snafu(backtrace)]
+        source: ReadError,
+    },
+
+    /// failed to build the DICOM object
+    BuildObject {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Read a single data set from DICOM JSON text.
+pub fn from_slice(json: &[u8]) -> Result<InMemDicomObject> {
+    let value: JsonValue = serde_json::from_slice(json).context(ParseJsonSnafu)?;
+    from_value(&value)
+}
+
+/// Read an array of data sets from DICOM JSON text,
+/// as in a QIDO-RS response.
+pub fn array_from_slice(json: &[u8]) -> Result<Vec<InMemDicomObject>> {
+    let value: JsonValue = serde_json::from_slice(json).context(ParseJsonSnafu)?;
+    value
+        .as_array()
+        .context(NotAnArraySnafu)?
+        .iter()
+        .map(from_value)
+        .collect()
+}
+
+/// Read a data set from a parsed JSON value.
+pub fn from_value(value: &JsonValue) -> Result<InMemDicomObject> {
+    let tokens = read_tokens(value).context(ReadDataSetSnafu)?;
+    InMemDicomObject::from_tokens(tokens).context(BuildObjectSnafu)
+}
+
+/// Read a single data set from DICOM JSON text,
+/// retrieving the values referred to by a `BulkDataURI`
+/// with the given function.
+///
+/// See [`from_value_with_bulk_data`] for more information.
+pub fn from_slice_with_bulk_data<F>(json: &[u8], bulk_data: F) -> Result<InMemDicomObject>
+where
+    F: FnMut(&str) -> Option<Vec<u8>>,
+{
+    let value: JsonValue = serde_json::from_slice(json).context(ParseJsonSnafu)?;
+    from_value_with_bulk_data(&value, bulk_data)
+}
+
+/// Read a data set from a parsed JSON value,
+/// retrieving the values referred to by a `BulkDataURI`
+/// with the given function.
+///
+/// The function receives the bulk data URI
+/// and returns the bytes of the value in little endian,
+/// or `None` if the value could not be retrieved,
+/// in which case reading fails.
+pub fn from_value_with_bulk_data<F>(value: &JsonValue, bulk_data: F) -> Result<InMemDicomObject>
+where
+    F: FnMut(&str) -> Option<Vec<u8>>,
+{
+    let tokens = read_tokens_with_bulk_data(value, bulk_data).context(ReadDataSetSnafu)?;
+    InMemDicomObject::from_tokens(tokens).context(BuildObjectSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::value::PrimitiveValue;
+    use dicom_core::{Tag, VR};
+    use dicom_dictionary_std::tags;
+
+    #[test]
+    fn read_data_set() {
+        let json = br#"{
+            "00080020": { "vr": "DA", "Value": ["20230115"] },
+            "00100010": { "vr": "PN", "Value": [{ "Alphabetic": "Doe^John" }] },
+            "00200013": { "vr": "IS", "Value": [4] },
+            "00280010": { "vr": "US", "Value": [512] },
+            "00280030": { "vr": "DS", "Value": [0.5, "0.25"] },
+            "00081190": { "vr": "UR", "Value": ["https://pacs/studies/1.2"] },
+            "00100020": { "vr": "LO" },
+            "00091002": { "vr": "OB", "InlineBinary": "AQID" },
+            "7FE00010": { "vr": "OW", "BulkDataURI": "https://pacs/bulk/1" },
+            "00081199": { "vr": "SQ", "Value": [
+                { "00081155": { "vr": "UI", "Value": ["1.2.3.4"] } }
+            ] }
+        }"#;
+
+        let obj = from_slice(json).unwrap();
+        assert_eq!(obj.get_string(tags::STUDY_DATE).unwrap(), "20230115");
+        assert_eq!(obj.get_string(tags::PATIENT_NAME).unwrap(), "Doe^John");
+        assert_eq!(obj.get_string(tags::INSTANCE_NUMBER).unwrap(), "4");
+        assert_eq!(obj.get_u16(tags::ROWS).unwrap(), 512);
+        assert_eq!(obj.get_f64s(tags::PIXEL_SPACING).unwrap(), vec![0.5, 0.25]);
+        assert_eq!(
+            obj.element(tags::PATIENT_ID)
+                .unwrap()
+                .value()
+                .multiplicity(),
+            0
+        );
+        assert_eq!(
+            obj.element(Tag(0x0009, 0x1002))
+                .unwrap()
+                .to_bytes()
+                .unwrap()
+                .as_ref(),
+            &[1, 2, 3]
+        );
+        assert_eq!(obj.element(tags::PIXEL_DATA).unwrap().vr(), VR::OW);
+
+        let items = obj.get_sequence(tags::REFERENCED_SOP_SEQUENCE).unwrap();
+        assert_eq!(items.len(), 1);
+        assert_eq!(
+            items[0]
+                .get_string(tags::REFERENCED_SOP_INSTANCE_UID)
+                .unwrap(),
+            "1.2.3.4"
+        );
+    }
+
+    #[test]
+    fn read_data_set_with_bulk_data() {
+        let json = br#"{
+            "00091002": { "vr": "OB", "BulkDataURI": "bulk/1" },
+            "00081199": { "vr": "SQ", "Value": [
+                { "00660023": { "vr": "OW", "BulkDataURI": "bulk/2" } }
+            ] }
+        }"#;
+
+        let obj = from_slice_with_bulk_data(json, |uri| match uri {
+            "bulk/1" => Some(vec![1, 2, 3]),
+            "bulk/2" => Some(vec![1, 0, 2, 0]),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(
+            obj.element(Tag(0x0009, 0x1002))
+                .unwrap()
+                .to_bytes()
+                .unwrap()
+                .as_ref(),
+            &[1, 2, 3]
+        );
+        let items = obj.get_sequence(tags::REFERENCED_SOP_SEQUENCE).unwrap();
+        assert_eq!(
+            items[0]
+                .element(Tag(0x0066, 0x0023))
+                .unwrap()
+                .value()
+                .primitive(),
+            Some(&PrimitiveValue::U16([1, 2].as_ref().into()))
+        );
+
+        assert!(matches!(
+            from_slice_with_bulk_data(json, |_| None),
+            Err(Error::ReadDataSet {
+                source: ReadError::UnresolvedBulkData { .. },
+            })
+        ));
+    }
+
+    #[test]
+    fn read_array_of_data_sets() {
+        let json = br#"[
+            { "0020000D": { "vr": "UI", "Value": ["1.2.1"] } },
+            { "0020000D": { "vr": "UI", "Value": ["1.2.2"] } }
+        ]"#;
+        let objects = array_from_slice(json).unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(
+            objects[1].get_string(tags::STUDY_INSTANCE_UID).unwrap(),
+            "1.2.2"
+        );
+    }
+
+    #[test]
+    fn reject_malformed_attributes() {
+        assert!(matches!(
+            from_slice(br#"{ "0010": { "vr": "LO" } }"#),
+            Err(Error::ReadDataSet {
+                source: ReadError::InvalidTag { .. },
+            })
+        ));
+        assert!(matches!(
+            from_slice(br#"{ "00100010": { "Value": ["x"] } }"#),
+            Err(Error::ReadDataSet {
+                source: ReadError::MissingVr { .. },
+            })
+        ));
+        assert!(matches!(
+            from_slice(br#"{ "00280010": { "vr": "US", "Value": ["x"] } }"#),
+            Err(Error::ReadDataSet {
+                source: ReadError::InvalidValue { .. },
+            })
+        ));
+    }
+}
@@ -0,0 +1,41 @@
+//! This crate implements DICOMweb services,
+//! through which DICOM objects are retrieved, stored, and searched for
+//! over HTTP.
+//!
+//! - The [`wado`](crate::wado) module
+//! describes the resources available through WADO-RS
+//! and reads the `multipart/related` bodies of its responses
+//! into DICOM objects, data set token readers, or frames.
+//! - The [`stow`](crate::stow) module
+//! encodes DICOM objects into STOW-RS requests
+//! and reads the outcome of storage from its responses.
+//! - The [`qido`](crate::qido) module
+//! builds QIDO-RS search requests
+//! and reads the matching data sets from its responses.
+//! - The [`json`](crate::json) module
+//! reads data sets in the DICOM JSON model into in-memory DICOM objects.
+//! - The [`multipart`](crate::multipart) module
+//! reads and writes `multipart/related` message bodies,
+//! either in memory or one part at a time.
+//! - The `client` module,
+//! available with the `client` feature,
+//! provides a blocking HTTP client for DICOMweb services.
+//!
+//! With the exception of `client`,
+//! these modules do not depend on a particular HTTP implementation.
+
+#[cfg(feature = "client")]
+pub mod client;
+pub mod json;
+pub mod multipart;
+pub mod qido;
+pub mod stow;
+pub mod wado;
+
+// re-exports
+
+#[cfg(feature = "client")]
+pub use client::WebClient;
+pub use qido::Query;
+pub use stow::StoreResponse;
+pub use wado::{InstanceData, Target};
@@ -0,0 +1,653 @@
+//! Encoding and decoding of `multipart/related` message bodies.
+//!
+//! Message bodies can be split into their parts at once via [`parse`],
+//! or one part at a time from any byte source via [`PartReader`].
+//! Likewise, [`write`] encodes a full message body in memory,
+//! whereas [`PartWriter`] writes parts to any byte sink
+//! as they become available.
+//!
+//! Parameters of a part's content type,
+//! such as the `transfer-syntax` of DICOM content,
+//! are retrieved through [`parameter`].
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_web::multipart::{self, Part, PartReader, PartWriter};
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let part = Part::new("application/dicom; transfer-syntax=1.2.840.10008.1.2.1", vec![0; 16]);
+//! let boundary = multipart::choose_boundary(std::slice::from_ref(&part));
+//!
+//! let mut writer = PartWriter::new(Vec::new(), &boundary)?;
+//! writer.write_part(&part)?;
+//! let body = writer.finish()?;
+//!
+//! let content_type = multipart::related_content_type("application/dicom", &boundary);
+//! let mut reader = PartReader::from_content_type(&body[..], &content_type)?;
+//! let read = reader.next_part()?.unwrap();
+//! assert_eq!(read.transfer_syntax(), Some("1.2.840.10008.1.2.1"));
+//! assert!(reader.next_part()?.is_none());
+//! # Ok(())
+//! # }
+//! ```
+use std::io::{Read, Write};
+
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    #[snafu(display("content type `{}` is not multipart", content_type))]
+    NotMultipart {
+        content_type: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("missing boundary in content type `{}`", content_type))]
+    MissingBoundary {
+        content_type: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("invalid boundary `{}`", boundary))]
+    InvalidBoundary {
+        boundary: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("missing delimiter `--{}` in message body", boundary))]
+    MissingDelimiter {
+        boundary: String,
+        backtrace: Backtrace,
+    },
+
+    /// message body ended before the closing delimiter
+    UnexpectedEnd { backtrace: Backtrace },
+
+    /// malformed header in message part
+    MalformedHeader { backtrace: Backtrace },
+
+    /// failed to read message body
+    ReadBody {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    /// failed to write message body
+    WriteBody {
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The maximum length of a boundary, as per RFC 2046.
+const MAX_BOUNDARY_LENGTH: usize = 70;
+
+/// The number of bytes requested from the source at a time.
+const READ_CHUNK_SIZE: usize = 8192;
+
+/// A single part of a multipart message body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Part {
+    /// the part's headers, with lowercase names
+    pub headers: Vec<(String, String)>,
+    /// the part's content
+    pub body: Vec<u8>,
+}
+
+impl Part {
+    /// Create a part with the given content type and content.
+    pub fn new(content_type: impl Into<String>, body: Vec<u8>) -> Self {
+        Part {
+            headers: vec![("content-type".to_string(), content_type.into())],
+            body,
+        }
+    }
+
+    /// Add a header to the part.
+    pub fn with_header(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.headers.push((name.to_ascii_lowercase(), value.into()));
+        self
+    }
+
+    /// Retrieve the value of a header by its case-insensitive name.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Retrieve the part's content type, if specified.
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    /// Retrieve the part's media type without parameters,
+    /// if specified.
+    pub fn media_type(&self) -> Option<&str> {
+        self.content_type().map(media_type)
+    }
+
+    /// Retrieve the `transfer-syntax` parameter
+    /// of the part's content type, if specified.
+    pub fn transfer_syntax(&self) -> Option<&str> {
+        self.content_type()
+            .and_then(|content_type| parameter(content_type, "transfer-syntax"))
+    }
+}
+
+/// Retrieve the media type of a content type,
+/// without its parameters.
+pub fn media_type(content_type: &str) -> &str {
+    content_type.split(';').next().unwrap_or_default().trim()
+}
+
+/// Retrieve a parameter from a content type,
+/// such as `boundary` or `transfer-syntax`,
+/// without surrounding quotes.
+///
+/// Separators within quoted values are not treated as such,
+/// but escaped characters in quoted values are returned as is.
+pub fn parameter<'a>(content_type: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = &content_type[split_parameter(content_type)..];
+    while let Some(param) = rest.strip_prefix(';') {
+        let end = split_parameter(param);
+        rest = &param[end..];
+        if let Some((key, value)) = param[..end].split_once('=') {
+            if key.trim().eq_ignore_ascii_case(name) {
+                let value = value.trim();
+                return Some(
+                    value
+                        .strip_prefix('"')
+                        .and_then(|value| value.strip_suffix('"'))
+                        .unwrap_or(value),
+                );
+            }
+        }
+    }
+    None
+}
+
+/// Find the end of the first parameter in the text,
+/// which is the next `;` outside of quotes or the end of the text.
+fn split_parameter(text: &str) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in text.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' if quoted => escaped = true,
+            '"' => quoted = !quoted,
+            ';' if !quoted => return i,
+            _ => {}
+        }
+    }
+    text.len()
+}
+
+/// Build the content type of a `multipart/related` message body
+/// with parts of the given media type, separated by the given boundary.
+pub fn related_content_type(part_type: &str, boundary: &str) -> String {
+    format!(
+        "multipart/related; type=\"{}\"; boundary={}",
+        part_type,
+        quote_if_needed(boundary)
+    )
+}
+
+fn quote_if_needed(value: &str) -> std::borrow::Cow<'_, str> {
+    let is_token = value
+        .bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+    if is_token && !value.is_empty() {
+        value.into()
+    } else {
+        format!("\"{}\"", value).into()
+    }
+}
+
+/// Check whether the text is a valid boundary as per RFC 2046:
+/// 1 to 70 characters from a restricted set,
+/// not ending with a space.
+pub fn is_valid_boundary(boundary: &str) -> bool {
+    !boundary.is_empty()
+        && boundary.len() <= MAX_BOUNDARY_LENGTH
+        && !boundary.ends_with(' ')
+        && boundary
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"'()+_,-./:=? ".contains(&b))
+}
+
+/// Pick a boundary which does not occur in the content of any of the parts.
+pub fn choose_boundary(parts: &[Part]) -> String {
+    (0_u32..)
+        .map(|i| format!("DICOMwebBoundary{:08x}", i))
+        .find(|boundary| {
+            parts
+                .iter()
+                .all(|part| find(&part.body, boundary.as_bytes(), 0).is_none())
+        })
+        .unwrap()
+}
+
+/// Split a multipart message body into its parts,
+/// according to the boundary in the given content type.
+pub fn parse(content_type: &str, body: &[u8]) -> Result<Vec<Part>> {
+    PartReader::from_content_type(body, content_type)?.collect()
+}
+
+/// Encode the given parts into a multipart message body,
+/// separated by the given boundary.
+///
+/// The boundary must not occur in the content of any part.
+/// An error is returned if the boundary is not valid.
+pub fn write(boundary: &str, parts: &[Part]) -> Result<Vec<u8>> {
+    let mut writer = PartWriter::new(Vec::new(), boundary)?;
+    for part in parts {
+        writer.write_part(part)?;
+    }
+    writer.finish()
+}
+
+/// The position of a part reader in the message body.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum ReaderState {
+    /// before the first delimiter
+    Preamble,
+    /// right after a delimiter
+    Delimiter,
+    /// after the closing delimiter
+    Done,
+}
+
+/// A reader of multipart message parts from a byte source,
+/// which only keeps one part in memory at a time.
+///
+/// The parts are also available through the [`Iterator`] implementation,
+/// which stops after the first error.
+#[derive(Debug)]
+pub struct PartReader<R> {
+    source: R,
+    /// `--` followed by the boundary
+    delimiter: Vec<u8>,
+    /// bytes read from the source but not consumed yet
+    buffer: Vec<u8>,
+    eof: bool,
+    state: ReaderState,
+}
+
+impl<R> PartReader<R>
+where
+    R: Read,
+{
+    /// Create a reader of parts separated by the given boundary.
+    pub fn new(source: R, boundary: &str) -> Self {
+        PartReader {
+            source,
+            delimiter: format!("--{}", boundary).into_bytes(),
+            buffer: Vec::new(),
+            eof: false,
+            state: ReaderState::Preamble,
+        }
+    }
+
+    /// Create a reader of parts separated by the boundary
+    /// in the given `multipart` content type.
+    pub fn from_content_type(source: R, content_type: &str) -> Result<Self> {
+        ensure!(
+            content_type
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with("multipart/"),
+            NotMultipartSnafu { content_type }
+        );
+        let boundary =
+            parameter(content_type, "boundary").context(MissingBoundarySnafu { content_type })?;
+        Ok(Self::new(source, boundary))
+    }
+
+    /// Read the next part of the message body,
+    /// or `None` after the closing delimiter.
+    ///
+    /// The preamble and epilogue of the message body are ignored.
+    pub fn next_part(&mut self) -> Result<Option<Part>> {
+        match self.state {
+            ReaderState::Done => return Ok(None),
+            ReaderState::Preamble => {
+                let delimiter = self.delimiter.clone();
+                let pos = self.fill_until(&delimiter).map_err(|e| match e {
+                    Error::UnexpectedEnd { .. } => MissingDelimiterSnafu {
+                        boundary: String::from_utf8_lossy(&delimiter[2..]),
+                    }
+                    .build(),
+                    e => e,
+                })?;
+                self.buffer.drain(..pos + delimiter.len());
+                self.state = ReaderState::Delimiter;
+            }
+            ReaderState::Delimiter => {}
+        }
+
+        // closing delimiter
+        self.fill_to(2)?;
+        if self.buffer.starts_with(b"--") {
+            self.state = ReaderState::Done;
+            return Ok(None);
+        }
+        // skip transport padding up to the end of the delimiter line
+        let pos = self.fill_until(b"\r\n")?;
+        self.buffer.drain(..pos + 2);
+
+        self.fill_to(2)?;
+        let headers = if self.buffer.starts_with(b"\r\n") {
+            // no headers
+            self.buffer.drain(..2);
+            Vec::new()
+        } else {
+            let header_end = self.fill_until(b"\r\n\r\n")?;
+            let headers = parse_headers(&self.buffer[..header_end])?;
+            self.buffer.drain(..header_end + 4);
+            headers
+        };
+
+        // the delimiter is preceded by a line break which is not part of the content
+        let mut next_delimiter = Vec::with_capacity(self.delimiter.len() + 2);
+        next_delimiter.extend_from_slice(b"\r\n");
+        next_delimiter.extend_from_slice(&self.delimiter);
+        let content_end = self.fill_until(&next_delimiter)?;
+        let body = self.buffer.drain(..content_end).collect();
+        self.buffer.drain(..next_delimiter.len());
+
+        Ok(Some(Part { headers, body }))
+    }
+
+    /// Read from the source until the buffer holds at least `len` bytes.
+    fn fill_to(&mut self, len: usize) -> Result<()> {
+        while self.buffer.len() < len {
+            ensure!(self.fill()?, UnexpectedEndSnafu);
+        }
+        Ok(())
+    }
+
+    /// Read from the source until the buffer contains `needle`,
+    /// returning its position in the buffer.
+    fn fill_until(&mut self, needle: &[u8]) -> Result<usize> {
+        let mut from = 0;
+        loop {
+            if let Some(pos) = find(&self.buffer, needle, from) {
+                return Ok(pos);
+            }
+            // the needle may still start in the last few bytes
+            from = (self.buffer.len() + 1).saturating_sub(needle.len());
+            ensure!(self.fill()?, UnexpectedEndSnafu);
+        }
+    }
+
+    /// Read another chunk from the source into the buffer,
+    /// returning whether any bytes were read.
+    fn fill(&mut self) -> Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        let start = self.buffer.len();
+        self.buffer.resize(start + READ_CHUNK_SIZE, 0);
+        let read = loop {
+            match self.source.read(&mut self.buffer[start..]) {
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                read => break read,
+            }
+        };
+        let read = match read {
+            Ok(read) => read,
+            Err(e) => {
+                self.buffer.truncate(start);
+                return Err(e).context(ReadBodySnafu);
+            }
+        };
+        self.buffer.truncate(start + read);
+        self.eof = read == 0;
+        Ok(read > 0)
+    }
+}
+
+impl<R> Iterator for PartReader<R>
+where
+    R: Read,
+{
+    type Item = Result<Part>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_part() {
+            Ok(part) => part.map(Ok),
+            Err(e) => {
+                self.state = ReaderState::Done;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// A writer of multipart message parts to a byte sink.
+///
+/// The closing delimiter is only written by [`finish`](PartWriter::finish),
+/// which must be called once all parts are written.
+/// The boundary must not occur in the content of any part,
+/// which is not checked by the writer.
+#[derive(Debug)]
+pub struct PartWriter<W> {
+    sink: W,
+    /// `--` followed by the boundary
+    delimiter: Vec<u8>,
+}
+
+impl<W> PartWriter<W>
+where
+    W: Write,
+{
+    /// Create a writer of parts separated by the given boundary.
+    pub fn new(sink: W, boundary: &str) -> Result<Self> {
+        ensure!(
+            is_valid_boundary(boundary),
+            InvalidBoundarySnafu { boundary }
+        );
+        Ok(PartWriter {
+            sink,
+            delimiter: format!("--{}", boundary).into_bytes(),
+        })
+    }
+
+    /// Write a whole part to the message body.
+    pub fn write_part(&mut self, part: &Part) -> Result<()> {
+        self.write_part_from(&part.headers, &part.body[..])
+    }
+
+    /// Write a part to the message body
+    /// with the given headers,
+    /// copying its content from the given source until its end.
+    pub fn write_part_from(
+        &mut self,
+        headers: &[(String, String)],
+        mut content: impl Read,
+    ) -> Result<()> {
+        let mut head = self.delimiter.clone();
+        head.extend_from_slice(b"\r\n");
+        for (name, value) in headers {
+            head.extend_from_slice(name.as_bytes());
+            head.extend_from_slice(b": ");
+            head.extend_from_slice(value.as_bytes());
+            head.extend_from_slice(b"\r\n");
+        }
+        head.extend_from_slice(b"\r\n");
+        self.sink.write_all(&head).context(WriteBodySnafu)?;
+        std::io::copy(&mut content, &mut self.sink).context(WriteBodySnafu)?;
+        self.sink.write_all(b"\r\n").context(WriteBodySnafu)
+    }
+
+    /// Write the closing delimiter and retrieve the underlying sink.
+    pub fn finish(mut self) -> Result<W> {
+        self.sink
+            .write_all(&self.delimiter)
+            .and_then(|_| self.sink.write_all(b"--\r\n"))
+            .and_then(|_| self.sink.flush())
+            .context(WriteBodySnafu)?;
+        Ok(self.sink)
+    }
+}
+
+fn parse_headers(data: &[u8]) -> Result<Vec<(String, String)>> {
+    let text = std::str::from_utf8(data)
+        .ok()
+        .context(MalformedHeaderSnafu)?;
+    text.split("\r\n")
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let (name, value) = line.split_once(':').context(MalformedHeaderSnafu)?;
+            Ok((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+/// Find the first occurrence of `needle` in `haystack`
+/// at or after position `from`.
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|i| i + from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_related_body() {
+        let content_type = r#"multipart/related; type="application/dicom"; boundary="XYZ""#;
+        let body = b"preamble\r\n\
+            --XYZ\r\n\
+            Content-Type: application/dicom\r\n\
+            \r\n\
+            first\r\n\
+            --XYZ \r\n\
+            Content-Type: application/octet-stream; transfer-syntax=1.2.840.10008.1.2.1\r\n\
+            Content-Location: /frames/2\r\n\
+            \r\n\
+            second\r\nline\r\n\
+            --XYZ--\r\n\
+            epilogue";
+
+        let parts = parse(content_type, body).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].content_type(), Some("application/dicom"));
+        assert_eq!(parts[0].body, b"first");
+        assert_eq!(parts[1].header("Content-Location"), Some("/frames/2"));
+        assert_eq!(parts[1].media_type(), Some("application/octet-stream"));
+        assert_eq!(parts[1].transfer_syntax(), Some("1.2.840.10008.1.2.1"));
+        assert_eq!(parts[1].body, b"second\r\nline");
+    }
+
+    #[test]
+    fn quoted_parameters() {
+        let content_type = r#"multipart/related; type="application/dicom; x=1"; boundary="a;b""#;
+        assert_eq!(media_type(content_type), "multipart/related");
+        assert_eq!(
+            parameter(content_type, "type"),
+            Some("application/dicom; x=1")
+        );
+        assert_eq!(parameter(content_type, "Boundary"), Some("a;b"));
+        assert_eq!(parameter(content_type, "x"), None);
+
+        assert_eq!(
+            related_content_type("application/dicom", "a:b"),
+            r#"multipart/related; type="application/dicom"; boundary="a:b""#
+        );
+        assert!(is_valid_boundary("a:b c"));
+        assert!(!is_valid_boundary("trailing "));
+        assert!(!is_valid_boundary(&"x".repeat(71)));
+    }
+
+    #[test]
+    fn write_and_parse() {
+        let parts = vec![
+            Part::new("application/dicom", b"first\r\n".to_vec()),
+            Part {
+                headers: vec![],
+                body: vec![],
+            },
+        ];
+        let body = write("b0undary", &parts).unwrap();
+        assert_eq!(
+            parse("multipart/related; boundary=b0undary", &body).unwrap(),
+            parts
+        );
+
+        assert!(matches!(
+            write("not a boundary ", &parts),
+            Err(Error::InvalidBoundary { .. })
+        ));
+    }
+
+    /// A source which yields a single byte per read.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn stream_parts() {
+        let parts = vec![
+            Part::new("application/octet-stream", vec![0x2D; 100])
+                .with_header("Content-Location", "/frames/1"),
+            Part::new("application/octet-stream", b"\r\n--b0undar".to_vec()),
+        ];
+        let mut writer = PartWriter::new(Vec::new(), "b0undary").unwrap();
+        writer.write_part(&parts[0]).unwrap();
+        writer
+            .write_part_from(&parts[1].headers, &parts[1].body[..])
+            .unwrap();
+        let body = writer.finish().unwrap();
+        assert_eq!(body, write("b0undary", &parts).unwrap());
+
+        let mut reader = PartReader::new(Trickle(&body), "b0undary");
+        assert_eq!(reader.next_part().unwrap().as_ref(), Some(&parts[0]));
+        assert_eq!(reader.next_part().unwrap().as_ref(), Some(&parts[1]));
+        assert_eq!(reader.next_part().unwrap(), None);
+        assert_eq!(reader.next_part().unwrap(), None);
+
+        assert!(matches!(
+            PartWriter::new(Vec::new(), ""),
+            Err(Error::InvalidBoundary { .. })
+        ));
+    }
+
+    #[test]
+    fn reject_truncated_body() {
+        let body = b"--XYZ\r\nContent-Type: application/dicom\r\n\r\nincomplete";
+        assert!(matches!(
+            parse("multipart/related; boundary=XYZ", body),
+            Err(Error::UnexpectedEnd { .. })
+        ));
+        assert!(matches!(
+            parse("multipart/related; boundary=ABC", body),
+            Err(Error::MissingDelimiter { .. })
+        ));
+        assert!(matches!(
+            parse("application/dicom", body),
+            Err(Error::NotMultipart { .. })
+        ));
+    }
+}
@@ -0,0 +1,307 @@
+//! QIDO-RS module.
+//!
+//! This module builds QIDO-RS search requests
+//! and reads their DICOM JSON responses into in-memory DICOM objects,
+//! independently of the HTTP implementation used.
+//!
+//! # Example
+//!
+//! ```
+//! # use dicom_web::qido::Query;
+//! let query = Query::studies()
+//!     .with("PatientName", "Doe*")
+//!     .include_field("StudyDescription")
+//!     .limit(10);
+//! assert_eq!(
+//!     query.path(),
+//!     "studies?PatientName=Doe*&includefield=StudyDescription&limit=10",
+//! );
+//! ```
+use dicom_core::Tag;
+use dicom_object::InMemDicomObject;
+use snafu::{ResultExt, Snafu};
+
+use crate::json;
+
+/// The value of the `Accept` header for QIDO-RS responses.
+pub const ACCEPT_JSON: &str = "application/dicom+json";
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to read QIDO-RS response
+    ReadResponse {
+        #[snafu(backtrace)]
+        source: json::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The level of the entities to search for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Level {
+    Studies,
+    Series,
+    Instances,
+}
+
+/// A QIDO-RS search request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    level: Level,
+    study_instance_uid: Option<String>,
+    series_instance_uid: Option<String>,
+    matches: Vec<(String, String)>,
+    include_fields: Vec<String>,
+    fuzzy_matching: bool,
+    limit: Option<u32>,
+    offset: Option<u32>,
+}
+
+impl Query {
+    /// Create a new search request at the given level.
+    pub fn new(level: Level) -> Self {
+        Query {
+            level,
+            study_instance_uid: None,
+            series_instance_uid: None,
+            matches: Vec::new(),
+            include_fields: Vec::new(),
+            fuzzy_matching: false,
+            limit: None,
+            offset: None,
+        }
+    }
+
+    /// Search for studies.
+    pub fn studies() -> Self {
+        Self::new(Level::Studies)
+    }
+
+    /// Search for series.
+    pub fn series() -> Self {
+        Self::new(Level::Series)
+    }
+
+    /// Search for instances.
+    pub fn instances() -> Self {
+        Self::new(Level::Instances)
+    }
+
+    /// Restrict the search to the given study.
+    ///
+    /// When searching for studies,
+    /// this is equivalent to matching the Study Instance UID.
+    pub fn in_study(mut self, study_instance_uid: impl Into<String>) -> Self {
+        self.study_instance_uid = Some(study_instance_uid.into());
+        self
+    }
+
+    /// Restrict the search to the given series of a study.
+    ///
+    /// This only applies to searches for instances.
+    pub fn in_series(
+        mut self,
+        study_instance_uid: impl Into<String>,
+        series_instance_uid: impl Into<String>,
+    ) -> Self {
+        self.study_instance_uid = Some(study_instance_uid.into());
+        self.series_instance_uid = Some(series_instance_uid.into());
+        self
+    }
+
+    /// Add a matching key,
+    /// by attribute keyword (such as `PatientID`)
+    /// or tag in the form `GGGGEEEE`.
+    pub fn with(mut self, attribute: impl Into<String>, value: impl Into<String>) -> Self {
+        self.matches.push((attribute.into(), value.into()));
+        self
+    }
+
+    /// Add a matching key by attribute tag.
+    pub fn with_tag(self, tag: Tag, value: impl Into<String>) -> Self {
+        self.with(tag_key(tag), value)
+    }
+
+    /// Request an additional attribute to be included in the results,
+    /// by keyword or tag in the form `GGGGEEEE`.
+    pub fn include_field(mut self, attribute: impl Into<String>) -> Self {
+        self.include_fields.push(attribute.into());
+        self
+    }
+
+    /// Request all available attributes to be included in the results.
+    pub fn include_all_fields(self) -> Self {
+        self.include_field("all")
+    }
+
+    /// Set whether fuzzy matching is applied to person names.
+    pub fn fuzzy_matching(mut self, fuzzy_matching: bool) -> Self {
+        self.fuzzy_matching = fuzzy_matching;
+        self
+    }
+
+    /// Set the maximum number of results.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Set the number of results to skip.
+    pub fn offset(mut self, offset: u32) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    /// Build the path of the search request, query string included,
+    /// relative to the service's base URL.
+    pub fn path(&self) -> String {
+        let mut path = String::new();
+        let mut params = Vec::new();
+        match (self.level, &self.study_instance_uid) {
+            (Level::Studies, Some(study)) => {
+                path.push_str("studies");
+                params.push(format!("StudyInstanceUID={}", encode(study)));
+            }
+            (Level::Studies, None) => path.push_str("studies"),
+            (level, Some(study)) => {
+                path.push_str("studies/");
+                path.push_str(study);
+                if let (Some(series), Level::Instances) = (&self.series_instance_uid, level) {
+                    path.push_str("/series/");
+                    path.push_str(series);
+                }
+            }
+            (_, None) => {}
+        }
+        if self.level != Level::Studies {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(match self.level {
+                Level::Series => "series",
+                _ => "instances",
+            });
+        }
+
+        params.extend(
+            self.matches
+                .iter()
+                .map(|(attribute, value)| format!("{}={}", encode(attribute), encode(value))),
+        );
+        params.extend(
+            self.include_fields
+                .iter()
+                .map(|attribute| format!("includefield={}", encode(attribute))),
+        );
+        if self.fuzzy_matching {
+            params.push("fuzzymatching=true".to_string());
+        }
+        if let Some(limit) = self.limit {
+            params.push(format!("limit={}", limit));
+        }
+        if let Some(offset) = self.offset {
+            params.push(format!("offset={}", offset));
+        }
+
+        if params.is_empty() {
+            path
+        } else {
+            format!("{}?{}", path, params.join("&"))
+        }
+    }
+}
+
+/// Read a QIDO-RS response body in DICOM JSON
+/// into in-memory DICOM objects.
+///
+/// An empty body, as in a response with no matches, yields no objects.
+pub fn read_response(body: &[u8]) -> Result<Vec<InMemDicomObject>> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(Vec::new());
+    }
+    json::array_from_slice(body).context(ReadResponseSnafu)
+}
+
+fn tag_key(tag: Tag) -> String {
+    format!("{:04X}{:04X}", tag.0, tag.1)
+}
+
+/// Percent-encode a query string component,
+/// keeping the characters used in DICOM matching as is.
+fn encode(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for b in text.bytes() {
+        match b {
+            b'A'..=b'Z'
+            | b'a'..=b'z'
+            | b'0'..=b'9'
+            | b'-'
+            | b'.'
+            | b'_'
+            | b'~'
+            | b'*'
+            | b'?'
+            | b'^'
+            | b',' => out.push(b as char),
+            _ => out.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_dictionary_std::tags;
+
+    #[test]
+    fn query_paths() {
+        assert_eq!(Query::studies().path(), "studies");
+        assert_eq!(
+            Query::studies().in_study("1.2.3").path(),
+            "studies?StudyInstanceUID=1.2.3"
+        );
+        assert_eq!(
+            Query::series()
+                .in_study("1.2.3")
+                .with("Modality", "CT")
+                .path(),
+            "studies/1.2.3/series?Modality=CT"
+        );
+        assert_eq!(
+            Query::instances()
+                .in_series("1.2.3", "1.2.3.4")
+                .with_tag(tags::INSTANCE_NUMBER, "1")
+                .offset(20)
+                .path(),
+            "studies/1.2.3/series/1.2.3.4/instances?00200013=1&offset=20"
+        );
+        assert_eq!(
+            Query::studies()
+                .with("StudyDate", "20230101-20230131")
+                .with("PatientName", "Doe John")
+                .include_all_fields()
+                .fuzzy_matching(true)
+                .path(),
+            "studies?StudyDate=20230101-20230131&PatientName=Doe%20John\
+             &includefield=all&fuzzymatching=true"
+        );
+    }
+
+    #[test]
+    fn read_matches() {
+        let body = br#"[{
+            "0020000D": { "vr": "UI", "Value": ["1.2.3"] },
+            "00100010": { "vr": "PN", "Value": [{ "Alphabetic": "Doe^John" }] }
+        }]"#;
+        let objects = read_response(body).unwrap();
+        assert_eq!(objects.len(), 1);
+        assert_eq!(
+            objects[0].get_string(tags::PATIENT_NAME).unwrap(),
+            "Doe^John"
+        );
+        assert!(read_response(b"").unwrap().is_empty());
+    }
+}
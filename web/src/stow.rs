@@ -0,0 +1,240 @@
+//! STOW-RS module.
+//!
+//! This module encodes DICOM objects into STOW-RS request bodies,
+//! and reads the responses of the service,
+//! independently of the HTTP implementation used.
+//!
+//! A request body is a `multipart/related` message
+//! with one `application/dicom` part per DICOM file,
+//! which is sent to the path given by [`store_path`].
+//! The service responds with a DICOM JSON data set
+//! listing the instances which were stored and those which failed,
+//! read via [`read_response`].
+use dicom_core::Tag;
+use dicom_dictionary_std::tags;
+use dicom_object::{DefaultDicomObject, InMemDicomObject};
+use snafu::{ResultExt, Snafu};
+
+use crate::json;
+use crate::multipart::{self, Part};
+use crate::wado::DICOM_MEDIA_TYPE;
+
+/// The media type of DICOM JSON content.
+pub const DICOM_JSON_MEDIA_TYPE: &str = "application/dicom+json";
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to encode DICOM object
+    WriteObject {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    /// failed to encode STOW-RS request body
+    WriteBody {
+        #[snafu(backtrace)]
+        source: multipart::Error,
+    },
+
+    /// failed to read STOW-RS response
+    ReadResponse {
+        #[snafu(backtrace)]
+        source: json::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// An encoded STOW-RS request body.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoreRequest {
+    /// the value of the `Content-Type` header,
+    /// including the multipart boundary
+    pub content_type: String,
+    /// the request body
+    pub body: Vec<u8>,
+}
+
+/// An instance successfully stored through STOW-RS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StoredInstance {
+    /// the SOP class UID of the instance
+    pub sop_class_uid: String,
+    /// the SOP instance UID of the instance
+    pub sop_instance_uid: String,
+    /// the URL from which the instance can be retrieved, if provided
+    pub retrieve_url: Option<String>,
+    /// the warning reason code, if any
+    pub warning_reason: Option<u16>,
+}
+
+/// An instance which could not be stored through STOW-RS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FailedInstance {
+    /// the SOP class UID of the instance
+    pub sop_class_uid: String,
+    /// the SOP instance UID of the instance
+    pub sop_instance_uid: String,
+    /// the failure reason code, if provided
+    pub failure_reason: Option<u16>,
+}
+
+/// The outcome of a STOW-RS request.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StoreResponse {
+    /// the URL of the study, if provided
+    pub retrieve_url: Option<String>,
+    /// the instances which were stored
+    pub stored: Vec<StoredInstance>,
+    /// the instances which could not be stored
+    pub failed: Vec<FailedInstance>,
+}
+
+impl StoreResponse {
+    /// Whether all instances in the request were stored.
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Build the path for storing instances,
+/// relative to the service's base URL.
+///
+/// If a study is given,
+/// the service will reject instances of other studies.
+pub fn store_path(study_instance_uid: Option<&str>) -> String {
+    match study_instance_uid {
+        Some(uid) => format!("studies/{}", uid),
+        None => "studies".to_string(),
+    }
+}
+
+/// Encode the given DICOM objects into a STOW-RS request body.
+pub fn encode_instances<'a, I>(objects: I) -> Result<StoreRequest>
+where
+    I: IntoIterator<Item = &'a DefaultDicomObject>,
+{
+    let files = objects
+        .into_iter()
+        .map(|obj| {
+            let mut data = Vec::new();
+            obj.write_all(&mut data).context(WriteObjectSnafu)?;
+            Ok(data)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    encode_files(files)
+}
+
+/// Encode the given DICOM files, preamble included,
+/// into a STOW-RS request body.
+pub fn encode_files<I>(files: I) -> Result<StoreRequest>
+where
+    I: IntoIterator<Item = Vec<u8>>,
+{
+    let parts: Vec<_> = files
+        .into_iter()
+        .map(|body| Part::new(DICOM_MEDIA_TYPE, body))
+        .collect();
+    let boundary = multipart::choose_boundary(&parts);
+    let body = multipart::write(&boundary, &parts).context(WriteBodySnafu)?;
+    Ok(StoreRequest {
+        content_type: multipart::related_content_type(DICOM_MEDIA_TYPE, &boundary),
+        body,
+    })
+}
+
+/// Read a STOW-RS response body in DICOM JSON.
+///
+/// An empty body yields an empty response.
+pub fn read_response(body: &[u8]) -> Result<StoreResponse> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(StoreResponse::default());
+    }
+    let obj = json::from_slice(body).context(ReadResponseSnafu)?;
+
+    let stored = items(&obj, tags::REFERENCED_SOP_SEQUENCE)
+        .iter()
+        .map(|item| StoredInstance {
+            sop_class_uid: string(item, tags::REFERENCED_SOP_CLASS_UID).unwrap_or_default(),
+            sop_instance_uid: string(item, tags::REFERENCED_SOP_INSTANCE_UID).unwrap_or_default(),
+            retrieve_url: string(item, tags::RETRIEVE_URL),
+            warning_reason: item.get_u16(tags::WARNING_REASON).ok(),
+        })
+        .collect();
+    let failed = items(&obj, tags::FAILED_SOP_SEQUENCE)
+        .iter()
+        .map(|item| FailedInstance {
+            sop_class_uid: string(item, tags::REFERENCED_SOP_CLASS_UID).unwrap_or_default(),
+            sop_instance_uid: string(item, tags::REFERENCED_SOP_INSTANCE_UID).unwrap_or_default(),
+            failure_reason: item.get_u16(tags::FAILURE_REASON).ok(),
+        })
+        .collect();
+
+    Ok(StoreResponse {
+        retrieve_url: string(&obj, tags::RETRIEVE_URL),
+        stored,
+        failed,
+    })
+}
+
+fn items(obj: &InMemDicomObject, tag: Tag) -> &[InMemDicomObject] {
+    obj.get_sequence(tag).unwrap_or_default()
+}
+
+fn string(obj: &InMemDicomObject, tag: Tag) -> Option<String> {
+    obj.get_string(tag).ok().map(|s| {
+        s.trim_end_matches(|c: char| c == '\0' || c == ' ')
+            .to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_files_as_multipart() {
+        let request =
+            encode_files(vec![b"DICOMwebBoundary00000000".to_vec(), vec![1, 2, 3]]).unwrap();
+        assert_eq!(
+            request.content_type,
+            "multipart/related; type=\"application/dicom\"; boundary=DICOMwebBoundary00000001"
+        );
+
+        let parts = multipart::parse(&request.content_type, &request.body).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].content_type(), Some("application/dicom"));
+        assert_eq!(parts[1].body, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn read_partial_failure() {
+        let body = br#"{
+            "00081190": { "vr": "UR", "Value": ["https://pacs/studies/1.2"] },
+            "00081198": { "vr": "SQ", "Value": [{
+                "00081150": { "vr": "UI", "Value": ["1.2.840.10008.5.1.4.1.1.2"] },
+                "00081155": { "vr": "UI", "Value": ["1.2.3.2"] },
+                "00081197": { "vr": "US", "Value": [49442] }
+            }] },
+            "00081199": { "vr": "SQ", "Value": [{
+                "00081150": { "vr": "UI", "Value": ["1.2.840.10008.5.1.4.1.1.2"] },
+                "00081155": { "vr": "UI", "Value": ["1.2.3.1"] },
+                "00081190": { "vr": "UR", "Value": ["https://pacs/studies/1.2/series/1.2.3/instances/1.2.3.1"] }
+            }] }
+        }"#;
+
+        let response = read_response(body).unwrap();
+        assert!(!response.is_success());
+        assert_eq!(
+            response.retrieve_url.as_deref(),
+            Some("https://pacs/studies/1.2")
+        );
+        assert_eq!(response.stored.len(), 1);
+        assert_eq!(response.stored[0].sop_instance_uid, "1.2.3.1");
+        assert_eq!(response.failed.len(), 1);
+        assert_eq!(response.failed[0].failure_reason, Some(0xC122));
+
+        assert_eq!(read_response(b"").unwrap(), StoreResponse::default());
+    }
+}
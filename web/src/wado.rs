@@ -0,0 +1,414 @@
+//! WADO-RS module.
+//!
+//! This module describes the resources which can be retrieved
+//! through the WADO-RS service,
+//! and decodes the bodies of the respective responses,
+//! independently of the HTTP implementation used.
+//!
+//! Studies, series, and instances are retrieved
+//! as `multipart/related` bodies of DICOM files,
+//! which can be read into in-memory objects via [`read_instances`],
+//! or kept as [`InstanceData`] to be read as a stream of data set tokens.
+//! Frames are retrieved as `multipart/related` bodies of pixel data,
+//! read via [`read_frames`].
+use dicom_encoding::text::SpecificCharacterSet;
+use dicom_encoding::transfer_syntax::TransferSyntaxIndex;
+use dicom_object::{DefaultDicomObject, FileMetaTable};
+use dicom_parser::dataset::read::DataSetReader;
+use dicom_parser::stateful::decode::DynStatefulDecoder;
+use dicom_transfer_syntax_registry::TransferSyntaxRegistry;
+use snafu::{ensure, Backtrace, OptionExt, ResultExt, Snafu};
+
+use crate::multipart;
+
+/// The media type of DICOM files in WADO-RS responses.
+pub const DICOM_MEDIA_TYPE: &str = "application/dicom";
+
+/// The value of the `Accept` header for retrieving DICOM instances
+/// in any transfer syntax.
+pub const ACCEPT_DICOM: &str = "multipart/related; type=\"application/dicom\"; transfer-syntax=*";
+
+/// The value of the `Accept` header for retrieving frames
+/// in any transfer syntax.
+pub const ACCEPT_FRAMES: &str =
+    "multipart/related; type=\"application/octet-stream\"; transfer-syntax=*";
+
+#[derive(Debug, Snafu)]
+#[non_exhaustive]
+pub enum Error {
+    /// failed to split multipart response body
+    Multipart {
+        #[snafu(backtrace)]
+        source: multipart::Error,
+    },
+
+    #[snafu(display("unexpected media type `{}` in response part", media_type))]
+    UnexpectedMediaType {
+        media_type: String,
+        backtrace: Backtrace,
+    },
+
+    /// missing DICOM file meta group in response part
+    MissingMeta { backtrace: Backtrace },
+
+    /// failed to read DICOM file meta group
+    ReadMeta {
+        #[snafu(backtrace)]
+        source: dicom_object::meta::Error,
+    },
+
+    /// failed to read DICOM object
+    ReadObject {
+        #[snafu(backtrace)]
+        source: dicom_object::Error,
+    },
+
+    #[snafu(display("unsupported transfer syntax {}", uid))]
+    UnsupportedTransferSyntax { uid: String, backtrace: Backtrace },
+
+    /// failed to create data set reader
+    CreateReader {
+        #[snafu(backtrace)]
+        source: dicom_parser::dataset::read::Error,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// A resource which can be retrieved through WADO-RS.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Target {
+    /// all instances of a study
+    Study { study_instance_uid: String },
+    /// all instances of a series
+    Series {
+        study_instance_uid: String,
+        series_instance_uid: String,
+    },
+    /// a single instance
+    Instance {
+        study_instance_uid: String,
+        series_instance_uid: String,
+        sop_instance_uid: String,
+    },
+}
+
+impl Target {
+    /// Refer to a study.
+    pub fn study(study_instance_uid: impl Into<String>) -> Self {
+        Target::Study {
+            study_instance_uid: study_instance_uid.into(),
+        }
+    }
+
+    /// Refer to a series.
+    pub fn series(
+        study_instance_uid: impl Into<String>,
+        series_instance_uid: impl Into<String>,
+    ) -> Self {
+        Target::Series {
+            study_instance_uid: study_instance_uid.into(),
+            series_instance_uid: series_instance_uid.into(),
+        }
+    }
+
+    /// Refer to an instance.
+    pub fn instance(
+        study_instance_uid: impl Into<String>,
+        series_instance_uid: impl Into<String>,
+        sop_instance_uid: impl Into<String>,
+    ) -> Self {
+        Target::Instance {
+            study_instance_uid: study_instance_uid.into(),
+            series_instance_uid: series_instance_uid.into(),
+            sop_instance_uid: sop_instance_uid.into(),
+        }
+    }
+
+    /// Build the path to the resource,
+    /// relative to the service's base URL.
+    pub fn path(&self) -> String {
+        match self {
+            Target::Study { study_instance_uid } => format!("studies/{}", study_instance_uid),
+            Target::Series {
+                study_instance_uid,
+                series_instance_uid,
+            } => format!(
+                "studies/{}/series/{}",
+                study_instance_uid, series_instance_uid
+            ),
+            Target::Instance {
+                study_instance_uid,
+                series_instance_uid,
+                sop_instance_uid,
+            } => format!(
+                "studies/{}/series/{}/instances/{}",
+                study_instance_uid, series_instance_uid, sop_instance_uid
+            ),
+        }
+    }
+
+    /// Build the path to the given frames of an instance,
+    /// relative to the service's base URL.
+    /// Frame numbers start at 1.
+    ///
+    /// Returns `None` if the target is not an instance.
+    pub fn frames_path(&self, frame_numbers: &[u32]) -> Option<String> {
+        match self {
+            Target::Instance { .. } => {
+                let frames: Vec<_> = frame_numbers.iter().map(u32::to_string).collect();
+                Some(format!("{}/frames/{}", self.path(), frames.join(",")))
+            }
+            _ => None,
+        }
+    }
+
+    /// Build the path to the rendered representation of the resource,
+    /// relative to the service's base URL.
+    pub fn rendered_path(&self) -> String {
+        format!("{}/rendered", self.path())
+    }
+}
+
+/// An encoded DICOM file retrieved through WADO-RS.
+///
+/// The file can be read as an in-memory object
+/// or through a data set reader,
+/// which yields the data set's tokens one at a time.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstanceData {
+    data: Vec<u8>,
+}
+
+impl InstanceData {
+    /// Wrap the encoded bytes of a DICOM file,
+    /// with or without the 128-byte preamble.
+    pub fn new(data: Vec<u8>) -> Self {
+        InstanceData { data }
+    }
+
+    /// Retrieve the encoded bytes of the file.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Retrieve the encoded bytes of the file.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+
+    /// Read the whole file into an in-memory DICOM object.
+    pub fn to_object(&self) -> Result<DefaultDicomObject> {
+        dicom_object::from_reader(self.without_preamble()?).context(ReadObjectSnafu)
+    }
+
+    /// Read the file meta group,
+    /// and create a reader for the tokens of the data set which follows.
+    pub fn token_reader(
+        &self,
+    ) -> Result<(FileMetaTable, DataSetReader<DynStatefulDecoder<&[u8]>>)> {
+        let mut data = self.without_preamble()?;
+        let meta = FileMetaTable::from_reader(&mut data).context(ReadMetaSnafu)?;
+        let uid = meta
+            .transfer_syntax
+            .trim_end_matches(|c: char| c == '\0' || c == ' ');
+        let registry = TransferSyntaxRegistry;
+        let ts = registry
+            .get(uid)
+            .context(UnsupportedTransferSyntaxSnafu { uid })?;
+        let reader = DataSetReader::new_with_ts_cs(data, ts, SpecificCharacterSet::Default)
+            .context(CreateReaderSnafu)?;
+        Ok((meta, reader))
+    }
+
+    /// Obtain the file's bytes starting at the magic code.
+    fn without_preamble(&self) -> Result<&[u8]> {
+        if self.data.len() >= 132 && &self.data[128..132] == b"DICM" {
+            Ok(&self.data[128..])
+        } else {
+            ensure!(self.data.starts_with(b"DICM"), MissingMetaSnafu);
+            Ok(&self.data)
+        }
+    }
+}
+
+/// A frame of pixel data retrieved through WADO-RS.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// the media type of the frame, if specified
+    pub media_type: Option<String>,
+    /// the transfer syntax of the frame, if specified
+    pub transfer_syntax: Option<String>,
+    /// the frame's pixel data
+    pub data: Vec<u8>,
+}
+
+/// Split a WADO-RS response body of DICOM files
+/// into the encoded instances.
+pub fn read_instance_data(content_type: &str, body: &[u8]) -> Result<Vec<InstanceData>> {
+    multipart::parse(content_type, body)
+        .context(MultipartSnafu)?
+        .into_iter()
+        .map(|part| {
+            if let Some(media_type) = part.content_type() {
+                ensure!(
+                    multipart::media_type(media_type).eq_ignore_ascii_case(DICOM_MEDIA_TYPE),
+                    UnexpectedMediaTypeSnafu { media_type }
+                );
+            }
+            Ok(InstanceData::new(part.body))
+        })
+        .collect()
+}
+
+/// Read a WADO-RS response body of DICOM files
+/// into in-memory DICOM objects.
+pub fn read_instances(content_type: &str, body: &[u8]) -> Result<Vec<DefaultDicomObject>> {
+    read_instance_data(content_type, body)?
+        .iter()
+        .map(InstanceData::to_object)
+        .collect()
+}
+
+/// Read a WADO-RS response body of frames.
+pub fn read_frames(content_type: &str, body: &[u8]) -> Result<Vec<Frame>> {
+    Ok(multipart::parse(content_type, body)
+        .context(MultipartSnafu)?
+        .into_iter()
+        .map(|part| {
+            let transfer_syntax = part.transfer_syntax().map(str::to_string);
+            Frame {
+                media_type: part.content_type().map(str::to_string),
+                transfer_syntax,
+                data: part.body,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dicom_core::{DataElement, PrimitiveValue, VR};
+    use dicom_dictionary_std::tags;
+    use dicom_object::{FileMetaTableBuilder, InMemDicomObject};
+    use dicom_parser::dataset::DataToken;
+
+    fn test_file(sop_instance_uid: &str) -> Vec<u8> {
+        let obj = InMemDicomObject::from_element_iter([
+            DataElement::new(
+                tags::SOP_CLASS_UID,
+                VR::UI,
+                PrimitiveValue::from("1.2.840.10008.5.1.4.1.1.7"),
+            ),
+            DataElement::new(
+                tags::SOP_INSTANCE_UID,
+                VR::UI,
+                PrimitiveValue::from(sop_instance_uid),
+            ),
+            DataElement::new(tags::PATIENT_NAME, VR::PN, PrimitiveValue::from("Doe^John")),
+        ])
+        .with_meta(
+            FileMetaTableBuilder::new()
+                .media_storage_sop_class_uid("1.2.840.10008.5.1.4.1.1.7")
+                .transfer_syntax("1.2.840.10008.1.2.1"),
+        )
+        .unwrap();
+        let mut data = Vec::new();
+        obj.write_all(&mut data).unwrap();
+        data
+    }
+
+    fn multipart_body(parts: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (media_type, data) in parts {
+            body.extend_from_slice(b"--BOUNDARY\r\nContent-Type: ");
+            body.extend_from_slice(media_type.as_bytes());
+            body.extend_from_slice(b"\r\n\r\n");
+            body.extend_from_slice(data);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--BOUNDARY--\r\n");
+        body
+    }
+
+    #[test]
+    fn target_paths() {
+        let instance = Target::instance("1.2", "1.2.3", "1.2.3.4");
+        assert_eq!(
+            instance.path(),
+            "studies/1.2/series/1.2.3/instances/1.2.3.4"
+        );
+        assert_eq!(
+            instance.frames_path(&[1, 3]).unwrap(),
+            "studies/1.2/series/1.2.3/instances/1.2.3.4/frames/1,3"
+        );
+        assert_eq!(Target::study("1.2").rendered_path(), "studies/1.2/rendered");
+        assert_eq!(Target::series("1.2", "1.2.3").frames_path(&[1]), None);
+    }
+
+    #[test]
+    fn read_instances_from_multipart() {
+        let first = test_file("1.2.3.4.1");
+        let second = test_file("1.2.3.4.2");
+        let body = multipart_body(&[
+            ("application/dicom", &first),
+            (
+                "application/dicom; transfer-syntax=1.2.840.10008.1.2.1",
+                &second,
+            ),
+        ]);
+        let content_type = "multipart/related; type=\"application/dicom\"; boundary=BOUNDARY";
+
+        let objects = read_instances(content_type, &body).unwrap();
+        assert_eq!(objects.len(), 2);
+        assert_eq!(
+            objects[1]
+                .element(tags::SOP_INSTANCE_UID)
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .trim_end_matches('\0'),
+            "1.2.3.4.2"
+        );
+
+        let instances = read_instance_data(content_type, &body).unwrap();
+        let (meta, reader) = instances[0].token_reader().unwrap();
+        assert_eq!(
+            meta.media_storage_sop_instance_uid.trim_end_matches('\0'),
+            "1.2.3.4.1"
+        );
+        let tokens: Vec<_> = reader.collect::<std::result::Result<_, _>>().unwrap();
+        assert!(tokens.iter().any(
+            |token| matches!(token, DataToken::ElementHeader(h) if h.tag == tags::PATIENT_NAME)
+        ));
+    }
+
+    #[test]
+    fn read_frames_from_multipart() {
+        let body = multipart_body(&[
+            (
+                "application/octet-stream; transfer-syntax=1.2.840.10008.1.2.1",
+                &[1, 2, 3, 4],
+            ),
+            ("application/octet-stream", &[5, 6, 7, 8]),
+        ]);
+        let frames = read_frames("multipart/related; boundary=BOUNDARY", &body).unwrap();
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[0].transfer_syntax.as_deref(),
+            Some("1.2.840.10008.1.2.1")
+        );
+        assert_eq!(frames[0].data, vec![1, 2, 3, 4]);
+        assert_eq!(frames[1].transfer_syntax, None);
+    }
+
+    #[test]
+    fn reject_unexpected_media_type() {
+        let body = multipart_body(&[("image/jpeg", &[0xFF, 0xD8])]);
+        assert!(matches!(
+            read_instance_data("multipart/related; boundary=BOUNDARY", &body),
+            Err(Error::UnexpectedMediaType { .. })
+        ));
+    }
+}